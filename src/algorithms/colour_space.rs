@@ -0,0 +1,219 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use std::f64::consts::PI;
+
+/// Converts red, green, and blue values (0-1) into intensity (0-1), hue (0-2PI), and
+/// saturation (0-1) values, using the IHS transformation of Haydn et al. (1982).
+pub fn rgb_to_ihs(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let sum = r + g + b;
+    let i = sum / 3f64;
+    if sum <= 0f64 {
+        return (0f64, 0f64, 0f64);
+    }
+
+    let rn = r / sum;
+    let gn = g / sum;
+    let bn = b / sum;
+
+    let mut h = if rn != gn || rn != bn {
+        ((0.5 * ((rn - gn) + (rn - bn))) / ((rn - gn) * (rn - gn) + (rn - bn) * (gn - bn)).sqrt())
+            .clamp(-1f64, 1f64)
+            .acos()
+    } else {
+        0f64
+    };
+    if b > g {
+        h = 2f64 * PI - h;
+    }
+
+    let s = 1f64 - 3f64 * rn.min(gn).min(bn);
+
+    (i, h, s)
+}
+
+/// Converts intensity (0-1), hue (0-2PI), and saturation (0-1) values into red, green,
+/// and blue values (0-1). This is the inverse of `rgb_to_ihs`.
+pub fn ihs_to_rgb(i: f64, h: f64, s: f64) -> (f64, f64, f64) {
+    let x = i * (1f64 - s);
+    let (r, g, b) = if h < 2f64 * PI / 3f64 {
+        let y = i * (1f64 + (s * h.cos()) / ((PI / 3f64 - h).cos()));
+        let z = 3f64 * i - (x + y);
+        (y, z, x)
+    } else if h < 4f64 * PI / 3f64 {
+        let h = h - 2f64 * PI / 3f64;
+        let y = i * (1f64 + (s * h.cos()) / ((PI / 3f64 - h).cos()));
+        let z = 3f64 * i - (x + y);
+        (x, y, z)
+    } else {
+        let h = h - 4f64 * PI / 3f64;
+        let y = i * (1f64 + (s * h.cos()) / ((PI / 3f64 - h).cos()));
+        let z = 3f64 * i - (x + y);
+        (z, x, y)
+    };
+    (r.clamp(0f64, 1f64), g.clamp(0f64, 1f64), b.clamp(0f64, 1f64))
+}
+
+/// Converts red, green, and blue values (0-1) into hue (0-360 degrees), saturation (0-1),
+/// and value (0-1).
+pub fn rgb_to_hsv(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let mut h = if delta == 0f64 {
+        0f64
+    } else if max == r {
+        60f64 * ((g - b) / delta)
+    } else if max == g {
+        60f64 * ((b - r) / delta + 2f64)
+    } else {
+        60f64 * ((r - g) / delta + 4f64)
+    };
+    if h < 0f64 {
+        h += 360f64;
+    }
+    let s = if max > 0f64 { delta / max } else { 0f64 };
+    (h, s, max)
+}
+
+/// Converts hue (0-360 degrees), saturation (0-1), and value (0-1) values into red, green,
+/// and blue values (0-1). This is the inverse of `rgb_to_hsv`.
+pub fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (f64, f64, f64) {
+    let s = s.clamp(0f64, 1f64);
+    let v = v.clamp(0f64, 1f64);
+    let h = h.rem_euclid(360f64) / 60f64;
+    let c = v * s;
+    let x = c * (1f64 - (h % 2f64 - 1f64).abs());
+    let (r, g, b) = match h as usize {
+        0 => (c, x, 0f64),
+        1 => (x, c, 0f64),
+        2 => (0f64, c, x),
+        3 => (0f64, x, c),
+        4 => (x, 0f64, c),
+        _ => (c, 0f64, x),
+    };
+    let m = v - c;
+    (r + m, g + m, b + m)
+}
+
+// The CIE XYZ tristimulus values of the D65 reference white.
+const XN: f64 = 0.950_47;
+const YN: f64 = 1.0;
+const ZN: f64 = 1.088_83;
+
+/// Converts sRGB red, green, and blue values (0-1) into CIE L*a*b* values, with L* ranging
+/// from 0 to 100 and a* and b* ranging from approximately -128 to 127, using the D65
+/// reference white.
+pub fn rgb_to_lab(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let to_linear = |c: f64| {
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+    let x = 0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+    let z = 0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b;
+
+    let f = |t: f64| {
+        if t > 216f64 / 24_389f64 {
+            t.cbrt()
+        } else {
+            (24_389f64 / 27f64 * t + 16f64) / 116f64
+        }
+    };
+    let (fx, fy, fz) = (f(x / XN), f(y / YN), f(z / ZN));
+    (116f64 * fy - 16f64, 500f64 * (fx - fy), 200f64 * (fy - fz))
+}
+
+/// Converts CIE L*a*b* values into sRGB red, green, and blue values (0-1). This is the
+/// inverse of `rgb_to_lab`. Colours that are outside of the sRGB gamut are clipped.
+pub fn lab_to_rgb(l: f64, a: f64, b: f64) -> (f64, f64, f64) {
+    let fy = (l + 16f64) / 116f64;
+    let fx = fy + a / 500f64;
+    let fz = fy - b / 200f64;
+    let f_inv = |t: f64| {
+        if t > 6f64 / 29f64 {
+            t * t * t
+        } else {
+            (116f64 * t - 16f64) * 27f64 / 24_389f64
+        }
+    };
+    let (x, y, z) = (f_inv(fx) * XN, f_inv(fy) * YN, f_inv(fz) * ZN);
+
+    let r = 3.240_454_2 * x - 1.537_138_5 * y - 0.498_531_4 * z;
+    let g = -0.969_266 * x + 1.876_010_8 * y + 0.041_556 * z;
+    let b = 0.055_643_4 * x - 0.204_025_9 * y + 1.057_225_2 * z;
+    let from_linear = |c: f64| {
+        let c = c.clamp(0f64, 1f64);
+        if c <= 0.003_130_8 {
+            12.92 * c
+        } else {
+            1.055 * c.powf(1f64 / 2.4) - 0.055
+        }
+    };
+    (from_linear(r), from_linear(g), from_linear(b))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hsv_to_rgb, ihs_to_rgb, lab_to_rgb, rgb_to_hsv, rgb_to_ihs, rgb_to_lab};
+
+    const COLOURS: [(f64, f64, f64); 7] = [
+        (1.0, 0.0, 0.0),
+        (0.0, 1.0, 0.0),
+        (0.0, 0.0, 1.0),
+        (0.2, 0.4, 0.6),
+        (0.9, 0.7, 0.1),
+        (0.5, 0.5, 0.5),
+        (0.3, 0.8, 0.75),
+    ];
+
+    fn assert_close(a: (f64, f64, f64), b: (f64, f64, f64), tolerance: f64) {
+        assert!(
+            (a.0 - b.0).abs() < tolerance
+                && (a.1 - b.1).abs() < tolerance
+                && (a.2 - b.2).abs() < tolerance,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn test_ihs_round_trip() {
+        for (r, g, b) in COLOURS.iter() {
+            let (i, h, s) = rgb_to_ihs(*r, *g, *b);
+            assert_close(ihs_to_rgb(i, h, s), (*r, *g, *b), 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_hsv() {
+        assert_close(rgb_to_hsv(1.0, 0.0, 0.0), (0.0, 1.0, 1.0), 1e-9);
+        assert_close(rgb_to_hsv(0.0, 0.0, 1.0), (240.0, 1.0, 1.0), 1e-9);
+        assert_close(rgb_to_hsv(0.5, 0.5, 0.5), (0.0, 0.0, 0.5), 1e-9);
+        for (r, g, b) in COLOURS.iter() {
+            let (h, s, v) = rgb_to_hsv(*r, *g, *b);
+            assert_close(hsv_to_rgb(h, s, v), (*r, *g, *b), 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_lab() {
+        assert_close(rgb_to_lab(1.0, 1.0, 1.0), (100.0, 0.0, 0.0), 1e-3);
+        assert_close(rgb_to_lab(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), 1e-9);
+        assert_close(rgb_to_lab(1.0, 0.0, 0.0), (53.24, 80.09, 67.20), 1e-2);
+        for (r, g, b) in COLOURS.iter() {
+            let (l, a, b2) = rgb_to_lab(*r, *g, *b);
+            assert_close(lab_to_rgb(l, a, b2), (*r, *g, *b), 1e-4);
+        }
+    }
+}
@@ -6,17 +6,24 @@ Last Modified: 25/09/2018
 License: MIT
 */
 // private sub-module defined in other files
+mod colour_space;
 mod convex_hull;
 mod delaunay_triangulation;
 mod is_clockwise_order;
 mod line_ops;
 mod minimum_bounding_box;
+mod natural_neighbour;
 mod poly_area;
 mod poly_ops;
 mod poly_perimeter;
+mod region_boundaries;
 mod smallest_enclosing_circle;
+mod tin_interpolation;
 
 // exports identifiers from private sub-modules in the current module namespace
+pub use self::colour_space::{
+    hsv_to_rgb, ihs_to_rgb, lab_to_rgb, rgb_to_hsv, rgb_to_ihs, rgb_to_lab,
+};
 pub use self::convex_hull::convex_hull;
 pub use self::delaunay_triangulation::{triangulate, Triangulation};
 pub use self::is_clockwise_order::is_clockwise_order;
@@ -24,9 +31,12 @@ pub use self::line_ops::{
     do_polylines_intersect, find_line_intersections, find_split_points_at_line_intersections,
 };
 pub use self::minimum_bounding_box::{minimum_bounding_box, MinimizationCriterion};
+pub use self::natural_neighbour::NaturalNeighbourInterpolator;
 pub use self::poly_area::polygon_area;
 pub use self::poly_perimeter::polygon_perimeter;
 pub use self::poly_ops::{
     interior_point, point_in_poly, poly_in_poly, poly_is_convex, poly_overlaps_poly, winding_number,
 };
+pub use self::region_boundaries::trace_region_boundaries;
 pub use self::smallest_enclosing_circle::smallest_enclosing_circle;
+pub use self::tin_interpolation::TinInterpolator;
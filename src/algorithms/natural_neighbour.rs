@@ -0,0 +1,169 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::delaunay_triangulation::{Triangulation, EMPTY};
+use crate::structures::Point2D;
+
+/// Calculates Sibson natural neighbour coordinates, i.e. interpolation weights, from an
+/// existing Delaunay triangulation. The natural neighbours of a location are the vertices
+/// of the triangles whose circumcircles contain it, and the weight of each neighbour is the
+/// proportion of the location's Voronoi cell, were it inserted into the triangulation, that
+/// would be taken from the neighbour's Voronoi cell. The weights are calculated from the
+/// circumcentres of these triangles (Watson, 1992), without modifying the triangulation.
+///
+/// Unlike linear interpolation on the triangle facets, natural neighbour interpolation has
+/// a continuous gradient everywhere except at the data points themselves.
+///
+/// # Reference
+/// Watson, D. F. (1992). Contouring: A Guide to the Analysis and Display of Spatial Data.
+/// Pergamon Press, Oxford.
+pub struct NaturalNeighbourInterpolator<'a> {
+    points: &'a [Point2D],
+    triangulation: &'a Triangulation,
+    centres: Vec<Point2D>,
+    radii_sqrd: Vec<f64>,
+}
+
+impl<'a> NaturalNeighbourInterpolator<'a> {
+    /// Creates a new interpolator from a set of points and their Delaunay triangulation.
+    pub fn new(points: &'a [Point2D], triangulation: &'a Triangulation) -> Self {
+        let num_triangles = triangulation.len();
+        let mut centres = Vec::with_capacity(num_triangles);
+        let mut radii_sqrd = Vec::with_capacity(num_triangles);
+        for triangle in 0..num_triangles {
+            let p = triangulation.points_of_triangle(triangle);
+            let (dx, dy) = points[p[0]].circumdelta(&points[p[1]], &points[p[2]]);
+            centres.push(Point2D::new(points[p[0]].x + dx, points[p[0]].y + dy));
+            radii_sqrd.push(dx * dx + dy * dy);
+        }
+        NaturalNeighbourInterpolator {
+            points,
+            triangulation,
+            centres,
+            radii_sqrd,
+        }
+    }
+
+    /// Returns the natural neighbours of the location `p`, as point indices, and their
+    /// weights, which sum to one. `triangle` must be the index of a triangle containing `p`.
+    /// Returns `None` if the weights cannot be calculated, e.g. when `p` lies exactly on a
+    /// triangle edge, in which case linear interpolation within the triangle is appropriate.
+    pub fn get_weights(&self, p: &Point2D, triangle: usize) -> Option<Vec<(usize, f64)>> {
+        for v in self.triangulation.points_of_triangle(triangle).iter() {
+            if self.points[*v].distance_squared(p) == 0f64 {
+                return Some(vec![(*v, 1f64)]);
+            }
+        }
+
+        // find the triangles whose circumcircles contain p; these form a connected region
+        let mut cavity = vec![triangle];
+        let mut stack = vec![triangle];
+        while let Some(t) = stack.pop() {
+            for e in self.triangulation.edges_of_triangle(t).iter() {
+                let opposite = self.triangulation.halfedges[*e];
+                if opposite != EMPTY {
+                    let t2 = self.triangulation.triangle_of_edge(opposite);
+                    if !cavity.contains(&t2)
+                        && self.centres[t2].distance_squared(p) < self.radii_sqrd[t2]
+                    {
+                        cavity.push(t2);
+                        stack.push(t2);
+                    }
+                }
+            }
+        }
+
+        // The area taken from the Voronoi cell of vertex a of each cavity triangle (a, b, c) is
+        // the triangle formed by the circumcentres of (p, a, b), (a, b, c), and (p, a, c). All
+        // coordinates are relative to p to avoid a loss of precision.
+        let mut weights: Vec<(usize, f64)> = Vec::with_capacity(cavity.len() + 2);
+        let mut sum = 0f64;
+        for t in &cavity {
+            let gx = self.centres[*t].x - p.x;
+            let gy = self.centres[*t].y - p.y;
+            for e in self.triangulation.edges_of_triangle(*t).iter() {
+                let a = self.triangulation.triangles[*e];
+                let b = self.triangulation.triangles[self.triangulation.next_halfedge(*e)];
+                let c = self.triangulation.triangles[self.triangulation.prev_halfedge(*e)];
+                let (ux, uy) = p.circumdelta(&self.points[a], &self.points[b]);
+                let (vx, vy) = p.circumdelta(&self.points[a], &self.points[c]);
+                let area = ((gx - ux) * (vy - uy) - (gy - uy) * (vx - ux)) / 2f64;
+                match weights.iter_mut().find(|w| w.0 == a) {
+                    Some(w) => w.1 += area,
+                    None => weights.push((a, area)),
+                }
+                sum += area;
+            }
+        }
+
+        if !sum.is_finite() || sum == 0f64 {
+            return None;
+        }
+        for w in weights.iter_mut() {
+            w.1 /= sum;
+        }
+        Some(weights)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::NaturalNeighbourInterpolator;
+    use crate::algorithms::triangulate;
+    use crate::structures::Point2D;
+
+    #[test]
+    fn test_natural_neighbour_weights() {
+        // a deterministic, irregular set of points
+        let mut points = vec![];
+        let mut seed = 12345u64;
+        for _ in 0..200 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let x = (seed >> 33) as f64 / (1u64 << 31) as f64 * 100.0 + 500000.0;
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let y = (seed >> 33) as f64 / (1u64 << 31) as f64 * 100.0 + 4800000.0;
+            points.push(Point2D::new(x, y));
+        }
+        let triangulation = triangulate(&points).unwrap();
+        let nn = NaturalNeighbourInterpolator::new(&points, &triangulation);
+
+        let queries = vec![
+            Point2D::new(500050.3, 4800050.7),
+            Point2D::new(500020.1, 4800070.9),
+            Point2D::new(500081.6, 4800033.2),
+        ];
+        for q in queries {
+            let mut triangle = None;
+            for t in 0..triangulation.len() {
+                let p = triangulation.points_of_triangle(t);
+                let (a, b, c) = (points[p[0]], points[p[1]], points[p[2]]);
+                let d1 = (b.x - a.x) * (q.y - a.y) - (b.y - a.y) * (q.x - a.x);
+                let d2 = (c.x - b.x) * (q.y - b.y) - (c.y - b.y) * (q.x - b.x);
+                let d3 = (a.x - c.x) * (q.y - c.y) - (a.y - c.y) * (q.x - c.x);
+                if (d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0) || (d1 <= 0.0 && d2 <= 0.0 && d3 <= 0.0) {
+                    triangle = Some(t);
+                    break;
+                }
+            }
+            let weights = nn.get_weights(&q, triangle.unwrap()).unwrap();
+            assert!(weights.len() >= 3);
+
+            // the weights are positive, sum to one, and reproduce linear functions
+            let (mut sum, mut x, mut y) = (0f64, 0f64, 0f64);
+            for (i, w) in &weights {
+                assert!(*w > 0f64);
+                sum += w;
+                x += w * (points[*i].x - 500000.0);
+                y += w * (points[*i].y - 4800000.0);
+            }
+            assert!((sum - 1.0).abs() < 1e-9);
+            assert!((x - (q.x - 500000.0)).abs() < 1e-6);
+            assert!((y - (q.y - 4800000.0)).abs() < 1e-6);
+        }
+    }
+}
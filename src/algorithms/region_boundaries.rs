@@ -0,0 +1,138 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use std::collections::HashMap;
+
+/// Traces the boundaries of a region of grid cells, returning a set of closed rings of grid
+/// corner coordinates, as (row, column) pairs, where corner (r, c) is the upper-left corner of
+/// cell (r, c). The region is defined by the function `in_region(row, column)`, which is only
+/// called for cells within the `rows` by `columns` grid.
+///
+/// Rings are ordered such that the region lies to their right when the rows increase downwards,
+/// i.e. outer boundaries are clockwise and the boundaries of holes are counter-clockwise, as in
+/// the Shapefile polygon convention. Cells that only touch diagonally are separated into distinct
+/// rings. Corners that are collinear with their neighbours are not included and the first corner
+/// of each ring is repeated at its end.
+pub fn trace_region_boundaries<F>(rows: isize, columns: isize, in_region: F) -> Vec<Vec<(isize, isize)>>
+where
+    F: Fn(isize, isize) -> bool,
+{
+    let is_in = |row: isize, col: isize| -> bool {
+        row >= 0 && col >= 0 && row < rows && col < columns && in_region(row, col)
+    };
+
+    // directed boundary edges, keyed on their starting corner
+    let mut edges: HashMap<(isize, isize), Vec<(isize, isize)>> = HashMap::new();
+    let mut num_edges = 0usize;
+    for row in 0..rows {
+        for col in 0..columns {
+            if in_region(row, col) {
+                if !is_in(row - 1, col) {
+                    edges.entry((row, col)).or_default().push((row, col + 1));
+                    num_edges += 1;
+                }
+                if !is_in(row, col + 1) {
+                    edges.entry((row, col + 1)).or_default().push((row + 1, col + 1));
+                    num_edges += 1;
+                }
+                if !is_in(row + 1, col) {
+                    edges.entry((row + 1, col + 1)).or_default().push((row + 1, col));
+                    num_edges += 1;
+                }
+                if !is_in(row, col - 1) {
+                    edges.entry((row + 1, col)).or_default().push((row, col));
+                    num_edges += 1;
+                }
+            }
+        }
+    }
+
+    let mut rings = vec![];
+    let mut starts: Vec<(isize, isize)> = edges.keys().cloned().collect();
+    starts.sort();
+    for start in starts {
+        while num_edges > 0 {
+            let first = match edges.get_mut(&start).and_then(|e| e.pop()) {
+                Some(end) => end,
+                None => break,
+            };
+            num_edges -= 1;
+            let mut ring = vec![start];
+            let mut prev = start;
+            let mut current = first;
+            while current != start {
+                let dir = (current.0 - prev.0, current.1 - prev.1);
+                let outgoing = edges.get_mut(&current).unwrap();
+                let k = if outgoing.len() > 1 {
+                    // where cells touch diagonally, turn towards the region, i.e. to the right
+                    let right = (current.0 + dir.1, current.1 - dir.0);
+                    outgoing.iter().position(|e| *e == right).unwrap_or(0)
+                } else {
+                    0
+                };
+                let next = outgoing.swap_remove(k);
+                num_edges -= 1;
+                if (next.0 - current.0, next.1 - current.1) != dir {
+                    ring.push(current);
+                }
+                prev = current;
+                current = next;
+            }
+            // the starting corner may be collinear with its neighbours
+            if ring.len() > 2 {
+                let d1 = (ring[1].0 - ring[0].0, ring[1].1 - ring[0].1);
+                let d0 = (ring[0].0 - prev.0, ring[0].1 - prev.1);
+                if d0.0.signum() == d1.0.signum() && d0.1.signum() == d1.1.signum() {
+                    ring.remove(0);
+                }
+            }
+            ring.push(ring[0]);
+            rings.push(ring);
+        }
+    }
+    rings
+}
+
+#[cfg(test)]
+mod test {
+    use super::trace_region_boundaries;
+
+    fn signed_area(ring: &[(isize, isize)]) -> isize {
+        // positive for rings that are clockwise when rows increase downwards
+        let mut area = 0isize;
+        for i in 0..ring.len() - 1 {
+            area += ring[i].1 * ring[i + 1].0 - ring[i + 1].1 * ring[i].0;
+        }
+        area
+    }
+
+    #[test]
+    fn test_region_with_hole() {
+        // a 5 x 5 block of cells with a hole in the centre
+        let rings = trace_region_boundaries(7, 7, |r, c| {
+            r >= 1 && r <= 5 && c >= 1 && c <= 5 && !(r == 3 && c == 3)
+        });
+        assert_eq!(rings.len(), 2);
+        let mut areas: Vec<isize> = rings.iter().map(|r| signed_area(r)).collect();
+        areas.sort();
+        assert_eq!(areas, vec![-2, 50]);
+        for ring in &rings {
+            assert_eq!(ring.len(), 5);
+            assert_eq!(ring[0], ring[ring.len() - 1]);
+        }
+    }
+
+    #[test]
+    fn test_diagonal_cells() {
+        let rings = trace_region_boundaries(2, 2, |r, c| r == c);
+        assert_eq!(rings.len(), 2);
+        for ring in &rings {
+            assert_eq!(signed_area(ring), 2);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::natural_neighbour::NaturalNeighbourInterpolator;
+use super::poly_ops::point_in_poly;
+use crate::na::Vector3;
+use crate::structures::Point2D;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+/// A triangular irregular network (TIN) fitted to a set of points, and the grid onto which it is
+/// rasterized. One or more parameters, e.g. the elevations and intensities of LiDAR points, may be
+/// interpolated from the same triangulation, either linearly, on the planar facets of the TIN, or
+/// by Sibson's natural neighbour interpolation, if a `NaturalNeighbourInterpolator` is supplied.
+/// Triangles with an edge longer than the maximum edge length, measured in three dimensions
+/// using each parameter's values, are not interpolated for that parameter.
+///
+/// The interpolated values are sampled at the locations `(west + col * grid_res, north - row *
+/// grid_res)`, i.e. `west` and `north` are the coordinates of the sample location of the upper-left
+/// grid cell, which may be its corner or, offset by half a cell, its centre.
+pub struct TinInterpolator<'a> {
+    pub points: &'a [Point2D],
+    /// One set of values for each interpolation parameter
+    pub z_values: &'a [Vec<f64>],
+    /// Whether each parameter is an RGB colour, packed as ((a << 24) | (b << 16) | (g << 8) | r)
+    pub is_rgb: &'a [bool],
+    /// The point indices of the triangles, e.g. of a Delaunay `Triangulation`
+    pub triangles: &'a [usize],
+    pub nn_interpolator: Option<&'a NaturalNeighbourInterpolator<'a>>,
+    /// The squared maximum triangle edge length
+    pub max_triangle_edge_length: f64,
+    pub west: f64,
+    pub north: f64,
+    pub grid_res: f64,
+    pub columns: isize,
+    pub nodata: f64,
+}
+
+impl<'a> TinInterpolator<'a> {
+    /// Returns the first and last grid rows that may intersect a triangle.
+    pub fn row_range(&self, triangle: usize) -> (isize, isize) {
+        let i = triangle * 3;
+        let (p1, p2, p3) = (self.triangles[i], self.triangles[i + 1], self.triangles[i + 2]);
+        let bottom = self.points[p1].y.min(self.points[p2].y.min(self.points[p3].y));
+        let top = self.points[p1].y.max(self.points[p2].y.max(self.points[p3].y));
+        (
+            ((self.north - top) / self.grid_res).floor() as isize,
+            ((self.north - bottom) / self.grid_res).ceil() as isize,
+        )
+    }
+
+    /// Rasterizes the listed triangles onto the grid rows from `start_row` up to, but not
+    /// including, `end_row`. Returns the interpolated values of each parameter in row-major order;
+    /// cells that are not covered by an included triangle are assigned the nodata value. Where
+    /// triangles share a grid node, the value of the last triangle in the list is retained.
+    pub fn rasterize(&self, triangles: &[usize], start_row: isize, end_row: isize) -> Vec<Vec<f64>> {
+        let num_params = self.z_values.len();
+        let columns = self.columns;
+        let mut data = vec![vec![self.nodata; ((end_row - start_row) * columns) as usize]; num_params];
+        let points = self.points;
+        let z_values = self.z_values;
+        let is_rgb = self.is_rgb;
+
+        let (mut p1, mut p2, mut p3): (usize, usize, usize);
+        let (mut left, mut right): (f64, f64);
+        let (mut top_row, mut bottom_row, mut left_col, mut right_col): (isize, isize, isize, isize);
+        let mut tri_points: Vec<Point2D> = vec![Point2D::new(0f64, 0f64); 4];
+        let (mut a, mut b, mut c): (Vector3<f64>, Vector3<f64>, Vector3<f64>);
+        let (mut x, mut y): (f64, f64);
+        let mut zn: f64;
+        let mut i: usize;
+        // RGB values are interpolated separately for each of the red, green, and blue channels
+        let num_channels: Vec<usize> = is_rgb.iter().map(|v| if *v { 3 } else { 1 }).collect();
+        let mut included = vec![false; num_params];
+        // the plane equation, i.e. the normal and k, of each parameter's channels
+        let mut planes = vec![[(Vector3::new(0f64, 0f64, 0f64), 0f64); 3]; num_params];
+        let mut channel_values = [0f64; 3];
+        for &triangle in triangles {
+            i = triangle * 3;
+            p1 = self.triangles[i];
+            p2 = self.triangles[i + 1];
+            p3 = self.triangles[i + 2];
+
+            for k in 0..num_params {
+                included[k] = max_distance_squared(points[p1], points[p2], points[p3], z_values[k][p1],
+                    z_values[k][p2], z_values[k][p3]) < self.max_triangle_edge_length;
+            }
+
+            if !included.iter().any(|v| *v) {
+                continue;
+            }
+
+            tri_points[0] = points[p1].clone();
+            tri_points[1] = points[p2].clone();
+            tri_points[2] = points[p3].clone();
+            tri_points[3] = points[p1].clone();
+
+            // get the equations of the planes
+            for k in 0..num_params {
+                for ch in 0..num_channels[k] {
+                    let v1 = channel_value(z_values[k][p1], is_rgb[k], ch);
+                    let v2 = channel_value(z_values[k][p2], is_rgb[k], ch);
+                    let v3 = channel_value(z_values[k][p3], is_rgb[k], ch);
+                    a = Vector3::new(tri_points[0].x, tri_points[0].y, v1);
+                    b = Vector3::new(tri_points[1].x, tri_points[1].y, v2);
+                    c = Vector3::new(tri_points[2].x, tri_points[2].y, v3);
+                    let norm = (b - a).cross(&(c - a));
+                    let k_val = -(tri_points[0].x * norm.x + tri_points[0].y * norm.y + norm.z * v1);
+                    planes[k][ch] = (norm, k_val);
+                }
+            }
+
+            // find grid intersections with this triangle, within the block of rows
+            left = points[p1].x.min(points[p2].x.min(points[p3].x));
+            right = points[p1].x.max(points[p2].x.max(points[p3].x));
+            let (first_row, last_row) = self.row_range(triangle);
+            top_row = first_row.max(start_row);
+            bottom_row = last_row.min(end_row - 1);
+            left_col = (((left - self.west) / self.grid_res).floor() as isize).max(0);
+            right_col = (((right - self.west) / self.grid_res).ceil() as isize).min(columns - 1);
+
+            for row in top_row..=bottom_row {
+                for col in left_col..=right_col {
+                    x = self.west + col as f64 * self.grid_res;
+                    y = self.north - row as f64 * self.grid_res;
+                    if point_in_poly(&Point2D::new(x, y), &tri_points) {
+                        let weights = match self.nn_interpolator {
+                            Some(nn) => nn.get_weights(&Point2D::new(x, y), triangle),
+                            None => None,
+                        };
+                        for k in (0..num_params).filter(|k| included[*k]) {
+                            for ch in 0..num_channels[k] {
+                                channel_values[ch] = match &weights {
+                                    Some(weights) => weights
+                                        .iter()
+                                        .map(|(j, w)| w * channel_value(z_values[k][*j], is_rgb[k], ch))
+                                        .sum(),
+                                    None => {
+                                        let (norm, k_val) = planes[k][ch];
+                                        -(norm.x * x + norm.y * y + k_val) / norm.z
+                                    }
+                                };
+                            }
+                            zn = if is_rgb[k] {
+                                ((255u32 << 24) | ((channel_values[2].round() as u32) << 16) | ((channel_values[1].round() as u32) << 8) | (channel_values[0].round() as u32)) as f64
+                            } else {
+                                channel_values[0]
+                            };
+                            data[k][((row - start_row) * columns + col) as usize] = zn;
+                        }
+                    }
+                }
+            }
+        }
+        data
+    }
+
+    /// Rasterizes the TIN onto the grid rows from 0 up to, but not including, `rows`. The rows
+    /// are divided into blocks, each rasterized from the triangles that intersect it, and when
+    /// `num_threads` is greater than one, the blocks are shared among that many threads. The
+    /// values of each block are passed to `store`, along with its first and last (exclusive)
+    /// rows, in the order in which the blocks are completed (see `rasterize`).
+    pub fn rasterize_rows<F>(&self, rows: isize, num_threads: isize, verbose: bool, mut store: F)
+    where
+        F: FnMut(isize, isize, Vec<Vec<f64>>),
+    {
+        if rows <= 0 {
+            return;
+        }
+        let num_triangles = self.triangles.len() / 3;
+        let mut num_blocks = if num_threads > 1 {
+            (num_threads * 8).min(rows).max(1)
+        } else {
+            1
+        };
+        let block_size = (rows + num_blocks - 1) / num_blocks;
+        num_blocks = (rows + block_size - 1) / block_size;
+
+        // bin each triangle into the blocks of rows that it intersects
+        let mut block_triangles: Vec<Vec<usize>> = vec![vec![]; num_blocks as usize];
+        for triangle in 0..num_triangles {
+            let (top_row, bottom_row) = self.row_range(triangle);
+            if bottom_row < 0 || top_row >= rows {
+                continue;
+            }
+            let first_block = top_row.max(0) / block_size;
+            let last_block = bottom_row.min(rows - 1) / block_size;
+            for block in first_block..=last_block {
+                block_triangles[block as usize].push(triangle);
+            }
+        }
+
+        if num_blocks == 1 {
+            store(0, rows, self.rasterize(&block_triangles[0], 0, rows));
+            return;
+        }
+
+        let mut old_progress: i32 = -1;
+        let block_list = Mutex::new(0..num_blocks);
+        let (tx, rx) = mpsc::channel();
+        thread::scope(|s| {
+            for _ in 0..num_threads {
+                let block_triangles = &block_triangles;
+                let block_list = &block_list;
+                let tx = tx.clone();
+                s.spawn(move || loop {
+                    let block = match block_list.lock().unwrap().next() {
+                        Some(val) => val,
+                        None => break, // There are no more blocks to rasterize
+                    };
+                    let start_row = block * block_size;
+                    let end_row = (start_row + block_size).min(rows);
+                    let data = self.rasterize(&block_triangles[block as usize], start_row, end_row);
+                    tx.send((start_row, end_row, data)).unwrap();
+                });
+            }
+
+            for block in 0..num_blocks {
+                let (start_row, end_row, data) = rx.recv().unwrap();
+                store(start_row, end_row, data);
+                if verbose {
+                    let progress = (100.0_f64 * block as f64 / (num_blocks - 1) as f64) as i32;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Returns the value of one channel of an interpolation parameter; RGB colours are packed as
+/// ((a << 24) | (b << 16) | (g << 8) | r) and channels 0, 1, and 2 are red, green, and blue.
+fn channel_value(value: f64, is_rgb: bool, channel: usize) -> f64 {
+    if is_rgb {
+        ((value as u32 >> (8 * channel)) & 0xFF) as f64
+    } else {
+        value
+    }
+}
+
+/// Returns the largest squared three-dimensional length of the edges of a triangle.
+fn max_distance_squared(p1: Point2D, p2: Point2D, p3: Point2D, z1: f64, z2: f64, z3: f64) -> f64 {
+    let mut dx = p1.x - p2.x;
+    let mut dy = p1.y - p2.y;
+    let mut dz = z1 - z2;
+    let mut max_dist = dx * dx + dy * dy + dz * dz;
+
+    dx = p1.x - p3.x;
+    dy = p1.y - p3.y;
+    dz = z1 - z3;
+    let mut dist = dx * dx + dy * dy + dz * dz;
+
+    if dist > max_dist {
+        max_dist = dist
+    }
+
+    dx = p2.x - p3.x;
+    dy = p2.y - p3.y;
+    dz = z2 - z3;
+    dist = dx * dx + dy * dy + dz * dz;
+
+    if dist > max_dist {
+        max_dist = dist
+    }
+
+    max_dist
+}
@@ -0,0 +1,84 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+/*!
+WhiteboxTools is an advanced geospatial data analysis platform developed at
+the University of Guelph's Geomorphometry and Hydrogeomatics Research Group (GHRG).
+
+In addition to the `whitebox_tools` command-line program, the crate can be used as a
+library by other Rust projects that need to read and write the geospatial data formats
+supported by WhiteboxTools. The following modules make up the public API of the library:
+
+| Module         | Description                                                                         |
+| -------------- | ----------------------------------------------------------------------------------- |
+| `raster`       | The `Raster` type and the drivers for GeoTIFF, Whitebox, ArcGIS, GRASS, Idrisi, SAGA, and Surfer rasters |
+| `lidar`        | The `LasFile` type for reading and writing LAS (and zipped LAS) point clouds        |
+| `vector`       | The `Shapefile` type, its geometries, and its attribute tables                      |
+| `structures`   | General-purpose data structures, e.g. `Array2D`, `BoundingBox`, and `Point2D`       |
+
+The most commonly used types are also re-exported from the crate root:
+
+```no_run
+use whitebox_tools::{LasFile, Raster, Shapefile};
+
+fn main() -> std::io::Result<()> {
+    // Read a raster and create a new raster with the same extent
+    let dem = Raster::new("/path/to/DEM.tif", "r")?;
+    let mut output = Raster::initialize_using_file("/path/to/output.tif", &dem);
+    output.set_value(0, 0, dem.get_value(0, 0) * 2.0);
+    output.write()?;
+
+    // Read the points in a LAS file
+    let las = LasFile::new("/path/to/points.las", "r")?;
+    for i in 0..las.header.number_of_points as usize {
+        let p = las.get_point_info(i);
+        println!("{} {} {}", p.x, p.y, p.z);
+    }
+
+    // Read a Shapefile
+    let streams = Shapefile::read("/path/to/streams.shp")?;
+    println!("Number of records: {}", streams.num_records);
+    Ok(())
+}
+```
+
+# Stability
+
+The `raster`, `lidar`, `vector`, and `structures` modules, and the crate-root re-exports,
+follow semantic versioning: items in these modules will not be removed or changed in an
+incompatible way except in a new major version of the crate. The remaining modules
+(`tools`, `algorithms`, `rendering`, `spatial_ref_system`, and `utils`) support the
+command-line program and its tools; they are public so that the program can use them but are
+hidden from the documentation and may change in any release.
+*/
+
+pub mod lidar;
+pub mod raster;
+pub mod structures;
+pub mod vector;
+
+#[doc(hidden)]
+pub mod algorithms;
+#[doc(hidden)]
+pub mod rendering;
+#[doc(hidden)]
+pub mod spatial_ref_system;
+#[doc(hidden)]
+pub mod tools;
+#[doc(hidden)]
+pub mod utils;
+
+pub use crate::lidar::{LasFile, LasHeader, PointData};
+pub use crate::raster::{DataType, PhotometricInterpretation, Raster, RasterConfigs, RasterType};
+pub use crate::structures::{Array2D, BoundingBox, Point2D};
+pub use crate::vector::{ShapeType, Shapefile, ShapefileGeometry};
+
+use nalgebra as na;
+
+#[macro_use]
+extern crate serde_derive;
@@ -0,0 +1,793 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::point_cloud::{CloudPoint, PointCloud};
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::path::Path;
+
+/// The size of the physical pages of an E57 file. Each page ends with a 4-byte CRC-32C checksum.
+const PAGE_SIZE: usize = 1024;
+const PAGE_DATA_SIZE: usize = PAGE_SIZE - 4;
+const FILE_HEADER_SIZE: usize = 48;
+const SECTION_HEADER_SIZE: usize = 32;
+const MAX_PACKET_SIZE: usize = 65536;
+const COMPRESSED_VECTOR_SECTION: u8 = 1;
+const DATA_PACKET: u8 = 1;
+
+/// Reads an ASTM E57 file. The points of all of the scans in the file's 'data3D' vector are
+/// combined, after transforming them by each scan's pose. Scans stored in spherical coordinates
+/// are converted to Cartesian coordinates and points flagged as invalid are skipped. Only the
+/// bitpack codec, which is used for virtually all E57 files, is supported.
+pub fn read_e57(file_name: &str) -> Result<PointCloud, Error> {
+    let buffer = fs::read(file_name)?;
+    if buffer.len() < FILE_HEADER_SIZE || &buffer[0..8] != b"ASTM-E57" {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The file does not appear to be an E57 file.",
+        ));
+    }
+    let xml_physical_offset = read_u64(&buffer, 24)? as usize;
+    let xml_length = read_u64(&buffer, 32)? as usize;
+    let page_size = read_u64(&buffer, 40)? as usize;
+    if page_size <= 4 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The E57 file header contains an invalid page size.",
+        ));
+    }
+
+    // strip the page checksums, leaving the logical byte sequence
+    let logical: Vec<u8> = buffer
+        .chunks(page_size)
+        .flat_map(|page| page[0..page.len().saturating_sub(4)].iter().cloned())
+        .collect();
+    let xml_start = physical_to_logical(xml_physical_offset, page_size);
+    let xml_bytes = logical
+        .get(xml_start..xml_start + xml_length)
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::UnexpectedEof,
+                "The E57 file's XML section is truncated.",
+            )
+        })?;
+    let root = parse_xml(&String::from_utf8_lossy(xml_bytes))?;
+
+    let data3d = match root.child("data3D") {
+        Some(d) => d,
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The E57 file does not contain any 3D data.",
+            ))
+        }
+    };
+
+    let mut cloud = PointCloud::default();
+    let mut intensities: Vec<f64> = vec![];
+    let (mut reds, mut greens, mut blues): (Vec<f64>, Vec<f64>, Vec<f64>) =
+        (vec![], vec![], vec![]);
+    let mut has_intensity = false;
+    let mut has_colour = false;
+    for scan in &data3d.children {
+        let points = match scan.child("points") {
+            Some(p) => p,
+            None => continue,
+        };
+        let prototype = match points.child("prototype") {
+            Some(p) => p,
+            None => continue,
+        };
+        let record_count = points
+            .attribute("recordCount")
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+        let file_offset = points
+            .attribute("fileOffset")
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+        if record_count == 0 {
+            continue;
+        }
+        if let Some(codecs) = points.child("codecs") {
+            if !codecs.children.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The E57 file uses an unsupported codec; only bitpack-encoded data can be read.",
+                ));
+            }
+        }
+
+        let streams =
+            read_compressed_vector(&logical, file_offset, page_size, prototype.children.len())?;
+        let mut fields: HashMap<&str, Vec<f64>> = HashMap::new();
+        for (field, stream) in prototype.children.iter().zip(&streams) {
+            fields.insert(
+                field.name.as_str(),
+                decode_field(field, stream, record_count)?,
+            );
+        }
+
+        let coordinates = if let (Some(x), Some(y), Some(z)) = (
+            fields.get("cartesianX"),
+            fields.get("cartesianY"),
+            fields.get("cartesianZ"),
+        ) {
+            (x.clone(), y.clone(), z.clone())
+        } else if let (Some(range), Some(azimuth), Some(elevation)) = (
+            fields.get("sphericalRange"),
+            fields.get("sphericalAzimuth"),
+            fields.get("sphericalElevation"),
+        ) {
+            let mut x = vec![0f64; record_count];
+            let mut y = vec![0f64; record_count];
+            let mut z = vec![0f64; record_count];
+            for i in 0..record_count {
+                x[i] = range[i] * elevation[i].cos() * azimuth[i].cos();
+                y[i] = range[i] * elevation[i].cos() * azimuth[i].sin();
+                z[i] = range[i] * elevation[i].sin();
+            }
+            (x, y, z)
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "An E57 scan does not contain either Cartesian or spherical coordinates.",
+            ));
+        };
+        let invalid_state = fields
+            .get("cartesianInvalidState")
+            .or_else(|| fields.get("sphericalInvalidState"));
+
+        let (rotation, translation) = match scan.child("pose") {
+            Some(pose) => read_pose(pose),
+            None => (
+                [[1f64, 0f64, 0f64], [0f64, 1f64, 0f64], [0f64, 0f64, 1f64]],
+                [0f64; 3],
+            ),
+        };
+
+        let intensity = fields.get("intensity");
+        let colours = match (
+            fields.get("colorRed"),
+            fields.get("colorGreen"),
+            fields.get("colorBlue"),
+        ) {
+            (Some(r), Some(g), Some(b)) => Some((r, g, b)),
+            _ => None,
+        };
+        has_intensity |= intensity.is_some();
+        has_colour |= colours.is_some();
+        for i in 0..record_count {
+            if let Some(state) = invalid_state {
+                if state[i] != 0f64 {
+                    continue;
+                }
+            }
+            let (x, y, z) = (coordinates.0[i], coordinates.1[i], coordinates.2[i]);
+            cloud.points.push(CloudPoint {
+                x: rotation[0][0] * x + rotation[0][1] * y + rotation[0][2] * z + translation[0],
+                y: rotation[1][0] * x + rotation[1][1] * y + rotation[1][2] * z + translation[1],
+                z: rotation[2][0] * x + rotation[2][1] * y + rotation[2][2] * z + translation[2],
+                ..Default::default()
+            });
+            intensities.push(intensity.map(|v| v[i]).unwrap_or(0f64));
+            if let Some((r, g, b)) = colours {
+                reds.push(r[i]);
+                greens.push(g[i]);
+                blues.push(b[i]);
+            } else {
+                reds.push(0f64);
+                greens.push(0f64);
+                blues.push(0f64);
+            }
+        }
+    }
+
+    if has_intensity {
+        cloud.set_intensities(&intensities);
+    }
+    if has_colour {
+        cloud.set_colours(&reds, &greens, &blues);
+    }
+    if let Some(crs) = root.child("coordinateMetadata") {
+        cloud.wkt = crs.text.trim().to_string();
+    }
+
+    Ok(cloud)
+}
+
+/// Writes a point cloud to an ASTM E57 file containing a single scan. Coordinates are stored
+/// as double-precision floats, and intensities and colours as bitpacked integers.
+pub fn write_e57(cloud: &PointCloud, file_name: &str) -> Result<(), Error> {
+    let colour_max: u16 = if cloud.is_colour_16bit() { 65535 } else { 255 };
+    let colour_size = if colour_max > 255 { 2 } else { 1 };
+    let mut num_streams = 3;
+    let mut record_size = 24;
+    if cloud.has_intensity {
+        num_streams += 1;
+        record_size += 2;
+    }
+    if cloud.has_colour {
+        num_streams += 3;
+        record_size += 3 * colour_size;
+    }
+
+    // The binary section holding the points immediately follows the file header. The integer
+    // fields use whole bytes, so that no bits are carried over between packets.
+    let mut logical: Vec<u8> = vec![0u8; FILE_HEADER_SIZE + SECTION_HEADER_SIZE];
+    let section_start = FILE_HEADER_SIZE;
+    let data_start = logical.len();
+    let packet_header_size = 6 + 2 * num_streams;
+    let records_per_packet = (MAX_PACKET_SIZE - packet_header_size - 4) / record_size;
+    for chunk in cloud.points.chunks(records_per_packet.max(1)) {
+        let mut buffers: Vec<Vec<u8>> = vec![vec![]; num_streams];
+        for p in chunk {
+            buffers[0].extend_from_slice(&p.x.to_le_bytes());
+            buffers[1].extend_from_slice(&p.y.to_le_bytes());
+            buffers[2].extend_from_slice(&p.z.to_le_bytes());
+            let mut s = 3;
+            if cloud.has_intensity {
+                buffers[s].extend_from_slice(&p.intensity.to_le_bytes());
+                s += 1;
+            }
+            if cloud.has_colour {
+                for (j, value) in [p.red, p.green, p.blue].iter().enumerate() {
+                    if colour_size == 2 {
+                        buffers[s + j].extend_from_slice(&value.to_le_bytes());
+                    } else {
+                        buffers[s + j].push(*value as u8);
+                    }
+                }
+            }
+        }
+        let packet_start = logical.len();
+        logical.push(DATA_PACKET);
+        logical.push(0u8); // packet flags
+        logical.extend_from_slice(&[0u8; 2]); // packet length, which is set below
+        logical.extend_from_slice(&(num_streams as u16).to_le_bytes());
+        for b in &buffers {
+            logical.extend_from_slice(&(b.len() as u16).to_le_bytes());
+        }
+        for b in &buffers {
+            logical.extend_from_slice(b);
+        }
+        // packets are padded to a multiple of four bytes
+        let padded_length = (logical.len() - packet_start).div_ceil(4) * 4;
+        logical.resize(packet_start + padded_length, 0u8);
+        let packet_length = (logical.len() - packet_start - 1) as u16;
+        logical[packet_start + 2..packet_start + 4].copy_from_slice(&packet_length.to_le_bytes());
+    }
+    let section_length = (logical.len() - section_start) as u64;
+    logical[section_start] = COMPRESSED_VECTOR_SECTION;
+    logical[section_start + 8..section_start + 16].copy_from_slice(&section_length.to_le_bytes());
+    logical[section_start + 16..section_start + 24]
+        .copy_from_slice(&(logical_to_physical(data_start) as u64).to_le_bytes());
+
+    // the XML section describing the file's contents
+    let xml = create_xml(cloud, file_name, section_start, colour_max);
+    let xml_start = logical.len();
+    logical.extend_from_slice(xml.as_bytes());
+
+    let num_pages = logical.len().div_ceil(PAGE_DATA_SIZE);
+    logical[0..8].copy_from_slice(b"ASTM-E57");
+    logical[8..12].copy_from_slice(&1u32.to_le_bytes()); // major version
+    logical[12..16].copy_from_slice(&0u32.to_le_bytes()); // minor version
+    logical[16..24].copy_from_slice(&((num_pages * PAGE_SIZE) as u64).to_le_bytes());
+    logical[24..32].copy_from_slice(&(logical_to_physical(xml_start) as u64).to_le_bytes());
+    logical[32..40].copy_from_slice(&(xml.len() as u64).to_le_bytes());
+    logical[40..48].copy_from_slice(&(PAGE_SIZE as u64).to_le_bytes());
+    logical.resize(num_pages * PAGE_DATA_SIZE, 0u8);
+
+    let f = File::create(file_name)?;
+    let mut writer = BufWriter::new(f);
+    for page in logical.chunks(PAGE_DATA_SIZE) {
+        writer.write_all(page)?;
+        writer.write_all(&crc32c(page).to_be_bytes())?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn create_xml(cloud: &PointCloud, file_name: &str, file_offset: usize, colour_max: u16) -> String {
+    let name = Path::new(file_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_string();
+    let (mut min_x, mut min_y, mut min_z) = (f64::INFINITY, f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y, mut max_z) =
+        (f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for p in &cloud.points {
+        min_x = min_x.min(p.x);
+        min_y = min_y.min(p.y);
+        min_z = min_z.min(p.z);
+        max_x = max_x.max(p.x);
+        max_y = max_y.max(p.y);
+        max_z = max_z.max(p.z);
+    }
+    if cloud.points.is_empty() {
+        min_x = 0f64;
+        min_y = 0f64;
+        min_z = 0f64;
+        max_x = 0f64;
+        max_y = 0f64;
+        max_z = 0f64;
+    }
+
+    let mut s = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    s.push_str(
+        "<e57Root type=\"Structure\" xmlns=\"http://www.astm.org/COMMIT/E57/2010-e57-v1.0\">\n",
+    );
+    s.push_str(
+        "  <formatName type=\"String\"><![CDATA[ASTM E57 3D Imaging Data File]]></formatName>\n",
+    );
+    s.push_str(&format!(
+        "  <guid type=\"String\"><![CDATA[{}]]></guid>\n",
+        create_guid()
+    ));
+    s.push_str("  <versionMajor type=\"Integer\">1</versionMajor>\n");
+    s.push_str("  <versionMinor type=\"Integer\">0</versionMinor>\n");
+    s.push_str(
+        "  <e57LibraryVersion type=\"String\"><![CDATA[WhiteboxTools]]></e57LibraryVersion>\n",
+    );
+    s.push_str(&format!(
+        "  <coordinateMetadata type=\"String\"><![CDATA[{}]]></coordinateMetadata>\n",
+        cloud.wkt
+    ));
+    s.push_str("  <data3D type=\"Vector\" allowHeterogeneousChildren=\"1\">\n");
+    s.push_str("    <vectorChild type=\"Structure\">\n");
+    s.push_str(&format!(
+        "      <guid type=\"String\"><![CDATA[{}]]></guid>\n",
+        create_guid()
+    ));
+    s.push_str(&format!(
+        "      <name type=\"String\"><![CDATA[{}]]></name>\n",
+        name
+    ));
+    s.push_str("      <cartesianBounds type=\"Structure\">\n");
+    for (tag, value) in &[
+        ("xMinimum", min_x),
+        ("xMaximum", max_x),
+        ("yMinimum", min_y),
+        ("yMaximum", max_y),
+        ("zMinimum", min_z),
+        ("zMaximum", max_z),
+    ] {
+        s.push_str(&format!(
+            "        <{0} type=\"Float\">{1:?}</{0}>\n",
+            tag, value
+        ));
+    }
+    s.push_str("      </cartesianBounds>\n");
+    if cloud.has_intensity {
+        s.push_str("      <intensityLimits type=\"Structure\">\n");
+        s.push_str("        <intensityMinimum type=\"Integer\">0</intensityMinimum>\n");
+        s.push_str("        <intensityMaximum type=\"Integer\">65535</intensityMaximum>\n");
+        s.push_str("      </intensityLimits>\n");
+    }
+    if cloud.has_colour {
+        s.push_str("      <colorLimits type=\"Structure\">\n");
+        for channel in &["Red", "Green", "Blue"] {
+            s.push_str(&format!(
+                "        <color{0}Minimum type=\"Integer\">0</color{0}Minimum>\n",
+                channel
+            ));
+            s.push_str(&format!(
+                "        <color{0}Maximum type=\"Integer\">{1}</color{0}Maximum>\n",
+                channel, colour_max
+            ));
+        }
+        s.push_str("      </colorLimits>\n");
+    }
+    s.push_str(&format!(
+        "      <points type=\"CompressedVector\" fileOffset=\"{}\" recordCount=\"{}\">\n",
+        logical_to_physical(file_offset),
+        cloud.points.len()
+    ));
+    s.push_str("        <prototype type=\"Structure\">\n");
+    s.push_str("          <cartesianX type=\"Float\"/>\n");
+    s.push_str("          <cartesianY type=\"Float\"/>\n");
+    s.push_str("          <cartesianZ type=\"Float\"/>\n");
+    if cloud.has_intensity {
+        s.push_str("          <intensity type=\"Integer\" minimum=\"0\" maximum=\"65535\"/>\n");
+    }
+    if cloud.has_colour {
+        for channel in &["Red", "Green", "Blue"] {
+            s.push_str(&format!(
+                "          <color{} type=\"Integer\" minimum=\"0\" maximum=\"{}\"/>\n",
+                channel, colour_max
+            ));
+        }
+    }
+    s.push_str("        </prototype>\n");
+    s.push_str("        <codecs type=\"Vector\" allowHeterogeneousChildren=\"1\"/>\n");
+    s.push_str("      </points>\n");
+    s.push_str("    </vectorChild>\n");
+    s.push_str("  </data3D>\n");
+    s.push_str("  <images2D type=\"Vector\" allowHeterogeneousChildren=\"1\"/>\n");
+    s.push_str("</e57Root>\n");
+    s
+}
+
+fn create_guid() -> String {
+    let a: u32 = rand::random();
+    let b: u32 = rand::random();
+    let c: u32 = rand::random();
+    let d: u32 = rand::random();
+    format!(
+        "{{{:08X}-{:04X}-{:04X}-{:04X}-{:04X}{:08X}}}",
+        a,
+        b >> 16,
+        (b & 0x0FFF) | 0x4000,
+        (c >> 16) & 0x3FFF | 0x8000,
+        c & 0xFFFF,
+        d
+    )
+}
+
+/// Reads the data packets of a compressed vector binary section, returning the concatenated
+/// bytes of each of the section's bytestreams.
+fn read_compressed_vector(
+    logical: &[u8],
+    file_offset: usize,
+    page_size: usize,
+    num_streams: usize,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let truncated = || {
+        Error::new(
+            ErrorKind::UnexpectedEof,
+            "An E57 binary section is truncated.",
+        )
+    };
+    let start = physical_to_logical(file_offset, page_size);
+    if start + SECTION_HEADER_SIZE > logical.len() || logical[start] != COMPRESSED_VECTOR_SECTION {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "An E57 points vector does not refer to a compressed vector section.",
+        ));
+    }
+    let section_length = read_u64(logical, start + 8)? as usize;
+    let end = (start + section_length).min(logical.len());
+    let mut pos = physical_to_logical(read_u64(logical, start + 16)? as usize, page_size);
+    let mut streams: Vec<Vec<u8>> = vec![vec![]; num_streams];
+    while pos + 4 <= end {
+        let packet_type = logical[pos];
+        let packet_length = read_u16(logical, pos + 2)? as usize + 1;
+        if packet_type == DATA_PACKET {
+            let count = read_u16(logical, pos + 4)? as usize;
+            let mut p = pos + 6 + 2 * count;
+            for i in 0..count {
+                let length = read_u16(logical, pos + 6 + 2 * i)? as usize;
+                let bytes = logical.get(p..p + length).ok_or_else(truncated)?;
+                if let Some(stream) = streams.get_mut(i) {
+                    stream.extend_from_slice(bytes);
+                }
+                p += length;
+            }
+        }
+        pos += packet_length;
+    }
+    Ok(streams)
+}
+
+/// Decodes the values of a prototype field from its bitpacked bytestream.
+fn decode_field(field: &XmlElement, bytes: &[u8], count: usize) -> Result<Vec<f64>, Error> {
+    let truncated = || {
+        Error::new(
+            ErrorKind::UnexpectedEof,
+            "An E57 bytestream contains too few values.",
+        )
+    };
+    match field.attribute("type") {
+        Some("Float") => {
+            if field.attribute("precision") == Some("single") {
+                let data = bytes.get(0..count * 4).ok_or_else(truncated)?;
+                Ok(data
+                    .chunks(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+                    .collect())
+            } else {
+                let data = bytes.get(0..count * 8).ok_or_else(truncated)?;
+                Ok(data
+                    .chunks(8)
+                    .map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+                    .collect())
+            }
+        }
+        Some(t) if t == "Integer" || t == "ScaledInteger" => {
+            let minimum = field
+                .attribute("minimum")
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(i64::MIN);
+            let maximum = field
+                .attribute("maximum")
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(i64::MAX);
+            let (scale, offset) = if t == "ScaledInteger" {
+                (
+                    field
+                        .attribute("scale")
+                        .and_then(|v| v.parse::<f64>().ok())
+                        .unwrap_or(1f64),
+                    field
+                        .attribute("offset")
+                        .and_then(|v| v.parse::<f64>().ok())
+                        .unwrap_or(0f64),
+                )
+            } else {
+                (1f64, 0f64)
+            };
+            let range = (maximum as i128 - minimum as i128).max(0) as u128;
+            let bits = 128 - range.leading_zeros() as usize;
+            if bytes.len() * 8 < bits * count {
+                return Err(truncated());
+            }
+            Ok(unpack_integers(bytes, count, bits, minimum)
+                .iter()
+                .map(|v| *v as f64 * scale + offset)
+                .collect())
+        }
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "The E57 point field '{}' has an unsupported type.",
+                field.name
+            ),
+        )),
+    }
+}
+
+/// Unpacks integers stored in a least-significant-bit-first bitstream, with each value stored
+/// as its offset from the field minimum.
+fn unpack_integers(bytes: &[u8], count: usize, bits: usize, minimum: i64) -> Vec<i64> {
+    let mut values = Vec::with_capacity(count);
+    let mut bit_pos = 0usize;
+    for _ in 0..count {
+        let mut value = 0u64;
+        let mut num_read = 0usize;
+        while num_read < bits {
+            let byte = bytes[bit_pos / 8] as u64;
+            let bit_offset = bit_pos % 8;
+            let take = (8 - bit_offset).min(bits - num_read);
+            value |= ((byte >> bit_offset) & ((1u64 << take) - 1)) << num_read;
+            num_read += take;
+            bit_pos += take;
+        }
+        values.push(minimum.wrapping_add(value as i64));
+    }
+    values
+}
+
+/// Returns the rotation matrix and translation of a scan's pose, which is described by a unit
+/// quaternion and a translation vector.
+fn read_pose(pose: &XmlElement) -> ([[f64; 3]; 3], [f64; 3]) {
+    let value = |parent: Option<&XmlElement>, name: &str, default: f64| -> f64 {
+        parent
+            .and_then(|p| p.child(name))
+            .and_then(|c| c.text.trim().parse::<f64>().ok())
+            .unwrap_or(default)
+    };
+    let rotation = pose.child("rotation");
+    let (w, x, y, z) = (
+        value(rotation, "w", 1f64),
+        value(rotation, "x", 0f64),
+        value(rotation, "y", 0f64),
+        value(rotation, "z", 0f64),
+    );
+    let translation = pose.child("translation");
+    (
+        [
+            [
+                1f64 - 2f64 * (y * y + z * z),
+                2f64 * (x * y - z * w),
+                2f64 * (x * z + y * w),
+            ],
+            [
+                2f64 * (x * y + z * w),
+                1f64 - 2f64 * (x * x + z * z),
+                2f64 * (y * z - x * w),
+            ],
+            [
+                2f64 * (x * z - y * w),
+                2f64 * (y * z + x * w),
+                1f64 - 2f64 * (x * x + y * y),
+            ],
+        ],
+        [
+            value(translation, "x", 0f64),
+            value(translation, "y", 0f64),
+            value(translation, "z", 0f64),
+        ],
+    )
+}
+
+fn physical_to_logical(offset: usize, page_size: usize) -> usize {
+    (offset / page_size) * (page_size - 4) + offset % page_size
+}
+
+fn logical_to_physical(offset: usize) -> usize {
+    (offset / PAGE_DATA_SIZE) * PAGE_SIZE + offset % PAGE_DATA_SIZE
+}
+
+fn read_u16(bytes: &[u8], pos: usize) -> Result<u16, Error> {
+    match bytes.get(pos..pos + 2) {
+        Some(b) => Ok(u16::from_le_bytes([b[0], b[1]])),
+        None => Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "Unexpected end of the E57 file.",
+        )),
+    }
+}
+
+fn read_u64(bytes: &[u8], pos: usize) -> Result<u64, Error> {
+    match bytes.get(pos..pos + 8) {
+        Some(b) => Ok(u64::from_le_bytes([
+            b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7],
+        ])),
+        None => Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "Unexpected end of the E57 file.",
+        )),
+    }
+}
+
+/// Calculates the CRC-32C (Castagnoli) checksum used by the E57 page structure.
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0x82F6_3B78
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// A minimal XML element, sufficient for reading the XML section of an E57 file.
+#[derive(Default, Debug)]
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|a| a.0 == name)
+            .map(|a| a.1.as_str())
+    }
+}
+
+/// Parses an XML document, returning its root element.
+fn parse_xml(text: &str) -> Result<XmlElement, Error> {
+    let malformed = || Error::new(ErrorKind::InvalidData, "The E57 file's XML is malformed.");
+    let mut stack: Vec<XmlElement> = vec![XmlElement::default()];
+    let mut pos = 0usize;
+    while pos < text.len() {
+        let rest = &text[pos..];
+        if rest.starts_with("<![CDATA[") {
+            let end = rest.find("]]>").ok_or_else(malformed)?;
+            stack
+                .last_mut()
+                .ok_or_else(malformed)?
+                .text
+                .push_str(&rest[9..end]);
+            pos += end + 3;
+        } else if rest.starts_with("<!--") {
+            pos += rest.find("-->").ok_or_else(malformed)? + 3;
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            pos += rest.find('>').ok_or_else(malformed)? + 1;
+        } else if rest.starts_with("</") {
+            pos += rest.find('>').ok_or_else(malformed)? + 1;
+            let element = stack.pop().ok_or_else(malformed)?;
+            stack
+                .last_mut()
+                .ok_or_else(malformed)?
+                .children
+                .push(element);
+        } else if rest.starts_with('<') {
+            // find the end of the tag, ignoring any '>' within quoted attribute values
+            let mut quote: Option<char> = None;
+            let mut end = None;
+            for (i, c) in rest.char_indices() {
+                match quote {
+                    Some(q) if c == q => quote = None,
+                    Some(_) => {}
+                    None if c == '"' || c == '\'' => quote = Some(c),
+                    None if c == '>' => {
+                        end = Some(i);
+                        break;
+                    }
+                    None => {}
+                }
+            }
+            let end = end.ok_or_else(malformed)?;
+            let mut tag = &rest[1..end];
+            let self_closing = tag.ends_with('/');
+            if self_closing {
+                tag = &tag[..tag.len() - 1];
+            }
+            let element = parse_tag(tag).ok_or_else(malformed)?;
+            if self_closing {
+                stack
+                    .last_mut()
+                    .ok_or_else(malformed)?
+                    .children
+                    .push(element);
+            } else {
+                stack.push(element);
+            }
+            pos += end + 1;
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            stack
+                .last_mut()
+                .ok_or_else(malformed)?
+                .text
+                .push_str(&unescape_xml(&rest[..end]));
+            pos += end;
+        }
+    }
+    if stack.len() != 1 {
+        return Err(malformed());
+    }
+    let mut document = stack.pop().ok_or_else(malformed)?;
+    if document.children.is_empty() {
+        return Err(malformed());
+    }
+    Ok(document.children.remove(0))
+}
+
+/// Parses the name and attributes of an XML start tag.
+fn parse_tag(tag: &str) -> Option<XmlElement> {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let mut element = XmlElement {
+        name: tag[..name_end].to_string(),
+        ..Default::default()
+    };
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let equals = rest.find('=')?;
+        let name = rest[..equals].trim().to_string();
+        rest = rest[equals + 1..].trim_start();
+        let quote = rest.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value_end = rest[1..].find(quote)? + 1;
+        element
+            .attributes
+            .push((name, unescape_xml(&rest[1..value_end])));
+        rest = rest[value_end + 1..].trim_start();
+    }
+    Some(element)
+}
+
+fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,303 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use byteorder::{ByteOrder, LittleEndian};
+use std::fmt;
+
+/// The size, in bytes, of a single extra bytes descriptor within the
+/// Extra Bytes VLR (User ID 'LASF_Spec', Record ID 4).
+pub const EXTRA_BYTES_DESCRIPTOR_SIZE: usize = 192;
+
+/// The record ID of the Extra Bytes VLR.
+pub const EXTRA_BYTES_RECORD_ID: u16 = 4;
+
+/// Describes a single per-point attribute stored in the 'extra bytes' that follow
+/// the standard fields of a LAS point record, as defined by the LAS 1.4
+/// specification Extra Bytes VLR.
+#[derive(Clone, Debug)]
+pub struct ExtraBytesDescriptor {
+    pub data_type: u8,
+    pub options: u8,
+    pub name: String,
+    /// The no_data, min, and max values hold one 8-byte 'anytype' value per element, i.e. a
+    /// u64 for unsigned data types, an i64 for signed data types, and an f64 for floating
+    /// point data types, regardless of the size of the data type.
+    pub no_data: [u8; 24],
+    pub min: [u8; 24],
+    pub max: [u8; 24],
+    pub scale: [f64; 3],
+    pub offset: [f64; 3],
+    pub description: String,
+}
+
+impl Default for ExtraBytesDescriptor {
+    fn default() -> ExtraBytesDescriptor {
+        ExtraBytesDescriptor {
+            data_type: 0u8,
+            options: 0u8,
+            name: String::new(),
+            no_data: [0u8; 24],
+            min: [0u8; 24],
+            max: [0u8; 24],
+            scale: [1f64; 3],
+            offset: [0f64; 3],
+            description: String::new(),
+        }
+    }
+}
+
+impl ExtraBytesDescriptor {
+    /// Creates a new descriptor for a scalar attribute. The `data_type` follows the
+    /// LAS 1.4 numbering (1 = u8, 2 = i8, 3 = u16, 4 = i16, 5 = u32, 6 = i32,
+    /// 7 = u64, 8 = i64, 9 = f32, 10 = f64).
+    pub fn new(name: &str, data_type: u8, description: &str) -> ExtraBytesDescriptor {
+        ExtraBytesDescriptor {
+            data_type,
+            name: name.to_string(),
+            description: description.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Interprets a 192-byte descriptor record.
+    pub fn from_bytes(bytes: &[u8]) -> ExtraBytesDescriptor {
+        let mut ebd = ExtraBytesDescriptor {
+            data_type: bytes[2],
+            options: bytes[3],
+            name: bytes_to_string(&bytes[4..36]),
+            description: bytes_to_string(&bytes[160..192]),
+            ..Default::default()
+        };
+        ebd.no_data.copy_from_slice(&bytes[40..64]);
+        ebd.min.copy_from_slice(&bytes[64..88]);
+        ebd.max.copy_from_slice(&bytes[88..112]);
+        for i in 0..3 {
+            ebd.scale[i] = LittleEndian::read_f64(&bytes[112 + i * 8..120 + i * 8]);
+            ebd.offset[i] = LittleEndian::read_f64(&bytes[136 + i * 8..144 + i * 8]);
+        }
+        ebd
+    }
+
+    /// Returns the 192-byte record used to represent this descriptor in the Extra Bytes VLR.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; EXTRA_BYTES_DESCRIPTOR_SIZE];
+        bytes[2] = self.data_type;
+        bytes[3] = self.options;
+        string_to_bytes(&self.name, &mut bytes[4..36]);
+        bytes[40..64].copy_from_slice(&self.no_data);
+        bytes[64..88].copy_from_slice(&self.min);
+        bytes[88..112].copy_from_slice(&self.max);
+        for i in 0..3 {
+            LittleEndian::write_f64(&mut bytes[112 + i * 8..120 + i * 8], self.scale[i]);
+            LittleEndian::write_f64(&mut bytes[136 + i * 8..144 + i * 8], self.offset[i]);
+        }
+        string_to_bytes(&self.description, &mut bytes[160..192]);
+        bytes
+    }
+
+    /// Returns the number of elements (1, 2, or 3) in the attribute. Data types
+    /// 11-30 are deprecated in LAS 1.4 but represent two- and three-element arrays.
+    pub fn num_elements(&self) -> usize {
+        match self.data_type {
+            11..=20 => 2,
+            21..=30 => 3,
+            _ => 1,
+        }
+    }
+
+    /// Returns the size, in bytes, occupied by this attribute within each point record.
+    pub fn get_size(&self) -> usize {
+        if self.data_type == 0 {
+            // undocumented extra bytes; the options field holds the number of bytes
+            return self.options as usize;
+        }
+        let base_size = match (self.data_type - 1) % 10 + 1 {
+            1 | 2 => 1,
+            3 | 4 => 2,
+            5 | 6 | 9 => 4,
+            _ => 8,
+        };
+        base_size * self.num_elements()
+    }
+
+    pub fn has_no_data(&self) -> bool {
+        self.options & 1u8 == 1u8
+    }
+
+    pub fn has_scale(&self) -> bool {
+        self.options & 8u8 == 8u8
+    }
+
+    pub fn has_offset(&self) -> bool {
+        self.options & 16u8 == 16u8
+    }
+
+    /// Sets the scale factor that is applied to the stored value of the first element.
+    pub fn set_scale(&mut self, scale: f64) {
+        self.scale[0] = scale;
+        self.options |= 8u8;
+    }
+
+    /// Sets the offset that is added to the scaled value of the first element.
+    pub fn set_offset(&mut self, offset: f64) {
+        self.offset[0] = offset;
+        self.options |= 16u8;
+    }
+
+    /// Sets the raw (i.e. unscaled) value used to represent missing data.
+    pub fn set_no_data(&mut self, value: f64) {
+        // the value is limited to the range of the data type, as the stored values are, such that
+        // it compares equal to the values of points that are set to it
+        let raw_descriptor = ExtraBytesDescriptor {
+            data_type: self.data_type,
+            ..Default::default()
+        };
+        let mut raw_bytes = [0u8; 8];
+        raw_descriptor.set_value(value, &mut raw_bytes);
+        let mut bytes = [0u8; 24];
+        write_anytype((self.data_type - 1) % 10 + 1, &raw_bytes, &mut bytes[0..8]);
+        self.no_data = bytes;
+        self.options |= 1u8;
+    }
+
+    /// Interprets the first element of the attribute from the extra bytes of a
+    /// single point, applying the scale and offset, if they are specified.
+    /// Returns `f64::NAN` for undocumented extra bytes and for no-data values.
+    pub fn get_value(&self, bytes: &[u8]) -> f64 {
+        if self.data_type == 0 || bytes.len() < self.get_size() {
+            return f64::NAN;
+        }
+        let raw = read_typed_value((self.data_type - 1) % 10 + 1, bytes);
+        if self.has_no_data() {
+            let no_data = read_anytype((self.data_type - 1) % 10 + 1, &self.no_data);
+            if raw == no_data {
+                return f64::NAN;
+            }
+        }
+        let mut value = raw;
+        if self.has_scale() {
+            value *= self.scale[0];
+        }
+        if self.has_offset() {
+            value += self.offset[0];
+        }
+        value
+    }
+
+    /// Encodes a value into the bytes occupied by the attribute within a point
+    /// record, reversing the scale and offset, if they are specified.
+    pub fn set_value(&self, value: f64, bytes: &mut [u8]) {
+        if self.data_type == 0 || bytes.len() < self.get_size() {
+            return;
+        }
+        let mut raw = value;
+        if self.has_offset() {
+            raw -= self.offset[0];
+        }
+        if self.has_scale() && self.scale[0] != 0f64 {
+            raw /= self.scale[0];
+        }
+        let raw_int = raw.round();
+        match (self.data_type - 1) % 10 + 1 {
+            1 => bytes[0] = raw_int.max(0f64).min(u8::MAX as f64) as u8,
+            2 => bytes[0] = (raw_int.max(i8::MIN as f64).min(i8::MAX as f64) as i8) as u8,
+            3 => LittleEndian::write_u16(bytes, raw_int.max(0f64).min(u16::MAX as f64) as u16),
+            4 => LittleEndian::write_i16(
+                bytes,
+                raw_int.max(i16::MIN as f64).min(i16::MAX as f64) as i16,
+            ),
+            5 => LittleEndian::write_u32(bytes, raw_int.max(0f64).min(u32::MAX as f64) as u32),
+            6 => LittleEndian::write_i32(
+                bytes,
+                raw_int.max(i32::MIN as f64).min(i32::MAX as f64) as i32,
+            ),
+            7 => LittleEndian::write_u64(bytes, raw_int.max(0f64) as u64),
+            8 => LittleEndian::write_i64(bytes, raw_int as i64),
+            9 => LittleEndian::write_f32(bytes, raw as f32),
+            _ => LittleEndian::write_f64(bytes, raw),
+        }
+    }
+}
+
+impl fmt::Display for ExtraBytesDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = format!("\tName: {}", self.name);
+        s = s + &format!("\n\tData Type: {}", self.data_type);
+        s = s + &format!("\n\tSize: {}", self.get_size());
+        if self.has_scale() {
+            s = s + &format!("\n\tScale: {}", self.scale[0]);
+        }
+        if self.has_offset() {
+            s = s + &format!("\n\tOffset: {}", self.offset[0]);
+        }
+        s = s + &format!("\n\tDescription: {}", self.description);
+        write!(f, "{}", s)
+    }
+}
+
+/// Parses the binary data of an Extra Bytes VLR into a list of descriptors.
+pub fn read_extra_bytes_descriptors(binary_data: &[u8]) -> Vec<ExtraBytesDescriptor> {
+    let mut descriptors = vec![];
+    let num_descriptors = binary_data.len() / EXTRA_BYTES_DESCRIPTOR_SIZE;
+    for i in 0..num_descriptors {
+        descriptors.push(ExtraBytesDescriptor::from_bytes(
+            &binary_data[i * EXTRA_BYTES_DESCRIPTOR_SIZE..(i + 1) * EXTRA_BYTES_DESCRIPTOR_SIZE],
+        ));
+    }
+    descriptors
+}
+
+fn read_typed_value(data_type: u8, bytes: &[u8]) -> f64 {
+    match data_type {
+        1 => bytes[0] as f64,
+        2 => (bytes[0] as i8) as f64,
+        3 => LittleEndian::read_u16(bytes) as f64,
+        4 => LittleEndian::read_i16(bytes) as f64,
+        5 => LittleEndian::read_u32(bytes) as f64,
+        6 => LittleEndian::read_i32(bytes) as f64,
+        7 => LittleEndian::read_u64(bytes) as f64,
+        8 => LittleEndian::read_i64(bytes) as f64,
+        9 => LittleEndian::read_f32(bytes) as f64,
+        _ => LittleEndian::read_f64(bytes),
+    }
+}
+
+/// Reads the first value of a no_data, min, or max field, which is stored as an 8-byte
+/// 'anytype' chosen by whether the data type is unsigned, signed, or floating point.
+fn read_anytype(data_type: u8, bytes: &[u8]) -> f64 {
+    match data_type {
+        1 | 3 | 5 | 7 => LittleEndian::read_u64(bytes) as f64,
+        2 | 4 | 6 | 8 => LittleEndian::read_i64(bytes) as f64,
+        _ => LittleEndian::read_f64(bytes),
+    }
+}
+
+/// Widens a raw value, as stored in a point record, into an 8-byte 'anytype'.
+fn write_anytype(data_type: u8, raw_bytes: &[u8], bytes: &mut [u8]) {
+    match data_type {
+        1 | 3 | 5 => LittleEndian::write_u64(bytes, read_typed_value(data_type, raw_bytes) as u64),
+        2 | 4 | 6 => LittleEndian::write_i64(bytes, read_typed_value(data_type, raw_bytes) as i64),
+        // 64-bit integers are copied, since they may not be represented exactly as f64
+        7 | 8 => bytes.copy_from_slice(&raw_bytes[0..8]),
+        _ => LittleEndian::write_f64(bytes, read_typed_value(data_type, raw_bytes)),
+    }
+}
+
+fn bytes_to_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0u8).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[0..end]).trim().to_string()
+}
+
+fn string_to_bytes(s: &str, bytes: &mut [u8]) {
+    for (i, b) in s.as_bytes().iter().enumerate() {
+        if i == bytes.len() {
+            break;
+        }
+        bytes[i] = if *b > 127u8 { b' ' } else { *b };
+    }
+}
@@ -1,4 +1,5 @@
-use super::las::GlobalEncodingField;
+use super::las::{is_zipped_las, read_zipped_las, GlobalEncodingField, LasFile};
+use super::point_cloud::PointCloudFormat;
 use crate::utils::{ByteOrderReader, Endianness};
 use std::fmt;
 use std::fs::File;
@@ -132,11 +133,21 @@ impl LasHeader {
     header but don't want to read the file's data.
     */
     pub fn read_las_header(file_name: &str) -> Result<LasHeader, Error> {
-        let mut f = File::open(file_name)?;
-        let mut buffer = vec![0; 375]; // A LAS header is about 375 bytes, depending on optional parameters.
-
-        // read the file's header bytes into a buffer
-        f.read(&mut buffer)?;
+        if PointCloudFormat::from_file_name(file_name).is_some() {
+            // other point cloud formats do not have a separate header
+            return Ok(LasFile::new(file_name, "r")?.header);
+        }
+        // A LAS header is about 375 bytes, depending on optional parameters.
+        let buffer = if is_zipped_las(file_name) {
+            read_zipped_las(file_name, Some(375))?
+        } else {
+            let mut f = File::open(file_name)?;
+            let mut buffer = vec![0; 375];
+
+            // read the file's header bytes into a buffer
+            f.read(&mut buffer)?;
+            buffer
+        };
         let mut header: LasHeader = Default::default();
 
         header.project_id_used = true;
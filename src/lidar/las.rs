@@ -7,9 +7,20 @@ License: MIT
 */
 
 #![allow(dead_code, unused_assignments)]
+use super::extra_bytes::{
+    read_extra_bytes_descriptors, ExtraBytesDescriptor, EXTRA_BYTES_RECORD_ID,
+};
 use super::header::LasHeader;
+use super::lax::{get_lax_file_name, LasIndex};
+use super::point_cloud::{
+    read_point_cloud, write_point_cloud, CloudPoint, PointCloud, PointCloudFormat,
+};
 use super::point_data::{ColourData, PointData, WaveformPacket};
 use super::vlr::Vlr;
+use super::waveform::{
+    WaveformPacketDescriptor, WAVEFORM_DESCRIPTOR_FIRST_RECORD_ID,
+    WAVEFORM_DESCRIPTOR_LAST_RECORD_ID,
+};
 use crate::raster::geotiff::geokeys::GeoKeys;
 use crate::spatial_ref_system::esri_wkt_from_epsg;
 use crate::structures::BoundingBox;
@@ -21,7 +32,7 @@ use std::fmt;
 use std::fs;
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::{BufWriter, Cursor, Error, ErrorKind, Seek};
+use std::io::{BufWriter, Cursor, Error, ErrorKind, Seek, SeekFrom};
 use std::mem;
 use std::ops::Index;
 use std::path::Path;
@@ -42,12 +53,23 @@ pub struct LasFile {
     gps_data: Vec<f64>,
     colour_data: Vec<ColourData>,
     waveform_data: Vec<WaveformPacket>,
+    waveform_packet_descriptors: Vec<Option<WaveformPacketDescriptor>>,
+    waveform_packet_data: Vec<u8>,
+    pub extra_bytes_descriptors: Vec<ExtraBytesDescriptor>,
+    extra_bytes_offsets: Vec<usize>,
+    extra_bytes_length: usize,
+    extra_bytes_data: Vec<u8>,
     pub geokeys: GeoKeys,
     pub wkt: String,
     // starting_point: usize,
     header_is_set: bool,
+    read_extent: Option<BoundingBox>,
     pub use_point_intensity: bool,
     pub use_point_userdata: bool,
+    /// If true, the x, y, and z offsets in the header are retained when the file is written,
+    /// rather than being set to the minimum coordinates of the points.
+    pub use_header_offsets: bool,
+    rgb_is_16bit: bool,
 }
 
 impl<'a> IntoIterator for &'a LasFile {
@@ -71,8 +93,8 @@ impl LasFile {
 
     /// Constructs a new `LasFile` based on a file.
     /// The function takes the name of an existing raster file (`file_name`)
-    /// and the `file_mode`, wich can be 'r' (read), 'rh' (read header), and
-    /// 'w' (write).
+    /// and the `file_mode`, wich can be 'r' (read), 'rh' (read header), 'rv'
+    /// (read the header and VLRs, but not the points), and 'w' (write).
     pub fn new<'a>(file_name: &'a str, file_mode: &'a str) -> Result<LasFile, Error> {
         //LasFile {
         let mut lf = LasFile {
@@ -81,7 +103,7 @@ impl LasFile {
             ..Default::default()
         };
         lf.file_mode = file_mode.to_lowercase();
-        if lf.file_mode == "r" || lf.file_mode == "rh" {
+        if lf.file_mode == "r" || lf.file_mode == "rh" || lf.file_mode == "rv" {
             lf.read()?;
         } else {
             lf.file_mode = "w".to_string();
@@ -92,6 +114,26 @@ impl LasFile {
         Ok(lf)
     }
 
+    /// Constructs a new `LasFile` containing only the points of an existing file
+    /// that lie within an extent. If the file has a LAX spatial index (see the
+    /// `LidarIndex` tool), only the blocks of point records that overlap the extent
+    /// are read, which is considerably faster than reading the entire file when the
+    /// extent covers a small part of it. Notice that the header values, other than
+    /// the number of points, describe the entire file.
+    pub fn new_in_extent(file_name: &str, extent: BoundingBox) -> Result<LasFile, Error> {
+        let mut lf = LasFile {
+            file_name: file_name.to_string(),
+            file_mode: "r".to_string(),
+            wkt: String::new(),
+            read_extent: Some(extent),
+            ..Default::default()
+        };
+        lf.read()?;
+        lf.use_point_intensity = true;
+        lf.use_point_userdata = true;
+        Ok(lf)
+    }
+
     /// This function returns a new `LasFile` that has been initialized using another
     /// `LasFile`.
     /// Input Parameters:
@@ -123,6 +165,12 @@ impl LasFile {
             // }
         }
 
+        // The Extra Bytes VLR has been copied, but the per-point extra bytes
+        // must be added by the calling tool using add_extra_bytes().
+        output.extra_bytes_descriptors = input.extra_bytes_descriptors.clone();
+        output.extra_bytes_offsets = input.extra_bytes_offsets.clone();
+        output.extra_bytes_length = input.extra_bytes_length;
+
         output
     }
 
@@ -170,6 +218,71 @@ impl LasFile {
         self.header.number_of_vlrs += 1;
     }
 
+    /// Sets the descriptors of the per-point attributes stored in the extra bytes
+    /// of each point record, adding (or replacing) the Extra Bytes VLR. Values
+    /// must subsequently be added for each point using `add_extra_bytes`.
+    pub fn set_extra_bytes_descriptors(&mut self, descriptors: Vec<ExtraBytesDescriptor>) {
+        if self.file_mode == "r" {
+            return;
+        }
+        if !self.header_is_set {
+            panic!(
+                "The header of a LAS file must be added before any VLRs. Please see add_header()."
+            );
+        }
+        self.remove_extra_bytes_vlr();
+        let mut vlr: Vlr = Default::default();
+        vlr.user_id = "LASF_Spec".to_string();
+        vlr.record_id = EXTRA_BYTES_RECORD_ID;
+        vlr.description = "Extra Bytes Record".to_string();
+        for ebd in &descriptors {
+            vlr.binary_data.extend(ebd.to_bytes());
+        }
+        vlr.record_length_after_header = vlr.binary_data.len() as u16;
+        self.add_vlr(vlr);
+        self.set_extra_bytes_layout(descriptors);
+        self.extra_bytes_data.clear();
+    }
+
+    fn set_extra_bytes_layout(&mut self, descriptors: Vec<ExtraBytesDescriptor>) {
+        self.extra_bytes_offsets.clear();
+        self.extra_bytes_length = 0;
+        for ebd in &descriptors {
+            self.extra_bytes_offsets.push(self.extra_bytes_length);
+            self.extra_bytes_length += ebd.get_size();
+        }
+        self.extra_bytes_descriptors = descriptors;
+    }
+
+    fn remove_extra_bytes_vlr(&mut self) {
+        let num_vlrs = self.vlr_data.len();
+        self.vlr_data.retain(|vlr| {
+            !(vlr.record_id == EXTRA_BYTES_RECORD_ID && vlr.user_id.starts_with("LASF_Spec"))
+        });
+        self.header.number_of_vlrs -= (num_vlrs - self.vlr_data.len()) as u32;
+        self.extra_bytes_descriptors.clear();
+        self.extra_bytes_offsets.clear();
+        self.extra_bytes_length = 0;
+        self.extra_bytes_data.clear();
+    }
+
+    /// Adds the extra bytes for the most recently added point record. The slice
+    /// should be `get_extra_bytes_length()` bytes long; shorter slices are zero-padded.
+    pub fn add_extra_bytes(&mut self, bytes: &[u8]) {
+        if self.file_mode == "r" || self.extra_bytes_length == 0 {
+            return;
+        }
+        if bytes.len() >= self.extra_bytes_length {
+            self.extra_bytes_data
+                .extend_from_slice(&bytes[0..self.extra_bytes_length]);
+        } else {
+            self.extra_bytes_data.extend_from_slice(bytes);
+            for _ in bytes.len()..self.extra_bytes_length {
+                self.extra_bytes_data.push(0u8);
+            }
+        }
+    }
+
     pub fn add_point_record(&mut self, point: LidarPointRecord) {
         if self.file_mode == "r" {
             return;
@@ -450,14 +563,315 @@ impl LasFile {
         self.colour_data.len() > 0
     }
 
+    /// Returns true if the point records contain near-infrared values (point formats 8 and 10).
+    pub fn has_nir(&self) -> bool {
+        (self.header.point_format == 8 || self.header.point_format == 10) && self.has_rgb()
+    }
+
+    /// Returns the colour of a point record as 8-bit channels packed into a single value,
+    /// i.e. ((255 << 24) | (b << 16) | (g << 8) | r). The LAS specification calls for colours
+    /// to be scaled to the 16-bit range, although many files store 8-bit values; colours are
+    /// only rescaled if any colour value in the file exceeds 255.
+    pub fn get_rgb_packed(&self, index: usize) -> Option<u32> {
+        let clr = self.colour_data.get(index)?;
+        let (r, g, b) = if self.rgb_is_16bit {
+            (clr.red >> 8, clr.green >> 8, clr.blue >> 8)
+        } else {
+            (clr.red, clr.green, clr.blue)
+        };
+        Some((255u32 << 24) | ((b as u32) << 16) | ((g as u32) << 8) | (r as u32))
+    }
+
     pub fn get_gps_time(&self, index: usize) -> Result<f64, Error> {
-        if self.gps_data.len() >= index {
+        if self.gps_data.len() > index {
             return Ok(self.gps_data[index]);
         } else {
             return Err(Error::new(ErrorKind::NotFound, "GPS time value not found, possibly because the file point format does not include GPS data."));
         }
     }
 
+    /// Returns true if the point records contain GPS time values (point formats other than 0 and 2).
+    pub fn has_gps_time(&self) -> bool {
+        !self.gps_data.is_empty()
+    }
+
+    /// Returns true if the GPS time of a point lies within the range from `min_time` to
+    /// `max_time`, inclusive. Points without GPS time values are only within unbounded ranges.
+    pub fn is_gps_time_in_range(&self, index: usize, min_time: f64, max_time: f64) -> bool {
+        match self.gps_data.get(index) {
+            Some(t) => *t >= min_time && *t <= max_time,
+            None => min_time == f64::NEG_INFINITY && max_time == f64::INFINITY,
+        }
+    }
+
+    /// Returns true if the point records contain waveform packets (point formats 4, 5, 9, and 10).
+    pub fn has_waveform_data(&self) -> bool {
+        !self.waveform_data.is_empty()
+    }
+
+    /// Returns the waveform packet of a point, if the point format includes waveform packets.
+    pub fn get_waveform_packet(&self, index: usize) -> Option<WaveformPacket> {
+        self.waveform_data.get(index).copied()
+    }
+
+    /// Returns the Waveform Packet Descriptor with the specified index (1-255), i.e.
+    /// the value of the `packet_descriptor_index` field of a point's waveform packet.
+    pub fn get_waveform_packet_descriptor(&self, index: u8) -> Option<WaveformPacketDescriptor> {
+        match self.waveform_packet_descriptors.get(index as usize) {
+            Some(Some(wpd)) => Some(*wpd),
+            _ => None,
+        }
+    }
+
+    /// Reads the waveform packet data, which are stored either internally, after the
+    /// point records, or in an external .wdp file with the same name as the LAS file.
+    /// This must be called before `get_waveform_samples`. Waveform data are not
+    /// read by default because they are often substantially larger than the points.
+    pub fn read_waveform_data(&mut self) -> Result<(), Error> {
+        if !self.waveform_packet_data.is_empty() {
+            return Ok(());
+        }
+        if self.waveform_data.is_empty() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                "Waveform data not found, possibly because the file point format does not include waveform packets.",
+            ));
+        }
+        let is_zipped = is_zipped_las(&self.file_name);
+        if self.header.global_encoding.waveform_data_internal()
+            && self.header.waveform_data_start > 0
+        {
+            let start = self.header.waveform_data_start as usize;
+            if !is_zipped {
+                let mut f = File::open(&self.file_name)?;
+                f.seek(SeekFrom::Start(start as u64))?;
+                f.read_to_end(&mut self.waveform_packet_data)?;
+            } else {
+                let buffer = read_zipped_las(&self.file_name, None)?;
+                if start < buffer.len() {
+                    self.waveform_packet_data = buffer[start..].to_vec();
+                }
+            }
+        } else {
+            let las_name = if is_zipped {
+                let archive_name = split_zip_entry(&self.file_name).0;
+                archive_name[0..archive_name.len() - 4].to_string()
+            } else {
+                self.file_name.clone()
+            };
+            let mut wdp_file = Path::new(&las_name).with_extension("wdp");
+            if !wdp_file.exists() {
+                wdp_file = Path::new(&las_name).with_extension("WDP");
+            }
+            if !wdp_file.exists() {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "The external waveform data file ({}) could not be found.",
+                        Path::new(&las_name).with_extension("wdp").display()
+                    ),
+                ));
+            }
+            self.waveform_packet_data = fs::read(wdp_file)?;
+        }
+        if self.waveform_packet_data.is_empty() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                "The waveform packet data could not be read.",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the digitized waveform samples (`gain * sample + offset`) of a point.
+    /// The waveform data must first be read using `read_waveform_data`.
+    pub fn get_waveform_samples(&self, index: usize) -> Result<Vec<f64>, Error> {
+        let wfp = match self.waveform_data.get(index) {
+            Some(wfp) => *wfp,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    "Waveform packet not found, possibly because the file point format does not include waveform packets.",
+                ))
+            }
+        };
+        let wpd = match self.get_waveform_packet_descriptor(wfp.packet_descriptor_index) {
+            Some(wpd) => wpd,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "Waveform Packet Descriptor {} not found.",
+                        wfp.packet_descriptor_index
+                    ),
+                ))
+            }
+        };
+        let start = wfp.offset_to_waveform_data as usize;
+        let size = if wfp.waveform_packet_size > 0 {
+            wfp.waveform_packet_size as usize
+        } else {
+            wpd.get_packet_size()
+        };
+        if start + size > self.waveform_packet_data.len() {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "The waveform packet lies outside of the waveform data. Has read_waveform_data() been called?",
+            ));
+        }
+        wpd.decode_samples(&self.waveform_packet_data[start..start + size])
+    }
+
+    /// Returns true if the point records contain attributes described by an Extra Bytes VLR.
+    pub fn has_extra_bytes(&self) -> bool {
+        self.extra_bytes_length > 0
+    }
+
+    /// Returns the number of extra bytes in each point record.
+    pub fn get_extra_bytes_length(&self) -> usize {
+        self.extra_bytes_length
+    }
+
+    /// Returns the names of the attributes contained within the extra bytes.
+    pub fn get_extra_bytes_attribute_names(&self) -> Vec<String> {
+        self.extra_bytes_descriptors
+            .iter()
+            .map(|ebd| ebd.name.clone())
+            .collect()
+    }
+
+    /// Finds the index of a named extra bytes attribute. Matching is case-insensitive.
+    pub fn get_extra_bytes_attribute_index(&self, name: &str) -> Option<usize> {
+        let name = name.trim().to_lowercase();
+        self.extra_bytes_descriptors
+            .iter()
+            .position(|ebd| ebd.name.to_lowercase() == name)
+    }
+
+    /// Returns the raw extra bytes of a point record.
+    pub fn get_extra_bytes(&self, index: usize) -> &[u8] {
+        if self.extra_bytes_length == 0
+            || (index + 1) * self.extra_bytes_length > self.extra_bytes_data.len()
+        {
+            return &[];
+        }
+        &self.extra_bytes_data[index * self.extra_bytes_length..(index + 1) * self.extra_bytes_length]
+    }
+
+    /// Returns the value of an extra bytes attribute for a point record, with
+    /// the attribute's scale and offset applied. No-data values are returned as NaN.
+    pub fn get_extra_bytes_value(&self, index: usize, attribute: usize) -> f64 {
+        let bytes = self.get_extra_bytes(index);
+        if attribute >= self.extra_bytes_descriptors.len() || bytes.is_empty() {
+            return f64::NAN;
+        }
+        let offset = self.extra_bytes_offsets[attribute];
+        self.extra_bytes_descriptors[attribute].get_value(&bytes[offset..])
+    }
+
+    /// Returns the offset of an attribute within the extra bytes of a point record.
+    pub fn get_extra_bytes_offset(&self, attribute: usize) -> usize {
+        self.extra_bytes_offsets[attribute]
+    }
+
+    /// Resolves the name of a point attribute, e.g. 'elevation', 'intensity', 'class',
+    /// 'return_number', 'number_of_returns', 'scan angle', 'rgb', 'red', 'green', 'blue',
+    /// 'nir', 'user data', or the name of an extra bytes attribute. Returns None if the
+    /// name is not recognized.
+    pub fn get_point_attribute(&self, name: &str) -> Option<PointAttribute> {
+        match &name.trim().to_lowercase() as &str {
+            "elevation" | "z" => Some(PointAttribute::Elevation),
+            "intensity" => Some(PointAttribute::Intensity),
+            "class" | "classification" => Some(PointAttribute::Classification),
+            "return_number" | "return number" => Some(PointAttribute::ReturnNumber),
+            "number_of_returns" | "number of returns" => Some(PointAttribute::NumberOfReturns),
+            "scan angle" | "scan_angle" => Some(PointAttribute::ScanAngle),
+            "rgb" => Some(PointAttribute::Rgb),
+            "red" => Some(PointAttribute::Red),
+            "green" => Some(PointAttribute::Green),
+            "blue" => Some(PointAttribute::Blue),
+            "nir" | "near infrared" | "near_infrared" => Some(PointAttribute::Nir),
+            "user data" | "user_data" => Some(PointAttribute::UserData),
+            _ => self
+                .get_extra_bytes_attribute_index(name)
+                .map(PointAttribute::ExtraBytes),
+        }
+    }
+
+    /// Resolves the names of a set of point attributes (see `get_point_attribute`), checking
+    /// that the point format of the file includes any colour or near-infrared channels that
+    /// are named. Only the header and VLRs need to have been read, i.e. file mode "rv", such
+    /// that the attributes of a file can be checked before its points are read.
+    pub fn get_point_attributes(&self, names: &[String]) -> Result<Vec<PointAttribute>, Error> {
+        let has_rgb = matches!(self.header.point_format, 2 | 3 | 5 | 7 | 8 | 10);
+        let has_nir = matches!(self.header.point_format, 8 | 10);
+        let mut attributes = Vec::with_capacity(names.len());
+        for name in names {
+            match self.get_point_attribute(name) {
+                Some(PointAttribute::Rgb)
+                | Some(PointAttribute::Red)
+                | Some(PointAttribute::Green)
+                | Some(PointAttribute::Blue)
+                    if !has_rgb =>
+                {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "The file does not contain RGB colour data.",
+                    ));
+                }
+                Some(PointAttribute::Nir) if !has_nir => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "The file does not contain near-infrared data (point formats 8 and 10).",
+                    ));
+                }
+                Some(attribute) => attributes.push(attribute),
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "The point attribute '{}' is neither a standard point attribute nor an extra bytes attribute. Available extra bytes attributes: {:?}",
+                            name,
+                            self.get_extra_bytes_attribute_names()
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// Returns the value of a point attribute for a point record. RGB colours are packed
+    /// into a single value (see `get_rgb_packed`), whereas individual colour channels are
+    /// returned as stored, i.e. without rescaling. Missing values are returned as NaN.
+    pub fn get_point_attribute_value(&self, index: usize, attribute: PointAttribute) -> f64 {
+        let p = self.point_data[index];
+        match attribute {
+            PointAttribute::Elevation => p.z,
+            PointAttribute::Intensity => p.intensity as f64,
+            PointAttribute::Classification => p.classification() as f64,
+            PointAttribute::ReturnNumber => p.return_number() as f64,
+            PointAttribute::NumberOfReturns => p.number_of_returns() as f64,
+            PointAttribute::ScanAngle => p.scan_angle as f64,
+            PointAttribute::Rgb => match self.get_rgb_packed(index) {
+                Some(clr) => clr as f64,
+                None => f64::NAN,
+            },
+            PointAttribute::Red => self.colour_data.get(index).map_or(f64::NAN, |c| c.red as f64),
+            PointAttribute::Green => self.colour_data.get(index).map_or(f64::NAN, |c| c.green as f64),
+            PointAttribute::Blue => self.colour_data.get(index).map_or(f64::NAN, |c| c.blue as f64),
+            PointAttribute::Nir => {
+                if self.has_nir() {
+                    self.colour_data.get(index).map_or(f64::NAN, |c| c.nir as f64)
+                } else {
+                    f64::NAN
+                }
+            }
+            PointAttribute::UserData => p.user_data as f64,
+            PointAttribute::ExtraBytes(a) => self.get_extra_bytes_value(index, a),
+        }
+    }
+
     pub fn get_short_filename(&self) -> String {
         let path = Path::new(&self.file_name);
         let file_name = path.file_stem().unwrap();
@@ -483,67 +897,67 @@ impl LasFile {
     }
 
     pub fn read(&mut self) -> Result<(), Error> {
-        let buffer = match self.file_name.to_lowercase().ends_with(".zip") {
-            false => {
-                let mut f = File::open(&self.file_name)?;
-                let metadata = fs::metadata(&self.file_name)?;
-                let file_size: usize = if self.file_mode != "rh" {
-                    metadata.len() as usize
-                } else {
-                    375 // the size of the header
-                };
-
-                let mut buffer = vec![0; file_size]; // Vec::with_capacity(file_size);
-                if file_size < 1024 * 1024 * 500 {
-                    // 2147483646 is the actual maximum file read on Mac
-                    f.read(&mut buffer)?;
-                } else {
-                    // let br = BufReader::new(f);
-                    // let mut i = 0;
-                    // for byte in br.bytes() {
-                    //     buffer[i] = byte.unwrap();
-                    //     i += 1;
-                    // }
+        if let Some(format) = PointCloudFormat::from_file_name(&self.file_name) {
+            return self.read_point_cloud_file(format);
+        }
+        let indexed_buffer = match self.read_extent {
+            Some(extent) if self.file_mode == "r" => self.read_indexed_points(extent)?,
+            _ => None,
+        };
+        let mut num_indexed_points: Option<u64> = None;
+        let buffer = if let Some((buffer, n)) = indexed_buffer {
+            num_indexed_points = Some(n);
+            buffer
+        } else {
+            match is_zipped_las(&self.file_name) {
+                false => {
+                    let mut f = File::open(&self.file_name)?;
+                    let metadata = fs::metadata(&self.file_name)?;
+                    let file_size: usize = if self.file_mode == "rh" {
+                        375 // the size of the header
+                    } else if self.file_mode == "rv" {
+                        // the header and VLRs precede the point records
+                        let header = LasHeader::read_las_header(&self.file_name)?;
+                        (header.offset_to_points as usize)
+                            .max(375)
+                            .min(metadata.len() as usize)
+                    } else {
+                        metadata.len() as usize
+                    };
 
-                    let block_size = 1024 * 1024 * 500;
-                    let mut start_byte = 0usize;
-                    let mut end_byte = block_size;
-                    let mut bytes_read = 0;
-                    while bytes_read < file_size {
-                        f.read(&mut buffer[start_byte..end_byte])?;
-                        start_byte += block_size;
-                        end_byte += block_size;
-                        if end_byte > file_size {
-                            end_byte = file_size;
+                    let mut buffer = vec![0; file_size]; // Vec::with_capacity(file_size);
+                    if file_size < 1024 * 1024 * 500 {
+                        // 2147483646 is the actual maximum file read on Mac
+                        f.read(&mut buffer)?;
+                    } else {
+                        // let br = BufReader::new(f);
+                        // let mut i = 0;
+                        // for byte in br.bytes() {
+                        //     buffer[i] = byte.unwrap();
+                        //     i += 1;
+                        // }
+
+                        let block_size = 1024 * 1024 * 500;
+                        let mut start_byte = 0usize;
+                        let mut end_byte = block_size;
+                        let mut bytes_read = 0;
+                        while bytes_read < file_size {
+                            f.read(&mut buffer[start_byte..end_byte])?;
+                            start_byte += block_size;
+                            end_byte += block_size;
+                            if end_byte > file_size {
+                                end_byte = file_size;
+                            }
+                            bytes_read += block_size;
                         }
-                        bytes_read += block_size;
                     }
-                }
 
-                buffer
-            }
-            true => {
-                let file = File::open(&self.file_name)?;
-                let mut zip = (zip::ZipArchive::new(file))?;
-                let mut f = zip.by_index(0).unwrap();
-                if !f.name().to_lowercase().ends_with(".las") {
-                    return Err(Error::new(ErrorKind::InvalidData,
-                     "The data file contained within zipped archive does not have the proper 'las' extension."));
-                }
-                match f.compression() {
-                    CompressionMethod::Stored | CompressionMethod::Deflated | CompressionMethod::Bzip2 => (),
-                    _ => return Err(Error::new(ErrorKind::InvalidData,
-                    "Either the file is formatted incorrectly or it is an unsupported compression type.")),
+                    buffer
                 }
-                let file_size: usize = f.size() as usize;
-                let mut buffer = vec![0; file_size];
-
-                // read the file's bytes into a buffer
-                match f.read_exact(&mut buffer) {
-                    Err(e) => return Err(e),
-                    Ok(()) => (),
+                true => {
+                    let max_bytes = if self.file_mode == "rh" { Some(375) } else { None };
+                    read_zipped_las(&self.file_name, max_bytes)?
                 }
-                buffer
             }
         };
 
@@ -649,6 +1063,11 @@ impl LasFile {
             self.header.number_of_points = 0;
         }
 
+        if let Some(n) = num_indexed_points {
+            // only the indexed point records that overlap the read extent are in the buffer
+            self.header.number_of_points = n;
+        }
+
         if self.file_mode != "rh" {
             // file_mode = "rh" does not read points or the VLR data, only the header.
 
@@ -676,6 +1095,21 @@ impl LasFile {
                         .add_double_params(&vlr.binary_data, Endianness::LittleEndian);
                 } else if vlr.record_id == 34_737 {
                     self.geokeys.add_ascii_params(&vlr.binary_data);
+                } else if vlr.record_id == EXTRA_BYTES_RECORD_ID
+                    && vlr.user_id.starts_with("LASF_Spec")
+                {
+                    self.set_extra_bytes_layout(read_extra_bytes_descriptors(&vlr.binary_data));
+                } else if vlr.record_id >= WAVEFORM_DESCRIPTOR_FIRST_RECORD_ID
+                    && vlr.record_id <= WAVEFORM_DESCRIPTOR_LAST_RECORD_ID
+                    && vlr.user_id.starts_with("LASF_Spec")
+                {
+                    if let Ok(wpd) = WaveformPacketDescriptor::from_bytes(&vlr.binary_data) {
+                        if self.waveform_packet_descriptors.is_empty() {
+                            self.waveform_packet_descriptors = vec![None; 256];
+                        }
+                        let index = (vlr.record_id - WAVEFORM_DESCRIPTOR_FIRST_RECORD_ID + 1) as usize;
+                        self.waveform_packet_descriptors[index] = Some(wpd);
+                    }
                 } else if vlr.record_id == 2112 {
                     let skip = if vlr.binary_data[vlr.binary_data.len() - 1] == 0u8 {
                         1
@@ -694,7 +1128,7 @@ impl LasFile {
             // Read the point data //
             /////////////////////////
             
-            if self.header.number_of_points == 0 {
+            if self.header.number_of_points == 0 || self.file_mode == "rv" {
                 return Ok(());
             }
             
@@ -744,11 +1178,28 @@ impl LasFile {
                 skip_bytes = (self.header.point_record_length - rec_lengths[self.header.point_format as usize][0]) as usize;
             }
 
+            // If the extra data in each point record are described by an Extra Bytes
+            // VLR, they are retained rather than skipped.
+            let num_extra_bytes = if self.extra_bytes_length > 0 && self.extra_bytes_length <= skip_bytes {
+                self.extra_bytes_data = Vec::with_capacity(self.header.number_of_points as usize * self.extra_bytes_length);
+                self.extra_bytes_length
+            } else {
+                if self.extra_bytes_length > 0 {
+                    println!("Warning: The Extra Bytes VLR does not match the point record length. Extra bytes will be ignored.");
+                    self.extra_bytes_descriptors.clear();
+                    self.extra_bytes_offsets.clear();
+                    self.extra_bytes_length = 0;
+                }
+                0usize
+            };
+
             self.point_data = Vec::with_capacity(self.header.number_of_points as usize);
             let mut p: PointData = Default::default();
-            bor.seek(self.header.offset_to_points as usize);
+            let offset_to_points = self.header.offset_to_points as usize;
+            let record_length = self.header.point_record_length as usize;
+            bor.seek(offset_to_points);
             if self.header.point_format == 0 {
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     // bor.seek(
                     //     self.header.offset_to_points as usize
                     //         + (i as usize) * (self.header.point_record_length as usize),
@@ -768,13 +1219,16 @@ impl LasFile {
                     }
                     p.point_source_id = bor.read_u16()?;
                     self.point_data.push(p);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             } else if self.header.point_format == 1 {
                 self.gps_data = Vec::with_capacity(self.header.number_of_points as usize);
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     p.x = bor.read_i32()? as f64 * self.header.x_scale_factor + self.header.x_offset;
                     p.y = bor.read_i32()? as f64 * self.header.y_scale_factor + self.header.y_offset;
                     p.z = bor.read_i32()? as f64 * self.header.z_scale_factor + self.header.z_offset;
@@ -791,14 +1245,17 @@ impl LasFile {
                     self.point_data.push(p);
                     // read the GPS data
                     self.gps_data.push(bor.read_f64()?);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             } else if self.header.point_format == 2 {
                 self.colour_data = Vec::with_capacity(self.header.number_of_points as usize);
                 let mut rgb: ColourData = Default::default();
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     p.x = bor.read_i32()? as f64 * self.header.x_scale_factor + self.header.x_offset;
                     p.y = bor.read_i32()? as f64 * self.header.y_scale_factor + self.header.y_offset;
                     p.z = bor.read_i32()? as f64 * self.header.z_scale_factor + self.header.z_offset;
@@ -818,16 +1275,19 @@ impl LasFile {
                     rgb.green = bor.read_u16()?;
                     rgb.blue = bor.read_u16()?;
                     self.colour_data.push(rgb);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             } else if self.header.point_format == 3 {
                 self.gps_data = Vec::with_capacity(self.header.number_of_points as usize);
                 self.colour_data = Vec::with_capacity(self.header.number_of_points as usize);
                 let mut rgb: ColourData = Default::default();
                 bor.seek(self.header.offset_to_points as usize);
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     p.x = bor.read_i32()? as f64 * self.header.x_scale_factor + self.header.x_offset;
                     p.y = bor.read_i32()? as f64 * self.header.y_scale_factor + self.header.y_offset;
                     p.z = bor.read_i32()? as f64 * self.header.z_scale_factor + self.header.z_offset;
@@ -849,15 +1309,18 @@ impl LasFile {
                     rgb.green = bor.read_u16()?;
                     rgb.blue = bor.read_u16()?;
                     self.colour_data.push(rgb);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             } else if self.header.point_format == 4 {
                 self.gps_data = Vec::with_capacity(self.header.number_of_points as usize);
                 self.waveform_data = Vec::with_capacity(self.header.number_of_points as usize);
                 let mut wfp: WaveformPacket;
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     p.x = bor.read_i32()? as f64 * self.header.x_scale_factor + self.header.x_offset;
                     p.y = bor.read_i32()? as f64 * self.header.y_scale_factor + self.header.y_offset;
                     p.z = bor.read_i32()? as f64 * self.header.z_scale_factor + self.header.z_offset;
@@ -884,9 +1347,12 @@ impl LasFile {
                     wfp.yt = bor.read_f32()?;
                     wfp.zt = bor.read_f32()?;
                     self.waveform_data.push(wfp);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             } else if self.header.point_format == 5 {
                 self.gps_data = Vec::with_capacity(self.header.number_of_points as usize);
@@ -894,7 +1360,7 @@ impl LasFile {
                 self.waveform_data = Vec::with_capacity(self.header.number_of_points as usize);
                 let mut rgb: ColourData = Default::default();
                 let mut wfp: WaveformPacket;
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     p.x = bor.read_i32()? as f64 * self.header.x_scale_factor + self.header.x_offset;
                     p.y = bor.read_i32()? as f64 * self.header.y_scale_factor + self.header.y_offset;
                     p.z = bor.read_i32()? as f64 * self.header.z_scale_factor + self.header.z_offset;
@@ -926,14 +1392,17 @@ impl LasFile {
                     wfp.yt = bor.read_f32()?;
                     wfp.zt = bor.read_f32()?;
                     self.waveform_data.push(wfp);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             } else if self.header.point_format == 6 {
                 // 64-bit
                 self.gps_data = Vec::with_capacity(self.header.number_of_points as usize);
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     p.is_64bit = true;
                     p.x = bor.read_i32()? as f64 * self.header.x_scale_factor + self.header.x_offset;
                     p.y = bor.read_i32()? as f64 * self.header.y_scale_factor + self.header.y_offset;
@@ -952,16 +1421,19 @@ impl LasFile {
                     self.point_data.push(p);
                     // read the GPS data
                     self.gps_data.push(bor.read_f64()?);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             } else if self.header.point_format == 7 {
                 // 64-bit
                 self.gps_data = Vec::with_capacity(self.header.number_of_points as usize);
                 self.colour_data = Vec::with_capacity(self.header.number_of_points as usize);
                 let mut rgb: ColourData = Default::default();
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     p.is_64bit = true;
                     p.x = bor.read_i32()? as f64 * self.header.x_scale_factor + self.header.x_offset;
                     p.y = bor.read_i32()? as f64 * self.header.y_scale_factor + self.header.y_offset;
@@ -985,9 +1457,12 @@ impl LasFile {
                     rgb.green = bor.read_u16()?;
                     rgb.blue = bor.read_u16()?;
                     self.colour_data.push(rgb);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             } else if self.header.point_format == 8 {
                 // 64-bit
@@ -995,7 +1470,7 @@ impl LasFile {
                 self.gps_data = Vec::with_capacity(self.header.number_of_points as usize);
                 self.colour_data = Vec::with_capacity(self.header.number_of_points as usize);
                 let mut rgb: ColourData = Default::default();
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     p.is_64bit = true;
                     p.x = bor.read_i32()? as f64 * self.header.x_scale_factor + self.header.x_offset;
                     p.y = bor.read_i32()? as f64 * self.header.y_scale_factor + self.header.y_offset;
@@ -1020,9 +1495,12 @@ impl LasFile {
                     rgb.blue = bor.read_u16()?;
                     rgb.nir = bor.read_u16()?;
                     self.colour_data.push(rgb);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             } else if self.header.point_format == 9 {
                 // 64-bit
@@ -1030,7 +1508,7 @@ impl LasFile {
                 self.gps_data = Vec::with_capacity(self.header.number_of_points as usize);
                 self.waveform_data = Vec::with_capacity(self.header.number_of_points as usize);
                 let mut wfp: WaveformPacket;
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     p.is_64bit = true;
                     p.x = bor.read_i32()? as f64 * self.header.x_scale_factor + self.header.x_offset;
                     p.y = bor.read_i32()? as f64 * self.header.y_scale_factor + self.header.y_offset;
@@ -1059,9 +1537,12 @@ impl LasFile {
                     wfp.yt = bor.read_f32()?;
                     wfp.zt = bor.read_f32()?;
                     self.waveform_data.push(wfp);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             } else if self.header.point_format == 10 {
                 // 64-bit
@@ -1071,7 +1552,7 @@ impl LasFile {
                 self.waveform_data = Vec::with_capacity(self.header.number_of_points as usize);
                 let mut rgb: ColourData = Default::default();
                 let mut wfp: WaveformPacket;
-                for _ in 0..self.header.number_of_points {
+                for i in 0..self.header.number_of_points {
                     p.is_64bit = true;
                     p.x = bor.read_i32()? as f64 * self.header.x_scale_factor + self.header.x_offset;
                     p.y = bor.read_i32()? as f64 * self.header.y_scale_factor + self.header.y_offset;
@@ -1106,16 +1587,232 @@ impl LasFile {
                     wfp.yt = bor.read_f32()?;
                     wfp.zt = bor.read_f32()?;
                     self.waveform_data.push(wfp);
-                    if skip_bytes > 0 { 
-                        bor.inc_pos(skip_bytes); 
-                    }
+                    self.read_point_extra_bytes(
+                        &mut bor,
+                        offset_to_points + i as usize * record_length,
+                        skip_bytes,
+                        num_extra_bytes,
+                    )?;
                 }
             }
+
+            if let Some(extent) = self.read_extent {
+                self.retain_points_in_extent(extent);
+            }
         }
 
+        self.rgb_is_16bit = self
+            .colour_data
+            .iter()
+            .any(|c| c.red > 255 || c.green > 255 || c.blue > 255);
+
         Ok(())
     }
 
+    /// Reads the `skip_bytes` bytes that follow the standard fields of the point record that
+    /// begins at `record_offset`, retaining the first `num_extra_bytes` of them, i.e. those
+    /// described by the Extra Bytes VLR, and positions the reader at the next point record.
+    fn read_point_extra_bytes<R: Read + Seek>(
+        &mut self,
+        bor: &mut ByteOrderReader<R>,
+        record_offset: usize,
+        skip_bytes: usize,
+        num_extra_bytes: usize,
+    ) -> Result<(), Error> {
+        if skip_bytes == 0 {
+            return Ok(());
+        }
+        let record_end = record_offset + self.header.point_record_length as usize;
+        if num_extra_bytes > 0 {
+            let start = self.extra_bytes_data.len();
+            self.extra_bytes_data.resize(start + num_extra_bytes, 0u8);
+            bor.seek(record_end - skip_bytes);
+            bor.read_exact(&mut self.extra_bytes_data[start..])?;
+        }
+        bor.seek(record_end);
+        Ok(())
+    }
+
+    /// Reads a file in one of the non-LAS `PointCloudFormat`s. The points are stored using
+    /// point format 2 if they are coloured and point format 0 otherwise.
+    fn read_point_cloud_file(&mut self, format: PointCloudFormat) -> Result<(), Error> {
+        let cloud = read_point_cloud(&self.file_name, format)?;
+        let file_mode = self.file_mode.clone();
+        self.file_mode = "w".to_string();
+        self.add_header(LasHeader {
+            project_id_used: true,
+            point_format: if cloud.has_colour { 2 } else { 0 },
+            point_record_length: if cloud.has_colour { 26 } else { 20 },
+            header_size: 227,
+            offset_to_points: 227,
+            ..Default::default()
+        });
+        self.wkt = cloud.wkt.clone();
+        for p in &cloud.points {
+            let mut point_data = PointData {
+                x: p.x,
+                y: p.y,
+                z: p.z,
+                intensity: p.intensity,
+                ..Default::default()
+            };
+            point_data.set_return_number(1);
+            point_data.set_number_of_returns(1);
+            point_data.set_classification(p.classification);
+            if cloud.has_colour {
+                self.add_point_record(LidarPointRecord::PointRecord2 {
+                    point_data: point_data,
+                    colour_data: ColourData {
+                        red: p.red,
+                        green: p.green,
+                        blue: p.blue,
+                        nir: 0,
+                    },
+                });
+            } else {
+                self.add_point_record(LidarPointRecord::PointRecord0 {
+                    point_data: point_data,
+                });
+            }
+        }
+        self.header.number_of_points_old = self.header.number_of_points.min(u32::MAX as u64) as u32;
+        self.file_mode = file_mode;
+        if let Some(extent) = self.read_extent {
+            self.retain_points_in_extent(extent);
+        }
+        self.rgb_is_16bit = cloud.is_colour_16bit();
+        Ok(())
+    }
+
+    /// Converts the point records to a `PointCloud`, for output in a non-LAS format.
+    fn to_point_cloud(&self) -> PointCloud {
+        let mut cloud = PointCloud {
+            has_intensity: true,
+            has_colour: self.has_rgb(),
+            has_classification: true,
+            wkt: self.wkt.clone(),
+            ..Default::default()
+        };
+        cloud.points = self
+            .point_data
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let colour = self.colour_data.get(i).cloned().unwrap_or_default();
+                CloudPoint {
+                    x: p.x,
+                    y: p.y,
+                    z: p.z,
+                    intensity: p.intensity,
+                    classification: p.classification(),
+                    red: colour.red,
+                    green: colour.green,
+                    blue: colour.blue,
+                }
+            })
+            .collect();
+        cloud
+    }
+
+    /// Reads the header, VLRs, and those point records that are within the cells of
+    /// the file's LAX index that overlap an extent. The number of points read is
+    /// returned with the buffer. Returns None if the file is not indexed.
+    fn read_indexed_points(&self, extent: BoundingBox) -> Result<Option<(Vec<u8>, u64)>, Error> {
+        let lax_file = get_lax_file_name(&self.file_name);
+        if is_zipped_las(&self.file_name) || !Path::new(&lax_file).exists() {
+            return Ok(None);
+        }
+        let index = match LasIndex::read(&lax_file) {
+            Ok(index) => index,
+            Err(e) => {
+                println!("Warning: The LAX file could not be read ({}). The entire file will be read.", e);
+                return Ok(None);
+            }
+        };
+        let header = LasHeader::read_las_header(&self.file_name)?;
+        let offset_to_points = header.offset_to_points as u64;
+        let record_length = header.point_record_length as u64;
+        let num_points = header.number_of_points;
+
+        let mut f = File::open(&self.file_name)?;
+        let mut buffer = vec![0u8; offset_to_points as usize];
+        f.read_exact(&mut buffer)?;
+        let mut n = 0u64;
+        for (start, end) in index.get_intervals(extent) {
+            if start as u64 >= num_points {
+                continue;
+            }
+            let end = (end as u64).min(num_points - 1);
+            let num_bytes = ((end - start as u64 + 1) * record_length) as usize;
+            let pos = buffer.len();
+            buffer.resize(pos + num_bytes, 0u8);
+            f.seek(SeekFrom::Start(offset_to_points + start as u64 * record_length))?;
+            f.read_exact(&mut buffer[pos..])?;
+            n += end - start as u64 + 1;
+        }
+        if buffer.len() < 375 {
+            // the header is always parsed from the first 375 bytes
+            buffer.resize(375, 0u8);
+        }
+        Ok(Some((buffer, n)))
+    }
+
+    /// Removes the points that are not within an extent.
+    fn retain_points_in_extent(&mut self, extent: BoundingBox) {
+        let n = self.point_data.len();
+        let keep: Vec<bool> = self
+            .point_data
+            .iter()
+            .map(|p| extent.is_point_in_box(p.x, p.y))
+            .collect();
+        let mut j = 0usize;
+        for (i, in_extent) in keep.into_iter().enumerate() {
+            if in_extent {
+                self.point_data[j] = self.point_data[i];
+                if self.gps_data.len() == n {
+                    self.gps_data[j] = self.gps_data[i];
+                }
+                if self.colour_data.len() == n {
+                    self.colour_data[j] = self.colour_data[i];
+                }
+                if self.waveform_data.len() == n {
+                    self.waveform_data[j] = self.waveform_data[i];
+                }
+                if self.extra_bytes_length > 0
+                    && self.extra_bytes_data.len() == n * self.extra_bytes_length
+                {
+                    let len = self.extra_bytes_length;
+                    self.extra_bytes_data.copy_within(i * len..(i + 1) * len, j * len);
+                }
+                j += 1;
+            }
+        }
+        self.point_data.truncate(j);
+        if self.gps_data.len() == n {
+            self.gps_data.truncate(j);
+        }
+        if self.colour_data.len() == n {
+            self.colour_data.truncate(j);
+        }
+        if self.waveform_data.len() == n {
+            self.waveform_data.truncate(j);
+        }
+        if self.extra_bytes_length > 0 && self.extra_bytes_data.len() == n * self.extra_bytes_length
+        {
+            self.extra_bytes_data.truncate(j * self.extra_bytes_length);
+        }
+        self.header.number_of_points = j as u64;
+        if self.header.number_of_points_old != 0 {
+            self.header.number_of_points_old = j as u32;
+        }
+    }
+
+    /// Writes the file to disk. LAS files are always output as LAS 1.3 and point
+    /// formats 4-10 are converted to formats 1 or 3, which drops the waveform,
+    /// NIR, and extended return fields. Extra bytes attributes are retained by this
+    /// conversion, provided that extra bytes were supplied for every point. They
+    /// are not output when the file name has a PLY, PCD, or E57 extension, as those
+    /// files are written from a `PointCloud`.
     pub fn write(&mut self) -> Result<(), Error> {
         if self.file_mode == "r" {
             return Err(Error::new(
@@ -1132,9 +1829,15 @@ impl LasFile {
             println!("WARNING: There are fewer than two points in the LAS file. This may cause some tools to fail when reading these data.");
         }
 
-        self.header.x_offset = self.header.min_x;
-        self.header.y_offset = self.header.min_y;
-        self.header.z_offset = self.header.min_z;
+        if let Some(format) = PointCloudFormat::from_file_name(&self.file_name) {
+            return write_point_cloud(&self.to_point_cloud(), &self.file_name, format);
+        }
+
+        if !self.use_header_offsets {
+            self.header.x_offset = self.header.min_x;
+            self.header.y_offset = self.header.min_y;
+            self.header.z_offset = self.header.min_z;
+        }
 
         let mut mantissa: usize = (format!("{}", (self.header.max_x - self.header.min_x).floor()))
             .to_string()
@@ -1160,6 +1863,15 @@ impl LasFile {
             self.header.z_scale_factor = dec;
         }
 
+        if split_zip_entry(&self.file_name).1.is_some() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Cannot write to {}; LAS files may only be written to a zip archive containing a single file.",
+                    self.file_name
+                ),
+            ));
+        }
         if !self.file_name.to_lowercase().ends_with(".zip") {
             let f = File::create(&self.file_name)?;
             let mut writer = BufWriter::new(f);
@@ -1196,6 +1908,18 @@ impl LasFile {
         self.header.file_signature = "LASF".to_string();
         writer.write_all(self.header.file_signature.as_bytes())?;
 
+        // Waveform packets are not output, so the waveform data flags and
+        // descriptors must not be carried over from an input file.
+        self.header.global_encoding.value &= !0b0000_0110u16;
+        self.header.waveform_data_start = 0;
+        let num_vlrs = self.vlr_data.len();
+        self.vlr_data.retain(|vlr| {
+            !(vlr.record_id >= WAVEFORM_DESCRIPTOR_FIRST_RECORD_ID
+                && vlr.record_id <= WAVEFORM_DESCRIPTOR_LAST_RECORD_ID
+                && vlr.user_id.starts_with("LASF_Spec"))
+        });
+        self.header.number_of_vlrs -= (num_vlrs - self.vlr_data.len()) as u32;
+
         u16_bytes = unsafe { mem::transmute(self.header.file_source_id) };
         writer.write_all(&u16_bytes)?;
 
@@ -1251,6 +1975,17 @@ impl LasFile {
         u16_bytes = unsafe { mem::transmute(self.header.header_size) };
         writer.write_all(&u16_bytes)?;
 
+        // The Extra Bytes VLR is only output if extra bytes were supplied for every point.
+        if self.extra_bytes_length == 0
+            || self.extra_bytes_data.len()
+                != self.header.number_of_points as usize * self.extra_bytes_length
+        {
+            if !self.extra_bytes_data.is_empty() {
+                println!("Warning: Extra bytes were not supplied for every point. Extra bytes attributes will not be output.");
+            }
+            self.remove_extra_bytes_vlr();
+        }
+
         // figure out the offset to points
         let mut total_vlr_size = 54 * self.header.number_of_vlrs;
         for i in 0..(self.header.number_of_vlrs as usize) {
@@ -1331,6 +2066,7 @@ impl LasFile {
             //if !self.use_point_intensity && !self.use_point_userdata {
             self.header.point_record_length = rec_lengths[self.header.point_format as usize][3];
         }
+        self.header.point_record_length += self.extra_bytes_length as u16;
 
         u16_bytes = unsafe { mem::transmute(self.header.point_record_length) };
         writer.write_all(&u16_bytes)?;
@@ -1433,7 +2169,7 @@ impl LasFile {
             0 => {
                 for i in 0..self.header.number_of_points as usize {
                     val = ((self.point_data[i].x - self.header.x_offset)
-                        / self.header.x_scale_factor) as i32;
+                        / self.header.x_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
 
@@ -1450,12 +2186,12 @@ impl LasFile {
                     // }
 
                     val = ((self.point_data[i].y - self.header.y_offset)
-                        / self.header.y_scale_factor) as i32;
+                        / self.header.y_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
 
                     val = ((self.point_data[i].z - self.header.z_offset)
-                        / self.header.z_scale_factor) as i32;
+                        / self.header.z_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
 
@@ -1491,23 +2227,29 @@ impl LasFile {
 
                     u16_bytes = unsafe { mem::transmute(self.point_data[i].point_source_id) };
                     writer.write_all(&u16_bytes)?;
+                    if self.extra_bytes_length > 0 {
+                        writer.write_all(
+                            &self.extra_bytes_data
+                                [i * self.extra_bytes_length..(i + 1) * self.extra_bytes_length],
+                        )?;
+                    }
                 }
             }
             1 => {
                 for i in 0..self.header.number_of_points as usize {
                     // x
                     val = ((self.point_data[i].x - self.header.x_offset)
-                        / self.header.x_scale_factor) as i32;
+                        / self.header.x_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
                     // y
                     val = ((self.point_data[i].y - self.header.y_offset)
-                        / self.header.y_scale_factor) as i32;
+                        / self.header.y_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
                     // z
                     val = ((self.point_data[i].z - self.header.z_offset)
-                        / self.header.z_scale_factor) as i32;
+                        / self.header.z_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
                     // intensity
@@ -1545,22 +2287,28 @@ impl LasFile {
 
                     u64_bytes = unsafe { mem::transmute(self.gps_data[i]) };
                     writer.write_all(&u64_bytes)?;
+                    if self.extra_bytes_length > 0 {
+                        writer.write_all(
+                            &self.extra_bytes_data
+                                [i * self.extra_bytes_length..(i + 1) * self.extra_bytes_length],
+                        )?;
+                    }
                 }
             }
             2 => {
                 for i in 0..self.header.number_of_points as usize {
                     val = ((self.point_data[i].x - self.header.x_offset)
-                        / self.header.x_scale_factor) as i32;
+                        / self.header.x_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
 
                     val = ((self.point_data[i].y - self.header.y_offset)
-                        / self.header.y_scale_factor) as i32;
+                        / self.header.y_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
 
                     val = ((self.point_data[i].z - self.header.z_offset)
-                        / self.header.z_scale_factor) as i32;
+                        / self.header.z_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
 
@@ -1605,22 +2353,28 @@ impl LasFile {
 
                     u16_bytes = unsafe { mem::transmute(self.colour_data[i].blue) };
                     writer.write_all(&u16_bytes)?;
+                    if self.extra_bytes_length > 0 {
+                        writer.write_all(
+                            &self.extra_bytes_data
+                                [i * self.extra_bytes_length..(i + 1) * self.extra_bytes_length],
+                        )?;
+                    }
                 }
             }
             3 => {
                 for i in 0..self.header.number_of_points as usize {
                     val = ((self.point_data[i].x - self.header.x_offset)
-                        / self.header.x_scale_factor) as i32;
+                        / self.header.x_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
 
                     val = ((self.point_data[i].y - self.header.y_offset)
-                        / self.header.y_scale_factor) as i32;
+                        / self.header.y_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
 
                     val = ((self.point_data[i].z - self.header.z_offset)
-                        / self.header.z_scale_factor) as i32;
+                        / self.header.z_scale_factor).round() as i32;
                     u32_bytes = unsafe { mem::transmute(val) };
                     writer.write_all(&u32_bytes)?;
 
@@ -1668,6 +2422,12 @@ impl LasFile {
 
                     u16_bytes = unsafe { mem::transmute(self.colour_data[i].blue) };
                     writer.write_all(&u16_bytes)?;
+                    if self.extra_bytes_length > 0 {
+                        writer.write_all(
+                            &self.extra_bytes_data
+                                [i * self.extra_bytes_length..(i + 1) * self.extra_bytes_length],
+                        )?;
+                    }
                 }
             }
             _ => {
@@ -1767,6 +2527,24 @@ impl fmt::Display for GlobalEncodingField {
     }
 }
 
+/// A point attribute that may be retrieved by `LasFile::get_point_attribute_value`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointAttribute {
+    Elevation,
+    Intensity,
+    Classification,
+    ReturnNumber,
+    NumberOfReturns,
+    ScanAngle,
+    Rgb,
+    Red,
+    Green,
+    Blue,
+    Nir,
+    UserData,
+    ExtraBytes(usize),
+}
+
 #[derive(Debug)]
 pub enum GpsTimeType {
     GpsWeekTime,
@@ -1835,6 +2613,26 @@ pub enum LidarPointRecord {
     },
 }
 
+impl LidarPointRecord {
+    /// Returns a mutable reference to the point data of the record, regardless of its format,
+    /// e.g. for reclassifying a point before it is added to an output file.
+    pub fn get_point_data_mut(&mut self) -> &mut PointData {
+        match self {
+            LidarPointRecord::PointRecord0 { point_data }
+            | LidarPointRecord::PointRecord1 { point_data, .. }
+            | LidarPointRecord::PointRecord2 { point_data, .. }
+            | LidarPointRecord::PointRecord3 { point_data, .. }
+            | LidarPointRecord::PointRecord4 { point_data, .. }
+            | LidarPointRecord::PointRecord5 { point_data, .. }
+            | LidarPointRecord::PointRecord6 { point_data, .. }
+            | LidarPointRecord::PointRecord7 { point_data, .. }
+            | LidarPointRecord::PointRecord8 { point_data, .. }
+            | LidarPointRecord::PointRecord9 { point_data, .. }
+            | LidarPointRecord::PointRecord10 { point_data, .. } => point_data,
+        }
+    }
+}
+
 #[derive(Default, Copy, Clone, Debug)]
 pub struct PointRecord0 {
     pub point_data: PointData,
@@ -2011,3 +2809,76 @@ where
         .map(|i| archive.by_index(i).and_then(|file| browse_func(&file)))
         .collect()
 }
+
+/// Splits the name of a LAS file that is contained within a zip archive, given as
+/// `archive.zip#folder/file.las`, into the archive name and the name of the archive
+/// entry. The entry name is `None` if the file name does not refer to an archive entry.
+pub fn split_zip_entry(file_name: &str) -> (&str, Option<&str>) {
+    // ASCII lower-casing preserves the byte offsets of the original string
+    match file_name.to_ascii_lowercase().find(".zip#") {
+        Some(i) => (&file_name[..i + 4], Some(&file_name[i + 5..])),
+        None => (file_name, None),
+    }
+}
+
+/// Returns true if a LAS file name refers to a zip archive, or to an entry of one.
+pub fn is_zipped_las(file_name: &str) -> bool {
+    split_zip_entry(file_name)
+        .0
+        .to_ascii_lowercase()
+        .ends_with(".zip")
+}
+
+/// Returns the names of the LAS files contained within a zip archive, including those
+/// within folders of the archive, in the order in which they are stored.
+pub fn list_zipped_las_files(archive_name: &str) -> Result<Vec<String>, Error> {
+    let mut file = File::open(archive_name)?;
+    let entries = browse_zip_archive(&mut file, |f| {
+        // folders are stored as entries with a trailing separator and so never end with .las
+        if f.name().to_lowercase().ends_with(".las") {
+            Ok(Some(f.name().to_string()))
+        } else {
+            Ok(None)
+        }
+    })?;
+    Ok(entries.into_iter().flatten().collect())
+}
+
+/// Reads the bytes of a zipped LAS file, up to `max_bytes` if specified. The file name
+/// may either be an archive entry (`archive.zip#file.las`) or an archive that contains
+/// a single LAS file.
+pub(crate) fn read_zipped_las(file_name: &str, max_bytes: Option<usize>) -> Result<Vec<u8>, Error> {
+    let (archive_name, entry) = split_zip_entry(file_name);
+    let entry_name = match entry {
+        Some(e) => e.to_string(),
+        None => {
+            let mut entries = list_zipped_las_files(archive_name)?;
+            match entries.len() {
+                0 => return Err(Error::new(ErrorKind::InvalidData,
+                    "The zipped archive does not contain a data file with the proper 'las' extension.")),
+                1 => entries.remove(0),
+                n => return Err(Error::new(ErrorKind::InvalidInput,
+                    format!("The zipped archive {} contains {} LAS files. Specify one of them using the form '{}#{}'.",
+                    archive_name, n, archive_name, entries[0]))),
+            }
+        }
+    };
+    let mut zip = ZipArchive::new(File::open(archive_name)?)?;
+    let mut f = match zip.by_name(&entry_name) {
+        Ok(f) => f,
+        Err(_) => return Err(Error::new(ErrorKind::NotFound,
+            format!("The file {} could not be found within the zipped archive {}.", entry_name, archive_name))),
+    };
+    match f.compression() {
+        CompressionMethod::Stored | CompressionMethod::Deflated | CompressionMethod::Bzip2 => (),
+        _ => return Err(Error::new(ErrorKind::InvalidData,
+            "Either the file is formatted incorrectly or it is an unsupported compression type.")),
+    }
+    let file_size = match max_bytes {
+        Some(n) => n.min(f.size() as usize),
+        None => f.size() as usize,
+    };
+    let mut buffer = vec![0; file_size];
+    f.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
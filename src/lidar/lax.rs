@@ -0,0 +1,306 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::las::LasFile;
+use crate::structures::BoundingBox;
+use byteorder::{ByteOrder, LittleEndian};
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::path::Path;
+
+/// A single cell of a quadtree spatial index and the intervals of point
+/// records (inclusive start and end indices) that fall within it.
+#[derive(Default, Clone, Debug)]
+pub struct LasIndexCell {
+    pub index: i32,
+    pub num_points: u32,
+    pub intervals: Vec<(u32, u32)>,
+}
+
+/// A quadtree-based spatial index of the point records in a LAS file. The
+/// index is stored in a LAX file, which uses the same format as the LASindex
+/// files created by LAStools, allowing the indexes created by either software
+/// to be used interchangeably.
+#[derive(Default, Clone, Debug)]
+pub struct LasIndex {
+    pub levels: u32,
+    pub min_x: f64,
+    pub max_x: f64,
+    pub min_y: f64,
+    pub max_y: f64,
+    pub cells: Vec<LasIndexCell>,
+}
+
+impl LasIndex {
+    /// Builds a spatial index for a LAS file. The depth of the quadtree is chosen
+    /// such that the cells contain, on average, roughly `points_per_cell` points.
+    /// Runs of records within the same cell that are separated by fewer than
+    /// `points_per_cell / 10` records are merged to reduce the number of reads.
+    pub fn build(las: &LasFile, points_per_cell: usize) -> LasIndex {
+        let n = las.header.number_of_points as usize;
+        let width = las.header.max_x - las.header.min_x;
+        let height = las.header.max_y - las.header.min_y;
+        let half_size = width.max(height) / 2f64 + 0.001;
+        let centre_x = (las.header.min_x + las.header.max_x) / 2f64;
+        let centre_y = (las.header.min_y + las.header.max_y) / 2f64;
+
+        let mut levels = 0u32;
+        let points_per_cell = points_per_cell.max(1);
+        while levels < 12 && (n / points_per_cell) > 4usize.pow(levels) {
+            levels += 1;
+        }
+
+        let mut index = LasIndex {
+            levels,
+            min_x: ((centre_x - half_size) as f32) as f64,
+            max_x: ((centre_x + half_size) as f32) as f64,
+            min_y: ((centre_y - half_size) as f32) as f64,
+            max_y: ((centre_y + half_size) as f32) as f64,
+            cells: vec![],
+        };
+
+        let max_gap = (points_per_cell / 10) as u32;
+        let mut cell_map: HashMap<i32, usize> = HashMap::new();
+        for i in 0..n {
+            let p = las[i];
+            let cell_index = index.get_cell_index(p.x, p.y);
+            let c = match cell_map.get(&cell_index) {
+                Some(c) => *c,
+                None => {
+                    cell_map.insert(cell_index, index.cells.len());
+                    index.cells.push(LasIndexCell {
+                        index: cell_index,
+                        ..Default::default()
+                    });
+                    index.cells.len() - 1
+                }
+            };
+            let cell = &mut index.cells[c];
+            cell.num_points += 1;
+            let i = i as u32;
+            match cell.intervals.last_mut() {
+                Some(interval) if i - interval.1 <= max_gap + 1 => interval.1 = i,
+                _ => cell.intervals.push((i, i)),
+            }
+        }
+        index.cells.sort_by_key(|c| c.index);
+        index
+    }
+
+    /// Returns the index of the cell at the finest level of the quadtree
+    /// containing the point (x, y).
+    pub fn get_cell_index(&self, x: f64, y: f64) -> i32 {
+        let (mut cell_min_x, mut cell_max_x) = (self.min_x, self.max_x);
+        let (mut cell_min_y, mut cell_max_y) = (self.min_y, self.max_y);
+        let mut level_index = 0u32;
+        for _ in 0..self.levels {
+            level_index <<= 2;
+            let mid_x = (cell_min_x + cell_max_x) / 2f64;
+            let mid_y = (cell_min_y + cell_max_y) / 2f64;
+            if x < mid_x {
+                cell_max_x = mid_x;
+            } else {
+                cell_min_x = mid_x;
+                level_index |= 1;
+            }
+            if y < mid_y {
+                cell_max_y = mid_y;
+            } else {
+                cell_min_y = mid_y;
+                level_index |= 2;
+            }
+        }
+        (level_offset(self.levels) + level_index) as i32
+    }
+
+    /// Returns the bounding box of a cell, which may be at any level of the quadtree.
+    pub fn get_cell_bounding_box(&self, cell_index: i32) -> BoundingBox {
+        let cell_index = cell_index.max(0) as u32;
+        let mut level = 0u32;
+        while level < 16 && level_offset(level + 1) <= cell_index {
+            level += 1;
+        }
+        let level_index = cell_index - level_offset(level);
+        let (mut min_x, mut max_x) = (self.min_x, self.max_x);
+        let (mut min_y, mut max_y) = (self.min_y, self.max_y);
+        for l in (0..level).rev() {
+            let quadrant = (level_index >> (2 * l)) & 3;
+            let mid_x = (min_x + max_x) / 2f64;
+            let mid_y = (min_y + max_y) / 2f64;
+            if quadrant & 1 == 1 {
+                min_x = mid_x;
+            } else {
+                max_x = mid_x;
+            }
+            if quadrant & 2 == 2 {
+                min_y = mid_y;
+            } else {
+                max_y = mid_y;
+            }
+        }
+        BoundingBox::new(min_x, max_x, min_y, max_y)
+    }
+
+    /// Returns the sorted, non-overlapping intervals of point records (inclusive
+    /// start and end indices) within the cells that overlap an extent. Notice that
+    /// these intervals may contain points that lie outside of the extent.
+    pub fn get_intervals(&self, extent: BoundingBox) -> Vec<(u32, u32)> {
+        // cell bounds are stored in single precision
+        let mut extent = extent;
+        extent.expand_by((self.max_x - self.min_x) * 1e-6);
+        let mut intervals = vec![];
+        for cell in &self.cells {
+            if self.get_cell_bounding_box(cell.index).overlaps(extent) {
+                intervals.extend_from_slice(&cell.intervals);
+            }
+        }
+        intervals.sort();
+        let mut merged: Vec<(u32, u32)> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.0 <= last.1.saturating_add(1) => {
+                    last.1 = last.1.max(interval.1)
+                }
+                _ => merged.push(interval),
+            }
+        }
+        merged
+    }
+
+    /// Reads a LAX file.
+    pub fn read(file_name: &str) -> Result<LasIndex, Error> {
+        let buffer = fs::read(file_name)?;
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("The LAX file {} appears to be formatted incorrectly.", file_name),
+            )
+        };
+        if buffer.len() < 48 || &buffer[0..4] != b"LASX" {
+            return Err(invalid());
+        }
+        let mut pos = 8; // signature and version
+        if &buffer[pos..pos + 4] != b"LASS" || LittleEndian::read_u32(&buffer[pos + 4..]) != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Only quadtree-based LAX spatial indexes are supported.",
+            ));
+        }
+        pos += 8;
+        if &buffer[pos..pos + 4] != b"LASQ" {
+            return Err(invalid());
+        }
+        pos += 8; // signature and version
+        let mut index = LasIndex {
+            levels: LittleEndian::read_u32(&buffer[pos..]),
+            ..Default::default()
+        };
+        pos += 12; // levels, level index, and implicit levels
+        index.min_x = LittleEndian::read_f32(&buffer[pos..]) as f64;
+        index.max_x = LittleEndian::read_f32(&buffer[pos + 4..]) as f64;
+        index.min_y = LittleEndian::read_f32(&buffer[pos + 8..]) as f64;
+        index.max_y = LittleEndian::read_f32(&buffer[pos + 12..]) as f64;
+        pos += 16;
+
+        if buffer.len() < pos + 12 || &buffer[pos..pos + 4] != b"LASV" {
+            return Err(invalid());
+        }
+        let num_cells = LittleEndian::read_u32(&buffer[pos + 8..]) as usize;
+        pos += 12;
+        for _ in 0..num_cells {
+            if buffer.len() < pos + 12 {
+                return Err(invalid());
+            }
+            let mut cell = LasIndexCell {
+                index: LittleEndian::read_i32(&buffer[pos..]),
+                num_points: LittleEndian::read_u32(&buffer[pos + 8..]),
+                ..Default::default()
+            };
+            let num_intervals = LittleEndian::read_u32(&buffer[pos + 4..]) as usize;
+            pos += 12;
+            if buffer.len() < pos + num_intervals * 8 {
+                return Err(invalid());
+            }
+            for _ in 0..num_intervals {
+                cell.intervals.push((
+                    LittleEndian::read_u32(&buffer[pos..]),
+                    LittleEndian::read_u32(&buffer[pos + 4..]),
+                ));
+                pos += 8;
+            }
+            index.cells.push(cell);
+        }
+        Ok(index)
+    }
+
+    /// Writes the index to a LAX file.
+    pub fn write(&self, file_name: &str) -> Result<(), Error> {
+        let f = File::create(file_name)?;
+        let mut writer = BufWriter::new(f);
+        let mut u32_bytes = [0u8; 4];
+
+        writer.write_all(b"LASX")?;
+        writer.write_all(&[0u8; 4])?; // version
+
+        // the quadtree
+        writer.write_all(b"LASS")?;
+        writer.write_all(&[0u8; 4])?; // quadtree type
+        writer.write_all(b"LASQ")?;
+        writer.write_all(&[0u8; 4])?; // version
+        LittleEndian::write_u32(&mut u32_bytes, self.levels);
+        writer.write_all(&u32_bytes)?;
+        writer.write_all(&[0u8; 8])?; // level index and implicit levels
+        for v in &[self.min_x, self.max_x, self.min_y, self.max_y] {
+            LittleEndian::write_f32(&mut u32_bytes, *v as f32);
+            writer.write_all(&u32_bytes)?;
+        }
+
+        // the intervals
+        writer.write_all(b"LASV")?;
+        writer.write_all(&[0u8; 4])?; // version
+        LittleEndian::write_u32(&mut u32_bytes, self.cells.len() as u32);
+        writer.write_all(&u32_bytes)?;
+        for cell in &self.cells {
+            LittleEndian::write_i32(&mut u32_bytes, cell.index);
+            writer.write_all(&u32_bytes)?;
+            LittleEndian::write_u32(&mut u32_bytes, cell.intervals.len() as u32);
+            writer.write_all(&u32_bytes)?;
+            LittleEndian::write_u32(&mut u32_bytes, cell.num_points);
+            writer.write_all(&u32_bytes)?;
+            for interval in &cell.intervals {
+                LittleEndian::write_u32(&mut u32_bytes, interval.0);
+                writer.write_all(&u32_bytes)?;
+                LittleEndian::write_u32(&mut u32_bytes, interval.1);
+                writer.write_all(&u32_bytes)?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Returns the name of the LAX file associated with a LAS file.
+pub fn get_lax_file_name(las_file_name: &str) -> String {
+    Path::new(las_file_name)
+        .with_extension("lax")
+        .to_str()
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Returns the index of the first cell at a level of the quadtree.
+fn level_offset(level: u32) -> u32 {
+    let mut offset = 0u32;
+    for l in 0..level {
+        offset += 1u32 << (2 * l);
+    }
+    offset
+}
@@ -1,15 +1,32 @@
+//! Reading and writing of LiDAR point clouds in the LAS format (versions 1.0-1.4), including
+//! zipped LAS files, extra bytes, variable length records, and waveform packets. The main type is
+//! `LasFile`, which holds the header and point records of a LAS file. Point clouds in the PLY,
+//! PCD, and E57 formats are also read into, and written from, a `LasFile`, based on the file
+//! extension.
+
 // private sub-module defined in other files
+mod e57;
+mod extra_bytes;
 mod header;
 mod las;
+mod lax;
+mod pcd;
+mod ply;
+mod point_cloud;
 mod point_data;
 mod vlr;
+mod waveform;
 
 // exports identifiers from private sub-modules in the current module namespace
+pub use self::extra_bytes::ExtraBytesDescriptor;
 pub use self::header::LasHeader;
 pub use self::las::CoordinateReferenceSystem;
 pub use self::las::GlobalEncodingField;
 pub use self::las::GpsTimeType;
+pub use self::las::{is_zipped_las, list_zipped_las_files, split_zip_entry};
 pub use self::las::LasFile;
+pub use self::lax::LasIndex;
+pub use self::lax::LasIndexCell;
 pub use self::las::LidarPointRecord;
 pub use self::las::PointRecord0;
 pub use self::las::PointRecord1;
@@ -22,8 +39,11 @@ pub use self::las::PointRecord6;
 pub use self::las::PointRecord7;
 pub use self::las::PointRecord8;
 pub use self::las::PointRecord9;
+pub use self::las::PointAttribute;
+pub use self::point_cloud::PointCloudFormat;
 pub use self::point_data::convert_class_val_to_class_string;
 pub use self::point_data::ColourData;
 pub use self::point_data::PointData;
 pub use self::point_data::WaveformPacket;
 pub use self::vlr::Vlr;
+pub use self::waveform::WaveformPacketDescriptor;
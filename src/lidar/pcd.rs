@@ -0,0 +1,395 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::point_cloud::{CloudPoint, PointCloud};
+use crate::utils::{ByteOrderReader, Endianness};
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Cursor, Error, ErrorKind};
+
+/// Reads a Point Cloud Library PCD file, with either ascii, binary, or binary_compressed data.
+/// Points with non-finite coordinates, which mark invalid measurements within organized point
+/// clouds, are skipped. Colours may be stored in either a packed 'rgb' or 'rgba' field.
+pub fn read_pcd(file_name: &str) -> Result<PointCloud, Error> {
+    let buffer = fs::read(file_name)?;
+
+    // read the header, which ends with the DATA line
+    let mut fields: Vec<PcdField> = vec![];
+    let mut sizes: Vec<usize> = vec![];
+    let mut types: Vec<char> = vec![];
+    let mut counts: Vec<usize> = vec![];
+    let mut num_points = 0usize;
+    let mut data_type = String::new();
+    let mut pos = 0usize;
+    while pos < buffer.len() {
+        let line_end = match buffer[pos..].iter().position(|b| *b == b'\n') {
+            Some(i) => pos + i,
+            None => buffer.len(),
+        };
+        let line = String::from_utf8_lossy(&buffer[pos..line_end]).to_string();
+        pos = line_end + 1;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() || words[0].starts_with('#') {
+            continue;
+        }
+        let parse_usize = |s: &str| -> Result<usize, Error> {
+            s.parse::<usize>()
+                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid value in PCD header."))
+        };
+        match words[0].to_uppercase().as_str() {
+            "FIELDS" | "COLUMNS" => {
+                fields = words[1..]
+                    .iter()
+                    .map(|w| PcdField {
+                        name: w.to_lowercase(),
+                        offset: 0,
+                    })
+                    .collect()
+            }
+            "SIZE" => {
+                sizes = words[1..]
+                    .iter()
+                    .map(|w| parse_usize(w))
+                    .collect::<Result<Vec<usize>, Error>>()?
+            }
+            "TYPE" => {
+                types = words[1..]
+                    .iter()
+                    .map(|w| w.chars().next().unwrap_or('F').to_ascii_uppercase())
+                    .collect()
+            }
+            "COUNT" => {
+                counts = words[1..]
+                    .iter()
+                    .map(|w| parse_usize(w))
+                    .collect::<Result<Vec<usize>, Error>>()?
+            }
+            "POINTS" if words.len() > 1 => num_points = parse_usize(words[1])?,
+            "DATA" if words.len() > 1 => {
+                data_type = words[1].to_lowercase();
+                break;
+            }
+            _ => {} // VERSION, WIDTH, HEIGHT, and VIEWPOINT
+        }
+    }
+    if data_type.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The PCD file header does not contain a DATA line.",
+        ));
+    }
+    if counts.is_empty() {
+        counts = vec![1; fields.len()];
+    }
+    if sizes.len() != fields.len() || types.len() != fields.len() || counts.len() != fields.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The PCD header FIELDS, SIZE, TYPE, and COUNT entries are inconsistent.",
+        ));
+    }
+    // the offset of each field's first value, in values for ascii data and bytes otherwise
+    let mut record_values = 0usize;
+    let mut record_length = 0usize;
+    for i in 0..fields.len() {
+        fields[i].offset = if data_type == "ascii" {
+            record_values
+        } else {
+            record_length
+        };
+        record_values += counts[i];
+        record_length += sizes[i] * counts[i];
+    }
+
+    let find_field = |names: &[&str]| -> Option<usize> {
+        fields.iter().position(|f| names.contains(&f.name.as_str()))
+    };
+    let (x, y, z) = match (find_field(&["x"]), find_field(&["y"]), find_field(&["z"])) {
+        (Some(x), Some(y), Some(z)) => (x, y, z),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The PCD file must contain x, y, and z fields.",
+            ))
+        }
+    };
+    let intensity = find_field(&["intensity", "reflectance"]);
+    let rgb = find_field(&["rgb", "rgba"]);
+    let classification = find_field(&["label", "classification"]);
+
+    // read the values of the fields of interest; packed colours are kept as raw bit patterns
+    let wanted: Vec<usize> = [Some(x), Some(y), Some(z), intensity, rgb, classification]
+        .iter()
+        .filter_map(|f| *f)
+        .collect();
+    let mut values: Vec<Vec<f64>> = vec![vec![]; fields.len()];
+    let mut packed_colours: Vec<u32> = vec![];
+    match data_type.as_str() {
+        "ascii" => {
+            let text = String::from_utf8_lossy(&buffer[pos.min(buffer.len())..]).to_string();
+            for line in text.lines() {
+                let words: Vec<&str> = line.split_whitespace().collect();
+                if words.len() < record_values {
+                    continue;
+                }
+                for &f in &wanted {
+                    let word = words[fields[f].offset];
+                    if Some(f) == rgb {
+                        // packed colours are written as either floats or unsigned integers
+                        let packed = if types[f] == 'F' {
+                            word.parse::<f32>().map(|v| v.to_bits()).unwrap_or(0)
+                        } else {
+                            word.parse::<f64>().map(|v| v as u32).unwrap_or(0)
+                        };
+                        packed_colours.push(packed);
+                    } else {
+                        values[f].push(word.parse::<f64>().unwrap_or(f64::NAN));
+                    }
+                }
+            }
+        }
+        "binary" | "binary_compressed" => {
+            let data = if data_type == "binary" {
+                buffer[pos.min(buffer.len())..].to_vec()
+            } else {
+                if pos + 8 > buffer.len() {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "The PCD file is missing its compressed data.",
+                    ));
+                }
+                let mut sizes_buf = [0u8; 4];
+                sizes_buf.copy_from_slice(&buffer[pos..pos + 4]);
+                let compressed_size = u32::from_le_bytes(sizes_buf) as usize;
+                sizes_buf.copy_from_slice(&buffer[pos + 4..pos + 8]);
+                let uncompressed_size = u32::from_le_bytes(sizes_buf) as usize;
+                let end = (pos + 8 + compressed_size).min(buffer.len());
+                lzf_decompress(&buffer[pos + 8..end], uncompressed_size)?
+            };
+            if data.len() < num_points * record_length {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "The PCD file contains fewer points than indicated by its header.",
+                ));
+            }
+            let mut bor = ByteOrderReader::new(Cursor::new(&data[..]), Endianness::LittleEndian);
+            for &f in &wanted {
+                // binary data are interleaved by point; compressed data are stored field by field
+                let (first, stride) = if data_type == "binary" {
+                    (fields[f].offset, record_length)
+                } else {
+                    (fields[f].offset * num_points, sizes[f] * counts[f])
+                };
+                for i in 0..num_points {
+                    bor.seek(first + i * stride);
+                    if Some(f) == rgb {
+                        packed_colours.push(bor.read_u32()?);
+                    } else {
+                        values[f].push(read_value(&mut bor, types[f], sizes[f])?);
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unsupported PCD data type '{}'.", data_type),
+            ))
+        }
+    }
+
+    let num_read = values[x].len();
+    let is_valid: Vec<bool> = (0..num_read)
+        .map(|i| values[x][i].is_finite() && values[y][i].is_finite() && values[z][i].is_finite())
+        .collect();
+    let keep = |v: &Vec<f64>| -> Vec<f64> {
+        v.iter()
+            .zip(&is_valid)
+            .filter(|(_, valid)| **valid)
+            .map(|(v, _)| *v)
+            .collect()
+    };
+
+    let mut cloud = PointCloud {
+        points: (0..num_read)
+            .filter(|i| is_valid[*i])
+            .map(|i| CloudPoint {
+                x: values[x][i],
+                y: values[y][i],
+                z: values[z][i],
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    if let Some(f) = intensity {
+        cloud.set_intensities(&keep(&values[f]));
+    }
+    if rgb.is_some() {
+        let packed: Vec<u32> = packed_colours
+            .iter()
+            .zip(&is_valid)
+            .filter(|(_, valid)| **valid)
+            .map(|(v, _)| *v)
+            .collect();
+        for (p, v) in cloud.points.iter_mut().zip(&packed) {
+            p.red = ((v >> 16) & 0xFF) as u16;
+            p.green = ((v >> 8) & 0xFF) as u16;
+            p.blue = (v & 0xFF) as u16;
+        }
+        cloud.has_colour = true;
+    }
+    if let Some(f) = classification {
+        for (p, v) in cloud.points.iter_mut().zip(&keep(&values[f])) {
+            p.classification = v.clamp(0f64, 255f64) as u8;
+        }
+        cloud.has_classification = true;
+    }
+
+    Ok(cloud)
+}
+
+/// Writes a point cloud to a binary PCD file. Coordinates are stored as doubles, to preserve the
+/// precision of projected coordinates, colours are packed into an 8-bit 'rgb' field, and point
+/// classes are stored in the 'label' field.
+pub fn write_pcd(cloud: &PointCloud, file_name: &str) -> Result<(), Error> {
+    let mut field_names = vec!["x", "y", "z"];
+    let mut sizes = vec!["8", "8", "8"];
+    let mut types = vec!["F", "F", "F"];
+    if cloud.has_intensity {
+        field_names.push("intensity");
+        sizes.push("4");
+        types.push("F");
+    }
+    if cloud.has_colour {
+        field_names.push("rgb");
+        sizes.push("4");
+        types.push("U");
+    }
+    if cloud.has_classification {
+        field_names.push("label");
+        sizes.push("4");
+        types.push("U");
+    }
+    let num_points = cloud.points.len();
+    let mut header = String::from("# .PCD v0.7 - Point Cloud Data file format\nVERSION 0.7\n");
+    header.push_str(&format!("FIELDS {}\n", field_names.join(" ")));
+    header.push_str(&format!("SIZE {}\n", sizes.join(" ")));
+    header.push_str(&format!("TYPE {}\n", types.join(" ")));
+    header.push_str(&format!(
+        "COUNT {}\n",
+        vec!["1"; field_names.len()].join(" ")
+    ));
+    header.push_str(&format!("WIDTH {}\nHEIGHT 1\n", num_points));
+    header.push_str("VIEWPOINT 0 0 0 1 0 0 0\n");
+    header.push_str(&format!("POINTS {}\nDATA binary\n", num_points));
+
+    let shift = if cloud.is_colour_16bit() { 8 } else { 0 };
+    let f = File::create(file_name)?;
+    let mut writer = BufWriter::new(f);
+    writer.write_all(header.as_bytes())?;
+    for p in &cloud.points {
+        writer.write_all(&p.x.to_le_bytes())?;
+        writer.write_all(&p.y.to_le_bytes())?;
+        writer.write_all(&p.z.to_le_bytes())?;
+        if cloud.has_intensity {
+            writer.write_all(&(p.intensity as f32).to_le_bytes())?;
+        }
+        if cloud.has_colour {
+            let packed = (((p.red >> shift) as u32 & 0xFF) << 16)
+                | (((p.green >> shift) as u32 & 0xFF) << 8)
+                | ((p.blue >> shift) as u32 & 0xFF);
+            writer.write_all(&packed.to_le_bytes())?;
+        }
+        if cloud.has_classification {
+            writer.write_all(&(p.classification as u32).to_le_bytes())?;
+        }
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+struct PcdField {
+    name: String,
+    offset: usize,
+}
+
+fn read_value<R: Read + Seek>(
+    bor: &mut ByteOrderReader<R>,
+    data_type: char,
+    size: usize,
+) -> Result<f64, Error> {
+    Ok(match (data_type, size) {
+        ('F', 4) => bor.read_f32()? as f64,
+        ('F', 8) => bor.read_f64()?,
+        ('U', 1) => bor.read_u8()? as f64,
+        ('U', 2) => bor.read_u16()? as f64,
+        ('U', 4) => bor.read_u32()? as f64,
+        ('U', 8) => bor.read_u64()? as f64,
+        ('I', 1) => bor.read_i8()? as f64,
+        ('I', 2) => bor.read_i16()? as f64,
+        ('I', 4) => bor.read_i32()? as f64,
+        ('I', 8) => bor.read_i64()? as f64,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Unsupported PCD field type '{}' of size {}.",
+                    data_type, size
+                ),
+            ))
+        }
+    })
+}
+
+/// Decompresses LZF-compressed data, as used by binary_compressed PCD files.
+fn lzf_decompress(input: &[u8], output_length: usize) -> Result<Vec<u8>, Error> {
+    let mut output: Vec<u8> = Vec::with_capacity(output_length);
+    let corrupt = || {
+        Error::new(
+            ErrorKind::InvalidData,
+            "The compressed PCD data are corrupt.",
+        )
+    };
+    let mut i = 0usize;
+    while i < input.len() {
+        let ctrl = input[i] as usize;
+        i += 1;
+        if ctrl < 32 {
+            // a run of literal bytes
+            let run = ctrl + 1;
+            if i + run > input.len() {
+                return Err(corrupt());
+            }
+            output.extend_from_slice(&input[i..i + run]);
+            i += run;
+        } else {
+            // a back reference
+            let mut length = ctrl >> 5;
+            if length == 7 {
+                length += *input.get(i).ok_or_else(corrupt)? as usize;
+                i += 1;
+            }
+            let distance = ((ctrl & 0x1f) << 8) + *input.get(i).ok_or_else(corrupt)? as usize + 1;
+            i += 1;
+            if distance > output.len() {
+                return Err(corrupt());
+            }
+            let start = output.len() - distance;
+            for j in 0..length + 2 {
+                let b = output[start + j];
+                output.push(b);
+            }
+        }
+    }
+    if output.len() != output_length {
+        return Err(corrupt());
+    }
+    Ok(output)
+}
@@ -0,0 +1,344 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::point_cloud::{CloudPoint, PointCloud};
+use crate::utils::{ByteOrderReader, Endianness};
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Cursor, Error, ErrorKind};
+
+/// Reads a PLY (Polygon File Format) file, in either its ASCII or binary encodings. The points
+/// are taken from the file's 'vertex' element; any other elements, e.g. faces, are ignored.
+pub fn read_ply(file_name: &str) -> Result<PointCloud, Error> {
+    let buffer = fs::read(file_name)?;
+    let header_end = match find_bytes(&buffer, b"end_header") {
+        Some(i) => i,
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The PLY file header does not contain an 'end_header' line.",
+            ))
+        }
+    };
+    let data_start = match buffer[header_end..].iter().position(|b| *b == b'\n') {
+        Some(i) => header_end + i + 1,
+        None => buffer.len(),
+    };
+    let header = String::from_utf8_lossy(&buffer[0..header_end]).to_string();
+
+    let mut format = String::new();
+    let mut elements: Vec<PlyElement> = vec![];
+    for (i, line) in header.lines().enumerate() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if i == 0 {
+            if words.is_empty() || words[0] != "ply" {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The file does not appear to be a PLY file.",
+                ));
+            }
+            continue;
+        }
+        if words.is_empty() {
+            continue;
+        }
+        match words[0] {
+            "format" if words.len() > 1 => format = words[1].to_string(),
+            "element" if words.len() > 2 => {
+                elements.push(PlyElement {
+                    name: words[1].to_string(),
+                    count: words[2].parse::<usize>().map_err(|_| {
+                        Error::new(ErrorKind::InvalidData, "Invalid PLY element count.")
+                    })?,
+                    properties: vec![],
+                });
+            }
+            "property" => {
+                let property = if words.len() > 4 && words[1] == "list" {
+                    PlyProperty {
+                        name: words[4].to_lowercase(),
+                        data_type: PlyType::from_str(words[3])?,
+                        list_count_type: Some(PlyType::from_str(words[2])?),
+                    }
+                } else if words.len() > 2 {
+                    PlyProperty {
+                        name: words[2].to_lowercase(),
+                        data_type: PlyType::from_str(words[1])?,
+                        list_count_type: None,
+                    }
+                } else {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "Invalid PLY property definition.",
+                    ));
+                };
+                match elements.last_mut() {
+                    Some(e) => e.properties.push(property),
+                    None => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            "A PLY property is defined before any element.",
+                        ))
+                    }
+                }
+            }
+            _ => {} // comments and obj_info
+        }
+    }
+
+    let vertex_element = match elements.iter().position(|e| e.name == "vertex") {
+        Some(i) => i,
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The PLY file does not contain a vertex element.",
+            ))
+        }
+    };
+
+    // read the values of each scalar property of the vertex element
+    let num_vertices = elements[vertex_element].count;
+    let mut values: Vec<Vec<f64>> =
+        vec![Vec::with_capacity(num_vertices); elements[vertex_element].properties.len()];
+    if format == "ascii" {
+        let text = String::from_utf8_lossy(&buffer[data_start..]);
+        let mut tokens = text.split_whitespace();
+        let mut next_value = || -> Result<f64, Error> {
+            match tokens.next() {
+                Some(t) => t
+                    .parse::<f64>()
+                    .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid value in PLY data.")),
+                None => Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "The PLY file contains fewer records than indicated by its header.",
+                )),
+            }
+        };
+        for element in &elements[0..=vertex_element] {
+            let is_vertex = element.name == "vertex";
+            for _ in 0..element.count {
+                for (j, property) in element.properties.iter().enumerate() {
+                    if property.list_count_type.is_some() {
+                        let n = next_value()? as usize;
+                        for _ in 0..n {
+                            next_value()?;
+                        }
+                    } else {
+                        let v = next_value()?;
+                        if is_vertex {
+                            values[j].push(v);
+                        }
+                    }
+                }
+            }
+        }
+    } else {
+        let endianness = match format.as_str() {
+            "binary_little_endian" => Endianness::LittleEndian,
+            "binary_big_endian" => Endianness::BigEndian,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Unsupported PLY format '{}'.", format),
+                ))
+            }
+        };
+        let mut bor = ByteOrderReader::new(Cursor::new(&buffer[data_start..]), endianness);
+        for element in &elements[0..=vertex_element] {
+            let is_vertex = element.name == "vertex";
+            for _ in 0..element.count {
+                for (j, property) in element.properties.iter().enumerate() {
+                    if let Some(count_type) = property.list_count_type {
+                        let n = count_type.read(&mut bor)? as usize;
+                        bor.inc_pos(n * property.data_type.size());
+                    } else {
+                        let v = property.data_type.read(&mut bor)?;
+                        if is_vertex {
+                            values[j].push(v);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let find_property = |names: &[&str]| -> Option<usize> {
+        elements[vertex_element]
+            .properties
+            .iter()
+            .position(|p| p.list_count_type.is_none() && names.contains(&p.name.as_str()))
+    };
+    let (x, y, z) = match (
+        find_property(&["x"]),
+        find_property(&["y"]),
+        find_property(&["z"]),
+    ) {
+        (Some(x), Some(y), Some(z)) => (x, y, z),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The PLY vertex element must contain x, y, and z properties.",
+            ))
+        }
+    };
+
+    let mut cloud = PointCloud {
+        points: (0..num_vertices)
+            .map(|i| CloudPoint {
+                x: values[x][i],
+                y: values[y][i],
+                z: values[z][i],
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    if let Some(i) = find_property(&[
+        "intensity",
+        "scalar_intensity",
+        "reflectance",
+        "scalar_reflectance",
+    ]) {
+        cloud.set_intensities(&values[i]);
+    }
+    if let (Some(r), Some(g), Some(b)) = (
+        find_property(&["red", "r", "diffuse_red"]),
+        find_property(&["green", "g", "diffuse_green"]),
+        find_property(&["blue", "b", "diffuse_blue"]),
+    ) {
+        cloud.set_colours(&values[r], &values[g], &values[b]);
+    }
+    if let Some(i) = find_property(&["classification", "scalar_classification", "class"]) {
+        for (p, v) in cloud.points.iter_mut().zip(&values[i]) {
+            p.classification = v.clamp(0f64, 255f64) as u8;
+        }
+        cloud.has_classification = true;
+    }
+
+    Ok(cloud)
+}
+
+/// Writes a point cloud to a binary (little-endian) PLY file. Coordinates are stored as doubles
+/// to preserve the precision of projected coordinates.
+pub fn write_ply(cloud: &PointCloud, file_name: &str) -> Result<(), Error> {
+    let colour_16bit = cloud.is_colour_16bit();
+    let mut header = String::from("ply\nformat binary_little_endian 1.0\n");
+    header.push_str("comment Created by WhiteboxTools\n");
+    header.push_str(&format!("element vertex {}\n", cloud.points.len()));
+    header.push_str("property double x\nproperty double y\nproperty double z\n");
+    if cloud.has_intensity {
+        header.push_str("property ushort intensity\n");
+    }
+    if cloud.has_colour {
+        let colour_type = if colour_16bit { "ushort" } else { "uchar" };
+        for channel in &["red", "green", "blue"] {
+            header.push_str(&format!("property {} {}\n", colour_type, channel));
+        }
+    }
+    if cloud.has_classification {
+        header.push_str("property uchar classification\n");
+    }
+    header.push_str("end_header\n");
+
+    let f = File::create(file_name)?;
+    let mut writer = BufWriter::new(f);
+    writer.write_all(header.as_bytes())?;
+    for p in &cloud.points {
+        writer.write_all(&p.x.to_le_bytes())?;
+        writer.write_all(&p.y.to_le_bytes())?;
+        writer.write_all(&p.z.to_le_bytes())?;
+        if cloud.has_intensity {
+            writer.write_all(&p.intensity.to_le_bytes())?;
+        }
+        if cloud.has_colour {
+            if colour_16bit {
+                writer.write_all(&p.red.to_le_bytes())?;
+                writer.write_all(&p.green.to_le_bytes())?;
+                writer.write_all(&p.blue.to_le_bytes())?;
+            } else {
+                writer.write_all(&[p.red as u8, p.green as u8, p.blue as u8])?;
+            }
+        }
+        if cloud.has_classification {
+            writer.write_all(&[p.classification])?;
+        }
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn find_bytes(buffer: &[u8], pattern: &[u8]) -> Option<usize> {
+    buffer.windows(pattern.len()).position(|w| w == pattern)
+}
+
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+struct PlyProperty {
+    name: String,
+    data_type: PlyType,
+    list_count_type: Option<PlyType>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PlyType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl PlyType {
+    fn from_str(s: &str) -> Result<PlyType, Error> {
+        match s {
+            "char" | "int8" => Ok(PlyType::I8),
+            "uchar" | "uint8" => Ok(PlyType::U8),
+            "short" | "int16" => Ok(PlyType::I16),
+            "ushort" | "uint16" => Ok(PlyType::U16),
+            "int" | "int32" => Ok(PlyType::I32),
+            "uint" | "uint32" => Ok(PlyType::U32),
+            "float" | "float32" => Ok(PlyType::F32),
+            "double" | "float64" => Ok(PlyType::F64),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unrecognized PLY property type '{}'.", s),
+            )),
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            PlyType::I8 | PlyType::U8 => 1,
+            PlyType::I16 | PlyType::U16 => 2,
+            PlyType::I32 | PlyType::U32 | PlyType::F32 => 4,
+            PlyType::F64 => 8,
+        }
+    }
+
+    fn read<R: Read + Seek>(&self, bor: &mut ByteOrderReader<R>) -> Result<f64, Error> {
+        Ok(match self {
+            PlyType::I8 => bor.read_i8()? as f64,
+            PlyType::U8 => bor.read_u8()? as f64,
+            PlyType::I16 => bor.read_i16()? as f64,
+            PlyType::U16 => bor.read_u16()? as f64,
+            PlyType::I32 => bor.read_i32()? as f64,
+            PlyType::U32 => bor.read_u32()? as f64,
+            PlyType::F32 => bor.read_f32()? as f64,
+            PlyType::F64 => bor.read_f64()?,
+        })
+    }
+}
@@ -0,0 +1,130 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::e57::{read_e57, write_e57};
+use super::pcd::{read_pcd, write_pcd};
+use super::ply::{read_ply, write_ply};
+use std::io::Error;
+
+/// The point cloud file formats, other than LAS, that can be read into, and written from, a
+/// `LasFile`. The format of a file is determined by its extension.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointCloudFormat {
+    /// The Polygon File Format (.ply), in either its ASCII or binary encodings.
+    Ply,
+    /// The Point Cloud Library's Point Cloud Data format (.pcd).
+    Pcd,
+    /// The ASTM E57 3D imaging data exchange format (.e57).
+    E57,
+}
+
+impl PointCloudFormat {
+    /// Returns the point cloud format of a file based on its extension, or `None` if the file is
+    /// not one of the supported non-LAS formats.
+    pub fn from_file_name(file_name: &str) -> Option<PointCloudFormat> {
+        let file_name = file_name.to_lowercase();
+        if file_name.ends_with(".ply") {
+            Some(PointCloudFormat::Ply)
+        } else if file_name.ends_with(".pcd") {
+            Some(PointCloudFormat::Pcd)
+        } else if file_name.ends_with(".e57") {
+            Some(PointCloudFormat::E57)
+        } else {
+            None
+        }
+    }
+}
+
+/// A point within a `PointCloud`. Colour channels are stored on the scale used by the source
+/// file, i.e. either 8- or 16-bit.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct CloudPoint {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub intensity: u16,
+    pub classification: u8,
+    pub red: u16,
+    pub green: u16,
+    pub blue: u16,
+}
+
+/// An intermediate representation of a point cloud that is read from, or written to, one of the
+/// `PointCloudFormat`s. Only those point attributes with LAS equivalents are retained.
+#[derive(Default, Clone, Debug)]
+pub struct PointCloud {
+    pub points: Vec<CloudPoint>,
+    pub has_intensity: bool,
+    pub has_colour: bool,
+    pub has_classification: bool,
+    pub wkt: String,
+}
+
+impl PointCloud {
+    /// Sets the point intensities from their values in the source file. Intensities in the
+    /// range 0-1, which is common for scanner data, are scaled to the 16-bit range used by LAS.
+    pub fn set_intensities(&mut self, values: &[f64]) {
+        let max_val = values
+            .iter()
+            .filter(|v| v.is_finite())
+            .fold(f64::NEG_INFINITY, |acc, v| acc.max(*v));
+        let multiplier = if max_val <= 1f64 { 65535f64 } else { 1f64 };
+        for (p, v) in self.points.iter_mut().zip(values) {
+            p.intensity = (v * multiplier).round().clamp(0f64, 65535f64) as u16;
+        }
+        self.has_intensity = true;
+    }
+
+    /// Sets the point colours from their values in the source file. Floating-point colours in the
+    /// range 0-1 are scaled to the 8-bit range.
+    pub fn set_colours(&mut self, red: &[f64], green: &[f64], blue: &[f64]) {
+        let max_val = red
+            .iter()
+            .chain(green.iter())
+            .chain(blue.iter())
+            .filter(|v| v.is_finite())
+            .fold(f64::NEG_INFINITY, |acc, v| acc.max(*v));
+        let multiplier = if max_val <= 1f64 { 255f64 } else { 1f64 };
+        let to_u16 = |v: f64| (v * multiplier).round().clamp(0f64, 65535f64) as u16;
+        for i in 0..self.points.len() {
+            self.points[i].red = to_u16(red[i]);
+            self.points[i].green = to_u16(green[i]);
+            self.points[i].blue = to_u16(blue[i]);
+        }
+        self.has_colour = true;
+    }
+
+    /// Returns true if any of the point colours use the 16-bit range.
+    pub fn is_colour_16bit(&self) -> bool {
+        self.points
+            .iter()
+            .any(|p| p.red > 255 || p.green > 255 || p.blue > 255)
+    }
+}
+
+/// Reads a point cloud file in one of the `PointCloudFormat`s.
+pub fn read_point_cloud(file_name: &str, format: PointCloudFormat) -> Result<PointCloud, Error> {
+    match format {
+        PointCloudFormat::Ply => read_ply(file_name),
+        PointCloudFormat::Pcd => read_pcd(file_name),
+        PointCloudFormat::E57 => read_e57(file_name),
+    }
+}
+
+/// Writes a point cloud to a file in one of the `PointCloudFormat`s.
+pub fn write_point_cloud(
+    cloud: &PointCloud,
+    file_name: &str,
+    format: PointCloudFormat,
+) -> Result<(), Error> {
+    match format {
+        PointCloudFormat::Ply => write_ply(cloud, file_name),
+        PointCloudFormat::Pcd => write_pcd(cloud, file_name),
+        PointCloudFormat::E57 => write_e57(cloud, file_name),
+    }
+}
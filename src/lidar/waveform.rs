@@ -0,0 +1,148 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use byteorder::{ByteOrder, LittleEndian};
+use std::fmt;
+use std::io::{Error, ErrorKind};
+
+/// The first record ID of the Waveform Packet Descriptor VLRs. Descriptors are
+/// stored in VLRs with record IDs 100 to 354 (User ID 'LASF_Spec'), where the
+/// record ID minus 99 is the descriptor index referenced by each point's
+/// waveform packet.
+pub const WAVEFORM_DESCRIPTOR_FIRST_RECORD_ID: u16 = 100;
+
+/// The last record ID of the Waveform Packet Descriptor VLRs.
+pub const WAVEFORM_DESCRIPTOR_LAST_RECORD_ID: u16 = 354;
+
+/// Describes the format of the waveform packets that reference it, as defined
+/// by the LAS 1.3/1.4 specification Waveform Packet Descriptor VLR.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct WaveformPacketDescriptor {
+    pub bits_per_sample: u8,
+    pub compression_type: u8,
+    pub number_of_samples: u32,
+    /// The temporal sample spacing, in picoseconds.
+    pub temporal_sample_spacing: u32,
+    pub digitizer_gain: f64,
+    pub digitizer_offset: f64,
+}
+
+impl WaveformPacketDescriptor {
+    /// Interprets the 26-byte payload of a Waveform Packet Descriptor VLR.
+    pub fn from_bytes(bytes: &[u8]) -> Result<WaveformPacketDescriptor, Error> {
+        if bytes.len() < 26 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Waveform Packet Descriptor VLR is smaller than the expected 26 bytes.",
+            ));
+        }
+        Ok(WaveformPacketDescriptor {
+            bits_per_sample: bytes[0],
+            compression_type: bytes[1],
+            number_of_samples: LittleEndian::read_u32(&bytes[2..6]),
+            temporal_sample_spacing: LittleEndian::read_u32(&bytes[6..10]),
+            digitizer_gain: LittleEndian::read_f64(&bytes[10..18]),
+            digitizer_offset: LittleEndian::read_f64(&bytes[18..26]),
+        })
+    }
+
+    /// Returns the number of bytes occupied by a packet using this descriptor.
+    pub fn get_packet_size(&self) -> usize {
+        (self.number_of_samples as usize * self.bits_per_sample as usize).div_ceil(8)
+    }
+
+    /// Decodes the raw bytes of a waveform packet into digitizer values, i.e.
+    /// `gain * sample + offset`. Samples are packed least-significant bit first
+    /// and may be between 1 and 32 bits in size. Compressed packets are not
+    /// currently supported.
+    pub fn decode_samples(&self, bytes: &[u8]) -> Result<Vec<f64>, Error> {
+        if self.compression_type != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Unsupported waveform compression type ({}).",
+                    self.compression_type
+                ),
+            ));
+        }
+        if self.bits_per_sample == 0 || self.bits_per_sample > 32 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Unsupported number of bits per waveform sample ({}).",
+                    self.bits_per_sample
+                ),
+            ));
+        }
+        if bytes.len() < self.get_packet_size() {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "The waveform packet is smaller than specified by its descriptor.",
+            ));
+        }
+        let gain = if self.digitizer_gain != 0f64 {
+            self.digitizer_gain
+        } else {
+            1f64
+        };
+        let n = self.number_of_samples as usize;
+        let mut samples = Vec::with_capacity(n);
+        match self.bits_per_sample {
+            8 => {
+                for b in bytes.iter().take(n) {
+                    samples.push(gain * *b as f64 + self.digitizer_offset);
+                }
+            }
+            16 => {
+                for i in 0..n {
+                    samples.push(
+                        gain * LittleEndian::read_u16(&bytes[i * 2..i * 2 + 2]) as f64
+                            + self.digitizer_offset,
+                    );
+                }
+            }
+            32 => {
+                for i in 0..n {
+                    samples.push(
+                        gain * LittleEndian::read_u32(&bytes[i * 4..i * 4 + 4]) as f64
+                            + self.digitizer_offset,
+                    );
+                }
+            }
+            bits => {
+                let bits = bits as usize;
+                let mut bit_pos = 0usize;
+                for _ in 0..n {
+                    let mut raw = 0u64;
+                    for b in 0..bits {
+                        let bit = (bytes[(bit_pos + b) / 8] >> ((bit_pos + b) % 8)) & 1u8;
+                        raw |= (bit as u64) << b;
+                    }
+                    bit_pos += bits;
+                    samples.push(gain * raw as f64 + self.digitizer_offset);
+                }
+            }
+        }
+        Ok(samples)
+    }
+}
+
+impl fmt::Display for WaveformPacketDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = format!("\tBits Per Sample: {}", self.bits_per_sample);
+        s = s + &format!("\n\tCompression Type: {}", self.compression_type);
+        s = s + &format!("\n\tNumber of Samples: {}", self.number_of_samples);
+        s = s + &format!(
+            "\n\tTemporal Sample Spacing (ps): {}",
+            self.temporal_sample_spacing
+        );
+        s = s + &format!("\n\tDigitizer Gain: {}", self.digitizer_gain);
+        s = s + &format!("\n\tDigitizer Offset: {}", self.digitizer_offset);
+        write!(f, "{}", s)
+    }
+}
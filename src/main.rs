@@ -2,7 +2,7 @@
 This code is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: June 21, 2017
-Last Modified: 07/11/2018
+Last Modified: 17/10/2026
 License: MIT
 */
 
@@ -17,11 +17,17 @@ by the WhiteboxTools library:
 
 | Command           | Description                                                                                       |
 | ----------------- | ------------------------------------------------------------------------------------------------- |
+| --aux_xml         | Writes a GDAL-style statistics and histogram sidecar (output.tif.aux.xml) for each raster output. |
+| --bigtiff         | Writes GeoTIFF outputs in the BigTIFF format, which is otherwise only used for outputs over 4 GB. |
 | --cd, --wd        | Changes the working directory; used in conjunction with --run flag.                               |
+| --cog             | Writes GeoTIFF outputs as Cloud Optimized GeoTIFFs, i.e. tiled with internal overviews.           |
+| --compress        | Compresses GeoTIFF outputs; --compress=deflate or --compress=lzw.                                 |
 | -h, --help        | Prints help information.                                                                          |
 | -l, --license     | Prints the whitebox-tools license.                                                                |
 | --listtools       | Lists all available tools, with tool descriptions. Keywords may also be used, --listtools slope.  |
+| --predictor       | Sets the predictor of compressed GeoTIFF outputs; 2 (differencing) or 3 (floating point).         |
 | -r, --run         | Runs a tool; used in conjunction with --cd flag; -r="LidarInfo".                                  |
+| --spatial_index   | Writes a quadtree spatial index sidecar (output.qix) for each Shapefile output.                   |
 | --toolbox         | Prints the toolbox associated with a tool; --toolbox=Slope.                                       |
 | --toolhelp        | Prints the help associated with a tool; --toolhelp="LidarInfo".                                   |
 | --toolparameters  | Prints the parameters (in json form) for a specific tool; --toolparameters=\"LidarInfo\".         |
@@ -31,24 +37,13 @@ by the WhiteboxTools library:
 
 */
 
-pub mod algorithms;
-pub mod lidar;
-pub mod raster;
-pub mod rendering;
-pub mod spatial_ref_system;
-pub mod structures;
-pub mod tools;
-pub mod utils;
-pub mod vector;
-
-use crate::tools::ToolManager;
-use nalgebra as na;
 use std::env;
-use std::io::Error;
+use std::io::{Error, ErrorKind};
 use std::path;
-
-#[macro_use]
-extern crate serde_derive;
+use whitebox_tools::raster;
+use whitebox_tools::tools::ToolManager;
+use whitebox_tools::utils;
+use whitebox_tools::vector;
 
 /// WhiteboxTools is an advanced geospatial data analysis engine.
 ///
@@ -93,6 +88,13 @@ fn run() -> Result<(), Error> {
     let mut view_code = false;
     let mut tool_args_vec: Vec<String> = vec![];
     let mut verbose = false;
+    let mut provenance = false;
+    let mut cog = false;
+    let mut aux_xml = false;
+    let mut spatial_index = false;
+    let mut big_tiff = false;
+    let mut compression = raster::geotiff::GeoTiffCompression::None;
+    let mut predictor = 1u16;
     let mut finding_working_dir = false;
     let args: Vec<String> = env::args().collect();
     if args.len() <= 1 {
@@ -203,6 +205,46 @@ fn run() -> Result<(), Error> {
             return Ok(());
         } else if arg.trim() == "-v" {
             verbose = true;
+        } else if flag_val == "-provenance" {
+            provenance = true;
+        } else if flag_val == "-cog" {
+            cog = true;
+        } else if flag_val == "-aux_xml" {
+            aux_xml = true;
+        } else if flag_val == "-spatial_index" {
+            spatial_index = true;
+        } else if flag_val == "-bigtiff" {
+            big_tiff = true;
+        } else if flag_val.starts_with("-compress") {
+            let v = flag_val
+                .replace("-compress", "")
+                .replace("=", "")
+                .replace("\"", "")
+                .replace("\'", "");
+            compression = match raster::geotiff::GeoTiffCompression::from_name(&v) {
+                Some(c) => c,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "The --compress flag must be 'deflate', 'lzw', or 'none'.",
+                    ))
+                }
+            };
+        } else if flag_val.starts_with("-predictor") {
+            let v = flag_val
+                .replace("-predictor", "")
+                .replace("=", "")
+                .replace("\"", "")
+                .replace("\'", "");
+            predictor = match v.trim().parse::<u16>() {
+                Ok(p) if (1..=3).contains(&p) => p,
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "The --predictor flag must be 1 (none), 2 (differencing), or 3 (floating point).",
+                    ))
+                }
+            };
         } else if arg.starts_with("-") {
             // it's an arg to be fed to the tool
             if !arg.contains("-17976931348623157") {
@@ -231,11 +273,24 @@ fn run() -> Result<(), Error> {
         }
     }
 
+    // a relative (or missing) working directory is taken relative to the current directory
+    if !utils::is_absolute_path(&working_dir) {
+        if let Ok(current_dir) = env::current_dir() {
+            let relative_dir = if working_dir.is_empty() { "." } else { &working_dir };
+            working_dir = utils::resolve_path(relative_dir, &current_dir.to_string_lossy());
+        }
+    }
     let sep = path::MAIN_SEPARATOR;
     if !working_dir.ends_with(sep) {
         working_dir.push_str(&(sep.to_string()));
     }
-    let tm = ToolManager::new(&working_dir, &verbose)?;
+    let mut tm = ToolManager::new(&working_dir, &verbose)?;
+    tm.provenance = provenance;
+    raster::geotiff::set_cog_output(cog);
+    raster::geotiff::set_big_tiff_output(big_tiff);
+    raster::geotiff::set_compression_output(compression, predictor);
+    raster::set_aux_xml_output(aux_xml);
+    vector::set_spatial_index_output(spatial_index);
     if run_tool {
         if tool_name.is_empty() && keywords.len() > 0 {
             tool_name = keywords[0].clone();
@@ -286,20 +341,32 @@ fn help() {
     let s = "WhiteboxTools Help
 
 The following commands are recognized:
+--aux_xml        Writes a GDAL-style statistics and histogram sidecar (output.tif.aux.xml) for each raster output.
+--bigtiff        Writes GeoTIFF outputs in the BigTIFF format, which is otherwise only used for outputs over 4 GB.
 --cd, --wd       Changes the working directory; used in conjunction with --run flag.
+--cog            Writes GeoTIFF outputs as Cloud Optimized GeoTIFFs, i.e. tiled with internal overviews.
+--compress       Compresses GeoTIFF outputs; --compress=deflate or --compress=lzw.
 -h, --help       Prints help information.
 -l, --license    Prints the whitebox-tools license.
+--provenance     Writes a provenance sidecar (output.provenance.json), including run telemetry, for each output file.
 --listtools      Lists all available tools. Keywords may also be used, --listtools slope.
+--predictor      Sets the predictor of compressed GeoTIFF outputs; 2 (differencing) or 3 (floating point).
 -r, --run        Runs a tool; used in conjuction with --wd flag; -r=\"LidarInfo\".
+--spatial_index  Writes a quadtree spatial index sidecar (output.qix) for each Shapefile output.
 --toolbox        Prints the toolbox associated with a tool; --toolbox=Slope.
 --toolhelp       Prints the help associated with a tool; --toolhelp=\"LidarInfo\".
 --toolparameters Prints the parameters (in json form) for a specific tool; --toolparameters=\"LidarInfo\".
--v               Verbose mode. Without this flag, tool outputs will not be printed.
+-v               Verbose mode. Without this flag, tool outputs will not be printed. Run telemetry
+                 (stage wall times and peak memory usage) is printed when a tool completes.
 --viewcode       Opens the source code of a tool in a web browser; --viewcode=\"LidarInfo\".
 --version        Prints the version information.
 
+Output file names may be templates based on the input file name, using the placeholders
+{input_dir}, {input_name}, {input_stem}, {input_ext}, and {tool}, e.g. -o='{input_stem}_hs.tif'.
+
 Example Usage:
 >> .*EXE_NAME -r=lidar_info --cd=\"*path*to*data*\" -i=input.las --vlr --geokeys
+>> .*EXE_NAME -r=Hillshade --cd=\"*path*to*data*\" -i=DEM.tif -o='{input_stem}_hs.tif'
 "
             .replace("*", &sep)
             .replace("EXE_NAME", exe_name);
@@ -1,11 +1,12 @@
 use super::*;
 use crate::utils::Endianness;
 use std::f64;
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::io::prelude::*;
 use std::io::BufReader;
 use std::io::BufWriter;
 use std::io::Error;
+use std::io::SeekFrom;
 use std::mem;
 
 pub fn read_arcbinary(
@@ -186,7 +187,7 @@ pub fn write_arcbinary<'a>(r: &'a mut Raster) -> Result<(), Error> {
 
     let num_cells: usize = r.configs.rows * r.configs.columns;
     for i in 0..num_cells {
-        u32_bytes = unsafe { mem::transmute(r.data[i] as f32) };
+        u32_bytes = unsafe { mem::transmute(r.data.get_value(i) as f32) };
         writer.write(&u32_bytes)?;
     }
 
@@ -194,3 +195,17 @@ pub fn write_arcbinary<'a>(r: &'a mut Raster) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Writes the values of the cells at the indices `cells` into the existing data file, without
+/// rewriting the remainder of the grid.
+pub fn update_arcbinary(r: &Raster, cells: &[usize]) -> Result<(), Error> {
+    let data_file = Path::new(&r.file_name).with_extension("flt").into_os_string().into_string().unwrap();
+    let mut f = OpenOptions::new().write(true).open(&data_file)?;
+    for &i in cells {
+        f.seek(SeekFrom::Start((i * 4) as u64))?;
+        f.write_all(&(r.data.get_value(i) as f32).to_ne_bytes())?;
+    }
+    f.flush()?;
+
+    Ok(())
+}
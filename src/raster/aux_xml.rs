@@ -0,0 +1,113 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+//! GDAL-style `.aux.xml` sidecar files, i.e. Persistent Auxiliary Metadata (PAM) datasets, which
+//! store the statistics and histogram of each band of a raster. GDAL, QGIS, and ArcGIS read these
+//! sidecars rather than scanning the raster, e.g. to determine a default contrast stretch.
+
+use super::*;
+use std::fs::File;
+use std::io::{BufWriter, Error, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static AUX_XML_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// The default number of histogram bins, which is that used by GDAL.
+pub const AUX_XML_HISTOGRAM_BINS: usize = 256;
+
+/// Sets whether an `.aux.xml` statistics sidecar is written for every raster output, e.g. when
+/// the `--aux_xml` flag is specified.
+pub fn set_aux_xml_output(enabled: bool) {
+    AUX_XML_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns true if an `.aux.xml` statistics sidecar is written for every raster output.
+pub fn is_aux_xml_output() -> bool {
+    AUX_XML_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Returns the file name of the `.aux.xml` sidecar of a raster file, e.g. 'dem.tif.aux.xml'.
+pub fn aux_xml_file_name(file_name: &str) -> String {
+    format!("{}.aux.xml", file_name)
+}
+
+/// Writes the statistics (minimum, maximum, mean, standard deviation, and percentage of valid
+/// cells) and histogram of each band of a raster to an `.aux.xml` sidecar, using `num_bins`
+/// equal-width histogram bins. Following GDAL, the bins of 8-bit rasters are centred on the
+/// integer values 0-255, and otherwise the outer bins are centred on the minimum and maximum.
+pub fn write_aux_xml(r: &Raster, num_bins: usize) -> Result<(), Error> {
+    let num_bins = num_bins.max(1);
+    let num_cells = r.num_cells();
+    let f = File::create(aux_xml_file_name(&r.file_name))?;
+    let mut writer = BufWriter::new(f);
+    writer.write_all(b"<PAMDataset>\n")?;
+    for band in 0..r.num_bands() {
+        let nodata = r.get_band_nodata(band);
+        let (minimum, maximum, mean, stdev) = r.calculate_band_statistics(band);
+        let (hist_min, hist_max) = if r.configs.data_type == DataType::U8 && num_bins == 256 {
+            (-0.5, 255.5)
+        } else if maximum > minimum {
+            let half_bin = (maximum - minimum) / (2.0 * (num_bins.max(2) - 1) as f64);
+            (minimum - half_bin, maximum + half_bin)
+        } else if minimum.is_finite() {
+            (minimum - 0.5, minimum + 0.5)
+        } else {
+            (0.0, 1.0)
+        };
+        let bin_size = (hist_max - hist_min) / num_bins as f64;
+        let mut counts = vec![0usize; num_bins];
+        let mut num_valid = 0usize;
+        for row in 0..r.configs.rows as isize {
+            for col in 0..r.configs.columns as isize {
+                let z = r.get_band_value(band, row, col);
+                if z != nodata && !z.is_nan() {
+                    num_valid += 1;
+                    if z >= hist_min && z <= hist_max {
+                        let bin = (((z - hist_min) / bin_size) as usize).min(num_bins - 1);
+                        counts[bin] += 1;
+                    }
+                }
+            }
+        }
+
+        writer.write_all(format!("  <PAMRasterBand band=\"{}\">\n", band + 1).as_bytes())?;
+        writer.write_all(b"    <Histograms>\n      <HistItem>\n")?;
+        writer.write_all(format!("        <HistMin>{}</HistMin>\n", hist_min).as_bytes())?;
+        writer.write_all(format!("        <HistMax>{}</HistMax>\n", hist_max).as_bytes())?;
+        writer.write_all(format!("        <BucketCount>{}</BucketCount>\n", num_bins).as_bytes())?;
+        writer.write_all(b"        <IncludeOutOfRange>0</IncludeOutOfRange>\n")?;
+        writer.write_all(b"        <Approximate>0</Approximate>\n")?;
+        let counts_str = counts
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<String>>()
+            .join("|");
+        writer.write_all(format!("        <HistCounts>{}</HistCounts>\n", counts_str).as_bytes())?;
+        writer.write_all(b"      </HistItem>\n    </Histograms>\n")?;
+        if num_valid > 0 {
+            writer.write_all(b"    <Metadata>\n")?;
+            let entries = [
+                ("STATISTICS_MAXIMUM", maximum),
+                ("STATISTICS_MEAN", mean),
+                ("STATISTICS_MINIMUM", minimum),
+                ("STATISTICS_STDDEV", stdev),
+                (
+                    "STATISTICS_VALID_PERCENT",
+                    100.0 * num_valid as f64 / num_cells.max(1) as f64,
+                ),
+            ];
+            for (key, value) in entries.iter() {
+                writer.write_all(format!("      <MDI key=\"{}\">{}</MDI>\n", key, value).as_bytes())?;
+            }
+            writer.write_all(b"    </Metadata>\n")?;
+        }
+        writer.write_all(b"  </PAMRasterBand>\n")?;
+    }
+    writer.write_all(b"</PAMDataset>\n")?;
+    writer.flush()
+}
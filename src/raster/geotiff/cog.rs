@@ -0,0 +1,435 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use super::ifd::Entry;
+use super::tiff_consts::*;
+use super::compression::{compress_block, output_compression};
+use super::{is_big_tiff_output, push_image_structure_entries, push_metadata_entries, write_ifd};
+use crate::raster::*;
+use crate::utils::ByteOrderWriter;
+use std::fs::File;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The maximum width and height, in cells, of the tiles of Cloud Optimized GeoTIFFs. Rasters that
+/// are smaller than this use tiles that are just large enough to hold them (see `cog_tile_size`).
+pub const COG_TILE_SIZE: usize = 512;
+
+static COG_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether every GeoTIFF output is written as a Cloud Optimized GeoTIFF, e.g. when the
+/// `--cog` flag is specified, regardless of the `cloud_optimized` option of its configs.
+pub fn set_cog_output(enabled: bool) {
+    COG_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns true if every GeoTIFF output is written as a Cloud Optimized GeoTIFF.
+pub fn is_cog_output() -> bool {
+    COG_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Writes a raster as a Cloud Optimized GeoTIFF (COG), i.e. a tiled GeoTIFF with internal
+/// overviews, laid out such that a client can read the IFDs of all of the resolution levels with
+/// a single request from the start of the file and then fetch individual tiles using HTTP range
+/// requests. The full-resolution image is stored in the first IFD, followed by the IFDs of the
+/// overviews, each half the size of the previous level, until a level fits within a single tile.
+/// The tile data follow the IFDs, from the smallest overview to the full-resolution image.
+/// Every level uses the tile dimensions of the full-resolution image.
+///
+/// Overviews of continuous data are the mean of the valid cells of each 2 x 2 block, while those
+/// of categorical, Boolean, and RGB data are sampled from the upper-left cell of each block.
+pub fn write_cog<'a>(r: &'a mut Raster) -> Result<(), Error> {
+    let bytes_per_pixel = r.configs.data_type.get_data_size();
+    if bytes_per_pixel == 0 || r.configs.data_type == DataType::RGB48 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unsupported data type for Cloud Optimized GeoTIFF output: {:?}.",
+                r.configs.data_type
+            ),
+        ));
+    }
+    let bands = r.configs.bands.max(1) as usize;
+    if bands > 1
+        && matches!(
+            r.configs.data_type,
+            DataType::RGB24 | DataType::RGBA32 | DataType::RGB48
+        )
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Multi-band rasters must have a single-sample data type.",
+        ));
+    }
+
+    // Categorical data are resampled by nearest neighbour, although they are written as
+    // continuous data, as they are by write_geotiff.
+    let nearest = matches!(
+        r.configs.photometric_interp,
+        PhotometricInterpretation::Categorical
+            | PhotometricInterpretation::Paletted
+            | PhotometricInterpretation::Boolean
+            | PhotometricInterpretation::RGB
+    );
+    if r.configs.photometric_interp == PhotometricInterpretation::Categorical
+        || r.configs.photometric_interp == PhotometricInterpretation::Paletted
+    {
+        r.configs.photometric_interp = PhotometricInterpretation::Continuous;
+    }
+    if r.configs.photometric_interp == PhotometricInterpretation::Unknown {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Error while writing GeoTIFF file. Unknown Photometric Interpretation.",
+        ));
+    }
+
+    ///////////////////////////////////////////////
+    // Create the overviews and encode the tiles //
+    ///////////////////////////////////////////////
+
+    let mut overviews: Vec<(usize, usize, RasterData)> = vec![];
+    let (mut columns, mut rows) = (r.configs.columns, r.configs.rows);
+    while columns > COG_TILE_SIZE || rows > COG_TILE_SIZE {
+        let overview = {
+            let data = match overviews.last() {
+                Some(level) => &level.2,
+                None => &r.data,
+            };
+            downsample(data, columns, rows, bands, r.configs.nodata, nearest)
+        };
+        columns = overview.0;
+        rows = overview.1;
+        overviews.push(overview);
+    }
+
+    let tile_size = (
+        cog_tile_size(r.configs.columns),
+        cog_tile_size(r.configs.rows),
+    );
+    let mut levels: Vec<(usize, usize, Vec<Vec<u8>>)> = vec![(
+        r.configs.columns,
+        r.configs.rows,
+        encode_tiles(r, &r.data, r.configs.columns, r.configs.rows, bands, tile_size)?,
+    )];
+    for (columns, rows, data) in &overviews {
+        levels.push((
+            *columns,
+            *rows,
+            encode_tiles(r, data, *columns, *rows, bands, tile_size)?,
+        ));
+    }
+    drop(overviews);
+
+    let total_tile_bytes: usize = levels
+        .iter()
+        .map(|level| level.2.iter().map(|tile| tile.len()).sum::<usize>())
+        .sum();
+    let is_big_tiff =
+        is_big_tiff_output() || total_tile_bytes as u64 + 1_000_000u64 >= u32::MAX as u64;
+
+    ////////////////////////////////////
+    // Lay out the IFDs and tile data //
+    ////////////////////////////////////
+
+    // GDAL's structural metadata, which follows the header, identify the file as a COG.
+    let structural_metadata = "LAYOUT=IFDS_BEFORE_DATA\nBLOCK_ORDER=ROW_MAJOR\nKNOWN_INCOMPATIBLE_EDITION=NO\n";
+    let mut ghost_area = format!(
+        "GDAL_STRUCTURAL_METADATA_SIZE={:06} bytes\n{}",
+        structural_metadata.len(),
+        structural_metadata
+    )
+    .into_bytes();
+    if ghost_area.len() % 2 == 1 {
+        ghost_area.push(32);
+    }
+    let header_length = if !is_big_tiff { 8u64 } else { 16u64 };
+
+    // The IFDs are first created with placeholder tile offsets to determine their lengths, which
+    // are independent of the offset values.
+    let mut ifd_starts = vec![];
+    let mut pos = header_length + ghost_area.len() as u64;
+    for level in 0..levels.len() {
+        let placeholder_offsets = vec![0u64; levels[level].2.len()];
+        let (entries, larger_values_data) =
+            create_level_ifd(r, &levels, level, &placeholder_offsets, tile_size, is_big_tiff)?;
+        ifd_starts.push(pos);
+        pos += ifd_length(entries.len(), is_big_tiff) + larger_values_data.len() as u64;
+    }
+
+    // tile data are stored from the smallest overview to the full-resolution image
+    let mut tile_offsets = vec![vec![]; levels.len()];
+    for level in (0..levels.len()).rev() {
+        for tile in &levels[level].2 {
+            tile_offsets[level].push(pos);
+            pos += tile.len() as u64;
+        }
+    }
+
+    ////////////////////
+    // Write the file //
+    ////////////////////
+
+    let f = File::create(r.file_name.clone())?;
+    let writer = BufWriter::new(f);
+    let mut bow = ByteOrderWriter::<BufWriter<File>>::new(writer, r.configs.endian);
+
+    if r.configs.endian == Endianness::LittleEndian {
+        bow.write_bytes("II".as_bytes())?;
+    } else {
+        bow.write_bytes("MM".as_bytes())?;
+    }
+    if !is_big_tiff {
+        bow.write_u16(42u16)?;
+        bow.write_u32(ifd_starts[0] as u32)?;
+    } else {
+        bow.write_u16(43u16)?;
+        bow.write_u16(8u16)?; // Bytesize of offsets
+        bow.write_u16(0u16)?; // Always 0
+        bow.write_u64(ifd_starts[0])?;
+    }
+    bow.write_bytes(&ghost_area)?;
+
+    for level in 0..levels.len() {
+        let (entries, larger_values_data) =
+            create_level_ifd(r, &levels, level, &tile_offsets[level], tile_size, is_big_tiff)?;
+        let next_ifd = if level + 1 < levels.len() {
+            ifd_starts[level + 1]
+        } else {
+            0u64
+        };
+        write_ifd(
+            &mut bow,
+            entries,
+            &larger_values_data,
+            ifd_starts[level],
+            next_ifd,
+            is_big_tiff,
+        )?;
+        bow.write_bytes(&larger_values_data)?;
+    }
+
+    for level in (0..levels.len()).rev() {
+        for tile in &levels[level].2 {
+            bow.write_bytes(tile)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates the IFD entries, and the values that do not fit within the entries, of a resolution
+/// level. The first level is the full-resolution image, which holds the georeferencing tags.
+fn create_level_ifd(
+    r: &Raster,
+    levels: &[(usize, usize, Vec<Vec<u8>>)],
+    level: usize,
+    tile_offsets: &[u64],
+    tile_size: (usize, usize),
+    is_big_tiff: bool,
+) -> Result<(Vec<Entry>, Vec<u8>), Error> {
+    let mut ifd_entries: Vec<Entry> = vec![];
+    let mut larger_values_data = ByteOrderWriter::<Vec<u8>>::new(vec![], r.configs.endian);
+    let (columns, rows, tiles) = (&levels[level].0, &levels[level].1, &levels[level].2);
+    let (compression, predictor) = output_compression(r);
+
+    push_image_structure_entries(
+        r,
+        *columns,
+        *rows,
+        compression,
+        predictor,
+        &mut ifd_entries,
+        &mut larger_values_data,
+    )?;
+
+    if level == 0 {
+        push_metadata_entries(r, is_big_tiff, &mut ifd_entries, &mut larger_values_data)?;
+    } else {
+        // NewSubfileType tag (254); a reduced-resolution version of the image
+        ifd_entries.push(Entry::new(TAG_NEWSUBFILETYPE, DT_LONG, 1u64, 1u64));
+    }
+
+    // TileWidth (322) and TileLength (323) tags
+    ifd_entries.push(Entry::new(TAG_TILEWIDTH, DT_SHORT, 1u64, tile_size.0 as u64));
+    ifd_entries.push(Entry::new(TAG_TILELENGTH, DT_SHORT, 1u64, tile_size.1 as u64));
+
+    // TileOffsets (324) and TileByteCounts (325) tags
+    let byte_counts: Vec<u64> = tiles.iter().map(|tile| tile.len() as u64).collect();
+    for (tag, values) in [(TAG_TILEOFFSETS, tile_offsets), (TAG_TILEBYTECOUNTS, &byte_counts[..])] {
+        let ifd_type = if !is_big_tiff { DT_LONG } else { DT_TIFF_LONG8 };
+        if values.len() == 1 {
+            // the value fits within the value offset
+            ifd_entries.push(Entry::new(tag, ifd_type, 1u64, values[0]));
+        } else {
+            ifd_entries.push(Entry::new(
+                tag,
+                ifd_type,
+                values.len() as u64,
+                larger_values_data.len() as u64,
+            ));
+            for value in values {
+                if !is_big_tiff {
+                    larger_values_data.write_u32(*value as u32)?;
+                } else {
+                    larger_values_data.write_u64(*value)?;
+                }
+            }
+        }
+    }
+
+    // the following IFD must start on a word
+    if larger_values_data.len() % 2 == 1 {
+        larger_values_data.write_u8(0u8)?;
+    }
+
+    Ok((ifd_entries, larger_values_data.into_inner()))
+}
+
+/// Returns the tile width (or height) for a raster of `cells` columns (or rows), i.e.
+/// `COG_TILE_SIZE`, or, for smaller rasters, the smallest multiple of 16 (as TIFF requires) that
+/// holds the raster, such that small rasters are not padded to much larger tiles.
+fn cog_tile_size(cells: usize) -> usize {
+    (cells.max(1).div_ceil(16) * 16).min(COG_TILE_SIZE)
+}
+
+fn ifd_length(num_entries: usize, is_big_tiff: bool) -> u64 {
+    if !is_big_tiff {
+        2u64 + num_entries as u64 * 12u64 + 4u64
+    } else {
+        8u64 + num_entries as u64 * 20u64 + 8u64
+    }
+}
+
+/// Creates an overview of band-sequential data by halving their resolution. Returns the columns,
+/// rows, and data of the overview.
+fn downsample(
+    data: &RasterData,
+    columns: usize,
+    rows: usize,
+    bands: usize,
+    nodata: f64,
+    nearest: bool,
+) -> (usize, usize, RasterData) {
+    let out_columns = (columns + 1) / 2;
+    let out_rows = (rows + 1) / 2;
+    let mut output = vec![nodata; out_columns * out_rows * bands];
+    for band in 0..bands {
+        let band_offset = band * rows * columns;
+        let out_band_offset = band * out_rows * out_columns;
+        for row in 0..out_rows {
+            for col in 0..out_columns {
+                let i = out_band_offset + row * out_columns + col;
+                if nearest {
+                    output[i] = data.get_value(band_offset + 2 * row * columns + 2 * col);
+                    continue;
+                }
+                let mut total = 0f64;
+                let mut n = 0f64;
+                for r in (2 * row)..(2 * row + 2).min(rows) {
+                    for c in (2 * col)..(2 * col + 2).min(columns) {
+                        let z = data.get_value(band_offset + r * columns + c);
+                        if z != nodata {
+                            total += z;
+                            n += 1f64;
+                        }
+                    }
+                }
+                if n > 0f64 {
+                    output[i] = total / n;
+                }
+            }
+        }
+    }
+    (out_columns, out_rows, RasterData::from(output))
+}
+
+/// Encodes band-sequential data as tiles of `tile_size` (width, height) cells, ordered by
+/// band, tile row, and tile column. The tiles at the right and bottom edges are padded with
+/// NoData, or zero for RGB data, as tiles must be complete. Each tile is compressed individually
+/// if the output is compressed.
+fn encode_tiles(
+    r: &Raster,
+    data: &RasterData,
+    columns: usize,
+    rows: usize,
+    bands: usize,
+    tile_size: (usize, usize),
+) -> Result<Vec<Vec<u8>>, Error> {
+    let (tile_width, tile_height) = tile_size;
+    let tiles_across = columns.div_ceil(tile_width);
+    let tiles_down = rows.div_ceil(tile_height);
+    let is_rgb = bands == 1 && r.configs.photometric_interp == PhotometricInterpretation::RGB;
+    let is_integer = !matches!(r.configs.data_type, DataType::F32 | DataType::F64);
+    let fill = if is_rgb { 0f64 } else { r.configs.nodata };
+    let tile_bytes = tile_width * tile_height * r.configs.data_type.get_data_size();
+    let (compression, predictor) = output_compression(r);
+    let mut tiles = Vec::with_capacity(tiles_across * tiles_down * bands);
+    for band in 0..bands {
+        let band_offset = band * rows * columns;
+        for tile_row in 0..tiles_down {
+            for tile_col in 0..tiles_across {
+                let mut bow = ByteOrderWriter::<Vec<u8>>::new(
+                    Vec::with_capacity(tile_bytes),
+                    r.configs.endian,
+                );
+                for row in (tile_row * tile_height)..((tile_row + 1) * tile_height) {
+                    for col in (tile_col * tile_width)..((tile_col + 1) * tile_width) {
+                        let mut z = if row < rows && col < columns {
+                            data.get_value(band_offset + row * columns + col)
+                        } else {
+                            fill
+                        };
+                        if is_integer && !is_rgb {
+                            // overviews of integer data are averages
+                            z = z.round();
+                        }
+                        if is_rgb {
+                            let val = z as u32;
+                            bow.write_u8((val & 0xFF) as u8)?; // red
+                            bow.write_u8(((val >> 8u32) & 0xFF) as u8)?; // green
+                            bow.write_u8(((val >> 16u32) & 0xFF) as u8)?; // blue
+                            if r.configs.data_type != DataType::RGB24 {
+                                bow.write_u8(((val >> 24u32) & 0xFF) as u8)?; // a
+                            }
+                            continue;
+                        }
+                        match r.configs.data_type {
+                            DataType::F64 => bow.write_f64(z)?,
+                            DataType::F32 => bow.write_f32(z as f32)?,
+                            DataType::U64 => bow.write_u64(z as u64)?,
+                            DataType::U32 => bow.write_u32(z as u32)?,
+                            DataType::U16 => bow.write_u16(z as u16)?,
+                            DataType::U8 => bow.write_u8(z as u8)?,
+                            DataType::I64 => bow.write_i64(z as i64)?,
+                            DataType::I32 => bow.write_i32(z as i32)?,
+                            DataType::I16 => bow.write_i16(z as i16)?,
+                            DataType::I8 => bow.write_i8(z as i8)?,
+                            _ => {
+                                return Err(Error::new(
+                                    ErrorKind::InvalidData,
+                                    format!(
+                                        "Unknown data type: {:?}. Photomet interp: {:?}",
+                                        r.configs.data_type, r.configs.photometric_interp
+                                    ),
+                                ));
+                            }
+                        }
+                    }
+                }
+                tiles.push(compress_block(
+                    r,
+                    bow.into_inner(),
+                    tile_width,
+                    compression,
+                    predictor,
+                )?);
+            }
+        }
+    }
+    Ok(tiles)
+}
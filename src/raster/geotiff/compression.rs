@@ -0,0 +1,305 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::tiff_consts::*;
+use crate::raster::*;
+use crate::utils::Endianness;
+use libflate::zlib::Encoder;
+use std::collections::HashMap;
+use std::io::{Error, Write};
+use std::sync::atomic::{AtomicU16, AtomicU8, Ordering};
+
+static COMPRESSION_OUTPUT: AtomicU8 = AtomicU8::new(0);
+static PREDICTOR_OUTPUT: AtomicU16 = AtomicU16::new(1);
+
+/// The compression of the image data of GeoTIFF outputs.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GeoTiffCompression {
+    #[default]
+    None,
+    Lzw,
+    Deflate,
+}
+
+impl GeoTiffCompression {
+    /// Returns the compression with a given name, i.e. 'none', 'lzw', or 'deflate' (or 'zip').
+    pub fn from_name(name: &str) -> Option<GeoTiffCompression> {
+        match name.trim().to_lowercase().as_str() {
+            "none" => Some(GeoTiffCompression::None),
+            "lzw" => Some(GeoTiffCompression::Lzw),
+            "deflate" | "zip" => Some(GeoTiffCompression::Deflate),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the Compression tag (259) of the compression.
+    pub fn tag_value(&self) -> u16 {
+        match self {
+            GeoTiffCompression::None => COMPRESS_NONE,
+            GeoTiffCompression::Lzw => COMPRESS_LZW,
+            GeoTiffCompression::Deflate => COMPRESS_DEFLATE,
+        }
+    }
+}
+
+/// Sets the compression and predictor of every GeoTIFF output whose configs do not specify a
+/// compression, e.g. when the `--compress` and `--predictor` flags are specified. The predictor is
+/// 1 (none), 2 (horizontal differencing), or 3 (floating point prediction).
+pub fn set_compression_output(compression: GeoTiffCompression, predictor: u16) {
+    let value = match compression {
+        GeoTiffCompression::None => 0u8,
+        GeoTiffCompression::Lzw => 1u8,
+        GeoTiffCompression::Deflate => 2u8,
+    };
+    COMPRESSION_OUTPUT.store(value, Ordering::Relaxed);
+    PREDICTOR_OUTPUT.store(predictor, Ordering::Relaxed);
+}
+
+/// Returns the compression and predictor of GeoTIFF outputs whose configs do not specify a
+/// compression.
+pub fn compression_output() -> (GeoTiffCompression, u16) {
+    let compression = match COMPRESSION_OUTPUT.load(Ordering::Relaxed) {
+        1 => GeoTiffCompression::Lzw,
+        2 => GeoTiffCompression::Deflate,
+        _ => GeoTiffCompression::None,
+    };
+    (compression, PREDICTOR_OUTPUT.load(Ordering::Relaxed))
+}
+
+/// Returns the compression and predictor with which a raster is written, i.e. those of its configs,
+/// if a compression is specified, and otherwise those of all outputs. Floating point prediction
+/// (predictor 3) only applies to floating point data, for which horizontal differencing is used
+/// instead, and no predictor is used with uncompressed data.
+pub(super) fn output_compression(r: &Raster) -> (GeoTiffCompression, u16) {
+    let (compression, mut predictor) = if r.configs.compression != GeoTiffCompression::None {
+        (r.configs.compression, r.configs.predictor)
+    } else {
+        compression_output()
+    };
+    if compression == GeoTiffCompression::None || !(2..=3).contains(&predictor) {
+        predictor = 1;
+    } else if predictor == 3 && !matches!(r.configs.data_type, DataType::F32 | DataType::F64) {
+        predictor = 2;
+    }
+    (compression, predictor)
+}
+
+/// Applies the predictor to an encoded strip or tile of a raster, whose rows are each `columns`
+/// cells wide, and then compresses it.
+pub(super) fn compress_block(
+    r: &Raster,
+    mut data: Vec<u8>,
+    columns: usize,
+    compression: GeoTiffCompression,
+    predictor: u16,
+) -> Result<Vec<u8>, Error> {
+    let samples_per_pixel = if r.configs.bands <= 1
+        && r.configs.photometric_interp == PhotometricInterpretation::RGB
+    {
+        match r.configs.data_type {
+            DataType::RGB24 => 3usize,
+            _ => 4usize,
+        }
+    } else {
+        1usize
+    };
+    let bytes_per_sample = r.configs.data_type.get_data_size() / samples_per_pixel;
+    if predictor > 1 {
+        apply_predictor(
+            &mut data,
+            predictor,
+            columns * samples_per_pixel,
+            samples_per_pixel,
+            bytes_per_sample,
+            r.configs.endian,
+        );
+    }
+    match compression {
+        GeoTiffCompression::None => Ok(data),
+        GeoTiffCompression::Lzw => Ok(lzw_encode(&data)),
+        GeoTiffCompression::Deflate => {
+            let mut encoder = Encoder::new(Vec::with_capacity(data.len() / 2))?;
+            encoder.write_all(&data)?;
+            encoder.finish().into_result()
+        }
+    }
+}
+
+/// Applies the predictor to the rows of a strip or tile, each containing `row_samples` samples of
+/// `bytes_per_sample` bytes, interleaved by pixel. This is the inverse of `undo_predictor`.
+fn apply_predictor(
+    buf: &mut [u8],
+    predictor: u16,
+    row_samples: usize,
+    samples_per_pixel: usize,
+    bytes_per_sample: usize,
+    endian: Endianness,
+) {
+    let bps = bytes_per_sample;
+    let row_bytes = row_samples * bps;
+    if row_bytes == 0 || bps == 0 {
+        return;
+    }
+    let little_endian = endian == Endianness::LittleEndian;
+    for row in buf.chunks_exact_mut(row_bytes) {
+        if predictor == 2 {
+            // samples are differenced from the last to the first, such that each is differenced
+            // from the original value of the previous pixel
+            for i in (samples_per_pixel..row_samples).rev() {
+                let (prev, cur) = row.split_at_mut(i * bps);
+                let prev = &prev[(i - samples_per_pixel) * bps..];
+                difference_sample(&mut cur[0..bps], prev, little_endian, false);
+            }
+        } else if predictor == 3 {
+            let tmp = row.to_vec();
+            for count in 0..row_samples {
+                for byte in 0..bps {
+                    let i = if little_endian {
+                        (bps - byte - 1) * row_samples + count
+                    } else {
+                        byte * row_samples + count
+                    };
+                    row[i] = tmp[bps * count + byte];
+                }
+            }
+            for i in (samples_per_pixel..row_bytes).rev() {
+                row[i] = row[i].wrapping_sub(row[i - samples_per_pixel]);
+            }
+        }
+    }
+}
+
+/// Reverses the prediction of a decoded strip or tile, whose rows each contain `row_samples`
+/// samples of `bytes_per_sample` bytes, interleaved by pixel. With horizontal differencing
+/// (`predictor` 2), each sample is stored as the integer difference from the corresponding sample
+/// of the previous pixel in the row, even for floating point data. With floating point prediction
+/// (`predictor` 3), the bytes of each row are stored most significant byte first, grouped by byte
+/// significance, and differenced.
+pub(super) fn undo_predictor(
+    buf: &mut [u8],
+    predictor: u16,
+    row_samples: usize,
+    samples_per_pixel: usize,
+    bytes_per_sample: usize,
+    endian: Endianness,
+) {
+    let bps = bytes_per_sample;
+    let row_bytes = row_samples * bps;
+    if row_bytes == 0 || bps == 0 {
+        return;
+    }
+    let little_endian = endian == Endianness::LittleEndian;
+    for row in buf.chunks_exact_mut(row_bytes) {
+        if predictor == 2 {
+            for i in samples_per_pixel..row_samples {
+                let (prev, cur) = row.split_at_mut(i * bps);
+                let prev = &prev[(i - samples_per_pixel) * bps..];
+                difference_sample(&mut cur[0..bps], prev, little_endian, true);
+            }
+        } else if predictor == 3 {
+            for i in samples_per_pixel..row_bytes {
+                row[i] = row[i].wrapping_add(row[i - samples_per_pixel]);
+            }
+            let tmp = row.to_vec();
+            for count in 0..row_samples {
+                for byte in 0..bps {
+                    row[bps * count + byte] = if little_endian {
+                        tmp[(bps - byte - 1) * row_samples + count]
+                    } else {
+                        tmp[byte * row_samples + count]
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Adds the integer sample `prev` to the sample `cur`, or subtracts it, with wrapping, where both
+/// samples are stored in the given byte order.
+fn difference_sample(cur: &mut [u8], prev: &[u8], little_endian: bool, add: bool) {
+    let bps = cur.len();
+    let mut carry = 0i16;
+    for k in 0..bps {
+        // bytes from the least to the most significant
+        let b = if little_endian { k } else { bps - k - 1 };
+        let v = if add {
+            cur[b] as i16 + prev[b] as i16 + carry
+        } else {
+            cur[b] as i16 - prev[b] as i16 + carry
+        };
+        cur[b] = v as u8;
+        carry = if v > 255 {
+            1
+        } else if v < 0 {
+            -1
+        } else {
+            0
+        };
+    }
+}
+
+/// Compresses data using the LZW variant of TIFF, i.e. with codes written most significant bit
+/// first, a Clear code at the start of the data, and the code width increasing one code earlier
+/// than in GIF files ("early change"), which is the stream read by `lzw::DecoderEarlyChange`.
+fn lzw_encode(data: &[u8]) -> Vec<u8> {
+    const CLEAR_CODE: u32 = 256;
+    const EOI_CODE: u32 = 257;
+    const FIRST_CODE: u32 = 258;
+    // the table is reset before its codes would exceed 12 bits
+    const TABLE_FULL: u32 = 4094;
+
+    let mut output = Vec::with_capacity(data.len() / 2);
+    let mut bit_buffer = 0u64;
+    let mut num_bits = 0u32;
+    let mut write_code = |code: u32, code_size: u32, output: &mut Vec<u8>| {
+        bit_buffer = (bit_buffer << code_size) | code as u64;
+        num_bits += code_size;
+        while num_bits >= 8 {
+            num_bits -= 8;
+            output.push((bit_buffer >> num_bits) as u8);
+        }
+        bit_buffer &= (1u64 << num_bits) - 1;
+    };
+
+    let mut table: HashMap<(u32, u8), u32> = HashMap::new();
+    let mut code_size = 9u32;
+    let mut next_code = FIRST_CODE;
+    write_code(CLEAR_CODE, code_size, &mut output);
+    if !data.is_empty() {
+        let mut prefix = data[0] as u32;
+        // Each code that is written, including the last, adds an entry to the decoder's table, and
+        // the code width increases once the next entry would not fit.
+        for i in 1..=data.len() {
+            if i < data.len() {
+                if let Some(code) = table.get(&(prefix, data[i])) {
+                    prefix = *code;
+                    continue;
+                }
+            }
+            write_code(prefix, code_size, &mut output);
+            if i < data.len() {
+                table.insert((prefix, data[i]), next_code);
+                prefix = data[i] as u32;
+            }
+            next_code += 1;
+            if next_code == TABLE_FULL {
+                write_code(CLEAR_CODE, code_size, &mut output);
+                table.clear();
+                next_code = FIRST_CODE;
+                code_size = 9;
+            } else if next_code > (1 << code_size) - 1 {
+                code_size += 1;
+            }
+        }
+    }
+    write_code(EOI_CODE, code_size, &mut output);
+    if num_bits > 0 {
+        output.push((bit_buffer << (8 - num_bits)) as u8);
+    }
+    output
+}
@@ -145,6 +145,25 @@ impl GeoKeys {
         epsg_code
     }
 
+    /// Returns the value of a GeoKey that is stored directly in the key directory, i.e. a
+    /// short (u16) value, such as the GTModelTypeGeoKey (1024) or ProjLinearUnitsGeoKey (3076).
+    pub fn get_short_value(&self, key_id: u16) -> Option<u16> {
+        if self.geo_key_directory.len() < 4 {
+            return None;
+        }
+        let number_of_keys = self.geo_key_directory[3] as usize;
+        for i in 0..number_of_keys {
+            let offset = 4 * (i + 1);
+            if offset + 3 >= self.geo_key_directory.len() {
+                break;
+            }
+            if self.geo_key_directory[offset] == key_id && self.geo_key_directory[offset + 1] == 0 {
+                return Some(self.geo_key_directory[offset + 3]);
+            }
+        }
+        None
+    }
+
     pub fn interpret_geokeys(&self) -> String {
         if self.geo_key_directory.len() == 0 {
             return "GeoKeys have not been set.".to_string();
@@ -1,4 +1,6 @@
 #![allow(unused_assignments, dead_code)]
+pub mod cog;
+pub mod compression;
 pub mod geokeys;
 pub mod tiff_consts;
 pub mod ifd;
@@ -17,9 +19,17 @@ use std::default::Default;
 use std::f64;
 // use std::fs;
 use std::fs::File;
-use std::io::{BufReader, BufWriter, Cursor, Error, ErrorKind, Read};
+use std::io::{BufReader, BufWriter, Cursor, Error, ErrorKind, Read, Write};
 use ifd::{Entry, Ifd};
+pub use cog::{is_cog_output, set_cog_output, write_cog, COG_TILE_SIZE};
+use compression::{compress_block, output_compression, undo_predictor};
+pub use compression::{compression_output, set_compression_output, GeoTiffCompression};
 use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+static BIG_TIFF_OUTPUT: AtomicBool = AtomicBool::new(false);
 
 pub fn print_tags<'a>(file_name: &'a String) -> Result<(), Error> {
     let f = File::open(file_name.clone())?;
@@ -214,7 +224,8 @@ pub fn print_tags<'a>(file_name: &'a String) -> Result<(), Error> {
 pub fn read_geotiff<'a>(
     file_name: &'a String,
     configs: &'a mut RasterConfigs,
-    data: &'a mut Vec<f64>,
+    data: &'a mut RasterData,
+    native: bool,
 ) -> Result<(), Error> {
     let f = File::open(file_name.clone())?;
 
@@ -249,7 +260,7 @@ pub fn read_geotiff<'a>(
         return Err(Error::new(ErrorKind::InvalidData, "The BigTIFF raster format cannot be read on a 32-bit system."))
     }
 
-    let mut ifd_offset = if !is_big_tiff {
+    let ifd_offset = if !is_big_tiff {
         th.read_u32()? as usize
     } else {
         // Bytesize of offsets
@@ -271,7 +282,10 @@ pub fn read_geotiff<'a>(
     let mut ifd_map = HashMap::new();
     let mut geokeys: GeoKeys = Default::default();
     let mut cur_pos: usize;
-    while ifd_offset > 0 {
+    // Only the first IFD, i.e. the full-resolution image, is read. Any subsequent IFDs hold
+    // overviews or masks, e.g. those of Cloud Optimized GeoTIFFs, whose tags would otherwise
+    // replace those of the image.
+    if ifd_offset > 0 {
         th.seek(ifd_offset);
         let num_directories = if !is_big_tiff {
             th.read_u16()? as u64
@@ -360,11 +374,6 @@ pub fn read_geotiff<'a>(
             
             ifd_map.insert(tag_id, ifd.clone());
         }
-        if !is_big_tiff {
-            ifd_offset = th.read_u32()? as usize;
-        } else {
-            ifd_offset = th.read_u64()? as usize;
-        }
     }
 
     configs.columns = match ifd_map.get(&256) {
@@ -443,10 +452,16 @@ pub fn read_geotiff<'a>(
         }
     };
 
-    // let num_samples = match ifd_map.get(&277) {
-    //     Some(ifd) => ifd.interpret_as_u16()[0],
-    //     _ => 0,
-    // };
+    let samples_per_pixel = match ifd_map.get(&277) {
+        Some(ifd) => ifd.interpret_as_u16()[0] as usize,
+        _ => bits_per_sample.len(),
+    };
+
+    // PlanarConfiguration; 1 (samples interleaved by pixel) or 2 (each sample stored in its own plane)
+    let planar_config = match ifd_map.get(&284) {
+        Some(ifd) => ifd.interpret_as_u16()[0],
+        _ => 1u16,
+    };
 
     match ifd_map.get(&280) {
         Some(ifd) => {
@@ -467,10 +482,23 @@ pub fn read_geotiff<'a>(
         _ => 0,
     };
 
+    // SampleFormat defaults to unsigned integer data when it is absent
     let sample_format = match ifd_map.get(&339) {
         Some(ifd) => ifd.interpret_as_u16(),
-        _ => [0].to_vec(),
+        _ => [1].to_vec(),
+    };
+
+    // Predictor; 1 (none), 2 (horizontal differencing), or 3 (floating point)
+    let predictor = match ifd_map.get(&317) {
+        Some(ifd) => ifd.interpret_as_u16()[0],
+        _ => 1u16,
     };
+    if predictor > 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Unsupported TIFF predictor: {}", predictor),
+        ));
+    }
 
     configs.nodata = match ifd_map.get(&TAG_GDAL_NODATA) {
         Some(ifd) => 
@@ -714,7 +742,28 @@ pub fn read_geotiff<'a>(
     // let mode: ImageMode;
     let mode: u16;
     let mut palette = vec![];
-    if photomet_str == "RGB" {
+    // Images with several samples per pixel are read as multi-band rasters, with one band per
+    // sample, unless they are (pixel-interleaved) RGB images, which are read as packed colours.
+    let bands = if samples_per_pixel > 1 && (planar_config == 2 || photomet_str != "RGB") {
+        samples_per_pixel
+    } else {
+        1
+    };
+    if bands > u8::MAX as usize {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The raster contains too many bands.",
+        ));
+    }
+    configs.bands = bands as u8;
+    if bands > 1 {
+        configs.photometric_interp = PhotometricInterpretation::Continuous;
+        mode = if photomet_str == "WhiteIsZero" {
+            IM_GRAYINVERT
+        } else {
+            IM_GRAY
+        };
+    } else if photomet_str == "RGB" {
         configs.photometric_interp = PhotometricInterpretation::RGB;
         if bits_per_sample[0] == 16 {
             if bits_per_sample[1] != 16 || bits_per_sample[2] != 16 {
@@ -1010,410 +1059,209 @@ pub fn read_geotiff<'a>(
     ////////////////////
     // Read the data! //
     ////////////////////
-    if data.len() > 0 {
-        data.clear();
+    // Values are stored either as f64 or, if native storage is requested, in the smallest type
+    // that holds them, i.e. their sample type for grey-scale images and packed 32-bit colours for
+    // 8-bit colour images.
+    let storage = if !native {
+        DataType::F64
+    } else {
+        match mode {
+            IM_GRAYINVERT | IM_GRAY => {
+                let sample_type = match (sample_format[0], bits_per_sample[0]) {
+                    (1, 8) => DataType::U8,
+                    (1, 16) => DataType::U16,
+                    (1, 32) => DataType::U32,
+                    (2, 8) => DataType::I8,
+                    (2, 16) => DataType::I16,
+                    (2, 32) => DataType::I32,
+                    (3, 32) => DataType::F32,
+                    _ => DataType::F64,
+                };
+                RasterData::storage_type_for(sample_type, &[configs.nodata])
+            }
+            _ if bits_per_sample[0] == 8 => {
+                RasterData::storage_type_for(DataType::U32, &[configs.nodata])
+            }
+            _ => DataType::F64,
+        }
+    };
+    *data = RasterData::new(storage, configs.rows * configs.columns * bands, 0f64);
+
+    // The strips or tiles are read and decompressed concurrently, each thread using its own file
+    // handle, and then assembled into the data as they are decoded. The number of decoded blocks
+    // that are waiting to be assembled is bounded, to limit memory use. The bands of multi-band
+    // images are stored band-sequentially in the data. Those of planar images are stored in
+    // separate blocks, each band after the other, while the blocks of pixel-interleaved images are
+    // split into one block per band as they are decoded, such that both are assembled alike.
+    let band_blocks = blocks_across * blocks_down;
+    let interleaved_bands = if planar_config == 2 { 1 } else { bands };
+    let num_blocks = if planar_config == 2 { band_blocks * bands } else { band_blocks };
+    if block_offsets.len() < num_blocks || block_counts.len() < num_blocks {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The raster contains fewer strips or tiles than expected.",
+        ));
     }
-    data.reserve_exact(configs.rows * configs.columns);
-    unsafe { 
-        // The memory will be initialized when we read 
-        // the pixel values.
-        data.set_len(configs.rows * configs.columns); 
+    let num_procs = num_cpus::get().clamp(1, num_blocks.max(1));
+    let block_offsets = Arc::new(block_offsets);
+    let block_counts = Arc::new(block_counts);
+    let (tx, rx) = mpsc::sync_channel(num_procs * 4);
+    for tid in 0..num_procs {
+        let file_name = file_name.clone();
+        let block_offsets = block_offsets.clone();
+        let block_counts = block_counts.clone();
+        let tx = tx.clone();
+        let endian = configs.endian;
+        let samples_per_pixel = if planar_config == 2 { 1 } else { samples_per_pixel };
+        let bytes_per_sample = (bits_per_sample[0] / 8) as usize;
+        thread::spawn(move || {
+            let mut reader = match File::open(&file_name) {
+                Ok(f) => ByteOrderReader::<BufReader<File>>::new(BufReader::new(f), endian),
+                Err(e) => {
+                    let _ = tx.send((tid, Err(e)));
+                    return;
+                }
+            };
+            for k in (0..num_blocks).filter(|k| k % num_procs == tid) {
+                let offset = block_offsets[k] as usize;
+                let result = decode_block(&mut reader, offset, block_counts[k] as usize, compression)
+                    .map(|mut buf| {
+                        // Prediction is applied to each row of a strip or tile, i.e. over the
+                        // (padded) block width.
+                        if predictor > 1 {
+                            undo_predictor(
+                                &mut buf,
+                                predictor,
+                                block_width * samples_per_pixel,
+                                samples_per_pixel,
+                                bytes_per_sample,
+                                endian,
+                            );
+                        }
+                        buf
+                    });
+                let failed = result.is_err();
+                let sent = match result {
+                    Ok(buf) if interleaved_bands > 1 => {
+                        deinterleave_bands(&buf, interleaved_bands, bytes_per_sample)
+                            .into_iter()
+                            .enumerate()
+                            .all(|(band, band_buf)| tx.send((band * num_blocks + k, Ok(band_buf))).is_ok())
+                    }
+                    _ => tx.send((k, result)).is_ok(),
+                };
+                if !sent || failed {
+                    break;
+                }
+            }
+        });
     }
+    drop(tx);
 
-    for i in 0..blocks_across {
+    for _ in 0..band_blocks * bands {
+        let (k, result) = match rx.recv() {
+            Ok(block) => block,
+            Err(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The raster was not read correctly",
+                ))
+            }
+        };
+        let buf = result?;
+        let band_offset = (k / band_blocks) * width * height;
+        let (i, j) = ((k % band_blocks) % blocks_across, (k % band_blocks) / blocks_across);
         let mut blk_w = block_width;
         if !block_padding && i == blocks_across - 1 && width % block_width != 0 {
             blk_w = width % block_width;
         }
-        for j in 0..blocks_down {
-            let mut blk_h = block_height;
-            if !block_padding && j == blocks_down - 1 && height % block_height != 0 {
-                blk_h = height % block_height;
-            }
-            let offset = block_offsets[j * blocks_across + i] as usize;
-            let n = block_counts[j * blocks_across + i] as usize;
-            let mut buf: Vec<u8> = vec![];
-            if n != 0 {
-                // it's not a sparse tile
-                match compression {
-                    COMPRESS_NONE => {
-                        // no compression
-                        // buf = vec![0u8; n];
-                        buf.reserve_exact(n);
-                        unsafe { buf.set_len(n); }
-                        th.seek(offset);
-                        th.read_exact(&mut buf)?;
-                    }
-                    COMPRESS_PACKBITS => {
-                        // buf = packbits_decoder(th.buffer[offset..(offset + n)].to_vec());
-                        let mut b = vec![0u8; n];
-                        th.seek(offset);
-                        th.read_exact(&mut b)?;
-                        buf = packbits_decoder(b);
-                    }
-                    COMPRESS_LZW => {
-                        let mut dec = lzw::DecoderEarlyChange::new(lzw::MsbReader::new(), 8u8);
-                        th.seek(offset);
-                        let mut b = vec![0u8; n];
-                        th.read_exact(&mut b)?;
-                        let mut compressed = &b[0..];
-                        // let mut compressed = &th.buffer[offset..(offset + n)];
-                        while compressed.len() > 0 {
-                            let (start, bytes) = dec.decode_bytes(&compressed).unwrap();
-                            compressed = &compressed[start..];
-                            buf.extend(bytes.iter().map(|&i| i));
-                        }
-                    }
-                    COMPRESS_DEFLATE => {
-                        // let mut dec = GzDecoder::new(th.buffer[offset..(offset + n)].to_vec());
-                        // let compressed = &th.buffer[offset..(offset + n)];
-                        // let mut decoder = Decoder::new(&compressed[..]).unwrap();
-                        // decoder.read_to_end(&mut buf).unwrap();
-                        th.seek(offset);
-                        let mut compressed = vec![0u8; n];
-                        th.read_exact(&mut compressed)?;
-                        let mut decoder = Decoder::new(&compressed[..])?;
-                        decoder.read_to_end(&mut buf).unwrap();
-                    }
-                    _ => {
-                        return Err(Error::new(
-                            ErrorKind::InvalidData,
-                            "The WhiteboxTools GeoTIFF decoder currently only supports PACKBITS, LZW, and DEFLATE compression.",
-                        ))
-                    }
-                }
-            }
+        let mut blk_h = block_height;
+        if !block_padding && j == blocks_down - 1 && height % block_height != 0 {
+            blk_h = height % block_height;
+        }
+        let n = block_counts[k % num_blocks] as usize;
 
-            let mut bor = ByteOrderReader::<Cursor<Vec<u8>>>::new(Cursor::new(buf), configs.endian);
+        let mut bor = ByteOrderReader::<Cursor<Vec<u8>>>::new(Cursor::new(buf), configs.endian);
 
-            let xmin = i * block_width;
-            let ymin = j * block_height;
-            let mut xmax = xmin + blk_w;
-            let mut ymax = ymin + blk_h;
+        let xmin = i * block_width;
+        let ymin = j * block_height;
+        let mut xmax = xmin + blk_w;
+        let mut ymax = ymin + blk_h;
 
-            xmax = min(xmax, width);
-            ymax = min(ymax, height);
+        xmax = min(xmax, width);
+        ymax = min(ymax, height);
 
-            let skip_bytes = if xmin + blk_w > width {
-                xmin + blk_w - width
-            } else {
-                0
-            };
+        let skip_bytes = if xmin + blk_w > width {
+            xmin + blk_w - width
+        } else {
+            0
+        };
 
-            let mut off = 0;
-            let mut i: usize;
-            let (mut red, mut green, mut blue): (u32, u32, u32);
-            if n != 0 {
-                match mode {
-                    IM_GRAYINVERT | IM_GRAY => {
-                        match sample_format[0] {
-                            1 => {
-                                // unsigned integer
-                                match bits_per_sample[0] {
-                                    8 => {
-                                        for y in ymin..ymax {
-                                            for x in xmin..xmax {
-                                                if off <= bor.len() {
-                                                    i = y * width + x;
-                                                    data[i] = bor.read_u8()? as f64;
-                                                    off += 1;
-                                                }
-                                            }
-                                            if skip_bytes > 0 {
-                                                bor.inc_pos(skip_bytes);
-                                            }
-                                        }
-                                    }
-                                    16 => {
-                                        for y in ymin..ymax {
-                                            for x in xmin..xmax {
-                                                if off <= bor.len() {
-                                                    i = y * width + x;
-                                                    data[i] = bor.read_u16()? as f64;
-                                                    off += 2;
-                                                }
-                                            }
-                                            if skip_bytes > 0 {
-                                                bor.inc_pos(skip_bytes * 2);
-                                            }
-                                        }
-                                    }
-                                    32 => {
-                                        for y in ymin..ymax {
-                                            for x in xmin..xmax {
-                                                if off <= bor.len() {
-                                                    i = y * width + x;
-                                                    data[i] = bor.read_u32()? as f64;
-                                                    off += 4;
-                                                }
-                                            }
-                                            if skip_bytes > 0 {
-                                                bor.inc_pos(skip_bytes * 4);
+        let mut off = 0;
+        let mut i: usize;
+        let (mut red, mut green, mut blue): (u32, u32, u32);
+        if n != 0 {
+            match mode {
+                IM_GRAYINVERT | IM_GRAY => {
+                    match sample_format[0] {
+                        1 => {
+                            // unsigned integer
+                            match bits_per_sample[0] {
+                                8 => {
+                                    for y in ymin..ymax {
+                                        for x in xmin..xmax {
+                                            if off <= bor.len() {
+                                                i = band_offset + y * width + x;
+                                                data.set_value(i, bor.read_u8()? as f64);
+                                                off += 1;
                                             }
                                         }
-                                    }
-                                    64 => {
-                                        for y in ymin..ymax {
-                                            for x in xmin..xmax {
-                                                if off <= bor.len() {
-                                                    i = y * width + x;
-                                                    data[i] = bor.read_u64()? as f64;
-                                                    off += 8;
-                                                }
-                                            }
-                                            if skip_bytes > 0 {
-                                                bor.inc_pos(skip_bytes * 8);
-                                            }
+                                        if skip_bytes > 0 {
+                                            bor.inc_pos(skip_bytes);
                                         }
                                     }
-                                    _ => {
-                                        return Err(Error::new(
-                                            ErrorKind::InvalidData,
-                                            "The raster was not read correctly",
-                                        ))
-                                    }
                                 }
-                            }
-                            2 => {
-                                // signed integer
-                                match bits_per_sample[0] {
-                                    8 => {
-                                        for y in ymin..ymax {
-                                            for x in xmin..xmax {
-                                                if off <= bor.len() {
-                                                    i = y * width + x;
-                                                    data[i] = bor.read_i8()? as f64;
-                                                    off += 1;
-                                                }
-                                            }
-                                            if skip_bytes > 0 {
-                                                bor.inc_pos(skip_bytes);
+                                16 => {
+                                    for y in ymin..ymax {
+                                        for x in xmin..xmax {
+                                            if off <= bor.len() {
+                                                i = band_offset + y * width + x;
+                                                data.set_value(i, bor.read_u16()? as f64);
+                                                off += 2;
                                             }
                                         }
-                                    }
-                                    16 => {
-                                        for y in ymin..ymax {
-                                            for x in xmin..xmax {
-                                                if off <= bor.len() {
-                                                    i = y * width + x;
-                                                    data[i] = bor.read_i16()? as f64;
-                                                    off += 2;
-                                                }
-                                            }
-                                            if skip_bytes > 0 {
-                                                bor.inc_pos(skip_bytes * 2);
-                                            }
+                                        if skip_bytes > 0 {
+                                            bor.inc_pos(skip_bytes * 2);
                                         }
                                     }
-                                    32 => {
-                                        for y in ymin..ymax {
-                                            for x in xmin..xmax {
-                                                if off <= bor.len() {
-                                                    i = y * width + x;
-                                                    data[i] = bor.read_i32()? as f64;
-                                                    off += 4;
-                                                }
-                                            }
-                                            if skip_bytes > 0 {
-                                                bor.inc_pos(skip_bytes * 4);
+                                }
+                                32 => {
+                                    for y in ymin..ymax {
+                                        for x in xmin..xmax {
+                                            if off <= bor.len() {
+                                                i = band_offset + y * width + x;
+                                                data.set_value(i, bor.read_u32()? as f64);
+                                                off += 4;
                                             }
                                         }
-                                    }
-                                    64 => {
-                                        for y in ymin..ymax {
-                                            for x in xmin..xmax {
-                                                if off <= bor.len() {
-                                                    i = y * width + x;
-                                                    data[i] = bor.read_i64()? as f64;
-                                                    off += 8;
-                                                }
-                                            }
-                                            if skip_bytes > 0 {
-                                                bor.inc_pos(skip_bytes * 8);
-                                            }
+                                        if skip_bytes > 0 {
+                                            bor.inc_pos(skip_bytes * 4);
                                         }
                                     }
-                                    _ => {
-                                        return Err(Error::new(
-                                            ErrorKind::InvalidData,
-                                            "The raster was not read correctly",
-                                        ))
-                                    }
                                 }
-                            }
-                            3 => {
-                                // floating point
-                                match bits_per_sample[0] {
-                                    32 => {
-                                        for y in ymin..ymax {
-                                            for x in xmin..xmax {
-                                                i = y * width + x;
-                                                data[i] = bor.read_f32()? as f64;
-                                                off += 4;
-                                            }
-                                            if skip_bytes > 0 {
-                                                bor.inc_pos(skip_bytes * 4);
+                                64 => {
+                                    for y in ymin..ymax {
+                                        for x in xmin..xmax {
+                                            if off <= bor.len() {
+                                                i = band_offset + y * width + x;
+                                                data.set_value(i, bor.read_u64()? as f64);
+                                                off += 8;
                                             }
                                         }
-                                    }
-                                    64 => {
-                                        for y in ymin..ymax {
-                                            for x in xmin..xmax {
-                                                if off <= bor.len() {
-                                                    i = y * width + x;
-                                                    data[i] = bor.read_f64()?;
-                                                    off += 8;
-                                                }
-                                            }
-                                            if skip_bytes > 0 {
-                                                bor.inc_pos(skip_bytes * 8);
-                                            }
+                                        if skip_bytes > 0 {
+                                            bor.inc_pos(skip_bytes * 8);
                                         }
                                     }
-                                    _ => {
-                                        return Err(Error::new(
-                                            ErrorKind::InvalidData,
-                                            "The raster was not read correctly",
-                                        ))
-                                    }
-                                }
-                            }
-                            _ => {
-                                return Err(Error::new(
-                                    ErrorKind::InvalidData,
-                                    "The raster was not read correctly",
-                                ))
-                            }
-                        }
-                    }
-                    IM_PALETTED => {
-                        let mut value: usize;
-                        for y in ymin..ymax {
-                            for x in xmin..xmax {
-                                i = y * width + x;
-                                value = bor.read_u8()? as usize;
-                                data[i] = palette[value] as f64;
-                            }
-                        }
-                    }
-                    IM_RGB => {
-                        let mut value: u32;
-                        let mut a: u32;
-                        if bits_per_sample[0] == 8 {
-                            for y in ymin..ymax {
-                                for x in xmin..xmax {
-                                    red = bor.read_u8()? as u32; //uint32(g.buf[g.off]);
-                                    green = bor.read_u8()? as u32; //uint32(g.buf[g.off+1]);
-                                    blue = bor.read_u8()? as u32; //uint32(g.buf[g.off+2]);
-                                    a = 255u32;
-                                    value = (a << 24) | (blue << 16) | (green << 8) | red;
-                                    i = y * width + x;
-                                    data[i] = value as f64;
-                                }
-                            }
-                        } else if bits_per_sample[0] == 16 {
-                            // the spec doesn't talk about 16-bit RGB images so
-                            // I'm not sure why I bother with this. They specifically
-                            // say that RGB images are 8-bits per channel. Anyhow,
-                            // I rescale the 16-bits to an 8-bit channel for simplicity.
-                            let mut value: u32;
-                            let mut a: u32;
-                            for y in ymin..ymax {
-                                for x in xmin..xmax {
-                                    red = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
-                                    green = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
-                                    blue = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
-                                    a = 255u32;
-                                    value = (a << 24) | (blue << 16) | (green << 8) | red;
-                                    i = y * width + x;
-                                    data[i] = value as f64;
-                                }
-                            }
-                        } else {
-                            return Err(Error::new(
-                                ErrorKind::InvalidData,
-                                "The raster was not read correctly",
-                            ));
-                        }
-                    }
-                    IM_NRGBA | IM_RGBA => {
-                        let mut value: u32;
-                        let mut a: u32;
-                        if bits_per_sample[0] == 8 {
-                            for y in ymin..ymax {
-                                for x in xmin..xmax {
-                                    red = bor.read_u8()? as u32; //uint32(g.buf[g.off]);
-                                    green = bor.read_u8()? as u32; //uint32(g.buf[g.off+1]);
-                                    blue = bor.read_u8()? as u32; //uint32(g.buf[g.off+2]);
-                                    a = bor.read_u8()? as u32;
-                                    value = (a << 24) | (blue << 16) | (green << 8) | red;
-                                    i = y * width + x;
-                                    data[i] = value as f64;
-                                }
-                            }
-                        } else if bits_per_sample[0] == 16 {
-                            // the spec doesn't talk about 16-bit RGB images so
-                            // I'm not sure why I bother with this. They specifically
-                            // say that RGB images are 8-bits per channel. Anyhow,
-                            // I rescale the 16-bits to an 8-bit channel for simplicity.
-                            let mut value: u32;
-                            let mut a: u32;
-                            for y in ymin..ymax {
-                                for x in xmin..xmax {
-                                    red = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
-                                    green = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
-                                    blue = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
-                                    a = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
-                                    value = (a << 24) | (blue << 16) | (green << 8) | red;
-                                    i = y * width + x;
-                                    data[i] = value as f64;
-                                }
-                            }
-                        } else {
-                            return Err(Error::new(
-                                ErrorKind::InvalidData,
-                                "The raster was not read correctly",
-                            ));
-                        }
-                    }
-                    _ => {
-                        return Err(Error::new(
-                            ErrorKind::InvalidData,
-                            "The raster was not read correctly",
-                        ))
-                    }
-                }
-            } else {
-                // GDAL supports sparse tiles. That is, if the block count is zero,
-                // instead of reading the block, simply assume it is filled with either
-                // nodata, if the value is defined, or zeros otherwise.
-                for y in ymin..ymax {
-                    for x in xmin..xmax {
-                        i = y * width + x;
-                        data[i] = configs.nodata;
-                    }
-                }
-            }
-
-            match mode {
-                IM_GRAYINVERT | IM_GRAY => {
-                    //ImageMode::GrayInvert | ImageMode::Gray => {
-                    configs.photometric_interp = PhotometricInterpretation::Continuous;
-                    match sample_format[0] {
-                        1 => {
-                            // unsigned integer
-                            match bits_per_sample[0] {
-                                8 => {
-                                    configs.data_type = DataType::U8;
-                                }
-                                16 => {
-                                    configs.data_type = DataType::U16;
-                                }
-                                32 => {
-                                    configs.data_type = DataType::U32;
-                                }
-                                64 => {
-                                    configs.data_type = DataType::U64;
                                 }
                                 _ => {
                                     return Err(Error::new(
@@ -1427,16 +1275,60 @@ pub fn read_geotiff<'a>(
                             // signed integer
                             match bits_per_sample[0] {
                                 8 => {
-                                    configs.data_type = DataType::I8;
+                                    for y in ymin..ymax {
+                                        for x in xmin..xmax {
+                                            if off <= bor.len() {
+                                                i = band_offset + y * width + x;
+                                                data.set_value(i, bor.read_i8()? as f64);
+                                                off += 1;
+                                            }
+                                        }
+                                        if skip_bytes > 0 {
+                                            bor.inc_pos(skip_bytes);
+                                        }
+                                    }
                                 }
                                 16 => {
-                                    configs.data_type = DataType::I16;
+                                    for y in ymin..ymax {
+                                        for x in xmin..xmax {
+                                            if off <= bor.len() {
+                                                i = band_offset + y * width + x;
+                                                data.set_value(i, bor.read_i16()? as f64);
+                                                off += 2;
+                                            }
+                                        }
+                                        if skip_bytes > 0 {
+                                            bor.inc_pos(skip_bytes * 2);
+                                        }
+                                    }
                                 }
                                 32 => {
-                                    configs.data_type = DataType::I32;
+                                    for y in ymin..ymax {
+                                        for x in xmin..xmax {
+                                            if off <= bor.len() {
+                                                i = band_offset + y * width + x;
+                                                data.set_value(i, bor.read_i32()? as f64);
+                                                off += 4;
+                                            }
+                                        }
+                                        if skip_bytes > 0 {
+                                            bor.inc_pos(skip_bytes * 4);
+                                        }
+                                    }
                                 }
                                 64 => {
-                                    configs.data_type = DataType::I64;
+                                    for y in ymin..ymax {
+                                        for x in xmin..xmax {
+                                            if off <= bor.len() {
+                                                i = band_offset + y * width + x;
+                                                data.set_value(i, bor.read_i64()? as f64);
+                                                off += 8;
+                                            }
+                                        }
+                                        if skip_bytes > 0 {
+                                            bor.inc_pos(skip_bytes * 8);
+                                        }
+                                    }
                                 }
                                 _ => {
                                     return Err(Error::new(
@@ -1450,10 +1342,30 @@ pub fn read_geotiff<'a>(
                             // floating point
                             match bits_per_sample[0] {
                                 32 => {
-                                    configs.data_type = DataType::F32;
+                                    for y in ymin..ymax {
+                                        for x in xmin..xmax {
+                                            i = band_offset + y * width + x;
+                                            data.set_value(i, bor.read_f32()? as f64);
+                                            off += 4;
+                                        }
+                                        if skip_bytes > 0 {
+                                            bor.inc_pos(skip_bytes * 4);
+                                        }
+                                    }
                                 }
                                 64 => {
-                                    configs.data_type = DataType::F64;
+                                    for y in ymin..ymax {
+                                        for x in xmin..xmax {
+                                            if off <= bor.len() {
+                                                i = band_offset + y * width + x;
+                                                data.set_value(i, bor.read_f64()?);
+                                                off += 8;
+                                            }
+                                        }
+                                        if skip_bytes > 0 {
+                                            bor.inc_pos(skip_bytes * 8);
+                                        }
+                                    }
                                 }
                                 _ => {
                                     return Err(Error::new(
@@ -1472,16 +1384,57 @@ pub fn read_geotiff<'a>(
                     }
                 }
                 IM_PALETTED => {
-                    //ImageMode::Paletted => {
-                    configs.photometric_interp = PhotometricInterpretation::Categorical;
-                    configs.data_type = DataType::U8;
+                    let mut value: usize;
+                    for y in ymin..ymax {
+                        for x in xmin..xmax {
+                            i = band_offset + y * width + x;
+                            value = bor.read_u8()? as usize;
+                            data.set_value(i, palette[value] as f64);
+                        }
+                        if skip_bytes > 0 {
+                            bor.inc_pos(skip_bytes);
+                        }
+                    }
                 }
                 IM_RGB => {
-                    configs.photometric_interp = PhotometricInterpretation::RGB;
+                    let mut value: u32;
+                    let mut a: u32;
                     if bits_per_sample[0] == 8 {
-                        configs.data_type = DataType::U8;
+                        for y in ymin..ymax {
+                            for x in xmin..xmax {
+                                red = bor.read_u8()? as u32; //uint32(g.buf[g.off]);
+                                green = bor.read_u8()? as u32; //uint32(g.buf[g.off+1]);
+                                blue = bor.read_u8()? as u32; //uint32(g.buf[g.off+2]);
+                                a = 255u32;
+                                value = (a << 24) | (blue << 16) | (green << 8) | red;
+                                i = band_offset + y * width + x;
+                                data.set_value(i, value as f64);
+                            }
+                            if skip_bytes > 0 {
+                                bor.inc_pos(skip_bytes * 3);
+                            }
+                        }
                     } else if bits_per_sample[0] == 16 {
-                        configs.data_type = DataType::U16;
+                        // the spec doesn't talk about 16-bit RGB images so
+                        // I'm not sure why I bother with this. They specifically
+                        // say that RGB images are 8-bits per channel. Anyhow,
+                        // I rescale the 16-bits to an 8-bit channel for simplicity.
+                        let mut value: u32;
+                        let mut a: u32;
+                        for y in ymin..ymax {
+                            for x in xmin..xmax {
+                                red = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
+                                green = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
+                                blue = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
+                                a = 255u32;
+                                value = (a << 24) | (blue << 16) | (green << 8) | red;
+                                i = band_offset + y * width + x;
+                                data.set_value(i, value as f64);
+                            }
+                            if skip_bytes > 0 {
+                                bor.inc_pos(skip_bytes * 6);
+                            }
+                        }
                     } else {
                         return Err(Error::new(
                             ErrorKind::InvalidData,
@@ -1490,22 +1443,44 @@ pub fn read_geotiff<'a>(
                     }
                 }
                 IM_NRGBA | IM_RGBA => {
-                    // if bits_per_sample[0] == 8 {
-                    //     configs.data_type = DataType::U32;
-                    // } else if bits_per_sample[0] == 16 {
-                    //     configs.data_type = DataType::U64;
-                    // } else {
-                    //     return Err(Error::new(
-                    //         ErrorKind::InvalidData,
-                    //         "The raster was not read correctly",
-                    //     ));
-                    // }
-                    if bits_per_sample[0] == 8 && bits_per_sample.len() == 4 {
-                        configs.data_type = DataType::RGBA32;
-                    } else if bits_per_sample[0] == 8 && bits_per_sample.len() == 3 {
-                        configs.data_type = DataType::RGB24;
+                    let mut value: u32;
+                    let mut a: u32;
+                    if bits_per_sample[0] == 8 {
+                        for y in ymin..ymax {
+                            for x in xmin..xmax {
+                                red = bor.read_u8()? as u32; //uint32(g.buf[g.off]);
+                                green = bor.read_u8()? as u32; //uint32(g.buf[g.off+1]);
+                                blue = bor.read_u8()? as u32; //uint32(g.buf[g.off+2]);
+                                a = bor.read_u8()? as u32;
+                                value = (a << 24) | (blue << 16) | (green << 8) | red;
+                                i = band_offset + y * width + x;
+                                data.set_value(i, value as f64);
+                            }
+                            if skip_bytes > 0 {
+                                bor.inc_pos(skip_bytes * 4);
+                            }
+                        }
                     } else if bits_per_sample[0] == 16 {
-                        configs.data_type = DataType::U16;
+                        // the spec doesn't talk about 16-bit RGB images so
+                        // I'm not sure why I bother with this. They specifically
+                        // say that RGB images are 8-bits per channel. Anyhow,
+                        // I rescale the 16-bits to an 8-bit channel for simplicity.
+                        let mut value: u32;
+                        let mut a: u32;
+                        for y in ymin..ymax {
+                            for x in xmin..xmax {
+                                red = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
+                                green = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
+                                blue = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
+                                a = (bor.read_u16()? as f64 / 65535f64 * 255f64) as u32;
+                                value = (a << 24) | (blue << 16) | (green << 8) | red;
+                                i = band_offset + y * width + x;
+                                data.set_value(i, value as f64);
+                            }
+                            if skip_bytes > 0 {
+                                bor.inc_pos(skip_bytes * 8);
+                            }
+                        }
                     } else {
                         return Err(Error::new(
                             ErrorKind::InvalidData,
@@ -1520,54 +1495,238 @@ pub fn read_geotiff<'a>(
                     ))
                 }
             }
-        }
-    }
-
-    // Check to see if a predictor is used with LZW
-    match ifd_map.get(&317) {
-        Some(ifd) => {
-            if ifd.interpret_as_u16()[0] == 2 {
-                // Horizontal predictor
-                // transform the data
-                let mut idx: usize;
-                for row in 0..configs.rows {
-                    for col in 1..configs.columns {
-                        //(0..configs.columns-1).rev() {
-                        idx = row * configs.columns + col;
-                        data[idx] += data[idx - 1];
-                    }
+        } else {
+            // GDAL supports sparse tiles. That is, if the block count is zero,
+            // instead of reading the block, simply assume it is filled with either
+            // nodata, if the value is defined, or zeros otherwise.
+            for y in ymin..ymax {
+                for x in xmin..xmax {
+                    i = band_offset + y * width + x;
+                    data.set_value(i, configs.nodata);
                 }
             }
         }
-        _ => {} // do nothing,
-    }
-
-    // match geokeys_map.get(&1024) {
-    //     Some(ifd) => geokeys.add_key_directory(&ifd.data),
-    //     _ => return Err(Error::new(ErrorKind::InvalidData, "The TIFF file does not contain geokeys")),
-    // };
-
-    let mut map_sorter = vec![];
-    for (key, _) in ifd_map.iter() {
-        map_sorter.push(key);
-    }
-    map_sorter.sort();
-
-    map_sorter.clear();
-    for (key, _) in geokeys_map.iter() {
-        map_sorter.push(key);
-    }
-
-    Ok(())
-}
 
-pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
-    // get the ByteOrderWriter
-    let f = File::create(r.file_name.clone())?;
-    let writer = BufWriter::new(f);
-    let mut bow = ByteOrderWriter::<BufWriter<File>>::new(writer, r.configs.endian);
-    
-    // get the bytes per pixel
+        match mode {
+            IM_GRAYINVERT | IM_GRAY => {
+                //ImageMode::GrayInvert | ImageMode::Gray => {
+                configs.photometric_interp = PhotometricInterpretation::Continuous;
+                match sample_format[0] {
+                    1 => {
+                        // unsigned integer
+                        match bits_per_sample[0] {
+                            8 => {
+                                configs.data_type = DataType::U8;
+                            }
+                            16 => {
+                                configs.data_type = DataType::U16;
+                            }
+                            32 => {
+                                configs.data_type = DataType::U32;
+                            }
+                            64 => {
+                                configs.data_type = DataType::U64;
+                            }
+                            _ => {
+                                return Err(Error::new(
+                                    ErrorKind::InvalidData,
+                                    "The raster was not read correctly",
+                                ))
+                            }
+                        }
+                    }
+                    2 => {
+                        // signed integer
+                        match bits_per_sample[0] {
+                            8 => {
+                                configs.data_type = DataType::I8;
+                            }
+                            16 => {
+                                configs.data_type = DataType::I16;
+                            }
+                            32 => {
+                                configs.data_type = DataType::I32;
+                            }
+                            64 => {
+                                configs.data_type = DataType::I64;
+                            }
+                            _ => {
+                                return Err(Error::new(
+                                    ErrorKind::InvalidData,
+                                    "The raster was not read correctly",
+                                ))
+                            }
+                        }
+                    }
+                    3 => {
+                        // floating point
+                        match bits_per_sample[0] {
+                            32 => {
+                                configs.data_type = DataType::F32;
+                            }
+                            64 => {
+                                configs.data_type = DataType::F64;
+                            }
+                            _ => {
+                                return Err(Error::new(
+                                    ErrorKind::InvalidData,
+                                    "The raster was not read correctly",
+                                ))
+                            }
+                        }
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            "The raster was not read correctly",
+                        ))
+                    }
+                }
+            }
+            IM_PALETTED => {
+                //ImageMode::Paletted => {
+                configs.photometric_interp = PhotometricInterpretation::Categorical;
+                configs.data_type = DataType::U8;
+            }
+            IM_RGB => {
+                configs.photometric_interp = PhotometricInterpretation::RGB;
+                if bits_per_sample[0] == 8 {
+                    configs.data_type = DataType::U8;
+                } else if bits_per_sample[0] == 16 {
+                    configs.data_type = DataType::U16;
+                } else {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "The raster was not read correctly",
+                    ));
+                }
+            }
+            IM_NRGBA | IM_RGBA => {
+                // if bits_per_sample[0] == 8 {
+                //     configs.data_type = DataType::U32;
+                // } else if bits_per_sample[0] == 16 {
+                //     configs.data_type = DataType::U64;
+                // } else {
+                //     return Err(Error::new(
+                //         ErrorKind::InvalidData,
+                //         "The raster was not read correctly",
+                //     ));
+                // }
+                if bits_per_sample[0] == 8 && bits_per_sample.len() == 4 {
+                    configs.data_type = DataType::RGBA32;
+                } else if bits_per_sample[0] == 8 && bits_per_sample.len() == 3 {
+                    configs.data_type = DataType::RGB24;
+                } else if bits_per_sample[0] == 16 {
+                    configs.data_type = DataType::U16;
+                } else {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "The raster was not read correctly",
+                    ));
+                }
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The raster was not read correctly",
+                ))
+            }
+        }
+    }
+
+    // match geokeys_map.get(&1024) {
+    //     Some(ifd) => geokeys.add_key_directory(&ifd.data),
+    //     _ => return Err(Error::new(ErrorKind::InvalidData, "The TIFF file does not contain geokeys")),
+    // };
+
+    let mut map_sorter = vec![];
+    for (key, _) in ifd_map.iter() {
+        map_sorter.push(key);
+    }
+    map_sorter.sort();
+
+    map_sorter.clear();
+    for (key, _) in geokeys_map.iter() {
+        map_sorter.push(key);
+    }
+
+    Ok(())
+}
+
+/// Splits a decoded strip or tile, whose pixels each contain `bands` interleaved samples of
+/// `bytes_per_sample` bytes, into one block for each band.
+fn deinterleave_bands(buf: &[u8], bands: usize, bytes_per_sample: usize) -> Vec<Vec<u8>> {
+    let mut band_bufs = vec![Vec::with_capacity(buf.len() / bands); bands];
+    for (s, sample) in buf.chunks_exact(bytes_per_sample.max(1)).enumerate() {
+        band_bufs[s % bands].extend_from_slice(sample);
+    }
+    band_bufs
+}
+
+/// Reads the `n` bytes of a strip or tile, starting at `offset`, and decompresses them. Sparse
+/// blocks, i.e. those with a byte count of zero, are empty.
+fn decode_block(
+    th: &mut ByteOrderReader<BufReader<File>>,
+    offset: usize,
+    n: usize,
+    compression: u16,
+) -> Result<Vec<u8>, Error> {
+    let mut buf: Vec<u8> = vec![];
+    if n == 0 {
+        return Ok(buf);
+    }
+    match compression {
+        COMPRESS_NONE => {
+            // no compression
+            buf = vec![0u8; n];
+            th.seek(offset);
+            th.read_exact(&mut buf)?;
+        }
+        COMPRESS_PACKBITS => {
+            let mut b = vec![0u8; n];
+            th.seek(offset);
+            th.read_exact(&mut b)?;
+            buf = packbits_decoder(b);
+        }
+        COMPRESS_LZW => {
+            let mut dec = lzw::DecoderEarlyChange::new(lzw::MsbReader::new(), 8u8);
+            th.seek(offset);
+            let mut b = vec![0u8; n];
+            th.read_exact(&mut b)?;
+            let mut compressed = &b[0..];
+            while !compressed.is_empty() {
+                let (start, bytes) = dec.decode_bytes(compressed)?;
+                if start == 0 && bytes.is_empty() {
+                    break;
+                }
+                compressed = &compressed[start..];
+                buf.extend_from_slice(bytes);
+            }
+        }
+        COMPRESS_DEFLATE => {
+            th.seek(offset);
+            let mut compressed = vec![0u8; n];
+            th.read_exact(&mut compressed)?;
+            let mut decoder = Decoder::new(&compressed[..])?;
+            decoder.read_to_end(&mut buf)?;
+        }
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The WhiteboxTools GeoTIFF decoder currently only supports PACKBITS, LZW, and DEFLATE compression.",
+            ))
+        }
+    }
+    Ok(buf)
+}
+
+pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
+    // get the ByteOrderWriter
+    let f = File::create(r.file_name.clone())?;
+    let writer = BufWriter::new(f);
+    let mut bow = ByteOrderWriter::<BufWriter<File>>::new(writer, r.configs.endian);
+    
+    // get the bytes per pixel
     let total_bytes_per_pixel = r.configs.data_type.get_data_size();
     if total_bytes_per_pixel == 0 {
         return Err(Error::new(
@@ -1579,20 +1738,84 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         ));
     }
 
-    // is it a BigTiff?
-    let is_big_tiff = if 8usize + (r.configs.rows * r.configs.columns) as usize * 
-        total_bytes_per_pixel >= 4_000_000_000 {
-        true
+    // Multi-band rasters are stored with one strip per row of each band (i.e. planar
+    // configuration), such that the image data are simply the band-sequential values.
+    let bands = r.configs.bands.max(1) as usize;
+    if bands > 1
+        && matches!(
+            r.configs.data_type,
+            DataType::RGB24 | DataType::RGBA32 | DataType::RGB48
+        )
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Multi-band rasters must have a single-sample data type.",
+        ));
+    }
+
+    // At the moment, categorical and paletted output is not supported.
+    if r.configs.photometric_interp == PhotometricInterpretation::Categorical
+        || r.configs.photometric_interp == PhotometricInterpretation::Paletted
+    {
+        r.configs.photometric_interp = PhotometricInterpretation::Continuous;
+    }
+
+    // The bands of multi-band rasters are written sample-by-sample, whatever their photometric interpretation.
+    let data_interp = if bands > 1 {
+        PhotometricInterpretation::Continuous
     } else {
-        false
+        r.configs.photometric_interp
     };
 
-    // get the offset to the first ifd
-    let mut ifd_start = if !is_big_tiff {
-        (8usize + (r.configs.rows * r.configs.columns) as usize * total_bytes_per_pixel) as u64 // plus the 8-byte header
+    // Compressed image data are instead stored in strips of several rows, of about 64 KB each,
+    // which are compressed in memory before the file is written. A strip never spans two bands.
+    let (compression, predictor) = output_compression(r);
+    let row_length_in_bytes = (r.configs.columns * total_bytes_per_pixel) as u64;
+    let mut rows_per_strip = 1usize;
+    let mut compressed_strips: Vec<Vec<u8>> = vec![];
+    if compression != GeoTiffCompression::None {
+        rows_per_strip = (65536 / row_length_in_bytes.max(1)) as usize;
+        rows_per_strip = rows_per_strip.clamp(1, r.configs.rows.max(1));
+        let strips_per_band = r.configs.rows.div_ceil(rows_per_strip);
+        for band in 0..bands {
+            for strip in 0..strips_per_band {
+                let first_row = band * r.configs.rows + strip * rows_per_strip;
+                let last_row =
+                    band * r.configs.rows + ((strip + 1) * rows_per_strip).min(r.configs.rows);
+                let mut strip_bow = ByteOrderWriter::<Vec<u8>>::new(
+                    Vec::with_capacity((last_row - first_row) * row_length_in_bytes as usize),
+                    r.configs.endian,
+                );
+                write_image_rows(r, &mut strip_bow, data_interp, first_row..last_row)?;
+                compressed_strips.push(compress_block(
+                    r,
+                    strip_bow.into_inner(),
+                    r.configs.columns,
+                    compression,
+                    predictor,
+                )?);
+            }
+        }
+    }
+    let num_strips = if compression != GeoTiffCompression::None {
+        compressed_strips.len()
     } else {
-        (16usize + (r.configs.rows * r.configs.columns) as usize * total_bytes_per_pixel) as u64 // plus the 8-byte header
+        r.configs.rows * bands
     };
+
+    // is it a BigTiff? Classic TIFFs use 4-byte offsets and so cannot exceed 4 GB, including the
+    // strip offsets and counts and the other tag data that follow the image data.
+    let data_bytes = if compression != GeoTiffCompression::None {
+        compressed_strips.iter().map(|s| s.len() as u64).sum()
+    } else {
+        num_strips as u64 * row_length_in_bytes
+    };
+    let is_big_tiff = is_big_tiff_output()
+        || data_bytes + num_strips as u64 * 8u64 + 1_000_000u64 >= u32::MAX as u64;
+    let header_length = if !is_big_tiff { 8u64 } else { 16u64 };
+
+    // get the offset to the first ifd, which follows the header and the image data
+    let mut ifd_start = header_length + data_bytes;
     let mut ifd_start_needs_extra_byte = false;
     if ifd_start % 2 == 1 {
         ifd_start += 1;
@@ -1627,108 +1850,288 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         bow.write_u64(ifd_start)?;
     }
 
-    // At the moment, categorical and paletted output is not supported.
-    if r.configs.photometric_interp == PhotometricInterpretation::Categorical
-        || r.configs.photometric_interp == PhotometricInterpretation::Paletted
-    {
-        r.configs.photometric_interp = PhotometricInterpretation::Continuous;
-    }
-
 
     //////////////////////////
     // Write the image data //
     //////////////////////////
-    match r.configs.photometric_interp {
+    if compression != GeoTiffCompression::None {
+        for strip in &compressed_strips {
+            bow.write_bytes(strip)?;
+        }
+    } else {
+        write_image_rows(r, &mut bow, data_interp, 0..num_strips)?;
+    }
+
+    // This is just because the IFD must start on a word (i.e. an even value). If the data are
+    // single bytes, then this may not be the case.
+    if ifd_start_needs_extra_byte {
+        bow.write_u8(0u8)?;
+    }
+
+
+    ////////////////////////////
+    // Create the IFD entries //
+    ////////////////////////////
+
+    /*
+        Required Fields for Bilevel Images
+        - ImageWidth
+        - ImageLength
+        - Compression
+        - PhotometricInterpretation
+        - StripOffsets
+        - RowsPerStrip
+        - StripByteCounts
+        - XResolution
+        - YResolution
+        - ResolutionUnit
+    */
+
+    /*
+        Required Fields for Grayscale Images
+        - ImageWidth
+        - ImageLength
+        - BitsPerSample
+        - Compression
+        - PhotometricInterpretation
+        - StripOffsets
+        - RowsPerStrip
+        - StripByteCounts
+        - XResolution
+        - YResolution
+        - ResolutionUnit
+    */
+
+    /*
+        Required Fields for Palette Colour Images
+        - ImageWidth
+        - ImageLength
+        - BitsPerSample
+        - Compression
+        - PhotometricInterpretation
+        - StripOffsets
+        - RowsPerStrip
+        - StripByteCounts
+        - XResolution
+        - YResolution
+        - ResolutionUnit
+        - ColorMap
+    */
+
+    /*
+        Required Fields for RGB Images
+        - ImageWidth
+        - ImageLength
+        - BitsPerSample
+        - Compression
+        - PhotometricInterpretation
+        - StripOffsets
+        - SamplesPerPixel
+        - RowsPerStrip
+        - StripByteCounts
+        - XResolution
+        - YResolution
+        - ResolutionUnit
+    */
+
+    let mut ifd_entries: Vec<Entry> = vec![];
+    // let mut larger_values_data: Vec<u8> = vec![];
+    let mut larger_values_data = ByteOrderWriter::<Vec<u8>>::new(vec![], r.configs.endian);
+
+    /*
+    Classic TIFF IFD entries
+
+    Bytes 0-1 The Tag that identifies the field.
+    Bytes 2-3 The field Type.
+    Bytes 4-7 The number of values, Count of the indicated Type.
+    Bytes 8-11 The Value Offset, the file offset (in bytes) of the Value for the field.
+    The Value is expected to begin on a word boundary; the corresponding
+    Value Offset will thus be an even number. This file offset may
+    point anywhere in the file, even after the image data.
+
+    To save time and space the Value Offset contains the Value instead of pointing to
+    the Value if and only if the Value fits into 4 bytes. If the Value is shorter than 4
+    bytes, it is left-justified within the 4-byte Value Offset, i.e., stored in the lowernumbered
+    bytes. Whether the Value fits within 4 bytes is determined by the Type
+    and Count of the field.
+    */
+
+    push_image_structure_entries(
+        r,
+        r.configs.columns,
+        r.configs.rows,
+        compression,
+        predictor,
+        &mut ifd_entries,
+        &mut larger_values_data,
+    )?;
+
+    // StripOffsets (273) and StripByteCounts (279) tags
+    let strip_byte_counts: Vec<u64> = if compression != GeoTiffCompression::None {
+        compressed_strips.iter().map(|s| s.len() as u64).collect()
+    } else {
+        vec![row_length_in_bytes; num_strips]
+    };
+    let mut strip_offsets = Vec::with_capacity(num_strips);
+    let mut offset = header_length;
+    for byte_count in &strip_byte_counts {
+        strip_offsets.push(offset);
+        offset += byte_count;
+    }
+    for (tag, values) in [
+        (TAG_STRIPOFFSETS, &strip_offsets),
+        (TAG_STRIPBYTECOUNTS, &strip_byte_counts),
+    ] {
+        let ifd_type = if !is_big_tiff { DT_LONG } else { DT_TIFF_LONG8 };
+        if values.len() == 1 {
+            // the value fits within the value offset
+            ifd_entries.push(Entry::new(tag, ifd_type, 1u64, values[0]));
+        } else {
+            ifd_entries.push(Entry::new(
+                tag,
+                ifd_type,
+                values.len() as u64,
+                larger_values_data.len() as u64,
+            ));
+            for value in values {
+                if !is_big_tiff {
+                    larger_values_data.write_u32(*value as u32)?;
+                } else {
+                    larger_values_data.write_u64(*value)?;
+                }
+            }
+        }
+    }
+
+    // RowsPerStrip tag (278)
+    if rows_per_strip <= u16::MAX as usize {
+        ifd_entries.push(Entry::new(TAG_ROWSPERSTRIP, DT_SHORT, 1u64, rows_per_strip as u64));
+    } else {
+        ifd_entries.push(Entry::new(TAG_ROWSPERSTRIP, DT_LONG, 1u64, rows_per_strip as u64));
+    }
+
+    push_metadata_entries(r, is_big_tiff, &mut ifd_entries, &mut larger_values_data)?;
+
+    ///////////////////
+    // Write the IFD //
+    ///////////////////
+
+    write_ifd(
+        &mut bow,
+        ifd_entries,
+        larger_values_data.get_inner(),
+        ifd_start,
+        0u64,
+        is_big_tiff,
+    )?;
+
+    //////////////////////////////////
+    // Write the larger_values_data //
+    //////////////////////////////////
+    bow.write_bytes(larger_values_data.get_inner())?;
+
+    Ok(())
+}
+
+/// Writes the encoded values of a range of rows of the image data, where the rows of the bands of
+/// multi-band rasters follow one another.
+fn write_image_rows<W: Write>(
+    r: &Raster,
+    bow: &mut ByteOrderWriter<W>,
+    data_interp: PhotometricInterpretation,
+    strip_rows: std::ops::Range<usize>,
+) -> Result<(), Error> {
+    match data_interp {
         PhotometricInterpretation::Continuous
         | PhotometricInterpretation::Categorical
         | PhotometricInterpretation::Boolean => match r.configs.data_type {
             DataType::F64 => {
                 let mut i: usize;
-                for row in 0..r.configs.rows {
+                for row in strip_rows.clone() {
                     for col in 0..r.configs.columns {
                         i = row * r.configs.columns + col;
-                        bow.write_f64(r.data[i])?;
+                        bow.write_f64(r.data.get_value(i))?;
                     }
                 }
             }
             DataType::F32 => {
                 let mut i: usize;
-                for row in 0..r.configs.rows {
+                for row in strip_rows.clone() {
                     for col in 0..r.configs.columns {
                         i = row * r.configs.columns + col;
-                        bow.write_f32(r.data[i] as f32)?;
+                        bow.write_f32(r.data.get_value(i) as f32)?;
                     }
                 }
             }
             DataType::U64 => {
                 let mut i: usize;
-                for row in 0..r.configs.rows {
+                for row in strip_rows.clone() {
                     for col in 0..r.configs.columns {
                         i = row * r.configs.columns + col;
-                        bow.write_u64(r.data[i] as u64)?;
+                        bow.write_u64(r.data.get_value(i) as u64)?;
                     }
                 }
             }
             DataType::U32 => {
                 let mut i: usize;
-                for row in 0..r.configs.rows {
+                for row in strip_rows.clone() {
                     for col in 0..r.configs.columns {
                         i = row * r.configs.columns + col;
-                        bow.write_u32(r.data[i] as u32)?;
+                        bow.write_u32(r.data.get_value(i) as u32)?;
                     }
                 }
             }
             DataType::U16 => {
                 let mut i: usize;
-                for row in 0..r.configs.rows {
+                for row in strip_rows.clone() {
                     for col in 0..r.configs.columns {
                         i = row * r.configs.columns + col;
-                        bow.write_u16(r.data[i] as u16)?;
+                        bow.write_u16(r.data.get_value(i) as u16)?;
                     }
                 }
             }
             DataType::U8 => {
                 let mut i: usize;
-                for row in 0..r.configs.rows {
+                for row in strip_rows.clone() {
                     for col in 0..r.configs.columns {
                         i = row * r.configs.columns + col;
-                        bow.write_u8(r.data[i] as u8)?;
+                        bow.write_u8(r.data.get_value(i) as u8)?;
                     }
                 }
             }
             DataType::I64 => {
                 let mut i: usize;
-                for row in 0..r.configs.rows {
+                for row in strip_rows.clone() {
                     for col in 0..r.configs.columns {
                         i = row * r.configs.columns + col;
-                        bow.write_i64(r.data[i] as i64)?;
+                        bow.write_i64(r.data.get_value(i) as i64)?;
                     }
                 }
             }
             DataType::I32 => {
                 let mut i: usize;
-                for row in 0..r.configs.rows {
+                for row in strip_rows.clone() {
                     for col in 0..r.configs.columns {
                         i = row * r.configs.columns + col;
-                        bow.write_i32(r.data[i] as i32)?;
+                        bow.write_i32(r.data.get_value(i) as i32)?;
                     }
                 }
             }
             DataType::I16 => {
                 let mut i: usize;
-                for row in 0..r.configs.rows {
+                for row in strip_rows.clone() {
                     for col in 0..r.configs.columns {
                         i = row * r.configs.columns + col;
-                        bow.write_i16(r.data[i] as i16)?;
+                        bow.write_i16(r.data.get_value(i) as i16)?;
                     }
                 }
             }
             DataType::I8 => {
                 let mut i: usize;
-                for row in 0..r.configs.rows {
+                for row in strip_rows.clone() {
                     for col in 0..r.configs.columns {
                         i = row * r.configs.columns + col;
-                        bow.write_i8(r.data[i] as i8)?;
+                        bow.write_i8(r.data.get_value(i) as i8)?;
                     }
                 }
             }
@@ -1747,10 +2150,10 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
                 DataType::RGB24 => {
                     let mut bytes: [u8; 3] = [0u8; 3];
                     let mut i: usize;
-                    for row in 0..r.configs.rows {
+                    for row in strip_rows.clone() {
                         for col in 0..r.configs.columns {
                             i = row * r.configs.columns + col;
-                            let val = r.data[i] as u32;
+                            let val = r.data.get_value(i) as u32;
                             bytes[2] = ((val >> 16u32) & 0xFF) as u8; // blue
                             bytes[1] = ((val >> 8u32) & 0xFF) as u8; // green
                             bytes[0] = (val & 0xFF) as u8; // red
@@ -1761,10 +2164,10 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
                 DataType::RGBA32 | DataType::U32 => {
                     let mut i: usize;
                     let mut bytes: [u8; 4] = [0u8; 4];
-                    for row in 0..r.configs.rows {
+                    for row in strip_rows.clone() {
                         for col in 0..r.configs.columns {
                             i = row * r.configs.columns + col;
-                            let val = r.data[i] as u32;
+                            let val = r.data.get_value(i) as u32;
                             bytes[2] = ((val >> 16u32) & 0xFF) as u8; // blue
                             bytes[1] = ((val >> 8u32) & 0xFF) as u8; // green
                             bytes[0] = (val & 0xFF) as u8; // red
@@ -1798,106 +2201,41 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         }
     }
 
-    // This is just because the IFD must start on a word (i.e. an even value). If the data are
-    // single bytes, then this may not be the case.
-    if ifd_start_needs_extra_byte {
-        bow.write_u8(0u8)?;
-    }
-
-
-    ////////////////////////////
-    // Create the IFD entries //
-    ////////////////////////////
-
-    /*
-        Required Fields for Bilevel Images
-        - ImageWidth
-        - ImageLength
-        - Compression
-        - PhotometricInterpretation
-        - StripOffsets
-        - RowsPerStrip
-        - StripByteCounts
-        - XResolution
-        - YResolution
-        - ResolutionUnit
-    */
-
-    /*
-        Required Fields for Grayscale Images
-        - ImageWidth
-        - ImageLength
-        - BitsPerSample
-        - Compression
-        - PhotometricInterpretation
-        - StripOffsets
-        - RowsPerStrip
-        - StripByteCounts
-        - XResolution
-        - YResolution
-        - ResolutionUnit
-    */
-
-    /*
-        Required Fields for Palette Colour Images
-        - ImageWidth
-        - ImageLength
-        - BitsPerSample
-        - Compression
-        - PhotometricInterpretation
-        - StripOffsets
-        - RowsPerStrip
-        - StripByteCounts
-        - XResolution
-        - YResolution
-        - ResolutionUnit
-        - ColorMap
-    */
-
-    /*
-        Required Fields for RGB Images
-        - ImageWidth
-        - ImageLength
-        - BitsPerSample
-        - Compression
-        - PhotometricInterpretation
-        - StripOffsets
-        - SamplesPerPixel
-        - RowsPerStrip
-        - StripByteCounts
-        - XResolution
-        - YResolution
-        - ResolutionUnit
-    */
-
-    let mut ifd_entries: Vec<Entry> = vec![];
-    // let mut larger_values_data: Vec<u8> = vec![];
-    let mut larger_values_data = ByteOrderWriter::<Vec<u8>>::new(vec![], r.configs.endian);
+    Ok(())
+}
 
-    /*
-    Classic TIFF IFD entries
+/// Sets whether every GeoTIFF output is written in the BigTIFF format, e.g. when the `--bigtiff`
+/// flag is specified. Otherwise, the BigTIFF format is only used for outputs that are too large for
+/// the 4 GB limit of classic TIFFs.
+pub fn set_big_tiff_output(enabled: bool) {
+    BIG_TIFF_OUTPUT.store(enabled, Ordering::Relaxed);
+}
 
-    Bytes 0-1 The Tag that identifies the field.
-    Bytes 2-3 The field Type.
-    Bytes 4-7 The number of values, Count of the indicated Type.
-    Bytes 8-11 The Value Offset, the file offset (in bytes) of the Value for the field.
-    The Value is expected to begin on a word boundary; the corresponding
-    Value Offset will thus be an even number. This file offset may
-    point anywhere in the file, even after the image data.
+/// Returns true if every GeoTIFF output is written in the BigTIFF format.
+pub fn is_big_tiff_output() -> bool {
+    BIG_TIFF_OUTPUT.load(Ordering::Relaxed)
+}
 
-    To save time and space the Value Offset contains the Value instead of pointing to
-    the Value if and only if the Value fits into 4 bytes. If the Value is shorter than 4
-    bytes, it is left-justified within the 4-byte Value Offset, i.e., stored in the lowernumbered
-    bytes. Whether the Value fits within 4 bytes is determined by the Type
-    and Count of the field.
-    */
+/// Adds the IFD entries that describe the structure of an image of `columns` by `rows` cells, i.e.
+/// its dimensions, samples, compression and predictor, and photometric interpretation. These
+/// entries are shared by a full-resolution image and its overviews.
+fn push_image_structure_entries(
+    r: &Raster,
+    columns: usize,
+    rows: usize,
+    compression: GeoTiffCompression,
+    predictor: u16,
+    ifd_entries: &mut Vec<Entry>,
+    larger_values_data: &mut ByteOrderWriter<Vec<u8>>,
+) -> Result<(), Error> {
+    let bands = r.configs.bands.max(1) as usize;
 
     // ImageWidth tag (256)
     ifd_entries.push(Entry::new(
         TAG_IMAGEWIDTH,
         DT_LONG,
         1u64,
-        r.configs.columns as u64,
+        columns as u64,
     ));
 
     // ImageLength tag (257)
@@ -1905,7 +2243,7 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         TAG_IMAGELENGTH,
         DT_LONG,
         1u64,
-        r.configs.rows as u64,
+        rows as u64,
     ));
 
     let bits_per_sample = match r.configs.data_type {
@@ -1922,6 +2260,7 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
     };
 
     let samples_per_pixel = match r.configs.data_type {
+        _ if bands > 1 => bands as u16,
         DataType::I8 | DataType::U8 => 1u16,
         DataType::I16 | DataType::U16 => 1u16,
         DataType::I32 | DataType::U32 | DataType::F32 => 1u16,
@@ -1943,6 +2282,14 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
                 samples_per_pixel as u64,
                 bits_per_sample as u64,
             ));
+        } else if samples_per_pixel == 2 {
+            // both values fit within the value offset
+            ifd_entries.push(Entry::new(
+                TAG_BITSPERSAMPLE,
+                DT_SHORT,
+                2u64,
+                (bits_per_sample as u64) << 16 | bits_per_sample as u64,
+            ));
         } else {
             ifd_entries.push(Entry::new(
                 TAG_BITSPERSAMPLE,
@@ -1961,9 +2308,14 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         TAG_COMPRESSION,
         DT_SHORT,
         1u64,
-        COMPRESS_NONE as u64,
+        compression.tag_value() as u64,
     ));
 
+    // Predictor tag (317)
+    if predictor > 1 {
+        ifd_entries.push(Entry::new(TAG_PREDICTOR, DT_SHORT, 1u64, predictor as u64));
+    }
+
     // PhotometricInterpretation tag (262)
     let pi = match r.configs.photometric_interp {
         PhotometricInterpretation::Continuous => PI_BLACKISZERO,
@@ -1986,31 +2338,6 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         pi as u64,
     ));
 
-    // StripOffsets tag (273)
-    if !is_big_tiff {
-        ifd_entries.push(Entry::new(
-            TAG_STRIPOFFSETS,
-            DT_LONG,
-            r.configs.rows as u64,
-            larger_values_data.len() as u64,
-        ));
-        let row_length_in_bytes: u32 = r.configs.columns as u32 * total_bytes_per_pixel as u32;
-        for i in 0..r.configs.rows as u32 {
-            larger_values_data.write_u32(8u32 + row_length_in_bytes * i)?;
-        }
-    } else {
-        ifd_entries.push(Entry::new(
-            TAG_STRIPOFFSETS,
-            DT_TIFF_LONG8,
-            r.configs.rows as u64,
-            larger_values_data.len() as u64,
-        ));
-        let row_length_in_bytes: u64 = r.configs.columns as u64 * total_bytes_per_pixel as u64;
-        for i in 0..r.configs.rows as u64 {
-            larger_values_data.write_u64(8u64 + row_length_in_bytes * i)?;
-        }
-    }
-
     // SamplesPerPixel tag (277)
     ifd_entries.push(Entry::new(
         TAG_SAMPLESPERPIXEL,
@@ -2019,58 +2346,80 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         samples_per_pixel as u64,
     ));
 
-    // RowsPerStrip tag (278)
-    ifd_entries.push(Entry::new(TAG_ROWSPERSTRIP, DT_SHORT, 1u64, 1u64));
+    if bands > 1 {
+        // PlanarConfiguration tag (284)
+        ifd_entries.push(Entry::new(TAG_PLANARCONFIGURATION, DT_SHORT, 1u64, 2u64));
 
-    // StripByteCounts tag (279)
-    if !is_big_tiff {
-        ifd_entries.push(Entry::new(
-            TAG_STRIPBYTECOUNTS,
-            DT_LONG,
-            r.configs.rows as u64,
-            larger_values_data.len() as u64,
-        ));
-        let total_bytes_per_pixel = match r.configs.data_type {
-            DataType::I8 | DataType::U8 => 1u32,
-            DataType::I16 | DataType::U16 => 2u32,
-            DataType::I32 | DataType::U32 | DataType::F32 => 4u32,
-            DataType::I64 | DataType::U64 | DataType::F64 => 8u32,
-            DataType::RGB24 => 3u32,
-            DataType::RGBA32 => 4u32,
-            DataType::RGB48 => 6u32,
-            _ => {
-                return Err(Error::new(ErrorKind::InvalidData, "Unknown data type."));
+        // ExtraSamples tag (338); any bands beyond the colour (or grey) channels are unspecified data
+        let colour_samples = if pi == PI_RGB { 3usize } else { 1usize };
+        if bands > colour_samples {
+            let num_extra = bands - colour_samples;
+            if num_extra <= 2 {
+                ifd_entries.push(Entry::new(TAG_EXTRASAMPLES, DT_SHORT, num_extra as u64, 0u64));
+            } else {
+                ifd_entries.push(Entry::new(
+                    TAG_EXTRASAMPLES,
+                    DT_SHORT,
+                    num_extra as u64,
+                    larger_values_data.len() as u64,
+                ));
+                for _ in 0..num_extra {
+                    larger_values_data.write_u16(0u16)?;
+                }
             }
-        };
-        let row_length_in_bytes: u32 = r.configs.columns as u32 * total_bytes_per_pixel;
-        for _ in 0..r.configs.rows as u32 {
-            larger_values_data.write_u32(row_length_in_bytes)?;
         }
+    } else if samples_per_pixel == 4 {
+        // ExtraSamples tag (338)
+        ifd_entries.push(Entry::new(TAG_EXTRASAMPLES, DT_SHORT, 1u64, 2u64));
+    }
+
+    // SampleFormat tag (339)
+    let samples_format = match r.configs.data_type {
+        DataType::U8 | DataType::U16 | DataType::U32 | DataType::U64 => 1u16,
+        DataType::I8 | DataType::I16 | DataType::I32 | DataType::I64 => 2u16,
+        DataType::F32 | DataType::F64 => 3u16,
+        DataType::RGB24 | DataType::RGBA32 | DataType::RGB48 => 1u16,
+        _ => {
+            return Err(Error::new(ErrorKind::InvalidData, "Unknown data type."));
+        }
+    };
+    if samples_per_pixel == 1 {
+        ifd_entries.push(Entry::new(
+            TAG_SAMPLEFORMAT,
+            DT_SHORT,
+            samples_per_pixel as u64,
+            samples_format as u64,
+        ));
+    } else if samples_per_pixel == 2 {
+        ifd_entries.push(Entry::new(
+            TAG_SAMPLEFORMAT,
+            DT_SHORT,
+            2u64,
+            (samples_format as u64) << 16 | samples_format as u64,
+        ));
     } else {
         ifd_entries.push(Entry::new(
-            TAG_STRIPBYTECOUNTS,
-            DT_TIFF_LONG8,
-            r.configs.rows as u64,
+            TAG_SAMPLEFORMAT,
+            DT_SHORT,
+            samples_per_pixel as u64,
             larger_values_data.len() as u64,
         ));
-        let total_bytes_per_pixel = match r.configs.data_type {
-            DataType::I8 | DataType::U8 => 1u64,
-            DataType::I16 | DataType::U16 => 2u64,
-            DataType::I32 | DataType::U32 | DataType::F32 => 4u64,
-            DataType::I64 | DataType::U64 | DataType::F64 => 8u64,
-            DataType::RGB24 => 3u64,
-            DataType::RGBA32 => 4u64,
-            DataType::RGB48 => 6u64,
-            _ => {
-                return Err(Error::new(ErrorKind::InvalidData, "Unknown data type."));
-            }
-        };
-        let row_length_in_bytes: u64 = r.configs.columns as u64 * total_bytes_per_pixel;
-        for _ in 0..r.configs.rows as u32 {
-            larger_values_data.write_u64(row_length_in_bytes)?;
+        for _ in 0..samples_per_pixel {
+            larger_values_data.write_u16(samples_format)?;
         }
     }
 
+    Ok(())
+}
+
+/// Adds the IFD entries of a full-resolution image that are independent of its structure, i.e. the
+/// georeferencing tags, the GeoKey directory, and the NoData value.
+fn push_metadata_entries(
+    r: &Raster,
+    is_big_tiff: bool,
+    ifd_entries: &mut Vec<Entry>,
+    larger_values_data: &mut ByteOrderWriter<Vec<u8>>,
+) -> Result<(), Error> {
     // There is currently no support for storing the image resolution, so give a bogus value of 72x72 dpi.
     // XResolution tag (282)
     ifd_entries.push(Entry::new(
@@ -2107,40 +2456,6 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
     ));
     larger_values_data.write_bytes(&soft_bytes)?;
 
-    if samples_per_pixel == 4 {
-        // ExtraSamples tag (338)
-        ifd_entries.push(Entry::new(TAG_EXTRASAMPLES, DT_SHORT, 1u64, 2u64));
-    }
-
-    // SampleFormat tag (339)
-    let samples_format = match r.configs.data_type {
-        DataType::U8 | DataType::U16 | DataType::U32 | DataType::U64 => 1u16,
-        DataType::I8 | DataType::I16 | DataType::I32 | DataType::I64 => 2u16,
-        DataType::F32 | DataType::F64 => 3u16,
-        DataType::RGB24 | DataType::RGBA32 | DataType::RGB48 => 1u16,
-        _ => {
-            return Err(Error::new(ErrorKind::InvalidData, "Unknown data type."));
-        }
-    };
-    if samples_per_pixel == 1 {
-        ifd_entries.push(Entry::new(
-            TAG_SAMPLEFORMAT,
-            DT_SHORT,
-            samples_per_pixel as u64,
-            samples_format as u64,
-        ));
-    } else {
-        ifd_entries.push(Entry::new(
-            TAG_SAMPLEFORMAT,
-            DT_SHORT,
-            samples_per_pixel as u64,
-            larger_values_data.len() as u64,
-        ));
-        for _ in 0..samples_per_pixel {
-            larger_values_data.write_u16(samples_format)?;
-        }
-    }
-
     // ModelPixelScaleTag tag (33550)
     if r.configs.model_pixel_scale[0] == 0f64 && r.configs.model_tiepoint.is_empty() && r.configs.model_transformation[0] == 0f64 {
         ifd_entries.push(Entry::new(
@@ -2553,10 +2868,23 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         }
     }
 
-    ///////////////////
-    // Write the IFD //
-    ///////////////////
+    Ok(())
+}
 
+/// Writes an IFD starting at the file offset `ifd_start`, with its entries sorted by tag. Values that
+/// do not fit within an entry are expected to be written immediately after the IFD, with entry
+/// offsets relative to the start of these data, i.e. `larger_values_data`. Those values that are
+/// small enough to fit within the entry's value offset (4 bytes for TIFF, 8 bytes for BigTIFF) are
+/// instead stored within the entry, as the TIFF specification requires. `next_ifd` is the offset of
+/// the following IFD, or zero for the last IFD of the file.
+fn write_ifd<W: Write>(
+    bow: &mut ByteOrderWriter<W>,
+    mut ifd_entries: Vec<Entry>,
+    larger_values_data: &[u8],
+    ifd_start: u64,
+    next_ifd: u64,
+    is_big_tiff: bool,
+) -> Result<(), Error> {
     // Number of Directory Entries.
     if !is_big_tiff {
         bow.write_u16(ifd_entries.len() as u16)?;
@@ -2581,15 +2909,16 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
             } else if ifde.ifd_type == DT_SHORT && ifde.num_values == 2 {
                 // I'm not really sure about this one. Two shorts will fit in the value_offset, but will they be interpreted correctly?
                 bow.write_u32(ifde.offset as u32)?; // Value
+            } else if let Some(value) = inline_value(&ifde, larger_values_data, 4) {
+                bow.write_bytes(&value)?;
             } else {
                 // it's an offset
                 bow.write_u32(ifd_start as u32 + ifd_length as u32 + ifde.offset as u32)?;
             }
         }
 
-        // 4-byte offset of the next IFD; Note, only single image TIFFs are currently supported
-        // and therefore, this will always be set to '0'.
-        bow.write_u32(0u32)?;
+        // 4-byte offset of the next IFD, or '0' for the last IFD.
+        bow.write_u32(next_ifd as u32)?;
     } else {
         bow.write_u64(ifd_entries.len() as u64)?;
 
@@ -2620,25 +2949,40 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
                 (ifde.ifd_type == DT_TIFF_LONG8 && ifde.num_values == 1) {
                 // it's a value
                 bow.write_u64(ifde.offset)?;
+            } else if let Some(value) = inline_value(&ifde, larger_values_data, 8) {
+                bow.write_bytes(&value)?;
             } else {
                 // it's an offset
                 bow.write_u64(ifd_start + ifd_length + ifde.offset)?;
             }
         }
 
-        // 4-byte offset of the next IFD; Note, only single image TIFFs are currently supported
-        // and therefore, this will always be set to '0'.
-        bow.write_u64(0u64)?;
+        // 8-byte offset of the next IFD, or '0' for the last IFD.
+        bow.write_u64(next_ifd)?;
     }
 
-    //////////////////////////////////
-    // Write the larger_values_data //
-    //////////////////////////////////
-    bow.write_bytes(larger_values_data.get_inner())?;
-
     Ok(())
 }
 
+/// Returns the value of an entry whose data were written to `larger_values_data`, padded to
+/// `value_size` bytes, if the data fit within the entry's value offset of `value_size` bytes.
+fn inline_value(ifde: &Entry, larger_values_data: &[u8], value_size: usize) -> Option<Vec<u8>> {
+    let type_size = match ifde.ifd_type {
+        DT_BYTE | DT_ASCII | DT_SBYTE | DT_UNDEFINED => 1usize,
+        DT_SHORT | DT_SSHORT => 2usize,
+        DT_LONG | DT_SLONG | DT_FLOAT => 4usize,
+        _ => 8usize,
+    };
+    let num_bytes = type_size * ifde.num_values as usize;
+    let start = ifde.offset as usize;
+    if num_bytes > value_size || start + num_bytes > larger_values_data.len() {
+        return None;
+    }
+    let mut value = larger_values_data[start..start + num_bytes].to_vec();
+    value.resize(value_size, 0u8);
+    Some(value)
+}
+
 // An implimentation of a PackBits reader
 pub fn packbits_decoder(input_data: Vec<u8>) -> Vec<u8> {
     let mut output_data = vec![];
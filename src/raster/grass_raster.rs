@@ -170,9 +170,9 @@ pub fn write_grass_raster<'a>(r: &'a mut Raster) -> Result<(), Error> {
     if r.configs.data_type == DataType::F32 || r.configs.data_type == DataType::F64 {
         for i in 0..num_cells {
             if col < r.configs.columns - 1 {
-                s2 += &format!("{:.*} ", 2, r.data[i]);
+                s2 += &format!("{:.*} ", 2, r.data.get_value(i));
             } else {
-                s2 += &format!("{:.*}\n", 2, r.data[i]);
+                s2 += &format!("{:.*}\n", 2, r.data.get_value(i));
             }
             col += 1;
             if col == r.configs.columns {
@@ -184,9 +184,9 @@ pub fn write_grass_raster<'a>(r: &'a mut Raster) -> Result<(), Error> {
     } else {
         for i in 0..num_cells {
             if col < r.configs.columns - 1 {
-                s2 += &format!("{:.*} ", 0, r.data[i]);
+                s2 += &format!("{:.*} ", 0, r.data.get_value(i));
             } else {
-                s2 += &format!("{:.*}\n", 0, r.data[i]);
+                s2 += &format!("{:.*}\n", 0, r.data.get_value(i));
             }
             col += 1;
             if col == r.configs.columns {
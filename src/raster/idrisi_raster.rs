@@ -226,8 +226,7 @@ pub fn read_idrisi(
 
 pub fn write_idrisi<'a>(r: &'a mut Raster) -> Result<(), Error> {
     // figure out the minimum and maximum values
-    for val in &r.data {
-        let v = *val;
+    for v in r.data.iter() {
         if v != r.configs.nodata {
             if v < r.configs.minimum {
                 r.configs.minimum = v;
@@ -358,7 +357,7 @@ pub fn write_idrisi<'a>(r: &'a mut Raster) -> Result<(), Error> {
     match r.configs.data_type {
         DataType::F32 => {
             for i in 0..num_cells {
-                u32_bytes = unsafe { mem::transmute(r.data[i] as f32) };
+                u32_bytes = unsafe { mem::transmute(r.data.get_value(i) as f32) };
                 writer.write(&u32_bytes)?;
             }
         }
@@ -369,19 +368,19 @@ pub fn write_idrisi<'a>(r: &'a mut Raster) -> Result<(), Error> {
                 "Writing RGB24 raster is not currently supported.",
             ));
             // for i in 0..num_cells {
-            //     u24_bytes = unsafe { mem::transmute(r.data[i] as u32) };
+            //     u24_bytes = unsafe { mem::transmute(r.data.get_value(i) as u32) };
             //     try!(writer.write(&u16_bytes));
             // }
         }
         DataType::I16 => {
             for i in 0..num_cells {
-                u16_bytes = unsafe { mem::transmute(r.data[i] as u16) };
+                u16_bytes = unsafe { mem::transmute(r.data.get_value(i) as u16) };
                 writer.write(&u16_bytes)?;
             }
         }
         DataType::U8 => {
             for i in 0..num_cells {
-                writer.write(&[r.data[i] as u8])?;
+                writer.write(&[r.data.get_value(i) as u8])?;
             }
         }
         _ => {
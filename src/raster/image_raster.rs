@@ -0,0 +1,602 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+//! Reading and writing of PNG and JPEG images that are georeferenced by world files, e.g. drone
+//! orthomosaic previews and scanned maps. A world file is a text file of six lines, giving the
+//! pixel width, two rotation terms, the (negative) pixel height, and the x and y coordinates of
+//! the centre of the upper-left pixel. It shares the name of the image, with an extension formed
+//! from the first and last letters of the image's extension and a 'w' (.pgw or .jgw), the image's
+//! extension and a 'w' (.pngw or .jpgw), or '.wld'. The projection is read from, and written to, an
+//! optional .prj file. Images without a world file are read in pixel coordinates.
+//!
+//! Greyscale images are read as single-band 8-bit or 16-bit rasters, and colour images as packed
+//! RGB24 (or RGBA32, for images with transparency) rasters, in which each cell value holds the
+//! red, green, blue, and alpha components of a pixel in its four bytes, as they are used by the
+//! image processing tools. Paletted images are expanded to colour. Transparent pixels are NoData.
+//! Since neither format stores values of other data types, rasters whose values are not integers
+//! in the range of 8-bit (or, for PNG, 16-bit) greyscale are rescaled to 8 bits when written. The
+//! NoData cells of PNG outputs are transparent, whereas those of JPEG outputs, which do not support
+//! transparency, are black.
+
+use super::*;
+use crate::spatial_ref_system::esri_wkt_from_epsg;
+use std::f64;
+use std::fs;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// The quality of JPEG outputs, from 1 to 100.
+const JPEG_QUALITY: u8 = 90;
+
+/// The NoData value of greyscale images.
+const GREY_NODATA: f64 = -32768.0;
+
+/// Returns true if a file name refers to a JPEG image.
+pub fn is_jpeg_file(file_name: &str) -> bool {
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    extension == "jpg" || extension == "jpeg" || extension == "jpe"
+}
+
+/// Returns the names of the world files that may accompany an image, in order of preference.
+fn world_file_names(file_name: &str) -> Vec<PathBuf> {
+    let path = Path::new(file_name);
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut names = vec![];
+    if extension.len() >= 2 {
+        let first = &extension[0..1];
+        let last = &extension[extension.len() - 1..];
+        names.push(path.with_extension(format!("{}{}w", first, last)));
+    }
+    names.push(path.with_extension(format!("{}w", extension)));
+    names.push(path.with_extension("wld"));
+    names
+}
+
+/// Reads the georeferencing of an image from its world file, if it has one, and otherwise places
+/// the image in pixel coordinates, with its lower-left corner at the origin.
+fn read_world_file(file_name: &str, configs: &mut RasterConfigs) -> Result<(), Error> {
+    let rows = configs.rows as f64;
+    let columns = configs.columns as f64;
+    let world_file = world_file_names(file_name).into_iter().find(|f| f.exists());
+    match world_file {
+        Some(world_file) => {
+            let contents = fs::read_to_string(&world_file)?;
+            let terms = contents
+                .split_whitespace()
+                .map(|s| s.parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Invalid world file: {}", world_file.display()),
+                    )
+                })?;
+            if terms.len() < 6 {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid world file: {}", world_file.display()),
+                ));
+            }
+            if terms[1] != 0f64 || terms[2] != 0f64 {
+                println!(
+                    "Warning: The rotation terms of the world file {} are not supported and have been ignored.",
+                    world_file.display()
+                );
+            }
+            configs.resolution_x = terms[0].abs();
+            configs.resolution_y = terms[3].abs();
+            // the world file gives the centre of the upper-left pixel
+            configs.west = terms[4] - configs.resolution_x / 2f64;
+            configs.north = terms[5] + configs.resolution_y / 2f64;
+        }
+        None => {
+            configs.resolution_x = 1f64;
+            configs.resolution_y = 1f64;
+            configs.west = 0f64;
+            configs.north = rows;
+        }
+    }
+    configs.east = configs.west + columns * configs.resolution_x;
+    configs.south = configs.north - rows * configs.resolution_y;
+
+    // the projection, if any, is stored in a .prj file
+    let prj_file = Path::new(file_name).with_extension("prj");
+    if let Ok(prj) = fs::read_to_string(&prj_file) {
+        if !prj.trim().is_empty() {
+            configs.coordinate_ref_system_wkt = prj.trim().to_string();
+        }
+    }
+    Ok(())
+}
+
+/// Writes the world file and, if the projection is known, the .prj file of an image.
+fn write_world_file(r: &Raster) -> Result<(), Error> {
+    let world_file = world_file_names(&r.file_name).remove(0);
+    let contents = format!(
+        "{}\n0.0\n0.0\n{}\n{}\n{}\n",
+        r.configs.resolution_x,
+        -r.configs.resolution_y,
+        r.configs.west + r.configs.resolution_x / 2f64,
+        r.configs.north - r.configs.resolution_y / 2f64
+    );
+    fs::write(&world_file, contents.as_bytes())?;
+
+    let wkt = if !r.configs.coordinate_ref_system_wkt.is_empty()
+        && r.configs.coordinate_ref_system_wkt.to_lowercase() != "not specified"
+    {
+        r.configs.coordinate_ref_system_wkt.clone()
+    } else if r.configs.epsg_code != 0 {
+        esri_wkt_from_epsg(r.configs.epsg_code)
+    } else {
+        String::new()
+    };
+    if !wkt.is_empty() && !wkt.to_lowercase().contains("unknown epsg") {
+        fs::write(Path::new(&r.file_name).with_extension("prj"), wkt.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Packs the components of a pixel into the value of a cell of an RGB24 or RGBA32 raster.
+fn pack_rgba(red: u8, green: u8, blue: u8, alpha: u8) -> f64 {
+    (((alpha as u32) << 24) | ((blue as u32) << 16) | ((green as u32) << 8) | red as u32) as f64
+}
+
+/// Sets the configs of a greyscale image of `bit_depth` bits.
+fn set_grey_configs(configs: &mut RasterConfigs, bit_depth: usize) {
+    configs.photometric_interp = PhotometricInterpretation::Continuous;
+    configs.data_type = if bit_depth > 8 { DataType::U16 } else { DataType::U8 };
+    configs.nodata = GREY_NODATA;
+}
+
+/// Sets the minimum and maximum, and the display range, of an image from its values.
+fn set_min_max(configs: &mut RasterConfigs, data: &[f64]) {
+    configs.minimum = f64::INFINITY;
+    configs.maximum = f64::NEG_INFINITY;
+    for &v in data.iter() {
+        if v != configs.nodata {
+            configs.minimum = configs.minimum.min(v);
+            configs.maximum = configs.maximum.max(v);
+        }
+    }
+    configs.display_min = configs.minimum;
+    configs.display_max = configs.maximum;
+}
+
+/// Sets the configs of a colour image.
+fn set_colour_configs(configs: &mut RasterConfigs, has_alpha: bool) {
+    configs.photometric_interp = PhotometricInterpretation::RGB;
+    configs.data_type = if has_alpha { DataType::RGBA32 } else { DataType::RGB24 };
+    configs.nodata = 0f64;
+}
+
+pub fn read_png(
+    file_name: &String,
+    configs: &mut RasterConfigs,
+    data: &mut Vec<f64>,
+) -> Result<(), Error> {
+    let f = File::open(file_name)?;
+    let mut decoder = png::Decoder::new(BufReader::new(f));
+    // palettes are expanded to colour, transparency chunks to alpha, and small bit depths to 8
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0u8; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf)?;
+
+    configs.rows = info.height as usize;
+    configs.columns = info.width as usize;
+    configs.bands = 1;
+    let sixteen_bit = info.bit_depth == png::BitDepth::Sixteen;
+    let bytes_per_sample = if sixteen_bit { 2 } else { 1 };
+    let samples = match info.color_type {
+        png::ColorType::Grayscale => 1,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::Rgb => 3,
+        png::ColorType::Rgba => 4,
+        png::ColorType::Indexed => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The palette of the PNG image could not be expanded.",
+            ))
+        }
+    };
+    // 16-bit colour samples are reduced to their most significant byte
+    let sample = |row: &[u8], col: usize, s: usize| -> u16 {
+        let i = (col * samples + s) * bytes_per_sample;
+        if sixteen_bit {
+            u16::from_be_bytes([row[i], row[i + 1]])
+        } else {
+            row[i] as u16
+        }
+    };
+    let msb = |v: u16| -> u8 {
+        if sixteen_bit {
+            (v >> 8) as u8
+        } else {
+            v as u8
+        }
+    };
+
+    if samples <= 2 {
+        set_grey_configs(configs, if sixteen_bit { 16 } else { 8 });
+    } else {
+        set_colour_configs(configs, samples == 4);
+    }
+    data.clear();
+    data.reserve(configs.rows * configs.columns);
+    for row in 0..configs.rows {
+        let line = &buf[row * info.line_size..(row + 1) * info.line_size];
+        for col in 0..configs.columns {
+            let z = match samples {
+                1 => sample(line, col, 0) as f64,
+                2 => {
+                    if sample(line, col, 1) == 0 {
+                        GREY_NODATA
+                    } else {
+                        sample(line, col, 0) as f64
+                    }
+                }
+                _ => {
+                    let alpha = if samples == 4 { msb(sample(line, col, 3)) } else { 255u8 };
+                    if alpha == 0 {
+                        0f64
+                    } else {
+                        pack_rgba(
+                            msb(sample(line, col, 0)),
+                            msb(sample(line, col, 1)),
+                            msb(sample(line, col, 2)),
+                            alpha,
+                        )
+                    }
+                }
+            };
+            data.push(z);
+        }
+    }
+    set_min_max(configs, data);
+
+    read_world_file(file_name, configs)
+}
+
+pub fn read_jpeg(
+    file_name: &String,
+    configs: &mut RasterConfigs,
+    data: &mut Vec<f64>,
+) -> Result<(), Error> {
+    let f = File::open(file_name)?;
+    let mut decoder = jpeg_decoder::Decoder::new(BufReader::new(f));
+    let pixels = decoder
+        .decode()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("Error decoding JPEG: {}", e)))?;
+    let info = match decoder.info() {
+        Some(info) => info,
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The JPEG image could not be read.",
+            ))
+        }
+    };
+
+    configs.rows = info.height as usize;
+    configs.columns = info.width as usize;
+    configs.bands = 1;
+    let num_cells = configs.rows * configs.columns;
+    data.clear();
+    data.reserve(num_cells);
+    match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => {
+            set_grey_configs(configs, 8);
+            data.extend(pixels.iter().take(num_cells).map(|v| *v as f64));
+        }
+        jpeg_decoder::PixelFormat::L16 => {
+            set_grey_configs(configs, 16);
+            data.extend(
+                pixels
+                    .chunks_exact(2)
+                    .take(num_cells)
+                    .map(|v| u16::from_ne_bytes([v[0], v[1]]) as f64),
+            );
+        }
+        jpeg_decoder::PixelFormat::RGB24 => {
+            set_colour_configs(configs, false);
+            data.extend(
+                pixels
+                    .chunks_exact(3)
+                    .take(num_cells)
+                    .map(|p| pack_rgba(p[0], p[1], p[2], 255u8)),
+            );
+        }
+        jpeg_decoder::PixelFormat::CMYK32 => {
+            set_colour_configs(configs, false);
+            let channel = |c: u8, k: u8| ((255 - c as u32) * (255 - k as u32) / 255) as u8;
+            data.extend(pixels.chunks_exact(4).take(num_cells).map(|p| {
+                pack_rgba(channel(p[0], p[3]), channel(p[1], p[3]), channel(p[2], p[3]), 255u8)
+            }));
+        }
+    }
+    if data.len() != num_cells {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The JPEG image could not be read.",
+        ));
+    }
+    set_min_max(configs, data);
+
+    read_world_file(file_name, configs)
+}
+
+/// The pixels of an image to be written, with their samples interleaved.
+struct ImagePixels {
+    bytes: Vec<u8>,
+    /// the number of samples of each pixel, i.e. 1 (grey), 2 (grey and alpha), 3 (RGB), or 4 (RGBA)
+    samples: usize,
+    sixteen_bit: bool,
+}
+
+/// Converts the values of a raster to the pixels of an image. Colour rasters are unpacked into
+/// their components and the values of other rasters are written as greyscale, rescaled to 8 bits
+/// if they are not integers within the range of the greyscale bit depths that are supported. An
+/// alpha sample is added if the image is to have transparency and the raster contains NoData.
+fn raster_to_pixels(r: &Raster, transparency: bool, max_grey: f64) -> ImagePixels {
+    let nodata = r.configs.nodata;
+    let num_cells = r.configs.rows * r.configs.columns;
+    let has_nodata = transparency && r.data.iter().take(num_cells).any(|v| v == nodata);
+    let is_colour = r.configs.photometric_interp == PhotometricInterpretation::RGB
+        || r.configs.data_type == DataType::RGB24
+        || r.configs.data_type == DataType::RGBA32;
+
+    if is_colour {
+        let has_alpha = transparency && (r.configs.data_type == DataType::RGBA32 || has_nodata);
+        let samples = if has_alpha { 4 } else { 3 };
+        let mut bytes = Vec::with_capacity(num_cells * samples);
+        for v in r.data.iter().take(num_cells) {
+            let value = if v == nodata { 0u32 } else { v as u32 };
+            bytes.push((value & 0xFF) as u8);
+            bytes.push(((value >> 8) & 0xFF) as u8);
+            bytes.push(((value >> 16) & 0xFF) as u8);
+            if has_alpha {
+                let alpha = if v == nodata {
+                    0u8
+                } else if r.configs.data_type == DataType::RGBA32 {
+                    ((value >> 24) & 0xFF) as u8
+                } else {
+                    255u8
+                };
+                bytes.push(alpha);
+            }
+        }
+        return ImagePixels {
+            bytes,
+            samples,
+            sixteen_bit: false,
+        };
+    }
+
+    // find the range of the valid values and whether they are all integers
+    let mut min = f64::INFINITY;
+    let mut max = f64::NEG_INFINITY;
+    let mut all_integers = true;
+    for v in r.data.iter().take(num_cells) {
+        if v != nodata && !v.is_nan() {
+            min = min.min(v);
+            max = max.max(v);
+            if v.fract() != 0f64 {
+                all_integers = false;
+            }
+        }
+    }
+    let (scale, offset, sixteen_bit) = if min > max || (all_integers && min >= 0f64 && max <= 255f64) {
+        (1f64, 0f64, false)
+    } else if all_integers && min >= 0f64 && max <= max_grey {
+        (1f64, 0f64, true)
+    } else {
+        // rescale the display range of the values to 8 bits
+        let (low, high) = if r.configs.display_min.is_finite()
+            && r.configs.display_max.is_finite()
+            && r.configs.display_max > r.configs.display_min
+        {
+            (r.configs.display_min, r.configs.display_max)
+        } else {
+            (min, max)
+        };
+        println!(
+            "Warning: The values of {} have been rescaled from {}-{} to 8 bits.",
+            r.file_name, low, high
+        );
+        let scale = if high > low { 255f64 / (high - low) } else { 1f64 };
+        (scale, low, false)
+    };
+    let max_value = if sixteen_bit { 65535f64 } else { 255f64 };
+
+    let samples = if has_nodata { 2 } else { 1 };
+    let bytes_per_sample = if sixteen_bit { 2 } else { 1 };
+    let mut bytes = Vec::with_capacity(num_cells * samples * bytes_per_sample);
+    let push_sample = |bytes: &mut Vec<u8>, value: u16| {
+        if sixteen_bit {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        } else {
+            bytes.push(value as u8);
+        }
+    };
+    for v in r.data.iter().take(num_cells) {
+        if v == nodata || v.is_nan() {
+            push_sample(&mut bytes, 0);
+            if has_nodata {
+                push_sample(&mut bytes, 0);
+            }
+        } else {
+            let z = ((v - offset) * scale).round().max(0f64).min(max_value);
+            push_sample(&mut bytes, z as u16);
+            if has_nodata {
+                push_sample(&mut bytes, max_value as u16);
+            }
+        }
+    }
+    ImagePixels {
+        bytes,
+        samples,
+        sixteen_bit,
+    }
+}
+
+pub fn write_png(r: &mut Raster) -> Result<(), Error> {
+    let pixels = raster_to_pixels(r, true, 65535f64);
+    let f = File::create(&r.file_name)?;
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(f),
+        r.configs.columns as u32,
+        r.configs.rows as u32,
+    );
+    encoder.set_color(match pixels.samples {
+        1 => png::ColorType::Grayscale,
+        2 => png::ColorType::GrayscaleAlpha,
+        3 => png::ColorType::Rgb,
+        _ => png::ColorType::Rgba,
+    });
+    encoder.set_depth(if pixels.sixteen_bit {
+        png::BitDepth::Sixteen
+    } else {
+        png::BitDepth::Eight
+    });
+    encoder.set_compression(png::Compression::Default);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels.bytes)?;
+    writer.finish()?;
+
+    write_world_file(r)
+}
+
+pub fn write_jpeg(r: &mut Raster) -> Result<(), Error> {
+    if r.configs.rows > u16::MAX as usize || r.configs.columns > u16::MAX as usize {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "JPEG images may be no larger than 65535 pixels in each dimension.",
+        ));
+    }
+    let pixels = raster_to_pixels(r, false, 255f64);
+    let color_type = if pixels.samples == 1 {
+        jpeg_encoder::ColorType::Luma
+    } else {
+        jpeg_encoder::ColorType::Rgb
+    };
+    let f = File::create(&r.file_name)?;
+    let encoder = jpeg_encoder::Encoder::new(BufWriter::new(f), JPEG_QUALITY);
+    encoder
+        .encode(
+            &pixels.bytes,
+            r.configs.columns as u16,
+            r.configs.rows as u16,
+            color_type,
+        )
+        .map_err(|e| Error::other(format!("Error encoding JPEG: {}", e)))?;
+
+    write_world_file(r)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::*;
+    use std::fs;
+
+    fn test_configs() -> RasterConfigs {
+        let mut configs = RasterConfigs::default();
+        configs.rows = 3;
+        configs.columns = 4;
+        configs.west = 500.0;
+        configs.south = 1000.0;
+        configs.resolution_x = 10.0;
+        configs.resolution_y = 10.0;
+        configs.east = configs.west + 40.0;
+        configs.north = configs.south + 30.0;
+        configs.nodata = -32768.0;
+        configs.data_type = DataType::U8;
+        configs.epsg_code = 32617;
+        configs
+    }
+
+    #[test]
+    fn test_png_world_file_round_trip() {
+        let dir = std::env::temp_dir().join("wbt_png_round_trip");
+        let _ = fs::create_dir_all(&dir);
+        let file_name = dir.join("grey.png").to_string_lossy().to_string();
+        let mut output = Raster::initialize_using_config(&file_name, &test_configs());
+        for i in 0..12 {
+            output.data.set_value(i, (i * 20) as f64);
+        }
+        output.data.set_value(5, -32768.0);
+        output.write().unwrap();
+        assert!(dir.join("grey.pgw").exists());
+        assert!(dir.join("grey.prj").exists());
+
+        let input = Raster::new(&file_name, "r").unwrap();
+        assert_eq!(input.configs.rows, 3);
+        assert_eq!(input.configs.columns, 4);
+        assert!((input.configs.west - 500.0).abs() < 1e-9);
+        assert!((input.configs.north - 1030.0).abs() < 1e-9);
+        assert!((input.configs.resolution_y - 10.0).abs() < 1e-9);
+        assert_eq!(input.get_value(0, 1), 20.0);
+        assert_eq!(input.get_value(1, 1), input.configs.nodata);
+        assert_eq!(input.get_value(2, 3), 220.0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_png_min_max() {
+        let dir = std::env::temp_dir().join("wbt_png_min_max");
+        let _ = fs::create_dir_all(&dir);
+        let file_name = dir.join("grey.png").to_string_lossy().to_string();
+        let mut output = Raster::initialize_using_config(&file_name, &test_configs());
+        for i in 0..12 {
+            output.data.set_value(i, (10 + i * 5) as f64);
+        }
+        output.data.set_value(0, -32768.0);
+        output.write().unwrap();
+
+        let input = Raster::new(&file_name, "r").unwrap();
+        assert_eq!(input.configs.minimum, 15.0);
+        assert_eq!(input.configs.maximum, 65.0);
+        assert_eq!(input.configs.display_min, 15.0);
+        assert_eq!(input.configs.display_max, 65.0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_jpeg_colour_round_trip() {
+        let dir = std::env::temp_dir().join("wbt_jpeg_round_trip");
+        let _ = fs::create_dir_all(&dir);
+        let file_name = dir.join("colour.jpg").to_string_lossy().to_string();
+        let mut configs = test_configs();
+        configs.data_type = DataType::RGB24;
+        configs.photometric_interp = PhotometricInterpretation::RGB;
+        configs.nodata = 0.0;
+        let mut output = Raster::initialize_using_config(&file_name, &configs);
+        // a uniform colour survives lossy compression nearly unchanged
+        let value = ((255u32 << 24) | (50u32 << 16) | (100u32 << 8) | 200u32) as f64;
+        for i in 0..12 {
+            output.data.set_value(i, value);
+        }
+        output.write().unwrap();
+        assert!(dir.join("colour.jgw").exists());
+
+        let input = Raster::new(&file_name, "r").unwrap();
+        assert_eq!(input.configs.data_type, DataType::RGB24);
+        let v = input.get_value(1, 2) as u32;
+        assert!(((v & 0xFF) as i32 - 200).abs() <= 3);
+        assert!((((v >> 8) & 0xFF) as i32 - 100).abs() <= 3);
+        assert!((((v >> 16) & 0xFF) as i32 - 50).abs() <= 3);
+        assert!((input.configs.east - 540.0).abs() < 1e-9);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -2,29 +2,49 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 02/06/2017
-Last Modified: 09/02/2019
+Last Modified: 17/10/2026
 License: MIT
 */
 
+//! Reading and writing of raster data. The `Raster` type abstracts over each of the supported
+//! formats, which are determined from the file extension, and the format drivers are contained
+//! in the sub-modules. Stacks of co-registered rasters, e.g. the images of a time series, can be
+//! iterated over in chunks of rows using `RasterCube`, without holding each raster in memory.
+
 pub mod arcascii_raster;
 pub mod arcbinary_raster;
+pub mod aux_xml;
 pub mod geotiff;
 pub mod grass_raster;
 pub mod idrisi_raster;
+pub mod image_raster;
+pub mod netcdf_raster;
+pub mod raster_cube;
+pub mod raster_data;
 pub mod saga_raster;
 pub mod surfer7_raster;
 pub mod surfer_ascii_raster;
 pub mod whitebox_raster;
+pub mod zarr_raster;
 
 use self::arcascii_raster::*;
 use self::arcbinary_raster::*;
+pub use self::aux_xml::{
+    aux_xml_file_name, is_aux_xml_output, set_aux_xml_output, write_aux_xml,
+    AUX_XML_HISTOGRAM_BINS,
+};
 use self::geotiff::*;
 use self::grass_raster::*;
 use self::idrisi_raster::*;
+use self::image_raster::*;
+use self::netcdf_raster::*;
+pub use self::raster_cube::{CubeChunk, CubeLayer, RasterCube};
+pub use self::raster_data::RasterData;
 use self::saga_raster::*;
 use self::surfer7_raster::*;
 use self::surfer_ascii_raster::*;
 use self::whitebox_raster::*;
+use self::zarr_raster::*;
 use crate::structures::{Array2D, BoundingBox};
 use crate::utils::*;
 use std::cmp::Ordering::Equal;
@@ -43,17 +63,29 @@ use std::thread;
 
 /// Raster is a common data structure that abstracts over several raster data formats,
 /// including GeoTIFFs, ArcGIS ASCII and binary rasters, Whitebox rasters, Idrisi
-/// rasters, Saga rasters, and GRASS ASCII rasters.
+/// rasters, Saga rasters (including compressed .sg-grd-z grids), GRASS ASCII rasters, NetCDF
+/// grids, Zarr arrays, and PNG and JPEG images georeferenced by world files.
+///
+/// Cell values are stored in memory as f64 by default. Rasters that are read using `new_native`,
+/// or whose storage is set using `set_native_storage` or `set_storage_type`, instead store their
+/// values in their native data type, e.g. using one byte per cell for 8-bit imagery. Such rasters
+/// must be accessed using `get_value`, `set_value`, and the other accessor methods; indexing them,
+/// e.g. `raster[(row, col)]`, panics.
 ///
 /// Examples:
 ///
-/// ```
+/// ```no_run
+/// # use whitebox_tools::raster::Raster;
+/// # fn main() -> std::io::Result<()> {
+/// # let (input_file, output_file) = ("input.tif", "output.tif");
 /// // Read an existing raster file
 /// let input = Raster::new(&input_file, "r")?;
 ///
 /// // Create a new raster file with the dimensions
 /// // and location of an existing file.
 /// let mut output = Raster::initialize_using_file(&output_file, &input);
+/// # Ok(())
+/// # }
 /// ```
 #[derive(Default, Clone)]
 pub struct Raster {
@@ -61,9 +93,12 @@ pub struct Raster {
     pub file_mode: String,
     pub raster_type: RasterType,
     pub configs: RasterConfigs,
-    data: Vec<f64>,
+    data: RasterData,
 }
 
+const NATIVE_STORAGE_INDEX_MSG: &str =
+    "Rasters with native-typed storage cannot be indexed; use get_value and set_value instead.";
+
 impl Index<(isize, isize)> for Raster {
     type Output = f64;
 
@@ -88,7 +123,10 @@ impl Index<(isize, isize)> for Raster {
             return &self.configs.nodata;
         }
         let idx: usize = r * self.configs.columns + c;
-        &self.data[idx]
+        match self.data.as_f64_slice() {
+            Some(data) => &data[idx],
+            None => panic!("{}", NATIVE_STORAGE_INDEX_MSG),
+        }
     }
 }
 
@@ -111,7 +149,10 @@ impl IndexMut<(isize, isize)> for Raster {
             return &mut self.configs.nodata;
         }
         let idx = r * self.configs.columns + c;
-        &mut self.data[idx as usize]
+        match self.data.as_f64_slice_mut() {
+            Some(data) => &mut data[idx],
+            None => panic!("{}", NATIVE_STORAGE_INDEX_MSG),
+        }
     }
 }
 
@@ -124,6 +165,86 @@ impl Raster {
     /// To create a new `Raster` file, most applications should prefer the
     /// `initialize_using_config` or `initialize_using_file` functions instead.
     pub fn new<'a>(file_name: &'a str, file_mode: &'a str) -> Result<Raster, Error> {
+        Raster::open(file_name, file_mode, false)
+    }
+
+    /// Creates an in-memory `Raster` object, like `new`, but storing the values of an existing
+    /// file in the raster's native data type rather than as f64, which reduces memory use eight-fold
+    /// for 8-bit imagery. GeoTIFF values are decoded directly into the native storage. The
+    /// storage is widened where needed to hold the nodata value, e.g. 8-bit data with a nodata
+    /// value of -32768 are stored as 16-bit integers.
+    pub fn new_native<'a>(file_name: &'a str, file_mode: &'a str) -> Result<Raster, Error> {
+        Raster::open(file_name, file_mode, true)
+    }
+
+    /// Reads a window of an existing raster file, with `rows` rows and `columns` columns and its
+    /// upper-left cell at (`row`, `column`) of the file's grid. The returned raster covers only the
+    /// window and is read-only. For Zarr arrays, only the chunks that intersect the window are
+    /// read, such that a small window of a large array is read quickly; rasters in other formats
+    /// are read in their entirety and then cropped.
+    pub fn new_window<'a>(
+        file_name: &'a str,
+        row: usize,
+        column: usize,
+        rows: usize,
+        columns: usize,
+    ) -> Result<Raster, Error> {
+        let raster_type = get_raster_type_from_file(file_name.to_string(), "r".to_string());
+        let mut r = if raster_type == RasterType::Zarr {
+            let mut r = Raster {
+                file_name: file_name.to_string(),
+                file_mode: "r".to_string(),
+                raster_type,
+                ..Default::default()
+            };
+            let mut data: Vec<f64> = vec![];
+            read_zarr_window(
+                &r.file_name,
+                &mut r.configs,
+                &mut data,
+                Some((row, column, rows, columns)),
+            )?;
+            r.data = RasterData::from(data);
+            r
+        } else {
+            let mut r = Raster::new(file_name, "r")?;
+            if rows == 0
+                || columns == 0
+                || row + rows > r.configs.rows
+                || column + columns > r.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The window extends beyond the edges of the raster.",
+                ));
+            }
+            let mut data: Vec<f64> = Vec::with_capacity(rows * columns * r.num_bands());
+            for band in 0..r.num_bands() {
+                let band_nodata = r.get_band_nodata(band);
+                for rw in row..row + rows {
+                    for col in column..column + columns {
+                        let z = r.get_band_value(band, rw as isize, col as isize);
+                        data.push(if z == band_nodata { r.configs.nodata } else { z });
+                    }
+                }
+            }
+            r.configs.band_nodata = vec![];
+            r.data = RasterData::from(data);
+            r
+        };
+        r.configs.rows = rows;
+        r.configs.columns = columns;
+        r.configs.west += column as f64 * r.configs.resolution_x;
+        r.configs.north -= row as f64 * r.configs.resolution_y;
+        r.configs.east = r.configs.west + columns as f64 * r.configs.resolution_x;
+        r.configs.south = r.configs.north - rows as f64 * r.configs.resolution_y;
+        r.configs.display_min = f64::INFINITY;
+        r.configs.display_max = f64::NEG_INFINITY;
+        r.update_min_max();
+        Ok(r)
+    }
+
+    fn open<'a>(file_name: &'a str, file_mode: &'a str, native: bool) -> Result<Raster, Error> {
         let fm: String = file_mode.to_lowercase();
         let mut r = Raster {
             file_name: file_name.to_string(),
@@ -132,53 +253,59 @@ impl Raster {
             ..Default::default()
         };
         if r.file_mode.contains("r") {
+            let mut data: Vec<f64> = vec![];
             match get_raster_type_from_file(file_name.to_string(), fm) {
                 RasterType::ArcBinary => {
-                    let _ = read_arcbinary(&r.file_name, &mut r.configs, &mut r.data)?;
-                    return Ok(r);
+                    let _ = read_arcbinary(&r.file_name, &mut r.configs, &mut data)?;
                 }
                 RasterType::ArcAscii => {
-                    let _ = read_arcascii(&r.file_name, &mut r.configs, &mut r.data)?;
-                    return Ok(r);
+                    let _ = read_arcascii(&r.file_name, &mut r.configs, &mut data)?;
                 }
                 RasterType::GeoTiff => {
-                    let _ = read_geotiff(&r.file_name, &mut r.configs, &mut r.data)?;
+                    let _ = read_geotiff(&r.file_name, &mut r.configs, &mut r.data, native)?;
                     r.update_min_max();
                     return Ok(r);
                 }
                 RasterType::GrassAscii => {
-                    let _ = read_grass_raster(&r.file_name, &mut r.configs, &mut r.data)?;
-                    return Ok(r);
+                    let _ = read_grass_raster(&r.file_name, &mut r.configs, &mut data)?;
                 }
                 RasterType::IdrisiBinary => {
-                    let _ = read_idrisi(&r.file_name, &mut r.configs, &mut r.data)?;
-                    return Ok(r);
+                    let _ = read_idrisi(&r.file_name, &mut r.configs, &mut data)?;
+                }
+                RasterType::Jpeg => {
+                    let _ = read_jpeg(&r.file_name, &mut r.configs, &mut data)?;
+                }
+                RasterType::NetCdf => {
+                    let _ = read_netcdf(&r.file_name, &mut r.configs, &mut data)?;
+                }
+                RasterType::Png => {
+                    let _ = read_png(&r.file_name, &mut r.configs, &mut data)?;
                 }
                 RasterType::SagaBinary => {
-                    let _ = read_saga(&r.file_name, &mut r.configs, &mut r.data)?;
-                    return Ok(r);
+                    let _ = read_saga(&r.file_name, &mut r.configs, &mut data)?;
                 }
                 RasterType::Surfer7Binary => {
-                    let _ = read_surfer7(&r.file_name, &mut r.configs, &mut r.data)?;
-                    return Ok(r);
+                    let _ = read_surfer7(&r.file_name, &mut r.configs, &mut data)?;
                 }
                 RasterType::SurferAscii => {
-                    let _ = read_surfer_ascii_raster(&r.file_name, &mut r.configs, &mut r.data)?;
-                    return Ok(r);
+                    let _ = read_surfer_ascii_raster(&r.file_name, &mut r.configs, &mut data)?;
                 }
                 RasterType::Whitebox => {
-                    let _ = read_whitebox(&r.file_name, &mut r.configs, &mut r.data)?;
-                    return Ok(r);
+                    let _ = read_whitebox(&r.file_name, &mut r.configs, &mut data)?;
+                }
+                RasterType::Zarr => {
+                    let _ = read_zarr(&r.file_name, &mut r.configs, &mut data)?;
                 }
                 RasterType::Unknown => {
                     return Err(Error::new(ErrorKind::Other, "Unrecognized raster type"));
                 }
             }
-        } else {
-            // write
-            return Ok(r);
+            r.data = RasterData::from(data);
+            if native {
+                r.set_native_storage();
+            }
         }
-        // Err(Error::new(ErrorKind::Other, "Error creating raster"))
+        Ok(r)
     }
 
     /// Creates a new in-memory `Raster` object with grid extent and location
@@ -229,8 +356,11 @@ impl Raster {
         {
             output.configs.nodata = 1.71041e38;
         }
-        output.data.reserve(output.configs.rows * output.configs.columns);
-        output.data = vec![output.configs.nodata; output.configs.rows * output.configs.columns];
+        output.data = RasterData::new(
+            DataType::F64,
+            output.configs.rows * output.configs.columns,
+            output.configs.nodata,
+        );
 
         output
     }
@@ -282,8 +412,11 @@ impl Raster {
         {
             output.configs.nodata = 1.71041e38;
         }
-        output.data.reserve(output.configs.rows * output.configs.columns);
-        output.data = vec![output.configs.nodata; output.configs.rows * output.configs.columns];
+        output.data = RasterData::new(
+            DataType::F64,
+            output.configs.rows * output.configs.columns,
+            output.configs.nodata,
+        );
         output
     }
 
@@ -334,12 +467,13 @@ impl Raster {
         {
             output.configs.nodata = 1.71041e38;
         }
-        output.data.reserve_exact(output.configs.rows * output.configs.columns);
+        let mut data: Vec<f64> = Vec::with_capacity(output.configs.rows * output.configs.columns);
         for row in 0..array.rows {
             for col in 0..array.columns {
-                output.data.push(array.get_value(row, col).into());
+                data.push(array.get_value(row, col).into());
             }
         }
+        output.data = RasterData::from(data);
         output
     }
 
@@ -375,7 +509,7 @@ impl Raster {
             let r: usize = row as usize;
 
             let idx: usize = r * self.configs.columns + c;
-            return self.data[idx];
+            return self.data.get_value(idx);
         }
 
         // it's not within the area of the data
@@ -417,7 +551,7 @@ impl Raster {
             let r: usize = row as usize;
             if c < self.configs.columns && r < self.configs.rows {
                 let idx = r * self.configs.columns + c;
-                self.data[idx] = value;
+                self.data.set_value(idx, value);
             }
         }
     }
@@ -428,10 +562,11 @@ impl Raster {
             let r: usize = row as usize;
             if c < self.configs.columns && r < self.configs.rows {
                 let idx = r * self.configs.columns + c;
-                if self.data[idx] != self.configs.nodata {
-                    self.data[idx] -= value;
+                let z = self.data.get_value(idx);
+                if z != self.configs.nodata {
+                    self.data.set_value(idx, z - value);
                 } else {
-                    self.data[idx] = value;
+                    self.data.set_value(idx, value);
                 }
             }
         }
@@ -443,15 +578,204 @@ impl Raster {
             let r: usize = row as usize;
             if c < self.configs.columns && r < self.configs.rows {
                 let idx = r * self.configs.columns + c;
-                if self.data[idx] != self.configs.nodata {
-                    self.data[idx] += value;
+                let z = self.data.get_value(idx);
+                if z != self.configs.nodata {
+                    self.data.set_value(idx, z + value);
                 } else {
-                    self.data[idx] = value;
+                    self.data.set_value(idx, value);
                 }
             }
         }
     }
 
+    /// Sets the number of bands of a raster that is being created, e.g. the red, green, blue,
+    /// and near-infrared bands of a colour image. The values of all bands are initialized to
+    /// the nodata value, which all bands share. Band values are stored band-sequentially, such that the first band is
+    /// also accessed by single-band methods like `get_value` and `set_value`. Multi-band
+    /// rasters may currently only be written in the GeoTIFF format.
+    pub fn set_num_bands(&mut self, bands: u8) {
+        let bands = bands.max(1);
+        self.configs.bands = bands;
+        self.configs.band_nodata = vec![];
+        self.data = RasterData::new(
+            self.data.storage_type(),
+            self.configs.rows * self.configs.columns * bands as usize,
+            self.configs.nodata,
+        );
+    }
+
+    /// Returns the value of a cell in a band of a multi-band raster, or nodata if the cell
+    /// or band is outside of the raster.
+    pub fn get_band_value(&self, band: usize, row: isize, column: isize) -> f64 {
+        if band < self.configs.bands.max(1) as usize
+            && row >= 0
+            && column >= 0
+            && (row as usize) < self.configs.rows
+            && (column as usize) < self.configs.columns
+        {
+            let idx = (band * self.configs.rows + row as usize) * self.configs.columns + column as usize;
+            return self.data.get_value(idx);
+        }
+        self.configs.nodata
+    }
+
+    /// Sets the value of a cell in a band of a multi-band raster.
+    pub fn set_band_value(&mut self, band: usize, row: isize, column: isize, value: f64) {
+        if band < self.configs.bands.max(1) as usize
+            && row >= 0
+            && column >= 0
+            && (row as usize) < self.configs.rows
+            && (column as usize) < self.configs.columns
+        {
+            let idx = (band * self.configs.rows + row as usize) * self.configs.columns + column as usize;
+            self.data.set_value(idx, value);
+        }
+    }
+
+    /// Returns the number of bands of the raster.
+    pub fn num_bands(&self) -> usize {
+        self.configs.bands.max(1) as usize
+    }
+
+    /// Returns the nodata value of a band. Unless a band-specific value has been set using
+    /// `set_band_nodata`, each band shares the raster's nodata value.
+    pub fn get_band_nodata(&self, band: usize) -> f64 {
+        match self.configs.band_nodata.get(band) {
+            Some(nodata) => *nodata,
+            None => self.configs.nodata,
+        }
+    }
+
+    /// Sets the nodata value of a band. Since GeoTIFF files store a single nodata value, any
+    /// band-specific nodata values are replaced with the raster's nodata value when it is written.
+    pub fn set_band_nodata(&mut self, band: usize, nodata: f64) {
+        if band < self.num_bands() {
+            if self.configs.band_nodata.len() < self.num_bands() {
+                self.configs.band_nodata.resize(self.num_bands(), self.configs.nodata);
+            }
+            self.configs.band_nodata[band] = nodata;
+        }
+    }
+
+    /// Returns a copy of a band of a multi-band raster as a single-band raster, with the same
+    /// file name and configurations.
+    pub fn get_band(&self, band: usize) -> Raster {
+        let num_cells = self.num_cells();
+        let mut configs = self.configs.clone();
+        configs.bands = 1;
+        configs.nodata = self.get_band_nodata(band);
+        configs.band_nodata = vec![];
+        let mut data = RasterData::new(self.data.storage_type(), num_cells, configs.nodata);
+        if band < self.num_bands() {
+            for i in 0..num_cells {
+                data.set_value(i, self.data.get_value(band * num_cells + i));
+            }
+        }
+        let mut output = Raster {
+            file_name: self.file_name.clone(),
+            file_mode: self.file_mode.clone(),
+            raster_type: self.raster_type.clone(),
+            configs,
+            data,
+        };
+        output.update_min_max();
+        output
+    }
+
+    /// Splits a raster into single-band rasters, one for each of its bands. A single-band raster
+    /// is returned as is.
+    pub fn into_bands(self) -> Vec<Raster> {
+        if self.num_bands() == 1 {
+            return vec![self];
+        }
+        (0..self.num_bands()).map(|band| self.get_band(band)).collect()
+    }
+
+    /// Combines single-band rasters of the same dimensions into a multi-band raster, with the
+    /// file name and configurations of the first. A single raster is returned as is.
+    pub fn from_bands(mut bands: Vec<Raster>) -> Result<Raster, Error> {
+        if bands.len() == 1 {
+            return Ok(bands.pop().unwrap());
+        }
+        if bands.is_empty() || bands.len() > u8::MAX as usize {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A multi-band raster must contain between 1 and 255 bands.",
+            ));
+        }
+        let mut output = Raster {
+            file_name: bands[0].file_name.clone(),
+            file_mode: bands[0].file_mode.clone(),
+            raster_type: bands[0].raster_type.clone(),
+            configs: bands[0].configs.clone(),
+            data: RasterData::new(bands[0].data.storage_type(), 0, 0f64),
+        };
+        output.set_num_bands(bands.len() as u8);
+        for (band, raster) in bands.iter().enumerate() {
+            output.set_band(band, raster)?;
+        }
+        output.update_min_max();
+        Ok(output)
+    }
+
+    /// Sets the values of a band of a multi-band raster to those of a single-band raster of the
+    /// same dimensions. Nodata cells of the single-band raster are set to the band's nodata value.
+    pub fn set_band(&mut self, band: usize, other: &Raster) -> Result<(), Error> {
+        if self.configs.rows != other.configs.rows || self.configs.columns != other.configs.columns
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Rasters must have the same dimensions and extent.",
+            ));
+        }
+        if band >= self.num_bands() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("The raster does not contain band {}.", band + 1),
+            ));
+        }
+        let num_cells = self.num_cells();
+        let nodata = self.get_band_nodata(band);
+        let other_nodata = other.configs.nodata;
+        for (i, other_value) in other.data.iter().enumerate().take(num_cells) {
+            self.data.set_value(
+                band * num_cells + i,
+                if other_value != other_nodata {
+                    other_value
+                } else {
+                    nodata
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the minimum, maximum, mean, and standard deviation of the valid values of a band.
+    pub fn calculate_band_statistics(&self, band: usize) -> (f64, f64, f64, f64) {
+        let num_cells = self.num_cells();
+        if band >= self.num_bands() || self.data.len() < (band + 1) * num_cells {
+            return (f64::NAN, f64::NAN, f64::NAN, f64::NAN);
+        }
+        let nodata = self.get_band_nodata(band);
+        let values = self.data.get_values(band * num_cells, (band + 1) * num_cells);
+        let mut min_val = f64::INFINITY;
+        let mut max_val = f64::NEG_INFINITY;
+        let mut sum = 0f64;
+        let mut count = 0f64;
+        for &value in values.iter().filter(|v| **v != nodata) {
+            min_val = min_val.min(value);
+            max_val = max_val.max(value);
+            sum += value;
+            count += 1.0;
+        }
+        let mean = sum / count;
+        let mut sq_diff_sum = 0f64;
+        for &value in values.iter().filter(|v| **v != nodata) {
+            sq_diff_sum += (value - mean) * (value - mean);
+        }
+        (min_val, max_val, mean, (sq_diff_sum / count).sqrt())
+    }
+
     pub fn set_row_data(&mut self, row: isize, values: Vec<f64>) {
         for column in 0..values.len() {
             if row >= 0 {
@@ -459,7 +783,7 @@ impl Raster {
                 let r: usize = row as usize;
                 if c < self.configs.columns && r < self.configs.rows {
                     let idx = r * self.configs.columns + c;
-                    self.data[idx] = values[c];
+                    self.data.set_value(idx, values[c]);
                 }
             }
         }
@@ -469,7 +793,7 @@ impl Raster {
         let mut values: Vec<f64> = vec![self.configs.nodata; self.configs.columns];
         if row >= 0 && row < self.configs.rows as isize {
             for column in 0..values.len() {
-                values[column] = self.data[row as usize * self.configs.columns + column];
+                values[column] = self.data.get_value(row as usize * self.configs.columns + column);
             }
         }
         values
@@ -482,7 +806,7 @@ impl Raster {
                 let r: usize = row as usize;
                 if c < self.configs.columns && r < self.configs.rows {
                     let idx = r * self.configs.columns + c;
-                    self.data[idx] += values[c];
+                    self.data.set_value(idx, self.data.get_value(idx) + values[c]);
                 }
             }
         }
@@ -495,7 +819,7 @@ impl Raster {
                 let r: usize = row as usize;
                 if c < self.configs.columns && r < self.configs.rows {
                     let idx = r * self.configs.columns + c;
-                    self.data[idx] -= values[c];
+                    self.data.set_value(idx, self.data.get_value(idx) - values[c]);
                 }
             }
         }
@@ -560,7 +884,7 @@ impl Raster {
         for row in 0..array.rows {
             for col in 0..array.columns {
                 i = row as usize * self.configs.columns + col as usize;
-                self.data[i] = array.get_value(row, col).into();
+                self.data.set_value(i, array.get_value(row, col).into());
             }
         }
         self.configs.nodata = array.nodata().into();
@@ -568,7 +892,11 @@ impl Raster {
     }
     
     pub fn reinitialize_values(&mut self, value: f64) {
-        self.data = vec![value; self.configs.rows * self.configs.columns];
+        self.data = RasterData::new(
+            self.data.storage_type(),
+            self.configs.rows * self.configs.columns,
+            value,
+        );
     }
 
     pub fn get_value_as_rgba(&self, row: isize, column: isize) -> (u8, u8, u8, u8) {
@@ -589,7 +917,7 @@ impl Raster {
             return (0, 0, 0, 0);
         }
         let idx: usize = r * self.configs.columns + c;
-        let z = self.data[idx];
+        let z = self.data.get_value(idx);
 
         let r = (z as u32 & 0xFF) as u8;
         let g = ((z as u32 >> 8) & 0xFF) as u8;
@@ -606,15 +934,54 @@ impl Raster {
             if c < self.configs.columns && r < self.configs.rows {
                 let idx = r * self.configs.columns + c;
                 let (r, g, b, a) = rgba;
-                self.data[idx] += ((a << 24) | (b << 16) | (g << 8) | r) as f64;
+                let z = self.data.get_value(idx);
+                self.data
+                    .set_value(idx, z + ((a << 24) | (b << 16) | (g << 8) | r) as f64);
             }
         }
     }
 
     /// Returns the size of the pixel data in bytes.
     pub fn get_data_size_in_bytes(&self) -> usize {
-        use std::mem;
-        mem::size_of_val(&*self.data)
+        self.data.size_in_bytes()
+    }
+
+    /// Returns the data type in which the cell values are stored in memory.
+    pub fn storage_type(&self) -> DataType {
+        self.data.storage_type()
+    }
+
+    /// Stores the cell values in memory in the raster's native data type, e.g. one byte per cell
+    /// for 8-bit data. Colour images, including paletted images, store packed 32-bit colour values.
+    /// Values are truncated and saturated to the range of the data type when set, so the data type
+    /// of a new raster should be set beforehand. Unless the data type is F64, the raster can no
+    /// longer be indexed, i.e. `raster[(row, col)]` panics.
+    pub fn set_native_storage(&mut self) {
+        let data_type = match self.configs.photometric_interp {
+            PhotometricInterpretation::RGB | PhotometricInterpretation::Categorical => {
+                match self.configs.data_type {
+                    DataType::U8 | DataType::RGB24 | DataType::RGBA32 => DataType::U32,
+                    _ => DataType::F64,
+                }
+            }
+            _ => self.configs.data_type,
+        };
+        self.set_storage_type(data_type);
+    }
+
+    /// Sets the data type in which the cell values are stored in memory, e.g. `DataType::F32` for
+    /// a raster of real-valued outputs, converting any existing values. The storage is widened
+    /// where needed to hold the nodata values exactly, such that the nodata value should be set
+    /// before the storage type. Data types without a storage type of their own, e.g. I64, are
+    /// stored as f64, which is also the storage that indexing requires; indexing a raster with any
+    /// other storage panics.
+    pub fn set_storage_type(&mut self, data_type: DataType) {
+        let mut nodata = vec![self.configs.nodata];
+        nodata.extend(self.configs.band_nodata.iter());
+        let storage = RasterData::storage_type_for(data_type, &nodata);
+        if storage != self.data.storage_type() {
+            self.data = self.data.convert(storage);
+        }
     }
 
     pub fn get_x_from_column(&self, column: isize) -> f64 {
@@ -642,7 +1009,7 @@ impl Raster {
 
     pub fn clip_display_min_max(&mut self, percent: f64) {
         let t = (percent / 100.0 * (self.configs.rows * self.configs.columns) as f64) as usize;
-        let mut d = self.data.clone();
+        let mut d = self.data.to_f64_vec();
         d.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Equal));
         let mut sum = 0;
         for i in 0..d.len() {
@@ -669,7 +1036,7 @@ impl Raster {
 
     pub fn clip_display_min(&mut self, percent: f64) {
         let t = (percent / 100.0 * (self.configs.rows * self.configs.columns) as f64) as usize;
-        let mut d = self.data.clone();
+        let mut d = self.data.to_f64_vec();
         d.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Equal));
         let mut sum = 0;
         for i in 0..d.len() {
@@ -685,7 +1052,7 @@ impl Raster {
 
     pub fn clip_display_max(&mut self, percent: f64) {
         let t = (percent / 100.0 * (self.configs.rows * self.configs.columns) as f64) as usize;
-        let mut d = self.data.clone();
+        let mut d = self.data.to_f64_vec();
         d.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Equal));
         let mut sum = 0;
         for i in (0..d.len()).rev() {
@@ -737,7 +1104,7 @@ impl Raster {
 
     pub fn clip_min_by_percent(&mut self, percent: f64) {
         let t = (percent / 100.0 * (self.configs.rows * self.configs.columns) as f64) as usize;
-        let mut d = self.data.clone();
+        let mut d = self.data.to_f64_vec();
         d.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Equal));
         let mut sum = 0;
         let mut val = 0.0;
@@ -752,9 +1119,10 @@ impl Raster {
         }
 
         for i in 0..self.data.len() {
-            if self.data[i] != self.configs.nodata {
-                if self.data[i] < val {
-                    self.data[i] = val;
+            let z = self.data.get_value(i);
+            if z != self.configs.nodata {
+                if z < val {
+                    self.data.set_value(i, val);
                 }
             }
         }
@@ -764,7 +1132,7 @@ impl Raster {
 
     pub fn clip_max_by_percent(&mut self, percent: f64) {
         let t = (percent / 100.0 * (self.configs.rows * self.configs.columns) as f64) as usize;
-        let mut d = self.data.clone();
+        let mut d = self.data.to_f64_vec();
         d.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Equal));
         let mut sum = 0;
         let mut val = 0.0;
@@ -779,9 +1147,10 @@ impl Raster {
         }
 
         for i in 0..self.data.len() {
-            if self.data[i] != self.configs.nodata {
-                if self.data[i] > val {
-                    self.data[i] = val;
+            let z = self.data.get_value(i);
+            if z != self.configs.nodata {
+                if z > val {
+                    self.data.set_value(i, val);
                 }
             }
         }
@@ -791,7 +1160,7 @@ impl Raster {
 
     pub fn clip_min_and_max_by_percent(&mut self, percent: f64) {
         let t = (percent / 100.0 * (self.configs.rows * self.configs.columns) as f64) as usize;
-        let mut d = self.data.clone();
+        let mut d = self.data.to_f64_vec();
         d.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Equal));
         let mut sum = 0;
         let mut lower_val = 0.0;
@@ -818,11 +1187,12 @@ impl Raster {
         }
 
         for i in 0..self.data.len() {
-            if self.data[i] != self.configs.nodata {
-                if self.data[i] < lower_val {
-                    self.data[i] = lower_val;
-                } else if self.data[i] > upper_val {
-                    self.data[i] = upper_val;
+            let z = self.data.get_value(i);
+            if z != self.configs.nodata {
+                if z < lower_val {
+                    self.data.set_value(i, lower_val);
+                } else if z > upper_val {
+                    self.data.set_value(i, upper_val);
                 }
             }
         }
@@ -846,7 +1216,7 @@ impl Raster {
                 let mut max_val = f64::NEG_INFINITY;
                 let mut value: f64;
                 for i in (0..values.len()).filter(|v| v % num_procs == tid) {
-                    value = values[i];
+                    value = values.get_value(i);
                     if value != nodata {
                         if value < min_val {
                             min_val = value;
@@ -906,7 +1276,7 @@ impl Raster {
             thread::spawn(move || {
                 let mut count = 0usize;
                 for i in (0..num_cells).filter(|r| r % num_procs == tid) {
-                    if values[i] != nodata {
+                    if values.get_value(i) != nodata {
                         count += 1;
                     }
                 }
@@ -938,8 +1308,8 @@ impl Raster {
                 let mut sum = 0.0f64;
                 let mut count = 0.0f64;
                 for i in (0..num_cells).filter(|r| r % num_procs == tid) {
-                    if values[i] != nodata {
-                        sum += values[i];
+                    if values.get_value(i) != nodata {
+                        sum += values.get_value(i);
                         count += 1.0;
                     }
                 }
@@ -976,18 +1346,18 @@ impl Raster {
                 let mut sq_diff_sum = 0.0f64;
                 let mut count = 0.0f64;
                 for i in (0..num_cells).filter(|r| r % num_procs == tid) {
-                    if values[i] != nodata {
-                        sq_diff_sum += (values[i] - mean) * (values[i] - mean);
+                    if values.get_value(i) != nodata {
+                        sq_diff_sum += (values.get_value(i) - mean) * (values.get_value(i) - mean);
                         count += 1.0;
                     }
-                    tx.send((sq_diff_sum, count)).unwrap();
                 }
+                tx.send((sq_diff_sum, count)).unwrap();
             });
         }
 
         let mut sq_diff_sum = 0.0f64;
         let mut count = 0.0f64;
-        for _ in 0..num_cells {
+        for _ in 0..num_procs {
             let (s, c) = rx.recv().unwrap();
             sq_diff_sum += s;
             count += c;
@@ -1000,7 +1370,7 @@ impl Raster {
         let t = (percent / 100.0 * (self.configs.rows * self.configs.columns) as f64) as usize;
         let mut lower_tail = f64::NEG_INFINITY;
         let mut upper_tail = f64::NEG_INFINITY;
-        let mut d = self.data.clone();
+        let mut d = self.data.to_f64_vec();
         d.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Equal));
         let mut sum = 0;
         for i in 0..d.len() {
@@ -1034,6 +1404,32 @@ impl Raster {
                 "Cannot write raster that is not created in write mmode ('w').",
             ));
         }
+        if self.configs.bands > 1
+            && self.raster_type != RasterType::GeoTiff
+            && self.raster_type != RasterType::NetCdf
+            && self.raster_type != RasterType::Zarr
+        {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "Multi-band rasters may only be written in the GeoTIFF, NetCDF, and Zarr formats.",
+            ));
+        }
+        if !self.configs.band_nodata.is_empty() {
+            // a single nodata value is stored for all bands
+            let num_cells = self.num_cells();
+            let nodata = self.configs.nodata;
+            for band in 0..self.num_bands() {
+                let band_nodata = self.get_band_nodata(band);
+                if band_nodata != nodata {
+                    for i in band * num_cells..(band + 1) * num_cells {
+                        if self.data.get_value(i) == band_nodata {
+                            self.data.set_value(i, nodata);
+                        }
+                    }
+                }
+            }
+            self.configs.band_nodata = vec![];
+        }
         match self.raster_type {
             RasterType::ArcAscii => {
                 let _ = match write_arcascii(self) {
@@ -1048,7 +1444,12 @@ impl Raster {
                 };
             }
             RasterType::GeoTiff => {
-                let _ = match write_geotiff(self) {
+                let result = if self.configs.cloud_optimized || is_cog_output() {
+                    write_cog(self)
+                } else {
+                    write_geotiff(self)
+                };
+                let _ = match result {
                     Ok(_) => (),
                     Err(e) => println!("error while writing: {:?}", e),
                 };
@@ -1065,6 +1466,24 @@ impl Raster {
                     Err(e) => println!("error while writing: {:?}", e),
                 };
             }
+            RasterType::Jpeg => {
+                let _ = match write_jpeg(self) {
+                    Ok(_) => (),
+                    Err(e) => println!("error while writing: {:?}", e),
+                };
+            }
+            RasterType::NetCdf => {
+                let _ = match write_netcdf(self) {
+                    Ok(_) => (),
+                    Err(e) => println!("error while writing: {:?}", e),
+                };
+            }
+            RasterType::Png => {
+                let _ = match write_png(self) {
+                    Ok(_) => (),
+                    Err(e) => println!("error while writing: {:?}", e),
+                };
+            }
             RasterType::SagaBinary => {
                 let _ = match write_saga(self) {
                     Ok(_) => (),
@@ -1089,13 +1508,54 @@ impl Raster {
                     Err(e) => println!("error while writing: {:?}", e),
                 };
             }
+            RasterType::Zarr => {
+                let _ = match write_zarr(self) {
+                    Ok(_) => (),
+                    Err(e) => println!("error while writing: {:?}", e),
+                };
+            }
             RasterType::Unknown => {
                 return Err(Error::new(ErrorKind::Other, "Unrecognized raster type"));
             }
         }
+        if is_aux_xml_output() {
+            write_aux_xml(self, AUX_XML_HISTOGRAM_BINS)?;
+        }
         Ok(())
     }
 
+    /// Writes the values of the grid cells `cells`, given as (row, column) pairs, into the existing
+    /// file of the raster, without rewriting the remainder of the grid. This is only possible for the
+    /// uncompressed Whitebox and Esri float grid formats, in which each cell occupies a fixed position
+    /// within the data file. For all other formats, the file is left unchanged and `false` is
+    /// returned, in which case the raster must be saved using `write`. Cells outside of the grid are
+    /// ignored.
+    pub fn write_cells_in_place(&mut self, cells: &[(isize, isize)]) -> Result<bool, Error> {
+        if self.num_bands() > 1 || !Path::new(&self.file_name).exists() {
+            return Ok(false);
+        }
+        let rows = self.configs.rows as isize;
+        let columns = self.configs.columns as isize;
+        let indices: Vec<usize> = cells
+            .iter()
+            .filter(|(row, col)| *row >= 0 && *row < rows && *col >= 0 && *col < columns)
+            .map(|(row, col)| (row * columns + col) as usize)
+            .collect();
+        match self.raster_type {
+            RasterType::ArcBinary => update_arcbinary(self, &indices)?,
+            RasterType::Whitebox => update_whitebox(self, &indices)?,
+            _ => return Ok(false),
+        }
+        // any statistics sidecar would otherwise describe the unedited grid
+        let aux_file = aux_xml_file_name(&self.file_name);
+        if is_aux_xml_output() {
+            write_aux_xml(self, AUX_XML_HISTOGRAM_BINS)?;
+        } else if Path::new(&aux_file).exists() {
+            std::fs::remove_file(&aux_file)?;
+        }
+        Ok(true)
+    }
+
     pub fn add_metadata_entry(&mut self, value: String) {
         self.configs.metadata.push(value);
     }
@@ -1143,6 +1603,7 @@ pub struct RasterConfigs {
     pub columns: usize,
     pub bands: u8,
     pub nodata: f64,
+    pub band_nodata: Vec<f64>,
     pub north: f64,
     pub south: f64,
     pub east: f64,
@@ -1163,6 +1624,9 @@ pub struct RasterConfigs {
     pub xy_units: String,
     pub reflect_at_edges: bool,
     pub pixel_is_area: bool,
+    pub cloud_optimized: bool,
+    pub compression: GeoTiffCompression,
+    pub predictor: u16,
     pub epsg_code: u16,
     pub coordinate_ref_system_wkt: String,
     pub model_tiepoint: Vec<f64>,
@@ -1179,6 +1643,7 @@ impl Default for RasterConfigs {
         RasterConfigs {
             title: String::from(""),
             bands: 1,
+            band_nodata: vec![],
             rows: 0,
             columns: 0,
             nodata: -32768.0,
@@ -1202,6 +1667,9 @@ impl Default for RasterConfigs {
             xy_units: "not specified".to_string(),
             reflect_at_edges: false,
             pixel_is_area: true,
+            cloud_optimized: false,
+            compression: GeoTiffCompression::None,
+            predictor: 1u16,
             epsg_code: 0u16,
             coordinate_ref_system_wkt: "not specified".to_string(),
             model_tiepoint: vec![],
@@ -1223,10 +1691,14 @@ pub enum RasterType {
     GeoTiff,
     GrassAscii,
     IdrisiBinary,
+    Jpeg,
+    NetCdf,
+    Png,
     SagaBinary,
     Surfer7Binary,
     SurferAscii,
     Whitebox, // EsriBIL
+    Zarr,
 }
 
 impl Default for RasterType {
@@ -1236,6 +1708,14 @@ impl Default for RasterType {
 }
 
 fn get_raster_type_from_file(file_name: String, file_mode: String) -> RasterType {
+    // NetCDF file names may be followed by a variable name and slice index
+    if parse_netcdf_file_name(&file_name).is_some() {
+        return RasterType::NetCdf;
+    }
+    // as may Zarr store names, which are directories
+    if parse_zarr_file_name(&file_name).is_some() {
+        return RasterType::Zarr;
+    }
     // get the file extension
     let extension: String = match Path::new(&file_name).extension().unwrap().to_str() {
         Some(n) => n.to_string().to_lowercase(),
@@ -1252,8 +1732,12 @@ fn get_raster_type_from_file(file_name: String, file_mode: String) -> RasterType
         return RasterType::ArcBinary;
     } else if extension == "rdc" || extension == "rst" {
         return RasterType::IdrisiBinary;
-    } else if extension == "sdat" || extension == "sgrd" {
+    } else if extension == "sdat" || extension == "sgrd" || extension == "sg-grd-z" {
         return RasterType::SagaBinary;
+    } else if extension == "png" {
+        return RasterType::Png;
+    } else if is_jpeg_file(&file_name) {
+        return RasterType::Jpeg;
     } else if extension == "grd" {
         if file_mode == "r" {
             // It could be a SurferAscii or a Surfer7Binary.
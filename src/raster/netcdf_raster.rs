@@ -0,0 +1,1000 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+//! Reading and writing of NetCDF classic (CDF-1) and 64-bit offset (CDF-2) files, following the
+//! CF conventions for gridded data. NetCDF-4 files, which are HDF5 files, are not supported.
+//!
+//! A file may contain several variables, each of which may have leading dimensions, e.g. time
+//! and vertical level, in addition to its two spatial dimensions. The variable and the index of
+//! the first (usually time) dimension may be selected by appending them to the file name, e.g.
+//! `sst.nc:analysed_sst[12]`. By default, the first variable with two or more dimensions that is
+//! not a coordinate variable is read, and each slice of the leading dimensions becomes a band.
+
+use super::*;
+use crate::spatial_ref_system::esri_wkt_from_epsg;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufReader, BufWriter, Error, ErrorKind, SeekFrom};
+
+const NC_DIMENSION: u32 = 10;
+const NC_VARIABLE: u32 = 11;
+const NC_ATTRIBUTE: u32 = 12;
+
+const NC_BYTE: u32 = 1;
+const NC_CHAR: u32 = 2;
+const NC_SHORT: u32 = 3;
+const NC_INT: u32 = 4;
+const NC_FLOAT: u32 = 5;
+const NC_DOUBLE: u32 = 6;
+
+/// The default fill values, which mark missing data in variables without a `_FillValue`.
+const NC_FILL_BYTE: f64 = -127.0;
+const NC_FILL_SHORT: f64 = -32767.0;
+const NC_FILL_INT: f64 = -2147483647.0;
+const NC_FILL_FLOAT: f64 = 9.969_209_968_386_869e36;
+
+/// The nodata value used where the file's fill value is NaN, or the data are packed.
+const DEFAULT_NODATA: f64 = -32768.0;
+
+/// The name of the variable written to NetCDF files, unless another is given in the file name.
+const DEFAULT_VARIABLE_NAME: &str = "data";
+
+/// A NetCDF file name, split into the path of the file and the optional variable name and slice
+/// index that follow it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetCdfFileName {
+    pub path: String,
+    pub variable: Option<String>,
+    pub slice: Option<usize>,
+}
+
+/// Parses a NetCDF file name of the form `path.nc[:variable][[slice]]`, returning `None` if the
+/// name is not that of a NetCDF file or the selector is malformed.
+pub fn parse_netcdf_file_name(file_name: &str) -> Option<NetCdfFileName> {
+    let lower = file_name.to_lowercase();
+    let mut split = None;
+    for (i, _) in lower.match_indices(".nc") {
+        let rest = &lower[i + 3..];
+        if rest.is_empty() || rest.starts_with(':') || rest.starts_with('[') {
+            split = Some(i + 3);
+        }
+    }
+    let split = split?;
+    let path = file_name[..split].to_string();
+    let mut selector = &file_name[split..];
+    let mut variable = None;
+    let mut slice = None;
+    if selector.starts_with(':') {
+        let end = selector.find('[').unwrap_or(selector.len());
+        let name = selector[1..end].trim();
+        if name.is_empty() {
+            return None;
+        }
+        variable = Some(name.to_string());
+        selector = &selector[end..];
+    }
+    if selector.starts_with('[') {
+        if !selector.ends_with(']') {
+            return None;
+        }
+        slice = Some(selector[1..selector.len() - 1].trim().parse::<usize>().ok()?);
+    } else if !selector.is_empty() {
+        return None;
+    }
+    Some(NetCdfFileName {
+        path,
+        variable,
+        slice,
+    })
+}
+
+#[derive(Clone, Debug)]
+enum AttributeValue {
+    Text(String),
+    Numbers(Vec<f64>),
+}
+
+#[derive(Clone, Debug)]
+struct NcAttribute {
+    name: String,
+    nc_type: u32,
+    value: AttributeValue,
+}
+
+#[derive(Clone, Debug)]
+struct NcDimension {
+    name: String,
+    length: usize, // zero for the record (unlimited) dimension
+}
+
+#[derive(Clone, Debug)]
+struct NcVariable {
+    name: String,
+    dims: Vec<usize>,
+    attributes: Vec<NcAttribute>,
+    nc_type: u32,
+    begin: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+struct NcHeader {
+    num_records: usize,
+    dims: Vec<NcDimension>,
+    attributes: Vec<NcAttribute>,
+    variables: Vec<NcVariable>,
+}
+
+fn type_size(nc_type: u32) -> usize {
+    match nc_type {
+        NC_SHORT => 2,
+        NC_INT | NC_FLOAT => 4,
+        NC_DOUBLE => 8,
+        _ => 1,
+    }
+}
+
+fn padding(len: usize) -> usize {
+    (4 - len % 4) % 4
+}
+
+fn find_attribute<'a>(attributes: &'a [NcAttribute], name: &str) -> Option<&'a AttributeValue> {
+    attributes.iter().find(|a| a.name == name).map(|a| &a.value)
+}
+
+fn text_attribute(attributes: &[NcAttribute], name: &str) -> Option<String> {
+    match find_attribute(attributes, name) {
+        Some(AttributeValue::Text(s)) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn number_attribute(attributes: &[NcAttribute], name: &str) -> Option<f64> {
+    match find_attribute(attributes, name) {
+        Some(AttributeValue::Numbers(v)) if !v.is_empty() => Some(v[0]),
+        _ => None,
+    }
+}
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg.to_string())
+}
+
+fn read_name<R: Read>(reader: &mut R) -> Result<String, Error> {
+    let len = reader.read_u32::<BigEndian>()? as usize;
+    let mut buf = vec![0u8; len + padding(len)];
+    reader.read_exact(&mut buf)?;
+    buf.truncate(len);
+    Ok(String::from_utf8_lossy(&buf).to_string())
+}
+
+fn read_values<R: Read>(reader: &mut R, nc_type: u32, n: usize) -> Result<Vec<f64>, Error> {
+    let mut values = Vec::with_capacity(n);
+    for _ in 0..n {
+        values.push(match nc_type {
+            NC_BYTE => reader.read_i8()? as f64,
+            NC_SHORT => reader.read_i16::<BigEndian>()? as f64,
+            NC_INT => reader.read_i32::<BigEndian>()? as f64,
+            NC_FLOAT => reader.read_f32::<BigEndian>()? as f64,
+            NC_DOUBLE => reader.read_f64::<BigEndian>()?,
+            _ => return Err(invalid_data("Unsupported NetCDF data type.")),
+        });
+    }
+    Ok(values)
+}
+
+fn read_attributes<R: Read>(reader: &mut R) -> Result<Vec<NcAttribute>, Error> {
+    let tag = reader.read_u32::<BigEndian>()?;
+    let n = reader.read_u32::<BigEndian>()? as usize;
+    if tag != NC_ATTRIBUTE && !(tag == 0 && n == 0) {
+        return Err(invalid_data("Malformed NetCDF attribute list."));
+    }
+    let mut attributes = Vec::with_capacity(n);
+    for _ in 0..n {
+        let name = read_name(reader)?;
+        let nc_type = reader.read_u32::<BigEndian>()?;
+        let num_values = reader.read_u32::<BigEndian>()? as usize;
+        let len = num_values * type_size(nc_type);
+        let value = if nc_type == NC_CHAR {
+            let mut buf = vec![0u8; len];
+            reader.read_exact(&mut buf)?;
+            AttributeValue::Text(
+                String::from_utf8_lossy(&buf)
+                    .trim_end_matches('\0')
+                    .to_string(),
+            )
+        } else {
+            AttributeValue::Numbers(read_values(reader, nc_type, num_values)?)
+        };
+        let mut pad = vec![0u8; padding(len)];
+        reader.read_exact(&mut pad)?;
+        attributes.push(NcAttribute {
+            name,
+            nc_type,
+            value,
+        });
+    }
+    Ok(attributes)
+}
+
+fn read_header<R: Read>(reader: &mut R) -> Result<NcHeader, Error> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic[0..3] != b"CDF" {
+        if &magic[1..4] == b"HDF" {
+            return Err(invalid_data("NetCDF-4 (HDF5-based) files are not supported; convert the file to the classic format, e.g. using 'nccopy -k classic'."));
+        }
+        return Err(invalid_data("The file is not a NetCDF file."));
+    }
+    let version = magic[3];
+    if version != 1 && version != 2 {
+        return Err(invalid_data(
+            "Only the NetCDF classic and 64-bit offset formats are supported.",
+        ));
+    }
+    let mut header = NcHeader {
+        num_records: reader.read_u32::<BigEndian>()? as usize,
+        ..Default::default()
+    };
+
+    let tag = reader.read_u32::<BigEndian>()?;
+    let n = reader.read_u32::<BigEndian>()? as usize;
+    if tag != NC_DIMENSION && !(tag == 0 && n == 0) {
+        return Err(invalid_data("Malformed NetCDF dimension list."));
+    }
+    for _ in 0..n {
+        let name = read_name(reader)?;
+        let length = reader.read_u32::<BigEndian>()? as usize;
+        header.dims.push(NcDimension { name, length });
+    }
+
+    header.attributes = read_attributes(reader)?;
+
+    let tag = reader.read_u32::<BigEndian>()?;
+    let n = reader.read_u32::<BigEndian>()? as usize;
+    if tag != NC_VARIABLE && !(tag == 0 && n == 0) {
+        return Err(invalid_data("Malformed NetCDF variable list."));
+    }
+    for _ in 0..n {
+        let name = read_name(reader)?;
+        let num_dims = reader.read_u32::<BigEndian>()? as usize;
+        let mut dims = Vec::with_capacity(num_dims);
+        for _ in 0..num_dims {
+            let d = reader.read_u32::<BigEndian>()? as usize;
+            if d >= header.dims.len() {
+                return Err(invalid_data("Malformed NetCDF variable dimensions."));
+            }
+            dims.push(d);
+        }
+        let attributes = read_attributes(reader)?;
+        let nc_type = reader.read_u32::<BigEndian>()?;
+        let _vsize = reader.read_u32::<BigEndian>()?;
+        let begin = if version == 1 {
+            reader.read_u32::<BigEndian>()? as u64
+        } else {
+            reader.read_u64::<BigEndian>()?
+        };
+        header.variables.push(NcVariable {
+            name,
+            dims,
+            attributes,
+            nc_type,
+            begin,
+        });
+    }
+    Ok(header)
+}
+
+impl NcHeader {
+    fn is_record_variable(&self, v: &NcVariable) -> bool {
+        !v.dims.is_empty() && self.dims[v.dims[0]].length == 0
+    }
+
+    /// The size of each record, i.e. of one slice of each of the record variables.
+    fn record_size(&self) -> u64 {
+        let record_vars: Vec<&NcVariable> = self
+            .variables
+            .iter()
+            .filter(|v| self.is_record_variable(v))
+            .collect();
+        let slice_size = |v: &NcVariable| {
+            v.dims[1..]
+                .iter()
+                .map(|&d| self.dims[d].length)
+                .product::<usize>()
+                * type_size(v.nc_type)
+        };
+        if record_vars.len() == 1 {
+            // a single record variable is not padded
+            return slice_size(record_vars[0]) as u64;
+        }
+        record_vars
+            .iter()
+            .map(|v| {
+                let s = slice_size(v);
+                (s + padding(s)) as u64
+            })
+            .sum()
+    }
+
+    fn dim_length(&self, d: usize) -> usize {
+        if self.dims[d].length == 0 {
+            self.num_records
+        } else {
+            self.dims[d].length
+        }
+    }
+
+    /// Returns the values of the coordinate variable of a dimension, if there is one.
+    fn coordinate_values(&self, f: &mut File, d: usize) -> Result<Option<Vec<f64>>, Error> {
+        match self
+            .variables
+            .iter()
+            .find(|v| v.dims.len() == 1 && v.dims[0] == d && v.name == self.dims[d].name)
+        {
+            Some(v) if !self.is_record_variable(v) && v.nc_type != NC_CHAR => {
+                f.seek(SeekFrom::Start(v.begin))?;
+                let n = self.dims[d].length;
+                let mut reader = BufReader::new(f);
+                Ok(Some(read_values(&mut reader, v.nc_type, n)?))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+pub(super) fn is_unspecified(wkt: &str) -> bool {
+    wkt.is_empty() || wkt.to_lowercase() == "not specified"
+}
+
+pub(super) fn is_latitude(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.starts_with("lat")
+}
+
+pub(super) fn is_longitude(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.starts_with("lon")
+}
+
+pub fn read_netcdf(
+    file_name: &String,
+    configs: &mut RasterConfigs,
+    data: &mut Vec<f64>,
+) -> Result<(), Error> {
+    let parts = match parse_netcdf_file_name(file_name) {
+        Some(p) => p,
+        None => return Err(invalid_data("The NetCDF file name could not be parsed.")),
+    };
+    let mut f = File::open(&parts.path)?;
+    let header = {
+        let mut reader = BufReader::new(&mut f);
+        read_header(&mut reader)?
+    };
+    if header.num_records == 0xFFFF_FFFF {
+        return Err(invalid_data(
+            "NetCDF files with an indeterminate number of records are not supported.",
+        ));
+    }
+
+    let is_coordinate_variable =
+        |v: &NcVariable| v.dims.len() == 1 && header.dims[v.dims[0]].name == v.name;
+    let var = match &parts.variable {
+        Some(name) => match header.variables.iter().find(|v| &v.name == name) {
+            Some(v) => v,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    format!("The variable '{}' was not found in the NetCDF file.", name),
+                ))
+            }
+        },
+        None => match header
+            .variables
+            .iter()
+            .find(|v| v.dims.len() >= 2 && v.nc_type != NC_CHAR && !is_coordinate_variable(v))
+        {
+            Some(v) => v,
+            None => return Err(invalid_data("The NetCDF file contains no gridded variables.")),
+        },
+    };
+    if var.dims.len() < 2 || var.nc_type == NC_CHAR {
+        return Err(invalid_data(&format!(
+            "The NetCDF variable '{}' is not a grid.",
+            var.name
+        )));
+    }
+
+    // The last two dimensions are the spatial dimensions; the slices of any others are bands.
+    let num_dims = var.dims.len();
+    let (y_dim, x_dim) = (var.dims[num_dims - 2], var.dims[num_dims - 1]);
+    let rows = header.dim_length(y_dim);
+    let columns = header.dim_length(x_dim);
+    let leading: Vec<usize> = var.dims[..num_dims - 2]
+        .iter()
+        .map(|&d| header.dim_length(d))
+        .collect();
+    let slices_per_index: usize = leading.iter().skip(1).product();
+    let num_slices: usize = leading.iter().product();
+    let slice_range = match parts.slice {
+        Some(s) => {
+            if leading.is_empty() || s >= leading[0] {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "The slice index {} is outside of the range of the NetCDF variable '{}'.",
+                        s, var.name
+                    ),
+                ));
+            }
+            s * slices_per_index..(s + 1) * slices_per_index
+        }
+        None => 0..num_slices,
+    };
+    if slice_range.is_empty() {
+        return Err(invalid_data(&format!(
+            "The NetCDF variable '{}' contains no data.",
+            var.name
+        )));
+    }
+    if slice_range.len() > 255 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("The NetCDF variable '{}' has {} slices, more than the 255 bands supported; select a slice by appending its index to the file name, e.g. '{}:{}[0]'.", var.name, slice_range.len(), parts.path, var.name),
+        ));
+    }
+
+    // georeferencing
+    let x_coords = header.coordinate_values(&mut f, x_dim)?;
+    let y_coords = header.coordinate_values(&mut f, y_dim)?;
+    let (mut flip_columns, mut flip_rows) = (false, false);
+    configs.rows = rows;
+    configs.columns = columns;
+    match x_coords {
+        Some(x) if columns > 1 => {
+            configs.resolution_x = ((x[columns - 1] - x[0]) / (columns - 1) as f64).abs();
+            flip_columns = x[0] > x[columns - 1];
+            configs.west = x[0].min(x[columns - 1]) - configs.resolution_x / 2f64;
+        }
+        _ => {
+            configs.resolution_x = 1f64;
+            configs.west = 0f64;
+        }
+    }
+    match y_coords {
+        Some(y) if rows > 1 => {
+            configs.resolution_y = ((y[rows - 1] - y[0]) / (rows - 1) as f64).abs();
+            // rows are stored from north to south, whereas CF grids are usually south to north
+            flip_rows = y[0] < y[rows - 1];
+            configs.north = y[0].max(y[rows - 1]) + configs.resolution_y / 2f64;
+        }
+        _ => {
+            configs.resolution_y = 1f64;
+            configs.north = rows as f64;
+        }
+    }
+    configs.east = configs.west + columns as f64 * configs.resolution_x;
+    configs.south = configs.north - rows as f64 * configs.resolution_y;
+
+    // coordinate reference system
+    if let Some(grid_mapping) = text_attribute(&var.attributes, "grid_mapping") {
+        if let Some(gm) = header.variables.iter().find(|v| v.name == grid_mapping) {
+            if let Some(code) = text_attribute(&gm.attributes, "epsg_code") {
+                configs.epsg_code = code
+                    .to_lowercase()
+                    .replace("epsg:", "")
+                    .trim()
+                    .parse::<u16>()
+                    .unwrap_or(0);
+            }
+            if let Some(wkt) = text_attribute(&gm.attributes, "crs_wkt")
+                .or_else(|| text_attribute(&gm.attributes, "spatial_ref"))
+            {
+                configs.coordinate_ref_system_wkt = wkt;
+            }
+        }
+    }
+    if configs.epsg_code == 0
+        && is_unspecified(&configs.coordinate_ref_system_wkt)
+        && is_latitude(&header.dims[y_dim].name)
+        && is_longitude(&header.dims[x_dim].name)
+    {
+        configs.epsg_code = 4326;
+    }
+    if is_unspecified(&configs.coordinate_ref_system_wkt) && configs.epsg_code != 0 {
+        configs.coordinate_ref_system_wkt = esri_wkt_from_epsg(configs.epsg_code);
+    }
+    if let Some(x_var) = header
+        .variables
+        .iter()
+        .find(|v| v.dims.len() == 1 && v.dims[0] == x_dim)
+    {
+        if let Some(units) = text_attribute(&x_var.attributes, "units") {
+            configs.xy_units = if units.contains("degree") {
+                "degrees".to_string()
+            } else {
+                units
+            };
+        }
+    }
+
+    // missing data and packing
+    let unsigned = text_attribute(&var.attributes, "_Unsigned")
+        .map(|s| s.to_lowercase() == "true")
+        .unwrap_or(false);
+    let to_native = |v: f64| -> f64 {
+        match var.nc_type {
+            NC_BYTE if unsigned => v as i8 as u8 as f64,
+            NC_SHORT if unsigned => v as i16 as u16 as f64,
+            NC_FLOAT => v as f32 as f64,
+            _ => v,
+        }
+    };
+    let fill = number_attribute(&var.attributes, "_FillValue")
+        .or_else(|| number_attribute(&var.attributes, "missing_value"))
+        .map(to_native)
+        .unwrap_or(match var.nc_type {
+            NC_BYTE => NC_FILL_BYTE,
+            NC_SHORT => NC_FILL_SHORT,
+            NC_INT => NC_FILL_INT,
+            NC_FLOAT => NC_FILL_FLOAT as f32 as f64,
+            _ => NC_FILL_FLOAT,
+        });
+    let scale = number_attribute(&var.attributes, "scale_factor");
+    let offset = number_attribute(&var.attributes, "add_offset");
+    let packed = scale.is_some() || offset.is_some();
+    let (scale, offset) = (scale.unwrap_or(1f64), offset.unwrap_or(0f64));
+    configs.nodata = if packed || fill.is_nan() {
+        DEFAULT_NODATA
+    } else {
+        fill
+    };
+    configs.data_type = if packed {
+        match var
+            .attributes
+            .iter()
+            .find(|a| a.name == "scale_factor" || a.name == "add_offset")
+        {
+            Some(a) if a.nc_type == NC_FLOAT => DataType::F32,
+            _ => DataType::F64,
+        }
+    } else {
+        match (var.nc_type, unsigned) {
+            (NC_BYTE, true) => DataType::U8,
+            (NC_BYTE, false) => DataType::I8,
+            (NC_SHORT, true) => DataType::U16,
+            (NC_SHORT, false) => DataType::I16,
+            (NC_INT, _) => DataType::I32,
+            (NC_FLOAT, _) => DataType::F32,
+            _ => DataType::F64,
+        }
+    };
+    configs.photometric_interp = PhotometricInterpretation::Continuous;
+    configs.endian = Endianness::BigEndian;
+    configs.bands = slice_range.len() as u8;
+    if let Some(units) = text_attribute(&var.attributes, "units") {
+        configs.z_units = units;
+    }
+    configs.title = text_attribute(&var.attributes, "long_name")
+        .or_else(|| text_attribute(&header.attributes, "title"))
+        .unwrap_or(var.name.clone());
+    if let Some(history) = text_attribute(&header.attributes, "history") {
+        for line in history.lines().filter(|l| !l.trim().is_empty()) {
+            configs.metadata.push(line.to_string());
+        }
+    }
+
+    // read the slices
+    let size = type_size(var.nc_type);
+    let num_cells = rows * columns;
+    let is_record = header.is_record_variable(var);
+    let record_size = header.record_size();
+    data.reserve(num_cells * slice_range.len());
+    let mut buf = vec![0u8; num_cells * size];
+    for k in slice_range {
+        let position = if is_record {
+            let (record, sub_slice) = (k / slices_per_index, k % slices_per_index);
+            var.begin + record as u64 * record_size + (sub_slice * num_cells * size) as u64
+        } else {
+            var.begin + (k * num_cells * size) as u64
+        };
+        f.seek(SeekFrom::Start(position))?;
+        f.read_exact(&mut buf)?;
+        let mut reader = &buf[..];
+        let values = read_values(&mut reader, var.nc_type, num_cells)?;
+        for row in 0..rows {
+            let src_row = if flip_rows { rows - 1 - row } else { row };
+            for col in 0..columns {
+                let src_col = if flip_columns { columns - 1 - col } else { col };
+                let v = to_native(values[src_row * columns + src_col]);
+                data.push(if v == fill || v.is_nan() {
+                    configs.nodata
+                } else {
+                    v * scale + offset
+                });
+            }
+        }
+    }
+
+    configs.minimum = f64::INFINITY;
+    configs.maximum = f64::NEG_INFINITY;
+    for &v in data.iter() {
+        if v != configs.nodata {
+            configs.minimum = configs.minimum.min(v);
+            configs.maximum = configs.maximum.max(v);
+        }
+    }
+    configs.display_min = configs.minimum;
+    configs.display_max = configs.maximum;
+
+    Ok(())
+}
+
+fn write_name(buf: &mut Vec<u8>, name: &str) -> Result<(), Error> {
+    buf.write_u32::<BigEndian>(name.len() as u32)?;
+    buf.extend_from_slice(name.as_bytes());
+    buf.extend(vec![0u8; padding(name.len())]);
+    Ok(())
+}
+
+fn write_value(buf: &mut Vec<u8>, nc_type: u32, value: f64) -> Result<(), Error> {
+    match nc_type {
+        NC_BYTE => buf.write_i8(value as i64 as i8)?,
+        NC_SHORT => buf.write_i16::<BigEndian>(value as i64 as i16)?,
+        NC_INT => buf.write_i32::<BigEndian>(value as i32)?,
+        NC_FLOAT => buf.write_f32::<BigEndian>(value as f32)?,
+        _ => buf.write_f64::<BigEndian>(value)?,
+    }
+    Ok(())
+}
+
+fn write_attributes(buf: &mut Vec<u8>, attributes: &[NcAttribute]) -> Result<(), Error> {
+    if attributes.is_empty() {
+        buf.write_u64::<BigEndian>(0)?; // ABSENT
+        return Ok(());
+    }
+    buf.write_u32::<BigEndian>(NC_ATTRIBUTE)?;
+    buf.write_u32::<BigEndian>(attributes.len() as u32)?;
+    for a in attributes {
+        write_name(buf, &a.name)?;
+        buf.write_u32::<BigEndian>(a.nc_type)?;
+        match &a.value {
+            AttributeValue::Text(s) => {
+                buf.write_u32::<BigEndian>(s.len() as u32)?;
+                buf.extend_from_slice(s.as_bytes());
+                buf.extend(vec![0u8; padding(s.len())]);
+            }
+            AttributeValue::Numbers(v) => {
+                buf.write_u32::<BigEndian>(v.len() as u32)?;
+                for &value in v {
+                    write_value(buf, a.nc_type, value)?;
+                }
+                buf.extend(vec![0u8; padding(v.len() * type_size(a.nc_type))]);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn text(name: &str, value: &str) -> NcAttribute {
+    NcAttribute {
+        name: name.to_string(),
+        nc_type: NC_CHAR,
+        value: AttributeValue::Text(value.to_string()),
+    }
+}
+
+fn number(name: &str, nc_type: u32, value: f64) -> NcAttribute {
+    NcAttribute {
+        name: name.to_string(),
+        nc_type,
+        value: AttributeValue::Numbers(vec![value]),
+    }
+}
+
+fn serialize_header(header: &NcHeader, version: u8) -> Result<Vec<u8>, Error> {
+    let mut buf = vec![];
+    buf.extend_from_slice(b"CDF");
+    buf.push(version);
+    buf.write_u32::<BigEndian>(header.num_records as u32)?;
+    buf.write_u32::<BigEndian>(NC_DIMENSION)?;
+    buf.write_u32::<BigEndian>(header.dims.len() as u32)?;
+    for d in &header.dims {
+        write_name(&mut buf, &d.name)?;
+        buf.write_u32::<BigEndian>(d.length as u32)?;
+    }
+    write_attributes(&mut buf, &header.attributes)?;
+    buf.write_u32::<BigEndian>(NC_VARIABLE)?;
+    buf.write_u32::<BigEndian>(header.variables.len() as u32)?;
+    for v in &header.variables {
+        write_name(&mut buf, &v.name)?;
+        buf.write_u32::<BigEndian>(v.dims.len() as u32)?;
+        for &d in &v.dims {
+            buf.write_u32::<BigEndian>(d as u32)?;
+        }
+        write_attributes(&mut buf, &v.attributes)?;
+        buf.write_u32::<BigEndian>(v.nc_type)?;
+        let vsize = variable_size(header, v);
+        buf.write_u32::<BigEndian>(if vsize > u32::MAX as u64 {
+            u32::MAX
+        } else {
+            vsize as u32
+        })?;
+        if version == 1 {
+            buf.write_u32::<BigEndian>(v.begin as u32)?;
+        } else {
+            buf.write_u64::<BigEndian>(v.begin)?;
+        }
+    }
+    Ok(buf)
+}
+
+/// The padded size of a (non-record) variable.
+fn variable_size(header: &NcHeader, v: &NcVariable) -> u64 {
+    let n: usize = v.dims.iter().map(|&d| header.dims[d].length).product();
+    let s = n * type_size(v.nc_type);
+    (s + padding(s)) as u64
+}
+
+pub fn write_netcdf<'a>(r: &'a mut Raster) -> Result<(), Error> {
+    let parts = match parse_netcdf_file_name(&r.file_name) {
+        Some(p) => p,
+        None => return Err(invalid_data("The NetCDF file name could not be parsed.")),
+    };
+    let var_name = parts
+        .variable
+        .clone()
+        .unwrap_or(DEFAULT_VARIABLE_NAME.to_string());
+    let rows = r.configs.rows;
+    let columns = r.configs.columns;
+    let bands = r.num_bands();
+
+    // NetCDF classic files have no unsigned types, except by the _Unsigned convention
+    let nodata = r.configs.nodata;
+    let (nc_type, unsigned) =
+        match RasterData::storage_type_for(r.configs.data_type, &[nodata]) {
+            DataType::U8 => (NC_BYTE, true),
+            DataType::I16 => (NC_SHORT, false),
+            DataType::U16 => (NC_SHORT, true),
+            DataType::I32 => (NC_INT, false),
+            DataType::F32 => (NC_FLOAT, false),
+            _ => (NC_DOUBLE, false),
+        };
+
+    let geographic = r.configs.epsg_code == 4326
+        || (r.configs.west >= -180f64
+            && r.configs.east <= 360f64
+            && r.configs.south >= -90f64
+            && r.configs.north <= 90f64
+            && r.configs.xy_units.to_lowercase().contains("deg"));
+    let (x_name, y_name) = if geographic { ("lon", "lat") } else { ("x", "y") };
+
+    let mut header = NcHeader::default();
+    if bands > 1 {
+        header.dims.push(NcDimension {
+            name: "band".to_string(),
+            length: bands,
+        });
+    }
+    header.dims.push(NcDimension {
+        name: y_name.to_string(),
+        length: rows,
+    });
+    header.dims.push(NcDimension {
+        name: x_name.to_string(),
+        length: columns,
+    });
+    let (y_dim, x_dim) = (header.dims.len() - 2, header.dims.len() - 1);
+
+    header.attributes.push(text("Conventions", "CF-1.6"));
+    if !r.configs.title.is_empty() {
+        header.attributes.push(text("title", &r.configs.title));
+    }
+    header.attributes.push(text("source", "WhiteboxTools"));
+    if !r.configs.metadata.is_empty() {
+        header
+            .attributes
+            .push(text("history", &r.configs.metadata.join("\n")));
+    }
+
+    let coordinate_attributes = |axis: &str| {
+        let mut attributes = vec![];
+        if geographic {
+            if axis == "X" {
+                attributes.push(text("standard_name", "longitude"));
+                attributes.push(text("units", "degrees_east"));
+            } else {
+                attributes.push(text("standard_name", "latitude"));
+                attributes.push(text("units", "degrees_north"));
+            }
+        } else {
+            attributes.push(text(
+                "standard_name",
+                if axis == "X" {
+                    "projection_x_coordinate"
+                } else {
+                    "projection_y_coordinate"
+                },
+            ));
+            if !r.configs.xy_units.is_empty()
+                && r.configs.xy_units.to_lowercase() != "not specified"
+            {
+                attributes.push(text("units", &r.configs.xy_units));
+            }
+        }
+        attributes.push(text("axis", axis));
+        attributes
+    };
+    header.variables.push(NcVariable {
+        name: x_name.to_string(),
+        dims: vec![x_dim],
+        attributes: coordinate_attributes("X"),
+        nc_type: NC_DOUBLE,
+        begin: 0,
+    });
+    header.variables.push(NcVariable {
+        name: y_name.to_string(),
+        dims: vec![y_dim],
+        attributes: coordinate_attributes("Y"),
+        nc_type: NC_DOUBLE,
+        begin: 0,
+    });
+    if bands > 1 {
+        header.variables.push(NcVariable {
+            name: "band".to_string(),
+            dims: vec![0],
+            attributes: vec![text("long_name", "band number")],
+            nc_type: NC_INT,
+            begin: 0,
+        });
+    }
+
+    let has_wkt = !is_unspecified(&r.configs.coordinate_ref_system_wkt);
+    let has_crs = r.configs.epsg_code != 0 || has_wkt;
+    if has_crs {
+        let mut attributes = vec![];
+        if r.configs.epsg_code != 0 {
+            attributes.push(text(
+                "epsg_code",
+                &format!("EPSG:{}", r.configs.epsg_code),
+            ));
+        }
+        if has_wkt {
+            attributes.push(text("crs_wkt", &r.configs.coordinate_ref_system_wkt));
+            attributes.push(text("spatial_ref", &r.configs.coordinate_ref_system_wkt));
+        }
+        attributes.push(text(
+            "GeoTransform",
+            &format!(
+                "{} {} 0 {} 0 {}",
+                r.configs.west, r.configs.resolution_x, r.configs.north, -r.configs.resolution_y
+            ),
+        ));
+        header.variables.push(NcVariable {
+            name: "crs".to_string(),
+            dims: vec![],
+            attributes,
+            nc_type: NC_INT,
+            begin: 0,
+        });
+    }
+
+    let mut attributes = vec![];
+    attributes.push(text("long_name", &var_name));
+    if !r.configs.z_units.is_empty() && r.configs.z_units.to_lowercase() != "not specified" {
+        attributes.push(text("units", &r.configs.z_units));
+    }
+    if unsigned {
+        attributes.push(text("_Unsigned", "true"));
+    }
+    // the fill value is stored in its raw, i.e. signed, form
+    let raw = |v: f64| match (nc_type, unsigned) {
+        (NC_BYTE, true) => v as u8 as i8 as f64,
+        (NC_SHORT, true) => v as u16 as i16 as f64,
+        _ => v,
+    };
+    attributes.push(number("_FillValue", nc_type, raw(nodata)));
+    if has_crs {
+        attributes.push(text("grid_mapping", "crs"));
+    }
+    let mut dims = vec![y_dim, x_dim];
+    if bands > 1 {
+        dims.insert(0, 0);
+    }
+    header.variables.push(NcVariable {
+        name: var_name.clone(),
+        dims,
+        attributes,
+        nc_type,
+        begin: 0,
+    });
+
+    // lay out the variables after the header
+    let total: u64 = header
+        .variables
+        .iter()
+        .map(|v| variable_size(&header, v))
+        .sum();
+    let version = if total < (1u64 << 31) { 1 } else { 2 };
+    let mut begin = serialize_header(&header, version)?.len() as u64;
+    for i in 0..header.variables.len() {
+        header.variables[i].begin = begin;
+        begin += variable_size(&header, &header.variables[i]);
+    }
+
+    let f = File::create(&parts.path)?;
+    let mut writer = BufWriter::new(f);
+    writer.write_all(&serialize_header(&header, version)?)?;
+
+    let mut buf = vec![];
+    for v in header.variables.iter() {
+        buf.clear();
+        if v.name == x_name && v.dims == vec![x_dim] {
+            for col in 0..columns {
+                write_value(&mut buf, NC_DOUBLE, r.get_x_from_column(col as isize))?;
+            }
+        } else if v.name == y_name && v.dims == vec![y_dim] {
+            for row in 0..rows {
+                write_value(&mut buf, NC_DOUBLE, r.get_y_from_row(row as isize))?;
+            }
+        } else if v.name == "band" && v.dims == vec![0] && bands > 1 {
+            for b in 0..bands {
+                write_value(&mut buf, NC_INT, (b + 1) as f64)?;
+            }
+        } else if v.dims.is_empty() {
+            write_value(&mut buf, NC_INT, 0f64)?;
+        } else {
+            for band in 0..bands {
+                let band_nodata = r.get_band_nodata(band);
+                for row in 0..rows as isize {
+                    buf.clear();
+                    for col in 0..columns as isize {
+                        let z = r.get_band_value(band, row, col);
+                        let z = if z == band_nodata { nodata } else { z };
+                        write_value(&mut buf, nc_type, raw(z))?;
+                    }
+                    writer.write_all(&buf)?;
+                }
+            }
+            let s = rows * columns * bands * type_size(nc_type);
+            writer.write_all(&vec![0u8; padding(s)])?;
+            continue;
+        }
+        let s = buf.len();
+        buf.extend(vec![0u8; padding(s)]);
+        writer.write_all(&buf)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_netcdf_file_name;
+
+    #[test]
+    fn test_parse_netcdf_file_name() {
+        let p = parse_netcdf_file_name("/data/sst.nc").unwrap();
+        assert_eq!(p.path, "/data/sst.nc");
+        assert_eq!((p.variable, p.slice), (None, None));
+
+        let p = parse_netcdf_file_name("C:\\data\\air.NC:air_temp[12]").unwrap();
+        assert_eq!(p.path, "C:\\data\\air.NC");
+        assert_eq!(p.variable, Some("air_temp".to_string()));
+        assert_eq!(p.slice, Some(12));
+
+        let p = parse_netcdf_file_name("/data/precip.nc[3]").unwrap();
+        assert_eq!((p.variable, p.slice), (None, Some(3)));
+
+        assert!(parse_netcdf_file_name("/data/dem.tif").is_none());
+        assert!(parse_netcdf_file_name("/data/x.ncx").is_none());
+        assert!(parse_netcdf_file_name("/data/x.nc[a]").is_none());
+    }
+}
@@ -0,0 +1,469 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::{Raster, RasterConfigs};
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static CACHE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A layer of a `RasterCube`, i.e. one time step of a time series or one band of a stack.
+#[derive(Clone, Debug)]
+pub struct CubeLayer {
+    /// The raster file containing the layer.
+    pub file_name: String,
+    /// The position of the layer along the time/band dimension. Unless specified by the cube
+    /// definition file, this is the zero-based index of the layer.
+    pub time: f64,
+    /// A label describing the layer, e.g. an acquisition date.
+    pub label: String,
+}
+
+/// `RasterCube` is a three-dimensional (row, column, time/band) stack of co-registered rasters,
+/// e.g. the images of a time series or the bands of a multi-spectral dataset. Rather than holding
+/// each of the layers in memory, the cube reads each layer once, when it is created, into a
+/// band-sequential cache file, and then serves slices of the cube from the cache on demand. Tools
+/// can therefore iterate over the cube in chunks of rows, with the memory requirement determined
+/// by the chunk size rather than by the number of layers. The cache file is removed when the cube
+/// is dropped.
+///
+/// All layers must have the same number of rows and columns and the same extent. NoData values in
+/// each layer are converted to the NoData value of the cube, which is that of the first layer.
+///
+/// Examples:
+///
+/// ```no_run
+/// # use whitebox_tools::raster::RasterCube;
+/// # fn main() -> std::io::Result<()> {
+/// // A cube defined by a list of files...
+/// let files = vec!["ndvi_2019.tif".to_string(), "ndvi_2020.tif".to_string()];
+/// let cube = RasterCube::new(&files)?;
+///
+/// // ...or by a GDAL-style VRT, or a text file (.lst) listing one 'file,time' per line
+/// let cube = RasterCube::from_definition_file("ndvi_series.vrt")?;
+///
+/// // Iterate over the cube in chunks of rows
+/// for chunk in cube.chunks(cube.default_chunk_rows()) {
+///     let chunk = chunk?;
+///     for row in chunk.start_row..chunk.end_row() {
+///         for col in 0..cube.columns() {
+///             let series = chunk.series(row, col); // one value per layer
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct RasterCube {
+    /// The configurations of the first layer, which describe the extent of the cube.
+    pub configs: RasterConfigs,
+    pub layers: Vec<CubeLayer>,
+    cache_file: PathBuf,
+}
+
+impl RasterCube {
+    /// Creates a cube from a list of raster files. The layer times are the layer indices.
+    pub fn new(file_names: &[String]) -> Result<RasterCube, Error> {
+        let layers = file_names
+            .iter()
+            .enumerate()
+            .map(|(i, f)| CubeLayer {
+                file_name: f.clone(),
+                time: i as f64,
+                label: String::new(),
+            })
+            .collect();
+        RasterCube::from_layers(layers)
+    }
+
+    /// Creates a cube from a definition file, which is either a GDAL-style virtual raster (.vrt)
+    /// listing the source file of each band, or a text file (.lst) with one layer per line. Each line
+    /// of a text file contains a raster file name and, optionally, a comma-separated time value,
+    /// which is either a number or an ISO date (YYYY-MM-DD), e.g.:
+    ///
+    /// ```text
+    /// # file, time
+    /// ndvi_2019.tif, 2019-06-15
+    /// ndvi_2020.tif, 2020-06-12
+    /// ```
+    ///
+    /// Dates are converted to decimal years. Relative file names are resolved relative to the
+    /// directory containing the definition file.
+    pub fn from_definition_file(file_name: &str) -> Result<RasterCube, Error> {
+        let contents = fs::read_to_string(file_name)?;
+        let dir = Path::new(file_name)
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_default();
+        let entries = if file_name.to_lowercase().ends_with(".vrt") {
+            parse_vrt(&contents)?
+        } else {
+            parse_layer_list(&contents)
+        };
+        if entries.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "The cube definition file {} does not list any layers.",
+                    file_name
+                ),
+            ));
+        }
+        let mut layers = Vec::with_capacity(entries.len());
+        for (i, (source, label)) in entries.into_iter().enumerate() {
+            let path = Path::new(&source);
+            let file_name = if path.is_absolute() {
+                source.clone()
+            } else {
+                dir.join(path).to_string_lossy().to_string()
+            };
+            let time = if label.is_empty() {
+                i as f64
+            } else {
+                parse_time(&label).unwrap_or(i as f64)
+            };
+            layers.push(CubeLayer {
+                file_name: file_name,
+                time: time,
+                label: label,
+            });
+        }
+        RasterCube::from_layers(layers)
+    }
+
+    /// Returns true if a file name is a cube definition file, rather than a raster.
+    pub fn is_definition_file(file_name: &str) -> bool {
+        let f = file_name.to_lowercase();
+        f.ends_with(".vrt") || f.ends_with(".lst")
+    }
+
+    /// Creates a cube from a list of layers, reading each layer into the cache file.
+    pub fn from_layers(layers: Vec<CubeLayer>) -> Result<RasterCube, Error> {
+        if layers.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A raster cube must contain at least one layer.",
+            ));
+        }
+        let cache_file = env::temp_dir().join(format!(
+            "wbt_cube_{}_{}_{}.bin",
+            process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0),
+            CACHE_COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        let mut cube = RasterCube {
+            configs: RasterConfigs::default(),
+            layers: layers,
+            cache_file: cache_file,
+        };
+
+        let mut writer = BufWriter::new(File::create(&cube.cache_file)?);
+        for i in 0..cube.layers.len() {
+            let input = Raster::new(&cube.layers[i].file_name, "r")?;
+            if i == 0 {
+                cube.configs = input.configs.clone();
+            } else {
+                cube.check_layer_extent(&input)?;
+            }
+            let in_nodata = input.configs.nodata;
+            let nodata = cube.configs.nodata;
+            for row in 0..cube.configs.rows as isize {
+                for value in input.get_row_data(row) {
+                    let value = if value == in_nodata { nodata } else { value };
+                    writer.write_all(&value.to_le_bytes())?;
+                }
+            }
+        }
+        writer.flush()?;
+
+        Ok(cube)
+    }
+
+    fn check_layer_extent(&self, input: &Raster) -> Result<(), Error> {
+        let c = &input.configs;
+        if c.rows != self.configs.rows
+            || c.columns != self.configs.columns
+            || (c.north - self.configs.north).abs() > 0.5 * self.configs.resolution_y
+            || (c.west - self.configs.west).abs() > 0.5 * self.configs.resolution_x
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The layers of a raster cube must have the same number of rows and columns and spatial extent; {} differs from {}.",
+                    input.file_name, self.layers[0].file_name
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn rows(&self) -> usize {
+        self.configs.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.configs.columns
+    }
+
+    pub fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn nodata(&self) -> f64 {
+        self.configs.nodata
+    }
+
+    /// Returns the times of the layers.
+    pub fn times(&self) -> Vec<f64> {
+        self.layers.iter().map(|l| l.time).collect()
+    }
+
+    /// Returns the number of rows in a chunk such that the chunk occupies roughly 256 MB.
+    pub fn default_chunk_rows(&self) -> usize {
+        let bytes_per_row = (self.columns() * self.num_layers() * 8).max(1);
+        (256 * 1024 * 1024 / bytes_per_row).clamp(1, self.rows().max(1))
+    }
+
+    /// Reads the values of a range of rows for one layer, in row-major order.
+    pub fn get_layer_rows(
+        &self,
+        layer: usize,
+        start_row: usize,
+        num_rows: usize,
+    ) -> Result<Vec<f64>, Error> {
+        if layer >= self.num_layers() || start_row + num_rows > self.rows() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The requested slice lies outside of the raster cube.",
+            ));
+        }
+        let columns = self.columns();
+        let offset = ((layer * self.rows() + start_row) * columns * 8) as u64;
+        let mut buffer = vec![0u8; num_rows * columns * 8];
+        let mut f = File::open(&self.cache_file)?;
+        f.seek(SeekFrom::Start(offset))?;
+        f.read_exact(&mut buffer)?;
+        Ok(buffer
+            .chunks_exact(8)
+            .map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+            .collect())
+    }
+
+    /// Reads a layer of the cube into a new in-memory raster, e.g. to use as an output template.
+    pub fn get_layer(&self, layer: usize) -> Result<Raster, Error> {
+        let data = self.get_layer_rows(layer, 0, self.rows())?;
+        let mut output =
+            Raster::initialize_using_config(&self.layers[layer].file_name, &self.configs);
+        let columns = self.columns();
+        for row in 0..self.rows() {
+            output.set_row_data(
+                row as isize,
+                data[row * columns..(row + 1) * columns].to_vec(),
+            );
+        }
+        Ok(output)
+    }
+
+    /// Reads a chunk of rows, across all layers.
+    pub fn get_chunk(&self, start_row: usize, num_rows: usize) -> Result<CubeChunk, Error> {
+        let num_rows = num_rows.min(self.rows().saturating_sub(start_row));
+        let num_layers = self.num_layers();
+        let n = num_rows * self.columns();
+        let mut data = vec![0f64; n * num_layers];
+        for layer in 0..num_layers {
+            let values = self.get_layer_rows(layer, start_row, num_rows)?;
+            for (i, v) in values.into_iter().enumerate() {
+                data[i * num_layers + layer] = v;
+            }
+        }
+        Ok(CubeChunk {
+            start_row: start_row,
+            num_rows: num_rows,
+            columns: self.columns(),
+            num_layers: num_layers,
+            data: data,
+        })
+    }
+
+    /// Returns an iterator over the cube in chunks of `rows_per_chunk` rows.
+    pub fn chunks(&self, rows_per_chunk: usize) -> CubeChunks<'_> {
+        CubeChunks {
+            cube: self,
+            next_row: 0,
+            rows_per_chunk: rows_per_chunk.max(1),
+        }
+    }
+
+    /// Reads the series of values, one per layer, at a grid cell.
+    pub fn get_series(&self, row: usize, column: usize) -> Result<Vec<f64>, Error> {
+        let chunk = self.get_chunk(row, 1)?;
+        Ok(chunk.series(row, column).to_vec())
+    }
+}
+
+impl Drop for RasterCube {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.cache_file);
+    }
+}
+
+/// A chunk of consecutive rows of a `RasterCube`. The values of each grid cell are stored
+/// contiguously, so that the series of a cell can be accessed as a slice.
+pub struct CubeChunk {
+    pub start_row: usize,
+    pub num_rows: usize,
+    pub columns: usize,
+    pub num_layers: usize,
+    data: Vec<f64>,
+}
+
+impl CubeChunk {
+    /// Returns the row following the last row of the chunk.
+    pub fn end_row(&self) -> usize {
+        self.start_row + self.num_rows
+    }
+
+    /// Returns the values of a grid cell, one per layer. `row` is a row of the cube and must
+    /// lie within the chunk.
+    pub fn series(&self, row: usize, column: usize) -> &[f64] {
+        let i = ((row - self.start_row) * self.columns + column) * self.num_layers;
+        &self.data[i..i + self.num_layers]
+    }
+
+    pub fn get_value(&self, layer: usize, row: usize, column: usize) -> f64 {
+        self.series(row, column)[layer]
+    }
+}
+
+/// An iterator over the chunks of a `RasterCube`; see `RasterCube::chunks`.
+pub struct CubeChunks<'a> {
+    cube: &'a RasterCube,
+    next_row: usize,
+    rows_per_chunk: usize,
+}
+
+impl<'a> Iterator for CubeChunks<'a> {
+    type Item = Result<CubeChunk, Error>;
+
+    fn next(&mut self) -> Option<Result<CubeChunk, Error>> {
+        if self.next_row >= self.cube.rows() {
+            return None;
+        }
+        let start_row = self.next_row;
+        self.next_row += self.rows_per_chunk;
+        Some(self.cube.get_chunk(start_row, self.rows_per_chunk))
+    }
+}
+
+/// Parses a text layer list, returning the file name and time label of each layer.
+fn parse_layer_list(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| {
+            let mut parts = l.splitn(2, ',');
+            let file = parts.next().unwrap_or("").trim().to_string();
+            let label = parts.next().unwrap_or("").trim().to_string();
+            (file, label)
+        })
+        .collect()
+}
+
+/// Parses a GDAL virtual raster (.vrt), returning the source file name and description of each
+/// band. Each band must be the first band of a single source raster.
+fn parse_vrt(contents: &str) -> Result<Vec<(String, String)>, Error> {
+    let mut layers = vec![];
+    for band in contents.split("<VRTRasterBand").skip(1) {
+        let band = band.split("</VRTRasterBand>").next().unwrap_or("");
+        let source = match element_text(band, "SourceFilename") {
+            Some(s) => s,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "A VRT band does not contain a SourceFilename element.",
+                ))
+            }
+        };
+        if let Some(b) = element_text(band, "SourceBand") {
+            if b.trim() != "1" {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "The VRT band sourced from band {} of {} is not supported; only single-band sources can be used.",
+                        b, source
+                    ),
+                ));
+            }
+        }
+        let label = element_text(band, "Description").unwrap_or_default();
+        layers.push((source, label));
+    }
+    Ok(layers)
+}
+
+/// Returns the text of the first element with a given tag name.
+fn element_text(xml: &str, tag: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}", tag))?;
+    let text_start = start + xml[start..].find('>')? + 1;
+    let text_end = text_start + xml[text_start..].find(&format!("</{}>", tag))?;
+    Some(
+        xml[text_start..text_end]
+            .trim()
+            .replace("&amp;", "&")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">"),
+    )
+}
+
+/// Parses a time value, which is either a number or an ISO date (YYYY-MM-DD), converted to a
+/// decimal year.
+fn parse_time(s: &str) -> Option<f64> {
+    if let Ok(t) = s.trim().parse::<f64>() {
+        return Some(t);
+    }
+    let date = s.trim().get(0..10)?;
+    let parts: Vec<&str> = date.split('-').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let year = parts[0].parse::<i32>().ok()?;
+    let month = parts[1].parse::<usize>().ok()?;
+    let day = parts[2].parse::<usize>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let is_leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let month_days = [
+        31,
+        if is_leap { 29 } else { 28 },
+        31,
+        30,
+        31,
+        30,
+        31,
+        31,
+        30,
+        31,
+        30,
+        31,
+    ];
+    let day_of_year = month_days[..month - 1].iter().sum::<usize>() + day - 1;
+    let days_in_year = if is_leap { 366f64 } else { 365f64 };
+    Some(year as f64 + day_of_year as f64 / days_in_year)
+}
@@ -0,0 +1,223 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+//! The in-memory cell values of a raster. Values are stored as f64 by default, but they may also
+//! be stored in the native data type of the raster, e.g. one byte per cell for 8-bit imagery,
+//! with values converted to and from f64 as they are accessed.
+
+use super::DataType;
+use std::mem;
+
+/// The cell values of a raster, stored in one of several data types.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RasterData {
+    F64(Vec<f64>),
+    F32(Vec<f32>),
+    I32(Vec<i32>),
+    U32(Vec<u32>),
+    I16(Vec<i16>),
+    U16(Vec<u16>),
+    U8(Vec<u8>),
+}
+
+impl Default for RasterData {
+    fn default() -> RasterData {
+        RasterData::F64(vec![])
+    }
+}
+
+impl From<Vec<f64>> for RasterData {
+    fn from(values: Vec<f64>) -> RasterData {
+        RasterData::F64(values)
+    }
+}
+
+impl RasterData {
+    /// Creates `len` values, each initialized to `value`, stored in the data type `storage`. Data
+    /// types that have no storage of their own, e.g. I64 and RGB48, are stored as f64.
+    pub fn new(storage: DataType, len: usize, value: f64) -> RasterData {
+        match storage {
+            DataType::F32 => RasterData::F32(vec![value as f32; len]),
+            DataType::I32 => RasterData::I32(vec![value as i32; len]),
+            DataType::U32 => RasterData::U32(vec![value as u32; len]),
+            DataType::I16 => RasterData::I16(vec![value as i16; len]),
+            DataType::U16 => RasterData::U16(vec![value as u16; len]),
+            DataType::U8 => RasterData::U8(vec![value as u8; len]),
+            _ => RasterData::F64(vec![value; len]),
+        }
+    }
+
+    /// Returns the smallest storage type that holds all values of the raster data type `data_type`
+    /// and each of the `nodata` values exactly. For example, 8-bit data are stored as U8, unless the
+    /// nodata value is negative, in which case they are stored as I16.
+    pub fn storage_type_for(data_type: DataType, nodata: &[f64]) -> DataType {
+        let candidates: &[DataType] = match data_type {
+            DataType::U8 => &[DataType::U8, DataType::I16, DataType::I32],
+            DataType::I8 | DataType::I16 => &[DataType::I16, DataType::I32],
+            DataType::U16 => &[DataType::U16, DataType::I32],
+            DataType::I32 => &[DataType::I32],
+            DataType::U32 | DataType::RGB24 | DataType::RGBA32 => &[DataType::U32],
+            DataType::F32 => &[DataType::F32],
+            _ => &[],
+        };
+        for storage in candidates {
+            let representable = nodata.iter().all(|&v| {
+                let stored = RasterData::new(*storage, 1, v).get_value(0);
+                stored == v || (v.is_nan() && stored.is_nan())
+            });
+            if representable {
+                return *storage;
+            }
+        }
+        DataType::F64
+    }
+
+    /// Returns the data type in which the values are stored.
+    pub fn storage_type(&self) -> DataType {
+        match self {
+            RasterData::F64(_) => DataType::F64,
+            RasterData::F32(_) => DataType::F32,
+            RasterData::I32(_) => DataType::I32,
+            RasterData::U32(_) => DataType::U32,
+            RasterData::I16(_) => DataType::I16,
+            RasterData::U16(_) => DataType::U16,
+            RasterData::U8(_) => DataType::U8,
+        }
+    }
+
+    /// Returns a copy of the values, stored in the data type `storage`.
+    pub fn convert(&self, storage: DataType) -> RasterData {
+        let mut output = RasterData::new(storage, self.len(), 0f64);
+        for i in 0..self.len() {
+            output.set_value(i, self.get_value(i));
+        }
+        output
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        match self {
+            RasterData::F64(v) => v.len(),
+            RasterData::F32(v) => v.len(),
+            RasterData::I32(v) => v.len(),
+            RasterData::U32(v) => v.len(),
+            RasterData::I16(v) => v.len(),
+            RasterData::U16(v) => v.len(),
+            RasterData::U8(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the size of the values in memory, in bytes.
+    pub fn size_in_bytes(&self) -> usize {
+        match self {
+            RasterData::F64(v) => mem::size_of_val(&v[..]),
+            RasterData::F32(v) => mem::size_of_val(&v[..]),
+            RasterData::I32(v) => mem::size_of_val(&v[..]),
+            RasterData::U32(v) => mem::size_of_val(&v[..]),
+            RasterData::I16(v) => mem::size_of_val(&v[..]),
+            RasterData::U16(v) => mem::size_of_val(&v[..]),
+            RasterData::U8(v) => mem::size_of_val(&v[..]),
+        }
+    }
+
+    /// Returns the value at index `i`.
+    #[inline]
+    pub fn get_value(&self, i: usize) -> f64 {
+        match self {
+            RasterData::F64(v) => v[i],
+            RasterData::F32(v) => v[i] as f64,
+            RasterData::I32(v) => v[i] as f64,
+            RasterData::U32(v) => v[i] as f64,
+            RasterData::I16(v) => v[i] as f64,
+            RasterData::U16(v) => v[i] as f64,
+            RasterData::U8(v) => v[i] as f64,
+        }
+    }
+
+    /// Sets the value at index `i`. As when writing a raster file, values are truncated, and
+    /// saturated at the limits of the storage type, when stored as integers.
+    #[inline]
+    pub fn set_value(&mut self, i: usize, value: f64) {
+        match self {
+            RasterData::F64(v) => v[i] = value,
+            RasterData::F32(v) => v[i] = value as f32,
+            RasterData::I32(v) => v[i] = value as i32,
+            RasterData::U32(v) => v[i] = value as u32,
+            RasterData::I16(v) => v[i] = value as i16,
+            RasterData::U16(v) => v[i] = value as u16,
+            RasterData::U8(v) => v[i] = value as u8,
+        }
+    }
+
+    /// Sets all of the values to `value`.
+    pub fn fill(&mut self, value: f64) {
+        *self = RasterData::new(self.storage_type(), self.len(), value);
+    }
+
+    /// Returns the values from index `start` to `end` (exclusive), as f64.
+    pub fn get_values(&self, start: usize, end: usize) -> Vec<f64> {
+        (start..end).map(|i| self.get_value(i)).collect()
+    }
+
+    /// Returns a copy of all of the values, as f64.
+    pub fn to_f64_vec(&self) -> Vec<f64> {
+        self.get_values(0, self.len())
+    }
+
+    /// Returns an iterator over the values, as f64.
+    pub fn iter(&self) -> impl Iterator<Item = f64> + '_ {
+        (0..self.len()).map(move |i| self.get_value(i))
+    }
+
+    /// Returns the values as a slice, if they are stored as f64.
+    pub fn as_f64_slice(&self) -> Option<&[f64]> {
+        match self {
+            RasterData::F64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the values as a mutable slice, if they are stored as f64.
+    pub fn as_f64_slice_mut(&mut self) -> Option<&mut [f64]> {
+        match self {
+            RasterData::F64(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RasterData;
+    use crate::raster::DataType;
+
+    #[test]
+    fn test_native_storage_round_trip() {
+        let mut data = RasterData::new(DataType::U8, 4, 0.0);
+        data.set_value(1, 200.0);
+        data.set_value(2, 300.0);
+        assert_eq!(data.get_value(1), 200.0);
+        assert_eq!(data.get_value(2), 255.0);
+        assert_eq!(data.size_in_bytes(), 4);
+        assert_eq!(data.convert(DataType::F64).to_f64_vec(), vec![0.0, 200.0, 255.0, 0.0]);
+    }
+
+    #[test]
+    fn test_storage_type_for_nodata() {
+        assert_eq!(RasterData::storage_type_for(DataType::U8, &[0.0]), DataType::U8);
+        assert_eq!(RasterData::storage_type_for(DataType::U8, &[-32768.0]), DataType::I16);
+        assert_eq!(RasterData::storage_type_for(DataType::U16, &[-32768.0]), DataType::I32);
+        assert_eq!(RasterData::storage_type_for(DataType::F32, &[f64::NAN]), DataType::F32);
+        assert_eq!(RasterData::storage_type_for(DataType::F32, &[1.71041e38]), DataType::F64);
+        assert_eq!(RasterData::storage_type_for(DataType::I64, &[-32768.0]), DataType::F64);
+    }
+}
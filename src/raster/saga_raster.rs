@@ -1,379 +1,247 @@
 use super::*;
+use crate::spatial_ref_system::esri_wkt_from_epsg;
 use crate::utils::ByteOrderReader;
 use std::f64;
+use std::fs;
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::{BufReader, BufWriter, Cursor, Error, ErrorKind, SeekFrom};
-use std::mem;
+use std::io::{BufReader, Cursor, Error, ErrorKind, SeekFrom};
 use std::path::Path;
+use zip::read::ZipArchive;
+use zip::write::{FileOptions, ZipWriter};
+use zip::CompressionMethod;
+
+/// Returns true if a file name refers to a compressed SAGA grid (.sg-grd-z), i.e. a zip
+/// archive containing the .sgrd header, .sdat data, and .prj projection files of a grid.
+pub fn is_compressed_saga(file_name: &str) -> bool {
+    file_name.to_lowercase().ends_with(".sg-grd-z")
+}
 
 pub fn read_saga(
     file_name: &String,
     configs: &mut RasterConfigs,
     data: &mut Vec<f64>,
 ) -> Result<(), Error> {
+    if is_compressed_saga(file_name) {
+        let mut archive = ZipArchive::new(File::open(file_name)?)?;
+        let entry_names: Vec<String> = (0..archive.len())
+            .filter_map(|i| archive.by_index(i).ok().map(|f| f.name().to_string()))
+            .collect();
+        let header_entry = match entry_names
+            .iter()
+            .find(|n| n.to_lowercase().ends_with(".sgrd"))
+        {
+            Some(n) => n.clone(),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("The compressed SAGA grid {} does not contain a .sgrd header file.", file_name),
+                ))
+            }
+        };
+        let stem = &header_entry[..header_entry.len() - 5];
+        let find_entry = |extension: &str| -> Option<String> {
+            let name = format!("{}.{}", stem, extension);
+            entry_names.iter().find(|n| n.eq_ignore_ascii_case(&name)).cloned()
+        };
+        let data_entry = match find_entry("sdat") {
+            Some(n) => n,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("The compressed SAGA grid {} does not contain a .sdat data file.", file_name),
+                ))
+            }
+        };
+
+        let header = read_zip_entry(&mut archive, &header_entry)?;
+        let header = read_saga_header(Cursor::new(header), configs)?;
+        if let Some(prj_entry) = find_entry("prj") {
+            let prj = read_zip_entry(&mut archive, &prj_entry)?;
+            configs.coordinate_ref_system_wkt = String::from_utf8_lossy(&prj).trim().to_string();
+        }
+        let bytes = read_zip_entry(&mut archive, &data_entry)?;
+        return read_saga_data(Cursor::new(bytes), &header, configs, data);
+    }
+
     // read the header file
-    // let header_file = file_name.replace(".sdat", ".sgrd");
     let header_file = Path::new(&file_name).with_extension("sgrd").into_os_string().into_string().unwrap();
     let f = File::open(header_file)?;
-    let f = BufReader::new(f);
-    let mut data_file_offset = 0u64;
-    let mut top_to_bottom = false;
-    let mut z_factor = 1.0;
-    for line in f.lines() {
-        let line_unwrapped = line.unwrap();
-        //let line_split = line_unwrapped.split("\t");
-        let line_split = line_unwrapped.split("=");
-        let vec = line_split.collect::<Vec<&str>>();
-        if vec[0].to_lowercase().contains("name") {
-            configs.title = vec[1].replace("=", "").trim().to_string();
-        } else if vec[0].to_lowercase().contains("description") {
-            if vec[1].replace("=", "").trim() != "" {
-                configs
-                    .metadata
-                    .push(vec[1].trim().replace("=", "").to_string());
+    let header = read_saga_header(BufReader::new(f), configs)?;
+
+    // read the data file
+    let data_file = Path::new(&file_name).with_extension("sdat").into_os_string().into_string().unwrap();
+    let f = File::open(data_file)?;
+    read_saga_data(BufReader::new(f), &header, configs, data)
+}
+
+/// The properties of a SAGA grid header that are needed to decode its data.
+struct SagaHeader {
+    data_file_offset: u64,
+    /// the data type in which the cell values are stored
+    raw_type: DataType,
+    top_to_bottom: bool,
+    z_factor: f64,
+}
+
+fn read_saga_header<R: BufRead>(reader: R, configs: &mut RasterConfigs) -> Result<SagaHeader, Error> {
+    let parse_f64 = |s: &str| -> Result<f64, Error> {
+        s.trim().parse::<f64>().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Unrecognized SAGA header value '{}'.", s.trim()),
+            )
+        })
+    };
+    let mut header = SagaHeader {
+        data_file_offset: 0u64,
+        raw_type: DataType::F32,
+        top_to_bottom: false,
+        z_factor: 1.0,
+    };
+    let mut cell_size = 0f64;
+    let mut x_min = 0f64;
+    let mut y_min = 0f64;
+    for line in reader.lines() {
+        let line_unwrapped = line?;
+        let vec = line_unwrapped.splitn(2, '=').collect::<Vec<&str>>();
+        if vec.len() < 2 {
+            continue;
+        }
+        let key = vec[0].trim().to_lowercase();
+        let value = vec[1].trim();
+        if key == "name" {
+            configs.title = value.to_string();
+        } else if key == "description" {
+            if !value.is_empty() {
+                configs.metadata.push(value.to_string());
             }
-        } else if vec[0].to_lowercase().contains("unit") {
-            if vec[1].replace("=", "").trim() != "" {
-                configs.xy_units = vec[1].trim().replace("=", "").to_string();
+        } else if key == "unit" {
+            if !value.is_empty() {
+                configs.xy_units = value.to_string();
             }
-        } else if vec[0].to_lowercase().contains("datafile_offset") {
-            data_file_offset = vec[1]
-                .replace("=", "")
-                .trim()
-                .to_string()
-                .parse::<u64>()
-                .unwrap();
-        } else if vec[0].to_lowercase().contains("dataformat") {
-            let data_format = vec[1].replace("=", "").trim().to_lowercase().to_string();
-            match &data_format[..] {
-                "bit" => {
-                    return Err(Error::new(
-                        ErrorKind::InvalidInput,
-                        "Reading of this kind of SAGA raster file is not currently supported",
-                    ))
-                }
-                "byte_unsigned" => configs.data_type = DataType::U8,
-                "byte" => configs.data_type = DataType::U8,
-                "shortint_unsigned" => configs.data_type = DataType::U16,
-                "shortint" => configs.data_type = DataType::I16,
-                "integer_unsigned" => configs.data_type = DataType::U32,
-                "integer" => configs.data_type = DataType::I32,
-                "float" => configs.data_type = DataType::F32,
-                "double" => configs.data_type = DataType::F64,
+        } else if key == "datafile_offset" {
+            header.data_file_offset = parse_f64(value)? as u64;
+        } else if key == "dataformat" {
+            header.raw_type = match &value.to_lowercase()[..] {
+                "byte_unsigned" => DataType::U8,
+                "byte" => DataType::U8,
+                "shortint_unsigned" => DataType::U16,
+                "shortint" => DataType::I16,
+                "integer_unsigned" => DataType::U32,
+                "integer" => DataType::I32,
+                "float" => DataType::F32,
+                "double" => DataType::F64,
                 _ => {
                     return Err(Error::new(
                         ErrorKind::InvalidInput,
                         "Reading of this kind of SAGA raster file is not currently supported",
                     ))
                 }
-            }
-        } else if vec[0].to_lowercase().contains("byteorder_big") {
-            if vec[1].replace("=", "").trim().to_lowercase().contains("f")
-                || vec[1]
-                    .replace("=", "")
-                    .trim()
-                    .to_lowercase()
-                    .contains("lsb")
-            {
+            };
+        } else if key == "byteorder_big" {
+            let value = value.to_lowercase();
+            if value.contains('f') || value.contains("lsb") {
                 configs.endian = Endianness::LittleEndian;
             } else {
                 configs.endian = Endianness::BigEndian;
             }
-        } else if vec[0].to_lowercase().contains("position_xmin") {
-            configs.west = vec[1]
-                .replace("=", "")
-                .trim()
-                .to_string()
-                .parse::<f64>()
-                .unwrap();
-        } else if vec[0].to_lowercase().contains("position_ymin") {
-            configs.south = vec[1]
-                .replace("=", "")
-                .trim()
-                .to_string()
-                .parse::<f64>()
-                .unwrap();
-        } else if vec[0].to_lowercase().contains("cellcount_x") {
-            configs.columns = vec[1]
-                .replace("=", "")
-                .trim()
-                .to_string()
-                .parse::<usize>()
-                .unwrap();
-        } else if vec[0].to_lowercase().contains("cellcount_y") {
-            configs.rows = vec[1]
-                .replace("=", "")
-                .trim()
-                .to_string()
-                .parse::<usize>()
-                .unwrap();
-        } else if vec[0].to_lowercase().contains("cellsize") {
-            configs.resolution_x = vec[1]
-                .replace("=", "")
-                .trim()
-                .to_string()
-                .parse::<f64>()
-                .unwrap();
-            configs.resolution_y = vec[1]
-                .replace("=", "")
-                .trim()
-                .to_string()
-                .parse::<f64>()
-                .unwrap();
-        } else if vec[0].to_lowercase().contains("z_factor") {
-            z_factor = vec[1]
-                .replace("=", "")
-                .trim()
-                .to_string()
-                .parse::<f64>()
-                .unwrap();
-        } else if vec[0].to_lowercase().contains("nodata_value") {
-            configs.nodata = vec[1]
-                .replace("=", "")
-                .trim()
-                .to_string()
-                .parse::<f64>()
-                .unwrap();
-        } else if vec[0].to_lowercase().contains("toptobottom") {
-            top_to_bottom = vec[1].replace("=", "").trim().to_lowercase().contains("t")
+        } else if key == "position_xmin" {
+            x_min = parse_f64(value)?;
+        } else if key == "position_ymin" {
+            y_min = parse_f64(value)?;
+        } else if key == "cellcount_x" {
+            configs.columns = parse_f64(value)? as usize;
+        } else if key == "cellcount_y" {
+            configs.rows = parse_f64(value)? as usize;
+        } else if key == "cellsize" {
+            cell_size = parse_f64(value)?;
+        } else if key == "z_factor" {
+            header.z_factor = parse_f64(value)?;
+        } else if key == "nodata_value" {
+            configs.nodata = parse_f64(value)?;
+        } else if key == "toptobottom" {
+            header.top_to_bottom = value.to_lowercase().contains('t');
         }
     }
 
+    configs.resolution_x = cell_size;
+    configs.resolution_y = cell_size;
+    configs.west = x_min;
+    configs.south = y_min;
     configs.north = configs.south + configs.resolution_y * configs.rows as f64;
     configs.east = configs.west + configs.resolution_x * configs.columns as f64;
 
-    if z_factor < 0.0 && (configs.data_type == DataType::F32 || configs.data_type == DataType::F64)
-    {
+    configs.data_type = header.raw_type;
+    if header.z_factor < 0.0 && (header.raw_type == DataType::F32 || header.raw_type == DataType::F64) {
         configs.data_type = DataType::F32;
     }
 
-    let mut row_start = 0;
-    if !top_to_bottom {
-        row_start = configs.rows - 1;
-    }
-
-    data.reserve(configs.rows * configs.columns);
-    
-    // read the data file
-    // let data_file = file_name.replace(".sgrd", ".sdat");
-    let data_file = Path::new(&file_name).with_extension("sdat").into_os_string().into_string().unwrap();
-    let mut f = File::open(data_file.clone())?;
-    f.seek(SeekFrom::Start(data_file_offset))?;
+    Ok(header)
+}
 
-    let data_size = if configs.data_type == DataType::F64 {
-        8
-    } else if configs.data_type == DataType::F32
-        || configs.data_type == DataType::I32
-        || configs.data_type == DataType::U32
-    {
-        4
-    } else if configs.data_type == DataType::I16 || configs.data_type == DataType::U16 {
-        2
-    } else {
-        // DataType::U8 or I8
-        1
+fn read_saga_data<R: Read + Seek>(
+    mut reader: R,
+    header: &SagaHeader,
+    configs: &RasterConfigs,
+    data: &mut Vec<f64>,
+) -> Result<(), Error> {
+    reader.seek(SeekFrom::Start(header.data_file_offset))?;
+
+    let (rows, columns) = (configs.rows, configs.columns);
+    let data_size = match header.raw_type {
+        DataType::F64 => 8,
+        DataType::F32 | DataType::I32 | DataType::U32 => 4,
+        DataType::I16 | DataType::U16 => 2,
+        _ => 1,
     };
-
-    let num_cells = configs.rows * configs.columns;
     data.clear();
-    data.reserve(num_cells);
-    for _ in 0..num_cells {
-        data.push(configs.nodata);
+    data.resize(rows * columns, configs.nodata);
+
+    for r in 0..rows {
+        let mut buffer = vec![0u8; columns * data_size];
+        reader.read_exact(&mut buffer)?;
+        let mut bor = ByteOrderReader::<Cursor<Vec<u8>>>::new(Cursor::new(buffer), configs.endian);
+        // rows are stored from bottom to top unless otherwise specified
+        let row = if header.top_to_bottom { r } else { rows - 1 - r };
+        for col in 0..columns {
+            let z = match header.raw_type {
+                DataType::F64 => bor.read_f64()?,
+                DataType::F32 => bor.read_f32()? as f64,
+                DataType::I32 => bor.read_i32()? as f64,
+                DataType::U32 => bor.read_u32()? as f64,
+                DataType::I16 => bor.read_i16()? as f64,
+                DataType::U16 => bor.read_u16()? as f64,
+                DataType::I8 => bor.read_i8()? as f64,
+                _ => bor.read_u8()? as f64,
+            };
+            data[row * columns + col] = z * header.z_factor;
+        }
     }
 
-    let buf_size = 1_000_000usize;
-    let mut j = 0;
-    let mut row = row_start;
-    let mut col = 0;
-    let mut k: usize;
-    while j < num_cells {
-        let mut buffer = vec![0; buf_size * data_size];
-
-        f.read(&mut buffer)?;
-
-        let mut bor = if configs.endian == Endianness::LittleEndian {
-            ByteOrderReader::<Cursor<Vec<u8>>>::new(Cursor::new(buffer), Endianness::LittleEndian)
-        } else {
-            ByteOrderReader::<Cursor<Vec<u8>>>::new(Cursor::new(buffer), Endianness::BigEndian)
-        };
-        bor.seek(0);
-
-        match configs.data_type {
-            DataType::F64 => {
-                for _ in 0..buf_size {
-                    k = row * configs.columns + col;
-                    data[k] = bor.read_f64()? as f64 * z_factor;
-
-                    j += 1;
-                    if j == num_cells {
-                        break;
-                    }
-                    col += 1;
-                    if col >= configs.columns {
-                        col = 0;
-                        if !top_to_bottom {
-                            row -= 1;
-                        } else {
-                            row += 1;
-                        }
-                    }
-                }
-            }
-            DataType::F32 => {
-                for _ in 0..buf_size {
-                    k = row * configs.columns + col;
-                    data[k] = bor.read_f32()? as f64 * z_factor;
-
-                    j += 1;
-                    if j == num_cells {
-                        break;
-                    }
-                    col += 1;
-                    if col >= configs.columns {
-                        col = 0;
-                        if !top_to_bottom {
-                            row -= 1;
-                        } else {
-                            row += 1;
-                        }
-                    }
-                }
-            }
-            DataType::I32 => {
-                for _ in 0..buf_size {
-                    k = row * configs.columns + col;
-                    data[k] = bor.read_i32()? as f64 * z_factor;
-
-                    j += 1;
-                    if j == num_cells {
-                        break;
-                    }
-                    col += 1;
-                    if col >= configs.columns {
-                        col = 0;
-                        if !top_to_bottom {
-                            row -= 1;
-                        } else {
-                            row += 1;
-                        }
-                    }
-                }
-            }
-            DataType::U32 => {
-                for _ in 0..buf_size {
-                    k = row * configs.columns + col;
-                    data[k] = bor.read_u32()? as f64 * z_factor;
-
-                    j += 1;
-                    if j == num_cells {
-                        break;
-                    }
-                    col += 1;
-                    if col >= configs.columns {
-                        col = 0;
-                        if !top_to_bottom {
-                            row -= 1;
-                        } else {
-                            row += 1;
-                        }
-                    }
-                }
-            }
-            DataType::I16 => {
-                for _ in 0..buf_size {
-                    k = row * configs.columns + col;
-                    data[k] = bor.read_i16()? as f64 * z_factor;
-
-                    j += 1;
-                    if j == num_cells {
-                        break;
-                    }
-                    col += 1;
-                    if col >= configs.columns {
-                        col = 0;
-                        if !top_to_bottom {
-                            row -= 1;
-                        } else {
-                            row += 1;
-                        }
-                    }
-                }
-            }
-            DataType::U16 => {
-                for _ in 0..buf_size {
-                    k = row * configs.columns + col;
-                    data[k] = bor.read_u16()? as f64 * z_factor;
+    Ok(())
+}
 
-                    j += 1;
-                    if j == num_cells {
-                        break;
-                    }
-                    col += 1;
-                    if col >= configs.columns {
-                        col = 0;
-                        if !top_to_bottom {
-                            row -= 1;
-                        } else {
-                            row += 1;
-                        }
-                    }
-                }
-            }
-            DataType::I8 => {
-                for _ in 0..buf_size {
-                    k = row * configs.columns + col;
-                    data[k] = bor.read_i8()? as f64 * z_factor;
-                    j += 1;
-                    if j == num_cells {
-                        break;
-                    }
-                    col += 1;
-                    if col >= configs.columns {
-                        col = 0;
-                        if !top_to_bottom {
-                            row -= 1;
-                        } else {
-                            row += 1;
-                        }
-                    }
-                }
-            }
-            DataType::U8 => {
-                for _ in 0..buf_size {
-                    k = row * configs.columns + col;
-                    data[k] = bor.read_u8()? as f64 * z_factor;
-                    j += 1;
-                    if j == num_cells {
-                        break;
-                    }
-                    col += 1;
-                    if col >= configs.columns {
-                        col = 0;
-                        if !top_to_bottom {
-                            row -= 1;
-                        } else {
-                            row += 1;
-                        }
-                    }
-                }
-            }
-            _ => {
-                return Err(Error::new(
-                    ErrorKind::NotFound,
-                    "Raster data type is unknown.",
-                ));
-            }
+/// Reads the bytes of an entry of a zip archive.
+fn read_zip_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Vec<u8>, Error> {
+    let mut f = archive.by_name(name)?;
+    match f.compression() {
+        CompressionMethod::Stored | CompressionMethod::Deflated => (),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Either the file is formatted incorrectly or it is an unsupported compression type.",
+            ))
         }
     }
-
-    Ok(())
+    let mut buffer = Vec::with_capacity(f.size() as usize);
+    f.read_to_end(&mut buffer)?;
+    Ok(buffer)
 }
 
-pub fn write_saga<'a>(r: &'a mut Raster) -> Result<(), Error> {
+pub fn write_saga(r: &mut Raster) -> Result<(), Error> {
     // figure out the minimum and maximum values
-    for val in &r.data {
-        let v = *val;
+    for v in r.data.iter() {
         if v != r.configs.nodata {
             if v < r.configs.minimum {
                 r.configs.minimum = v;
@@ -391,59 +259,15 @@ pub fn write_saga<'a>(r: &'a mut Raster) -> Result<(), Error> {
         r.configs.display_max = r.configs.maximum;
     }
 
-    // Save the header file
-    // let header_file = r.file_name.replace(".sdat", ".sgrd");
-    let header_file = Path::new(&r.file_name).with_extension("sgrd").into_os_string().into_string().unwrap();
-    let f = File::create(header_file.clone())?;
-    let mut writer = BufWriter::new(f);
-
-    // get the short file NAME
-    let short_name: String = match Path::new(&header_file).file_name().unwrap().to_str() {
-        Some(n) => n.to_string().to_lowercase(),
-        None => "".to_string(),
-    };
-
-    writer.write_all(format!("NAME\t= {}\n", short_name).as_bytes())?;
-
-    if r.configs.metadata.len() > 0 {
-        writer.write_all(format!("DESCRIPTION\t= {}\n", r.configs.metadata[0]).as_bytes())?;
-    } else {
-        writer.write_all("DESCRIPTION\t=\n".as_bytes())?;
-    }
-
-    if r.configs.xy_units != "not specified" {
-        writer.write_all(format!("UNIT\t= {}\n", r.configs.xy_units).as_bytes())?;
-    } else {
-        writer.write_all("UNIT\t=\n".as_bytes())?;
-    }
-
-    writer.write_all("DATAFILE_OFFSET\t= 0\n".as_bytes())?;
-
-    match r.configs.data_type {
-        DataType::F64 => {
-            writer.write_all("DATAFORMAT\t= DOUBLE\n".as_bytes())?;
-        }
-        DataType::F32 => {
-            writer.write_all("DATAFORMAT\t= FLOAT\n".as_bytes())?;
-        }
-        DataType::I32 => {
-            writer.write_all("DATAFORMAT\t= INTEGER\n".as_bytes())?;
-        }
-        DataType::U32 => {
-            writer.write_all("DATAFORMAT\t= INTEGER_UNSIGNED\n".as_bytes())?;
-        }
-        DataType::I16 => {
-            writer.write_all("DATAFORMAT\t= SHORTINT\n".as_bytes())?;
-        }
-        DataType::U16 => {
-            writer.write_all("DATAFORMAT\t= SHORTINT_UNSIGNED\n".as_bytes())?;
-        }
-        DataType::U8 => {
-            writer.write_all("DATAFORMAT\t= BYTE_UNSIGNED\n".as_bytes())?;
-        }
-        DataType::I8 => {
-            writer.write_all("DATAFORMAT\t= BYTE\n".as_bytes())?;
-        }
+    let data_format = match r.configs.data_type {
+        DataType::F64 => "DOUBLE",
+        DataType::F32 => "FLOAT",
+        DataType::I32 => "INTEGER",
+        DataType::U32 => "INTEGER_UNSIGNED",
+        DataType::I16 => "SHORTINT",
+        DataType::U16 => "SHORTINT_UNSIGNED",
+        DataType::U8 => "BYTE_UNSIGNED",
+        DataType::I8 => "BYTE",
         _ => {
             return Err(Error::new(
                 ErrorKind::NotFound,
@@ -453,120 +277,172 @@ pub fn write_saga<'a>(r: &'a mut Raster) -> Result<(), Error> {
                 ),
             ));
         }
-    }
+    };
 
-    if r.configs.endian == Endianness::LittleEndian {
-        writer.write_all("BYTEORDER_BIG\t= FALSE\n".as_bytes())?;
+    // the header file
+    let stem = match Path::new(&r.file_name).file_stem().and_then(|s| s.to_str()) {
+        Some(n) => n.to_string(),
+        None => "grid".to_string(),
+    };
+    let mut header = String::new();
+    header.push_str(&format!("NAME\t= {}.sgrd\n", stem.to_lowercase()));
+    if !r.configs.metadata.is_empty() {
+        header.push_str(&format!("DESCRIPTION\t= {}\n", r.configs.metadata[0]));
     } else {
-        writer.write_all("BYTEORDER_BIG\t= TRUE\n".as_bytes())?;
+        header.push_str("DESCRIPTION\t=\n");
     }
-
-    writer.write_all(format!("POSITION_XMIN\t= {}\n", r.configs.west).as_bytes())?;
-
-    writer.write_all(format!("POSITION_YMIN\t= {}\n", r.configs.south).as_bytes())?;
-
-    writer.write_all(format!("CELLCOUNT_X\t= {}\n", r.configs.columns).as_bytes())?;
-
-    writer.write_all(format!("CELLCOUNT_Y\t= {}\n", r.configs.rows).as_bytes())?;
-
-    writer.write_all(
-        format!(
-            "CELLSIZE\t= {}\n",
-            (r.configs.resolution_x + r.configs.resolution_y) / 2.0
-        )
-        .as_bytes(),
-    )?;
-
-    writer.write_all("Z_FACTOR\t= 1.000000\n".as_bytes())?;
-
-    writer.write_all(format!("NODATA_VALUE\t= {}\n", r.configs.nodata).as_bytes())?;
-
-    writer.write_all("TOPTOBOTTOM\t= FALSE\n".as_bytes())?;
-
-    let _ = writer.flush();
-
-    // write the data file
-    // let data_file = r.file_name.replace(".sgrd", ".sdat");
-    let data_file = Path::new(&r.file_name).with_extension("sdat").into_os_string().into_string().unwrap();
-    let f = File::create(&data_file)?;
-    let mut writer = BufWriter::new(f);
-
-    let mut u16_bytes: [u8; 2];
-    let mut u32_bytes: [u8; 4];
-    let mut u64_bytes: [u8; 8];
+    if r.configs.xy_units != "not specified" {
+        header.push_str(&format!("UNIT\t= {}\n", r.configs.xy_units));
+    } else {
+        header.push_str("UNIT\t=\n");
+    }
+    header.push_str("DATAFILE_OFFSET\t= 0\n");
+    header.push_str(&format!("DATAFORMAT\t= {}\n", data_format));
+    header.push_str("BYTEORDER_BIG\t= FALSE\n");
+    let cell_size = (r.configs.resolution_x + r.configs.resolution_y) / 2.0;
+    header.push_str(&format!("POSITION_XMIN\t= {}\n", r.configs.west));
+    header.push_str(&format!("POSITION_YMIN\t= {}\n", r.configs.south));
+    header.push_str(&format!("CELLCOUNT_X\t= {}\n", r.configs.columns));
+    header.push_str(&format!("CELLCOUNT_Y\t= {}\n", r.configs.rows));
+    header.push_str(&format!("CELLSIZE\t= {}\n", cell_size));
+    header.push_str("Z_FACTOR\t= 1.000000\n");
+    header.push_str(&format!("NODATA_VALUE\t= {}\n", r.configs.nodata));
+    header.push_str("TOPTOBOTTOM\t= FALSE\n");
+
+    // the data file, stored in little-endian byte order from the bottom row to the top
+    let (rows, columns) = (r.configs.rows, r.configs.columns);
+    let mut bytes: Vec<u8> = Vec::with_capacity(rows * columns * 8);
     let mut i: usize;
-    match r.configs.data_type {
-        DataType::F64 => {
-            for row in (0..r.configs.rows).rev() {
-                for col in 0..r.configs.columns {
-                    i = row * r.configs.columns + col;
-                    u64_bytes = unsafe { mem::transmute(r.data[i]) };
-                    writer.write(&u64_bytes)?;
-                }
-            }
-        }
-        DataType::F32 => {
-            for row in (0..r.configs.rows).rev() {
-                for col in 0..r.configs.columns {
-                    i = row * r.configs.columns + col;
-                    u32_bytes = unsafe { mem::transmute(r.data[i] as f32) };
-                    writer.write(&u32_bytes)?;
-                }
-            }
-        }
-        DataType::I32 => {
-            for row in (0..r.configs.rows).rev() {
-                for col in 0..r.configs.columns {
-                    i = row * r.configs.columns + col;
-                    u32_bytes = unsafe { mem::transmute(r.data[i] as i32) };
-                    writer.write(&u32_bytes)?;
-                }
-            }
-        }
-        DataType::U32 => {
-            for row in (0..r.configs.rows).rev() {
-                for col in 0..r.configs.columns {
-                    i = row * r.configs.columns + col;
-                    u32_bytes = unsafe { mem::transmute(r.data[i] as u32) };
-                    writer.write(&u32_bytes)?;
-                }
+    for row in (0..rows).rev() {
+        for col in 0..columns {
+            i = row * columns + col;
+            let z = r.data.get_value(i);
+            match r.configs.data_type {
+                DataType::F64 => bytes.extend_from_slice(&z.to_le_bytes()),
+                DataType::F32 => bytes.extend_from_slice(&(z as f32).to_le_bytes()),
+                DataType::I32 => bytes.extend_from_slice(&(z as i32).to_le_bytes()),
+                DataType::U32 => bytes.extend_from_slice(&(z as u32).to_le_bytes()),
+                DataType::I16 => bytes.extend_from_slice(&(z as i16).to_le_bytes()),
+                DataType::U16 => bytes.extend_from_slice(&(z as u16).to_le_bytes()),
+                _ => bytes.push(z as u8),
             }
         }
-        DataType::I16 => {
-            for row in (0..r.configs.rows).rev() {
-                for col in 0..r.configs.columns {
-                    i = row * r.configs.columns + col;
-                    u16_bytes = unsafe { mem::transmute(r.data[i] as i16) };
-                    writer.write(&u16_bytes)?;
-                }
-            }
+    }
+
+    if is_compressed_saga(&r.file_name) {
+        // compressed grids embed the projection file
+        let wkt = if !r.configs.coordinate_ref_system_wkt.is_empty()
+            && r.configs.coordinate_ref_system_wkt.to_lowercase() != "not specified"
+        {
+            r.configs.coordinate_ref_system_wkt.clone()
+        } else if r.configs.epsg_code != 0 {
+            esri_wkt_from_epsg(r.configs.epsg_code)
+        } else {
+            String::new()
+        };
+        let f = File::create(&r.file_name)?;
+        let mut writer = ZipWriter::new(f);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        writer.start_file(format!("{}.sdat", stem), options)?;
+        writer.write_all(&bytes)?;
+        writer.start_file(format!("{}.sgrd", stem), options)?;
+        writer.write_all(header.as_bytes())?;
+        if !wkt.is_empty() && !wkt.to_lowercase().contains("unknown epsg") {
+            writer.start_file(format!("{}.prj", stem), options)?;
+            writer.write_all(wkt.as_bytes())?;
         }
-        DataType::U16 => {
-            for row in (0..r.configs.rows).rev() {
-                for col in 0..r.configs.columns {
-                    i = row * r.configs.columns + col;
-                    u16_bytes = unsafe { mem::transmute(r.data[i] as u16) };
-                    writer.write(&u16_bytes)?;
-                }
+        writer.finish()?;
+    } else {
+        let header_file = Path::new(&r.file_name).with_extension("sgrd");
+        fs::write(&header_file, header.as_bytes())?;
+        let data_file = Path::new(&r.file_name).with_extension("sdat");
+        fs::write(&data_file, &bytes)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::*;
+
+    #[test]
+    fn test_compressed_saga_round_trip() {
+        let mut configs = RasterConfigs::default();
+        configs.rows = 3;
+        configs.columns = 4;
+        configs.west = 500.0;
+        configs.south = 1000.0;
+        configs.resolution_x = 10.0;
+        configs.resolution_y = 10.0;
+        configs.east = configs.west + 40.0;
+        configs.north = configs.south + 30.0;
+        configs.nodata = -9999.0;
+        configs.data_type = DataType::I16;
+        configs.epsg_code = 32617;
+        let file_name = std::env::temp_dir()
+            .join("wbt_saga_test.sg-grd-z")
+            .to_str()
+            .unwrap()
+            .to_string();
+        let mut output = Raster::initialize_using_config(&file_name, &configs);
+        for row in 0..3 {
+            for col in 0..4 {
+                output.set_value(row, col, (row * 10 + col) as f64);
             }
         }
-        DataType::U8 | DataType::I8 => {
-            for row in (0..r.configs.rows).rev() {
-                for col in 0..r.configs.columns {
-                    i = row * r.configs.columns + col;
-                    writer.write(&[r.data[i] as u8])?;
-                }
+        output.set_value(1, 2, -9999.0);
+        output.write().unwrap();
+
+        let input = Raster::new(&file_name, "r").unwrap();
+        let _ = std::fs::remove_file(&file_name);
+        assert_eq!((input.configs.rows, input.configs.columns), (3, 4));
+        assert_eq!((input.configs.west, input.configs.north), (500.0, 1030.0));
+        assert_eq!(input.configs.data_type, DataType::I16);
+        assert!(input.configs.coordinate_ref_system_wkt.contains("UTM"));
+        assert_eq!(input.get_value(0, 0), 0.0);
+        assert_eq!(input.get_value(2, 3), 23.0);
+        assert_eq!(input.get_value(1, 2), -9999.0);
+    }
+
+    #[test]
+    fn test_saga_grid_position() {
+        // the header of a grid as written by SAGA; the position is read as the lower-left corner
+        let dir = std::env::temp_dir().join("wbt_saga_position");
+        let _ = std::fs::create_dir_all(&dir);
+        let header = "NAME\t= dem\nDESCRIPTION\t=\nUNIT\t= metres\nDATAFILE_OFFSET\t= 0\n\
+                      DATAFORMAT\t= BYTE\nBYTEORDER_BIG\t= FALSE\nPOSITION_XMIN\t= 500.0000000000\n\
+                      POSITION_YMIN\t= 1000.0000000000\nCELLCOUNT_X\t= 2\nCELLCOUNT_Y\t= 2\n\
+                      CELLSIZE\t= 10.0000000000\nZ_FACTOR\t= 1.000000\nNODATA_VALUE\t= 0.000000\n\
+                      TOPTOBOTTOM\t= FALSE\n";
+        std::fs::write(dir.join("dem.sgrd"), header).unwrap();
+        std::fs::write(dir.join("dem.sdat"), [1u8, 2u8, 3u8, 200u8]).unwrap();
+        let file_name = dir.join("dem.sdat").to_str().unwrap().to_string();
+
+        let input = Raster::new(&file_name, "r").unwrap();
+        assert_eq!((input.configs.west, input.configs.south), (500.0, 1000.0));
+        assert_eq!((input.configs.east, input.configs.north), (520.0, 1020.0));
+        assert_eq!(input.configs.data_type, DataType::U8);
+        assert_eq!(input.configs.xy_units, "metres");
+        // rows are stored from bottom to top
+        assert_eq!(input.get_value(0, 0), 3.0);
+        assert_eq!(input.get_value(1, 1), 2.0);
+        assert_eq!(input.get_value(0, 1), 200.0);
+
+        // the grid is written back with the same header values
+        let out_name = dir.join("out.sdat").to_str().unwrap().to_string();
+        let mut output = Raster::initialize_using_file(&out_name, &input);
+        for row in 0..2 {
+            for col in 0..2 {
+                output.set_value(row, col, input.get_value(row, col));
             }
         }
-        _ => {
-            return Err(Error::new(
-                ErrorKind::NotFound,
-                "Raster data type is unsupported.",
-            ));
-        }
+        output.write().unwrap();
+        let out_header = std::fs::read_to_string(dir.join("out.sgrd")).unwrap();
+        assert!(out_header.contains("POSITION_XMIN\t= 500\n"));
+        assert!(out_header.contains("POSITION_YMIN\t= 1000\n"));
+        assert!(out_header.contains("DATAFORMAT\t= BYTE_UNSIGNED\n"));
+        assert_eq!(std::fs::read(dir.join("out.sdat")).unwrap(), vec![1u8, 2u8, 3u8, 200u8]);
+        let _ = std::fs::remove_dir_all(&dir);
     }
-
-    let _ = writer.flush();
-
-    Ok(())
 }
@@ -121,8 +121,7 @@ pub fn write_surfer_ascii_raster<'a>(r: &'a mut Raster) -> Result<(), Error> {
     }
 
     // figure out the minimum and maximum values
-    for val in &r.data {
-        let v = *val;
+    for v in r.data.iter() {
         if v != r.configs.nodata {
             if v < r.configs.minimum {
                 r.configs.minimum = v;
@@ -154,14 +153,14 @@ pub fn write_surfer_ascii_raster<'a>(r: &'a mut Raster) -> Result<(), Error> {
         for col in 0..r.configs.columns {
             let i = row * r.configs.columns + col;
             if col < r.configs.columns - 1 {
-                if r.data[i] != r.configs.nodata {
-                    s2 += &format!("{:.*} ", num_decimals, r.data[i]);
+                if r.data.get_value(i) != r.configs.nodata {
+                    s2 += &format!("{:.*} ", num_decimals, r.data.get_value(i));
                 } else {
                     s2 += &format!("1.71041e38 ");
                 }
             } else {
-                if r.data[i] != r.configs.nodata {
-                    s2 += &format!("{:.*}\n", num_decimals, r.data[i]);
+                if r.data.get_value(i) != r.configs.nodata {
+                    s2 += &format!("{:.*}\n", num_decimals, r.data.get_value(i));
                 } else {
                     s2 += &format!("1.71041e38\n");
                 }
@@ -1,12 +1,12 @@
 use super::*;
 use crate::utils::Endianness;
-use byteorder::{LittleEndian, WriteBytesExt};
+use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
 use std::f64;
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::io::prelude::*;
 use std::io::Error;
 use std::io::ErrorKind;
-use std::io::{BufReader, BufWriter, Cursor};
+use std::io::{BufReader, BufWriter, Cursor, SeekFrom};
 use std::mem;
 use std::path::Path;
 
@@ -228,10 +228,9 @@ pub fn read_whitebox(
     Ok(())
 }
 
-pub fn write_whitebox<'a>(r: &'a mut Raster) -> Result<(), Error> {
+fn write_whitebox_header(r: &mut Raster) -> Result<(), Error> {
     // figure out the minimum and maximum values
-    for val in &r.data {
-        let v = *val;
+    for v in r.data.iter() {
         if v != r.configs.nodata {
             if v < r.configs.minimum {
                 r.configs.minimum = v;
@@ -395,6 +394,12 @@ pub fn write_whitebox<'a>(r: &'a mut Raster) -> Result<(), Error> {
 
     let _ = writer.flush();
 
+    Ok(())
+}
+
+pub fn write_whitebox<'a>(r: &'a mut Raster) -> Result<(), Error> {
+    write_whitebox_header(r)?;
+
     // write the data file
     // let data_file = r.file_name.replace(".dep", ".tas");
     let data_file = Path::new(&r.file_name).with_extension("tas").into_os_string().into_string().unwrap();
@@ -410,19 +415,19 @@ pub fn write_whitebox<'a>(r: &'a mut Raster) -> Result<(), Error> {
         DataType::F64 | DataType::U32 => {
             if r.configs.photometric_interp != PhotometricInterpretation::RGB {
                 for i in 0..num_cells {
-                    u64_bytes = unsafe { mem::transmute(r.data[i]) };
+                    u64_bytes = unsafe { mem::transmute(r.data.get_value(i)) };
                     writer.write(&u64_bytes)?;
                 }
             } else {
                 for i in 0..num_cells {
-                    u32_bytes = unsafe { mem::transmute(r.data[i] as u32) };
+                    u32_bytes = unsafe { mem::transmute(r.data.get_value(i) as u32) };
                     writer.write(&u32_bytes)?;
                 }
             }
         }
         DataType::F32 | DataType::U16 => {
             for i in 0..num_cells {
-                writer.write_f32::<LittleEndian>(r.data[i] as f32)?;
+                writer.write_f32::<LittleEndian>(r.data.get_value(i) as f32)?;
             }
         }
         DataType::I32 => {
@@ -430,15 +435,15 @@ pub fn write_whitebox<'a>(r: &'a mut Raster) -> Result<(), Error> {
             //     println!("Warning: the I32 data type may not be supported on all versions of Whitebox GAT.");
             // }
             // for i in 0..num_cells {
-            //     writer.write_i32::<LittleEndian>(r.data[i] as i32)?;
+            //     writer.write_i32::<LittleEndian>(r.data.get_value(i) as i32)?;
             // }
             for i in 0..num_cells {
-                writer.write_f32::<LittleEndian>(r.data[i] as f32)?;
+                writer.write_f32::<LittleEndian>(r.data.get_value(i) as f32)?;
             }
         }
         DataType::RGBA32 => {
             for i in 0..num_cells {
-                u32_bytes = unsafe { mem::transmute(r.data[i] as u32 as i32 as f32) };
+                u32_bytes = unsafe { mem::transmute(r.data.get_value(i) as u32 as i32 as f32) };
                 writer.write(&u32_bytes)?;
             }
         }
@@ -448,21 +453,21 @@ pub fn write_whitebox<'a>(r: &'a mut Raster) -> Result<(), Error> {
             let mut val: u32;
             let alpha_mask = (255 << 24) as u32;
             for i in 0..num_cells {
-                val = alpha_mask | (r.data[i] as u32);
+                val = alpha_mask | (r.data.get_value(i) as u32);
                 u32_bytes = unsafe { mem::transmute(val) };
                 writer.write(&u32_bytes)?;
             }
         }
         DataType::I16 => {
             for i in 0..num_cells {
-                // u16_bytes = unsafe { mem::transmute(r.data[i] as u16) };
+                // u16_bytes = unsafe { mem::transmute(r.data.get_value(i) as u16) };
                 // writer.write(&u16_bytes)?;
-                writer.write_i16::<LittleEndian>(r.data[i] as i16)?;
+                writer.write_i16::<LittleEndian>(r.data.get_value(i) as i16)?;
             }
         }
         DataType::U8 | DataType::I8 => {
             for i in 0..num_cells {
-                writer.write(&[r.data[i] as u8])?;
+                writer.write(&[r.data.get_value(i) as u8])?;
             }
         }
         _ => {
@@ -477,3 +482,47 @@ pub fn write_whitebox<'a>(r: &'a mut Raster) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Writes the values of the cells at the indices `cells` into the existing data file, without
+/// rewriting the remainder of the grid. The header file is rewritten to update the value range.
+pub fn update_whitebox(r: &mut Raster, cells: &[usize]) -> Result<(), Error> {
+    let size = match r.configs.data_type {
+        DataType::F64 => 8,
+        DataType::F32 | DataType::I32 | DataType::RGBA32 => 4,
+        DataType::I16 => 2,
+        DataType::U8 => 1,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                "Raster data type is unknown.",
+            ));
+        }
+    };
+    let data_file = Path::new(&r.file_name).with_extension("tas").into_os_string().into_string().unwrap();
+    let mut f = OpenOptions::new().write(true).open(&data_file)?;
+    let mut buf = vec![];
+    for &i in cells {
+        let v = r.data.get_value(i);
+        buf.clear();
+        match (r.configs.data_type, r.configs.endian) {
+            (DataType::F64, Endianness::LittleEndian) => buf.write_f64::<LittleEndian>(v)?,
+            (DataType::F64, _) => buf.write_f64::<BigEndian>(v)?,
+            (DataType::F32, Endianness::LittleEndian) => buf.write_f32::<LittleEndian>(v as f32)?,
+            (DataType::F32, _) => buf.write_f32::<BigEndian>(v as f32)?,
+            (DataType::I32, Endianness::LittleEndian) => buf.write_i32::<LittleEndian>(v as i32)?,
+            (DataType::I32, _) => buf.write_i32::<BigEndian>(v as i32)?,
+            (DataType::RGBA32, Endianness::LittleEndian) => {
+                buf.write_f32::<LittleEndian>(v as u32 as i32 as f32)?
+            }
+            (DataType::RGBA32, _) => buf.write_f32::<BigEndian>(v as u32 as i32 as f32)?,
+            (DataType::I16, Endianness::LittleEndian) => buf.write_i16::<LittleEndian>(v as i16)?,
+            (DataType::I16, _) => buf.write_i16::<BigEndian>(v as i16)?,
+            _ => buf.write_u8(v as u8)?,
+        }
+        f.seek(SeekFrom::Start((i * size) as u64))?;
+        f.write_all(&buf)?;
+    }
+    f.flush()?;
+
+    write_whitebox_header(r)
+}
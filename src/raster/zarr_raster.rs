@@ -0,0 +1,1151 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+//! Reading and writing of Zarr (version 2) arrays. A Zarr store is a directory in which an array
+//! is described by a JSON `.zarray` file and divided into chunks, each stored in its own file,
+//! such that a window of the array can be read without reading the rest of it. Chunks may be
+//! uncompressed, or compressed using zlib or gzip; the Blosc and Zstandard compressors, which
+//! require native libraries, are not supported.
+//!
+//! A store may be a single array, or a group of arrays, in which case the array may be selected by
+//! appending its name to the store name, e.g. `cube.zarr:elevation`. By default, the first
+//! array with two or more dimensions that is not a coordinate array is read. Arrays with a third,
+//! leading (e.g. time) dimension are read with each slice as a band, unless a slice is selected,
+//! e.g. `cube.zarr:ndvi[3]`. Georeferencing is taken from the `GeoTransform` and CRS attributes
+//! of a CF grid mapping array (as written by xarray/rioxarray), GDAL's `_CRS` attribute, or the
+//! coordinate arrays named by the array's `_ARRAY_DIMENSIONS` attribute.
+
+use super::netcdf_raster::{is_latitude, is_longitude, is_unspecified};
+use super::*;
+use crate::spatial_ref_system::esri_wkt_from_epsg;
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use libflate::{gzip, zlib};
+use serde_json::{json, Map, Value};
+use std::f64;
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// The nodata value used where the array's fill value is NaN or undefined, or the data are packed.
+const DEFAULT_NODATA: f64 = -32768.0;
+
+/// The name of the array written to Zarr stores, unless another is given in the file name.
+const DEFAULT_ARRAY_NAME: &str = "data";
+
+/// The number of rows and columns in each chunk of written arrays.
+const CHUNK_SIZE: usize = 256;
+
+/// A Zarr store name, split into the path of the store and the optional array name and slice
+/// index that follow it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZarrFileName {
+    pub path: String,
+    pub array: Option<String>,
+    pub slice: Option<usize>,
+}
+
+/// Parses a Zarr store name of the form `path.zarr[:array][[slice]]`, returning `None` if the
+/// name is not that of a Zarr store or the selector is malformed.
+pub fn parse_zarr_file_name(file_name: &str) -> Option<ZarrFileName> {
+    let lower = file_name.to_lowercase();
+    let mut split = None;
+    for (i, _) in lower.match_indices(".zarr") {
+        let rest = &lower[i + 5..];
+        if rest.is_empty()
+            || rest.starts_with(':')
+            || rest.starts_with('[')
+            || ((rest == "/" || rest == "\\") && i + 6 == lower.len())
+        {
+            split = Some(i + 5);
+        }
+    }
+    let split = split?;
+    let path = file_name[..split].to_string();
+    let mut selector = file_name[split..].trim_end_matches(['/', '\\']);
+    let mut array = None;
+    let mut slice = None;
+    if selector.starts_with(':') {
+        let end = selector.find('[').unwrap_or(selector.len());
+        let name = selector[1..end].trim();
+        if name.is_empty() {
+            return None;
+        }
+        array = Some(name.to_string());
+        selector = &selector[end..];
+    }
+    if selector.starts_with('[') {
+        if !selector.ends_with(']') {
+            return None;
+        }
+        slice = Some(selector[1..selector.len() - 1].trim().parse::<usize>().ok()?);
+    } else if !selector.is_empty() {
+        return None;
+    }
+    Some(ZarrFileName { path, array, slice })
+}
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+fn read_json(file_name: &Path) -> Result<Value, Error> {
+    let mut s = String::new();
+    File::open(file_name)?.read_to_string(&mut s)?;
+    serde_json::from_str(&s).map_err(|e| {
+        invalid_data(&format!(
+            "The Zarr metadata file {} could not be parsed: {}",
+            file_name.display(),
+            e
+        ))
+    })
+}
+
+fn write_json(file_name: &Path, value: &Value) -> Result<(), Error> {
+    let s = serde_json::to_string_pretty(value)
+        .map_err(|e| Error::other(format!("{}", e)))?;
+    fs::write(file_name, s)
+}
+
+/// Reads a JSON number, or one of the strings used by Zarr for special floating-point values.
+fn json_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => match s.as_str() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            _ => s.trim().parse::<f64>().ok(),
+        },
+        Value::Bool(b) => Some(if *b { 1f64 } else { 0f64 }),
+        _ => None,
+    }
+}
+
+fn json_text(attrs: &Value, name: &str) -> Option<String> {
+    match attrs.get(name) {
+        Some(Value::String(s)) => Some(s.clone()),
+        Some(Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Writes a floating-point value as a JSON number, or a Zarr special-value string.
+fn number_json(value: f64) -> Value {
+    if value.is_nan() {
+        json!("NaN")
+    } else if value.is_infinite() {
+        json!(if value > 0f64 { "Infinity" } else { "-Infinity" })
+    } else {
+        json!(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ZarrType {
+    kind: char, // 'b' (boolean), 'i', 'u', or 'f'
+    size: usize,
+    big_endian: bool,
+}
+
+impl ZarrType {
+    fn parse(dtype: &str) -> Option<ZarrType> {
+        let mut chars = dtype.chars();
+        let big_endian = match chars.next()? {
+            '<' | '|' => false,
+            '>' => true,
+            _ => return None,
+        };
+        let kind = chars.next()?;
+        let size = chars.as_str().parse::<usize>().ok()?;
+        let valid = match kind {
+            'b' => size == 1,
+            'i' | 'u' => size == 1 || size == 2 || size == 4 || size == 8,
+            'f' => size == 4 || size == 8,
+            _ => false,
+        };
+        if valid {
+            Some(ZarrType {
+                kind,
+                size,
+                big_endian,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn for_data_type(data_type: DataType) -> ZarrType {
+        let (kind, size) = match data_type {
+            DataType::U8 => ('u', 1),
+            DataType::I16 => ('i', 2),
+            DataType::U16 => ('u', 2),
+            DataType::I32 => ('i', 4),
+            DataType::U32 => ('u', 4),
+            DataType::F32 => ('f', 4),
+            _ => ('f', 8),
+        };
+        ZarrType {
+            kind,
+            size,
+            big_endian: false,
+        }
+    }
+
+    fn dtype_string(&self) -> String {
+        let order = if self.size == 1 {
+            '|'
+        } else if self.big_endian {
+            '>'
+        } else {
+            '<'
+        };
+        format!("{}{}{}", order, self.kind, self.size)
+    }
+
+    fn data_type(&self) -> DataType {
+        match (self.kind, self.size) {
+            ('b', _) | ('u', 1) => DataType::U8,
+            ('i', 1) => DataType::I8,
+            ('i', 2) => DataType::I16,
+            ('u', 2) => DataType::U16,
+            ('i', 4) => DataType::I32,
+            ('u', 4) => DataType::U32,
+            ('i', 8) => DataType::I64,
+            ('u', 8) => DataType::U64,
+            ('f', 4) => DataType::F32,
+            _ => DataType::F64,
+        }
+    }
+
+    fn decode(&self, bytes: &[u8], values: &mut Vec<f64>) {
+        values.clear();
+        values.reserve(bytes.len() / self.size);
+        for b in bytes.chunks_exact(self.size) {
+            let v = if self.big_endian {
+                match (self.kind, self.size) {
+                    ('i', 2) => BigEndian::read_i16(b) as f64,
+                    ('u', 2) => BigEndian::read_u16(b) as f64,
+                    ('i', 4) => BigEndian::read_i32(b) as f64,
+                    ('u', 4) => BigEndian::read_u32(b) as f64,
+                    ('i', 8) => BigEndian::read_i64(b) as f64,
+                    ('u', 8) => BigEndian::read_u64(b) as f64,
+                    ('f', 4) => BigEndian::read_f32(b) as f64,
+                    ('f', 8) => BigEndian::read_f64(b),
+                    ('i', _) => b[0] as i8 as f64,
+                    _ => b[0] as f64,
+                }
+            } else {
+                match (self.kind, self.size) {
+                    ('i', 2) => LittleEndian::read_i16(b) as f64,
+                    ('u', 2) => LittleEndian::read_u16(b) as f64,
+                    ('i', 4) => LittleEndian::read_i32(b) as f64,
+                    ('u', 4) => LittleEndian::read_u32(b) as f64,
+                    ('i', 8) => LittleEndian::read_i64(b) as f64,
+                    ('u', 8) => LittleEndian::read_u64(b) as f64,
+                    ('f', 4) => LittleEndian::read_f32(b) as f64,
+                    ('f', 8) => LittleEndian::read_f64(b),
+                    ('i', _) => b[0] as i8 as f64,
+                    _ => b[0] as f64,
+                }
+            };
+            values.push(v);
+        }
+    }
+
+    /// Encodes values in little-endian order.
+    fn encode(&self, value: f64, buf: &mut Vec<u8>) {
+        match (self.kind, self.size) {
+            ('u', 1) => buf.push(value as u8),
+            ('i', 2) => buf.extend_from_slice(&(value as i16).to_le_bytes()),
+            ('u', 2) => buf.extend_from_slice(&(value as u16).to_le_bytes()),
+            ('i', 4) => buf.extend_from_slice(&(value as i32).to_le_bytes()),
+            ('u', 4) => buf.extend_from_slice(&(value as u32).to_le_bytes()),
+            ('f', 4) => buf.extend_from_slice(&(value as f32).to_le_bytes()),
+            _ => buf.extend_from_slice(&value.to_le_bytes()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Compressor {
+    None,
+    Zlib,
+    Gzip,
+}
+
+/// The metadata of a Zarr array, i.e. the contents of its `.zarray` and `.zattrs` files.
+#[derive(Clone, Debug)]
+struct ZarrArray {
+    dir: PathBuf,
+    shape: Vec<usize>,
+    chunks: Vec<usize>,
+    dtype: ZarrType,
+    compressor: Compressor,
+    fill_value: Option<f64>,
+    fortran_order: bool,
+    separator: String,
+    attrs: Value,
+}
+
+impl ZarrArray {
+    fn is_array(dir: &Path) -> bool {
+        dir.join(".zarray").is_file()
+    }
+
+    fn open(dir: &Path) -> Result<ZarrArray, Error> {
+        let meta = read_json(&dir.join(".zarray"))?;
+        if meta.get("zarr_format").and_then(|v| v.as_u64()) != Some(2) {
+            return Err(invalid_data("Only version 2 Zarr arrays are supported."));
+        }
+        let dims = |name: &str| -> Result<Vec<usize>, Error> {
+            match meta.get(name).and_then(|v| v.as_array()) {
+                Some(a) => a
+                    .iter()
+                    .map(|v| {
+                        v.as_u64()
+                            .map(|n| n as usize)
+                            .ok_or_else(|| invalid_data("The Zarr array metadata is malformed."))
+                    })
+                    .collect(),
+                None => Err(invalid_data("The Zarr array metadata is malformed.")),
+            }
+        };
+        let shape = dims("shape")?;
+        let chunks = dims("chunks")?;
+        if chunks.len() != shape.len() || chunks.contains(&0) {
+            return Err(invalid_data("The Zarr array metadata is malformed."));
+        }
+        let dtype_str = meta.get("dtype").and_then(|v| v.as_str()).unwrap_or("");
+        let dtype = match ZarrType::parse(dtype_str) {
+            Some(t) => t,
+            None => {
+                return Err(invalid_data(&format!(
+                    "The Zarr data type '{}' is not supported.",
+                    dtype_str
+                )))
+            }
+        };
+        let compressor = match meta.get("compressor") {
+            None | Some(Value::Null) => Compressor::None,
+            Some(c) => match c.get("id").and_then(|v| v.as_str()) {
+                Some("zlib") => Compressor::Zlib,
+                Some("gzip") => Compressor::Gzip,
+                id => {
+                    return Err(invalid_data(&format!(
+                        "The Zarr compressor '{}' is not supported; only zlib and gzip compression are supported.",
+                        id.unwrap_or("unknown")
+                    )))
+                }
+            },
+        };
+        match meta.get("filters") {
+            None | Some(Value::Null) => {}
+            Some(Value::Array(a)) if a.is_empty() => {}
+            _ => return Err(invalid_data("Zarr arrays with filters are not supported.")),
+        }
+        let fill_value = meta.get("fill_value").and_then(json_number);
+        let fortran_order = meta.get("order").and_then(|v| v.as_str()) == Some("F");
+        let separator = meta
+            .get("dimension_separator")
+            .and_then(|v| v.as_str())
+            .unwrap_or(".")
+            .to_string();
+        let attrs = if dir.join(".zattrs").is_file() {
+            read_json(&dir.join(".zattrs"))?
+        } else {
+            json!({})
+        };
+        Ok(ZarrArray {
+            dir: dir.to_path_buf(),
+            shape,
+            chunks,
+            dtype,
+            compressor,
+            fill_value,
+            fortran_order,
+            separator,
+            attrs,
+        })
+    }
+
+    /// The names of the array's dimensions, from the xarray `_ARRAY_DIMENSIONS` attribute.
+    fn dimension_names(&self) -> Vec<String> {
+        match self.attrs.get("_ARRAY_DIMENSIONS").and_then(|v| v.as_array()) {
+            Some(a) if a.len() == self.shape.len() => a
+                .iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect(),
+            _ => vec![String::new(); self.shape.len()],
+        }
+    }
+
+    /// Reads and decodes the chunk with the chunk grid indices `index`, returning `None` if the
+    /// chunk has not been written, i.e. it contains only the fill value.
+    fn read_chunk(&self, index: &[usize], values: &mut Vec<f64>) -> Result<bool, Error> {
+        let key = if index.is_empty() {
+            "0".to_string()
+        } else {
+            index
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<String>>()
+                .join(&self.separator)
+        };
+        let file_name = self.dir.join(key);
+        if !file_name.is_file() {
+            return Ok(false);
+        }
+        let raw = fs::read(&file_name)?;
+        let bytes = match self.compressor {
+            Compressor::None => raw,
+            Compressor::Zlib => {
+                let mut buf = vec![];
+                zlib::Decoder::new(&raw[..])?.read_to_end(&mut buf)?;
+                buf
+            }
+            Compressor::Gzip => {
+                let mut buf = vec![];
+                gzip::Decoder::new(&raw[..])?.read_to_end(&mut buf)?;
+                buf
+            }
+        };
+        let n: usize = self.chunks.iter().product::<usize>() * self.dtype.size;
+        if bytes.len() < n {
+            return Err(invalid_data(&format!(
+                "The Zarr chunk {} is truncated.",
+                file_name.display()
+            )));
+        }
+        self.dtype.decode(&bytes[..n], values);
+        Ok(true)
+    }
+
+    /// The position within a chunk of the element with the within-chunk indices `local`.
+    fn chunk_offset(&self, local: &[usize]) -> usize {
+        let mut offset = 0;
+        let mut stride = 1;
+        if self.fortran_order {
+            for (i, chunk) in local.iter().zip(&self.chunks) {
+                offset += i * stride;
+                stride *= chunk;
+            }
+        } else {
+            for (i, chunk) in local.iter().zip(&self.chunks).rev() {
+                offset += i * stride;
+                stride *= chunk;
+            }
+        }
+        offset
+    }
+
+    /// Reads all of the values of a one-dimensional (e.g. coordinate) array.
+    fn read_1d(&self) -> Result<Vec<f64>, Error> {
+        let n = self.shape[0];
+        let c = self.chunks[0];
+        let mut out = vec![self.fill_value.unwrap_or(f64::NAN); n];
+        let mut values = vec![];
+        for ci in 0..n.div_ceil(c) {
+            if self.read_chunk(&[ci], &mut values)? {
+                for i in ci * c..((ci + 1) * c).min(n) {
+                    out[i] = values[i - ci * c];
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Finds the directory of the array to be read from a store, and the group containing it.
+fn locate_array(parts: &ZarrFileName) -> Result<(PathBuf, Option<PathBuf>), Error> {
+    let root = PathBuf::from(&parts.path);
+    if !root.is_dir() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("The Zarr store {} does not exist.", parts.path),
+        ));
+    }
+    if let Some(name) = &parts.array {
+        let dir = root.join(name);
+        if !ZarrArray::is_array(&dir) {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("The array '{}' was not found in the Zarr store.", name),
+            ));
+        }
+        let group = dir.parent().map(|p| p.to_path_buf());
+        return Ok((dir, group));
+    }
+    if ZarrArray::is_array(&root) {
+        return Ok((root, None));
+    }
+
+    // the first array with two or more dimensions that is not a coordinate array
+    let mut names = vec![];
+    for entry in fs::read_dir(&root)? {
+        let entry = entry?;
+        if ZarrArray::is_array(&entry.path()) {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    names.sort();
+    for name in &names {
+        let dir = root.join(name);
+        let array = ZarrArray::open(&dir)?;
+        let dims = array.dimension_names();
+        if array.shape.len() >= 2 && !dims.contains(name) {
+            return Ok((dir, Some(root)));
+        }
+    }
+    Err(invalid_data("The Zarr store contains no gridded arrays."))
+}
+
+/// Reads a Zarr array. See `read_zarr_window`.
+pub fn read_zarr(
+    file_name: &String,
+    configs: &mut RasterConfigs,
+    data: &mut Vec<f64>,
+) -> Result<(), Error> {
+    read_zarr_window(file_name, configs, data, None)
+}
+
+/// Reads a Zarr array into `configs` and `data`. If a `window` (first row, first column, number
+/// of rows, number of columns) is given, only the chunks that intersect the window are read, and
+/// `data` contains the values of the window's cells, while `configs` describes the full grid.
+pub fn read_zarr_window(
+    file_name: &str,
+    configs: &mut RasterConfigs,
+    data: &mut Vec<f64>,
+    window: Option<(usize, usize, usize, usize)>,
+) -> Result<(), Error> {
+    let parts = match parse_zarr_file_name(file_name) {
+        Some(p) => p,
+        None => return Err(invalid_data("The Zarr store name could not be parsed.")),
+    };
+    let (array_dir, group_dir) = locate_array(&parts)?;
+    let array = ZarrArray::open(&array_dir)?;
+    let array_name = array_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let nd = array.shape.len();
+    if !(2..=3).contains(&nd) {
+        return Err(invalid_data(&format!(
+            "The Zarr array '{}' has {} dimensions; only two- and three-dimensional arrays are supported.",
+            array_name, nd
+        )));
+    }
+    let (rows, columns) = (array.shape[nd - 2], array.shape[nd - 1]);
+    let num_slices = if nd == 3 { array.shape[0] } else { 1 };
+    let slice_range = match parts.slice {
+        Some(s) => {
+            if nd == 2 || s >= num_slices {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "The slice index {} is outside of the range of the Zarr array '{}'.",
+                        s, array_name
+                    ),
+                ));
+            }
+            s..s + 1
+        }
+        None => 0..num_slices,
+    };
+    if rows == 0 || columns == 0 || slice_range.is_empty() {
+        return Err(invalid_data(&format!(
+            "The Zarr array '{}' contains no data.",
+            array_name
+        )));
+    }
+    if slice_range.len() > 255 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("The Zarr array '{}' has {} slices, more than the 255 bands supported; select a slice by appending its index to the store name, e.g. '{}:{}[0]'.", array_name, slice_range.len(), parts.path, array_name),
+        ));
+    }
+    let attrs = &array.attrs;
+    let dim_names = array.dimension_names();
+    let (y_name, x_name) = (&dim_names[nd - 2], &dim_names[nd - 1]);
+    let group_array = |name: &str| -> Option<ZarrArray> {
+        let group_dir = group_dir.as_ref()?;
+        if name.is_empty() || !ZarrArray::is_array(&group_dir.join(name)) {
+            return None;
+        }
+        ZarrArray::open(&group_dir.join(name)).ok()
+    };
+    let grid_mapping = json_text(attrs, "grid_mapping").and_then(|gm| group_array(&gm));
+
+    // georeferencing
+    configs.rows = rows;
+    configs.columns = columns;
+    let (mut flip_columns, mut flip_rows) = (false, false);
+    let geo_transform = grid_mapping
+        .as_ref()
+        .and_then(|gm| json_text(&gm.attrs, "GeoTransform"))
+        .or_else(|| json_text(attrs, "GeoTransform"))
+        .map(|s| {
+            s.split_whitespace()
+                .filter_map(|v| v.parse::<f64>().ok())
+                .collect::<Vec<f64>>()
+        })
+        .filter(|gt| gt.len() == 6 && gt[1] != 0f64 && gt[5] != 0f64);
+    if let Some(gt) = geo_transform {
+        configs.resolution_x = gt[1].abs();
+        configs.resolution_y = gt[5].abs();
+        flip_columns = gt[1] < 0f64;
+        flip_rows = gt[5] > 0f64;
+        configs.west = if flip_columns {
+            gt[0] - columns as f64 * configs.resolution_x
+        } else {
+            gt[0]
+        };
+        configs.north = if flip_rows {
+            gt[3] + rows as f64 * configs.resolution_y
+        } else {
+            gt[3]
+        };
+    } else {
+        let x_coords = match group_array(x_name) {
+            Some(a) if a.shape == vec![columns] => Some(a.read_1d()?),
+            _ => None,
+        };
+        let y_coords = match group_array(y_name) {
+            Some(a) if a.shape == vec![rows] => Some(a.read_1d()?),
+            _ => None,
+        };
+        match x_coords {
+            Some(x) if columns > 1 => {
+                configs.resolution_x = ((x[columns - 1] - x[0]) / (columns - 1) as f64).abs();
+                flip_columns = x[0] > x[columns - 1];
+                configs.west = x[0].min(x[columns - 1]) - configs.resolution_x / 2f64;
+            }
+            _ => {
+                configs.resolution_x = 1f64;
+                configs.west = 0f64;
+            }
+        }
+        match y_coords {
+            Some(y) if rows > 1 => {
+                configs.resolution_y = ((y[rows - 1] - y[0]) / (rows - 1) as f64).abs();
+                flip_rows = y[0] < y[rows - 1];
+                configs.north = y[0].max(y[rows - 1]) + configs.resolution_y / 2f64;
+            }
+            _ => {
+                configs.resolution_y = 1f64;
+                configs.north = rows as f64;
+            }
+        }
+    }
+    configs.east = configs.west + columns as f64 * configs.resolution_x;
+    configs.south = configs.north - rows as f64 * configs.resolution_y;
+
+    // coordinate reference system
+    let epsg_from_text = |s: &str| -> u16 {
+        let s = s.to_lowercase();
+        let code = s.rsplit([':', '/']).next().unwrap_or("");
+        code.trim().parse::<u16>().unwrap_or(0)
+    };
+    if let Some(gm) = &grid_mapping {
+        if let Some(code) = json_text(&gm.attrs, "epsg_code") {
+            configs.epsg_code = epsg_from_text(&code);
+        }
+        if let Some(wkt) =
+            json_text(&gm.attrs, "crs_wkt").or_else(|| json_text(&gm.attrs, "spatial_ref"))
+        {
+            configs.coordinate_ref_system_wkt = wkt;
+        }
+    }
+    if let Some(crs) = attrs.get("_CRS") {
+        if let Some(wkt) = json_text(crs, "wkt") {
+            configs.coordinate_ref_system_wkt = wkt;
+        }
+        if let Some(url) = json_text(crs, "url") {
+            configs.epsg_code = epsg_from_text(&url);
+        }
+    }
+    if configs.epsg_code == 0 {
+        if let Some(code) = attrs.get("proj:epsg").and_then(|v| v.as_u64()) {
+            configs.epsg_code = code as u16;
+        }
+    }
+    if configs.epsg_code == 0
+        && is_unspecified(&configs.coordinate_ref_system_wkt)
+        && is_latitude(y_name)
+        && is_longitude(x_name)
+    {
+        configs.epsg_code = 4326;
+    }
+    if is_unspecified(&configs.coordinate_ref_system_wkt) && configs.epsg_code != 0 {
+        configs.coordinate_ref_system_wkt = esri_wkt_from_epsg(configs.epsg_code);
+    }
+    if let Some(x_array) = group_array(x_name) {
+        if let Some(units) = json_text(&x_array.attrs, "units") {
+            configs.xy_units = if units.contains("degree") {
+                "degrees".to_string()
+            } else {
+                units
+            };
+        }
+    }
+
+    // missing data and packing
+    let fill = json_text(attrs, "_FillValue")
+        .and_then(|s| json_number(&Value::String(s)))
+        .or_else(|| attrs.get("missing_value").and_then(json_number))
+        .or(array.fill_value);
+    let scale = attrs.get("scale_factor").and_then(json_number);
+    let offset = attrs.get("add_offset").and_then(json_number);
+    let packed = scale.is_some() || offset.is_some();
+    let (scale, offset) = (scale.unwrap_or(1f64), offset.unwrap_or(0f64));
+    configs.nodata = match fill {
+        Some(f) if !packed && !f.is_nan() => f,
+        _ => DEFAULT_NODATA,
+    };
+    configs.data_type = if packed {
+        if array.dtype.size <= 2 {
+            DataType::F32
+        } else {
+            DataType::F64
+        }
+    } else {
+        array.dtype.data_type()
+    };
+    configs.photometric_interp = PhotometricInterpretation::Continuous;
+    configs.endian = Endianness::LittleEndian;
+    configs.bands = slice_range.len() as u8;
+    if let Some(units) = json_text(attrs, "units") {
+        configs.z_units = units;
+    }
+    configs.title = json_text(attrs, "long_name").unwrap_or(array_name.clone());
+    if let Some(group_dir) = &group_dir {
+        if group_dir.join(".zattrs").is_file() {
+            let group_attrs = read_json(&group_dir.join(".zattrs"))?;
+            if let Some(history) = json_text(&group_attrs, "history") {
+                for line in history.lines().filter(|l| !l.trim().is_empty()) {
+                    configs.metadata.push(line.to_string());
+                }
+            }
+        }
+    }
+
+    // read the chunks that intersect the window
+    let (row0, col0, window_rows, window_columns) = window.unwrap_or((0, 0, rows, columns));
+    if window_rows == 0
+        || window_columns == 0
+        || row0 + window_rows > rows
+        || col0 + window_columns > columns
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "The window extends beyond the edges of the Zarr array.",
+        ));
+    }
+    // the array rows and columns of the window
+    let (src_row_min, src_row_max) = if flip_rows {
+        (rows - row0 - window_rows, rows - 1 - row0)
+    } else {
+        (row0, row0 + window_rows - 1)
+    };
+    let (src_col_min, src_col_max) = if flip_columns {
+        (columns - col0 - window_columns, columns - 1 - col0)
+    } else {
+        (col0, col0 + window_columns - 1)
+    };
+    let (chunk_rows, chunk_columns) = (array.chunks[nd - 2], array.chunks[nd - 1]);
+    let chunk_slices = if nd == 3 { array.chunks[0] } else { 1 };
+    let cells_per_band = window_rows * window_columns;
+    data.clear();
+    data.resize(cells_per_band * slice_range.len(), configs.nodata);
+    let mut values = vec![];
+    let first_slice = slice_range.start;
+    for cb in slice_range.start / chunk_slices..=(slice_range.end - 1) / chunk_slices {
+        for cy in src_row_min / chunk_rows..=src_row_max / chunk_rows {
+            for cx in src_col_min / chunk_columns..=src_col_max / chunk_columns {
+                let index = if nd == 3 {
+                    vec![cb, cy, cx]
+                } else {
+                    vec![cy, cx]
+                };
+                if !array.read_chunk(&index, &mut values)? {
+                    continue; // the chunk contains only the fill value
+                }
+                let slices = (cb * chunk_slices).max(slice_range.start)
+                    ..((cb + 1) * chunk_slices).min(slice_range.end);
+                for k in slices {
+                    let band_offset = (k - first_slice) * cells_per_band;
+                    for sr in (cy * chunk_rows).max(src_row_min)
+                        ..=((cy + 1) * chunk_rows - 1).min(src_row_max)
+                    {
+                        let out_row = if flip_rows { rows - 1 - sr } else { sr } - row0;
+                        for sc in (cx * chunk_columns).max(src_col_min)
+                            ..=((cx + 1) * chunk_columns - 1).min(src_col_max)
+                        {
+                            let out_col = if flip_columns { columns - 1 - sc } else { sc } - col0;
+                            let local = if nd == 3 {
+                                vec![k - cb * chunk_slices, sr - cy * chunk_rows, sc - cx * chunk_columns]
+                            } else {
+                                vec![sr - cy * chunk_rows, sc - cx * chunk_columns]
+                            };
+                            let v = values[array.chunk_offset(&local)];
+                            let is_fill = match fill {
+                                Some(f) => v == f,
+                                None => false,
+                            };
+                            data[band_offset + out_row * window_columns + out_col] =
+                                if is_fill || v.is_nan() {
+                                    configs.nodata
+                                } else {
+                                    v * scale + offset
+                                };
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    configs.minimum = f64::INFINITY;
+    configs.maximum = f64::NEG_INFINITY;
+    for &v in data.iter() {
+        if v != configs.nodata {
+            configs.minimum = configs.minimum.min(v);
+            configs.maximum = configs.maximum.max(v);
+        }
+    }
+    configs.display_min = configs.minimum;
+    configs.display_max = configs.maximum;
+
+    Ok(())
+}
+
+/// Writes the array `name` to the group directory `group`, replacing any existing array of
+/// the same name.
+fn create_array_dir(group: &Path, name: &str, zarray: &Value, zattrs: &Value) -> Result<PathBuf, Error> {
+    let dir = group.join(name);
+    if ZarrArray::is_array(&dir) {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(&dir)?;
+    write_json(&dir.join(".zarray"), zarray)?;
+    write_json(&dir.join(".zattrs"), zattrs)?;
+    Ok(dir)
+}
+
+/// Writes a raster to a Zarr store, as a group containing the (zlib-compressed) data array, its
+/// x and y coordinate arrays, and a CF grid mapping array holding the coordinate reference system.
+pub fn write_zarr<'a>(r: &'a mut Raster) -> Result<(), Error> {
+    let parts = match parse_zarr_file_name(&r.file_name) {
+        Some(p) => p,
+        None => return Err(invalid_data("The Zarr store name could not be parsed.")),
+    };
+    let array_name = parts
+        .array
+        .clone()
+        .unwrap_or(DEFAULT_ARRAY_NAME.to_string());
+    let root = PathBuf::from(&parts.path);
+    if root.is_file() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} is a file rather than a Zarr store directory.", parts.path),
+        ));
+    }
+    if ZarrArray::is_array(&root) {
+        // a single-array store is replaced by a group
+        fs::remove_dir_all(&root)?;
+    }
+    fs::create_dir_all(&root)?;
+    let array_path = root.join(&array_name);
+    let group_dir = array_path.parent().unwrap_or(&root).to_path_buf();
+    fs::create_dir_all(&group_dir)?;
+    if !group_dir.join(".zgroup").is_file() {
+        write_json(&group_dir.join(".zgroup"), &json!({ "zarr_format": 2 }))?;
+    }
+    let leaf_name = array_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or(array_name.clone());
+
+    let rows = r.configs.rows;
+    let columns = r.configs.columns;
+    let bands = r.num_bands();
+    let nodata = r.configs.nodata;
+    let dtype = ZarrType::for_data_type(RasterData::storage_type_for(
+        r.configs.data_type,
+        &[nodata],
+    ));
+
+    let geographic = r.configs.epsg_code == 4326
+        || (r.configs.west >= -180f64
+            && r.configs.east <= 360f64
+            && r.configs.south >= -90f64
+            && r.configs.north <= 90f64
+            && r.configs.xy_units.to_lowercase().contains("deg"));
+    let (x_name, y_name) = if geographic { ("lon", "lat") } else { ("x", "y") };
+
+    // group attributes
+    let mut group_attrs = Map::new();
+    group_attrs.insert("Conventions".to_string(), json!("CF-1.6"));
+    if !r.configs.title.is_empty() {
+        group_attrs.insert("title".to_string(), json!(r.configs.title));
+    }
+    group_attrs.insert("source".to_string(), json!("WhiteboxTools"));
+    if !r.configs.metadata.is_empty() {
+        group_attrs.insert("history".to_string(), json!(r.configs.metadata.join("\n")));
+    }
+    write_json(&group_dir.join(".zattrs"), &Value::Object(group_attrs))?;
+
+    // coordinate arrays
+    for (name, axis, n) in [(x_name, "X", columns), (y_name, "Y", rows)].iter() {
+        let mut attrs = Map::new();
+        attrs.insert("_ARRAY_DIMENSIONS".to_string(), json!([name]));
+        attrs.insert("axis".to_string(), json!(axis));
+        if geographic {
+            if *axis == "X" {
+                attrs.insert("standard_name".to_string(), json!("longitude"));
+                attrs.insert("units".to_string(), json!("degrees_east"));
+            } else {
+                attrs.insert("standard_name".to_string(), json!("latitude"));
+                attrs.insert("units".to_string(), json!("degrees_north"));
+            }
+        } else {
+            let standard_name = if *axis == "X" {
+                "projection_x_coordinate"
+            } else {
+                "projection_y_coordinate"
+            };
+            attrs.insert("standard_name".to_string(), json!(standard_name));
+            if !r.configs.xy_units.is_empty()
+                && r.configs.xy_units.to_lowercase() != "not specified"
+            {
+                attrs.insert("units".to_string(), json!(r.configs.xy_units));
+            }
+        }
+        let zarray = json!({
+            "zarr_format": 2,
+            "shape": [n],
+            "chunks": [n],
+            "dtype": "<f8",
+            "compressor": null,
+            "fill_value": "NaN",
+            "filters": null,
+            "order": "C"
+        });
+        let dir = create_array_dir(&group_dir, name, &zarray, &Value::Object(attrs))?;
+        let mut buf = Vec::with_capacity(n * 8);
+        for i in 0..*n as isize {
+            let v = if *axis == "X" {
+                r.get_x_from_column(i)
+            } else {
+                r.get_y_from_row(i)
+            };
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        fs::write(dir.join("0"), buf)?;
+    }
+
+    // grid mapping
+    let has_wkt = !is_unspecified(&r.configs.coordinate_ref_system_wkt);
+    let has_crs = r.configs.epsg_code != 0 || has_wkt;
+    if has_crs {
+        let mut attrs = Map::new();
+        attrs.insert("_ARRAY_DIMENSIONS".to_string(), json!([]));
+        if r.configs.epsg_code != 0 {
+            attrs.insert(
+                "epsg_code".to_string(),
+                json!(format!("EPSG:{}", r.configs.epsg_code)),
+            );
+        }
+        if has_wkt {
+            attrs.insert("crs_wkt".to_string(), json!(r.configs.coordinate_ref_system_wkt));
+            attrs.insert("spatial_ref".to_string(), json!(r.configs.coordinate_ref_system_wkt));
+        }
+        attrs.insert(
+            "GeoTransform".to_string(),
+            json!(format!(
+                "{} {} 0 {} 0 {}",
+                r.configs.west, r.configs.resolution_x, r.configs.north, -r.configs.resolution_y
+            )),
+        );
+        let zarray = json!({
+            "zarr_format": 2,
+            "shape": [],
+            "chunks": [],
+            "dtype": "<i4",
+            "compressor": null,
+            "fill_value": 0,
+            "filters": null,
+            "order": "C"
+        });
+        let dir = create_array_dir(&group_dir, "spatial_ref", &zarray, &Value::Object(attrs))?;
+        fs::write(dir.join("0"), 0i32.to_le_bytes())?;
+    }
+
+    // the data array
+    let mut attrs = Map::new();
+    let mut dims = vec![json!(y_name), json!(x_name)];
+    let mut shape = vec![rows, columns];
+    let chunk_rows = rows.min(CHUNK_SIZE);
+    let chunk_columns = columns.min(CHUNK_SIZE);
+    let mut chunks = vec![chunk_rows, chunk_columns];
+    if bands > 1 {
+        dims.insert(0, json!("band"));
+        shape.insert(0, bands);
+        chunks.insert(0, 1);
+    }
+    attrs.insert("_ARRAY_DIMENSIONS".to_string(), Value::Array(dims));
+    attrs.insert("long_name".to_string(), json!(leaf_name));
+    if !r.configs.z_units.is_empty() && r.configs.z_units.to_lowercase() != "not specified" {
+        attrs.insert("units".to_string(), json!(r.configs.z_units));
+    }
+    if has_crs {
+        attrs.insert("grid_mapping".to_string(), json!("spatial_ref"));
+        let mut crs = Map::new();
+        if has_wkt {
+            crs.insert("wkt".to_string(), json!(r.configs.coordinate_ref_system_wkt));
+        }
+        if r.configs.epsg_code != 0 {
+            crs.insert(
+                "url".to_string(),
+                json!(format!(
+                    "http://www.opengis.net/def/crs/EPSG/0/{}",
+                    r.configs.epsg_code
+                )),
+            );
+        }
+        attrs.insert("_CRS".to_string(), Value::Object(crs));
+    }
+    let zarray = json!({
+        "zarr_format": 2,
+        "shape": shape,
+        "chunks": chunks,
+        "dtype": dtype.dtype_string(),
+        "compressor": { "id": "zlib" },
+        "fill_value": number_json(nodata),
+        "filters": null,
+        "order": "C"
+    });
+    let dir = create_array_dir(&group_dir, &leaf_name, &zarray, &Value::Object(attrs))?;
+
+    // chunks that contain only nodata are not written
+    let mut buf = Vec::with_capacity(chunk_rows * chunk_columns * dtype.size);
+    for band in 0..bands {
+        let band_nodata = r.get_band_nodata(band);
+        for cy in 0..rows.div_ceil(chunk_rows) {
+            for cx in 0..columns.div_ceil(chunk_columns) {
+                buf.clear();
+                let mut is_empty = true;
+                for row in cy * chunk_rows..(cy + 1) * chunk_rows {
+                    for col in cx * chunk_columns..(cx + 1) * chunk_columns {
+                        let z = if row < rows && col < columns {
+                            r.get_band_value(band, row as isize, col as isize)
+                        } else {
+                            band_nodata
+                        };
+                        if z == band_nodata {
+                            dtype.encode(nodata, &mut buf);
+                        } else {
+                            is_empty = false;
+                            dtype.encode(z, &mut buf);
+                        }
+                    }
+                }
+                if is_empty {
+                    continue;
+                }
+                let mut encoder = zlib::Encoder::new(Vec::with_capacity(buf.len() / 2))?;
+                encoder.write_all(&buf)?;
+                let compressed = encoder.finish().into_result()?;
+                let key = if bands > 1 {
+                    format!("{}.{}.{}", band, cy, cx)
+                } else {
+                    format!("{}.{}", cy, cx)
+                };
+                fs::write(dir.join(key), compressed)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_zarr_file_name, ZarrType};
+    use crate::raster::{DataType, Raster, RasterConfigs};
+
+    #[test]
+    fn test_parse_zarr_file_name() {
+        let p = parse_zarr_file_name("/data/dem.zarr").unwrap();
+        assert_eq!(p.path, "/data/dem.zarr");
+        assert_eq!((p.array, p.slice), (None, None));
+
+        let p = parse_zarr_file_name("/data/dem.zarr/").unwrap();
+        assert_eq!(p.path, "/data/dem.zarr");
+
+        let p = parse_zarr_file_name("C:\\data\\cube.ZARR:ndvi[3]").unwrap();
+        assert_eq!(p.path, "C:\\data\\cube.ZARR");
+        assert_eq!(p.array, Some("ndvi".to_string()));
+        assert_eq!(p.slice, Some(3));
+
+        assert!(parse_zarr_file_name("/data/dem.tif").is_none());
+        assert!(parse_zarr_file_name("/data/dem.zarr/data/0.0").is_none());
+        assert!(parse_zarr_file_name("/data/dem.zarr[a]").is_none());
+    }
+
+    #[test]
+    fn test_zarr_type() {
+        let t = ZarrType::parse(">i2").unwrap();
+        assert!(t.big_endian);
+        let mut values = vec![];
+        t.decode(&[0xff, 0xfe, 0x01, 0x00], &mut values);
+        assert_eq!(values, vec![-2.0, 256.0]);
+        assert_eq!(ZarrType::parse("|u1").unwrap().dtype_string(), "|u1");
+        assert!(ZarrType::parse("<c8").is_none());
+        assert!(ZarrType::parse("<f2").is_none());
+    }
+
+    #[test]
+    fn test_zarr_window_round_trip() {
+        // a 300 x 600 grid spans two rows and three columns of 256 x 256 chunks
+        let mut configs = RasterConfigs::default();
+        configs.rows = 300;
+        configs.columns = 600;
+        configs.west = 1000.0;
+        configs.north = 5000.0;
+        configs.resolution_x = 10.0;
+        configs.resolution_y = 5.0;
+        configs.east = configs.west + 6000.0;
+        configs.south = configs.north - 1500.0;
+        configs.nodata = -32768.0;
+        configs.data_type = DataType::I32;
+        configs.epsg_code = 32617;
+        let dir = std::env::temp_dir().join("wbt_zarr_window_test.zarr");
+        let file_name = dir.to_str().unwrap().to_string();
+        let mut output = Raster::initialize_using_config(&file_name, &configs);
+        for row in 0..300 {
+            for col in 0..600 {
+                let z = if row < 10 { -32768.0 } else { (row * 1000 + col) as f64 };
+                output.set_value(row, col, z);
+            }
+        }
+        output.write().unwrap();
+
+        let window = Raster::new_window(&file_name, 250, 500, 40, 30).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!((window.configs.rows, window.configs.columns), (40, 30));
+        assert_eq!(window.configs.west, 6000.0);
+        assert_eq!(window.configs.north, 3750.0);
+        assert_eq!(window.configs.epsg_code, 32617);
+        assert_eq!(window.configs.data_type, DataType::I32);
+        assert_eq!(window.get_value(0, 0), 250500.0);
+        assert_eq!(window.get_value(39, 29), 289529.0);
+        assert!(Raster::new_window(&file_name, 290, 0, 20, 10).is_err());
+    }
+}
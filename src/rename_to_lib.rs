@@ -1,6 +1,6 @@
 // This is an test file used to experiment with a whitebox_tools shared library (DLL).
-// It is not intended for widespread use. Please note, that the file must be renamed
-// 'lib.rs' and the Cargo.toml file must have a lib entry for this to work.
+// It is not intended for widespread use. Please note, that its contents must be moved
+// into lib.rs and the Cargo.toml file must have a cdylib crate type for this to work.
 
 // extern crate libc;
 // extern crate byteorder;
@@ -0,0 +1,815 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::esri_wkt_from_epsg;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+
+/// The reference ellipsoid of a geodetic datum, described by its semi-major axis (`a`, in
+/// metres) and flattening (`f`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ellipsoid {
+    pub a: f64,
+    pub f: f64,
+}
+
+impl Ellipsoid {
+    pub const WGS84: Ellipsoid = Ellipsoid {
+        a: 6378137.0,
+        f: 1.0 / 298.257223563,
+    };
+
+    /// Creates an ellipsoid from its semi-major axis and inverse flattening. An inverse
+    /// flattening of zero describes a sphere.
+    pub fn new(a: f64, inverse_flattening: f64) -> Ellipsoid {
+        let f = if inverse_flattening == 0f64 {
+            0f64
+        } else {
+            1f64 / inverse_flattening
+        };
+        Ellipsoid { a, f }
+    }
+
+    /// Returns the ellipsoid with a PROJ name, e.g. `GRS80`.
+    pub fn from_name(name: &str) -> Option<Ellipsoid> {
+        match name.to_lowercase().as_str() {
+            "wgs84" => Some(Ellipsoid::WGS84),
+            "grs80" => Some(Ellipsoid::new(6378137.0, 298.257222101)),
+            "clrk66" => Some(Ellipsoid::new(6378206.4, 294.9786982)),
+            "clrk80" => Some(Ellipsoid::new(6378249.145, 293.4663)),
+            "airy" => Some(Ellipsoid::new(6377563.396, 299.3249646)),
+            "mod_airy" => Some(Ellipsoid::new(6377340.189, 299.3249646)),
+            "bessel" => Some(Ellipsoid::new(6377397.155, 299.1528128)),
+            "intl" => Some(Ellipsoid::new(6378388.0, 297.0)),
+            "krass" => Some(Ellipsoid::new(6378245.0, 298.3)),
+            "wgs72" => Some(Ellipsoid::new(6378135.0, 298.26)),
+            "sphere" => Some(Ellipsoid::new(6370997.0, 0.0)),
+            _ => None,
+        }
+    }
+
+    /// The squared first eccentricity.
+    pub fn e2(&self) -> f64 {
+        self.f * (2f64 - self.f)
+    }
+}
+
+/// The map projection of a coordinate reference system.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProjectionMethod {
+    /// Longitude and latitude, in degrees.
+    Geographic,
+    /// Transverse Mercator, including UTM and Gauss-Kruger systems.
+    TransverseMercator {
+        lat0: f64,
+        lon0: f64,
+        k0: f64,
+        x0: f64,
+        y0: f64,
+    },
+    /// Lambert Conformal Conic, with one (`lat1 == lat2`) or two standard parallels.
+    LambertConformalConic {
+        lat0: f64,
+        lon0: f64,
+        lat1: f64,
+        lat2: f64,
+        k0: f64,
+        x0: f64,
+        y0: f64,
+    },
+}
+
+/// A horizontal coordinate reference system that can transform coordinates to and from
+/// longitude and latitude. Projections may be created from EPSG codes, ESRI/OGC WKT, or PROJ
+/// strings (e.g. `+proj=utm +zone=17 +datum=WGS84`). Angles are in degrees, and the false
+/// eastings and northings (`x0` and `y0`) of the `ProjectionMethod` are in metres.
+///
+/// Datum transformations use the seven-parameter (Helmert) transformation to WGS84 of each
+/// system, when known, either from a PROJ `+towgs84` parameter or from a small list of datums
+/// that are equivalent to WGS84 at the metre level or that have well-established parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Projection {
+    pub name: String,
+    pub epsg_code: Option<u16>,
+    pub datum: String,
+    pub ellipsoid: Ellipsoid,
+    /// The Helmert parameters (dx, dy, dz in metres; rx, ry, rz in arc-seconds; scale in ppm)
+    /// that transform the datum into WGS84, using the position vector convention.
+    pub to_wgs84: Option<[f64; 7]>,
+    /// The longitude of the prime meridian, relative to Greenwich, in degrees.
+    pub prime_meridian: f64,
+    pub method: ProjectionMethod,
+    /// The length of the linear unit in metres.
+    pub linear_unit: f64,
+}
+
+impl Projection {
+    /// Creates a projection from an EPSG code (e.g. `32617`), an `EPSG:` code, a PROJ string,
+    /// or a WKT string.
+    pub fn parse(s: &str) -> Result<Projection, Error> {
+        let s = s.trim();
+        let lower = s.to_lowercase();
+        let code = lower.trim_start_matches("epsg:");
+        if let Ok(code) = code.parse::<u16>() {
+            return Projection::from_epsg(code);
+        }
+        if s.starts_with('+') {
+            return Projection::from_proj_string(s);
+        }
+        Projection::from_wkt(s)
+    }
+
+    /// Creates a projection from an EPSG code.
+    pub fn from_epsg(code: u16) -> Result<Projection, Error> {
+        let wkt = esri_wkt_from_epsg(code);
+        if wkt.starts_with("Unknown") {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unrecognized EPSG code {}.", code),
+            ));
+        }
+        let mut p = Projection::from_wkt(&wkt)?;
+        p.epsg_code = Some(code);
+        Ok(p)
+    }
+
+    /// Creates a projection from an ESRI or OGC (version 1) WKT string.
+    pub fn from_wkt(wkt: &str) -> Result<Projection, Error> {
+        let upper = wkt.to_uppercase();
+        if !upper.starts_with("PROJCS[") && !upper.starts_with("GEOGCS[") {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The coordinate reference system is not a PROJCS or GEOGCS WKT string.",
+            ));
+        }
+        let name = wkt_quoted_value(wkt, 0).unwrap_or_default();
+        let datum = match upper.find("DATUM[") {
+            Some(i) => wkt_quoted_value(wkt, i).unwrap_or_default(),
+            None => String::new(),
+        };
+        let ellipsoid = match upper.find("SPHEROID[").or_else(|| upper.find("ELLIPSOID[")) {
+            Some(i) => {
+                let values = wkt_numbers(&wkt[i..]);
+                if values.len() < 2 {
+                    return Err(Error::new(ErrorKind::InvalidInput, "Invalid SPHEROID in WKT."));
+                }
+                Ellipsoid::new(values[0], values[1])
+            }
+            None => Ellipsoid::WGS84,
+        };
+        let prime_meridian = match upper.find("PRIMEM[") {
+            Some(i) => wkt_numbers(&wkt[i..]).first().cloned().unwrap_or(0f64),
+            None => 0f64,
+        };
+        let to_wgs84 = match upper.find("TOWGS84[") {
+            Some(i) => {
+                let v = wkt_numbers(&wkt[i..]);
+                if v.len() >= 7 {
+                    Some([v[0], v[1], v[2], v[3], v[4], v[5], v[6]])
+                } else if v.len() >= 3 {
+                    Some([v[0], v[1], v[2], 0f64, 0f64, 0f64, 0f64])
+                } else {
+                    None
+                }
+            }
+            None => known_datum_shift(&datum),
+        };
+
+        if upper.starts_with("GEOGCS[") {
+            return Ok(Projection {
+                name,
+                epsg_code: None,
+                datum,
+                ellipsoid,
+                to_wgs84,
+                prime_meridian,
+                method: ProjectionMethod::Geographic,
+                linear_unit: 1f64,
+            });
+        }
+
+        // the linear unit of the projected system is its last UNIT
+        let linear_unit = match upper.rfind("UNIT[") {
+            Some(i) => wkt_numbers(&wkt[i..]).first().cloned().unwrap_or(1f64),
+            None => 1f64,
+        };
+        let projection = match upper.find("PROJECTION[") {
+            Some(i) => wkt_quoted_value(wkt, i).unwrap_or_default().to_lowercase(),
+            None => String::new(),
+        };
+        let param = |name: &str, default: f64| -> f64 {
+            let key = format!("PARAMETER[\"{}\"", name.to_uppercase());
+            match upper.find(&key) {
+                Some(i) => wkt_numbers(&wkt[i + key.len()..]).first().cloned().unwrap_or(default),
+                None => default,
+            }
+        };
+        // false eastings and northings are expressed in the linear units
+        let x0 = param("False_Easting", 0f64) * linear_unit;
+        let y0 = param("False_Northing", 0f64) * linear_unit;
+        let lon0 = param("Central_Meridian", param("Longitude_Of_Origin", 0f64));
+        let lat0 = param("Latitude_Of_Origin", 0f64);
+        let k0 = param("Scale_Factor", 1f64);
+        let method = if projection.contains("transverse_mercator") || projection.contains("gauss_kruger") {
+            ProjectionMethod::TransverseMercator { lat0, lon0, k0, x0, y0 }
+        } else if projection.contains("lambert_conformal_conic") {
+            let lat1 = param("Standard_Parallel_1", lat0);
+            let lat2 = param("Standard_Parallel_2", lat1);
+            ProjectionMethod::LambertConformalConic { lat0, lon0, lat1, lat2, k0, x0, y0 }
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unsupported projection '{}'. Transverse Mercator (including UTM), Lambert Conformal Conic, and geographic coordinates are supported.", projection),
+            ));
+        };
+        Ok(Projection {
+            name,
+            epsg_code: None,
+            datum,
+            ellipsoid,
+            to_wgs84,
+            prime_meridian,
+            method,
+            linear_unit,
+        })
+    }
+
+    /// Creates a projection from a PROJ string. The `utm`, `tmerc`, `etmerc`, `lcc`, and
+    /// `longlat` projections are supported, along with the `ellps`, `datum`, `a`, `rf`,
+    /// `towgs84`, `units`, and `to_meter` parameters.
+    pub fn from_proj_string(s: &str) -> Result<Projection, Error> {
+        let mut proj = String::new();
+        let mut params = std::collections::HashMap::new();
+        let mut south = false;
+        for token in s.split_whitespace() {
+            let token = token.trim_start_matches('+');
+            if token.is_empty() {
+                continue;
+            }
+            let mut kv = token.splitn(2, '=');
+            let key = kv.next().unwrap_or("").to_lowercase();
+            let value = kv.next().unwrap_or("").to_string();
+            match key.as_str() {
+                "proj" => proj = value.to_lowercase(),
+                "south" => south = true,
+                _ => {
+                    params.insert(key, value);
+                }
+            }
+        }
+        let num = |key: &str, default: f64| -> Result<f64, Error> {
+            match params.get(key) {
+                Some(v) => v.parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Invalid value for PROJ parameter '{}': {}", key, v),
+                    )
+                }),
+                None => Ok(default),
+            }
+        };
+
+        let mut datum = String::new();
+        let mut ellipsoid = Ellipsoid::WGS84;
+        let mut to_wgs84 = None;
+        if let Some(d) = params.get("datum") {
+            datum = d.to_string();
+            let (e, shift) = match d.to_lowercase().as_str() {
+                "wgs84" => (Ellipsoid::WGS84, [0f64; 7]),
+                "nad83" => (Ellipsoid::from_name("GRS80").unwrap(), [0f64; 7]),
+                "osgb36" => (
+                    Ellipsoid::from_name("airy").unwrap(),
+                    known_datum_shift("OSGB_1936").unwrap(),
+                ),
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unsupported PROJ datum '{}'; use +ellps and +towgs84 instead.", d),
+                    ))
+                }
+            };
+            ellipsoid = e;
+            to_wgs84 = Some(shift);
+        }
+        if let Some(e) = params.get("ellps") {
+            ellipsoid = match Ellipsoid::from_name(e) {
+                Some(e) => e,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unsupported PROJ ellipsoid '{}'.", e),
+                    ))
+                }
+            };
+            if datum.is_empty() {
+                datum = e.to_string();
+                if e.eq_ignore_ascii_case("wgs84") || e.eq_ignore_ascii_case("grs80") {
+                    to_wgs84 = Some([0f64; 7]);
+                }
+            }
+        }
+        if params.contains_key("a") {
+            ellipsoid = Ellipsoid::new(num("a", 6378137.0)?, num("rf", 0f64)?);
+        }
+        if let Some(v) = params.get("towgs84") {
+            let mut shift = [0f64; 7];
+            for (i, p) in v.split(',').take(7).enumerate() {
+                shift[i] = p.trim().parse::<f64>().map_err(|_| {
+                    Error::new(ErrorKind::InvalidInput, format!("Invalid +towgs84 value: {}", v))
+                })?;
+            }
+            to_wgs84 = Some(shift);
+            if datum.is_empty() {
+                datum = "towgs84".to_string();
+            }
+        }
+        if datum.is_empty() {
+            datum = "WGS84".to_string();
+            to_wgs84 = Some([0f64; 7]);
+        }
+
+        let linear_unit = if params.contains_key("to_meter") {
+            num("to_meter", 1f64)?
+        } else {
+            match params.get("units").map(|u| u.to_lowercase()) {
+                Some(ref u) if u == "ft" => 0.3048,
+                Some(ref u) if u == "us-ft" => 1200.0 / 3937.0,
+                Some(ref u) if u == "km" => 1000.0,
+                _ => 1f64,
+            }
+        };
+
+        let method = match proj.as_str() {
+            "longlat" | "latlong" | "lonlat" | "latlon" => ProjectionMethod::Geographic,
+            "utm" => {
+                let zone = num("zone", 0f64)?;
+                if !(1f64..=60f64).contains(&zone) || zone.fract() != 0f64 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "A UTM projection requires a +zone parameter between 1 and 60.",
+                    ));
+                }
+                ProjectionMethod::TransverseMercator {
+                    lat0: 0f64,
+                    lon0: zone * 6f64 - 183f64,
+                    k0: 0.9996,
+                    x0: 500000f64,
+                    y0: if south { 10000000f64 } else { 0f64 },
+                }
+            }
+            "tmerc" | "etmerc" => ProjectionMethod::TransverseMercator {
+                lat0: num("lat_0", 0f64)?,
+                lon0: num("lon_0", 0f64)?,
+                k0: num("k_0", num("k", 1f64)?)?,
+                x0: num("x_0", 0f64)?,
+                y0: num("y_0", 0f64)?,
+            },
+            "lcc" => {
+                let lat1 = num("lat_1", num("lat_0", 0f64)?)?;
+                ProjectionMethod::LambertConformalConic {
+                    lat0: num("lat_0", lat1)?,
+                    lon0: num("lon_0", 0f64)?,
+                    lat1,
+                    lat2: num("lat_2", lat1)?,
+                    k0: num("k_0", num("k", 1f64)?)?,
+                    x0: num("x_0", 0f64)?,
+                    y0: num("y_0", 0f64)?,
+                }
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unsupported PROJ projection '{}'. Supported projections include utm, tmerc, lcc, and longlat.", proj),
+                ))
+            }
+        };
+
+        Ok(Projection {
+            name: s.trim().to_string(),
+            epsg_code: None,
+            datum,
+            ellipsoid,
+            to_wgs84,
+            prime_meridian: 0f64,
+            method,
+            linear_unit,
+        })
+    }
+
+    /// Returns true if the coordinates are longitudes and latitudes.
+    pub fn is_geographic(&self) -> bool {
+        self.method == ProjectionMethod::Geographic
+    }
+
+    /// Projects a longitude and latitude (degrees, relative to Greenwich) into map coordinates,
+    /// in the linear units of the projection.
+    pub fn forward(&self, lon: f64, lat: f64) -> (f64, f64) {
+        let lon = lon - self.prime_meridian;
+        match self.method {
+            ProjectionMethod::Geographic => (lon, lat),
+            ProjectionMethod::TransverseMercator { lat0, lon0, k0, x0, y0 } => {
+                let tm = TransverseMercator::new(self.ellipsoid, lat0, k0);
+                let (x, y) = tm.forward((lon - lon0).to_radians(), lat.to_radians());
+                ((x + x0) / self.linear_unit, (y + y0) / self.linear_unit)
+            }
+            ProjectionMethod::LambertConformalConic { lat0, lon0, lat1, lat2, k0, x0, y0 } => {
+                let lcc = LambertConformalConic::new(self.ellipsoid, lat0, lat1, lat2, k0);
+                let (x, y) = lcc.forward((lon - lon0).to_radians(), lat.to_radians());
+                ((x + x0) / self.linear_unit, (y + y0) / self.linear_unit)
+            }
+        }
+    }
+
+    /// Converts map coordinates, in the linear units of the projection, into a longitude and
+    /// latitude (degrees, relative to Greenwich).
+    pub fn inverse(&self, x: f64, y: f64) -> (f64, f64) {
+        let (lon, lat) = match self.method {
+            ProjectionMethod::Geographic => (x, y),
+            ProjectionMethod::TransverseMercator { lat0, lon0, k0, x0, y0 } => {
+                let tm = TransverseMercator::new(self.ellipsoid, lat0, k0);
+                let (dlon, lat) = tm.inverse(x * self.linear_unit - x0, y * self.linear_unit - y0);
+                (dlon.to_degrees() + lon0, lat.to_degrees())
+            }
+            ProjectionMethod::LambertConformalConic { lat0, lon0, lat1, lat2, k0, x0, y0 } => {
+                let lcc = LambertConformalConic::new(self.ellipsoid, lat0, lat1, lat2, k0);
+                let (dlon, lat) = lcc.inverse(x * self.linear_unit - x0, y * self.linear_unit - y0);
+                (dlon.to_degrees() + lon0, lat.to_degrees())
+            }
+        };
+        (lon + self.prime_meridian, lat)
+    }
+
+    /// Returns true if transforming coordinates into the `other` system involves a change of
+    /// datum.
+    pub fn datum_differs(&self, other: &Projection) -> bool {
+        if self.ellipsoid != other.ellipsoid {
+            return true;
+        }
+        match (self.to_wgs84, other.to_wgs84) {
+            (Some(a), Some(b)) => a != b,
+            _ => normalize_datum_name(&self.datum) != normalize_datum_name(&other.datum),
+        }
+    }
+
+    /// Returns true if coordinates can be transformed into the `other` system, i.e. the datums
+    /// are the same or both have known transformations to WGS84.
+    pub fn can_transform_datum(&self, other: &Projection) -> bool {
+        !self.datum_differs(other) || (self.to_wgs84.is_some() && other.to_wgs84.is_some())
+    }
+
+    /// Transforms a point from this coordinate system into the `target` system. When the datums
+    /// differ, the transformation is carried out through WGS84 geocentric coordinates, assuming
+    /// a point on the ellipsoid; if either datum's transformation is unknown, only the map
+    /// projection is changed.
+    pub fn transform_to(&self, target: &Projection, x: f64, y: f64) -> (f64, f64) {
+        let (mut lon, mut lat) = self.inverse(x, y);
+        if self.datum_differs(target) {
+            if let (Some(src), Some(dst)) = (self.to_wgs84, target.to_wgs84) {
+                let mut xyz = geodetic_to_geocentric(self.ellipsoid, lon, lat);
+                xyz = helmert(xyz, &src, false);
+                xyz = helmert(xyz, &dst, true);
+                let (lo, la) = geocentric_to_geodetic(target.ellipsoid, xyz);
+                lon = lo;
+                lat = la;
+            }
+        }
+        target.forward(lon, lat)
+    }
+
+    /// Returns an ESRI WKT description of the projection.
+    pub fn to_wkt(&self) -> String {
+        if let Some(code) = self.epsg_code {
+            return esri_wkt_from_epsg(code);
+        }
+        let inv_f = if self.ellipsoid.f == 0f64 {
+            0f64
+        } else {
+            1f64 / self.ellipsoid.f
+        };
+        let geogcs = format!(
+            "GEOGCS[\"GCS_{0}\",DATUM[\"D_{0}\",SPHEROID[\"{0}\",{1},{2}]],PRIMEM[\"Greenwich\",{3}],UNIT[\"Degree\",0.0174532925199433]]",
+            self.datum, self.ellipsoid.a, inv_f, self.prime_meridian
+        );
+        let unit = if (self.linear_unit - 0.3048).abs() < 1e-9 {
+            "Foot".to_string()
+        } else if (self.linear_unit - 1200.0 / 3937.0).abs() < 1e-9 {
+            "Foot_US".to_string()
+        } else {
+            "Meter".to_string()
+        };
+        match self.method {
+            ProjectionMethod::Geographic => geogcs,
+            ProjectionMethod::TransverseMercator { lat0, lon0, k0, x0, y0 } => format!(
+                "PROJCS[\"{}\",{},PROJECTION[\"Transverse_Mercator\"],PARAMETER[\"False_Easting\",{}],PARAMETER[\"False_Northing\",{}],PARAMETER[\"Central_Meridian\",{}],PARAMETER[\"Scale_Factor\",{}],PARAMETER[\"Latitude_Of_Origin\",{}],UNIT[\"{}\",{}]]",
+                self.name.replace("\"", ""), geogcs, x0 / self.linear_unit, y0 / self.linear_unit, lon0, k0, lat0, unit, self.linear_unit
+            ),
+            ProjectionMethod::LambertConformalConic { lat0, lon0, lat1, lat2, k0, x0, y0 } => format!(
+                "PROJCS[\"{}\",{},PROJECTION[\"Lambert_Conformal_Conic\"],PARAMETER[\"False_Easting\",{}],PARAMETER[\"False_Northing\",{}],PARAMETER[\"Central_Meridian\",{}],PARAMETER[\"Standard_Parallel_1\",{}],PARAMETER[\"Standard_Parallel_2\",{}],PARAMETER[\"Scale_Factor\",{}],PARAMETER[\"Latitude_Of_Origin\",{}],UNIT[\"{}\",{}]]",
+                self.name.replace("\"", ""), geogcs, x0 / self.linear_unit, y0 / self.linear_unit, lon0, lat1, lat2, k0, lat0, unit, self.linear_unit
+            ),
+        }
+    }
+}
+
+/// Returns the first quoted value following position `start` in a WKT string.
+fn wkt_quoted_value(wkt: &str, start: usize) -> Option<String> {
+    let s = &wkt[start..];
+    let i = s.find('"')?;
+    let j = s[i + 1..].find('"')?;
+    Some(s[i + 1..i + 1 + j].to_string())
+}
+
+/// Returns the numeric values of the WKT element that begins the string, e.g. the semi-major
+/// axis and inverse flattening of `SPHEROID["GRS_1980",6378137.0,298.257222101]`.
+fn wkt_numbers(s: &str) -> Vec<f64> {
+    // skip any nested element (e.g. an AUTHORITY) by only reading up to the first ']'
+    let end = s.find(']').unwrap_or(s.len());
+    let start = s[..end].find('[').map(|i| i + 1).unwrap_or(0);
+    s[start..end]
+        .split(',')
+        .filter_map(|v| v.trim().parse::<f64>().ok())
+        .collect()
+}
+
+fn normalize_datum_name(name: &str) -> String {
+    name.to_lowercase()
+        .trim_start_matches("d_")
+        .replace("_", "")
+        .replace(" ", "")
+}
+
+/// Datums that can be transformed to WGS84 without a grid-based transformation.
+fn known_datum_shift(datum: &str) -> Option<[f64; 7]> {
+    match normalize_datum_name(datum).as_str() {
+        "wgs1984" | "wgs84" | "northamerican1983" | "nad83" | "etrs1989" | "etrs89"
+        | "gda1994" | "gda2020" | "nzgd2000" | "rgf1993" | "sirgas2000" | "grs1980" => {
+            Some([0f64; 7])
+        }
+        "osgb1936" => Some([446.448, -125.157, 542.06, 0.15, 0.247, 0.842, -20.489]),
+        _ => None,
+    }
+}
+
+fn geodetic_to_geocentric(e: Ellipsoid, lon: f64, lat: f64) -> [f64; 3] {
+    let (lon, lat) = (lon.to_radians(), lat.to_radians());
+    let e2 = e.e2();
+    let n = e.a / (1f64 - e2 * lat.sin() * lat.sin()).sqrt();
+    [
+        n * lat.cos() * lon.cos(),
+        n * lat.cos() * lon.sin(),
+        n * (1f64 - e2) * lat.sin(),
+    ]
+}
+
+fn geocentric_to_geodetic(e: Ellipsoid, xyz: [f64; 3]) -> (f64, f64) {
+    let e2 = e.e2();
+    let p = (xyz[0] * xyz[0] + xyz[1] * xyz[1]).sqrt();
+    let lon = xyz[1].atan2(xyz[0]);
+    let mut lat = xyz[2].atan2(p * (1f64 - e2));
+    for _ in 0..10 {
+        let n = e.a / (1f64 - e2 * lat.sin() * lat.sin()).sqrt();
+        let h = p / lat.cos() - n;
+        let new_lat = xyz[2].atan2(p * (1f64 - e2 * n / (n + h)));
+        if (new_lat - lat).abs() < 1e-12 {
+            lat = new_lat;
+            break;
+        }
+        lat = new_lat;
+    }
+    (lon.to_degrees(), lat.to_degrees())
+}
+
+/// Applies a seven-parameter Helmert transformation (position vector convention), or its
+/// inverse, to geocentric coordinates.
+fn helmert(xyz: [f64; 3], p: &[f64; 7], inverse: bool) -> [f64; 3] {
+    let sign = if inverse { -1f64 } else { 1f64 };
+    let sec = PI / (180f64 * 3600f64);
+    let (dx, dy, dz) = (sign * p[0], sign * p[1], sign * p[2]);
+    let (rx, ry, rz) = (sign * p[3] * sec, sign * p[4] * sec, sign * p[5] * sec);
+    let s = 1f64 + sign * p[6] * 1e-6;
+    let [x, y, z] = xyz;
+    [
+        dx + s * (x - rz * y + ry * z),
+        dy + s * (rz * x + y - rx * z),
+        dz + s * (-ry * x + rx * y + z),
+    ]
+}
+
+/// Transverse Mercator projection using Krüger's series to sixth order in the third
+/// flattening, which is accurate to within a few millimetres several thousand kilometres from
+/// the central meridian (Karney, 2011). Longitudes are relative to the central meridian.
+struct TransverseMercator {
+    e: f64,
+    a_hat: f64,
+    alpha: [f64; 6],
+    m0: f64,
+}
+
+impl TransverseMercator {
+    fn new(ellipsoid: Ellipsoid, lat0: f64, k0: f64) -> TransverseMercator {
+        let f = ellipsoid.f;
+        let n = f / (2f64 - f);
+        let (n2, n3) = (n * n, n * n * n);
+        let (n4, n5, n6) = (n3 * n, n3 * n2, n3 * n3);
+        let a = ellipsoid.a / (1f64 + n) * (1f64 + n2 / 4f64 + n4 / 64f64 + n6 / 256f64);
+        let alpha = [
+            n / 2f64 - 2f64 * n2 / 3f64 + 5f64 * n3 / 16f64 + 41f64 * n4 / 180f64
+                - 127f64 * n5 / 288f64
+                + 7891f64 * n6 / 37800f64,
+            13f64 * n2 / 48f64 - 3f64 * n3 / 5f64 + 557f64 * n4 / 1440f64 + 281f64 * n5 / 630f64
+                - 1983433f64 * n6 / 1935360f64,
+            61f64 * n3 / 240f64 - 103f64 * n4 / 140f64 + 15061f64 * n5 / 26880f64
+                + 167603f64 * n6 / 181440f64,
+            49561f64 * n4 / 161280f64 - 179f64 * n5 / 168f64 + 6601661f64 * n6 / 7257600f64,
+            34729f64 * n5 / 80640f64 - 3418889f64 * n6 / 1995840f64,
+            212378941f64 * n6 / 319334400f64,
+        ];
+        let mut tm = TransverseMercator {
+            e: ellipsoid.e2().sqrt(),
+            a_hat: k0 * a,
+            alpha,
+            m0: 0f64,
+        };
+        tm.m0 = tm.forward(0f64, lat0.to_radians()).1;
+        tm
+    }
+
+    /// The conformal latitude.
+    fn conformal_latitude(&self, lat: f64) -> f64 {
+        let e = self.e;
+        (lat.sin().atanh() - e * (e * lat.sin()).atanh()).sinh().atan()
+    }
+
+    fn forward(&self, dlon: f64, lat: f64) -> (f64, f64) {
+        let chi = self.conformal_latitude(lat);
+        let xi_p = chi.tan().atan2(dlon.cos());
+        let eta_p = (dlon.sin() / (1f64 + chi.tan() * chi.tan()).sqrt()).atanh();
+        let (mut xi, mut eta) = (xi_p, eta_p);
+        for (j, a) in self.alpha.iter().enumerate() {
+            let k = 2f64 * (j + 1) as f64;
+            xi += a * (k * xi_p).sin() * (k * eta_p).cosh();
+            eta += a * (k * xi_p).cos() * (k * eta_p).sinh();
+        }
+        (self.a_hat * eta, self.a_hat * xi - self.m0)
+    }
+
+    fn inverse(&self, x: f64, y: f64) -> (f64, f64) {
+        let xi = (y + self.m0) / self.a_hat;
+        let eta = x / self.a_hat;
+        // invert zeta = zeta' + sum(alpha_j sin(2 j zeta')), with zeta = xi + i eta, by
+        // Newton's method in complex arithmetic
+        let (mut xp, mut ep) = (xi, eta);
+        for _ in 0..20 {
+            let (mut fx, mut fe) = (xp, ep);
+            let (mut dx, mut de) = (1f64, 0f64);
+            for (j, a) in self.alpha.iter().enumerate() {
+                let k = 2f64 * (j + 1) as f64;
+                // sin(k z) and cos(k z) for complex z = xp + i ep
+                fx += a * (k * xp).sin() * (k * ep).cosh();
+                fe += a * (k * xp).cos() * (k * ep).sinh();
+                dx += a * k * (k * xp).cos() * (k * ep).cosh();
+                de -= a * k * (k * xp).sin() * (k * ep).sinh();
+            }
+            // (f - zeta) / f'
+            let (rx, re) = (fx - xi, fe - eta);
+            let denom = dx * dx + de * de;
+            let step_x = (rx * dx + re * de) / denom;
+            let step_e = (re * dx - rx * de) / denom;
+            xp -= step_x;
+            ep -= step_e;
+            if step_x.abs() < 1e-14 && step_e.abs() < 1e-14 {
+                break;
+            }
+        }
+        let chi = (xp.sin() / ep.cosh()).asin();
+        let dlon = ep.sinh().atan2(xp.cos());
+        (dlon, self.geodetic_latitude(chi))
+    }
+
+    /// Inverts the conformal latitude by Newton's method.
+    fn geodetic_latitude(&self, chi: f64) -> f64 {
+        let e2 = self.e * self.e;
+        let mut lat = chi;
+        for _ in 0..20 {
+            let c = self.conformal_latitude(lat);
+            let step = (c - chi) * (1f64 - e2 * lat.sin() * lat.sin()) / (1f64 - e2) * lat.cos()
+                / c.cos();
+            lat -= step;
+            if step.abs() < 1e-14 {
+                break;
+            }
+        }
+        lat
+    }
+}
+
+/// Lambert Conformal Conic projection (Snyder, 1987). Longitudes are relative to the central
+/// meridian.
+struct LambertConformalConic {
+    a: f64,
+    e: f64,
+    n: f64,
+    f: f64,
+    rho0: f64,
+}
+
+impl LambertConformalConic {
+    fn new(ellipsoid: Ellipsoid, lat0: f64, lat1: f64, lat2: f64, k0: f64) -> LambertConformalConic {
+        let e = ellipsoid.e2().sqrt();
+        let m = |lat: f64| lat.cos() / (1f64 - e * e * lat.sin() * lat.sin()).sqrt();
+        let t = |lat: f64| {
+            (PI / 4f64 - lat / 2f64).tan()
+                / ((1f64 - e * lat.sin()) / (1f64 + e * lat.sin())).powf(e / 2f64)
+        };
+        let (lat0, lat1, lat2) = (lat0.to_radians(), lat1.to_radians(), lat2.to_radians());
+        let n = if (lat1 - lat2).abs() < 1e-12 {
+            lat1.sin()
+        } else {
+            (m(lat1).ln() - m(lat2).ln()) / (t(lat1).ln() - t(lat2).ln())
+        };
+        let f = m(lat1) / (n * t(lat1).powf(n));
+        let a = ellipsoid.a * k0;
+        LambertConformalConic {
+            a,
+            e,
+            n,
+            f,
+            rho0: a * f * t(lat0).powf(n),
+        }
+    }
+
+    fn t(&self, lat: f64) -> f64 {
+        let e = self.e;
+        (PI / 4f64 - lat / 2f64).tan() / ((1f64 - e * lat.sin()) / (1f64 + e * lat.sin())).powf(e / 2f64)
+    }
+
+    fn forward(&self, dlon: f64, lat: f64) -> (f64, f64) {
+        let rho = self.a * self.f * self.t(lat).powf(self.n);
+        let theta = self.n * dlon;
+        (rho * theta.sin(), self.rho0 - rho * theta.cos())
+    }
+
+    fn inverse(&self, x: f64, y: f64) -> (f64, f64) {
+        let sign = self.n.signum();
+        let dy = self.rho0 - y;
+        let rho = sign * (x * x + dy * dy).sqrt();
+        let theta = (sign * x).atan2(sign * dy);
+        let t = (rho / (self.a * self.f)).powf(1f64 / self.n);
+        let e = self.e;
+        let mut lat = PI / 2f64 - 2f64 * t.atan();
+        for _ in 0..20 {
+            let new_lat = PI / 2f64
+                - 2f64 * (t * ((1f64 - e * lat.sin()) / (1f64 + e * lat.sin())).powf(e / 2f64)).atan();
+            if (new_lat - lat).abs() < 1e-14 {
+                lat = new_lat;
+                break;
+            }
+            lat = new_lat;
+        }
+        (theta / self.n, lat)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Projection;
+
+    #[test]
+    fn test_utm_meridian_distance() {
+        // the WGS84 meridian distance to 45 degrees is 4984944.378 m
+        let utm = Projection::from_epsg(32617).unwrap();
+        let (x, y) = utm.forward(-81.0, 45.0);
+        assert!((x - 500000.0).abs() < 1e-6);
+        assert!((y - 0.9996 * 4984944.378).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_utm_round_trip_and_zone_shift() {
+        let z17 = Projection::parse("+proj=utm +zone=17 +datum=WGS84").unwrap();
+        let z18 = Projection::parse("EPSG:32618").unwrap();
+        let (lon, lat) = (-78.2, 44.1);
+        let (x17, y17) = z17.forward(lon, lat);
+        let (lon2, lat2) = z17.inverse(x17, y17);
+        assert!((lon2 - lon).abs() < 1e-9 && (lat2 - lat).abs() < 1e-9);
+        // a point in zone 18 projected through zone 17 coordinates
+        let (x18, y18) = z17.transform_to(&z18, x17, y17);
+        let (x, y) = z18.forward(lon, lat);
+        assert!((x18 - x).abs() < 0.001 && (y18 - y).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_lambert_conformal_conic() {
+        // Lambert-93 projects its origin (3 E, 46.5 N) to the false origin
+        let l93 = Projection::from_epsg(2154).unwrap();
+        let (x, y) = l93.forward(3.0, 46.5);
+        assert!((x - 700000.0).abs() < 1e-6 && (y - 6600000.0).abs() < 1e-6);
+        let (lon, lat) = l93.inverse(652000.0, 6862000.0);
+        let (x, y) = l93.forward(lon, lat);
+        assert!((x - 652000.0).abs() < 1e-6 && (y - 6862000.0).abs() < 1e-6);
+    }
+}
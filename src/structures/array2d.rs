@@ -12,6 +12,8 @@ use std::ops::{AddAssign, Index, IndexMut, SubAssign};
 /// Example:
 /// 
 /// ```
+/// # use whitebox_tools::structures::Array2D;
+/// # fn main() -> std::io::Result<()> {
 /// let rows = 100;
 /// let columns = 500;
 /// let initial_value = 0f64;
@@ -19,6 +21,9 @@ use std::ops::{AddAssign, Index, IndexMut, SubAssign};
 /// let mut x: Array2D<f64> = Array2D::new(rows, columns, initial_value, nodata_value)?;
 /// let cell_val = x.get_value(50, 100);
 /// x.set_value(50, 100, 1f64);
+/// # assert_eq!(cell_val, 0f64);
+/// # Ok(())
+/// # }
 /// ```
 #[derive(Clone, Debug)]
 pub struct Array2D<T: Copy + AddAssign + SubAssign> {
@@ -37,11 +42,15 @@ where
     /// Example:
     /// 
     /// ```
+    /// # use whitebox_tools::structures::Array2D;
+    /// # fn main() -> std::io::Result<()> {
     /// let rows = 100;
     /// let columns = 500;
     /// let initial_value = 0f64;
     /// let nodata_value = -999f64;
     /// let mut x: Array2D<f64> = Array2D::new(rows, columns, initial_value, nodata_value)?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn new(
         rows: isize,
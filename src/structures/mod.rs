@@ -1,3 +1,7 @@
+//! General-purpose data structures used throughout the library, including the `Array2D` grid,
+//! bounding boxes, points and line segments, fixed-radius neighbourhood searches, and the
+//! topology of vector stream networks.
+
 // private sub-module defined in other files
 mod array2d;
 mod bounding_box;
@@ -9,6 +13,7 @@ mod n_minimizer;
 mod point2d;
 mod polyline;
 mod polynomial_regression_2d;
+mod stream_network_graph;
 
 // exports identifiers from private sub-modules in the current module namespace
 pub use self::array2d::Array2D;
@@ -22,4 +27,5 @@ pub use self::point2d::Direction;
 pub use self::point2d::Point2D;
 pub use self::polyline::MultiPolyline;
 pub use self::polyline::Polyline;
-pub use self::polynomial_regression_2d::PolynomialRegression2D;
\ No newline at end of file
+pub use self::polynomial_regression_2d::PolynomialRegression2D;
+pub use self::stream_network_graph::StreamNetworkGraph;
\ No newline at end of file
@@ -11,16 +11,19 @@ License: MIT
 /// efficient and small memory solution.
 ///
 /// ## Example
-///     let mut highs = NMaximizer::new(4);
+/// ```
+/// # use whitebox_tools::structures::NMaximizer;
+/// let mut highs = NMaximizer::new(4);
 ///
-///     let data = vec![4.0, 3.0, -2.0, 9.0, 3.0, 2.0, 1.0, 8.0, 5.0];
-///     for val in data {
-///         highs.insert(val);
-///     }
-///     
-///     for i in 0..4 {
-///         println!("{}", highs.get(i).unwrap());
-///     }
+/// let data = vec![4.0, 3.0, -2.0, 9.0, 3.0, 2.0, 1.0, 8.0, 5.0];
+/// for val in data {
+///     highs.insert(val);
+/// }
+///
+/// for i in 0..4 {
+///     println!("{}", highs.get(i).unwrap());
+/// }
+/// ```
 pub struct NMaximizer<T: Copy + PartialOrd + PartialEq> {
     values: Vec<T>,
     n: usize,
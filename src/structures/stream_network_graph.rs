@@ -0,0 +1,227 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use super::Point2D;
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use std::collections::VecDeque;
+
+/// StreamNetworkGraph is the topology of a vector stream network, i.e. the directed graph of
+/// stream links in which each link flows into the downstream link that begins where it ends.
+/// Each link is described by its upstream and downstream end points, and links are connected
+/// when the downstream end of one link is within a snap distance of the upstream end of another.
+/// Links must therefore be split at confluences, as are the links output by
+/// `RasterStreamsToVector`. Where the downstream end of a link meets the upstream ends of several
+/// links, e.g. at a distributary, it flows into the nearest of them, such that the network is a
+/// forest of trees rooted at the outlets.
+///
+/// ## Example
+/// ```
+/// # use whitebox_tools::structures::{Point2D, StreamNetworkGraph};
+/// // two headwater links joining to form a third link
+/// let links = vec![
+///     (Point2D::new(0.0, 10.0), Point2D::new(5.0, 5.0)),
+///     (Point2D::new(10.0, 10.0), Point2D::new(5.0, 5.0)),
+///     (Point2D::new(5.0, 5.0), Point2D::new(5.0, 0.0)),
+/// ];
+/// let graph = StreamNetworkGraph::new(&links, 0.0);
+/// assert_eq!(graph.downstream_link(0), Some(2));
+/// assert_eq!(graph.upstream_links(2), &[0, 1]);
+/// let lengths = vec![7.0, 7.0, 5.0];
+/// assert_eq!(graph.accumulate_downstream(&lengths, |a, b| a + b), vec![7.0, 7.0, 19.0]);
+/// ```
+pub struct StreamNetworkGraph {
+    downstream: Vec<Option<usize>>,
+    upstream: Vec<Vec<usize>>,
+    order: Vec<usize>,
+    num_cyclic: usize,
+}
+
+impl StreamNetworkGraph {
+    /// Creates the graph of a set of links, given as their (upstream, downstream) end points.
+    pub fn new(links: &[(Point2D, Point2D)], snap_distance: f64) -> StreamNetworkGraph {
+        let num_links = links.len();
+        let mut kdtree = KdTree::new_with_capacity(2, 64);
+        for (i, link) in links.iter().enumerate() {
+            kdtree.add([link.0.x, link.0.y], i).unwrap();
+        }
+        // squared distances are compared; a small tolerance allows for rounding of coordinates
+        let radius = snap_distance.max(0f64) + 1e-9f64;
+        let mut downstream = vec![None; num_links];
+        let mut upstream = vec![vec![]; num_links];
+        for (i, link) in links.iter().enumerate() {
+            let p = link.1;
+            let ret = kdtree
+                .within(&[p.x, p.y], radius * radius, &squared_euclidean)
+                .unwrap();
+            // the results are sorted by distance
+            if let Some((_, &j)) = ret.iter().find(|(_, &j)| j != i) {
+                downstream[i] = Some(j);
+                upstream[j].push(i);
+            }
+        }
+        for u in upstream.iter_mut() {
+            u.sort_unstable();
+        }
+
+        // order the links from the headwaters to the outlets
+        let mut num_inflowing: Vec<usize> = upstream.iter().map(|u| u.len()).collect();
+        let mut queue: VecDeque<usize> = (0..num_links).filter(|i| num_inflowing[*i] == 0).collect();
+        let mut order = Vec::with_capacity(num_links);
+        while let Some(i) = queue.pop_front() {
+            order.push(i);
+            if let Some(j) = downstream[i] {
+                num_inflowing[j] -= 1;
+                if num_inflowing[j] == 0 {
+                    queue.push_back(j);
+                }
+            }
+        }
+        // links on cycles, e.g. those digitized in the wrong direction, are appended in the
+        // order in which they are stored
+        let num_cyclic = num_links - order.len();
+        if num_cyclic > 0 {
+            let mut is_ordered = vec![false; num_links];
+            for &i in &order {
+                is_ordered[i] = true;
+            }
+            order.extend((0..num_links).filter(|i| !is_ordered[*i]));
+        }
+
+        StreamNetworkGraph {
+            downstream,
+            upstream,
+            order,
+            num_cyclic,
+        }
+    }
+
+    /// Returns the number of links in the network.
+    pub fn num_links(&self) -> usize {
+        self.downstream.len()
+    }
+
+    /// Returns the link into which a link flows, or `None` if it is an outlet link.
+    pub fn downstream_link(&self, link: usize) -> Option<usize> {
+        self.downstream[link]
+    }
+
+    /// Returns the links that flow into a link, which is empty for headwater links.
+    pub fn upstream_links(&self, link: usize) -> &[usize] {
+        &self.upstream[link]
+    }
+
+    /// Returns the outlet links, i.e. those that do not flow into another link.
+    pub fn outlets(&self) -> Vec<usize> {
+        (0..self.num_links())
+            .filter(|i| self.downstream[*i].is_none())
+            .collect()
+    }
+
+    /// Returns the links ordered such that each link follows all of the links upstream of it.
+    pub fn topological_order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Returns the number of links that are on, or downstream of, a cycle in the network. The
+    /// accumulated values of these links are unreliable.
+    pub fn num_cyclic_links(&self) -> usize {
+        self.num_cyclic
+    }
+
+    /// Accumulates link values in the downstream direction, such that the value of each link is
+    /// its own value combined with the accumulated values of the links that flow into it, e.g.
+    /// the total length of the upstream network.
+    pub fn accumulate_downstream<T: Clone, F: Fn(&T, &T) -> T>(&self, values: &[T], combine: F) -> Vec<T> {
+        let mut accumulated = values.to_vec();
+        for &i in &self.order {
+            if let Some(j) = self.downstream[i] {
+                accumulated[j] = combine(&accumulated[j], &accumulated[i]);
+            }
+        }
+        accumulated
+    }
+
+    /// Accumulates link values in the upstream direction, such that the value of each link is its
+    /// own value combined with the accumulated value of the link into which it flows, e.g. the
+    /// length of the flow path from the link to the outlet.
+    pub fn accumulate_upstream<T: Clone, F: Fn(&T, &T) -> T>(&self, values: &[T], combine: F) -> Vec<T> {
+        let mut accumulated = values.to_vec();
+        for &i in self.order.iter().rev() {
+            if let Some(j) = self.downstream[i] {
+                accumulated[i] = combine(&accumulated[i], &accumulated[j]);
+            }
+        }
+        accumulated
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::StreamNetworkGraph;
+    use crate::structures::Point2D;
+
+    fn network() -> StreamNetworkGraph {
+        // links 0 and 1 join to form link 2, which is joined by link 3 to form the outlet link 4;
+        // link 5 is an isolated link and the end points of link 3 are offset slightly
+        let links = vec![
+            (Point2D::new(0.0, 20.0), Point2D::new(5.0, 15.0)),
+            (Point2D::new(10.0, 20.0), Point2D::new(5.0, 15.0)),
+            (Point2D::new(5.0, 15.0), Point2D::new(5.0, 10.0)),
+            (Point2D::new(15.0, 15.0), Point2D::new(5.05, 10.0)),
+            (Point2D::new(5.0, 10.0), Point2D::new(5.0, 0.0)),
+            (Point2D::new(50.0, 50.0), Point2D::new(60.0, 50.0)),
+        ];
+        StreamNetworkGraph::new(&links, 0.1)
+    }
+
+    #[test]
+    fn test_stream_network_graph_topology() {
+        let graph = network();
+        assert_eq!(graph.num_links(), 6);
+        assert_eq!(graph.downstream_link(0), Some(2));
+        assert_eq!(graph.downstream_link(3), Some(4));
+        assert_eq!(graph.downstream_link(4), None);
+        assert_eq!(graph.upstream_links(4), &[2, 3]);
+        assert_eq!(graph.outlets(), vec![4, 5]);
+        assert_eq!(graph.num_cyclic_links(), 0);
+        let order = graph.topological_order();
+        let position = |link: usize| order.iter().position(|l| *l == link).unwrap();
+        assert!(position(0) < position(2) && position(2) < position(4));
+    }
+
+    #[test]
+    fn test_stream_network_graph_accumulation() {
+        let graph = network();
+        let values = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        assert_eq!(
+            graph.accumulate_downstream(&values, |a, b| a + b),
+            vec![1.0, 2.0, 6.0, 4.0, 15.0, 6.0]
+        );
+        assert_eq!(
+            graph.accumulate_upstream(&values, |a, b| a + b),
+            vec![9.0, 10.0, 8.0, 9.0, 5.0, 6.0]
+        );
+        assert_eq!(
+            graph.accumulate_downstream(&values, |a: &f64, b: &f64| a.max(*b)),
+            vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+    }
+
+    #[test]
+    fn test_stream_network_graph_cycle() {
+        // a link digitized in the wrong direction forms a cycle with its downstream link
+        let links = vec![
+            (Point2D::new(0.0, 0.0), Point2D::new(0.0, 10.0)),
+            (Point2D::new(0.0, 10.0), Point2D::new(0.0, 0.0)),
+        ];
+        let graph = StreamNetworkGraph::new(&links, 0.0);
+        assert_eq!(graph.num_cyclic_links(), 2);
+        assert_eq!(graph.topological_order().len(), 2);
+    }
+}
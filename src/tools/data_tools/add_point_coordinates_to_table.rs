@@ -147,11 +147,7 @@ impl WhiteboxTool for AddPointCoordinatesToTable {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -159,15 +159,8 @@ impl WhiteboxTool for ConvertNodataToZero {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            // Windows now takes forward slash in directory names.
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         let input = Arc::new(Raster::new(&input_file, "r")?);
 
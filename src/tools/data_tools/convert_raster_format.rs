@@ -2,22 +2,48 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: July 6, 2017
-Last Modified: 12/10/2018
+Last Modified: 17/10/2026
 License: MIT
 */
 
+use crate::raster::geotiff::GeoTiffCompression;
 use crate::raster::*;
 use crate::tools::*;
+use num_cpus;
 use std::env;
 use std::io::{Error, ErrorKind};
 use std::path;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
 
 /// This tool converts raster data from one format to another. It determines input/output raster
 /// formats based on extensions, but due to file extension naming collisions, it would be good to
 /// add user hints. For example, the extension 'grd' could belong to a SurferAscii or a Surfer7Binary.
 /// This is more important for distinguishing output files since input files can be read and
 /// distiguishing features idenfitied from the file structure. At the moment, this tool does not
-/// support user hints however.
+/// support user hints however. All of the bands of multi-band rasters are converted, although
+/// multi-band rasters may only be output in the GeoTIFF format.
+///
+/// Many rasters may be converted in a single run by specifying a list of input files (`--input`),
+/// separated by semicolons or commas. The output files (`--output`) are then either a list of the
+/// same length, or are named after the input files, with the extension of the output format
+/// (`--format`, e.g. 'tif', 'sdat', 'rst', 'flt', or 'sg-grd-z'), and written to the output
+/// directory (`--out_dir`), or else to the directory of each input file. The rasters are converted
+/// concurrently by a number of workers (`--workers`), which defaults to the number of processors.
+/// Since each worker holds its input and output rasters in memory, fewer workers may be needed to
+/// convert very large rasters. If a raster cannot be converted, the remaining rasters are still
+/// converted and the tool reports the failures once it is finished.
+///
+/// The compression of GeoTIFF outputs (`--compress`) may be 'none', 'lzw', or 'deflate', and the
+/// predictor (`--predictor`) may be 1 (none), 2 (horizontal differencing), or 3 (floating point
+/// prediction). The output data type (`--data_type`) may be one of 'f64', 'f32', 'i32', 'i16', 'i8',
+/// 'u32', 'u16', or 'u8', and defaults to the data type of each input. When rasters are converted
+/// to an integer data type, their values are rounded to the nearest integer and limited to the
+/// range of the type, and the NoData value is replaced by the lowest (signed) or highest (unsigned)
+/// value of the type if it cannot be represented.
 pub struct ConvertRasterFormat {
     name: String,
     description: String,
@@ -38,21 +64,88 @@ impl ConvertRasterFormat {
 
         let mut parameters = vec![];
         parameters.push(ToolParameter {
-            name: "Input File".to_owned(),
+            name: "Input Files".to_owned(),
             flags: vec!["-i".to_owned(), "--input".to_owned()],
-            description: "Input raster file.".to_owned(),
-            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            description: "Input raster file(s), separated by semicolons or commas.".to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Raster),
             default_value: None,
             optional: false,
         });
 
         parameters.push(ToolParameter {
-            name: "Output File".to_owned(),
+            name: "Output Files".to_owned(),
             flags: vec!["-o".to_owned(), "--output".to_owned()],
-            description: "Output raster file.".to_owned(),
+            description: "Output raster file(s); if unspecified, the outputs are named after the inputs.".to_owned(),
             parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
             default_value: None,
-            optional: false,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Format Extension".to_owned(),
+            flags: vec!["--format".to_owned()],
+            description: "Extension of the output format, e.g. 'tif', 'sdat', or 'rst', used when output files are unspecified.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Directory".to_owned(),
+            flags: vec!["--out_dir".to_owned()],
+            description: "Directory of the outputs named after the inputs; defaults to the directory of each input.".to_owned(),
+            parameter_type: ParameterType::Directory,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "GeoTIFF Compression".to_owned(),
+            flags: vec!["--compress".to_owned()],
+            description: "Compression of GeoTIFF outputs; options are 'none', 'lzw', and 'deflate'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "none".to_owned(),
+                "lzw".to_owned(),
+                "deflate".to_owned(),
+            ]),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "GeoTIFF Predictor".to_owned(),
+            flags: vec!["--predictor".to_owned()],
+            description: "Predictor of compressed GeoTIFF outputs; 1 (none), 2 (horizontal differencing), or 3 (floating point).".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("1".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Data Type".to_owned(),
+            flags: vec!["--data_type".to_owned()],
+            description: "Output data type; defaults to the data type of each input.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "f64".to_owned(),
+                "f32".to_owned(),
+                "i32".to_owned(),
+                "i16".to_owned(),
+                "i8".to_owned(),
+                "u32".to_owned(),
+                "u16".to_owned(),
+                "u8".to_owned(),
+            ]),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Workers".to_owned(),
+            flags: vec!["--workers".to_owned()],
+            description: "Number of rasters converted concurrently; defaults to the number of processors.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
         });
 
         let sep: String = path::MAIN_SEPARATOR.to_string();
@@ -67,7 +160,8 @@ impl ConvertRasterFormat {
             short_exe += ".exe";
         }
         let usage = format!(
-            ">>.*{} -r={} -v --wd=\"*path*to*data*\" --input=DEM.tif -o=output.tif",
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --input=DEM.tif -o=output.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --input='DEM1.dep;DEM2.dep;DEM3.dep' --format=tif --out_dir=converted --compress=deflate --predictor=3 --data_type=f32 --workers=2",
             short_exe, name
         )
         .replace("*", &sep);
@@ -116,8 +210,14 @@ impl WhiteboxTool for ConvertRasterFormat {
         working_directory: &'a str,
         verbose: bool,
     ) -> Result<(), Error> {
-        let mut input_file = String::new();
-        let mut output_file = String::new();
+        let mut input_files_str = String::new();
+        let mut output_files_str = String::new();
+        let mut format = String::new();
+        let mut out_dir = String::new();
+        let mut compress = String::new();
+        let mut predictor = 1u16;
+        let mut data_type_str = String::new();
+        let mut num_workers = 0usize;
 
         if args.len() == 0 {
             return Err(Error::new(
@@ -134,18 +234,55 @@ impl WhiteboxTool for ConvertRasterFormat {
             if vec.len() > 1 {
                 keyval = true;
             }
-            if vec[0].to_lowercase() == "-i" || vec[0].to_lowercase() == "--input" {
-                if keyval {
-                    input_file = vec[1].to_string();
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-inputs" {
+                input_files_str = if keyval {
+                    vec[1].to_string()
                 } else {
-                    input_file = args[i + 1].to_string();
-                }
-            } else if vec[0].to_lowercase() == "-o" || vec[0].to_lowercase() == "--output" {
-                if keyval {
-                    output_file = vec[1].to_string();
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" || flag_val == "-outputs" {
+                output_files_str = if keyval {
+                    vec[1].to_string()
                 } else {
-                    output_file = args[i + 1].to_string();
-                }
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-format" {
+                format = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-out_dir" {
+                out_dir = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-compress" {
+                compress = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-predictor" {
+                predictor = if keyval {
+                    vec[1].to_string().parse::<u16>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<u16>().unwrap()
+                };
+            } else if flag_val == "-data_type" {
+                data_type_str = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-workers" {
+                num_workers = if keyval {
+                    vec[1].to_string().parse::<usize>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<usize>().unwrap()
+                };
             }
         }
 
@@ -155,57 +292,333 @@ impl WhiteboxTool for ConvertRasterFormat {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let compression = if compress.trim().is_empty() {
+            None
+        } else {
+            match GeoTiffCompression::from_name(&compress) {
+                Some(c) => Some(c),
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "Unrecognized compression; options are 'none', 'lzw', and 'deflate'.",
+                    ))
+                }
+            }
+        };
+        if !(1..=3).contains(&predictor) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The predictor must be 1 (none), 2 (horizontal differencing), or 3 (floating point).",
+            ));
+        }
+
+        let data_type = if data_type_str.trim().is_empty() {
+            None
+        } else {
+            match data_type_from_name(&data_type_str) {
+                Some(dt) => Some(dt),
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "Unrecognized data type; options are 'f64', 'f32', 'i32', 'i16', 'i8', 'u32', 'u16', and 'u8'.",
+                    ))
+                }
+            }
+        };
+
+        let input_files = split_file_list(&input_files_str);
+        if input_files.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No input files were specified.",
+            ));
+        }
+        let output_files = split_file_list(&output_files_str);
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
+        // pair each input with its output
+        let format = format.trim().trim_start_matches('.').to_string();
+        if !out_dir.trim().is_empty() {
+            out_dir = resolve_path(&out_dir, working_directory);
+            if !Path::new(&out_dir).is_dir() {
+                std::fs::create_dir_all(&out_dir)?;
+            }
+        }
+        let mut jobs = Vec::with_capacity(input_files.len());
+        if !output_files.is_empty() {
+            if output_files.len() != input_files.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The number of output files must equal the number of input files.",
+                ));
+            }
+            for (input_file, output_file) in input_files.iter().zip(output_files.iter()) {
+                jobs.push((
+                    resolve_path(input_file, working_directory),
+                    resolve_path(output_file, working_directory),
+                ));
+            }
+        } else {
+            if format.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Either the output files (--output) or the output format (--format) must be specified.",
+                ));
+            }
+            for input_file in &input_files {
+                let input_file = resolve_path(input_file, working_directory);
+                let input_path = Path::new(&input_file);
+                let stem = match input_path.file_stem() {
+                    Some(stem) => stem.to_string_lossy().to_string(),
+                    None => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("Invalid input file name: {}", input_file),
+                        ))
+                    }
+                };
+                let dir = if !out_dir.trim().is_empty() {
+                    Path::new(&out_dir).to_path_buf()
+                } else {
+                    match input_path.parent() {
+                        Some(parent) => parent.to_path_buf(),
+                        None => Path::new(working_directory).to_path_buf(),
+                    }
+                };
+                let output_file = dir
+                    .join(format!("{}.{}", stem, format))
+                    .to_string_lossy()
+                    .to_string();
+                jobs.push((input_file, output_file));
+            }
         }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
+        for (input_file, output_file) in &jobs {
+            if Path::new(input_file) == Path::new(output_file) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The output file would overwrite the input file: {}", input_file),
+                ));
+            }
         }
 
-        if verbose {
-            println!("Reading data...")
-        };
+        let num_files = jobs.len();
+        if num_workers == 0 {
+            num_workers = num_cpus::get();
+        }
+        num_workers = num_workers.max(1).min(num_files);
+        if verbose && num_files > 1 {
+            println!(
+                "Converting {} rasters using {} worker(s)...",
+                num_files, num_workers
+            );
+        }
 
-        let input = Raster::new(&input_file, "r")?;
         let start = Instant::now();
 
-        let mut output = Raster::initialize_using_file(&output_file, &input);
-        println!("Initializing the output raster...");
-        match output.set_data_from_raster(&input) {
-            Ok(_) => (), // do nothings
-            Err(err) => return Err(err),
+        let tool_name = self.get_tool_name();
+        let jobs = Arc::new(jobs);
+        let next_job = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..num_workers {
+            let jobs = jobs.clone();
+            let next_job = next_job.clone();
+            let tool_name = tool_name.clone();
+            let tx = tx.clone();
+            thread::spawn(move || loop {
+                let job = next_job.fetch_add(1, Ordering::SeqCst);
+                if job >= jobs.len() {
+                    break;
+                }
+                let (input_file, output_file) = &jobs[job];
+                let result = convert_raster(
+                    input_file,
+                    output_file,
+                    compression.map(|c| (c, predictor)),
+                    data_type,
+                    &tool_name,
+                );
+                tx.send((job, result)).unwrap();
+            });
+        }
+        drop(tx);
+
+        let mut failures = vec![];
+        for (num_converted, (job, result)) in rx.iter().enumerate() {
+            let (input_file, output_file) = &jobs[job];
+            match result {
+                Ok(num_clamped) => {
+                    if num_clamped > 0 {
+                        println!(
+                            "Warning: {} values of {} were outside of the range of the output data type and were clamped.",
+                            num_clamped, input_file
+                        );
+                    }
+                    if verbose {
+                        if num_files > 1 {
+                            println!(
+                                "Converted {} of {}: {}",
+                                num_converted + 1,
+                                num_files,
+                                output_file
+                            );
+                        } else {
+                            println!("Output file written")
+                        }
+                    }
+                }
+                Err(e) => {
+                    if verbose {
+                        println!("Error converting {}: {}", input_file, e);
+                    }
+                    failures.push(format!("{} ({})", input_file, e));
+                }
+            }
         }
-        drop(input);
 
         let elapsed_time = get_formatted_elapsed_time(start);
-        output.add_metadata_entry(format!(
-            "Created by whitebox_tools\' {} tool",
-            self.get_tool_name()
-        ));
-        output.add_metadata_entry(format!("Input file: {}", input_file));
-        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
 
-        if verbose {
-            println!("Saving data...")
-        };
-        let _ = match output.write() {
-            Ok(_) => {
-                if verbose {
-                    println!("Output file written")
-                }
-            }
-            Err(e) => return Err(e),
-        };
+        if !failures.is_empty() {
+            return Err(Error::other(format!(
+                "{} of {} rasters could not be converted: {}",
+                failures.len(),
+                num_files,
+                failures.join("; ")
+            )));
+        }
 
         if verbose {
-            println!(
-                "{}",
-                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
-            );
+            println!("{}", &format!("Elapsed Time (including I/O): {}", elapsed_time));
         }
 
         Ok(())
     }
 }
+
+/// Splits a list of file names separated by semicolons or, if there are none, by commas.
+fn split_file_list(files: &str) -> Vec<String> {
+    let delimiter = if files.contains(';') { ';' } else { ',' };
+    files
+        .split(delimiter)
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .collect()
+}
+
+fn data_type_from_name(name: &str) -> Option<DataType> {
+    match name.trim() {
+        "f64" | "float64" | "double" => Some(DataType::F64),
+        "f32" | "float32" | "float" => Some(DataType::F32),
+        "i32" | "int32" => Some(DataType::I32),
+        "i16" | "int16" => Some(DataType::I16),
+        "i8" | "int8" => Some(DataType::I8),
+        "u32" | "uint32" => Some(DataType::U32),
+        "u16" | "uint16" => Some(DataType::U16),
+        "u8" | "uint8" | "byte" => Some(DataType::U8),
+        _ => None,
+    }
+}
+
+/// Returns the range of the values of an integer data type, or `None` for other data types.
+fn integer_range(data_type: DataType) -> Option<(f64, f64)> {
+    match data_type {
+        DataType::I32 => Some((i32::MIN as f64, i32::MAX as f64)),
+        DataType::I16 => Some((i16::MIN as f64, i16::MAX as f64)),
+        DataType::I8 => Some((i8::MIN as f64, i8::MAX as f64)),
+        DataType::U32 => Some((0f64, u32::MAX as f64)),
+        DataType::U16 => Some((0f64, u16::MAX as f64)),
+        DataType::U8 => Some((0f64, u8::MAX as f64)),
+        _ => None,
+    }
+}
+
+/// Converts a raster, returning the number of values that were clamped to the range of the
+/// output data type.
+fn convert_raster(
+    input_file: &str,
+    output_file: &str,
+    compression: Option<(GeoTiffCompression, u16)>,
+    data_type: Option<DataType>,
+    tool_name: &str,
+) -> Result<usize, Error> {
+    let input = Raster::new(input_file, "r")?;
+    let start = Instant::now();
+
+    let mut output = Raster::initialize_using_file(output_file, &input);
+    if input.num_bands() > 1 {
+        // multi-band rasters may only be output as GeoTIFFs
+        output.set_num_bands(input.configs.bands);
+        for band in 0..input.num_bands() {
+            output.set_band(band, &input.get_band(band))?;
+        }
+    } else {
+        output.set_data_from_raster(&input)?;
+    }
+    drop(input);
+
+    if let Some((compression, predictor)) = compression {
+        output.configs.compression = compression;
+        output.configs.predictor = predictor;
+    }
+
+    let mut num_clamped = 0usize;
+    if let Some(data_type) = data_type {
+        output.configs.data_type = data_type;
+        if let Some((min, max)) = integer_range(data_type) {
+            let rows = output.configs.rows as isize;
+            let columns = output.configs.columns as isize;
+            for band in 0..output.num_bands() {
+                let nodata = output.get_band_nodata(band);
+                let out_nodata = if nodata.fract() == 0f64 && nodata >= min && nodata <= max {
+                    nodata
+                } else if min < 0f64 {
+                    min
+                } else {
+                    max
+                };
+                // values must not be clamped to a substituted nodata value
+                let (lo, hi) = if out_nodata != nodata && out_nodata == min {
+                    (min + 1f64, max)
+                } else if out_nodata != nodata && out_nodata == max {
+                    (min, max - 1f64)
+                } else {
+                    (min, max)
+                };
+                for row in 0..rows {
+                    for col in 0..columns {
+                        let z = output.get_band_value(band, row, col);
+                        let z_out = if z == nodata || z.is_nan() {
+                            out_nodata
+                        } else {
+                            let r = z.round();
+                            if r < lo || r > hi {
+                                num_clamped += 1;
+                            }
+                            r.max(lo).min(hi)
+                        };
+                        if z_out != z {
+                            output.set_band_value(band, row, col, z_out);
+                        }
+                    }
+                }
+                if out_nodata != nodata {
+                    if output.num_bands() > 1 {
+                        output.set_band_nodata(band, out_nodata);
+                    }
+                    if band == 0 {
+                        output.configs.nodata = out_nodata;
+                    }
+                }
+            }
+        }
+    }
+
+    let elapsed_time = get_formatted_elapsed_time(start);
+    output.add_metadata_entry(format!(
+        "Created by whitebox_tools\' {} tool",
+        tool_name
+    ));
+    output.add_metadata_entry(format!("Input file: {}", input_file));
+    output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+    output.write()?;
+
+    Ok(num_clamped)
+}
@@ -0,0 +1,188 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use crate::vector::shapefile::spatial_index::spatial_index_file_name;
+use crate::vector::*;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool creates a quadtree spatial index (`.qix`) file for an existing Shapefile. The index
+/// stores the features of the Shapefile in a tree of nested rectangular nodes, such that the
+/// features that may overlap an area of interest can be found without testing the extent of every
+/// feature in the file. Tools that perform spatial queries against an input Shapefile, such as
+/// `Clip`, use the index when it is present and more recent than the Shapefile, and other
+/// software, including GDAL/OGR, QGIS, and MapServer, use it when drawing and querying the file.
+///
+/// The index is written in the `.qix` format of the shapelib library. The ESRI `.sbn`/`.sbx`
+/// format is not supported. Spatial indices may also be created for every Shapefile output of a
+/// tool using the `--spatial_index` command-line flag. Notice that the index is not updated when
+/// the Shapefile is subsequently modified by other software, in which case the tool should be
+/// re-run.
+///
+/// # See Also
+/// `Clip`, `CleanVector`
+pub struct CreateSpatialIndex {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl CreateSpatialIndex {
+    pub fn new() -> CreateSpatialIndex {
+        // public constructor
+        let name = "CreateSpatialIndex".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description = "Creates a quadtree spatial index (.qix) file for a Shapefile.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Vector File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input vector file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.shp",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        CreateSpatialIndex {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for CreateSpatialIndex {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        let start = Instant::now();
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        input_file = resolve_path(&input_file, working_directory);
+
+        // read the input file
+        let input = Shapefile::read(&input_file)?;
+
+        if verbose {
+            println!("Building the index...");
+        }
+        let index = SpatialIndex::new(&input);
+        let qix_file = spatial_index_file_name(&input_file);
+        index.write(&qix_file)?;
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of indexed features: {}", input.num_records);
+            println!("Tree depth: {}", index.max_depth());
+            println!("Index file written to {}", qix_file);
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
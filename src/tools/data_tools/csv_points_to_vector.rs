@@ -214,18 +214,12 @@ impl WhiteboxTool for CsvPointsToVector {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
         
         // File strings need a full directory
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         let start = Instant::now();
 
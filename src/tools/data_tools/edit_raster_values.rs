@@ -0,0 +1,348 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufReader, Error, ErrorKind};
+use std::path;
+
+/// This tool applies a set of cell-value edits to an existing raster (`--input`), modifying the
+/// raster in place. It is intended for quick manual corrections, e.g. for fixing blunders such as
+/// spikes and pits in a DEM, or misclassified cells in a classified image. The edits may be
+/// specified in a text file (`--edits`), a patch raster (`--patch`), or both, in which case the
+/// edits of the text file are applied after those of the patch.
+///
+/// The edits file contains one edit per line, consisting of the row, column, and new value of a
+/// cell, separated by commas, semicolons, or spaces, e.g. `120, 345, 267.5`. When the `--xy` flag
+/// is specified, the first two values of each line are instead interpreted as the x and y
+/// coordinates of a point within the edited cell. A value of `nodata` sets the cell to the raster's
+/// NoData value. Blank lines, lines beginning with `#`, and a header line are ignored. For a patch
+/// raster, each valid (i.e. non-NoData) cell of the patch replaces the value of the input cell that
+/// contains the patch cell's centre, such that the patch may be a small raster that was clipped
+/// from, and edited in, another program.
+///
+/// For the uncompressed Whitebox (*.tas*) and Esri float grid (*.flt*) formats, only the edited
+/// cells are written to the existing data file, and the remainder of the grid is not rewritten,
+/// which is considerably faster for large rasters. Rasters in all other formats are rewritten in
+/// their entirety. Edits that fall outside of the raster are ignored. Because the input raster is
+/// overwritten, users should make a copy of the raster before running the tool if the original
+/// values need to be retained.
+///
+/// # See Also
+/// `UpdateRasterFromVector`, `SetNodataValue`
+pub struct EditRasterValues {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl EditRasterValues {
+    /// public constructor
+    pub fn new() -> EditRasterValues {
+        let name = "EditRasterValues".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Applies a list of cell-value edits, or a patch raster, to an existing raster in place."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Raster File To Edit".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Existing raster file, which is modified by the tool.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Edits File (optional)".to_owned(),
+            flags: vec!["--edits".to_owned()],
+            description: "Text file of edits, each a line containing a row, column (or x, y), and value.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Edits are x, y coordinates?".to_owned(),
+            flags: vec!["--xy".to_owned()],
+            description: "Interpret the first two values of each edit as x and y coordinates, rather than a row and column.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Patch Raster File (optional)".to_owned(),
+            flags: vec!["--patch".to_owned()],
+            description: "Raster file whose valid cells replace the corresponding cells of the input.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=dem.tas --edits=fixes.csv
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=dem.tif --edits=fixes.txt --xy --patch=patch.tif", short_exe, name).replace("*", &sep);
+
+        EditRasterValues {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for EditRasterValues {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut edits_file = String::new();
+        let mut patch_file = String::new();
+        let mut use_xy = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-edits" {
+                edits_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-patch" {
+                patch_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-xy" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    use_xy = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if edits_file.trim().is_empty() && patch_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either an edits file (--edits) or a patch raster (--patch) must be specified.",
+            ));
+        }
+        input_file = resolve_path(&input_file, working_directory);
+        edits_file = resolve_path(&edits_file, working_directory);
+        patch_file = resolve_path(&patch_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let mut output = Raster::new(&input_file, "rw")?;
+        let nodata = output.configs.nodata;
+
+        let start = Instant::now();
+
+        // the (row, column, value) of each edit, in the order in which they are applied
+        let mut edits: Vec<(isize, isize, f64)> = vec![];
+
+        if !patch_file.is_empty() {
+            let patch = Raster::new(&patch_file, "r")?;
+            for row in 0..patch.configs.rows as isize {
+                let y = patch.get_y_from_row(row);
+                for col in 0..patch.configs.columns as isize {
+                    let z = patch.get_value(row, col);
+                    if z != patch.configs.nodata {
+                        let x = patch.get_x_from_column(col);
+                        edits.push((output.get_row_from_y(y), output.get_column_from_x(x), z));
+                    }
+                }
+            }
+        }
+
+        if !edits_file.is_empty() {
+            let f = BufReader::new(File::open(&edits_file)?);
+            for (line_num, line) in f.lines().enumerate() {
+                let line = line?;
+                let line = line.trim();
+                if line.is_empty() || line.starts_with("#") {
+                    continue;
+                }
+                let vals: Vec<&str> = line
+                    .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                let parsed = if vals.len() == 3 {
+                    let value = if vals[2].to_lowercase() == "nodata" {
+                        Some(nodata)
+                    } else {
+                        vals[2].parse::<f64>().ok()
+                    };
+                    match (vals[0].parse::<f64>(), vals[1].parse::<f64>(), value) {
+                        (Ok(a), Ok(b), Some(z)) => {
+                            if use_xy {
+                                Some((output.get_row_from_y(b), output.get_column_from_x(a), z))
+                            } else if a.fract() == 0f64 && b.fract() == 0f64 {
+                                Some((a as isize, b as isize, z))
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    }
+                } else {
+                    None
+                };
+                match parsed {
+                    Some(edit) => edits.push(edit),
+                    None => {
+                        if line_num == 0 {
+                            continue; // a header line
+                        }
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!(
+                                "Line {} of the edits file is not of the form '{}, value'.",
+                                line_num + 1,
+                                if use_xy { "x, y" } else { "row, column" }
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let rows = output.configs.rows as isize;
+        let columns = output.configs.columns as isize;
+        let mut cells = vec![];
+        let mut num_outside = 0usize;
+        for &(row, col, z) in edits.iter() {
+            if row < 0 || row >= rows || col < 0 || col >= columns {
+                num_outside += 1;
+                continue;
+            }
+            output.set_value(row, col, z);
+            cells.push((row, col));
+        }
+        cells.sort();
+        cells.dedup();
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of edited cells: {}", cells.len());
+            if num_outside > 0 {
+                println!(
+                    "Warning: {} edits lie outside of the raster and were ignored.",
+                    num_outside
+                );
+            }
+            println!("Saving data...")
+        };
+        output.update_min_max();
+        if output.write_cells_in_place(&cells)? {
+            if verbose {
+                println!("Edited cells written in place")
+            }
+        } else {
+            if verbose {
+                println!("The raster format does not support editing in place; rewriting the file...")
+            }
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
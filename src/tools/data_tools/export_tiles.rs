@@ -0,0 +1,928 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::spatial_ref_system::Projection;
+use crate::tools::*;
+use crate::utils::{SqlValue, SqliteWriter};
+use num_cpus;
+use std::env;
+use std::f64;
+use std::f64::consts::PI;
+use std::fs::{DirBuilder, File};
+use std::io::{Error, ErrorKind, Write};
+use std::path;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// The radius of the sphere of the Web Mercator projection, in metres.
+const EARTH_RADIUS: f64 = 6_378_137f64;
+/// The latitude at which the Web Mercator world is square.
+const MAX_LATITUDE: f64 = 85.051_128_779_806_6;
+const TILE_SIZE: usize = 256;
+/// The spacing, in pixels, of the points at which the transformation into the raster's
+/// coordinate system is calculated exactly; it is interpolated between them.
+const LATTICE_STEP: usize = 16;
+/// The application ID of MBTiles databases, i.e. 'MPBX'.
+const MBTILES_APPLICATION_ID: u32 = 0x4d50_4258;
+
+/// This tool renders a raster (`--input`) into a pyramid of 256 x 256 pixel PNG image tiles in the
+/// Web Mercator (EPSG:3857) tiling scheme used by web maps, such that the results of an analysis can
+/// be displayed directly in Leaflet, OpenLayers, MapLibre, QGIS, and similar software. The tiles
+/// are saved either in an MBTiles database, if the output file (`--output`) has the extension
+/// *.mbtiles*, or otherwise as PNG files in an output directory with the *z/x/y.png* layout of XYZ
+/// tile services, in which rows are counted from the north.
+///
+/// Tiles are rendered for each zoom level from the minimum (`--min_zoom`) to the maximum
+/// (`--max_zoom`) zoom level, inclusive. By default, the maximum zoom level is the lowest level
+/// at which the tile pixels are at least as fine as the raster's grid cells, and the minimum zoom
+/// level is the highest level at which the raster fits within a single tile. Note that the number
+/// of tiles quadruples with each zoom level.
+///
+/// Raster values are coloured using a palette (`--palette`), which may be 'grey', 'spectrum',
+/// 'high_relief', 'blue_white_red', 'blueyellow', 'viridis', 'qual', or 'rgb'. The continuous
+/// palettes are stretched linearly between the display minimum and maximum values of the raster,
+/// or its minimum and maximum values, unless the range is specified (`--min_value` and
+/// `--max_value`). The 'qual' palette assigns distinct colours to the integer values of categorical
+/// rasters, e.g. classes or watershed identifiers, and the 'rgb' palette displays the packed colour
+/// values of colour composite images. By default, the palette is selected from the palette and
+/// photometric interpretation of the input raster. NoData cells are transparent, and tiles that
+/// contain only NoData cells are not saved.
+///
+/// The coordinate reference system of the input is read from the raster's EPSG code or WKT
+/// description, or else it must be specified (`--crs`) as an EPSG code (e.g. 'EPSG:32617'), PROJ
+/// string, or WKT string. Geographic, Transverse Mercator (including UTM), Lambert Conformal Conic,
+/// and Web Mercator systems are supported. Rasters are resampled into the tiles using the nearest
+/// neighbour method.
+///
+/// # See Also
+/// `TileRaster`, `ConvertRasterFormat`, `CreateColourComposite`
+pub struct ExportTiles {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ExportTiles {
+    pub fn new() -> ExportTiles {
+        // public constructor
+        let name = "ExportTiles".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Renders a raster into an MBTiles database or z/x/y directory of PNG web map tiles."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File or Directory".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output MBTiles file (*.mbtiles) or tile directory.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Any),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Zoom Level".to_owned(),
+            flags: vec!["--min_zoom".to_owned()],
+            description: "Minimum zoom level; by default, the level at which the raster fits in one tile.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Zoom Level".to_owned(),
+            flags: vec!["--max_zoom".to_owned()],
+            description: "Maximum zoom level; by default, the level matching the raster's resolution.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Palette".to_owned(),
+            flags: vec!["--palette".to_owned()],
+            description: "Palette; options include 'auto', 'grey', 'spectrum', 'high_relief', 'blue_white_red', 'blueyellow', 'viridis', 'qual', and 'rgb'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "auto".to_owned(),
+                "grey".to_owned(),
+                "spectrum".to_owned(),
+                "high_relief".to_owned(),
+                "blue_white_red".to_owned(),
+                "blueyellow".to_owned(),
+                "viridis".to_owned(),
+                "qual".to_owned(),
+                "rgb".to_owned(),
+            ]),
+            default_value: Some("auto".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Palette Minimum Value".to_owned(),
+            flags: vec!["--min_value".to_owned()],
+            description: "Value displayed with the start of the palette.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Palette Maximum Value".to_owned(),
+            flags: vec!["--max_value".to_owned()],
+            description: "Value displayed with the end of the palette.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Coordinate Reference System".to_owned(),
+            flags: vec!["--crs".to_owned()],
+            description: "Coordinate reference system of the input, if it is not defined in the file (EPSG code, PROJ string, or WKT).".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=hillshade.tif -o=hillshade.mbtiles
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=DEM.tif -o=tiles --min_zoom=8 --max_zoom=14 --palette=high_relief --crs=EPSG:32617", short_exe, name).replace("*", &sep);
+
+        ExportTiles {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ExportTiles {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--min_zoom".to_string(), ParameterConstraint::Range(0f64, 24f64)),
+            ("--max_zoom".to_string(), ParameterConstraint::Range(0f64, 24f64)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut min_zoom: Option<isize> = None;
+        let mut max_zoom: Option<isize> = None;
+        let mut palette = String::from("auto");
+        let mut min_value: Option<f64> = None;
+        let mut max_value: Option<f64> = None;
+        let mut crs = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-min_zoom" {
+                min_zoom = Some(if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as isize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as isize
+                });
+            } else if flag_val == "-max_zoom" {
+                max_zoom = Some(if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as isize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as isize
+                });
+            } else if flag_val == "-palette" {
+                palette = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                }
+                .to_lowercase();
+            } else if flag_val == "-min_value" {
+                min_value = Some(if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                });
+            } else if flag_val == "-max_value" {
+                max_value = Some(if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                });
+            } else if flag_val == "-crs" {
+                // WKT strings may contain equals signs
+                crs = if keyval {
+                    arg[arg.find('=').unwrap() + 1..].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        if output_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The output file or directory (--output) must be specified.",
+            ));
+        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        let is_mbtiles = output_file.to_lowercase().ends_with(".mbtiles");
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let mut input = Raster::new(&input_file, "r")?;
+        let start = Instant::now();
+        // not all formats record the range of values, which the palettes are stretched over
+        if !input.configs.minimum.is_finite() || !input.configs.maximum.is_finite() {
+            input.update_min_max();
+        }
+
+        // the coordinate reference system; Web Mercator rasters need no transformation
+        let wgs84 = Projection::from_epsg(4326)?;
+        let source: Option<Projection> = if !crs.trim().is_empty() {
+            if is_web_mercator_crs(&crs) {
+                None
+            } else {
+                Some(Projection::parse(&crs)?)
+            }
+        } else if is_web_mercator_epsg(input.configs.epsg_code)
+            || is_web_mercator_crs(&input.configs.coordinate_ref_system_wkt)
+        {
+            None
+        } else if input.configs.epsg_code != 0 && input.configs.epsg_code != 32767 {
+            Some(Projection::from_epsg(input.configs.epsg_code)?)
+        } else {
+            let wkt = input.configs.coordinate_ref_system_wkt.clone();
+            if wkt.trim().is_empty() || wkt.starts_with("Unknown") {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The coordinate reference system of the input raster could not be determined. Please specify it using the --crs parameter.",
+                ));
+            }
+            Some(Projection::from_wkt(&wkt)?)
+        };
+        if let Some(source) = &source {
+            if source.datum_differs(&wgs84) && !source.can_transform_datum(&wgs84) {
+                println!(
+                    "Warning: No transformation is known between the {} and WGS84 datums. The tiles may be offset by up to several hundred metres.",
+                    source.datum
+                );
+            }
+        }
+
+        // the extent of the raster in Web Mercator coordinates, from points along its edges
+        let (west, north) = (input.configs.west, input.configs.north);
+        let res_x = input.configs.resolution_x;
+        let max_merc_y = lat_to_merc_y(MAX_LATITUDE);
+        let (mut merc_west, mut merc_east) = (f64::INFINITY, f64::NEG_INFINITY);
+        let (mut merc_south, mut merc_north) = (f64::INFINITY, f64::NEG_INFINITY);
+        let num_edge_points = 64;
+        for k in 0..=num_edge_points {
+            let t = k as f64 / num_edge_points as f64;
+            let x = west + t * (input.configs.east - west);
+            let y = input.configs.south + t * (north - input.configs.south);
+            for (px, py) in [
+                (x, north),
+                (x, input.configs.south),
+                (west, y),
+                (input.configs.east, y),
+            ] {
+                let (mx, my) = match &source {
+                    Some(source) => {
+                        let (lon, lat) = source.transform_to(&wgs84, px, py);
+                        (lon_to_merc_x(lon), lat_to_merc_y(lat.clamp(-MAX_LATITUDE, MAX_LATITUDE)))
+                    }
+                    None => (px, py.clamp(-max_merc_y, max_merc_y)),
+                };
+                if mx.is_finite() && my.is_finite() {
+                    merc_west = merc_west.min(mx);
+                    merc_east = merc_east.max(mx);
+                    merc_south = merc_south.min(my);
+                    merc_north = merc_north.max(my);
+                }
+            }
+        }
+        if !merc_west.is_finite() || !merc_south.is_finite() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The extent of the input raster could not be transformed into the Web Mercator projection.",
+            ));
+        }
+        let world_size = 2f64 * PI * EARTH_RADIUS;
+        merc_west = merc_west.max(-world_size / 2f64);
+        merc_east = merc_east.min(world_size / 2f64);
+
+        // the resolution of the raster in Web Mercator metres, at the centre of the raster
+        let centre_lat = merc_y_to_lat((merc_south + merc_north) / 2f64);
+        let merc_resolution = match &source {
+            Some(source) if source.is_geographic() => res_x.to_radians() * EARTH_RADIUS,
+            Some(source) => res_x * source.linear_unit / centre_lat.to_radians().cos(),
+            None => res_x,
+        };
+        let native_zoom = ((world_size / (TILE_SIZE as f64 * merc_resolution)).log2() - 1e-6)
+            .ceil()
+            .clamp(0f64, 24f64) as isize;
+        let max_zoom = max_zoom.unwrap_or(native_zoom);
+        let fit_zoom = (world_size / (merc_east - merc_west).max(merc_north - merc_south))
+            .log2()
+            .floor()
+            .clamp(0f64, 24f64) as isize;
+        let min_zoom = min_zoom.unwrap_or(fit_zoom.min(max_zoom));
+        if min_zoom < 0 || max_zoom > 24 || min_zoom > max_zoom {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The zoom levels must be between 0 and 24, with the minimum zoom level (--min_zoom) no greater than the maximum zoom level (--max_zoom).",
+            ));
+        }
+
+        // the colour mapping
+        let renderer = Renderer::new(&input, &palette, min_value, max_value)?;
+
+        // the tiles that intersect the raster
+        let mut tiles: Vec<(u8, u32, u32)> = vec![];
+        for z in min_zoom..=max_zoom {
+            let n = 1i64 << z;
+            let tile_span = world_size / n as f64;
+            let to_index = |v: f64| ((v / tile_span).floor() as i64).clamp(0, n - 1) as u32;
+            let (x1, x2) = (
+                to_index(merc_west + world_size / 2f64),
+                to_index(merc_east + world_size / 2f64),
+            );
+            let (y1, y2) = (
+                to_index(world_size / 2f64 - merc_north),
+                to_index(world_size / 2f64 - merc_south),
+            );
+            for ty in y1..=y2 {
+                for tx in x1..=x2 {
+                    tiles.push((z as u8, tx, ty));
+                }
+            }
+        }
+        if verbose {
+            println!(
+                "Rendering up to {} tiles at zoom levels {} to {}...",
+                tiles.len(),
+                min_zoom,
+                max_zoom
+            );
+        }
+
+        let mut database: Option<(SqliteWriter, usize)> = None;
+        if is_mbtiles {
+            let mut db = SqliteWriter::create(&output_file)?;
+            db.set_application_id(MBTILES_APPLICATION_ID);
+            let metadata = db.create_table("metadata", "CREATE TABLE metadata (name text, value text)");
+            db.create_index(
+                "name",
+                metadata,
+                "CREATE UNIQUE INDEX name ON metadata (name)",
+                &[0],
+            );
+            let tiles_table = db.create_table(
+                "tiles",
+                "CREATE TABLE tiles (zoom_level integer, tile_column integer, tile_row integer, tile_data blob)",
+            );
+            db.create_index(
+                "tile_index",
+                tiles_table,
+                "CREATE UNIQUE INDEX tile_index ON tiles (zoom_level, tile_column, tile_row)",
+                &[0, 1, 2],
+            );
+
+            let name: String = match Path::new(&input_file).file_stem().unwrap().to_str() {
+                Some(n) => n.to_string(),
+                None => "".to_string(),
+            };
+            let (lon1, lat1) = (merc_x_to_lon(merc_west), merc_y_to_lat(merc_south));
+            let (lon2, lat2) = (merc_x_to_lon(merc_east), merc_y_to_lat(merc_north));
+            let entries = vec![
+                ("name", name),
+                ("format", "png".to_string()),
+                ("type", "overlay".to_string()),
+                ("version", "1.0".to_string()),
+                (
+                    "description",
+                    format!("Created by the WhiteboxTools {} tool", self.get_tool_name()),
+                ),
+                ("minzoom", format!("{}", min_zoom)),
+                ("maxzoom", format!("{}", max_zoom)),
+                ("bounds", format!("{:.6},{:.6},{:.6},{:.6}", lon1, lat1, lon2, lat2)),
+                (
+                    "center",
+                    format!(
+                        "{:.6},{:.6},{}",
+                        (lon1 + lon2) / 2f64,
+                        centre_lat,
+                        min_zoom
+                    ),
+                ),
+            ];
+            for (key, value) in entries {
+                db.insert(
+                    metadata,
+                    &[SqlValue::Text(key.to_string()), SqlValue::Text(value)],
+                )?;
+            }
+            database = Some((db, tiles_table));
+        } else {
+            DirBuilder::new().recursive(true).create(&output_file)?;
+        }
+
+        let input = Arc::new(input);
+        let tiles = Arc::new(tiles);
+        let tile_renderer = Arc::new(TileRenderer {
+            renderer,
+            source,
+            wgs84,
+        });
+        let next_tile = Arc::new(AtomicUsize::new(0));
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..num_procs {
+            let input = input.clone();
+            let tiles = tiles.clone();
+            let tile_renderer = tile_renderer.clone();
+            let next_tile = next_tile.clone();
+            let tx = tx.clone();
+            thread::spawn(move || loop {
+                let k = next_tile.fetch_add(1, Ordering::SeqCst);
+                if k >= tiles.len() {
+                    break;
+                }
+                let (z, x, y) = tiles[k];
+                let png = match tile_renderer.render(&input, z, x, y) {
+                    Some(pixels) => encode_png(&pixels).map(Some),
+                    None => Ok(None),
+                };
+                tx.send((k, png)).unwrap();
+            });
+        }
+
+        let mut num_written = 0usize;
+        for i in 0..tiles.len() {
+            let (k, png) = rx.recv().expect("Error receiving data from thread.");
+            if let Some(png) = png? {
+                let (z, x, y) = tiles[k];
+                match &mut database {
+                    Some((db, tiles_table)) => {
+                        // MBTiles rows are counted from the south
+                        let tile_row = (1i64 << z) - 1 - y as i64;
+                        db.insert(
+                            *tiles_table,
+                            &[
+                                SqlValue::Integer(z as i64),
+                                SqlValue::Integer(x as i64),
+                                SqlValue::Integer(tile_row),
+                                SqlValue::Blob(png),
+                            ],
+                        )?;
+                    }
+                    None => {
+                        let dir = format!("{}{}{}{}{}", output_file, sep, z, sep, x);
+                        DirBuilder::new().recursive(true).create(&dir)?;
+                        let mut f = File::create(format!("{}{}{}.png", dir, sep, y))?;
+                        f.write_all(&png)?;
+                    }
+                }
+                num_written += 1;
+            }
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / tiles.len() as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if let Some((db, _)) = database {
+            if verbose {
+                println!("Saving MBTiles database...")
+            };
+            db.finish()?;
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "Tiles written: {} of {} (empty tiles are not saved)",
+                num_written,
+                tiles.len()
+            );
+            println!("{}", &format!("Elapsed Time: {}", elapsed_time));
+        }
+
+        Ok(())
+    }
+}
+
+fn is_web_mercator_epsg(code: u16) -> bool {
+    code == 3857 || code == 3785
+}
+
+/// Returns true if a CRS string, e.g. 'EPSG:3857' or a WKT description, is Web Mercator.
+fn is_web_mercator_crs(crs: &str) -> bool {
+    let lower = crs.trim().to_lowercase();
+    let code = lower.trim_start_matches("epsg:");
+    if let Ok(code) = code.parse::<u32>() {
+        return code == 3857 || code == 3785 || code == 900913 || code == 102100;
+    }
+    lower.contains("mercator_auxiliary_sphere")
+        || lower.contains("pseudo-mercator")
+        || lower.contains("pseudo_mercator")
+        || lower.contains("+proj=merc +a=6378137 +b=6378137")
+}
+
+fn lon_to_merc_x(lon: f64) -> f64 {
+    lon.to_radians() * EARTH_RADIUS
+}
+
+fn lat_to_merc_y(lat: f64) -> f64 {
+    (PI / 4f64 + lat.to_radians() / 2f64).tan().ln() * EARTH_RADIUS
+}
+
+fn merc_x_to_lon(x: f64) -> f64 {
+    (x / EARTH_RADIUS).to_degrees()
+}
+
+fn merc_y_to_lat(y: f64) -> f64 {
+    (y / EARTH_RADIUS).sinh().atan().to_degrees()
+}
+
+/// The mapping of raster values to colours.
+enum Renderer {
+    /// Packed colour values, with or without an alpha channel.
+    Rgb { has_alpha: bool },
+    /// Distinct colours for integer values.
+    Qualitative,
+    /// A colour ramp of (position, colour) stops, stretched over a range of values.
+    Ramp {
+        stops: Vec<(f64, [u8; 3])>,
+        min: f64,
+        range: f64,
+        gamma: f64,
+    },
+}
+
+impl Renderer {
+    fn new(
+        input: &Raster,
+        palette: &str,
+        min_value: Option<f64>,
+        max_value: Option<f64>,
+    ) -> Result<Renderer, Error> {
+        let configs = &input.configs;
+        let palette = if palette == "auto" {
+            if configs.photometric_interp == PhotometricInterpretation::RGB
+                || configs.data_type == DataType::RGB24
+                || configs.data_type == DataType::RGBA32
+            {
+                "rgb".to_string()
+            } else if configs.photometric_interp == PhotometricInterpretation::Categorical {
+                "qual".to_string()
+            } else {
+                palette_from_file_name(&configs.palette).to_string()
+            }
+        } else {
+            palette.to_string()
+        };
+        if palette == "rgb" {
+            return Ok(Renderer::Rgb {
+                has_alpha: configs.data_type == DataType::RGBA32,
+            });
+        }
+        if palette == "qual" {
+            return Ok(Renderer::Qualitative);
+        }
+        let stops: Vec<(f64, [u8; 3])> = match palette.as_str() {
+            "grey" => vec![(0.0, [0, 0, 0]), (1.0, [255, 255, 255])],
+            "spectrum" => vec![
+                (0.0, [0, 0, 255]),
+                (0.25, [0, 255, 255]),
+                (0.5, [0, 255, 0]),
+                (0.75, [255, 255, 0]),
+                (1.0, [255, 0, 0]),
+            ],
+            "high_relief" => vec![
+                (0.0, [38, 115, 0]),
+                (0.2, [112, 168, 0]),
+                (0.4, [230, 230, 0]),
+                (0.6, [168, 112, 0]),
+                (0.8, [137, 90, 68]),
+                (1.0, [255, 255, 255]),
+            ],
+            "blue_white_red" => vec![
+                (0.0, [0, 0, 255]),
+                (0.5, [255, 255, 255]),
+                (1.0, [255, 0, 0]),
+            ],
+            "blueyellow" => vec![(0.0, [0, 0, 255]), (1.0, [255, 255, 0])],
+            "viridis" => vec![
+                (0.0, [68, 1, 84]),
+                (0.25, [59, 82, 139]),
+                (0.5, [33, 145, 140]),
+                (0.75, [94, 201, 98]),
+                (1.0, [253, 231, 37]),
+            ],
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The palette (--palette) must be one of 'auto', 'grey', 'spectrum', 'high_relief', 'blue_white_red', 'blueyellow', 'viridis', 'qual', or 'rgb'.",
+                ));
+            }
+        };
+
+        let (mut min, mut max) = (configs.display_min, configs.display_max);
+        if !min.is_finite() || !max.is_finite() || max <= min || min == configs.nodata {
+            min = configs.minimum;
+            max = configs.maximum;
+        }
+        let min = min_value.unwrap_or(min);
+        let max = max_value.unwrap_or(max);
+        let gamma = if configs.palette_nonlinearity > 0f64 {
+            configs.palette_nonlinearity
+        } else {
+            1f64
+        };
+        Ok(Renderer::Ramp {
+            stops,
+            min,
+            range: if max > min { max - min } else { 1f64 },
+            gamma,
+        })
+    }
+
+    fn colour(&self, z: f64) -> [u8; 4] {
+        match self {
+            Renderer::Rgb { has_alpha } => {
+                let v = z as u32;
+                let a = if *has_alpha { (v >> 24) as u8 } else { 255 };
+                [v as u8, (v >> 8) as u8, (v >> 16) as u8, a]
+            }
+            Renderer::Qualitative => {
+                // successive integers are separated by the golden angle in hue
+                let hue = (z.round() * 0.618_033_988_749_895).rem_euclid(1f64) * 6f64;
+                let (s, v) = (0.65f64, 0.95f64);
+                let f = hue - hue.floor();
+                let (p, q, t) = (v * (1.0 - s), v * (1.0 - s * f), v * (1.0 - s * (1.0 - f)));
+                let (r, g, b) = match hue.floor() as usize {
+                    0 => (v, t, p),
+                    1 => (q, v, p),
+                    2 => (p, v, t),
+                    3 => (p, q, v),
+                    4 => (t, p, v),
+                    _ => (v, p, q),
+                };
+                [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8, 255]
+            }
+            Renderer::Ramp {
+                stops,
+                min,
+                range,
+                gamma,
+            } => {
+                let mut t = ((z - min) / range).clamp(0f64, 1f64);
+                if *gamma != 1f64 {
+                    t = t.powf(*gamma);
+                }
+                let k = stops
+                    .iter()
+                    .position(|s| s.0 >= t)
+                    .unwrap_or(stops.len() - 1)
+                    .max(1);
+                let (t1, c1) = stops[k - 1];
+                let (t2, c2) = stops[k];
+                let w = ((t - t1) / (t2 - t1)).clamp(0f64, 1f64);
+                let mix = |a: u8, b: u8| (a as f64 + w * (b as f64 - a as f64)).round() as u8;
+                [mix(c1[0], c2[0]), mix(c1[1], c2[1]), mix(c1[2], c2[2]), 255]
+            }
+        }
+    }
+}
+
+/// Returns the palette of this tool that best matches a raster's palette file name.
+fn palette_from_file_name(file_name: &str) -> &'static str {
+    let name = file_name.to_lowercase().replace(".plt", "").replace(".pal", "");
+    match name.as_str() {
+        "spectrum" | "spectrum_soft" | "spectrum_black_background" | "muted_spectrum" => {
+            "spectrum"
+        }
+        "high_relief" | "atlas" | "arid" | "soft" | "earthtones" => "high_relief",
+        "blue_white_red" => "blue_white_red",
+        "blueyellow" => "blueyellow",
+        "viridis" => "viridis",
+        "qual" => "qual",
+        _ => "grey",
+    }
+}
+
+struct TileRenderer {
+    renderer: Renderer,
+    /// The coordinate system of the raster, or `None` if it is Web Mercator.
+    source: Option<Projection>,
+    wgs84: Projection,
+}
+
+impl TileRenderer {
+    /// Returns the raster coordinates of a point in Web Mercator coordinates.
+    fn to_source(&self, mx: f64, my: f64) -> (f64, f64) {
+        match &self.source {
+            Some(source) => self
+                .wgs84
+                .transform_to(source, merc_x_to_lon(mx), merc_y_to_lat(my)),
+            None => (mx, my),
+        }
+    }
+
+    /// Renders the RGBA pixels of a tile, or returns `None` if the tile is empty.
+    fn render(&self, input: &Raster, z: u8, tx: u32, ty: u32) -> Option<Vec<u8>> {
+        let world_size = 2f64 * PI * EARTH_RADIUS;
+        let tile_span = world_size / (1u64 << z) as f64;
+        let x0 = -world_size / 2f64 + tx as f64 * tile_span;
+        let y0 = world_size / 2f64 - ty as f64 * tile_span;
+        let pixel_size = tile_span / TILE_SIZE as f64;
+
+        // the exact transformation at the lattice points, which is interpolated between them
+        let n = TILE_SIZE / LATTICE_STEP + 1;
+        let mut lattice = vec![(0f64, 0f64); n * n];
+        for j in 0..n {
+            for i in 0..n {
+                lattice[j * n + i] = self.to_source(
+                    x0 + (i * LATTICE_STEP) as f64 * pixel_size,
+                    y0 - (j * LATTICE_STEP) as f64 * pixel_size,
+                );
+            }
+        }
+
+        let configs = &input.configs;
+        let nodata = configs.nodata;
+        let mut pixels = vec![0u8; TILE_SIZE * TILE_SIZE * 4];
+        let mut is_empty = true;
+        for py in 0..TILE_SIZE {
+            for px in 0..TILE_SIZE {
+                let (x, y) = if self.source.is_none() {
+                    (
+                        x0 + (px as f64 + 0.5) * pixel_size,
+                        y0 - (py as f64 + 0.5) * pixel_size,
+                    )
+                } else {
+                    let u = (px as f64 + 0.5) / LATTICE_STEP as f64;
+                    let v = (py as f64 + 0.5) / LATTICE_STEP as f64;
+                    let (i, j) = (u as usize, v as usize);
+                    let (fu, fv) = (u - i as f64, v - j as f64);
+                    let p00 = lattice[j * n + i];
+                    let p10 = lattice[j * n + i + 1];
+                    let p01 = lattice[(j + 1) * n + i];
+                    let p11 = lattice[(j + 1) * n + i + 1];
+                    let lerp = |a: f64, b: f64, c: f64, d: f64| {
+                        (a * (1.0 - fu) + b * fu) * (1.0 - fv) + (c * (1.0 - fu) + d * fu) * fv
+                    };
+                    (
+                        lerp(p00.0, p10.0, p01.0, p11.0),
+                        lerp(p00.1, p10.1, p01.1, p11.1),
+                    )
+                };
+                let col = ((x - configs.west) / configs.resolution_x).floor();
+                let row = ((configs.north - y) / configs.resolution_y).floor();
+                if !(col >= 0f64
+                    && row >= 0f64
+                    && col < configs.columns as f64
+                    && row < configs.rows as f64)
+                {
+                    continue;
+                }
+                let value = input.get_value(row as isize, col as isize);
+                if value == nodata {
+                    continue;
+                }
+                let colour = self.renderer.colour(value);
+                if colour[3] > 0 {
+                    let idx = (py * TILE_SIZE + px) * 4;
+                    pixels[idx..idx + 4].copy_from_slice(&colour);
+                    is_empty = false;
+                }
+            }
+        }
+        if is_empty {
+            None
+        } else {
+            Some(pixels)
+        }
+    }
+}
+
+/// Encodes the RGBA pixels of a tile as a PNG image.
+fn encode_png(pixels: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![];
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, TILE_SIZE as u32, TILE_SIZE as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(pixels)?;
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,687 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use crate::vector::{AttributeField, DateData, FieldData, FieldDataType, Shapefile};
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufReader, Error, ErrorKind};
+use std::path;
+
+/// This tool joins the columns of a comma separated values (CSV) text file onto the attribute table
+/// of a vector file, creating a new output vector (`--output`). Records are matched using a key field
+/// in the vector's attribute table (`--key`) and a key column in the CSV file (`--csv_key`). Key values
+/// are compared as text, after removing leading and trailing white space; numerical keys are compared
+/// by value, such that a key of `12` in the attribute table matches a value of `12.0` in the CSV file.
+///
+/// The CSV file must possess a header row containing the column names. Fields may be separated by
+/// commas, semicolons, or tabs, which is determined from the header row, and values containing the
+/// delimiter may be enclosed in double quotes. By default, all of the columns of the CSV file, with
+/// the exception of the key column, are appended to the table. Alternatively, the user may specify a
+/// comma-separated list of the columns to import (`--fields`). The data type of each imported field
+/// is inferred from all of the values in the column: columns containing only integer values are
+/// imported as integer fields, columns containing only numbers as floating-point fields, columns
+/// containing only `true`/`false` values as Boolean fields, and columns containing only dates of the
+/// form `YYYY-MM-DD` as date fields. All other columns are imported as text. Empty cells are imported
+/// as null values. Field names are truncated to the 10 characters allowed by the Shapefile format
+/// and, where an imported name conflicts with an existing field, a numerical suffix is added.
+///
+/// The `--one_to_many` parameter determines how features are handled when their key matches more
+/// than one row of the CSV file. The options are `first` (default) and `last`, which join the first
+/// or last matching row respectively, `duplicate`, in which case the feature is copied to the output
+/// once for each matching row, and `error`, which stops the tool if any such key is found. Features
+/// without a matching row receive null values, unless the `--matched_only` flag is specified, in
+/// which case they are excluded from the output.
+///
+/// # See Also
+/// `MergeTableWithCsv`, `JoinTables`, `ExportTableToCsv`
+pub struct JoinTableToVector {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl JoinTableToVector {
+    /// public constructor
+    pub fn new() -> JoinTableToVector {
+        let name = "JoinTableToVector".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Joins the columns of a CSV file onto a vector's attribute table using a key field."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Vector File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input vector file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Vector Key Field".to_owned(),
+            flags: vec!["--key".to_owned()],
+            description: "Key field in the vector's attribute table.".to_owned(),
+            parameter_type: ParameterType::VectorAttributeField(
+                AttributeType::Any,
+                "--input".to_string(),
+            ),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input CSV File".to_owned(),
+            flags: vec!["--csv".to_owned()],
+            description: "Input CSV file containing the data to join.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Csv),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "CSV Key Column".to_owned(),
+            flags: vec!["--csv_key".to_owned()],
+            description: "Key column in the CSV file.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Imported Columns".to_owned(),
+            flags: vec!["--fields".to_owned()],
+            description: "Comma-separated list of CSV columns to import (all columns are imported if not specified).".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "One-to-Many Handling".to_owned(),
+            flags: vec!["--one_to_many".to_owned()],
+            description: "How to handle keys matching multiple CSV rows; options include 'first', 'last', 'duplicate', and 'error'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "first".to_owned(),
+                "last".to_owned(),
+                "duplicate".to_owned(),
+                "error".to_owned(),
+            ]),
+            default_value: Some("first".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output matched features only?".to_owned(),
+            flags: vec!["--matched_only".to_owned()],
+            description: "Exclude features without a matching CSV row from the output.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=counties.shp --key=FIPS --csv=census.csv --csv_key=fips -o=output.shp
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=parcels.shp --key=PID --csv=owners.csv --csv_key=PID --fields=NAME,SINCE --one_to_many=duplicate --matched_only -o=output.shp",
+            short_exe, name
+        ).replace("*", &sep);
+
+        JoinTableToVector {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for JoinTableToVector {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut key_field = String::new();
+        let mut csv_file = String::new();
+        let mut csv_key = String::new();
+        let mut import_fields = String::new();
+        let mut one_to_many = String::from("first");
+        let mut matched_only = false;
+        let mut output_file = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-key" {
+                key_field = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-csv" {
+                csv_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-csv_key" {
+                csv_key = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-fields" {
+                import_fields = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-one_to_many" {
+                one_to_many = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-matched_only" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    matched_only = true;
+                }
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        csv_file = resolve_path(&csv_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if !["first", "last", "duplicate", "error"].contains(&one_to_many.as_str()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The --one_to_many parameter must be one of 'first', 'last', 'duplicate', or 'error'.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let input = Shapefile::read(&input_file)?;
+        if input.attributes.get_field_num(&key_field).is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The key field '{}' was not located in the attribute table.",
+                    key_field
+                ),
+            ));
+        }
+
+        let start = Instant::now();
+
+        // read in the CSV file
+        let f = BufReader::new(File::open(&csv_file)?);
+        let mut lines = f.lines();
+        let header_line = match lines.next() {
+            Some(line) => line?,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The CSV file does not contain a header row.",
+                ))
+            }
+        };
+        let header_line = header_line.trim_start_matches('\u{feff}');
+        let delimiter = if header_line.contains(',') {
+            ','
+        } else if header_line.contains(';') {
+            ';'
+        } else if header_line.contains('\t') {
+            '\t'
+        } else {
+            ','
+        };
+        let csv_headers = split_csv_record(header_line, delimiter);
+        let key_index = match csv_headers.iter().position(|h| h == csv_key.trim()) {
+            Some(i) => i,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "The key column '{}' was not located in the CSV file.",
+                        csv_key
+                    ),
+                ))
+            }
+        };
+        let column_indices: Vec<usize> = if import_fields.trim().is_empty() {
+            (0..csv_headers.len()).filter(|&i| i != key_index).collect()
+        } else {
+            let mut indices = vec![];
+            for name in import_fields.split(',').map(|s| s.trim()) {
+                match csv_headers.iter().position(|h| h == name) {
+                    Some(i) => indices.push(i),
+                    None => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("The column '{}' was not located in the CSV file.", name),
+                        ))
+                    }
+                }
+            }
+            indices
+        };
+
+        let mut rows: Vec<Vec<String>> = vec![];
+        let mut key_rows: HashMap<String, Vec<usize>> = HashMap::new();
+        for (line_num, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = split_csv_record(&line, delimiter);
+            if record.len() != csv_headers.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Line {} of the CSV file contains {} fields but the header row contains {}.",
+                        line_num + 2,
+                        record.len(),
+                        csv_headers.len()
+                    ),
+                ));
+            }
+            key_rows
+                .entry(normalize_key(&record[key_index]))
+                .or_default()
+                .push(rows.len());
+            rows.push(record);
+        }
+
+        if one_to_many == "error" {
+            if let Some((key, _)) = key_rows.iter().find(|(_, r)| r.len() > 1) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "The key '{}' occurs in more than one row of the CSV file.",
+                        key
+                    ),
+                ));
+            }
+        }
+
+        // infer the type of each imported column from all of its values
+        let mut output =
+            Shapefile::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
+        let mut column_types = Vec::with_capacity(column_indices.len());
+        for &c in &column_indices {
+            let column_type = infer_column_type(rows.iter().map(|r| r[c].as_str()));
+            let (length, precision) = match column_type {
+                FieldDataType::Int => (
+                    rows.iter()
+                        .map(|r| r[c].len())
+                        .max()
+                        .unwrap_or(1)
+                        .clamp(1, 18) as u8,
+                    0u8,
+                ),
+                FieldDataType::Real => {
+                    let precision = rows
+                        .iter()
+                        .map(|r| get_precision(&r[c]))
+                        .max()
+                        .unwrap_or(0)
+                        .min(15);
+                    // the number of characters before the decimal point
+                    let whole = rows
+                        .iter()
+                        .map(|r| r[c].find('.').unwrap_or(r[c].len()))
+                        .max()
+                        .unwrap_or(1);
+                    (
+                        (whole + precision as usize + 1).clamp(3, 254) as u8,
+                        precision,
+                    )
+                }
+                FieldDataType::Bool => (1u8, 0u8),
+                FieldDataType::Date => (8u8, 0u8),
+                FieldDataType::Text => (
+                    rows.iter()
+                        .map(|r| r[c].len())
+                        .max()
+                        .unwrap_or(1)
+                        .clamp(1, 254) as u8,
+                    0u8,
+                ),
+            };
+            let name = unique_field_name(&csv_headers[c], &output);
+            if verbose && name != csv_headers[c] {
+                println!(
+                    "Warning: the column '{}' is imported as the field '{}'.",
+                    csv_headers[c], name
+                );
+            }
+            output.attributes.add_field(&AttributeField::new(
+                &name,
+                column_type.clone(),
+                length,
+                precision,
+            ));
+            column_types.push(column_type);
+        }
+
+        let (mut num_matched, mut num_unmatched, mut num_multiple) = (0usize, 0usize, 0usize);
+        for record_num in 0..input.num_records {
+            let key = key_from_field_data(&input.attributes.get_value(record_num, &key_field));
+            let matches: Vec<usize> = match key_rows.get(&key) {
+                Some(r) => {
+                    num_matched += 1;
+                    if r.len() > 1 {
+                        num_multiple += 1;
+                    }
+                    match one_to_many.as_str() {
+                        "last" => vec![r[r.len() - 1]],
+                        "duplicate" => r.clone(),
+                        _ => vec![r[0]],
+                    }
+                }
+                None => {
+                    num_unmatched += 1;
+                    if matched_only {
+                        vec![]
+                    } else {
+                        // a single output record with null values
+                        vec![usize::MAX]
+                    }
+                }
+            };
+
+            for row in matches {
+                let mut atts = input.attributes.get_record(record_num);
+                for (i, &c) in column_indices.iter().enumerate() {
+                    atts.push(if row == usize::MAX {
+                        FieldData::Null
+                    } else {
+                        parse_value(&rows[row][c], &column_types[i])
+                    });
+                }
+                output.add_record(input.get_record(record_num).clone());
+                output.attributes.add_record(atts, false);
+            }
+
+            if verbose {
+                progress =
+                    (100.0_f64 * (record_num + 1) as f64 / input.num_records as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if verbose {
+            println!(
+                "Matched features: {}\nUnmatched features: {}\nFeatures matching more than one row: {}",
+                num_matched, num_unmatched, num_multiple
+            );
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("{}", &format!("Elapsed Time: {}", elapsed_time));
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits a line of a CSV file into its fields, handling values enclosed in double quotes.
+fn split_csv_record(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '"' {
+            if in_quotes && chars.peek() == Some(&'"') {
+                // an escaped quote
+                field.push('"');
+                chars.next();
+            } else {
+                in_quotes = !in_quotes;
+            }
+        } else if c == delimiter && !in_quotes {
+            fields.push(field.trim().to_string());
+            field.clear();
+        } else {
+            field.push(c);
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Returns the form of a key value used for matching. Numerical keys are compared by value.
+fn normalize_key(s: &str) -> String {
+    let s = s.trim();
+    match s.parse::<f64>() {
+        Ok(v) if v.is_finite() && v.fract() == 0f64 && v.abs() < 1e15 => format!("{}", v as i64),
+        Ok(v) if v.is_finite() => format!("{}", v),
+        _ => s.to_string(),
+    }
+}
+
+fn key_from_field_data(value: &FieldData) -> String {
+    match value {
+        FieldData::Int(v) => v.to_string(),
+        FieldData::Real(v) => normalize_key(&v.to_string()),
+        FieldData::Text(v) => normalize_key(v),
+        FieldData::Date(v) => v.to_string(),
+        FieldData::Bool(v) => v.to_string(),
+        FieldData::Null => String::new(),
+    }
+}
+
+fn parse_date(s: &str) -> Option<DateData> {
+    let parts: Vec<&str> = s.trim().split(['-', '/']).collect();
+    if parts.len() != 3 || parts[0].len() != 4 {
+        return None;
+    }
+    let year = parts[0].parse::<u16>().ok()?;
+    let month = parts[1].parse::<u8>().ok()?;
+    let day = parts[2].parse::<u8>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(DateData { year, month, day })
+}
+
+/// Infers the type of a CSV column from its non-empty values.
+fn infer_column_type<'a, I: Iterator<Item = &'a str>>(values: I) -> FieldDataType {
+    let (mut is_int, mut is_real, mut is_bool, mut is_date) = (true, true, true, true);
+    let mut num_values = 0;
+    for v in values.map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        num_values += 1;
+        is_int = is_int && v.parse::<i32>().is_ok();
+        is_real = is_real && v.parse::<f64>().is_ok_and(|x| x.is_finite());
+        is_bool = is_bool && v.to_lowercase().parse::<bool>().is_ok();
+        is_date = is_date && parse_date(v).is_some();
+    }
+    if num_values == 0 {
+        FieldDataType::Text
+    } else if is_int {
+        FieldDataType::Int
+    } else if is_real {
+        FieldDataType::Real
+    } else if is_bool {
+        FieldDataType::Bool
+    } else if is_date {
+        FieldDataType::Date
+    } else {
+        FieldDataType::Text
+    }
+}
+
+fn parse_value(s: &str, field_type: &FieldDataType) -> FieldData {
+    let s = s.trim();
+    if s.is_empty() {
+        return FieldData::Null;
+    }
+    match field_type {
+        FieldDataType::Int => s.parse::<i32>().map_or(FieldData::Null, FieldData::Int),
+        FieldDataType::Real => s.parse::<f64>().map_or(FieldData::Null, FieldData::Real),
+        FieldDataType::Bool => s
+            .to_lowercase()
+            .parse::<bool>()
+            .map_or(FieldData::Null, FieldData::Bool),
+        FieldDataType::Date => parse_date(s).map_or(FieldData::Null, FieldData::Date),
+        FieldDataType::Text => FieldData::Text(s.to_string()),
+    }
+}
+
+fn get_precision(s: &str) -> u8 {
+    match s.trim().find('.') {
+        Some(p) => (s.trim().len() - p - 1).min(255) as u8,
+        None => 0u8,
+    }
+}
+
+/// Returns a field name, no longer than the 10 characters allowed in a DBF file, that does not
+/// conflict with the fields already in the output table.
+fn unique_field_name(name: &str, output: &Shapefile) -> String {
+    let base: String = name.trim().chars().take(10).collect();
+    let base = if base.is_empty() {
+        "FIELD".to_string()
+    } else {
+        base
+    };
+    let exists = |n: &str| {
+        output
+            .attributes
+            .get_fields()
+            .iter()
+            .any(|f| f.name.to_lowercase() == n.to_lowercase())
+    };
+    if !exists(&base) {
+        return base;
+    }
+    let mut i = 2;
+    loop {
+        let suffix = format!("_{}", i);
+        let n: String = base
+            .chars()
+            .take(10 - suffix.len())
+            .chain(suffix.chars())
+            .collect();
+        if !exists(&n) {
+            return n;
+        }
+        i += 1;
+    }
+}
@@ -247,17 +247,11 @@ impl WhiteboxTool for MergeTableWithCsv {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !csv_file.contains(&sep) && !csv_file.contains("/") {
-            csv_file = format!("{}{}", working_directory, csv_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        csv_file = resolve_path(&csv_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -172,7 +172,6 @@ impl WhiteboxTool for MergeVectors {
             }
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -184,9 +183,7 @@ impl WhiteboxTool for MergeVectors {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let mut cmd = input_files.split(";");
         let mut vec = cmd.collect::<Vec<&str>>();
@@ -220,9 +217,7 @@ impl WhiteboxTool for MergeVectors {
         for value in vec {
             if !value.trim().is_empty() {
                 let mut input_file = value.trim().to_string();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
 
                 in_files.push(input_file.clone());
 
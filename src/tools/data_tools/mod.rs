@@ -3,8 +3,12 @@ mod add_point_coordinates_to_table;
 mod clean_vector;
 mod convert_nodata_to_zero;
 mod convert_raster_format;
+mod create_spatial_index;
 mod csv_points_to_vector;
+mod edit_raster_values;
 mod export_table_to_csv;
+mod export_tiles;
+mod join_table_to_vector;
 mod join_tables;
 mod lines_to_polygons;
 mod merge_table_with_csv;
@@ -20,17 +24,24 @@ mod reinitialize_attribute_table;
 mod remove_polygon_holes;
 mod set_nodata_value;
 mod singlepart_to_multipart;
+mod tile_raster;
+mod update_raster_from_vector;
 mod vector_lines_to_raster;
 mod vector_points_to_raster;
 mod vector_polygons_to_raster;
+mod write_raster_statistics;
 
 // exports identifiers from private sub-modules in the current module namespace
 pub use self::add_point_coordinates_to_table::AddPointCoordinatesToTable;
 pub use self::clean_vector::CleanVector;
 pub use self::convert_nodata_to_zero::ConvertNodataToZero;
 pub use self::convert_raster_format::ConvertRasterFormat;
+pub use self::create_spatial_index::CreateSpatialIndex;
 pub use self::csv_points_to_vector::CsvPointsToVector;
+pub use self::edit_raster_values::EditRasterValues;
 pub use self::export_table_to_csv::ExportTableToCsv;
+pub use self::export_tiles::ExportTiles;
+pub use self::join_table_to_vector::JoinTableToVector;
 pub use self::join_tables::JoinTables;
 pub use self::lines_to_polygons::LinesToPolygons;
 pub use self::merge_table_with_csv::MergeTableWithCsv;
@@ -46,6 +57,9 @@ pub use self::reinitialize_attribute_table::ReinitializeAttributeTable;
 pub use self::remove_polygon_holes::RemovePolygonHoles;
 pub use self::set_nodata_value::SetNodataValue;
 pub use self::singlepart_to_multipart::SinglePartToMultiPart;
+pub use self::tile_raster::TileRaster;
+pub use self::update_raster_from_vector::UpdateRasterFromVector;
 pub use self::vector_lines_to_raster::VectorLinesToRaster;
 pub use self::vector_points_to_raster::VectorPointsToRaster;
 pub use self::vector_polygons_to_raster::VectorPolygonsToRaster;
+pub use self::write_raster_statistics::WriteRasterStatistics;
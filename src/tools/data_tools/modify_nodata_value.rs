@@ -155,12 +155,7 @@ impl WhiteboxTool for ModifyNoDataValue {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            // Windows now takes forward slash in directory names.
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
 
         let mut input = Raster::new(&input_file, "rw")?;
 
@@ -157,7 +157,6 @@ impl WhiteboxTool for PolygonsToLines {
             }
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -169,13 +168,9 @@ impl WhiteboxTool for PolygonsToLines {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let input = Shapefile::read(&input_file)?;
 
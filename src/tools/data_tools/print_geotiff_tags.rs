@@ -155,11 +155,7 @@ impl WhiteboxTool for PrintGeoTiffTags {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
 
         // make sure that it is a tiff file
         if !input_file.to_lowercase().ends_with(".tiff")
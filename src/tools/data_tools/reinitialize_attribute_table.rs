@@ -148,9 +148,7 @@ impl WhiteboxTool for ReinitializeAttributeTable {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
 
         let input = Shapefile::read(&input_file)?;
 
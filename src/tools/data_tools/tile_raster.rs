@@ -0,0 +1,531 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::structures::Point2D;
+use crate::tools::*;
+use crate::vector::*;
+use std::env;
+use std::f64;
+use std::fs::DirBuilder;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::path::Path;
+
+/// This tool splits a large raster (`--input`) into a regular grid of smaller rectangular tiles,
+/// e.g. to distribute the processing of a large data set across multiple processes or machines.
+/// The tile size is specified in grid cells (`--width` and `--height`). Tiles along the right and
+/// bottom edges of the raster may be smaller than the specified size. Adjacent tiles can optionally
+/// overlap by a specified number of grid cells (`--overlap`), which is useful for operations that
+/// require neighbouring cells, e.g. filters and terrain derivatives, so that the edge effects within
+/// each tile can be trimmed away when the processed tiles are mosaicked back together.
+///
+/// The tiles are saved in the output directory (`--output_dir`), which is created if it does not
+/// exist; by default, a directory with the same name as the input file is created alongside the
+/// input. Tiles are saved in the format of the input raster. The tile file names are based on the
+/// input file name and one of the following naming schemes (`--naming`):
+///
+/// | Scheme   | Example                    | Description                                              |
+/// |----------|----------------------------|----------------------------------------------------------|
+/// | rowcol   | DEM_row2_col3.tif          | The tile row and column numbers, starting from one       |
+/// | index    | DEM_0007.tif               | The sequential tile number, in row-major order            |
+/// | coords   | DEM_560000_4820000.tif     | The x and y coordinates of the lower-left corner of the tile, excluding overlap |
+///
+/// Tiles containing only NoData values are not saved unless the `--keep_empty` flag is specified.
+///
+/// The tool also writes an index polygon vector (`--index`), containing one rectangle for the
+/// full extent of each saved tile, including its overlap. The attribute table contains the tile
+/// file name (TILE_NAME), the tile row and column numbers (ROW and COLUMN), and the extent of the
+/// tile excluding overlap (CORE_XMIN, CORE_XMAX, CORE_YMIN, and CORE_YMAX), which can be used to
+/// trim processed tiles. By default, the index is saved in the output directory with the name
+/// *\<input\>_index.shp*.
+///
+/// # See Also
+/// `LidarTile`, `Mosaic`, `CreateRectangularVectorGrid`
+pub struct TileRaster {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl TileRaster {
+    pub fn new() -> TileRaster {
+        // public constructor
+        let name = "TileRaster".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Splits a raster into regular, optionally overlapping, tiles and writes a tile index."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Directory".to_owned(),
+            flags: vec!["--output_dir".to_owned()],
+            description: "Output directory for the tiles; by default, a directory with the name of the input file.".to_owned(),
+            parameter_type: ParameterType::Directory,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Index File".to_owned(),
+            flags: vec!["--index".to_owned()],
+            description: "Output tile index polygon vector file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Tile Width (columns)".to_owned(),
+            flags: vec!["--width".to_owned()],
+            description: "Tile width, in grid cells.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("1000".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Tile Height (rows)".to_owned(),
+            flags: vec!["--height".to_owned()],
+            description: "Tile height, in grid cells.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("1000".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Overlap (cells)".to_owned(),
+            flags: vec!["--overlap".to_owned()],
+            description: "Number of grid cells by which adjacent tiles overlap.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Tile Naming Scheme".to_owned(),
+            flags: vec!["--naming".to_owned()],
+            description: "Tile naming scheme; options include 'rowcol', 'index', and 'coords'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "rowcol".to_owned(),
+                "index".to_owned(),
+                "coords".to_owned(),
+            ]),
+            default_value: Some("rowcol".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Keep empty tiles?".to_owned(),
+            flags: vec!["--keep_empty".to_owned()],
+            description: "Save tiles that contain only NoData values.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=DEM.tif --width=2000 --height=2000 --overlap=50
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=DEM.tif --output_dir=*path*to*tiles* --index=tiles.shp --width=1000 --height=1000 --naming=coords", short_exe, name).replace("*", &sep);
+
+        TileRaster {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for TileRaster {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--width".to_string(), ParameterConstraint::Min(1f64)),
+            ("--height".to_string(), ParameterConstraint::Min(1f64)),
+            ("--overlap".to_string(), ParameterConstraint::Min(0f64)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_dir = String::new();
+        let mut index_file = String::new();
+        let mut tile_width = 1000isize;
+        let mut tile_height = 1000isize;
+        let mut overlap = 0isize;
+        let mut naming = String::from("rowcol");
+        let mut keep_empty = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-output_dir" {
+                output_dir = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-index" {
+                index_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-width" {
+                tile_width = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as isize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as isize
+                };
+            } else if flag_val == "-height" {
+                tile_height = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as isize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as isize
+                };
+            } else if flag_val == "-overlap" {
+                overlap = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as isize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as isize
+                };
+            } else if flag_val == "-naming" {
+                naming = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                }
+                .to_lowercase();
+            } else if flag_val == "-keep_empty" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    keep_empty = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if naming != "rowcol" && naming != "index" && naming != "coords" {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The tile naming scheme (--naming) must be one of 'rowcol', 'index', or 'coords'.",
+            ));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+
+        let name: String = match Path::new(&input_file).file_stem().unwrap().to_str() {
+            Some(n) => n.to_string(),
+            None => "".to_string(),
+        };
+        let extension: String = match Path::new(&input_file).extension() {
+            Some(e) => e.to_str().unwrap_or("tif").to_string(),
+            None => "tif".to_string(),
+        };
+        if output_dir.is_empty() {
+            let dir: String = match Path::new(&input_file).parent().unwrap().to_str() {
+                Some(n) => n.to_string(),
+                None => "".to_string(),
+            };
+            output_dir = format!("{}{}{}", dir, sep, name);
+        } else {
+            output_dir = resolve_path(&output_dir, working_directory);
+        }
+        if !output_dir.ends_with(&sep) && !output_dir.ends_with("/") {
+            output_dir.push_str(&sep);
+        }
+        if index_file.is_empty() {
+            index_file = format!("{}{}_index.shp", output_dir, name);
+        } else {
+            index_file = resolve_path(&index_file, working_directory);
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let input = Raster::new(&input_file, "r")?;
+
+        let start = Instant::now();
+
+        DirBuilder::new().recursive(true).create(&output_dir)?;
+
+        let rows = input.configs.rows as isize;
+        let columns = input.configs.columns as isize;
+        let nodata = input.configs.nodata;
+        let res_x = input.configs.resolution_x;
+        let res_y = input.configs.resolution_y;
+        let west = input.configs.west;
+        let north = input.configs.north;
+        let tile_rows = (rows as f64 / tile_height as f64).ceil() as isize;
+        let tile_cols = (columns as f64 / tile_width as f64).ceil() as isize;
+        let num_tiles = tile_rows * tile_cols;
+        let index_digits = format!("{}", num_tiles).len().max(4);
+
+        let mut index = Shapefile::new(&index_file, ShapeType::Polygon)?;
+        index.projection = input.configs.coordinate_ref_system_wkt.clone();
+        index
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        index.attributes.add_field(&AttributeField::new(
+            "TILE_NAME",
+            FieldDataType::Text,
+            (name.len() + 32).min(254) as u8,
+            0u8,
+        ));
+        index
+            .attributes
+            .add_field(&AttributeField::new("ROW", FieldDataType::Int, 7u8, 0u8));
+        index
+            .attributes
+            .add_field(&AttributeField::new("COLUMN", FieldDataType::Int, 7u8, 0u8));
+        for field_name in &["CORE_XMIN", "CORE_XMAX", "CORE_YMIN", "CORE_YMAX"] {
+            index.attributes.add_field(&AttributeField::new(
+                field_name,
+                FieldDataType::Real,
+                18u8,
+                6u8,
+            ));
+        }
+
+        let mut num_written = 0i32;
+        for tr in 0..tile_rows {
+            for tc in 0..tile_cols {
+                // the core extent of the tile, in grid cells, and the extent including overlap
+                let core_row1 = tr * tile_height;
+                let core_row2 = ((tr + 1) * tile_height).min(rows);
+                let core_col1 = tc * tile_width;
+                let core_col2 = ((tc + 1) * tile_width).min(columns);
+                let row1 = (core_row1 - overlap).max(0);
+                let row2 = (core_row2 + overlap).min(rows);
+                let col1 = (core_col1 - overlap).max(0);
+                let col2 = (core_col2 + overlap).min(columns);
+
+                let mut configs = RasterConfigs {
+                    ..Default::default()
+                };
+                configs.rows = (row2 - row1) as usize;
+                configs.columns = (col2 - col1) as usize;
+                configs.north = north - row1 as f64 * res_y;
+                configs.south = north - row2 as f64 * res_y;
+                configs.west = west + col1 as f64 * res_x;
+                configs.east = west + col2 as f64 * res_x;
+                configs.resolution_x = res_x;
+                configs.resolution_y = res_y;
+                configs.nodata = nodata;
+                configs.data_type = input.configs.data_type;
+                configs.photometric_interp = input.configs.photometric_interp;
+                configs.palette = input.configs.palette.clone();
+                configs.projection = input.configs.projection.clone();
+                configs.xy_units = input.configs.xy_units.clone();
+                configs.z_units = input.configs.z_units.clone();
+                configs.epsg_code = input.configs.epsg_code;
+                configs.coordinate_ref_system_wkt =
+                    input.configs.coordinate_ref_system_wkt.clone();
+                configs.geo_key_directory = input.configs.geo_key_directory.clone();
+                configs.geo_double_params = input.configs.geo_double_params.clone();
+                configs.geo_ascii_params = input.configs.geo_ascii_params.clone();
+
+                let tile_name = match &naming as &str {
+                    "index" => format!(
+                        "{}_{:0width$}",
+                        name,
+                        tr * tile_cols + tc + 1,
+                        width = index_digits
+                    ),
+                    "coords" => format!(
+                        "{}_{}_{}",
+                        name,
+                        (west + core_col1 as f64 * res_x).round() as i64,
+                        (north - core_row2 as f64 * res_y).round() as i64
+                    ),
+                    _ => format!("{}_row{}_col{}", name, tr + 1, tc + 1),
+                };
+                let tile_file = format!("{}{}.{}", output_dir, tile_name, extension);
+
+                let mut output = Raster::initialize_using_config(&tile_file, &configs);
+                let mut has_data = false;
+                for row in row1..row2 {
+                    let data = input.get_row_data(row)[col1 as usize..col2 as usize].to_vec();
+                    if !has_data {
+                        has_data = data.iter().any(|z| *z != nodata);
+                    }
+                    output.set_row_data(row - row1, data);
+                }
+
+                if has_data || keep_empty {
+                    output.add_metadata_entry(format!(
+                        "Created by whitebox_tools\' {} tool",
+                        self.get_tool_name()
+                    ));
+                    output.add_metadata_entry(format!("Input file: {}", input_file));
+                    output.add_metadata_entry(format!(
+                        "Tile row: {}; tile column: {}; overlap: {}",
+                        tr + 1,
+                        tc + 1,
+                        overlap
+                    ));
+                    let _ = match output.write() {
+                        Ok(_) => (), // do nothing
+                        Err(e) => {
+                            return Err(Error::new(
+                                ErrorKind::Other,
+                                format!("Error while writing {}: {:?}", tile_file, e),
+                            ))
+                        }
+                    };
+
+                    let points = vec![
+                        Point2D::new(configs.west, configs.north),
+                        Point2D::new(configs.east, configs.north),
+                        Point2D::new(configs.east, configs.south),
+                        Point2D::new(configs.west, configs.south),
+                        Point2D::new(configs.west, configs.north),
+                    ];
+                    let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+                    sfg.add_part(&points);
+                    index.add_record(sfg);
+
+                    num_written += 1;
+                    index.attributes.add_record(
+                        vec![
+                            FieldData::Int(num_written),
+                            FieldData::Text(format!("{}.{}", tile_name, extension)),
+                            FieldData::Int((tr + 1) as i32),
+                            FieldData::Int((tc + 1) as i32),
+                            FieldData::Real(west + core_col1 as f64 * res_x),
+                            FieldData::Real(west + core_col2 as f64 * res_x),
+                            FieldData::Real(north - core_row2 as f64 * res_y),
+                            FieldData::Real(north - core_row1 as f64 * res_y),
+                        ],
+                        false,
+                    );
+                }
+
+                if verbose {
+                    progress = (100.0_f64 * (tr * tile_cols + tc + 1) as f64 / num_tiles as f64)
+                        as usize;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        if verbose {
+            println!("Saving tile index...")
+        };
+        let _ = match index.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "Tiles created: {} of {} ({} rows x {} columns)",
+                num_written, num_tiles, tile_rows, tile_cols
+            );
+            println!("{}", &format!("Elapsed Time: {}", elapsed_time));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,557 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::point_in_poly;
+use crate::raster::*;
+use crate::structures::{Array2D, BoundingBox, Point2D};
+use crate::tools::*;
+use crate::vector::{FieldData, ShapeType, Shapefile, ShapefileGeometry};
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool burns the features of a vector file directly into an existing raster (`--raster`),
+/// modifying the raster in place. Cells that lie along lines, within polygons (i.e. polygons that
+/// contain the cell centre, excluding holes), or that contain points are updated, while all other
+/// cells, and the raster's extent, data type, and metadata, are left unchanged. This avoids the
+/// need to rasterize the vector using a tool such as `VectorPolygonsToRaster` and then to overlay
+/// the result on the raster, and is useful for editing DEMs, e.g. for enforcing culverts and road
+/// crossings in a DEM prior to hydrological analysis, or for masking buildings.
+///
+/// The burned value is either a constant (`--value`), which may be `nodata` to set the affected
+/// cells to the raster's NoData value, or a numeric attribute of the vector (`--field`). The
+/// `--method` parameter determines how the burned value is combined with the existing cell value:
+///
+/// | Method    | Updated cell value                                   |
+/// |-----------|------------------------------------------------------|
+/// | `replace` | The burned value (default)                           |
+/// | `minimum` | The lesser of the existing and burned values         |
+/// | `maximum` | The greater of the existing and burned values        |
+/// | `add`     | The existing value plus the burned value             |
+///
+/// The `minimum` method is convenient for cutting culverts through road embankments, since it
+/// lowers the DEM along the culvert without raising any cells that are already lower. With the
+/// `minimum` and `maximum` methods, NoData cells are assigned the burned value, whereas NoData
+/// cells are unaffected by the `add` method. Each cell is updated at most once by each feature,
+/// but cells that are covered by several features are updated by each of them.
+///
+/// Lines are burned into every cell that they pass through, such that burned lines are continuous.
+/// Because the input raster is overwritten, users should make a copy of the raster before running
+/// the tool if the original values need to be retained.
+///
+/// # See Also
+/// `VectorLinesToRaster`, `VectorPolygonsToRaster`, `VectorPointsToRaster`, `ErasePolygonFromRaster`
+pub struct UpdateRasterFromVector {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl UpdateRasterFromVector {
+    /// public constructor
+    pub fn new() -> UpdateRasterFromVector {
+        let name = "UpdateRasterFromVector".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Burns vector points, lines, or polygons into an existing raster, modifying it in place."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Vector File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input vector points, lines, or polygons file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Raster File To Update".to_owned(),
+            flags: vec!["--raster".to_owned()],
+            description: "Existing raster file, which is modified by the tool.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Field Name (optional)".to_owned(),
+            flags: vec!["--field".to_owned()],
+            description: "Numeric attribute field containing the burned values. Not used when a value is specified.".to_owned(),
+            parameter_type: ParameterType::VectorAttributeField(
+                AttributeType::Number,
+                "--input".to_string(),
+            ),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Burn Value (optional)".to_owned(),
+            flags: vec!["--value".to_owned()],
+            description: "Constant burned value, or 'nodata'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Update Method".to_owned(),
+            flags: vec!["--method".to_owned()],
+            description: "Method used to combine the burned and existing values; options include 'replace', 'minimum', 'maximum', and 'add'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "replace".to_owned(),
+                "minimum".to_owned(),
+                "maximum".to_owned(),
+                "add".to_owned(),
+            ]),
+            default_value: Some("replace".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=culverts.shp --raster=dem.tif --field=INVERT --method=minimum
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=buildings.shp --raster=dem.tif --value=nodata", short_exe, name).replace("*", &sep);
+
+        UpdateRasterFromVector {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for UpdateRasterFromVector {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut raster_file = String::new();
+        let mut field_name = String::new();
+        let mut value_str = String::new();
+        let mut method = String::from("replace");
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-raster" {
+                raster_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-field" {
+                field_name = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-value" {
+                value_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-method" {
+                method = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        raster_file = resolve_path(&raster_file, working_directory);
+
+        let update_method = if method.contains("min") {
+            UpdateMethod::Minimum
+        } else if method.contains("max") {
+            UpdateMethod::Maximum
+        } else if method.contains("add") || method.contains("sum") {
+            UpdateMethod::Add
+        } else if method.contains("replace") {
+            UpdateMethod::Replace
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unrecognized update method '{}'. Use 'replace', 'minimum', 'maximum', or 'add'.", method),
+            ));
+        };
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let vector_data = Shapefile::read(&input_file)?;
+        let mut output = Raster::new(&raster_file, "rw")?;
+
+        let start = Instant::now();
+
+        let nodata = output.configs.nodata;
+        let rows = output.configs.rows as isize;
+        let columns = output.configs.columns as isize;
+
+        // the burned value of each record
+        let mut burn_values = vec![nodata; vector_data.num_records];
+        if !value_str.trim().is_empty() {
+            let value = if value_str.trim().to_lowercase() == "nodata" {
+                nodata
+            } else {
+                match value_str.trim().parse::<f64>() {
+                    Ok(v) => v,
+                    Err(_) => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("The burn value '{}' is not a number or 'nodata'.", value_str),
+                        ))
+                    }
+                }
+            };
+            for v in burn_values.iter_mut() {
+                *v = value;
+            }
+        } else if !field_name.trim().is_empty() {
+            let field_index = match vector_data.attributes.get_field_num(&field_name) {
+                Some(i) => i,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("The field '{}' was not found in the attribute table.", field_name),
+                    ))
+                }
+            };
+            if !vector_data.attributes.is_field_numeric(field_index) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The burned values must be contained in a numeric attribute field.",
+                ));
+            }
+            for record_num in 0..vector_data.num_records {
+                burn_values[record_num] = match vector_data.attributes.get_value(record_num, &field_name) {
+                    FieldData::Int(val) => val as f64,
+                    FieldData::Real(val) => val,
+                    _ => nodata, // null values are burned as NoData
+                };
+            }
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either a burn value (--value) or an attribute field (--field) must be specified.",
+            ));
+        }
+
+        let base_shape_type = vector_data.header.shape_type.base_shape_type();
+        let raster_bb = BoundingBox::new(
+            output.configs.west,
+            output.configs.east,
+            output.configs.south,
+            output.configs.north,
+        );
+
+        // the last record to have updated each cell, so that no feature updates a cell twice
+        let mut updated_by: Array2D<i32> = Array2D::new(rows, columns, -1i32, -1i32)?;
+        let mut num_updated_cells = 0usize;
+        let num_records = vector_data.num_records;
+        for record_num in 0..num_records {
+            let record = vector_data.get_record(record_num);
+            let rec_bb = BoundingBox::new(record.x_min, record.x_max, record.y_min, record.y_max);
+            if rec_bb.overlaps(raster_bb) {
+                let cells = match base_shape_type {
+                    ShapeType::Point | ShapeType::MultiPoint => record
+                        .points
+                        .iter()
+                        .map(|p| (output.get_row_from_y(p.y), output.get_column_from_x(p.x)))
+                        .collect(),
+                    ShapeType::PolyLine => line_cells(&output, record),
+                    _ => polygon_cells(&output, record),
+                };
+
+                let value = burn_values[record_num];
+                for (row, col) in cells {
+                    if row < 0 || row >= rows || col < 0 || col >= columns {
+                        continue;
+                    }
+                    if updated_by.get_value(row, col) == record_num as i32 {
+                        continue;
+                    }
+                    updated_by.set_value(row, col, record_num as i32);
+                    let z = output.get_value(row, col);
+                    let z_new = if value == nodata {
+                        nodata
+                    } else {
+                        match update_method {
+                            UpdateMethod::Replace => value,
+                            UpdateMethod::Minimum => {
+                                if z == nodata || value < z {
+                                    value
+                                } else {
+                                    z
+                                }
+                            }
+                            UpdateMethod::Maximum => {
+                                if z == nodata || value > z {
+                                    value
+                                } else {
+                                    z
+                                }
+                            }
+                            UpdateMethod::Add => {
+                                if z == nodata {
+                                    nodata
+                                } else {
+                                    z + value
+                                }
+                            }
+                        }
+                    };
+                    if z_new != z {
+                        output.set_value(row, col, z_new);
+                        num_updated_cells += 1;
+                    }
+                }
+            }
+
+            if verbose {
+                progress = (100.0_f64 * (record_num + 1) as f64 / num_records as f64) as usize;
+                if progress != old_progress {
+                    println!("Updating raster: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of modified cells: {}", num_updated_cells);
+            println!("Saving data...")
+        };
+        output.update_min_max();
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum UpdateMethod {
+    Replace,
+    Minimum,
+    Maximum,
+    Add,
+}
+
+/// Returns the range of a record's points that belong to one of its parts.
+fn part_range(record: &ShapefileGeometry, part: usize) -> (usize, usize) {
+    let start = record.parts[part] as usize;
+    let end = if part < record.num_parts as usize - 1 {
+        record.parts[part + 1] as usize
+    } else {
+        record.num_points as usize
+    };
+    (start, end)
+}
+
+/// Returns the (row, column) of each cell that a polyline passes through. Each segment is
+/// traversed from cell to cell, in the manner of Amanatides and Woo's voxel traversal, so that
+/// the cells of a line are 4-connected.
+fn line_cells(raster: &Raster, record: &ShapefileGeometry) -> Vec<(isize, isize)> {
+    let mut cells = vec![];
+    let res_x = raster.configs.resolution_x;
+    let res_y = raster.configs.resolution_y;
+    for part in 0..record.num_parts as usize {
+        let (start, end) = part_range(record, part);
+        for i in start..end.saturating_sub(1) {
+            let (p1, p2) = (record.points[i], record.points[i + 1]);
+            // continuous cell coordinates, i.e. column and row, of the segment end points
+            let (x1, y1) = ((p1.x - raster.configs.west) / res_x, (raster.configs.north - p1.y) / res_y);
+            let (x2, y2) = ((p2.x - raster.configs.west) / res_x, (raster.configs.north - p2.y) / res_y);
+            let (mut col, mut row) = (x1.floor() as isize, y1.floor() as isize);
+            let (end_col, end_row) = (x2.floor() as isize, y2.floor() as isize);
+            let (dx, dy) = (x2 - x1, y2 - y1);
+            let step_col = if dx > 0f64 { 1 } else { -1 };
+            let step_row = if dy > 0f64 { 1 } else { -1 };
+            // the parametric distance along the segment to the next column and row boundaries
+            let t_delta_x = if dx != 0f64 { (1f64 / dx).abs() } else { f64::INFINITY };
+            let t_delta_y = if dy != 0f64 { (1f64 / dy).abs() } else { f64::INFINITY };
+            let mut t_max_x = if dx > 0f64 {
+                (x1.floor() + 1f64 - x1) * t_delta_x
+            } else if dx < 0f64 {
+                (x1 - x1.floor()) * t_delta_x
+            } else {
+                f64::INFINITY
+            };
+            let mut t_max_y = if dy > 0f64 {
+                (y1.floor() + 1f64 - y1) * t_delta_y
+            } else if dy < 0f64 {
+                (y1 - y1.floor()) * t_delta_y
+            } else {
+                f64::INFINITY
+            };
+            cells.push((row, col));
+            let max_steps = (end_col - col).abs() + (end_row - row).abs();
+            for _ in 0..max_steps {
+                if t_max_x < t_max_y {
+                    col += step_col;
+                    t_max_x += t_delta_x;
+                } else {
+                    row += step_row;
+                    t_max_y += t_delta_y;
+                }
+                cells.push((row, col));
+            }
+        }
+        if end > start {
+            let p = record.points[end - 1];
+            cells.push((raster.get_row_from_y(p.y), raster.get_column_from_x(p.x)));
+        }
+    }
+    cells
+}
+
+/// Returns the (row, column) of each cell with a centre that lies within a polygon, excluding
+/// the cells within the polygon's holes.
+fn polygon_cells(raster: &Raster, record: &ShapefileGeometry) -> Vec<(isize, isize)> {
+    let rows = raster.configs.rows as isize;
+    let columns = raster.configs.columns as isize;
+    let mut cells = vec![];
+    let mut holes = vec![];
+    for part in 0..record.num_parts as usize {
+        if record.is_hole(part as i32) {
+            holes.push(part_range(record, part));
+        }
+    }
+    for part in 0..record.num_parts as usize {
+        if record.is_hole(part as i32) {
+            continue;
+        }
+        let (start, end) = part_range(record, part);
+        let ring = &record.points[start..end];
+        if ring.is_empty() {
+            continue;
+        }
+        let mut bb = BoundingBox {
+            ..Default::default()
+        };
+        bb.initialize_to_inf();
+        for p in ring {
+            bb.min_x = bb.min_x.min(p.x);
+            bb.max_x = bb.max_x.max(p.x);
+            bb.min_y = bb.min_y.min(p.y);
+            bb.max_y = bb.max_y.max(p.y);
+        }
+        let top_row = raster.get_row_from_y(bb.max_y).max(0);
+        let bottom_row = raster.get_row_from_y(bb.min_y).min(rows - 1);
+        let left_col = raster.get_column_from_x(bb.min_x).max(0);
+        let right_col = raster.get_column_from_x(bb.max_x).min(columns - 1);
+        for row in top_row..=bottom_row {
+            let y = raster.get_y_from_row(row);
+            for col in left_col..=right_col {
+                let p = Point2D::new(raster.get_x_from_column(col), y);
+                if point_in_poly(&p, ring)
+                    && !holes
+                        .iter()
+                        .any(|&(s, e)| point_in_poly(&p, &record.points[s..e]))
+                {
+                    cells.push((row, col));
+                }
+            }
+        }
+    }
+    cells
+}
@@ -241,17 +241,11 @@ impl WhiteboxTool for VectorLinesToRaster {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -297,9 +291,7 @@ impl WhiteboxTool for VectorLinesToRaster {
         // If both are specified, the base raster takes priority.
 
         let mut output = if !base_file.trim().is_empty() || cell_size == 0f64 {
-            if !base_file.contains(&sep) && !base_file.contains("/") {
-                base_file = format!("{}{}", working_directory, base_file);
-            }
+            base_file = resolve_path(&base_file, working_directory);
             let base = Raster::new(&base_file, "r")?;
             Raster::initialize_using_file(&output_file, &base)
         } else {
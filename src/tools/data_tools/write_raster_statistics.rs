@@ -0,0 +1,210 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool calculates the statistics (minimum, maximum, mean, standard deviation, and percentage of
+/// valid cells) and histogram of each band of one or more input rasters (`--inputs`) and writes them
+/// to GDAL-style Persistent Auxiliary Metadata sidecar files, named after the input files with an
+/// added `.aux.xml` extension (e.g. *dem.tif.aux.xml*). GDAL, QGIS, and ArcGIS read the statistics
+/// stored in these sidecars rather than scanning the raster, such that Whitebox outputs are displayed
+/// with sensible default contrast stretches. The number of histogram bins may be specified using the
+/// `--bins` parameter; the default of 256 bins is that used by GDAL. NoData cells are excluded from
+/// both the statistics and the histogram. The input files themselves are not modified.
+///
+/// Sidecars may also be written for every raster output of any tool by running it with the global
+/// `--aux_xml` flag.
+///
+/// # See Also
+/// `RasterSummaryStats`, `RasterHistogram`
+pub struct WriteRasterStatistics {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl WriteRasterStatistics {
+    pub fn new() -> WriteRasterStatistics {
+        // public constructor
+        let name = "WriteRasterStatistics".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Writes the statistics and histograms of rasters to GDAL-style .aux.xml sidecar files."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Files".to_owned(),
+            flags: vec!["-i".to_owned(), "--inputs".to_owned()],
+            description: "Input raster files.".to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Histogram Bins".to_owned(),
+            flags: vec!["--bins".to_owned()],
+            description: "Number of histogram bins.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("256".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='dem.tif;image.tif' --bins=256",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        WriteRasterStatistics {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for WriteRasterStatistics {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_files_str = String::new();
+        let mut num_bins = AUX_XML_HISTOGRAM_BINS;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-inputs" || flag_val == "-input" {
+                input_files_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-bins" {
+                num_bins = if keyval {
+                    vec[1].to_string().parse::<usize>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<usize>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if num_bins < 1 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The number of histogram bins must be at least 1.",
+            ));
+        }
+
+        let mut cmd = input_files_str.split(";");
+        let mut input_files = cmd.collect::<Vec<&str>>();
+        if input_files.len() == 1 {
+            cmd = input_files_str.split(",");
+            input_files = cmd.collect::<Vec<&str>>();
+        }
+
+        let start = Instant::now();
+
+        let num_files = input_files.len();
+        for (i, file) in input_files.iter().enumerate() {
+            if file.trim().is_empty() {
+                continue;
+            }
+            let mut input_file = file.trim().to_owned();
+            input_file = resolve_path(&input_file, working_directory);
+            if verbose {
+                println!("Reading file {} of {}...", i + 1, num_files);
+            }
+            let input = Raster::new(&input_file, "r")?;
+            write_aux_xml(&input, num_bins)?;
+            if verbose {
+                println!("Written: {}", aux_xml_file_name(&input_file));
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
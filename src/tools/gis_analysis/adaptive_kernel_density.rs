@@ -0,0 +1,555 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::tools::*;
+use crate::vector::*;
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool estimates the spatial pattern of point density from a set of points (`--input`),
+/// which may be either a vector points file (Shapefile) or a LiDAR (LAS) file, using an adaptive
+/// kernel density estimator. Unlike fixed-radius counting (e.g. `LidarPointDensity`), in which the
+/// density is the number of points within a constant search radius divided by the search area,
+/// each point here is assigned its own kernel bandwidth, equal to the distance to its k-th nearest
+/// neighbour (`--k`). Kernels are therefore narrow where points are dense, preserving detail, and
+/// wide where points are sparse, which smooths the estimate and bridges the gaps between isolated
+/// points that otherwise result in zero-valued holes in the density map. The density at each grid
+/// cell centre is the sum of the kernels of all points:
+///
+/// > f(x) = &Sigma; K(|x - x<sub>i</sub>| / h<sub>i</sub>) / h<sub>i</sub><sup>2</sup>
+///
+/// where h<sub>i</sub> is the bandwidth of point i and K is the kernel function, either 'gaussian'
+/// (the default; truncated at three bandwidths) or 'epanechnikov' (`--kernel`). Both kernels are
+/// normalized to integrate to one, such that the output is measured in points per square map unit
+/// and the output grid sums, over its area, to the number of points.
+///
+/// The bandwidths may be restricted to a range using the `--min_bandwidth` and `--max_bandwidth`
+/// parameters. The minimum bandwidth defaults to the grid cell size, because kernels narrower than
+/// a grid cell are poorly sampled by the cell centres. The maximum bandwidth, which is unlimited by
+/// default, limits the spread of isolated points. Larger values of k increase the smoothness of the
+/// density surface.
+///
+/// The output grid resolution is specified using the `--cell_size` parameter and the grid extent is
+/// that of the input points. Alternatively, the output grid may be based on an existing raster
+/// (`--base`), in which case its extent and resolution are used. Withheld LiDAR points are ignored.
+///
+/// # See Also
+/// `LidarPointDensity`, `VectorPointsToRaster`
+pub struct AdaptiveKernelDensity {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl AdaptiveKernelDensity {
+    pub fn new() -> AdaptiveKernelDensity {
+        // public constructor
+        let name = "AdaptiveKernelDensity".to_string();
+        let toolbox = "GIS Analysis".to_string();
+        let description =
+            "Estimates point density using kernels with bandwidths adapted to the k-nearest neighbour distance."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Points File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input vector points or LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Any),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Neighbours (k)".to_owned(),
+            flags: vec!["--k".to_owned()],
+            description: "Number of nearest neighbours used to determine the bandwidth of each point.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("15".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Kernel Function".to_owned(),
+            flags: vec!["--kernel".to_owned()],
+            description: "Kernel function; options are 'gaussian' and 'epanechnikov'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "gaussian".to_owned(),
+                "epanechnikov".to_owned(),
+            ]),
+            default_value: Some("gaussian".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Bandwidth (optional)".to_owned(),
+            flags: vec!["--min_bandwidth".to_owned()],
+            description: "Optional minimum kernel bandwidth; the cell size is used by default.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Bandwidth (optional)".to_owned(),
+            flags: vec!["--max_bandwidth".to_owned()],
+            description: "Optional maximum kernel bandwidth.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Cell Size (optional)".to_owned(),
+            flags: vec!["--cell_size".to_owned()],
+            description:
+                "Optionally specified cell size of output raster. Not used when base raster is specified."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Base Raster File (optional)".to_owned(),
+            flags: vec!["--base".to_owned()],
+            description:
+                "Optionally specified input base raster file. Not used when a cell size is specified."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp -o=density.tif --k=20 --cell_size=5.0
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=density.tif --kernel=epanechnikov --max_bandwidth=50.0 --base=dem.tif", short_exe, name).replace("*", &sep);
+
+        AdaptiveKernelDensity {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for AdaptiveKernelDensity {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut base_file = String::new();
+        let mut k = 15usize;
+        let mut kernel = "gaussian".to_string();
+        let mut min_bandwidth = f64::NAN;
+        let mut max_bandwidth = f64::INFINITY;
+        let mut cell_size = 0f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-k" {
+                k = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-kernel" {
+                kernel = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-min_bandwidth" {
+                min_bandwidth = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_bandwidth" {
+                max_bandwidth = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-cell_size" {
+                cell_size = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-base" {
+                base_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        base_file = resolve_path(&base_file, working_directory);
+
+        let use_gaussian = match kernel.as_str() {
+            "gaussian" | "gauss" => true,
+            "epanechnikov" => false,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The --kernel parameter must be either 'gaussian' or 'epanechnikov'.",
+                ))
+            }
+        };
+        if k == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The number of neighbours (--k) must be greater than zero.",
+            ));
+        }
+        if base_file.is_empty() && cell_size <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either a positive --cell_size or a --base raster must be specified.",
+            ));
+        }
+
+        begin_stage("Reading data");
+        if verbose {
+            println!("Reading data...")
+        };
+        let mut points: Vec<[f64; 2]> = vec![];
+        if input_file.to_lowercase().ends_with(".las") {
+            let input = LasFile::new(&input_file, "r")?;
+            let n_points = input.header.number_of_points as usize;
+            points.reserve(n_points);
+            for i in 0..n_points {
+                let p: PointData = input[i];
+                if !p.withheld() {
+                    points.push([p.x, p.y]);
+                }
+            }
+        } else {
+            let input = Shapefile::read(&input_file)?;
+            if input.header.shape_type.base_shape_type() != ShapeType::Point
+                && input.header.shape_type.base_shape_type() != ShapeType::MultiPoint
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input vector data must be of a point or multipoint base shape type.",
+                ));
+            }
+            for record_num in 0..input.num_records {
+                let record = input.get_record(record_num);
+                for p in &record.points {
+                    points.push([p.x, p.y]);
+                }
+            }
+        }
+        let num_points = points.len();
+        if num_points < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file must contain at least two points.",
+            ));
+        }
+        if k >= num_points {
+            println!(
+                "Warning: The number of neighbours is larger than the number of points; k = {} will be used.",
+                num_points - 1
+            );
+            k = num_points - 1;
+        }
+
+        let start = Instant::now();
+
+        let mut output = if cell_size <= 0f64 {
+            let base = Raster::new(&base_file, "r")?;
+            let mut output = Raster::initialize_using_file(&output_file, &base);
+            output.reinitialize_values(0f64);
+            output
+        } else {
+            let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+            let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+            for p in &points {
+                min_x = min_x.min(p[0]);
+                max_x = max_x.max(p[0]);
+                min_y = min_y.min(p[1]);
+                max_y = max_y.max(p[1]);
+            }
+            let west = min_x;
+            let north = max_y;
+            let rows = (((north - min_y) / cell_size).ceil() as usize).max(1);
+            let columns = (((max_x - west) / cell_size).ceil() as usize).max(1);
+            let mut configs = RasterConfigs {
+                ..Default::default()
+            };
+            configs.rows = rows;
+            configs.columns = columns;
+            configs.north = north;
+            configs.south = north - rows as f64 * cell_size;
+            configs.east = west + columns as f64 * cell_size;
+            configs.west = west;
+            configs.resolution_x = cell_size;
+            configs.resolution_y = cell_size;
+            configs.nodata = -32768f64;
+            configs.data_type = DataType::F32;
+            configs.photometric_interp = PhotometricInterpretation::Continuous;
+            let mut output = Raster::initialize_using_config(&output_file, &configs);
+            output.reinitialize_values(0f64);
+            output
+        };
+        output.configs.data_type = DataType::F32;
+        output.configs.palette = "spectrum.plt".to_string();
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+
+        let rows = output.configs.rows as isize;
+        let columns = output.configs.columns as isize;
+        let west = output.configs.west;
+        let north = output.configs.north;
+        let res_x = output.configs.resolution_x;
+        let res_y = output.configs.resolution_y;
+        if min_bandwidth.is_nan() {
+            min_bandwidth = res_x.max(res_y);
+        }
+        if max_bandwidth < min_bandwidth {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The maximum bandwidth must not be smaller than the minimum bandwidth.",
+            ));
+        }
+
+        // find the bandwidth of each point, i.e. the distance to its k-th nearest neighbour
+        begin_stage("Nearest neighbour search");
+        if verbose {
+            println!("Finding the k-nearest neighbour distances...")
+        };
+        let mut tree = KdTree::new_with_capacity(2, 64);
+        for (i, p) in points.iter().enumerate() {
+            tree.add(*p, i).unwrap();
+        }
+        let tree = Arc::new(tree);
+        let points = Arc::new(points);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let tree = tree.clone();
+            let points = points.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let start_index = tid * num_points / num_procs;
+                let end_index = (tid + 1) * num_points / num_procs;
+                let mut bandwidths = Vec::with_capacity(end_index - start_index);
+                for i in start_index..end_index {
+                    // the point itself is its own nearest neighbour
+                    let ret = tree
+                        .nearest(&points[i], k + 1, &squared_euclidean)
+                        .unwrap();
+                    let dist = ret.last().map(|n| n.0.sqrt()).unwrap_or(0f64);
+                    bandwidths.push(dist.max(min_bandwidth).min(max_bandwidth));
+                }
+                tx.send((start_index, bandwidths)).unwrap();
+            });
+        }
+        let mut bandwidth = vec![0f64; num_points];
+        for t in 0..num_procs {
+            let (start_index, bandwidths) = rx.recv().expect("Error receiving data from thread.");
+            bandwidth[start_index..start_index + bandwidths.len()].copy_from_slice(&bandwidths);
+            if verbose {
+                progress = (100.0_f64 * (t + 1) as f64 / num_procs as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // sum the kernels of all points at the cell centres
+        begin_stage("Kernel summation");
+        let (support, norm) = if use_gaussian {
+            // the Gaussian kernel is truncated at three bandwidths and renormalized
+            (3f64, 1f64 / (2f64 * PI * (1f64 - (-4.5f64).exp())))
+        } else {
+            (1f64, 2f64 / PI)
+        };
+        let mut density = vec![0f64; (rows * columns) as usize];
+        for i in 0..num_points {
+            let (x, y) = (points[i][0], points[i][1]);
+            let h = bandwidth[i];
+            let radius = support * h;
+            let scale = norm / (h * h);
+            let col_start = (((x - radius - west) / res_x).floor() as isize).max(0);
+            let col_end = (((x + radius - west) / res_x).floor() as isize).min(columns - 1);
+            let row_start = (((north - (y + radius)) / res_y).floor() as isize).max(0);
+            let row_end = (((north - (y - radius)) / res_y).floor() as isize).min(rows - 1);
+            for row in row_start..=row_end {
+                let cy = north - (row as f64 + 0.5) * res_y;
+                for col in col_start..=col_end {
+                    let cx = west + (col as f64 + 0.5) * res_x;
+                    let u_sqr = ((cx - x) * (cx - x) + (cy - y) * (cy - y)) / (h * h);
+                    if u_sqr <= support * support {
+                        let kernel_value = if use_gaussian {
+                            (-0.5 * u_sqr).exp()
+                        } else {
+                            1f64 - u_sqr
+                        };
+                        density[(row * columns + col) as usize] += scale * kernel_value;
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / num_points as f64) as usize;
+                if progress != old_progress {
+                    println!("Summing kernels: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        for row in 0..rows {
+            for col in 0..columns {
+                output.set_value(row, col, density[(row * columns + col) as usize]);
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        output.add_metadata_entry(format!("Number of neighbours (k): {}", k));
+        output.add_metadata_entry(format!("Kernel: {}", kernel));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            let (min_h, max_h) = bandwidth
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &h| {
+                    (lo.min(h), hi.max(h))
+                });
+            println!("Bandwidth range: {:.3} to {:.3}", min_h, max_h);
+            println!("Saving data...")
+        };
+        begin_stage("Writing data");
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
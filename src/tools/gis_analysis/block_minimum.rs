@@ -214,17 +214,11 @@ impl WhiteboxTool for BlockMinimumGridding {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -248,9 +242,7 @@ impl WhiteboxTool for BlockMinimumGridding {
         let nodata = -32768.0f64;
 
         let mut output = if !base_file.trim().is_empty() || grid_res == 0f64 {
-            if !base_file.contains(&sep) && !base_file.contains("/") {
-                base_file = format!("{}{}", working_directory, base_file);
-            }
+            base_file = resolve_path(&base_file, working_directory);
             let base = Raster::new(&base_file, "r")?;
             Raster::initialize_using_file(&output_file, &base)
         } else {
@@ -198,7 +198,6 @@ impl WhiteboxTool for Clip {
             }
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -210,15 +209,9 @@ impl WhiteboxTool for Clip {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !clip_file.contains(&sep) && !clip_file.contains("/") {
-            clip_file = format!("{}{}", working_directory, clip_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        clip_file = resolve_path(&clip_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -238,10 +231,12 @@ impl WhiteboxTool for Clip {
         let mut clip_bb: Vec<BoundingBox> = vec![];
         let mut clip_polylines: Vec<Polyline> = vec![];
         let mut is_clip_part_a_hole: Vec<bool> = vec![];
+        let mut clip_record_parts: Vec<(usize, usize)> = vec![];
         let mut first_point_in_part: usize;
         let mut last_point_in_part: usize;
         for record_num in 0..clip.num_records {
             let record = clip.get_record(record_num);
+            clip_record_parts.push((clip_bb.len(), clip_bb.len() + record.num_parts as usize));
             for part in 0..record.num_parts as usize {
                 first_point_in_part = record.parts[part] as usize;
                 last_point_in_part = if part < record.num_parts as usize - 1 {
@@ -269,6 +264,10 @@ impl WhiteboxTool for Clip {
             }
         }
 
+        // The clip file's spatial index is used to find the clip parts that may overlap a feature.
+        let clip_index = Arc::new(clip.spatial_index());
+        let clip_record_parts = Arc::new(clip_record_parts);
+
         let input = Shapefile::read(&input_file)?;
         let projection = input.projection.clone();
 
@@ -294,6 +293,8 @@ impl WhiteboxTool for Clip {
                 for tid in 0..num_procs {
                     let input = input.clone();
                     let clip_bb = clip_bb.clone();
+                    let clip_index = clip_index.clone();
+                    let clip_record_parts = clip_record_parts.clone();
                     let clip_polylines = clip_polylines.clone();
                     let is_clip_part_a_hole = is_clip_part_a_hole.clone();
                     let tx = tx.clone();
@@ -304,7 +305,8 @@ impl WhiteboxTool for Clip {
                             out = false;
                             let record = input.get_record(record_num);
                             p = record.points[0];
-                            for a in 0..clip_polylines.len() {
+                            let bb = BoundingBox::new(p.x, p.x, p.y, p.y);
+                            for a in candidate_clip_parts(bb, &clip_index, &clip_record_parts) {
                                 if clip_bb[a].is_point_in_box(p.x, p.y) {
                                     if point_in_poly(&p, &(clip_polylines[a].vertices)) {
                                         if !is_clip_part_a_hole[a] {
@@ -371,6 +373,8 @@ impl WhiteboxTool for Clip {
                     for tid in 0..num_procs {
                         let record = record.clone();
                         let clip_bb = clip_bb.clone();
+                        let clip_index = clip_index.clone();
+                        let clip_record_parts = clip_record_parts.clone();
                         let clip_polylines = clip_polylines.clone();
                         let is_clip_part_a_hole = is_clip_part_a_hole.clone();
                         let tx = tx.clone();
@@ -380,7 +384,8 @@ impl WhiteboxTool for Clip {
                             for point_num in (0..num_points).filter(|r| r % num_procs == tid) {
                                 p = record.points[point_num].clone();
                                 out = false;
-                                for a in 0..clip_polylines.len() {
+                                let bb = BoundingBox::new(p.x, p.x, p.y, p.y);
+                                for a in candidate_clip_parts(bb, &clip_index, &clip_record_parts) {
                                     if clip_bb[a].is_point_in_box(p.x, p.y) {
                                         if point_in_poly(&p, &(clip_polylines[a].vertices)) {
                                             if !is_clip_part_a_hole[a] {
@@ -493,7 +498,12 @@ impl WhiteboxTool for Clip {
 
                 // hunt for intersections in the overlapping bounding boxes
                 for record_num1 in 0..features_polylines.len() {
-                    for record_num2 in 0..clip_polylines.len() {
+                    let candidates = candidate_clip_parts(
+                        features_bb[record_num1],
+                        &clip_index,
+                        &clip_record_parts,
+                    );
+                    for record_num2 in candidates {
                         if features_bb[record_num1].overlaps(clip_bb[record_num2]) {
                             // find any intersections between the polylines
                             find_split_points_at_line_intersections(
@@ -511,7 +521,10 @@ impl WhiteboxTool for Clip {
                         if split_lines[j].len() > 1 {
                             let mut out = false;
                             let p = Point2D::midpoint(&split_lines[j][0], &split_lines[j][1]); // lies along the polyline
-                            for record_num2 in 0..clip_polylines.len() {
+                            let bb = BoundingBox::new(p.x, p.x, p.y, p.y);
+                            for record_num2 in
+                                candidate_clip_parts(bb, &clip_index, &clip_record_parts)
+                            {
                                 if clip_bb[record_num2].is_point_in_box(p.x, p.y) {
                                     if point_in_poly(&p, &(clip_polylines[record_num2].vertices)) {
                                         if !is_clip_part_a_hole[record_num2] {
@@ -591,7 +604,7 @@ impl WhiteboxTool for Clip {
                         pl.source_file = 2;
                         let plbb = pl.get_bounding_box();
                         let mut overlaps_with_clip = false;
-                        for i in 0..clip_polylines.len() {
+                        for i in candidate_clip_parts(plbb, &clip_index, &clip_record_parts) {
                             if plbb.overlaps(clip_bb[i]) {
                                 if poly_overlaps_poly(&(pl.vertices), &(clip_polylines[i].vertices))
                                 {
@@ -2600,6 +2613,21 @@ impl PartialOrd for Link {
     }
 }
 
+/// Returns the indices, in ascending order, of the parts of the clip records that the clip
+/// file's spatial index reports as potentially overlapping a bounding box.
+fn candidate_clip_parts(
+    bb: BoundingBox,
+    clip_index: &SpatialIndex,
+    clip_record_parts: &[(usize, usize)],
+) -> Vec<usize> {
+    let mut parts = vec![];
+    for record_num in clip_index.query(&bb) {
+        let (first_part, last_part) = clip_record_parts[record_num];
+        parts.extend(first_part..last_part);
+    }
+    parts
+}
+
 fn get_other_endnode(index: usize) -> usize {
     if index % 2 == 0 {
         // it's a starting node and we need the end
@@ -2,21 +2,21 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 25/04/2018
-Last Modified: 18/10/2019
+Last Modified: 16/10/2026
 License: MIT
 
 NOTES: This tool differs from the Whitebox GAT tool in that it only takes a single raster input.
 */
 
-use crate::algorithms::point_in_poly;
 use crate::raster::*;
 use crate::structures::BoundingBox;
-use crate::structures::Point2D;
 use crate::tools::*;
-use crate::vector::{ShapeType, Shapefile};
+use crate::vector::{ShapeType, Shapefile, ShapefileGeometry};
 use std::env;
+use std::f64;
 use std::io::{Error, ErrorKind};
 use std::path;
+use std::path::Path;
 
 /// This tool can be used to clip an input raster (`--input`) to the extent of a vector polygon (shapefile). The user 
 /// must specify the name of the input clip file (`--polygons`), wich must be a vector of a Polygon base shape type. 
@@ -25,10 +25,25 @@ use std::path;
 /// cells that fall outside of a polygons in the clip file will be assigned the NoData background value in the output 
 /// file. By default, the output raster will be cropped to the spatial extent of the clip file, unless the 
 /// `--maintain_dimensions` parameter is used, in which case the output grid extent will match that of the input raster. 
-/// The grid resolution of output raster is the same as the input raster.
+/// The cropped extent is snapped to the grid of the input raster, such that the output cells coincide with input
+/// cells and their values are not resampled. The grid resolution of output raster is the same as the input raster.
+///
+/// By default, a grid cell is considered to be within a polygon if the cell's centre point is inside the polygon.
+/// If the `--all_touched` parameter is specified, every cell that is touched by a polygon, including those cells
+/// that are only partially covered by the polygon's edges, will be included. This rule is useful when clipping
+/// to small or narrow polygons, which may otherwise not contain any cell centres.
+///
+/// When the `--inverse` parameter is specified, the tool operates in erase mode, i.e. the cells that are within
+/// the polygons are assigned the NoData value and all other cells retain their input values. The output raster
+/// will have the same extent as the input raster in this mode.
+///
+/// When the `--split` parameter is specified, each polygon feature in the clip file is output to a separate raster,
+/// cropped to the extent of the feature (unless `--maintain_dimensions` is used). The output file names are derived
+/// from the `--output` file name by appending an underscore and the feature number (e.g. *output_1.tif*,
+/// *output_2.tif*, etc.).
 /// 
 /// # See Also
-/// `ErasePolygonFromRaster`
+/// `ErasePolygonFromRaster`, `VectorPolygonsToRaster`
 pub struct ClipRasterToPolygon {
     name: String,
     description: String,
@@ -83,6 +98,33 @@ impl ClipRasterToPolygon {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Include all cells touched by polygons?".to_owned(),
+            flags: vec!["--all_touched".to_owned()],
+            description: "Include all cells touched by the polygons, rather than only those with centres inside the polygons?".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Erase polygons (inverse clip)?".to_owned(),
+            flags: vec!["--inverse".to_owned()],
+            description: "Erase the areas within the polygons rather than those outside of them?".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output each polygon to a separate file?".to_owned(),
+            flags: vec!["--split".to_owned()],
+            description: "Output a separate raster for each polygon feature?".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -94,7 +136,8 @@ impl ClipRasterToPolygon {
         if e.contains(".exe") {
             short_exe += ".exe";
         }
-        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=raster.tif --polygons=poly.shp -o=output.tif --maintain_dimensions", short_exe, name).replace("*", &sep);
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=raster.tif --polygons=poly.shp -o=output.tif --maintain_dimensions
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=raster.tif --polygons=poly.shp -o=output.tif --all_touched --split", short_exe, name).replace("*", &sep);
 
         ClipRasterToPolygon {
             name: name,
@@ -144,6 +187,9 @@ impl WhiteboxTool for ClipRasterToPolygon {
         let mut polygons_file = String::new();
         let mut output_file = String::new();
         let mut maintain_dimensions = false;
+        let mut all_touched = false;
+        let mut inverse = false;
+        let mut split = false;
 
         if args.len() == 0 {
             return Err(Error::new(
@@ -183,6 +229,18 @@ impl WhiteboxTool for ClipRasterToPolygon {
                 if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
                     maintain_dimensions = true;
                 }
+            } else if flag_val == "-all_touched" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    all_touched = true;
+                }
+            } else if flag_val == "-inverse" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    inverse = true;
+                }
+            } else if flag_val == "-split" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    split = true;
+                }
             }
         }
 
@@ -192,20 +250,9 @@ impl WhiteboxTool for ClipRasterToPolygon {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
-        let mut progress: usize;
-        let mut old_progress: usize = 1;
-
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !polygons_file.contains(&sep) && !polygons_file.contains("/") {
-            polygons_file = format!("{}{}", working_directory, polygons_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        polygons_file = resolve_path(&polygons_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -219,7 +266,7 @@ impl WhiteboxTool for ClipRasterToPolygon {
 
         let polygons = Shapefile::read(&polygons_file)?;
 
-        // make sure the input vector file is of points type
+        // make sure the input vector file is of polygon type
         if polygons.header.shape_type.base_shape_type() != ShapeType::Polygon {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
@@ -227,398 +274,259 @@ impl WhiteboxTool for ClipRasterToPolygon {
             ));
         }
 
-        if maintain_dimensions {
-            // Output raster has same dimensions as the input
-            let mut output = Raster::initialize_using_file(&output_file, &input);
-
-            let mut start_point_in_part: usize;
-            let mut end_point_in_part: usize;
-            let (mut row, mut col): (isize, isize);
-            let (mut x, mut y): (f64, f64);
-            let (mut starting_row, mut ending_row, mut starting_col, mut ending_col): (
-                isize,
-                isize,
-                isize,
-                isize,
-            );
-            let num_records = polygons.num_records;
-            for record_num in 0..polygons.num_records {
+        // Each output is associated with a group of polygon records.
+        let num_records = polygons.num_records;
+        let groups: Vec<Vec<usize>> = if split {
+            (0..num_records).map(|r| vec![r]).collect()
+        } else {
+            vec![(0..num_records).collect()]
+        };
+
+        let mut old_progress: usize = 1;
+        let mut elapsed_time = String::new();
+        for (group_num, group) in groups.iter().enumerate() {
+            let mut extent = BoundingBox::default();
+            for &record_num in group {
                 let record = polygons.get_record(record_num);
+                extent.expand_to(BoundingBox::new(
+                    record.x_min,
+                    record.x_max,
+                    record.y_min,
+                    record.y_max,
+                ));
+            }
 
-                let mut part_num = 1;
-                for part in 0..record.num_parts as usize {
-                    if !record.is_hole(part as i32) {
-                        // Add these cells in
-
-                        start_point_in_part = record.parts[part] as usize;
-                        end_point_in_part = if part < record.num_parts as usize - 1 {
-                            record.parts[part + 1] as usize - 1
-                        } else {
-                            record.num_points as usize - 1
-                        };
-
-                        // First, figure out the minimum and maximum row and column for the polygon part
-                        starting_row = rows;
-                        ending_row = 0;
-                        starting_col = columns;
-                        ending_col = 0;
-                        for p in start_point_in_part..end_point_in_part + 1 {
-                            row = input.get_row_from_y(record.points[p].y);
-                            col = input.get_column_from_x(record.points[p].x);
-                            if row < starting_row {
-                                starting_row = row;
-                            }
-                            if row > ending_row {
-                                ending_row = row;
-                            }
-                            if col < starting_col {
-                                starting_col = col;
-                            }
-                            if col > ending_col {
-                                ending_col = col;
-                            }
-                        }
-
-                        for r in starting_row..ending_row {
-                            y = input.get_y_from_row(r);
-                            for c in starting_col..ending_col {
-                                x = input.get_x_from_column(c);
-                                if point_in_poly(
-                                    &Point2D { x: x, y: y },
-                                    &record.points[start_point_in_part..end_point_in_part + 1],
-                                ) {
-                                    output.set_value(r, c, input.get_value(r, c));
-                                }
-                            }
-                            if verbose {
-                                progress = (100.0_f64 * r as f64
-                                    / (ending_row - starting_row) as f64)
-                                    as usize;
-                                if progress != old_progress {
-                                    println!(
-                                        "Progress (rec {} of {} part {}): {}%",
-                                        record_num + 1,
-                                        num_records,
-                                        part_num,
-                                        progress
-                                    );
-                                    old_progress = progress;
-                                }
-                            }
-                        }
-                        part_num += 1;
+            // The window of input cells covered by the output, snapped to the input grid.
+            let (start_row, end_row, start_col, end_col) = if maintain_dimensions || inverse {
+                (0, rows - 1, 0, columns - 1)
+            } else {
+                let (top, bottom, left, right) = if all_touched {
+                    (
+                        input.get_row_from_y(extent.max_y),
+                        input.get_row_from_y(extent.min_y),
+                        input.get_column_from_x(extent.min_x),
+                        input.get_column_from_x(extent.max_x),
+                    )
+                } else {
+                    // only the cells with centres inside of the extent can be within a polygon
+                    let res_x = input.configs.resolution_x;
+                    let res_y = input.configs.resolution_y;
+                    (
+                        ((input.configs.north - extent.max_y) / res_y - 0.5).ceil() as isize,
+                        ((input.configs.north - extent.min_y) / res_y - 0.5).floor() as isize,
+                        ((extent.min_x - input.configs.west) / res_x - 0.5).ceil() as isize,
+                        ((extent.max_x - input.configs.west) / res_x - 0.5).floor() as isize,
+                    )
+                };
+                (top.max(0), bottom.min(rows - 1), left.max(0), right.min(columns - 1))
+            };
+            if end_row < start_row || end_col < start_col {
+                println!(
+                    "Warning: The polygons do not overlap any grid cells of the input raster; output {} was not created.",
+                    group_num + 1
+                );
+                continue;
+            }
+            let out_rows = (end_row - start_row + 1) as usize;
+            let out_columns = (end_col - start_col + 1) as usize;
+
+            let mut configs = input.configs.clone();
+            configs.rows = out_rows;
+            configs.columns = out_columns;
+            configs.north = input.configs.north - start_row as f64 * input.configs.resolution_y;
+            configs.south = configs.north - out_rows as f64 * input.configs.resolution_y;
+            configs.west = input.configs.west + start_col as f64 * input.configs.resolution_x;
+            configs.east = configs.west + out_columns as f64 * input.configs.resolution_x;
+
+            // Rasterize the polygons within the output grid.
+            let mut mask = vec![false; out_rows * out_columns];
+            for &record_num in group {
+                let record = polygons.get_record(record_num);
+                rasterize_polygon(record, &configs, all_touched, &mut mask);
+                if verbose && !split {
+                    let progress = (100.0_f64 * (record_num + 1) as f64 / num_records as f64) as usize;
+                    if progress != old_progress {
+                        println!("Rasterizing polygons: {}%", progress);
+                        old_progress = progress;
                     }
                 }
+            }
 
-                for part in 0..record.num_parts as usize {
-                    if record.is_hole(part as i32) {
-                        // Erase these cells
-
-                        start_point_in_part = record.parts[part] as usize;
-                        end_point_in_part = if part < record.num_parts as usize - 1 {
-                            record.parts[part + 1] as usize - 1
-                        } else {
-                            record.num_points as usize - 1
-                        };
-
-                        // First, figure out the minimum and maximum row and column for the polygon part
-                        starting_row = rows;
-                        ending_row = 0;
-                        starting_col = columns;
-                        ending_col = 0;
-                        for p in start_point_in_part..end_point_in_part + 1 {
-                            row = input.get_row_from_y(record.points[p].y);
-                            col = input.get_column_from_x(record.points[p].x);
-                            if row < starting_row {
-                                starting_row = row;
-                            }
-                            if row > ending_row {
-                                ending_row = row;
-                            }
-                            if col < starting_col {
-                                starting_col = col;
-                            }
-                            if col > ending_col {
-                                ending_col = col;
-                            }
-                        }
-
-                        for r in starting_row..ending_row {
-                            y = input.get_y_from_row(r);
-                            for c in starting_col..ending_col {
-                                x = input.get_x_from_column(c);
-                                if point_in_poly(
-                                    &Point2D { x: x, y: y },
-                                    &record.points[start_point_in_part..end_point_in_part + 1],
-                                ) {
-                                    output.set_value(r, c, nodata);
-                                }
-                            }
-                            if verbose {
-                                progress = (100.0_f64 * r as f64
-                                    / (ending_row - starting_row) as f64)
-                                    as usize;
-                                if progress != old_progress {
-                                    println!(
-                                        "Progress (rec {} of {} part {}): {}%",
-                                        record_num + 1,
-                                        num_records,
-                                        part_num,
-                                        progress
-                                    );
-                                    old_progress = progress;
-                                }
-                            }
-                        }
-                        part_num += 1;
+            let out_file = if split {
+                let p = Path::new(&output_file);
+                let stem = p.file_stem().unwrap().to_str().unwrap();
+                let file_name = match p.extension() {
+                    Some(ext) => format!("{}_{}.{}", stem, group_num + 1, ext.to_str().unwrap()),
+                    None => format!("{}_{}", stem, group_num + 1),
+                };
+                p.with_file_name(file_name).to_str().unwrap().to_string()
+            } else {
+                output_file.clone()
+            };
+            let mut output = Raster::initialize_using_config(&out_file, &configs);
+            for r in 0..out_rows {
+                let mut data = vec![nodata; out_columns];
+                for c in 0..out_columns {
+                    if mask[r * out_columns + c] != inverse {
+                        data[c] = input.get_value(r as isize + start_row, c as isize + start_col);
+                    }
+                }
+                output.set_row_data(r as isize, data);
+                if verbose && !split {
+                    let progress = (100.0_f64 * r as f64 / (out_rows - 1).max(1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
                     }
                 }
             }
 
-            let elapsed_time = get_formatted_elapsed_time(start);
+            elapsed_time = get_formatted_elapsed_time(start);
             output.add_metadata_entry(format!(
                 "Created by whitebox_tools\' {} tool",
                 self.get_tool_name()
             ));
             output.add_metadata_entry(format!("Input file: {}", input_file));
+            output.add_metadata_entry(format!("Polygons file: {}", polygons_file));
             output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
 
-            if verbose {
+            if verbose && !split {
                 println!("Saving data...")
             };
             let _ = match output.write() {
                 Ok(_) => {
-                    if verbose {
+                    if verbose && !split {
                         println!("Output file written")
                     }
                 }
                 Err(e) => return Err(e),
             };
 
-            if verbose {
-                println!(
-                    "{}",
-                    &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
-                );
+            if verbose && split {
+                let progress = (100.0_f64 * (group_num + 1) as f64 / groups.len() as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
             }
-        } else {
-            // we'll need to trim the raster to the extent of the polygons.
-            let vec_bb = BoundingBox::new(
-                polygons.header.x_min,
-                polygons.header.x_max,
-                polygons.header.y_min,
-                polygons.header.y_max,
-            );
-            let mut rast_bb = BoundingBox::new(
-                input.configs.west,
-                input.configs.east,
-                input.configs.south,
-                input.configs.north,
-            );
-            rast_bb.contract_to(vec_bb);
-
-            let west: f64 = rast_bb.min_x;
-            let north: f64 = rast_bb.max_y;
-            let rows: isize =
-                (((north - rast_bb.min_y) / input.configs.resolution_y).ceil()) as isize;
-            let columns: isize =
-                (((rast_bb.max_x - west) / input.configs.resolution_x).ceil()) as isize;
-            let south: f64 = north - rows as f64 * input.configs.resolution_y;
-            let east = west + columns as f64 * input.configs.resolution_x;
-
-            let mut configs = RasterConfigs {
-                ..Default::default()
-            };
-            configs.rows = rows as usize;
-            configs.columns = columns as usize;
-            configs.north = north;
-            configs.south = south;
-            configs.east = east;
-            configs.west = west;
-            configs.resolution_x = input.configs.resolution_x;
-            configs.resolution_y = input.configs.resolution_y;
-            configs.nodata = nodata;
-            configs.data_type = input.configs.data_type;
-            configs.photometric_interp = input.configs.photometric_interp;
-            configs.palette = input.configs.palette.clone();
-
-            let mut output = Raster::initialize_using_config(&output_file, &configs);
-
-            let mut start_point_in_part: usize;
-            let mut end_point_in_part: usize;
-            let (mut row, mut col): (isize, isize);
-            let (mut row_in, mut col_in): (isize, isize);
-            let (mut x, mut y): (f64, f64);
-            let (mut starting_row, mut ending_row, mut starting_col, mut ending_col): (
-                isize,
-                isize,
-                isize,
-                isize,
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
             );
-            let num_records = polygons.num_records;
-            for record_num in 0..polygons.num_records {
-                let record = polygons.get_record(record_num);
+        }
 
-                let mut part_num = 1;
-                for part in 0..record.num_parts as usize {
-                    if !record.is_hole(part as i32) {
-                        // Add these cells in
-
-                        start_point_in_part = record.parts[part] as usize;
-                        end_point_in_part = if part < record.num_parts as usize - 1 {
-                            record.parts[part + 1] as usize - 1
-                        } else {
-                            record.num_points as usize - 1
-                        };
-
-                        // First, figure out the minimum and maximum row and column for the polygon part
-                        starting_row = rows;
-                        ending_row = 0;
-                        starting_col = columns;
-                        ending_col = 0;
-                        for p in start_point_in_part..end_point_in_part + 1 {
-                            row = output.get_row_from_y(record.points[p].y);
-                            col = output.get_column_from_x(record.points[p].x);
-                            if row < starting_row {
-                                starting_row = row;
-                            }
-                            if row > ending_row {
-                                ending_row = row;
-                            }
-                            if col < starting_col {
-                                starting_col = col;
-                            }
-                            if col > ending_col {
-                                ending_col = col;
-                            }
-                        }
-
-                        for r in starting_row..ending_row {
-                            y = output.get_y_from_row(r);
-                            for c in starting_col..ending_col {
-                                x = output.get_x_from_column(c);
-                                if point_in_poly(
-                                    &Point2D { x: x, y: y },
-                                    &record.points[start_point_in_part..end_point_in_part + 1],
-                                ) {
-                                    row_in = input.get_row_from_y(y);
-                                    col_in = input.get_column_from_x(x);
-                                    output.set_value(r, c, input.get_value(row_in, col_in));
-                                }
-                            }
-                            if verbose {
-                                progress = (100.0_f64 * r as f64
-                                    / (ending_row - starting_row) as f64)
-                                    as usize;
-                                if progress != old_progress {
-                                    println!(
-                                        "Progress (rec {} of {} part {}): {}%",
-                                        record_num + 1,
-                                        num_records,
-                                        part_num,
-                                        progress
-                                    );
-                                    old_progress = progress;
-                                }
-                            }
-                        }
-                        part_num += 1;
-                    }
-                }
+        Ok(())
+    }
+}
 
-                for part in 0..record.num_parts as usize {
-                    if record.is_hole(part as i32) {
-                        // Erase these cells
-
-                        start_point_in_part = record.parts[part] as usize;
-                        end_point_in_part = if part < record.num_parts as usize - 1 {
-                            record.parts[part + 1] as usize - 1
-                        } else {
-                            record.num_points as usize - 1
-                        };
-
-                        // First, figure out the minimum and maximum row and column for the polygon part
-                        starting_row = rows;
-                        ending_row = 0;
-                        starting_col = columns;
-                        ending_col = 0;
-                        for p in start_point_in_part..end_point_in_part + 1 {
-                            row = output.get_row_from_y(record.points[p].y);
-                            col = output.get_column_from_x(record.points[p].x);
-                            if row < starting_row {
-                                starting_row = row;
-                            }
-                            if row > ending_row {
-                                ending_row = row;
-                            }
-                            if col < starting_col {
-                                starting_col = col;
-                            }
-                            if col > ending_col {
-                                ending_col = col;
-                            }
-                        }
-
-                        for r in starting_row..ending_row {
-                            y = output.get_y_from_row(r);
-                            for c in starting_col..ending_col {
-                                x = output.get_x_from_column(c);
-                                if point_in_poly(
-                                    &Point2D { x: x, y: y },
-                                    &record.points[start_point_in_part..end_point_in_part + 1],
-                                ) {
-                                    output.set_value(r, c, nodata);
-                                }
-                            }
-                            if verbose {
-                                progress = (100.0_f64 * r as f64
-                                    / (ending_row - starting_row) as f64)
-                                    as usize;
-                                if progress != old_progress {
-                                    println!(
-                                        "Progress (rec {} of {} part {}): {}%",
-                                        record_num + 1,
-                                        num_records,
-                                        part_num,
-                                        progress
-                                    );
-                                    old_progress = progress;
-                                }
-                            }
-                        }
-                        part_num += 1;
-                    }
+/// Marks the cells of a grid, described by `configs`, that are within a polygon record.
+/// Cells are within the polygon if their centres are inside an odd number of the record's
+/// rings, which respects polygon holes. If `all_touched` is true, the cells that are
+/// crossed by the rings are also marked.
+fn rasterize_polygon(
+    record: &ShapefileGeometry,
+    configs: &RasterConfigs,
+    all_touched: bool,
+    mask: &mut [bool],
+) {
+    let rows = configs.rows as isize;
+    let columns = configs.columns as isize;
+    let res_x = configs.resolution_x;
+    let res_y = configs.resolution_y;
+    // the ring vertices, in fractional grid coordinates
+    let mut rings: Vec<Vec<(f64, f64)>> = vec![];
+    for part in 0..record.num_parts as usize {
+        let start_point_in_part = record.parts[part] as usize;
+        let end_point_in_part = if part < record.num_parts as usize - 1 {
+            record.parts[part + 1] as usize - 1
+        } else {
+            record.num_points as usize - 1
+        };
+        rings.push(
+            record.points[start_point_in_part..=end_point_in_part]
+                .iter()
+                .map(|p| ((p.x - configs.west) / res_x, (configs.north - p.y) / res_y))
+                .collect(),
+        );
+    }
+
+    // Scanline fill using the cell centres.
+    let min_row = ((configs.north - record.y_max) / res_y).floor().max(0f64) as isize;
+    let max_row = (((configs.north - record.y_min) / res_y).ceil() as isize).min(rows - 1);
+    let mut crossings = vec![];
+    for r in min_row..=max_row {
+        let y = r as f64 + 0.5;
+        crossings.clear();
+        for ring in &rings {
+            for i in 0..ring.len() {
+                let (x1, y1) = ring[i];
+                let (x2, y2) = ring[(i + 1) % ring.len()];
+                if (y1 <= y && y2 > y) || (y2 <= y && y1 > y) {
+                    crossings.push(x1 + (y - y1) / (y2 - y1) * (x2 - x1));
                 }
             }
+        }
+        crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for pair in crossings.chunks(2) {
+            if pair.len() < 2 {
+                break;
+            }
+            // the columns with centres between the two crossings
+            let c1 = ((pair[0] - 0.5).ceil() as isize).max(0);
+            let c2 = ((pair[1] - 0.5).floor() as isize).min(columns - 1);
+            for c in c1..=c2 {
+                mask[(r * columns + c) as usize] = true;
+            }
+        }
+    }
 
-            let elapsed_time = get_formatted_elapsed_time(start);
-            output.add_metadata_entry(format!(
-                "Created by whitebox_tools\' {} tool",
-                self.get_tool_name()
-            ));
-            output.add_metadata_entry(format!("Input file: {}", input_file));
-            output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
-
-            if verbose {
-                println!("Saving data...")
-            };
-            let _ = match output.write() {
-                Ok(_) => {
-                    if verbose {
-                        println!("Output file written")
+    if all_touched {
+        // Mark every cell crossed by each ring segment by walking the grid along the segment.
+        for ring in &rings {
+            for i in 0..ring.len() {
+                let (x1, y1) = ring[i];
+                let (x2, y2) = ring[(i + 1) % ring.len()];
+                let (mut c, mut r) = (x1.floor() as isize, y1.floor() as isize);
+                let (end_c, end_r) = (x2.floor() as isize, y2.floor() as isize);
+                let (dx, dy) = (x2 - x1, y2 - y1);
+                let step_c = if dx > 0f64 { 1 } else { -1 };
+                let step_r = if dy > 0f64 { 1 } else { -1 };
+                let t_delta_x = if dx != 0f64 { (1f64 / dx).abs() } else { f64::INFINITY };
+                let t_delta_y = if dy != 0f64 { (1f64 / dy).abs() } else { f64::INFINITY };
+                let mut t_max_x = if dx > 0f64 {
+                    (c as f64 + 1f64 - x1) / dx
+                } else if dx < 0f64 {
+                    (c as f64 - x1) / dx
+                } else {
+                    f64::INFINITY
+                };
+                let mut t_max_y = if dy > 0f64 {
+                    (r as f64 + 1f64 - y1) / dy
+                } else if dy < 0f64 {
+                    (r as f64 - y1) / dy
+                } else {
+                    f64::INFINITY
+                };
+                loop {
+                    if r >= 0 && r < rows && c >= 0 && c < columns {
+                        mask[(r * columns + c) as usize] = true;
+                    }
+                    if (c == end_c && r == end_r) || (t_max_x > 1f64 && t_max_y > 1f64) {
+                        break;
+                    }
+                    if t_max_x < t_max_y {
+                        t_max_x += t_delta_x;
+                        c += step_c;
+                    } else {
+                        t_max_y += t_delta_y;
+                        r += step_r;
                     }
                 }
-                Err(e) => return Err(e),
-            };
-
-            if verbose {
-                println!(
-                    "{}",
-                    &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
-                );
             }
         }
-
-        Ok(())
     }
 }
+
@@ -213,23 +213,13 @@ impl WhiteboxTool for CostDistance {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !source_file.contains(&sep) && !source_file.contains("/") {
-            source_file = format!("{}{}", working_directory, source_file);
-        }
-        if !cost_file.contains(&sep) && !cost_file.contains("/") {
-            cost_file = format!("{}{}", working_directory, cost_file);
-        }
-        if !accum_file.contains(&sep) && !accum_file.contains("/") {
-            accum_file = format!("{}{}", working_directory, accum_file);
-        }
-        if !backlink_file.contains(&sep) && !backlink_file.contains("/") {
-            backlink_file = format!("{}{}", working_directory, backlink_file);
-        }
+        source_file = resolve_path(&source_file, working_directory);
+        cost_file = resolve_path(&cost_file, working_directory);
+        accum_file = resolve_path(&accum_file, working_directory);
+        backlink_file = resolve_path(&backlink_file, working_directory);
 
         if verbose {
             println!("Reading source data...")
@@ -194,20 +194,12 @@ impl WhiteboxTool for CostPathway {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !destination_file.contains(&sep) && !destination_file.contains("/") {
-            destination_file = format!("{}{}", working_directory, destination_file);
-        }
-        if !backlink_file.contains(&sep) && !backlink_file.contains("/") {
-            backlink_file = format!("{}{}", working_directory, backlink_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        destination_file = resolve_path(&destination_file, working_directory);
+        backlink_file = resolve_path(&backlink_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading destination data...")
@@ -170,14 +170,10 @@ impl WhiteboxTool for CountIf {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let mut cmd = input_files.split(";");
         let mut vec = cmd.collect::<Vec<&str>>();
@@ -216,9 +212,7 @@ impl WhiteboxTool for CountIf {
                 };
 
                 let mut input_file = value.trim().to_owned();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
                 let input = Raster::new(&input_file, "r")?;
                 in_nodata = input.configs.nodata;
                 if !read_first_file {
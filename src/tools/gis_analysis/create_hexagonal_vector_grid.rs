@@ -206,7 +206,6 @@ impl WhiteboxTool for CreateHexagonalVectorGrid {
             }
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -225,13 +224,9 @@ impl WhiteboxTool for CreateHexagonalVectorGrid {
             ));
         }
 
-        if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         // Get the spatial extent
         let (extent, proj_info) = if input_file.to_lowercase().ends_with(".shp") {
@@ -207,14 +207,8 @@ impl WhiteboxTool for CreatePlane {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
-        if !base_file.contains(&sep) && !base_file.contains("/") {
-            base_file = format!("{}{}", working_directory, base_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        base_file = resolve_path(&base_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         let base = Raster::new(&base_file, "r")?;
 
@@ -0,0 +1,940 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::{point_in_poly, polygon_area};
+use crate::lidar::*;
+use crate::raster::*;
+use crate::structures::{BoundingBox, DistanceMetric, FixedRadiusSearch2D, Point2D};
+use crate::tools::*;
+use crate::vector::ShapefileGeometry;
+use crate::vector::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool creates a rectangular (fishnet) or hexagonal tessellation of polygons covering the
+/// extent of a base file (`--base`), which may be any supported raster format, a shapefile, or a
+/// LAS file. The cell shape is specified using the `--shape` parameter, with options of
+/// 'rectangle' and 'hexagon'. Rectangular cells are defined by their width and height (`--width`
+/// and `--height`) and the grid origin (`--xorig` and `--yorig`), as with the
+/// `CreateRectangularVectorGrid` tool, and cells along the edges of the grid are clipped to the
+/// extent of the base file. Alternatively, when the base file is a raster, the `--match_raster`
+/// flag creates one rectangular polygon for each cell of the raster grid, in which case the `ROW`
+/// and `COLUMN` attributes of the polygons are the raster's row and column numbers. Hexagonal cells
+/// are defined by their width (`--width`), i.e. the distance between opposing sides, and their
+/// orientation (`--orientation`), as with the `CreateHexagonalVectorGrid` tool.
+///
+/// Each polygon of the output is assigned an `AREA` attribute. The tessellation may optionally be
+/// used to summarize point and raster data for density mapping:
+///
+/// - If an input points file (`--points`), either a point or multipoint shapefile or a LAS file,
+///   is specified, the number of points within each cell is stored in a `COUNT` attribute, and the
+///   number of points per unit area in a `DENSITY` attribute. Withheld LiDAR points are excluded.
+/// - If an input raster (`--raster`) is specified, the raster cells whose centres lie within each
+///   polygon are summarized in the `N`, `MIN`, `MAX`, `MEAN`, and `STDEV` attributes. NoData
+///   cells are excluded, and polygons that do not contain any valid raster cells are assigned
+///   values of zero.
+///
+/// When either summary is calculated, the `--drop_empty` flag may be used to omit those cells that
+/// contain neither points nor valid raster cells from the output.
+///
+/// # See Also
+/// `CreateRectangularVectorGrid`, `CreateHexagonalVectorGrid`, `VectorHexBinning`, `LidarHexBinning`
+pub struct CreateVectorTessellation {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl CreateVectorTessellation {
+    pub fn new() -> CreateVectorTessellation {
+        // public constructor
+        let name = "CreateVectorTessellation".to_string();
+        let toolbox = "GIS Analysis".to_string();
+        let description =
+            "Creates a rectangular or hexagonal vector tessellation, optionally summarizing points and raster values within each cell."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Base File".to_owned(),
+            flags: vec!["-i".to_owned(), "--base".to_owned(), "--input".to_owned()],
+            description: "Input base file, defining the extent of the tessellation.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::RasterAndVector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Polygon File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector polygon file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Cell Shape".to_owned(),
+            flags: vec!["--shape".to_owned()],
+            description: "Cell shape; options are 'rectangle' and 'hexagon'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "rectangle".to_owned(),
+                "hexagon".to_owned(),
+            ]),
+            default_value: Some("rectangle".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Cell Width".to_owned(),
+            flags: vec!["--width".to_owned()],
+            description: "The cell width; for hexagons, the distance between opposing sides.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Cell Height".to_owned(),
+            flags: vec!["--height".to_owned()],
+            description: "The rectangular cell height; defaults to the cell width.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid origin x-coordinate".to_owned(),
+            flags: vec!["--xorig".to_owned()],
+            description: "The rectangular grid origin x-coordinate.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid origin y-coordinate".to_owned(),
+            flags: vec!["--yorig".to_owned()],
+            description: "The rectangular grid origin y-coordinate.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Hexagon Orientation".to_owned(),
+            flags: vec!["--orientation".to_owned()],
+            description: "Hexagon grid orientation ('horizontal' or 'vertical').".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "horizontal".to_owned(),
+                "vertical".to_owned(),
+            ]),
+            default_value: Some("horizontal".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Match the base raster's grid?".to_owned(),
+            flags: vec!["--match_raster".to_owned()],
+            description: "Create one rectangular polygon for each cell of the base raster.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Points File (optional)".to_owned(),
+            flags: vec!["--points".to_owned()],
+            description: "Optional input points file (vector or LiDAR) to count within each cell.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Raster File (optional)".to_owned(),
+            flags: vec!["--raster".to_owned()],
+            description: "Optional input raster file to summarize within each cell.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Drop empty cells?".to_owned(),
+            flags: vec!["--drop_empty".to_owned()],
+            description: "Omit cells that contain neither points nor valid raster cells.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.shp -o=outfile.shp --shape=hexagon --width=100.0 --orientation=vertical --points=wells.shp --drop_empty
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=dem.tif -o=outfile.shp --shape=rectangle --width=500.0 --height=500.0 --raster=dem.tif",
+            short_exe, name
+        ).replace("*", &sep);
+
+        CreateVectorTessellation {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for CreateVectorTessellation {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut shape = "rectangle".to_string();
+        let mut width = 0f64;
+        let mut height = 0f64;
+        let mut xorig = 0f64;
+        let mut yorig = 0f64;
+        let mut orientation = "h".to_string();
+        let mut match_raster = false;
+        let mut points_file = String::new();
+        let mut raster_file = String::new();
+        let mut drop_empty = false;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-base" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-shape" {
+                shape = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-width" {
+                width = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-height" {
+                height = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-xorig" {
+                xorig = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-yorig" {
+                yorig = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-orientation" {
+                orientation = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+                orientation = if orientation.contains("v") {
+                    "v".to_string()
+                } else {
+                    "h".to_string()
+                };
+            } else if flag_val == "-match_raster" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    match_raster = true;
+                }
+            } else if flag_val == "-points" {
+                points_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-raster" {
+                raster_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-drop_empty" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    drop_empty = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        let start = Instant::now();
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        points_file = resolve_path(&points_file, working_directory);
+        raster_file = resolve_path(&raster_file, working_directory);
+
+        let is_hexagon = shape.contains("hex");
+        if is_hexagon && match_raster {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The --match_raster flag can only be used with rectangular cells.",
+            ));
+        }
+        if !match_raster {
+            if width <= 0f64 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The cell width must be greater than zero.",
+                ));
+            }
+            if height <= 0f64 {
+                height = width;
+            }
+        }
+
+        // Get the spatial extent
+        let mut base_raster: Option<Raster> = None;
+        let (extent, proj_info) = if input_file.to_lowercase().ends_with(".shp") {
+            let input = Shapefile::read(&input_file)?;
+            (
+                BoundingBox::new(
+                    input.header.x_min,
+                    input.header.x_max,
+                    input.header.y_min,
+                    input.header.y_max,
+                ),
+                input.projection,
+            )
+        } else if input_file.to_lowercase().ends_with(".las") {
+            let mut input = LasFile::new(&input_file, "r")?;
+            (
+                BoundingBox::new(
+                    input.header.min_x,
+                    input.header.max_x,
+                    input.header.min_y,
+                    input.header.max_y,
+                ),
+                input.get_wkt(),
+            )
+        } else {
+            // must be a raster
+            let input = Raster::new(&input_file, "r")?;
+            let ret = (
+                BoundingBox::new(
+                    input.configs.west,
+                    input.configs.east,
+                    input.configs.south,
+                    input.configs.north,
+                ),
+                input.configs.coordinate_ref_system_wkt.clone(),
+            );
+            base_raster = Some(input);
+            ret
+        };
+        if match_raster && base_raster.is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The --match_raster flag requires a raster base file.",
+            ));
+        }
+
+        if verbose {
+            println!("Creating the tessellation...");
+        }
+        let tessellation = if match_raster {
+            Tessellation::from_raster(base_raster.as_ref().unwrap())?
+        } else if is_hexagon {
+            Tessellation::hexagonal(&extent, width, &orientation)?
+        } else {
+            Tessellation::rectangular(&extent, width, height, xorig, yorig)?
+        };
+        let num_cells = tessellation.cells.len();
+
+        // count the points within each cell
+        let count_points = !points_file.is_empty();
+        let mut count = vec![0i32; num_cells];
+        if count_points {
+            if verbose {
+                println!("Counting points...");
+            }
+            if points_file.to_lowercase().ends_with(".las") {
+                let input = LasFile::new(&points_file, "r")?;
+                let n_points = input.header.number_of_points as usize;
+                for i in 0..n_points {
+                    let p: PointData = input[i];
+                    if !p.withheld() {
+                        if let Some(cell) = tessellation.locate(p.x, p.y) {
+                            count[cell] += 1;
+                        }
+                    }
+                    if verbose {
+                        progress = (100.0_f64 * (i + 1) as f64 / n_points as f64) as usize;
+                        if progress != old_progress {
+                            println!("Counting points: {}%", progress);
+                            old_progress = progress;
+                        }
+                    }
+                }
+            } else {
+                let input = Shapefile::read(&points_file)?;
+                if input.header.shape_type.base_shape_type() != ShapeType::Point
+                    && input.header.shape_type.base_shape_type() != ShapeType::MultiPoint
+                {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "The input points file must be of a point or multipoint base shape type.",
+                    ));
+                }
+                for record_num in 0..input.num_records {
+                    let record = input.get_record(record_num);
+                    for p in &record.points {
+                        if let Some(cell) = tessellation.locate(p.x, p.y) {
+                            count[cell] += 1;
+                        }
+                    }
+                    if verbose {
+                        progress =
+                            (100.0_f64 * (record_num + 1) as f64 / input.num_records as f64) as usize;
+                        if progress != old_progress {
+                            println!("Counting points: {}%", progress);
+                            old_progress = progress;
+                        }
+                    }
+                }
+            }
+        }
+
+        // summarize the raster cells within each cell
+        let summarize_raster = !raster_file.is_empty();
+        let mut stats = vec![RunningStats::default(); num_cells];
+        if summarize_raster {
+            if verbose {
+                println!("Summarizing raster values...");
+            }
+            let raster = match base_raster {
+                Some(r) if raster_file == input_file => r,
+                _ => Raster::new(&raster_file, "r")?,
+            };
+            let rows = raster.configs.rows as isize;
+            let columns = raster.configs.columns as isize;
+            let nodata = raster.configs.nodata;
+            for row in 0..rows {
+                let y = raster.get_y_from_row(row);
+                for col in 0..columns {
+                    let z = raster.get_value(row, col);
+                    if z != nodata {
+                        if let Some(cell) = tessellation.locate(raster.get_x_from_column(col), y)
+                        {
+                            stats[cell].add(z);
+                        }
+                    }
+                }
+                if verbose {
+                    progress = (100.0_f64 * (row + 1) as f64 / rows as f64) as usize;
+                    if progress != old_progress {
+                        println!("Summarizing raster values: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        // create output file
+        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+
+        // set the projection information
+        if !proj_info.is_empty() && proj_info.to_lowercase() != "not specified" {
+            output.projection = proj_info;
+        }
+
+        // add the attributes
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("ROW", FieldDataType::Int, 7u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("COLUMN", FieldDataType::Int, 7u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("AREA", FieldDataType::Real, 12u8, 4u8));
+        if count_points {
+            output
+                .attributes
+                .add_field(&AttributeField::new("COUNT", FieldDataType::Int, 9u8, 0u8));
+            output
+                .attributes
+                .add_field(&AttributeField::new("DENSITY", FieldDataType::Real, 12u8, 6u8));
+        }
+        if summarize_raster {
+            output
+                .attributes
+                .add_field(&AttributeField::new("N", FieldDataType::Int, 9u8, 0u8));
+            output
+                .attributes
+                .add_field(&AttributeField::new("MIN", FieldDataType::Real, 12u8, 4u8));
+            output
+                .attributes
+                .add_field(&AttributeField::new("MAX", FieldDataType::Real, 12u8, 4u8));
+            output
+                .attributes
+                .add_field(&AttributeField::new("MEAN", FieldDataType::Real, 12u8, 4u8));
+            output
+                .attributes
+                .add_field(&AttributeField::new("STDEV", FieldDataType::Real, 12u8, 4u8));
+        }
+
+        let mut rec_num = 1i32;
+        for (i, cell) in tessellation.cells.iter().enumerate() {
+            if drop_empty && (count_points || summarize_raster) && count[i] == 0 && stats[i].n == 0
+            {
+                continue;
+            }
+            let area = polygon_area(&cell.points);
+            let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+            sfg.add_part(&cell.points);
+            output.add_record(sfg);
+
+            let mut atts = vec![
+                FieldData::Int(rec_num),
+                FieldData::Int(cell.row),
+                FieldData::Int(cell.col),
+                FieldData::Real(area),
+            ];
+            if count_points {
+                atts.push(FieldData::Int(count[i]));
+                atts.push(FieldData::Real(if area > 0f64 {
+                    count[i] as f64 / area
+                } else {
+                    0f64
+                }));
+            }
+            if summarize_raster {
+                let s = &stats[i];
+                atts.push(FieldData::Int(s.n as i32));
+                if s.n > 0 {
+                    let mean = s.sum / s.n as f64;
+                    atts.push(FieldData::Real(s.min));
+                    atts.push(FieldData::Real(s.max));
+                    atts.push(FieldData::Real(mean));
+                    atts.push(FieldData::Real(
+                        (s.sum_sqr / s.n as f64 - mean * mean).max(0f64).sqrt(),
+                    ));
+                } else {
+                    for _ in 0..4 {
+                        atts.push(FieldData::Real(0f64));
+                    }
+                }
+            }
+            output.attributes.add_record(atts, false);
+            rec_num += 1i32;
+
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / num_cells as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("{}", &format!("Elapsed Time: {}", elapsed_time));
+        }
+
+        Ok(())
+    }
+}
+
+/// The maximum number of polygons in an output tessellation.
+const MAX_CELLS: usize = 5_000_000;
+
+struct TessellationCell {
+    row: i32,
+    col: i32,
+    points: Vec<Point2D>,
+}
+
+/// Describes how a coordinate is located within the cells of a tessellation.
+enum CellLocator {
+    /// A rectangular grid, clipped to an extent; cells are stored in row-major order.
+    Rectangular {
+        extent: BoundingBox,
+        xorig: f64,
+        yorig: f64,
+        width: f64,
+        height: f64,
+        start_row: i32,
+        start_col: i32,
+        rows: i32,
+        columns: i32,
+    },
+    /// Hexagons are located by their nearest centre and a point-in-polygon test.
+    Hexagonal(FixedRadiusSearch2D<usize>),
+}
+
+struct Tessellation {
+    cells: Vec<TessellationCell>,
+    locator: CellLocator,
+}
+
+impl Tessellation {
+    fn rectangular(
+        extent: &BoundingBox,
+        width: f64,
+        height: f64,
+        xorig: f64,
+        yorig: f64,
+    ) -> Result<Tessellation, Error> {
+        let start_col = ((extent.min_x - xorig) / width).floor() as i32;
+        let end_col = ((extent.max_x - xorig) / width).ceil() as i32;
+        let start_row = ((extent.min_y - yorig) / height).floor() as i32;
+        let end_row = ((extent.max_y - yorig) / height).ceil() as i32;
+        let rows = (end_row - start_row).max(1);
+        let columns = (end_col - start_col).max(1);
+        if rows as usize * columns as usize > MAX_CELLS {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "This operation would produce a vector file with too many polygons. Perhaps choose a larger cell size.",
+            ));
+        }
+        let clip = |x: f64, y: f64| {
+            Point2D::new(
+                x.max(extent.min_x).min(extent.max_x),
+                y.max(extent.min_y).min(extent.max_y),
+            )
+        };
+        let mut cells = Vec::with_capacity(rows as usize * columns as usize);
+        for row in start_row..start_row + rows {
+            for col in start_col..start_col + columns {
+                let (x1, x2) = (xorig + col as f64 * width, xorig + (col + 1) as f64 * width);
+                let (y1, y2) = (yorig + row as f64 * height, yorig + (row + 1) as f64 * height);
+                // clockwise vertex order
+                let points = vec![clip(x1, y1), clip(x1, y2), clip(x2, y2), clip(x2, y1), clip(x1, y1)];
+                cells.push(TessellationCell { row, col, points });
+            }
+        }
+        Ok(Tessellation {
+            cells,
+            locator: CellLocator::Rectangular {
+                extent: extent.clone(),
+                xorig,
+                yorig,
+                width,
+                height,
+                start_row,
+                start_col,
+                rows,
+                columns,
+            },
+        })
+    }
+
+    /// Creates one cell for each grid cell of a raster. Cells are stored from the bottom row of
+    /// the raster upwards, for consistency with the rectangular locator, but retain the raster's
+    /// own row numbering.
+    fn from_raster(raster: &Raster) -> Result<Tessellation, Error> {
+        let rows = raster.configs.rows as i32;
+        let columns = raster.configs.columns as i32;
+        if rows as usize * columns as usize > MAX_CELLS {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "This operation would produce a vector file with too many polygons. Perhaps choose a coarser raster.",
+            ));
+        }
+        let (west, north) = (raster.configs.west, raster.configs.north);
+        let (res_x, res_y) = (raster.configs.resolution_x, raster.configs.resolution_y);
+        let mut cells = Vec::with_capacity(rows as usize * columns as usize);
+        for row in (0..rows).rev() {
+            for col in 0..columns {
+                let (x1, x2) = (west + col as f64 * res_x, west + (col + 1) as f64 * res_x);
+                let (y1, y2) = (north - (row + 1) as f64 * res_y, north - row as f64 * res_y);
+                let points = vec![
+                    Point2D::new(x1, y1),
+                    Point2D::new(x1, y2),
+                    Point2D::new(x2, y2),
+                    Point2D::new(x2, y1),
+                    Point2D::new(x1, y1),
+                ];
+                cells.push(TessellationCell { row, col, points });
+            }
+        }
+        Ok(Tessellation {
+            cells,
+            locator: CellLocator::Rectangular {
+                extent: BoundingBox::new(
+                    raster.configs.west,
+                    raster.configs.east,
+                    raster.configs.south,
+                    raster.configs.north,
+                ),
+                xorig: west,
+                yorig: raster.configs.south,
+                width: res_x,
+                height: res_y,
+                start_row: 0,
+                start_col: 0,
+                rows,
+                columns,
+            },
+        })
+    }
+
+    fn hexagonal(extent: &BoundingBox, width: f64, orientation: &str) -> Result<Tessellation, Error> {
+        let sixty_degrees = f64::consts::PI / 6f64;
+        let half_width = 0.5 * width;
+        let size = half_width / sixty_degrees.cos();
+        let height = size * 2f64;
+        let three_quarter_height = 0.75 * height;
+        let mut centres: Vec<(i32, i32, f64, f64)> = vec![];
+        if orientation == "h" {
+            // horizontal orientation
+            let center_x_0 = extent.min_x + half_width;
+            let center_y_0 = extent.max_y - 0.25 * height;
+            let rows = (extent.get_height() / three_quarter_height).ceil().max(1f64) as usize;
+            for row in 0..rows {
+                let center_y = center_y_0 - row as f64 * three_quarter_height;
+                let columns = ((extent.get_width() + half_width * (row as f64 % 2f64)) / width)
+                    .ceil()
+                    .max(1f64) as usize;
+                if rows * columns > MAX_CELLS {
+                    break;
+                }
+                for col in 0..columns {
+                    let center_x =
+                        (center_x_0 - half_width * (row as f64 % 2f64)) + col as f64 * width;
+                    centres.push((row as i32, col as i32, center_x, center_y));
+                }
+            }
+        } else {
+            let center_x_0 = extent.min_x + 0.5 * size;
+            let center_y_0 = extent.max_y - half_width;
+            let columns = (extent.get_width() / three_quarter_height).ceil().max(1f64) as usize;
+            for col in 0..columns {
+                let rows = ((extent.get_height() + ((col as f64 % 2f64) * half_width)) / width)
+                    .ceil()
+                    .max(1f64) as usize;
+                if rows * columns > MAX_CELLS {
+                    break;
+                }
+                for row in 0..rows {
+                    let center_x = center_x_0 + col as f64 * three_quarter_height;
+                    let center_y =
+                        center_y_0 - row as f64 * width + ((col as f64 % 2f64) * half_width);
+                    centres.push((row as i32, col as i32, center_x, center_y));
+                }
+            }
+        }
+        if centres.len() > MAX_CELLS {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "This operation would produce a vector file with too many polygons. Perhaps choose a larger hexagon width.",
+            ));
+        }
+
+        let mut frs: FixedRadiusSearch2D<usize> =
+            FixedRadiusSearch2D::new(width * 2f64, DistanceMetric::SquaredEuclidean);
+        let angle_offset = if orientation == "h" { 0f64 } else { -sixty_degrees };
+        let mut cells = Vec::with_capacity(centres.len());
+        for (i, &(row, col, center_x, center_y)) in centres.iter().enumerate() {
+            let mut points: Vec<Point2D> = Vec::with_capacity(7);
+            for v in (1..=6).rev() {
+                let angle = 2f64 * sixty_degrees * (v as f64 + 0.5) + angle_offset;
+                points.push(Point2D::new(
+                    center_x + size * angle.cos(),
+                    center_y + size * angle.sin(),
+                ));
+            }
+            // close the polygon exactly, as required by the point-in-polygon test
+            points.push(points[0]);
+            frs.insert(center_x, center_y, i);
+            cells.push(TessellationCell { row, col, points });
+        }
+        Ok(Tessellation {
+            cells,
+            locator: CellLocator::Hexagonal(frs),
+        })
+    }
+
+    /// Returns the index of the cell containing the coordinate, if any.
+    fn locate(&self, x: f64, y: f64) -> Option<usize> {
+        match &self.locator {
+            CellLocator::Rectangular {
+                extent,
+                xorig,
+                yorig,
+                width,
+                height,
+                start_row,
+                start_col,
+                rows,
+                columns,
+            } => {
+                if x < extent.min_x || x > extent.max_x || y < extent.min_y || y > extent.max_y {
+                    return None;
+                }
+                // coordinates on the extent's upper edges belong to the last row and column
+                let row = (((y - yorig) / height).floor() as i32 - start_row).min(rows - 1);
+                let col = (((x - xorig) / width).floor() as i32 - start_col).min(columns - 1);
+                if row < 0 || col < 0 {
+                    return None;
+                }
+                Some((row * columns + col) as usize)
+            }
+            CellLocator::Hexagonal(frs) => {
+                let ret = frs.knn_search(x, y, 1);
+                if ret.is_empty() {
+                    return None;
+                }
+                // the nearest centre identifies the hexagon, except beyond the tessellation's edge
+                let cell = ret[0].0;
+                if point_in_poly(&Point2D::new(x, y), &self.cells[cell].points) {
+                    Some(cell)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct RunningStats {
+    n: usize,
+    min: f64,
+    max: f64,
+    sum: f64,
+    sum_sqr: f64,
+}
+
+impl Default for RunningStats {
+    fn default() -> RunningStats {
+        RunningStats {
+            n: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0f64,
+            sum_sqr: 0f64,
+        }
+    }
+}
+
+impl RunningStats {
+    fn add(&mut self, z: f64) {
+        self.n += 1;
+        self.min = self.min.min(z);
+        self.max = self.max.max(z);
+        self.sum += z;
+        self.sum_sqr += z * z;
+    }
+}
@@ -0,0 +1,548 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::point_in_poly;
+use crate::raster::*;
+use crate::structures::Point2D;
+use crate::tools::*;
+use crate::vector::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool can be used to calculate the volumes of cut and fill between an existing surface and a
+/// second surface. The existing surface is a digital elevation model (DEM) specified by `--dem`. The
+/// second surface (`--design`) may be either another DEM, e.g. a DEM of the same site surveyed at a
+/// later date or a design surface for a proposed earthwork, or a constant elevation value, in which
+/// case the volumes are calculated relative to a horizontal plane (e.g. a proposed building pad).
+/// Inclined planes can be modelled by creating a design surface with the `CreatePlane` tool. Grid
+/// cells where the design surface is lower than the existing surface are *cut* (material removed),
+/// while cells where it is higher are *fill* (material added). Differences with magnitudes less than
+/// or equal to the optional threshold (`--threshold`) are considered unchanged.
+///
+/// The output raster (`--output`) contains the elevation difference between the two surfaces
+/// (design minus existing), such that fill depths are positive and cut depths are negative. A
+/// summary of the total cut and fill areas and volumes, and the net volume (fill minus cut), is also
+/// reported.
+///
+/// Optionally, the user may specify a polygon vector file (`--polygons`), e.g. of the boundaries
+/// of construction sites, stockpiles, or survey units, within which the volumes are calculated
+/// individually. In this case, the output polygon file (`--out_polygons`) contains all of the
+/// attributes of the input polygons and the following additional fields:
+///
+/// | Field      | Description                                    |
+/// |------------|------------------------------------------------|
+/// | CUT_AREA   | The area of cut                                |
+/// | CUT_VOL    | The volume of cut                              |
+/// | FILL_AREA  | The area of fill                               |
+/// | FILL_VOL   | The volume of fill                             |
+/// | NET_VOL    | The net volume, i.e. fill minus cut            |
+///
+/// Grid cells are considered to be within a polygon if their centres are within the polygon,
+/// excluding any holes. Areas and volumes are measured in map units or, for DEMs in geographic
+/// coordinates, in square metres and elevation units multiplied by square metres respectively. The
+/// two input rasters must share the same grid, and cells that are NoData in either surface are
+/// excluded from all calculations.
+///
+/// # See Also
+/// `CreatePlane`, `ConditioningAudit`, `Subtract`
+pub struct CutFill {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl CutFill {
+    pub fn new() -> CutFill {
+        // public constructor
+        let name = "CutFill".to_string();
+        let toolbox = "GIS Analysis".to_string();
+        let description =
+            "Calculates the cut and fill volumes between a DEM and a second surface or constant elevation."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Existing DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster DEM file of the existing surface.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Design Surface File Or Constant Value".to_owned(),
+            flags: vec!["--design".to_owned()],
+            description: "Input raster file of the second (e.g. design or post-construction) surface, or a constant elevation value.".to_owned(),
+            parameter_type: ParameterType::ExistingFileOrFloat(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file of cut (negative) and fill (positive) depths.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Boundary Polygons File (optional)".to_owned(),
+            flags: vec!["--polygons".to_owned()],
+            description: "Optional input vector polygon file, within which volumes are reported.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Polygons File (optional)".to_owned(),
+            flags: vec!["--out_polygons".to_owned()],
+            description: "Output vector polygon file of per-polygon cut and fill volumes.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Change Threshold".to_owned(),
+            flags: vec!["--threshold".to_owned()],
+            description: "Minimum absolute elevation difference for a cell to be considered cut or fill.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem='pre.tif' --design='post.tif' -o='cut_fill.tif'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem='dem.tif' --design=102.5 -o='cut_fill.tif' --polygons='sites.shp' --out_polygons='site_volumes.shp' --threshold=0.05", short_exe, name).replace("*", &sep);
+
+        CutFill {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for CutFill {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            (
+                "--out_polygons".to_string(),
+                ParameterConstraint::RequiredIf("--polygons".to_string()),
+            ),
+            (
+                "--polygons".to_string(),
+                ParameterConstraint::RequiredIf("--out_polygons".to_string()),
+            ),
+            ("--threshold".to_string(), ParameterConstraint::Min(0f64)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut design = String::new();
+        let mut output_file = String::new();
+        let mut polygons_file = String::new();
+        let mut out_polygons_file = String::new();
+        let mut threshold = 0f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-design" {
+                design = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-polygons" {
+                polygons_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_polygons" {
+                out_polygons_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-threshold" {
+                threshold = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(&dem_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        polygons_file = resolve_path(&polygons_file, working_directory);
+        out_polygons_file = resolve_path(&out_polygons_file, working_directory);
+
+        // Is the design surface a constant elevation?
+        let design_constant = design.parse::<f64>().ok();
+        if design_constant.is_none() {
+            design = resolve_path(&design, working_directory);
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let dem = Raster::new(&dem_file, "r")?;
+        let design_surface = match design_constant {
+            Some(_) => None,
+            None => Some(Raster::new(&design, "r")?),
+        };
+
+        let start = Instant::now();
+
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+
+        if let Some(ref surface) = design_surface {
+            if surface.configs.rows != dem.configs.rows
+                || surface.configs.columns != dem.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+        }
+
+        // calculate the cut and fill depths
+        let mut output = Raster::initialize_using_file(&output_file, &dem);
+        output.configs.data_type = DataType::F32;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        output.configs.palette = "blueyellow.plt".to_string();
+        for row in 0..rows {
+            let mut data = vec![nodata; columns as usize];
+            for col in 0..columns {
+                let z = dem.get_value(row, col);
+                let z_design = match design_surface {
+                    Some(ref surface) => {
+                        let zd = surface.get_value(row, col);
+                        if zd != surface.configs.nodata {
+                            Some(zd)
+                        } else {
+                            None
+                        }
+                    }
+                    None => design_constant,
+                };
+                if let Some(zd) = z_design {
+                    if z != nodata {
+                        data[col as usize] = zd - z;
+                    }
+                }
+            }
+            output.set_row_data(row, data);
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Calculating depths: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let is_geographic = dem.is_in_geographic_coordinates();
+        let cell_area = |row: isize| -> f64 {
+            if is_geographic {
+                let lat = dem.get_y_from_row(row).to_radians();
+                dem.configs.resolution_x * dem.configs.resolution_y * 111319.49f64 * 111319.49f64 * lat.cos()
+            } else {
+                dem.configs.resolution_x * dem.configs.resolution_y
+            }
+        };
+
+        let mut totals = CutFillVolumes::new();
+        for row in 0..rows {
+            let area = cell_area(row);
+            for col in 0..columns {
+                let diff = output.get_value(row, col);
+                if diff != nodata {
+                    totals.add_cell(diff, area, threshold);
+                }
+            }
+        }
+
+        if !polygons_file.is_empty() {
+            let polygons = Shapefile::read(&polygons_file)?;
+            if polygons.header.shape_type.base_shape_type() != ShapeType::Polygon {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input vector data must be of polygon base shape type.",
+                ));
+            }
+
+            let mut out_polygons = Shapefile::initialize_using_file(
+                &out_polygons_file,
+                &polygons,
+                polygons.header.shape_type,
+                true,
+            )?;
+            for field_name in ["CUT_AREA", "CUT_VOL", "FILL_AREA", "FILL_VOL", "NET_VOL"] {
+                out_polygons.attributes.add_field(&AttributeField::new(
+                    field_name,
+                    FieldDataType::Real,
+                    14u8,
+                    3u8,
+                ));
+            }
+
+            if verbose {
+                println!(
+                    "{:>8} {:>14} {:>14} {:>14} {:>14} {:>14}",
+                    "FID", "CUT_AREA", "CUT_VOL", "FILL_AREA", "FILL_VOL", "NET_VOL"
+                );
+            }
+            let num_records = polygons.num_records;
+            for record_num in 0..num_records {
+                let record = polygons.get_record(record_num);
+                let mut volumes = CutFillVolumes::new();
+                let start_row = dem.get_row_from_y(record.y_max).max(0);
+                let end_row = dem.get_row_from_y(record.y_min).min(rows - 1);
+                let start_col = dem.get_column_from_x(record.x_min).max(0);
+                let end_col = dem.get_column_from_x(record.x_max).min(columns - 1);
+                for row in start_row..=end_row {
+                    let y = dem.get_y_from_row(row);
+                    let area = cell_area(row);
+                    for col in start_col..=end_col {
+                        let diff = output.get_value(row, col);
+                        if diff == nodata {
+                            continue;
+                        }
+                        // A cell centre is within the polygon if it is within an odd number of the
+                        // record's rings, which accounts for holes.
+                        let point = Point2D::new(dem.get_x_from_column(col), y);
+                        let mut inside = false;
+                        for part in 0..record.num_parts as usize {
+                            let part_start = record.parts[part] as usize;
+                            let part_end = if part < record.num_parts as usize - 1 {
+                                record.parts[part + 1] as usize - 1
+                            } else {
+                                record.num_points as usize - 1
+                            };
+                            if point_in_poly(&point, &record.points[part_start..=part_end]) {
+                                inside = !inside;
+                            }
+                        }
+                        if inside {
+                            volumes.add_cell(diff, area, threshold);
+                        }
+                    }
+                }
+
+                out_polygons.add_record(record.clone());
+                let mut atts = polygons.attributes.get_record(record_num);
+                atts.push(FieldData::Real(volumes.cut_area));
+                atts.push(FieldData::Real(volumes.cut_volume));
+                atts.push(FieldData::Real(volumes.fill_area));
+                atts.push(FieldData::Real(volumes.fill_volume));
+                atts.push(FieldData::Real(volumes.net_volume()));
+                out_polygons.attributes.add_record(atts, false);
+
+                if verbose {
+                    println!(
+                        "{:>8} {:>14.3} {:>14.3} {:>14.3} {:>14.3} {:>14.3}",
+                        record_num + 1,
+                        volumes.cut_area,
+                        volumes.cut_volume,
+                        volumes.fill_area,
+                        volumes.fill_volume,
+                        volumes.net_volume()
+                    );
+                }
+            }
+
+            if verbose {
+                println!("Saving polygons...")
+            };
+            let _ = match out_polygons.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output polygons file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "Cut: area {:.3}, volume {:.3}",
+                totals.cut_area, totals.cut_volume
+            );
+            println!(
+                "Fill: area {:.3}, volume {:.3}",
+                totals.fill_area, totals.fill_volume
+            );
+            println!("Net volume (fill - cut): {:.3}", totals.net_volume());
+        }
+
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Existing DEM file: {}", dem_file));
+        output.add_metadata_entry(format!("Design surface: {}", design));
+        output.add_metadata_entry(format!("Change threshold: {}", threshold));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The areas and volumes of cut and fill within a region.
+struct CutFillVolumes {
+    cut_area: f64,
+    cut_volume: f64,
+    fill_area: f64,
+    fill_volume: f64,
+}
+
+impl CutFillVolumes {
+    fn new() -> CutFillVolumes {
+        CutFillVolumes {
+            cut_area: 0f64,
+            cut_volume: 0f64,
+            fill_area: 0f64,
+            fill_volume: 0f64,
+        }
+    }
+
+    fn add_cell(&mut self, diff: f64, cell_area: f64, threshold: f64) {
+        if diff > threshold {
+            self.fill_area += cell_area;
+            self.fill_volume += diff * cell_area;
+        } else if diff < -threshold {
+            self.cut_area += cell_area;
+            self.cut_volume -= diff * cell_area;
+        }
+    }
+
+    fn net_volume(&self) -> f64 {
+        self.fill_volume - self.cut_volume
+    }
+}
@@ -181,8 +181,6 @@ impl WhiteboxTool for ExtractRasterValuesAtPoints {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let start = Instant::now();
 
         let mut cmd = input_files.split(";");
@@ -237,9 +235,7 @@ impl WhiteboxTool for ExtractRasterValuesAtPoints {
                 };
 
                 let mut input_file = value.trim().to_owned();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
                 let input = Raster::new(&input_file, "r")?;
 
                 for record_num in 0..num_records {
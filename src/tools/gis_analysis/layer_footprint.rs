@@ -172,8 +172,6 @@ impl WhiteboxTool for LayerFootprint {
             }
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let start = Instant::now();
 
         if verbose {
@@ -182,13 +180,9 @@ impl WhiteboxTool for LayerFootprint {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         // is it a vector or a raster file?
         if input_file.to_lowercase().ends_with(".shp") {
@@ -189,7 +189,6 @@ impl WhiteboxTool for LineIntersections {
             }
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -201,17 +200,11 @@ impl WhiteboxTool for LineIntersections {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        if !input1_file.contains(path::MAIN_SEPARATOR) && !input1_file.contains("/") {
-            input1_file = format!("{}{}", working_directory, input1_file);
-        }
+        input1_file = resolve_path(&input1_file, working_directory);
 
-        if !input2_file.contains(path::MAIN_SEPARATOR) && !input2_file.contains("/") {
-            input2_file = format!("{}{}", working_directory, input2_file);
-        }
+        input2_file = resolve_path(&input2_file, working_directory);
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let input1 = Arc::new(Shapefile::read(&input1_file)?);
 
@@ -1,4 +1,5 @@
 // private sub-module defined in other files
+mod adaptive_kernel_density;
 mod aggregate_raster;
 mod average_overlay;
 mod block_maximum;
@@ -20,6 +21,8 @@ mod count_if;
 mod create_hexagonal_vector_grid;
 mod create_plane;
 mod create_rectangular_vector_grid;
+mod create_vector_tessellation;
+mod cut_fill;
 mod difference;
 mod edge_proportion;
 mod eliminate_coincident_points;
@@ -73,7 +76,7 @@ mod reclass_from_file;
 mod related_circumscribing_circle;
 mod shape_complexity_index;
 mod shape_complexity_raster;
-// mod sibson_interpolation;
+mod sibson_interpolation;
 mod dissolve;
 mod smooth_vectors;
 mod split_with_lines;
@@ -87,6 +90,7 @@ mod weighted_overlay;
 mod weighted_sum;
 
 // exports identifiers from private sub-modules in the current module namespace
+pub use self::adaptive_kernel_density::AdaptiveKernelDensity;
 pub use self::aggregate_raster::AggregateRaster;
 pub use self::average_overlay::AverageOverlay;
 pub use self::block_maximum::BlockMaximumGridding;
@@ -108,6 +112,8 @@ pub use self::count_if::CountIf;
 pub use self::create_hexagonal_vector_grid::CreateHexagonalVectorGrid;
 pub use self::create_plane::CreatePlane;
 pub use self::create_rectangular_vector_grid::CreateRectangularVectorGrid;
+pub use self::create_vector_tessellation::CreateVectorTessellation;
+pub use self::cut_fill::CutFill;
 pub use self::difference::Difference;
 pub use self::edge_proportion::EdgeProportion;
 pub use self::eliminate_coincident_points::EliminateCoincidentPoints;
@@ -161,7 +167,7 @@ pub use self::reclass_from_file::ReclassFromFile;
 pub use self::related_circumscribing_circle::RelatedCircumscribingCircle;
 pub use self::shape_complexity_index::ShapeComplexityIndex;
 pub use self::shape_complexity_raster::ShapeComplexityIndexRaster;
-// pub use self::sibson_interpolation::SibsonInterpolation;
+pub use self::sibson_interpolation::SibsonInterpolation;
 pub use self::dissolve::Dissolve;
 pub use self::smooth_vectors::SmoothVectors;
 pub use self::split_with_lines::SplitWithLines;
@@ -175,17 +175,11 @@ impl WhiteboxTool for PercentGreaterThan {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !comparison_files.contains(&sep) && !comparison_files.contains("/") {
-            comparison_files = format!("{}{}", working_directory, comparison_files);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        comparison_files = resolve_path(&comparison_files, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         let mut cmd = input_files.split(";");
         let mut vec = cmd.collect::<Vec<&str>>();
@@ -220,9 +214,7 @@ impl WhiteboxTool for PercentGreaterThan {
                 };
 
                 let mut input_file = value.trim().to_owned();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
                 let input = Raster::new(&input_file, "r")?;
                 in_nodata = input.configs.nodata;
                 // check to ensure that all inputs have the same rows and columns
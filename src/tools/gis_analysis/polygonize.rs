@@ -174,7 +174,6 @@ impl WhiteboxTool for Polygonize {
             }
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -186,9 +185,7 @@ impl WhiteboxTool for Polygonize {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let mut cmd = input_files.split(";");
         let mut vec = cmd.collect::<Vec<&str>>();
@@ -214,9 +211,7 @@ impl WhiteboxTool for Polygonize {
         for value in vec {
             if !value.trim().is_empty() {
                 let mut input_file = value.trim().to_owned();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
 
                 let input = Shapefile::read(&input_file)?;
                 projection = input.projection.clone();
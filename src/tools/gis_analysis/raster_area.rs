@@ -218,14 +218,10 @@ impl WhiteboxTool for RasterArea {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
 
         if !output_raster && !output_text {
             println!("Warning: Niether a raster nor text outputs were selected. An area raster will be generated.");
@@ -234,9 +230,7 @@ impl WhiteboxTool for RasterArea {
         }
 
         if output_raster {
-            if !output_file.contains(&sep) && !output_file.contains("/") {
-                output_file = format!("{}{}", working_directory, output_file);
-            }
+            output_file = resolve_path(&output_file, working_directory);
         }
 
         if verbose {
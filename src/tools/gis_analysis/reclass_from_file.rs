@@ -188,11 +188,7 @@ impl WhiteboxTool for ReclassFromFile {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
-        if !reclass_file.contains(&sep) {
-            reclass_file = format!("{}{}", working_directory, reclass_file);
-        }
+        reclass_file = resolve_path(&reclass_file, working_directory);
 
         let f = File::open(reclass_file)?;
         let f = BufReader::new(f);
@@ -236,12 +232,8 @@ impl WhiteboxTool for ReclassFromFile {
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -228,7 +228,6 @@ impl WhiteboxTool for SymmetricalDifference {
             }
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -240,15 +239,9 @@ impl WhiteboxTool for SymmetricalDifference {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !overlay_file.contains(&sep) && !overlay_file.contains("/") {
-            overlay_file = format!("{}{}", working_directory, overlay_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        overlay_file = resolve_path(&overlay_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
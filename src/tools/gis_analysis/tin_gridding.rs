@@ -2,27 +2,35 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 23/09/2018
-Last Modified: 18/10/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
-use self::na::Vector3;
-use crate::algorithms::{point_in_poly, triangulate};
-use crate::na;
+use crate::algorithms::{triangulate, NaturalNeighbourInterpolator, TinInterpolator};
 use crate::raster::*;
 use crate::structures::Point2D;
 use crate::tools::*;
 use crate::vector::*;
+use num_cpus;
 use std::env;
 use std::f64;
 use std::io::{Error, ErrorKind};
 use std::path;
 
 /// Creates a raster grid based on a triangular irregular network (TIN) fitted to vector points
-/// and linear interpolation within each triangular-shaped plane.
+/// and linear interpolation within each triangular-shaped plane. The interpolated values are
+/// taken from either an attribute field (`--field`) or the z values of the point geometries
+/// (`--use_z`).
+///
+/// The tool shares its interpolation engine with `LidarTINGridding`. Sibson's natural neighbour
+/// interpolation may be used instead of linear interpolation (`--method=natural_neighbour`),
+/// and triangles with an edge longer than the maximum triangle edge length
+/// (`--max_triangle_edge_length`), e.g. those spanning gaps between survey lines, are not
+/// gridded. The rasterization of the TIN is divided into blocks of rows that are processed in
+/// parallel.
 ///
 /// # See Also
-/// `LidarTINGridding`, `ConstructVectorTIN`
+/// `LidarTINGridding`, `SibsonInterpolation`, `ConstructVectorTIN`
 pub struct TINGridding {
     name: String,
     description: String,
@@ -93,6 +101,29 @@ impl TINGridding {
             optional: false,
         });
 
+        parameters.push(ToolParameter {
+            name: "Maximum Triangle Edge Length (optional)".to_owned(),
+            flags: vec!["--max_triangle_edge_length".to_owned()],
+            description: "Optional maximum triangle edge length; triangles larger than this size will not be gridded.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Interpolation Method".to_owned(),
+            flags: vec!["--method".to_owned()],
+            description:
+                "Interpolation method; options are 'linear' (default) and 'natural_neighbour'."
+                    .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "linear".to_owned(),
+                "natural_neighbour".to_owned(),
+            ]),
+            default_value: Some("linear".to_owned()),
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -106,7 +137,7 @@ impl TINGridding {
         }
         let usage = format!(
             ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --field=HEIGHT -o=tin.shp --resolution=10.0
->>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --use_z -o=tin.shp --resolution=5.0",
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=points.shp --use_z -o=tin.shp --resolution=5.0 --max_triangle_edge_length=50.0 --method=natural_neighbour",
             short_exe, name
         ).replace("*", &sep);
 
@@ -167,6 +198,8 @@ impl WhiteboxTool for TINGridding {
         let mut use_field = false;
         let mut output_file: String = "".to_string();
         let mut grid_res: f64 = 1.0;
+        let mut max_triangle_edge_length = f64::INFINITY;
+        let mut natural_neighbour = false;
 
         // read the arguments
         if args.len() == 0 {
@@ -214,10 +247,24 @@ impl WhiteboxTool for TINGridding {
                 } else {
                     args[i + 1].to_string().parse::<f64>().unwrap()
                 };
+            } else if flag_val == "-max_triangle_edge_length" {
+                max_triangle_edge_length = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+
+                max_triangle_edge_length *= max_triangle_edge_length; // actually squared distance
+            } else if flag_val == "-method" {
+                let method = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+                natural_neighbour = method.contains("natural") || method == "nn" || method == "sibson";
             }
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -229,13 +276,9 @@ impl WhiteboxTool for TINGridding {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let input = Shapefile::read(&input_file)?;
 
@@ -347,78 +390,34 @@ impl WhiteboxTool for TINGridding {
         }
         // this is where the heavy-lifting is
         let delaunay = triangulate(&points).expect("No triangulation exists.");
-        let num_triangles = delaunay.triangles.len() / 3;
-
-        let (mut p1, mut p2, mut p3): (usize, usize, usize);
-        let (mut top, mut bottom, mut left, mut right): (f64, f64, f64, f64);
-
-        let (mut top_row, mut bottom_row, mut left_col, mut right_col): (
-            isize,
-            isize,
-            isize,
-            isize,
-        );
-        let mut tri_points: Vec<Point2D> = vec![Point2D::new(0f64, 0f64); 4];
-        let mut k: f64;
-        let mut norm: Vector3<f64>;
-        let (mut a, mut b, mut c): (Vector3<f64>, Vector3<f64>, Vector3<f64>);
-        let (mut x, mut y): (f64, f64);
-        let mut z: f64;
-        let mut i: usize;
-        for triangle in 0..num_triangles {
-            i = triangle * 3;
-            p1 = delaunay.triangles[i];
-            p2 = delaunay.triangles[i + 1];
-            p3 = delaunay.triangles[i + 2];
-            tri_points[0] = points[p1].clone();
-            tri_points[1] = points[p2].clone();
-            tri_points[2] = points[p3].clone();
-            tri_points[3] = points[p1].clone();
-            // if is_clockwise_order(&tri_points) {
-            //     tri_points.reverse();
-            // }
-
-            // get the equation of the plane
-            a = Vector3::new(tri_points[0].x, tri_points[0].y, z_values[p1]);
-            b = Vector3::new(tri_points[1].x, tri_points[1].y, z_values[p2]);
-            c = Vector3::new(tri_points[2].x, tri_points[2].y, z_values[p3]);
-            norm = (b - a).cross(&(c - a));
-
-            if norm.z != 0f64 {
-                k = -(tri_points[0].x * norm.x + tri_points[0].y * norm.y + norm.z * z_values[p1]);
-
-                // find grid intersections with this triangle
-                bottom = points[p1].y.min(points[p2].y.min(points[p3].y));
-                top = points[p1].y.max(points[p2].y.max(points[p3].y));
-                left = points[p1].x.min(points[p2].x.min(points[p3].x));
-                right = points[p1].x.max(points[p2].x.max(points[p3].x));
-
-                bottom_row = ((north - bottom) / grid_res).ceil() as isize;
-                top_row = ((north - top) / grid_res).floor() as isize;
-                left_col = ((left - west) / grid_res).floor() as isize;
-                right_col = ((right - west) / grid_res).ceil() as isize;
-
-                for row in top_row..=bottom_row {
-                    for col in left_col..=right_col {
-                        x = west + (col as f64 + 0.5) * grid_res;
-                        y = north - (row as f64 + 0.5) * grid_res;
-                        if point_in_poly(&Point2D::new(x, y), &tri_points) {
-                            // calculate the z values
-                            z = -(norm.x * x + norm.y * y + k) / norm.z;
-                            output.set_value(row, col, z);
-                        }
-                    }
-                }
-            }
+        let nn_interpolator = if natural_neighbour {
+            Some(NaturalNeighbourInterpolator::new(&points, &delaunay))
+        } else {
+            None
+        };
 
-            if verbose {
-                progress = (100.0_f64 * triangle as f64 / (num_triangles - 1) as f64) as usize;
-                if progress != old_progress {
-                    println!("Progress: {}%", progress);
-                    old_progress = progress;
-                }
+        // values are interpolated at the grid cell centres
+        let z_values = vec![z_values];
+        let surface = TinInterpolator {
+            points: &points,
+            z_values: &z_values,
+            is_rgb: &[false],
+            triangles: &delaunay.triangles,
+            nn_interpolator: nn_interpolator.as_ref(),
+            max_triangle_edge_length: max_triangle_edge_length,
+            west: west + 0.5 * grid_res,
+            north: north - 0.5 * grid_res,
+            grid_res: grid_res,
+            columns: columns,
+            nodata: nodata,
+        };
+        let num_procs = num_cpus::get() as isize;
+        surface.rasterize_rows(rows, num_procs, verbose, |start_row, end_row, data| {
+            for row in start_row..end_row {
+                let offset = ((row - start_row) * columns) as usize;
+                output.set_row_data(row, data[0][offset..offset + columns as usize].to_vec());
             }
-        }
+        });
 
         let elapsed_time = get_formatted_elapsed_time(start);
 
@@ -428,6 +427,10 @@ impl WhiteboxTool for TINGridding {
         ));
         output.add_metadata_entry(format!("Input file: {}", input_file));
         output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
+        output.add_metadata_entry(format!(
+            "Interpolation method: {}",
+            if natural_neighbour { "natural neighbour" } else { "linear" }
+        ));
         output.add_metadata_entry(format!("Elapsed Time (including I/O): {}", elapsed_time));
 
         if verbose {
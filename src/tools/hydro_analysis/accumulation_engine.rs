@@ -0,0 +1,928 @@
+/*
+This file is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use crate::raster::Raster;
+use crate::structures::Array2D;
+use crate::tools::{ParameterType, ToolParameter};
+use num_cpus;
+use std::f64;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// The operation used to combine values that are propagated along flowpaths.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reducer {
+    Sum,
+    Max,
+    Min,
+}
+
+impl Reducer {
+    /// Returns the value that leaves any other value unchanged when combined with it.
+    pub fn identity(&self) -> f64 {
+        match self {
+            Reducer::Sum => 0f64,
+            Reducer::Max => f64::NEG_INFINITY,
+            Reducer::Min => f64::INFINITY,
+        }
+    }
+
+    pub fn combine(&self, a: f64, b: f64) -> f64 {
+        match self {
+            Reducer::Sum => a + b,
+            Reducer::Max => a.max(b),
+            Reducer::Min => a.min(b),
+        }
+    }
+}
+
+/// Flow directions derived from a DEM, using either the D8 or the D-infinity flow algorithm.
+#[derive(Clone)]
+pub enum FlowDirections {
+    /// D8 pointers, in which 0-7 index the neighbours clockwise from the north-east and -1
+    /// marks cells without a downslope neighbour.
+    D8(Arc<Array2D<i8>>),
+    /// D-infinity flow angles, in degrees clockwise from north, in which -1 marks cells without
+    /// a downslope neighbour.
+    DInf(Arc<Array2D<f64>>),
+}
+
+impl FlowDirections {
+    /// Returns the (up to two) neighbours that receive the flow from a cell and the proportion
+    /// of the flow that each receives. Unused entries have a proportion of zero.
+    pub fn receivers(&self, row: isize, col: isize) -> [(isize, isize, f64); 2] {
+        let mut ret = [(row, col, 0f64), (row, col, 0f64)];
+        match self {
+            FlowDirections::D8(flow_dir) => {
+                let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+                let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+                let dir = flow_dir.get_value(row, col);
+                if dir >= 0 {
+                    ret[0] = (row + dy[dir as usize], col + dx[dir as usize], 1f64);
+                }
+            }
+            FlowDirections::DInf(flow_dir) => {
+                // the facets are bounded by neighbours clockwise from the north
+                let dx = [0, 1, 1, 1, 0, -1, -1, -1, 0];
+                let dy = [-1, -1, 0, 1, 1, 1, 0, -1, -1];
+                let dir = flow_dir.get_value(row, col);
+                if dir >= 0f64 {
+                    let facet = ((dir / 45f64).floor() as usize).min(7);
+                    let proportion2 = (dir - facet as f64 * 45f64) / 45f64;
+                    ret[0] = (row + dy[facet], col + dx[facet], 1f64 - proportion2);
+                    ret[1] = (row + dy[facet + 1], col + dx[facet + 1], proportion2);
+                }
+            }
+        }
+        ret
+    }
+}
+
+/// How ties between equally steep downslope neighbours are resolved by D8 flow directions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum D8TieBreaking {
+    /// The first of the tied neighbours, clockwise from the north-east, receives the flow, which
+    /// produces grid-aligned flowpaths on planar surfaces.
+    First,
+    /// One of the tied neighbours is selected pseudo-randomly, using a hash of the seed and the
+    /// position of the cell, such that the flow directions are reproducible whatever the number
+    /// of threads.
+    Random(u64),
+    /// The tied neighbour whose direction is closest to the aspect of the cell, i.e. the downslope
+    /// direction of the 3 x 3 neighbourhood, receives the flow.
+    Aspect,
+}
+
+impl D8TieBreaking {
+    /// Returns the tool parameters of the tie-breaking method, i.e. `--tie_break` and `--seed`.
+    pub fn parameters() -> Vec<ToolParameter> {
+        vec![
+            ToolParameter {
+                name: "Tie-Breaking Method".to_owned(),
+                flags: vec!["--tie_break".to_owned()],
+                description: "Resolution of ties between equally steep downslope neighbours; one of 'first' (default), 'random', and 'aspect'.".to_owned(),
+                parameter_type: ParameterType::OptionList(vec![
+                    "first".to_owned(),
+                    "random".to_owned(),
+                    "aspect".to_owned(),
+                ]),
+                default_value: Some("first".to_owned()),
+                optional: true,
+            },
+            ToolParameter {
+                name: "Random Seed (optional)".to_owned(),
+                flags: vec!["--seed".to_owned()],
+                description: "Seed of the 'random' tie-breaking method; the same seed always produces the same flow directions.".to_owned(),
+                parameter_type: ParameterType::Integer,
+                default_value: Some("0".to_owned()),
+                optional: true,
+            },
+        ]
+    }
+
+    /// Returns the tie-breaking method with a given name, i.e. 'first', 'random', or 'aspect'.
+    pub fn new(method: &str, seed: u64) -> Result<D8TieBreaking, Error> {
+        match method.trim().to_lowercase().as_str() {
+            "first" | "" => Ok(D8TieBreaking::First),
+            "random" => Ok(D8TieBreaking::Random(seed)),
+            "aspect" => Ok(D8TieBreaking::Aspect),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The tie-breaking method (--tie_break) must be 'first', 'random', or 'aspect'.",
+            )),
+        }
+    }
+
+    /// Selects the neighbour that receives the flow from a cell among two or more equally steep
+    /// downslope neighbours, `ties`, given in clockwise order from the north-east.
+    fn resolve(&self, input: &Raster, row: isize, col: isize, ties: &[usize]) -> usize {
+        match self {
+            D8TieBreaking::First => ties[0],
+            D8TieBreaking::Random(seed) => {
+                // a SplitMix64 hash of the seed and cell position
+                let mut h = seed
+                    .wrapping_add((row as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+                    .wrapping_add((col as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F));
+                h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+                h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+                h ^= h >> 31;
+                ties[(h % ties.len() as u64) as usize]
+            }
+            D8TieBreaking::Aspect => {
+                // Horn's (1981) gradient, in which NoData neighbours take the value of the cell
+                let nodata = input.configs.nodata;
+                let z = input.get_value(row, col);
+                let v = |dr: isize, dc: isize| {
+                    let zn = input.get_value(row + dr, col + dc);
+                    if zn != nodata {
+                        zn
+                    } else {
+                        z
+                    }
+                };
+                let dz_dx = ((v(-1, 1) + 2f64 * v(0, 1) + v(1, 1))
+                    - (v(-1, -1) + 2f64 * v(0, -1) + v(1, -1)))
+                    / (8f64 * input.configs.resolution_x);
+                let dz_dy = ((v(-1, -1) + 2f64 * v(-1, 0) + v(-1, 1))
+                    - (v(1, -1) + 2f64 * v(1, 0) + v(1, 1)))
+                    / (8f64 * input.configs.resolution_y);
+                // the downslope direction, as easting and northing components
+                let (east, north) = (-dz_dx, -dz_dy);
+                let dx = [1f64, 1f64, 1f64, 0f64, -1f64, -1f64, -1f64, 0f64];
+                let dn = [1f64, 0f64, -1f64, -1f64, -1f64, 0f64, 1f64, 1f64];
+                let mut best = ties[0];
+                let mut max_cos = f64::NEG_INFINITY;
+                for &i in ties {
+                    let cos = (east * dx[i] + north * dn[i]) / (dx[i] * dx[i] + dn[i] * dn[i]).sqrt();
+                    if cos > max_cos {
+                        max_cos = cos;
+                        best = i;
+                    }
+                }
+                best
+            }
+        }
+    }
+}
+
+/// Calculates the D8 flow directions of a DEM in parallel. Returns the flow directions, in which
+/// -1 marks cells without a downslope neighbour, and whether any interior pits were found. Ties
+/// between equally steep downslope neighbours are resolved using `tie_breaking`.
+pub fn d8_flow_directions(
+    input: &Arc<Raster>,
+    tie_breaking: D8TieBreaking,
+    verbose: bool,
+) -> Result<(Array2D<i8>, bool), Error> {
+    let rows = input.configs.rows as isize;
+    let columns = input.configs.columns as isize;
+    let cell_size_x = input.configs.resolution_x;
+    let cell_size_y = input.configs.resolution_y;
+    let diag_cell_size = (cell_size_x * cell_size_x + cell_size_y * cell_size_y).sqrt();
+    let mut progress: usize;
+    let mut old_progress: usize = 1;
+
+    let mut flow_dir: Array2D<i8> = Array2D::new(rows, columns, -1, -1)?;
+    let num_procs = num_cpus::get() as isize;
+    let (tx, rx) = mpsc::channel();
+    for tid in 0..num_procs {
+        let input = input.clone();
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let nodata = input.configs.nodata;
+            let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+            let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+            let grid_lengths = [
+                diag_cell_size,
+                cell_size_x,
+                diag_cell_size,
+                cell_size_y,
+                diag_cell_size,
+                cell_size_x,
+                diag_cell_size,
+                cell_size_y,
+            ];
+            let (mut z, mut z_n): (f64, f64);
+            let (mut max_slope, mut slope): (f64, f64);
+            let mut ties = [0usize; 8];
+            let mut num_ties: usize;
+            let mut neighbouring_nodata: bool;
+            let mut interior_pit_found = false;
+            for row in (0..rows).filter(|r| r % num_procs == tid) {
+                let mut data: Vec<i8> = vec![-1i8; columns as usize];
+                for col in 0..columns {
+                    z = input.get_value(row, col);
+                    if z != nodata {
+                        num_ties = 0;
+                        max_slope = f64::MIN;
+                        neighbouring_nodata = false;
+                        for i in 0..8 {
+                            z_n = input.get_value(row + dy[i], col + dx[i]);
+                            if z_n != nodata {
+                                slope = (z - z_n) / grid_lengths[i];
+                                if slope > max_slope && slope > 0f64 {
+                                    max_slope = slope;
+                                    ties[0] = i;
+                                    num_ties = 1;
+                                } else if slope == max_slope && slope > 0f64 {
+                                    ties[num_ties] = i;
+                                    num_ties += 1;
+                                }
+                            } else {
+                                neighbouring_nodata = true;
+                            }
+                        }
+                        if num_ties == 1 {
+                            data[col as usize] = ties[0] as i8;
+                        } else if num_ties > 1 {
+                            data[col as usize] =
+                                tie_breaking.resolve(&input, row, col, &ties[..num_ties]) as i8;
+                        } else {
+                            data[col as usize] = -1i8;
+                            if !neighbouring_nodata {
+                                interior_pit_found = true;
+                            }
+                        }
+                    } else {
+                        data[col as usize] = -1i8;
+                    }
+                }
+                tx.send((row, data, interior_pit_found)).unwrap();
+            }
+        });
+    }
+
+    let mut interior_pit_found = false;
+    for r in 0..rows {
+        let (row, data, pit) = rx.recv().unwrap();
+        flow_dir.set_row_data(row, data);
+        if pit {
+            interior_pit_found = true;
+        }
+        if verbose {
+            progress = (100.0_f64 * r as f64 / (rows - 1) as f64) as usize;
+            if progress != old_progress {
+                println!("Flow directions: {}%", progress);
+                old_progress = progress;
+            }
+        }
+    }
+
+    Ok((flow_dir, interior_pit_found))
+}
+
+/// Calculates the D-infinity flow directions (Tarboton, 1997) of a DEM in parallel. Returns the
+/// flow angles, in degrees clockwise from north, in which -1 marks cells without a downslope
+/// neighbour, and whether any interior pits were found.
+pub fn dinf_flow_directions(
+    input: &Arc<Raster>,
+    verbose: bool,
+) -> Result<(Array2D<f64>, bool), Error> {
+    let rows = input.configs.rows as isize;
+    let columns = input.configs.columns as isize;
+    let nodata = input.configs.nodata;
+    let cell_size_x = input.configs.resolution_x;
+    let cell_size_y = input.configs.resolution_y;
+    let diag_cell_size = (cell_size_x * cell_size_x + cell_size_y * cell_size_y).sqrt();
+    let mut progress: usize;
+    let mut old_progress: usize = 1;
+
+    let mut flow_dir: Array2D<f64> = Array2D::new(rows, columns, nodata, nodata)?;
+    let num_procs = num_cpus::get() as isize;
+    let (tx, rx) = mpsc::channel();
+    for tid in 0..num_procs {
+        let input = input.clone();
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let nodata = input.configs.nodata;
+            let grid_res = (cell_size_x + cell_size_y) / 2.0;
+            let mut dir: f64;
+            let mut max_slope: f64;
+            let mut e0: f64;
+            let mut af: f64;
+            let mut ac: f64;
+            let (mut e1, mut r, mut s1, mut s2, mut s, mut e2): (f64, f64, f64, f64, f64, f64);
+
+            let ac_vals = [0f64, 1f64, 1f64, 2f64, 2f64, 3f64, 3f64, 4f64];
+            let af_vals = [1f64, -1f64, 1f64, -1f64, 1f64, -1f64, 1f64, -1f64];
+
+            let e1_col = [1, 0, 0, -1, -1, 0, 0, 1];
+            let e1_row = [0, -1, -1, 0, 0, 1, 1, 0];
+
+            let e2_col = [1, 1, -1, -1, -1, -1, 1, 1];
+            let e2_row = [-1, -1, -1, -1, 1, 1, 1, 1];
+
+            let atanof1 = 1.0f64.atan();
+
+            let mut neighbouring_nodata: bool;
+            let mut interior_pit_found = false;
+            for row in (0..rows).filter(|r| r % num_procs == tid) {
+                let mut data: Vec<f64> = vec![nodata; columns as usize];
+                for col in 0..columns {
+                    e0 = input.get_value(row, col);
+                    if e0 != nodata {
+                        dir = 360.0;
+                        max_slope = f64::MIN;
+                        neighbouring_nodata = false;
+                        for i in 0..8 {
+                            ac = ac_vals[i];
+                            af = af_vals[i];
+                            e1 = input.get_value(row + e1_row[i], col + e1_col[i]);
+                            e2 = input.get_value(row + e2_row[i], col + e2_col[i]);
+                            if e1 != nodata && e2 != nodata {
+                                if e0 > e1 && e0 > e2 {
+                                    s1 = (e0 - e1) / grid_res;
+                                    if s1 == 0.0 {
+                                        s1 = 0.00001;
+                                    }
+                                    s2 = (e1 - e2) / grid_res;
+                                    r = (s2 / s1).atan();
+                                    s = (s1 * s1 + s2 * s2).sqrt();
+                                    if s1 < 0.0 && s2 < 0.0 {
+                                        s = -s;
+                                    }
+                                    if s1 < 0.0 && s2 == 0.0 {
+                                        s = -s;
+                                    }
+                                    if s1 == 0.0 && s2 < 0.0 {
+                                        s = -s;
+                                    }
+                                    if s1 == 0.001 && s2 < 0.0 {
+                                        s = -s;
+                                    }
+                                    if r < 0.0 || r > atanof1 {
+                                        if r < 0.0 {
+                                            r = 0.0;
+                                            s = s1;
+                                        } else {
+                                            r = atanof1;
+                                            s = (e0 - e2) / diag_cell_size;
+                                        }
+                                    }
+                                    if s >= max_slope && s != 0.00001 {
+                                        max_slope = s;
+                                        dir = af * r + ac * (PI / 2.0);
+                                    }
+                                } else if e0 > e1 || e0 > e2 {
+                                    if e0 > e1 {
+                                        r = 0.0;
+                                        s = (e0 - e1) / grid_res;
+                                    } else {
+                                        r = atanof1;
+                                        s = (e0 - e2) / diag_cell_size;
+                                    }
+                                    if s >= max_slope && s != 0.00001 {
+                                        max_slope = s;
+                                        dir = af * r + ac * (PI / 2.0);
+                                    }
+                                }
+                            } else {
+                                neighbouring_nodata = true;
+                            }
+                        }
+
+                        if max_slope > 0f64 {
+                            dir = 360.0 - dir.to_degrees() + 90.0;
+                            if dir > 360.0 {
+                                dir -= 360.0;
+                            }
+                            data[col as usize] = dir;
+                        } else {
+                            data[col as usize] = -1f64;
+                            if !neighbouring_nodata {
+                                interior_pit_found = true;
+                            }
+                        }
+                    } else {
+                        data[col as usize] = -1f64;
+                    }
+                }
+                tx.send((row, data, interior_pit_found)).unwrap();
+            }
+        });
+    }
+
+    let mut interior_pit_found = false;
+    for r in 0..rows {
+        let (row, data, pit) = rx.recv().unwrap();
+        flow_dir.set_row_data(row, data);
+        if pit {
+            interior_pit_found = true;
+        }
+        if verbose {
+            progress = (100.0_f64 * r as f64 / (rows - 1) as f64) as usize;
+            if progress != old_progress {
+                println!("Flow directions: {}%", progress);
+                old_progress = progress;
+            }
+        }
+    }
+
+    Ok((flow_dir, interior_pit_found))
+}
+
+/// Accumulates the weights of grid cells (one, if no weights are specified) downslope along
+/// flowpaths, such that each cell is assigned the combination of the weights of all of the cells
+/// in its upslope area, including itself. With `Reducer::Sum`, the weight of a cell is divided
+/// among its receivers in proportion to their share of its flow, e.g. unit weights produce the
+/// flow accumulation in cells. Cells that are NoData in the DEM are NoData in the output.
+///
+/// D8 accumulation is calculated in parallel using a tiled algorithm (Barnes, 2017) and
+/// D-infinity accumulation is calculated by visiting the cells in topological order.
+pub fn upslope_accumulation(
+    input: &Arc<Raster>,
+    flow_dirs: &FlowDirections,
+    weights: Option<Arc<Array2D<f64>>>,
+    reducer: Reducer,
+    verbose: bool,
+) -> Result<Array2D<f64>, Error> {
+    if let FlowDirections::D8(flow_dir) = flow_dirs {
+        return d8_upslope_accumulation(input, flow_dir, weights, reducer, verbose);
+    }
+    let rows = input.configs.rows as isize;
+    let columns = input.configs.columns as isize;
+    let nodata = input.configs.nodata;
+    let mut acc: Array2D<f64> = Array2D::new(rows, columns, nodata, nodata)?;
+    let order = topological_order(input, flow_dirs)?;
+    for &(row, col) in &order {
+        acc.set_value(
+            row,
+            col,
+            weights.as_ref().map_or(1f64, |w| w.get_value(row, col)),
+        );
+    }
+    let num_cells = order.len();
+    let mut progress: usize;
+    let mut old_progress: usize = 1;
+    for (i, &(row, col)) in order.iter().enumerate() {
+        let v = acc.get_value(row, col);
+        for (row_n, col_n, p) in flow_dirs.receivers(row, col).iter().copied() {
+            if p > 0f64 && input.get_value(row_n, col_n) != nodata {
+                let v_n = acc.get_value(row_n, col_n);
+                if reducer == Reducer::Sum {
+                    acc.set_value(row_n, col_n, v_n + v * p);
+                } else {
+                    acc.set_value(row_n, col_n, reducer.combine(v_n, v));
+                }
+            }
+        }
+        if verbose {
+            progress = (100.0_f64 * (i + 1) as f64 / num_cells as f64) as usize;
+            if progress != old_progress {
+                println!("Flow accumulation: {}%", progress);
+                old_progress = progress;
+            }
+        }
+    }
+    Ok(acc)
+}
+
+/// Propagates the weights of grid cells (one, if no weights are specified) upslope along
+/// flowpaths, such that each cell is assigned the combination of its own weight and the weights
+/// of all of the cells along its downslope flowpath, to the edge of the DEM or a pit. With
+/// `Reducer::Sum` and divergent (D-infinity) flow, the values of the receivers of a cell are
+/// weighted by their share of its flow, i.e. the result is the expected sum along the flowpath.
+/// Cells that are NoData in the DEM are NoData in the output.
+pub fn downslope_accumulation(
+    input: &Arc<Raster>,
+    flow_dirs: &FlowDirections,
+    weights: Option<Arc<Array2D<f64>>>,
+    reducer: Reducer,
+    verbose: bool,
+) -> Result<Array2D<f64>, Error> {
+    let rows = input.configs.rows as isize;
+    let columns = input.configs.columns as isize;
+    let nodata = input.configs.nodata;
+    let mut acc: Array2D<f64> = Array2D::new(rows, columns, nodata, nodata)?;
+    let order = topological_order(input, flow_dirs)?;
+    let num_cells = order.len();
+    let mut progress: usize;
+    let mut old_progress: usize = 1;
+    for (i, &(row, col)) in order.iter().rev().enumerate() {
+        let mut v = weights.as_ref().map_or(1f64, |w| w.get_value(row, col));
+        for (row_n, col_n, p) in flow_dirs.receivers(row, col).iter().copied() {
+            if p > 0f64 && input.get_value(row_n, col_n) != nodata {
+                let v_n = acc.get_value(row_n, col_n);
+                if reducer == Reducer::Sum {
+                    v += v_n * p;
+                } else {
+                    v = reducer.combine(v, v_n);
+                }
+            }
+        }
+        acc.set_value(row, col, v);
+        if verbose {
+            progress = (100.0_f64 * (i + 1) as f64 / num_cells as f64) as usize;
+            if progress != old_progress {
+                println!("Flow accumulation: {}%", progress);
+                old_progress = progress;
+            }
+        }
+    }
+    Ok(acc)
+}
+
+/// Returns the valid cells of a DEM in topological order, i.e. each cell precedes all of the
+/// cells that receive its flow.
+fn topological_order(
+    input: &Raster,
+    flow_dirs: &FlowDirections,
+) -> Result<Vec<(isize, isize)>, Error> {
+    let rows = input.configs.rows as isize;
+    let columns = input.configs.columns as isize;
+    let nodata = input.configs.nodata;
+    let mut num_inflowing: Array2D<u8> = Array2D::new(rows, columns, 0, 0)?;
+    let mut num_cells = 0usize;
+    for row in 0..rows {
+        for col in 0..columns {
+            if input.get_value(row, col) != nodata {
+                num_cells += 1;
+                for (row_n, col_n, p) in flow_dirs.receivers(row, col).iter().copied() {
+                    if p > 0f64 && input.get_value(row_n, col_n) != nodata {
+                        num_inflowing.increment(row_n, col_n, 1);
+                    }
+                }
+            }
+        }
+    }
+    let mut stack = vec![];
+    for row in 0..rows {
+        for col in 0..columns {
+            if input.get_value(row, col) != nodata && num_inflowing.get_value(row, col) == 0 {
+                stack.push((row, col));
+            }
+        }
+    }
+    let mut order = Vec::with_capacity(num_cells);
+    while let Some((row, col)) = stack.pop() {
+        order.push((row, col));
+        for (row_n, col_n, p) in flow_dirs.receivers(row, col).iter().copied() {
+            if p > 0f64 && input.get_value(row_n, col_n) != nodata {
+                num_inflowing.decrement(row_n, col_n, 1);
+                if num_inflowing.get_value(row_n, col_n) == 0 {
+                    stack.push((row_n, col_n));
+                }
+            }
+        }
+    }
+    Ok(order)
+}
+
+/// Accumulates values along D8 flowpaths in parallel using a tiled algorithm (Barnes, 2017).
+/// The grid is divided into tiles (strips of rows) and the accumulation within each tile,
+/// considering only the flow originating within the tile, is calculated independently. The flow
+/// exchanged between tiles is then resolved using a graph of the cells on the tile edges, and
+/// finally, the accumulation within each tile is updated with its inflow.
+fn d8_upslope_accumulation(
+    input: &Arc<Raster>,
+    flow_dir: &Arc<Array2D<i8>>,
+    weights: Option<Arc<Array2D<f64>>>,
+    reducer: Reducer,
+    verbose: bool,
+) -> Result<Array2D<f64>, Error> {
+    let rows = input.configs.rows as isize;
+    let columns = input.configs.columns as isize;
+    let nodata = input.configs.nodata;
+    let identity = reducer.identity();
+    let mut progress: usize;
+    let mut old_progress: usize = 1;
+
+    let num_procs = num_cpus::get() as isize;
+    let num_tiles = (num_procs * 4).min(rows / 2).max(1);
+    let tile_rows = (rows as f64 / num_tiles as f64).ceil() as isize;
+    let num_tiles = (rows + tile_rows - 1) / tile_rows;
+
+    // each row on the edge of a tile has a slot in the tile-edge graph
+    let mut edge_slot = vec![-1isize; rows as usize];
+    let mut num_slots = 0isize;
+    for tile in 0..num_tiles {
+        let row_start = tile * tile_rows;
+        let row_end = (row_start + tile_rows).min(rows);
+        for row in tile_edge_rows(row_start, row_end) {
+            if edge_slot[row as usize] == -1 {
+                edge_slot[row as usize] = num_slots;
+                num_slots += 1;
+            }
+        }
+    }
+    let edge_slot = Arc::new(edge_slot);
+
+    let (tx, rx) = mpsc::channel();
+    for tid in 0..num_procs {
+        let input = input.clone();
+        let flow_dir = flow_dir.clone();
+        let weights = weights.clone();
+        let edge_slot = edge_slot.clone();
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for tile in (0..num_tiles).filter(|t| t % num_procs == tid) {
+                let row_start = tile * tile_rows;
+                let row_end = (row_start + tile_rows).min(rows);
+                let acc = accumulate_tile(
+                    &input,
+                    &flow_dir,
+                    weights.as_deref(),
+                    reducer,
+                    row_start,
+                    row_end,
+                    &[],
+                );
+                let edges =
+                    trace_tile_edges(&input, &flow_dir, &edge_slot, row_start, row_end, &acc);
+                tx.send(edges).unwrap();
+            }
+        });
+    }
+
+    // resolve the flow exchanged between tiles
+    let num_nodes = (num_slots * columns) as usize;
+    let mut local_acc = vec![identity; num_nodes];
+    let mut links: Vec<Option<usize>> = vec![None; num_nodes];
+    let mut outflows: Vec<Option<usize>> = vec![None; num_nodes];
+    let mut num_upslope = vec![0usize; num_nodes];
+    let mut is_valid = vec![false; num_nodes];
+    for t in 0..num_tiles {
+        for (node, acc, link, outflow) in rx.recv().unwrap() {
+            local_acc[node] = acc;
+            links[node] = link;
+            outflows[node] = outflow;
+            is_valid[node] = true;
+            if let Some(n) = link {
+                num_upslope[n] += 1;
+            }
+            if let Some(n) = outflow {
+                num_upslope[n] += 1;
+            }
+        }
+        if verbose {
+            progress = (100.0_f64 * (t + 1) as f64 / num_tiles as f64) as usize;
+            if progress != old_progress {
+                println!("Flow accumulation (tiles): {}%", progress);
+                old_progress = progress;
+            }
+        }
+    }
+    // The extra accumulation of an edge cell is the flow that it receives from outside of its
+    // tile, either directly from a neighbouring tile or from an upslope edge cell in its tile.
+    let mut extra_acc = vec![identity; num_nodes];
+    let mut inflow = vec![identity; num_nodes];
+    let mut stack: Vec<usize> = (0..num_nodes)
+        .filter(|n| is_valid[*n] && num_upslope[*n] == 0)
+        .collect();
+    while let Some(node) = stack.pop() {
+        if let Some(n) = links[node] {
+            extra_acc[n] = reducer.combine(extra_acc[n], extra_acc[node]);
+            num_upslope[n] -= 1;
+            if num_upslope[n] == 0 {
+                stack.push(n);
+            }
+        }
+        if let Some(n) = outflows[node] {
+            let total_acc = reducer.combine(local_acc[node], extra_acc[node]);
+            extra_acc[n] = reducer.combine(extra_acc[n], total_acc);
+            inflow[n] = reducer.combine(inflow[n], total_acc);
+            num_upslope[n] -= 1;
+            if num_upslope[n] == 0 {
+                stack.push(n);
+            }
+        }
+    }
+
+    // update the accumulation within each tile with its inflow
+    let inflow = Arc::new(inflow);
+    let (tx, rx) = mpsc::channel();
+    for tid in 0..num_procs {
+        let input = input.clone();
+        let flow_dir = flow_dir.clone();
+        let weights = weights.clone();
+        let edge_slot = edge_slot.clone();
+        let inflow = inflow.clone();
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for tile in (0..num_tiles).filter(|t| t % num_procs == tid) {
+                let row_start = tile * tile_rows;
+                let row_end = (row_start + tile_rows).min(rows);
+                let mut tile_inflow = vec![];
+                for row in tile_edge_rows(row_start, row_end) {
+                    let slot = edge_slot[row as usize];
+                    for col in 0..columns {
+                        let v = inflow[(slot * columns + col) as usize];
+                        if v != identity {
+                            tile_inflow.push((row, col, v));
+                        }
+                    }
+                }
+                let acc = accumulate_tile(
+                    &input,
+                    &flow_dir,
+                    weights.as_deref(),
+                    reducer,
+                    row_start,
+                    row_end,
+                    &tile_inflow,
+                );
+                tx.send((row_start, row_end, acc)).unwrap();
+            }
+        });
+    }
+
+    let mut output: Array2D<f64> = Array2D::new(rows, columns, nodata, nodata)?;
+    for t in 0..num_tiles {
+        let (row_start, row_end, acc) = rx.recv().unwrap();
+        for row in row_start..row_end {
+            let offset = ((row - row_start) * columns) as usize;
+            output.set_row_data(row, acc[offset..offset + columns as usize].to_vec());
+        }
+        if verbose {
+            progress = (100.0_f64 * (t + 1) as f64 / num_tiles as f64) as usize;
+            if progress != old_progress {
+                println!("Flow accumulation: {}%", progress);
+                old_progress = progress;
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Returns the rows on the edges of a tile, i.e. its top and bottom rows.
+fn tile_edge_rows(row_start: isize, row_end: isize) -> Vec<isize> {
+    if row_end - row_start > 1 {
+        vec![row_start, row_end - 1]
+    } else {
+        vec![row_start]
+    }
+}
+
+/// Calculates the D8 accumulation within a tile spanning the rows from `row_start` to `row_end`
+/// (exclusive), considering only the flow originating within the tile and the flow entering the
+/// tile at the specified (row, column, inflow) cells. The accumulation is returned in row-major
+/// order, with NoData values for the NoData cells of the DEM.
+fn accumulate_tile(
+    input: &Raster,
+    flow_dir: &Array2D<i8>,
+    weights: Option<&Array2D<f64>>,
+    reducer: Reducer,
+    row_start: isize,
+    row_end: isize,
+    inflow: &[(isize, isize, f64)],
+) -> Vec<f64> {
+    let columns = input.configs.columns as isize;
+    let nodata = input.configs.nodata;
+    let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+    let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+    let num_cells = ((row_end - row_start) * columns) as usize;
+    let mut acc = vec![nodata; num_cells];
+    let mut num_inflowing = vec![0u8; num_cells];
+    let mut is_valid = vec![false; num_cells];
+    for row in row_start..row_end {
+        for col in 0..columns {
+            if input.get_value(row, col) != nodata {
+                let i = ((row - row_start) * columns + col) as usize;
+                acc[i] = weights.map_or(1f64, |w| w.get_value(row, col));
+                is_valid[i] = true;
+                let dir = flow_dir.get_value(row, col);
+                if dir >= 0 {
+                    let row_n = row + dy[dir as usize];
+                    let col_n = col + dx[dir as usize];
+                    if row_n >= row_start && row_n < row_end {
+                        num_inflowing[((row_n - row_start) * columns + col_n) as usize] += 1;
+                    }
+                }
+            }
+        }
+    }
+    for &(row, col, v) in inflow {
+        let i = ((row - row_start) * columns + col) as usize;
+        acc[i] = reducer.combine(acc[i], v);
+    }
+    let mut stack: Vec<usize> = (0..num_cells)
+        .filter(|i| is_valid[*i] && num_inflowing[*i] == 0)
+        .collect();
+    while let Some(i) = stack.pop() {
+        let row = i as isize / columns + row_start;
+        let col = i as isize % columns;
+        let dir = flow_dir.get_value(row, col);
+        if dir >= 0 {
+            let row_n = row + dy[dir as usize];
+            let col_n = col + dx[dir as usize];
+            if row_n >= row_start && row_n < row_end {
+                let j = ((row_n - row_start) * columns + col_n) as usize;
+                acc[j] = reducer.combine(acc[j], acc[i]);
+                num_inflowing[j] -= 1;
+                if num_inflowing[j] == 0 {
+                    stack.push(j);
+                }
+            }
+        }
+    }
+    acc
+}
+
+/// Traces the flow from each cell on the top and bottom edges of a tile, returning the cell's node
+/// in the tile-edge graph, its local accumulation, the first downslope edge cell within the tile
+/// (if any), and the edge cell in a neighbouring tile into which it flows (if any).
+fn trace_tile_edges(
+    input: &Raster,
+    flow_dir: &Array2D<i8>,
+    edge_slot: &[isize],
+    row_start: isize,
+    row_end: isize,
+    acc: &[f64],
+) -> Vec<(usize, f64, Option<usize>, Option<usize>)> {
+    let columns = input.configs.columns as isize;
+    let nodata = input.configs.nodata;
+    let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+    let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+    let node = |row: isize, col: isize| (edge_slot[row as usize] * columns + col) as usize;
+    let is_edge = |row: isize| row == row_start || row == row_end - 1;
+    let downslope = |row: isize, col: isize| -> Option<(isize, isize)> {
+        let dir = flow_dir.get_value(row, col);
+        if dir >= 0 {
+            Some((row + dy[dir as usize], col + dx[dir as usize]))
+        } else {
+            None
+        }
+    };
+
+    // the downslope edge cell of each interior cell, found by following its flowpath
+    let unknown = -2isize;
+    let mut downslope_edge = vec![unknown; ((row_end - row_start) * columns) as usize];
+    let mut path = vec![];
+    let mut ret = vec![];
+    for row in tile_edge_rows(row_start, row_end) {
+        for col in 0..columns {
+            if input.get_value(row, col) == nodata {
+                continue;
+            }
+            let mut link = None;
+            let mut outflow = None;
+            if let Some((row_n, col_n)) = downslope(row, col) {
+                if row_n < row_start || row_n >= row_end {
+                    outflow = Some(node(row_n, col_n));
+                } else if is_edge(row_n) {
+                    link = Some(node(row_n, col_n));
+                } else {
+                    path.clear();
+                    let (mut r, mut c) = (row_n, col_n);
+                    let mut target = -1isize;
+                    loop {
+                        let i = ((r - row_start) * columns + c) as usize;
+                        if downslope_edge[i] != unknown {
+                            target = downslope_edge[i];
+                            break;
+                        }
+                        path.push(i);
+                        match downslope(r, c) {
+                            Some((rn, cn)) => {
+                                if is_edge(rn) {
+                                    target = node(rn, cn) as isize;
+                                    break;
+                                }
+                                r = rn;
+                                c = cn;
+                            }
+                            None => break,
+                        }
+                    }
+                    for i in &path {
+                        downslope_edge[*i] = target;
+                    }
+                    if target >= 0 {
+                        link = Some(target as usize);
+                    }
+                }
+            }
+            let i = ((row - row_start) * columns + col) as usize;
+            ret.push((node(row, col), acc[i], link, outflow));
+        }
+    }
+    ret
+}
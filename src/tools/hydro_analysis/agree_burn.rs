@@ -0,0 +1,615 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::structures::{Array2D, BoundingBox};
+use crate::tools::*;
+use crate::vector::{ShapeType, Shapefile};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool reconditions a digital elevation model (DEM) to enforce the drainage pattern of an
+/// observed stream network using the AGREE method (Hellweger, 1997). AGREE is a widely used
+/// alternative to simple stream burning (e.g. `FillBurn`), which lowers only the stream cells and
+/// therefore creates artificial walls along the stream edges and parallel drainage within the
+/// lowered channel. Instead, AGREE combines a sharp drop at the stream cells with a smooth drop within
+/// a buffer surrounding the streams, such that the adjacent terrain gradually slopes towards the
+/// mapped channels.
+///
+/// The input streams (`--streams`) may either be a vector polyline file or a raster, in which case
+/// all non-zero, valid grid cells are treated as stream cells. Vector streams are rasterized onto the
+/// grid of the input DEM (`--dem`). The reconditioned elevations are calculated as follows:
+///
+/// 1. The smoothed elevation of each stream cell is its original elevation less the smooth drop
+///    (`--smooth_drop`).
+/// 2. Each cell within the buffer distance (`--buffer`, in map units) of a stream is assigned the
+///    elevation that is interpolated linearly between the smoothed elevation of its nearest stream
+///    cell, at a distance of zero, and its original elevation, at the buffer distance. Where the
+///    buffers of neighbouring streams overlap, the nearest stream takes precedence. By default, the
+///    buffer distance is five grid cells.
+/// 3. The stream cells are lowered by the sharp drop (`--sharp_drop`), relative to their smoothed
+///    elevations.
+///
+/// Observed hydrography frequently conflicts with the DEM, e.g. where a mapped stream crosses a
+/// road embankment or where its position is offset from the valley bottom, and burning alone can
+/// leave pits along the channel. The tool resolves these conflicts by performing a priority-flood
+/// operation constrained to the stream cells, starting at the stream outlets, i.e. the stream cells
+/// at the edges of the data, such that each stream cell has a continuous, descending flow path to an
+/// outlet along the burned channel. Where a connected network of streams does not reach the edge of
+/// the data, its lowest cell is treated as the outlet. The reconditioned DEM may still contain
+/// depressions beyond the stream network, and it should be pre-processed using either
+/// `BreachDepressions` or `FillDepressions` before flow routing.
+///
+/// # Reference
+/// Hellweger, F. (1997). AGREE - DEM surface reconditioning system. University of Texas at Austin.
+///
+/// Lindsay JB. 2016. The practice of DEM stream burning revisited. Earth Surface Processes
+/// and Landforms, 41(5): 658-668. DOI: 10.1002/esp.3888
+///
+/// # See Also
+/// `FillBurn`, `BurnStreamsAtRoads`, `BreachDepressions`
+pub struct AgreeBurn {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl AgreeBurn {
+    pub fn new() -> AgreeBurn {
+        // public constructor
+        let name = "AgreeBurn".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Reconditions a DEM to enforce mapped streams using the AGREE (Hellweger, 1997) method."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input vector or raster streams file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::RasterAndVector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Buffer Distance".to_owned(),
+            flags: vec!["--buffer".to_owned()],
+            description: "Buffer distance around the streams, in map units; by default, five grid cells.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Smooth Drop".to_owned(),
+            flags: vec!["--smooth_drop".to_owned()],
+            description: "Smooth drop of the stream cells, in elevation units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Sharp Drop".to_owned(),
+            flags: vec!["--sharp_drop".to_owned()],
+            description: "Additional sharp drop of the stream cells, in elevation units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1000.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif --streams=streams.shp -o=dem_agree.tif --buffer=50.0 --smooth_drop=5.0 --sharp_drop=100.0", short_exe, name).replace("*", &sep);
+
+        AgreeBurn {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for AgreeBurn {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--buffer".to_string(), ParameterConstraint::GreaterThan(0f64)),
+            ("--smooth_drop".to_string(), ParameterConstraint::Min(0f64)),
+            ("--sharp_drop".to_string(), ParameterConstraint::Min(0f64)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut streams_file = String::new();
+        let mut output_file = String::new();
+        let mut buffer = f64::NAN;
+        let mut smooth_drop = 10f64;
+        let mut sharp_drop = 1000f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-buffer" {
+                buffer = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-smooth_drop" {
+                smooth_drop = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-sharp_drop" {
+                sharp_drop = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        streams_file = resolve_path(&streams_file, working_directory);
+        dem_file = resolve_path(&dem_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading DEM data...")
+        };
+        let dem = Raster::new(&dem_file, "r")?;
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+        let res_x = dem.configs.resolution_x;
+        let res_y = dem.configs.resolution_y;
+        if buffer.is_nan() {
+            buffer = 5f64 * (res_x + res_y) / 2f64;
+        }
+
+        if verbose {
+            println!("Reading streams data...")
+        };
+        let mut raster_streams: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        if streams_file.to_lowercase().ends_with(".shp") {
+            let streams = Shapefile::read(&streams_file)?;
+
+            // make sure the input vector file is of lines type
+            if streams.header.shape_type.base_shape_type() != ShapeType::PolyLine {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input vector data must be of polyline base shape type.",
+                ));
+            }
+            rasterize_streams(&streams, &dem, &mut raster_streams);
+        } else {
+            let streams = Raster::new(&streams_file, "r")?;
+            if streams.configs.rows != dem.configs.rows
+                || streams.configs.columns != dem.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+            let streams_nodata = streams.configs.nodata;
+            for row in 0..rows {
+                for col in 0..columns {
+                    let z = streams.get_value(row, col);
+                    if z != streams_nodata && z != 0f64 {
+                        raster_streams.set_value(row, col, 1u8);
+                    }
+                }
+            }
+        }
+
+        let start = Instant::now();
+
+        // Find the distance from each cell within the buffer to its nearest stream
+        // cell and the smoothed elevation of that stream cell.
+        let mut distance: Array2D<f64> = Array2D::new(rows, columns, f64::INFINITY, f64::INFINITY)?;
+        let mut stream_z: Array2D<f64> = Array2D::new(rows, columns, nodata, nodata)?;
+        let buffer_rows = (buffer / res_y).floor() as isize;
+        let buffer_cols = (buffer / res_x).floor() as isize;
+        let mut num_stream_cells = 0usize;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = dem.get_value(row, col);
+                if raster_streams.get_value(row, col) == 0u8 || z == nodata {
+                    continue;
+                }
+                num_stream_cells += 1;
+                let zs = z - smooth_drop;
+                for r in (row - buffer_rows).max(0)..(row + buffer_rows + 1).min(rows) {
+                    let dy = (r - row) as f64 * res_y;
+                    for c in (col - buffer_cols).max(0)..(col + buffer_cols + 1).min(columns) {
+                        let dx = (c - col) as f64 * res_x;
+                        let dist = (dx * dx + dy * dy).sqrt();
+                        if dist <= buffer && dist < distance.get_value(r, c) {
+                            distance.set_value(r, c, dist);
+                            stream_z.set_value(r, c, zs);
+                        }
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Calculating buffer distances: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if num_stream_cells == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input streams do not overlap with the valid cells of the DEM.",
+            ));
+        }
+
+        let mut output = Raster::initialize_using_file(&output_file, &dem);
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = dem.get_value(row, col);
+                if z == nodata {
+                    continue;
+                }
+                if raster_streams.get_value(row, col) == 1u8 {
+                    output.set_value(row, col, z - smooth_drop - sharp_drop);
+                } else {
+                    let dist = distance.get_value(row, col);
+                    if dist <= buffer {
+                        let zs = stream_z.get_value(row, col);
+                        output.set_value(row, col, zs + (z - zs) * dist / buffer);
+                    } else {
+                        output.set_value(row, col, z);
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Reconditioning DEM: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Resolve conflicts between the streams and the DEM by performing a priority-flood
+        // operation along the stream cells, starting at the stream outlets.
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+        let min_val = dem.configs.minimum - smooth_drop - sharp_drop;
+        let elev_digits = ((dem.configs.maximum - min_val) as i64).to_string().len();
+        let elev_multiplier = 10.0_f64.powi((7 - elev_digits as i32).max(0));
+        let small_num = 1.0 / elev_multiplier;
+        let mut visited: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut minheap = BinaryHeap::with_capacity(num_stream_cells);
+        let mut interior_cells: Vec<(f64, isize, isize)> = vec![];
+        for row in 0..rows {
+            for col in 0..columns {
+                if raster_streams.get_value(row, col) == 0u8 || dem.get_value(row, col) == nodata
+                {
+                    continue;
+                }
+                let z = output.get_value(row, col);
+                let is_outlet = (0..8).any(|n| dem.get_value(row + dy[n], col + dx[n]) == nodata);
+                if is_outlet {
+                    minheap.push(GridCell {
+                        row,
+                        column: col,
+                        priority: z,
+                    });
+                    visited.set_value(row, col, 1u8);
+                } else {
+                    interior_cells.push((z, row, col));
+                }
+            }
+        }
+        interior_cells.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+        let mut num_raised = 0usize;
+        let mut next_seed = 0usize;
+        loop {
+            while let Some(cell) = minheap.pop() {
+                let zout = output.get_value(cell.row, cell.column);
+                for n in 0..8 {
+                    let row_n = cell.row + dy[n];
+                    let col_n = cell.column + dx[n];
+                    if raster_streams.get_value(row_n, col_n) == 1u8
+                        && visited.get_value(row_n, col_n) == 0u8
+                        && dem.get_value(row_n, col_n) != nodata
+                    {
+                        let mut zout_n = output.get_value(row_n, col_n);
+                        if zout_n < zout + small_num {
+                            // a pit or flat along the channel; raise the elevation
+                            if zout_n < zout {
+                                num_raised += 1;
+                            }
+                            zout_n = zout + small_num;
+                            output.set_value(row_n, col_n, zout_n);
+                        }
+                        visited.set_value(row_n, col_n, 1u8);
+                        minheap.push(GridCell {
+                            row: row_n,
+                            column: col_n,
+                            priority: zout_n,
+                        });
+                    }
+                }
+            }
+
+            // streams that do not reach the edge of the data drain to their lowest cell
+            while next_seed < interior_cells.len()
+                && visited.get_value(interior_cells[next_seed].1, interior_cells[next_seed].2)
+                    == 1u8
+            {
+                next_seed += 1;
+            }
+            if next_seed == interior_cells.len() {
+                break;
+            }
+            let (z, row, col) = interior_cells[next_seed];
+            visited.set_value(row, col, 1u8);
+            minheap.push(GridCell {
+                row,
+                column: col,
+                priority: z,
+            });
+        }
+
+        if verbose {
+            println!("Number of stream cells: {}", num_stream_cells);
+            println!("Stream cells raised to resolve conflicts: {}", num_raised);
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input DEM file: {}", dem_file));
+        output.add_metadata_entry(format!("Input streams file: {}", streams_file));
+        output.add_metadata_entry(format!("Buffer distance: {}", buffer));
+        output.add_metadata_entry(format!("Smooth drop: {}", smooth_drop));
+        output.add_metadata_entry(format!("Sharp drop: {}", sharp_drop));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Marks the grid cells that are intersected by the polyline streams.
+fn rasterize_streams(streams: &Shapefile, dem: &Raster, raster_streams: &mut Array2D<u8>) {
+    let mut bb = BoundingBox {
+        ..Default::default()
+    };
+    for record_num in 0..streams.num_records {
+        let record = streams.get_record(record_num);
+        for part in 0..record.num_parts as usize {
+            let start_point_in_part = record.parts[part] as usize;
+            let end_point_in_part = if part < record.num_parts as usize - 1 {
+                record.parts[part + 1] as usize - 1
+            } else {
+                record.num_points as usize - 1
+            };
+
+            for i in &[start_point_in_part, end_point_in_part] {
+                let row = dem.get_row_from_y(record.points[*i].y);
+                let col = dem.get_column_from_x(record.points[*i].x);
+                raster_streams.set_value(row, col, 1u8);
+            }
+
+            bb.initialize_to_inf();
+            for i in start_point_in_part..end_point_in_part + 1 {
+                bb.min_x = bb.min_x.min(record.points[i].x);
+                bb.max_x = bb.max_x.max(record.points[i].x);
+                bb.min_y = bb.min_y.min(record.points[i].y);
+                bb.max_y = bb.max_y.max(record.points[i].y);
+            }
+            let top_row = dem.get_row_from_y(bb.max_y);
+            let bottom_row = dem.get_row_from_y(bb.min_y);
+            let left_col = dem.get_column_from_x(bb.min_x);
+            let right_col = dem.get_column_from_x(bb.max_x);
+
+            // find each intersection with a row.
+            for row in top_row..bottom_row + 1 {
+                let row_y_coord = dem.get_y_from_row(row);
+                for i in start_point_in_part..end_point_in_part {
+                    let (y1, y2) = (record.points[i].y, record.points[i + 1].y);
+                    if is_between(row_y_coord, y1, y2) && y1 != y2 {
+                        let (x1, x2) = (record.points[i].x, record.points[i + 1].x);
+                        let x_prime = x1 + (row_y_coord - y1) / (y2 - y1) * (x2 - x1);
+                        let col = dem.get_column_from_x(x_prime);
+                        raster_streams.set_value(row, col, 1u8);
+                    }
+                }
+            }
+
+            // find each intersection with a column.
+            for col in left_col..right_col + 1 {
+                let col_x_coord = dem.get_x_from_column(col);
+                for i in start_point_in_part..end_point_in_part {
+                    let (x1, x2) = (record.points[i].x, record.points[i + 1].x);
+                    if is_between(col_x_coord, x1, x2) && x1 != x2 {
+                        let (y1, y2) = (record.points[i].y, record.points[i + 1].y);
+                        let y_prime = y1 + (col_x_coord - x1) / (x2 - x1) * (y2 - y1);
+                        let row = dem.get_row_from_y(y_prime);
+                        raster_streams.set_value(row, col, 1u8);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_between(val: f64, threshold1: f64, threshold2: f64) -> bool {
+    if val == threshold1 || val == threshold2 {
+        return true;
+    }
+    if threshold2 > threshold1 {
+        return val > threshold1 && val < threshold2;
+    }
+    val > threshold2 && val < threshold1
+}
+
+#[derive(PartialEq, Debug)]
+struct GridCell {
+    row: isize,
+    column: isize,
+    priority: f64,
+}
+
+impl Eq for GridCell {}
+
+impl PartialOrd for GridCell {
+    fn partial_cmp(&self, other: &GridCell) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GridCell {
+    fn cmp(&self, other: &GridCell) -> Ordering {
+        // reversed, such that the BinaryHeap is a min-heap
+        other
+            .priority
+            .partial_cmp(&self.priority)
+            .unwrap_or(Ordering::Equal)
+    }
+}
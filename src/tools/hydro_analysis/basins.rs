@@ -187,17 +187,11 @@ impl WhiteboxTool for Basins {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !d8_file.contains(&sep) && !d8_file.contains("/") {
-            d8_file = format!("{}{}", working_directory, d8_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        d8_file = resolve_path(&d8_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -221,20 +221,12 @@ impl WhiteboxTool for BurnStreamsAtRoads {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !streams_file.contains(&sep) && !streams_file.contains("/") {
-            streams_file = format!("{}{}", working_directory, streams_file);
-        }
-        if !dem_file.contains(&sep) && !dem_file.contains("/") {
-            dem_file = format!("{}{}", working_directory, dem_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        streams_file = resolve_path(&streams_file, working_directory);
+        dem_file = resolve_path(&dem_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading streams and roads data...")
@@ -0,0 +1,556 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::trace_region_boundaries;
+use crate::raster::*;
+use crate::structures::{Array2D, Point2D};
+use crate::tools::*;
+use crate::vector::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool can be used to audit the modifications that a hydrological conditioning operation,
+/// e.g. `FillDepressions`, `BreachDepressions`, or `BurnStreamsAtRoads`, has made to a digital
+/// elevation model (DEM). The original DEM (`--dem`) is compared with the conditioned DEM
+/// (`--conditioned`) and each modified grid cell is classified as either a *fill*, where the
+/// conditioned elevation is higher than the original, or a *breach*, where it is lower. Only
+/// changes with magnitudes greater than the change threshold (`--threshold`) are considered, which
+/// can be used to exclude the small elevation increments that are applied to flat areas by some
+/// conditioning methods.
+///
+/// Modified cells of the same type are grouped into zones using eight-cell connectivity and the
+/// zones are vectorized into an output polygon file (`--output`). The attribute table of the output
+/// file contains the following statistics for each zone, which are useful for reviewing the
+/// conditioned DEM and reporting on the conditioning process, e.g. to identify large fills
+/// upstream of road embankments that may indicate missing culverts:
+///
+/// | Field      | Description                                                  |
+/// |------------|--------------------------------------------------------------|
+/// | TYPE       | The modification type, either 'fill' or 'breach'             |
+/// | NUM_CELLS  | The number of grid cells in the zone                         |
+/// | AREA       | The zone area                                                |
+/// | VOLUME     | The volume of material added (fill) or removed (breach)      |
+/// | MEAN_DIFF  | The mean absolute elevation change                           |
+/// | MAX_DIFF   | The maximum absolute elevation change                        |
+/// | MIN_ELEV   | The minimum original elevation within the zone               |
+/// | MAX_ELEV   | The maximum original elevation within the zone               |
+///
+/// Areas and volumes are measured in map units or, for DEMs in geographic coordinates, in square
+/// metres and elevation units multiplied by square metres respectively. Optionally, a raster
+/// (`--zones`) may be output, in which each modified cell is assigned the FID of its zone in the
+/// output vector file. A summary of the total number of zones and the total fill and breach
+/// volumes is also reported.
+///
+/// # See Also
+/// `FillDepressions`, `BreachDepressions`, `DepthInSink`, `BurnStreamsAtRoads`
+pub struct ConditioningAudit {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ConditioningAudit {
+    pub fn new() -> ConditioningAudit {
+        // public constructor
+        let name = "ConditioningAudit".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Compares an original and a hydrologically conditioned DEM, mapping the fill and breach zones."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Original DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster DEM file, prior to conditioning.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Conditioned DEM File".to_owned(),
+            flags: vec!["--conditioned".to_owned()],
+            description: "Input hydrologically conditioned raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Zones File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector polygon file of modified zones.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Zones Raster File (optional)".to_owned(),
+            flags: vec!["--zones".to_owned()],
+            description: "Optional output raster file of zone identifiers.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Change Threshold".to_owned(),
+            flags: vec!["--threshold".to_owned()],
+            description: "Minimum absolute elevation change for a cell to be considered modified.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem='dem.tif' --conditioned='breached.tif' -o='audit.shp' --zones='zones.tif' --threshold=0.01", short_exe, name).replace("*", &sep);
+
+        ConditioningAudit {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ConditioningAudit {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut conditioned_file = String::new();
+        let mut output_file = String::new();
+        let mut zones_file = String::new();
+        let mut threshold = 0f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-conditioned" {
+                conditioned_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-zones" {
+                zones_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-threshold" {
+                threshold = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(&dem_file, working_directory);
+        conditioned_file = resolve_path(&conditioned_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        zones_file = resolve_path(&zones_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let dem = Raster::new(&dem_file, "r")?;
+        let conditioned = Raster::new(&conditioned_file, "r")?;
+
+        let start = Instant::now();
+
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+        let cond_nodata = conditioned.configs.nodata;
+
+        // make sure the input files have the same size
+        if conditioned.configs.rows != dem.configs.rows
+            || conditioned.configs.columns != dem.configs.columns
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must have the same number of rows and columns and spatial extent.",
+            ));
+        }
+
+        // classify the modified cells; 1 = fill, 2 = breach
+        let mut modification: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = dem.get_value(row, col);
+                let zc = conditioned.get_value(row, col);
+                if z != nodata && zc != cond_nodata {
+                    if zc - z > threshold {
+                        modification.set_value(row, col, 1u8);
+                    } else if z - zc > threshold {
+                        modification.set_value(row, col, 2u8);
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Classifying modifications: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // group the modified cells into zones and calculate the zone statistics
+        let is_geographic = dem.is_in_geographic_coordinates();
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+        let mut labels: Array2D<i32> = Array2D::new(rows, columns, -1, -1)?;
+        let mut zones: Vec<Zone> = vec![];
+        let mut stack = vec![];
+        let cell_area = |row: isize| -> f64 {
+            if is_geographic {
+                let lat = dem.get_y_from_row(row).to_radians();
+                dem.configs.resolution_x * dem.configs.resolution_y * 111319.49f64 * 111319.49f64 * lat.cos()
+            } else {
+                dem.configs.resolution_x * dem.configs.resolution_y
+            }
+        };
+        for row in 0..rows {
+            for col in 0..columns {
+                let zone_type = modification.get_value(row, col);
+                if zone_type == 0 || labels.get_value(row, col) != -1 {
+                    continue;
+                }
+                let label = zones.len() as i32;
+                let mut zone = Zone::new(zone_type);
+                labels.set_value(row, col, label);
+                stack.push((row, col));
+                while let Some((r, c)) = stack.pop() {
+                    let z = dem.get_value(r, c);
+                    let diff = (conditioned.get_value(r, c) - z).abs();
+                    zone.add_cell(r, c, z, diff, cell_area(r));
+                    for n in 0..8 {
+                        let (rn, cn) = (r + dy[n], c + dx[n]);
+                        if rn >= 0
+                            && cn >= 0
+                            && rn < rows
+                            && cn < columns
+                            && modification.get_value(rn, cn) == zone_type
+                            && labels.get_value(rn, cn) == -1
+                        {
+                            labels.set_value(rn, cn, label);
+                            stack.push((rn, cn));
+                        }
+                    }
+                }
+                zones.push(zone);
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Identifying zones: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // vectorize the zones
+        let west = dem.configs.west;
+        let north = dem.configs.north;
+        let res_x = dem.configs.resolution_x;
+        let res_y = dem.configs.resolution_y;
+        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        output.projection = dem.configs.coordinate_ref_system_wkt.clone();
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("TYPE", FieldDataType::Text, 6u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("NUM_CELLS", FieldDataType::Int, 9u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("AREA", FieldDataType::Real, 14u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("VOLUME", FieldDataType::Real, 14u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("MEAN_DIFF", FieldDataType::Real, 12u8, 4u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("MAX_DIFF", FieldDataType::Real, 12u8, 4u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("MIN_ELEV", FieldDataType::Real, 12u8, 4u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("MAX_ELEV", FieldDataType::Real, 12u8, 4u8));
+
+        let num_zones = zones.len();
+        for (i, zone) in zones.iter().enumerate() {
+            let label = i as i32;
+            let rings = trace_region_boundaries(
+                zone.max_row - zone.min_row + 1,
+                zone.max_col - zone.min_col + 1,
+                |r, c| labels.get_value(r + zone.min_row, c + zone.min_col) == label,
+            );
+            let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+            for ring in &rings {
+                let points: Vec<Point2D> = ring
+                    .iter()
+                    .map(|p| {
+                        Point2D::new(
+                            west + (p.1 + zone.min_col) as f64 * res_x,
+                            north - (p.0 + zone.min_row) as f64 * res_y,
+                        )
+                    })
+                    .collect();
+                sfg.add_part(&points);
+            }
+            output.add_record(sfg);
+            output.attributes.add_record(
+                vec![
+                    FieldData::Int(label + 1),
+                    FieldData::Text(
+                        if zone.zone_type == 1 { "fill" } else { "breach" }.to_string(),
+                    ),
+                    FieldData::Int(zone.num_cells as i32),
+                    FieldData::Real(zone.area),
+                    FieldData::Real(zone.volume),
+                    FieldData::Real(zone.sum_diff / zone.num_cells as f64),
+                    FieldData::Real(zone.max_diff),
+                    FieldData::Real(zone.min_elev),
+                    FieldData::Real(zone.max_elev),
+                ],
+                false,
+            );
+
+            if verbose {
+                progress = (100.0_f64 * i as f64 / (num_zones - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Vectorizing zones: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            let summarize = |zone_type: u8| -> (usize, f64, f64) {
+                zones
+                    .iter()
+                    .filter(|z| z.zone_type == zone_type)
+                    .fold((0, 0f64, 0f64), |acc, z| {
+                        (acc.0 + 1, acc.1 + z.area, acc.2 + z.volume)
+                    })
+            };
+            let (num_fills, fill_area, fill_volume) = summarize(1u8);
+            let (num_breaches, breach_area, breach_volume) = summarize(2u8);
+            println!(
+                "Fill zones: {} (area: {:.3}, volume: {:.3})",
+                num_fills, fill_area, fill_volume
+            );
+            println!(
+                "Breach zones: {} (area: {:.3}, volume: {:.3})",
+                num_breaches, breach_area, breach_volume
+            );
+            println!("Saving data...")
+        };
+        if zones.is_empty() {
+            println!("Warning: No modified cells were found in the conditioned DEM. The output file will not be written.")
+        } else {
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if !zones_file.is_empty() {
+            let mut zones_raster = Raster::initialize_using_file(&zones_file, &dem);
+            let out_nodata = -32768f64;
+            zones_raster.configs.nodata = out_nodata;
+            zones_raster.reinitialize_values(out_nodata);
+            for row in 0..rows {
+                for col in 0..columns {
+                    let label = labels.get_value(row, col);
+                    if label >= 0 {
+                        zones_raster.set_value(row, col, (label + 1) as f64);
+                    }
+                }
+            }
+            zones_raster.configs.data_type = DataType::I32;
+            zones_raster.configs.palette = "qual.plt".to_string();
+            zones_raster.configs.photometric_interp = PhotometricInterpretation::Categorical;
+            zones_raster.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            zones_raster.add_metadata_entry(format!("Original DEM file: {}", dem_file));
+            zones_raster.add_metadata_entry(format!("Conditioned DEM file: {}", conditioned_file));
+            zones_raster.add_metadata_entry(format!("Change threshold: {}", threshold));
+            zones_raster.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+            let _ = match zones_raster.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Zones raster written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The summary statistics of a connected zone of modified cells.
+struct Zone {
+    zone_type: u8,
+    num_cells: usize,
+    area: f64,
+    volume: f64,
+    sum_diff: f64,
+    max_diff: f64,
+    min_elev: f64,
+    max_elev: f64,
+    min_row: isize,
+    max_row: isize,
+    min_col: isize,
+    max_col: isize,
+}
+
+impl Zone {
+    fn new(zone_type: u8) -> Zone {
+        Zone {
+            zone_type: zone_type,
+            num_cells: 0,
+            area: 0f64,
+            volume: 0f64,
+            sum_diff: 0f64,
+            max_diff: f64::NEG_INFINITY,
+            min_elev: f64::INFINITY,
+            max_elev: f64::NEG_INFINITY,
+            min_row: isize::MAX,
+            max_row: isize::MIN,
+            min_col: isize::MAX,
+            max_col: isize::MIN,
+        }
+    }
+
+    fn add_cell(&mut self, row: isize, col: isize, z: f64, diff: f64, cell_area: f64) {
+        self.num_cells += 1;
+        self.area += cell_area;
+        self.volume += diff * cell_area;
+        self.sum_diff += diff;
+        self.max_diff = self.max_diff.max(diff);
+        self.min_elev = self.min_elev.min(z);
+        self.max_elev = self.max_elev.max(z);
+        self.min_row = self.min_row.min(row);
+        self.max_row = self.max_row.max(row);
+        self.min_col = self.min_col.min(col);
+        self.max_col = self.max_col.max(col);
+    }
+}
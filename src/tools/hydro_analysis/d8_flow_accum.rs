@@ -2,21 +2,20 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 26/016/2017
-Last Modified: 18/10/2019
+Last Modified: 17/10/2026
 License: MIT
 */
 
+use super::accumulation_engine::{
+    d8_flow_directions, upslope_accumulation, D8TieBreaking, FlowDirections, Reducer,
+};
 use crate::raster::*;
-use crate::structures::Array2D;
 use crate::tools::*;
-use num_cpus;
 use std::env;
 use std::f64;
 use std::io::{Error, ErrorKind};
 use std::path;
-use std::sync::mpsc;
 use std::sync::Arc;
-use std::thread;
 
 /// This tool is used to generate a flow accumulation grid (i.e. catchment area) using the 
 /// D8 (O'Callaghan and Mark, 1984) algorithm. This algorithm is an example of single-flow-direction 
@@ -42,8 +41,25 @@ use std::thread;
 /// Grid cells possessing the **NoData** value in the input flow-pointer grid are assigned the **NoData** 
 /// value in the output flow-accumulation image.
 /// 
+/// When two or more downslope neighbours are equally steep, the flow is, by default, directed to the 
+/// first of them clockwise from the north-east (`--tie_break=first`), which produces grid-aligned 
+/// flowpaths on planar surfaces. Ties may instead be resolved by selecting one of the tied neighbours 
+/// pseudo-randomly (`--tie_break=random`), in which case the same `--seed` always produces the same 
+/// output, or by selecting the tied neighbour closest to the aspect of the cell (`--tie_break=aspect`).
+/// 
+/// The flow accumulation is calculated in parallel. The DEM is divided into tiles and the 
+/// accumulation within each tile is calculated independently, before the flow that is exchanged 
+/// between neighbouring tiles is resolved using a graph of the cells along the tile edges, which 
+/// is much smaller than the grid (Barnes, 2017). The tool therefore scales well with the number of 
+/// available processors, which is important for very large DEMs. For the same reason, the DEM and
+/// output are held in memory in their native data types, e.g. four bytes per cell for 32-bit DEMs.
+/// 
+/// # Reference
+/// Barnes, R. (2017). Parallel non-divergent flow accumulation for trillion cell digital elevation 
+/// models on desktops or clusters. *Environmental Modelling & Software*, 92, 202-212.
+/// 
 /// # See Also:
-/// `DInfFlowAccumulation`, `BreachDepressions`, `FillDepressions`
+/// `DInfFlowAccumulation`, `BreachDepressions`, `FillDepressions`, `FindPitsAndFlats`
 pub struct D8FlowAccumulation {
     name: String,
     description: String,
@@ -105,6 +121,8 @@ impl D8FlowAccumulation {
             optional: true,
         });
 
+        parameters.extend(D8TieBreaking::parameters());
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -117,7 +135,8 @@ impl D8FlowAccumulation {
             short_exe += ".exe";
         }
         let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=output.tif --out_type='cells'
->>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=output.tif --out_type='specific catchment area' --log --clip", short_exe, name).replace("*", &sep);
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=output.tif --out_type='specific catchment area' --log --clip
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=output.tif --tie_break=random --seed=42", short_exe, name).replace("*", &sep);
 
         D8FlowAccumulation {
             name: name,
@@ -157,6 +176,10 @@ impl WhiteboxTool for D8FlowAccumulation {
         self.toolbox.clone()
     }
 
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_stem}_d8_fa{input_ext}".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -168,6 +191,8 @@ impl WhiteboxTool for D8FlowAccumulation {
         let mut out_type = String::from("sca");
         let mut log_transform = false;
         let mut clip_max = false;
+        let mut tie_break = String::from("first");
+        let mut seed = 0u64;
 
         if args.len() == 0 {
             return Err(Error::new(
@@ -221,8 +246,22 @@ impl WhiteboxTool for D8FlowAccumulation {
                 if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
                     clip_max = true;
                 }
+            } else if vec[0].to_lowercase() == "-tie_break" || vec[0].to_lowercase() == "--tie_break"
+            {
+                if keyval {
+                    tie_break = vec[1].to_string();
+                } else {
+                    tie_break = args[i + 1].to_string();
+                }
+            } else if vec[0].to_lowercase() == "-seed" || vec[0].to_lowercase() == "--seed" {
+                seed = if keyval {
+                    vec[1].to_string().parse::<u64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<u64>().unwrap()
+                };
             }
         }
+        let tie_breaking = D8TieBreaking::new(&tie_break, seed)?;
 
         if verbose {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
@@ -230,205 +269,32 @@ impl WhiteboxTool for D8FlowAccumulation {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
         };
 
-        let input = Arc::new(Raster::new(&input_file, "r")?);
+        let input = Arc::new(Raster::new_native(&input_file, "r")?);
 
         // calculate the flow direction
         let start = Instant::now();
         let rows = input.configs.rows as isize;
         let columns = input.configs.columns as isize;
-        let num_cells = rows * columns;
         let nodata = input.configs.nodata;
         let cell_size_x = input.configs.resolution_x;
         let cell_size_y = input.configs.resolution_y;
-        let diag_cell_size = (cell_size_x * cell_size_x + cell_size_y * cell_size_y).sqrt();
-
-        let mut flow_dir: Array2D<i8> = Array2D::new(rows, columns, -1, -1)?;
-        let num_procs = num_cpus::get() as isize;
-        let (tx, rx) = mpsc::channel();
-        for tid in 0..num_procs {
-            let input = input.clone();
-            let tx = tx.clone();
-            thread::spawn(move || {
-                let nodata = input.configs.nodata;
-                let dx = [1, 1, 1, 0, -1, -1, -1, 0];
-                let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
-                let grid_lengths = [
-                    diag_cell_size,
-                    cell_size_x,
-                    diag_cell_size,
-                    cell_size_y,
-                    diag_cell_size,
-                    cell_size_x,
-                    diag_cell_size,
-                    cell_size_y,
-                ];
-                let (mut z, mut z_n): (f64, f64);
-                let (mut max_slope, mut slope): (f64, f64);
-                let mut dir: i8;
-                let mut neighbouring_nodata: bool;
-                let mut interior_pit_found = false;
-                for row in (0..rows).filter(|r| r % num_procs == tid) {
-                    let mut data: Vec<i8> = vec![-1i8; columns as usize];
-                    for col in 0..columns {
-                        z = input[(row, col)];
-                        if z != nodata {
-                            dir = 0i8;
-                            max_slope = f64::MIN;
-                            neighbouring_nodata = false;
-                            for i in 0..8 {
-                                z_n = input[(row + dy[i], col + dx[i])];
-                                if z_n != nodata {
-                                    slope = (z - z_n) / grid_lengths[i];
-                                    if slope > max_slope && slope > 0f64 {
-                                        max_slope = slope;
-                                        dir = i as i8;
-                                    }
-                                } else {
-                                    neighbouring_nodata = true;
-                                }
-                            }
-                            if max_slope >= 0f64 {
-                                data[col as usize] = dir;
-                            } else {
-                                data[col as usize] = -1i8;
-                                if !neighbouring_nodata {
-                                    interior_pit_found = true;
-                                }
-                            }
-                        } else {
-                            data[col as usize] = -1i8;
-                        }
-                    }
-                    tx.send((row, data, interior_pit_found)).unwrap();
-                }
-            });
-        }
 
-        let mut interior_pit_found = false;
-        for r in 0..rows {
-            let (row, data, pit) = rx.recv().unwrap();
-            flow_dir.set_row_data(row, data); //(data.0, data.1);
-            if pit {
-                interior_pit_found = true;
-            }
-            if verbose {
-                progress = (100.0_f64 * r as f64 / (rows - 1) as f64) as usize;
-                if progress != old_progress {
-                    println!("Flow directions: {}%", progress);
-                    old_progress = progress;
-                }
-            }
-        }
+        let (flow_dir, interior_pit_found) = d8_flow_directions(&input, tie_breaking, verbose)?;
 
-        // calculate the number of inflowing cells
+        // The flow accumulation is calculated in parallel using a tiled algorithm (Barnes, 2017).
         let flow_dir = Arc::new(flow_dir);
-        let mut num_inflowing: Array2D<i8> = Array2D::new(rows, columns, -1, -1)?;
-
-        let (tx, rx) = mpsc::channel();
-        for tid in 0..num_procs {
-            let input = input.clone();
-            let flow_dir = flow_dir.clone();
-            let tx = tx.clone();
-            thread::spawn(move || {
-                let dx = [1, 1, 1, 0, -1, -1, -1, 0];
-                let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
-                let inflowing_vals: [i8; 8] = [4, 5, 6, 7, 0, 1, 2, 3];
-                let mut z: f64;
-                let mut count: i8;
-                for row in (0..rows).filter(|r| r % num_procs == tid) {
-                    let mut data: Vec<i8> = vec![-1i8; columns as usize];
-                    for col in 0..columns {
-                        z = input[(row, col)];
-                        if z != nodata {
-                            count = 0i8;
-                            for i in 0..8 {
-                                if flow_dir[(row + dy[i], col + dx[i])] == inflowing_vals[i] {
-                                    count += 1;
-                                }
-                            }
-                            data[col as usize] = count;
-                        } else {
-                            data[col as usize] = -1i8;
-                        }
-                    }
-                    tx.send((row, data)).unwrap();
-                }
-            });
-        }
-
-        let mut output = Raster::initialize_using_file(&output_file, &input);
-        output.reinitialize_values(1.0);
-        let mut stack = Vec::with_capacity((rows * columns) as usize);
-        let mut num_solved_cells = 0;
-        for r in 0..rows {
-            let (row, data) = rx.recv().unwrap();
-            num_inflowing.set_row_data(row, data);
-            for col in 0..columns {
-                if num_inflowing[(row, col)] == 0i8 {
-                    stack.push((row, col));
-                } else if num_inflowing[(row, col)] == -1i8 {
-                    num_solved_cells += 1;
-                }
-            }
-
-            if verbose {
-                progress = (100.0_f64 * r as f64 / (rows - 1) as f64) as usize;
-                if progress != old_progress {
-                    println!("Num. inflowing neighbours: {}%", progress);
-                    old_progress = progress;
-                }
-            }
-        }
-
-        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
-        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
-        let (mut row, mut col): (isize, isize);
-        let (mut row_n, mut col_n): (isize, isize);
-        // let mut cell: (isize, isize);
-        let mut dir: i8;
-        let mut fa: f64;
-        while !stack.is_empty() {
-            let cell = stack.pop().unwrap();
-            row = cell.0;
-            col = cell.1;
-            fa = output[(row, col)];
-            num_inflowing.decrement(row, col, 1i8);
-            dir = flow_dir[(row, col)];
-            if dir >= 0 {
-                row_n = row + dy[dir as usize];
-                col_n = col + dx[dir as usize];
-                output.increment(row_n, col_n, fa);
-                num_inflowing.decrement(row_n, col_n, 1i8);
-                if num_inflowing.get_value(row_n, col_n) == 0i8 {
-                    stack.push((row_n, col_n));
-                }
-            }
-
-            if verbose {
-                num_solved_cells += 1;
-                progress = (100.0_f64 * num_solved_cells as f64 / (num_cells - 1) as f64) as usize;
-                if progress != old_progress {
-                    println!("Flow accumulation: {}%", progress);
-                    old_progress = progress;
-                }
-            }
-        }
+        let flow_dirs = FlowDirections::D8(flow_dir.clone());
+        let acc = upslope_accumulation(&input, &flow_dirs, None, Reducer::Sum, verbose)?;
 
         let mut cell_area = cell_size_x * cell_size_y;
         // if flow width is allowed to vary by direction, the flow accumulation output will not
@@ -464,56 +330,36 @@ impl WhiteboxTool for D8FlowAccumulation {
             flow_widths = [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
         }
 
-        if log_transform {
-            for row in 0..rows {
-                for col in 0..columns {
-                    if input[(row, col)] == nodata {
-                        output[(row, col)] = nodata;
+        // The values are stored in the output's data type, since each is set only once. The output
+        // is real-valued, whatever the data type of the DEM.
+        let mut output = Raster::initialize_using_file(&output_file, &input);
+        output.configs.data_type = DataType::F32;
+        output.set_native_storage();
+        for row in 0..rows {
+            for col in 0..columns {
+                if input.get_value(row, col) == nodata {
+                    output.set_value(row, col, nodata);
+                } else {
+                    let dir = flow_dir[(row, col)];
+                    let flow_width = if dir >= 0 {
+                        flow_widths[dir as usize]
                     } else {
-                        let dir = flow_dir[(row, col)];
-                        if dir >= 0 {
-                            output[(row, col)] =
-                                (output[(row, col)] * cell_area / flow_widths[dir as usize]).ln();
-                        } else {
-                            output[(row, col)] =
-                                (output[(row, col)] * cell_area / flow_widths[3]).ln();
-                        }
-                    }
-                }
-
-                if verbose {
-                    progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
-                    if progress != old_progress {
-                        println!("Correcting values: {}%", progress);
-                        old_progress = progress;
-                    }
+                        flow_widths[3]
+                    };
+                    let value = acc.get_value(row, col) * cell_area / flow_width;
+                    output.set_value(row, col, if log_transform { value.ln() } else { value });
                 }
             }
-        } else {
-            for row in 0..rows {
-                for col in 0..columns {
-                    if input[(row, col)] == nodata {
-                        output[(row, col)] = nodata;
-                    } else {
-                        let dir = flow_dir[(row, col)];
-                        if dir >= 0 {
-                            output[(row, col)] =
-                                output[(row, col)] * cell_area / flow_widths[dir as usize];
-                        } else {
-                            output[(row, col)] = output[(row, col)] * cell_area / flow_widths[3];
-                        }
-                    }
-                }
 
-                if verbose {
-                    progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
-                    if progress != old_progress {
-                        println!("Correcting values: {}%", progress);
-                        old_progress = progress;
-                    }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Correcting values: {}%", progress);
+                    old_progress = progress;
                 }
             }
         }
+        drop(acc);
 
         output.configs.palette = "blueyellow.plt".to_string();
         if clip_max {
@@ -548,7 +394,7 @@ impl WhiteboxTool for D8FlowAccumulation {
             println!("**********************************************************************************");
             println!("WARNING: Interior pit cells were found within the input DEM. It is likely that the 
             DEM needs to be processed to remove topographic depressions and flats prior to
-            running this tool.");
+            running this tool. The FindPitsAndFlats tool can be used to locate the pits and flats.");
             println!("**********************************************************************************");
         }
 
@@ -142,6 +142,10 @@ impl WhiteboxTool for D8Pointer {
         self.toolbox.clone()
     }
 
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_stem}_d8_pntr{input_ext}".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -198,17 +202,11 @@ impl WhiteboxTool for D8Pointer {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -2,17 +2,17 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 24/06/2017
-Last Modified: 18/10/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
+use super::accumulation_engine::dinf_flow_directions;
 use crate::raster::*;
 use crate::structures::Array2D;
 use crate::tools::*;
 use num_cpus;
 use std::env;
 use std::f64;
-use std::f64::consts::PI;
 use std::io::{Error, ErrorKind};
 use std::path;
 use std::sync::mpsc;
@@ -178,6 +178,10 @@ impl WhiteboxTool for DInfFlowAccumulation {
         self.toolbox.clone()
     }
 
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_stem}_dinf_fa{input_ext}".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -260,17 +264,11 @@ impl WhiteboxTool for DInfFlowAccumulation {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -281,144 +279,12 @@ impl WhiteboxTool for DInfFlowAccumulation {
         let start = Instant::now();
         let rows = input.configs.rows as isize;
         let columns = input.configs.columns as isize;
-        let num_cells = rows * columns;
         let nodata = input.configs.nodata;
-        let cell_size_x = input.configs.resolution_x;
-        let cell_size_y = input.configs.resolution_y;
-        let diag_cell_size = (cell_size_x * cell_size_x + cell_size_y * cell_size_y).sqrt();
+        let num_cells = rows * columns;
 
         // calculate the flow directions
-        let mut flow_dir: Array2D<f64> = Array2D::new(rows, columns, nodata, nodata)?;
-
+        let (flow_dir, interior_pit_found) = dinf_flow_directions(&input, verbose)?;
         let num_procs = num_cpus::get() as isize;
-        let (tx, rx) = mpsc::channel();
-        for tid in 0..num_procs {
-            let input = input.clone();
-            let tx = tx.clone();
-            thread::spawn(move || {
-                let nodata = input.configs.nodata;
-                let grid_res = (cell_size_x + cell_size_y) / 2.0;
-                let mut dir: f64;
-                let mut max_slope: f64;
-                let mut e0: f64;
-                let mut af: f64;
-                let mut ac: f64;
-                let (mut e1, mut r, mut s1, mut s2, mut s, mut e2): (f64, f64, f64, f64, f64, f64);
-
-                let ac_vals = [0f64, 1f64, 1f64, 2f64, 2f64, 3f64, 3f64, 4f64];
-                let af_vals = [1f64, -1f64, 1f64, -1f64, 1f64, -1f64, 1f64, -1f64];
-
-                let e1_col = [1, 0, 0, -1, -1, 0, 0, 1];
-                let e1_row = [0, -1, -1, 0, 0, 1, 1, 0];
-
-                let e2_col = [1, 1, -1, -1, -1, -1, 1, 1];
-                let e2_row = [-1, -1, -1, -1, 1, 1, 1, 1];
-
-                let atanof1 = 1.0f64.atan();
-
-                let mut neighbouring_nodata: bool;
-                let mut interior_pit_found = false;
-                for row in (0..rows).filter(|r| r % num_procs == tid) {
-                    let mut data: Vec<f64> = vec![nodata; columns as usize];
-                    for col in 0..columns {
-                        e0 = input[(row, col)];
-                        if e0 != nodata {
-                            dir = 360.0;
-                            max_slope = f64::MIN;
-                            neighbouring_nodata = false;
-                            for i in 0..8 {
-                                ac = ac_vals[i];
-                                af = af_vals[i];
-                                e1 = input[(row + e1_row[i], col + e1_col[i])];
-                                e2 = input[(row + e2_row[i], col + e2_col[i])];
-                                if e1 != nodata && e2 != nodata {
-                                    if e0 > e1 && e0 > e2 {
-                                        s1 = (e0 - e1) / grid_res;
-                                        if s1 == 0.0 {
-                                            s1 = 0.00001;
-                                        }
-                                        s2 = (e1 - e2) / grid_res;
-                                        r = (s2 / s1).atan();
-                                        s = (s1 * s1 + s2 * s2).sqrt();
-                                        if s1 < 0.0 && s2 < 0.0 {
-                                            s = -1.0 * s;
-                                        }
-                                        if s1 < 0.0 && s2 == 0.0 {
-                                            s = -1.0 * s;
-                                        }
-                                        if s1 == 0.0 && s2 < 0.0 {
-                                            s = -1.0 * s;
-                                        }
-                                        if s1 == 0.001 && s2 < 0.0 {
-                                            s = -1.0 * s;
-                                        }
-                                        if r < 0.0 || r > atanof1 {
-                                            if r < 0.0 {
-                                                r = 0.0;
-                                                s = s1;
-                                            } else {
-                                                r = atanof1;
-                                                s = (e0 - e2) / diag_cell_size;
-                                            }
-                                        }
-                                        if s >= max_slope && s != 0.00001 {
-                                            max_slope = s;
-                                            dir = af * r + ac * (PI / 2.0);
-                                        }
-                                    } else if e0 > e1 || e0 > e2 {
-                                        if e0 > e1 {
-                                            r = 0.0;
-                                            s = (e0 - e1) / grid_res;
-                                        } else {
-                                            r = atanof1;
-                                            s = (e0 - e2) / diag_cell_size;
-                                        }
-                                        if s >= max_slope && s != 0.00001 {
-                                            max_slope = s;
-                                            dir = af * r + ac * (PI / 2.0);
-                                        }
-                                    }
-                                } else {
-                                    neighbouring_nodata = true;
-                                }
-                            }
-
-                            if max_slope > 0f64 {
-                                dir = 360.0 - dir.to_degrees() + 90.0;
-                                if dir > 360.0 {
-                                    dir = dir - 360.0;
-                                }
-                                data[col as usize] = dir;
-                            } else {
-                                data[col as usize] = -1f64;
-                                if !neighbouring_nodata {
-                                    interior_pit_found = true;
-                                }
-                            }
-                        } else {
-                            data[col as usize] = -1f64;
-                        }
-                    }
-                    tx.send((row, data, interior_pit_found)).unwrap();
-                }
-            });
-        }
-
-        let mut interior_pit_found = false;
-        for r in 0..rows {
-            let (row, data, pit) = rx.recv().unwrap();
-            flow_dir.set_row_data(row, data);
-            if pit {
-                interior_pit_found = true;
-            }
-            if verbose {
-                progress = (100.0_f64 * r as f64 / (rows - 1) as f64) as usize;
-                if progress != old_progress {
-                    println!("Flow directions: {}%", progress);
-                    old_progress = progress;
-                }
-            }
-        }
 
         // calculate the number of inflowing cells
         let flow_dir = Arc::new(flow_dir);
@@ -221,26 +221,14 @@ impl WhiteboxTool for DInfMassFlux {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !loading_file.contains(&sep) && !loading_file.contains("/") {
-            loading_file = format!("{}{}", working_directory, loading_file);
-        }
-        if !efficiency_file.contains(&sep) && !efficiency_file.contains("/") {
-            efficiency_file = format!("{}{}", working_directory, efficiency_file);
-        }
-        if !absorption_file.contains(&sep) && !absorption_file.contains("/") {
-            absorption_file = format!("{}{}", working_directory, absorption_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        loading_file = resolve_path(&loading_file, working_directory);
+        efficiency_file = resolve_path(&efficiency_file, working_directory);
+        absorption_file = resolve_path(&absorption_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
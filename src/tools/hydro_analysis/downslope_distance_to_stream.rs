@@ -2,21 +2,18 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 9/07/2017
-Last Modified: 04/10/2019
+Last Modified: 17/10/2026
 License: MIT
 */
 
+use super::accumulation_engine::{d8_flow_directions, D8TieBreaking};
 use crate::raster::*;
-use crate::structures::Array2D;
 use crate::tools::*;
-use num_cpus;
 use std::env;
 use std::f64;
 use std::io::{Error, ErrorKind};
 use std::path;
-use std::sync::mpsc;
 use std::sync::Arc;
-use std::thread;
 
 /// This tool can be used to calculate the distance from each grid cell in a raster to the nearest stream cell, 
 /// measured along the downslope flowpath. The user must specify the name of an input digital elevation model (`--dem`) 
@@ -26,6 +23,11 @@ use std::thread;
 /// non-zero values. The output of this tool, along with the `ElevationAboveStream` tool, can be useful for preliminary 
 /// flood plain mapping when combined with high-accuracy DEM data. 
 /// 
+/// Ties between equally steep downslope neighbours in the D8 flow directions are resolved using the 
+/// `--tie_break` method, i.e. `first` (default), the first of the neighbours clockwise from the 
+/// north-east, `random`, a pseudo-random selection that is reproducible for a given `--seed`, or 
+/// `aspect`, the neighbour closest to the aspect of the cell. See `D8FlowAccumulation` for details.
+/// 
 /// # See Also
 /// `ElevationAboveStream`, `DistanceToOutlet`
 pub struct DownslopeDistanceToStream {
@@ -71,6 +73,8 @@ impl DownslopeDistanceToStream {
             optional: false,
         });
 
+        parameters.extend(D8TieBreaking::parameters());
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -131,6 +135,8 @@ impl WhiteboxTool for DownslopeDistanceToStream {
         let mut dem_file = String::new();
         let mut streams_file = String::new();
         let mut output_file = String::new();
+        let mut tie_break = String::from("first");
+        let mut seed = 0u64;
 
         if args.len() == 0 {
             return Err(Error::new(
@@ -165,8 +171,22 @@ impl WhiteboxTool for DownslopeDistanceToStream {
                 } else {
                     output_file = args[i + 1].to_string();
                 }
+            } else if vec[0].to_lowercase() == "-tie_break" || vec[0].to_lowercase() == "--tie_break"
+            {
+                if keyval {
+                    tie_break = vec[1].to_string();
+                } else {
+                    tie_break = args[i + 1].to_string();
+                }
+            } else if vec[0].to_lowercase() == "-seed" || vec[0].to_lowercase() == "--seed" {
+                seed = if keyval {
+                    vec[1].to_string().parse::<u64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<u64>().unwrap()
+                };
             }
         }
+        let tie_breaking = D8TieBreaking::new(&tie_break, seed)?;
 
         if verbose {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
@@ -174,20 +194,12 @@ impl WhiteboxTool for DownslopeDistanceToStream {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !dem_file.contains(&sep) && !dem_file.contains("/") {
-            dem_file = format!("{}{}", working_directory, dem_file);
-        }
-        if !streams_file.contains(&sep) && !streams_file.contains("/") {
-            streams_file = format!("{}{}", working_directory, streams_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        dem_file = resolve_path(&dem_file, working_directory);
+        streams_file = resolve_path(&streams_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading DEM data...")
@@ -207,7 +219,6 @@ impl WhiteboxTool for DownslopeDistanceToStream {
         let cell_size_x = dem.configs.resolution_x;
         let cell_size_y = dem.configs.resolution_y;
         let diag_cell_size = (cell_size_x * cell_size_x + cell_size_y * cell_size_y).sqrt();
-        let flow_nodata = -2i8;
         let dx = [1, 1, 1, 0, -1, -1, -1, 0];
         let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
         let inflowing_vals = [4i8, 5i8, 6i8, 7i8, 0i8, 1i8, 2i8, 3i8];
@@ -225,77 +236,14 @@ impl WhiteboxTool for DownslopeDistanceToStream {
         /////////////////////////////////////////////
         // Perform the D8 flow pointer calculation //
         /////////////////////////////////////////////
-        let num_procs = num_cpus::get() as isize;
-        let (tx, rx) = mpsc::channel();
-        for tid in 0..num_procs {
-            let dem = dem.clone();
-            let tx = tx.clone();
-            thread::spawn(move || {
-                let dx = [1, 1, 1, 0, -1, -1, -1, 0];
-                let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
-                let grid_lengths = [
-                    diag_cell_size,
-                    cell_size_x,
-                    diag_cell_size,
-                    cell_size_y,
-                    diag_cell_size,
-                    cell_size_x,
-                    diag_cell_size,
-                    cell_size_y,
-                ];
-                let (mut z, mut z_n): (f64, f64);
-                let (mut max_slope, mut slope): (f64, f64);
-                let mut dir: i8;
-                let mut neighbouring_nodata: bool;
-                let mut interior_pit_found = false;
-                for row in (0..rows).filter(|r| r % num_procs == tid) {
-                    let mut data: Vec<i8> = vec![flow_nodata; columns as usize];
-                    for col in 0..columns {
-                        z = dem.get_value(row, col);
-                        if z != nodata {
-                            dir = 0i8;
-                            max_slope = f64::MIN;
-                            neighbouring_nodata = false;
-                            for i in 0..8 {
-                                z_n = dem.get_value(row + dy[i], col + dx[i]);
-                                if z_n != nodata {
-                                    slope = (z - z_n) / grid_lengths[i];
-                                    if slope > max_slope && slope > 0f64 {
-                                        max_slope = slope;
-                                        dir = i as i8;
-                                    }
-                                } else {
-                                    neighbouring_nodata = true;
-                                }
-                            }
-                            if max_slope >= 0f64 {
-                                data[col as usize] = dir;
-                            } else {
-                                data[col as usize] = -1i8;
-                                if !neighbouring_nodata {
-                                    interior_pit_found = true;
-                                }
-                            }
-                        }
-                    }
-                    tx.send((row, data, interior_pit_found)).unwrap();
-                }
-            });
-        }
+        let (flow_dir, interior_pit_found) = d8_flow_directions(&dem, tie_breaking, verbose)?;
 
-        let mut flow_dir: Array2D<i8> = Array2D::new(rows, columns, flow_nodata, flow_nodata)?;
-        let mut interior_pit_found = false;
         let mut output = Raster::initialize_using_file(&output_file, &dem);
         let background_value = f64::MIN;
         output.reinitialize_values(background_value);
         let mut stack = Vec::with_capacity((rows * columns) as usize);
         let mut num_solved_cells = 0;
-        for r in 0..rows {
-            let (row, data, pit) = rx.recv().unwrap();
-            flow_dir.set_row_data(row, data);
-            if pit {
-                interior_pit_found = true;
-            }
+        for row in 0..rows {
             for col in 0..columns {
                 // stream cells get added to the stack; nodata cells get assigned that in the output
                 if streams.get_value(row, col) > 0f64 && streams.get_value(row, col) != streams_nodata {
@@ -305,8 +253,7 @@ impl WhiteboxTool for DownslopeDistanceToStream {
                 if dem.get_value(row, col) == nodata {
                     output.set_value(row, col, nodata);
                     num_solved_cells += 1;
-                }
-                if flow_dir.get_value(row, col) == -1 {
+                } else if flow_dir.get_value(row, col) == -1 {
                     if output.get_value(row, col) != 0f64 {
                         stack.push((row, col, nodata));
                         output.set_value(row, col, nodata);
@@ -315,9 +262,9 @@ impl WhiteboxTool for DownslopeDistanceToStream {
                 }
             }
             if verbose {
-                progress = (100.0_f64 * r as f64 / (rows - 1) as f64) as usize;
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
                 if progress != old_progress {
-                    println!("Flow directions: {}%", progress);
+                    println!("Finding stream cells: {}%", progress);
                     old_progress = progress;
                 }
             }
@@ -216,32 +216,22 @@ impl WhiteboxTool for DownslopeFlowpathLength {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !d8_file.contains(&sep) && !d8_file.contains("/") {
-            d8_file = format!("{}{}", working_directory, d8_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        d8_file = resolve_path(&d8_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
         let use_watersheds: bool;
         if !watersheds_file.is_empty() {
             use_watersheds = true;
-            if !watersheds_file.contains(&sep) && !watersheds_file.contains("/") {
-                watersheds_file = format!("{}{}", working_directory, watersheds_file);
-            }
+            watersheds_file = resolve_path(&watersheds_file, working_directory);
         } else {
             use_watersheds = false;
         }
         let use_weights: bool;
         if !weights_file.is_empty() {
             use_weights = true;
-            if !weights_file.contains(&sep) {
-                weights_file = format!("{}{}", working_directory, weights_file);
-            }
+            weights_file = resolve_path(&weights_file, working_directory);
         } else {
             use_weights = false
         }
@@ -0,0 +1,432 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::accumulation_engine::{
+    d8_flow_directions, dinf_flow_directions, downslope_accumulation, D8TieBreaking, FlowDirections,
+    Reducer,
+};
+use crate::raster::*;
+use crate::structures::Array2D;
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::Arc;
+
+/// This tool summarizes the values of a raster (`--input`) along the downslope flowpath of each
+/// grid cell, i.e. from the cell to the edge of the grid, or to a pit, following the flow
+/// directions of a digital elevation model (`--dem`). For example, it can be used to map the
+/// minimum downslope elevation, the maximum slope gradient that runoff will encounter on its way to
+/// a stream, or the mean downslope soil permeability. The DEM should have been pre-processed to
+/// remove artifact topographic depressions and flat areas (`BreachDepressions`, `FillDepressions`).
+/// If no input raster is specified, the values of the DEM are summarized, e.g. the default
+/// statistic is the minimum downslope elevation.
+///
+/// The statistic (`--stat`) may be one of `min`, `max`, `mean`, or `sum`. The `mean` is weighted
+/// by flowpath length, i.e. it is the integral of the values along the flowpath, interpolated
+/// linearly between the cell centres, divided by the flowpath length. As such, it is unaffected by
+/// the mixture of diagonal and cardinal flow directions along a flowpath. The mean of a cell
+/// without a downslope neighbour is its own value. The `sum` is the total of the values of the
+/// cells along the flowpath, including the cell itself, e.g. the sum of per-cell travel times.
+///
+/// Flow may be routed (`--routing`) using either the `d8` (O'Callaghan and Mark, 1984) or the
+/// `dinf` (Tarboton, 1997) algorithm. With D-infinity routing, flowpaths may diverge, and the
+/// `mean` and `sum` are the expected values over the downslope flowpaths, weighted by the
+/// proportion of flow that follows each of them. The `min` and `max` consider every cell that
+/// receives any flow.
+///
+/// The statistics are calculated using the same accumulation engine as `UpslopeStatistic`, in a
+/// single pass over the grid, in the reverse of the order in which flow is accumulated, i.e. the
+/// processing time is proportional to the number of grid cells rather than to the total length of
+/// the flowpaths.
+///
+/// NoData valued cells in the DEM are assigned NoData in the output. NoData valued cells in the
+/// input raster are ignored, and cells without any valid downslope values are assigned NoData,
+/// except for the `sum`, which is zero.
+///
+/// # See Also
+/// `UpslopeStatistic`, `DownslopeFlowpathLength`, `DownslopeDistanceToStream`, `ElevationAboveStream`
+pub struct DownslopeStatistic {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl DownslopeStatistic {
+    pub fn new() -> DownslopeStatistic {
+        // public constructor
+        let name = "DownslopeStatistic".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Calculates a statistic of the values of a raster along the downslope flowpath of each cell."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster DEM file, used for flow routing.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Values File (optional)".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description:
+                "Optional input raster of values to summarize; the DEM is used if unspecified."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Statistic".to_owned(),
+            flags: vec!["--stat".to_owned()],
+            description: "Downslope statistic; one of 'min' (default), 'max', 'mean', and 'sum'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "min".to_owned(),
+                "max".to_owned(),
+                "mean".to_owned(),
+                "sum".to_owned(),
+            ]),
+            default_value: Some("min".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Flow Routing".to_owned(),
+            flags: vec!["--routing".to_owned()],
+            description: "Flow routing algorithm; one of 'd8' (default) and 'dinf'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec!["d8".to_owned(), "dinf".to_owned()]),
+            default_value: Some("d8".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=min_downslope_elev.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -i=slope.tif -o=mean_downslope_slope.tif --stat=mean --routing=dinf", short_exe, name).replace("*", &sep);
+
+        DownslopeStatistic {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for DownslopeStatistic {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_stem}_downslope{input_ext}".to_string())
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut stat = String::from("min");
+        let mut routing = String::from("d8");
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-stat" {
+                stat = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-routing" {
+                routing = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let reducer = if stat.contains("max") {
+            stat = String::from("max");
+            Reducer::Max
+        } else if stat.contains("min") {
+            stat = String::from("min");
+            Reducer::Min
+        } else if stat.contains("mean") || stat.contains("av") {
+            stat = String::from("mean");
+            Reducer::Sum
+        } else if stat.contains("sum") || stat.contains("total") {
+            stat = String::from("sum");
+            Reducer::Sum
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The statistic (--stat) must be one of 'max', 'min', 'mean', and 'sum'.",
+            ));
+        };
+        let use_dinf = routing.contains("inf");
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(&dem_file, working_directory);
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let dem = Arc::new(Raster::new(&dem_file, "r")?);
+        let values = if input_file.is_empty() {
+            dem.clone()
+        } else {
+            Arc::new(Raster::new(&input_file, "r")?)
+        };
+
+        let start = Instant::now();
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+        let values_nodata = values.configs.nodata;
+
+        if values.configs.rows != dem.configs.rows || values.configs.columns != dem.configs.columns
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must have the same number of rows and columns and spatial extent.",
+            ));
+        }
+
+        let (flow_dirs, interior_pit_found) = if use_dinf {
+            let (flow_dir, pit) = dinf_flow_directions(&dem, verbose)?;
+            (FlowDirections::DInf(Arc::new(flow_dir)), pit)
+        } else {
+            let (flow_dir, pit) = d8_flow_directions(&dem, D8TieBreaking::First, verbose)?;
+            (FlowDirections::D8(Arc::new(flow_dir)), pit)
+        };
+
+        // Each cell contributes its value to the statistic, or nothing if it is NoData. The mean
+        // is the ratio of the downslope integral of the values to the downslope flowpath length,
+        // both measured between the centres of cells with valid values.
+        let res_x = dem.configs.resolution_x;
+        let res_y = dem.configs.resolution_y;
+        let mut weights: Array2D<f64> = Array2D::new(rows, columns, reducer.identity(), nodata)?;
+        let mut lengths: Array2D<f64> = Array2D::new(rows, columns, 0f64, nodata)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = values.get_value(row, col);
+                if dem.get_value(row, col) == nodata || z == values_nodata {
+                    continue;
+                }
+                if stat != "mean" {
+                    weights.set_value(row, col, z);
+                    continue;
+                }
+                let (mut integral, mut length) = (0f64, 0f64);
+                for (row_n, col_n, p) in flow_dirs.receivers(row, col).iter().copied() {
+                    let z_n = values.get_value(row_n, col_n);
+                    if p > 0f64 && dem.get_value(row_n, col_n) != nodata && z_n != values_nodata {
+                        let dist =
+                            ((col_n - col) as f64 * res_x).hypot((row_n - row) as f64 * res_y);
+                        integral += p * dist * (z + z_n) / 2f64;
+                        length += p * dist;
+                    }
+                }
+                weights.set_value(row, col, integral);
+                lengths.set_value(row, col, length);
+            }
+        }
+
+        let acc =
+            downslope_accumulation(&dem, &flow_dirs, Some(Arc::new(weights)), reducer, verbose)?;
+        let lengths = if stat == "mean" {
+            downslope_accumulation(
+                &dem,
+                &flow_dirs,
+                Some(Arc::new(lengths)),
+                Reducer::Sum,
+                verbose,
+            )?
+        } else {
+            lengths
+        };
+
+        let mut output = Raster::initialize_using_file(&output_file, &values);
+        output.configs.nodata = nodata;
+        output.configs.data_type = DataType::F32;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        for row in 0..rows {
+            let mut data = vec![nodata; columns as usize];
+            for col in 0..columns {
+                if dem.get_value(row, col) != nodata {
+                    let v = acc.get_value(row, col);
+                    if stat == "mean" {
+                        let length = lengths.get_value(row, col);
+                        if length > 0f64 {
+                            data[col as usize] = v / length;
+                        } else if values.get_value(row, col) != values_nodata {
+                            data[col as usize] = values.get_value(row, col);
+                        }
+                    } else if v.is_finite() {
+                        data[col as usize] = v;
+                    }
+                }
+            }
+            output.set_row_data(row, data);
+
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input DEM file: {}", dem_file));
+        if !input_file.is_empty() {
+            output.add_metadata_entry(format!("Input values file: {}", input_file));
+        }
+        output.add_metadata_entry(format!("Statistic: {}", stat));
+        output.add_metadata_entry(format!(
+            "Flow routing: {}",
+            if use_dinf { "dinf" } else { "d8" }
+        ));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+        if interior_pit_found {
+            println!("**********************************************************************************");
+            println!(
+                "WARNING: Interior pit cells were found within the input DEM. It is likely that the
+            DEM needs to be processed to remove topographic depressions and flats prior to
+            running this tool."
+            );
+            println!("**********************************************************************************");
+        }
+
+        Ok(())
+    }
+}
@@ -182,20 +182,12 @@ impl WhiteboxTool for ElevationAboveStreamEuclidean {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !dem_file.contains(&sep) && !dem_file.contains("/") {
-            dem_file = format!("{}{}", working_directory, dem_file);
-        }
-        if !streams_file.contains(&sep) && !streams_file.contains("/") {
-            streams_file = format!("{}{}", working_directory, streams_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        dem_file = resolve_path(&dem_file, working_directory);
+        streams_file = resolve_path(&streams_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -180,14 +180,8 @@ impl WhiteboxTool for FindParallelFlow {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
-        if !d8_file.contains(&sep) && !d8_file.contains("/") {
-            d8_file = format!("{}{}", working_directory, d8_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        d8_file = resolve_path(&d8_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         let pntr = Arc::new(Raster::new(&d8_file, "r")?);
 
@@ -0,0 +1,660 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::trace_region_boundaries;
+use crate::raster::*;
+use crate::structures::{Array2D, Point2D};
+use crate::tools::*;
+use crate::vector::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool can be used to locate the interior pits and flat areas within a digital elevation
+/// model (DEM) that will interrupt flow routing, e.g. those that are responsible for the interior
+/// pit warning that is issued by `D8FlowAccumulation`. Rather than conditioning the entire DEM
+/// with `FillDepressions` or `BreachDepressions`, the outputs of this tool allow users to review
+/// the problem areas and to target their conditioning efforts, e.g. by adding missing culverts
+/// with `BurnStreamsAtRoads` or by editing individual artifacts.
+///
+/// A grid cell is considered to be a *no-flow* cell if all of its eight neighbours are valid and
+/// none of them are lower than the cell. Each no-flow cell is grouped with all of the connected
+/// cells of equal elevation. Single-cell groups are reported as *pits* and groups of two or more
+/// cells are reported as *flats*. The flats are vectorized into the output polygon file
+/// (`--flats`), with the following attributes:
+///
+/// | Field      | Description                                                        |
+/// |------------|--------------------------------------------------------------------|
+/// | ELEV       | The elevation of the flat                                          |
+/// | NUM_CELLS  | The number of grid cells in the flat                               |
+/// | AREA       | The flat area                                                      |
+/// | OUTLETS    | The number of flat cells with a lower neighbour, or on the DEM edge |
+///
+/// A flat with no outlets is the flat bottom of a depression and it cannot be drained without
+/// raising or breaching the surrounding terrain. Flats with outlets drain, but flow directions
+/// within them are undefined until the flat is resolved, e.g. using `FlattenLakes` or the small
+/// elevation increments applied by `FillDepressions`.
+///
+/// The locations of the pits, and of each flat without an outlet, are output as points to the
+/// pits file (`--pits`). The severity of each pit is described by the depression that contains it,
+/// which is the connected area that would be raised by filling the DEM. The point attributes are:
+///
+/// | Field      | Description                                                  |
+/// |------------|--------------------------------------------------------------|
+/// | TYPE       | Either 'pit' for single-cell pits or 'flat' for closed flats |
+/// | ELEV       | The elevation of the pit                                     |
+/// | DEPTH      | The depth of the pit below its depression's spill elevation  |
+/// | DEP_ID     | The identifier of the depression containing the pit          |
+/// | DEP_AREA   | The area of the depression containing the pit                |
+/// | DEP_VOLUME | The volume of the depression containing the pit              |
+///
+/// Several pits may share a single depression. Areas and volumes are measured in map units or,
+/// for DEMs in geographic coordinates, in square metres and elevation units multiplied by square
+/// metres respectively. A summary of the number of pits, flats, and depressions is also reported.
+///
+/// # See Also
+/// `D8FlowAccumulation`, `FillDepressions`, `BreachDepressions`, `DepthInSink`, `ConditioningAudit`
+pub struct FindPitsAndFlats {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl FindPitsAndFlats {
+    pub fn new() -> FindPitsAndFlats {
+        // public constructor
+        let name = "FindPitsAndFlats".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Locates the interior pits and flats in a DEM and reports the severity of each.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Pits File".to_owned(),
+            flags: vec!["--pits".to_owned()],
+            description: "Output vector points file of pit locations.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Flats File".to_owned(),
+            flags: vec!["--flats".to_owned()],
+            description: "Output vector polygon file of flat areas.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem='dem.tif' --pits='pits.shp' --flats='flats.shp'", short_exe, name).replace("*", &sep);
+
+        FindPitsAndFlats {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for FindPitsAndFlats {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut pits_file = String::new();
+        let mut flats_file = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-pits" {
+                pits_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-flats" {
+                flats_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(&dem_file, working_directory);
+        pits_file = resolve_path(&pits_file, working_directory);
+        flats_file = resolve_path(&flats_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let dem = Raster::new(&dem_file, "r")?;
+
+        let start = Instant::now();
+
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+        let is_valid = |row: isize, col: isize| -> bool {
+            row >= 0
+                && col >= 0
+                && row < rows
+                && col < columns
+                && dem.get_value(row, col) != nodata
+        };
+
+        // Fill the depressions using the priority-flood method, seeding the queue with the valid
+        // cells along the DEM edges and bordering nodata areas. No gradient is applied to flats.
+        let mut filled: Array2D<f64> = Array2D::new(rows, columns, nodata, nodata)?;
+        let mut queued: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut heap = BinaryHeap::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                if is_valid(row, col) && (0..8).any(|n| !is_valid(row + dy[n], col + dx[n])) {
+                    let z = dem.get_value(row, col);
+                    filled.set_value(row, col, z);
+                    queued.set_value(row, col, 1u8);
+                    heap.push(GridCell {
+                        row: row,
+                        column: col,
+                        priority: z,
+                    });
+                }
+            }
+        }
+        let num_valid_cells = (0..rows)
+            .map(|row| (0..columns).filter(|&col| is_valid(row, col)).count())
+            .sum::<usize>();
+        let mut num_solved = 0usize;
+        while let Some(cell) = heap.pop() {
+            for n in 0..8 {
+                let (rn, cn) = (cell.row + dy[n], cell.column + dx[n]);
+                if is_valid(rn, cn) && queued.get_value(rn, cn) == 0u8 {
+                    let zn = dem.get_value(rn, cn).max(cell.priority);
+                    filled.set_value(rn, cn, zn);
+                    queued.set_value(rn, cn, 1u8);
+                    heap.push(GridCell {
+                        row: rn,
+                        column: cn,
+                        priority: zn,
+                    });
+                }
+            }
+            if verbose {
+                num_solved += 1;
+                progress = (100.0_f64 * num_solved as f64 / num_valid_cells.max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Filling depressions: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let is_geographic = dem.is_in_geographic_coordinates();
+        let cell_area = |row: isize| -> f64 {
+            if is_geographic {
+                let lat = dem.get_y_from_row(row).to_radians();
+                dem.configs.resolution_x * dem.configs.resolution_y * 111319.49f64 * 111319.49f64 * lat.cos()
+            } else {
+                dem.configs.resolution_x * dem.configs.resolution_y
+            }
+        };
+
+        // label the depressions, i.e. the connected areas raised by filling
+        let mut depression_labels: Array2D<i32> = Array2D::new(rows, columns, -1, -1)?;
+        let mut depressions: Vec<(f64, f64)> = vec![]; // (area, volume)
+        let mut stack = vec![];
+        for row in 0..rows {
+            for col in 0..columns {
+                if !is_valid(row, col)
+                    || filled.get_value(row, col) <= dem.get_value(row, col)
+                    || depression_labels.get_value(row, col) != -1
+                {
+                    continue;
+                }
+                let label = depressions.len() as i32;
+                let (mut area, mut volume) = (0f64, 0f64);
+                depression_labels.set_value(row, col, label);
+                stack.push((row, col));
+                while let Some((r, c)) = stack.pop() {
+                    let a = cell_area(r);
+                    area += a;
+                    volume += (filled.get_value(r, c) - dem.get_value(r, c)) * a;
+                    for n in 0..8 {
+                        let (rn, cn) = (r + dy[n], c + dx[n]);
+                        if is_valid(rn, cn)
+                            && filled.get_value(rn, cn) > dem.get_value(rn, cn)
+                            && depression_labels.get_value(rn, cn) == -1
+                        {
+                            depression_labels.set_value(rn, cn, label);
+                            stack.push((rn, cn));
+                        }
+                    }
+                }
+                depressions.push((area, volume));
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Identifying depressions: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // find the no-flow cells and group them with their connected cells of equal elevation
+        let mut flat_labels: Array2D<i32> = Array2D::new(rows, columns, -1, -1)?;
+        let mut pits: Vec<(isize, isize, bool)> = vec![]; // (row, column, is flat)
+        let mut flats: Vec<Flat> = vec![];
+        for row in 0..rows {
+            for col in 0..columns {
+                if !is_valid(row, col) || flat_labels.get_value(row, col) != -1 {
+                    continue;
+                }
+                let z = dem.get_value(row, col);
+                let is_no_flow = (0..8).all(|n| {
+                    is_valid(row + dy[n], col + dx[n])
+                        && dem.get_value(row + dy[n], col + dx[n]) >= z
+                });
+                if !is_no_flow {
+                    continue;
+                }
+                let label = flats.len() as i32;
+                let mut flat = Flat::new(z);
+                flat_labels.set_value(row, col, label);
+                stack.push((row, col));
+                while let Some((r, c)) = stack.pop() {
+                    let is_outlet = (0..8).any(|n| {
+                        !is_valid(r + dy[n], c + dx[n])
+                            || dem.get_value(r + dy[n], c + dx[n]) < z
+                    });
+                    flat.add_cell(r, c, cell_area(r), is_outlet);
+                    for n in 0..8 {
+                        let (rn, cn) = (r + dy[n], c + dx[n]);
+                        if is_valid(rn, cn)
+                            && dem.get_value(rn, cn) == z
+                            && flat_labels.get_value(rn, cn) == -1
+                        {
+                            flat_labels.set_value(rn, cn, label);
+                            stack.push((rn, cn));
+                        }
+                    }
+                }
+                if flat.num_cells == 1 {
+                    // a single-cell pit; it is not retained as a flat
+                    flat_labels.set_value(row, col, -2);
+                    pits.push((row, col, false));
+                } else {
+                    if flat.num_outlets == 0 {
+                        // locate the closed flat by its cell nearest the centroid
+                        let (mean_row, mean_col) = (
+                            flat.sum_row / flat.num_cells as f64,
+                            flat.sum_col / flat.num_cells as f64,
+                        );
+                        let mut nearest = (row, col);
+                        let mut min_dist = f64::INFINITY;
+                        for r in flat.min_row..=flat.max_row {
+                            for c in flat.min_col..=flat.max_col {
+                                if flat_labels.get_value(r, c) == label {
+                                    let dist = (r as f64 - mean_row).powi(2)
+                                        + (c as f64 - mean_col).powi(2);
+                                    if dist < min_dist {
+                                        min_dist = dist;
+                                        nearest = (r, c);
+                                    }
+                                }
+                            }
+                        }
+                        pits.push((nearest.0, nearest.1, true));
+                    }
+                    flats.push(flat);
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Identifying pits and flats: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // output the pits
+        let mut pits_output = Shapefile::new(&pits_file, ShapeType::Point)?;
+        pits_output.projection = dem.configs.coordinate_ref_system_wkt.clone();
+        pits_output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        pits_output
+            .attributes
+            .add_field(&AttributeField::new("TYPE", FieldDataType::Text, 4u8, 0u8));
+        pits_output
+            .attributes
+            .add_field(&AttributeField::new("ELEV", FieldDataType::Real, 12u8, 4u8));
+        pits_output
+            .attributes
+            .add_field(&AttributeField::new("DEPTH", FieldDataType::Real, 12u8, 4u8));
+        pits_output
+            .attributes
+            .add_field(&AttributeField::new("DEP_ID", FieldDataType::Int, 7u8, 0u8));
+        pits_output
+            .attributes
+            .add_field(&AttributeField::new("DEP_AREA", FieldDataType::Real, 14u8, 3u8));
+        pits_output
+            .attributes
+            .add_field(&AttributeField::new("DEP_VOLUME", FieldDataType::Real, 14u8, 3u8));
+
+        let mut max_depth = 0f64;
+        for (i, &(row, col, is_flat)) in pits.iter().enumerate() {
+            let z = dem.get_value(row, col);
+            let depth = filled.get_value(row, col) - z;
+            max_depth = max_depth.max(depth);
+            let dep_label = depression_labels.get_value(row, col);
+            let (dep_area, dep_volume) = if dep_label >= 0 {
+                depressions[dep_label as usize]
+            } else {
+                (0f64, 0f64)
+            };
+            pits_output.add_point_record(dem.get_x_from_column(col), dem.get_y_from_row(row));
+            pits_output.attributes.add_record(
+                vec![
+                    FieldData::Int(i as i32 + 1),
+                    FieldData::Text(if is_flat { "flat" } else { "pit" }.to_string()),
+                    FieldData::Real(z),
+                    FieldData::Real(depth),
+                    FieldData::Int(dep_label + 1),
+                    FieldData::Real(dep_area),
+                    FieldData::Real(dep_volume),
+                ],
+                false,
+            );
+        }
+
+        // vectorize the flats
+        let west = dem.configs.west;
+        let north = dem.configs.north;
+        let res_x = dem.configs.resolution_x;
+        let res_y = dem.configs.resolution_y;
+        let mut flats_output = Shapefile::new(&flats_file, ShapeType::Polygon)?;
+        flats_output.projection = dem.configs.coordinate_ref_system_wkt.clone();
+        flats_output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        flats_output
+            .attributes
+            .add_field(&AttributeField::new("ELEV", FieldDataType::Real, 12u8, 4u8));
+        flats_output
+            .attributes
+            .add_field(&AttributeField::new("NUM_CELLS", FieldDataType::Int, 9u8, 0u8));
+        flats_output
+            .attributes
+            .add_field(&AttributeField::new("AREA", FieldDataType::Real, 14u8, 3u8));
+        flats_output
+            .attributes
+            .add_field(&AttributeField::new("OUTLETS", FieldDataType::Int, 9u8, 0u8));
+
+        let num_flats = flats.len();
+        for (i, flat) in flats.iter().enumerate() {
+            let label = i as i32;
+            let rings = trace_region_boundaries(
+                flat.max_row - flat.min_row + 1,
+                flat.max_col - flat.min_col + 1,
+                |r, c| flat_labels.get_value(r + flat.min_row, c + flat.min_col) == label,
+            );
+            let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+            for ring in &rings {
+                let points: Vec<Point2D> = ring
+                    .iter()
+                    .map(|p| {
+                        Point2D::new(
+                            west + (p.1 + flat.min_col) as f64 * res_x,
+                            north - (p.0 + flat.min_row) as f64 * res_y,
+                        )
+                    })
+                    .collect();
+                sfg.add_part(&points);
+            }
+            flats_output.add_record(sfg);
+            flats_output.attributes.add_record(
+                vec![
+                    FieldData::Int(label + 1),
+                    FieldData::Real(flat.elevation),
+                    FieldData::Int(flat.num_cells as i32),
+                    FieldData::Real(flat.area),
+                    FieldData::Int(flat.num_outlets as i32),
+                ],
+                false,
+            );
+
+            if verbose {
+                progress = (100.0_f64 * i as f64 / (num_flats - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Vectorizing flats: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            let num_pits = pits.iter().filter(|p| !p.2).count();
+            let num_closed_flats = pits.len() - num_pits;
+            let total_volume = depressions.iter().fold(0f64, |acc, d| acc + d.1);
+            println!("Single-cell pits: {}", num_pits);
+            println!(
+                "Flats: {} ({} without an outlet)",
+                num_flats, num_closed_flats
+            );
+            println!(
+                "Depressions: {} (volume: {:.3}, maximum pit depth: {:.4})",
+                depressions.len(),
+                total_volume,
+                max_depth
+            );
+            println!("Saving data...")
+        };
+        if pits.is_empty() {
+            println!("Warning: No pits were found in the input DEM. The pits file will not be written.")
+        } else {
+            let _ = match pits_output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Pits file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+        if flats.is_empty() {
+            println!("Warning: No flats were found in the input DEM. The flats file will not be written.")
+        } else {
+            let _ = match flats_output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Flats file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The summary statistics of a connected flat area.
+struct Flat {
+    elevation: f64,
+    num_cells: usize,
+    num_outlets: usize,
+    area: f64,
+    sum_row: f64,
+    sum_col: f64,
+    min_row: isize,
+    max_row: isize,
+    min_col: isize,
+    max_col: isize,
+}
+
+impl Flat {
+    fn new(elevation: f64) -> Flat {
+        Flat {
+            elevation: elevation,
+            num_cells: 0,
+            num_outlets: 0,
+            area: 0f64,
+            sum_row: 0f64,
+            sum_col: 0f64,
+            min_row: isize::MAX,
+            max_row: isize::MIN,
+            min_col: isize::MAX,
+            max_col: isize::MIN,
+        }
+    }
+
+    fn add_cell(&mut self, row: isize, col: isize, cell_area: f64, is_outlet: bool) {
+        self.num_cells += 1;
+        if is_outlet {
+            self.num_outlets += 1;
+        }
+        self.area += cell_area;
+        self.sum_row += row as f64;
+        self.sum_col += col as f64;
+        self.min_row = self.min_row.min(row);
+        self.max_row = self.max_row.max(row);
+        self.min_col = self.min_col.min(col);
+        self.max_col = self.max_col.max(col);
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct GridCell {
+    row: isize,
+    column: isize,
+    priority: f64,
+}
+
+impl Eq for GridCell {}
+
+impl PartialOrd for GridCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GridCell {
+    fn cmp(&self, other: &GridCell) -> Ordering {
+        // reversed, so that the BinaryHeap is a min-heap
+        other
+            .priority
+            .partial_cmp(&self.priority)
+            .unwrap_or(Ordering::Equal)
+    }
+}
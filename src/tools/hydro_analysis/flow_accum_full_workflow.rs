@@ -267,23 +267,13 @@ impl WhiteboxTool for FlowAccumulationFullWorkflow {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !outdem_file.contains(&sep) && !outdem_file.contains("/") {
-            outdem_file = format!("{}{}", working_directory, outdem_file);
-        }
-        if !pntr_file.contains(&sep) && !pntr_file.contains("/") {
-            pntr_file = format!("{}{}", working_directory, pntr_file);
-        }
-        if !accum_file.contains(&sep) && !accum_file.contains("/") {
-            accum_file = format!("{}{}", working_directory, accum_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        outdem_file = resolve_path(&outdem_file, working_directory);
+        pntr_file = resolve_path(&pntr_file, working_directory);
+        accum_file = resolve_path(&accum_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -0,0 +1,917 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::{point_in_poly, trace_region_boundaries};
+use crate::raster::*;
+use crate::structures::{Array2D, Point2D};
+use crate::tools::*;
+use crate::vector::*;
+use std::collections::HashMap;
+use std::env;
+use std::f64;
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::path;
+
+/// This tool streamlines the set-up of semi-distributed hydrological models from a set of stream
+/// gauge (i.e. outlet) locations. The gauges (`--gauges`) may be provided either as a vector
+/// points file or as a CSV table containing ID, X, and Y columns. When a CSV table is used, the
+/// columns are identified by header names (e.g. `id` or `name`, `x` or `easting`, and `y` or
+/// `northing`), and are otherwise assumed to be the first three columns. For vector inputs, the
+/// gauge IDs are taken from the `--id_field` attribute, if specified, or are otherwise the record
+/// numbers.
+///
+/// Each gauge is first snapped to the nearest stream cell (`--streams`) within the maximum snap
+/// distance (`--snap_dist`), in the manner of `JensonSnapPourPoints`. Gauges without a stream cell
+/// within the snap distance remain at their original locations. The catchment of each gauge is then
+/// derived from the D8 flow pointer raster (`--d8_pntr`). By default, the catchments are split at
+/// the gauges, such that each gauge's catchment contains only the area draining to it directly and
+/// not via an upstream gauge, i.e. the catchments do not overlap. Alternatively, the full upslope
+/// catchments of the gauges may be output (`--total_catchments`), in which case the catchments of
+/// nested gauges overlap.
+///
+/// The output polygon file (`--output`) contains the gauge ID, the total and incremental
+/// catchment areas, the ID of the next downstream gauge, and the number of upstream gauges. The
+/// optional topology table (`--topology`) is a CSV file containing, for each gauge, its original
+/// and snapped coordinates, snap distance, areas, the next downstream gauge, the immediately
+/// upstream gauges, and all nested (i.e. upstream) gauges, which may be used to route flows
+/// between catchments. The catchment polygons may also be exported as well-known text (WKT) to a
+/// CSV file (`--wkt`), which is convenient for loading into databases and modelling frameworks.
+///
+/// Catchment areas are measured in squared map units or, for pointer rasters in geographic
+/// coordinates, in square metres. If the pointer file contains ESRI flow direction values, the
+/// `--esri_pntr` parameter must be specified.
+///
+/// # See Also
+/// `Watershed`, `JensonSnapPourPoints`, `UnnestBasins`, `D8Pointer`
+pub struct GaugeCatchments {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl GaugeCatchments {
+    pub fn new() -> GaugeCatchments {
+        // public constructor
+        let name = "GaugeCatchments".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description = "Snaps stream gauges to a stream network and delineates their nested or split catchments and topology.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input D8 pointer raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Gauges File".to_owned(),
+            flags: vec!["--gauges".to_owned()],
+            description: "Input gauge locations; either a vector points file or a CSV table with ID, X, and Y columns.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Any),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Gauge ID Field (optional)".to_owned(),
+            flags: vec!["--id_field".to_owned()],
+            description: "Optional gauge ID field name, for vector gauge files.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Catchments File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector polygon catchments file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Topology File (optional)".to_owned(),
+            flags: vec!["--topology".to_owned()],
+            description: "Optional output gauge topology CSV file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Csv),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output WKT File (optional)".to_owned(),
+            flags: vec!["--wkt".to_owned()],
+            description: "Optional output CSV file of catchment polygons as well-known text (WKT)."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Csv),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Snap Distance (map units)".to_owned(),
+            flags: vec!["--snap_dist".to_owned()],
+            description: "Maximum snap distance in map units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output total (overlapping) catchments?".to_owned(),
+            flags: vec!["--total_catchments".to_owned()],
+            description: "Output the total upslope catchment of each gauge, rather than splitting the catchments at the gauges.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --streams='streams.tif' --gauges='gauges.csv' -o='catchments.shp' --topology='topology.csv' --snap_dist=50.0", short_exe, name).replace("*", &sep);
+
+        GaugeCatchments {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for GaugeCatchments {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut d8_file = String::new();
+        let mut streams_file = String::new();
+        let mut gauges_file = String::new();
+        let mut id_field = String::new();
+        let mut output_file = String::new();
+        let mut topology_file = String::new();
+        let mut wkt_file = String::new();
+        let mut snap_dist = 0.0;
+        let mut total_catchments = false;
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-gauges" {
+                gauges_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-id_field" {
+                id_field = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-topology" {
+                topology_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-wkt" {
+                wkt_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-snap_dist" {
+                snap_dist = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-total_catchments" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    total_catchments = true;
+                }
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        d8_file = resolve_path(&d8_file, working_directory);
+        streams_file = resolve_path(&streams_file, working_directory);
+        gauges_file = resolve_path(&gauges_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        topology_file = resolve_path(&topology_file, working_directory);
+        wkt_file = resolve_path(&wkt_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let pntr = Raster::new(&d8_file, "r")?;
+        let streams = Raster::new(&streams_file, "r")?;
+        let gauges = read_gauges(&gauges_file, &id_field)?;
+
+        let start = Instant::now();
+
+        let rows = pntr.configs.rows as isize;
+        let columns = pntr.configs.columns as isize;
+        let pntr_nodata = pntr.configs.nodata;
+        let streams_nodata = streams.configs.nodata;
+
+        // make sure the input files have the same size
+        if streams.configs.rows != pntr.configs.rows
+            || streams.configs.columns != pntr.configs.columns
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must have the same number of rows and columns and spatial extent.",
+            ));
+        }
+
+        let num_gauges = gauges.len();
+        if num_gauges == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The gauges file does not contain any gauge locations.",
+            ));
+        }
+
+        // snap the gauges to the nearest stream cell
+        let snap_dist_int = (snap_dist / pntr.configs.resolution_x).ceil() as isize;
+        let mut gauge_cells: Vec<Option<(isize, isize)>> = Vec::with_capacity(num_gauges);
+        let mut snapped_xy: Vec<(f64, f64)> = Vec::with_capacity(num_gauges);
+        for g in 0..num_gauges {
+            let (_, gx, gy) = &gauges[g];
+            let row = pntr.get_row_from_y(*gy);
+            let col = pntr.get_column_from_x(*gx);
+            let mut cell = (row, col);
+            let mut min_dist = f64::INFINITY;
+            for r in (row - snap_dist_int)..(row + snap_dist_int + 1) {
+                for c in (col - snap_dist_int)..(col + snap_dist_int + 1) {
+                    let zn = streams.get_value(r, c);
+                    if zn > 0f64 && zn != streams_nodata {
+                        let x = pntr.get_x_from_column(c);
+                        let y = pntr.get_y_from_row(r);
+                        let dist = ((x - gx) * (x - gx) + (y - gy) * (y - gy)).sqrt();
+                        if dist <= snap_dist && dist < min_dist {
+                            min_dist = dist;
+                            cell = (r, c);
+                        }
+                    }
+                }
+            }
+            if cell.0 < 0 || cell.1 < 0 || cell.0 >= rows || cell.1 >= columns
+                || pntr.get_value(cell.0, cell.1) == pntr_nodata
+            {
+                if verbose {
+                    println!(
+                        "Warning: Gauge {} is located outside of the D8 pointer raster and will be ignored.",
+                        gauges[g].0
+                    );
+                }
+                gauge_cells.push(None);
+                snapped_xy.push((*gx, *gy));
+            } else {
+                gauge_cells.push(Some(cell));
+                snapped_xy.push((
+                    pntr.get_x_from_column(cell.1),
+                    pntr.get_y_from_row(cell.0),
+                ));
+            }
+        }
+
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+
+        // Create a mapping from the pointer values to cells offsets.
+        let mut pntr_matches: [i8; 129] = [0i8; 129];
+        if !esri_style {
+            pntr_matches[1] = 0i8;
+            pntr_matches[2] = 1i8;
+            pntr_matches[4] = 2i8;
+            pntr_matches[8] = 3i8;
+            pntr_matches[16] = 4i8;
+            pntr_matches[32] = 5i8;
+            pntr_matches[64] = 6i8;
+            pntr_matches[128] = 7i8;
+        } else {
+            pntr_matches[1] = 1i8;
+            pntr_matches[2] = 2i8;
+            pntr_matches[4] = 3i8;
+            pntr_matches[8] = 4i8;
+            pntr_matches[16] = 5i8;
+            pntr_matches[32] = 6i8;
+            pntr_matches[64] = 7i8;
+            pntr_matches[128] = 0i8;
+        }
+
+        // Labels are gauge indices; -1 is unvisited and -2 is not draining to any gauge.
+        let mut flow_dir: Array2D<i8> = Array2D::new(rows, columns, -2, -2)?;
+        let mut labels: Array2D<i32> = Array2D::new(rows, columns, -1, -2)?;
+        let mut z: f64;
+        for row in 0..rows {
+            for col in 0..columns {
+                z = pntr.get_value(row, col);
+                if z != pntr_nodata {
+                    if z > 0.0 {
+                        flow_dir.set_value(row, col, pntr_matches[z as usize]);
+                    } else {
+                        flow_dir.set_value(row, col, -1i8);
+                    }
+                } else {
+                    labels.set_value(row, col, -2);
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Initializing: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Gauges that are snapped to the same cell share a catchment; the first one is used.
+        let mut cell_gauge: HashMap<(isize, isize), usize> = HashMap::new();
+        let mut coincident: Vec<Option<usize>> = vec![None; num_gauges];
+        for g in 0..num_gauges {
+            if let Some(cell) = gauge_cells[g] {
+                match cell_gauge.get(&cell) {
+                    Some(first) => {
+                        coincident[g] = Some(*first);
+                        if verbose {
+                            println!(
+                                "Warning: Gauges {} and {} are located in the same cell.",
+                                gauges[*first].0, gauges[g].0
+                            );
+                        }
+                    }
+                    None => {
+                        cell_gauge.insert(cell, g);
+                        labels.set_value(cell.0, cell.1, g as i32);
+                    }
+                }
+            }
+        }
+
+        // label each cell with the first gauge downstream of it
+        let mut dir: i8;
+        let (mut x, mut y): (isize, isize);
+        let mut label: i32;
+        for row in 0..rows {
+            for col in 0..columns {
+                if labels.get_value(row, col) == -1 {
+                    x = col;
+                    y = row;
+                    label = -2;
+                    loop {
+                        dir = flow_dir.get_value(y, x);
+                        if dir < 0 {
+                            break;
+                        }
+                        x += dx[dir as usize];
+                        y += dy[dir as usize];
+                        let l = labels.get_value(y, x);
+                        if l != -1 {
+                            label = l;
+                            break;
+                        }
+                    }
+                    x = col;
+                    y = row;
+                    labels.set_value(y, x, label);
+                    loop {
+                        dir = flow_dir.get_value(y, x);
+                        if dir < 0 {
+                            break;
+                        }
+                        x += dx[dir as usize];
+                        y += dy[dir as usize];
+                        if labels.get_value(y, x) != -1 {
+                            break;
+                        }
+                        labels.set_value(y, x, label);
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Delineating catchments: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // incremental areas and the extent of each catchment
+        let is_geographic = pntr.is_in_geographic_coordinates();
+        let mut incr_area = vec![0f64; num_gauges];
+        let mut extents = vec![(isize::MAX, isize::MIN, isize::MAX, isize::MIN); num_gauges];
+        for row in 0..rows {
+            let cell_area = if is_geographic {
+                let lat = pntr.get_y_from_row(row).to_radians();
+                pntr.configs.resolution_x * pntr.configs.resolution_y * 111319.49f64 * 111319.49f64 * lat.cos()
+            } else {
+                pntr.configs.resolution_x * pntr.configs.resolution_y
+            };
+            for col in 0..columns {
+                let l = labels.get_value(row, col);
+                if l >= 0 {
+                    let g = l as usize;
+                    incr_area[g] += cell_area;
+                    let e = &mut extents[g];
+                    e.0 = e.0.min(row);
+                    e.1 = e.1.max(row);
+                    e.2 = e.2.min(col);
+                    e.3 = e.3.max(col);
+                }
+            }
+        }
+
+        // the next downstream gauge of each gauge
+        let mut downstream: Vec<Option<usize>> = vec![None; num_gauges];
+        for g in 0..num_gauges {
+            if coincident[g].is_some() {
+                downstream[g] = coincident[g];
+                continue;
+            }
+            if let Some(cell) = gauge_cells[g] {
+                x = cell.1;
+                y = cell.0;
+                loop {
+                    dir = flow_dir.get_value(y, x);
+                    if dir < 0 {
+                        break;
+                    }
+                    x += dx[dir as usize];
+                    y += dy[dir as usize];
+                    if let Some(d) = cell_gauge.get(&(y, x)) {
+                        downstream[g] = Some(*d);
+                        break;
+                    }
+                }
+            }
+        }
+        let mut upstream: Vec<Vec<usize>> = vec![vec![]; num_gauges];
+        for g in 0..num_gauges {
+            if let Some(d) = downstream[g] {
+                upstream[d].push(g);
+            }
+        }
+        // all nested gauges, i.e. the gauges upstream of each gauge
+        let mut nested: Vec<Vec<usize>> = vec![vec![]; num_gauges];
+        for g in 0..num_gauges {
+            let mut stack = upstream[g].clone();
+            while let Some(u) = stack.pop() {
+                nested[g].push(u);
+                stack.extend(upstream[u].iter());
+            }
+            nested[g].sort();
+        }
+        let mut total_area = vec![0f64; num_gauges];
+        for g in 0..num_gauges {
+            if coincident[g].is_none() {
+                total_area[g] = incr_area[g]
+                    + nested[g]
+                        .iter()
+                        .filter(|u| coincident[**u].is_none())
+                        .map(|u| incr_area[*u])
+                        .sum::<f64>();
+            }
+        }
+        for g in 0..num_gauges {
+            if let Some(first) = coincident[g] {
+                total_area[g] = total_area[first];
+            }
+        }
+
+        // trace the catchment polygons
+        let west = pntr.configs.west;
+        let north = pntr.configs.north;
+        let res_x = pntr.configs.resolution_x;
+        let res_y = pntr.configs.resolution_y;
+        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        output.projection = pntr.configs.coordinate_ref_system_wkt.clone();
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("GAUGE_ID", FieldDataType::Text, 25u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("AREA", FieldDataType::Real, 14u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("INCR_AREA", FieldDataType::Real, 14u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("DS_GAUGE", FieldDataType::Text, 25u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("NUM_UP", FieldDataType::Int, 7u8, 0u8));
+
+        let mut wkt_records = vec![];
+        let mut fid = 1i32;
+        for g in 0..num_gauges {
+            if gauge_cells[g].is_none() {
+                continue;
+            }
+            let first = coincident[g].unwrap_or(g);
+            let mut members = vec![first];
+            if total_catchments {
+                members.extend(nested[first].iter().filter(|u| coincident[**u].is_none()));
+            } else if coincident[g].is_some() {
+                // the catchment is output for the first gauge in the cell
+                continue;
+            }
+            let mut is_member = vec![false; num_gauges];
+            let (mut min_row, mut max_row, mut min_col, mut max_col) =
+                (isize::MAX, isize::MIN, isize::MAX, isize::MIN);
+            for m in &members {
+                is_member[*m] = true;
+                min_row = min_row.min(extents[*m].0);
+                max_row = max_row.max(extents[*m].1);
+                min_col = min_col.min(extents[*m].2);
+                max_col = max_col.max(extents[*m].3);
+            }
+            if min_row > max_row {
+                continue;
+            }
+            let rings = trace_region_boundaries(
+                max_row - min_row + 1,
+                max_col - min_col + 1,
+                |r, c| {
+                    let l = labels.get_value(r + min_row, c + min_col);
+                    l >= 0 && is_member[l as usize]
+                },
+            );
+            let rings: Vec<Vec<Point2D>> = rings
+                .iter()
+                .map(|ring| {
+                    ring.iter()
+                        .map(|p| {
+                            Point2D::new(
+                                west + (p.1 + min_col) as f64 * res_x,
+                                north - (p.0 + min_row) as f64 * res_y,
+                            )
+                        })
+                        .collect()
+                })
+                .collect();
+
+            let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+            for ring in &rings {
+                sfg.add_part(ring);
+            }
+            output.add_record(sfg);
+            output.attributes.add_record(
+                vec![
+                    FieldData::Int(fid),
+                    FieldData::Text(gauges[g].0.clone()),
+                    FieldData::Real(total_area[g]),
+                    FieldData::Real(if coincident[g].is_some() { 0f64 } else { incr_area[g] }),
+                    match downstream[g] {
+                        Some(d) => FieldData::Text(gauges[d].0.clone()),
+                        None => FieldData::Null,
+                    },
+                    FieldData::Int(nested[g].len() as i32),
+                ],
+                false,
+            );
+            fid += 1;
+            if !wkt_file.is_empty() {
+                wkt_records.push((gauges[g].0.clone(), to_wkt(&rings)));
+            }
+
+            if verbose {
+                progress = (100.0_f64 * g as f64 / (num_gauges - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Tracing catchments: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if !topology_file.is_empty() {
+            let f = File::create(&topology_file)?;
+            let mut writer = BufWriter::new(f);
+            writer.write_all(
+                "ID,X,Y,SNAP_X,SNAP_Y,SNAP_DIST,AREA,INCR_AREA,DOWNSTREAM,UPSTREAM,NESTED\n"
+                    .as_bytes(),
+            )?;
+            for g in 0..num_gauges {
+                let (id, gx, gy) = &gauges[g];
+                let (sx, sy) = snapped_xy[g];
+                let ids = |list: &Vec<usize>| -> String {
+                    list.iter()
+                        .map(|u| gauges[*u].0.clone())
+                        .collect::<Vec<String>>()
+                        .join(";")
+                };
+                let s = format!(
+                    "\"{}\",{},{},{},{},{},{},{},\"{}\",\"{}\",\"{}\"\n",
+                    id,
+                    gx,
+                    gy,
+                    sx,
+                    sy,
+                    ((sx - gx) * (sx - gx) + (sy - gy) * (sy - gy)).sqrt(),
+                    total_area[g],
+                    if coincident[g].is_some() { 0f64 } else { incr_area[g] },
+                    match downstream[g] {
+                        Some(d) => gauges[d].0.clone(),
+                        None => String::new(),
+                    },
+                    ids(&upstream[g]),
+                    ids(&nested[g])
+                );
+                writer.write_all(s.as_bytes())?;
+            }
+            writer.flush()?;
+        }
+
+        if !wkt_file.is_empty() {
+            let f = File::create(&wkt_file)?;
+            let mut writer = BufWriter::new(f);
+            writer.write_all("ID,WKT\n".as_bytes())?;
+            for (id, wkt) in &wkt_records {
+                writer.write_all(format!("\"{}\",\"{}\"\n", id, wkt).as_bytes())?;
+            }
+            writer.flush()?;
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the gauge IDs and locations from either a vector points file or a CSV table.
+fn read_gauges(file_name: &str, id_field: &str) -> Result<Vec<(String, f64, f64)>, Error> {
+    let mut gauges = vec![];
+    if file_name.to_lowercase().ends_with(".csv") || file_name.to_lowercase().ends_with(".txt") {
+        let contents = fs::read_to_string(file_name)?;
+        let mut lines = contents.lines().filter(|l| !l.trim().is_empty()).peekable();
+        let mut columns = (0usize, 1usize, 2usize);
+        if let Some(first) = lines.peek() {
+            let fields: Vec<String> = first
+                .split(',')
+                .map(|f| f.trim().trim_matches('"').to_lowercase())
+                .collect();
+            if fields.len() < 3 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The gauges table must contain ID, X, and Y columns.",
+                ));
+            }
+            if fields.iter().all(|f| f.parse::<f64>().is_err()) {
+                // it's a header
+                for (i, f) in fields.iter().enumerate() {
+                    match f.as_str() {
+                        "id" | "name" | "gauge" | "gauge_id" | "station" => columns.0 = i,
+                        "x" | "easting" | "lon" | "long" | "longitude" => columns.1 = i,
+                        "y" | "northing" | "lat" | "latitude" => columns.2 = i,
+                        _ => {}
+                    }
+                }
+                lines.next();
+            }
+        }
+        for line in lines {
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim().trim_matches('"')).collect();
+            let max_col = columns.0.max(columns.1).max(columns.2);
+            if fields.len() <= max_col {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Missing values in gauges table line: {}", line),
+                ));
+            }
+            let x = fields[columns.1].parse::<f64>();
+            let y = fields[columns.2].parse::<f64>();
+            match (x, y) {
+                (Ok(x), Ok(y)) => gauges.push((fields[columns.0].to_string(), x, y)),
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Invalid coordinates in gauges table line: {}", line),
+                    ))
+                }
+            }
+        }
+    } else {
+        let points = Shapefile::read(file_name)?;
+        if points.header.shape_type.base_shape_type() != ShapeType::Point {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input vector data must be of point base shape type.",
+            ));
+        }
+        if !id_field.is_empty() && points.attributes.get_field_num(id_field).is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("The gauges file does not contain a field named {}.", id_field),
+            ));
+        }
+        for record_num in 0..points.num_records {
+            let record = points.get_record(record_num);
+            let id = if id_field.is_empty() {
+                format!("{}", record_num + 1)
+            } else {
+                match points.attributes.get_value(record_num, id_field) {
+                    FieldData::Int(v) => format!("{}", v),
+                    FieldData::Real(v) => format!("{}", v),
+                    FieldData::Text(v) => v.trim().to_string(),
+                    FieldData::Date(v) => format!("{}", v),
+                    FieldData::Bool(v) => format!("{}", v),
+                    FieldData::Null => format!("{}", record_num + 1),
+                }
+            };
+            gauges.push((id, record.points[0].x, record.points[0].y));
+        }
+    }
+    Ok(gauges)
+}
+
+/// Converts a set of polygon rings, with clockwise outer rings and counter-clockwise holes, into
+/// a WKT POLYGON or MULTIPOLYGON. In the WKT, outer rings are counter-clockwise and holes are
+/// clockwise.
+fn to_wkt(rings: &[Vec<Point2D>]) -> String {
+    let is_hole = |ring: &Vec<Point2D>| -> bool {
+        let mut area = 0f64;
+        for i in 0..ring.len() - 1 {
+            area += ring[i].x * ring[i + 1].y - ring[i + 1].x * ring[i].y;
+        }
+        area > 0f64
+    };
+    let mut polygons: Vec<Vec<&Vec<Point2D>>> = rings
+        .iter()
+        .filter(|r| !is_hole(r))
+        .map(|r| vec![r])
+        .collect();
+    for ring in rings.iter().filter(|r| is_hole(r)) {
+        // the midpoint of the hole's first edge cannot lie on another ring
+        let p = Point2D::midpoint(&ring[0], &ring[1]);
+        for polygon in polygons.iter_mut() {
+            if point_in_poly(&p, polygon[0]) {
+                polygon.push(ring);
+                break;
+            }
+        }
+    }
+    let ring_wkt = |ring: &Vec<Point2D>| -> String {
+        let coords: Vec<String> = ring
+            .iter()
+            .rev()
+            .map(|p| format!("{} {}", p.x, p.y))
+            .collect();
+        format!("({})", coords.join(", "))
+    };
+    let polygons_wkt: Vec<String> = polygons
+        .iter()
+        .map(|polygon| {
+            let parts: Vec<String> = polygon.iter().map(|r| ring_wkt(r)).collect();
+            format!("({})", parts.join(", "))
+        })
+        .collect();
+    if polygons_wkt.len() == 1 {
+        format!("POLYGON {}", polygons_wkt[0])
+    } else {
+        format!("MULTIPOLYGON ({})", polygons_wkt.join(", "))
+    }
+}
@@ -0,0 +1,714 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::trace_region_boundaries;
+use crate::raster::*;
+use crate::structures::{Array2D, Point2D};
+use crate::tools::*;
+use crate::vector::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool delineates geomorphic floodplains from a digital elevation model (DEM) and a raster
+/// stream network, using terrain alone. It combines three terrain descriptors:
+///
+/// 1. The height above nearest drainage (HAND), i.e. the elevation of each grid cell above the
+///    stream cell into which it drains, measured along the D8 flowpath, as calculated by the
+///    `ElevationAboveStream` tool.
+/// 2. The Strahler order of the stream cell into which each grid cell drains. Larger rivers have
+///    deeper channels and higher floods, and so the HAND threshold of a cell is scaled by the
+///    stream order, *h* = *d* × *order*<sup>*b*</sup>, where *d* is a flood depth and *b* is the
+///    order-scaling exponent (`--order_exponent`). An exponent of zero applies the same threshold
+///    to all streams.
+/// 3. The valley-bottom flatness, measured from the local slope gradient. Cells steeper than the
+///    maximum slope (`--max_slope`, in degrees) are not part of the valley bottom and are excluded
+///    from the floodplain; the flatness of the remaining cells is 1 - slope / max slope.
+///
+/// Floodplains are delineated for several flood depths (`--depths`), which act as proxies for
+/// increasing return periods, e.g. '1.0,2.0,4.0'. The output raster (`--output`) contains the
+/// index of the most frequent (shallowest) flood level that inundates each cell, i.e. 1 for the
+/// first depth, 2 for the second, and so on, with zero for cells outside of the floodplain at all
+/// levels. The depths are sorted in increasing order. Each cell is assigned a confidence value,
+/// the average of its flatness and its relative depth below the threshold of its level,
+/// (*h* - HAND) / *h*; cells near the floodplain edge or on steeper ground have lower confidence.
+///
+/// Optionally, the floodplains may also be output as polygons (`--out_polygons`). One polygon is
+/// created for each connected area that is inundated at each level; the polygons of a level
+/// include the areas inundated by more frequent levels, such that the polygons of different levels
+/// are nested. The polygon attributes include the `LEVEL` index, the flood `DEPTH`, the `AREA`,
+/// and the mean (`CONFIDENCE`) confidence of the polygon's cells.
+///
+/// The DEM must have been pre-processed to remove artifact topographic depressions and flat areas
+/// (see `BreachDepressions`), and the streams raster, in which stream cells are designated by
+/// positive values, should have been derived from the same DEM, e.g. using `ExtractStreams`.
+///
+/// # See Also
+/// `ElevationAboveStream`, `StrahlerStreamOrder`, `ExtractStreams`, `BreachDepressions`
+pub struct GeomorphicFloodplain {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl GeomorphicFloodplain {
+    pub fn new() -> GeomorphicFloodplain {
+        // public constructor
+        let name = "GeomorphicFloodplain".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Delineates geomorphic floodplains at several flood depths from HAND, valley-bottom flatness, and stream order."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["-i".to_owned(), "--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file of flood levels.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Polygons File (optional)".to_owned(),
+            flags: vec!["--out_polygons".to_owned()],
+            description: "Optional output vector polygon file of floodplains.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Flood Depths".to_owned(),
+            flags: vec!["--depths".to_owned()],
+            description: "Comma-separated list of flood depths for first-order streams, one for each flood level.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: Some("1.0,2.0,4.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Stream Order Exponent".to_owned(),
+            flags: vec!["--order_exponent".to_owned()],
+            description: "Exponent used to scale the flood depths by Strahler stream order.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Valley-Bottom Slope (degrees)".to_owned(),
+            flags: vec!["--max_slope".to_owned()],
+            description: "Maximum slope gradient, in degrees, of floodplain cells.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("5.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif --streams=streams.tif -o=floodplain.tif --out_polygons=floodplain.shp --depths='1.0,2.0,4.0' --order_exponent=0.5 --max_slope=5.0", short_exe, name).replace("*", &sep);
+
+        GeomorphicFloodplain {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for GeomorphicFloodplain {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut streams_file = String::new();
+        let mut output_file = String::new();
+        let mut polygons_file = String::new();
+        let mut depths_str = "1.0,2.0,4.0".to_string();
+        let mut order_exponent = 0.5f64;
+        let mut max_slope = 5f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-dem" || flag_val == "-i" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_polygons" {
+                polygons_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-depths" {
+                depths_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-order_exponent" {
+                order_exponent = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_slope" {
+                max_slope = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(&dem_file, working_directory);
+        streams_file = resolve_path(&streams_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        polygons_file = resolve_path(&polygons_file, working_directory);
+
+        let mut depths = vec![];
+        for value in depths_str.split([',', ';']) {
+            if !value.trim().is_empty() {
+                let d = value.trim().parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Invalid flood depth '{}'.", value.trim()),
+                    )
+                })?;
+                if d <= 0f64 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "Flood depths must be greater than zero.",
+                    ));
+                }
+                depths.push(d);
+            }
+        }
+        if depths.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one flood depth must be specified.",
+            ));
+        }
+        depths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        if max_slope <= 0f64 || max_slope >= 90f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The maximum slope must be between 0 and 90 degrees.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let dem = Raster::new(&dem_file, "r")?;
+        let streams = Raster::new(&streams_file, "r")?;
+
+        let start = Instant::now();
+
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+        let streams_nodata = streams.configs.nodata;
+
+        // make sure the input files have the same size
+        if dem.configs.rows != streams.configs.rows
+            || dem.configs.columns != streams.configs.columns
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must have the same number of rows and columns and spatial extent.",
+            ));
+        }
+
+        // cell sizes in metres are needed for slopes in geographic coordinates
+        let is_geographic = dem.is_in_geographic_coordinates();
+        let cell_sizes = |row: isize| -> (f64, f64) {
+            if is_geographic {
+                let lat = dem.get_y_from_row(row).to_radians();
+                (
+                    dem.configs.resolution_x * 111319.49f64 * lat.cos(),
+                    dem.configs.resolution_y * 111319.49f64,
+                )
+            } else {
+                (dem.configs.resolution_x, dem.configs.resolution_y)
+            }
+        };
+
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+        let inflowing_vals = [4i8, 5i8, 6i8, 7i8, 0i8, 1i8, 2i8, 3i8];
+        let is_stream = |row: isize, col: isize| -> bool {
+            let s = streams.get_value(row, col);
+            s > 0f64 && s != streams_nodata && dem.get_value(row, col) != nodata
+        };
+
+        // D8 flow directions and slope gradients
+        let mut flow_dir: Array2D<i8> = Array2D::new(rows, columns, -2i8, -2i8)?;
+        let mut flatness: Array2D<f64> = Array2D::new(rows, columns, -1f64, -1f64)?;
+        let mut interior_pit_found = false;
+        for row in 0..rows {
+            let (cell_size_x, cell_size_y) = cell_sizes(row);
+            let diag_cell_size = (cell_size_x * cell_size_x + cell_size_y * cell_size_y).sqrt();
+            let grid_lengths = [
+                diag_cell_size,
+                cell_size_x,
+                diag_cell_size,
+                cell_size_y,
+                diag_cell_size,
+                cell_size_x,
+                diag_cell_size,
+                cell_size_y,
+            ];
+            for col in 0..columns {
+                let z = dem.get_value(row, col);
+                if z == nodata {
+                    continue;
+                }
+                let mut dir = -1i8;
+                let mut max_drop = 0f64;
+                let mut neighbouring_nodata = false;
+                let mut zn = [z; 8];
+                for n in 0..8 {
+                    let z_n = dem.get_value(row + dy[n], col + dx[n]);
+                    if z_n != nodata {
+                        zn[n] = z_n;
+                        let drop = (z - z_n) / grid_lengths[n];
+                        if drop > max_drop {
+                            max_drop = drop;
+                            dir = n as i8;
+                        }
+                    } else {
+                        neighbouring_nodata = true;
+                    }
+                }
+                if dir == -1 && !neighbouring_nodata && !is_stream(row, col) {
+                    interior_pit_found = true;
+                }
+                flow_dir.set_value(row, col, dir);
+
+                // Horn's (1981) slope gradient, with NoData neighbours replaced by the cell
+                let fx = ((zn[0] + 2f64 * zn[1] + zn[2]) - (zn[6] + 2f64 * zn[5] + zn[4]))
+                    / (8f64 * cell_size_x);
+                let fy = ((zn[6] + 2f64 * zn[7] + zn[0]) - (zn[4] + 2f64 * zn[3] + zn[2]))
+                    / (8f64 * cell_size_y);
+                let tan_slope = (fx * fx + fy * fy).sqrt();
+                flatness.set_value(row, col, 1f64 - tan_slope.atan().to_degrees() / max_slope);
+            }
+            if verbose {
+                progress = (100.0_f64 * (row + 1) as f64 / rows as f64) as usize;
+                if progress != old_progress {
+                    println!("Flow directions: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Strahler order of the stream cells, processed downstream from the channel heads
+        let mut order: Array2D<u16> = Array2D::new(rows, columns, 0u16, 0u16)?;
+        let mut num_inflowing: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut max_inflowing_order: Array2D<u16> = Array2D::new(rows, columns, 0u16, 0u16)?;
+        let mut num_max_inflowing: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut stack = vec![];
+        for row in 0..rows {
+            for col in 0..columns {
+                if is_stream(row, col) {
+                    let mut count = 0u8;
+                    for n in 0..8 {
+                        if is_stream(row + dy[n], col + dx[n])
+                            && flow_dir.get_value(row + dy[n], col + dx[n]) == inflowing_vals[n]
+                        {
+                            count += 1;
+                        }
+                    }
+                    num_inflowing.set_value(row, col, count);
+                    if count == 0 {
+                        order.set_value(row, col, 1);
+                        stack.push((row, col));
+                    }
+                }
+            }
+        }
+        let mut max_order = 0u16;
+        while let Some((row, col)) = stack.pop() {
+            let o = order.get_value(row, col);
+            max_order = max_order.max(o);
+            let dir = flow_dir.get_value(row, col);
+            if dir < 0 {
+                continue;
+            }
+            let (row_n, col_n) = (row + dy[dir as usize], col + dx[dir as usize]);
+            if !is_stream(row_n, col_n) {
+                continue;
+            }
+            let m = max_inflowing_order.get_value(row_n, col_n);
+            if o > m {
+                max_inflowing_order.set_value(row_n, col_n, o);
+                num_max_inflowing.set_value(row_n, col_n, 1);
+            } else if o == m {
+                num_max_inflowing.increment(row_n, col_n, 1);
+            }
+            let remaining = num_inflowing.get_value(row_n, col_n) - 1;
+            num_inflowing.set_value(row_n, col_n, remaining);
+            if remaining == 0 {
+                let m = max_inflowing_order.get_value(row_n, col_n);
+                let count = num_max_inflowing.get_value(row_n, col_n);
+                order.set_value(row_n, col_n, if count > 1 { m + 1 } else { m });
+                stack.push((row_n, col_n));
+            }
+        }
+
+        // HAND, and the order of the stream that each cell drains to, traced upslope from streams
+        let background_value = f64::MIN;
+        let mut hand: Array2D<f64> = Array2D::new(rows, columns, background_value, nodata)?;
+        let mut drains_to: Array2D<u16> = Array2D::new(rows, columns, 0u16, 0u16)?;
+        let mut stack = vec![];
+        for row in 0..rows {
+            for col in 0..columns {
+                if is_stream(row, col) {
+                    hand.set_value(row, col, 0f64);
+                    drains_to.set_value(row, col, order.get_value(row, col).max(1));
+                    stack.push((row, col, dem.get_value(row, col), order.get_value(row, col).max(1)));
+                }
+            }
+        }
+        while let Some((row, col, stream_elev, stream_order)) = stack.pop() {
+            for n in 0..8 {
+                let (row_n, col_n) = (row + dy[n], col + dx[n]);
+                if flow_dir.get_value(row_n, col_n) == inflowing_vals[n]
+                    && hand.get_value(row_n, col_n) == background_value
+                {
+                    hand.set_value(row_n, col_n, dem.get_value(row_n, col_n) - stream_elev);
+                    drains_to.set_value(row_n, col_n, stream_order);
+                    stack.push((row_n, col_n, stream_elev, stream_order));
+                }
+            }
+        }
+
+        // flood levels and confidence
+        let num_levels = depths.len();
+        let mut level: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut confidence: Array2D<f64> = Array2D::new(rows, columns, 0f64, 0f64)?;
+        let mut output = Raster::initialize_using_file(&output_file, &dem);
+        output.configs.data_type = DataType::I16;
+        output.configs.nodata = -32768f64;
+        let out_nodata = output.configs.nodata;
+        let mut num_flooded = vec![0usize; num_levels];
+        for row in 0..rows {
+            for col in 0..columns {
+                if dem.get_value(row, col) == nodata {
+                    output.set_value(row, col, out_nodata);
+                    continue;
+                }
+                output.set_value(row, col, 0f64);
+                let h = hand.get_value(row, col);
+                let f = flatness.get_value(row, col);
+                if h == background_value || f < 0f64 {
+                    continue;
+                }
+                let scale = (drains_to.get_value(row, col) as f64).powf(order_exponent);
+                for k in 0..num_levels {
+                    let threshold = depths[k] * scale;
+                    if h <= threshold {
+                        level.set_value(row, col, (k + 1) as u8);
+                        confidence.set_value(
+                            row,
+                            col,
+                            0.5 * (f + ((threshold - h) / threshold).min(1f64)),
+                        );
+                        output.set_value(row, col, (k + 1) as f64);
+                        for count in num_flooded.iter_mut().skip(k) {
+                            *count += 1;
+                        }
+                        break;
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * (row + 1) as f64 / rows as f64) as usize;
+                if progress != old_progress {
+                    println!("Flood levels: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if !polygons_file.is_empty() {
+            let west = dem.configs.west;
+            let north = dem.configs.north;
+            let res_x = dem.configs.resolution_x;
+            let res_y = dem.configs.resolution_y;
+            let cell_area = |row: isize| -> f64 {
+                let (x, y) = cell_sizes(row);
+                x * y
+            };
+
+            let mut vector = Shapefile::new(&polygons_file, ShapeType::Polygon)?;
+            vector.projection = dem.configs.coordinate_ref_system_wkt.clone();
+            vector
+                .attributes
+                .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+            vector
+                .attributes
+                .add_field(&AttributeField::new("LEVEL", FieldDataType::Int, 3u8, 0u8));
+            vector
+                .attributes
+                .add_field(&AttributeField::new("DEPTH", FieldDataType::Real, 10u8, 3u8));
+            vector
+                .attributes
+                .add_field(&AttributeField::new("NUM_CELLS", FieldDataType::Int, 9u8, 0u8));
+            vector
+                .attributes
+                .add_field(&AttributeField::new("AREA", FieldDataType::Real, 14u8, 3u8));
+            vector
+                .attributes
+                .add_field(&AttributeField::new("CONFIDENCE", FieldDataType::Real, 7u8, 4u8));
+
+            let mut fid = 1i32;
+            let mut labels: Array2D<i32> = Array2D::new(rows, columns, -1, -1)?;
+            for k in 1..=num_levels as u8 {
+                let in_level = |row: isize, col: isize| -> bool {
+                    let l = level.get_value(row, col);
+                    l > 0 && l <= k
+                };
+                // label the connected areas of the level, using four-cell connectivity to match
+                // the separation of diagonally touching cells by the boundary tracing
+                labels.reinitialize_values(-1);
+                let mut label = 0i32;
+                for row in 0..rows {
+                    for col in 0..columns {
+                        if !in_level(row, col) || labels.get_value(row, col) != -1 {
+                            continue;
+                        }
+                        let (mut min_row, mut max_row, mut min_col, mut max_col) =
+                            (row, row, col, col);
+                        let (mut num_cells, mut area, mut sum_confidence) = (0usize, 0f64, 0f64);
+                        labels.set_value(row, col, label);
+                        let mut stack = vec![(row, col)];
+                        while let Some((r, c)) = stack.pop() {
+                            min_row = min_row.min(r);
+                            max_row = max_row.max(r);
+                            min_col = min_col.min(c);
+                            max_col = max_col.max(c);
+                            num_cells += 1;
+                            area += cell_area(r);
+                            sum_confidence += confidence.get_value(r, c);
+                            for (rn, cn) in [(r - 1, c), (r, c + 1), (r + 1, c), (r, c - 1)] {
+                                if rn >= 0
+                                    && cn >= 0
+                                    && rn < rows
+                                    && cn < columns
+                                    && in_level(rn, cn)
+                                    && labels.get_value(rn, cn) == -1
+                                {
+                                    labels.set_value(rn, cn, label);
+                                    stack.push((rn, cn));
+                                }
+                            }
+                        }
+
+                        let rings = trace_region_boundaries(
+                            max_row - min_row + 1,
+                            max_col - min_col + 1,
+                            |r, c| labels.get_value(r + min_row, c + min_col) == label,
+                        );
+                        let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+                        for ring in &rings {
+                            let points: Vec<Point2D> = ring
+                                .iter()
+                                .map(|p| {
+                                    Point2D::new(
+                                        west + (p.1 + min_col) as f64 * res_x,
+                                        north - (p.0 + min_row) as f64 * res_y,
+                                    )
+                                })
+                                .collect();
+                            sfg.add_part(&points);
+                        }
+                        vector.add_record(sfg);
+                        vector.attributes.add_record(
+                            vec![
+                                FieldData::Int(fid),
+                                FieldData::Int(k as i32),
+                                FieldData::Real(depths[k as usize - 1]),
+                                FieldData::Int(num_cells as i32),
+                                FieldData::Real(area),
+                                FieldData::Real(sum_confidence / num_cells as f64),
+                            ],
+                            false,
+                        );
+                        fid += 1;
+                        label += 1;
+                    }
+                }
+                if verbose {
+                    println!("Vectorizing flood level {} of {}", k, num_levels);
+                }
+            }
+
+            if verbose {
+                println!("Saving polygons...")
+            };
+            vector.write()?;
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.configs.palette = "qual.plt".to_string();
+        output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("DEM file: {}", dem_file));
+        output.add_metadata_entry(format!("Streams file: {}", streams_file));
+        output.add_metadata_entry(format!("Flood depths: {}", depths_str));
+        output.add_metadata_entry(format!("Stream order exponent: {}", order_exponent));
+        output.add_metadata_entry(format!("Maximum slope: {}", max_slope));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Maximum stream order: {}", max_order);
+            for k in 0..num_levels {
+                println!(
+                    "Level {} (depth {}): {} flooded cells",
+                    k + 1,
+                    depths[k],
+                    num_flooded[k]
+                );
+            }
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        if interior_pit_found {
+            println!("**********************************************************************************");
+            println!("WARNING: Interior pit cells were found within the input DEM. It is likely that the
+            DEM needs to be processed to remove topographic depressions and flats prior to
+            running this tool.");
+            println!("**********************************************************************************");
+        }
+
+        Ok(())
+    }
+}
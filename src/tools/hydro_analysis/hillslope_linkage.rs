@@ -0,0 +1,572 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::structures::Array2D;
+use crate::tools::*;
+use std::collections::BTreeMap;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::path;
+
+/// This tool partitions the landscape into hillslopes relative to a stream network and labels each
+/// hillslope with the identifier of the stream link that it drains to, which is the spatial
+/// discretization required by many distributed and semi-distributed hydrological and erosion
+/// models (e.g. WEPP). Each stream link receives three kinds of hillslope: the left-bank and
+/// right-bank hillslopes, draining to the sides of the link, and, for links beginning at a channel
+/// head, the headwater (or top) hillslope draining to the channel head. The left and right banks
+/// are defined looking downstream.
+///
+/// Each hillslope is assigned an identifier following the TOPAZ convention, i.e. the receiving
+/// link identifier multiplied by ten plus a type code of 1 (headwater), 2 (right bank) or 3 (left
+/// bank). For example, the hillslope with an identifier of 153 is the left-bank hillslope of link
+/// 15. The link identifiers are the same as those output by the `StreamLinkIdentifier` tool, such
+/// that the hillslopes can be joined to other link attributes. Stream cells are assigned zero in
+/// the output raster (`--output`), and cells that do not drain to a stream, including those that
+/// drain off of the edge of the grid, are assigned NoData. The bank of a grid cell is determined by
+/// the side of the channel, relative to the local channel direction, from which its flow path
+/// enters the stream.
+///
+/// The user may optionally specify an output table (`--out_table`), a comma-separated values (CSV)
+/// file that lists, for each hillslope, its identifier, receiving link (LINK), the link that the
+/// receiving link drains to (DS_LINK; zero for outlet links), hillslope type, number of grid cells,
+/// and area. The table describes the linkage of hillslopes to channels and channels to one another,
+/// as required for the parameterization of distributed models.
+///
+/// The user must specify the name of a flow pointer (flow direction) raster (`--d8_pntr`) and a
+/// streams raster (`--streams`), both of which should be generated using the `D8Pointer` algorithm
+/// applied to a depressionless DEM, processed using either the `BreachDepressions` or
+/// `FillDepressions` tool. By default, the pointer raster is assumed to use the clockwise indexing
+/// method used by WhiteboxTools. If the pointer file contains ESRI flow direction values instead,
+/// the `--esri_pntr` parameter must be specified.
+///
+/// # See Also
+/// `Hillslopes`, `StreamLinkIdentifier`, `Subbasins`, `D8Pointer`
+pub struct HillslopeLinkage {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl HillslopeLinkage {
+    pub fn new() -> HillslopeLinkage {
+        // public constructor
+        let name = "HillslopeLinkage".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Delineates headwater, left-bank and right-bank hillslopes labelled by their receiving stream link."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input raster D8 pointer file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster hillslope file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Linkage Table (optional)".to_owned(),
+            flags: vec!["--out_table".to_owned()],
+            description: "Optional output CSV table of hillslope-to-link linkages.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Csv),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --streams='streams.tif' -o='hillslopes.tif' --out_table='linkage.csv'", short_exe, name).replace("*", &sep);
+
+        HillslopeLinkage {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for HillslopeLinkage {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut d8_file = String::new();
+        let mut streams_file = String::new();
+        let mut output_file = String::new();
+        let mut table_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_table" {
+                table_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        d8_file = resolve_path(&d8_file, working_directory);
+        streams_file = resolve_path(&streams_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        table_file = resolve_path(&table_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let pntr = Raster::new(&d8_file, "r")?;
+        let streams = Raster::new(&streams_file, "r")?;
+
+        let start = Instant::now();
+
+        let rows = pntr.configs.rows as isize;
+        let columns = pntr.configs.columns as isize;
+        let nodata = streams.configs.nodata;
+        let pntr_nodata = pntr.configs.nodata;
+
+        // make sure the input files have the same size
+        if streams.configs.rows != pntr.configs.rows
+            || streams.configs.columns != pntr.configs.columns
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must have the same number of rows and columns and spatial extent.",
+            ));
+        }
+
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+        let mut inflowing_vals = [16f64, 32f64, 64f64, 128f64, 1f64, 2f64, 4f64, 8f64];
+        if esri_style {
+            inflowing_vals = [8f64, 16f64, 32f64, 64f64, 128f64, 1f64, 2f64, 4f64];
+        }
+
+        // Create a mapping from the pointer values to cells offsets.
+        let mut pntr_matches: [usize; 129] = [999usize; 129];
+        if !esri_style {
+            // This maps Whitebox-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 0usize;
+            pntr_matches[2] = 1usize;
+            pntr_matches[4] = 2usize;
+            pntr_matches[8] = 3usize;
+            pntr_matches[16] = 4usize;
+            pntr_matches[32] = 5usize;
+            pntr_matches[64] = 6usize;
+            pntr_matches[128] = 7usize;
+        } else {
+            // This maps Esri-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 1usize;
+            pntr_matches[2] = 2usize;
+            pntr_matches[4] = 3usize;
+            pntr_matches[8] = 4usize;
+            pntr_matches[16] = 5usize;
+            pntr_matches[32] = 6usize;
+            pntr_matches[64] = 7usize;
+            pntr_matches[128] = 0usize;
+        }
+
+        // returns the index of the downslope neighbour of a cell, if it has one
+        let flow_dir = |row: isize, col: isize| -> Result<Option<usize>, Error> {
+            let z = pntr.get_value(row, col);
+            if z == pntr_nodata || z <= 0f64 {
+                return Ok(None);
+            }
+            let dir = z as usize;
+            if dir > 128 || pntr_matches[dir] == 999 {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "An unexpected value has been identified in the pointer image. This tool requires a pointer grid that has been created using either the D8 or Rho8 tools."));
+            }
+            Ok(Some(pntr_matches[dir]))
+        };
+        let is_stream =
+            |row: isize, col: isize| -> bool { let z = streams.get_value(row, col); z > 0f64 && z != nodata };
+
+        // Assign each stream link a unique identifier, in the same way as StreamLinkIdentifier
+        let mut link_id: Array2D<i32> = Array2D::new(rows, columns, 0, 0)?;
+        let mut num_inflowing: Array2D<i8> = Array2D::new(rows, columns, -1, -1)?;
+        let mut is_head: Array2D<u8> = Array2D::new(rows, columns, 0, 0)?;
+        let mut stack = vec![];
+        let mut current_id = 1i32;
+        let mut count: i8;
+        for row in 0..rows {
+            for col in 0..columns {
+                if is_stream(row, col) {
+                    count = 0i8;
+                    for i in 0..8 {
+                        if is_stream(row + dy[i], col + dx[i])
+                            && pntr.get_value(row + dy[i], col + dx[i]) == inflowing_vals[i]
+                        {
+                            count += 1;
+                        }
+                    }
+                    num_inflowing.set_value(row, col, count);
+                    if count == 0 {
+                        // It's a headwater; add it to the stack
+                        stack.push((row, col));
+                        is_head.set_value(row, col, 1u8);
+                        link_id.set_value(row, col, current_id);
+                        current_id += 1;
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Identifying stream links: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        while let Some((row, col)) = stack.pop() {
+            let id = link_id.get_value(row, col);
+            if let Some(c) = flow_dir(row, col)? {
+                let (row_n, col_n) = (row + dy[c], col + dx[c]);
+                if !is_stream(row_n, col_n) {
+                    continue;
+                }
+                if num_inflowing.get_value(row_n, col_n) > 1 {
+                    current_id += 1;
+                    link_id.set_value(row_n, col_n, current_id);
+                } else if link_id.get_value(row_n, col_n) == 0 {
+                    link_id.set_value(row_n, col_n, id);
+                }
+                num_inflowing.decrement(row_n, col_n, 1);
+                if num_inflowing.get_value(row_n, col_n) == 0 {
+                    stack.push((row_n, col_n));
+                }
+            }
+        }
+
+        // the link that each link drains to, or zero for outlet links
+        let mut downstream_link: BTreeMap<i32, i32> = BTreeMap::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                let id = link_id.get_value(row, col);
+                if id > 0 {
+                    let ds = downstream_link.entry(id).or_insert(0);
+                    if let Some(c) = flow_dir(row, col)? {
+                        let id_n = link_id.get_value(row + dy[c], col + dx[c]);
+                        if id_n > 0 && id_n != id {
+                            *ds = id_n;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Label the hillslopes. The label of each cell is that of its downslope neighbour, unless the
+        // neighbour is a stream cell, in which case the side of the channel from which the flow path
+        // enters the stream determines the hillslope type.
+        let unsolved = -2i32;
+        let no_hillslope = -1i32;
+        let mut label: Array2D<i32> = Array2D::new(rows, columns, unsolved, no_hillslope)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                if pntr.get_value(row, col) == pntr_nodata {
+                    label.set_value(row, col, no_hillslope);
+                } else if is_stream(row, col) {
+                    label.set_value(row, col, 0);
+                }
+            }
+        }
+
+        // the hillslope type of a cell (r, c) entering the stream at stream cell (rs, cs)
+        let entry_type = |rs: isize, cs: isize, r: isize, c: isize| -> Result<i32, Error> {
+            if is_head.get_value(rs, cs) == 1u8 {
+                return Ok(1);
+            }
+            // the local channel direction, in map coordinates (y up), is the sum of the outflowing
+            // and inflowing channel directions
+            let id = link_id.get_value(rs, cs);
+            let mut d_out = (0f64, 0f64);
+            if let Some(k) = flow_dir(rs, cs)? {
+                d_out = (dx[k] as f64, -dy[k] as f64);
+            }
+            let mut d_in = (0f64, 0f64);
+            for i in 0..8 {
+                if is_stream(rs + dy[i], cs + dx[i])
+                    && pntr.get_value(rs + dy[i], cs + dx[i]) == inflowing_vals[i]
+                {
+                    d_in = (-dx[i] as f64, dy[i] as f64);
+                    if link_id.get_value(rs + dy[i], cs + dx[i]) == id {
+                        break;
+                    }
+                }
+            }
+            let v = ((c - cs) as f64, -(r - rs) as f64);
+            let cross = |t: (f64, f64)| t.0 * v.1 - t.1 * v.0;
+            let mut side = cross((d_out.0 + d_in.0, d_out.1 + d_in.1));
+            if side == 0f64 {
+                side = cross(d_out);
+            }
+            if side == 0f64 {
+                side = cross(d_in);
+            }
+            Ok(if side > 0f64 { 3 } else { 2 })
+        };
+
+        let mut path = vec![];
+        for row in 0..rows {
+            for col in 0..columns {
+                if label.get_value(row, col) != unsolved {
+                    continue;
+                }
+                path.clear();
+                let (mut r, mut c) = (row, col);
+                let value: i32;
+                loop {
+                    path.push((r, c));
+                    match flow_dir(r, c)? {
+                        Some(k) => {
+                            let (rn, cn) = (r + dy[k], c + dx[k]);
+                            let ln = label.get_value(rn, cn);
+                            if ln == 0 {
+                                // entering a stream
+                                value = link_id.get_value(rn, cn) * 10 + entry_type(rn, cn, r, c)?;
+                                break;
+                            } else if ln != unsolved {
+                                value = ln;
+                                break;
+                            }
+                            if path.len() as isize > rows * columns {
+                                return Err(Error::new(ErrorKind::InvalidInput,
+                                    "A flow path cycle has been identified in the pointer image."));
+                            }
+                            r = rn;
+                            c = cn;
+                        }
+                        None => {
+                            value = no_hillslope;
+                            break;
+                        }
+                    }
+                }
+                for &(pr, pc) in &path {
+                    label.set_value(pr, pc, value);
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Labelling hillslopes: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let mut output = Raster::initialize_using_file(&output_file, &pntr);
+        output.configs.data_type = DataType::I32;
+        output.configs.nodata = nodata;
+        output.configs.palette = "qual.plt".to_string();
+        output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+        let mut num_cells: BTreeMap<i32, usize> = BTreeMap::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                let l = label.get_value(row, col);
+                if l == no_hillslope {
+                    output.set_value(row, col, nodata);
+                } else {
+                    output.set_value(row, col, l as f64);
+                    if l > 0 {
+                        *num_cells.entry(l).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        if !table_file.is_empty() {
+            if verbose {
+                println!("Writing linkage table...")
+            };
+            let cell_area = pntr.configs.resolution_x * pntr.configs.resolution_y;
+            let f = File::create(&table_file)?;
+            let mut writer = BufWriter::new(f);
+            writer.write_all("HILLSLOPE,LINK,DS_LINK,TYPE,NUM_CELLS,AREA\n".as_bytes())?;
+            for (id, n) in &num_cells {
+                let link = id / 10;
+                let hillslope_type = match id % 10 {
+                    1 => "headwater",
+                    2 => "right",
+                    _ => "left",
+                };
+                writer.write_all(
+                    format!(
+                        "{},{},{},{},{},{}\n",
+                        id,
+                        link,
+                        downstream_link.get(&link).unwrap_or(&0),
+                        hillslope_type,
+                        n,
+                        *n as f64 * cell_area
+                    )
+                    .as_bytes(),
+                )?;
+            }
+            writer.flush()?;
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("D8 pointer file: {}", d8_file));
+        output.add_metadata_entry(format!("Streams file: {}", streams_file));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!(
+                "Number of stream links: {}; number of hillslopes: {}",
+                downstream_link.len(),
+                num_cells.len()
+            );
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,10 +1,13 @@
 // private sub-module defined in other files
+mod accumulation_engine;
+mod agree_burn;
 mod average_flowpath_slope;
 mod average_upslope_flowpath_length;
 mod basins;
 mod breach_depressions;
 mod breach_pits;
 mod burn_streams_at_roads;
+mod conditioning_audit;
 mod d8_flow_accum;
 mod d8_mass_flux;
 mod d8_pointer;
@@ -14,6 +17,7 @@ mod dinf_mass_flux;
 mod dinf_pointer;
 mod downslope_distance_to_stream;
 mod downslope_flowpath_length;
+mod downslope_statistic;
 mod elevation_above_stream;
 mod elevation_above_stream_euclidean;
 mod fd8_flow_accum;
@@ -23,10 +27,14 @@ mod fill_depressions;
 mod fill_pits;
 mod find_noflow_cells;
 mod find_parallel_flow;
+mod find_pits_and_flats;
 mod flatten_lakes;
 mod flood_order;
 mod flow_accum_full_workflow;
 mod flow_length_diff;
+mod gauge_catchments;
+mod geomorphic_floodplain;
+mod hillslope_linkage;
 mod hillslopes;
 mod impoundment_index;
 mod isobasins;
@@ -35,7 +43,9 @@ mod longest_flowpath;
 mod max_upslope_flowpath;
 mod num_inflowing_neighbours;
 mod raise_walls;
+mod relative_elevation_model;
 mod rho8_pointer;
+mod sea_level_rise_inundation;
 mod sink;
 mod snap_pour_points;
 mod stochastic_depression_analysis;
@@ -43,15 +53,19 @@ mod strahler_basins;
 mod subbasins;
 mod trace_downslope_flowpaths;
 mod unnest_basins;
+mod upslope_statistic;
 mod watershed;
+mod watershed_stratified_sample;
 
 // exports identifiers from private sub-modules in the current module namespace
+pub use self::agree_burn::AgreeBurn;
 pub use self::average_flowpath_slope::AverageFlowpathSlope;
 pub use self::average_upslope_flowpath_length::AverageUpslopeFlowpathLength;
 pub use self::basins::Basins;
 pub use self::breach_depressions::BreachDepressions;
 pub use self::breach_pits::BreachSingleCellPits;
 pub use self::burn_streams_at_roads::BurnStreamsAtRoads;
+pub use self::conditioning_audit::ConditioningAudit;
 pub use self::d8_flow_accum::D8FlowAccumulation;
 pub use self::d8_mass_flux::D8MassFlux;
 pub use self::d8_pointer::D8Pointer;
@@ -61,6 +75,7 @@ pub use self::dinf_mass_flux::DInfMassFlux;
 pub use self::dinf_pointer::DInfPointer;
 pub use self::downslope_distance_to_stream::DownslopeDistanceToStream;
 pub use self::downslope_flowpath_length::DownslopeFlowpathLength;
+pub use self::downslope_statistic::DownslopeStatistic;
 pub use self::elevation_above_stream::ElevationAboveStream;
 pub use self::elevation_above_stream_euclidean::ElevationAboveStreamEuclidean;
 pub use self::fd8_flow_accum::FD8FlowAccumulation;
@@ -70,10 +85,14 @@ pub use self::fill_depressions::FillDepressions;
 pub use self::fill_pits::FillSingleCellPits;
 pub use self::find_noflow_cells::FindNoFlowCells;
 pub use self::find_parallel_flow::FindParallelFlow;
+pub use self::find_pits_and_flats::FindPitsAndFlats;
 pub use self::flatten_lakes::FlattenLakes;
 pub use self::flood_order::FloodOrder;
 pub use self::flow_accum_full_workflow::FlowAccumulationFullWorkflow;
 pub use self::flow_length_diff::FlowLengthDiff;
+pub use self::gauge_catchments::GaugeCatchments;
+pub use self::geomorphic_floodplain::GeomorphicFloodplain;
+pub use self::hillslope_linkage::HillslopeLinkage;
 pub use self::hillslopes::Hillslopes;
 pub use self::impoundment_index::ImpoundmentSizeIndex;
 pub use self::isobasins::Isobasins;
@@ -82,7 +101,9 @@ pub use self::longest_flowpath::LongestFlowpath;
 pub use self::max_upslope_flowpath::MaxUpslopeFlowpathLength;
 pub use self::num_inflowing_neighbours::NumInflowingNeighbours;
 pub use self::raise_walls::RaiseWalls;
+pub use self::relative_elevation_model::RelativeElevationModel;
 pub use self::rho8_pointer::Rho8Pointer;
+pub use self::sea_level_rise_inundation::SeaLevelRiseInundation;
 pub use self::sink::Sink;
 pub use self::snap_pour_points::SnapPourPoints;
 pub use self::stochastic_depression_analysis::StochasticDepressionAnalysis;
@@ -90,4 +111,6 @@ pub use self::strahler_basins::StrahlerOrderBasins;
 pub use self::subbasins::Subbasins;
 pub use self::trace_downslope_flowpaths::TraceDownslopeFlowpaths;
 pub use self::unnest_basins::UnnestBasins;
+pub use self::upslope_statistic::UpslopeStatistic;
 pub use self::watershed::Watershed;
+pub use self::watershed_stratified_sample::WatershedStratifiedSample;
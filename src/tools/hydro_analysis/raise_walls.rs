@@ -206,20 +206,12 @@ impl WhiteboxTool for RaiseWalls {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !dem_file.contains(&sep) && !dem_file.contains("/") {
-            dem_file = format!("{}{}", working_directory, dem_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        dem_file = resolve_path(&dem_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -439,9 +431,7 @@ impl WhiteboxTool for RaiseWalls {
 
         // If breach lines are provided, breach the wall along the lines
         if !breach_file.trim().is_empty() {
-            if !breach_file.contains(&sep) && !breach_file.contains("/") {
-                breach_file = format!("{}{}", working_directory, breach_file);
-            }
+            breach_file = resolve_path(&breach_file, working_directory);
 
             let breach_data = Shapefile::read(&breach_file)?;
 
@@ -0,0 +1,570 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::structures::Point2D;
+use crate::tools::*;
+use crate::vector::{ShapeType, Shapefile};
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool creates a relative elevation model (REM), i.e. a digital elevation model (`--dem`)
+/// that has been detrended by subtracting the elevation of a water surface, or valley floor, that
+/// is interpolated from the elevations along a river centerline (`--streams`). Because the
+/// down-valley gradient is removed, the elevations of a REM are relative to the river, which makes
+/// fluvial landforms such as terraces, palaeochannels, levees, and floodplain extents apparent,
+/// and makes a REM useful for floodplain mapping.
+///
+/// The tool proceeds in three steps. First, the centerline vector, which must be of a POLYLINE
+/// shape type, is sampled at stations that are spaced at regular intervals (`--spacing`) along
+/// each of its lines, defaulting to the DEM's grid resolution. Second, the elevation of each
+/// station is estimated as a low percentile (`--percentile`) of the DEM elevations within a
+/// circular window (`--window`) around the station. Because a centerline rarely follows the
+/// lowest part of the channel exactly, and LiDAR DEMs often contain noise, vegetation, or bridges
+/// over the water surface, a low percentile estimates the water surface more robustly than the
+/// elevation beneath the station; a percentile of 50 uses the median elevation and a percentile
+/// of 0 uses the minimum. Third, the station elevations are interpolated across the valley using
+/// inverse-distance weighting (IDW) of the nearest stations (`--num_neighbours`, `--weight`), and
+/// the interpolated surface is subtracted from the DEM.
+///
+/// The output values are therefore the heights above the interpolated water surface, with
+/// negative values occurring below it. Optionally, cells that are farther than a maximum distance
+/// from the centerline (`--max_dist`), e.g. outside of the valley of interest, may be assigned
+/// NoData, and the interpolated water surface may be saved (`--trend`).
+///
+/// # See Also
+/// `ElevationAboveStreamEuclidean`, `ElevationAboveStream`, `GeomorphicFloodplain`
+pub struct RelativeElevationModel {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl RelativeElevationModel {
+    pub fn new() -> RelativeElevationModel {
+        // public constructor
+        let name = "RelativeElevationModel".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Detrends a DEM against a water surface interpolated from the elevations along a river centerline."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["-i".to_owned(), "--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input River Centerline File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input vector river centerline file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster relative elevation model file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Water Surface File (optional)".to_owned(),
+            flags: vec!["--trend".to_owned()],
+            description: "Optional output raster file of the interpolated water surface."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Station Spacing (optional)".to_owned(),
+            flags: vec!["--spacing".to_owned()],
+            description: "Distance between the sampling stations along the centerline; the DEM's grid resolution if unspecified."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Station Window Radius (optional)".to_owned(),
+            flags: vec!["--window".to_owned()],
+            description: "Radius of the window around each station within which DEM elevations are sampled; three grid cells if unspecified."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Elevation Percentile".to_owned(),
+            flags: vec!["--percentile".to_owned()],
+            description: "Percentile (0-100) of the elevations within a station's window used as the station elevation."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Neighbouring Stations".to_owned(),
+            flags: vec!["--num_neighbours".to_owned()],
+            description: "Number of nearest stations used to interpolate the water surface."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("8".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "IDW Weight (Exponent) Value".to_owned(),
+            flags: vec!["--weight".to_owned()],
+            description: "IDW weight value.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Distance (optional)".to_owned(),
+            flags: vec!["--max_dist".to_owned()],
+            description: "Optional maximum distance from the centerline; cells beyond it are assigned NoData."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=lidar_dem.tif --streams=centerline.shp -o=rem.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=lidar_dem.tif --streams=centerline.shp -o=rem.tif --trend=water_surface.tif --spacing=10.0 --window=15.0 --percentile=5 --num_neighbours=12 --max_dist=2000.0", short_exe, name).replace("*", &sep);
+
+        RelativeElevationModel {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for RelativeElevationModel {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_stem}_rem{input_ext}".to_string())
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut streams_file = String::new();
+        let mut output_file = String::new();
+        let mut trend_file = String::new();
+        let mut spacing = 0f64;
+        let mut window = 0f64;
+        let mut percentile = 10f64;
+        let mut num_neighbours = 8usize;
+        let mut weight = 2f64;
+        let mut max_dist = f64::INFINITY;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-trend" {
+                trend_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-spacing" {
+                spacing = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-window" {
+                window = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-percentile" {
+                percentile = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-num_neighbours" || flag_val == "-num_neighbors" {
+                num_neighbours = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-weight" {
+                weight = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_dist" {
+                max_dist = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+                if max_dist <= 0f64 {
+                    max_dist = f64::INFINITY;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let percentile = percentile.max(0f64).min(100f64);
+        let num_neighbours = num_neighbours.max(1);
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(&dem_file, working_directory);
+        streams_file = resolve_path(&streams_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        trend_file = resolve_path(&trend_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let dem = Arc::new(Raster::new(&dem_file, "r")?);
+        let streams = Shapefile::read(&streams_file)?;
+
+        // make sure the input vector file is of polyline type
+        if streams.header.shape_type.base_shape_type() != ShapeType::PolyLine {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The river centerline vector data must be of POLYLINE base shape type.",
+            ));
+        }
+
+        let start = Instant::now();
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+        let resolution = (dem.configs.resolution_x + dem.configs.resolution_y) / 2f64;
+        if spacing <= 0f64 {
+            spacing = resolution;
+        }
+        if window <= 0f64 {
+            window = 3f64 * resolution;
+        }
+
+        // Sample the centerline at regularly spaced stations, including the end of each line.
+        let mut stations: Vec<Point2D> = vec![];
+        for record_num in 0..streams.num_records {
+            let record = streams.get_record(record_num);
+            for part in 0..record.num_parts as usize {
+                let first_point_in_part = record.parts[part] as usize;
+                let last_point_in_part = if part < record.num_parts as usize - 1 {
+                    record.parts[part + 1] as usize - 1
+                } else {
+                    record.num_points as usize - 1
+                };
+                // the distance along the current segment to the next station
+                let mut next_station = 0f64;
+                for i in first_point_in_part..last_point_in_part {
+                    let (p1, p2) = (record.points[i], record.points[i + 1]);
+                    let segment_length = p1.distance(&p2);
+                    while next_station < segment_length {
+                        let t = next_station / segment_length;
+                        stations.push(Point2D::new(
+                            p1.x + t * (p2.x - p1.x),
+                            p1.y + t * (p2.y - p1.y),
+                        ));
+                        next_station += spacing;
+                    }
+                    next_station -= segment_length;
+                }
+                stations.push(record.points[last_point_in_part]);
+            }
+        }
+
+        // Estimate the elevation of each station from the DEM cells within its window.
+        let window_cells = (window / resolution).ceil() as isize;
+        let mut tree = KdTree::new_with_capacity(2, stations.len().max(1));
+        let mut num_stations = 0usize;
+        let mut values = vec![];
+        for (s, p) in stations.iter().enumerate() {
+            let row = dem.get_row_from_y(p.y);
+            let col = dem.get_column_from_x(p.x);
+            values.clear();
+            for r in (row - window_cells)..=(row + window_cells) {
+                for c in (col - window_cells)..=(col + window_cells) {
+                    let z = dem.get_value(r, c);
+                    if z != nodata {
+                        let dx = dem.get_x_from_column(c) - p.x;
+                        let dy = dem.get_y_from_row(r) - p.y;
+                        if dx * dx + dy * dy <= window * window {
+                            values.push(z);
+                        }
+                    }
+                }
+            }
+            if !values.is_empty() {
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                let i = (percentile / 100f64 * (values.len() - 1) as f64).round() as usize;
+                tree.add([p.x, p.y], values[i]).unwrap();
+                num_stations += 1;
+            }
+
+            if verbose {
+                progress = (100.0_f64 * s as f64 / (stations.len() - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Sampling centerline: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if num_stations == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The river centerline does not overlap any valid cells of the DEM.",
+            ));
+        }
+
+        // Interpolate the water surface using IDW and subtract it from the DEM.
+        let tree = Arc::new(tree);
+        let num_neighbours = num_neighbours.min(num_stations);
+        let max_dist_squared = max_dist * max_dist;
+        let num_procs = num_cpus::get() as isize;
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let dem = dem.clone();
+            let tree = tree.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                    let y = dem.get_y_from_row(row);
+                    let mut surface = vec![nodata; columns as usize];
+                    for col in 0..columns {
+                        if dem.get_value(row, col) == nodata {
+                            continue;
+                        }
+                        let x = dem.get_x_from_column(col);
+                        let neighbours = tree
+                            .nearest(&[x, y], num_neighbours, &squared_euclidean)
+                            .unwrap();
+                        if neighbours[0].0 > max_dist_squared {
+                            continue;
+                        }
+                        let mut sum_weights = 0f64;
+                        let mut sum_values = 0f64;
+                        let mut exact = None;
+                        for (dist_squared, z) in neighbours {
+                            if dist_squared == 0f64 {
+                                exact = Some(*z);
+                                break;
+                            }
+                            let w = 1f64 / dist_squared.sqrt().powf(weight);
+                            sum_weights += w;
+                            sum_values += w * z;
+                        }
+                        surface[col as usize] = match exact {
+                            Some(z) => z,
+                            None => sum_values / sum_weights,
+                        };
+                    }
+                    tx.send((row, surface)).unwrap();
+                }
+            });
+        }
+
+        let mut output = Raster::initialize_using_file(&output_file, &dem);
+        output.configs.data_type = DataType::F32;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        let mut trend = if !trend_file.is_empty() {
+            let mut trend = Raster::initialize_using_file(&trend_file, &dem);
+            trend.configs.data_type = DataType::F32;
+            trend.configs.photometric_interp = PhotometricInterpretation::Continuous;
+            Some(trend)
+        } else {
+            None
+        };
+        for r in 0..rows {
+            let (row, surface) = rx.recv().expect("Error receiving data from thread.");
+            let mut data = vec![nodata; columns as usize];
+            for col in 0..columns as usize {
+                if surface[col] != nodata {
+                    data[col] = dem.get_value(row, col as isize) - surface[col];
+                }
+            }
+            output.set_row_data(row, data);
+            if let Some(trend) = trend.as_mut() {
+                trend.set_row_data(row, surface);
+            }
+
+            if verbose {
+                progress = (100.0_f64 * r as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.configs.palette = "blue_white_red.plt".to_string();
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input DEM file: {}", dem_file));
+        output.add_metadata_entry(format!("Input river centerline file: {}", streams_file));
+        output.add_metadata_entry(format!("Station spacing: {}", spacing));
+        output.add_metadata_entry(format!("Station window radius: {}", window));
+        output.add_metadata_entry(format!("Elevation percentile: {}", percentile));
+        output.add_metadata_entry(format!("Num. neighbouring stations: {}", num_neighbours));
+        output.add_metadata_entry(format!("IDW weight: {}", weight));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(mut trend) = trend {
+            trend.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            trend.add_metadata_entry(format!("Input DEM file: {}", dem_file));
+            trend.add_metadata_entry(format!("Input river centerline file: {}", streams_file));
+            let _ = match trend.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Water surface file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
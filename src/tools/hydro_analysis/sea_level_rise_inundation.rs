@@ -0,0 +1,560 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::structures::Array2D;
+use crate::tools::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::collections::VecDeque;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::path;
+use std::path::Path;
+
+/// This tool maps the area inundated by each of a set of sea-level rise scenarios using a 'bathtub'
+/// model with a hydrological connectivity constraint. Unlike a simple threshold of the digital
+/// elevation model (DEM), a grid cell is only considered to be inundated under a given sea level
+/// (`--levels`) if its elevation is at or below that level *and* it is connected to the ocean through
+/// an 8-connected path of cells that are also at or below that level. Low-lying areas that are
+/// protected by higher ground (e.g. behind dykes or coastal ridges) are therefore not flooded.
+///
+/// The ocean is specified using the optional `--ocean` raster, in which any non-zero, valid cell is
+/// treated as ocean. If this raster is not specified, the ocean is assumed to be the NoData areas
+/// that are connected to the edges of the DEM, along with any valid cells along the DEM edges that
+/// are at or below an elevation of zero. The coastline cells adjacent to the ocean are the seeds of
+/// a priority-flood operation that determines, for every cell, the minimum sea level at which it
+/// becomes connected to the ocean. This allows all scenarios to be evaluated with a single pass.
+///
+/// For each sea level, the tool outputs an inundation extent raster (1 = inundated, 0 = dry) and
+/// a water depth raster (NoData where dry). The output file names are derived from the `--output`
+/// base file name by appending '_extent_*level*' and '_depth_*level*'. A summary table containing the
+/// number of inundated cells, inundated area (in map units squared), and mean and maximum depths of
+/// each scenario is written to the CSV file `--out_table`.
+///
+/// # See Also
+/// `FloodOrder`, `FillDepressions`, `DepthInSink`
+pub struct SeaLevelRiseInundation {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl SeaLevelRiseInundation {
+    pub fn new() -> SeaLevelRiseInundation {
+        // public constructor
+        let name = "SeaLevelRiseInundation".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description = "Maps hydrologically connected (bathtub) inundation for a set of sea-level rise scenarios.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["-i".to_owned(), "--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Ocean File (optional)".to_owned(),
+            flags: vec!["--ocean".to_owned()],
+            description: "Optional input raster in which non-zero cells are ocean.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Base File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output base raster file name; scenario names are appended.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Summary Table File".to_owned(),
+            flags: vec!["--out_table".to_owned()],
+            description: "Output CSV summary table file; defaults to the output base name with a '_summary.csv' suffix.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Csv),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Sea Levels".to_owned(),
+            flags: vec!["--levels".to_owned()],
+            description: "Comma-separated list of sea-level scenarios, in elevation units (e.g. '0.5,1.0,2.0').".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{} -r={} -v --wd=\"*path*to*data*\" --dem=DEM.tif --ocean=ocean.tif -o=slr.tif --out_table=slr_summary.csv --levels='0.5,1.0,2.0'",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        SeaLevelRiseInundation {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for SeaLevelRiseInundation {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut ocean_file = String::new();
+        let mut output_file = String::new();
+        let mut table_file = String::new();
+        let mut levels_str = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-dem" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-ocean" {
+                ocean_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_table" {
+                table_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-levels" {
+                levels_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        ocean_file = resolve_path(&ocean_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        if table_file.is_empty() {
+            table_file = match Path::new(&output_file).extension() {
+                Some(ext) => output_file.replace(
+                    &format!(".{}", ext.to_str().unwrap()),
+                    "_summary.csv",
+                ),
+                None => format!("{}_summary.csv", output_file),
+            };
+        }
+        table_file = resolve_path(&table_file, working_directory);
+
+        let mut cmd = levels_str.split(",");
+        let mut vec = cmd.collect::<Vec<&str>>();
+        if vec.len() == 1 {
+            cmd = levels_str.split(";");
+            vec = cmd.collect::<Vec<&str>>();
+        }
+        let mut levels = vec![];
+        for value in vec {
+            if !value.trim().is_empty() {
+                levels.push(match value.trim().parse::<f64>() {
+                    Ok(v) => v,
+                    Err(_) => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("Error parsing the sea level '{}'.", value.trim()),
+                        ))
+                    }
+                });
+            }
+        }
+        if levels.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one sea level must be specified (--levels).",
+            ));
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let input = Raster::new(&input_file, "r")?;
+
+        let start = Instant::now();
+        let rows = input.configs.rows as isize;
+        let columns = input.configs.columns as isize;
+        let num_cells = rows * columns;
+        let nodata = input.configs.nodata;
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+        let (mut row, mut col): (isize, isize);
+        let (mut row_n, mut col_n): (isize, isize);
+        let mut z: f64;
+
+        /*
+        Identify the ocean cells. These are either supplied by the user or are assumed to
+        be the nodata areas connected to the raster edges, as well as edge cells with
+        elevations at or below zero.
+        */
+        let mut is_ocean: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        if !ocean_file.is_empty() {
+            let ocean = Raster::new(&ocean_file, "r")?;
+            if ocean.configs.rows as isize != rows || ocean.configs.columns as isize != columns {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+            let ocean_nodata = ocean.configs.nodata;
+            for row in 0..rows {
+                for col in 0..columns {
+                    z = ocean[(row, col)];
+                    if z != ocean_nodata && z != 0f64 {
+                        is_ocean.set_value(row, col, 1u8);
+                    }
+                }
+            }
+        } else {
+            let mut queue: VecDeque<(isize, isize)> = VecDeque::new();
+            for row in 0..rows {
+                queue.push_back((row, 0));
+                queue.push_back((row, columns - 1));
+            }
+            for col in 0..columns {
+                queue.push_back((0, col));
+                queue.push_back((rows - 1, col));
+            }
+            while let Some(cell) = queue.pop_front() {
+                row = cell.0;
+                col = cell.1;
+                if is_ocean.get_value(row, col) == 1u8 {
+                    continue;
+                }
+                z = input[(row, col)];
+                if z == nodata {
+                    is_ocean.set_value(row, col, 1u8);
+                    for n in 0..8 {
+                        row_n = row + dy[n];
+                        col_n = col + dx[n];
+                        if row_n >= 0 && row_n < rows && col_n >= 0 && col_n < columns {
+                            if is_ocean.get_value(row_n, col_n) == 0u8
+                                && input[(row_n, col_n)] == nodata
+                            {
+                                queue.push_back((row_n, col_n));
+                            }
+                        }
+                    }
+                } else if z <= 0f64
+                    && (row == 0 || row == rows - 1 || col == 0 || col == columns - 1)
+                {
+                    is_ocean.set_value(row, col, 1u8);
+                }
+            }
+        }
+
+        /*
+        Perform a priority-flood operation seeded at the ocean cells. The flood level of each
+        cell is the minimum sea level at which the cell becomes connected to the ocean.
+        */
+        let background_val = f64::NEG_INFINITY;
+        let mut flood_level: Array2D<f64> = Array2D::new(rows, columns, background_val, nodata)?;
+        let mut minheap = BinaryHeap::with_capacity(num_cells as usize);
+        for row in 0..rows {
+            for col in 0..columns {
+                if is_ocean.get_value(row, col) == 1u8 {
+                    z = input[(row, col)];
+                    if z != nodata {
+                        flood_level.set_value(row, col, z);
+                        minheap.push(GridCell {
+                            row: row,
+                            column: col,
+                            priority: z,
+                        });
+                    } else {
+                        // nodata ocean cells seed their valid neighbours
+                        for n in 0..8 {
+                            row_n = row + dy[n];
+                            col_n = col + dx[n];
+                            z = input[(row_n, col_n)];
+                            if z != nodata
+                                && flood_level.get_value(row_n, col_n) == background_val
+                            {
+                                flood_level.set_value(row_n, col_n, z);
+                                minheap.push(GridCell {
+                                    row: row_n,
+                                    column: col_n,
+                                    priority: z,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut num_solved_cells = 0;
+        let mut level: f64;
+        while let Some(cell) = minheap.pop() {
+            row = cell.row;
+            col = cell.column;
+            level = flood_level.get_value(row, col);
+            for n in 0..8 {
+                row_n = row + dy[n];
+                col_n = col + dx[n];
+                if row_n >= 0 && row_n < rows && col_n >= 0 && col_n < columns {
+                    if flood_level.get_value(row_n, col_n) == background_val {
+                        z = input[(row_n, col_n)];
+                        if z != nodata {
+                            // a cell cannot be reached at a level lower than its neighbour's spill level
+                            z = z.max(level);
+                            flood_level.set_value(row_n, col_n, z);
+                            minheap.push(GridCell {
+                                row: row_n,
+                                column: col_n,
+                                priority: z,
+                            });
+                        }
+                    }
+                }
+            }
+
+            if verbose {
+                num_solved_cells += 1;
+                progress = (100.0_f64 * num_solved_cells as f64 / (num_cells - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Flooding: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let cell_area = input.configs.resolution_x * input.configs.resolution_y;
+        let mut summary = vec![];
+        let (base_name, extension) = match Path::new(&output_file).extension() {
+            Some(ext) => {
+                let ext = ext.to_str().unwrap().to_string();
+                (
+                    output_file[0..output_file.len() - ext.len() - 1].to_string(),
+                    ext,
+                )
+            }
+            None => (output_file.clone(), "tif".to_string()),
+        };
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        for (scenario, sea_level) in levels.iter().enumerate() {
+            let extent_file = format!("{}_extent_{}.{}", base_name, sea_level, extension);
+            let depth_file = format!("{}_depth_{}.{}", base_name, sea_level, extension);
+            let mut extent = Raster::initialize_using_file(&extent_file, &input);
+            extent.configs.data_type = DataType::I16;
+            extent.configs.photometric_interp = PhotometricInterpretation::Categorical;
+            let mut depth = Raster::initialize_using_file(&depth_file, &input);
+            depth.configs.data_type = DataType::F32;
+            depth.configs.photometric_interp = PhotometricInterpretation::Continuous;
+            let mut num_inundated = 0usize;
+            let mut depth_sum = 0f64;
+            let mut max_depth = 0f64;
+            let mut d: f64;
+            for row in 0..rows {
+                for col in 0..columns {
+                    z = input[(row, col)];
+                    if z != nodata {
+                        level = flood_level.get_value(row, col);
+                        if level != background_val && level <= *sea_level {
+                            d = sea_level - z;
+                            extent.set_value(row, col, 1f64);
+                            depth.set_value(row, col, d);
+                            num_inundated += 1;
+                            depth_sum += d;
+                            if d > max_depth {
+                                max_depth = d;
+                            }
+                        } else {
+                            extent.set_value(row, col, 0f64);
+                        }
+                    }
+                }
+            }
+
+            summary.push((
+                *sea_level,
+                num_inundated,
+                num_inundated as f64 * cell_area,
+                if num_inundated > 0 {
+                    depth_sum / num_inundated as f64
+                } else {
+                    0f64
+                },
+                max_depth,
+            ));
+
+            for (output, palette) in vec![(&mut extent, "qual.plt"), (&mut depth, "blues.plt")] {
+                output.configs.palette = palette.to_string();
+                output.add_metadata_entry(format!(
+                    "Created by whitebox_tools\' {} tool",
+                    self.get_tool_name()
+                ));
+                output.add_metadata_entry(format!("Input file: {}", input_file));
+                if !ocean_file.is_empty() {
+                    output.add_metadata_entry(format!("Ocean file: {}", ocean_file));
+                }
+                output.add_metadata_entry(format!("Sea level: {}", sea_level));
+                output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+                let _ = match output.write() {
+                    Ok(_) => {}
+                    Err(e) => return Err(e),
+                };
+            }
+
+            if verbose {
+                progress = (100.0_f64 * (scenario + 1) as f64 / levels.len() as f64) as usize;
+                println!("Saving scenarios: {}%", progress);
+            }
+        }
+
+        let f = File::create(&table_file)?;
+        let mut writer = BufWriter::new(f);
+        writer.write_all("SEA_LEVEL,NUM_CELLS,AREA,MEAN_DEPTH,MAX_DEPTH\n".as_bytes())?;
+        for s in &summary {
+            writer.write_all(format!("{},{},{},{},{}\n", s.0, s.1, s.2, s.3, s.4).as_bytes())?;
+        }
+        let _ = writer.flush();
+
+        if verbose {
+            println!("Output files written");
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct GridCell {
+    row: isize,
+    column: isize,
+    priority: f64,
+}
+
+impl Eq for GridCell {}
+
+impl PartialOrd for GridCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GridCell {
+    fn cmp(&self, other: &GridCell) -> Ordering {
+        // reversed so that the BinaryHeap acts as a min-heap
+        other
+            .priority
+            .partial_cmp(&self.priority)
+            .unwrap_or(Ordering::Equal)
+    }
+}
@@ -206,20 +206,12 @@ impl WhiteboxTool for SnapPourPoints {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !pourpts_file.contains(&sep) && !pourpts_file.contains("/") {
-            pourpts_file = format!("{}{}", working_directory, pourpts_file);
-        }
-        if !flow_accum_file.contains(&sep) && !flow_accum_file.contains("/") {
-            flow_accum_file = format!("{}{}", working_directory, flow_accum_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        pourpts_file = resolve_path(&pourpts_file, working_directory);
+        flow_accum_file = resolve_path(&flow_accum_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -222,20 +222,12 @@ impl WhiteboxTool for TraceDownslopeFlowpaths {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !seed_file.contains(&sep) && !seed_file.contains("/") {
-            seed_file = format!("{}{}", working_directory, seed_file);
-        }
-        if !flowdir_file.contains(&sep) && !flowdir_file.contains("/") {
-            flowdir_file = format!("{}{}", working_directory, flowdir_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        seed_file = resolve_path(&seed_file, working_directory);
+        flowdir_file = resolve_path(&flowdir_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         // if verbose { println!("Reading destination data...") };
         // let seeds = Raster::new(&seed_file, "r")?;
@@ -0,0 +1,405 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::accumulation_engine::{
+    d8_flow_directions, dinf_flow_directions, upslope_accumulation, D8TieBreaking, FlowDirections,
+    Reducer,
+};
+use crate::raster::*;
+use crate::structures::Array2D;
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::Arc;
+
+/// This tool summarizes the values of a raster (`--input`) over the upslope area of each grid
+/// cell, i.e. over the cells whose flow passes through it, including itself. For example, it can
+/// be used to map the maximum upslope elevation, the minimum upslope slope, the mean catchment
+/// rainfall, or the total upslope impervious area. Flow is routed over a digital elevation model
+/// (`--dem`), which should have been pre-processed to remove artifact topographic depressions and
+/// flat areas (`BreachDepressions`, `FillDepressions`). If no input raster is specified, the
+/// values of the DEM are summarized, e.g. the default statistic is the maximum upslope elevation.
+///
+/// The statistic (`--stat`) may be one of `max`, `min`, `mean`, or `sum`. Flow may be routed
+/// (`--routing`) using either the `d8` (O'Callaghan and Mark, 1984) or the `dinf` (Tarboton, 1997)
+/// algorithm. With D-infinity routing, the upslope `mean` and `sum` account for the proportion of
+/// the flow from each upslope cell that passes through the cell, i.e. the mean is weighted by the
+/// specific contribution of each upslope cell. The `max` and `min` consider every cell that
+/// contributes any flow.
+///
+/// The statistics are calculated using the same accumulation engine as `D8FlowAccumulation`, in a
+/// single pass over the grid, i.e. the processing time is proportional to the number of grid
+/// cells rather than to the total length of the flowpaths. D8 statistics are calculated in
+/// parallel.
+///
+/// NoData valued cells in the DEM are assigned NoData in the output. NoData valued cells in the
+/// input raster do not contribute to the statistics of their downslope cells, and cells without
+/// any valid upslope values are assigned NoData, except for the `sum`, which is zero.
+///
+/// # See Also
+/// `DownslopeStatistic`, `D8FlowAccumulation`, `DInfFlowAccumulation`, `MaxUpslopeFlowpathLength`
+pub struct UpslopeStatistic {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl UpslopeStatistic {
+    pub fn new() -> UpslopeStatistic {
+        // public constructor
+        let name = "UpslopeStatistic".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Calculates a statistic of the values of a raster over the upslope area of each cell."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster DEM file, used for flow routing.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Values File (optional)".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description:
+                "Optional input raster of values to summarize; the DEM is used if unspecified."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Statistic".to_owned(),
+            flags: vec!["--stat".to_owned()],
+            description: "Upslope statistic; one of 'max' (default), 'min', 'mean', and 'sum'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "max".to_owned(),
+                "min".to_owned(),
+                "mean".to_owned(),
+                "sum".to_owned(),
+            ]),
+            default_value: Some("max".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Flow Routing".to_owned(),
+            flags: vec!["--routing".to_owned()],
+            description: "Flow routing algorithm; one of 'd8' (default) and 'dinf'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec!["d8".to_owned(), "dinf".to_owned()]),
+            default_value: Some("d8".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=max_upslope_elev.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -i=rainfall.tif -o=catchment_rainfall.tif --stat=mean --routing=dinf", short_exe, name).replace("*", &sep);
+
+        UpslopeStatistic {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for UpslopeStatistic {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_stem}_upslope{input_ext}".to_string())
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut stat = String::from("max");
+        let mut routing = String::from("d8");
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-stat" {
+                stat = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-routing" {
+                routing = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let reducer = if stat.contains("max") {
+            stat = String::from("max");
+            Reducer::Max
+        } else if stat.contains("min") {
+            stat = String::from("min");
+            Reducer::Min
+        } else if stat.contains("mean") || stat.contains("av") {
+            stat = String::from("mean");
+            Reducer::Sum
+        } else if stat.contains("sum") || stat.contains("total") {
+            stat = String::from("sum");
+            Reducer::Sum
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The statistic (--stat) must be one of 'max', 'min', 'mean', and 'sum'.",
+            ));
+        };
+        let use_dinf = routing.contains("inf");
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(&dem_file, working_directory);
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let dem = Arc::new(Raster::new(&dem_file, "r")?);
+        let values = if input_file.is_empty() {
+            dem.clone()
+        } else {
+            Arc::new(Raster::new(&input_file, "r")?)
+        };
+
+        let start = Instant::now();
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+        let values_nodata = values.configs.nodata;
+
+        if values.configs.rows != dem.configs.rows || values.configs.columns != dem.configs.columns
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must have the same number of rows and columns and spatial extent.",
+            ));
+        }
+
+        let (flow_dirs, interior_pit_found) = if use_dinf {
+            let (flow_dir, pit) = dinf_flow_directions(&dem, verbose)?;
+            (FlowDirections::DInf(Arc::new(flow_dir)), pit)
+        } else {
+            let (flow_dir, pit) = d8_flow_directions(&dem, D8TieBreaking::First, verbose)?;
+            (FlowDirections::D8(Arc::new(flow_dir)), pit)
+        };
+
+        // Each cell contributes its value to the statistic, or nothing if it is NoData. The mean
+        // is the ratio of the upslope sum of the values to the upslope count of valid values.
+        let mut weights: Array2D<f64> = Array2D::new(rows, columns, reducer.identity(), nodata)?;
+        let mut counts: Array2D<f64> = Array2D::new(rows, columns, 0f64, nodata)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = values.get_value(row, col);
+                if z != values_nodata {
+                    weights.set_value(row, col, z);
+                    counts.set_value(row, col, 1f64);
+                }
+            }
+        }
+
+        let acc =
+            upslope_accumulation(&dem, &flow_dirs, Some(Arc::new(weights)), reducer, verbose)?;
+        let counts = if stat == "mean" {
+            upslope_accumulation(
+                &dem,
+                &flow_dirs,
+                Some(Arc::new(counts)),
+                Reducer::Sum,
+                verbose,
+            )?
+        } else {
+            counts
+        };
+
+        let mut output = Raster::initialize_using_file(&output_file, &values);
+        output.configs.nodata = nodata;
+        output.configs.data_type = DataType::F32;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        for row in 0..rows {
+            let mut data = vec![nodata; columns as usize];
+            for col in 0..columns {
+                if dem.get_value(row, col) != nodata {
+                    let v = acc.get_value(row, col);
+                    if stat == "mean" {
+                        let n = counts.get_value(row, col);
+                        if n > 0f64 {
+                            data[col as usize] = v / n;
+                        }
+                    } else if v.is_finite() {
+                        data[col as usize] = v;
+                    }
+                }
+            }
+            output.set_row_data(row, data);
+
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input DEM file: {}", dem_file));
+        if !input_file.is_empty() {
+            output.add_metadata_entry(format!("Input values file: {}", input_file));
+        }
+        output.add_metadata_entry(format!("Statistic: {}", stat));
+        output.add_metadata_entry(format!(
+            "Flow routing: {}",
+            if use_dinf { "dinf" } else { "d8" }
+        ));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+        if interior_pit_found {
+            println!("**********************************************************************************");
+            println!(
+                "WARNING: Interior pit cells were found within the input DEM. It is likely that the
+            DEM needs to be processed to remove topographic depressions and flats prior to
+            running this tool."
+            );
+            println!("**********************************************************************************");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,573 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use crate::vector::*;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::collections::BTreeMap;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool creates a random sample of points that is stratified by sub-basin and, optionally,
+/// by the classes of a terrain attribute, e.g. slope classes. The resulting points are suited to
+/// the calibration and validation of distributed hydrological models, for which every sub-basin
+/// and every part of the range of the terrain conditions within it should be represented, and to
+/// the design of soil sampling campaigns.
+///
+/// The sub-basins raster (`--basins`) contains a unique identifier for each sub-basin, e.g. the
+/// output of the `Subbasins`, `Watershed`, or `Isobasins` tools. NoData and zero-valued cells in
+/// this raster, which commonly represent the background, are not sampled. If a stratification
+/// raster (`--strat`) is specified, the cells of each sub-basin are further divided into bins of
+/// the stratification raster's values. The bins may either be defined by a list of class breaks
+/// (`--breaks`), e.g. `--breaks='2;5;15'` divides slopes into the four classes less than 2, 2 to 5,
+/// 5 to 15, and greater than or equal to 15, or by a number of bins (`--num_bins`), in which case
+/// the breaks are the quantiles of the stratification raster's values, such that each bin
+/// contains roughly the same number of cells.
+///
+/// With the `equal` allocation method (`--allocation`), `--num_samples` points are sampled within
+/// each stratum, i.e. each combination of sub-basin and bin. With the `proportional` method,
+/// `--num_samples` is the total number of points, which are allocated to the strata in proportion
+/// to their areas, with at least one point in every stratum. A stratum that contains fewer cells
+/// than its allocation is sampled entirely. Points are located at the centres of the sampled grid
+/// cells and no cell is sampled more than once. A random seed (`--seed`) may be specified to
+/// create a reproducible sample.
+///
+/// The output vector's attribute table contains the sub-basin (`BASIN`) and bin (`BIN`, starting
+/// at 1) of each point, and the values of the stratification raster and of any other attribute
+/// rasters (`--attributes`), e.g. elevation, wetness index, or land cover, extracted at the point.
+/// These fields are named after the raster files. Cells for which any of these rasters is NoData
+/// are not sampled. All of the input rasters must have the same extent and grid resolution.
+///
+/// # See Also
+/// `RandomSample`, `Subbasins`, `Watershed`, `ExtractRasterValuesAtPoints`
+pub struct WatershedStratifiedSample {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl WatershedStratifiedSample {
+    pub fn new() -> WatershedStratifiedSample {
+        // public constructor
+        let name = "WatershedStratifiedSample".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Creates random sample points stratified by sub-basin and by terrain attribute classes."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Sub-basins File".to_owned(),
+            flags: vec!["--basins".to_owned()],
+            description:
+                "Input raster sub-basins file, containing a unique identifier for each sub-basin."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Stratification File (optional)".to_owned(),
+            flags: vec!["--strat".to_owned()],
+            description: "Optional input raster terrain attribute (e.g. slope) used to stratify the sub-basins into bins."
+                .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Attribute Files (optional)".to_owned(),
+            flags: vec!["--attributes".to_owned()],
+            description:
+                "Optional input raster files whose values are extracted at the sample points."
+                    .to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector points file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Bins".to_owned(),
+            flags: vec!["--num_bins".to_owned()],
+            description: "Number of quantile bins of the stratification raster; ignored if class breaks are specified."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("3".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Class Breaks (optional)".to_owned(),
+            flags: vec!["--breaks".to_owned()],
+            description: "Optional semicolon- or comma-separated class breaks of the stratification raster, e.g. '2;5;15'."
+                .to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Num. Samples".to_owned(),
+            flags: vec!["--num_samples".to_owned()],
+            description: "Number of samples per stratum ('equal' allocation) or in total ('proportional' allocation)."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("10".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Allocation Method".to_owned(),
+            flags: vec!["--allocation".to_owned()],
+            description: "Allocation of the samples to the strata; one of 'equal' (default) and 'proportional'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "equal".to_owned(),
+                "proportional".to_owned(),
+            ]),
+            default_value: Some("equal".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Random Seed (optional)".to_owned(),
+            flags: vec!["--seed".to_owned()],
+            description: "Optional seed of the random number generator, for a reproducible sample."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --basins=subbasins.tif -o=samples.shp --num_samples=5
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --basins=subbasins.tif --strat=slope.tif --breaks='2;5;15' --attributes='DEM.tif;twi.tif' -o=samples.shp --num_samples=200 --allocation=proportional --seed=42", short_exe, name).replace("*", &sep);
+
+        WatershedStratifiedSample {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for WatershedStratifiedSample {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut basins_file = String::new();
+        let mut strat_file = String::new();
+        let mut attribute_files = String::new();
+        let mut output_file = String::new();
+        let mut num_bins = 3usize;
+        let mut breaks_str = String::new();
+        let mut num_samples = 10usize;
+        let mut allocation = String::from("equal");
+        let mut seed: Option<u64> = None;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-basins" {
+                basins_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-strat" {
+                strat_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-attributes" {
+                attribute_files = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-num_bins" {
+                num_bins = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-breaks" {
+                breaks_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-num_samples" {
+                num_samples = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-allocation" {
+                allocation = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-seed" {
+                seed = Some(if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as u64
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as u64
+                });
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let proportional = allocation.contains("prop");
+        if num_samples == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The number of samples (--num_samples) must be greater than zero.",
+            ));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        basins_file = resolve_path(&basins_file, working_directory);
+        strat_file = resolve_path(&strat_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        let mut attribute_list = vec![];
+        for s in attribute_files.split(|c| c == ';' || c == ',') {
+            let mut s = s.trim().to_string();
+            if s.is_empty() {
+                continue;
+            }
+            s = resolve_path(&s, working_directory);
+            // the values of the stratification raster are always extracted
+            if s != strat_file {
+                attribute_list.push(s);
+            }
+        }
+
+        let mut breaks = vec![];
+        for s in breaks_str.split(|c| c == ';' || c == ',') {
+            if !s.trim().is_empty() {
+                breaks.push(s.trim().parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Invalid class break value: {}", s.trim()),
+                    )
+                })?);
+            }
+        }
+        breaks.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let basins = Raster::new(&basins_file, "r")?;
+        let strat = if !strat_file.is_empty() {
+            Some(Raster::new(&strat_file, "r")?)
+        } else {
+            None
+        };
+        let mut attributes = vec![];
+        for file in &attribute_list {
+            attributes.push(Raster::new(file, "r")?);
+        }
+
+        let start = Instant::now();
+        let rows = basins.configs.rows as isize;
+        let columns = basins.configs.columns as isize;
+        let nodata = basins.configs.nodata;
+
+        for r in strat.iter().chain(attributes.iter()) {
+            if r.configs.rows != basins.configs.rows || r.configs.columns != basins.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+        }
+
+        // A cell may be sampled if it is within a sub-basin and all of the extracted values are valid.
+        let is_valid = |row: isize, col: isize| -> bool {
+            let basin = basins.get_value(row, col);
+            basin != nodata
+                && basin != 0f64
+                && strat
+                    .iter()
+                    .chain(attributes.iter())
+                    .all(|r| r.get_value(row, col) != r.configs.nodata)
+        };
+
+        // Quantile breaks are derived from the stratification values of the valid cells.
+        if let Some(strat) = &strat {
+            if breaks.is_empty() && num_bins > 1 {
+                let mut values = vec![];
+                for row in 0..rows {
+                    for col in 0..columns {
+                        if is_valid(row, col) {
+                            values.push(strat.get_value(row, col));
+                        }
+                    }
+                }
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                if !values.is_empty() {
+                    for bin in 1..num_bins {
+                        let i = (bin * values.len() / num_bins).min(values.len() - 1);
+                        breaks.push(values[i]);
+                    }
+                }
+                breaks.dedup();
+            }
+        }
+
+        // Group the valid cells by stratum, i.e. by sub-basin and bin.
+        let mut strata: BTreeMap<(i64, usize), Vec<usize>> = BTreeMap::new();
+        let mut num_valid_cells = 0usize;
+        for row in 0..rows {
+            for col in 0..columns {
+                if is_valid(row, col) {
+                    let basin = basins.get_value(row, col).round() as i64;
+                    let bin = match &strat {
+                        Some(strat) => {
+                            let z = strat.get_value(row, col);
+                            breaks.iter().filter(|b| z >= **b).count()
+                        }
+                        None => 0usize,
+                    };
+                    strata
+                        .entry((basin, bin))
+                        .or_insert(vec![])
+                        .push((row * columns + col) as usize);
+                    num_valid_cells += 1;
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if num_valid_cells == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The sub-basins raster does not contain any cells that can be sampled.",
+            ));
+        }
+
+        let mut rng = match seed {
+            Some(s) => StdRng::seed_from_u64(s),
+            None => StdRng::from_entropy(),
+        };
+
+        let mut output = Shapefile::new(&output_file, ShapeType::Point)?;
+
+        // set the projection information
+        output.projection = basins.configs.coordinate_ref_system_wkt.clone();
+
+        // add the attributes
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("BASIN", FieldDataType::Int, 10u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("BIN", FieldDataType::Int, 4u8, 0u8));
+        let mut field_names: Vec<String> =
+            vec!["FID".to_string(), "BASIN".to_string(), "BIN".to_string()];
+        let value_files: Vec<&String> = if strat.is_some() {
+            vec![&strat_file]
+                .into_iter()
+                .chain(attribute_list.iter())
+                .collect()
+        } else {
+            attribute_list.iter().collect()
+        };
+        for file in &value_files {
+            let stem = path::Path::new(file)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("VALUE")
+                .to_uppercase();
+            // dBase field names are limited to ten characters and must be unique
+            let mut field_name: String = stem.chars().take(10).collect();
+            let mut n = 2;
+            while field_names.contains(&field_name) {
+                let suffix = format!("_{}", n);
+                field_name = stem.chars().take(10 - suffix.len()).collect::<String>() + &suffix;
+                n += 1;
+            }
+            output.attributes.add_field(&AttributeField::new(
+                &field_name,
+                FieldDataType::Real,
+                12u8,
+                4u8,
+            ));
+            field_names.push(field_name);
+        }
+
+        let mut rec_num = 1i32;
+        let num_strata = strata.len();
+        for (stratum, ((basin, bin), cells)) in strata.iter_mut().enumerate() {
+            let n = if proportional {
+                ((num_samples as f64 * cells.len() as f64 / num_valid_cells as f64).round()
+                    as usize)
+                    .max(1)
+            } else {
+                num_samples
+            }
+            .min(cells.len());
+
+            // a partial Fisher-Yates shuffle selects n distinct cells
+            for i in 0..n {
+                let j = rng.gen_range(i, cells.len());
+                cells.swap(i, j);
+            }
+            let mut selected = cells[0..n].to_vec();
+            selected.sort();
+            for cell in selected {
+                let row = cell as isize / columns;
+                let col = cell as isize % columns;
+                output.add_point_record(basins.get_x_from_column(col), basins.get_y_from_row(row));
+                let mut record = vec![
+                    FieldData::Int(rec_num),
+                    FieldData::Int(*basin as i32),
+                    FieldData::Int(*bin as i32 + 1),
+                ];
+                for r in strat.iter().chain(attributes.iter()) {
+                    record.push(FieldData::Real(r.get_value(row, col)));
+                }
+                output.attributes.add_record(record, false);
+                rec_num += 1i32;
+            }
+
+            if verbose {
+                progress = (100.0_f64 * stratum as f64 / (num_strata - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!("Num. strata: {}, num. samples: {}", num_strata, rec_num - 1);
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
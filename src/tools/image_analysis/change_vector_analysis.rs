@@ -210,17 +210,11 @@ impl WhiteboxTool for ChangeVectorAnalysis {
 
         let start = Instant::now();
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !magnitude_file.contains(&sep) && !magnitude_file.contains("/") {
-            magnitude_file = format!("{}{}", working_directory, magnitude_file);
-        }
-        if !direction_file.contains(&sep) && !direction_file.contains("/") {
-            direction_file = format!("{}{}", working_directory, direction_file);
-        }
+        magnitude_file = resolve_path(&magnitude_file, working_directory);
+        direction_file = resolve_path(&direction_file, working_directory);
 
         let mut cmd = input1_files_str.split(";");
         let mut input1_files = cmd.collect::<Vec<&str>>();
@@ -264,9 +258,7 @@ impl WhiteboxTool for ChangeVectorAnalysis {
         // We will need to read one of the files in to get the rows, columns, and nodata values
         // in order to create the output files.
         let mut input1_file = input1_files[0].trim().to_owned();
-        if !input1_file.contains(&sep) && !input1_file.contains("/") {
-            input1_file = format!("{}{}", working_directory, input1_file);
-        }
+        input1_file = resolve_path(&input1_file, working_directory);
         let input1 = Raster::new(&input1_file, "r")?;
         let rows = input1.configs.rows as isize;
         let columns = input1.configs.columns as isize;
@@ -287,14 +279,10 @@ impl WhiteboxTool for ChangeVectorAnalysis {
             }
             if !input1_files[i].trim().is_empty() && !input2_files[i].trim().is_empty() {
                 let mut input1_file = input1_files[i].trim().to_owned();
-                if !input1_file.contains(&sep) && !input1_file.contains("/") {
-                    input1_file = format!("{}{}", working_directory, input1_file);
-                }
+                input1_file = resolve_path(&input1_file, working_directory);
 
                 let mut input2_file = input2_files[i].trim().to_owned();
-                if !input2_file.contains(&sep) && !input2_file.contains("/") {
-                    input2_file = format!("{}{}", working_directory, input2_file);
-                }
+                input2_file = resolve_path(&input2_file, working_directory);
 
                 let input1 = Raster::new(&input1_file, "r")?;
                 let input2 = Raster::new(&input2_file, "r")?;
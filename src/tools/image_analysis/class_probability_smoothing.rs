@@ -0,0 +1,609 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool can be used to smooth a classified image using the per-class probabilities (or membership values)
+/// output by a classifier, rather than the hard class labels alone. Unlike the `MajorityFilter`, which counts
+/// each neighbouring label equally, this tool accounts for the confidence of the classifier at each grid cell, so
+/// that confidently classified cells are retained while uncertain cells are drawn towards the class of their
+/// neighbourhood.
+///
+/// The user must specify the per-class probability rasters (`--inputs`), one per class, either as a list of
+/// raster files or as a cube definition file (.vrt or .lst; see `TimeSeriesStatistic`). The probabilities of each
+/// cell are normalized to sum to one, so that percentages and un-normalized membership scores may also be used.
+/// The class value assigned to each input in the output image may be specified using `--class_values`, as a
+/// comma-separated list; by default, the classes are numbered 1 to *n* in the order of the inputs.
+///
+/// Two smoothing methods (`--method`) are available:
+///
+/// - `icm` (default): The classification is modelled as a Markov random field (MRF) and the labelling that
+///   minimizes the energy
+///
+///   > E(*k*) = -ln *p*<sub>*k*</sub> + *β* × (number of neighbours not labelled *k*)
+///
+///   is found at each cell using iterated conditional modes (ICM), starting from the maximum-probability
+///   labelling. The smoothness weight, *β* (`--beta`), controls the strength of the smoothing; with *β* = 0
+///   the output is the maximum-probability classification. Cells are updated in a colour-interleaved order,
+///   such that no two cells updated at the same time are neighbours, and the iterations continue until no
+///   cell changes class or the maximum number of iterations (`--max_iterations`) is reached.
+/// - `majority`: A probability-weighted majority filter, in which each cell is assigned the class with the
+///   largest sum of probabilities within the neighbourhood.
+///
+/// The neighbourhood is a square window of `--filter` by `--filter` cells (3 by default) centred on each cell.
+/// Cells for which each of the inputs contains NoData are assigned NoData in the output and are not counted as
+/// neighbours.
+///
+/// # Reference
+/// Besag, J. (1986). On the statistical analysis of dirty pictures. *Journal of the Royal Statistical Society:
+/// Series B*, 48(3), 259-302.
+///
+/// # See Also
+/// `MajorityFilter`, `GeneralizeClassifiedRaster`, `KMeansClustering`
+pub struct ClassProbabilitySmoothing {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ClassProbabilitySmoothing {
+    pub fn new() -> ClassProbabilitySmoothing {
+        // public constructor
+        let name = "ClassProbabilitySmoothing".to_string();
+        let toolbox = "Image Processing Tools".to_string();
+        let description = "Smooths a classification using per-class probability rasters and a Markov random field (ICM) or probability-weighted majority.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Class Probability Files".to_owned(),
+            flags: vec!["-i".to_owned(), "--inputs".to_owned()],
+            description: "Input per-class probability rasters, one per class, or a cube definition file (.vrt or .lst).".to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output classified raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Smoothing Method".to_owned(),
+            flags: vec!["--method".to_owned()],
+            description: "Smoothing method; options are 'icm' (Markov random field) and 'majority' (probability-weighted majority).".to_owned(),
+            parameter_type: ParameterType::OptionList(vec!["icm".to_owned(), "majority".to_owned()]),
+            default_value: Some("icm".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Filter Size".to_owned(),
+            flags: vec!["--filter".to_owned()],
+            description: "Size of the neighbourhood window, in cells.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("3".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Smoothness Weight (Beta)".to_owned(),
+            flags: vec!["--beta".to_owned()],
+            description: "Weight of the neighbourhood term in the MRF energy (icm method)."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Iterations".to_owned(),
+            flags: vec!["--max_iterations".to_owned()],
+            description: "Maximum number of ICM iterations (icm method).".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("10".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Class Values".to_owned(),
+            flags: vec!["--class_values".to_owned()],
+            description:
+                "Optional comma-separated output class value of each input; by default 1 to n."
+                    .to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='prob_water.tif;prob_forest.tif;prob_urban.tif' -o=classes.tif --method=icm --beta=1.5
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='prob_water.tif;prob_forest.tif;prob_urban.tif' -o=classes.tif --method=majority --filter=5 --class_values='11,41,21'", short_exe, name).replace("*", &sep);
+
+        ClassProbabilitySmoothing {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ClassProbabilitySmoothing {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_files = String::new();
+        let mut output_file = String::new();
+        let mut method = String::from("icm");
+        let mut filter_size = 3usize;
+        let mut beta = 1f64;
+        let mut max_iterations = 10usize;
+        let mut class_values_string = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-inputs" || flag_val == "-input" {
+                input_files = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-method" {
+                method = if keyval {
+                    vec[1].to_string().to_lowercase()
+                } else {
+                    args[i + 1].to_string().to_lowercase()
+                };
+            } else if flag_val == "-filter" {
+                filter_size = if keyval {
+                    vec[1].to_string().parse::<f32>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f32>().unwrap() as usize
+                };
+            } else if flag_val == "-beta" {
+                beta = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_iterations" {
+                max_iterations = if keyval {
+                    vec[1].to_string().parse::<f32>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f32>().unwrap() as usize
+                };
+            } else if flag_val == "-class_values" {
+                class_values_string = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let use_icm = if method.contains("icm") || method.contains("mrf") {
+            true
+        } else if method.contains("maj") {
+            false
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Unrecognized smoothing method '{}'; options are 'icm' and 'majority'.",
+                    method
+                ),
+            ));
+        };
+        if beta.is_nan() || beta < 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The smoothness weight (--beta) must be zero or greater.",
+            ));
+        }
+
+        // The filter dimensions must be odd numbers such that there is a middle pixel
+        if filter_size < 3 {
+            filter_size = 3;
+        }
+        if filter_size.is_multiple_of(2) {
+            filter_size += 1;
+        }
+        let midpoint = (filter_size / 2) as isize;
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        output_file = resolve_path(&output_file, working_directory);
+
+        let mut cmd = input_files.split(";");
+        let mut vec = cmd.collect::<Vec<&str>>();
+        if vec.len() == 1 {
+            cmd = input_files.split(",");
+            vec = cmd.collect::<Vec<&str>>();
+        }
+        let files: Vec<String> = vec
+            .iter()
+            .filter(|f| !f.trim().is_empty())
+            .map(|f| resolve_path(f, working_directory))
+            .collect();
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let cube = if files.len() == 1 && RasterCube::is_definition_file(&files[0]) {
+            RasterCube::from_definition_file(&files[0])?
+        } else {
+            RasterCube::new(&files)?
+        };
+        let num_classes = cube.num_layers();
+        if num_classes < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least two class probability rasters are required to operate this tool.",
+            ));
+        }
+
+        let class_values: Vec<f64> = if class_values_string.trim().is_empty() {
+            (1..=num_classes).map(|v| v as f64).collect()
+        } else {
+            class_values_string
+                .split(',')
+                .map(|v| v.trim().parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        "The class values (--class_values) could not be parsed.",
+                    )
+                })?
+        };
+        if class_values.len() != num_classes {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The number of class values ({}) does not match the number of input probability rasters ({}).",
+                    class_values.len(),
+                    num_classes
+                ),
+            ));
+        }
+
+        let start = Instant::now();
+        let rows = cube.rows() as isize;
+        let columns = cube.columns() as isize;
+        let in_nodata = cube.nodata();
+
+        // Read the normalized probabilities, stored by cell, and the maximum-probability labels.
+        let num_cells = cube.rows() * cube.columns();
+        let mut probs = vec![0f32; num_cells * num_classes];
+        let mut labels = vec![NO_CLASS; num_cells];
+        for chunk in cube.chunks(cube.default_chunk_rows()) {
+            let chunk = chunk?;
+            for row in chunk.start_row..chunk.end_row() {
+                for col in 0..cube.columns() {
+                    let series = chunk.series(row, col);
+                    let sum = series
+                        .iter()
+                        .filter(|p| **p != in_nodata && p.is_finite() && **p > 0f64)
+                        .fold(0f64, |acc, p| acc + p);
+                    if series.iter().all(|p| *p == in_nodata) {
+                        continue;
+                    }
+                    let cell = row * cube.columns() + col;
+                    let mut best = 0usize;
+                    for k in 0..num_classes {
+                        let p = series[k];
+                        let p = if p != in_nodata && p.is_finite() && p > 0f64 && sum > 0f64 {
+                            p / sum
+                        } else {
+                            0f64
+                        };
+                        probs[cell * num_classes + k] = p as f32;
+                        if probs[cell * num_classes + k] > probs[cell * num_classes + best] {
+                            best = k;
+                        }
+                    }
+                    labels[cell] = best as u16;
+                }
+            }
+        }
+        let mut configs = cube.configs.clone();
+        drop(cube);
+        let probs = Arc::new(probs);
+
+        let num_procs = num_cpus::get() as isize;
+        if use_icm {
+            // Cells that share a colour are at least filter_size cells apart in one of the directions,
+            // and therefore are not neighbours; each colour can then be updated in parallel.
+            let num_colours = (midpoint + 1) as usize;
+            let mut iteration = 0usize;
+            while iteration < max_iterations {
+                iteration += 1;
+                let mut cells_changed = 0usize;
+                for colour in 0..num_colours * num_colours {
+                    let colour_row = (colour / num_colours) as isize;
+                    let colour_col = (colour % num_colours) as isize;
+                    let colour_rows: Vec<isize> = (0..rows)
+                        .filter(|r| r % num_colours as isize == colour_row)
+                        .collect();
+                    let num_colour_rows = colour_rows.len();
+                    let colour_rows = Arc::new(colour_rows);
+                    let current = Arc::new(labels.clone());
+                    let (tx, rx) = mpsc::channel();
+                    for tid in 0..num_procs {
+                        let probs = probs.clone();
+                        let current = current.clone();
+                        let colour_rows = colour_rows.clone();
+                        let tx = tx.clone();
+                        thread::spawn(move || {
+                            let mut energy = vec![0f64; num_classes];
+                            for i in
+                                (0..colour_rows.len()).filter(|i| *i as isize % num_procs == tid)
+                            {
+                                let row = colour_rows[i];
+                                let mut changes = vec![];
+                                let mut col = colour_col;
+                                while col < columns {
+                                    let cell = (row * columns + col) as usize;
+                                    if current[cell] != NO_CLASS {
+                                        for k in 0..num_classes {
+                                            energy[k] = -(probs[cell * num_classes + k] as f64)
+                                                .max(MIN_PROB)
+                                                .ln();
+                                        }
+                                        for r in
+                                            (row - midpoint).max(0)..(row + midpoint + 1).min(rows)
+                                        {
+                                            for c in (col - midpoint).max(0)
+                                                ..(col + midpoint + 1).min(columns)
+                                            {
+                                                let neighbour = current[(r * columns + c) as usize];
+                                                if (r != row || c != col) && neighbour != NO_CLASS {
+                                                    for e in energy.iter_mut() {
+                                                        *e += beta;
+                                                    }
+                                                    energy[neighbour as usize] -= beta;
+                                                }
+                                            }
+                                        }
+                                        let mut best = current[cell] as usize;
+                                        for k in 0..num_classes {
+                                            if energy[k] < energy[best] {
+                                                best = k;
+                                            }
+                                        }
+                                        if best != current[cell] as usize {
+                                            changes.push((cell, best as u16));
+                                        }
+                                    }
+                                    col += num_colours as isize;
+                                }
+                                tx.send(changes).unwrap();
+                            }
+                        });
+                    }
+                    for _ in 0..num_colour_rows {
+                        let changes = rx.recv().unwrap();
+                        cells_changed += changes.len();
+                        for (cell, label) in changes {
+                            labels[cell] = label;
+                        }
+                    }
+                }
+                if verbose {
+                    println!(
+                        "Iteration {}: {} cells changed class",
+                        iteration, cells_changed
+                    );
+                }
+                if cells_changed == 0 {
+                    break;
+                }
+            }
+        } else {
+            let current = Arc::new(labels.clone());
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let probs = probs.clone();
+                let current = current.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    let mut score = vec![0f64; num_classes];
+                    for row in (0..rows).filter(|r| r % num_procs == tid) {
+                        let mut data = vec![NO_CLASS; columns as usize];
+                        for col in 0..columns {
+                            if current[(row * columns + col) as usize] == NO_CLASS {
+                                continue;
+                            }
+                            for s in score.iter_mut() {
+                                *s = 0f64;
+                            }
+                            for r in (row - midpoint).max(0)..(row + midpoint + 1).min(rows) {
+                                for c in (col - midpoint).max(0)..(col + midpoint + 1).min(columns)
+                                {
+                                    let neighbour = (r * columns + c) as usize;
+                                    if current[neighbour] != NO_CLASS {
+                                        for k in 0..num_classes {
+                                            score[k] += probs[neighbour * num_classes + k] as f64;
+                                        }
+                                    }
+                                }
+                            }
+                            let mut best = 0usize;
+                            for k in 1..num_classes {
+                                if score[k] > score[best] {
+                                    best = k;
+                                }
+                            }
+                            data[col as usize] = best as u16;
+                        }
+                        tx.send((row, data)).unwrap();
+                    }
+                });
+            }
+            for r in 0..rows {
+                let (row, data) = rx.recv().unwrap();
+                let offset = (row * columns) as usize;
+                labels[offset..offset + columns as usize].copy_from_slice(&data);
+                if verbose {
+                    progress = (100.0_f64 * r as f64 / (rows - 1).max(1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        let out_nodata = -32768f64;
+        configs.nodata = out_nodata;
+        configs.data_type = DataType::I16;
+        configs.palette = "qual.plt".to_string();
+        configs.photometric_interp = PhotometricInterpretation::Categorical;
+        let mut output = Raster::initialize_using_config(&output_file, &configs);
+        for row in 0..rows {
+            let offset = (row * columns) as usize;
+            let data: Vec<f64> = labels[offset..offset + columns as usize]
+                .iter()
+                .map(|l| {
+                    if *l == NO_CLASS {
+                        out_nodata
+                    } else {
+                        class_values[*l as usize]
+                    }
+                })
+                .collect();
+            output.set_row_data(row, data);
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Number of classes: {}", num_classes));
+        output.add_metadata_entry(format!(
+            "Method: {}",
+            if use_icm { "icm" } else { "majority" }
+        ));
+        output.add_metadata_entry(format!("Filter size: {}", filter_size));
+        if use_icm {
+            output.add_metadata_entry(format!("Beta: {}", beta));
+        }
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The label of cells with NoData in each of the probability rasters.
+const NO_CLASS: u16 = u16::MAX;
+
+/// The probability used in place of zero when calculating -ln(p).
+const MIN_PROB: f64 = 1e-6;
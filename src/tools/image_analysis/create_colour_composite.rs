@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 19/07/2017
-Last Modified: 18/10/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
@@ -20,12 +20,30 @@ use std::thread;
 
 /// This tool can be used to create a colour-composite image from three bands of multi-spectral imagery. 
 /// The user must specify the names of the input images to enter into the red, green, and blue channels 
-/// of the resulting composite image. The output image uses the 32-bit aRGB colour model, and therefore, 
-/// in addition to red, green and blue bands, the user may optionally specify a fourth image that will 
-/// be used to determine pixel opacity (the 'a' channel). If no opacity image is specified, each pixel 
-/// will be opaque. This can be useful for cropping an image to an irregular-shaped boundary. The opacity 
-/// channel can also be used to create transparent gradients in the composite image.
-/// 
+/// of the resulting composite image. In addition to red, green and blue bands, the user may optionally
+/// specify a fourth image that will be used to determine pixel opacity (the 'a' channel). If no opacity
+/// image is specified, each pixel will be opaque. This can be useful for cropping an image to an
+/// irregular-shaped boundary. The opacity channel can also be used to create transparent gradients in
+/// the composite image. The output image uses the 24-bit RGB colour model when no opacity image is
+/// specified and the 32-bit aRGB colour model otherwise. GeoTIFF outputs are written with an RGB
+/// photometric interpretation and, for aRGB images, with the opacity band identified as an
+/// unassociated alpha channel.
+///
+/// Each input may either be a single-band raster or a band of a multi-band colour image. Individual
+/// bands are specified by appending a colon and the band number to the file name, where bands 1-4
+/// correspond to the red, green, blue, and alpha channels of the colour image (e.g.
+/// `--red=image.tif:3 --green=image.tif:2 --blue=image.tif:1`).
+///
+/// A contrast stretch is applied to each of the red, green, and blue bands, independently, to map
+/// the band values onto the 0-255 range of the output channels. The `--stretch` parameter may be one
+/// of the following:
+///
+/// - `display` (default): a linear stretch between the display minimum and maximum of each band
+/// - `minmax`: a linear stretch between the minimum and maximum of each band
+/// - `percent`: a linear stretch between values that clip a percentage (`--clip`) of each tail
+/// - `stdev`: a linear stretch over the mean plus or minus a number of standard deviations (`--stdev`)
+/// - `histeq`: a histogram equalization of each band
+///
 /// A balance contrast enhancement (BCE) can optionally be performed on the bands prior to creation of
 /// the colour composite. While this operation will add to the runtime of `CreateColourComposite`, if
 /// the individual input bands have not already had contrast enhancements, then it is advisable that
@@ -38,7 +56,8 @@ use std::thread;
 /// the `--zeros` flag.
 /// 
 /// # See Also
-/// `BalanceContrastEnhancement`, `SplitColourComposite`
+/// `BalanceContrastEnhancement`, `SplitColourComposite`, `PercentageContrastStretch`,
+/// `StandardDeviationContrastStretch`, `HistogramEqualization`
 pub struct CreateColourComposite {
     name: String,
     description: String,
@@ -102,6 +121,39 @@ impl CreateColourComposite {
             optional: false,
         });
 
+        parameters.push(ToolParameter {
+            name: "Contrast Stretch".to_owned(),
+            flags: vec!["--stretch".to_owned()],
+            description: "Contrast stretch applied to each band; options are 'display' (default), 'minmax', 'percent', 'stdev', and 'histeq'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "display".to_owned(),
+                "minmax".to_owned(),
+                "percent".to_owned(),
+                "stdev".to_owned(),
+                "histeq".to_owned(),
+            ]),
+            default_value: Some("display".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Percent Clip".to_owned(),
+            flags: vec!["--clip".to_owned()],
+            description: "Percent of each tail clipped by the 'percent' stretch.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Standard Deviations".to_owned(),
+            flags: vec!["--stdev".to_owned()],
+            description: "Number of standard deviations used by the 'stdev' stretch.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
         parameters.push(ToolParameter {
             name: "Perform balance contrast enhancement?".to_owned(),
             flags: vec!["--enhance".to_owned()],
@@ -136,7 +188,8 @@ impl CreateColourComposite {
             short_exe += ".exe";
         }
         let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --red=band3.tif --green=band2.tif --blue=band1.tif -o=output.tif
->>.*{0} -r={1} -v --wd=\"*path*to*data*\" --red=band3.tif --green=band2.tif --blue=band1.tif --opacity=a.tif -o=output.tif --enhance --zeros", short_exe, name).replace("*", &sep);
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --red=band3.tif --green=band2.tif --blue=band1.tif --opacity=a.tif -o=output.tif --enhance --zeros
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --red=image.tif:3 --green=image.tif:2 --blue=image.tif:1 -o=output.tif --stretch=percent --clip=2.0", short_exe, name).replace("*", &sep);
 
         CreateColourComposite {
             name: name,
@@ -190,6 +243,9 @@ impl WhiteboxTool for CreateColourComposite {
         let mut output_file = String::new();
         let mut enhance = false;
         let mut no_zeros = false;
+        let mut stretch = "display".to_string();
+        let mut clip = 1f64;
+        let mut num_stdev = 2f64;
         if args.len() == 0 {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
@@ -237,6 +293,25 @@ impl WhiteboxTool for CreateColourComposite {
                 } else {
                     args[i + 1].to_string()
                 };
+            } else if flag_val == "-stretch" {
+                stretch = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                }
+                .to_lowercase();
+            } else if flag_val == "-clip" {
+                clip = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-stdev" {
+                num_stdev = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
             } else if flag_val == "-enhance" {
                 if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
                     enhance = true;
@@ -255,58 +330,41 @@ impl WhiteboxTool for CreateColourComposite {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input1_file.contains(&sep) && !input1_file.contains("/") {
-            input1_file = format!("{}{}", working_directory, input1_file);
-        }
-        if !input2_file.contains(&sep) && !input2_file.contains("/") {
-            input2_file = format!("{}{}", working_directory, input2_file);
-        }
-        if !input3_file.contains(&sep) && !input3_file.contains("/") {
-            input3_file = format!("{}{}", working_directory, input3_file);
-        }
+        input1_file = resolve_path(&input1_file, working_directory);
+        input2_file = resolve_path(&input2_file, working_directory);
+        input3_file = resolve_path(&input3_file, working_directory);
         if input4_used {
-            if !input4_file.contains(&sep) && !input4_file.contains("/") {
-                input4_file = format!("{}{}", working_directory, input4_file);
-            }
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
+            input4_file = resolve_path(&input4_file, working_directory);
         }
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading red band data...")
         };
-        let input_r = Arc::new(Raster::new(&input1_file, "r")?);
+        let mut input_r = read_band(&input1_file)?;
         if verbose {
             println!("Reading green band data...")
         };
-        let input_g = Arc::new(Raster::new(&input2_file, "r")?);
+        let mut input_g = read_band(&input2_file)?;
         if verbose {
             println!("Reading blue band data...")
         };
-        let input_b = Arc::new(Raster::new(&input3_file, "r")?);
+        let mut input_b = read_band(&input3_file)?;
 
         let rows = input_r.configs.rows as isize;
         let columns = input_r.configs.columns as isize;
-        let mut nodata_r = input_r.configs.nodata;
-        let mut nodata_g = input_g.configs.nodata;
-        let mut nodata_b = input_b.configs.nodata;
         if no_zeros {
-            nodata_r = 0f64;
-            nodata_g = 0f64;
-            nodata_b = 0f64;
+            for band in [&mut input_r, &mut input_g, &mut input_b] {
+                band.configs.nodata = 0f64;
+                band.update_min_max();
+            }
         }
-        let red_min = input_r.configs.display_min;
-        let green_min = input_g.configs.display_min;
-        let blue_min = input_b.configs.display_min;
-        let red_range = input_r.configs.display_max - red_min;
-        let green_range = input_g.configs.display_max - green_min;
-        let blue_range = input_b.configs.display_max - blue_min;
+        let nodata_r = input_r.configs.nodata;
+        let nodata_g = input_g.configs.nodata;
+        let nodata_b = input_b.configs.nodata;
         let a_min: f64;
         let a_range: f64;
         let input_a = match input4_used {
@@ -314,7 +372,7 @@ impl WhiteboxTool for CreateColourComposite {
                 if verbose {
                     println!("Reading opacity data...")
                 };
-                let opacity = Raster::new(&input4_file, "r")?;
+                let opacity = read_band(&input4_file)?;
                 a_min = opacity.configs.display_min;
                 a_range = opacity.configs.display_max - a_min;
                 if input_r.configs.rows != opacity.configs.rows
@@ -353,6 +411,16 @@ impl WhiteboxTool for CreateColourComposite {
             ));
         }
 
+        if verbose {
+            println!("Calculating band stretches...")
+        };
+        let stretch_r = Arc::new(BandStretch::new(&input_r, &stretch, clip, num_stdev)?);
+        let stretch_g = Arc::new(BandStretch::new(&input_g, &stretch, clip, num_stdev)?);
+        let stretch_b = Arc::new(BandStretch::new(&input_b, &stretch, clip, num_stdev)?);
+
+        let input_r = Arc::new(input_r);
+        let input_g = Arc::new(input_g);
+        let input_b = Arc::new(input_b);
         let num_procs = num_cpus::get() as isize;
         let (tx, rx) = mpsc::channel();
         for tid in 0..num_procs {
@@ -360,6 +428,9 @@ impl WhiteboxTool for CreateColourComposite {
             let input_g = input_g.clone();
             let input_b = input_b.clone();
             let input_a = input_a.clone();
+            let stretch_r = stretch_r.clone();
+            let stretch_g = stretch_g.clone();
+            let stretch_b = stretch_b.clone();
             let tx = tx.clone();
             thread::spawn(move || {
                 let mut red_val: f64;
@@ -374,32 +445,9 @@ impl WhiteboxTool for CreateColourComposite {
                         green_val = input_g[(row, col)];
                         blue_val = input_b[(row, col)];
                         if red_val != nodata_r && green_val != nodata_g && blue_val != nodata_b {
-                            red_val = (red_val - red_min) / red_range * 255f64;
-                            if red_val < 0f64 {
-                                red_val = 0f64;
-                            }
-                            if red_val > 255f64 {
-                                red_val = 255f64;
-                            }
-                            r = red_val as u32;
-
-                            green_val = (green_val - green_min) / green_range * 255f64;
-                            if green_val < 0f64 {
-                                green_val = 0f64;
-                            }
-                            if green_val > 255f64 {
-                                green_val = 255f64;
-                            }
-                            g = green_val as u32;
-
-                            blue_val = (blue_val - blue_min) / blue_range * 255f64;
-                            if blue_val < 0f64 {
-                                blue_val = 0f64;
-                            }
-                            if blue_val > 255f64 {
-                                blue_val = 255f64;
-                            }
-                            b = blue_val as u32;
+                            r = stretch_r.apply(red_val);
+                            g = stretch_g.apply(green_val);
+                            b = stretch_b.apply(blue_val);
 
                             a_val = input_a[(row, col)];
                             a_val = (a_val - a_min) / a_range * 255f64;
@@ -420,7 +468,11 @@ impl WhiteboxTool for CreateColourComposite {
 
         let mut output = Raster::initialize_using_file(&output_file, &input_r);
         output.configs.photometric_interp = PhotometricInterpretation::RGB;
-        output.configs.data_type = DataType::RGBA32;
+        output.configs.data_type = if input4_used {
+            DataType::RGBA32
+        } else {
+            DataType::RGB24
+        };
         for row in 0..rows {
             let data = rx.recv().unwrap();
             output.set_row_data(data.0, data.1);
@@ -586,6 +638,7 @@ impl WhiteboxTool for CreateColourComposite {
         if input4_used {
             output.add_metadata_entry(format!("Input opacity file: {}", input4_file));
         }
+        output.add_metadata_entry(format!("Contrast stretch: {}", stretch));
         output.add_metadata_entry(format!("Balance contrast enhancement: {}", enhance));
         output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
 
@@ -610,3 +663,132 @@ impl WhiteboxTool for CreateColourComposite {
         Ok(())
     }
 }
+
+/// Reads an input band, which is either a single-band raster or, if the file name ends with a colon
+/// and a band number (e.g. `image.tif:2`), one of the channels of a colour image.
+fn read_band(file_name: &str) -> Result<Raster, Error> {
+    let (file, band) = match file_name.rfind(':') {
+        Some(idx)
+            if idx + 1 < file_name.len()
+                && file_name[idx + 1..].chars().all(|c| c.is_ascii_digit()) =>
+        {
+            (
+                &file_name[..idx],
+                file_name[idx + 1..].parse::<usize>().unwrap(),
+            )
+        }
+        _ => (file_name, 0),
+    };
+    let input = Raster::new(file, "r")?;
+    if band == 0 {
+        return Ok(input);
+    }
+    let is_rgb = input.configs.photometric_interp == PhotometricInterpretation::RGB;
+    if band > 4 || (!is_rgb && band > 1) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Band {} does not exist in the input file {}.", band, file),
+        ));
+    }
+    if !is_rgb {
+        return Ok(input);
+    }
+    let nodata = -32768f64;
+    let mut output = Raster::initialize_using_file(file, &input);
+    output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+    output.configs.data_type = DataType::F32;
+    output.configs.nodata = nodata;
+    let shift = 8 * (band - 1) as u32;
+    let rows = input.configs.rows as isize;
+    let columns = input.configs.columns as isize;
+    for row in 0..rows {
+        for col in 0..columns {
+            let z = input.get_value(row, col);
+            if z != input.configs.nodata {
+                output.set_value(row, col, ((z as u32 >> shift) & 0xFF) as f64);
+            } else {
+                output.set_value(row, col, nodata);
+            }
+        }
+    }
+    output.update_min_max();
+    output.configs.display_min = 0f64;
+    output.configs.display_max = 255f64;
+    Ok(output)
+}
+
+/// The transfer function of a contrast stretch, mapping band values onto the 0-255 range.
+struct BandStretch {
+    min: f64,
+    range: f64,
+    cdf: Vec<f64>, // only used by histogram equalization
+}
+
+impl BandStretch {
+    fn new(band: &Raster, stretch: &str, clip: f64, num_stdev: f64) -> Result<BandStretch, Error> {
+        let (min, max) = match stretch {
+            "display" => (band.configs.display_min, band.configs.display_max),
+            "minmax" | "histeq" => (band.configs.minimum, band.configs.maximum),
+            "percent" => band.calculate_clip_values(clip),
+            "stdev" => {
+                let (mean, stdev) = band.calculate_mean_and_stdev();
+                (mean - num_stdev * stdev, mean + num_stdev * stdev)
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Specified 'stretch' parameter ({}) is not recognized.",
+                        stretch
+                    ),
+                ))
+            }
+        };
+        let mut cdf = vec![];
+        if stretch == "histeq" && max > min {
+            let num_bins = 1024usize;
+            let mut histo = vec![0f64; num_bins];
+            let nodata = band.configs.nodata;
+            for row in 0..band.configs.rows as isize {
+                for col in 0..band.configs.columns as isize {
+                    let z = band.get_value(row, col);
+                    if z != nodata {
+                        let bin =
+                            ((z - min) / (max - min) * (num_bins - 1) as f64).floor() as usize;
+                        histo[bin.min(num_bins - 1)] += 1f64;
+                    }
+                }
+            }
+            cdf = vec![0f64; num_bins];
+            cdf[0] = histo[0];
+            for j in 1..num_bins {
+                cdf[j] = cdf[j - 1] + histo[j];
+            }
+            let total = cdf[num_bins - 1] - histo[0];
+            for c in cdf.iter_mut() {
+                *c = if total > 0f64 {
+                    (*c - histo[0]) / total
+                } else {
+                    0f64
+                };
+            }
+        }
+        Ok(BandStretch {
+            min: min,
+            range: max - min,
+            cdf: cdf,
+        })
+    }
+
+    fn apply(&self, z: f64) -> u32 {
+        if self.range <= 0f64 {
+            return 0u32;
+        }
+        let mut v = (z - self.min) / self.range;
+        if !self.cdf.is_empty() {
+            let bin = (v * (self.cdf.len() - 1) as f64).floor().max(0f64) as usize;
+            v = self.cdf[bin.min(self.cdf.len() - 1)];
+        }
+        (v * 255f64).clamp(0f64, 255f64) as u32
+    }
+}
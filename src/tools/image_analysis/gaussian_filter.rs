@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 26/06/2017
-Last Modified: 22/10/2019
+Last Modified: 17/10/2026
 License: MIT
 */
 
@@ -32,7 +32,9 @@ use std::thread;
 /// 
 /// `GaussianFilter` works with both greyscale and red-green-blue (RGB) colour images. RGB images are 
 /// decomposed into intensity-hue-saturation (IHS) and the filter is applied to the intensity
-/// channel. NoData values in the input image are ignored during processing.
+/// channel. Each band of a multi-band image is filtered separately. NoData values in the input image are ignored during
+/// processing. The input and output images are held in memory in their native data types, e.g. one
+/// byte per cell for 8-bit imagery.
 /// 
 /// Like many low-pass filters, Gaussian filtering can signficantly blur well-defined edges in
 /// the input image. The `EdgePreservingMeanFilter` and `BilateralFilter` offer more robust 
@@ -192,14 +194,8 @@ impl WhiteboxTool for GaussianFilter {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if sigma_d < 0.5 {
             sigma_d = 0.5;
@@ -260,103 +256,114 @@ impl WhiteboxTool for GaussianFilter {
             println!("Reading data...")
         };
 
-        let input = Arc::new(Raster::new(&input_file, "r")?);
+        let input_raster = Raster::new_native(&input_file, "r")?;
         let d_x = Arc::new(d_x);
         let d_y = Arc::new(d_y);
         let weights = Arc::new(weights);
 
         let start = Instant::now();
 
-        let is_rgb_image = if input.configs.data_type == DataType::RGB24
-            || input.configs.data_type == DataType::RGBA32
-            || input.configs.photometric_interp == PhotometricInterpretation::RGB
-        {
-            true
-        } else {
-            false
-        };
-
-        let rows = input.configs.rows as isize;
-        let columns = input.configs.columns as isize;
-        let nodata = input.configs.nodata;
-
-        let mut output = Raster::initialize_using_file(&output_file, &input);
-
-        let num_procs = num_cpus::get() as isize;
-        let (tx, rx) = mpsc::channel();
-        for tid in 0..num_procs {
-            let input = input.clone();
-            let d_x = d_x.clone();
-            let d_y = d_y.clone();
-            let weights = weights.clone();
-            let tx1 = tx.clone();
-            thread::spawn(move || {
-                let input_fn: Box<dyn Fn(isize, isize) -> f64> = if !is_rgb_image {
-                    Box::new(|row: isize, col: isize| -> f64 { input.get_value(row, col) })
-                } else {
-                    Box::new(|row: isize, col: isize| -> f64 {
-                        let value = input.get_value(row, col);
-                        if value != nodata {
-                            return value2i(value);
-                        }
-                        nodata
-                    })
-                };
-
-                let output_fn: Box<dyn Fn(isize, isize, f64) -> f64> = if !is_rgb_image {
-                    // simply return the value.
-                    Box::new(|_: isize, _: isize, value: f64| -> f64 { value })
-                } else {
-                    // convert it back into an rgb value, using the modified intensity value.
-                    Box::new(|row: isize, col: isize, value: f64| -> f64 {
-                        if value != nodata {
-                            let (h, s, _) = value2hsi(input.get_value(row, col));
-                            return hsi2value(h, s, value);
-                        }
-                        nodata
-                    })
-                };
-
-                let (mut sum, mut z_final): (f64, f64);
-                let mut z: f64;
-                let mut zn: f64;
-                let (mut x, mut y): (isize, isize);
-                for row in (0..rows).filter(|r| r % num_procs == tid) {
-                    let mut data = vec![nodata; columns as usize];
-                    for col in 0..columns {
-                        z = input_fn(row, col);
-                        if z != nodata {
-                            sum = 0.0;
-                            z_final = 0.0;
-                            for a in 0..num_pixels_in_filter {
-                                x = col + d_x[a];
-                                y = row + d_y[a];
-                                zn = input_fn(y, x);
-                                if zn != nodata {
-                                    sum += weights[a];
-                                    z_final += weights[a] * zn;
+        // each band of a multi-band image is filtered separately
+        let mut outputs = Vec::with_capacity(input_raster.num_bands());
+        for input in input_raster.into_bands() {
+            let input = Arc::new(input);
+
+            let is_rgb_image = if input.configs.data_type == DataType::RGB24
+                || input.configs.data_type == DataType::RGBA32
+                || input.configs.photometric_interp == PhotometricInterpretation::RGB
+            {
+                true
+            } else {
+                false
+            };
+
+            let rows = input.configs.rows as isize;
+            let columns = input.configs.columns as isize;
+            let nodata = input.configs.nodata;
+
+            let mut output = Raster::initialize_using_file(&output_file, &input);
+
+            output.set_native_storage();
+
+            let num_procs = num_cpus::get() as isize;
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let input = input.clone();
+                let d_x = d_x.clone();
+                let d_y = d_y.clone();
+                let weights = weights.clone();
+                let tx1 = tx.clone();
+                thread::spawn(move || {
+                    let input_fn: Box<dyn Fn(isize, isize) -> f64> = if !is_rgb_image {
+                        Box::new(|row: isize, col: isize| -> f64 { input.get_value(row, col) })
+                    } else {
+                        Box::new(|row: isize, col: isize| -> f64 {
+                            let value = input.get_value(row, col);
+                            if value != nodata {
+                                return value2i(value);
+                            }
+                            nodata
+                        })
+                    };
+
+                    let output_fn: Box<dyn Fn(isize, isize, f64) -> f64> = if !is_rgb_image {
+                        // simply return the value.
+                        Box::new(|_: isize, _: isize, value: f64| -> f64 { value })
+                    } else {
+                        // convert it back into an rgb value, using the modified intensity value.
+                        Box::new(|row: isize, col: isize, value: f64| -> f64 {
+                            if value != nodata {
+                                let (h, s, _) = value2hsi(input.get_value(row, col));
+                                return hsi2value(h, s, value);
+                            }
+                            nodata
+                        })
+                    };
+
+                    let (mut sum, mut z_final): (f64, f64);
+                    let mut z: f64;
+                    let mut zn: f64;
+                    let (mut x, mut y): (isize, isize);
+                    for row in (0..rows).filter(|r| r % num_procs == tid) {
+                        let mut data = vec![nodata; columns as usize];
+                        for col in 0..columns {
+                            z = input_fn(row, col);
+                            if z != nodata {
+                                sum = 0.0;
+                                z_final = 0.0;
+                                for a in 0..num_pixels_in_filter {
+                                    x = col + d_x[a];
+                                    y = row + d_y[a];
+                                    zn = input_fn(y, x);
+                                    if zn != nodata {
+                                        sum += weights[a];
+                                        z_final += weights[a] * zn;
+                                    }
                                 }
+                                data[col as usize] = output_fn(row, col, z_final / sum);
                             }
-                            data[col as usize] = output_fn(row, col, z_final / sum);
                         }
-                    }
 
-                    tx1.send((row, data)).unwrap();
-                }
-            });
-        }
+                        tx1.send((row, data)).unwrap();
+                    }
+                });
+            }
 
-        for row in 0..rows {
-            let data = rx.recv().unwrap();
-            output.set_row_data(data.0, data.1);
-            if verbose {
-                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
-                if progress != old_progress {
-                    println!("Progress: {}%", progress);
-                    old_progress = progress;
+            for row in 0..rows {
+                let data = rx.recv().unwrap();
+                output.set_row_data(data.0, data.1);
+                if verbose {
+                    progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
                 }
             }
+
+            outputs.push(output);
         }
+        let mut output = Raster::from_bands(outputs)?;
 
         let elapsed_time = get_formatted_elapsed_time(start);
         output.add_metadata_entry(format!(
@@ -0,0 +1,573 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::structures::Array2D;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool generalizes a classified (i.e. categorical) raster, such as a land-cover map, prior
+/// to its publication. Generalization involves two optional steps. First, a majority filter may be
+/// applied (`--filter`, the filter size in grid cells, which must be an odd number of 3 or greater),
+/// replacing each grid cell with the most frequently occurring class in the surrounding square
+/// neighbourhood. When two or more classes are equally frequent and the cell's own class is one of
+/// them, the cell's class is retained. Second, a minimum mapping unit (MMU) is enforced, such that
+/// patches of contiguous cells of the same class that contain fewer than `--min_size` grid cells are
+/// merged into one of their neighbouring classes. Patches are identified using the four
+/// orthogonal neighbours of each cell, unless the `--diag` flag is specified, in which case diagonal
+/// connections are also considered.
+///
+/// The class into which an undersized patch is merged is determined by the `--method` parameter.
+/// The *dominant* method (the default) merges a patch into the neighbouring class that shares the
+/// longest border with the patch. The *priority* method merges a patch into the neighbouring class
+/// that is ranked highest in the priority list (`--priority`), which is a comma-separated list of
+/// class values ordered from the highest to lowest priority, e.g. `--priority='4,1,2'`. Classes that
+/// are not contained in the list are ranked lower than the listed classes and are otherwise chosen
+/// using the dominant method. Merging is repeated until every patch meets the MMU, so that small
+/// patches that are merged into other small patches are also enforced.
+///
+/// Classes contained in the optional `--preserve` list, e.g. rare classes such as wetlands or
+/// water bodies that must not be lost through generalization, are never modified by either step.
+/// Patches of these classes are retained regardless of their size, although other classes may
+/// still be merged into them. NoData cells are also never modified and patches that are entirely
+/// surrounded by NoData and preserved classes are retained, if no other neighbouring class is
+/// available to absorb them.
+///
+/// # See Also
+/// `MajorityFilter`, `Clump`
+pub struct GeneralizeClassifiedRaster {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl GeneralizeClassifiedRaster {
+    pub fn new() -> GeneralizeClassifiedRaster {
+        // public constructor
+        let name = "GeneralizeClassifiedRaster".to_string();
+        let toolbox = "Image Processing Tools".to_string();
+        let description = "Generalizes a classified raster using a majority filter and a minimum mapping unit, preserving specified classes.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input classified raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Patch Size (grid cells)".to_owned(),
+            flags: vec!["--min_size".to_owned()],
+            description: "Minimum mapping unit, i.e. the minimum patch size, in grid cells.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Majority Filter Size (optional)".to_owned(),
+            flags: vec!["--filter".to_owned()],
+            description: "Optional size of the majority filter applied prior to enforcing the minimum mapping unit; must be an odd integer of 3 or greater.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Merge Method".to_owned(),
+            flags: vec!["--method".to_owned()],
+            description: "Method used to select the class into which undersized patches are merged; options include 'dominant' and 'priority'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "dominant".to_owned(),
+                "priority".to_owned(),
+            ]),
+            default_value: Some("dominant".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Class Priority (optional)".to_owned(),
+            flags: vec!["--priority".to_owned()],
+            description: "Comma-separated list of class values, from highest to lowest priority, used by the 'priority' method.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Preserved Classes (optional)".to_owned(),
+            flags: vec!["--preserve".to_owned()],
+            description: "Comma-separated list of class values that are never modified.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Include diagonal connections?".to_owned(),
+            flags: vec!["--diag".to_owned()],
+            description: "Flag indicating whether diagonal connections should be considered when identifying patches.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=landcover.tif -o=generalized.tif --min_size=25 --filter=3
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=landcover.tif -o=generalized.tif --min_size=25 --method=priority --priority='5,1,3' --preserve='7' --diag", short_exe, name).replace("*", &sep);
+
+        GeneralizeClassifiedRaster {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for GeneralizeClassifiedRaster {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--min_size".to_string(), ParameterConstraint::Min(1.0)),
+            ("--filter".to_string(), ParameterConstraint::Min(3.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut min_size = 5usize;
+        let mut filter_size = 0isize;
+        let mut method = String::from("dominant");
+        let mut priority_str = String::new();
+        let mut preserve_str = String::new();
+        let mut diag = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-min_size" {
+                min_size = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-filter" {
+                filter_size = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as isize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as isize
+                };
+            } else if flag_val == "-method" {
+                method = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-priority" {
+                priority_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-preserve" {
+                preserve_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-diag" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    diag = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let use_priority = if method.contains("prior") {
+            true
+        } else if method.contains("dom") {
+            false
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unrecognized merge method; options include 'dominant' and 'priority'.",
+            ));
+        };
+        let priority = parse_class_list(&priority_str)?;
+        let preserved = parse_class_list(&preserve_str)?;
+        if use_priority && priority.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The 'priority' method requires a list of class priorities (--priority).",
+            ));
+        }
+        if filter_size > 0 && (filter_size < 3 || filter_size % 2 == 0) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The majority filter size must be an odd integer of 3 or greater.",
+            ));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let input = Arc::new(Raster::new(&input_file, "r")?);
+        let rows = input.configs.rows as isize;
+        let columns = input.configs.columns as isize;
+        let nodata = input.configs.nodata;
+
+        let start = Instant::now();
+
+        let mut output = Raster::initialize_using_file(&output_file, &input);
+
+        // majority filter
+        if filter_size >= 3 {
+            let midpoint = filter_size / 2;
+            let preserved = Arc::new(preserved.clone());
+            let num_procs = num_cpus::get() as isize;
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let input = input.clone();
+                let preserved = preserved.clone();
+                let tx1 = tx.clone();
+                thread::spawn(move || {
+                    let mut counts: Vec<(f64, usize)> = vec![];
+                    for row in (0..rows).filter(|r| r % num_procs == tid) {
+                        let mut data = vec![nodata; columns as usize];
+                        for col in 0..columns {
+                            let z = input.get_value(row, col);
+                            if z == nodata || preserved.contains(&z) {
+                                data[col as usize] = z;
+                                continue;
+                            }
+                            counts.clear();
+                            for row2 in (row - midpoint)..=(row + midpoint) {
+                                for col2 in (col - midpoint)..=(col + midpoint) {
+                                    let zn = input.get_value(row2, col2);
+                                    if zn != nodata {
+                                        match counts.iter_mut().find(|c| c.0 == zn) {
+                                            Some(c) => c.1 += 1,
+                                            None => counts.push((zn, 1)),
+                                        }
+                                    }
+                                }
+                            }
+                            let own_count = counts.iter().find(|c| c.0 == z).map_or(0, |c| c.1);
+                            let mut mode = (z, own_count);
+                            for c in &counts {
+                                if c.1 > mode.1 {
+                                    mode = *c;
+                                }
+                            }
+                            data[col as usize] = mode.0;
+                        }
+                        tx1.send((row, data)).unwrap();
+                    }
+                });
+            }
+
+            for row in 0..rows {
+                let data = rx.recv().unwrap();
+                output.set_row_data(data.0, data.1);
+                if verbose {
+                    progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Majority filter: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        } else {
+            for row in 0..rows {
+                output.set_row_data(row, input.get_row_data(row));
+            }
+        }
+        drop(input);
+
+        // enforce the minimum mapping unit
+        let (dx, dy) = if diag {
+            (
+                vec![1, 1, 1, 0, -1, -1, -1, 0],
+                vec![-1, 0, 1, 1, 1, 0, -1, -1],
+            )
+        } else {
+            (vec![1, 0, -1, 0], vec![0, 1, 0, -1])
+        };
+        let border_dx = [1, 0, -1, 0];
+        let border_dy = [0, 1, 0, -1];
+        let mut num_merged = 0usize;
+        let mut iteration = 1;
+        if min_size > 1 {
+            loop {
+                // identify the patches
+                let mut labels: Array2D<i32> = Array2D::new(rows, columns, -1, -1)?;
+                let mut patches: Vec<Vec<(isize, isize)>> = vec![];
+                let mut stack = vec![];
+                for row in 0..rows {
+                    for col in 0..columns {
+                        let z = output.get_value(row, col);
+                        if z == nodata || labels.get_value(row, col) != -1 {
+                            continue;
+                        }
+                        let label = patches.len() as i32;
+                        let mut cells = vec![];
+                        labels.set_value(row, col, label);
+                        stack.push((row, col));
+                        while let Some((r, c)) = stack.pop() {
+                            cells.push((r, c));
+                            for n in 0..dx.len() {
+                                let (rn, cn) = (r + dy[n], c + dx[n]);
+                                if rn >= 0
+                                    && cn >= 0
+                                    && rn < rows
+                                    && cn < columns
+                                    && labels.get_value(rn, cn) == -1
+                                    && output.get_value(rn, cn) == z
+                                {
+                                    labels.set_value(rn, cn, label);
+                                    stack.push((rn, cn));
+                                }
+                            }
+                        }
+                        patches.push(cells);
+                    }
+                }
+
+                // merge the undersized patches, smallest first
+                let mut small: Vec<usize> = (0..patches.len())
+                    .filter(|p| {
+                        patches[*p].len() < min_size
+                            && !preserved.contains(&output.get_value(patches[*p][0].0, patches[*p][0].1))
+                    })
+                    .collect();
+                small.sort_by_key(|p| patches[*p].len());
+                let mut num_changed = 0usize;
+                let mut border: Vec<(f64, usize)> = vec![];
+                for (i, p) in small.iter().enumerate() {
+                    let cells = &patches[*p];
+                    let z = output.get_value(cells[0].0, cells[0].1);
+                    border.clear();
+                    for (r, c) in cells {
+                        for n in 0..4 {
+                            let (rn, cn) = (r + border_dy[n], c + border_dx[n]);
+                            if rn < 0 || cn < 0 || rn >= rows || cn >= columns {
+                                continue;
+                            }
+                            let zn = output.get_value(rn, cn);
+                            if zn != nodata && zn != z && labels.get_value(rn, cn) != *p as i32 {
+                                match border.iter_mut().find(|b| b.0 == zn) {
+                                    Some(b) => b.1 += 1,
+                                    None => border.push((zn, 1)),
+                                }
+                            }
+                        }
+                    }
+                    let rank = |class: f64| -> usize {
+                        priority.iter().position(|v| *v == class).unwrap_or(priority.len())
+                    };
+                    let mut target: Option<(f64, usize)> = None;
+                    for b in &border {
+                        target = match target {
+                            None => Some(*b),
+                            Some(t) => {
+                                let better = if use_priority && rank(b.0) != rank(t.0) {
+                                    rank(b.0) < rank(t.0)
+                                } else {
+                                    b.1 > t.1 || (b.1 == t.1 && b.0 < t.0)
+                                };
+                                if better {
+                                    Some(*b)
+                                } else {
+                                    Some(t)
+                                }
+                            }
+                        };
+                    }
+                    if let Some((class, _)) = target {
+                        for (r, c) in cells {
+                            output.set_value(*r, *c, class);
+                        }
+                        num_changed += 1;
+                    }
+                    if verbose {
+                        progress = (100.0_f64 * i as f64 / (small.len() - 1).max(1) as f64) as usize;
+                        if progress != old_progress {
+                            println!("Merging patches (iteration {}): {}%", iteration, progress);
+                            old_progress = progress;
+                        }
+                    }
+                }
+                num_merged += num_changed;
+                if num_changed == 0 {
+                    break;
+                }
+                iteration += 1;
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        output.add_metadata_entry(format!("Minimum patch size: {}", min_size));
+        if filter_size >= 3 {
+            output.add_metadata_entry(format!("Majority filter size: {}", filter_size));
+        }
+        output.add_metadata_entry(format!(
+            "Merge method: {}",
+            if use_priority { "priority" } else { "dominant" }
+        ));
+        if !preserved.is_empty() {
+            output.add_metadata_entry(format!("Preserved classes: {}", preserve_str));
+        }
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Number of merged patches: {}", num_merged);
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a comma-separated list of class values.
+fn parse_class_list(s: &str) -> Result<Vec<f64>, Error> {
+    let mut classes = vec![];
+    for token in s.split(&[',', ';'][..]).map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        match token.parse::<f64>() {
+            Ok(v) => classes.push(v),
+            Err(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unrecognized class value '{}'.", token),
+                ))
+            }
+        }
+    }
+    Ok(classes)
+}
@@ -227,8 +227,6 @@ impl WhiteboxTool for HighPassMedianFilter {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         // a median filter of less than 3 x 3 doesn't make sense.
         if filter_size_x < 3 {
             filter_size_x = 3;
@@ -251,12 +249,8 @@ impl WhiteboxTool for HighPassMedianFilter {
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
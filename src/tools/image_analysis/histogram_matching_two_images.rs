@@ -193,20 +193,12 @@ impl WhiteboxTool for HistogramMatchingTwoImages {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file1.contains(&sep) && !input_file1.contains("/") {
-            input_file1 = format!("{}{}", working_directory, input_file1);
-        }
-        if !input_file2.contains(&sep) && !input_file2.contains("/") {
-            input_file2 = format!("{}{}", working_directory, input_file2);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file1 = resolve_path(&input_file1, working_directory);
+        input_file2 = resolve_path(&input_file2, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input data...")
@@ -2,16 +2,16 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 25/07/2017
-Last Modified: 22/10/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
+use crate::algorithms::ihs_to_rgb;
 use crate::raster::*;
 use crate::tools::*;
 use num_cpus;
 use std::env;
 use std::f64;
-use std::f64::consts::PI;
 use std::io::{Error, ErrorKind};
 use std::path;
 use std::sync::mpsc;
@@ -273,35 +273,19 @@ impl WhiteboxTool for IhsToRgb {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
         if !use_composite {
-            if !red_file.contains(&sep) && !red_file.contains("/") {
-                red_file = format!("{}{}", working_directory, red_file);
-            }
-            if !green_file.contains(&sep) && !green_file.contains("/") {
-                green_file = format!("{}{}", working_directory, green_file);
-            }
-            if !blue_file.contains(&sep) && !blue_file.contains("/") {
-                blue_file = format!("{}{}", working_directory, blue_file);
-            }
+            red_file = resolve_path(&red_file, working_directory);
+            green_file = resolve_path(&green_file, working_directory);
+            blue_file = resolve_path(&blue_file, working_directory);
         } else {
-            if !composite_file.contains(&sep) && !composite_file.contains("/") {
-                composite_file = format!("{}{}", working_directory, composite_file);
-            }
-        }
-        if !intensity_file.contains(&sep) && !intensity_file.contains("/") {
-            intensity_file = format!("{}{}", working_directory, intensity_file);
-        }
-        if !hue_file.contains(&sep) && !hue_file.contains("/") {
-            hue_file = format!("{}{}", working_directory, hue_file);
-        }
-        if !saturation_file.contains(&sep) && !saturation_file.contains("/") {
-            saturation_file = format!("{}{}", working_directory, saturation_file);
+            composite_file = resolve_path(&composite_file, working_directory);
         }
+        intensity_file = resolve_path(&intensity_file, working_directory);
+        hue_file = resolve_path(&hue_file, working_directory);
+        saturation_file = resolve_path(&saturation_file, working_directory);
 
         if verbose {
             println!("Reading intensity band data...")
@@ -362,11 +346,11 @@ impl WhiteboxTool for IhsToRgb {
                             h = input_h[(row, col)];
                             s = input_s[(row, col)];
                             if i != nodata_i && h != nodata_h && s != nodata_s {
-                                let (r, g, b) = hsi2rgb(h, s, i);
+                                let (r, g, b) = ihs_to_rgb(i, h, s);
 
-                                red_data[col as usize] = r as f64;
-                                green_data[col as usize] = g as f64;
-                                blue_data[col as usize] = b as f64;
+                                red_data[col as usize] = (r * 255f64).round();
+                                green_data[col as usize] = (g * 255f64).round();
+                                blue_data[col as usize] = (b * 255f64).round();
                             }
                         }
                         tx.send((row, red_data, green_data, blue_data)).unwrap();
@@ -498,7 +482,12 @@ impl WhiteboxTool for IhsToRgb {
                             h = input_h[(row, col)];
                             s = input_s[(row, col)];
                             if i != nodata_i && h != nodata_h && s != nodata_s {
-                                value = hsi2value(h, s, i);
+                                let (r, g, b) = ihs_to_rgb(i, h, s);
+                                value = ((255u32 << 24)
+                                    | (((b * 255f64).round() as u32) << 16)
+                                    | (((g * 255f64).round() as u32) << 8)
+                                    | ((r * 255f64).round() as u32))
+                                    as f64;
                                 data[col as usize] = value;
                             }
                         }
@@ -582,87 +571,3 @@ impl WhiteboxTool for IhsToRgb {
 
 //     (h, s, i)
 // }
-
-fn hsi2value(h: f64, s: f64, i: f64) -> f64 {
-    let mut r: u32;
-    let mut g: u32;
-    let mut b: u32;
-
-    let x = i * (1f64 - s);
-
-    if h < 2f64 * PI / 3f64 {
-        let y = i * (1f64 + (s * h.cos()) / ((PI / 3f64 - h).cos()));
-        let z = 3f64 * i - (x + y);
-        r = (y * 255f64).round() as u32;
-        g = (z * 255f64).round() as u32;
-        b = (x * 255f64).round() as u32;
-    } else if h < 4f64 * PI / 3f64 {
-        let h = h - 2f64 * PI / 3f64;
-        let y = i * (1f64 + (s * h.cos()) / ((PI / 3f64 - h).cos()));
-        let z = 3f64 * i - (x + y);
-        r = (x * 255f64).round() as u32;
-        g = (y * 255f64).round() as u32;
-        b = (z * 255f64).round() as u32;
-    } else {
-        let h = h - 4f64 * PI / 3f64;
-        let y = i * (1f64 + (s * h.cos()) / ((PI / 3f64 - h).cos()));
-        let z = 3f64 * i - (x + y);
-        r = (z * 255f64).round() as u32;
-        g = (x * 255f64).round() as u32;
-        b = (y * 255f64).round() as u32;
-    }
-
-    if r > 255u32 {
-        r = 255u32;
-    }
-    if g > 255u32 {
-        g = 255u32;
-    }
-    if b > 255u32 {
-        b = 255u32;
-    }
-
-    ((255 << 24) | (b << 16) | (g << 8) | r) as f64
-}
-
-fn hsi2rgb(h: f64, s: f64, i: f64) -> (u32, u32, u32) {
-    let mut r: u32;
-    let mut g: u32;
-    let mut b: u32;
-
-    let x = i * (1f64 - s);
-
-    if h < 2f64 * PI / 3f64 {
-        let y = i * (1f64 + (s * h.cos()) / ((PI / 3f64 - h).cos()));
-        let z = 3f64 * i - (x + y);
-        r = (y * 255f64).round() as u32;
-        g = (z * 255f64).round() as u32;
-        b = (x * 255f64).round() as u32;
-    } else if h < 4f64 * PI / 3f64 {
-        let h = h - 2f64 * PI / 3f64;
-        let y = i * (1f64 + (s * h.cos()) / ((PI / 3f64 - h).cos()));
-        let z = 3f64 * i - (x + y);
-        r = (x * 255f64).round() as u32;
-        g = (y * 255f64).round() as u32;
-        b = (z * 255f64).round() as u32;
-    } else {
-        let h = h - 4f64 * PI / 3f64;
-        let y = i * (1f64 + (s * h.cos()) / ((PI / 3f64 - h).cos()));
-        let z = 3f64 * i - (x + y);
-        r = (z * 255f64).round() as u32;
-        g = (x * 255f64).round() as u32;
-        b = (y * 255f64).round() as u32;
-    }
-
-    if r > 255u32 {
-        r = 255u32;
-    }
-    if g > 255u32 {
-        g = 255u32;
-    }
-    if b > 255u32 {
-        b = 255u32;
-    }
-
-    (r, g, b)
-}
\ No newline at end of file
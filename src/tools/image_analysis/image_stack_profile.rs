@@ -190,8 +190,6 @@ impl WhiteboxTool for ImageStackProfile {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -207,13 +205,9 @@ impl WhiteboxTool for ImageStackProfile {
                 "There is something incorrect about the input files. At least two inputs are required to operate this tool."));
         }
 
-        if !points_file.contains(&sep) && !points_file.contains("/") {
-            points_file = format!("{}{}", working_directory, points_file);
-        }
+        points_file = resolve_path(&points_file, working_directory);
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         if !output_file.ends_with(".html") {
             output_file.push_str(".html");
@@ -251,9 +245,7 @@ impl WhiteboxTool for ImageStackProfile {
         for i in 0..num_files {
             if !input_files[i].trim().is_empty() {
                 let mut input_file = input_files[i].trim().to_owned();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
 
                 let image = Raster::new(&input_file, "r")?;
                 let nodata = image.configs.nodata;
@@ -0,0 +1,421 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::lab_to_rgb;
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool transforms three CIE L\*a\*b\* (CIELAB) raster images into three equivalent multispectral
+/// images corresponding with the red, green, and blue channels of an RGB composite, or into a single
+/// colour-composite image. It is the inverse of the `RgbToLab` tool. The user must specify the names of the
+/// input L\*, a\*, and b\* images (`--l_star`, `--a_star`, `--b_star`). L\* is expected to range from 0 to
+/// 100, while a\* and b\* generally range from approximately -128 to 127. The user must also specify either
+/// the names of the output red, green, and blue images (`--red`, `--green`, `--blue`), which will have
+/// values ranging from 0 to 255, or the name of an output colour-composite image (`--output`). The
+/// transformation assumes sRGB primaries and the D65 reference white. Colours that fall outside of the
+/// sRGB gamut, which can result from modifying the L\*a\*b\* components, are clipped.
+///
+/// Because L\* is perceptually uniform and is separated from the chromatic information held in a\* and
+/// b\*, contrast enhancements (e.g. `HistogramEqualization`) and filters applied to the L\* image prior to
+/// the inverse transformation modify image brightness without introducing the colour shifts that are
+/// common when the same operations are applied to the individual RGB bands.
+///
+/// # See Also
+/// `RgbToLab`, `LabToRgb`, `IhsToRgb`, `HistogramEqualization`
+pub struct LabToRgb {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LabToRgb {
+    /// Public constructor.
+    pub fn new() -> LabToRgb {
+        let name = "LabToRgb".to_string();
+        let toolbox = "Image Processing Tools".to_string();
+        let description = "Converts CIE L*a*b* (CIELAB) images into red, green, and blue (RGB) images.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input L* File".to_owned(),
+            flags: vec!["--l_star".to_owned()],
+            description: "Input L* (lightness) raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input a* File".to_owned(),
+            flags: vec!["--a_star".to_owned()],
+            description: "Input a* (green-red) raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input b* File".to_owned(),
+            flags: vec!["--b_star".to_owned()],
+            description: "Input b* (blue-yellow) raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Red Band File (optional; only if colour-composite not specified)"
+                .to_owned(),
+            flags: vec!["--red".to_owned()],
+            description:
+                "Output red band file. Optionally specified if colour-composite not specified."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Green Band File (optional; only if colour-composite not specified)"
+                .to_owned(),
+            flags: vec!["--green".to_owned()],
+            description:
+                "Output green band file. Optionally specified if colour-composite not specified."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Blue Band File (optional; only if colour-composite not specified)"
+                .to_owned(),
+            flags: vec!["--blue".to_owned()],
+            description:
+                "Output blue band file. Optionally specified if colour-composite not specified."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Colour-Composite File (optional; only if individual bands not specified)"
+                .to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description:
+                "Output colour-composite file. Only used if individual bands are not specified."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --l_star=l.tif --a_star=a.tif --b_star=b.tif --red=band3.tif --green=band2.tif --blue=band1.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --l_star=l_enhanced.tif --a_star=a.tif --b_star=b.tif --output=image.tif", short_exe, name).replace("*", &sep);
+
+        LabToRgb {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LabToRgb {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            (
+                "--output".to_string(),
+                ParameterConstraint::ExclusiveWith("--red".to_string()),
+            ),
+            (
+                "--red".to_string(),
+                ParameterConstraint::RequiredIf("--green".to_string()),
+            ),
+            (
+                "--green".to_string(),
+                ParameterConstraint::RequiredIf("--blue".to_string()),
+            ),
+            (
+                "--blue".to_string(),
+                ParameterConstraint::RequiredIf("--red".to_string()),
+            ),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut l_file = String::new();
+        let mut a_file = String::new();
+        let mut b_file = String::new();
+        let mut red_file = String::new();
+        let mut green_file = String::new();
+        let mut blue_file = String::new();
+        let mut composite_file = String::new();
+        let mut use_composite = false;
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            let value = if keyval {
+                vec[1].to_string()
+            } else if i + 1 < args.len() {
+                args[i + 1].to_string()
+            } else {
+                String::new()
+            };
+            if flag_val == "-l_star" {
+                l_file = value;
+            } else if flag_val == "-a_star" {
+                a_file = value;
+            } else if flag_val == "-b_star" {
+                b_file = value;
+            } else if flag_val == "-red" {
+                red_file = value;
+            } else if flag_val == "-green" {
+                green_file = value;
+            } else if flag_val == "-blue" {
+                blue_file = value;
+            } else if flag_val == "-o" || flag_val == "-composite" || flag_val == "-output" {
+                composite_file = value;
+                use_composite = true;
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if !use_composite && (red_file.is_empty() || green_file.is_empty() || blue_file.is_empty()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either the red, green, and blue band files or a colour-composite file must be specified.",
+            ));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        for file in [
+            &mut l_file,
+            &mut a_file,
+            &mut b_file,
+            &mut red_file,
+            &mut green_file,
+            &mut blue_file,
+            &mut composite_file,
+        ] {
+            *file = resolve_path(file, working_directory);
+        }
+
+        if verbose {
+            println!("Reading input data...")
+        };
+        let input_l = Arc::new(Raster::new(&l_file, "r")?);
+        let input_a = Arc::new(Raster::new(&a_file, "r")?);
+        let input_b = Arc::new(Raster::new(&b_file, "r")?);
+
+        let rows = input_l.configs.rows as isize;
+        let columns = input_l.configs.columns as isize;
+        let nodata_l = input_l.configs.nodata;
+        let nodata_a = input_a.configs.nodata;
+        let nodata_b = input_b.configs.nodata;
+        for input in [&input_a, &input_b] {
+            if input.configs.rows as isize != rows || input.configs.columns as isize != columns {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+        }
+
+        let start = Instant::now();
+
+        // Invalid cells are assigned the NoData value of the L* image in the band outputs. For
+        // the colour-composite output they are flagged with a negative value and later become
+        // zero (i.e. transparent).
+        let out_nodata = if use_composite { -1f64 } else { nodata_l };
+        let num_procs = num_cpus::get() as isize;
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let input_l = input_l.clone();
+            let input_a = input_a.clone();
+            let input_b = input_b.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let (mut l, mut a, mut b): (f64, f64, f64);
+                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                    let mut red_data = vec![out_nodata; columns as usize];
+                    let mut green_data = vec![out_nodata; columns as usize];
+                    let mut blue_data = vec![out_nodata; columns as usize];
+                    for col in 0..columns {
+                        l = input_l[(row, col)];
+                        a = input_a[(row, col)];
+                        b = input_b[(row, col)];
+                        if l != nodata_l && a != nodata_a && b != nodata_b {
+                            let (r, g, b) = lab_to_rgb(l, a, b);
+                            red_data[col as usize] = (r * 255f64).round();
+                            green_data[col as usize] = (g * 255f64).round();
+                            blue_data[col as usize] = (b * 255f64).round();
+                        }
+                    }
+                    tx.send((row, red_data, green_data, blue_data)).unwrap();
+                }
+            });
+        }
+
+        let mut outputs = vec![];
+        if use_composite {
+            let mut output = Raster::initialize_using_file(&composite_file, &input_l);
+            output.configs.photometric_interp = PhotometricInterpretation::RGB;
+            output.configs.nodata = 0f64;
+            output.configs.data_type = DataType::RGBA32;
+            outputs.push(output);
+        } else {
+            for file in [&red_file, &green_file, &blue_file] {
+                let mut output = Raster::initialize_using_file(file, &input_l);
+                output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+                output.configs.data_type = DataType::F32;
+                outputs.push(output);
+            }
+        }
+
+        for row in 0..rows {
+            let (r, red_data, green_data, blue_data) = rx.recv().unwrap();
+            if use_composite {
+                let data = (0..columns as usize)
+                    .map(|col| {
+                        if red_data[col] < 0f64 {
+                            0f64
+                        } else {
+                            ((255u32 << 24)
+                                | ((blue_data[col] as u32) << 16)
+                                | ((green_data[col] as u32) << 8)
+                                | (red_data[col] as u32)) as f64
+                        }
+                    })
+                    .collect();
+                outputs[0].set_row_data(r, data);
+            } else {
+                outputs[0].set_row_data(r, red_data);
+                outputs[1].set_row_data(r, green_data);
+                outputs[2].set_row_data(r, blue_data);
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        for output in outputs.iter_mut() {
+            output.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            output.add_metadata_entry(format!("Input L* image file: {}", l_file));
+            output.add_metadata_entry(format!("Input a* image file: {}", a_file));
+            output.add_metadata_entry(format!("Input b* image file: {}", b_file));
+            output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+            if verbose {
+                println!("Saving data...")
+            };
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
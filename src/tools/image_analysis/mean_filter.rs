@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 25/06/2017
-Last Modified: 22/10/2019
+Last Modified: 17/10/2026
 License: MIT
 */
 
@@ -39,8 +39,9 @@ use crate::tools::*;
 /// 
 /// This tool works with both greyscale and red-green-blue (RGB) images. RGB images are 
 /// decomposed into intensity-hue-saturation (IHS) and the filter is applied to the intensity
-/// channel. NoData values in the input image are ignored during filtering. NoData values are assigned to all sites beyond
-/// the raster.
+/// channel. Each band of a multi-band image is filtered separately. NoData values in the input image are ignored
+/// during filtering. NoData values are assigned to all sites beyond the raster. The input and output
+/// images are held in memory in their native data types, e.g. one byte per cell for 8-bit imagery.
 /// 
 /// # Reference
 /// Crow, F. C. (1984, January). Summed-area tables for texture mapping. In ACM SIGGRAPH computer graphics (Vol. 18, No. 
@@ -217,8 +218,6 @@ impl WhiteboxTool for MeanFilter {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         if filter_size_x < 3 {
             filter_size_x = 3;
         }
@@ -239,184 +238,190 @@ impl WhiteboxTool for MeanFilter {
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
         };
 
-        let input = Arc::new(Raster::new(&input_file, "r")?);
+        let input_raster = Raster::new_native(&input_file, "r")?;
 
         let start = Instant::now();
 
-        let rows = input.configs.rows as isize;
-        let columns = input.configs.columns as isize;
-        let nodata = input.configs.nodata;
-
-        let is_rgb_image = if input.configs.data_type == DataType::RGB24
-            || input.configs.data_type == DataType::RGBA32
-            || input.configs.photometric_interp == PhotometricInterpretation::RGB
-        {
-            true
-        } else {
-            false
-        };
-
-        let min_val = if !is_rgb_image {
-            input.configs.minimum
-        } else {
-            0f64
-        };
-
-        // create the integral images
-        let mut integral: Array2D<f64> = Array2D::new(rows, columns, 0f64, nodata)?;
-        let mut integral_n: Array2D<i32> = Array2D::new(rows, columns, 0, -1)?;
-
-        let input_fn: Box<dyn Fn(isize, isize) -> f64> = if !is_rgb_image {
-            Box::new(|row: isize, col: isize| -> f64 { input.get_value(row, col) })
-        } else {
-            Box::new(|row: isize, col: isize| -> f64 {
-                let value = input.get_value(row, col);
-                if value != nodata {
-                    return value2i(value);
-                }
-                nodata
-            })
-        };
-
-        let mut val: f64;
-        let mut sum: f64;
-        let mut sum_n: i32;
-        let mut i_prev: f64;
-        let mut n_prev: i32;
-        for row in 0..rows {
-            sum = 0f64;
-            sum_n = 0;
-            for col in 0..columns {
-                val = input_fn(row, col);
-                if val == nodata {
-                    val = 0f64;
-                } else {
-                    val -= min_val;
-                    sum_n += 1;
-                }
-                sum += val;
-                if row > 0 {
-                    i_prev = integral[(row - 1, col)];
-                    n_prev = integral_n[(row - 1, col)];
-                    integral[(row, col)] = sum + i_prev;
-                    integral_n[(row, col)] = sum_n + n_prev;
-                } else {
-                    integral[(row, col)] = sum;
-                    integral_n[(row, col)] = sum_n;
+        // each band of a multi-band image is filtered separately
+        let mut outputs = Vec::with_capacity(input_raster.num_bands());
+        for input in input_raster.into_bands() {
+            let input = Arc::new(input);
+
+            let rows = input.configs.rows as isize;
+            let columns = input.configs.columns as isize;
+            let nodata = input.configs.nodata;
+
+            let is_rgb_image = if input.configs.data_type == DataType::RGB24
+                || input.configs.data_type == DataType::RGBA32
+                || input.configs.photometric_interp == PhotometricInterpretation::RGB
+            {
+                true
+            } else {
+                false
+            };
+
+            let min_val = if !is_rgb_image {
+                input.configs.minimum
+            } else {
+                0f64
+            };
+
+            // create the integral images
+            let mut integral: Array2D<f64> = Array2D::new(rows, columns, 0f64, nodata)?;
+            let mut integral_n: Array2D<i32> = Array2D::new(rows, columns, 0, -1)?;
+
+            let input_fn: Box<dyn Fn(isize, isize) -> f64> = if !is_rgb_image {
+                Box::new(|row: isize, col: isize| -> f64 { input.get_value(row, col) })
+            } else {
+                Box::new(|row: isize, col: isize| -> f64 {
+                    let value = input.get_value(row, col);
+                    if value != nodata {
+                        return value2i(value);
+                    }
+                    nodata
+                })
+            };
+
+            let mut val: f64;
+            let mut sum: f64;
+            let mut sum_n: i32;
+            let mut i_prev: f64;
+            let mut n_prev: i32;
+            for row in 0..rows {
+                sum = 0f64;
+                sum_n = 0;
+                for col in 0..columns {
+                    val = input_fn(row, col);
+                    if val == nodata {
+                        val = 0f64;
+                    } else {
+                        val -= min_val;
+                        sum_n += 1;
+                    }
+                    sum += val;
+                    if row > 0 {
+                        i_prev = integral[(row - 1, col)];
+                        n_prev = integral_n[(row - 1, col)];
+                        integral[(row, col)] = sum + i_prev;
+                        integral_n[(row, col)] = sum_n + n_prev;
+                    } else {
+                        integral[(row, col)] = sum;
+                        integral_n[(row, col)] = sum_n;
+                    }
                 }
-            }
-            if verbose {
-                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
-                if progress != old_progress {
-                    println!("Creating integral images: {}%", progress);
-                    old_progress = progress;
+                if verbose {
+                    progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Creating integral images: {}%", progress);
+                        old_progress = progress;
+                    }
                 }
             }
-        }
 
-        let i = Arc::new(integral); // wrap integral in an Arc
-        let i_n = Arc::new(integral_n); // wrap integral_n in an Arc
-        let mut output = Raster::initialize_using_file(&output_file, &input);
-        let (tx, rx) = mpsc::channel();
-        let num_procs = num_cpus::get() as isize;
-        for tid in 0..num_procs {
-            let input = input.clone();
-            let i = i.clone();
-            let i_n = i_n.clone();
-            let tx1 = tx.clone();
-            thread::spawn(move || {
-                let input_fn: Box<dyn Fn(isize, isize) -> f64> = if !is_rgb_image {
-                    Box::new(|row: isize, col: isize| -> f64 { input.get_value(row, col) })
-                } else {
-                    Box::new(|row: isize, col: isize| -> f64 {
-                        let value = input.get_value(row, col);
-                        if value != nodata {
-                            return value2i(value);
+            let i = Arc::new(integral); // wrap integral in an Arc
+            let i_n = Arc::new(integral_n); // wrap integral_n in an Arc
+            let mut output = Raster::initialize_using_file(&output_file, &input);
+            output.set_native_storage();
+            let (tx, rx) = mpsc::channel();
+            let num_procs = num_cpus::get() as isize;
+            for tid in 0..num_procs {
+                let input = input.clone();
+                let i = i.clone();
+                let i_n = i_n.clone();
+                let tx1 = tx.clone();
+                thread::spawn(move || {
+                    let input_fn: Box<dyn Fn(isize, isize) -> f64> = if !is_rgb_image {
+                        Box::new(|row: isize, col: isize| -> f64 { input.get_value(row, col) })
+                    } else {
+                        Box::new(|row: isize, col: isize| -> f64 {
+                            let value = input.get_value(row, col);
+                            if value != nodata {
+                                return value2i(value);
+                            }
+                            nodata
+                        })
+                    };
+
+                    let output_fn: Box<dyn Fn(isize, isize, f64) -> f64> = if !is_rgb_image {
+                        // simply return the value.
+                        Box::new(|_: isize, _: isize, value: f64| -> f64 { value })
+                    } else {
+                        // convert it back into an rgb value, using the modified intensity value.
+                        Box::new(|row: isize, col: isize, value: f64| -> f64 {
+                            if value != nodata {
+                                let (h, s, _) = value2hsi(input.get_value(row, col));
+                                return hsi2value(h, s, value);
+                            }
+                            nodata
+                        })
+                    };
+
+                    let (mut x1, mut x2, mut y1, mut y2): (isize, isize, isize, isize);
+                    let mut n: i32;
+                    let mut sum: f64;
+                    let mut mean: f64;
+                    let mut z: f64;
+                    for row in (0..rows).filter(|r| r % num_procs == tid) {
+                        y1 = row - midpoint_y - 1;
+                        if y1 < 0 {
+                            y1 = 0;
                         }
-                        nodata
-                    })
-                };
 
-                let output_fn: Box<dyn Fn(isize, isize, f64) -> f64> = if !is_rgb_image {
-                    // simply return the value.
-                    Box::new(|_: isize, _: isize, value: f64| -> f64 { value })
-                } else {
-                    // convert it back into an rgb value, using the modified intensity value.
-                    Box::new(|row: isize, col: isize, value: f64| -> f64 {
-                        if value != nodata {
-                            let (h, s, _) = value2hsi(input.get_value(row, col));
-                            return hsi2value(h, s, value);
+                        y2 = row + midpoint_y;
+                        if y2 >= rows {
+                            y2 = rows - 1;
                         }
-                        nodata
-                    })
-                };
-
-                let (mut x1, mut x2, mut y1, mut y2): (isize, isize, isize, isize);
-                let mut n: i32;
-                let mut sum: f64;
-                let mut mean: f64;
-                let mut z: f64;
-                for row in (0..rows).filter(|r| r % num_procs == tid) {
-                    y1 = row - midpoint_y - 1;
-                    if y1 < 0 {
-                        y1 = 0;
-                    }
-
-                    y2 = row + midpoint_y;
-                    if y2 >= rows {
-                        y2 = rows - 1;
-                    }
-                    let mut data = vec![nodata; columns as usize];
-                    for col in 0..columns {
-                        z = input_fn(row, col);
-                        if z != nodata {
-                            x1 = col - midpoint_x - 1;
-                            if x1 < 0 {
-                                x1 = 0;
-                            }
-
-                            x2 = col + midpoint_x;
-                            if x2 >= columns {
-                                x2 = columns - 1;
-                            }
-                            n = i_n[(y2, x2)] + i_n[(y1, x1)] - i_n[(y1, x2)] - i_n[(y2, x1)];
-                            if n > 0 {
-                                sum = i[(y2, x2)] + i[(y1, x1)] - i[(y1, x2)] - i[(y2, x1)];
-                                mean = sum / n as f64 + min_val;
-                                data[col as usize] = output_fn(row, col, mean);
-                            } else {
-                                data[col as usize] = output_fn(row, col, 0f64);
+                        let mut data = vec![nodata; columns as usize];
+                        for col in 0..columns {
+                            z = input_fn(row, col);
+                            if z != nodata {
+                                x1 = col - midpoint_x - 1;
+                                if x1 < 0 {
+                                    x1 = 0;
+                                }
+
+                                x2 = col + midpoint_x;
+                                if x2 >= columns {
+                                    x2 = columns - 1;
+                                }
+                                n = i_n[(y2, x2)] + i_n[(y1, x1)] - i_n[(y1, x2)] - i_n[(y2, x1)];
+                                if n > 0 {
+                                    sum = i[(y2, x2)] + i[(y1, x1)] - i[(y1, x2)] - i[(y2, x1)];
+                                    mean = sum / n as f64 + min_val;
+                                    data[col as usize] = output_fn(row, col, mean);
+                                } else {
+                                    data[col as usize] = output_fn(row, col, 0f64);
+                                }
                             }
                         }
-                    }
 
-                    tx1.send((row, data)).unwrap();
-                }
-            });
-        }
+                        tx1.send((row, data)).unwrap();
+                    }
+                });
+            }
 
-        for row in 0..rows {
-            let data = rx.recv().unwrap();
-            output.set_row_data(data.0, data.1);
-            if verbose {
-                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
-                if progress != old_progress {
-                    println!("Progress: {}%", progress);
-                    old_progress = progress;
+            for row in 0..rows {
+                let data = rx.recv().unwrap();
+                output.set_row_data(data.0, data.1);
+                if verbose {
+                    progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
                 }
             }
+
+            outputs.push(output);
         }
+        let mut output = Raster::from_bands(outputs)?;
 
         let elapsed_time = get_formatted_elapsed_time(start);
         output.add_metadata_entry(format!(
@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 15/07/2017
-Last Modified: 22/10/2019
+Last Modified: 17/10/2026
 License: MIT
 */
 
@@ -28,7 +28,9 @@ use std::thread;
 /// that the average is. As such, the median filter is far less sensitive to shot noise in an image than the mean filter. 
 /// 
 /// Neighbourhood size, or filter size, is specified in the x and y dimensions using the `--filterx` and `--filtery`flags. 
-/// These dimensions should be odd, positive integer values (e.g. 3, 5, 7, 9, etc.).
+/// These dimensions should be odd, positive integer values (e.g. 3, 5, 7, 9, etc.). Each band of a multi-band image is
+/// filtered separately. Images are held in memory in their native data types, which greatly reduces
+/// the memory needed to filter large 8-bit and 16-bit images.
 /// 
 /// # Reference
 /// Huang, T., Yang, G.J.T.G.Y. and Tang, G., 1979. A fast two-dimensional median filtering algorithm. IEEE 
@@ -232,8 +234,6 @@ impl WhiteboxTool for MedianFilter {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         // a median filter of less than 3 x 3 doesn't make sense.
         if filter_size_x < 3 {
             filter_size_x = 3;
@@ -256,264 +256,270 @@ impl WhiteboxTool for MedianFilter {
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
         };
 
-        let input = Arc::new(Raster::new(&input_file, "r")?);
+        let input_raster = Raster::new_native(&input_file, "r")?;
         // let input = Raster::new(&input_file, "r")?;
 
         let start = Instant::now();
 
-        let is_rgb_image = if input.configs.data_type == DataType::RGB24
-            || input.configs.data_type == DataType::RGBA32
-            || input.configs.photometric_interp == PhotometricInterpretation::RGB
-        {
-            true
-        } else {
-            false
-        };
+        // each band of a multi-band image is filtered separately
+        let mut outputs = Vec::with_capacity(input_raster.num_bands());
+        for input in input_raster.into_bands() {
+            let input = Arc::new(input);
 
-        if is_rgb_image && num_sig_digits < 4 {
-            num_sig_digits = 4;
-        }
+            let is_rgb_image = if input.configs.data_type == DataType::RGB24
+                || input.configs.data_type == DataType::RGBA32
+                || input.configs.photometric_interp == PhotometricInterpretation::RGB
+            {
+                true
+            } else {
+                false
+            };
 
-        // first bin the data
-        let rows = input.configs.rows as isize;
-        let columns = input.configs.columns as isize;
-        let nodata = input.configs.nodata;
-        let display_min = if !is_rgb_image {
-            input.configs.display_min
-        } else {
-            0f64
-        };
-        let display_max = if !is_rgb_image {
-            input.configs.display_max
-        } else {
-            1f64
-        };
-        let multiplier = 10f64.powi(num_sig_digits);
-        let min_val = if !is_rgb_image {
-            input.configs.minimum
-        } else {
-            0f64
-        };
-        let max_val = if !is_rgb_image {
-            input.configs.maximum
-        } else {
-            1f64
-        };
-        let min_bin = (min_val * multiplier).floor() as i64;
-        let num_bins = (max_val * multiplier).floor() as i64 - min_bin + 1;
-        let bin_nodata = i64::MIN;
-        let mut binned_data: Array2D<i64> = Array2D::new(rows, columns, bin_nodata, bin_nodata)?;
-
-        let num_procs = num_cpus::get() as isize;
-        let (tx, rx) = mpsc::channel();
-        for tid in 0..num_procs {
-            let input = input.clone();
-            let tx = tx.clone();
-            thread::spawn(move || {
-                let input_fn: Box<dyn Fn(isize, isize) -> f64> = if !is_rgb_image {
-                    Box::new(|row: isize, col: isize| -> f64 { input.get_value(row, col) })
-                } else {
-                    Box::new(|row: isize, col: isize| -> f64 {
-                        let value = input.get_value(row, col);
-                        if value != nodata {
-                            return value2i(value);
-                        }
-                        nodata
-                    })
-                };
-
-                let mut z: f64;
-                let mut val: i64;
-                for row in (0..rows).filter(|r| r % num_procs == tid) {
-                    let mut data = vec![bin_nodata; columns as usize];
-                    for col in 0..columns {
-                        z = input_fn(row, col);
-                        if z != nodata {
-                            val = (z * multiplier).floor() as i64 - min_bin;
-                            data[col as usize] = val;
+            if is_rgb_image && num_sig_digits < 4 {
+                num_sig_digits = 4;
+            }
+
+            // first bin the data
+            let rows = input.configs.rows as isize;
+            let columns = input.configs.columns as isize;
+            let nodata = input.configs.nodata;
+            let display_min = if !is_rgb_image {
+                input.configs.display_min
+            } else {
+                0f64
+            };
+            let display_max = if !is_rgb_image {
+                input.configs.display_max
+            } else {
+                1f64
+            };
+            let multiplier = 10f64.powi(num_sig_digits);
+            let min_val = if !is_rgb_image {
+                input.configs.minimum
+            } else {
+                0f64
+            };
+            let max_val = if !is_rgb_image {
+                input.configs.maximum
+            } else {
+                1f64
+            };
+            let min_bin = (min_val * multiplier).floor() as i64;
+            let num_bins = (max_val * multiplier).floor() as i64 - min_bin + 1;
+            let bin_nodata = i64::MIN;
+            let mut binned_data: Array2D<i64> = Array2D::new(rows, columns, bin_nodata, bin_nodata)?;
+
+            let num_procs = num_cpus::get() as isize;
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let input = input.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    let input_fn: Box<dyn Fn(isize, isize) -> f64> = if !is_rgb_image {
+                        Box::new(|row: isize, col: isize| -> f64 { input.get_value(row, col) })
+                    } else {
+                        Box::new(|row: isize, col: isize| -> f64 {
+                            let value = input.get_value(row, col);
+                            if value != nodata {
+                                return value2i(value);
+                            }
+                            nodata
+                        })
+                    };
+
+                    let mut z: f64;
+                    let mut val: i64;
+                    for row in (0..rows).filter(|r| r % num_procs == tid) {
+                        let mut data = vec![bin_nodata; columns as usize];
+                        for col in 0..columns {
+                            z = input_fn(row, col);
+                            if z != nodata {
+                                val = (z * multiplier).floor() as i64 - min_bin;
+                                data[col as usize] = val;
+                            }
                         }
+                        tx.send((row, data)).unwrap();
                     }
-                    tx.send((row, data)).unwrap();
-                }
-            });
-        }
+                });
+            }
 
-        for row in 0..rows {
-            let data = rx.recv().unwrap();
-            binned_data.set_row_data(data.0, data.1);
-            if verbose {
-                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
-                if progress != old_progress {
-                    println!("Binning data: {}%", progress);
-                    old_progress = progress;
+            for row in 0..rows {
+                let data = rx.recv().unwrap();
+                binned_data.set_row_data(data.0, data.1);
+                if verbose {
+                    progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Binning data: {}%", progress);
+                        old_progress = progress;
+                    }
                 }
             }
-        }
 
-        let bd = Arc::new(binned_data); // wrap binned_data in an Arc
-        let mut output = Raster::initialize_using_file(&output_file, &input);
-        let (tx, rx) = mpsc::channel();
-        for tid in 0..num_procs {
-            let binned_data = bd.clone();
-            let input = input.clone();
-            let tx = tx.clone();
-            thread::spawn(move || {
-                let output_fn: Box<dyn Fn(isize, isize, f64) -> f64> = if !is_rgb_image {
-                    // simply return the value.
-                    Box::new(|_: isize, _: isize, value: f64| -> f64 { value })
-                } else {
-                    // convert it back into an rgb value, using the modified intensity value.
-                    Box::new(|row: isize, col: isize, value: f64| -> f64 {
-                        if value != nodata {
-                            let (h, s, _) = value2hsi(input.get_value(row, col));
-                            return hsi2value(h, s, value);
-                        }
-                        nodata
-                    })
-                };
-                let (mut bin_val, mut bin_val_n): (i64, i64);
-                let (mut start_col, mut end_col, mut start_row, mut end_row): (
-                    isize,
-                    isize,
-                    isize,
-                    isize,
-                );
-                let mut median: i64;
-                let mut old_median: i64;
-                let (mut n, mut n_less_than): (f64, f64);
-                for row in (0..rows).filter(|r| r % num_procs == tid) {
-                    start_row = row - midpoint_y;
-                    end_row = row + midpoint_y;
-                    let mut histo: Vec<i64> = vec![];
-                    old_median = bin_nodata;
-                    median = bin_nodata;
-                    n = 0.0;
-                    n_less_than = 0.0;
-                    let mut data = vec![nodata; columns as usize];
-                    for col in 0..columns {
-                        bin_val = binned_data.get_value(row, col);
-                        if bin_val != bin_nodata {
-                            if old_median != bin_nodata {
-                                // remove the trailing column from the histo
-                                for row2 in start_row..end_row + 1 {
-                                    bin_val_n = binned_data.get_value(row2, col - midpoint_x - 1);
-                                    if bin_val_n != bin_nodata {
-                                        histo[bin_val_n as usize] -= 1;
-                                        n -= 1.0;
-                                        if bin_val_n < old_median {
-                                            n_less_than -= 1.0;
+            let bd = Arc::new(binned_data); // wrap binned_data in an Arc
+            let mut output = Raster::initialize_using_file(&output_file, &input);
+            output.set_native_storage();
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let binned_data = bd.clone();
+                let input = input.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    let output_fn: Box<dyn Fn(isize, isize, f64) -> f64> = if !is_rgb_image {
+                        // simply return the value.
+                        Box::new(|_: isize, _: isize, value: f64| -> f64 { value })
+                    } else {
+                        // convert it back into an rgb value, using the modified intensity value.
+                        Box::new(|row: isize, col: isize, value: f64| -> f64 {
+                            if value != nodata {
+                                let (h, s, _) = value2hsi(input.get_value(row, col));
+                                return hsi2value(h, s, value);
+                            }
+                            nodata
+                        })
+                    };
+                    let (mut bin_val, mut bin_val_n): (i64, i64);
+                    let (mut start_col, mut end_col, mut start_row, mut end_row): (
+                        isize,
+                        isize,
+                        isize,
+                        isize,
+                    );
+                    let mut median: i64;
+                    let mut old_median: i64;
+                    let (mut n, mut n_less_than): (f64, f64);
+                    for row in (0..rows).filter(|r| r % num_procs == tid) {
+                        start_row = row - midpoint_y;
+                        end_row = row + midpoint_y;
+                        let mut histo: Vec<i64> = vec![];
+                        old_median = bin_nodata;
+                        median = bin_nodata;
+                        n = 0.0;
+                        n_less_than = 0.0;
+                        let mut data = vec![nodata; columns as usize];
+                        for col in 0..columns {
+                            bin_val = binned_data.get_value(row, col);
+                            if bin_val != bin_nodata {
+                                if old_median != bin_nodata {
+                                    // remove the trailing column from the histo
+                                    for row2 in start_row..end_row + 1 {
+                                        bin_val_n = binned_data.get_value(row2, col - midpoint_x - 1);
+                                        if bin_val_n != bin_nodata {
+                                            histo[bin_val_n as usize] -= 1;
+                                            n -= 1.0;
+                                            if bin_val_n < old_median {
+                                                n_less_than -= 1.0;
+                                            }
                                         }
                                     }
-                                }
 
-                                // add the leading column to the histo
-                                for row2 in start_row..end_row + 1 {
-                                    bin_val_n = binned_data.get_value(row2, col + midpoint_x);
-                                    if bin_val_n != bin_nodata {
-                                        histo[bin_val_n as usize] += 1;
-                                        n += 1.0;
-                                        if bin_val_n < old_median {
-                                            n_less_than += 1.0;
+                                    // add the leading column to the histo
+                                    for row2 in start_row..end_row + 1 {
+                                        bin_val_n = binned_data.get_value(row2, col + midpoint_x);
+                                        if bin_val_n != bin_nodata {
+                                            histo[bin_val_n as usize] += 1;
+                                            n += 1.0;
+                                            if bin_val_n < old_median {
+                                                n_less_than += 1.0;
+                                            }
                                         }
                                     }
-                                }
 
-                                // adjust the median
-                                let target = (n / 2f64).floor();
-                                if n_less_than < target {
-                                    // add bins
-                                    for v in old_median..num_bins {
-                                        if n_less_than + (histo[v as usize] as f64) >= target {
-                                            median = v as i64;
-                                            break;
-                                        } else {
-                                            n_less_than += histo[v as usize] as f64;
+                                    // adjust the median
+                                    let target = (n / 2f64).floor();
+                                    if n_less_than < target {
+                                        // add bins
+                                        for v in old_median..num_bins {
+                                            if n_less_than + (histo[v as usize] as f64) >= target {
+                                                median = v as i64;
+                                                break;
+                                            } else {
+                                                n_less_than += histo[v as usize] as f64;
+                                            }
                                         }
-                                    }
+                                    } else {
+                                        //if n_less_than >= target { // remove bins
+                                        for v in (0..old_median).rev() {
+                                            if n_less_than - (histo[v as usize] as f64) >= target {
+                                                n_less_than -= histo[v as usize] as f64;
+                                            } else {
+                                                median = v + 1;
+                                                break;
+                                            }
+                                        }
+                                    } // otherwise they are in the same bin and there is no need to update
                                 } else {
-                                    //if n_less_than >= target { // remove bins
-                                    for v in (0..old_median).rev() {
-                                        if n_less_than - (histo[v as usize] as f64) >= target {
-                                            n_less_than -= histo[v as usize] as f64;
-                                        } else {
-                                            median = v + 1;
-                                            break;
+                                    // This is the first cell in a row or after a nodata cell; initialize the histogram.
+                                    histo = vec![0i64; num_bins as usize];
+                                    n = 0.0;
+                                    n_less_than = 0.0;
+                                    start_col = col - midpoint_x;
+                                    end_col = col + midpoint_x;
+                                    for col2 in start_col..end_col + 1 {
+                                        for row2 in start_row..end_row + 1 {
+                                            bin_val_n = binned_data.get_value(row2, col2);
+                                            if bin_val_n != bin_nodata {
+                                                histo[bin_val_n as usize] += 1;
+                                                n += 1f64;
+                                            }
                                         }
                                     }
-                                } // otherwise they are in the same bin and there is no need to update
-                            } else {
-                                // This is the first cell in a row or after a nodata cell; initialize the histogram.
-                                histo = vec![0i64; num_bins as usize];
-                                n = 0.0;
-                                n_less_than = 0.0;
-                                start_col = col - midpoint_x;
-                                end_col = col + midpoint_x;
-                                for col2 in start_col..end_col + 1 {
-                                    for row2 in start_row..end_row + 1 {
-                                        bin_val_n = binned_data.get_value(row2, col2);
-                                        if bin_val_n != bin_nodata {
-                                            histo[bin_val_n as usize] += 1;
-                                            n += 1f64;
+                                    // calcualate the median from the histogram
+                                    let mut sum = 0f64;
+                                    let target = (n / 2f64).floor();
+                                    for i in 0..num_bins as usize {
+                                        sum += histo[i] as f64;
+                                        if sum >= target {
+                                            median = i as i64;
+                                            break;
+                                        } else {
+                                            n_less_than = sum;
                                         }
                                     }
                                 }
-                                // calcualate the median from the histogram
-                                let mut sum = 0f64;
-                                let target = (n / 2f64).floor();
-                                for i in 0..num_bins as usize {
-                                    sum += histo[i] as f64;
-                                    if sum >= target {
-                                        median = i as i64;
-                                        break;
-                                    } else {
-                                        n_less_than = sum;
-                                    }
+
+                                if n > 0f64 {
+                                    data[col as usize] =
+                                        output_fn(row, col, (median + min_bin) as f64 / multiplier);
+                                } else {
+                                    data[col as usize] = nodata;
                                 }
-                            }
 
-                            if n > 0f64 {
-                                data[col as usize] =
-                                    output_fn(row, col, (median + min_bin) as f64 / multiplier);
+                                old_median = median;
                             } else {
-                                data[col as usize] = nodata;
+                                old_median = bin_nodata;
                             }
-
-                            old_median = median;
-                        } else {
-                            old_median = bin_nodata;
                         }
+                        tx.send((row, data)).unwrap();
                     }
-                    tx.send((row, data)).unwrap();
-                }
-            });
-        }
+                });
+            }
 
-        for row in 0..rows {
-            let data = rx.recv().unwrap();
-            output.set_row_data(data.0, data.1);
-            if verbose {
-                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
-                if progress != old_progress {
-                    println!("Performing analysis: {}%", progress);
-                    old_progress = progress;
+            for row in 0..rows {
+                let data = rx.recv().unwrap();
+                output.set_row_data(data.0, data.1);
+                if verbose {
+                    progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Performing analysis: {}%", progress);
+                        old_progress = progress;
+                    }
                 }
             }
+
+            output.configs.display_min = display_min;
+            output.configs.display_max = display_max;
+            outputs.push(output);
         }
+        let mut output = Raster::from_bands(outputs)?;
 
         let elapsed_time = get_formatted_elapsed_time(start);
-        output.configs.display_min = display_min;
-        output.configs.display_max = display_max;
         output.add_metadata_entry(format!(
             "Created by whitebox_tools\' {} tool",
             self.get_tool_name()
@@ -3,6 +3,7 @@ mod adaptive_filter;
 mod balance_contrast_enhancement;
 mod bilateral_filter;
 mod change_vector_analysis;
+mod class_probability_smoothing;
 mod closing;
 mod conservative_smoothing_filter;
 mod corner_detection;
@@ -18,16 +19,19 @@ mod flip_image;
 mod gamma_correction;
 mod gaussian_contrast_stretch;
 mod gaussian_filter;
+mod generalize_classified_raster;
 mod highpass_filter;
 mod highpass_median_filter;
 mod histogram_equalization;
 mod histogram_matching;
 mod histogram_matching_two_images;
+mod hsv_to_rgb;
 mod ihs_to_rgb;
 mod image_stack_profile;
 mod integral_image;
 mod k_means_clustering;
 mod k_nearest_mean_filter;
+mod lab_to_rgb;
 mod laplacian_filter;
 mod lee_filter;
 mod line_detection_filter;
@@ -49,10 +53,14 @@ mod pan_sharpening;
 mod percentage_contrast_stretch;
 mod percentile_filter;
 mod prewitt_filter;
+mod random_forest_regression;
 mod range_filter;
+mod region_growing;
 mod remove_spurs;
 mod resample;
+mod rgb_to_hsv;
 mod rgb_to_ihs;
+mod rgb_to_lab;
 mod roberts_filter;
 mod scharr_filter;
 mod sigmoidal_contrast_stretch;
@@ -72,6 +80,7 @@ pub use self::adaptive_filter::AdaptiveFilter;
 pub use self::balance_contrast_enhancement::BalanceContrastEnhancement;
 pub use self::bilateral_filter::BilateralFilter;
 pub use self::change_vector_analysis::ChangeVectorAnalysis;
+pub use self::class_probability_smoothing::ClassProbabilitySmoothing;
 pub use self::closing::Closing;
 pub use self::conservative_smoothing_filter::ConservativeSmoothingFilter;
 pub use self::corner_detection::CornerDetection;
@@ -87,16 +96,19 @@ pub use self::flip_image::FlipImage;
 pub use self::gamma_correction::GammaCorrection;
 pub use self::gaussian_contrast_stretch::GaussianContrastStretch;
 pub use self::gaussian_filter::GaussianFilter;
+pub use self::generalize_classified_raster::GeneralizeClassifiedRaster;
 pub use self::highpass_filter::HighPassFilter;
 pub use self::highpass_median_filter::HighPassMedianFilter;
 pub use self::histogram_equalization::HistogramEqualization;
 pub use self::histogram_matching::HistogramMatching;
 pub use self::histogram_matching_two_images::HistogramMatchingTwoImages;
+pub use self::hsv_to_rgb::HsvToRgb;
 pub use self::ihs_to_rgb::IhsToRgb;
 pub use self::image_stack_profile::ImageStackProfile;
 pub use self::integral_image::IntegralImage;
 pub use self::k_means_clustering::KMeansClustering;
 pub use self::k_nearest_mean_filter::KNearestMeanFilter;
+pub use self::lab_to_rgb::LabToRgb;
 pub use self::laplacian_filter::LaplacianFilter;
 pub use self::lee_filter::LeeSigmaFilter;
 pub use self::line_detection_filter::LineDetectionFilter;
@@ -118,10 +130,14 @@ pub use self::pan_sharpening::PanchromaticSharpening;
 pub use self::percentage_contrast_stretch::PercentageContrastStretch;
 pub use self::percentile_filter::PercentileFilter;
 pub use self::prewitt_filter::PrewittFilter;
+pub use self::random_forest_regression::RandomForestRegression;
 pub use self::range_filter::RangeFilter;
+pub use self::region_growing::RegionGrowing;
 pub use self::remove_spurs::RemoveSpurs;
 pub use self::resample::Resample;
+pub use self::rgb_to_hsv::RgbToHsv;
 pub use self::rgb_to_ihs::RgbToIhs;
+pub use self::rgb_to_lab::RgbToLab;
 pub use self::roberts_filter::RobertsCrossFilter;
 pub use self::scharr_filter::ScharrFilter;
 pub use self::sigmoidal_contrast_stretch::SigmoidalContrastStretch;
@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 30/12/2017
-Last Modified: 24/02/2019
+Last Modified: 17/10/2026
 License: MIT
 */
 
@@ -30,7 +30,8 @@ use std::thread;
 /// tool. Instead, the algorithm initializes with a very liberal overestimate of the number
 /// of classes and then merges classes that have cluster centres that are separated by less
 /// than a user-defined threshold. The main difference between this algorithm and the ISODATA
-/// technique is that clusters can not be broken apart into two smaller clusters.
+/// technique is that clusters can not be broken apart into two smaller clusters. Each band of a
+/// multi-band input image is treated as a separate input image.
 /// 
 /// # Reference
 /// Mather, P. M., & Koch, M. (2011). Computer processing of remotely-sensed images: an 
@@ -289,18 +290,12 @@ impl WhiteboxTool for ModifiedKMeansClustering {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
-        if !output_html_file.contains(&sep) {
-            output_html_file = format!("{}{}", working_directory, output_html_file);
-        }
+        output_html_file = resolve_path(&output_html_file, working_directory);
 
         if !output_html_file.ends_with(".html") {
             output_html_file.push_str(".html");
@@ -312,12 +307,6 @@ impl WhiteboxTool for ModifiedKMeansClustering {
             cmd = input_files_str.split(",");
             input_files = cmd.collect::<Vec<&str>>();
         }
-        let num_files = input_files.len();
-        if num_files < 2 {
-            return Err(Error::new(ErrorKind::InvalidInput,
-                                "There is something incorrect about the input files. At least two inputs are required to operate this tool."));
-        }
-
         if max_iterations < 2 || max_iterations > 250 {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
@@ -344,26 +333,31 @@ impl WhiteboxTool for ModifiedKMeansClustering {
         let mut rows = -1isize;
         let mut columns = -1isize;
 
-        let mut nodata: Vec<f64> = Vec::with_capacity(num_files);
-        let mut minimum: Vec<f64> = Vec::with_capacity(num_files);
-        let mut maximum: Vec<f64> = Vec::with_capacity(num_files);
-        let mut input_raster: Vec<Raster> = Vec::with_capacity(num_files);
+        let mut nodata: Vec<f64> = Vec::with_capacity(input_files.len());
+        let mut minimum: Vec<f64> = Vec::with_capacity(input_files.len());
+        let mut maximum: Vec<f64> = Vec::with_capacity(input_files.len());
+        let mut input_raster: Vec<Raster> = Vec::with_capacity(input_files.len());
+        let mut image_names = vec![];
 
-        for i in 0..num_files {
-            println!("Reading file {} of {}", i + 1, num_files);
+        for i in 0..input_files.len() {
+            println!("Reading file {} of {}", i + 1, input_files.len());
             if !input_files[i].trim().is_empty() {
                 let mut input_file = input_files[i].trim().to_owned();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
+                input_file = resolve_path(&input_file, working_directory);
+                // each band of a multi-band image is treated as a separate image
+                let input = Raster::new(&input_file, "r")?;
+                let bands = input.into_bands();
+                if bands.len() > 1 {
+                    for band in 0..bands.len() {
+                        image_names.push(format!("{} (band {})", input_files[i].trim(), band + 1));
+                    }
+                } else {
+                    image_names.push(input_files[i].trim().to_string());
                 }
-                input_raster.push(Raster::new(&input_file, "r")?);
-                nodata.push(input_raster[i].configs.nodata);
-                minimum.push(input_raster[i].configs.minimum);
-                maximum.push(input_raster[i].configs.maximum);
 
                 if rows == -1 || columns == -1 {
-                    rows = input_raster[i].configs.rows as isize;
-                    columns = input_raster[i].configs.columns as isize;
+                    rows = bands[0].configs.rows as isize;
+                    columns = bands[0].configs.columns as isize;
                     if num_classes < 2 || num_classes as isize > (rows * columns) {
                         return Err(Error::new(
                             ErrorKind::InvalidInput,
@@ -375,13 +369,20 @@ impl WhiteboxTool for ModifiedKMeansClustering {
                 //         "Min class size should be less than rows x columns / num_classes."));
                 // }
                 } else {
-                    if input_raster[i].configs.rows as isize != rows
-                        || input_raster[i].configs.columns as isize != columns
+                    if bands[0].configs.rows as isize != rows
+                        || bands[0].configs.columns as isize != columns
                     {
                         return Err(Error::new(ErrorKind::InvalidInput,
                             "All input images must share the same dimensions (rows and columns) and spatial extent."));
                     }
                 }
+
+                for band in bands {
+                    nodata.push(band.configs.nodata);
+                    minimum.push(band.configs.minimum);
+                    maximum.push(band.configs.maximum);
+                    input_raster.push(band);
+                }
             }
         }
 
@@ -392,6 +393,12 @@ impl WhiteboxTool for ModifiedKMeansClustering {
             ));
         }
 
+        let num_files = input_raster.len();
+        if num_files < 2 {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                                "There is something incorrect about the input files. At least two inputs, or bands of a multi-band input, are required to operate this tool."));
+        }
+
         let out_nodata = nodata[0];
         let mut output = Raster::initialize_using_file(&output_file, &input_raster[0]);
         let mut class_centres = vec![vec![0f64; num_files]; num_classes];
@@ -658,7 +665,7 @@ impl WhiteboxTool for ModifiedKMeansClustering {
                     &format!(
                         "<strong>Image {}</strong>: {}<br>",
                         i + 1,
-                        input_files[i].clone()
+                        image_names[i]
                     )
                     .as_bytes(),
                 )?;
@@ -265,29 +265,15 @@ impl WhiteboxTool for PanchromaticSharpening {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !red_file.contains(&sep) && !red_file.contains("/") {
-            red_file = format!("{}{}", working_directory, red_file);
-        }
-        if !green_file.contains(&sep) && !green_file.contains("/") {
-            green_file = format!("{}{}", working_directory, green_file);
-        }
-        if !blue_file.contains(&sep) && !blue_file.contains("/") {
-            blue_file = format!("{}{}", working_directory, blue_file);
-        }
-        if !composite_file.contains(&sep) && !composite_file.contains("/") {
-            composite_file = format!("{}{}", working_directory, composite_file);
-        }
-        if !pan_file.contains(&sep) && !pan_file.contains("/") {
-            pan_file = format!("{}{}", working_directory, pan_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        red_file = resolve_path(&red_file, working_directory);
+        green_file = resolve_path(&green_file, working_directory);
+        blue_file = resolve_path(&blue_file, working_directory);
+        composite_file = resolve_path(&composite_file, working_directory);
+        pan_file = resolve_path(&pan_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         let num_procs = num_cpus::get() as isize;
 
@@ -0,0 +1,776 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use crate::vector::{FieldData, ShapeType, Shapefile};
+use num_cpus;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool predicts a continuous target variable, e.g. above-ground biomass or a soil property,
+/// from a stack of predictor rasters (`--inputs`) using random forest regression (Breiman, 2001).
+/// Each band of a multi-band input image is treated as a separate predictor. The model is trained
+/// using a set of vector points (`--training`), with the target values contained in a numeric field
+/// of the attribute table (`--field`). The predictor values of each training point are those of the
+/// grid cell that contains it; points that fall outside the rasters, or in cells that are NoData in
+/// any of the predictors, are excluded.
+///
+/// A random forest is an ensemble of regression trees (`--n_trees`), each of which is grown from a
+/// bootstrap sample of the training points. At each node of a tree, the best split is found among a
+/// random subset of the predictors (`--mtry`; by default one third of the predictors), and nodes are
+/// split until they contain fewer than twice the minimum number of training points in a leaf
+/// (`--min_samples_leaf`). The predicted value of a grid cell (`--output`) is the mean of the
+/// predictions of the trees. The standard deviation of the tree predictions, which is greater where
+/// the trees disagree, e.g. where the predictor values are poorly represented by the training data,
+/// may be output as a measure of the prediction uncertainty (`--uncertainty`).
+///
+/// The accuracy of the model is estimated from the out-of-bag (OOB) predictions, i.e. the prediction
+/// of each training point by the trees whose bootstrap samples exclude it, which provides an unbiased
+/// estimate of the error without a separate validation sample. The OOB root-mean-square error (RMSE),
+/// mean absolute error (MAE), and coefficient of determination (R-squared) are reported, along with
+/// the relative importance of each predictor, i.e. the proportion of the total reduction in the sum
+/// of squared errors achieved by splits on the predictor. A random seed (`--seed`) may be specified
+/// for a reproducible model.
+///
+/// All of the input rasters must have the same number of rows and columns and the same spatial extent.
+/// Grid cells that are NoData in any of the predictors are NoData in the outputs.
+///
+/// # Reference
+/// Breiman, L. (2001). Random forests. *Machine Learning*, 45(1), 5-32.
+///
+/// # See Also
+/// `KMeansClustering`, `PrincipalComponentAnalysis`
+pub struct RandomForestRegression {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl RandomForestRegression {
+    pub fn new() -> RandomForestRegression {
+        // public constructor
+        let name = "RandomForestRegression".to_string();
+        let toolbox = "Image Processing Tools".to_string();
+        let description =
+            "Predicts a continuous variable from a raster stack using random forest regression."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Predictor Files".to_owned(),
+            flags: vec!["-i".to_owned(), "--inputs".to_owned()],
+            description: "Input predictor raster files.".to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Training Points".to_owned(),
+            flags: vec!["--training".to_owned()],
+            description: "Input vector points file containing the training data.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Target Field Name".to_owned(),
+            flags: vec!["--field".to_owned()],
+            description:
+                "Name of the attribute containing the target values of the training points."
+                    .to_owned(),
+            parameter_type: ParameterType::VectorAttributeField(
+                AttributeType::Number,
+                "--training".to_string(),
+            ),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output predicted raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Uncertainty File (optional)".to_owned(),
+            flags: vec!["--uncertainty".to_owned()],
+            description: "Optional output raster of the prediction uncertainty, i.e. the standard deviation of the tree predictions.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Trees".to_owned(),
+            flags: vec!["--n_trees".to_owned()],
+            description: "Number of trees in the forest.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("100".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Samples per Leaf".to_owned(),
+            flags: vec!["--min_samples_leaf".to_owned()],
+            description: "Minimum number of training points in a leaf of a tree.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Predictors per Split (optional)".to_owned(),
+            flags: vec!["--mtry".to_owned()],
+            description: "Number of randomly selected predictors considered at each split; by default, one third of the predictors.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Random Seed (optional)".to_owned(),
+            flags: vec!["--seed".to_owned()],
+            description: "Optional seed of the random number generator, for a reproducible model."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='band1.tif;band2.tif;dem.tif' --training=plots.shp --field=BIOMASS -o=biomass.tif --uncertainty=biomass_sd.tif --n_trees=200 --seed=42", short_exe, name).replace("*", &sep);
+
+        RandomForestRegression {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for RandomForestRegression {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--n_trees".to_string(), ParameterConstraint::Min(1.0)),
+            (
+                "--min_samples_leaf".to_string(),
+                ParameterConstraint::Min(1.0),
+            ),
+            ("--mtry".to_string(), ParameterConstraint::Min(1.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_files_str = String::new();
+        let mut training_file = String::new();
+        let mut field_name = String::new();
+        let mut output_file = String::new();
+        let mut uncertainty_file = String::new();
+        let mut num_trees = 100usize;
+        let mut min_samples_leaf = 5usize;
+        let mut mtry: Option<usize> = None;
+        let mut seed: Option<u64> = None;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-inputs" {
+                input_files_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-training" {
+                training_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-field" {
+                field_name = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-uncertainty" {
+                uncertainty_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-n_trees" {
+                num_trees = if keyval {
+                    vec[1].to_string().parse::<usize>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<usize>().unwrap()
+                };
+            } else if flag_val == "-min_samples_leaf" {
+                min_samples_leaf = if keyval {
+                    vec[1].to_string().parse::<usize>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<usize>().unwrap()
+                };
+            } else if flag_val == "-mtry" {
+                mtry = Some(if keyval {
+                    vec[1].to_string().parse::<usize>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<usize>().unwrap()
+                });
+            } else if flag_val == "-seed" {
+                seed = Some(if keyval {
+                    vec[1].to_string().parse::<u64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<u64>().unwrap()
+                });
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if num_trees < 1 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The number of trees must be at least 1.",
+            ));
+        }
+        let min_samples_leaf = min_samples_leaf.max(1);
+
+        training_file = resolve_path(&training_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        uncertainty_file = resolve_path(&uncertainty_file, working_directory);
+
+        let mut cmd = input_files_str.split(";");
+        let mut input_files = cmd.collect::<Vec<&str>>();
+        if input_files.len() == 1 {
+            cmd = input_files_str.split(",");
+            input_files = cmd.collect::<Vec<&str>>();
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        // each band of a multi-band image is treated as a separate predictor
+        let mut predictors: Vec<Raster> = vec![];
+        let mut predictor_names: Vec<String> = vec![];
+        for file in input_files.iter().filter(|f| !f.trim().is_empty()) {
+            let mut input_file = file.trim().to_owned();
+            input_file = resolve_path(&input_file, working_directory);
+            let bands = Raster::new(&input_file, "r")?.into_bands();
+            let num_bands = bands.len();
+            for (band, raster) in bands.into_iter().enumerate() {
+                if !predictors.is_empty()
+                    && (raster.configs.rows != predictors[0].configs.rows
+                        || raster.configs.columns != predictors[0].configs.columns)
+                {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "All input images must share the same dimensions (rows and columns) and spatial extent.",
+                    ));
+                }
+                predictor_names.push(if num_bands > 1 {
+                    format!("{} (band {})", file.trim(), band + 1)
+                } else {
+                    file.trim().to_owned()
+                });
+                predictors.push(raster);
+            }
+        }
+        if predictors.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one predictor raster is required.",
+            ));
+        }
+        let num_predictors = predictors.len();
+        let mtry = mtry
+            .unwrap_or((num_predictors / 3).max(1))
+            .max(1)
+            .min(num_predictors);
+
+        let training = Shapefile::read(&training_file)?;
+        if training.header.shape_type.base_shape_type() != ShapeType::Point {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The training data must be of point base shape type.",
+            ));
+        }
+        let field_index = match training.attributes.get_field_num(&field_name) {
+            Some(i) => i,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Attribute not found in table.",
+                ));
+            }
+        };
+        if !training.attributes.is_field_numeric(field_index) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The target field must be numeric.",
+            ));
+        }
+
+        let start = Instant::now();
+
+        // Extract the predictor values of the training points
+        let mut x: Vec<Vec<f64>> = vec![];
+        let mut y: Vec<f64> = vec![];
+        for record_num in 0..training.num_records {
+            let target = match training.attributes.get_value(record_num, &field_name) {
+                FieldData::Int(val) => val as f64,
+                FieldData::Real(val) => val,
+                _ => continue, // likely a null value
+            };
+            let record = training.get_record(record_num);
+            for point in record.points.iter() {
+                let row = predictors[0].get_row_from_y(point.y);
+                let col = predictors[0].get_column_from_x(point.x);
+                let values: Vec<f64> = predictors.iter().map(|p| p.get_value(row, col)).collect();
+                if values
+                    .iter()
+                    .zip(predictors.iter())
+                    .all(|(v, p)| *v != p.configs.nodata)
+                {
+                    x.push(values);
+                    y.push(target);
+                }
+            }
+        }
+        let num_samples = y.len();
+        if num_samples < 2 * min_samples_leaf {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Too few training points ({}) overlap valid predictor cells.",
+                    num_samples
+                ),
+            ));
+        }
+        if verbose {
+            println!(
+                "Number of training points: {}; number of predictors: {}",
+                num_samples, num_predictors
+            );
+        }
+
+        // Grow the trees
+        let base_seed = match seed {
+            Some(s) => s,
+            None => StdRng::from_entropy().gen(),
+        };
+        let x = Arc::new(x);
+        let y = Arc::new(y);
+        let num_procs = num_cpus::get().min(num_trees);
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let x = x.clone();
+            let y = y.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for t in (0..num_trees).filter(|t| t % num_procs == tid) {
+                    // each tree has its own generator, such that the forest is independent of the
+                    // number of threads
+                    let mut rng = StdRng::seed_from_u64(base_seed.wrapping_add(t as u64));
+                    let samples: Vec<usize> =
+                        (0..y.len()).map(|_| rng.gen_range(0, y.len())).collect();
+                    let mut in_bag = vec![false; y.len()];
+                    for &s in samples.iter() {
+                        in_bag[s] = true;
+                    }
+                    let tree =
+                        RegressionTree::grow(&x, &y, samples, mtry, min_samples_leaf, &mut rng);
+                    tx.send((t, tree, in_bag)).unwrap();
+                }
+            });
+        }
+        drop(tx);
+
+        let mut trees: Vec<Option<RegressionTree>> = (0..num_trees).map(|_| None).collect();
+        let mut oob_sum = vec![0f64; num_samples];
+        let mut oob_count = vec![0usize; num_samples];
+        let mut importance = vec![0f64; num_predictors];
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for i in 0..num_trees {
+            let (t, tree, in_bag) = rx.recv().unwrap();
+            for s in (0..num_samples).filter(|&s| !in_bag[s]) {
+                oob_sum[s] += tree.predict(&x[s]);
+                oob_count[s] += 1;
+            }
+            for (f, v) in tree.importance.iter().enumerate() {
+                importance[f] += v;
+            }
+            trees[t] = Some(tree);
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / num_trees as f64) as usize;
+                if progress != old_progress {
+                    println!("Growing trees: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        let trees: Arc<Vec<RegressionTree>> =
+            Arc::new(trees.into_iter().map(|t| t.unwrap()).collect());
+
+        // Out-of-bag accuracy assessment
+        let mut num_oob = 0usize;
+        let (mut sse, mut sae, mut sum_y, mut sum_y2) = (0f64, 0f64, 0f64, 0f64);
+        for s in (0..num_samples).filter(|&s| oob_count[s] > 0) {
+            let residual = y[s] - oob_sum[s] / oob_count[s] as f64;
+            sse += residual * residual;
+            sae += residual.abs();
+            sum_y += y[s];
+            sum_y2 += y[s] * y[s];
+            num_oob += 1;
+        }
+        let total_importance: f64 = importance.iter().sum();
+        println!(
+            "Out-of-bag accuracy ({} of {} training points):",
+            num_oob, num_samples
+        );
+        if num_oob > 0 {
+            let n = num_oob as f64;
+            let sst = sum_y2 - sum_y * sum_y / n;
+            println!("RMSE: {:.6}", (sse / n).sqrt());
+            println!("MAE: {:.6}", sae / n);
+            println!(
+                "R-squared: {:.4}",
+                if sst > 0f64 {
+                    1f64 - sse / sst
+                } else {
+                    f64::NAN
+                }
+            );
+        }
+        println!("Predictor importance:");
+        for (f, name) in predictor_names.iter().enumerate() {
+            println!(
+                "{}: {:.2}%",
+                name,
+                if total_importance > 0f64 {
+                    100f64 * importance[f] / total_importance
+                } else {
+                    0f64
+                }
+            );
+        }
+
+        // Predict the target at each grid cell
+        let rows = predictors[0].configs.rows as isize;
+        let columns = predictors[0].configs.columns as isize;
+        let out_nodata = -32768f64;
+        let mut output = Raster::initialize_using_file(&output_file, &predictors[0]);
+        output.configs.data_type = DataType::F32;
+        output.configs.nodata = out_nodata;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        output.configs.palette = "spectrum.plt".to_string();
+        let mut uncertainty = if !uncertainty_file.is_empty() {
+            let mut r = Raster::initialize_using_file(&uncertainty_file, &output);
+            r.configs.palette = "grey.plt".to_string();
+            Some(r)
+        } else {
+            None
+        };
+
+        let predictors = Arc::new(predictors);
+        let num_procs = num_cpus::get() as isize;
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let predictors = predictors.clone();
+            let trees = trees.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let mut values = vec![0f64; predictors.len()];
+                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                    let mut mean_data = vec![out_nodata; columns as usize];
+                    let mut sd_data = vec![out_nodata; columns as usize];
+                    'cells: for col in 0..columns {
+                        for (f, p) in predictors.iter().enumerate() {
+                            values[f] = p.get_value(row, col);
+                            if values[f] == p.configs.nodata {
+                                continue 'cells;
+                            }
+                        }
+                        let (mut sum, mut sum2) = (0f64, 0f64);
+                        for tree in trees.iter() {
+                            let z = tree.predict(&values);
+                            sum += z;
+                            sum2 += z * z;
+                        }
+                        let n = trees.len() as f64;
+                        let mean = sum / n;
+                        mean_data[col as usize] = mean;
+                        sd_data[col as usize] = (sum2 / n - mean * mean).max(0f64).sqrt();
+                    }
+                    tx.send((row, mean_data, sd_data)).unwrap();
+                }
+            });
+        }
+
+        for r in 0..rows {
+            let (row, mean_data, sd_data) = rx.recv().unwrap();
+            output.set_row_data(row, mean_data);
+            if let Some(u) = uncertainty.as_mut() {
+                u.set_row_data(row, sd_data);
+            }
+            if verbose {
+                progress = (100.0_f64 * r as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Predicting: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Training points: {}", training_file));
+        output.add_metadata_entry(format!("Target field: {}", field_name));
+        output.add_metadata_entry(format!("Number of trees: {}", num_trees));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(mut u) = uncertainty {
+            u.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            u.add_metadata_entry("Standard deviation of the tree predictions".to_string());
+            let _ = match u.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Uncertainty file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+enum Node {
+    Leaf(f64),
+    Split {
+        feature: usize,
+        threshold: f64,
+        left: usize,
+        right: usize,
+    },
+}
+
+/// A regression tree, grown using the CART algorithm with random predictor subsets at each split.
+struct RegressionTree {
+    nodes: Vec<Node>,
+    /// The reduction in the sum of squared errors achieved by the splits on each predictor.
+    importance: Vec<f64>,
+}
+
+impl RegressionTree {
+    /// Grows a tree from a (bootstrap) sample of the training data, indexed by `samples`.
+    fn grow(
+        x: &[Vec<f64>],
+        y: &[f64],
+        mut samples: Vec<usize>,
+        mtry: usize,
+        min_samples_leaf: usize,
+        rng: &mut StdRng,
+    ) -> RegressionTree {
+        let num_predictors = x[0].len();
+        let mut nodes = vec![Node::Leaf(0f64)];
+        let mut importance = vec![0f64; num_predictors];
+        // the nodes are split depth-first, each covering a range of the samples
+        let mut stack = vec![(0usize, 0usize, samples.len())];
+        let mut pairs: Vec<(f64, f64)> = Vec::with_capacity(samples.len());
+        while let Some((node, start, end)) = stack.pop() {
+            let n = end - start;
+            let sum: f64 = samples[start..end].iter().map(|&s| y[s]).sum();
+            let mean = sum / n as f64;
+            nodes[node] = Node::Leaf(mean);
+            if n < 2 * min_samples_leaf {
+                continue;
+            }
+
+            // find the split of the candidate predictors that most reduces the sum of squares
+            let mut best: Option<(usize, f64, f64)> = None; // feature, threshold, gain
+            for feature in rand::seq::index::sample(rng, num_predictors, mtry).into_iter() {
+                pairs.clear();
+                pairs.extend(samples[start..end].iter().map(|&s| (x[s][feature], y[s])));
+                pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                let mut left_sum = 0f64;
+                for k in 1..n {
+                    left_sum += pairs[k - 1].1;
+                    if k < min_samples_leaf
+                        || n - k < min_samples_leaf
+                        || pairs[k - 1].0 >= pairs[k].0
+                    {
+                        continue;
+                    }
+                    let right_sum = sum - left_sum;
+                    let gain = left_sum * left_sum / k as f64
+                        + right_sum * right_sum / (n - k) as f64
+                        - sum * sum / n as f64;
+                    if gain > best.map_or(1e-12, |b| b.2) {
+                        best = Some((feature, (pairs[k - 1].0 + pairs[k].0) / 2f64, gain));
+                    }
+                }
+            }
+
+            if let Some((feature, threshold, gain)) = best {
+                importance[feature] += gain;
+                // partition the samples about the threshold
+                let mut mid = start;
+                for i in start..end {
+                    if x[samples[i]][feature] <= threshold {
+                        samples.swap(i, mid);
+                        mid += 1;
+                    }
+                }
+                let left = nodes.len();
+                nodes.push(Node::Leaf(0f64));
+                nodes.push(Node::Leaf(0f64));
+                nodes[node] = Node::Split {
+                    feature,
+                    threshold,
+                    left,
+                    right: left + 1,
+                };
+                stack.push((left, start, mid));
+                stack.push((left + 1, mid, end));
+            }
+        }
+        RegressionTree { nodes, importance }
+    }
+
+    fn predict(&self, values: &[f64]) -> f64 {
+        let mut node = 0;
+        loop {
+            match self.nodes[node] {
+                Node::Leaf(value) => return value,
+                Node::Split {
+                    feature,
+                    threshold,
+                    left,
+                    right,
+                } => {
+                    node = if values[feature] <= threshold {
+                        left
+                    } else {
+                        right
+                    };
+                }
+            }
+        }
+    }
+}
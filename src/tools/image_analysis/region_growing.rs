@@ -0,0 +1,608 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::structures::Array2D;
+use crate::tools::*;
+use crate::vector::{ShapeType, Shapefile};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool segments a raster (`--input`) into regions that are grown outwards from a set of
+/// seed cells, e.g. to delineate landforms, smoke or sediment plumes, or burn scars from a few
+/// points identified by the user. The seeds may be specified using a vector points file
+/// (`--seeds`), in which case each point or multipoint feature seeds one region, labelled by its
+/// feature number (starting from 1). If no seeds file is specified, the local maxima of the input,
+/// i.e. cells with values greater than or equal to all other cells within a square window of size
+/// `--filter` (default 11 cells), are used as seeds instead, or the local minima if the `--minima`
+/// flag is specified; these regions are labelled in the order of the seed cells, from the
+/// top-left of the raster.
+///
+/// Regions are grown using seeded region growing (Adams and Bischof, 1994). All of the regions
+/// grow simultaneously and, at each step, the unlabelled cell bordering a region that is most
+/// similar to the region is added to it. Thus, cells lying between two regions are assigned to
+/// the region that they most closely resemble. A cell may only be added to a region if the absolute
+/// difference between its value and that of the region is no greater than the tolerance
+/// (`--tolerance`). The value of a region is that of its seed cell, or the running mean of all of
+/// the cells within the region if the `--compare` parameter is set to `mean`, which allows regions
+/// to follow gradual trends in the data. Regions are also limited to cells within the maximum
+/// distance (`--max_dist`, in map units) of their seed cell, if specified. Cells are connected to
+/// their four orthogonal neighbours, unless the `--diag` flag is specified, in which case diagonal
+/// connections are also considered.
+///
+/// The output raster contains the label of the region to which each cell belongs, with cells that
+/// are not contained within any region assigned zero and NoData cells in the input assigned
+/// NoData. Seeds located on NoData cells or outside of the raster, and seeds that lie within an
+/// earlier seed's cell, are ignored.
+///
+/// # Reference
+/// Adams, R., & Bischof, L. (1994). Seeded region growing. *IEEE Transactions on Pattern Analysis
+/// and Machine Intelligence*, 16(6), 641-647.
+///
+/// # See Also
+/// `Clump`, `KMeansClustering`, `GeneralizeClassifiedRaster`
+pub struct RegionGrowing {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl RegionGrowing {
+    pub fn new() -> RegionGrowing {
+        // public constructor
+        let name = "RegionGrowing".to_string();
+        let toolbox = "Image Processing Tools".to_string();
+        let description = "Segments a raster by growing regions from seed points or local extrema, subject to value-similarity and distance constraints.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Seed Points File (optional)".to_owned(),
+            flags: vec!["--seeds".to_owned()],
+            description: "Optional input vector points file containing the seeds; local extrema are used by default.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Similarity Tolerance".to_owned(),
+            flags: vec!["--tolerance".to_owned()],
+            description: "Maximum absolute difference between the value of a cell and that of the region.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Region Value".to_owned(),
+            flags: vec!["--compare".to_owned()],
+            description: "Value of a region against which cells are compared; options include 'seed' and 'mean'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "seed".to_owned(),
+                "mean".to_owned(),
+            ]),
+            default_value: Some("seed".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Distance (optional)".to_owned(),
+            flags: vec!["--max_dist".to_owned()],
+            description: "Optional maximum distance, in map units, between a region's cells and its seed.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Local Extrema Window Size (cells)".to_owned(),
+            flags: vec!["--filter".to_owned()],
+            description: "Size of the window used to identify local extrema seeds when no seeds file is specified.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("11".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Seed local minima?".to_owned(),
+            flags: vec!["--minima".to_owned()],
+            description: "Flag indicating whether local minima, rather than maxima, are used as seeds when no seeds file is specified.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Include diagonal connections?".to_owned(),
+            flags: vec!["--diag".to_owned()],
+            description: "Flag indicating whether diagonal connections should be considered when growing regions.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=nbr.tif --seeds=burn_seeds.shp -o=burn_scars.tif --tolerance=0.15 --compare=mean --max_dist=5000.0
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=dem.tif -o=hills.tif --tolerance=20.0 --filter=21 --diag", short_exe, name).replace("*", &sep);
+
+        RegionGrowing {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for RegionGrowing {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--tolerance".to_string(), ParameterConstraint::Min(0.0)),
+            ("--max_dist".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--filter".to_string(), ParameterConstraint::Min(3.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut seeds_file = String::new();
+        let mut output_file = String::new();
+        let mut tolerance = f64::NAN;
+        let mut compare = String::from("seed");
+        let mut max_dist = f64::INFINITY;
+        let mut filter_size = 11isize;
+        let mut minima = false;
+        let mut diag = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-seeds" {
+                seeds_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-tolerance" {
+                tolerance = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-compare" {
+                compare = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-max_dist" {
+                max_dist = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-filter" {
+                filter_size = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as isize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as isize
+                };
+            } else if flag_val == "-minima" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    minima = true;
+                }
+            } else if flag_val == "-diag" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    diag = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if tolerance.is_nan() || tolerance < 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A non-negative similarity tolerance (--tolerance) must be specified.",
+            ));
+        }
+        let use_mean = if compare.contains("mean") {
+            true
+        } else if compare.contains("seed") {
+            false
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unrecognized region value; options include 'seed' and 'mean'.",
+            ));
+        };
+        if filter_size < 3 {
+            filter_size = 3;
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        seeds_file = resolve_path(&seeds_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let input = Raster::new(&input_file, "r")?;
+        let rows = input.configs.rows as isize;
+        let columns = input.configs.columns as isize;
+        let nodata = input.configs.nodata;
+        let res_x = input.configs.resolution_x;
+        let res_y = input.configs.resolution_y;
+
+        let start = Instant::now();
+
+        // find the seed cells, as (row, column, label)
+        let mut seeds: Vec<(isize, isize, i32)> = vec![];
+        if !seeds_file.is_empty() {
+            let points = Shapefile::read(&seeds_file)?;
+            if points.header.shape_type.base_shape_type() != ShapeType::Point
+                && points.header.shape_type.base_shape_type() != ShapeType::MultiPoint
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input seeds file must be of a point or multipoint base shape type.",
+                ));
+            }
+            for record_num in 0..points.num_records {
+                let record = points.get_record(record_num);
+                for p in &record.points {
+                    let row = input.get_row_from_y(p.y);
+                    let col = input.get_column_from_x(p.x);
+                    seeds.push((row, col, record_num as i32 + 1));
+                }
+            }
+        } else {
+            let half = filter_size / 2;
+            for row in 0..rows {
+                for col in 0..columns {
+                    let z = input.get_value(row, col);
+                    if z == nodata {
+                        continue;
+                    }
+                    let mut is_extremum = true;
+                    'window: for r in (row - half)..=(row + half) {
+                        for c in (col - half)..=(col + half) {
+                            let zn = input.get_value(r, c);
+                            if (r == row && c == col) || zn == nodata {
+                                continue;
+                            }
+                            // cells on a flat extremum are only seeded once, at the first cell
+                            let earlier = r < row || (r == row && c < col);
+                            let diff = if minima { zn - z } else { z - zn };
+                            if diff < 0f64 || (diff == 0f64 && earlier) {
+                                is_extremum = false;
+                                break 'window;
+                            }
+                        }
+                    }
+                    if is_extremum {
+                        seeds.push((row, col, seeds.len() as i32 + 1));
+                    }
+                }
+                if verbose {
+                    progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Finding seeds: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        let mut labels: Array2D<i32> = Array2D::new(rows, columns, 0i32, -1i32)?;
+        let num_regions = seeds.iter().map(|s| s.2).max().unwrap_or(0) as usize;
+        let mut region_sum = vec![0f64; num_regions + 1];
+        let mut region_count = vec![0usize; num_regions + 1];
+        let mut region_seed = vec![(0isize, 0isize); num_regions + 1];
+        let mut num_ignored = 0;
+        let mut heap = BinaryHeap::new();
+        let mut order = 0usize;
+        let dx = [1, 0, -1, 0, 1, -1, -1, 1];
+        let dy = [0, 1, 0, -1, 1, 1, -1, -1];
+        let num_neighbours = if diag { 8 } else { 4 };
+        let mut queue_neighbours = |row: isize,
+                                    col: isize,
+                                    label: i32,
+                                    labels: &Array2D<i32>,
+                                    region_value: f64,
+                                    seed: (isize, isize),
+                                    heap: &mut BinaryHeap<Candidate>| {
+            for n in 0..num_neighbours {
+                let (rn, cn) = (row + dy[n], col + dx[n]);
+                if labels.get_value(rn, cn) != 0 {
+                    continue;
+                }
+                let z = input.get_value(rn, cn);
+                if z == nodata {
+                    continue;
+                }
+                let delta = (z - region_value).abs();
+                if delta > tolerance {
+                    continue;
+                }
+                let dist_x = (cn - seed.1) as f64 * res_x;
+                let dist_y = (rn - seed.0) as f64 * res_y;
+                if (dist_x * dist_x + dist_y * dist_y).sqrt() > max_dist {
+                    continue;
+                }
+                heap.push(Candidate {
+                    row: rn,
+                    column: cn,
+                    label,
+                    delta,
+                    order,
+                });
+                order += 1;
+            }
+        };
+
+        for &(row, col, label) in &seeds {
+            let z = input.get_value(row, col);
+            if z == nodata || labels.get_value(row, col) != 0 {
+                num_ignored += 1;
+                continue;
+            }
+            let l = label as usize;
+            if region_count[l] == 0 {
+                region_seed[l] = (row, col);
+            }
+            labels.set_value(row, col, label);
+            region_sum[l] += z;
+            region_count[l] += 1;
+        }
+        for &(row, col, label) in &seeds {
+            if labels.get_value(row, col) == label {
+                let l = label as usize;
+                let region_value = if use_mean {
+                    region_sum[l] / region_count[l] as f64
+                } else {
+                    input.get_value(region_seed[l].0, region_seed[l].1)
+                };
+                queue_neighbours(row, col, label, &labels, region_value, region_seed[l], &mut heap);
+            }
+        }
+
+        let num_cells = (rows * columns) as f64;
+        let mut num_labelled = 0usize;
+        while let Some(cell) = heap.pop() {
+            if labels.get_value(cell.row, cell.column) != 0 {
+                continue;
+            }
+            let l = cell.label as usize;
+            let z = input.get_value(cell.row, cell.column);
+            labels.set_value(cell.row, cell.column, cell.label);
+            region_sum[l] += z;
+            region_count[l] += 1;
+            let region_value = if use_mean {
+                region_sum[l] / region_count[l] as f64
+            } else {
+                input.get_value(region_seed[l].0, region_seed[l].1)
+            };
+            queue_neighbours(
+                cell.row,
+                cell.column,
+                cell.label,
+                &labels,
+                region_value,
+                region_seed[l],
+                &mut heap,
+            );
+            num_labelled += 1;
+            if verbose {
+                progress = (100.0_f64 * num_labelled as f64 / num_cells) as usize;
+                if progress != old_progress {
+                    println!("Growing regions: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if num_ignored > 0 {
+            println!(
+                "Warning: {} seeds were ignored because they are located on NoData cells, outside of the raster, or within the cell of an earlier seed.",
+                num_ignored
+            );
+        }
+
+        let mut output = Raster::initialize_using_file(&output_file, &input);
+        let out_nodata = -32768f64;
+        output.configs.nodata = out_nodata;
+        output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+        output.configs.data_type = DataType::I32;
+        output.configs.palette = "qual.plt".to_string();
+        for row in 0..rows {
+            for col in 0..columns {
+                if input.get_value(row, col) == nodata {
+                    output.set_value(row, col, out_nodata);
+                } else {
+                    output.set_value(row, col, labels.get_value(row, col) as f64);
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        if !seeds_file.is_empty() {
+            output.add_metadata_entry(format!("Seeds file: {}", seeds_file));
+        } else {
+            output.add_metadata_entry(format!(
+                "Seeds: local {} ({} cell window)",
+                if minima { "minima" } else { "maxima" },
+                filter_size
+            ));
+        }
+        output.add_metadata_entry(format!("Tolerance: {}", tolerance));
+        output.add_metadata_entry(format!("Region value: {}", if use_mean { "mean" } else { "seed" }));
+        if max_dist.is_finite() {
+            output.add_metadata_entry(format!("Maximum distance: {}", max_dist));
+        }
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!("Number of regions: {}", num_regions);
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A cell bordering a region, prioritized by its difference from the region's value and then by
+/// the order in which it was found.
+#[derive(PartialEq, Debug)]
+struct Candidate {
+    row: isize,
+    column: isize,
+    label: i32,
+    delta: f64,
+    order: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        // reversed, so that the BinaryHeap is a min-heap
+        other
+            .delta
+            .partial_cmp(&self.delta)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
@@ -188,14 +188,10 @@ impl WhiteboxTool for Resample {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !destination_file.contains(&sep) && !destination_file.contains("/") {
-            destination_file = format!("{}{}", working_directory, destination_file);
-        }
+        destination_file = resolve_path(&destination_file, working_directory);
 
         // see if the destination file exists.
         if !path::Path::new(&destination_file).exists() {
@@ -233,9 +229,7 @@ impl WhiteboxTool for Resample {
             let value = input_vec[i];
             if !value.trim().is_empty() {
                 let mut input_file = value.trim().to_owned();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
                 inputs.push(Raster::new(&input_file, "r")?);
                 nodata_vals.push(inputs[i].configs.nodata);
             } else {
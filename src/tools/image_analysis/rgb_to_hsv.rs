@@ -0,0 +1,438 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::rgb_to_hsv;
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool transforms three raster images of multispectral data (red, green, and blue channels), or a
+/// single colour-composite image, into their equivalent hue, saturation, and value (HSV) images. Hue is
+/// perceived as colour, saturation is the purity of the colour, and value is the brightness of the colour,
+/// defined as the largest of the red, green, and blue components. The output HSV values follow the ranges:
+///
+/// > 0 <= H < 360 (degrees)
+/// >
+/// > 0 <= S <= 1
+/// >
+/// > 0 <= V <= 1
+///
+/// The user must either specify the names of the red, green, and blue images (`--red`, `--green`,
+/// `--blue`), or the name of a colour-composite image (`--composite`). Individual bands are scaled to the
+/// 0-1 range using their display minimum and maximum values. The user must also specify the names of the
+/// output hue, saturation, and value images (`--hue`, `--saturation`, `--value`).
+///
+/// The HSV transform is commonly used to blend a colour image with a shaded-relief image, by replacing the
+/// value component with a hillshade image prior to the inverse transformation (`HsvToRgb`), and to enhance
+/// the saturation of vegetation and other features in imagery. Unlike the IHS transform (`RgbToIhs`), the
+/// value component of a fully saturated colour is equal to one, such that the brightness of the output
+/// composite is solely determined by the value image.
+///
+/// # See Also
+/// `HsvToRgb`, `RgbToIhs`, `RgbToLab`, `CreateColourComposite`
+pub struct RgbToHsv {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl RgbToHsv {
+    /// Public constructor.
+    pub fn new() -> RgbToHsv {
+        let name = "RgbToHsv".to_string();
+        let toolbox = "Image Processing Tools".to_string();
+        let description = "Converts red, green, and blue (RGB) images into hue, saturation, and value (HSV) images.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Red Band File (optional; only if colour-composite not specified)"
+                .to_owned(),
+            flags: vec!["--red".to_owned()],
+            description:
+                "Input red band image file. Optionally specified if colour-composite not specified."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Green Band File (optional; only if colour-composite not specified)"
+                .to_owned(),
+            flags: vec!["--green".to_owned()],
+            description:
+                "Input green band image file. Optionally specified if colour-composite not specified."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Blue Band File (optional; only if colour-composite not specified)"
+                .to_owned(),
+            flags: vec!["--blue".to_owned()],
+            description:
+                "Input blue band image file. Optionally specified if colour-composite not specified."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Colour-Composite Image File (optional; only if individual bands not specified)".to_owned(),
+            flags: vec!["--composite".to_owned()],
+            description: "Input colour-composite image file. Only used if individual bands are not specified.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Hue File".to_owned(),
+            flags: vec!["--hue".to_owned()],
+            description: "Output hue raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Saturation File".to_owned(),
+            flags: vec!["--saturation".to_owned()],
+            description: "Output saturation raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Value File".to_owned(),
+            flags: vec!["--value".to_owned()],
+            description: "Output value (brightness) raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --red=band3.tif --green=band2.tif --blue=band1.tif --hue=hue.tif --saturation=saturation.tif --value=value.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --composite=image.tif --hue=hue.tif --saturation=saturation.tif --value=value.tif", short_exe, name).replace("*", &sep);
+
+        RgbToHsv {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for RgbToHsv {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            (
+                "--composite".to_string(),
+                ParameterConstraint::ExclusiveWith("--red".to_string()),
+            ),
+            (
+                "--red".to_string(),
+                ParameterConstraint::RequiredIf("--green".to_string()),
+            ),
+            (
+                "--green".to_string(),
+                ParameterConstraint::RequiredIf("--blue".to_string()),
+            ),
+            (
+                "--blue".to_string(),
+                ParameterConstraint::RequiredIf("--red".to_string()),
+            ),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut red_file = String::new();
+        let mut green_file = String::new();
+        let mut blue_file = String::new();
+        let mut composite_file = String::new();
+        let mut hue_file = String::new();
+        let mut saturation_file = String::new();
+        let mut value_file = String::new();
+        let mut use_composite = false;
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            let value = if keyval {
+                vec[1].to_string()
+            } else if i + 1 < args.len() {
+                args[i + 1].to_string()
+            } else {
+                String::new()
+            };
+            if flag_val == "-red" {
+                red_file = value;
+            } else if flag_val == "-green" {
+                green_file = value;
+            } else if flag_val == "-blue" {
+                blue_file = value;
+            } else if flag_val == "-composite" {
+                composite_file = value;
+                use_composite = true;
+            } else if flag_val == "-hue" {
+                hue_file = value;
+            } else if flag_val == "-saturation" {
+                saturation_file = value;
+            } else if flag_val == "-value" {
+                value_file = value;
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if !use_composite && (red_file.is_empty() || green_file.is_empty() || blue_file.is_empty()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either the red, green, and blue band files or a colour-composite file must be specified.",
+            ));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        for file in [
+            &mut red_file,
+            &mut green_file,
+            &mut blue_file,
+            &mut composite_file,
+            &mut hue_file,
+            &mut saturation_file,
+            &mut value_file,
+        ] {
+            *file = resolve_path(file, working_directory);
+        }
+
+        let inputs = if use_composite {
+            if verbose {
+                println!("Reading image data...")
+            };
+            vec![Arc::new(Raster::new(&composite_file, "r")?)]
+        } else {
+            if verbose {
+                println!("Reading band data...")
+            };
+            vec![
+                Arc::new(Raster::new(&red_file, "r")?),
+                Arc::new(Raster::new(&green_file, "r")?),
+                Arc::new(Raster::new(&blue_file, "r")?),
+            ]
+        };
+
+        let rows = inputs[0].configs.rows as isize;
+        let columns = inputs[0].configs.columns as isize;
+        let nodata = inputs[0].configs.nodata;
+        for input in &inputs {
+            if input.configs.rows as isize != rows || input.configs.columns as isize != columns {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+        }
+
+        let start = Instant::now();
+
+        let inputs = Arc::new(inputs);
+        let num_procs = num_cpus::get() as isize;
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let inputs = inputs.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                    let mut hue_data = vec![nodata; columns as usize];
+                    let mut saturation_data = vec![nodata; columns as usize];
+                    let mut value_data = vec![nodata; columns as usize];
+                    for col in 0..columns {
+                        if let Some((r, g, b)) = get_normalized_rgb(&inputs, row, col) {
+                            let (h, s, v) = rgb_to_hsv(r, g, b);
+                            hue_data[col as usize] = h;
+                            saturation_data[col as usize] = s;
+                            value_data[col as usize] = v;
+                        }
+                    }
+                    tx.send((row, hue_data, saturation_data, value_data))
+                        .unwrap();
+                }
+            });
+        }
+
+        let mut outputs = vec![];
+        for file in [&hue_file, &saturation_file, &value_file] {
+            let mut output = Raster::initialize_using_file(file, &inputs[0]);
+            output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+            output.configs.data_type = DataType::F32;
+            output.configs.nodata = nodata;
+            outputs.push(output);
+        }
+
+        for row in 0..rows {
+            let data = rx.recv().unwrap();
+            outputs[0].set_row_data(data.0, data.1);
+            outputs[1].set_row_data(data.0, data.2);
+            outputs[2].set_row_data(data.0, data.3);
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        for (output, component) in outputs.iter_mut().zip(["hue", "saturation", "value"]) {
+            output.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            if use_composite {
+                output.add_metadata_entry(format!(
+                    "Input colour composite file: {}",
+                    composite_file
+                ));
+            } else {
+                output.add_metadata_entry(format!("Input red band file: {}", red_file));
+                output.add_metadata_entry(format!("Input green band file: {}", green_file));
+                output.add_metadata_entry(format!("Input blue band file: {}", blue_file));
+            }
+            output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+            if verbose {
+                println!("Saving {} data...", component)
+            };
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the red, green, and blue values (0-1) of a cell, either from a colour composite,
+/// or from three bands, each of which is scaled using its display minimum and maximum.
+fn get_normalized_rgb(inputs: &[Arc<Raster>], row: isize, col: isize) -> Option<(f64, f64, f64)> {
+    if inputs.len() == 1 {
+        let z = inputs[0].get_value(row, col);
+        if z == inputs[0].configs.nodata {
+            return None;
+        }
+        return Some((
+            (z as u32 & 0xFF) as f64 / 255f64,
+            ((z as u32 >> 8) & 0xFF) as f64 / 255f64,
+            ((z as u32 >> 16) & 0xFF) as f64 / 255f64,
+        ));
+    }
+    let mut rgb = [0f64; 3];
+    for (k, input) in inputs.iter().enumerate() {
+        let z = input.get_value(row, col);
+        if z == input.configs.nodata {
+            return None;
+        }
+        let range = input.configs.display_max - input.configs.display_min;
+        rgb[k] = if range > 0f64 {
+            ((z - input.configs.display_min) / range).clamp(0f64, 1f64)
+        } else {
+            0f64
+        };
+    }
+    Some((rgb[0], rgb[1], rgb[2]))
+}
@@ -2,16 +2,16 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 25/07/2017
-Last Modified: 22/10/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
+use crate::algorithms::rgb_to_ihs;
 use crate::raster::*;
 use crate::tools::*;
 use num_cpus;
 use std::env;
 use std::f64;
-use std::f64::consts::PI;
 use std::io::{Error, ErrorKind};
 use std::path;
 use std::sync::mpsc;
@@ -287,32 +287,16 @@ impl WhiteboxTool for RgbToIhs {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !red_file.contains(&sep) && !red_file.contains("/") {
-            red_file = format!("{}{}", working_directory, red_file);
-        }
-        if !green_file.contains(&sep) && !green_file.contains("/") {
-            green_file = format!("{}{}", working_directory, green_file);
-        }
-        if !blue_file.contains(&sep) && !blue_file.contains("/") {
-            blue_file = format!("{}{}", working_directory, blue_file);
-        }
-        if !composite_file.contains(&sep) && !composite_file.contains("/") {
-            composite_file = format!("{}{}", working_directory, composite_file);
-        }
-        if !intensity_file.contains(&sep) && !intensity_file.contains("/") {
-            intensity_file = format!("{}{}", working_directory, intensity_file);
-        }
-        if !hue_file.contains(&sep) && !hue_file.contains("/") {
-            hue_file = format!("{}{}", working_directory, hue_file);
-        }
-        if !saturation_file.contains(&sep) && !saturation_file.contains("/") {
-            saturation_file = format!("{}{}", working_directory, saturation_file);
-        }
+        red_file = resolve_path(&red_file, working_directory);
+        green_file = resolve_path(&green_file, working_directory);
+        blue_file = resolve_path(&blue_file, working_directory);
+        composite_file = resolve_path(&composite_file, working_directory);
+        intensity_file = resolve_path(&intensity_file, working_directory);
+        hue_file = resolve_path(&hue_file, working_directory);
+        saturation_file = resolve_path(&saturation_file, working_directory);
 
         let num_procs = num_cpus::get() as isize;
 
@@ -402,7 +386,7 @@ impl WhiteboxTool for RgbToIhs {
                                 red = (red - red_min) / red_range;
                                 green = (green - green_min) / green_range;
                                 blue = (blue - blue_min) / blue_range;
-                                let (h, s, i) = rgb2hsi(red, green, blue);
+                                let (i, h, s) = rgb_to_ihs(red, green, blue);
 
                                 hue_data[col as usize] = h;
                                 saturation_data[col as usize] = s;
@@ -653,7 +637,11 @@ impl WhiteboxTool for RgbToIhs {
                                 //     s = (i - 3f64 * g) / i;
                                 // }
 
-                                let (h, s, i) = value2hsi(z);
+                                let (i, h, s) = rgb_to_ihs(
+                                    (z as u32 & 0xFF) as f64 / 255f64,
+                                    ((z as u32 >> 8) & 0xFF) as f64 / 255f64,
+                                    ((z as u32 >> 16) & 0xFF) as f64 / 255f64,
+                                );
 
                                 intensity_data[col as usize] = i;
                                 hue_data[col as usize] = h;
@@ -768,55 +756,6 @@ impl WhiteboxTool for RgbToIhs {
     }
 }
 
-fn value2hsi(value: f64) -> (f64, f64, f64) {
-    let r = (value as u32 & 0xFF) as f64 / 255f64;
-    let g = ((value as u32 >> 8) & 0xFF) as f64 / 255f64;
-    let b = ((value as u32 >> 16) & 0xFF) as f64 / 255f64;
-
-    let i = (r + g + b) / 3f64;
-
-    let rn = r / (r + g + b);
-    let gn = g / (r + g + b);
-    let bn = b / (r + g + b);
-
-    let mut h = if rn != gn || rn != bn {
-        ((0.5 * ((rn - gn) + (rn - bn))) / ((rn - gn) * (rn - gn) + (rn - bn) * (gn - bn)).sqrt())
-            .acos()
-    } else {
-        0f64
-    };
-    if b > g {
-        h = 2f64 * PI - h;
-    }
-
-    let s = 1f64 - 3f64 * rn.min(gn).min(bn);
-
-    (h, s, i)
-}
-
-/// RGB values should be 0-1
-fn rgb2hsi(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
-    let i = (r + g + b) / 3f64;
-
-    let rn = r / (r + g + b);
-    let gn = g / (r + g + b);
-    let bn = b / (r + g + b);
-
-    let mut h = if rn != gn || rn != bn {
-        ((0.5 * ((rn - gn) + (rn - bn))) / ((rn - gn) * (rn - gn) + (rn - bn) * (gn - bn)).sqrt())
-            .acos()
-    } else {
-        0f64
-    };
-    if b > g {
-        h = 2f64 * PI - h;
-    }
-
-    let s = 1f64 - 3f64 * rn.min(gn).min(bn);
-
-    (h, s, i)
-}
-
 // fn hsi2value(h: f64, s: f64, i: f64) -> f64 {
 //     let mut r: u32;
 //     let mut g: u32;
@@ -0,0 +1,439 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::rgb_to_lab;
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool transforms three raster images of multispectral data (red, green, and blue channels), or a
+/// single colour-composite image, into their equivalent CIE L\*a\*b\* (CIELAB) images. The L\* component
+/// is the perceptual lightness of the colour, a\* describes the green-red opponent colours, and b\*
+/// describes the blue-yellow opponent colours. The input colours are assumed to be sRGB colours and are
+/// converted using the D65 reference white. The output values follow the approximate ranges:
+///
+/// > 0 <= L\* <= 100
+/// >
+/// > -128 <= a\* <= 127
+/// >
+/// > -128 <= b\* <= 127
+///
+/// The user must either specify the names of the red, green, and blue images (`--red`, `--green`,
+/// `--blue`), or the name of a colour-composite image (`--composite`). Individual bands are scaled to the
+/// 0-1 range using their display minimum and maximum values. The user must also specify the names of the
+/// output L\*, a\*, and b\* images (`--l_star`, `--a_star`, `--b_star`).
+///
+/// Because the CIELAB colour space is approximately perceptually uniform, equal differences in the
+/// components correspond with roughly equal perceived colour differences. Contrast enhancements applied
+/// to the L\* component therefore alter the brightness of an image without shifting its hues, and the a\*
+/// component is useful for separating green vegetation from other land covers. The enhanced components
+/// can be transformed back into RGB using the `LabToRgb` tool.
+///
+/// # See Also
+/// `LabToRgb`, `RgbToLab`, `RgbToIhs`, `CreateColourComposite`
+pub struct RgbToLab {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl RgbToLab {
+    /// Public constructor.
+    pub fn new() -> RgbToLab {
+        let name = "RgbToLab".to_string();
+        let toolbox = "Image Processing Tools".to_string();
+        let description = "Converts red, green, and blue (RGB) images into CIE L*a*b* images.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Red Band File (optional; only if colour-composite not specified)"
+                .to_owned(),
+            flags: vec!["--red".to_owned()],
+            description:
+                "Input red band image file. Optionally specified if colour-composite not specified."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Green Band File (optional; only if colour-composite not specified)"
+                .to_owned(),
+            flags: vec!["--green".to_owned()],
+            description:
+                "Input green band image file. Optionally specified if colour-composite not specified."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Blue Band File (optional; only if colour-composite not specified)"
+                .to_owned(),
+            flags: vec!["--blue".to_owned()],
+            description:
+                "Input blue band image file. Optionally specified if colour-composite not specified."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Colour-Composite Image File (optional; only if individual bands not specified)".to_owned(),
+            flags: vec!["--composite".to_owned()],
+            description: "Input colour-composite image file. Only used if individual bands are not specified.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output L* File".to_owned(),
+            flags: vec!["--l_star".to_owned()],
+            description: "Output L* (lightness) raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output a* File".to_owned(),
+            flags: vec!["--a_star".to_owned()],
+            description: "Output a* (green-red) raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output b* File".to_owned(),
+            flags: vec!["--b_star".to_owned()],
+            description: "Output b* (blue-yellow) raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --red=band3.tif --green=band2.tif --blue=band1.tif --l_star=l.tif --a_star=a.tif --b_star=b.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --composite=image.tif --l_star=l.tif --a_star=a.tif --b_star=b.tif", short_exe, name).replace("*", &sep);
+
+        RgbToLab {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for RgbToLab {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            (
+                "--composite".to_string(),
+                ParameterConstraint::ExclusiveWith("--red".to_string()),
+            ),
+            (
+                "--red".to_string(),
+                ParameterConstraint::RequiredIf("--green".to_string()),
+            ),
+            (
+                "--green".to_string(),
+                ParameterConstraint::RequiredIf("--blue".to_string()),
+            ),
+            (
+                "--blue".to_string(),
+                ParameterConstraint::RequiredIf("--red".to_string()),
+            ),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut red_file = String::new();
+        let mut green_file = String::new();
+        let mut blue_file = String::new();
+        let mut composite_file = String::new();
+        let mut l_file = String::new();
+        let mut a_file = String::new();
+        let mut b_file = String::new();
+        let mut use_composite = false;
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            let value = if keyval {
+                vec[1].to_string()
+            } else if i + 1 < args.len() {
+                args[i + 1].to_string()
+            } else {
+                String::new()
+            };
+            if flag_val == "-red" {
+                red_file = value;
+            } else if flag_val == "-green" {
+                green_file = value;
+            } else if flag_val == "-blue" {
+                blue_file = value;
+            } else if flag_val == "-composite" {
+                composite_file = value;
+                use_composite = true;
+            } else if flag_val == "-l_star" {
+                l_file = value;
+            } else if flag_val == "-a_star" {
+                a_file = value;
+            } else if flag_val == "-b_star" {
+                b_file = value;
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if !use_composite && (red_file.is_empty() || green_file.is_empty() || blue_file.is_empty()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either the red, green, and blue band files or a colour-composite file must be specified.",
+            ));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        for file in [
+            &mut red_file,
+            &mut green_file,
+            &mut blue_file,
+            &mut composite_file,
+            &mut l_file,
+            &mut a_file,
+            &mut b_file,
+        ] {
+            *file = resolve_path(file, working_directory);
+        }
+
+        let inputs = if use_composite {
+            if verbose {
+                println!("Reading image data...")
+            };
+            vec![Arc::new(Raster::new(&composite_file, "r")?)]
+        } else {
+            if verbose {
+                println!("Reading band data...")
+            };
+            vec![
+                Arc::new(Raster::new(&red_file, "r")?),
+                Arc::new(Raster::new(&green_file, "r")?),
+                Arc::new(Raster::new(&blue_file, "r")?),
+            ]
+        };
+
+        let rows = inputs[0].configs.rows as isize;
+        let columns = inputs[0].configs.columns as isize;
+        let nodata = inputs[0].configs.nodata;
+        for input in &inputs {
+            if input.configs.rows as isize != rows || input.configs.columns as isize != columns {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+        }
+
+        let start = Instant::now();
+
+        let inputs = Arc::new(inputs);
+        let num_procs = num_cpus::get() as isize;
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let inputs = inputs.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                    let mut l_data = vec![nodata; columns as usize];
+                    let mut a_data = vec![nodata; columns as usize];
+                    let mut b_data = vec![nodata; columns as usize];
+                    for col in 0..columns {
+                        if let Some((r, g, b)) = get_normalized_rgb(&inputs, row, col) {
+                            let (l, a, b) = rgb_to_lab(r, g, b);
+                            l_data[col as usize] = l;
+                            a_data[col as usize] = a;
+                            b_data[col as usize] = b;
+                        }
+                    }
+                    tx.send((row, l_data, a_data, b_data))
+                        .unwrap();
+                }
+            });
+        }
+
+        let mut outputs = vec![];
+        for file in [&l_file, &a_file, &b_file] {
+            let mut output = Raster::initialize_using_file(file, &inputs[0]);
+            output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+            output.configs.data_type = DataType::F32;
+            output.configs.nodata = nodata;
+            outputs.push(output);
+        }
+
+        for row in 0..rows {
+            let data = rx.recv().unwrap();
+            outputs[0].set_row_data(data.0, data.1);
+            outputs[1].set_row_data(data.0, data.2);
+            outputs[2].set_row_data(data.0, data.3);
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        for (output, component) in outputs.iter_mut().zip(["L*", "a*", "b*"]) {
+            output.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            if use_composite {
+                output.add_metadata_entry(format!(
+                    "Input colour composite file: {}",
+                    composite_file
+                ));
+            } else {
+                output.add_metadata_entry(format!("Input red band file: {}", red_file));
+                output.add_metadata_entry(format!("Input green band file: {}", green_file));
+                output.add_metadata_entry(format!("Input blue band file: {}", blue_file));
+            }
+            output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+            if verbose {
+                println!("Saving {} data...", component)
+            };
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the red, green, and blue values (0-1) of a cell, either from a colour composite,
+/// or from three bands, each of which is scaled using its display minimum and maximum.
+fn get_normalized_rgb(inputs: &[Arc<Raster>], row: isize, col: isize) -> Option<(f64, f64, f64)> {
+    if inputs.len() == 1 {
+        let z = inputs[0].get_value(row, col);
+        if z == inputs[0].configs.nodata {
+            return None;
+        }
+        return Some((
+            (z as u32 & 0xFF) as f64 / 255f64,
+            ((z as u32 >> 8) & 0xFF) as f64 / 255f64,
+            ((z as u32 >> 16) & 0xFF) as f64 / 255f64,
+        ));
+    }
+    let mut rgb = [0f64; 3];
+    for (k, input) in inputs.iter().enumerate() {
+        let z = input.get_value(row, col);
+        if z == input.configs.nodata {
+            return None;
+        }
+        let range = input.configs.display_max - input.configs.display_min;
+        rgb[k] = if range > 0f64 {
+            ((z - input.configs.display_min) / range).clamp(0f64, 1f64)
+        } else {
+            0f64
+        };
+    }
+    Some((rgb[0], rgb[1], rgb[2]))
+}
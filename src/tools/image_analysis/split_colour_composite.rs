@@ -203,23 +203,13 @@ impl WhiteboxTool for SplitColourComposite {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !red_file.contains(&sep) && !red_file.contains("/") {
-            red_file = format!("{}{}", working_directory, red_file);
-        }
-        if !green_file.contains(&sep) && !green_file.contains("/") {
-            green_file = format!("{}{}", working_directory, green_file);
-        }
-        if !blue_file.contains(&sep) && !blue_file.contains("/") {
-            blue_file = format!("{}{}", working_directory, blue_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        red_file = resolve_path(&red_file, working_directory);
+        green_file = resolve_path(&green_file, working_directory);
+        blue_file = resolve_path(&blue_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -205,17 +205,9 @@ impl WhiteboxTool for UserDefinedWeightsFilter {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !weights_file.contains(&sep) && !weights_file.contains("/") {
-            weights_file = format!("{}{}", working_directory, weights_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        weights_file = resolve_path(&weights_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         // read in the filter weights
         let f = File::open(weights_file.clone())?;
@@ -0,0 +1,76 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::split_zip_entry;
+use crate::structures::BoundingBox;
+use std::fs;
+use std::time::SystemTime;
+
+/// Returns the indices of the tiles whose outputs must be regenerated when a tool that grids a set
+/// of LiDAR tiles is run in incremental mode. The `inputs` are the input tile file names, `outputs`
+/// contains the output file names created for each input tile, and `bounding_boxes` are the extents
+/// of the input tiles (i.e. the tile index). Because points are gathered from neighbouring tiles
+/// within `search_radius` of a tile's edges, a tile is updated if any of its output files is
+/// missing or is older than either its own input file or any input file whose extent overlaps the
+/// tile's buffered extent.
+///
+/// Removing a tile from the data set does not change the modification times of the remaining
+/// files, and so its neighbours are not updated in this case.
+pub fn find_tiles_to_update(
+    inputs: &[String],
+    outputs: &[Vec<String>],
+    bounding_boxes: &[BoundingBox],
+    search_radius: f64,
+) -> Vec<usize> {
+    // the entries of a zip archive share the modification time of the archive
+    let modified = |file_name: &str| -> Option<SystemTime> {
+        fs::metadata(split_zip_entry(&file_name.replace("\"", "")).0)
+            .and_then(|m| m.modified())
+            .ok()
+    };
+    let input_times: Vec<Option<SystemTime>> = inputs.iter().map(|f| modified(f)).collect();
+
+    let mut tiles = vec![];
+    for tile in 0..inputs.len() {
+        // the oldest of the tile's outputs; a missing output always requires the tile to be updated
+        let mut output_time: Option<SystemTime> = None;
+        let mut missing = outputs[tile].is_empty();
+        for file_name in &outputs[tile] {
+            match modified(file_name) {
+                Some(t) => {
+                    if output_time.is_none_or(|ot| t < ot) {
+                        output_time = Some(t);
+                    }
+                }
+                None => missing = true,
+            }
+        }
+        let output_time = match output_time {
+            Some(t) if !missing => t,
+            _ => {
+                tiles.push(tile);
+                continue;
+            }
+        };
+
+        let bb = BoundingBox {
+            min_x: bounding_boxes[tile].min_x - search_radius,
+            max_x: bounding_boxes[tile].max_x + search_radius,
+            min_y: bounding_boxes[tile].min_y - search_radius,
+            max_y: bounding_boxes[tile].max_y + search_radius,
+        };
+        let is_stale = (0..inputs.len()).any(|m| {
+            (m == tile || bounding_boxes[m].overlaps(bb))
+                && input_times[m].is_none_or(|t| t > output_time)
+        });
+        if is_stale {
+            tiles.push(tile);
+        }
+    }
+    tiles
+}
@@ -0,0 +1,203 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::{ParameterType, ToolParameter};
+use crate::lidar::list_zipped_las_files;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// Selects the input files of tools that process every file of a directory when no individual
+/// input file is specified, e.g. the directory-batch mode of the LiDAR interpolation tools. Files
+/// may be selected using glob patterns (`--include`) and deselected using exclusion patterns
+/// (`--exclude`), and the subdirectories of the directory may be searched (`--recursive`).
+#[derive(Default, Clone, Debug)]
+pub struct InputFileFilter {
+    /// Files must match at least one of the inclusion patterns, if there are any.
+    pub include: Vec<String>,
+    /// Files matching any of the exclusion patterns are skipped.
+    pub exclude: Vec<String>,
+    /// Whether the subdirectories are searched.
+    pub recursive: bool,
+}
+
+impl InputFileFilter {
+    /// Returns the tool parameters of the filter, i.e. `--include`, `--exclude`, and `--recursive`.
+    pub(super) fn parameters() -> Vec<ToolParameter> {
+        vec![
+            ToolParameter {
+                name: "Include Patterns (optional)".to_owned(),
+                flags: vec!["--include".to_owned()],
+                description: "Optional comma-separated glob patterns of the files to process in the working directory, e.g. '*_ground.las'.".to_owned(),
+                parameter_type: ParameterType::String,
+                default_value: None,
+                optional: true,
+            },
+            ToolParameter {
+                name: "Exclude Patterns (optional)".to_owned(),
+                flags: vec!["--exclude".to_owned()],
+                description: "Optional comma-separated glob patterns of the files to skip in the working directory, e.g. '*_old.las,temp/*'.".to_owned(),
+                parameter_type: ParameterType::String,
+                default_value: None,
+                optional: true,
+            },
+            ToolParameter {
+                name: "Search subdirectories?".to_owned(),
+                flags: vec!["--recursive".to_owned()],
+                description: "Process the files within the subdirectories of the working directory.".to_owned(),
+                parameter_type: ParameterType::Boolean,
+                default_value: Some("false".to_owned()),
+                optional: true,
+            },
+        ]
+    }
+
+    /// Adds one or more comma- or semicolon-separated inclusion patterns.
+    pub fn add_include(&mut self, patterns: &str) {
+        self.include.extend(split_patterns(patterns));
+    }
+
+    /// Adds one or more comma- or semicolon-separated exclusion patterns.
+    pub fn add_exclude(&mut self, patterns: &str) {
+        self.exclude.extend(split_patterns(patterns));
+    }
+
+    /// Returns true if a file, given by its path relative to the searched directory, is selected.
+    /// Patterns containing a path separator are matched against the relative path, while other
+    /// patterns are matched against the file name alone. Matching is case-insensitive.
+    pub fn is_selected(&self, relative_path: &str) -> bool {
+        let relative_path = relative_path.replace('\\', "/");
+        let file_name = match relative_path.rfind('/') {
+            Some(i) => &relative_path[i + 1..],
+            None => &relative_path[..],
+        };
+        let matches = |pattern: &String| {
+            if pattern.contains('/') {
+                matches_glob(pattern, &relative_path)
+            } else {
+                matches_glob(pattern, file_name)
+            }
+        };
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+
+    /// Returns the selected files within a directory that have one of the `extensions` (e.g.
+    /// `["las", "zip"]`), sorted by path. Subdirectories are searched if the filter is recursive.
+    pub fn find_files(&self, directory: &str, extensions: &[&str]) -> Result<Vec<String>, Error> {
+        if !Path::new(directory).is_dir() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("The input directory ({}) is incorrect.", directory),
+            ));
+        }
+        let mut files = vec![];
+        let mut directories = vec![Path::new(directory).to_path_buf()];
+        while let Some(dir) = directories.pop() {
+            for entry in fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    if self.recursive {
+                        directories.push(path);
+                    }
+                    continue;
+                }
+                let has_extension = match path.extension().and_then(|e| e.to_str()) {
+                    Some(ext) => extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)),
+                    None => false,
+                };
+                if !has_extension {
+                    continue;
+                }
+                let s = path
+                    .into_os_string()
+                    .to_str()
+                    .expect("Error reading path string")
+                    .to_string();
+                if self.is_selected(relative_path(directory, &s)) {
+                    files.push(s);
+                }
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Returns the selected LAS files within a directory, including those stored in zip archives.
+    /// An archive containing a single LAS file is returned as is and is selected by its own name.
+    /// The LAS files within an archive containing several of them, possibly within folders, are
+    /// instead returned individually, named `archive.zip#folder/file.las`, and are selected as
+    /// though the archive were a directory, e.g. `*.las` or `tiles.zip/*`.
+    pub fn find_lidar_files(&self, directory: &str) -> Result<Vec<String>, Error> {
+        let all_files = InputFileFilter {
+            recursive: self.recursive,
+            ..Default::default()
+        };
+        let mut files = vec![];
+        for s in all_files.find_files(directory, &["las", "zip"])? {
+            let entries = if s.to_lowercase().ends_with(".zip") {
+                list_zipped_las_files(&s)?
+            } else {
+                vec![]
+            };
+            if entries.len() > 1 {
+                for entry in entries {
+                    if self.is_selected(&format!("{}/{}", relative_path(directory, &s), entry)) {
+                        files.push(format!("{}#{}", s, entry));
+                    }
+                }
+            } else if self.is_selected(relative_path(directory, &s)) {
+                files.push(s);
+            }
+        }
+        Ok(files)
+    }
+}
+
+fn relative_path<'a>(directory: &str, file_name: &'a str) -> &'a str {
+    file_name
+        .strip_prefix(directory)
+        .unwrap_or(file_name)
+        .trim_start_matches(['/', '\\'])
+}
+
+fn split_patterns(patterns: &str) -> Vec<String> {
+    patterns
+        .split([',', ';'])
+        .map(|p| p.trim().replace('\\', "/"))
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Returns true if `text` matches a glob `pattern`, in which `*` matches any sequence of
+/// characters, including an empty one, and `?` matches any single character. Matching is
+/// case-insensitive.
+pub fn matches_glob(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+    // the positions to resume from after the most recent '*', if a later match fails
+    let (mut pi, mut ti) = (0usize, 0usize);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            backtrack = Some((pi, ti));
+            pi += 1;
+        } else if let Some((star_pi, star_ti)) = backtrack {
+            // let the '*' consume one more character
+            pi = star_pi + 1;
+            ti = star_ti + 1;
+            backtrack = Some((star_pi, star_ti + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
@@ -0,0 +1,126 @@
+/*
+This file is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use std::io::{Error, ErrorKind};
+
+/// A LiDAR point field that is stored in a column of an ASCII point file. The column mapping
+/// of the `AsciiToLas` and `LasToAscii` tools is a list of these fields.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AsciiColumn {
+    X,
+    Y,
+    Z,
+    Intensity,
+    Class,
+    ReturnNumber,
+    NumReturns,
+    Time,
+    ScanAngle,
+    Red,
+    Green,
+    Blue,
+    UserData,
+    PointSourceId,
+    /// A column that is ignored when reading.
+    Skip,
+}
+
+impl AsciiColumn {
+    /// Returns the field named by a column mapping entry. Both the long names (e.g. `intensity`)
+    /// and the short names used by earlier versions of `AsciiToLas` (e.g. `i`) are recognized.
+    pub fn from_name(name: &str) -> Result<AsciiColumn, Error> {
+        match name.trim().to_lowercase().as_str() {
+            "x" => Ok(AsciiColumn::X),
+            "y" => Ok(AsciiColumn::Y),
+            "z" | "elev" | "elevation" => Ok(AsciiColumn::Z),
+            "i" | "intensity" => Ok(AsciiColumn::Intensity),
+            "c" | "class" | "classification" => Ok(AsciiColumn::Class),
+            "rn" | "return" | "return_number" => Ok(AsciiColumn::ReturnNumber),
+            "nr" | "num_returns" | "number_of_returns" => Ok(AsciiColumn::NumReturns),
+            "time" | "gps_time" => Ok(AsciiColumn::Time),
+            "sa" | "scan_angle" => Ok(AsciiColumn::ScanAngle),
+            "r" | "red" => Ok(AsciiColumn::Red),
+            "g" | "green" => Ok(AsciiColumn::Green),
+            "b" | "blue" => Ok(AsciiColumn::Blue),
+            "ud" | "user_data" => Ok(AsciiColumn::UserData),
+            "psid" | "point_source_id" => Ok(AsciiColumn::PointSourceId),
+            "" | "-" | "skip" => Ok(AsciiColumn::Skip),
+            n => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unrecognized column name '{}'.", n),
+            )),
+        }
+    }
+
+    /// Returns the name of the field used in the header row of an output ASCII file.
+    pub fn header_name(&self) -> &'static str {
+        match self {
+            AsciiColumn::X => "X",
+            AsciiColumn::Y => "Y",
+            AsciiColumn::Z => "Z",
+            AsciiColumn::Intensity => "INTENSITY",
+            AsciiColumn::Class => "CLASS",
+            AsciiColumn::ReturnNumber => "RETURN",
+            AsciiColumn::NumReturns => "NUM_RETURN",
+            AsciiColumn::Time => "TIME",
+            AsciiColumn::ScanAngle => "SCAN_ANGLE",
+            AsciiColumn::Red => "RED",
+            AsciiColumn::Green => "GREEN",
+            AsciiColumn::Blue => "BLUE",
+            AsciiColumn::UserData => "USER_DATA",
+            AsciiColumn::PointSourceId => "POINT_SOURCE_ID",
+            AsciiColumn::Skip => "SKIP",
+        }
+    }
+}
+
+/// Parses a comma-separated column mapping, e.g. `x,y,z,intensity,class,time`.
+pub fn parse_columns(mapping: &str) -> Result<Vec<AsciiColumn>, Error> {
+    let columns = mapping
+        .split(',')
+        .map(AsciiColumn::from_name)
+        .collect::<Result<Vec<AsciiColumn>, Error>>()?;
+    for (i, c) in columns.iter().enumerate() {
+        if *c != AsciiColumn::Skip && columns[..i].contains(c) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The column mapping contains the {} field more than once.",
+                    c.header_name()
+                ),
+            ));
+        }
+    }
+    Ok(columns)
+}
+
+/// Parses a delimiter name, i.e. `comma`, `tab`, `space`, or `semicolon`, or a single delimiter
+/// character. `None` is returned for `space`, in which case fields are separated by runs of
+/// whitespace.
+pub fn parse_delimiter(delimiter: &str) -> Result<Option<char>, Error> {
+    match delimiter.to_lowercase().as_str() {
+        "" | "comma" | "," => Ok(Some(',')),
+        "tab" | "\\t" | "\t" => Ok(Some('\t')),
+        "space" | "whitespace" | " " => Ok(None),
+        "semicolon" | ";" => Ok(Some(';')),
+        "pipe" | "|" => Ok(Some('|')),
+        d if d.chars().count() == 1 => Ok(d.chars().next()),
+        d => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Unrecognized delimiter '{}'.", d),
+        )),
+    }
+}
+
+/// Splits a line of an ASCII file into its fields.
+pub fn split_line(line: &str, delimiter: Option<char>) -> Vec<&str> {
+    match delimiter {
+        Some(d) => line.split(d).map(|s| s.trim()).collect(),
+        None => line.split_whitespace().collect(),
+    }
+}
@@ -2,10 +2,11 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 10/02/2019
-Last Modified: 01/08/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
+use super::ascii_columns::{parse_columns, parse_delimiter, split_line, AsciiColumn};
 use crate::lidar::*;
 use crate::tools::*;
 use std;
@@ -15,46 +16,62 @@ use std::io::prelude::*;
 use std::io::BufReader;
 use std::io::{Error, ErrorKind};
 use std::path;
-extern crate byteorder;
-use byteorder::{LittleEndian, WriteBytesExt};
 
-
-/// This tool can be used to convert one or more ASCII files, containing LiDAR point data, into LAS files. The user must 
-/// specify the name(s) of the input ASCII file(s) (`--inputs`). Each input file will have a coorespondingly named
-/// output file with a `.las` file extension. The output point data, each on a seperate line, will take the format:
-/// 
-/// ```
-/// x,y,z,intensity,class,return,num_returns"
-/// ```
-/// 
-/// | Value | Interpretation    |
-/// | :---- | :---------------- |
-/// | x     | x-coordinate      |
-/// | y     | y-coordinate      |
-/// | z     | elevation         |
-/// | i     | intensity value   |
-/// | c     | classification    |
-/// | rn    | return number     |
-/// | nr    | number of returns |
-/// | time  | GPS time          |
-/// | sa    | scan angle        |
-/// | r     | red               |
-/// | b     | blue              |
-/// | g     | green             |
-/// 
-/// The `x`, `y`, and `z` patterns must always be specified. If the `rn` pattern is used, the `nr` pattern must
-/// also be specified. Examples of valid pattern string inclue:
-/// 
+/// This tool can be used to convert one or more ASCII files, containing LiDAR point data, into LAS files. The user must
+/// specify the name(s) of the input ASCII file(s) (`--inputs`). Each input file will have a correspondingly named
+/// output file with a `.las` file extension. Each point is stored on a separate line of the input file and the
+/// user must specify how the columns of the file map onto the point fields (`--columns`) as a comma-separated
+/// list of field names, e.g.:
+///
+/// ```text
+/// x,y,z,intensity,class,time
 /// ```
+///
+/// | Name                        | Interpretation     |
+/// | :-------------------------- | :----------------- |
+/// | `x`                         | x-coordinate       |
+/// | `y`                         | y-coordinate       |
+/// | `z`                         | elevation          |
+/// | `intensity` or `i`          | intensity value    |
+/// | `class` or `c`              | classification     |
+/// | `return` or `rn`            | return number      |
+/// | `num_returns` or `nr`       | number of returns  |
+/// | `time` or `gps_time`        | GPS time           |
+/// | `scan_angle` or `sa`        | scan angle         |
+/// | `red` or `r`                | red                |
+/// | `green` or `g`              | green              |
+/// | `blue` or `b`               | blue               |
+/// | `user_data` or `ud`         | user data          |
+/// | `point_source_id` or `psid` | point source ID    |
+/// | `skip` or `-`               | ignored column     |
+///
+/// The `x`, `y`, and `z` fields must always be specified. If the `return` field is used, the `num_returns` field
+/// must also be specified; otherwise each point is stored as the first of one return. If any of the colour fields
+/// are used, all three of `red`, `green`, and `blue` must be specified. The output LAS point format is determined
+/// by the presence of GPS time and colour fields. Examples of valid column mappings include:
+///
+/// ```text
 /// 'x,y,z,i'
 /// 'x,y,z,i,rn,nr'
-/// 'x,y,z,i,c,rn,nr,sa'
-/// 'z,x,y,rn,nr'
+/// 'x,y,z,intensity,class,time'
+/// 'skip,x,y,z,class'
 /// 'x,y,z,i,rn,nr,r,g,b'
 /// ```
-/// 
+///
+/// The columns may be separated by commas (the default), tabs, spaces, semicolons, or any other single character,
+/// as specified using the `--delimiter` parameter. A number of lines at the top of each file, e.g. a metadata
+/// block, may be skipped (`--skip_lines`). Lines for which the x field is not numeric, such as a header row of
+/// column names, are also ignored.
+///
+/// The point coordinates are stored in LAS files as integers, which are scaled and offset to recover the original
+/// values. The scale factor (`--scale`) therefore determines the precision of the stored coordinates. It may be
+/// specified as a single value used for each of the x, y, and z coordinates, or as three comma-separated values.
+/// The default scale factor is 0.001, i.e. millimetre precision for projected coordinates. By default, the
+/// coordinate offsets are set to the minimum x, y, and z values of the points; the `--offset` parameter can be
+/// used to specify the offsets (x,y,z) instead, e.g. to share a common origin among the tiles of a delivery.
+///
 /// Use the `LasToAscii` tool to convert a LAS file into a text file containing LiDAR point data.
-/// 
+///
 /// # See Also
 /// `LasToAscii`
 pub struct AsciiToLas {
@@ -70,7 +87,8 @@ impl AsciiToLas {
         // public constructor
         let name = "AsciiToLas".to_string();
         let toolbox = "LiDAR Tools".to_string();
-        let description = "Converts one or more ASCII files containing LiDAR points into LAS files.".to_string();
+        let description =
+            "Converts one or more ASCII files containing LiDAR points into LAS files.".to_string();
 
         let mut parameters = vec![];
         parameters.push(ToolParameter {
@@ -83,14 +101,50 @@ impl AsciiToLas {
         });
 
         parameters.push(ToolParameter {
-            name: "Pattern".to_owned(),
-            flags: vec!["--pattern".to_owned()],
-            description: "Input field pattern.".to_owned(),
+            name: "Column Mapping".to_owned(),
+            flags: vec!["--columns".to_owned(), "--pattern".to_owned()],
+            description: "Comma-separated list of the point fields stored in each column, e.g. 'x,y,z,intensity,class,time'.".to_owned(),
             parameter_type: ParameterType::String,
-            default_value: None,
+            default_value: Some("x,y,z".to_owned()),
             optional: false,
         });
 
+        parameters.push(ToolParameter {
+            name: "Delimiter".to_owned(),
+            flags: vec!["--delimiter".to_owned()],
+            description: "Column delimiter; options include 'comma', 'tab', 'space', 'semicolon', or a single character.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: Some("comma".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Lines to Skip".to_owned(),
+            flags: vec!["--skip_lines".to_owned()],
+            description: "Number of lines at the top of each file to skip.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Coordinate Scale Factor".to_owned(),
+            flags: vec!["--scale".to_owned()],
+            description: "LAS coordinate scale factor, either a single value or x,y,z values.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: Some("0.001".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Coordinate Offsets (x,y,z)".to_owned(),
+            flags: vec!["--offset".to_owned()],
+            description: "LAS coordinate offsets (x,y,z). By default, the minimum coordinates of the points are used.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
         parameters.push(ToolParameter {
             name: "Well-known-text (WKT) string".to_owned(),
             flags: vec!["--wkt".to_owned()],
@@ -111,7 +165,7 @@ impl AsciiToLas {
         if e.contains(".exe") {
             short_exe += ".exe";
         }
-        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=\"file1.las, file2.las, file3.las\" -o=outfile.las\"", short_exe, name).replace("*", &sep);
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=\"file1.csv, file2.csv\" --columns='x,y,z,intensity,class,time' --delimiter=tab --skip_lines=1 --scale=0.01", short_exe, name).replace("*", &sep);
 
         AsciiToLas {
             name: name,
@@ -137,17 +191,10 @@ impl WhiteboxTool for AsciiToLas {
     }
 
     fn get_tool_parameters(&self) -> String {
-        let mut s = String::from("{\"parameters\": [");
-        for i in 0..self.parameters.len() {
-            if i < self.parameters.len() - 1 {
-                s.push_str(&(self.parameters[i].to_string()));
-                s.push_str(",");
-            } else {
-                s.push_str(&(self.parameters[i].to_string()));
-            }
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
         }
-        s.push_str("]}");
-        s
     }
 
     fn get_example_usage(&self) -> String {
@@ -165,7 +212,11 @@ impl WhiteboxTool for AsciiToLas {
         verbose: bool,
     ) -> Result<(), Error> {
         let mut input_files: String = String::new();
-        let mut pattern_string = String::new();
+        let mut column_string = String::new();
+        let mut delimiter_string = String::from("comma");
+        let mut skip_lines = 0usize;
+        let mut scale_string = String::from("0.001");
+        let mut offset_string = String::new();
         let mut wkt_string = String::new();
 
         // read the arguments
@@ -191,17 +242,47 @@ impl WhiteboxTool for AsciiToLas {
                 } else {
                     args[i + 1].to_string()
                 };
-            } else if flag_val == "-pattern" {
-                pattern_string = if keyval {
+            } else if flag_val == "-columns" || flag_val == "-pattern" {
+                column_string = if keyval {
                     vec[1].to_string().to_lowercase()
                 } else {
                     args[i + 1].to_string().to_lowercase()
                 };
+            } else if flag_val == "-delimiter" {
+                delimiter_string = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-skip_lines" {
+                let val = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                skip_lines = val.trim().parse::<usize>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        "The number of lines to skip (--skip_lines) must be a non-negative integer.",
+                    )
+                })?;
+            } else if flag_val == "-scale" {
+                scale_string = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-offset" {
+                offset_string = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
             } else if flag_val == "-wkt" {
                 wkt_string = if keyval {
-                    vec[1].to_string().to_lowercase()
+                    vec[1].to_string()
                 } else {
-                    args[i + 1].to_string().to_lowercase()
+                    args[i + 1].to_string()
                 };
             }
         }
@@ -212,89 +293,75 @@ impl WhiteboxTool for AsciiToLas {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = std::path::MAIN_SEPARATOR;
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
         let start = Instant::now();
 
-        if pattern_string.is_empty() {
+        if column_string.trim().is_empty() {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
-                "Error: The interpretation pattern string (e.g. 'x,y,z,i,c,rn,nr,sa') was not specified.",
+                "Error: The column mapping (e.g. 'x,y,z,intensity,class,time') was not specified.",
             ));
         }
-
-        // Do some quality control on the pattern
-        if !pattern_string.contains("x") ||
-            !pattern_string.contains("y") ||
-            !pattern_string.contains("z") {
+        let columns = parse_columns(&column_string)?;
+        let num_fields = columns.len();
+        let x_field = columns
+            .iter()
+            .position(|c| *c == AsciiColumn::X)
+            .unwrap_or(0);
+        let has_column = |c: AsciiColumn| columns.contains(&c);
+
+        // Do some quality control on the column mapping
+        if !has_column(AsciiColumn::X) || !has_column(AsciiColumn::Y) || !has_column(AsciiColumn::Z)
+        {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
-                "Error: The interpretation pattern string (e.g. 'x,y,z,i,c,rn,nr,sa') must contain x, y, and z fields.",
+                "Error: The column mapping (e.g. 'x,y,z,intensity,class,time') must contain x, y, and z fields.",
             ));
         }
 
-        if (pattern_string.contains("rn") && !pattern_string.contains("nr")) ||
-            (pattern_string.contains("nr") && !pattern_string.contains("rn")) {
+        let has_returns = has_column(AsciiColumn::ReturnNumber);
+        if has_returns != has_column(AsciiColumn::NumReturns) {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
-                "Error: If the interpretation pattern string (e.g. 'x,y,z,i,rn,nr,time') contains a 'rn' field it must also contain a 'nr' field and vice versa.",
+                "Error: If the column mapping contains a return number ('rn') field it must also contain a number of returns ('nr') field and vice versa.",
             ));
         }
 
-        let pattern_vec = pattern_string.trim().split(",").collect::<Vec<&str>>();
-        let num_fields = pattern_vec.len();
-
-        // convert the string pattern to numeric for easier look-up
-        let mut pattern_numeric = vec![0usize; num_fields];
-        let mut pattern_has_time = false;
-        let mut pattern_has_r = false;
-        let mut pattern_has_g = false;
-        let mut pattern_has_b = false;
-        for a in 0..num_fields {
-            match pattern_vec[a] {
-                "x" => pattern_numeric[a] = 0usize,
-                "y" => pattern_numeric[a] = 1usize,
-                "z" => pattern_numeric[a] = 2usize,
-                "i" => pattern_numeric[a] = 3usize,
-                "c" => pattern_numeric[a] = 4usize,
-                "rn" => pattern_numeric[a] = 5usize,
-                "nr" => pattern_numeric[a] = 6usize,
-                "time" => {
-                    pattern_has_time = true;
-                    pattern_numeric[a] = 7usize
-                },
-                "sa" => pattern_numeric[a] = 8usize,
-                "r" => {
-                    pattern_has_r = true;
-                    pattern_numeric[a] = 9usize
-                },
-                "g" => {
-                    pattern_has_g = true;
-                    pattern_numeric[a] = 10usize
-                },
-                "b" => {
-                    pattern_has_b = true;
-                    pattern_numeric[a] = 11usize
-                },
-                _ => println!("Unrecognized pattern {}", pattern_vec[a]),
-            }
+        // if the mapping contains any of 'r', 'g', or 'b', it must also contain all of 'r', 'g', and 'b'.
+        let num_clr_fields = [AsciiColumn::Red, AsciiColumn::Green, AsciiColumn::Blue]
+            .iter()
+            .filter(|c| has_column(**c))
+            .count();
+        if num_clr_fields != 0 && num_clr_fields != 3 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "If any of r, g, or b are provided, each of r, g, and b must be provided.",
+            ));
         }
+        let has_clr = num_clr_fields == 3;
+        let has_time = has_column(AsciiColumn::Time);
+
+        let delimiter = parse_delimiter(&delimiter_string)?;
 
-        // if the pattern contain any of 'r', 'g', or 'b', it must also contain all of 'r', 'g', and 'b'.
-        let mut pattern_has_clr = false;
-        if pattern_has_r && pattern_has_g && pattern_has_b {
-            pattern_has_clr = true;
-        } else if pattern_has_r || pattern_has_g || pattern_has_b {
-            // you can't have one and not all
+        let scale = parse_coordinate_values(&scale_string, true, "scale factor (--scale)")?;
+        if scale.iter().any(|v| v.is_nan() || *v <= 0f64) {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
-                "If any of r, g, or b are provided, each of r, g, and b must be provided.",
+                "The coordinate scale factor (--scale) must be greater than zero.",
             ));
         }
-        
+        let offset = if !offset_string.trim().is_empty() {
+            Some(parse_coordinate_values(
+                &offset_string,
+                false,
+                "offsets (--offset)",
+            )?)
+        } else {
+            None
+        };
+
         let mut cmd = input_files.split(";");
         let mut files_vec = cmd.collect::<Vec<&str>>();
         if files_vec.len() == 1 {
@@ -306,22 +373,35 @@ impl WhiteboxTool for AsciiToLas {
         for value in files_vec {
             if !value.trim().is_empty() {
                 let mut input_file = value.trim().to_owned();
-                if !input_file.contains(sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
 
                 // Initialize the output LAS file
-                let file_extension = get_file_extension(&input_file);
-                let output_file = input_file.replace(&format!(".{}", file_extension), ".las");
+                let output_file = path::Path::new(&input_file)
+                    .with_extension("las")
+                    .to_str()
+                    .unwrap_or_default()
+                    .to_string();
                 let mut output = LasFile::new(&output_file, "w")?;
-                let mut header: LasHeader = Default::default();
-                pattern_has_time = true;
-                if pattern_has_time {
-                    header.point_format = 1;
-                } else {
-                    header.point_format = 0;
+                let point_format = match (has_time, has_clr) {
+                    (false, false) => 0,
+                    (true, false) => 1,
+                    (false, true) => 2,
+                    (true, true) => 3,
+                };
+                output.add_header(LasHeader {
+                    project_id_used: true,
+                    point_format: point_format,
+                    ..Default::default()
+                });
+                output.header.x_scale_factor = scale[0];
+                output.header.y_scale_factor = scale[1];
+                output.header.z_scale_factor = scale[2];
+                if let Some(offset) = &offset {
+                    output.header.x_offset = offset[0];
+                    output.header.y_offset = offset[1];
+                    output.header.z_offset = offset[2];
+                    output.use_header_offsets = true;
                 }
-                output.add_header(header);
                 if !wkt_string.is_empty() {
                     output.wkt = wkt_string.clone();
                 }
@@ -332,115 +412,126 @@ impl WhiteboxTool for AsciiToLas {
                 let f = File::open(input_file.clone())?;
                 let f = BufReader::new(f);
 
-                let mut point_num = 0;
-                for line in f.lines() {
+                for (line_num, line) in f.lines().enumerate().skip(skip_lines) {
                     let line_unwrapped = line?;
-                    let line_data = line_unwrapped.split(",").collect::<Vec<&str>>();
-                    if line_data.len() >= num_fields {
-                        // check to see if the first field contains a number; if not, it's likely a header row and should be ignored
-                        let is_num = line_data[0].parse::<f64>().is_ok();
-                        if is_num {
-                            let mut point_data: PointData = Default::default();
-                            let mut gps_time = 0f64;
-                            let mut clr_data: ColourData = Default::default();
-                            // now convert each of the specified fields based on the input pattern
-                            for a in 0..num_fields {
-                                match pattern_numeric[a] {
-                                    0usize => point_data.x = line_data[a].parse::<f64>()?,
-                                    1usize => point_data.y = line_data[a].parse::<f64>()?,
-                                    2usize => point_data.z = line_data[a].parse::<f64>()?,
-                                    3usize => point_data.intensity = line_data[a].parse::<u16>()?,
-                                    4usize => point_data.set_classification(line_data[a].parse::<u8>()?,
-                                    5usize => point_data.set_return_number(line_data[a].parse::<u8>()?,
-                                    6usize => point_data.set_number_of_returns(line_data[a].parse::<u8>()?,
-                                    7usize => gps_time = line_data[a].parse::<f64>().unwrap(),
-                                    8usize => point_data.scan_angle = line_data[a].parse::<i16>()?,
-                                    9usize => clr_data.red = line_data[a].parse::<u16>()?,
-                                    10usize => clr_data.green = line_data[a].parse::<u16>()?,
-                                    11usize => clr_data.blue = line_data[a].parse::<u16>()?,
-                                    _ => println!("unrecognized pattern"),
-                                }
+                    let line_data = split_line(&line_unwrapped, delimiter);
+                    // check to see if the x field contains a number; if not, it's likely a header row
+                    // or a blank line and should be ignored
+                    match line_data.get(x_field) {
+                        Some(v) if v.parse::<f64>().is_ok() => {}
+                        _ => continue,
+                    }
+                    if line_data.len() < num_fields {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!(
+                                "Line {} of {} contains {} fields but the column mapping specifies {}.",
+                                line_num + 1,
+                                input_file,
+                                line_data.len(),
+                                num_fields
+                            ),
+                        ));
+                    }
+                    let mut point_data: PointData = Default::default();
+                    let mut gps_time = 0f64;
+                    let mut clr_data: ColourData = Default::default();
+                    if !has_returns {
+                        point_data.set_return_number(1);
+                        point_data.set_number_of_returns(1);
+                    }
+                    // now convert each of the specified fields based on the column mapping
+                    for a in 0..num_fields {
+                        if columns[a] == AsciiColumn::Skip {
+                            continue;
+                        }
+                        let val = line_data[a].parse::<f64>().map_err(|_| {
+                            Error::new(
+                                ErrorKind::InvalidData,
+                                format!(
+                                    "Unable to parse the {} value '{}' on line {} of {}.",
+                                    columns[a].header_name(),
+                                    line_data[a],
+                                    line_num + 1,
+                                    input_file
+                                ),
+                            )
+                        })?;
+                        match columns[a] {
+                            AsciiColumn::X => point_data.x = val,
+                            AsciiColumn::Y => point_data.y = val,
+                            AsciiColumn::Z => point_data.z = val,
+                            AsciiColumn::Intensity => {
+                                point_data.intensity = val.round().clamp(0f64, 65535f64) as u16
                             }
-
-                            point_num += 1;
-                            if point_num == 1 || point_num == 2 || point_num == 349528 {
-                                println!("Point {}: {:?}", point_num, point_data);
+                            AsciiColumn::Class => point_data
+                                .set_classification(val.round().clamp(0f64, 255f64) as u8),
+                            AsciiColumn::ReturnNumber => point_data
+                                .set_return_number(val.round().clamp(0f64, 7f64) as u8),
+                            AsciiColumn::NumReturns => point_data
+                                .set_number_of_returns(val.round().clamp(0f64, 7f64) as u8),
+                            AsciiColumn::Time => gps_time = val,
+                            AsciiColumn::ScanAngle => {
+                                point_data.scan_angle = val.round().clamp(-32768f64, 32767f64) as i16
                             }
-                            if !pattern_has_time && !pattern_has_clr {
-                                output.add_point_record(LidarPointRecord::PointRecord0 {
-                                    point_data: point_data,
-                                });
-                            } else if pattern_has_time && !pattern_has_clr {
-                                output.add_point_record(LidarPointRecord::PointRecord1 {
-                                    point_data: point_data,
-                                    gps_data: gps_time,
-                                });
-                            } else if !pattern_has_time && pattern_has_clr {
-                                output.add_point_record(LidarPointRecord::PointRecord2 {
-                                    point_data: point_data,
-                                    colour_data: clr_data,
-                                });
-                            } else { // if pattern_has_time && pattern_has_clr {
-                                output.add_point_record(LidarPointRecord::PointRecord3 {
-                                    point_data: point_data,
-                                    gps_data: gps_time,
-                                    colour_data: clr_data,
-                                });
+                            AsciiColumn::Red => {
+                                clr_data.red = val.round().clamp(0f64, 65535f64) as u16
                             }
+                            AsciiColumn::Green => {
+                                clr_data.green = val.round().clamp(0f64, 65535f64) as u16
+                            }
+                            AsciiColumn::Blue => {
+                                clr_data.blue = val.round().clamp(0f64, 65535f64) as u16
+                            }
+                            AsciiColumn::UserData => {
+                                point_data.user_data = val.round().clamp(0f64, 255f64) as u8
+                            }
+                            AsciiColumn::PointSourceId => {
+                                point_data.point_source_id =
+                                    val.round().clamp(0f64, 65535f64) as u16
+                            }
+                            AsciiColumn::Skip => {}
                         }
-                    } // else ignore the line.
+                    }
+
+                    output.add_point_record(match (has_time, has_clr) {
+                        (false, false) => LidarPointRecord::PointRecord0 {
+                            point_data: point_data,
+                        },
+                        (true, false) => LidarPointRecord::PointRecord1 {
+                            point_data: point_data,
+                            gps_data: gps_time,
+                        },
+                        (false, true) => LidarPointRecord::PointRecord2 {
+                            point_data: point_data,
+                            colour_data: clr_data,
+                        },
+                        (true, true) => LidarPointRecord::PointRecord3 {
+                            point_data: point_data,
+                            gps_data: gps_time,
+                            colour_data: clr_data,
+                        },
+                    });
                 }
 
-                // let mut vlr1: Vlr = Default::default();
-                // vlr1.reserved = 0u16;
-                // vlr1.user_id = String::from("LASF_Projection");
-                // vlr1.record_id = 34735u16;
-                // vlr1.description = String::from("GeoTiff Projection Keys");
-                // let raw_data = vec![1u16, 1, 0, 23, 1024, 0, 1, 1, 2048, 0, 1, 4269, 2049, 34737, 24, 64, 2050, 0, 1, 6269, 2051, 0, 1, 8901, 2054, 0, 1, 9102, 2055, 34736, 1, 9, 2056, 0, 1, 7019, 2057, 34736, 1, 6, 2059, 34736, 1, 7, 2061, 34736, 1, 8, 3072, 0, 1, 32145, 3073, 34737, 38, 0, 3075, 0, 1, 1, 3076, 0, 1, 9001, 3077, 34736, 1, 5, 3081, 34736, 1, 4, 3082, 34736, 1, 0, 3083, 34736, 1, 1, 3088, 34736, 1, 2, 3092, 34736, 1, 3, 4097, 34737, 26, 38, 4099, 0, 1, 9001];
-                // let mut v8: Vec<u8> = Vec::new();
-                // for n in raw_data {
-                //     v8.write_u16::<LittleEndian>(n).unwrap();
-                // }
-                // vlr1.binary_data = v8;
-                // vlr1.record_length_after_header = vlr1.binary_data.len() as u16; //192;
-                // println!("vlr1 (192): {} {}", vlr1.binary_data.len(), vlr1.record_length_after_header);
-                // output.add_vlr(vlr1);
-
-                // let mut vlr2: Vlr = Default::default();
-                // vlr2.reserved = 0u16;
-                // vlr2.user_id = String::from("LASF_Projection");
-                // vlr2.record_id = 34736u16;
-                // vlr2.description = String::from("GeoTiff double parameters");
-                // let raw_data = vec![500000f64, 0f64, -72.5f64, 0.9999642857142857f64, 42.5f64, 1f64, 6378137f64, 298.257222101f64, 0f64, 0.017453292519943295f64];
-                // let mut v8: Vec<u8> = Vec::new();
-                // for n in raw_data {
-                //     v8.write_f64::<LittleEndian>(n).unwrap();
-                // }
-                // vlr2.binary_data = v8;
-                // vlr2.record_length_after_header = vlr2.binary_data.len() as u16; //80;
-                // println!("vlr2 (80): {} {}", vlr2.binary_data.len(), vlr2.record_length_after_header);
-                // output.add_vlr(vlr2);
-
-                // let mut vlr3: Vlr = Default::default();
-                // vlr3.reserved = 0u16;
-                // vlr3.user_id = String::from("LASF_Projection");
-                // vlr3.record_id = 34737u16;
-                // vlr3.description = String::from("GeoTiff ASCII parameters");
-                // vlr3.binary_data = "NAD_1983_StatePlane_Vermont_FIPS_4400|NAVD88 - Geoid09 (Meters)|GCS_North_American_1983|".as_bytes().to_vec();
-                // vlr3.record_length_after_header = vlr3.binary_data.len() as u16; //89;
-                // println!("vlr3 (89): {} {}", vlr3.binary_data.len(), vlr3.record_length_after_header);
-                // output.add_vlr(vlr3);
-    
                 if verbose {
-                    println!("Writing output LAS file {}...", output.get_short_filename());
+                    println!(
+                        "Writing output LAS file {} ({} points)...",
+                        output.get_short_filename(),
+                        output.header.number_of_points
+                    );
                 }
                 let _ = match output.write() {
-                    Ok(_) => println!("Complete!"),
+                    Ok(_) => {
+                        if verbose {
+                            println!("Complete!")
+                        }
+                    }
                     Err(e) => println!("error while writing: {:?}", e),
                 };
             }
             if verbose {
-                progress = (100.0_f64 * (i + 1) as f64 / num_files as f64) as usize;
+                progress = (100.0_f64 * i as f64 / num_files as f64) as usize;
                 if progress != old_progress {
                     println!("Progress: {}%", progress);
                     old_progress = progress;
@@ -458,10 +549,29 @@ impl WhiteboxTool for AsciiToLas {
     }
 }
 
-/// Returns the file extension.
-pub fn get_file_extension(file_name: &str) -> String {
-    let file_path = std::path::Path::new(file_name);
-    let extension = file_path.extension().unwrap();
-    let e = extension.to_str().unwrap();
-    e.to_string()
-}
\ No newline at end of file
+/// Parses a list of x, y, and z values. If `allow_single` is true, a single value may be given,
+/// which is used for each of the coordinates.
+fn parse_coordinate_values(s: &str, allow_single: bool, name: &str) -> Result<[f64; 3], Error> {
+    let values = s
+        .split(',')
+        .map(|v| v.trim().parse::<f64>())
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unable to parse the coordinate {}.", name),
+            )
+        })?;
+    match values.len() {
+        1 if allow_single => Ok([values[0], values[0], values[0]]),
+        3 => Ok([values[0], values[1], values[2]]),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "The coordinate {} must be specified as {}x,y,z values.",
+                name,
+                if allow_single { "a single value or " } else { "" }
+            ),
+        )),
+    }
+}
@@ -12,7 +12,6 @@ use crate::tools::*;
 use num_cpus;
 use std::env;
 use std::f64;
-use std::fs;
 use std::io::{Error, ErrorKind};
 use std::path;
 use std::sync::mpsc;
@@ -71,6 +70,8 @@ impl LidarBlockMaximum {
         //     optional: true
         // });
 
+        parameters.extend(InputFileFilter::parameters());
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -130,6 +131,10 @@ impl WhiteboxTool for LidarBlockMaximum {
         self.toolbox.clone()
     }
 
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_dir}{input_stem}.tif".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -140,6 +145,7 @@ impl WhiteboxTool for LidarBlockMaximum {
         let mut output_file: String = "".to_string();
         let mut grid_res: f64 = 1.0;
         let mut palette = "default".to_string();
+        let mut file_filter = InputFileFilter::default();
 
         // read the arguments
         if args.len() == 0 {
@@ -182,6 +188,14 @@ impl WhiteboxTool for LidarBlockMaximum {
                 } else {
                     args[i + 1].to_string()
                 };
+            } else if flag_val == "-include" {
+                file_filter.add_include(if keyval { vec[1] } else { &args[i + 1] });
+            } else if flag_val == "-exclude" {
+                file_filter.add_exclude(if keyval { vec[1] } else { &args[i + 1] });
+            } else if flag_val == "-recursive" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    file_filter.recursive = true;
+                }
             }
         }
 
@@ -210,29 +224,20 @@ impl WhiteboxTool for LidarBlockMaximum {
             //         }
             //     }
             // }
+            // the output file may be a template used to name the output of each input file,
+            // e.g. '{input_stem}_dem.tif'
+            let output_template = if is_output_template(&output_file) {
+                output_file.clone()
+            } else {
+                self.get_default_output_template().unwrap()
+            };
             if std::path::Path::new(&working_directory).is_dir() {
-                for entry in fs::read_dir(working_directory.clone())? {
-                    let s = entry?
-                    .path()
-                    .into_os_string()
-                    .to_str()
-                    .expect("Error reading path string")
-                    .to_string();
-                    if s.to_lowercase().ends_with(".las") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".las", ".tif")
-                                .replace(".LAS", ".tif"),
-                        )
-                    } else if s.to_lowercase().ends_with(".zip") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".zip", ".tif")
-                                .replace(".ZIP", ".tif"),
-                        )
-                    }
+                for s in file_filter.find_lidar_files(working_directory)? {
+                    let mut out_file =
+                        expand_output_template(&output_template, &s, &self.get_tool_name());
+                    out_file = resolve_path(&out_file, working_directory);
+                    inputs.push(s);
+                    outputs.push(out_file);
                 }
             } else {
                 return Err(Error::new(
@@ -243,14 +248,13 @@ impl WhiteboxTool for LidarBlockMaximum {
         } else {
             inputs.push(input_file.clone());
             if output_file.is_empty() {
-                output_file = input_file
-                    .clone()
-                    .replace(".las", ".tif")
-                    .replace(".LAS", ".tif");
-            }
-            if !output_file.contains(path::MAIN_SEPARATOR) && !output_file.contains("/") {
-                output_file = format!("{}{}", working_directory, output_file);
+                output_file = expand_output_template(
+                    &self.get_default_output_template().unwrap(),
+                    &input_file,
+                    &self.get_tool_name(),
+                );
             }
+            output_file = resolve_path(&output_file, working_directory);
             outputs.push(output_file);
         }
 
@@ -273,12 +277,8 @@ impl WhiteboxTool for LidarBlockMaximum {
                 );
             }
 
-            if !input_file.contains(path::MAIN_SEPARATOR) {
-                input_file = format!("{}{}", working_directory, input_file);
-            }
-            if !output_file.contains(path::MAIN_SEPARATOR) {
-                output_file = format!("{}{}", working_directory, output_file);
-            }
+            input_file = resolve_path(&input_file, working_directory);
+            output_file = resolve_path(&output_file, working_directory);
 
             if verbose && inputs.len() == 1 {
                 println!("Reading input LAS file...");
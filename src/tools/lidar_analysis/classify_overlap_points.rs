@@ -198,17 +198,11 @@ impl WhiteboxTool for ClassifyOverlapPoints {
 
         let start = Instant::now();
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
@@ -440,6 +434,7 @@ impl WhiteboxTool for ClassifyOverlapPoints {
             for i in 0..n_points {
                 if !filtered[i] {
                     output.add_point_record(input.get_record(i));
+                    output.add_extra_bytes(input.get_extra_bytes(i));
                 }
                 if verbose {
                     progress = (100.0_f64 * i as f64 / num_points) as usize;
@@ -454,6 +449,7 @@ impl WhiteboxTool for ClassifyOverlapPoints {
             for i in 0..n_points {
                 if !overlapping[i] {
                     output.add_point_record(input.get_record(i));
+                    output.add_extra_bytes(input.get_extra_bytes(i));
                 } else {
                     let pr = input.get_record(i);
                     // pr.point_data.set_overlap(true); // change to this when 1.4 output is supported
@@ -585,6 +581,7 @@ impl WhiteboxTool for ClassifyOverlapPoints {
                         }
                     }
                     output.add_point_record(pr2);
+                    output.add_extra_bytes(input.get_extra_bytes(i));
                 }
                 if verbose {
                     progress = (100.0_f64 * i as f64 / num_points) as usize;
@@ -0,0 +1,487 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::structures::BoundingBox;
+use crate::tools::*;
+use crate::vector::{ShapeType, Shapefile};
+use num_cpus;
+use std::collections::HashMap;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool extracts all of the points in a LiDAR file (`--input`) that are within a specified
+/// horizontal distance (`--distance`) of a set of polylines (`--lines`), such as road, river, or
+/// railway centrelines, or transmission line routes, i.e. it clips the point cloud to a corridor,
+/// or swath, centred on the lines. The corridor has rounded ends and the distance is measured to
+/// the nearest line segment of any feature, in the horizontal units of the point cloud.
+///
+/// When the `--chainage` flag is specified, three attributes are added to the extra bytes of each
+/// output point, describing its position relative to the nearest line:
+///
+/// | Attribute  | Meaning                                                                   |
+/// | :--------- | :------------------------------------------------------------------------ |
+/// | `chainage` | Distance along the line, from its first vertex, to the nearest line point |
+/// | `offset`   | Signed perpendicular distance from the line; positive on the left         |
+/// | `route_id` | Feature number (starting from 1) of the nearest line                      |
+///
+/// Chainage is measured continuously through the parts of multi-part features, in the order in
+/// which they are stored. These attributes make it straightforward to create long profiles and
+/// cross-sections of the corridor, e.g. with the `chainage` and `z` values. Any existing extra
+/// bytes attributes are retained.
+///
+/// # See Also
+/// `ClipLidarToPolygon`, `LidarTileByPolygon`, `ErasePolygonFromLidar`
+pub struct ClipLidarToCorridor {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ClipLidarToCorridor {
+    /// public constructor
+    pub fn new() -> ClipLidarToCorridor {
+        let name = "ClipLidarToCorridor".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Clips a LiDAR point cloud to a corridor of a specified width along vector polylines."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Vector Lines File".to_owned(),
+            flags: vec!["--lines".to_owned()],
+            description: "Input vector polylines file, e.g. centrelines.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Corridor Half-Width".to_owned(),
+            flags: vec!["--distance".to_owned()],
+            description: "Maximum horizontal distance between the extracted points and the lines.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Add chainage attributes?".to_owned(),
+            flags: vec!["--chainage".to_owned()],
+            description: "Flag indicating whether chainage, offset, and route ID extra bytes attributes are added to the output points.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='data.las' --lines='centreline.shp' -o='corridor.las' --distance=25.0 --chainage", short_exe, name).replace("*", &sep);
+
+        ClipLidarToCorridor {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ClipLidarToCorridor {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![("--distance".to_string(), ParameterConstraint::GreaterThan(0.0))]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+        let mut lines_file: String = "".to_string();
+        let mut output_file: String = "".to_string();
+        let mut distance = f64::NAN;
+        let mut add_chainage = false;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-lines" {
+                lines_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-distance" {
+                distance = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-chainage" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    add_chainage = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if distance.is_nan() || distance <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The corridor half-width (--distance) must be greater than zero.",
+            ));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        lines_file = resolve_path(&lines_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    format!("Error reading file {}: {}", input_file, err),
+                ))
+            }
+        };
+
+        let lines = Shapefile::read(&lines_file)?;
+
+        let start = Instant::now();
+
+        // make sure the input vector file is of polyline type
+        if lines.header.shape_type.base_shape_type() != ShapeType::PolyLine {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input vector data must be of polyline base shape type.",
+            ));
+        }
+
+        // gather the line segments overlapping the point cloud
+        let lidar_bb = BoundingBox::new(
+            input.header.min_x - distance,
+            input.header.max_x + distance,
+            input.header.min_y - distance,
+            input.header.max_y + distance,
+        );
+        let mut segments: Vec<Segment> = vec![];
+        for record_num in 0..lines.num_records {
+            let record = lines.get_record(record_num);
+            let mut chainage = 0f64;
+            for part in 0..record.num_parts as usize {
+                let start_point_in_part = record.parts[part] as usize;
+                let end_point_in_part = if part < record.num_parts as usize - 1 {
+                    record.parts[part + 1] as usize - 1
+                } else {
+                    record.num_points as usize - 1
+                };
+                for i in start_point_in_part..end_point_in_part {
+                    let (p1, p2) = (record.points[i], record.points[i + 1]);
+                    let length = p1.distance(&p2);
+                    let segment_bb = BoundingBox::new(
+                        p1.x.min(p2.x),
+                        p1.x.max(p2.x),
+                        p1.y.min(p2.y),
+                        p1.y.max(p2.y),
+                    );
+                    if segment_bb.overlaps(lidar_bb) {
+                        segments.push(Segment {
+                            x1: p1.x,
+                            y1: p1.y,
+                            x2: p2.x,
+                            y2: p2.y,
+                            chainage,
+                            route: record_num as u32 + 1,
+                        });
+                    }
+                    chainage += length;
+                }
+            }
+        }
+
+        // bin the segments into a coarse grid, such that the segments within the corridor distance
+        // of a point are all contained in the point's bin
+        let mean_length = if segments.is_empty() {
+            distance
+        } else {
+            segments
+                .iter()
+                .map(|s| (s.x2 - s.x1).hypot(s.y2 - s.y1))
+                .sum::<f64>()
+                / segments.len() as f64
+        };
+        let bin_size = (2f64 * distance).max(mean_length);
+        let mut bins: HashMap<(isize, isize), Vec<usize>> = HashMap::new();
+        for (s, seg) in segments.iter().enumerate() {
+            let min_col = ((seg.x1.min(seg.x2) - distance) / bin_size).floor() as isize;
+            let max_col = ((seg.x1.max(seg.x2) + distance) / bin_size).floor() as isize;
+            let min_row = ((seg.y1.min(seg.y2) - distance) / bin_size).floor() as isize;
+            let max_row = ((seg.y1.max(seg.y2) + distance) / bin_size).floor() as isize;
+            for row in min_row..=max_row {
+                for col in min_col..=max_col {
+                    bins.entry((row, col)).or_insert(vec![]).push(s);
+                }
+            }
+        }
+
+        if verbose {
+            println!("Performing clip...")
+        };
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
+
+        let num_procs = num_cpus::get();
+        let input = Arc::new(input);
+        let segments = Arc::new(segments);
+        let bins = Arc::new(bins);
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let input = input.clone();
+            let segments = segments.clone();
+            let bins = bins.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for point_num in (0..n_points).filter(|point_num| point_num % num_procs == tid) {
+                    let p = input.get_point_info(point_num);
+                    let key = (
+                        (p.y / bin_size).floor() as isize,
+                        (p.x / bin_size).floor() as isize,
+                    );
+                    let mut nearest: Option<(f64, f64, f64, u32)> = None;
+                    if let Some(bin) = bins.get(&key) {
+                        for s in bin {
+                            let (dist, chainage, offset) = segments[*s].locate(p.x, p.y);
+                            if dist <= distance && (nearest.is_none() || dist < nearest.unwrap().0) {
+                                nearest = Some((dist, chainage, offset, segments[*s].route));
+                            }
+                        }
+                    }
+                    tx.send((point_num, nearest)).unwrap();
+                }
+            });
+        }
+
+        let mut locations: Vec<Option<(f64, f64, f64, u32)>> = vec![None; n_points];
+        for i in 0..n_points {
+            let data = rx.recv().unwrap();
+            locations[data.0] = data.1;
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        output.header.system_id = "EXTRACTION".to_string();
+
+        // the chainage attributes are appended to any existing extra bytes attributes
+        let mut new_descriptors = vec![];
+        let attributes_offset = input.get_extra_bytes_length();
+        if add_chainage {
+            let mut descriptors = input.extra_bytes_descriptors.clone();
+            new_descriptors = vec![
+                ExtraBytesDescriptor::new("chainage", 10u8, "distance along route"),
+                ExtraBytesDescriptor::new("offset", 9u8, "signed distance from route"),
+                ExtraBytesDescriptor::new("route_id", 5u8, "route feature number"),
+            ];
+            descriptors.extend(new_descriptors.iter().cloned());
+            output.set_extra_bytes_descriptors(descriptors);
+        }
+        let mut extra_bytes = vec![0u8; output.get_extra_bytes_length()];
+        let mut num_extracted = 0usize;
+        for (i, location) in locations.iter().enumerate() {
+            if let Some((_, chainage, offset, route)) = *location {
+                output.add_point_record(input.get_record(i));
+                if add_chainage {
+                    let input_bytes = input.get_extra_bytes(i);
+                    extra_bytes[0..input_bytes.len()].copy_from_slice(input_bytes);
+                    new_descriptors[0].set_value(chainage, &mut extra_bytes[attributes_offset..]);
+                    new_descriptors[1].set_value(offset, &mut extra_bytes[attributes_offset + 8..]);
+                    new_descriptors[2]
+                        .set_value(route as f64, &mut extra_bytes[attributes_offset + 12..]);
+                    output.add_extra_bytes(&extra_bytes);
+                } else {
+                    output.add_extra_bytes(input.get_extra_bytes(i));
+                }
+                num_extracted += 1;
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        if num_extracted > 0 {
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Complete!")
+                    }
+                }
+                Err(e) => println!("error while writing: {:?}", e),
+            };
+        } else {
+            println!("Warning: the file {} does not appear to contain any points within the corridor. No output file has been created.", output.get_short_filename());
+        }
+        if verbose {
+            println!("Number of points extracted: {}", num_extracted);
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A line segment, with the chainage of its first vertex along its route.
+struct Segment {
+    x1: f64,
+    y1: f64,
+    x2: f64,
+    y2: f64,
+    chainage: f64,
+    route: u32,
+}
+
+impl Segment {
+    /// Returns the distance from a point to the segment, the chainage of the nearest point on
+    /// the segment, and the signed offset of the point, which is positive to the left of the
+    /// segment.
+    fn locate(&self, x: f64, y: f64) -> (f64, f64, f64) {
+        let (dx, dy) = (self.x2 - self.x1, self.y2 - self.y1);
+        let length = dx.hypot(dy);
+        if length == 0f64 {
+            return ((x - self.x1).hypot(y - self.y1), self.chainage, 0f64);
+        }
+        let t = (((x - self.x1) * dx + (y - self.y1) * dy) / (length * length)).clamp(0f64, 1f64);
+        let (nx, ny) = (self.x1 + t * dx, self.y1 + t * dy);
+        let dist = (x - nx).hypot(y - ny);
+        let cross = dx * (y - self.y1) - dy * (x - self.x1);
+        let offset = if cross < 0f64 { -dist } else { dist };
+        (dist, self.chainage + t * length, offset)
+    }
+}
@@ -178,20 +178,12 @@ impl WhiteboxTool for ClipLidarToPolygon {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !polygons_file.contains(&sep) && !polygons_file.contains("/") {
-            polygons_file = format!("{}{}", working_directory, polygons_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        polygons_file = resolve_path(&polygons_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -327,6 +319,7 @@ impl WhiteboxTool for ClipLidarToPolygon {
             let data = rx.recv().unwrap();
             if data.0 {
                 output.add_point_record(input.get_record(data.1));
+                output.add_extra_bytes(input.get_extra_bytes(data.1));
             }
             if verbose {
                 progress = (100.0_f64 * i as f64 / num_points) as usize;
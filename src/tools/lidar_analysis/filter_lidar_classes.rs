@@ -149,6 +149,12 @@ impl WhiteboxTool for FilterLidarClasses {
         self.toolbox.clone()
     }
 
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--exclude_cls".to_string(), ParameterConstraint::ListRange(0.0, 255.0)),
+        ]
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -216,13 +222,8 @@ impl WhiteboxTool for FilterLidarClasses {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input LAS file...");
@@ -251,6 +252,7 @@ impl WhiteboxTool for FilterLidarClasses {
         for i in 0..n_points {
             if include_class_vals[input[i].classification() as usize] {
                 output.add_point_record(input.get_record(i));
+                output.add_extra_bytes(input.get_extra_bytes(i));
             }
             if verbose {
                 progress = (100.0_f64 * i as f64 / num_points) as i32;
@@ -155,13 +155,8 @@ impl WhiteboxTool for FindFlightlineEdgePoints {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input LAS file...");
@@ -185,6 +180,7 @@ impl WhiteboxTool for FindFlightlineEdgePoints {
         for i in 0..input.header.number_of_points as usize {
             if input.get_point_info(i).edge_of_flightline_flag() {
                 output.add_point_record(input.get_record(i));
+                output.add_extra_bytes(input.get_extra_bytes(i));
                 num_output_points += 1;
             }
             if verbose {
@@ -2,15 +2,12 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 19/06/2017
-Last Modified: 10/05/2019
+Last Modified: 16/10/2026
 License: MIT
-
-NOTES: This tool needs to be parallelized.
 */
 
 use crate::lidar::*;
 use crate::raster::*;
-use crate::structures::{DistanceMetric, FixedRadiusSearch2D};
 use crate::tools::*;
 use std::env;
 use std::f64;
@@ -18,6 +15,34 @@ use std::fs;
 use std::io::{Error, ErrorKind};
 use std::path;
 
+/// This tool can be used to map areas of overlapping flightlines in a LiDAR (LAS) file. The output
+/// raster (`--output`) contains the number of distinct flightlines (strips) that have points within
+/// each grid cell, with a grid resolution specified by `--resolution`. Grid cells that do not contain
+/// any points are assigned the NoData value.
+///
+/// Flightlines are identified either using the GPS time or the point source ID of each point. By
+/// default, the points within a grid cell are sorted by GPS time and a new flightline is started
+/// wherever two consecutive times differ by more than the time threshold (`--time_threshold`, in
+/// the same units as the GPS time, 15 by default). Nearby points that belong to the same flightline
+/// generally have GPS times that differ by several orders of magnitude less than this threshold,
+/// while points from neighbouring flightlines differ by several orders of magnitude more. If the
+/// `--use_point_source` flag is specified, or if the input file's point format does not contain GPS
+/// time data, each unique point source ID within a cell is treated as a separate flightline instead.
+/// This requires that the point source IDs were populated by the data provider.
+///
+/// Optionally, the user may specify an output LAS file (`--output_las`) in which the points of the
+/// non-dominant flightlines within each overlap cell are flagged. The dominant flightline in a cell
+/// is the one that contributes the most points to the cell. Flagged points have their overlap bit
+/// set and are assigned the LAS 1.1-1.3 'Overlap Points' class (12), since output LAS files are
+/// currently written using the legacy point formats, which cannot store the overlap bit. These
+/// points can subsequently be excluded from further processing using the `FilterLidarClasses` tool.
+///
+/// If the user does not specify an input file, all of the LAS files in the working directory will be
+/// processed. In this case, the output file names may be specified using templates (e.g.
+/// `--output_las={input_stem}_overlap.las`).
+///
+/// # See Also
+/// `ClassifyOverlapPoints`, `LidarPointDensity`, `FindFlightlineEdgePoints`, `FilterLidarClasses`
 pub struct FlightlineOverlap {
     name: String,
     description: String,
@@ -61,6 +86,35 @@ impl FlightlineOverlap {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Use Point Source ID?".to_owned(),
+            flags: vec!["--use_point_source".to_owned()],
+            description: "Identify flightlines using the point source ID rather than the GPS time?"
+                .to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "GPS Time Threshold".to_owned(),
+            flags: vec!["--time_threshold".to_owned()],
+            description: "Minimum difference in GPS time between points of different flightlines."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("15.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Overlap-Classified LiDAR File (optional)".to_owned(),
+            flags: vec!["--output_las".to_owned()],
+            description: "Optional output LiDAR file with the points of non-dominant flightlines in overlap areas flagged.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -73,7 +127,8 @@ impl FlightlineOverlap {
             short_exe += ".exe";
         }
         let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --resolution=2.0\"
-.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --resolution=5.0 --palette=light_quant.plt", short_exe, name).replace("*", &sep);
+.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --resolution=5.0 --palette=light_quant.plt
+.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --resolution=2.0 --use_point_source --output_las=overlap.las", short_exe, name).replace("*", &sep);
 
         FlightlineOverlap {
             name: name,
@@ -120,6 +175,23 @@ impl WhiteboxTool for FlightlineOverlap {
         self.toolbox.clone()
     }
 
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_dir}{input_stem}.tif".to_string())
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            (
+                "--resolution".to_string(),
+                ParameterConstraint::GreaterThan(0f64),
+            ),
+            (
+                "--time_threshold".to_string(),
+                ParameterConstraint::GreaterThan(0f64),
+            ),
+        ]
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -130,6 +202,9 @@ impl WhiteboxTool for FlightlineOverlap {
         let mut output_file: String = "".to_string();
         let mut grid_res: f64 = 1.0;
         let mut palette = "default".to_string();
+        let mut use_point_source = false;
+        let mut time_threshold = 15f64;
+        let mut output_las_file = String::new();
 
         // read the arguments
         if args.len() == 0 {
@@ -172,6 +247,22 @@ impl WhiteboxTool for FlightlineOverlap {
                 } else {
                     args[i + 1].to_string()
                 };
+            } else if flag_val == "-use_point_source" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    use_point_source = true;
+                }
+            } else if flag_val == "-time_threshold" {
+                time_threshold = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-output_las" {
+                output_las_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
             }
         }
 
@@ -179,6 +270,7 @@ impl WhiteboxTool for FlightlineOverlap {
 
         let mut inputs = vec![];
         let mut outputs = vec![];
+        let mut las_outputs = vec![];
         if input_file.is_empty() {
             if working_directory.is_empty() {
                 return Err(Error::new(ErrorKind::InvalidInput,
@@ -200,6 +292,19 @@ impl WhiteboxTool for FlightlineOverlap {
             //         }
             //     }
             // }
+            // the output file may be a template used to name the output of each input file,
+            // e.g. '{input_stem}_dem.tif'
+            let output_template = if is_output_template(&output_file) {
+                output_file.clone()
+            } else {
+                self.get_default_output_template().unwrap()
+            };
+            if !output_las_file.is_empty() && !is_output_template(&output_las_file) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "When processing all of the LAS files in the working directory, the output LAS file must be a file name template (e.g. '{input_stem}_overlap.las').",
+                ));
+            }
             if std::path::Path::new(&working_directory).is_dir() {
                 for entry in fs::read_dir(working_directory.clone())? {
                     let s = entry?
@@ -208,20 +313,19 @@ impl WhiteboxTool for FlightlineOverlap {
                     .to_str()
                     .expect("Error reading path string")
                     .to_string();
-                    if s.to_lowercase().ends_with(".las") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".las", ".tif")
-                                .replace(".LAS", ".tif"),
-                        )
-                    } else if s.to_lowercase().ends_with(".zip") {
+                    if s.to_lowercase().ends_with(".las") || s.to_lowercase().ends_with(".zip") {
+                        let mut out_file =
+                            expand_output_template(&output_template, &s, &self.get_tool_name());
+                        out_file = resolve_path(&out_file, working_directory);
+                        if !output_las_file.is_empty() {
+                            las_outputs.push(expand_output_template(
+                                &output_las_file,
+                                &s,
+                                &self.get_tool_name(),
+                            ));
+                        }
                         inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".zip", ".tif")
-                                .replace(".ZIP", ".tif"),
-                        )
+                        outputs.push(out_file);
                     }
                 }
             } else {
@@ -233,12 +337,16 @@ impl WhiteboxTool for FlightlineOverlap {
         } else {
             inputs.push(input_file.clone());
             if output_file.is_empty() {
-                output_file = input_file
-                    .clone()
-                    .replace(".las", ".tif")
-                    .replace(".LAS", ".tif");
+                output_file = expand_output_template(
+                    &self.get_default_output_template().unwrap(),
+                    &input_file,
+                    &self.get_tool_name(),
+                );
             }
             outputs.push(output_file);
+            if !output_las_file.is_empty() {
+                las_outputs.push(output_las_file.clone());
+            }
         }
 
         if verbose {
@@ -260,12 +368,8 @@ impl WhiteboxTool for FlightlineOverlap {
                 );
             }
 
-            if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-                input_file = format!("{}{}", working_directory, input_file);
-            }
-            if !output_file.contains(path::MAIN_SEPARATOR) && !output_file.contains("/") {
-                output_file = format!("{}{}", working_directory, output_file);
-            }
+            input_file = resolve_path(&input_file, working_directory);
+            output_file = resolve_path(&output_file, working_directory);
 
             if verbose && inputs.len() == 1 {
                 println!("Reading input LAS file...");
@@ -281,118 +385,39 @@ impl WhiteboxTool for FlightlineOverlap {
                 println!("Performing analysis...");
             }
 
-            // Make sure that the input LAS file have GPS time data?
-            if input.header.point_format == 0u8 || input.header.point_format == 2u8 {
-                panic!("The input file has a Point Format that does not include GPS time, which is required for the operation of this tool.");
+            let use_gps_time = !use_point_source && input.has_gps_time();
+            if verbose && !use_point_source && !use_gps_time {
+                println!("Warning: The input file has a Point Format that does not include GPS time. Flightlines will be identified using point source IDs.");
             }
 
             let n_points = input.header.number_of_points as usize;
             let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
 
-            let mut frs: FixedRadiusSearch2D<usize> =
-                FixedRadiusSearch2D::new(grid_res, DistanceMetric::SquaredEuclidean);
-            let mut gps_times = vec![-1f64; n_points];
-            let (mut x, mut y, mut gps_time): (f64, f64, f64);
+            let west: f64 = input.header.min_x; // - 0.5 * grid_res;
+            let north: f64 = input.header.max_y; // + 0.5 * grid_res;
+            let rows: usize = ((((north - input.header.min_y) / grid_res).ceil()) as usize).max(1);
+            let columns: usize = ((((input.header.max_x - west) / grid_res).ceil()) as usize).max(1);
+            let south: f64 = north - rows as f64 * grid_res;
+            let east = west + columns as f64 * grid_res;
+            let nodata = -32768.0f64;
+
+            // Bin each point into its grid cell. Sorting the points by cell and then by their
+            // flightline key (GPS time or point source ID) places the points of each flightline
+            // within a cell into a contiguous run.
+            let mut points: Vec<(usize, f64, usize)> = Vec::with_capacity(n_points);
+            let (mut row, mut col): (usize, usize);
             let mut progress: usize;
             let mut old_progress: usize = 1;
             for i in 0..n_points {
-                match input.get_record(i) {
-                    LidarPointRecord::PointRecord1 {
-                        point_data,
-                        gps_data,
-                    } => {
-                        x = point_data.x;
-                        y = point_data.y;
-                        gps_time = gps_data;
-                    }
-                    LidarPointRecord::PointRecord3 {
-                        point_data,
-                        gps_data,
-                        colour_data,
-                    } => {
-                        x = point_data.x;
-                        y = point_data.y;
-                        gps_time = gps_data;
-                        let _ = colour_data;
-                    }
-                    LidarPointRecord::PointRecord4 {
-                        point_data,
-                        gps_data,
-                        wave_packet,
-                    } => {
-                        x = point_data.x;
-                        y = point_data.y;
-                        gps_time = gps_data;
-                        let _ = wave_packet;
-                    }
-                    LidarPointRecord::PointRecord5 {
-                        point_data,
-                        gps_data,
-                        colour_data,
-                        wave_packet,
-                    } => {
-                        x = point_data.x;
-                        y = point_data.y;
-                        gps_time = gps_data;
-                        let _ = colour_data;
-                        let _ = wave_packet;
-                    }
-                    LidarPointRecord::PointRecord6 {
-                        point_data,
-                        gps_data,
-                    } => {
-                        x = point_data.x;
-                        y = point_data.y;
-                        gps_time = gps_data;
-                    }
-                    LidarPointRecord::PointRecord7 {
-                        point_data,
-                        gps_data,
-                        colour_data,
-                    } => {
-                        x = point_data.x;
-                        y = point_data.y;
-                        gps_time = gps_data;
-                        let _ = colour_data;
-                    }
-                    LidarPointRecord::PointRecord8 {
-                        point_data,
-                        gps_data,
-                        colour_data,
-                    } => {
-                        x = point_data.x;
-                        y = point_data.y;
-                        gps_time = gps_data;
-                        let _ = colour_data;
-                    }
-                    LidarPointRecord::PointRecord9 {
-                        point_data,
-                        gps_data,
-                        wave_packet,
-                    } => {
-                        x = point_data.x;
-                        y = point_data.y;
-                        gps_time = gps_data;
-                        let _ = wave_packet;
-                    }
-                    LidarPointRecord::PointRecord10 {
-                        point_data,
-                        gps_data,
-                        colour_data,
-                        wave_packet,
-                    } => {
-                        x = point_data.x;
-                        y = point_data.y;
-                        gps_time = gps_data;
-                        let _ = colour_data;
-                        let _ = wave_packet;
-                    }
-                    _ => {
-                        panic!("The input file has a Point Format that does not include GPS time, which is required for the operation of this tool.");
-                    }
+                let p = input[i];
+                row = (((north - p.y) / grid_res).floor() as usize).min(rows - 1);
+                col = (((p.x - west) / grid_res).floor() as usize).min(columns - 1);
+                let key = if use_gps_time {
+                    input.get_gps_time(i)?
+                } else {
+                    p.point_source_id as f64
                 };
-                frs.insert(x, y, i);
-                gps_times[i] = gps_time;
+                points.push((row * columns + col, key, i));
                 if verbose {
                     progress = (100.0_f64 * i as f64 / num_points) as usize;
                     if progress != old_progress {
@@ -401,14 +426,7 @@ impl WhiteboxTool for FlightlineOverlap {
                     }
                 }
             }
-
-            let west: f64 = input.header.min_x; // - 0.5 * grid_res;
-            let north: f64 = input.header.max_y; // + 0.5 * grid_res;
-            let rows: usize = (((north - input.header.min_y) / grid_res).ceil()) as usize;
-            let columns: usize = (((input.header.max_x - west) / grid_res).ceil()) as usize;
-            let south: f64 = north - rows as f64 * grid_res;
-            let east = west + columns as f64 * grid_res;
-            let nodata = -32768.0f64;
+            points.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.partial_cmp(&b.1).unwrap()));
 
             let mut configs = RasterConfigs {
                 ..Default::default()
@@ -422,57 +440,69 @@ impl WhiteboxTool for FlightlineOverlap {
             configs.resolution_x = grid_res;
             configs.resolution_y = grid_res;
             configs.nodata = nodata;
-            configs.data_type = DataType::F64;
+            configs.data_type = DataType::I16;
             configs.photometric_interp = PhotometricInterpretation::Continuous;
             configs.palette = palette.clone();
-            // configs.projection = input.configs.projection.clone();
-            // configs.xy_units = input.configs.xy_units.clone();
-            // configs.z_units = input.configs.z_units.clone();
-            // configs.endian = input.configs.endian.clone();
-            // configs.epsg_code = input.configs.epsg_code;
-            // configs.coordinate_ref_system_wkt = input.configs.coordinate_ref_system_wkt.clone();
             let mut output = Raster::initialize_using_config(&output_file, &configs);
-            let time_threshold = 15f64;
-            let (mut x_n, mut y_n): (f64, f64);
-            let mut index_n: usize;
-            let half_res_sqrd = grid_res / 2.0 * grid_res / 2.0;
-            for row in 0..rows as isize {
-                for col in 0..columns as isize {
-                    x = west + col as f64 * grid_res + 0.5;
-                    y = north - row as f64 * grid_res - 0.5;
-                    let ret = frs.search(x, y);
-                    if ret.len() > 0 {
-                        let mut times = vec![];
-                        for j in 0..ret.len() {
-                            index_n = ret[j].0;
-                            let p = input[index_n];
-                            x_n = p.x;
-                            y_n = p.y;
-                            if (x_n - x) * (x_n - x) <= half_res_sqrd
-                                && (y_n - y) * (y_n - y) <= half_res_sqrd
+
+            let classify = !las_outputs.is_empty();
+            let mut overlapping = vec![false; if classify { n_points } else { 0 }];
+            let mut num_overlap_cells = 0usize;
+            let mut cell_start = 0usize;
+            while cell_start < points.len() {
+                let cell = points[cell_start].0;
+                let mut cell_end = cell_start + 1;
+                while cell_end < points.len() && points[cell_end].0 == cell {
+                    cell_end += 1;
+                }
+
+                // Split the cell's points into flightlines, recording the (start, end) of each run.
+                let mut flightlines = vec![];
+                let mut run_start = cell_start;
+                for j in cell_start + 1..cell_end {
+                    let new_flightline = if use_gps_time {
+                        points[j].1 - points[j - 1].1 > time_threshold
+                    } else {
+                        points[j].1 != points[j - 1].1
+                    };
+                    if new_flightline {
+                        flightlines.push((run_start, j));
+                        run_start = j;
+                    }
+                }
+                flightlines.push((run_start, cell_end));
+
+                output.set_value(
+                    (cell / columns) as isize,
+                    (cell % columns) as isize,
+                    flightlines.len() as f64,
+                );
+
+                if flightlines.len() > 1 {
+                    num_overlap_cells += 1;
+                    if classify {
+                        // The dominant flightline is the one contributing the most points to the cell.
+                        let mut dominant = 0;
+                        for f in 1..flightlines.len() {
+                            if flightlines[f].1 - flightlines[f].0
+                                > flightlines[dominant].1 - flightlines[dominant].0
                             {
-                                // it falls within the grid cell
-                                times.push(gps_times[ret[j].0]);
+                                dominant = f;
                             }
                         }
-                        if times.len() > 0 {
-                            times.sort_by(|a, b| a.partial_cmp(&b).unwrap());
-                            let mut num_flightlines = 1.0;
-                            for j in 1..times.len() {
-                                if times[j] - times[j - 1] > time_threshold {
-                                    num_flightlines += 1.0;
+                        for f in 0..flightlines.len() {
+                            if f != dominant {
+                                for j in flightlines[f].0..flightlines[f].1 {
+                                    overlapping[points[j].2] = true;
                                 }
                             }
-                            output.set_value(row, col, num_flightlines);
-                        } else {
-                            output.set_value(row, col, nodata);
                         }
-                    } else {
-                        output.set_value(row, col, nodata);
                     }
                 }
+
+                cell_start = cell_end;
                 if verbose {
-                    progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                    progress = (100.0_f64 * cell_start as f64 / points.len() as f64) as usize;
                     if progress != old_progress {
                         println!("Progress: {}%", progress);
                         old_progress = progress;
@@ -480,6 +510,56 @@ impl WhiteboxTool for FlightlineOverlap {
                 }
             }
 
+            if verbose {
+                let num_cells = points
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, p)| *j == 0 || p.0 != points[j - 1].0)
+                    .count();
+                println!(
+                    "Overlap cells: {} of {} occupied cells ({:.1}%)",
+                    num_overlap_cells,
+                    num_cells,
+                    100f64 * num_overlap_cells as f64 / num_cells.max(1) as f64
+                );
+            }
+
+            if classify {
+                let mut las_file = las_outputs[k].replace("\"", "").clone();
+                las_file = resolve_path(&las_file, working_directory);
+                let mut output_las = LasFile::initialize_using_file(&las_file, &input);
+                output_las.header.system_id = "EXTRACTION".to_string();
+                let mut num_flagged = 0usize;
+                for i in 0..n_points {
+                    let mut pr = input.get_record(i);
+                    if overlapping[i] {
+                        // The overlap bit is only retained by the LAS 1.4 point formats; class 12
+                        // is used to flag overlap points in the legacy point formats.
+                        pr.get_point_data_mut().set_overlap(true);
+                        pr.get_point_data_mut().set_classification(12);
+                        num_flagged += 1;
+                    }
+                    output_las.add_point_record(pr);
+                    output_las.add_extra_bytes(input.get_extra_bytes(i));
+                }
+                if verbose {
+                    println!(
+                        "Points flagged as overlap: {} ({:.1}%)",
+                        num_flagged,
+                        100f64 * num_flagged as f64 / n_points.max(1) as f64
+                    );
+                    println!("Writing output LAS file...");
+                }
+                let _ = match output_las.write() {
+                    Ok(_) => {
+                        if verbose {
+                            println!("Complete!")
+                        }
+                    }
+                    Err(e) => return Err(e),
+                };
+            }
+
             let elapsed_time_run = get_formatted_elapsed_time(start_run);
             output.add_metadata_entry(format!(
                 "Created by whitebox_tools\' {} tool",
@@ -2,10 +2,11 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 16/07/2017
-Last Modified: 12/08/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
+use super::ascii_columns::{parse_columns, parse_delimiter, AsciiColumn};
 use crate::lidar::*;
 use crate::tools::*;
 use std;
@@ -22,13 +23,24 @@ use std::path;
 /// with each column cooresponding to a field in the table and each row a point value. Fields are separated by 
 /// commas in the ASCII formated file. The output point data, each on a seperate line, will take the format:
 /// 
-/// ```
+/// ```text
 /// X,Y,Z,INTENSITY,CLASS,RETURN,NUM_RETURN,SCAN_ANGLE
 /// ```
 /// 
 /// If the LAS file has a point format that contains RGB data, the final three columns will contain the RED,
-/// GREEN, and BLUE values respectively. Use the `AsciiToLas` tool to convert a text file containing LiDAR 
-/// point data into a LAS file.
+/// GREEN, and BLUE values respectively.
+///
+/// Alternatively, the output columns can be specified (`--columns`) as a comma-separated list of field names,
+/// e.g. `x,y,z,intensity,class,time`. The recognized field names are the same as those of the `AsciiToLas`
+/// tool, i.e. `x`, `y`, `z`, `intensity`, `class`, `return`, `num_returns`, `time`, `scan_angle`, `red`,
+/// `green`, `blue`, `user_data`, and `point_source_id`. The `time` and colour fields may only be used if the
+/// input files contain GPS time and RGB data respectively. The fields may be separated by commas (the default),
+/// tabs, spaces, semicolons, or any other single character (`--delimiter`); the output file will have a `.txt`
+/// extension if a delimiter other than a comma is used. Because the same column names are used by both tools,
+/// a file created by `LasToAscii` can be converted back into a LAS file with `AsciiToLas`, using the same
+/// `--columns` and `--delimiter` values.
+///
+/// Use the `AsciiToLas` tool to convert a text file containing LiDAR point data into a LAS file.
 /// 
 /// # See Also
 /// `AsciiToLas`
@@ -57,6 +69,24 @@ impl LasToAscii {
             optional: false,
         });
 
+        parameters.push(ToolParameter {
+            name: "Output Columns".to_owned(),
+            flags: vec!["--columns".to_owned()],
+            description: "Optional comma-separated list of the point fields to output, e.g. 'x,y,z,intensity,class,time'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Delimiter".to_owned(),
+            flags: vec!["--delimiter".to_owned()],
+            description: "Column delimiter; options include 'comma', 'tab', 'space', 'semicolon', or a single character.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: Some("comma".to_owned()),
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -68,7 +98,7 @@ impl LasToAscii {
         if e.contains(".exe") {
             short_exe += ".exe";
         }
-        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=\"file1.las, file2.las, file3.las\" -o=outfile.las\"", short_exe, name).replace("*", &sep);
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=\"file1.las, file2.las, file3.las\" --columns='x,y,z,intensity,class,time' --delimiter=tab", short_exe, name).replace("*", &sep);
 
         LasToAscii {
             name: name,
@@ -122,6 +152,8 @@ impl WhiteboxTool for LasToAscii {
         verbose: bool,
     ) -> Result<(), Error> {
         let mut input_files: String = String::new();
+        let mut column_string = String::new();
+        let mut delimiter_string = String::from("comma");
 
         // read the arguments
         if args.len() == 0 {
@@ -146,6 +178,18 @@ impl WhiteboxTool for LasToAscii {
                 } else {
                     input_files = args[i + 1].to_string();
                 }
+            } else if flag_val == "-columns" {
+                column_string = if keyval {
+                    vec[1].to_string().to_lowercase()
+                } else {
+                    args[i + 1].to_string().to_lowercase()
+                };
+            } else if flag_val == "-delimiter" {
+                delimiter_string = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
             }
         }
 
@@ -155,13 +199,19 @@ impl WhiteboxTool for LasToAscii {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = std::path::MAIN_SEPARATOR;
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
         let start = Instant::now();
 
+        let delimiter = parse_delimiter(&delimiter_string)?.unwrap_or(' ');
+        let mut columns = if !column_string.trim().is_empty() {
+            parse_columns(&column_string)?
+        } else {
+            vec![]
+        };
+        columns.retain(|c| *c != AsciiColumn::Skip);
+
         let mut cmd = input_files.split(";");
         let mut vec = cmd.collect::<Vec<&str>>();
         if vec.len() == 1 {
@@ -173,9 +223,7 @@ impl WhiteboxTool for LasToAscii {
         for value in vec {
             if !value.trim().is_empty() {
                 let mut input_file = value.trim().to_owned();
-                if !input_file.contains(sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
 
                 let input: LasFile = match LasFile::new(&input_file, "r") {
                     Ok(lf) => lf,
@@ -187,36 +235,60 @@ impl WhiteboxTool for LasToAscii {
                     }
                 };
 
-                let has_rgb = input.has_rgb();
-                let mut rgb: ColourData;
+                // the default columns depend on whether the file contains RGB data
+                let mut file_columns = columns.clone();
+                if file_columns.is_empty() {
+                    file_columns = vec![
+                        AsciiColumn::X,
+                        AsciiColumn::Y,
+                        AsciiColumn::Z,
+                        AsciiColumn::Intensity,
+                        AsciiColumn::Class,
+                        AsciiColumn::ReturnNumber,
+                        AsciiColumn::NumReturns,
+                        AsciiColumn::ScanAngle,
+                    ];
+                    if input.has_rgb() {
+                        file_columns.extend(&[AsciiColumn::Red, AsciiColumn::Green, AsciiColumn::Blue]);
+                    }
+                }
+                let needs_time = file_columns.contains(&AsciiColumn::Time);
+                if needs_time && !input.has_gps_time() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("The input file {} does not contain GPS time data.", input_file),
+                    ));
+                }
+                let needs_rgb = file_columns.iter().any(|c| {
+                    *c == AsciiColumn::Red || *c == AsciiColumn::Green || *c == AsciiColumn::Blue
+                });
+                if needs_rgb && !input.has_rgb() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("The input file {} does not contain RGB data.", input_file),
+                    ));
+                }
+
                 let file_extension = get_file_extension(&input_file);
-                let output_file = input_file.replace(&format!(".{}", file_extension), ".csv");
+                let output_extension = if delimiter == ',' { ".csv" } else { ".txt" };
+                let output_file = input_file.replace(&format!(".{}", file_extension), output_extension);
                 let f = File::create(output_file)?;
                 let mut writer = BufWriter::new(f);
 
                 let n_points = input.header.number_of_points as usize;
 
-                if !has_rgb {
-                    writer.write_all("X,Y,Z,INTENSITY,CLASS,RETURN,NUM_RETURN,SCAN_ANGLE\n".as_bytes())?;
-                } else {
-                    writer.write_all("X,Y,Z,INTENSITY,CLASS,RETURN,NUM_RETURN,SCAN_ANGLE,RED,GREEN,BLUE\n".as_bytes())?;
-                }
+                let header_row = file_columns
+                    .iter()
+                    .map(|c| c.header_name())
+                    .collect::<Vec<&str>>()
+                    .join(&delimiter.to_string());
+                writer.write_all(format!("{}\n", header_row).as_bytes())?;
+                let mut fields: Vec<String> = Vec::with_capacity(file_columns.len());
                 for k in 0..n_points {
                     let pd = input[k];
-                    let s = if !has_rgb {
-                        format!(
-                            "{},{},{},{},{},{},{},{}\n",
-                            pd.x,
-                            pd.y,
-                            pd.z,
-                            pd.intensity,
-                            pd.classification(),
-                            pd.return_number(),
-                            pd.number_of_returns(),
-                            pd.scan_angle
-                        )
-                    } else {
-                        rgb = match input.get_rgb(k) {
+                    let gps_time = if needs_time { input.get_gps_time(k)? } else { 0f64 };
+                    let rgb = if needs_rgb {
+                        match input.get_rgb(k) {
                             Ok(v) => v,
                             Err(_) => {
                                 return Err(Error::new(
@@ -224,23 +296,31 @@ impl WhiteboxTool for LasToAscii {
                                     "RGB data not read correctly in LAS file.",
                                 ))
                             }
-                        };
-                        format!(
-                            "{},{},{},{},{},{},{},{},{},{},{}\n",
-                            pd.x,
-                            pd.y,
-                            pd.z,
-                            pd.intensity,
-                            pd.classification(),
-                            pd.return_number(),
-                            pd.number_of_returns(),
-                            pd.scan_angle,
-                            rgb.red,
-                            rgb.green,
-                            rgb.blue
-                        )
+                        }
+                    } else {
+                        ColourData::default()
                     };
-                    writer.write_all(s.as_bytes())?;
+                    fields.clear();
+                    for c in &file_columns {
+                        fields.push(match c {
+                            AsciiColumn::X => pd.x.to_string(),
+                            AsciiColumn::Y => pd.y.to_string(),
+                            AsciiColumn::Z => pd.z.to_string(),
+                            AsciiColumn::Intensity => pd.intensity.to_string(),
+                            AsciiColumn::Class => pd.classification().to_string(),
+                            AsciiColumn::ReturnNumber => pd.return_number().to_string(),
+                            AsciiColumn::NumReturns => pd.number_of_returns().to_string(),
+                            AsciiColumn::Time => gps_time.to_string(),
+                            AsciiColumn::ScanAngle => pd.scan_angle.to_string(),
+                            AsciiColumn::Red => rgb.red.to_string(),
+                            AsciiColumn::Green => rgb.green.to_string(),
+                            AsciiColumn::Blue => rgb.blue.to_string(),
+                            AsciiColumn::UserData => pd.user_data.to_string(),
+                            AsciiColumn::PointSourceId => pd.point_source_id.to_string(),
+                            AsciiColumn::Skip => String::new(),
+                        });
+                    }
+                    writer.write_all(format!("{}\n", fields.join(&delimiter.to_string())).as_bytes())?;
 
                     if verbose {
                         progress = (100.0_f64 * k as f64 / (n_points - 1) as f64) as usize;
@@ -207,9 +207,7 @@ impl WhiteboxTool for LasToShapefile {
                 ));
             }
         } else {
-            if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-                input_file = format!("{}{}", working_directory, input_file);
-            }
+            input_file = resolve_path(&input_file, working_directory);
             inputs.push(input_file.clone());
         }
 
@@ -0,0 +1,574 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::structures::Array2D;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// This tool maps the vertical structure of vegetation by counting the number of distinct canopy
+/// layers within each cell of a raster grid, e.g. an overstory above a sub-canopy and a shrub
+/// layer, using all of the returns of a multi-return LiDAR point cloud. The input LAS file
+/// (`--input`) must be height-normalized, i.e. the z values of the points must be heights above the
+/// ground surface rather than elevations. Later returns are essential for detecting the lower
+/// layers, which are largely hidden from first returns beneath a closed canopy.
+///
+/// Within each grid cell, the heights of the vegetation returns, i.e. those with heights greater
+/// than or equal to the height threshold (`--height_threshold`, default 1.0), are sorted and a new
+/// layer begins wherever the vertical gap between consecutive return heights is at least the
+/// minimum gap size (`--min_gap`, default 1.0). Layers that contain fewer than the minimum number of
+/// returns (`--min_points`, default 3) are discarded, which removes isolated returns from branches,
+/// birds, and wires. The minimum gap should be larger than the typical vertical spacing of returns
+/// within a continuous crown, which increases as the point density decreases.
+///
+/// The number of layers in each cell is written to a raster named by appending `_layers` to the
+/// output file name (e.g. `outfile_layers.tif`). The heights of the top and base of each layer, up to
+/// the maximum number of layers (`--max_layers`, default 3), are written to rasters suffixed by
+/// `_layer1_top`, `_layer1_base`, `_layer2_top`, etc., where layer 1 is the uppermost layer, i.e.
+/// the overstory; the base height of the uppermost layer approximates the canopy base height. If no
+/// output file is specified, the outputs have the same base name as the input LAS file. Grid cells
+/// that do not contain any points are assigned the NoData value, cells without vegetation layers
+/// have a layer count of zero, and the layer height rasters are NoData in cells with fewer layers.
+/// Classes can be excluded from the analysis (`--exclude_cls`), e.g. noise points (classes 7 and
+/// 18); withheld points are always excluded. When the input/output parameters are not specified,
+/// the tool works on all LAS files contained within the working directory.
+///
+/// # See Also
+/// `LidarCanopyMetrics`, `LidarHistogram`, `LidarPointStats`
+pub struct LidarCanopyLayers {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarCanopyLayers {
+    pub fn new() -> LidarCanopyLayers {
+        // public constructor
+        let name = "LidarCanopyLayers".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Counts the distinct vertical vegetation layers within each grid cell of a height-normalized, multi-return LiDAR point cloud.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input height-normalized LiDAR file (including extension).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file (including extension); each output is suffixed by its contents.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Output raster's grid resolution.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("5.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Height Threshold".to_owned(),
+            flags: vec!["--height_threshold".to_owned()],
+            description: "Minimum height of vegetation returns.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Gap Size".to_owned(),
+            flags: vec!["--min_gap".to_owned()],
+            description: "Minimum vertical gap between return heights that separates two layers.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Returns per Layer".to_owned(),
+            flags: vec!["--min_points".to_owned()],
+            description: "Minimum number of returns within a layer.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("3".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Number of Layer Height Outputs".to_owned(),
+            flags: vec!["--max_layers".to_owned()],
+            description: "Number of layers, from the top down, for which top and base height rasters are output.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("3".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter{
+            name: "Exclusion Classes (0-18, based on LAS spec; e.g. 3,4,5,6,7)".to_owned(),
+            flags: vec!["--exclude_cls".to_owned()],
+            description: "Optional exclude classes from the analysis; Valid class values range from 0 to 18, based on LAS specifications. Example, --exclude_cls='7,18'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=normalized.las -o=structure.tif --resolution=5.0 --min_gap=1.5 --exclude_cls='7,18'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=normalized.las -o=structure.tif --height_threshold=0.5 --min_points=5 --max_layers=2",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        LidarCanopyLayers {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarCanopyLayers {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--resolution".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--min_gap".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--min_points".to_string(), ParameterConstraint::Min(1.0)),
+            ("--max_layers".to_string(), ParameterConstraint::Min(0.0)),
+            ("--exclude_cls".to_string(), ParameterConstraint::ListRange(0.0, 255.0)),
+        ]
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_dir}{input_stem}.tif".to_string())
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+        let mut output_file: String = "".to_string();
+        let mut grid_res: f64 = 5.0;
+        let mut height_threshold = 1f64;
+        let mut min_gap = 1f64;
+        let mut min_points = 3usize;
+        let mut max_layers = 3usize;
+        let mut include_class_vals = vec![true; 256];
+        let mut exclude_cls_str = String::new();
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution" {
+                grid_res = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-height_threshold" {
+                height_threshold = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_gap" {
+                min_gap = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_points" {
+                min_points = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-max_layers" {
+                max_layers = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-exclude_cls" {
+                exclude_cls_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                let mut cmd = exclude_cls_str.split(",");
+                let mut vec = cmd.collect::<Vec<&str>>();
+                if vec.len() == 1 {
+                    cmd = exclude_cls_str.split(";");
+                    vec = cmd.collect::<Vec<&str>>();
+                }
+                for value in vec {
+                    if !value.trim().is_empty() {
+                        let c = value.trim().parse::<usize>().unwrap();
+                        include_class_vals[c] = false;
+                    }
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let start = Instant::now();
+
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        if input_file.is_empty() {
+            if working_directory.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "This tool must be run by specifying either an individual input file or a working directory."));
+            }
+            // the output file may be a template used to name the output of each input file,
+            // e.g. '{input_stem}_structure.tif'
+            let output_template = if is_output_template(&output_file) {
+                output_file.clone()
+            } else {
+                self.get_default_output_template().unwrap()
+            };
+            if std::path::Path::new(&working_directory).is_dir() {
+                for entry in fs::read_dir(working_directory)? {
+                    let s = entry?
+                        .path()
+                        .into_os_string()
+                        .to_str()
+                        .expect("Error reading path string")
+                        .to_string();
+                    if s.to_lowercase().ends_with(".las") || s.to_lowercase().ends_with(".zip") {
+                        let mut out_file =
+                            expand_output_template(&output_template, &s, &self.get_tool_name());
+                        out_file = resolve_path(&out_file, working_directory);
+                        inputs.push(s);
+                        outputs.push(out_file);
+                    }
+                }
+            } else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The input directory ({}) is incorrect.", working_directory),
+                ));
+            }
+        } else {
+            input_file = resolve_path(&input_file, working_directory);
+            inputs.push(input_file.clone());
+            if output_file.is_empty() {
+                output_file = expand_output_template(
+                    &self.get_default_output_template().unwrap(),
+                    &input_file,
+                    &self.get_tool_name(),
+                );
+            }
+            output_file = resolve_path(&output_file, working_directory);
+            outputs.push(output_file);
+        }
+
+        let num_tiles = inputs.len();
+        let tile_list = Arc::new(Mutex::new(0..num_tiles));
+        let inputs = Arc::new(inputs);
+        let outputs = Arc::new(outputs);
+        let num_procs2 = num_cpus::get() as isize;
+        let (tx2, rx2) = mpsc::channel();
+        for _ in 0..num_procs2 {
+            let inputs = inputs.clone();
+            let outputs = outputs.clone();
+            let tile_list = tile_list.clone();
+            // copy over the string parameters
+            let exclude_cls_str = exclude_cls_str.clone();
+            let include_class_vals = include_class_vals.clone();
+            let tool_name = self.get_tool_name();
+            let tx2 = tx2.clone();
+            thread::spawn(move || {
+                let mut tile = 0;
+                while tile < num_tiles {
+                    // Get the next tile up for processing
+                    tile = match tile_list.lock().unwrap().next() {
+                        Some(val) => val,
+                        None => break, // There are no more tiles to process
+                    };
+                    let start_run = Instant::now();
+
+                    let input_file = inputs[tile].replace("\"", "").clone();
+                    let output_file = outputs[tile].replace("\"", "").clone();
+                    if verbose && inputs.len() == 1 {
+                        println!("Reading input LAS file...");
+                    }
+                    let input = match LasFile::new(&input_file, "r") {
+                        Ok(lf) => lf,
+                        Err(err) => panic!("Error reading file {}: {}", input_file, err),
+                    };
+
+                    let mut progress: i32;
+                    let mut old_progress: i32 = -1;
+
+                    let west: f64 = input.header.min_x;
+                    let north: f64 = input.header.max_y;
+                    let rows: isize = ((((north - input.header.min_y) / grid_res).ceil()) as isize).max(1);
+                    let columns: isize = ((((input.header.max_x - west) / grid_res).ceil()) as isize).max(1);
+                    let south: f64 = north - rows as f64 * grid_res;
+                    let east = west + columns as f64 * grid_res;
+                    let nodata = -32768.0f64;
+
+                    let mut heights: Vec<Vec<f32>> = vec![vec![]; (rows * columns) as usize];
+                    let mut num_points: Array2D<u32> = Array2D::new(rows, columns, 0u32, 0u32).unwrap();
+
+                    let n_points = input.header.number_of_points as usize;
+                    let num_points_float: f64 = (input.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+                    for i in 0..n_points {
+                        let p: PointData = input.get_point_info(i);
+                        if !p.withheld() && include_class_vals[p.classification() as usize] {
+                            // points on the southern and eastern edges belong to the last row and column
+                            let col = (((p.x - west) / grid_res).floor() as isize).min(columns - 1);
+                            let row = (((north - p.y) / grid_res).floor() as isize).min(rows - 1);
+                            num_points.increment(row, col, 1u32);
+                            if p.z >= height_threshold {
+                                heights[(row * columns + col) as usize].push(p.z as f32);
+                            }
+                        }
+                        if verbose && inputs.len() == 1 {
+                            progress = (100.0_f64 * i as f64 / num_points_float) as i32;
+                            if progress != old_progress {
+                                println!("Binning points: {}%", progress);
+                                old_progress = progress;
+                            }
+                        }
+                    }
+
+                    // identify the layers of each cell
+                    let mut layer_count: Array2D<f64> =
+                        Array2D::new(rows, columns, nodata, nodata).unwrap();
+                    let mut layer_tops: Vec<Array2D<f64>> = (0..max_layers)
+                        .map(|_| Array2D::new(rows, columns, nodata, nodata).unwrap())
+                        .collect();
+                    let mut layer_bases: Vec<Array2D<f64>> = (0..max_layers)
+                        .map(|_| Array2D::new(rows, columns, nodata, nodata).unwrap())
+                        .collect();
+                    for row in 0..rows {
+                        for col in 0..columns {
+                            if num_points.get_value(row, col) == 0 {
+                                continue;
+                            }
+                            let cell_heights = &mut heights[(row * columns + col) as usize];
+                            cell_heights.sort_by(|a, b| b.partial_cmp(a).unwrap());
+                            let layers = find_layers(cell_heights, min_gap, min_points);
+                            layer_count.set_value(row, col, layers.len() as f64);
+                            for (k, (top, base)) in layers.iter().take(max_layers).enumerate() {
+                                layer_tops[k].set_value(row, col, *top);
+                                layer_bases[k].set_value(row, col, *base);
+                            }
+                        }
+                    }
+
+                    let mut configs = RasterConfigs {
+                        ..Default::default()
+                    };
+                    configs.rows = rows as usize;
+                    configs.columns = columns as usize;
+                    configs.north = north;
+                    configs.south = south;
+                    configs.east = east;
+                    configs.west = west;
+                    configs.resolution_x = grid_res;
+                    configs.resolution_y = grid_res;
+                    configs.nodata = nodata;
+                    configs.data_type = DataType::F32;
+                    configs.photometric_interp = PhotometricInterpretation::Continuous;
+
+                    let elapsed_time_run = get_formatted_elapsed_time(start_run);
+
+                    if verbose && inputs.len() == 1 {
+                        println!("Saving data...")
+                    };
+
+                    let mut grids = vec![("layers".to_string(), &layer_count)];
+                    for k in 0..max_layers {
+                        grids.push((format!("layer{}_top", k + 1), &layer_tops[k]));
+                        grids.push((format!("layer{}_base", k + 1), &layer_bases[k]));
+                    }
+                    for (suffix, grid) in grids {
+                        let file_name = match output_file.rfind('.') {
+                            Some(pos) => format!("{}_{}{}", &output_file[..pos], suffix, &output_file[pos..]),
+                            None => format!("{}_{}", output_file, suffix),
+                        };
+                        let mut output = Raster::initialize_using_config(&file_name, &configs);
+                        if suffix == "layers" {
+                            output.configs.data_type = DataType::I16;
+                            output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+                        }
+                        for row in 0..rows {
+                            output.set_row_data(row, grid.get_row_data(row));
+                        }
+
+                        output.add_metadata_entry(format!(
+                            "Created by whitebox_tools\' {} tool",
+                            tool_name
+                        ));
+                        output.add_metadata_entry(format!("Input file: {}", input_file));
+                        output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
+                        output.add_metadata_entry(format!("Height threshold: {}", height_threshold));
+                        output.add_metadata_entry(format!("Minimum gap: {}", min_gap));
+                        output.add_metadata_entry(format!("Minimum returns per layer: {}", min_points));
+                        output.add_metadata_entry(format!("Excluded classes: {}", exclude_cls_str));
+                        output.add_metadata_entry(format!(
+                            "Elapsed Time (excluding I/O): {}",
+                            elapsed_time_run
+                        ));
+                        let _ = output.write().unwrap();
+                    }
+
+                    tx2.send(tile).unwrap();
+                }
+            });
+        }
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+        for tile in 0..inputs.len() {
+            let tile_completed = rx2.recv().unwrap();
+            if verbose {
+                println!(
+                    "Finished {} ({} of {})",
+                    inputs[tile_completed]
+                        .replace("\"", "")
+                        .replace(working_directory, "")
+                        .replace(".las", ""),
+                    tile + 1,
+                    inputs.len()
+                );
+            }
+            if verbose {
+                progress = (100.0_f64 * tile as f64 / (inputs.len() - 1).max(1) as f64) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits heights, sorted in descending order, into layers separated by vertical gaps of at least
+/// `min_gap`, returning the (top, base) heights of each layer with at least `min_points` returns,
+/// from the uppermost layer down.
+fn find_layers(sorted: &[f32], min_gap: f64, min_points: usize) -> Vec<(f64, f64)> {
+    let mut layers = vec![];
+    let mut start = 0usize;
+    for i in 1..=sorted.len() {
+        if i == sorted.len() || (sorted[i - 1] - sorted[i]) as f64 >= min_gap {
+            if i - start >= min_points {
+                layers.push((sorted[start] as f64, sorted[i - 1] as f64));
+            }
+            start = i;
+        }
+    }
+    layers
+}
@@ -0,0 +1,727 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::structures::Array2D;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// This tool calculates several forestry metrics describing the vegetation canopy within each cell
+/// of a raster grid, in a single pass through a LiDAR point cloud. The input LAS file (`--input`)
+/// must be height-normalized, i.e. the z values of the points must be heights above the ground
+/// surface rather than elevations. The metrics to calculate are specified as a comma-separated
+/// list (`--metrics`), with any of the following values:
+///
+/// | Metric   | Meaning                                                                      |
+/// | :------- | :--------------------------------------------------------------------------- |
+/// | `p25`    | 25th percentile of the heights of the vegetation returns                     |
+/// | `p50`    | Median (50th percentile) of the heights of the vegetation returns            |
+/// | `p95`    | 95th percentile of the heights of the vegetation returns                     |
+/// | `cover`  | Fraction of the first returns with heights above the height threshold        |
+/// | `rumple` | Rumple index, i.e. the ratio of the canopy surface area to the ground area   |
+/// | `vci`    | Vertical complexity index (van Ewijk et al., 2011)                           |
+///
+/// Vegetation returns are those points with heights greater than or equal to the height threshold
+/// (`--height_threshold`, default 2.0), which separates the canopy from the ground and understory.
+/// Percentiles are estimated by linear interpolation between the sorted heights. Canopy cover is
+/// calculated from first and only returns, since later returns are biased towards the canopy
+/// interior.
+///
+/// The rumple index is calculated from a canopy height model (CHM), created by assigning the
+/// maximum point height to each cell of a finer grid (`--chm_res`, default 1.0) within each output
+/// grid cell. The CHM is triangulated between the centres of neighbouring CHM cells and the total
+/// area of the triangulated canopy surface is divided by its planimetric area; a rumple index of 1.0
+/// indicates a perfectly flat canopy, and larger values indicate increasing structural complexity.
+/// CHM cells that do not contain any points are assumed to be at ground level (zero height).
+///
+/// The vertical complexity index is the normalized Shannon entropy of the distribution of point
+/// heights among fixed height bins (`--vci_bin`, default 1.0) between the ground and the maximum
+/// height within the cell, calculated from all returns with non-negative heights. It ranges from
+/// 0.0, where all of the returns are concentrated in a single height bin, to 1.0, where the returns
+/// are evenly distributed throughout the vertical profile.
+///
+/// One output raster is created for each metric, named by appending the metric to the output file
+/// name (e.g. `outfile_p95.tif`); if no output file is specified, the outputs have the same base
+/// name as the input LAS file. Grid cells that do not contain any points are assigned the NoData
+/// value, and the percentile metrics are NoData in cells without vegetation returns. Classes can be
+/// excluded from all of the metrics (`--exclude_cls`), e.g. noise points (classes 7 and 18);
+/// withheld points are always excluded. When the input/output parameters are not specified, the
+/// tool works on all LAS files contained within the working directory.
+///
+/// # Reference
+/// van Ewijk, K. Y., Treitz, P. M., & Scott, N. A. (2011). Characterizing forest succession in
+/// central Ontario using LiDAR-derived indices. *Photogrammetric Engineering & Remote Sensing*,
+/// 77(3), 261-269.
+///
+/// # See Also
+/// `LidarCellStats`, `LidarPointStats`, `LidarHistogram`
+pub struct LidarCanopyMetrics {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarCanopyMetrics {
+    pub fn new() -> LidarCanopyMetrics {
+        // public constructor
+        let name = "LidarCanopyMetrics".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Calculates per-cell forestry canopy metrics (height percentiles, cover, rumple, vertical complexity) from a height-normalized LiDAR point cloud.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input height-normalized LiDAR file (including extension).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file (including extension); each output is suffixed by its metric.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Metrics".to_owned(),
+            flags: vec!["--metrics".to_owned()],
+            description: "Comma-separated list of metrics to calculate; options are 'p25', 'p50', 'p95', 'cover', 'rumple', and 'vci' (default is all).".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: Some("p25,p50,p95,cover,rumple,vci".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Output raster's grid resolution.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Height Threshold".to_owned(),
+            flags: vec!["--height_threshold".to_owned()],
+            description: "Minimum height of vegetation returns, used for the percentiles and canopy cover.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "CHM Resolution".to_owned(),
+            flags: vec!["--chm_res".to_owned()],
+            description: "Resolution of the canopy height model used to calculate the rumple index.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "VCI Height Bin Size".to_owned(),
+            flags: vec!["--vci_bin".to_owned()],
+            description: "Height bin size used to calculate the vertical complexity index.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter{
+            name: "Exclusion Classes (0-18, based on LAS spec; e.g. 3,4,5,6,7)".to_owned(),
+            flags: vec!["--exclude_cls".to_owned()],
+            description: "Optional exclude classes from the metrics; Valid class values range from 0 to 18, based on LAS specifications. Example, --exclude_cls='7,18'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=normalized.las -o=canopy.tif --resolution=20.0 --height_threshold=2.0 --exclude_cls='7,18'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=normalized.las -o=canopy.tif --metrics='p95,cover'",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        LidarCanopyMetrics {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarCanopyMetrics {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--resolution".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--chm_res".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--vci_bin".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--exclude_cls".to_string(), ParameterConstraint::ListRange(0.0, 255.0)),
+        ]
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_dir}{input_stem}.tif".to_string())
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+        let mut output_file: String = "".to_string();
+        let mut metrics_str = "p25,p50,p95,cover,rumple,vci".to_string();
+        let mut grid_res: f64 = 10.0;
+        let mut height_threshold = 2f64;
+        let mut chm_res = 1f64;
+        let mut vci_bin = 1f64;
+        let mut include_class_vals = vec![true; 256];
+        let mut exclude_cls_str = String::new();
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-metrics" {
+                metrics_str = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-resolution" {
+                grid_res = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-height_threshold" {
+                height_threshold = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-chm_res" {
+                chm_res = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-vci_bin" {
+                vci_bin = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-exclude_cls" {
+                exclude_cls_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                let mut cmd = exclude_cls_str.split(",");
+                let mut vec = cmd.collect::<Vec<&str>>();
+                if vec.len() == 1 {
+                    cmd = exclude_cls_str.split(";");
+                    vec = cmd.collect::<Vec<&str>>();
+                }
+                for value in vec {
+                    if !value.trim().is_empty() {
+                        let c = value.trim().parse::<usize>().unwrap();
+                        include_class_vals[c] = false;
+                    }
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut metrics = vec![];
+        for m in metrics_str.split(",").map(|m| m.trim()).filter(|m| !m.is_empty()) {
+            let metric = match m {
+                "p25" => "p25",
+                "p50" | "median" => "p50",
+                "p95" => "p95",
+                "cover" | "canopy_cover" => "cover",
+                "rumple" => "rumple",
+                "vci" => "vci",
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unrecognized metric '{}'; options are 'p25', 'p50', 'p95', 'cover', 'rumple', and 'vci'.", m),
+                    ));
+                }
+            };
+            if !metrics.contains(&metric) {
+                metrics.push(metric);
+            }
+        }
+        if metrics.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one metric (--metrics) must be specified.",
+            ));
+        }
+        if chm_res > grid_res {
+            chm_res = grid_res;
+        }
+
+        let start = Instant::now();
+
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        if input_file.is_empty() {
+            if working_directory.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "This tool must be run by specifying either an individual input file or a working directory."));
+            }
+            // the output file may be a template used to name the output of each input file,
+            // e.g. '{input_stem}_canopy.tif'
+            let output_template = if is_output_template(&output_file) {
+                output_file.clone()
+            } else {
+                self.get_default_output_template().unwrap()
+            };
+            if std::path::Path::new(&working_directory).is_dir() {
+                for entry in fs::read_dir(working_directory)? {
+                    let s = entry?
+                        .path()
+                        .into_os_string()
+                        .to_str()
+                        .expect("Error reading path string")
+                        .to_string();
+                    if s.to_lowercase().ends_with(".las") || s.to_lowercase().ends_with(".zip") {
+                        let mut out_file =
+                            expand_output_template(&output_template, &s, &self.get_tool_name());
+                        out_file = resolve_path(&out_file, working_directory);
+                        inputs.push(s);
+                        outputs.push(out_file);
+                    }
+                }
+            } else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The input directory ({}) is incorrect.", working_directory),
+                ));
+            }
+        } else {
+            input_file = resolve_path(&input_file, working_directory);
+            inputs.push(input_file.clone());
+            if output_file.is_empty() {
+                output_file = expand_output_template(
+                    &self.get_default_output_template().unwrap(),
+                    &input_file,
+                    &self.get_tool_name(),
+                );
+            }
+            output_file = resolve_path(&output_file, working_directory);
+            outputs.push(output_file);
+        }
+
+        let num_tiles = inputs.len();
+        let tile_list = Arc::new(Mutex::new(0..num_tiles));
+        let inputs = Arc::new(inputs);
+        let outputs = Arc::new(outputs);
+        let metrics = Arc::new(metrics);
+        let num_procs2 = num_cpus::get() as isize;
+        let (tx2, rx2) = mpsc::channel();
+        for _ in 0..num_procs2 {
+            let inputs = inputs.clone();
+            let outputs = outputs.clone();
+            let metrics = metrics.clone();
+            let tile_list = tile_list.clone();
+            // copy over the string parameters
+            let exclude_cls_str = exclude_cls_str.clone();
+            let include_class_vals = include_class_vals.clone();
+            let tool_name = self.get_tool_name();
+            let tx2 = tx2.clone();
+            thread::spawn(move || {
+                let mut tile = 0;
+                while tile < num_tiles {
+                    // Get the next tile up for processing
+                    tile = match tile_list.lock().unwrap().next() {
+                        Some(val) => val,
+                        None => break, // There are no more tiles to process
+                    };
+                    let start_run = Instant::now();
+
+                    let input_file = inputs[tile].replace("\"", "").clone();
+                    let output_file = outputs[tile].replace("\"", "").clone();
+                    if verbose && inputs.len() == 1 {
+                        println!("Reading input LAS file...");
+                    }
+                    let input = match LasFile::new(&input_file, "r") {
+                        Ok(lf) => lf,
+                        Err(err) => panic!("Error reading file {}: {}", input_file, err),
+                    };
+
+                    let mut progress: i32;
+                    let mut old_progress: i32 = -1;
+
+                    let west: f64 = input.header.min_x;
+                    let north: f64 = input.header.max_y;
+                    let rows: isize = ((((north - input.header.min_y) / grid_res).ceil()) as isize).max(1);
+                    let columns: isize = ((((input.header.max_x - west) / grid_res).ceil()) as isize).max(1);
+                    let south: f64 = north - rows as f64 * grid_res;
+                    let east = west + columns as f64 * grid_res;
+                    let nodata = -32768.0f64;
+
+                    // the CHM cells nest within the output grid cells
+                    let chm_per_cell = ((grid_res / chm_res).round() as isize).max(1);
+                    let chm_cell_size = grid_res / chm_per_cell as f64;
+                    let chm_rows = rows * chm_per_cell;
+                    let chm_columns = columns * chm_per_cell;
+
+                    let mut heights: Vec<Vec<f32>> = vec![vec![]; (rows * columns) as usize];
+                    let mut num_first: Array2D<u32> = Array2D::new(rows, columns, 0u32, 0u32).unwrap();
+                    let mut num_first_canopy: Array2D<u32> =
+                        Array2D::new(rows, columns, 0u32, 0u32).unwrap();
+                    let mut chm: Array2D<f64> =
+                        Array2D::new(chm_rows, chm_columns, 0f64, 0f64).unwrap();
+
+                    let n_points = input.header.number_of_points as usize;
+                    let num_points_float: f64 = (input.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+                    for i in 0..n_points {
+                        let p: PointData = input.get_point_info(i);
+                        if !p.withheld() && include_class_vals[p.classification() as usize] {
+                            // points on the southern and eastern edges belong to the last row and column
+                            let chm_col = (((p.x - west) / chm_cell_size).floor() as isize).min(chm_columns - 1);
+                            let chm_row = (((north - p.y) / chm_cell_size).floor() as isize).min(chm_rows - 1);
+                            let row = chm_row / chm_per_cell;
+                            let col = chm_col / chm_per_cell;
+                            heights[(row * columns + col) as usize].push(p.z as f32);
+                            if p.is_early_return() {
+                                num_first.increment(row, col, 1u32);
+                                if p.z >= height_threshold {
+                                    num_first_canopy.increment(row, col, 1u32);
+                                }
+                            }
+                            if p.z > chm.get_value(chm_row, chm_col) {
+                                chm.set_value(chm_row, chm_col, p.z);
+                            }
+                        }
+                        if verbose && inputs.len() == 1 {
+                            progress = (100.0_f64 * i as f64 / num_points_float) as i32;
+                            if progress != old_progress {
+                                println!("Binning points: {}%", progress);
+                                old_progress = progress;
+                            }
+                        }
+                    }
+
+                    // calculate the metrics of each cell
+                    let mut values: Vec<Array2D<f64>> = metrics
+                        .iter()
+                        .map(|_| Array2D::new(rows, columns, nodata, nodata).unwrap())
+                        .collect();
+                    for row in 0..rows {
+                        for col in 0..columns {
+                            let cell_heights = &mut heights[(row * columns + col) as usize];
+                            if cell_heights.is_empty() {
+                                continue;
+                            }
+                            cell_heights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                            let first_veg = cell_heights
+                                .iter()
+                                .position(|z| *z as f64 >= height_threshold)
+                                .unwrap_or(cell_heights.len());
+                            let veg_heights = &cell_heights[first_veg..];
+                            for (m, metric) in metrics.iter().enumerate() {
+                                let value = match *metric {
+                                    "p25" => percentile(veg_heights, 25f64),
+                                    "p50" => percentile(veg_heights, 50f64),
+                                    "p95" => percentile(veg_heights, 95f64),
+                                    "cover" => {
+                                        let n = num_first.get_value(row, col);
+                                        if n > 0 {
+                                            num_first_canopy.get_value(row, col) as f64 / n as f64
+                                        } else {
+                                            nodata
+                                        }
+                                    }
+                                    "rumple" => rumple_index(
+                                        &chm,
+                                        row * chm_per_cell,
+                                        col * chm_per_cell,
+                                        chm_per_cell,
+                                        chm_cell_size,
+                                    ),
+                                    _ => vertical_complexity(cell_heights, vci_bin), // vci
+                                };
+                                values[m].set_value(row, col, value);
+                            }
+                        }
+                    }
+
+                    let mut configs = RasterConfigs {
+                        ..Default::default()
+                    };
+                    configs.rows = rows as usize;
+                    configs.columns = columns as usize;
+                    configs.north = north;
+                    configs.south = south;
+                    configs.east = east;
+                    configs.west = west;
+                    configs.resolution_x = grid_res;
+                    configs.resolution_y = grid_res;
+                    configs.nodata = nodata;
+                    configs.data_type = DataType::F32;
+                    configs.photometric_interp = PhotometricInterpretation::Continuous;
+
+                    let elapsed_time_run = get_formatted_elapsed_time(start_run);
+
+                    if verbose && inputs.len() == 1 {
+                        println!("Saving data...")
+                    };
+
+                    for (m, metric) in metrics.iter().enumerate() {
+                        let file_name = match output_file.rfind('.') {
+                            Some(pos) => format!("{}_{}{}", &output_file[..pos], metric, &output_file[pos..]),
+                            None => format!("{}_{}", output_file, metric),
+                        };
+                        let mut output = Raster::initialize_using_config(&file_name, &configs);
+                        for row in 0..rows {
+                            output.set_row_data(row, values[m].get_row_data(row));
+                        }
+
+                        output.add_metadata_entry(format!(
+                            "Created by whitebox_tools\' {} tool",
+                            tool_name
+                        ));
+                        output.add_metadata_entry(format!("Input file: {}", input_file));
+                        output.add_metadata_entry(format!("Metric: {}", metric));
+                        output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
+                        output.add_metadata_entry(format!("Height threshold: {}", height_threshold));
+                        if *metric == "rumple" {
+                            output.add_metadata_entry(format!("CHM resolution: {}", chm_cell_size));
+                        } else if *metric == "vci" {
+                            output.add_metadata_entry(format!("VCI bin size: {}", vci_bin));
+                        }
+                        output.add_metadata_entry(format!("Excluded classes: {}", exclude_cls_str));
+                        output.add_metadata_entry(format!(
+                            "Elapsed Time (excluding I/O): {}",
+                            elapsed_time_run
+                        ));
+                        let _ = output.write().unwrap();
+                    }
+
+                    tx2.send(tile).unwrap();
+                }
+            });
+        }
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+        for tile in 0..inputs.len() {
+            let tile_completed = rx2.recv().unwrap();
+            if verbose {
+                println!(
+                    "Finished {} ({} of {})",
+                    inputs[tile_completed]
+                        .replace("\"", "")
+                        .replace(working_directory, "")
+                        .replace(".las", ""),
+                    tile + 1,
+                    inputs.len()
+                );
+            }
+            if verbose {
+                progress = (100.0_f64 * tile as f64 / (inputs.len() - 1).max(1) as f64) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the percentile of sorted values, interpolating linearly between neighbouring values,
+/// or the NoData value (-32768) if there are no values.
+fn percentile(sorted: &[f32], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return -32768f64;
+    }
+    let pos = pct / 100f64 * (sorted.len() - 1) as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    let frac = pos - lower as f64;
+    sorted[lower] as f64 + (sorted[upper] as f64 - sorted[lower] as f64) * frac
+}
+
+/// Returns the ratio of the triangulated surface area to the planimetric area of a block of
+/// CHM cells, including the triangles connecting the block to the neighbouring cell centres.
+fn rumple_index(
+    chm: &Array2D<f64>,
+    start_row: isize,
+    start_col: isize,
+    size: isize,
+    cell_size: f64,
+) -> f64 {
+    let rows = chm.rows;
+    let columns = chm.columns;
+    let mut surface_area = 0f64;
+    let mut planar_area = 0f64;
+    let triangle_area = |a: [f64; 3], b: [f64; 3], c: [f64; 3]| -> f64 {
+        let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        let cross = [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ];
+        0.5 * (cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2]).sqrt()
+    };
+    for row in start_row..(start_row + size).min(rows - 1) {
+        for col in start_col..(start_col + size).min(columns - 1) {
+            let (x0, x1) = (col as f64 * cell_size, (col + 1) as f64 * cell_size);
+            let (y0, y1) = (-row as f64 * cell_size, -(row + 1) as f64 * cell_size);
+            let p00 = [x0, y0, chm.get_value(row, col)];
+            let p01 = [x1, y0, chm.get_value(row, col + 1)];
+            let p10 = [x0, y1, chm.get_value(row + 1, col)];
+            let p11 = [x1, y1, chm.get_value(row + 1, col + 1)];
+            surface_area += triangle_area(p00, p01, p11) + triangle_area(p00, p11, p10);
+            planar_area += cell_size * cell_size;
+        }
+    }
+    if planar_area > 0f64 {
+        surface_area / planar_area
+    } else {
+        1f64
+    }
+}
+
+/// Returns the vertical complexity index, i.e. the normalized entropy of the distribution of the
+/// sorted heights among bins between the ground and the maximum height.
+fn vertical_complexity(sorted: &[f32], bin_size: f64) -> f64 {
+    let first = sorted
+        .iter()
+        .position(|z| *z >= 0f32)
+        .unwrap_or(sorted.len());
+    let sorted = &sorted[first..];
+    if sorted.is_empty() {
+        return -32768f64;
+    }
+    let max_z = sorted[sorted.len() - 1] as f64;
+    let num_bins = ((max_z / bin_size).ceil() as usize).max(1);
+    if num_bins < 2 {
+        return 0f64;
+    }
+    let mut counts = vec![0usize; num_bins];
+    for z in sorted {
+        let bin = ((*z as f64 / bin_size).floor() as usize).min(num_bins - 1);
+        counts[bin] += 1;
+    }
+    let n = sorted.len() as f64;
+    let mut entropy = 0f64;
+    for count in counts {
+        if count > 0 {
+            let p = count as f64 / n;
+            entropy -= p * p.ln();
+        }
+    }
+    entropy / (num_bins as f64).ln()
+}
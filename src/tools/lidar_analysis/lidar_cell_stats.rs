@@ -0,0 +1,687 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::structures::Array2D;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// This tool bins the points in a LAS file into the cells of a raster grid and calculates several
+/// statistics of a point attribute within each grid cell, in a single pass through the point cloud.
+/// The user must specify the name of an input LAS file (`--input`), the output raster grid
+/// resolution (`--resolution`), and the point attribute to summarize (`--parameter`), which can be
+/// 'elevation' (default), 'intensity', 'class', 'return_number', 'number_of_returns', 'scan angle',
+/// 'user data', or the name of an extra bytes attribute. The statistics to calculate are specified
+/// as a comma-separated list (`--stats`), with any of the following values:
+///
+/// | Statistic | Meaning                                                  |
+/// | :-------- | :------------------------------------------------------- |
+/// | `min`     | Minimum value of the points in each grid cell            |
+/// | `max`     | Maximum value of the points in each grid cell            |
+/// | `mean`    | Average value of the points in each grid cell            |
+/// | `std`     | Standard deviation of the values in each grid cell       |
+/// | `count`   | Number of points in each grid cell                       |
+/// | `range`   | Difference between the maximum and minimum values        |
+///
+/// If the `--stats` parameter is not specified, all of the statistics are calculated. One output
+/// raster is created for each statistic, named by appending the statistic to the output file name
+/// (e.g. `outfile_mean.tif`); if no output file is specified, the outputs have the same base name as
+/// the input LAS file. Grid cells that do not contain any points are assigned the NoData value,
+/// with the exception of the `count` output, in which they are assigned zero. The standard deviation
+/// is the population standard deviation of the values within a cell.
+///
+/// Points may be excluded based on their return type (`--returns`), class (`--exclude_cls`), and
+/// GPS time (`--min_time` and `--max_time`). Withheld points are always excluded. When the
+/// input/output parameters are not specified, the tool works on all LAS files contained within
+/// the working directory.
+///
+/// # See Also
+/// `LidarPointStats`, `LidarBlockMinimum`, `LidarBlockMaximum`
+pub struct LidarCellStats {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarCellStats {
+    pub fn new() -> LidarCellStats {
+        // public constructor
+        let name = "LidarCellStats".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Calculates several per-cell statistics (min, max, mean, std, count, range) of a LiDAR point attribute in a single pass.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file (including extension).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file (including extension); each output is suffixed by its statistic.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Point Attribute".to_owned(),
+            flags: vec!["--parameter".to_owned()],
+            description: "Point attribute to summarize; options are 'elevation' (default), 'intensity', 'class', 'return_number', 'number_of_returns', 'scan angle', 'user data', or the name of an extra bytes attribute.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "elevation".to_owned(),
+                "intensity".to_owned(),
+                "class".to_owned(),
+                "return_number".to_owned(),
+                "number_of_returns".to_owned(),
+                "scan angle".to_owned(),
+                "user data".to_owned(),
+            ]),
+            default_value: Some("elevation".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Statistics".to_owned(),
+            flags: vec!["--stats".to_owned()],
+            description: "Comma-separated list of statistics to calculate; options are 'min', 'max', 'mean', 'std', 'count', and 'range' (default is all).".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: Some("min,max,mean,std,count,range".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Output raster's grid resolution.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Point Returns Included".to_owned(),
+            flags: vec!["--returns".to_owned()],
+            description:
+                "Point return types to include; options are 'all' (default), 'last', 'first'."
+                    .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "all".to_owned(),
+                "last".to_owned(),
+                "first".to_owned(),
+            ]),
+            default_value: Some("all".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter{
+            name: "Exclusion Classes (0-18, based on LAS spec; e.g. 3,4,5,6,7)".to_owned(),
+            flags: vec!["--exclude_cls".to_owned()],
+            description: "Optional exclude classes from the statistics; Valid class values range from 0 to 18, based on LAS specifications. Example, --exclude_cls='3,4,5,6,7,18'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum GPS Time (optional)".to_owned(),
+            flags: vec!["--min_time".to_owned()],
+            description: "Optional minimum GPS time of points to include.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum GPS Time (optional)".to_owned(),
+            flags: vec!["--max_time".to_owned()],
+            description: "Optional maximum GPS time of points to include.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --resolution=1.0 --stats='min,max,mean,std,count' --exclude_cls='7,18'",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        LidarCellStats {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarCellStats {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--resolution".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--exclude_cls".to_string(), ParameterConstraint::ListRange(0.0, 255.0)),
+        ]
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_dir}{input_stem}.tif".to_string())
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+        let mut output_file: String = "".to_string();
+        let mut parameter = "elevation".to_string();
+        let mut stats_str = "min,max,mean,std,count,range".to_string();
+        let mut grid_res: f64 = 1.0;
+        let mut return_type = "all".to_string();
+        let mut include_class_vals = vec![true; 256];
+        let mut exclude_cls_str = String::new();
+        let mut min_time = f64::NEG_INFINITY;
+        let mut max_time = f64::INFINITY;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-parameter" {
+                parameter = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-stats" {
+                stats_str = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-resolution" {
+                grid_res = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-returns" {
+                return_type = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-exclude_cls" {
+                exclude_cls_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                let mut cmd = exclude_cls_str.split(",");
+                let mut vec = cmd.collect::<Vec<&str>>();
+                if vec.len() == 1 {
+                    cmd = exclude_cls_str.split(";");
+                    vec = cmd.collect::<Vec<&str>>();
+                }
+                for value in vec {
+                    if !value.trim().is_empty() {
+                        let c = value.trim().parse::<usize>().unwrap();
+                        include_class_vals[c] = false;
+                    }
+                }
+            } else if flag_val == "-min_time" {
+                min_time = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_time" {
+                max_time = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let filter_by_time = min_time.is_finite() || max_time.is_finite();
+        if min_time > max_time {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The minimum GPS time (--min_time) must be less than the maximum GPS time (--max_time).",
+            ));
+        }
+
+        if grid_res <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The grid resolution (--resolution) must be greater than zero.",
+            ));
+        }
+
+        let mut stats = vec![];
+        for s in stats_str.split(",").map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let stat = match s {
+                "min" | "minimum" => "min",
+                "max" | "maximum" => "max",
+                "mean" | "average" | "avg" => "mean",
+                "std" | "stdev" | "sd" | "std_dev" => "std",
+                "count" | "num_points" | "n" => "count",
+                "range" => "range",
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unrecognized statistic '{}'; options are 'min', 'max', 'mean', 'std', 'count', and 'range'.", s),
+                    ));
+                }
+            };
+            if !stats.contains(&stat) {
+                stats.push(stat);
+            }
+        }
+        if stats.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one statistic (--stats) must be specified.",
+            ));
+        }
+        if parameter == "rgb" {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "RGB colour cannot be summarized by this tool; please choose a numeric point attribute.",
+            ));
+        }
+
+        let start = Instant::now();
+
+        let (all_returns, late_returns, early_returns): (bool, bool, bool);
+        if return_type.contains("last") {
+            all_returns = false;
+            late_returns = true;
+            early_returns = false;
+        } else if return_type.contains("first") {
+            all_returns = false;
+            late_returns = false;
+            early_returns = true;
+        } else {
+            // all
+            all_returns = true;
+            late_returns = false;
+            early_returns = false;
+        }
+
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        if input_file.is_empty() {
+            if working_directory.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "This tool must be run by specifying either an individual input file or a working directory."));
+            }
+            // the output file may be a template used to name the output of each input file,
+            // e.g. '{input_stem}_dem.tif'
+            let output_template = if is_output_template(&output_file) {
+                output_file.clone()
+            } else {
+                self.get_default_output_template().unwrap()
+            };
+            if std::path::Path::new(&working_directory).is_dir() {
+                for entry in fs::read_dir(working_directory)? {
+                    let s = entry?
+                        .path()
+                        .into_os_string()
+                        .to_str()
+                        .expect("Error reading path string")
+                        .to_string();
+                    if s.to_lowercase().ends_with(".las") || s.to_lowercase().ends_with(".zip") {
+                        let mut out_file =
+                            expand_output_template(&output_template, &s, &self.get_tool_name());
+                        out_file = resolve_path(&out_file, working_directory);
+                        inputs.push(s);
+                        outputs.push(out_file);
+                    }
+                }
+            } else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The input directory ({}) is incorrect.", working_directory),
+                ));
+            }
+        } else {
+            input_file = resolve_path(&input_file, working_directory);
+            inputs.push(input_file.clone());
+            if output_file.is_empty() {
+                output_file = expand_output_template(
+                    &self.get_default_output_template().unwrap(),
+                    &input_file,
+                    &self.get_tool_name(),
+                );
+            }
+            output_file = resolve_path(&output_file, working_directory);
+            outputs.push(output_file);
+        }
+
+        let num_tiles = inputs.len();
+        let tile_list = Arc::new(Mutex::new(0..num_tiles));
+        let inputs = Arc::new(inputs);
+        let outputs = Arc::new(outputs);
+        let stats = Arc::new(stats);
+        let num_procs2 = num_cpus::get() as isize;
+        let (tx2, rx2) = mpsc::channel();
+        for _ in 0..num_procs2 {
+            let inputs = inputs.clone();
+            let outputs = outputs.clone();
+            let stats = stats.clone();
+            let tile_list = tile_list.clone();
+            // copy over the string parameters
+            let parameter = parameter.clone();
+            let return_type = return_type.clone();
+            let exclude_cls_str = exclude_cls_str.clone();
+            let include_class_vals = include_class_vals.clone();
+            let tool_name = self.get_tool_name();
+            let tx2 = tx2.clone();
+            thread::spawn(move || {
+                let mut tile = 0;
+                while tile < num_tiles {
+                    // Get the next tile up for processing
+                    tile = match tile_list.lock().unwrap().next() {
+                        Some(val) => val,
+                        None => break, // There are no more tiles to process
+                    };
+                    let start_run = Instant::now();
+
+                    let input_file = inputs[tile].replace("\"", "").clone();
+                    let output_file = outputs[tile].replace("\"", "").clone();
+                    if verbose && inputs.len() == 1 {
+                        println!("Reading input LAS file...");
+                    }
+                    let input = match LasFile::new(&input_file, "r") {
+                        Ok(lf) => lf,
+                        Err(err) => panic!("Error reading file {}: {}", input_file, err),
+                    };
+                    if filter_by_time && !input.has_gps_time() {
+                        panic!(
+                            "The file {} does not contain GPS time data, which is required by --min_time and --max_time.",
+                            input_file
+                        );
+                    }
+                    let attribute = match input.get_point_attribute(&parameter) {
+                        Some(a) => a,
+                        None => panic!(
+                            "Error reading file {}: The parameter '{}' is neither a standard point attribute nor an extra bytes attribute. Available extra bytes attributes: {:?}",
+                            input_file,
+                            parameter,
+                            input.get_extra_bytes_attribute_names()
+                        ),
+                    };
+
+                    let mut progress: i32;
+                    let mut old_progress: i32 = -1;
+
+                    let west: f64 = input.header.min_x;
+                    let north: f64 = input.header.max_y;
+                    let rows: isize = ((((north - input.header.min_y) / grid_res).ceil()) as isize).max(1);
+                    let columns: isize = ((((input.header.max_x - west) / grid_res).ceil()) as isize).max(1);
+                    let south: f64 = north - rows as f64 * grid_res;
+                    let east = west + columns as f64 * grid_res;
+                    let nodata = -32768.0f64;
+
+                    // The mean and variance are accumulated using Welford's method, which avoids
+                    // the loss of precision associated with summing the squares of large values.
+                    let mut count: Array2D<f64> = Array2D::new(rows, columns, 0f64, nodata).unwrap();
+                    let mut mean: Array2D<f64> = Array2D::new(rows, columns, 0f64, nodata).unwrap();
+                    let mut m2: Array2D<f64> = Array2D::new(rows, columns, 0f64, nodata).unwrap();
+                    let mut min_val: Array2D<f64> =
+                        Array2D::new(rows, columns, f64::INFINITY, nodata).unwrap();
+                    let mut max_val: Array2D<f64> =
+                        Array2D::new(rows, columns, f64::NEG_INFINITY, nodata).unwrap();
+
+                    let n_points = input.header.number_of_points as usize;
+                    let num_points_float: f64 = (input.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+                    let (mut row, mut col): (isize, isize);
+                    let (mut n, mut value, mut delta): (f64, f64, f64);
+                    for i in 0..n_points {
+                        let p: PointData = input.get_point_info(i);
+                        if !p.withheld()
+                            && (all_returns
+                                || (p.is_late_return() & late_returns)
+                                || (p.is_early_return() & early_returns))
+                            && include_class_vals[p.classification() as usize]
+                            && input.is_gps_time_in_range(i, min_time, max_time)
+                        {
+                            value = input.get_point_attribute_value(i, attribute);
+                            if !value.is_nan() {
+                                // points on the southern and eastern edges belong to the last row and column
+                                col = (((p.x - west) / grid_res).floor() as isize).min(columns - 1);
+                                row = (((north - p.y) / grid_res).floor() as isize).min(rows - 1);
+                                count.increment(row, col, 1f64);
+                                n = count.get_value(row, col);
+                                delta = value - mean.get_value(row, col);
+                                mean.increment(row, col, delta / n);
+                                m2.increment(row, col, delta * (value - mean.get_value(row, col)));
+                                if value < min_val.get_value(row, col) {
+                                    min_val.set_value(row, col, value);
+                                }
+                                if value > max_val.get_value(row, col) {
+                                    max_val.set_value(row, col, value);
+                                }
+                            }
+                        }
+                        if verbose && inputs.len() == 1 {
+                            progress = (100.0_f64 * i as f64 / num_points_float) as i32;
+                            if progress != old_progress {
+                                println!("Binning points: {}%", progress);
+                                old_progress = progress;
+                            }
+                        }
+                    }
+
+                    let mut configs = RasterConfigs {
+                        ..Default::default()
+                    };
+                    configs.rows = rows as usize;
+                    configs.columns = columns as usize;
+                    configs.north = north;
+                    configs.south = south;
+                    configs.east = east;
+                    configs.west = west;
+                    configs.resolution_x = grid_res;
+                    configs.resolution_y = grid_res;
+                    configs.nodata = nodata;
+                    configs.data_type = DataType::F64;
+                    configs.photometric_interp = PhotometricInterpretation::Continuous;
+
+                    let elapsed_time_run = get_formatted_elapsed_time(start_run);
+
+                    if verbose && inputs.len() == 1 {
+                        println!("Saving data...")
+                    };
+
+                    for stat in stats.iter() {
+                        let file_name = match output_file.rfind('.') {
+                            Some(pos) => format!("{}_{}{}", &output_file[..pos], stat, &output_file[pos..]),
+                            None => format!("{}_{}", output_file, stat),
+                        };
+                        let mut output = Raster::initialize_using_config(&file_name, &configs);
+                        for row in 0..rows {
+                            for col in 0..columns {
+                                n = count.get_value(row, col);
+                                if n > 0f64 {
+                                    value = match *stat {
+                                        "min" => min_val.get_value(row, col),
+                                        "max" => max_val.get_value(row, col),
+                                        "mean" => mean.get_value(row, col),
+                                        "std" => (m2.get_value(row, col) / n).max(0f64).sqrt(),
+                                        "count" => n,
+                                        _ => max_val.get_value(row, col) - min_val.get_value(row, col), // range
+                                    };
+                                    output.set_value(row, col, value);
+                                } else if *stat == "count" {
+                                    output.set_value(row, col, 0f64);
+                                }
+                            }
+                        }
+
+                        output.add_metadata_entry(format!(
+                            "Created by whitebox_tools\' {} tool",
+                            tool_name
+                        ));
+                        output.add_metadata_entry(format!("Input file: {}", input_file));
+                        output.add_metadata_entry(format!("Point attribute: {}", parameter));
+                        output.add_metadata_entry(format!("Statistic: {}", stat));
+                        output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
+                        output.add_metadata_entry(format!("Returns: {}", return_type));
+                        output.add_metadata_entry(format!("Excluded classes: {}", exclude_cls_str));
+                        if filter_by_time {
+                            output.add_metadata_entry(format!(
+                                "GPS time range: {} to {}",
+                                min_time, max_time
+                            ));
+                        }
+                        output.add_metadata_entry(format!(
+                            "Elapsed Time (excluding I/O): {}",
+                            elapsed_time_run
+                        ));
+                        let _ = output.write().unwrap();
+                    }
+
+                    tx2.send(tile).unwrap();
+                }
+            });
+        }
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+        for tile in 0..inputs.len() {
+            let tile_completed = rx2.recv().unwrap();
+            if verbose {
+                println!(
+                    "Finished {} ({} of {})",
+                    inputs[tile_completed]
+                        .replace("\"", "")
+                        .replace(working_directory, "")
+                        .replace(".las", ""),
+                    tile + 1,
+                    inputs.len()
+                );
+            }
+            if verbose {
+                progress = (100.0_f64 * tile as f64 / (inputs.len() - 1).max(1) as f64) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
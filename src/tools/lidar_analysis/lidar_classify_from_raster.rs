@@ -0,0 +1,435 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::tools::*;
+use std::collections::HashMap;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufReader, Error, ErrorKind};
+use std::path;
+
+/// This tool transfers the values of a categorical raster (`--raster`), such as a land-cover map
+/// or a building mask, onto the points of a LiDAR point cloud (`--input`). Each point is assigned
+/// the value of the raster cell that contains it, which is written into either the point's
+/// classification field or its user-data byte (`--field`, with values of 'classification' and
+/// 'user_data'). The raster and the LiDAR file must share the same coordinate reference system.
+///
+/// Raster values may be translated into point values using an optional class-mapping table
+/// (`--class_map`). The table is specified either as a string of *New value*; *Raster value* pairs
+/// (e.g. '6;1;9;2', which assigns class 6 to points within raster cells of value 1 and class 9 to
+/// those within cells of value 2), following the convention of the `Reclass` tool's assign mode,
+/// or as the name of a text file containing the same two columns, delimited by a semicolon, comma,
+/// space, or tab, as used by the `ReclassFromFile` tool. When a mapping table is provided, points
+/// that lie within cells whose values do not appear in the table are left unaltered. Otherwise,
+/// raster values are rounded to the nearest integer and written directly.
+///
+/// Points that lie outside of the raster, or that coincide with NoData cells, retain their existing
+/// values. So do points whose new value is out of range for the target field. The classification
+/// field of point formats 0-5 holds values from 0 to 31, while that of point formats 6-10 and the
+/// user-data byte hold values from 0 to 255. Optionally, only points of certain classes may be
+/// modified by specifying a list of classes to exclude (`--exclude_cls`), e.g. to prevent
+/// ground points (class 2) from being assigned to a building class.
+///
+/// # See Also
+/// `LidarColourize`, `Reclass`, `ReclassFromFile`, `FilterLidarClasses`
+pub struct LidarClassifyFromRaster {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarClassifyFromRaster {
+    pub fn new() -> LidarClassifyFromRaster {
+        // public constructor
+        let name = "LidarClassifyFromRaster".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Assigns the values of a categorical raster to the classification or user-data field of LiDAR points."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Categorical Raster File".to_owned(),
+            flags: vec!["--raster".to_owned()],
+            description: "Input categorical raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output LiDAR File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Target Field".to_owned(),
+            flags: vec!["--field".to_owned()],
+            description: "Point field to write the raster values to; options are 'classification' and 'user_data'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "classification".to_owned(),
+                "user_data".to_owned(),
+            ]),
+            default_value: Some("classification".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Class Mapping Table".to_owned(),
+            flags: vec!["--class_map".to_owned()],
+            description: "Optional mapping of raster values to point values, as 'new;raster' value pairs or the name of a two-column text file.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Exclusion Classes (0-18, based on LAS spec; e.g. 3,4,5,6,7)".to_owned(),
+            flags: vec!["--exclude_cls".to_owned()],
+            description: "Optional exclude classes from modification; Valid class values range from 0 to 18, based on LAS specifications. Example, --exclude_cls='3,4,5,6,7,18'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las --raster=buildings.tif -o=output.las --class_map='6;1' --exclude_cls='2,7,18'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las --raster=landcover.tif -o=output.las --field=user_data", short_exe, name).replace("*", &sep);
+
+        LidarClassifyFromRaster {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarClassifyFromRaster {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut raster_file = String::new();
+        let mut output_file = String::new();
+        let mut field = "classification".to_string();
+        let mut class_map_str = String::new();
+        let mut include_class_vals = vec![true; 256];
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-raster" {
+                raster_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-field" {
+                field = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-class_map" {
+                class_map_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-exclude_cls" {
+                let exclude_cls_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                for value in exclude_cls_str.split([',', ';']) {
+                    if !value.trim().is_empty() {
+                        let c = value.trim().parse::<usize>().map_err(|_| {
+                            Error::new(
+                                ErrorKind::InvalidInput,
+                                format!("Invalid class value '{}'.", value.trim()),
+                            )
+                        })?;
+                        if c < 256 {
+                            include_class_vals[c] = false;
+                        }
+                    }
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        raster_file = resolve_path(&raster_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        let use_user_data = match field.as_str() {
+            "classification" | "class" => false,
+            "user_data" | "userdata" | "user data" => true,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The --field parameter must be either 'classification' or 'user_data'.",
+                ))
+            }
+        };
+
+        let class_map = if class_map_str.trim().is_empty() {
+            None
+        } else {
+            Some(read_class_map(&class_map_str, working_directory)?)
+        };
+
+        if verbose {
+            println!("Reading input files...");
+        }
+        let input = LasFile::new(&input_file, "r")?;
+        let raster = Raster::new(&raster_file, "r")?;
+
+        let start = Instant::now();
+
+        let nodata = raster.configs.nodata;
+        let max_value = if use_user_data || input.header.point_format >= 6 {
+            255i64
+        } else {
+            31i64
+        };
+
+        let n_points = input.header.number_of_points as usize;
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        let (mut num_modified, mut num_outside, mut num_out_of_range) = (0usize, 0usize, 0usize);
+        for i in 0..n_points {
+            let mut pr = input.get_record(i);
+            let p = pr.get_point_data_mut();
+            if include_class_vals[p.classification() as usize] {
+                let row = raster.get_row_from_y(p.y);
+                let col = raster.get_column_from_x(p.x);
+                let z = raster.get_value(row, col);
+                if z == nodata {
+                    num_outside += 1;
+                } else {
+                    let raster_value = z.round() as i64;
+                    let new_value = match &class_map {
+                        Some(map) => map.get(&raster_value).copied(),
+                        None => Some(raster_value),
+                    };
+                    if let Some(v) = new_value {
+                        if v >= 0 && v <= max_value {
+                            if use_user_data {
+                                p.user_data = v as u8;
+                            } else {
+                                p.set_classification(v as u8);
+                            }
+                            num_modified += 1;
+                        } else {
+                            num_out_of_range += 1;
+                        }
+                    }
+                }
+            }
+            output.add_point_record(pr);
+            output.add_extra_bytes(input.get_extra_bytes(i));
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / n_points as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of modified points: {}", num_modified);
+            if num_outside > 0 {
+                println!(
+                    "Warning: {} points were outside of the raster or coincided with NoData cells and were not modified.",
+                    num_outside
+                );
+            }
+            if num_out_of_range > 0 {
+                println!(
+                    "Warning: {} points were assigned values outside of the range of the target field (0-{}) and were not modified.",
+                    num_out_of_range, max_value
+                );
+            }
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a class-mapping table of new value, raster value pairs, given either as a delimited
+/// string or as the name of a two-column text file, into a map keyed by raster value.
+fn read_class_map(
+    class_map_str: &str,
+    working_directory: &str,
+) -> Result<HashMap<i64, i64>, Error> {
+    let mut file_name = class_map_str.trim().to_string();
+    file_name = resolve_path(&file_name, working_directory);
+    let mut values: Vec<&str> = vec![];
+    let contents: String;
+    if path::Path::new(&file_name).is_file() {
+        let mut reader = BufReader::new(File::open(&file_name)?);
+        let mut s = String::new();
+        reader.read_to_string(&mut s)?;
+        contents = s;
+        for line in contents.lines() {
+            // delimiter can be a semicolon, comma, space, or tab.
+            let v: Vec<&str> = line
+                .split([';', ',', ' ', '\t'])
+                .filter(|s| !s.trim().is_empty())
+                .collect();
+            if v.is_empty() {
+                continue;
+            }
+            if v.len() != 2 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Each line of the class-mapping file must contain two values (new value, raster value): '{}'", line),
+                ));
+            }
+            values.extend(v);
+        }
+    } else {
+        values = class_map_str
+            .split([';', ','])
+            .filter(|s| !s.trim().is_empty())
+            .collect();
+        if values.len() % 2 != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The class-mapping table must include pair values (new value; raster value), e.g. '6;1;9;2'",
+            ));
+        }
+    }
+
+    let mut map = HashMap::new();
+    for pair in values.chunks(2) {
+        let parse = |s: &str| {
+            s.trim().parse::<f64>().map(|v| v.round() as i64).map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Invalid class-mapping value '{}'.", s.trim()),
+                )
+            })
+        };
+        map.insert(parse(pair[1])?, parse(pair[0])?);
+    }
+    Ok(map)
+}
@@ -0,0 +1,769 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use self::na::{Matrix2, Matrix3, Vector3};
+use super::lidar_utils::fill_gaps;
+use crate::lidar::*;
+use crate::na;
+use crate::structures::{DistanceMetric, FixedRadiusSearch2D, FixedRadiusSearch3D};
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool classifies the points belonging to overhead power lines within a LiDAR point cloud.
+/// Wire conductor points are assigned class 14 and transmission tower points are assigned class 15;
+/// the classes of all other points are left unaltered. Points that are withheld or classified as
+/// noise (classes 7 and 18) are excluded from the analysis.
+///
+/// Wires are linear, elevated, and sparsely sampled structures. The tool proceeds as follows:
+///
+/// 1. The ground surface is estimated from the lowest ground-classified (class 2) point within
+///    each cell of a grid of size `--ground_res`, or the lowest point if the file does not contain
+///    ground points, and the height of each point above the ground is calculated.
+/// 2. Points at least `--min_height` above the ground are candidate wire points. The neighbours of
+///    each candidate within the search radius (`--radius`) are analyzed using principal component
+///    analysis, and candidates with a linearity, (λ1 - λ2) / λ1, of at least `--min_linearity`
+///    and a principal direction within 45 degrees of horizontal are retained. The search radius
+///    should be less than the spacing between neighbouring conductors and large enough to contain
+///    several points along a wire.
+/// 3. The linear points are grouped into candidate segments by connecting neighbours with similar
+///    principal directions (within 20 degrees).
+/// 4. A catenary curve, z = z0 + a (cosh((s - s0) / a) - 1), is fitted to the elevations of each
+///    segment's points as a function of their distance (s) along the segment's horizontal axis.
+///    Points within `--max_residual` of the curve are classified as wires, provided that they make
+///    up most (80%) of the segment and that the segment is at least `--min_length` long.
+/// 5. Towers are identified at the ends of the wire segments. The points within the tower radius
+///    (`--tower_radius`) of a segment end, and more than one metre (map unit) above the ground, are
+///    classified as a tower if they form a vertical structure, i.e. the vertical spread of the
+///    points exceeds their horizontal spread, that reaches at least half of the height of the wire.
+///
+/// Dense vegetation rarely satisfies the linearity criterion. However, vegetation growing
+/// immediately beneath the conductors may reduce the linearity of some wire points, and trees
+/// adjacent to towers may be classified as tower points.
+///
+/// # See Also
+/// `LidarEigenvalueFeatures`, `LidarRansacPlanes`, `LidarGroundPointFilter`
+pub struct LidarClassifyPowerlines {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarClassifyPowerlines {
+    pub fn new() -> LidarClassifyPowerlines {
+        // public constructor
+        let name = "LidarClassifyPowerlines".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Classifies power line wire (class 14) and tower (class 15) points within a LiDAR point cloud."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Radius".to_owned(),
+            flags: vec!["--radius".to_owned()],
+            description: "Search radius used to measure the linearity of candidate wire points.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Height".to_owned(),
+            flags: vec!["--min_height".to_owned()],
+            description: "Minimum height of wire points above the ground.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("5.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Linearity".to_owned(),
+            flags: vec!["--min_linearity".to_owned()],
+            description: "Minimum linearity (0-1) of the neighbourhood of a wire point.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.8".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Catenary Residual".to_owned(),
+            flags: vec!["--max_residual".to_owned()],
+            description: "Maximum vertical distance between a wire point and the fitted catenary.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.3".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Segment Length".to_owned(),
+            flags: vec!["--min_length".to_owned()],
+            description: "Minimum horizontal length of a wire segment.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Tower Radius".to_owned(),
+            flags: vec!["--tower_radius".to_owned()],
+            description: "Search radius for tower points around the ends of wire segments.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("5.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Ground Grid Resolution".to_owned(),
+            flags: vec!["--ground_res".to_owned()],
+            description: "Cell size of the grid used to estimate the ground surface.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("5.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --radius=1.5 --min_height=6.0 --min_length=20.0", short_exe, name).replace("*", &sep);
+
+        LidarClassifyPowerlines {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarClassifyPowerlines {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--radius".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--min_linearity".to_string(), ParameterConstraint::Range(0.0, 1.0)),
+            ("--max_residual".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--min_length".to_string(), ParameterConstraint::Min(0.0)),
+            ("--tower_radius".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--ground_res".to_string(), ParameterConstraint::GreaterThan(0.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+        let mut output_file: String = "".to_string();
+        let mut radius = 2f64;
+        let mut min_height = 5f64;
+        let mut min_linearity = 0.8f64;
+        let mut max_residual = 0.3f64;
+        let mut min_length = 10f64;
+        let mut tower_radius = 5f64;
+        let mut ground_res = 5f64;
+        let wire_class_value = 14u8;
+        let tower_class_value = 15u8;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-radius" {
+                radius = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_height" {
+                min_height = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_linearity" {
+                min_linearity = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_residual" {
+                max_residual = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_length" {
+                min_length = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-tower_radius" {
+                tower_radius = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-ground_res" {
+                ground_res = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if radius <= 0f64 || tower_radius <= 0f64 || ground_res <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The search radius, tower radius, and ground grid resolution must be greater than zero.",
+            ));
+        }
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        let start = Instant::now();
+
+        let n_points = input.header.number_of_points as usize;
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        // Estimate the ground surface from the lowest (ground) point in each cell
+        let west = input.header.min_x;
+        let north = input.header.max_y;
+        let rows = (((north - input.header.min_y) / ground_res).ceil() as isize).max(1);
+        let columns = (((input.header.max_x - west) / ground_res).ceil() as isize).max(1);
+        let cell_index = |x: f64, y: f64| -> usize {
+            let col = (((x - west) / ground_res).floor() as isize).max(0).min(columns - 1);
+            let row = (((north - y) / ground_res).floor() as isize).max(0).min(rows - 1);
+            (row * columns + col) as usize
+        };
+        let mut is_included = vec![false; n_points];
+        let mut has_ground = false;
+        for i in 0..n_points {
+            let p: PointData = input.get_point_info(i);
+            if !p.withheld() && !p.is_classified_noise() {
+                is_included[i] = true;
+                if p.classification() == 2 {
+                    has_ground = true;
+                }
+            }
+        }
+        let mut ground = vec![f64::NAN; (rows * columns) as usize];
+        for i in 0..n_points {
+            if is_included[i] {
+                let p: PointData = input.get_point_info(i);
+                if !has_ground || p.classification() == 2 {
+                    let idx = cell_index(p.x, p.y);
+                    if ground[idx].is_nan() || p.z < ground[idx] {
+                        ground[idx] = p.z;
+                    }
+                }
+            }
+        }
+        if ground.iter().all(|z| z.is_nan()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file does not contain any points that can be classified.",
+            ));
+        }
+        let ground = fill_gaps(&ground, rows, columns);
+        let height = |x: f64, y: f64, z: f64| -> f64 { z - ground[cell_index(x, y)] };
+
+        // Find the candidate wire points
+        let mut candidates: Vec<usize> = vec![];
+        for i in 0..n_points {
+            if is_included[i] {
+                let p: PointData = input.get_point_info(i);
+                if p.classification() != 2 && height(p.x, p.y, p.z) >= min_height {
+                    candidates.push(i);
+                }
+            }
+        }
+        let num_candidates = candidates.len();
+        if verbose {
+            println!("Number of candidate wire points: {}", num_candidates);
+        }
+
+        // Measure the linearity and principal direction of each candidate's neighbourhood
+        let mut frs: FixedRadiusSearch3D<usize> =
+            FixedRadiusSearch3D::new(radius, DistanceMetric::SquaredEuclidean);
+        let mut xyz = Vec::with_capacity(num_candidates);
+        for (k, &i) in candidates.iter().enumerate() {
+            let p: PointData = input.get_point_info(i);
+            frs.insert(p.x, p.y, p.z, k);
+            xyz.push([p.x, p.y, p.z]);
+        }
+        let frs = Arc::new(frs);
+        let xyz = Arc::new(xyz);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let frs = frs.clone();
+            let xyz = xyz.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for k in (0..num_candidates).filter(|k| k % num_procs == tid) {
+                    let p = xyz[k];
+                    let neighbours: Vec<[f64; 3]> = frs
+                        .search(p[0], p[1], p[2])
+                        .iter()
+                        .map(|&(j, _)| xyz[j])
+                        .collect();
+                    tx.send((k, principal_direction(&neighbours))).unwrap();
+                }
+            });
+        }
+        let max_vertical = 45f64.to_radians().sin();
+        let mut direction: Vec<Option<[f64; 3]>> = vec![None; num_candidates];
+        for n in 0..num_candidates {
+            let (k, ret) = rx.recv().unwrap();
+            if let Some((linearity, dir)) = ret {
+                if linearity >= min_linearity && dir[2].abs() <= max_vertical {
+                    direction[k] = Some(dir);
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * n as f64 / (num_candidates - 1).max(1) as f64) as i32;
+                if progress != old_progress {
+                    println!("Measuring linearity: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Group the linear points into segments of similar direction
+        let min_cos = 20f64.to_radians().cos();
+        let mut segment_id = vec![usize::MAX; num_candidates];
+        let mut segments: Vec<Vec<usize>> = vec![];
+        for k in 0..num_candidates {
+            if direction[k].is_some() && segment_id[k] == usize::MAX {
+                let id = segments.len();
+                let mut members = vec![k];
+                let mut stack = vec![k];
+                segment_id[k] = id;
+                while let Some(m) = stack.pop() {
+                    let dm = direction[m].unwrap();
+                    let p = xyz[m];
+                    for (j, _) in frs.search(p[0], p[1], p[2]) {
+                        if segment_id[j] == usize::MAX {
+                            if let Some(dj) = direction[j] {
+                                let cos = (dm[0] * dj[0] + dm[1] * dj[1] + dm[2] * dj[2]).abs();
+                                if cos >= min_cos {
+                                    segment_id[j] = id;
+                                    members.push(j);
+                                    stack.push(j);
+                                }
+                            }
+                        }
+                    }
+                }
+                segments.push(members);
+            }
+        }
+
+        // Fit a catenary to each segment and classify the wire points
+        let mut class_values: Vec<Option<u8>> = vec![None; n_points];
+        let mut segment_ends: Vec<[f64; 3]> = vec![];
+        let mut num_segments = 0;
+        for (s, members) in segments.iter().enumerate() {
+            if members.len() >= 5 {
+                let points: Vec<[f64; 3]> = members.iter().map(|&k| xyz[k]).collect();
+                if let Some(fit) = CatenaryFit::new(&points) {
+                    let is_wire: Vec<bool> = points
+                        .iter()
+                        .map(|p| (p[2] - fit.elevation(fit.station(p))).abs() <= max_residual)
+                        .collect();
+                    let num_wire = is_wire.iter().filter(|&&w| w).count();
+                    let (mut s_min, mut s_max) = (f64::INFINITY, f64::NEG_INFINITY);
+                    for (p, &w) in points.iter().zip(is_wire.iter()) {
+                        if w {
+                            s_min = s_min.min(fit.station(p));
+                            s_max = s_max.max(fit.station(p));
+                        }
+                    }
+                    if num_wire as f64 >= 0.8 * points.len() as f64 && s_max - s_min >= min_length {
+                        for (&k, &w) in members.iter().zip(is_wire.iter()) {
+                            if w {
+                                class_values[candidates[k]] = Some(wire_class_value);
+                            }
+                        }
+                        segment_ends.push(fit.location(s_min));
+                        segment_ends.push(fit.location(s_max));
+                        num_segments += 1;
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * s as f64 / (segments.len() - 1).max(1) as f64) as i32;
+                if progress != old_progress {
+                    println!("Fitting catenaries: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Classify the towers at the ends of the wire segments
+        let mut frs2d: FixedRadiusSearch2D<usize> =
+            FixedRadiusSearch2D::new(tower_radius, DistanceMetric::SquaredEuclidean);
+        for i in 0..n_points {
+            if is_included[i] && class_values[i].is_none() {
+                let p: PointData = input.get_point_info(i);
+                if p.classification() != 2 && height(p.x, p.y, p.z) > 1f64 {
+                    frs2d.insert(p.x, p.y, i);
+                }
+            }
+        }
+        let mut num_towers = 0;
+        for end in &segment_ends {
+            let ret = frs2d.search(end[0], end[1]);
+            if ret.len() < 3 {
+                continue;
+            }
+            let wire_height = height(end[0], end[1], end[2]);
+            let mut sum = [0f64; 3];
+            let mut sum_sqr = [0f64; 3];
+            let (mut min_z, mut max_z) = (f64::INFINITY, f64::NEG_INFINITY);
+            for &(i, _) in &ret {
+                let p: PointData = input.get_point_info(i);
+                let v = [p.x, p.y, p.z];
+                for a in 0..3 {
+                    sum[a] += v[a];
+                    sum_sqr[a] += v[a] * v[a];
+                }
+                min_z = min_z.min(p.z);
+                max_z = max_z.max(p.z);
+            }
+            let n = ret.len() as f64;
+            let sd: Vec<f64> = (0..3)
+                .map(|a| (sum_sqr[a] / n - (sum[a] / n).powi(2)).max(0f64).sqrt())
+                .collect();
+            if sd[2] > sd[0].max(sd[1]) && max_z - min_z >= 0.5 * wire_height {
+                for &(i, _) in &ret {
+                    if class_values[i].is_none() {
+                        class_values[i] = Some(tower_class_value);
+                    }
+                }
+                num_towers += 1;
+            }
+        }
+
+        // Output the data
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        let (mut num_wire_points, mut num_tower_points) = (0usize, 0usize);
+        for i in 0..n_points {
+            let mut pr = input.get_record(i);
+            if let Some(class_value) = class_values[i] {
+                pr.get_point_data_mut().set_classification(class_value);
+                if class_value == wire_class_value {
+                    num_wire_points += 1;
+                } else {
+                    num_tower_points += 1;
+                }
+            }
+            output.add_point_record(pr);
+            output.add_extra_bytes(input.get_extra_bytes(i));
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of wire segments: {}", num_segments);
+            println!("Number of wire points: {}", num_wire_points);
+            println!("Number of tower locations: {}", num_towers);
+            println!("Number of tower points: {}", num_tower_points);
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the linearity, (λ1 - λ2) / λ1, and unit principal direction of a set of points, or
+/// None if there are fewer than three points.
+fn principal_direction(points: &[[f64; 3]]) -> Option<(f64, [f64; 3])> {
+    let n = points.len();
+    if n < 3 {
+        return None;
+    }
+    let mut centroid = [0f64; 3];
+    for p in points {
+        for a in 0..3 {
+            centroid[a] += p[a] / n as f64;
+        }
+    }
+    let mut cov = Matrix3::<f64>::zeros();
+    for p in points {
+        let d = [p[0] - centroid[0], p[1] - centroid[1], p[2] - centroid[2]];
+        for r in 0..3 {
+            for c in 0..3 {
+                cov[(r, c)] += d[r] * d[c] / n as f64;
+            }
+        }
+    }
+    let eig = cov.symmetric_eigen();
+    let mut order = [0usize, 1, 2];
+    order.sort_by(|&a, &b| eig.eigenvalues[b].partial_cmp(&eig.eigenvalues[a]).unwrap());
+    let l1 = eig.eigenvalues[order[0]];
+    if l1 <= 0f64 {
+        return None;
+    }
+    let l2 = eig.eigenvalues[order[1]].max(0f64);
+    let v = eig.eigenvectors.column(order[0]);
+    Some(((l1 - l2) / l1, [v[0], v[1], v[2]]))
+}
+
+/// A catenary, z = z0 + a (cosh((s - s0) / a) - 1), fitted to the points of a wire segment, where s
+/// is the station of a point along the segment's horizontal axis. Where the points do not sag,
+/// a parabola (or straight line) is used instead.
+struct CatenaryFit {
+    centroid: [f64; 2],
+    axis: [f64; 2],
+    model: CurveModel,
+}
+
+enum CurveModel {
+    Catenary { z0: f64, a: f64, s0: f64 },
+    Parabola { c0: f64, c1: f64, c2: f64 },
+}
+
+impl CatenaryFit {
+    fn new(points: &[[f64; 3]]) -> Option<CatenaryFit> {
+        let n = points.len() as f64;
+        if points.len() < 3 {
+            return None;
+        }
+        // the horizontal axis of the segment
+        let mut centroid = [0f64; 2];
+        for p in points {
+            centroid[0] += p[0] / n;
+            centroid[1] += p[1] / n;
+        }
+        let mut cov = Matrix2::<f64>::zeros();
+        for p in points {
+            let d = [p[0] - centroid[0], p[1] - centroid[1]];
+            for r in 0..2 {
+                for c in 0..2 {
+                    cov[(r, c)] += d[r] * d[c];
+                }
+            }
+        }
+        let eig = cov.symmetric_eigen();
+        let k = if eig.eigenvalues[0] >= eig.eigenvalues[1] { 0 } else { 1 };
+        let v = eig.eigenvectors.column(k);
+        let mut fit = CatenaryFit {
+            centroid,
+            axis: [v[0], v[1]],
+            model: CurveModel::Parabola {
+                c0: 0f64,
+                c1: 0f64,
+                c2: 0f64,
+            },
+        };
+
+        // least-squares parabola, z = c0 + c1 s + c2 s^2
+        let mut ata = Matrix3::<f64>::zeros();
+        let mut atb = Vector3::<f64>::zeros();
+        let stations: Vec<f64> = points.iter().map(|p| fit.station(p)).collect();
+        for (p, &s) in points.iter().zip(stations.iter()) {
+            let row = [1f64, s, s * s];
+            for r in 0..3 {
+                for c in 0..3 {
+                    ata[(r, c)] += row[r] * row[c];
+                }
+                atb[r] += row[r] * p[2];
+            }
+        }
+        let c = ata.lu().solve(&atb)?;
+        fit.model = CurveModel::Parabola {
+            c0: c[0],
+            c1: c[1],
+            c2: c[2],
+        };
+        if c[2] <= 1e-9 {
+            return Some(fit);
+        }
+
+        // refine the catenary, starting from the parabola's vertex, using Gauss-Newton iterations
+        let mut a = 1f64 / (2f64 * c[2]);
+        let mut s0 = -c[1] / (2f64 * c[2]);
+        let mut z0 = c[0] - c[1] * c[1] / (4f64 * c[2]);
+        for _ in 0..20 {
+            let mut jtj = Matrix3::<f64>::zeros();
+            let mut jtr = Vector3::<f64>::zeros();
+            for (p, &s) in points.iter().zip(stations.iter()) {
+                let u = (s - s0) / a;
+                if u.abs() > 50f64 {
+                    return Some(fit);
+                }
+                let residual = p[2] - (z0 + a * (u.cosh() - 1f64));
+                let j = [1f64, u.cosh() - 1f64 - u * u.sinh(), -u.sinh()];
+                for r in 0..3 {
+                    for c in 0..3 {
+                        jtj[(r, c)] += j[r] * j[c];
+                    }
+                    jtr[r] += j[r] * residual;
+                }
+            }
+            let delta = match jtj.lu().solve(&jtr) {
+                Some(d) => d,
+                None => break,
+            };
+            z0 += delta[0];
+            a += delta[1];
+            s0 += delta[2];
+            if a <= 0f64 || !a.is_finite() {
+                return Some(fit);
+            }
+            if delta.norm() < 1e-8 {
+                break;
+            }
+        }
+        fit.model = CurveModel::Catenary { z0, a, s0 };
+        Some(fit)
+    }
+
+    fn station(&self, p: &[f64; 3]) -> f64 {
+        (p[0] - self.centroid[0]) * self.axis[0] + (p[1] - self.centroid[1]) * self.axis[1]
+    }
+
+    fn elevation(&self, s: f64) -> f64 {
+        match self.model {
+            CurveModel::Catenary { z0, a, s0 } => z0 + a * (((s - s0) / a).cosh() - 1f64),
+            CurveModel::Parabola { c0, c1, c2 } => c0 + c1 * s + c2 * s * s,
+        }
+    }
+
+    /// The (x, y, z) location on the curve at station s.
+    fn location(&self, s: f64) -> [f64; 3] {
+        [
+            self.centroid[0] + s * self.axis[0],
+            self.centroid[1] + s * self.axis[1],
+            self.elevation(s),
+        ]
+    }
+}
+
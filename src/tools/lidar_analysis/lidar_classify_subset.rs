@@ -241,16 +241,9 @@ impl WhiteboxTool for LidarClassifySubset {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_base_file.contains(sep) && !input_base_file.contains("/") {
-            input_base_file = format!("{}{}", working_directory, input_base_file);
-        }
-        if !input_subset_file.contains(sep) && !input_subset_file.contains("/") {
-            input_subset_file = format!("{}{}", working_directory, input_subset_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_base_file = resolve_path(&input_base_file, working_directory);
+        input_subset_file = resolve_path(&input_subset_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if subset_class > 18 {
             return Err(Error::new(
@@ -438,6 +431,7 @@ impl WhiteboxTool for LidarClassifySubset {
                     }
                 }
                 output.add_point_record(pr2);
+                output.add_extra_bytes(base_lidar.get_extra_bytes(i));
             } else {
                 // We don't have a match. It's not a subset point.
                 let class_val = match nonsubset_class == 255 {
@@ -571,6 +565,7 @@ impl WhiteboxTool for LidarClassifySubset {
                     }
                 }
                 output.add_point_record(pr2);
+                output.add_extra_bytes(base_lidar.get_extra_bytes(i));
             }
 
             if verbose {
@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: February 18, 2018
-Last Modified: 12/10/2018
+Last Modified: 16/10/2026
 License: MIT
 */
 
@@ -18,6 +18,25 @@ use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
 
+/// This tool can be used to add the red-green-blue (RGB) colour fields of a LiDAR (LAS) file based on
+/// an input colour image (`--in_image`), such as an orthophoto. Each point is assigned the colour of
+/// the image cell that contains it. The image and the LiDAR file must share the same coordinate
+/// reference system. If the input image is not a colour image (e.g. a single band of multispectral
+/// imagery), points are assigned a greyscale colour, stretched over the display range of the image.
+/// Notice that colour composites can be created from individual bands of multispectral imagery using
+/// the `CreateColourComposite` tool.
+///
+/// Image colours are scaled from the 8-bit range of the image channels to the 16-bit range of LAS colour
+/// values. Points that lie outside of the image, or that coincide with NoData cells, retain their
+/// existing colour values if the input file contains colour data, and are otherwise assigned black.
+///
+/// If the point format of the input file does not include colour data, the output file will be upgraded
+/// to a point format that does, i.e. point format 2 for inputs without GPS time data and point format 3
+/// for those with GPS time data. Colourized point clouds may be interpolated using the 'rgb' parameter of
+/// the `LidarNearestNeighbourGridding` and `LidarTINGridding` tools.
+///
+/// # See Also
+/// `CreateColourComposite`, `LidarNearestNeighbourGridding`, `LidarTINGridding`
 pub struct LidarColourize {
     name: String,
     description: String,
@@ -175,16 +194,9 @@ impl WhiteboxTool for LidarColourize {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_lidar_file.contains(sep) && !input_lidar_file.contains("/") {
-            input_lidar_file = format!("{}{}", working_directory, input_lidar_file);
-        }
-        if !input_image_file.contains(sep) && !input_image_file.contains("/") {
-            input_image_file = format!("{}{}", working_directory, input_image_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_lidar_file = resolve_path(&input_lidar_file, working_directory);
+        input_image_file = resolve_path(&input_image_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input files...");
@@ -199,7 +211,12 @@ impl WhiteboxTool for LidarColourize {
         }
 
         let n_points = in_lidar.header.number_of_points as usize;
-        let num_points: f64 = (in_lidar.header.number_of_points - 1) as f64; // used for progress calculation only
+        let num_points: f64 = (in_lidar.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+
+        // Single-band images are rendered as greyscale, stretched over their display range.
+        let is_rgb = in_image.configs.photometric_interp == PhotometricInterpretation::RGB;
+        let display_min = in_image.configs.display_min;
+        let display_range = in_image.configs.display_max - display_min;
 
         let mut progress: i32;
         let mut old_progress: i32 = -1;
@@ -212,22 +229,31 @@ impl WhiteboxTool for LidarColourize {
             thread::spawn(move || {
                 let (mut row, mut col): (isize, isize);
                 let mut value: f64;
+                let mut grey: u32;
                 let nodata = in_image.configs.nodata;
                 for i in (0..n_points).filter(|point_num| point_num % num_procs == tid) {
                     let p: PointData = in_lidar.get_point_info(i);
                     row = in_image.get_row_from_y(p.y);
                     col = in_image.get_column_from_x(p.x);
                     value = in_image.get_value(row, col);
-                    if value != nodata {
-                        tx.send((i, value as u32)).unwrap();
+                    if value == nodata {
+                        tx.send((i, None)).unwrap();
+                    } else if is_rgb {
+                        tx.send((i, Some(value as u32))).unwrap();
                     } else {
-                        tx.send((i, 0u32)).unwrap();
+                        grey = if display_range > 0f64 {
+                            ((value - display_min) / display_range * 255f64).clamp(0f64, 255f64)
+                                as u32
+                        } else {
+                            0u32
+                        };
+                        tx.send((i, Some((grey << 16) | (grey << 8) | grey))).unwrap();
                     }
                 }
             });
         }
 
-        let mut colour_values: Vec<u32> = vec![0u32; n_points];
+        let mut colour_values: Vec<Option<u32>> = vec![None; n_points];
         for i in 0..n_points {
             let data = rx.recv().unwrap();
             colour_values[data.0] = data.1;
@@ -257,22 +283,41 @@ impl WhiteboxTool for LidarColourize {
                 ))
             }
         };
+        if verbose && out_pt_format != in_lidar.header.point_format {
+            println!(
+                "Point format {} has been converted to point format {}.",
+                in_lidar.header.point_format, out_pt_format
+            );
+        }
         output.header.point_format = out_pt_format;
 
         let (mut r, mut g, mut b): (u16, u16, u16);
-        let mut value: u32;
         let mut p: PointData;
         let mut gps: f64;
-        for i in 0..in_lidar.header.number_of_points as usize {
-            value = colour_values[i];
-            r = (value & 0xFF) as u16 * 256u16;
-            g = ((value >> 8) & 0xFF) as u16 * 256u16;
-            b = ((value >> 16) & 0xFF) as u16 * 256u16;
-            let rgb: ColourData = ColourData {
-                red: r,
-                green: g,
-                blue: b,
-                nir: 0u16,
+        let mut num_outside = 0usize;
+        for i in 0..n_points {
+            let rgb: ColourData = match colour_values[i] {
+                Some(value) => {
+                    // 8-bit colours are scaled to the full 16-bit range of LAS colour values
+                    r = (value & 0xFF) as u16 * 257u16;
+                    g = ((value >> 8) & 0xFF) as u16 * 257u16;
+                    b = ((value >> 16) & 0xFF) as u16 * 257u16;
+                    ColourData {
+                        red: r,
+                        green: g,
+                        blue: b,
+                        nir: 0u16,
+                    }
+                }
+                None => {
+                    // points outside of the image retain their existing colour, if any
+                    num_outside += 1;
+                    if in_lidar.has_rgb() {
+                        in_lidar.get_rgb(i)?
+                    } else {
+                        ColourData::default()
+                    }
+                }
             };
 
             p = in_lidar[i];
@@ -290,6 +335,7 @@ impl WhiteboxTool for LidarColourize {
                     colour_data: rgb,
                 });
             }
+            output.add_extra_bytes(in_lidar.get_extra_bytes(i));
 
             if verbose {
                 progress = (100.0_f64 * i as f64 / num_points) as i32;
@@ -300,9 +346,15 @@ impl WhiteboxTool for LidarColourize {
             }
         }
 
+        if verbose && num_outside > 0 {
+            println!(
+                "Warning: {} points were outside of the image or coincided with NoData cells and were not colourized.",
+                num_outside
+            );
+        }
+
         let elapsed_time = get_formatted_elapsed_time(start);
 
-        println!("");
         if verbose {
             println!("Writing output LAS file...");
         }
@@ -166,6 +166,16 @@ impl WhiteboxTool for LidarConstructVectorTIN {
         self.toolbox.clone()
     }
 
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--exclude_cls".to_string(), ParameterConstraint::ListRange(0.0, 255.0)),
+        ]
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_dir}{input_stem}.shp".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -303,6 +313,13 @@ impl WhiteboxTool for LidarConstructVectorTIN {
             //         }
             //     }
             // }
+            // the output file may be a template used to name the output of each input file,
+            // e.g. '{input_stem}_dem.tif'
+            let output_template = if is_output_template(&output_file) {
+                output_file.clone()
+            } else {
+                self.get_default_output_template().unwrap()
+            };
             if std::path::Path::new(&working_directory).is_dir() {
                 for entry in fs::read_dir(working_directory.clone())? {
                     let s = entry?
@@ -311,20 +328,12 @@ impl WhiteboxTool for LidarConstructVectorTIN {
                     .to_str()
                     .expect("Error reading path string")
                     .to_string();
-                    if s.to_lowercase().ends_with(".las") {
+                    if s.to_lowercase().ends_with(".las") || s.to_lowercase().ends_with(".zip") {
+                        let mut out_file =
+                            expand_output_template(&output_template, &s, &self.get_tool_name());
+                        out_file = resolve_path(&out_file, working_directory);
                         inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".las", ".tif")
-                                .replace(".LAS", ".tif"),
-                        )
-                    } else if s.to_lowercase().ends_with(".zip") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".zip", ".tif")
-                                .replace(".ZIP", ".tif"),
-                        )
+                        outputs.push(out_file);
                     }
                 }
             } else {
@@ -334,19 +343,16 @@ impl WhiteboxTool for LidarConstructVectorTIN {
                 ));
             }
         } else {
-            if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-                input_file = format!("{}{}", working_directory, input_file);
-            }
+            input_file = resolve_path(&input_file, working_directory);
             inputs.push(input_file.clone());
             if output_file.is_empty() {
-                output_file = input_file
-                    .clone()
-                    .replace(".las", ".tif")
-                    .replace(".LAS", ".tif");
-            }
-            if !output_file.contains(path::MAIN_SEPARATOR) && !output_file.contains("/") {
-                output_file = format!("{}{}", working_directory, output_file);
+                output_file = expand_output_template(
+                    &self.get_default_output_template().unwrap(),
+                    &input_file,
+                    &self.get_tool_name(),
+                );
             }
+            output_file = resolve_path(&output_file, working_directory);
             outputs.push(output_file);
         }
 
@@ -0,0 +1,991 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::triangulate;
+use crate::lidar::*;
+use crate::raster::*;
+use crate::structures::Point2D;
+use crate::tools::*;
+use crate::vector::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufReader, BufWriter, Error, ErrorKind};
+use std::path;
+
+/// This tool measures the distances between the points in a LiDAR point cloud (`--input`) and a
+/// reference surface (`--reference`), such as a surveyed control surface, for the purpose of
+/// vertical accuracy assessment and certification. The reference surface may be either:
+///
+/// - a raster DEM, which is converted into a triangular mesh connecting its cell centres;
+/// - a triangular mesh in Wavefront OBJ format (e.g. created by `LidarTINToMesh` without the
+///   `--local_origin` option), in the same coordinate system as the point cloud; or
+/// - a set of control points, as either a LAS file or a PointZ Shapefile, which is converted into a
+///   triangulated irregular network (TIN) by Delaunay triangulation.
+///
+/// Two distances are calculated for each point: the vertical distance (i.e. the point elevation minus
+/// the surface elevation directly below or above the point) and the 3-D distance (i.e. the distance
+/// to the nearest location on the surface, signed by the side of the surface on which the point lies).
+/// The mesh triangles are indexed with an octree, such that the nearest triangles to each point are
+/// found efficiently. Points that do not lie above or below the reference surface are not measured.
+/// The user may exclude points of certain classes from the assessment (`--exclude_cls`); for example,
+/// accuracy assessments are commonly restricted to ground (class 2) points.
+///
+/// The output LAS file (`--output`) contains all of the input points, with three added extra bytes
+/// attributes: `VerticalDistance`, `Distance3D`, and `QCFlag`. The flag is 0 for points within the
+/// vertical tolerance of the surface, 1 for points exceeding the tolerance, 2 for points outside of
+/// the reference surface, and 3 for points of excluded classes. The distances of unmeasured points
+/// are set to the no-data value of -9999. The vertical tolerance (`--tolerance`) is, by default, the
+/// NSSDA vertical accuracy at the 95% confidence level (i.e. 1.96 &times; RMSE<sub>z</sub>).
+///
+/// The distance statistics, including the mean error (bias), standard deviation, RMSE<sub>z</sub>,
+/// NSSDA vertical accuracy, and the 95th percentile of the absolute vertical errors (the ASPRS
+/// measure of vertical accuracy in vegetated areas), are printed and may optionally be saved to an
+/// HTML report (`--report`).
+///
+/// # See Also
+/// `LidarNormalizeByDem`, `LidarTINToMesh`, `LidarKappaIndex`
+pub struct LidarDistanceToSurface {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarDistanceToSurface {
+    pub fn new() -> LidarDistanceToSurface {
+        // public constructor
+        let name = "LidarDistanceToSurface".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Measures the vertical and 3-D distances of LiDAR points to a reference surface for accuracy assessment."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Reference Surface File".to_owned(),
+            flags: vec!["--reference".to_owned()],
+            description: "Input reference surface; a raster DEM, OBJ mesh, or LAS/Shapefile control points.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Any),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output LiDAR File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Report File (optional)".to_owned(),
+            flags: vec!["--report".to_owned()],
+            description: "Optional output HTML report of the distance statistics.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Html),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Vertical Tolerance (optional)".to_owned(),
+            flags: vec!["--tolerance".to_owned()],
+            description: "Optional vertical tolerance used to flag points; the NSSDA 95% vertical accuracy is used by default.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Exclusion Classes (0-18, based on LAS spec; e.g. 3,4,5,6,7)".to_owned(),
+            flags: vec!["--exclude_cls".to_owned()],
+            description: "Optional exclude classes from the assessment; Valid class values range from 0 to 18, based on LAS specifications. Example, --exclude_cls='3,4,5,6,7,18'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las --reference=control.shp -o=qc.las --report=qc.html --exclude_cls='0,1,3,4,5,6,7,9,18'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las --reference=survey_dem.tif -o=qc.las --tolerance=0.196", short_exe, name).replace("*", &sep);
+
+        LidarDistanceToSurface {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarDistanceToSurface {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut reference_file = String::new();
+        let mut output_file = String::new();
+        let mut report_file = String::new();
+        let mut tolerance = f64::NAN;
+        let mut include_class_vals = vec![true; 256];
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-reference" {
+                reference_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-report" {
+                report_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-tolerance" {
+                tolerance = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-exclude_cls" {
+                let exclude_cls_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                for value in exclude_cls_str.split([',', ';']) {
+                    if !value.trim().is_empty() {
+                        let c = value.trim().parse::<usize>().map_err(|_| {
+                            Error::new(
+                                ErrorKind::InvalidInput,
+                                format!("Invalid class value '{}'.", value.trim()),
+                            )
+                        })?;
+                        if c < 256 {
+                            include_class_vals[c] = false;
+                        }
+                    }
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        reference_file = resolve_path(&reference_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        if !report_file.is_empty() {
+            report_file = resolve_path(&report_file, working_directory);
+            if !report_file.to_lowercase().ends_with(".html") {
+                report_file.push_str(".html");
+            }
+        }
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = LasFile::new(&input_file, "r")?;
+
+        let start = Instant::now();
+
+        if verbose {
+            println!("Reading the reference surface...");
+        }
+        let mesh = read_reference_surface(&reference_file, &input)?;
+        if mesh.triangles.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The reference surface does not contain any triangles within the extent of the point cloud.",
+            ));
+        }
+        if verbose {
+            println!(
+                "Building the octree of {} reference triangles...",
+                mesh.triangles.len()
+            );
+        }
+        let octree = TriangleOctree::new(&mesh);
+
+        // measure the distances
+        let n_points = input.header.number_of_points as usize;
+        let mut vertical_distances = vec![f64::NAN; n_points];
+        let mut distances_3d = vec![f64::NAN; n_points];
+        let mut flags = vec![0u8; n_points];
+        let (mut num_excluded, mut num_outside) = (0usize, 0usize);
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            if p.withheld() || !include_class_vals[p.classification() as usize] {
+                flags[i] = 3u8;
+                num_excluded += 1;
+            } else {
+                let point = [p.x, p.y, p.z];
+                match octree.surface_elevation(&mesh, p.x, p.y, p.z) {
+                    Some(surface_z) => {
+                        let dz = p.z - surface_z;
+                        let d = octree.nearest_distance(&mesh, &point);
+                        vertical_distances[i] = dz;
+                        distances_3d[i] = if dz < 0f64 { -d } else { d };
+                    }
+                    None => {
+                        flags[i] = 2u8;
+                        num_outside += 1;
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / n_points as f64) as usize;
+                if progress != old_progress {
+                    println!("Measuring distances: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let measured: Vec<usize> = (0..n_points).filter(|&i| flags[i] == 0u8).collect();
+        if measured.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "None of the points overlap the reference surface.",
+            ));
+        }
+        let stats = DistanceStats::new(
+            &measured.iter().map(|&i| vertical_distances[i]).collect::<Vec<f64>>(),
+            &measured.iter().map(|&i| distances_3d[i]).collect::<Vec<f64>>(),
+        );
+        if tolerance.is_nan() {
+            tolerance = stats.nssda_accuracy;
+        }
+        let mut num_exceeding = 0usize;
+        for &i in &measured {
+            if vertical_distances[i].abs() > tolerance {
+                flags[i] = 1u8;
+                num_exceeding += 1;
+            }
+        }
+
+        // create the output file with the distance attributes appended to the extra bytes
+        let no_data = -9999f64;
+        let mut vertical_descriptor = ExtraBytesDescriptor::new(
+            "VerticalDistance",
+            9u8,
+            "vertical distance to the reference surface",
+        );
+        vertical_descriptor.set_no_data(no_data);
+        let mut distance_descriptor =
+            ExtraBytesDescriptor::new("Distance3D", 9u8, "3-D distance to the reference surface");
+        distance_descriptor.set_no_data(no_data);
+        let flag_descriptor = ExtraBytesDescriptor::new("QCFlag", 1u8, "accuracy assessment flag");
+        let new_names = ["VerticalDistance", "Distance3D", "QCFlag"];
+        if input
+            .extra_bytes_descriptors
+            .iter()
+            .any(|d| new_names.contains(&d.name.as_str()))
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file already contains VerticalDistance, Distance3D, or QCFlag attributes.",
+            ));
+        }
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        let mut descriptors = input.extra_bytes_descriptors.clone();
+        descriptors.push(vertical_descriptor.clone());
+        descriptors.push(distance_descriptor.clone());
+        descriptors.push(flag_descriptor.clone());
+        output.set_extra_bytes_descriptors(descriptors);
+        let offset = input.get_extra_bytes_length();
+        let mut extra_bytes = vec![0u8; output.get_extra_bytes_length()];
+        for i in 0..n_points {
+            let input_bytes = input.get_extra_bytes(i);
+            extra_bytes[0..input_bytes.len()].copy_from_slice(input_bytes);
+            let value = |d: f64| if d.is_nan() { no_data } else { d };
+            vertical_descriptor.set_value(value(vertical_distances[i]), &mut extra_bytes[offset..]);
+            distance_descriptor.set_value(value(distances_3d[i]), &mut extra_bytes[offset + 4..]);
+            flag_descriptor.set_value(flags[i] as f64, &mut extra_bytes[offset + 8..]);
+            output.add_point_record(input.get_record(i));
+            output.add_extra_bytes(&extra_bytes);
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        println!("Vertical accuracy assessment ({} points):", stats.n);
+        println!("  Mean error (bias): {:.4}", stats.mean);
+        println!("  Standard deviation: {:.4}", stats.std_dev);
+        println!("  RMSEz: {:.4}", stats.rmse);
+        println!(
+            "  NSSDA vertical accuracy (95% confidence): {:.4}",
+            stats.nssda_accuracy
+        );
+        println!(
+            "  95th percentile absolute error: {:.4}",
+            stats.percentile_95
+        );
+        println!("  Mean 3-D distance: {:.4}", stats.mean_3d);
+        println!("  Points exceeding the tolerance ({:.4}): {}", tolerance, num_exceeding);
+        if num_outside > 0 {
+            println!(
+                "Warning: {} points were outside of the reference surface and were not measured.",
+                num_outside
+            );
+        }
+
+        if !report_file.is_empty() {
+            write_report(
+                &report_file,
+                &input_file,
+                &reference_file,
+                &stats,
+                tolerance,
+                num_exceeding,
+                num_outside,
+                num_excluded,
+            )?;
+            if verbose {
+                println!("Please see {} for the accuracy report.", report_file);
+            }
+        }
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A triangular mesh, with triangles stored as indices into the vertex list.
+struct Mesh {
+    vertices: Vec<[f64; 3]>,
+    triangles: Vec<[usize; 3]>,
+}
+
+/// Reads the reference surface, converting raster DEMs and control points into triangular meshes.
+/// Only the part of a raster DEM that overlaps the point cloud is converted.
+fn read_reference_surface(file_name: &str, las: &LasFile) -> Result<Mesh, Error> {
+    let lower = file_name.to_lowercase();
+    let mut vertices: Vec<[f64; 3]> = vec![];
+    let mut triangles: Vec<[usize; 3]> = vec![];
+    if lower.ends_with(".obj") {
+        let f = File::open(file_name)?;
+        for line in BufReader::new(f).lines() {
+            let line = line?;
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("v") => {
+                    let mut v = [0f64; 3];
+                    for j in 0..3 {
+                        v[j] = words
+                            .next()
+                            .and_then(|w| w.parse::<f64>().ok())
+                            .ok_or_else(|| {
+                                Error::new(ErrorKind::InvalidData, "Invalid OBJ vertex record.")
+                            })?;
+                    }
+                    vertices.push(v);
+                }
+                Some("f") => {
+                    // face vertices may take the forms v, v/vt, v//vn, or v/vt/vn, and negative
+                    // indices are relative to the end of the vertex list
+                    let mut face = vec![];
+                    for w in words {
+                        let index = w
+                            .split('/')
+                            .next()
+                            .and_then(|s| s.parse::<isize>().ok())
+                            .ok_or_else(|| {
+                                Error::new(ErrorKind::InvalidData, "Invalid OBJ face record.")
+                            })?;
+                        let index = if index < 0 {
+                            vertices.len() as isize + index
+                        } else {
+                            index - 1
+                        };
+                        if index < 0 || index as usize >= vertices.len() {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                "An OBJ face references an undefined vertex.",
+                            ));
+                        }
+                        face.push(index as usize);
+                    }
+                    for j in 1..face.len().saturating_sub(1) {
+                        triangles.push([face[0], face[j], face[j + 1]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    } else if lower.ends_with(".las") || lower.ends_with(".shp") {
+        let mut points: Vec<Point2D> = vec![];
+        if lower.ends_with(".las") {
+            let control = LasFile::new(file_name, "r")?;
+            for i in 0..control.header.number_of_points as usize {
+                let p: PointData = control[i];
+                if !p.withheld() {
+                    points.push(Point2D::new(p.x, p.y));
+                    vertices.push([p.x, p.y, p.z]);
+                }
+            }
+        } else {
+            let control = Shapefile::read(file_name)?;
+            if control.header.shape_type != ShapeType::PointZ
+                && control.header.shape_type != ShapeType::MultiPointZ
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The reference control points must be of a PointZ or MultiPointZ shape type.",
+                ));
+            }
+            for record_num in 0..control.num_records {
+                let record = control.get_record(record_num);
+                for (j, p) in record.points.iter().enumerate() {
+                    points.push(Point2D::new(p.x, p.y));
+                    vertices.push([p.x, p.y, record.z_array[j]]);
+                }
+            }
+        }
+        let result = triangulate(&points).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The control points could not be triangulated.",
+            )
+        })?;
+        for t in 0..result.triangles.len() / 3 {
+            let pts = result.points_of_triangle(t);
+            triangles.push([pts[0], pts[1], pts[2]]);
+        }
+    } else {
+        let dem = Raster::new(file_name, "r")?;
+        let nodata = dem.configs.nodata;
+        // the DEM is clipped to the extent of the point cloud, plus a margin of two cells
+        let row_start = (dem.get_row_from_y(las.header.max_y) - 2).max(0);
+        let row_end = (dem.get_row_from_y(las.header.min_y) + 2).min(dem.configs.rows as isize - 1);
+        let col_start = (dem.get_column_from_x(las.header.min_x) - 2).max(0);
+        let col_end =
+            (dem.get_column_from_x(las.header.max_x) + 2).min(dem.configs.columns as isize - 1);
+        if row_start > row_end || col_start > col_end {
+            return Ok(Mesh {
+                vertices: vertices,
+                triangles: triangles,
+            });
+        }
+        let columns = (col_end - col_start + 1) as usize;
+        let mut vertex_index = vec![usize::MAX; (row_end - row_start + 1) as usize * columns];
+        for row in row_start..=row_end {
+            for col in col_start..=col_end {
+                let z = dem.get_value(row, col);
+                if z != nodata {
+                    vertex_index[(row - row_start) as usize * columns + (col - col_start) as usize] =
+                        vertices.len();
+                    vertices.push([dem.get_x_from_column(col), dem.get_y_from_row(row), z]);
+                }
+            }
+        }
+        for r in 0..(row_end - row_start) as usize {
+            for c in 0..columns - 1 {
+                // the four corners of the quad formed by neighbouring cell centres
+                let v = [
+                    vertex_index[r * columns + c],
+                    vertex_index[r * columns + c + 1],
+                    vertex_index[(r + 1) * columns + c + 1],
+                    vertex_index[(r + 1) * columns + c],
+                ];
+                let num_valid = v.iter().filter(|&&i| i != usize::MAX).count();
+                if num_valid == 4 {
+                    triangles.push([v[0], v[1], v[2]]);
+                    triangles.push([v[0], v[2], v[3]]);
+                } else if num_valid == 3 {
+                    let t: Vec<usize> = v.iter().cloned().filter(|&i| i != usize::MAX).collect();
+                    triangles.push([t[0], t[1], t[2]]);
+                }
+            }
+        }
+    }
+    Ok(Mesh {
+        vertices: vertices,
+        triangles: triangles,
+    })
+}
+
+/// An octree node; leaves store the indices of the triangles whose bounding boxes overlap the node.
+struct OctreeNode {
+    min: [f64; 3],
+    max: [f64; 3],
+    first_child: usize,
+    triangles: Vec<usize>,
+}
+
+/// An octree of the triangles of a mesh, used for nearest-triangle and vertical searches.
+struct TriangleOctree {
+    nodes: Vec<OctreeNode>,
+}
+
+const OCTREE_LEAF_SIZE: usize = 16;
+const OCTREE_MAX_DEPTH: usize = 16;
+
+impl TriangleOctree {
+    fn new(mesh: &Mesh) -> TriangleOctree {
+        let bounds: Vec<([f64; 3], [f64; 3])> = mesh
+            .triangles
+            .iter()
+            .map(|t| {
+                let mut min = mesh.vertices[t[0]];
+                let mut max = min;
+                for &v in &t[1..] {
+                    for j in 0..3 {
+                        min[j] = min[j].min(mesh.vertices[v][j]);
+                        max[j] = max[j].max(mesh.vertices[v][j]);
+                    }
+                }
+                (min, max)
+            })
+            .collect();
+        let mut min = [f64::INFINITY; 3];
+        let mut max = [f64::NEG_INFINITY; 3];
+        for b in &bounds {
+            for j in 0..3 {
+                min[j] = min[j].min(b.0[j]);
+                max[j] = max[j].max(b.1[j]);
+            }
+        }
+        let mut octree = TriangleOctree {
+            nodes: vec![OctreeNode {
+                min: min,
+                max: max,
+                first_child: 0,
+                triangles: (0..mesh.triangles.len()).collect(),
+            }],
+        };
+        // split the nodes breadth-first
+        let mut stack = vec![(0usize, 0usize)];
+        while let Some((node, depth)) = stack.pop() {
+            if octree.nodes[node].triangles.len() <= OCTREE_LEAF_SIZE || depth >= OCTREE_MAX_DEPTH {
+                continue;
+            }
+            let (min, max) = (octree.nodes[node].min, octree.nodes[node].max);
+            let mid = [
+                (min[0] + max[0]) / 2f64,
+                (min[1] + max[1]) / 2f64,
+                (min[2] + max[2]) / 2f64,
+            ];
+            let mut children = vec![];
+            for k in 0..8 {
+                let mut child_min = min;
+                let mut child_max = mid;
+                for j in 0..3 {
+                    if k & (1 << j) != 0 {
+                        child_min[j] = mid[j];
+                        child_max[j] = max[j];
+                    }
+                }
+                let tris: Vec<usize> = octree.nodes[node]
+                    .triangles
+                    .iter()
+                    .cloned()
+                    .filter(|&t| {
+                        (0..3).all(|j| bounds[t].0[j] <= child_max[j] && bounds[t].1[j] >= child_min[j])
+                    })
+                    .collect();
+                children.push(OctreeNode {
+                    min: child_min,
+                    max: child_max,
+                    first_child: 0,
+                    triangles: tris,
+                });
+            }
+            // large triangles overlapping every child cannot be separated by splitting
+            let n = octree.nodes[node].triangles.len();
+            if children.iter().all(|c| c.triangles.len() == n) {
+                continue;
+            }
+            let first_child = octree.nodes.len();
+            octree.nodes[node].first_child = first_child;
+            octree.nodes[node].triangles = vec![];
+            for (k, child) in children.into_iter().enumerate() {
+                octree.nodes.push(child);
+                stack.push((first_child + k, depth + 1));
+            }
+        }
+        octree
+    }
+
+    fn is_leaf(&self, node: usize) -> bool {
+        self.nodes[node].first_child == 0
+    }
+
+    /// Returns the elevation of the surface at (x, y), or None if (x, y) is outside of the surface.
+    /// Where the surface overlaps itself, the elevation nearest to z is returned.
+    fn surface_elevation(&self, mesh: &Mesh, x: f64, y: f64, z: f64) -> Option<f64> {
+        let mut ret: Option<f64> = None;
+        let mut stack = vec![0usize];
+        while let Some(node) = stack.pop() {
+            let n = &self.nodes[node];
+            if x < n.min[0] || x > n.max[0] || y < n.min[1] || y > n.max[1] {
+                continue;
+            }
+            if !self.is_leaf(node) {
+                stack.extend(n.first_child..n.first_child + 8);
+                continue;
+            }
+            for &t in &n.triangles {
+                let [a, b, c] = [
+                    mesh.vertices[mesh.triangles[t][0]],
+                    mesh.vertices[mesh.triangles[t][1]],
+                    mesh.vertices[mesh.triangles[t][2]],
+                ];
+                let det = (b[1] - c[1]) * (a[0] - c[0]) + (c[0] - b[0]) * (a[1] - c[1]);
+                if det == 0f64 {
+                    continue;
+                }
+                let l1 = ((b[1] - c[1]) * (x - c[0]) + (c[0] - b[0]) * (y - c[1])) / det;
+                let l2 = ((c[1] - a[1]) * (x - c[0]) + (a[0] - c[0]) * (y - c[1])) / det;
+                let l3 = 1f64 - l1 - l2;
+                let eps = -1e-9;
+                if l1 >= eps && l2 >= eps && l3 >= eps {
+                    let zs = l1 * a[2] + l2 * b[2] + l3 * c[2];
+                    if ret.is_none_or(|r| (zs - z).abs() < (r - z).abs()) {
+                        ret = Some(zs);
+                    }
+                }
+            }
+        }
+        ret
+    }
+
+    /// Returns the distance from a point to the nearest location on the surface.
+    fn nearest_distance(&self, mesh: &Mesh, p: &[f64; 3]) -> f64 {
+        let box_dist_sqr = |n: &OctreeNode| -> f64 {
+            let mut d = 0f64;
+            for j in 0..3 {
+                let e = if p[j] < n.min[j] {
+                    n.min[j] - p[j]
+                } else if p[j] > n.max[j] {
+                    p[j] - n.max[j]
+                } else {
+                    0f64
+                };
+                d += e * e;
+            }
+            d
+        };
+        let mut best = f64::INFINITY;
+        // best-first traversal; non-negative distances order correctly by their bit patterns
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((box_dist_sqr(&self.nodes[0]).to_bits(), 0usize)));
+        while let Some(Reverse((d_bits, node))) = heap.pop() {
+            if f64::from_bits(d_bits) >= best {
+                break;
+            }
+            let n = &self.nodes[node];
+            if self.is_leaf(node) {
+                for &t in &n.triangles {
+                    let q = closest_point_on_triangle(
+                        p,
+                        &mesh.vertices[mesh.triangles[t][0]],
+                        &mesh.vertices[mesh.triangles[t][1]],
+                        &mesh.vertices[mesh.triangles[t][2]],
+                    );
+                    let d = (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2);
+                    if d < best {
+                        best = d;
+                    }
+                }
+            } else {
+                for child in n.first_child..n.first_child + 8 {
+                    let d = box_dist_sqr(&self.nodes[child]);
+                    if d < best
+                        && (!self.is_leaf(child) || !self.nodes[child].triangles.is_empty())
+                    {
+                        heap.push(Reverse((d.to_bits(), child)));
+                    }
+                }
+            }
+        }
+        best.sqrt()
+    }
+}
+
+/// Returns the closest point on triangle (a, b, c) to point p (after Ericson, 2005, Real-Time
+/// Collision Detection).
+fn closest_point_on_triangle(p: &[f64; 3], a: &[f64; 3], b: &[f64; 3], c: &[f64; 3]) -> [f64; 3] {
+    let sub = |u: &[f64; 3], v: &[f64; 3]| [u[0] - v[0], u[1] - v[1], u[2] - v[2]];
+    let dot = |u: &[f64; 3], v: &[f64; 3]| u[0] * v[0] + u[1] * v[1] + u[2] * v[2];
+    let along = |o: &[f64; 3], d: &[f64; 3], t: f64| [o[0] + t * d[0], o[1] + t * d[1], o[2] + t * d[2]];
+    let ab = sub(b, a);
+    let ac = sub(c, a);
+    let ap = sub(p, a);
+    let d1 = dot(&ab, &ap);
+    let d2 = dot(&ac, &ap);
+    if d1 <= 0f64 && d2 <= 0f64 {
+        return *a;
+    }
+    let bp = sub(p, b);
+    let d3 = dot(&ab, &bp);
+    let d4 = dot(&ac, &bp);
+    if d3 >= 0f64 && d4 <= d3 {
+        return *b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0f64 && d1 >= 0f64 && d3 <= 0f64 {
+        return along(a, &ab, d1 / (d1 - d3));
+    }
+    let cp = sub(p, c);
+    let d5 = dot(&ab, &cp);
+    let d6 = dot(&ac, &cp);
+    if d6 >= 0f64 && d5 <= d6 {
+        return *c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0f64 && d2 >= 0f64 && d6 <= 0f64 {
+        return along(a, &ac, d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0f64 && (d4 - d3) >= 0f64 && (d5 - d6) >= 0f64 {
+        let bc = sub(c, b);
+        return along(b, &bc, (d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = 1f64 / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    [
+        a[0] + ab[0] * v + ac[0] * w,
+        a[1] + ab[1] * v + ac[1] * w,
+        a[2] + ab[2] * v + ac[2] * w,
+    ]
+}
+
+/// Summary statistics of the vertical and 3-D distances.
+struct DistanceStats {
+    n: usize,
+    mean: f64,
+    std_dev: f64,
+    rmse: f64,
+    nssda_accuracy: f64,
+    percentile_95: f64,
+    min: f64,
+    max: f64,
+    mean_3d: f64,
+    rmse_3d: f64,
+}
+
+impl DistanceStats {
+    fn new(vertical: &[f64], distances_3d: &[f64]) -> DistanceStats {
+        let n = vertical.len();
+        let mean = vertical.iter().sum::<f64>() / n as f64;
+        let rmse = (vertical.iter().map(|d| d * d).sum::<f64>() / n as f64).sqrt();
+        let std_dev = if n > 1 {
+            (vertical.iter().map(|d| (d - mean) * (d - mean)).sum::<f64>() / (n - 1) as f64).sqrt()
+        } else {
+            0f64
+        };
+        let mut abs_errors: Vec<f64> = vertical.iter().map(|d| d.abs()).collect();
+        abs_errors.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let rank = ((0.95 * n as f64).ceil() as usize).clamp(1, n);
+        DistanceStats {
+            n: n,
+            mean: mean,
+            std_dev: std_dev,
+            rmse: rmse,
+            nssda_accuracy: 1.96 * rmse,
+            percentile_95: abs_errors[rank - 1],
+            min: vertical.iter().cloned().fold(f64::INFINITY, f64::min),
+            max: vertical.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            mean_3d: distances_3d.iter().map(|d| d.abs()).sum::<f64>() / n as f64,
+            rmse_3d: (distances_3d.iter().map(|d| d * d).sum::<f64>() / n as f64).sqrt(),
+        }
+    }
+}
+
+fn write_report(
+    report_file: &str,
+    input_file: &str,
+    reference_file: &str,
+    stats: &DistanceStats,
+    tolerance: f64,
+    num_exceeding: usize,
+    num_outside: usize,
+    num_excluded: usize,
+) -> Result<(), Error> {
+    let f = File::create(report_file)?;
+    let mut writer = BufWriter::new(f);
+    let file_name = |s: &str| {
+        path::Path::new(s)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    writer.write_all(
+        "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">
+<head>
+    <meta content=\"text/html; charset=iso-8859-1\" http-equiv=\"content-type\">
+    <title>LiDAR Vertical Accuracy Assessment</title>
+    <style  type=\"text/css\">
+        h1 { font-size: 14pt; margin-left: 15px; margin-right: 15px; text-align: center; font-family: Helvetica, Verdana, Geneva, Arial, sans-serif; }
+        p { font-size: 12pt; font-family: Helvetica, Verdana, Geneva, Arial, sans-serif; margin-left: 15px; margin-right: 15px; }
+        table { font-size: 12pt; font-family: Helvetica, Verdana, Geneva, Arial, sans-serif; border-collapse: collapse; margin-left: 15px; }
+        td { text-align: left; padding: 8px; }
+        tr:nth-child(1) { border-top: 2px solid #333333; }
+        tr:last-child { border-bottom: 2px solid #333333; }
+        tr:nth-child(even) { background-color: #dddddd; }
+        .numberCell { text-align: right; }
+    </style>
+</head>
+<body>
+<h1>LiDAR Vertical Accuracy Assessment</h1>\n"
+            .as_bytes(),
+    )?;
+    writer.write_all(
+        format!(
+            "<p><strong>Point Cloud:</strong> {}<br><strong>Reference Surface:</strong> {}</p>\n",
+            file_name(input_file),
+            file_name(reference_file)
+        )
+        .as_bytes(),
+    )?;
+    let rows = vec![
+        ("Number of points assessed", format!("{}", stats.n)),
+        ("Points outside of the reference surface", format!("{}", num_outside)),
+        ("Points of excluded classes", format!("{}", num_excluded)),
+        ("Mean vertical error (bias)", format!("{:.4}", stats.mean)),
+        ("Standard deviation", format!("{:.4}", stats.std_dev)),
+        ("Minimum vertical error", format!("{:.4}", stats.min)),
+        ("Maximum vertical error", format!("{:.4}", stats.max)),
+        ("RMSE<sub>z</sub>", format!("{:.4}", stats.rmse)),
+        (
+            "NSSDA vertical accuracy (95% confidence; 1.96 &times; RMSE<sub>z</sub>)",
+            format!("{:.4}", stats.nssda_accuracy),
+        ),
+        (
+            "95th percentile absolute vertical error",
+            format!("{:.4}", stats.percentile_95),
+        ),
+        ("Mean 3-D distance", format!("{:.4}", stats.mean_3d)),
+        ("RMSE<sub>3D</sub>", format!("{:.4}", stats.rmse_3d)),
+        ("Vertical tolerance", format!("{:.4}", tolerance)),
+        ("Points exceeding the tolerance", format!("{}", num_exceeding)),
+    ];
+    writer.write_all("<table>\n".as_bytes())?;
+    for (label, value) in rows {
+        writer.write_all(
+            format!(
+                "<tr><td>{}</td><td class=\"numberCell\">{}</td></tr>\n",
+                label, value
+            )
+            .as_bytes(),
+        )?;
+    }
+    writer.write_all("</table>\n".as_bytes())?;
+    writer.write_all("<p>Notes:<br>1. Vertical errors are the point elevations minus the reference surface elevations.<br>
+2. The NSSDA vertical accuracy assumes normally distributed errors and is appropriate for non-vegetated terrain, whereas the 95th percentile is the ASPRS measure of vertical accuracy in vegetated terrain.</p>\n</body>\n".as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,440 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::na::Matrix3;
+use crate::structures::{DistanceMetric, FixedRadiusSearch3D};
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool calculates a set of geometric features for each point in a LiDAR point cloud from the
+/// eigenvalues of the covariance matrix of the points within a spherical neighbourhood of radius
+/// `--radius`. If the eigenvalues are λ<sub>1</sub> ≥ λ<sub>2</sub> ≥ λ<sub>3</sub>, and
+/// *e*<sub>*i*</sub> = λ<sub>*i*</sub> / (λ<sub>1</sub> + λ<sub>2</sub> + λ<sub>3</sub>) are the
+/// normalized eigenvalues, the features are (Weinmann et al., 2015):
+///
+/// | Feature       | Definition                                                         |
+/// |---------------|--------------------------------------------------------------------|
+/// | linearity     | (*e*<sub>1</sub> - *e*<sub>2</sub>) / *e*<sub>1</sub>              |
+/// | planarity     | (*e*<sub>2</sub> - *e*<sub>3</sub>) / *e*<sub>1</sub>              |
+/// | sphericity    | *e*<sub>3</sub> / *e*<sub>1</sub>                                  |
+/// | omnivariance  | (*e*<sub>1</sub> *e*<sub>2</sub> *e*<sub>3</sub>)<sup>1/3</sup>    |
+/// | anisotropy    | (*e*<sub>1</sub> - *e*<sub>3</sub>) / *e*<sub>1</sub>              |
+/// | eigenentropy  | -Σ *e*<sub>*i*</sub> ln(*e*<sub>*i*</sub>)                         |
+/// | curvature     | *e*<sub>3</sub>, i.e. the surface variation                        |
+/// | verticality   | 1 - \|*n*<sub>*z*</sub>\|, where *n* is the eigenvector of λ<sub>3</sub> |
+///
+/// Linearity, planarity, and sphericity sum to one and describe whether a neighbourhood is
+/// predominantly one-dimensional (e.g. wires and edges), two-dimensional (e.g. the ground and
+/// roofs), or three-dimensional (e.g. vegetation). Verticality is near zero for horizontal
+/// surfaces and near one for vertical surfaces, e.g. walls. These features are commonly used as the
+/// inputs of point cloud classification, e.g. the separation of vegetation, buildings, and wires.
+///
+/// The features are stored as the 32-bit floating-point extra bytes attributes of each point in the
+/// output LAS file, named as above, in addition to any extra bytes attributes within the input
+/// file. Points with fewer than `--min_neighbours` points (including the point itself) within the
+/// search radius are assigned the NoData value (-9999). The appropriate search radius depends on
+/// the point density and the size of the objects of interest; it should generally contain at least
+/// 10 to 20 points. The extra bytes attributes can be used by other tools, e.g. `LidarTINGridding`
+/// with `--parameter=planarity`.
+///
+/// # Reference
+/// Weinmann, M., Jutzi, B., Hinz, S., & Mallet, C. (2015). Semantic point cloud interpretation based
+/// on optimal neighborhoods, relevant features and efficient classifiers. *ISPRS Journal of
+/// Photogrammetry and Remote Sensing*, 105, 286-304.
+///
+/// # See Also
+/// `LidarPointNormals`, `LidarSegmentation`, `LidarGroundPointFilter`
+pub struct LidarEigenvalueFeatures {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarEigenvalueFeatures {
+    pub fn new() -> LidarEigenvalueFeatures {
+        // public constructor
+        let name = "LidarEigenvalueFeatures".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Calculates eigenvalue-based geometric features (e.g. linearity, planarity, sphericity) for each point in a LiDAR point cloud.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Radius".to_owned(),
+            flags: vec!["--radius".to_owned()],
+            description: "Search radius of the local neighbourhood.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Number of Neighbours".to_owned(),
+            flags: vec!["--min_neighbours".to_owned()],
+            description: "Minimum number of points within the neighbourhood needed to calculate the features.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("5".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --radius=1.5 --min_neighbours=10", short_exe, name).replace("*", &sep);
+
+        LidarEigenvalueFeatures {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarEigenvalueFeatures {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--radius".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--min_neighbours".to_string(), ParameterConstraint::Min(3.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut search_radius = 1f64;
+        let mut min_neighbours = 5usize;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-radius" {
+                search_radius = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_neighbours" || flag_val == "-min_neighbors" {
+                min_neighbours = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if search_radius <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The search radius must be greater than zero.",
+            ));
+        }
+        if min_neighbours < 3 {
+            min_neighbours = 3;
+        }
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        let start = Instant::now();
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        let mut frs: FixedRadiusSearch3D<usize> =
+            FixedRadiusSearch3D::new(search_radius, DistanceMetric::SquaredEuclidean);
+        for i in 0..n_points {
+            let p: PointData = input.get_point_info(i);
+            frs.insert(p.x, p.y, p.z, i);
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Binning points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let frs = Arc::new(frs);
+        let input = Arc::new(input);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let frs = frs.clone();
+            let input = input.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let mut neighbours: Vec<[f64; 3]> = vec![];
+                for i in (0..n_points).filter(|point_num| point_num % num_procs == tid) {
+                    let p: PointData = input.get_point_info(i);
+                    neighbours.clear();
+                    for (j, _) in frs.search(p.x, p.y, p.z) {
+                        let p2 = input.get_point_info(j);
+                        neighbours.push([p2.x, p2.y, p2.z]);
+                    }
+                    let features = if neighbours.len() >= min_neighbours {
+                        eigenvalue_features(&neighbours)
+                    } else {
+                        None
+                    };
+                    tx.send((i, features)).unwrap();
+                }
+            });
+        }
+
+        let mut features: Vec<Option<[f64; NUM_FEATURES]>> = vec![None; n_points];
+        for i in 0..n_points {
+            let data = rx.recv().unwrap();
+            features[data.0] = data.1;
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Calculating features: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // now output the data; the features are appended to any existing extra bytes attributes.
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        let nodata = -9999f64;
+        let mut descriptors = input.extra_bytes_descriptors.clone();
+        let features_offset = input.get_extra_bytes_length();
+        let mut new_descriptors = [
+            ExtraBytesDescriptor::new("linearity", 9u8, "eigenvalue-based linearity"),
+            ExtraBytesDescriptor::new("planarity", 9u8, "eigenvalue-based planarity"),
+            ExtraBytesDescriptor::new("sphericity", 9u8, "eigenvalue-based sphericity"),
+            ExtraBytesDescriptor::new("omnivariance", 9u8, "eigenvalue-based omnivariance"),
+            ExtraBytesDescriptor::new("anisotropy", 9u8, "eigenvalue-based anisotropy"),
+            ExtraBytesDescriptor::new("eigenentropy", 9u8, "eigenvalue-based entropy"),
+            ExtraBytesDescriptor::new("curvature", 9u8, "surface variation"),
+            ExtraBytesDescriptor::new("verticality", 9u8, "one minus abs. normal z"),
+        ];
+        for d in new_descriptors.iter_mut() {
+            d.set_no_data(nodata);
+            descriptors.push(d.clone());
+        }
+        output.set_extra_bytes_descriptors(descriptors);
+        let mut extra_bytes = vec![0u8; output.get_extra_bytes_length()];
+        for i in 0..n_points {
+            let input_bytes = input.get_extra_bytes(i);
+            extra_bytes[0..input_bytes.len()].copy_from_slice(input_bytes);
+            let values = features[i].unwrap_or([nodata; NUM_FEATURES]);
+            for (a, d) in new_descriptors.iter().enumerate() {
+                d.set_value(values[a], &mut extra_bytes[features_offset + 4 * a..]);
+            }
+            output.add_point_record(input.get_record(i));
+            output.add_extra_bytes(&extra_bytes);
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Saving data: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+const NUM_FEATURES: usize = 8;
+
+/// Calculates the eigenvalue-based features of a neighbourhood of points, returned in the order
+/// linearity, planarity, sphericity, omnivariance, anisotropy, eigenentropy, curvature, and
+/// verticality, or None if the points are coincident.
+fn eigenvalue_features(points: &[[f64; 3]]) -> Option<[f64; NUM_FEATURES]> {
+    let n = points.len();
+    if n < 3 {
+        return None;
+    }
+    let mut centroid = [0f64; 3];
+    for p in points {
+        for a in 0..3 {
+            centroid[a] += p[a] / n as f64;
+        }
+    }
+    let mut cov = Matrix3::<f64>::zeros();
+    for p in points {
+        let d = [p[0] - centroid[0], p[1] - centroid[1], p[2] - centroid[2]];
+        for r in 0..3 {
+            for c in 0..3 {
+                cov[(r, c)] += d[r] * d[c] / n as f64;
+            }
+        }
+    }
+    let eig = cov.symmetric_eigen();
+
+    // sort the eigenvalues into descending order
+    let mut order = [0usize, 1, 2];
+    order.sort_by(|a, b| {
+        eig.eigenvalues[*b]
+            .partial_cmp(&eig.eigenvalues[*a])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let l: Vec<f64> = order.iter().map(|a| eig.eigenvalues[*a].max(0f64)).collect();
+    let sum = l[0] + l[1] + l[2];
+    if sum <= 0f64 || l[0] <= 0f64 {
+        // all of the points are coincident
+        return None;
+    }
+    let e = [l[0] / sum, l[1] / sum, l[2] / sum];
+    let linearity = (e[0] - e[1]) / e[0];
+    let planarity = (e[1] - e[2]) / e[0];
+    let sphericity = e[2] / e[0];
+    let omnivariance = (e[0] * e[1] * e[2]).cbrt();
+    let anisotropy = (e[0] - e[2]) / e[0];
+    let eigenentropy: f64 = e
+        .iter()
+        .filter(|v| **v > 0f64)
+        .map(|v| -v * v.ln())
+        .sum();
+    let curvature = e[2];
+    let normal = eig.eigenvectors.column(order[2]);
+    let verticality = 1f64 - normal[2].abs();
+    Some([
+        linearity,
+        planarity,
+        sphericity,
+        omnivariance,
+        anisotropy,
+        eigenentropy,
+        curvature,
+        verticality,
+    ])
+}
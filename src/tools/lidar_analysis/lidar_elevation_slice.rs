@@ -240,12 +240,8 @@ impl WhiteboxTool for LidarElevationSlice {
             }
         }
 
-        if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(path::MAIN_SEPARATOR) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("***********************************");
@@ -260,13 +256,8 @@ impl WhiteboxTool for LidarElevationSlice {
             ));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_file.contains(sep) {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(sep) {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input LAS file...");
@@ -297,6 +288,7 @@ impl WhiteboxTool for LidarElevationSlice {
                 z = input.get_point_info(i).z;
                 if z >= minz && z <= maxz {
                     output.add_point_record(input.get_record(i));
+                    output.add_extra_bytes(input.get_extra_bytes(i));
                     num_points_filtered += 1;
                 }
                 if verbose {
@@ -443,6 +435,7 @@ impl WhiteboxTool for LidarElevationSlice {
                     }
                 }
                 output.add_point_record(pr2);
+                output.add_extra_bytes(input.get_extra_bytes(i));
                 if verbose {
                     progress = (100.0_f64 * i as f64 / num_points) as i32;
                     if progress != old_progress {
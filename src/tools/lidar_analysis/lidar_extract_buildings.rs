@@ -0,0 +1,1243 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use self::na::Matrix3;
+use crate::algorithms::{
+    minimum_bounding_box, polygon_area, polygon_perimeter, trace_region_boundaries,
+    MinimizationCriterion,
+};
+use crate::lidar::*;
+use crate::na;
+use crate::structures::{Array2D, DistanceMetric, FixedRadiusSearch3D, Point2D};
+use crate::tools::*;
+use crate::vector::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool extracts building footprints and planar roof faces from the building-class points
+/// (`--building_cls`, default 6) of a LiDAR point cloud (`--input`). It is intended to bridge LiDAR
+/// and cadastral mapping workflows and requires a LAS file in which buildings have already been
+/// classified.
+///
+/// The building points are first segmented into planar roof faces by region growing. A normal
+/// vector is fitted to the neighbourhood (`--radius`) of each point, and regions are then grown
+/// from the flattest points into neighbouring points with normals within `--max_angle` degrees of
+/// the region's normal and that lie within `--max_dist` of the region's fitted plane. The plane is
+/// periodically re-fitted as the region grows. Regions with fewer than `--min_points` points are
+/// discarded, which removes chimneys, dormers, and other roof furniture that are too small to be
+/// modelled reliably. The search radius should be about three to four times the average point
+/// spacing.
+///
+/// Footprints are derived by rasterizing the building points at `--resolution`, closing small
+/// gaps between points, and grouping the resulting cells into connected buildings. Buildings and
+/// interior courtyards smaller than `--min_area` (in squared map units) are removed. The boundaries
+/// of each building are then regularized: the stair-stepped cell boundaries are simplified, and
+/// edges within `--snap_angle` degrees of the building's dominant orientation, or perpendicular to
+/// it, are snapped to these directions. The dominant orientation is that of the long axis of the
+/// minimum-area bounding rectangle of the building's points. Because the footprints are made up of
+/// the cells that contain building points, they extend beyond the outermost points by about half
+/// of a grid cell, on average, and the resolution should therefore be similar to the point spacing.
+///
+/// The footprints are saved to the output polygon file (`--output`), with attributes for the area,
+/// perimeter, orientation (`ORIENT`, an azimuth from 0 to 180 degrees), number of roof faces, number
+/// of points, and minimum, maximum, and mean roof elevations of each building. If a roof faces file
+/// (`--roof_faces`) is specified, the roof faces are also output as regularized polygons, each
+/// attributed with its building's `FID` (`BLDG_ID`), number of points, planimetric and surface
+/// areas, slope and aspect (degrees; faces with slopes of less than one degree have no aspect), mean
+/// elevation, and the root-mean-square error (`RMSE`) of the points about the face's plane. Cells
+/// within a footprint that do not contain any points from a roof face are assigned to the face of
+/// a neighbouring cell, such that the roof faces of each building cover its footprint.
+///
+/// # See Also
+/// `LidarSegmentation`, `LidarRansacPlanes`, `LidarExtractWaterBodies`
+pub struct LidarExtractBuildings {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarExtractBuildings {
+    pub fn new() -> LidarExtractBuildings {
+        // public constructor
+        let name = "LidarExtractBuildings".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Extracts building footprints and planar roof faces from building-class LiDAR points.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Footprints File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output building footprint vector polygon file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Roof Faces File (optional)".to_owned(),
+            flags: vec!["--roof_faces".to_owned()],
+            description: "Optional output roof face vector polygon file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Building Class Value".to_owned(),
+            flags: vec!["--building_cls".to_owned()],
+            description: "Class value of the building points.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("6".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Radius".to_owned(),
+            flags: vec!["--radius".to_owned()],
+            description: "Search radius used to fit point normals and grow roof faces.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Normal Angle (degrees)".to_owned(),
+            flags: vec!["--max_angle".to_owned()],
+            description: "Maximum angle between the normals of a point and its roof face.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Distance to Plane".to_owned(),
+            flags: vec!["--max_dist".to_owned()],
+            description: "Maximum distance of a point from the plane of its roof face.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.25".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Points per Roof Face".to_owned(),
+            flags: vec!["--min_points".to_owned()],
+            description: "Minimum number of points in a roof face.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("25".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Footprint Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Grid resolution used to derive the footprints.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Area".to_owned(),
+            flags: vec!["--min_area".to_owned()],
+            description: "Minimum area of buildings and courtyards, in squared map units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("25.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Snap Angle (degrees)".to_owned(),
+            flags: vec!["--snap_angle".to_owned()],
+            description: "Maximum deviation of a footprint edge from the building's orientation for it to be snapped.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("15.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=footprints.shp --roof_faces=roofs.shp --radius=1.5 --max_angle=8.0 --min_area=40.0", short_exe, name).replace("*", &sep);
+
+        LidarExtractBuildings {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarExtractBuildings {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--building_cls".to_string(), ParameterConstraint::Range(0.0, 255.0)),
+            ("--radius".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--max_angle".to_string(), ParameterConstraint::Range(0.0, 90.0)),
+            ("--max_dist".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--min_points".to_string(), ParameterConstraint::Min(3.0)),
+            ("--resolution".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--min_area".to_string(), ParameterConstraint::Min(0.0)),
+            ("--snap_angle".to_string(), ParameterConstraint::Range(0.0, 45.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut faces_file = String::new();
+        let mut building_cls = 6u8;
+        let mut radius = 2f64;
+        let mut max_angle = 10f64;
+        let mut max_dist = 0.25f64;
+        let mut min_points = 25usize;
+        let mut resolution = 1f64;
+        let mut min_area = 25f64;
+        let mut snap_angle = 15f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-roof_faces" {
+                faces_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-building_cls" {
+                building_cls = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as u8
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as u8
+                };
+            } else if flag_val == "-radius" {
+                radius = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_angle" {
+                max_angle = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_dist" {
+                max_dist = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_points" {
+                min_points = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-resolution" {
+                resolution = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_area" {
+                min_area = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-snap_angle" {
+                snap_angle = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        faces_file = resolve_path(&faces_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let mut input = LasFile::new(&input_file, "r")?;
+
+        let start = Instant::now();
+
+        let mut points: Vec<[f64; 3]> = vec![];
+        for i in 0..input.header.number_of_points as usize {
+            let p: PointData = input[i];
+            if !p.withheld() && p.classification() == building_cls {
+                points.push([p.x, p.y, p.z]);
+            }
+        }
+        let n_points = points.len();
+        if n_points < 3 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The input file does not contain enough building points (class {}).",
+                    building_cls
+                ),
+            ));
+        }
+
+        ////////////////////////////////////////////
+        // Fit a plane to each point's neighbours //
+        ////////////////////////////////////////////
+        let mut frs: FixedRadiusSearch3D<usize> =
+            FixedRadiusSearch3D::new(radius, DistanceMetric::SquaredEuclidean);
+        for (i, p) in points.iter().enumerate() {
+            frs.insert(p[0], p[1], p[2], i);
+        }
+        let frs = Arc::new(frs);
+        let points = Arc::new(points);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let frs = frs.clone();
+            let points = points.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for i in (0..n_points).filter(|i| i % num_procs == tid) {
+                    let p = points[i];
+                    let neighbours: Vec<[f64; 3]> = frs
+                        .search(p[0], p[1], p[2])
+                        .iter()
+                        .map(|&(j, _)| points[j])
+                        .collect();
+                    tx.send((i, fit_plane(&neighbours))).unwrap();
+                }
+            });
+        }
+
+        let mut planes: Vec<Option<Plane>> = vec![None; n_points];
+        for i in 0..n_points {
+            let (point_num, plane) = rx.recv().unwrap();
+            planes[point_num] = plane;
+            if verbose {
+                progress = (100.0_f64 * i as f64 / (n_points - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Calculating point normals: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        //////////////////////////////////////////
+        // Segment the points by region growing //
+        //////////////////////////////////////////
+        let cos_max_angle = max_angle.to_radians().cos();
+        let mut seeds: Vec<usize> = (0..n_points).filter(|&i| planes[i].is_some()).collect();
+        seeds.sort_by(|&a, &b| {
+            let ca = planes[a].unwrap().curvature;
+            let cb = planes[b].unwrap().curvature;
+            ca.partial_cmp(&cb).unwrap()
+        });
+        const UNASSIGNED: isize = -1;
+        const REJECTED: isize = -2;
+        let mut face_id = vec![UNASSIGNED; n_points];
+        let mut faces: Vec<RoofFace> = vec![];
+        for (k, &seed) in seeds.iter().enumerate() {
+            if face_id[seed] == UNASSIGNED {
+                let face = faces.len() as isize;
+                let mut plane = planes[seed].unwrap();
+                let mut members = vec![seed];
+                let mut stack = vec![seed];
+                let mut last_fit = 1;
+                face_id[seed] = face;
+                while let Some(i) = stack.pop() {
+                    let p = points[i];
+                    for (j, _) in frs.search(p[0], p[1], p[2]) {
+                        if face_id[j] == UNASSIGNED {
+                            if let Some(pj) = planes[j] {
+                                if plane.angle_cosine(&pj) >= cos_max_angle
+                                    && plane.distance(&points[j]).abs() <= max_dist
+                                {
+                                    face_id[j] = face;
+                                    members.push(j);
+                                    stack.push(j);
+                                }
+                            }
+                        }
+                    }
+                    // re-fit the plane each time the region doubles in size
+                    if members.len() >= 2 * last_fit && members.len() >= 10 {
+                        let member_points: Vec<[f64; 3]> =
+                            members.iter().map(|&m| points[m]).collect();
+                        if let Some(pl) = fit_plane(&member_points) {
+                            plane = pl;
+                        }
+                        last_fit = members.len();
+                    }
+                }
+
+                if members.len() >= min_points {
+                    let member_points: Vec<[f64; 3]> = members.iter().map(|&m| points[m]).collect();
+                    if let Some(pl) = fit_plane(&member_points) {
+                        plane = pl;
+                    }
+                    faces.push(RoofFace::new(plane, &member_points));
+                } else {
+                    // the members are free to join other faces, but the seed cannot start another
+                    for m in members {
+                        face_id[m] = UNASSIGNED;
+                    }
+                    face_id[seed] = REJECTED;
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * k as f64 / (seeds.len() - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Segmenting roof faces: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        let num_faces = faces.len();
+
+        ///////////////////////////////////
+        // Rasterize the building points //
+        ///////////////////////////////////
+        let (mut min_x, mut max_x) = (f64::INFINITY, f64::NEG_INFINITY);
+        let (mut min_y, mut max_y) = (f64::INFINITY, f64::NEG_INFINITY);
+        for p in points.iter() {
+            min_x = min_x.min(p[0]);
+            max_x = max_x.max(p[0]);
+            min_y = min_y.min(p[1]);
+            max_y = max_y.max(p[1]);
+        }
+        // the grid is padded by one cell on each side so that buildings do not touch its edges
+        let west = min_x - resolution;
+        let north = max_y + resolution;
+        let columns = ((max_x - min_x) / resolution).floor() as isize + 3;
+        let rows = ((max_y - min_y) / resolution).floor() as isize + 3;
+        let cell_of = |p: &[f64; 3]| -> (isize, isize) {
+            (
+                ((north - p[1]) / resolution).floor() as isize,
+                ((p[0] - west) / resolution).floor() as isize,
+            )
+        };
+
+        let mut building: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut cell_faces: Vec<(isize, isize, usize)> = vec![];
+        for i in 0..n_points {
+            let (row, col) = cell_of(&points[i]);
+            building.set_value(row, col, 1u8);
+            if face_id[i] >= 0 {
+                cell_faces.push((row, col, face_id[i] as usize));
+            }
+        }
+
+        // close the gaps between points with a 3 x 3 dilation followed by an erosion
+        let dx = [1, 1, 0, -1, -1, -1, 0, 1];
+        let dy = [0, 1, 1, 1, 0, -1, -1, -1];
+        let mut dilated: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                if building.get_value(row, col) == 1u8
+                    || (0..8).any(|n| building.get_value(row + dy[n], col + dx[n]) == 1u8)
+                {
+                    dilated.set_value(row, col, 1u8);
+                }
+            }
+        }
+        for row in 0..rows {
+            for col in 0..columns {
+                if dilated.get_value(row, col) == 1u8
+                    && (0..8).all(|n| dilated.get_value(row + dy[n], col + dx[n]) == 1u8)
+                {
+                    building.set_value(row, col, 1u8);
+                }
+            }
+        }
+        drop(dilated);
+
+        // Fill small courtyards and then remove small buildings. Courtyards are non-building
+        // regions that do not touch the edge of the grid.
+        let cell_area = resolution * resolution;
+        let dx4 = [1, 0, -1, 0];
+        let dy4 = [0, 1, 0, -1];
+        let mut labels: Array2D<i32> = Array2D::new(rows, columns, -1, -1)?;
+        for pass in 0..2 {
+            let target = if pass == 0 { 0u8 } else { 1u8 };
+            labels.reinitialize_values(-1);
+            for row in 0..rows {
+                for col in 0..columns {
+                    if building.get_value(row, col) == target && labels.get_value(row, col) == -1 {
+                        let mut cells = vec![(row, col)];
+                        let mut stack = vec![(row, col)];
+                        let mut touches_edge = false;
+                        labels.set_value(row, col, 0);
+                        while let Some((r, c)) = stack.pop() {
+                            for n in 0..4 {
+                                let (rn, cn) = (r + dy4[n], c + dx4[n]);
+                                if rn < 0 || cn < 0 || rn >= rows || cn >= columns {
+                                    touches_edge = true;
+                                } else if building.get_value(rn, cn) == target
+                                    && labels.get_value(rn, cn) == -1
+                                {
+                                    labels.set_value(rn, cn, 0);
+                                    cells.push((rn, cn));
+                                    stack.push((rn, cn));
+                                }
+                            }
+                        }
+                        if cells.len() as f64 * cell_area < min_area && (pass == 1 || !touches_edge) {
+                            for (r, c) in cells {
+                                building.set_value(r, c, 1u8 - target);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // label the buildings
+        labels.reinitialize_values(-1);
+        let mut extents: Vec<(isize, isize, isize, isize)> = vec![];
+        for row in 0..rows {
+            for col in 0..columns {
+                if building.get_value(row, col) == 1u8 && labels.get_value(row, col) == -1 {
+                    let label = extents.len() as i32;
+                    let mut extent = (row, row, col, col);
+                    let mut stack = vec![(row, col)];
+                    labels.set_value(row, col, label);
+                    while let Some((r, c)) = stack.pop() {
+                        extent.0 = extent.0.min(r);
+                        extent.1 = extent.1.max(r);
+                        extent.2 = extent.2.min(c);
+                        extent.3 = extent.3.max(c);
+                        for n in 0..4 {
+                            let (rn, cn) = (r + dy4[n], c + dx4[n]);
+                            if building.get_value(rn, cn) == 1u8 && labels.get_value(rn, cn) == -1 {
+                                labels.set_value(rn, cn, label);
+                                stack.push((rn, cn));
+                            }
+                        }
+                    }
+                    extents.push(extent);
+                }
+            }
+        }
+        let num_buildings = extents.len();
+
+        // Assign each building cell to the roof face with the most points in the cell, and then
+        // grow the faces into the cells that do not contain any face points.
+        let mut face_grid: Array2D<i32> = Array2D::new(rows, columns, -1, -1)?;
+        cell_faces.sort();
+        let mut k = 0;
+        while k < cell_faces.len() {
+            let (row, col, _) = cell_faces[k];
+            let (mut best_face, mut best_count) = (0usize, 0usize);
+            while k < cell_faces.len() && cell_faces[k].0 == row && cell_faces[k].1 == col {
+                let face = cell_faces[k].2;
+                let mut count = 0;
+                while k < cell_faces.len()
+                    && cell_faces[k].0 == row
+                    && cell_faces[k].1 == col
+                    && cell_faces[k].2 == face
+                {
+                    count += 1;
+                    k += 1;
+                }
+                if count > best_count {
+                    best_face = face;
+                    best_count = count;
+                }
+            }
+            if labels.get_value(row, col) >= 0 {
+                face_grid.set_value(row, col, best_face as i32);
+            }
+        }
+        drop(cell_faces);
+        loop {
+            let mut additions = vec![];
+            for row in 0..rows {
+                for col in 0..columns {
+                    if labels.get_value(row, col) >= 0 && face_grid.get_value(row, col) == -1 {
+                        for n in 0..4 {
+                            let face = face_grid.get_value(row + dy4[n], col + dx4[n]);
+                            if face >= 0 {
+                                additions.push((row, col, face));
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            if additions.is_empty() {
+                break;
+            }
+            for (row, col, face) in additions {
+                face_grid.set_value(row, col, face);
+            }
+        }
+
+        // building statistics and the points used to find each building's orientation
+        let mut building_stats = vec![(0usize, f64::INFINITY, f64::NEG_INFINITY, 0f64); num_buildings];
+        let mut building_points: Vec<Vec<Point2D>> = vec![vec![]; num_buildings];
+        for p in points.iter() {
+            let (row, col) = cell_of(p);
+            let label = labels.get_value(row, col);
+            if label >= 0 {
+                let stat = &mut building_stats[label as usize];
+                stat.0 += 1;
+                stat.1 = stat.1.min(p[2]);
+                stat.2 = stat.2.max(p[2]);
+                stat.3 += p[2];
+                building_points[label as usize].push(Point2D::new(p[0], p[1]));
+            }
+        }
+        let mut orientations = vec![0f64; num_buildings];
+        for b in 0..num_buildings {
+            if building_points[b].len() >= 3 {
+                // the orientation of the long axis of the bounding rectangle
+                let mbb = minimum_bounding_box(&mut building_points[b], MinimizationCriterion::Area);
+                let (p1, p2) = if mbb[0].distance(&mbb[1]) >= mbb[1].distance(&mbb[2]) {
+                    (mbb[0], mbb[1])
+                } else {
+                    (mbb[1], mbb[2])
+                };
+                orientations[b] = (p2.y - p1.y).atan2(p2.x - p1.x);
+            }
+        }
+        drop(building_points);
+
+        // the building and extent of each roof face
+        let mut face_cells = vec![vec![0usize; num_buildings]; num_faces];
+        let mut face_extents = vec![(isize::MAX, isize::MIN, isize::MAX, isize::MIN); num_faces];
+        for row in 0..rows {
+            for col in 0..columns {
+                let face = face_grid.get_value(row, col);
+                if face >= 0 {
+                    let face = face as usize;
+                    face_cells[face][labels.get_value(row, col) as usize] += 1;
+                    let extent = &mut face_extents[face];
+                    extent.0 = extent.0.min(row);
+                    extent.1 = extent.1.max(row);
+                    extent.2 = extent.2.min(col);
+                    extent.3 = extent.3.max(col);
+                }
+            }
+        }
+        let mut face_building = vec![None; num_faces];
+        let mut building_num_faces = vec![0usize; num_buildings];
+        for f in 0..num_faces {
+            let mut best: Option<usize> = None;
+            for b in 0..num_buildings {
+                if face_cells[f][b] > 0 {
+                    building_num_faces[b] += 1;
+                    if best.is_none_or(|bb| face_cells[f][b] > face_cells[f][bb]) {
+                        best = Some(b);
+                    }
+                }
+            }
+            face_building[f] = best;
+        }
+        drop(face_cells);
+
+        let to_map = |ring: &Vec<(isize, isize)>, min_row: isize, min_col: isize| -> Vec<Point2D> {
+            ring.iter()
+                .map(|p| {
+                    Point2D::new(
+                        west + (p.1 + min_col) as f64 * resolution,
+                        north - (p.0 + min_row) as f64 * resolution,
+                    )
+                })
+                .collect()
+        };
+        let snap_angle = snap_angle.to_radians();
+
+        ///////////////////////////
+        // Output the footprints //
+        ///////////////////////////
+        let wkt = input.get_wkt();
+        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        output.projection = wkt.clone();
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("AREA", FieldDataType::Real, 14u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("PERIMETER", FieldDataType::Real, 14u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("ORIENT", FieldDataType::Real, 8u8, 2u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("NUM_FACES", FieldDataType::Int, 6u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("NUM_PTS", FieldDataType::Int, 9u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("MIN_Z", FieldDataType::Real, 12u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("MAX_Z", FieldDataType::Real, 12u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("MEAN_Z", FieldDataType::Real, 12u8, 3u8));
+
+        for (label, extent) in extents.iter().enumerate() {
+            let (min_row, max_row, min_col, max_col) = *extent;
+            let rings = trace_region_boundaries(
+                max_row - min_row + 1,
+                max_col - min_col + 1,
+                |r, c| labels.get_value(r + min_row, c + min_col) == label as i32,
+            );
+            let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+            let mut area = 0f64;
+            let mut perimeter = 0f64;
+            for ring in &rings {
+                let points = regularize_ring(
+                    &to_map(ring, min_row, min_col),
+                    orientations[label],
+                    1.5 * resolution,
+                    snap_angle,
+                );
+                // outer rings are clockwise and holes are counter-clockwise
+                if is_clockwise(&points) {
+                    area += polygon_area(&points);
+                    perimeter += polygon_perimeter(&points);
+                } else {
+                    area -= polygon_area(&points);
+                }
+                sfg.add_part(&points);
+            }
+            output.add_record(sfg);
+
+            let stat = building_stats[label];
+            // orientation as an azimuth, in the range 0-180 degrees
+            let orient = (90f64 - orientations[label].to_degrees()).rem_euclid(180f64);
+            output.attributes.add_record(
+                vec![
+                    FieldData::Int(label as i32 + 1),
+                    FieldData::Real(area),
+                    FieldData::Real(perimeter),
+                    FieldData::Real(orient),
+                    FieldData::Int(building_num_faces[label] as i32),
+                    FieldData::Int(stat.0 as i32),
+                    FieldData::Real(stat.1),
+                    FieldData::Real(stat.2),
+                    FieldData::Real(stat.3 / stat.0 as f64),
+                ],
+                false,
+            );
+
+            if verbose {
+                progress = (100.0_f64 * label as f64 / (num_buildings - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Creating footprints: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        ///////////////////////////
+        // Output the roof faces //
+        ///////////////////////////
+        let mut faces_output = if !faces_file.is_empty() {
+            let mut faces_output = Shapefile::new(&faces_file, ShapeType::Polygon)?;
+            faces_output.projection = wkt.clone();
+            faces_output
+                .attributes
+                .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+            faces_output
+                .attributes
+                .add_field(&AttributeField::new("BLDG_ID", FieldDataType::Int, 7u8, 0u8));
+            faces_output
+                .attributes
+                .add_field(&AttributeField::new("NUM_PTS", FieldDataType::Int, 9u8, 0u8));
+            faces_output
+                .attributes
+                .add_field(&AttributeField::new("AREA", FieldDataType::Real, 14u8, 3u8));
+            faces_output
+                .attributes
+                .add_field(&AttributeField::new("SURF_AREA", FieldDataType::Real, 14u8, 3u8));
+            faces_output
+                .attributes
+                .add_field(&AttributeField::new("SLOPE", FieldDataType::Real, 8u8, 2u8));
+            faces_output
+                .attributes
+                .add_field(&AttributeField::new("ASPECT", FieldDataType::Real, 8u8, 2u8));
+            faces_output
+                .attributes
+                .add_field(&AttributeField::new("MEAN_Z", FieldDataType::Real, 12u8, 3u8));
+            faces_output
+                .attributes
+                .add_field(&AttributeField::new("RMSE", FieldDataType::Real, 10u8, 4u8));
+
+            let mut fid = 1;
+            for f in 0..num_faces {
+                let b = match face_building[f] {
+                    Some(b) => b,
+                    None => continue, // the face lies within a building that was removed
+                };
+                let (min_row, max_row, min_col, max_col) = face_extents[f];
+                let rings = trace_region_boundaries(
+                    max_row - min_row + 1,
+                    max_col - min_col + 1,
+                    |r, c| face_grid.get_value(r + min_row, c + min_col) == f as i32,
+                );
+                let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+                let mut area = 0f64;
+                for ring in &rings {
+                    let points = regularize_ring(
+                        &to_map(ring, min_row, min_col),
+                        orientations[b],
+                        1.5 * resolution,
+                        snap_angle,
+                    );
+                    if is_clockwise(&points) {
+                        area += polygon_area(&points);
+                    } else {
+                        area -= polygon_area(&points);
+                    }
+                    sfg.add_part(&points);
+                }
+                faces_output.add_record(sfg);
+
+                let face = &faces[f];
+                let slope = face.plane.slope();
+                faces_output.attributes.add_record(
+                    vec![
+                        FieldData::Int(fid),
+                        FieldData::Int(b as i32 + 1),
+                        FieldData::Int(face.num_points as i32),
+                        FieldData::Real(area),
+                        FieldData::Real(area / slope.to_radians().cos()),
+                        FieldData::Real(slope),
+                        match face.plane.aspect() {
+                            Some(aspect) => FieldData::Real(aspect),
+                            None => FieldData::Null,
+                        },
+                        FieldData::Real(face.mean_z),
+                        FieldData::Real(face.rmse),
+                    ],
+                    false,
+                );
+                fid += 1;
+            }
+            Some(faces_output)
+        } else {
+            None
+        };
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of buildings: {}", num_buildings);
+            println!("Number of roof faces: {}", num_faces);
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(ref mut faces_output) = faces_output {
+            let _ = match faces_output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Roof faces file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Plane {
+    normal: [f64; 3], // unit normal with a non-negative z component
+    centroid: [f64; 3],
+    curvature: f64,
+}
+
+impl Plane {
+    fn distance(&self, p: &[f64; 3]) -> f64 {
+        (p[0] - self.centroid[0]) * self.normal[0]
+            + (p[1] - self.centroid[1]) * self.normal[1]
+            + (p[2] - self.centroid[2]) * self.normal[2]
+    }
+
+    fn angle_cosine(&self, other: &Plane) -> f64 {
+        self.normal[0] * other.normal[0]
+            + self.normal[1] * other.normal[1]
+            + self.normal[2] * other.normal[2]
+    }
+
+    /// Slope in degrees.
+    fn slope(&self) -> f64 {
+        self.normal[2].min(1f64).acos().to_degrees()
+    }
+
+    /// Aspect in degrees clockwise from north, or None if the plane is nearly horizontal.
+    fn aspect(&self) -> Option<f64> {
+        if self.slope() < 1f64 {
+            return None;
+        }
+        Some(self.normal[0].atan2(self.normal[1]).to_degrees().rem_euclid(360f64))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RoofFace {
+    plane: Plane,
+    num_points: usize,
+    mean_z: f64,
+    rmse: f64,
+}
+
+impl RoofFace {
+    fn new(plane: Plane, points: &[[f64; 3]]) -> RoofFace {
+        let n = points.len() as f64;
+        let mean_z = points.iter().map(|p| p[2]).sum::<f64>() / n;
+        let sum_sqr: f64 = points.iter().map(|p| plane.distance(p).powi(2)).sum();
+        RoofFace {
+            plane,
+            num_points: points.len(),
+            mean_z,
+            rmse: (sum_sqr / n).sqrt(),
+        }
+    }
+}
+
+/// Fits a plane to a set of points by principal component analysis. The curvature is the
+/// proportion of the variance in the direction of the normal.
+fn fit_plane(points: &[[f64; 3]]) -> Option<Plane> {
+    let n = points.len();
+    if n < 3 {
+        return None;
+    }
+    let mut centroid = [0f64; 3];
+    for p in points {
+        for a in 0..3 {
+            centroid[a] += p[a] / n as f64;
+        }
+    }
+    let mut cov = Matrix3::<f64>::zeros();
+    for p in points {
+        let d = [p[0] - centroid[0], p[1] - centroid[1], p[2] - centroid[2]];
+        for r in 0..3 {
+            for c in 0..3 {
+                cov[(r, c)] += d[r] * d[c];
+            }
+        }
+    }
+    let eig = cov.symmetric_eigen();
+    let mut min_index = 0;
+    for a in 1..3 {
+        if eig.eigenvalues[a] < eig.eigenvalues[min_index] {
+            min_index = a;
+        }
+    }
+    let sum_eigenvalues: f64 = eig.eigenvalues.iter().map(|v| v.max(0f64)).sum();
+    if sum_eigenvalues <= 0f64 {
+        // all of the points are coincident
+        return None;
+    }
+    let v = eig.eigenvectors.column(min_index);
+    let sign = if v[2] < 0f64 { -1f64 } else { 1f64 };
+    Some(Plane {
+        normal: [sign * v[0], sign * v[1], sign * v[2]],
+        centroid,
+        curvature: eig.eigenvalues[min_index].max(0f64) / sum_eigenvalues,
+    })
+}
+
+fn is_clockwise(ring: &[Point2D]) -> bool {
+    let mut sum = 0f64;
+    for i in 0..ring.len() - 1 {
+        sum += (ring[i + 1].x - ring[i].x) * (ring[i + 1].y + ring[i].y);
+    }
+    sum > 0f64
+}
+
+/// Returns the indices of the vertices of a closed ring (with its first vertex repeated at its
+/// end) that are retained by Douglas-Peucker simplification.
+fn simplify_ring(ring: &[Point2D], tolerance: f64) -> Vec<usize> {
+    fn simplify(ring: &[Point2D], first: usize, last: usize, tolerance: f64, keep: &mut Vec<bool>) {
+        if last <= first + 1 {
+            return;
+        }
+        let (a, b) = (ring[first], ring[last]);
+        let len = a.distance(&b);
+        let mut max_dist = 0f64;
+        let mut index = first;
+        for i in first + 1..last {
+            let d = if len > 0f64 {
+                ((b.x - a.x) * (a.y - ring[i].y) - (a.x - ring[i].x) * (b.y - a.y)).abs() / len
+            } else {
+                a.distance(&ring[i])
+            };
+            if d > max_dist {
+                max_dist = d;
+                index = i;
+            }
+        }
+        if max_dist > tolerance {
+            keep[index] = true;
+            simplify(ring, first, index, tolerance, keep);
+            simplify(ring, index, last, tolerance, keep);
+        }
+    }
+
+    let n = ring.len() - 1;
+    // split the ring at its first vertex and the vertex farthest from it
+    let mut farthest = 0;
+    for i in 1..n {
+        if ring[i].distance(&ring[0]) > ring[farthest].distance(&ring[0]) {
+            farthest = i;
+        }
+    }
+    let mut keep = vec![false; n + 1];
+    keep[0] = true;
+    keep[farthest] = true;
+    simplify(ring, 0, farthest, tolerance, &mut keep);
+    simplify(ring, farthest, n, tolerance, &mut keep);
+    (0..n).filter(|&i| keep[i]).collect()
+}
+
+/// Regularizes a closed ring of stair-stepped grid cell boundaries. The ring is simplified, a line
+/// is fitted to the vertices of each simplified edge, and edges within `max_snap` radians of the
+/// `orientation` (or perpendicular to it) are snapped to that direction. Consecutive parallel
+/// edges are merged, and the vertices of the output ring are the intersections of the remaining
+/// lines. The simplified ring is returned if regularization fails to produce a similar polygon.
+fn regularize_ring(
+    ring: &[Point2D],
+    orientation: f64,
+    tolerance: f64,
+    max_snap: f64,
+) -> Vec<Point2D> {
+    let n = ring.len() - 1;
+    if n < 4 {
+        return ring.to_vec();
+    }
+    let keep = simplify_ring(ring, tolerance);
+    let m = keep.len();
+    if m < 3 {
+        return ring.to_vec();
+    }
+    let mut simplified: Vec<Point2D> = keep.iter().map(|&i| ring[i]).collect();
+    simplified.push(simplified[0]);
+
+    // one line per simplified edge: (point on the line, direction, length, end vertex)
+    let mut lines: Vec<(Point2D, f64, f64, Point2D)> = Vec::with_capacity(m);
+    for k in 0..m {
+        let (a, b) = (keep[k], if k + 1 < m { keep[k + 1] } else { n });
+        let count = (b - a + 1) as f64;
+        let mut centroid = Point2D::new(0f64, 0f64);
+        for p in &ring[a..=b] {
+            centroid.x += p.x / count;
+            centroid.y += p.y / count;
+        }
+        let mut direction = (ring[b].y - ring[a].y).atan2(ring[b].x - ring[a].x);
+        let deviation = (direction - orientation + PI / 4f64).rem_euclid(PI / 2f64) - PI / 4f64;
+        if deviation.abs() <= max_snap {
+            direction -= deviation;
+        }
+        lines.push((centroid, direction, ring[a].distance(&ring[b]), ring[b]));
+    }
+
+    // merge consecutive edges with the same direction
+    let same_direction = |d1: f64, d2: f64| -> bool {
+        let diff = (d1 - d2).rem_euclid(2f64 * PI);
+        diff < 1e-9 || 2f64 * PI - diff < 1e-9
+    };
+    let merge = |l1: (Point2D, f64, f64, Point2D), l2: (Point2D, f64, f64, Point2D)| {
+        let w = l1.2 + l2.2;
+        let point = if w > 0f64 {
+            Point2D::new(
+                (l1.0.x * l1.2 + l2.0.x * l2.2) / w,
+                (l1.0.y * l1.2 + l2.0.y * l2.2) / w,
+            )
+        } else {
+            l1.0
+        };
+        (point, l1.1, w, l2.3)
+    };
+    let mut merged: Vec<(Point2D, f64, f64, Point2D)> = Vec::with_capacity(m);
+    for line in lines {
+        match merged.last() {
+            Some(&last) if same_direction(last.1, line.1) => {
+                let len = merged.len();
+                merged[len - 1] = merge(last, line);
+            }
+            _ => merged.push(line),
+        }
+    }
+    while merged.len() > 1 && same_direction(merged[merged.len() - 1].1, merged[0].1) {
+        let last = merged.pop().unwrap();
+        merged[0] = merge(last, merged[0]);
+    }
+    let num_lines = merged.len();
+    if num_lines < 3 {
+        return simplified;
+    }
+
+    let project = |p: Point2D, line: &(Point2D, f64, f64, Point2D)| -> Point2D {
+        let (sin, cos) = line.1.sin_cos();
+        let t = (p.x - line.0.x) * cos + (p.y - line.0.y) * sin;
+        Point2D::new(line.0.x + t * cos, line.0.y + t * sin)
+    };
+    let min_sin = 10f64.to_radians().sin();
+    let mut output = vec![];
+    for k in 0..num_lines {
+        let l1 = merged[k];
+        let l2 = merged[(k + 1) % num_lines];
+        let corner = l1.3;
+        let (s1, c1) = l1.1.sin_cos();
+        let (s2, c2) = l2.1.sin_cos();
+        let cross = c1 * s2 - s1 * c2;
+        let mut intersection = None;
+        if cross.abs() >= min_sin {
+            let t = ((l2.0.x - l1.0.x) * s2 - (l2.0.y - l1.0.y) * c2) / cross;
+            let p = Point2D::new(l1.0.x + t * c1, l1.0.y + t * s1);
+            if p.distance(&corner) <= 5f64 * tolerance {
+                intersection = Some(p);
+            }
+        }
+        match intersection {
+            Some(p) => output.push(p),
+            None => {
+                // nearly parallel edges are joined by a short edge at the original corner
+                output.push(project(corner, &l1));
+                output.push(project(corner, &l2));
+            }
+        }
+    }
+    if output.len() < 3 {
+        return simplified;
+    }
+    output.push(output[0]);
+
+    let area = polygon_area(&output);
+    let original_area = polygon_area(ring);
+    if area < 0.5 * original_area || area > 1.5 * original_area {
+        return simplified;
+    }
+    output
+}
@@ -0,0 +1,642 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::trace_region_boundaries;
+use crate::lidar::*;
+use crate::raster::*;
+use crate::structures::{Array2D, Point2D};
+use crate::tools::*;
+use crate::vector::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool maps lakes and ponds using a combination of three lines of evidence: flat surfaces in a
+/// LiDAR-derived digital elevation model (`--dem`), low LiDAR return intensities, and point drop-outs
+/// in the input LAS file (`--input`). Water surfaces are strong absorbers of the near-infrared laser
+/// pulses used by most topographic LiDAR systems, and specular reflection away from the sensor means
+/// that many pulses over water return no signal at all. The surfaces interpolated over water bodies
+/// are therefore typically very flat, with few, and low-intensity, returns.
+///
+/// The evidence is summarized within a square moving window (`--filter`, default 5 cells) centred on
+/// each grid cell of the DEM. A cell is flat if the elevation range within the window is no more than
+/// the flatness tolerance (`--flatness`, default 0.1 elevation units). The intensity is considered
+/// low if the mean intensity of the points within the window is no more than the maximum intensity
+/// (`--max_intensity`), which defaults to half of the median window intensity of the tile. Drop-outs
+/// are measured by the proportion by which the point density within the window falls below the
+/// median point density of the tile; windows with a drop-out proportion of at least `--min_dropout`
+/// (default 0.5) are considered to have high drop-out densities. Flat cells that have either low
+/// intensity or a high drop-out density are classified as water. Because the windows that straddle
+/// shorelines are not flat, the water bodies are then grown by up to half of the window size into
+/// adjacent cells with elevations within the flatness tolerance.
+///
+/// Water bodies smaller than the minimum area (`--min_area`, in squared map units) are removed, as
+/// are holes (i.e. islands) smaller than this area, and the remaining water bodies are output as a
+/// vector polygon file (`--output`). The output attribute table contains each water body's area,
+/// minimum and mean DEM elevations, mean intensity, and point density. The polygons are suitable for
+/// hydro-flattening the DEM using the `FlattenLakes` tool.
+///
+/// The input DEM should be interpolated from the same LAS file, or at least from the same LiDAR
+/// survey, and the LAS file should not have been filtered to remove low-intensity points. Wet
+/// asphalt, fresh tar, and dark roofs may also have low intensities, but are rarely both flat and
+/// extensive, and can usually be removed by increasing the minimum area.
+///
+/// # See Also
+/// `FlattenLakes`, `LidarPointDensity`, `LidarTINGridding`
+pub struct LidarExtractWaterBodies {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarExtractWaterBodies {
+    pub fn new() -> LidarExtractWaterBodies {
+        // public constructor
+        let name = "LidarExtractWaterBodies".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Extracts lake and pond polygons using DEM flatness, low LiDAR intensity, and point drop-outs.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster DEM file, interpolated from the LiDAR data.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector Polygon File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector polygon file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Filter Size (cells)".to_owned(),
+            flags: vec!["--filter".to_owned()],
+            description: "Size of the moving window, in grid cells.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Flatness Tolerance".to_owned(),
+            flags: vec!["--flatness".to_owned()],
+            description: "Maximum elevation range within the window of a flat surface.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.1".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Intensity (optional)".to_owned(),
+            flags: vec!["--max_intensity".to_owned()],
+            description: "Optional maximum mean intensity of water. Defaults to half of the median intensity.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Drop-out Proportion".to_owned(),
+            flags: vec!["--min_dropout".to_owned()],
+            description: "Minimum proportion (0-1) by which the point density of water falls below the median density.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Area".to_owned(),
+            flags: vec!["--min_area".to_owned()],
+            description: "Minimum area of water bodies and islands, in squared map units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("250.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las --dem=dem.tif -o=lakes.shp --filter=7 --flatness=0.05 --min_area=500.0", short_exe, name).replace("*", &sep);
+
+        LidarExtractWaterBodies {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarExtractWaterBodies {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut dem_file = String::new();
+        let mut output_file = String::new();
+        let mut filter_size = 5usize;
+        let mut flatness = 0.1f64;
+        let mut max_intensity: Option<f64> = None;
+        let mut min_dropout = 0.5f64;
+        let mut min_area = 250f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-filter" {
+                filter_size = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-flatness" {
+                flatness = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_intensity" {
+                max_intensity = Some(if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                });
+            } else if flag_val == "-min_dropout" {
+                min_dropout = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_area" {
+                min_area = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        dem_file = resolve_path(&dem_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if filter_size < 3 {
+            filter_size = 3;
+        }
+        // the filter size must be odd
+        filter_size = (filter_size / 2) * 2 + 1;
+        let midpoint = (filter_size / 2) as isize;
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let dem = Raster::new(&dem_file, "r")?;
+        let input = LasFile::new(&input_file, "r")?;
+
+        let start = Instant::now();
+
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+
+        // bin the points into the DEM grid
+        let mut counts: Array2D<f64> = Array2D::new(rows, columns, 0f64, 0f64)?;
+        let mut intensities: Array2D<f64> = Array2D::new(rows, columns, 0f64, 0f64)?;
+        let n_points = input.header.number_of_points as usize;
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            if !p.withheld() {
+                let row = dem.get_row_from_y(p.y);
+                let col = dem.get_column_from_x(p.x);
+                if row >= 0 && col >= 0 && row < rows && col < columns {
+                    counts.increment(row, col, 1f64);
+                    intensities.increment(row, col, p.intensity as f64);
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / (n_points - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Binning points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // summed-area tables of the point counts and intensities
+        let stride = (columns + 1) as usize;
+        let mut count_sat = vec![0f64; stride * (rows + 1) as usize];
+        let mut intensity_sat = vec![0f64; stride * (rows + 1) as usize];
+        for row in 0..rows {
+            let (mut row_count, mut row_intensity) = (0f64, 0f64);
+            for col in 0..columns {
+                row_count += counts.get_value(row, col);
+                row_intensity += intensities.get_value(row, col);
+                let k = (row as usize + 1) * stride + col as usize + 1;
+                count_sat[k] = count_sat[k - stride] + row_count;
+                intensity_sat[k] = intensity_sat[k - stride] + row_intensity;
+            }
+        }
+        let window_sum = |sat: &Vec<f64>, row: isize, col: isize| -> (f64, f64) {
+            let r1 = (row - midpoint).max(0) as usize;
+            let r2 = (row + midpoint + 1).min(rows) as usize;
+            let c1 = (col - midpoint).max(0) as usize;
+            let c2 = (col + midpoint + 1).min(columns) as usize;
+            let sum = sat[r2 * stride + c2] - sat[r1 * stride + c2] - sat[r2 * stride + c1]
+                + sat[r1 * stride + c1];
+            (sum, ((r2 - r1) * (c2 - c1)) as f64)
+        };
+
+        // window point densities (points per cell) and mean intensities
+        let mut density: Array2D<f64> = Array2D::new(rows, columns, 0f64, -1f64)?;
+        let mut mean_intensity: Array2D<f64> = Array2D::new(rows, columns, -1f64, -1f64)?;
+        let mut density_values = vec![];
+        let mut intensity_values = vec![];
+        for row in 0..rows {
+            for col in 0..columns {
+                if dem.get_value(row, col) != nodata {
+                    let (count, num_cells) = window_sum(&count_sat, row, col);
+                    density.set_value(row, col, count / num_cells);
+                    density_values.push(count / num_cells);
+                    if count > 0f64 {
+                        let (intensity, _) = window_sum(&intensity_sat, row, col);
+                        mean_intensity.set_value(row, col, intensity / count);
+                        intensity_values.push(intensity / count);
+                    }
+                }
+            }
+        }
+        if density_values.is_empty() || intensity_values.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The LAS file does not overlap with the valid cells of the DEM.",
+            ));
+        }
+        density_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        intensity_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let median_density = density_values[density_values.len() / 2];
+        let median_intensity = intensity_values[intensity_values.len() / 2];
+        let max_intensity = max_intensity.unwrap_or(median_intensity / 2f64);
+        drop(density_values);
+        drop(intensity_values);
+        if verbose {
+            println!("Median point density: {:.3} points per cell", median_density);
+            println!("Maximum water intensity: {:.3}", max_intensity);
+        }
+
+        // classify the water cells
+        let mut water: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let (mut z, mut zn): (f64, f64);
+        let (mut min_z, mut max_z): (f64, f64);
+        for row in 0..rows {
+            for col in 0..columns {
+                z = dem.get_value(row, col);
+                if z != nodata {
+                    min_z = z;
+                    max_z = z;
+                    for r in (row - midpoint)..(row + midpoint + 1) {
+                        for c in (col - midpoint)..(col + midpoint + 1) {
+                            zn = dem.get_value(r, c);
+                            if zn != nodata {
+                                min_z = min_z.min(zn);
+                                max_z = max_z.max(zn);
+                            }
+                        }
+                    }
+                    if max_z - min_z <= flatness {
+                        let intensity = mean_intensity.get_value(row, col);
+                        let low_intensity = intensity < 0f64 || intensity <= max_intensity;
+                        let dropout = median_density > 0f64
+                            && 1f64 - density.get_value(row, col) / median_density >= min_dropout;
+                        if low_intensity || dropout {
+                            water.set_value(row, col, 1u8);
+                        }
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Classifying water: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // The windows straddling the shorelines are not flat, which erodes the water bodies by up
+        // to half of the window size. Restore the shorelines by growing the water bodies into
+        // neighbouring cells of similar elevation.
+        let dx = [1, 0, -1, 0];
+        let dy = [0, 1, 0, -1];
+        for _ in 0..midpoint {
+            let mut additions = vec![];
+            for row in 0..rows {
+                for col in 0..columns {
+                    z = dem.get_value(row, col);
+                    if water.get_value(row, col) == 0u8 && z != nodata {
+                        for n in 0..4 {
+                            if water.get_value(row + dy[n], col + dx[n]) == 1u8
+                                && (z - dem.get_value(row + dy[n], col + dx[n])).abs() <= flatness
+                            {
+                                additions.push((row, col));
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            for (row, col) in additions {
+                water.set_value(row, col, 1u8);
+            }
+        }
+
+        // Remove small islands and then small water bodies. Islands are non-water regions that
+        // do not touch the edge of the DEM or its NoData cells.
+        let cell_area = dem.configs.resolution_x * dem.configs.resolution_y;
+        let mut labels: Array2D<i32> = Array2D::new(rows, columns, -1, -1)?;
+        for pass in 0..2 {
+            let target = if pass == 0 { 0u8 } else { 1u8 };
+            labels.reinitialize_values(-1);
+            let mut num_regions = 0i32;
+            for row in 0..rows {
+                for col in 0..columns {
+                    if water.get_value(row, col) == target
+                        && dem.get_value(row, col) != nodata
+                        && labels.get_value(row, col) == -1
+                    {
+                        let mut cells = vec![(row, col)];
+                        let mut stack = vec![(row, col)];
+                        let mut touches_edge = false;
+                        labels.set_value(row, col, num_regions);
+                        while let Some((r, c)) = stack.pop() {
+                            for n in 0..4 {
+                                let (rn, cn) = (r + dy[n], c + dx[n]);
+                                if rn < 0 || cn < 0 || rn >= rows || cn >= columns
+                                    || dem.get_value(rn, cn) == nodata
+                                {
+                                    touches_edge = true;
+                                } else if water.get_value(rn, cn) == target
+                                    && labels.get_value(rn, cn) == -1
+                                {
+                                    labels.set_value(rn, cn, num_regions);
+                                    cells.push((rn, cn));
+                                    stack.push((rn, cn));
+                                }
+                            }
+                        }
+                        num_regions += 1;
+                        if cells.len() as f64 * cell_area < min_area && (pass == 1 || !touches_edge) {
+                            for (r, c) in cells {
+                                water.set_value(r, c, 1u8 - target);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // label the remaining water bodies and calculate their statistics
+        labels.reinitialize_values(-1);
+        let mut regions: Vec<(isize, isize, isize, isize)> = vec![]; // extents
+        let mut stats: Vec<(f64, f64, f64, f64, f64)> = vec![]; // num cells, min z, sum z, points, intensity
+        for row in 0..rows {
+            for col in 0..columns {
+                if water.get_value(row, col) == 1u8 && labels.get_value(row, col) == -1 {
+                    let label = regions.len() as i32;
+                    let mut extent = (row, row, col, col);
+                    let mut stat = (0f64, f64::INFINITY, 0f64, 0f64, 0f64);
+                    let mut stack = vec![(row, col)];
+                    labels.set_value(row, col, label);
+                    while let Some((r, c)) = stack.pop() {
+                        z = dem.get_value(r, c);
+                        stat.0 += 1f64;
+                        stat.1 = stat.1.min(z);
+                        stat.2 += z;
+                        stat.3 += counts.get_value(r, c);
+                        stat.4 += intensities.get_value(r, c);
+                        extent.0 = extent.0.min(r);
+                        extent.1 = extent.1.max(r);
+                        extent.2 = extent.2.min(c);
+                        extent.3 = extent.3.max(c);
+                        for n in 0..4 {
+                            let (rn, cn) = (r + dy[n], c + dx[n]);
+                            if water.get_value(rn, cn) == 1u8 && labels.get_value(rn, cn) == -1 {
+                                labels.set_value(rn, cn, label);
+                                stack.push((rn, cn));
+                            }
+                        }
+                    }
+                    regions.push(extent);
+                    stats.push(stat);
+                }
+            }
+        }
+
+        // output the water body polygons
+        let mut output = Shapefile::new(&output_file, ShapeType::Polygon)?;
+        output.projection = dem.configs.coordinate_ref_system_wkt.clone();
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("AREA", FieldDataType::Real, 14u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("MIN_ELEV", FieldDataType::Real, 12u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("MEAN_ELEV", FieldDataType::Real, 12u8, 3u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("MEAN_INT", FieldDataType::Real, 10u8, 2u8));
+        output
+            .attributes
+            .add_field(&AttributeField::new("DENSITY", FieldDataType::Real, 10u8, 4u8));
+
+        let west = dem.configs.west;
+        let north = dem.configs.north;
+        let res_x = dem.configs.resolution_x;
+        let res_y = dem.configs.resolution_y;
+        for (label, extent) in regions.iter().enumerate() {
+            let (min_row, max_row, min_col, max_col) = *extent;
+            let rings = trace_region_boundaries(
+                max_row - min_row + 1,
+                max_col - min_col + 1,
+                |r, c| labels.get_value(r + min_row, c + min_col) == label as i32,
+            );
+            let mut sfg = ShapefileGeometry::new(ShapeType::Polygon);
+            for ring in &rings {
+                let points: Vec<Point2D> = ring
+                    .iter()
+                    .map(|p| {
+                        Point2D::new(
+                            west + (p.1 + min_col) as f64 * res_x,
+                            north - (p.0 + min_row) as f64 * res_y,
+                        )
+                    })
+                    .collect();
+                sfg.add_part(&points);
+            }
+            output.add_record(sfg);
+
+            let stat = stats[label];
+            let area = stat.0 * cell_area;
+            output.attributes.add_record(
+                vec![
+                    FieldData::Int(label as i32 + 1),
+                    FieldData::Real(area),
+                    FieldData::Real(stat.1),
+                    FieldData::Real(stat.2 / stat.0),
+                    if stat.3 > 0f64 {
+                        FieldData::Real(stat.4 / stat.3)
+                    } else {
+                        FieldData::Null
+                    },
+                    FieldData::Real(stat.3 / area),
+                ],
+                false,
+            );
+
+            if verbose {
+                progress = (100.0_f64 * label as f64 / (regions.len() - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Creating polygons: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of water bodies: {}", regions.len());
+            println!("Saving data...")
+        };
+        if regions.is_empty() {
+            println!("Warning: No water bodies were found in the input point cloud. The output file will not be written.")
+        } else {
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -297,13 +297,8 @@ impl WhiteboxTool for LidarGroundPointFilter {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input LAS file...");
@@ -533,6 +528,7 @@ impl WhiteboxTool for LidarGroundPointFilter {
             for point_num in 0..n_points {
                 if !is_off_terrain[point_num] {
                     output.add_point_record(input.get_record(point_num));
+                    output.add_extra_bytes(input.get_extra_bytes(point_num));
                 } else {
                     num_points_filtered += 1;
                 }
@@ -725,9 +721,11 @@ impl WhiteboxTool for LidarGroundPointFilter {
                         }
                     }
                     output.add_point_record(pr2);
+                    output.add_extra_bytes(input.get_extra_bytes(point_num));
                 } else {
                     // Keep the classes of classified noise unaltered
                     output.add_point_record(input.get_record(point_num));
+                    output.add_extra_bytes(input.get_extra_bytes(point_num));
                 }
                 if verbose {
                     progress = (100.0_f64 * point_num as f64 / num_points) as i32;
@@ -213,13 +213,8 @@ impl WhiteboxTool for LidarHillshade {
         let sin_theta = altitude.sin();
         let cos_theta = altitude.cos();
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input LAS file...");
@@ -336,6 +331,7 @@ impl WhiteboxTool for LidarHillshade {
                 colour_data: rgb,
             };
             output.add_point_record(lpr);
+            output.add_extra_bytes(input.get_extra_bytes(i));
             if verbose {
                 progress = (100.0_f64 * i as f64 / num_points) as i32;
                 if progress != old_progress {
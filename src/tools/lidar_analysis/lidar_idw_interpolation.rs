@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 03/07/2017
-Last Modified: 30/08/2019
+Last Modified: 16/10/2026
 License: MIT
 
 NOTES:
@@ -11,6 +11,7 @@ NOTES:
 2. Need to add the ability to exclude points based on max scan angle divation.
 */
 
+use super::lidar_utils::{check_interpolation_parameters, parameter_output_file};
 use crate::lidar::*;
 use crate::raster::*;
 use crate::structures::{BoundingBox, DistanceMetric, FixedRadiusSearch2D};
@@ -18,7 +19,6 @@ use crate::tools::*;
 use num_cpus;
 use std::env;
 use std::f64;
-use std::fs;
 use std::io::{Error, ErrorKind};
 use std::path;
 use std::sync::mpsc;
@@ -63,7 +63,7 @@ impl LidarIdwInterpolation {
         parameters.push(ToolParameter{
             name: "Interpolation Parameter".to_owned(), 
             flags: vec!["--parameter".to_owned()], 
-            description: "Interpolation parameter; options are 'elevation' (default), 'intensity', 'class', 'return_number', 'number_of_returns', 'scan angle', 'rgb', 'user data'.".to_owned(),
+            description: "Interpolation parameter; options are 'elevation' (default), 'intensity', 'class', 'return_number', 'number_of_returns', 'scan angle', 'rgb', 'user data', or the name of an extra bytes attribute. Multiple comma-separated parameters (e.g. 'elevation,intensity,class') may be interpolated in a single pass, creating one output file per parameter.".to_owned(),
             parameter_type: ParameterType::OptionList(
                 vec![
                     "elevation".to_owned(), 
@@ -149,6 +149,58 @@ impl LidarIdwInterpolation {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Minimum GPS Time (optional)".to_owned(),
+            flags: vec!["--min_time".to_owned()],
+            description: "Optional minimum GPS time for inclusion in interpolation.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum GPS Time (optional)".to_owned(),
+            flags: vec!["--max_time".to_owned()],
+            description: "Optional maximum GPS time for inclusion in interpolation.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Only update changed tiles?".to_owned(),
+            flags: vec!["--incremental".to_owned()],
+            description: "Only process tiles with missing outputs, or outputs that are older than the tile or its overlapping neighbours.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Resolution Units".to_owned(),
+            flags: vec!["--res_units".to_owned()],
+            description: "Units of the grid resolution and search radius; 'native' indicates the units of the point coordinates.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "native".to_owned(),
+                "meters".to_owned(),
+                "feet".to_owned(),
+                "degrees".to_owned(),
+            ]),
+            default_value: Some("native".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Convert mismatched units?".to_owned(),
+            flags: vec!["--convert_units".to_owned()],
+            description: "Convert the grid resolution and search radius into the units of the point coordinates when they do not match.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.extend(InputFileFilter::parameters());
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -208,6 +260,18 @@ impl WhiteboxTool for LidarIdwInterpolation {
         self.toolbox.clone()
     }
 
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--resolution".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--radius".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--exclude_cls".to_string(), ParameterConstraint::ListRange(0.0, 255.0)),
+        ]
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_dir}{input_stem}.tif".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -217,7 +281,6 @@ impl WhiteboxTool for LidarIdwInterpolation {
         let mut input_file: String = "".to_string();
         let mut output_file: String = "".to_string();
         let mut interp_parameter = "elevation".to_string();
-        let mut interp_parameter_is_rgb = false;
         let mut return_type = "all".to_string();
         let mut grid_res: f64 = 1.0;
         let mut weight = 1.0;
@@ -227,6 +290,12 @@ impl WhiteboxTool for LidarIdwInterpolation {
         let mut exclude_cls_str = String::new();
         let mut max_z = f64::INFINITY;
         let mut min_z = f64::NEG_INFINITY;
+        let mut min_time = f64::NEG_INFINITY;
+        let mut max_time = f64::INFINITY;
+        let mut incremental = false;
+        let mut res_units = CoordinateUnits::NotSpecified;
+        let mut convert_units = false;
+        let mut file_filter = InputFileFilter::default();
 
         // read the arguments
         if args.len() == 0 {
@@ -263,9 +332,6 @@ impl WhiteboxTool for LidarIdwInterpolation {
                 } else {
                     args[i + 1].to_string().to_lowercase()
                 };
-                if interp_parameter == "rgb" {
-                    interp_parameter_is_rgb = true;
-                }
             } else if flag_val == "-returns" {
                 return_type = if keyval {
                     vec[1].to_string()
@@ -326,6 +392,40 @@ impl WhiteboxTool for LidarIdwInterpolation {
                 } else {
                     args[i + 1].to_string().parse::<f64>().unwrap()
                 };
+            } else if flag_val == "-min_time" {
+                min_time = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_time" {
+                max_time = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-incremental" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    incremental = true;
+                }
+            } else if flag_val == "-res_units" {
+                res_units = if keyval {
+                    CoordinateUnits::parse(vec[1])?
+                } else {
+                    CoordinateUnits::parse(&args[i + 1])?
+                };
+            } else if flag_val == "-convert_units" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    convert_units = true;
+                }
+            } else if flag_val == "-include" {
+                file_filter.add_include(if keyval { vec[1] } else { &args[i + 1] });
+            } else if flag_val == "-exclude" {
+                file_filter.add_exclude(if keyval { vec[1] } else { &args[i + 1] });
+            } else if flag_val == "-recursive" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    file_filter.recursive = true;
+                }
             }
         }
 
@@ -351,6 +451,28 @@ impl WhiteboxTool for LidarIdwInterpolation {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
+        let filter_by_time = min_time.is_finite() || max_time.is_finite();
+        if min_time > max_time {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The minimum GPS time (--min_time) must be less than the maximum GPS time (--max_time).",
+            ));
+        }
+
+        // Several comma-separated parameters may be interpolated from a single read of the points.
+        let interp_parameters: Vec<String> = interp_parameter
+            .split(",")
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+        if interp_parameters.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No interpolation parameter (--parameter) was specified.",
+            ));
+        }
+        let num_params = interp_parameters.len();
+
         let start = Instant::now();
 
         let mut inputs = vec![];
@@ -360,29 +482,20 @@ impl WhiteboxTool for LidarIdwInterpolation {
                 return Err(Error::new(ErrorKind::InvalidInput,
                     "This tool must be run by specifying either an individual input file or a working directory."));
             }
+            // the output file may be a template used to name the output of each input file,
+            // e.g. '{input_stem}_dem.tif'
+            let output_template = if is_output_template(&output_file) {
+                output_file.clone()
+            } else {
+                self.get_default_output_template().unwrap()
+            };
             if std::path::Path::new(&working_directory).is_dir() {
-                for entry in fs::read_dir(working_directory.clone())? {
-                    let s = entry?
-                    .path()
-                    .into_os_string()
-                    .to_str()
-                    .expect("Error reading path string")
-                    .to_string();
-                    if s.to_lowercase().ends_with(".las") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".las", ".tif")
-                                .replace(".LAS", ".tif"),
-                        )
-                    } else if s.to_lowercase().ends_with(".zip") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".zip", ".tif")
-                                .replace(".ZIP", ".tif"),
-                        )
-                    }
+                for s in file_filter.find_lidar_files(working_directory)? {
+                    let mut out_file =
+                        expand_output_template(&output_template, &s, &self.get_tool_name());
+                    out_file = resolve_path(&out_file, working_directory);
+                    inputs.push(s);
+                    outputs.push(out_file);
                 }
             } else {
                 return Err(Error::new(
@@ -391,19 +504,16 @@ impl WhiteboxTool for LidarIdwInterpolation {
                 ));
             }
         } else {
-            if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-                input_file = format!("{}{}", working_directory, input_file);
-            }
+            input_file = resolve_path(&input_file, working_directory);
             inputs.push(input_file.clone());
             if output_file.is_empty() {
-                output_file = input_file
-                    .clone()
-                    .replace(".las", ".tif")
-                    .replace(".LAS", ".tif");
-            }
-            if !output_file.contains(path::MAIN_SEPARATOR) && !output_file.contains("/") {
-                output_file = format!("{}{}", working_directory, output_file);
+                output_file = expand_output_template(
+                    &self.get_default_output_template().unwrap(),
+                    &input_file,
+                    &self.get_tool_name(),
+                );
             }
+            output_file = resolve_path(&output_file, working_directory);
             outputs.push(output_file);
         }
 
@@ -423,12 +533,57 @@ impl WhiteboxTool for LidarIdwInterpolation {
             });
         }
 
+        // check that each input file contains the interpolation parameters
+        check_interpolation_parameters(&inputs, &interp_parameters)?;
+
+        // check the grid resolution against the units of the point coordinates
+        if !inputs.is_empty() {
+            let mut first_tile = LasFile::new(&inputs[0].replace("\"", ""), "rv")?;
+            let data_units = CoordinateUnits::from_las(&mut first_tile);
+            let (multiplier, message) = check_distance_units(
+                "grid resolution",
+                grid_res,
+                res_units,
+                data_units,
+                bounding_boxes[0],
+                convert_units,
+            );
+            if let Some(message) = message {
+                println!("{}", message);
+            }
+            grid_res *= multiplier;
+            search_radius *= multiplier;
+        }
+
+        let tiles_to_update = if incremental {
+            let tile_outputs: Vec<Vec<String>> = outputs
+                .iter()
+                .map(|f| {
+                    interp_parameters
+                        .iter()
+                        .map(|param| parameter_output_file(f, param, num_params))
+                        .collect()
+                })
+                .collect();
+            find_tiles_to_update(&inputs, &tile_outputs, &bounding_boxes, search_radius)
+        } else {
+            (0..inputs.len()).collect()
+        };
+        let num_updates = tiles_to_update.len();
+        if verbose && incremental {
+            println!(
+                "{} of {} tiles require updating.",
+                num_updates,
+                inputs.len()
+            );
+        }
+
         if verbose {
             println!("Performing interpolation...");
         }
 
         let num_tiles = inputs.len();
-        let tile_list = Arc::new(Mutex::new(0..num_tiles));
+        let tile_list = Arc::new(Mutex::new(tiles_to_update.into_iter()));
         let inputs = Arc::new(inputs);
         let outputs = Arc::new(outputs);
         let bounding_boxes = Arc::new(bounding_boxes);
@@ -440,7 +595,7 @@ impl WhiteboxTool for LidarIdwInterpolation {
             let bounding_boxes = bounding_boxes.clone();
             let tile_list = tile_list.clone();
             // copy over the string parameters
-            let interp_parameter = interp_parameter.clone();
+            let interp_parameters = interp_parameters.clone();
             let palette = palette.clone();
             let return_type = return_type.clone();
             let tool_name = self.get_tool_name();
@@ -468,8 +623,11 @@ impl WhiteboxTool for LidarIdwInterpolation {
                         min_y: bounding_boxes[tile].min_y - search_radius,
                         max_y: bounding_boxes[tile].max_y + search_radius,
                     };
-                    let mut frs: FixedRadiusSearch2D<f64> =
+                    // the points are indexed by the search structure, with one set of values per parameter
+                    let mut frs: FixedRadiusSearch2D<usize> =
                         FixedRadiusSearch2D::new(search_radius, DistanceMetric::Euclidean);
+                    let mut z_values: Vec<Vec<f64>> = vec![vec![]; num_params];
+                    let mut is_rgb = vec![false; num_params];
 
                     if verbose && inputs.len() == 1 {
                         println!("Reading input LAS file...");
@@ -480,249 +638,73 @@ impl WhiteboxTool for LidarIdwInterpolation {
 
                     for m in 0..inputs.len() {
                         if bounding_boxes[m].overlaps(bb) {
-                            let input =
-                                match LasFile::new(&inputs[m].replace("\"", "").clone(), "r") {
-                                    Ok(lf) => lf,
-                                    Err(err) => panic!(
-                                        "Error reading file {}: {}",
-                                        inputs[m].replace("\"", ""),
-                                        err
-                                    ),
-                                };
-
+                            // Only the points within the buffered extent are needed from
+                            // neighbouring tiles, which can be read quickly if they are indexed.
+                            let las_file_name = inputs[m].replace("\"", "");
+                            let las = if m == tile {
+                                LasFile::new(&las_file_name, "r")
+                            } else {
+                                LasFile::new_in_extent(&las_file_name, bb)
+                            };
+                            let input = match las {
+                                Ok(lf) => lf,
+                                Err(err) => panic!("Error reading file {}: {}", las_file_name, err),
+                            };
+
+                            if filter_by_time && !input.has_gps_time() {
+                                panic!(
+                                    "The file {} does not contain GPS time data, which is required by --min_time and --max_time.",
+                                    las_file_name
+                                );
+                            }
                             let n_points = input.header.number_of_points as usize;
+                            if n_points == 0 {
+                                continue;
+                            }
                             let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
 
-                            match &interp_parameter as &str {
-                                "elevation" | "z" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(p.x, p.y, p.z);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Binning points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "intensity" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(p.x, p.y, p.intensity as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Binning points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "scan angle" | "scan_angle" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(p.x, p.y, p.scan_angle as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Binning points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "class" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(
-                                                            p.x,
-                                                            p.y,
-                                                            p.classification() as f64,
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Binning points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "return_number" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(p.x, p.y, p.return_number() as f64);
-                                                        
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "number_of_returns" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
+                            // the parameters were checked before the tiles were interpolated
+                            let attributes = match input.get_point_attributes(&interp_parameters) {
+                                Ok(attributes) => attributes,
+                                Err(err) => panic!("Error reading file {}: {}", las_file_name, err),
+                            };
+                            for k in 0..num_params {
+                                is_rgb[k] = attributes[k] == PointAttribute::Rgb;
+                            }
+
+                            let mut values = vec![0f64; num_params];
+                            for i in 0..n_points {
+                                let p: PointData = input[i];
+                                if !p.withheld() {
+                                    if all_returns
+                                        || (p.is_late_return() & late_returns)
+                                        || (p.is_early_return() & early_returns)
+                                    {
+                                        if include_class_vals[p.classification() as usize] {
+                                            if bb.is_point_in_box(p.x, p.y)
+                                                && p.z >= min_z
+                                                && p.z <= max_z
+                                                && input.is_gps_time_in_range(i, min_time, max_time)
                                             {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(p.x, p.y, p.number_of_returns() as f64);
-                                                    }
+                                                for k in 0..num_params {
+                                                    values[k] = input.get_point_attribute_value(i, attributes[k]);
                                                 }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "rgb" => {
-                                    if !input.has_rgb() {
-                                        println!("Error: The input LAS file does not contain RGB colour data. The interpolation will not proceed.");
-                                        break;
-                                    }
-                                    let mut clr: ColourData;
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        clr = match input.get_rgb(i) {
-                                                            Ok(value) => { value },
-                                                            Err(_) => break,
-                                                        };
-                                                        frs.insert(p.x, p.y, ((255u32 << 24) | ((clr.blue as u32) << 16) | ((clr.green as u32) << 8) | (clr.red as u32)) as f64);
+                                                // points lacking any of the parameters (e.g. no-data extra bytes) are excluded
+                                                if values.iter().all(|v| !v.is_nan()) {
+                                                    frs.insert(p.x, p.y, z_values[0].len());
+                                                    for k in 0..num_params {
+                                                        z_values[k].push(values[k]);
                                                     }
                                                 }
                                             }
                                         }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
                                     }
                                 }
-                                _ => {
-                                    // user data
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        frs.insert(p.x, p.y, p.user_data as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Binning points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
+                                if verbose && inputs.len() == 1 {
+                                    progress = (100.0_f64 * i as f64 / num_points) as i32;
+                                    if progress != old_progress {
+                                        println!("Reading points: {}%", progress);
+                                        old_progress = progress;
                                     }
                                 }
                             }
@@ -755,57 +737,31 @@ impl WhiteboxTool for LidarIdwInterpolation {
                     configs.photometric_interp = PhotometricInterpretation::Continuous;
                     configs.palette = palette.clone();
 
-                    let mut output = Raster::initialize_using_config(&output_file, &configs);
-                    if interp_parameter == "rgb" {
-                        output.configs.photometric_interp = PhotometricInterpretation::RGB;
-                        output.configs.data_type = DataType::RGBA32;
+                    // When there are multiple parameters, each output file name is suffixed by its parameter.
+                    let mut output_rasters = Vec::with_capacity(num_params);
+                    for k in 0..num_params {
+                        let file_name =
+                            parameter_output_file(&output_file, &interp_parameters[k], num_params);
+                        let mut output = Raster::initialize_using_config(&file_name, &configs);
+                        if is_rgb[k] {
+                            output.configs.photometric_interp = PhotometricInterpretation::RGB;
+                            output.configs.data_type = DataType::RGBA32;
+                        }
+                        output_rasters.push(output);
                     }
 
                     if num_tiles > 1 {
                         let (mut x, mut y): (f64, f64);
-                        let mut zn: f64;
-                        let mut dist: f64;
-                        let mut val: f64;
-                        let (mut val_red, mut val_green, mut val_blue): (f64, f64, f64);
-                        let (mut red, mut green, mut blue): (f64, f64, f64);
-                        let mut sum_weights: f64;
+                        let mut values = vec![nodata; num_params];
                         for row in 0..rows {
                             for col in 0..columns {
                                 x = west + (col as f64 + 0.5) * grid_res;
                                 y = north - (row as f64 + 0.5) * grid_res;
                                 let ret = frs.search(x, y);
                                 if ret.len() > 0 {
-                                    sum_weights = 0.0;
-                                    val = 0.0;
-                                    val_red = 0f64;
-                                    val_green = 0f64;
-                                    val_blue = 0f64;
-                                    for j in 0..ret.len() {
-                                        zn = ret[j].0;
-                                        dist = ret[j].1 as f64;
-                                        if dist > 0.0 {
-                                            if !interp_parameter_is_rgb {
-                                                val += zn / dist.powf(weight);
-                                            } else {
-                                                red = (zn as u32 & 0xFF) as f64;
-                                                green = ((zn as u32 >> 8) & 0xFF) as f64;
-                                                blue = ((zn as u32 >> 16) & 0xFF) as f64;
-                                                val_red += red / dist.powf(weight);
-                                                val_green += green / dist.powf(weight);
-                                                val_blue += blue / dist.powf(weight);
-                                            }
-                                            sum_weights += 1.0 / dist.powf(weight);
-                                        } else {
-                                            output.set_value(row, col, zn);
-                                            sum_weights = 0.0;
-                                            break;
-                                        }
-                                    }
-                                    if sum_weights > 0.0 {
-                                        if interp_parameter_is_rgb {
-                                            val = ((255u32 << 24) | ((val_blue.round() as u32) << 16) | ((val_green.round() as u32) << 8) | (val_red.round() as u32)) as f64;
-                                        }
-                                        output.set_value(row, col, val / sum_weights);
+                                    idw_values(&ret, &z_values, &is_rgb, weight, &mut values);
+                                    for k in 0..num_params {
+                                        output_rasters[k].set_value(row, col, values[k]);
                                     }
                                 }
                             }
@@ -820,57 +776,27 @@ impl WhiteboxTool for LidarIdwInterpolation {
                     } else {
                         // there's only one tile, so use all cores to interpolate this one tile.
                         let frs = Arc::new(frs); // wrap FRS in an Arc
+                        let z_values = Arc::new(z_values);
                         let num_procs = num_cpus::get() as isize;
                         let (tx, rx) = mpsc::channel();
                         for tid in 0..num_procs {
                             let frs = frs.clone();
+                            let z_values = z_values.clone();
+                            let is_rgb = is_rgb.clone();
                             let tx1 = tx.clone();
                             thread::spawn(move || {
                                 let (mut x, mut y): (f64, f64);
-                                let mut zn: f64;
-                                let mut dist: f64;
-                                let mut val: f64;
-                                let mut sum_weights: f64;
-                                let (mut val_red, mut val_green, mut val_blue): (f64, f64, f64);
-                                let (mut red, mut green, mut blue): (f64, f64, f64);
+                                let mut values = vec![nodata; num_params];
                                 for row in (0..rows).filter(|r| r % num_procs == tid) {
-                                    let mut data = vec![nodata; columns as usize];
+                                    let mut data = vec![vec![nodata; columns as usize]; num_params];
                                     for col in 0..columns {
                                         x = west + (col as f64 + 0.5) * grid_res;
                                         y = north - (row as f64 + 0.5) * grid_res;
                                         let ret = frs.search(x, y);
                                         if ret.len() > 0 {
-                                            sum_weights = 0.0;
-                                            val = 0.0;
-                                            val_red = 0f64;
-                                            val_green = 0f64;
-                                            val_blue = 0f64;
-                                            for j in 0..ret.len() {
-                                                zn = ret[j].0;
-                                                dist = ret[j].1 as f64;
-                                                if dist > 0.0 {
-                                                    if !interp_parameter_is_rgb {
-                                                        val += zn / dist.powf(weight);
-                                                    } else {
-                                                        red = (zn as u32 & 0xFF) as f64;
-                                                        green = ((zn as u32 >> 8) & 0xFF) as f64;
-                                                        blue = ((zn as u32 >> 16) & 0xFF) as f64;
-                                                        val_red += red / dist.powf(weight);
-                                                        val_green += green / dist.powf(weight);
-                                                        val_blue += blue / dist.powf(weight);
-                                                    }
-                                                    sum_weights += 1.0 / dist.powf(weight);
-                                                } else {
-                                                    data[col as usize] = zn;
-                                                    sum_weights = 0.0;
-                                                    break;
-                                                }
-                                            }
-                                            if sum_weights > 0.0 {
-                                                if interp_parameter_is_rgb {
-                                                    val = ((255u32 << 24) | ((val_blue.round() as u32) << 16) | ((val_green.round() as u32) << 8) | (val_red.round() as u32)) as f64;
-                                                }
-                                                data[col as usize] = val / sum_weights;
+                                            idw_values(&ret, &z_values, &is_rgb, weight, &mut values);
+                                            for k in 0..num_params {
+                                                data[k][col as usize] = values[k];
                                             }
                                         }
                                     }
@@ -880,8 +806,10 @@ impl WhiteboxTool for LidarIdwInterpolation {
                         }
 
                         for row in 0..rows {
-                            let data = rx.recv().unwrap();
-                            output.set_row_data(data.0, data.1);
+                            let (r, data) = rx.recv().unwrap();
+                            for (k, d) in data.into_iter().enumerate() {
+                                output_rasters[k].set_row_data(r, d);
+                            }
                             if verbose {
                                 progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as i32;
                                 if progress != old_progress {
@@ -894,30 +822,40 @@ impl WhiteboxTool for LidarIdwInterpolation {
 
                     let elapsed_time_run = get_formatted_elapsed_time(start_run);
 
-                    output.add_metadata_entry(format!(
-                        "Created by whitebox_tools\' {} tool",
-                        tool_name
-                    ));
-                    output.add_metadata_entry(format!("Input file: {}", input_file));
-                    output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
-                    output.add_metadata_entry(format!("Search radius: {}", search_radius));
-                    output.add_metadata_entry(format!("Weight: {}", weight));
-                    output.add_metadata_entry(format!(
-                        "Interpolation parameter: {}",
-                        interp_parameter
-                    ));
-                    output.add_metadata_entry(format!("Returns: {}", return_type));
-                    output.add_metadata_entry(format!("Excluded classes: {}", exclude_cls_str));
-                    output.add_metadata_entry(format!(
-                        "Elapsed Time (including I/O): {}",
-                        elapsed_time_run
-                    ));
+                    for (k, output) in output_rasters.iter_mut().enumerate() {
+                        output.add_metadata_entry(format!(
+                            "Created by whitebox_tools\' {} tool",
+                            tool_name
+                        ));
+                        output.add_metadata_entry(format!("Input file: {}", input_file));
+                        output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
+                        output.add_metadata_entry(format!("Search radius: {}", search_radius));
+                        output.add_metadata_entry(format!("Weight: {}", weight));
+                        output.add_metadata_entry(format!(
+                            "Interpolation parameter: {}",
+                            interp_parameters[k]
+                        ));
+                        output.add_metadata_entry(format!("Returns: {}", return_type));
+                        output.add_metadata_entry(format!("Excluded classes: {}", exclude_cls_str));
+                        if filter_by_time {
+                            output.add_metadata_entry(format!(
+                                "GPS time range: {} to {}",
+                                min_time, max_time
+                            ));
+                        }
+                        output.add_metadata_entry(format!(
+                            "Elapsed Time (including I/O): {}",
+                            elapsed_time_run
+                        ));
+                    }
 
                     if verbose && inputs.len() == 1 {
                         println!("Saving data...")
                     };
 
-                    let _ = output.write().unwrap();
+                    for output in output_rasters.iter_mut() {
+                        let _ = output.write().unwrap();
+                    }
 
                     tx2.send(tile).unwrap();
                 }
@@ -926,7 +864,7 @@ impl WhiteboxTool for LidarIdwInterpolation {
 
         let mut progress: i32;
         let mut old_progress: i32 = -1;
-        for tile in 0..inputs.len() {
+        for tile in 0..num_updates {
             let tile_completed = rx2.recv().unwrap();
             if verbose {
                 println!(
@@ -936,11 +874,11 @@ impl WhiteboxTool for LidarIdwInterpolation {
                         .replace(working_directory, "")
                         .replace(".las", ""),
                     tile + 1,
-                    inputs.len()
+                    num_updates
                 );
             }
             if verbose {
-                progress = (100.0_f64 * tile as f64 / (inputs.len() - 1) as f64) as i32;
+                progress = (100.0_f64 * tile as f64 / (num_updates - 1) as f64) as i32;
                 if progress != old_progress {
                     println!("Progress: {}%", progress);
                     old_progress = progress;
@@ -960,3 +898,51 @@ impl WhiteboxTool for LidarIdwInterpolation {
         Ok(())
     }
 }
+
+/// Calculates the inverse-distance weighted value of each interpolation parameter from the
+/// neighbouring points (point index, distance) returned by a fixed-radius search. RGB values
+/// are interpolated separately for each of the red, green, and blue channels.
+fn idw_values(
+    neighbours: &[(usize, f64)],
+    z_values: &[Vec<f64>],
+    is_rgb: &[bool],
+    weight: f64,
+    values: &mut [f64],
+) {
+    for k in 0..z_values.len() {
+        let mut sum_weights = 0f64;
+        let mut val = 0f64;
+        let (mut val_red, mut val_green, mut val_blue) = (0f64, 0f64, 0f64);
+        for &(j, dist) in neighbours {
+            let zn = z_values[k][j];
+            if dist > 0f64 {
+                let w = 1f64 / dist.powf(weight);
+                if is_rgb[k] {
+                    val_red += (zn as u32 & 0xFF) as f64 * w;
+                    val_green += ((zn as u32 >> 8) & 0xFF) as f64 * w;
+                    val_blue += ((zn as u32 >> 16) & 0xFF) as f64 * w;
+                } else {
+                    val += zn * w;
+                }
+                sum_weights += w;
+            } else {
+                // the grid cell coincides with a point
+                val = zn;
+                sum_weights = 0f64;
+                break;
+            }
+        }
+        values[k] = if sum_weights > 0f64 {
+            if is_rgb[k] {
+                ((255u32 << 24)
+                    | (((val_blue / sum_weights).round() as u32) << 16)
+                    | (((val_green / sum_weights).round() as u32) << 8)
+                    | ((val_red / sum_weights).round() as u32)) as f64
+            } else {
+                val / sum_weights
+            }
+        } else {
+            val
+        };
+    }
+}
@@ -0,0 +1,265 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::tools::*;
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool builds a quadtree spatial index for one or more LAS files. The index of each
+/// file is saved in a LAX file with the same name as the LAS file (e.g. *tile1.las* is indexed
+/// by *tile1.lax*). The LAX files use the same format as the spatial indexes created by the
+/// *lasindex* tool in LAStools, so indexes created by either software may be used
+/// interchangeably.
+///
+/// When a LAS file is indexed, tools that only require the points within part of the file
+/// read only the blocks of point records that overlap that area, rather than the entire file.
+/// This is particularly beneficial for tools that interpolate a tiled data set, such as
+/// `LidarTINGridding`, `LidarIdwInterpolation`, `LidarNearestNeighbourGridding`, and
+/// `LidarPointDensity`, which read the points within a buffer around each tile from the
+/// neighbouring tiles.
+///
+/// The depth of the quadtree is set such that the cells of the index contain roughly
+/// `--points_per_cell` points, on average. If the input file (`--input`) is not specified,
+/// all of the LAS files in the working directory will be indexed. Notice that an index must be
+/// rebuilt if the points in its LAS file are modified; LAS files that are output by the tools
+/// in WhiteboxTools are not indexed.
+///
+/// # See Also
+/// `LidarTile`, `LidarTINGridding`
+pub struct LidarIndex {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarIndex {
+    pub fn new() -> LidarIndex {
+        // public constructor
+        let name = "LidarIndex".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Builds a LAX spatial index for one or more LAS files.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file (including extension).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Average Points Per Index Cell".to_owned(),
+            flags: vec!["--points_per_cell".to_owned()],
+            description: "Average number of points within each cell of the index.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("1000".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las --points_per_cell=5000",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        LidarIndex {
+            name,
+            description,
+            toolbox,
+            parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarIndex {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut points_per_cell = 1000usize;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-points_per_cell" {
+                points_per_cell = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let start = Instant::now();
+
+        let mut inputs = vec![];
+        if input_file.is_empty() {
+            if working_directory.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "This tool must be run by specifying either an individual input file or a working directory."));
+            }
+            if std::path::Path::new(&working_directory).is_dir() {
+                for entry in fs::read_dir(working_directory)? {
+                    let s = entry?
+                        .path()
+                        .into_os_string()
+                        .to_str()
+                        .expect("Error reading path string")
+                        .to_string();
+                    if s.to_lowercase().ends_with(".las") {
+                        inputs.push(s);
+                    }
+                }
+            } else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The input directory ({}) is incorrect.", working_directory),
+                ));
+            }
+        } else {
+            input_file = resolve_path(&input_file, working_directory);
+            if !input_file.to_lowercase().ends_with(".las") {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Only uncompressed LAS files can be indexed.",
+                ));
+            }
+            inputs.push(input_file);
+        }
+
+        let num_files = inputs.len();
+        for (i, input_file) in inputs.iter().enumerate() {
+            if verbose && num_files == 1 {
+                println!("Reading input LAS file...");
+            }
+            let input = match LasFile::new(input_file, "r") {
+                Ok(lf) => lf,
+                Err(err) => panic!("Error reading file {}: {}", input_file, err),
+            };
+
+            let index = LasIndex::build(&input, points_per_cell);
+            let lax_file = path::Path::new(input_file)
+                .with_extension("lax")
+                .to_str()
+                .unwrap()
+                .to_string();
+            index.write(&lax_file)?;
+
+            if verbose {
+                if num_files == 1 {
+                    println!(
+                        "Indexed {} points in {} cells ({} levels)",
+                        input.header.number_of_points,
+                        index.cells.len(),
+                        index.levels
+                    );
+                } else {
+                    println!(
+                        "Indexed {} ({} of {})",
+                        input.get_short_filename(),
+                        i + 1,
+                        num_files
+                    );
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 01/06/2017
-Last Modified: 18/10/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
@@ -21,6 +21,20 @@ use std::u16;
 /// This tool can be used to print basic information about the data contained within a LAS file, used to store LiDAR
 /// data. The reported information will include including data on the header, point return frequency, and classification
 /// data and information about the variable length records (VLRs) and geokeys.
+///
+/// By default, the summary is written to an HTML report (`--output`). When the `--json` flag is
+/// specified, or the output file has a *.json* extension, a machine-readable JSON report is written
+/// instead. The JSON report contains all of the header fields, the list of VLRs, the point counts
+/// by classification, return number, and return position, point and pulse density statistics, the
+/// GPS time range, and the coordinate reference system (EPSG code and WKT) of the file. The density
+/// statistics are calculated from the number of points within the occupied cells of a grid laid over
+/// the file's extent, with a cell size chosen so that a cell contains 16 points on average. This
+/// format allows scripts and workflow managers to inspect the properties of a point cloud without
+/// parsing the text output of the tool. The `--vlr` and `--geokeys` flags only apply to the HTML
+/// report; the JSON report always includes the VLRs and the CRS.
+///
+/// # See Also
+/// `LidarTileFootprint`, `LidarPointStats`
 pub struct LidarInfo {
     name: String,
     description: String,
@@ -49,7 +63,7 @@ impl LidarInfo {
         parameters.push(ToolParameter {
             name: "Output Summary Report File".to_owned(),
             flags: vec!["-o".to_owned(), "--output".to_owned()],
-            description: "Output HTML file for summary report.".to_owned(),
+            description: "Output HTML file for summary report, or a JSON (*.json) file for a machine-readable report.".to_owned(),
             parameter_type: ParameterType::NewFile(ParameterFileType::Html),
             default_value: None,
             optional: true,
@@ -75,6 +89,15 @@ impl LidarInfo {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Write a machine-readable JSON report?".to_owned(),
+            flags: vec!["--json".to_owned()],
+            description: "Flag indicating whether the report should be written in JSON format rather than HTML.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -88,7 +111,8 @@ impl LidarInfo {
         }
         let usage = format!(
             ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las --vlr --geokeys\"
-.*{0} -r={1} --wd=\"*path*to*data*\" -i=file.las",
+.*{0} -r={1} --wd=\"*path*to*data*\" -i=file.las
+.*{0} -r={1} --wd=\"*path*to*data*\" -i=file.las -o=file_info.json",
             short_exe, name
         )
         .replace("*", &sep);
@@ -148,6 +172,7 @@ impl WhiteboxTool for LidarInfo {
         let mut output_file = String::new();
         let mut show_vlrs = false;
         let mut show_geokeys = false;
+        let mut json_output = false;
         let mut keyval: bool;
         if args.len() == 0 {
             return Err(Error::new(
@@ -185,6 +210,10 @@ impl WhiteboxTool for LidarInfo {
                 if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
                     show_geokeys = true;
                 }
+            } else if flag_val == "-json" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    json_output = true;
+                }
             }
         }
 
@@ -194,13 +223,38 @@ impl WhiteboxTool for LidarInfo {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = std::path::MAIN_SEPARATOR;
         // if !working_directory.ends_with(sep) {
         //     working_directory.push_str(&(sep.to_string()));
         // }
 
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
+        input_file = resolve_path(&input_file, working_directory);
+
+        output_file = resolve_path(&output_file, working_directory);
+
+        if output_file.to_lowercase().ends_with(".json") {
+            json_output = true;
+        }
+
+        if json_output {
+            if output_file.len() == 0 {
+                let stem = path::Path::new(&input_file).with_extension("");
+                output_file = format!("{}_summary.json", stem.to_string_lossy());
+            }
+            let mut input = LasFile::new(&input_file, "r")?;
+            let report = json_report(&mut input, &input_file);
+            let f = File::create(output_file.clone())?;
+            let mut writer = BufWriter::new(f);
+            writer.write_all(
+                serde_json::to_string_pretty(&report)
+                    .unwrap_or_default()
+                    .as_bytes(),
+            )?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
+            if verbose {
+                println!("Complete! Please see {} for output.", output_file);
+            }
+            return Ok(());
         }
 
         if output_file.len() == 0 {
@@ -511,3 +565,280 @@ impl WhiteboxTool for LidarInfo {
         Ok(())
     }
 }
+
+/// Builds the machine-readable report of a LAS file's header, VLRs, CRS, and point statistics.
+fn json_report(input: &mut LasFile, file_name: &str) -> serde_json::Value {
+    use serde_json::json;
+
+    let num_points = input.header.number_of_points as usize;
+    let has_gps_time = input.has_gps_time();
+    let min_x = input.header.min_x;
+    let min_y = input.header.min_y;
+    let width = input.header.max_x - min_x;
+    let height = input.header.max_y - min_y;
+    let area = width * height;
+
+    // the density grid has a cell size that places 16 points in each cell, on average
+    let (cell_size, grid_columns, grid_rows) = if num_points > 0 && area > 0f64 {
+        let cell_size = (16f64 * area / num_points as f64).sqrt();
+        (
+            cell_size,
+            (width / cell_size).floor() as usize + 1,
+            (height / cell_size).floor() as usize + 1,
+        )
+    } else {
+        (0f64, 0usize, 0usize)
+    };
+    let mut grid_points = vec![0u32; grid_columns * grid_rows];
+    let mut grid_pulses = vec![0u32; grid_columns * grid_rows];
+
+    let mut ret_array = [0u64; 15];
+    let mut class_array = [0u64; 256];
+    let (mut num_only, mut num_first, mut num_intermediate, mut num_last) =
+        (0u64, 0u64, 0u64, 0u64);
+    let (mut num_synthetic, mut num_keypoint, mut num_withheld, mut num_overlap) =
+        (0u64, 0u64, 0u64, 0u64);
+    let mut min_i = u16::MAX;
+    let mut max_i = u16::MIN;
+    let mut min_time = f64::INFINITY;
+    let mut max_time = f64::NEG_INFINITY;
+    for i in 0..num_points {
+        let p = input[i];
+        let ret = p.return_number().clamp(1, 15);
+        let nrets = p.number_of_returns();
+        ret_array[(ret - 1) as usize] += 1;
+        class_array[p.classification() as usize] += 1;
+        if nrets <= 1 {
+            num_only += 1;
+        } else if ret == 1 {
+            num_first += 1;
+        } else if ret >= nrets {
+            num_last += 1;
+        } else {
+            num_intermediate += 1;
+        }
+        if p.synthetic() {
+            num_synthetic += 1;
+        }
+        if p.keypoint() {
+            num_keypoint += 1;
+        }
+        if p.withheld() {
+            num_withheld += 1;
+        }
+        if p.overlap() {
+            num_overlap += 1;
+        }
+        min_i = min_i.min(p.intensity);
+        max_i = max_i.max(p.intensity);
+        if has_gps_time {
+            if let Ok(t) = input.get_gps_time(i) {
+                min_time = min_time.min(t);
+                max_time = max_time.max(t);
+            }
+        }
+        if cell_size > 0f64 {
+            let col = ((p.x - min_x) / cell_size).floor().max(0f64) as usize;
+            let row = ((p.y - min_y) / cell_size).floor().max(0f64) as usize;
+            let cell = row.min(grid_rows - 1) * grid_columns + col.min(grid_columns - 1);
+            grid_points[cell] += 1;
+            if ret == 1 {
+                grid_pulses[cell] += 1;
+            }
+        }
+    }
+
+    let density_stats = |counts: &[u32]| {
+        let mut densities: Vec<f64> = counts
+            .iter()
+            .filter(|&&n| n > 0)
+            .map(|&n| n as f64 / (cell_size * cell_size))
+            .collect();
+        if densities.is_empty() {
+            return serde_json::Value::Null;
+        }
+        densities.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = densities.len() as f64;
+        let mean = densities.iter().sum::<f64>() / n;
+        let stdev = (densities
+            .iter()
+            .map(|d| (d - mean) * (d - mean))
+            .sum::<f64>()
+            / n)
+            .sqrt();
+        let percentile = |q: f64| densities[((n - 1f64) * q).round() as usize];
+        json!({
+            "min": densities[0],
+            "max": densities[densities.len() - 1],
+            "mean": mean,
+            "stdev": stdev,
+            "p05": percentile(0.05),
+            "median": percentile(0.5),
+            "p95": percentile(0.95),
+        })
+    };
+    let occupied_cells = grid_points.iter().filter(|&&n| n > 0).count();
+    let num_pulses = ret_array[0];
+    let density = if cell_size > 0f64 {
+        json!({
+            "extent_area": area,
+            "points_per_unit_area": num_points as f64 / area,
+            "pulses_per_unit_area": num_pulses as f64 / area,
+            "mean_point_spacing": (area / num_points as f64).sqrt(),
+            "cell_size": cell_size,
+            "grid_columns": grid_columns,
+            "grid_rows": grid_rows,
+            "occupied_cells": occupied_cells,
+            "coverage": occupied_cells as f64 / (grid_columns * grid_rows) as f64,
+            "occupied_area": occupied_cells as f64 * cell_size * cell_size,
+            "point_density": density_stats(&grid_points),
+            "pulse_density": density_stats(&grid_pulses),
+        })
+    } else {
+        serde_json::Value::Null
+    };
+
+    let percent = |n: u64| {
+        if num_points > 0 {
+            100f64 * n as f64 / num_points as f64
+        } else {
+            0f64
+        }
+    };
+    let returns: Vec<serde_json::Value> = ret_array
+        .iter()
+        .enumerate()
+        .filter(|(_, &n)| n > 0)
+        .map(|(i, &n)| json!({ "return_number": i + 1, "count": n, "percent": percent(n) }))
+        .collect();
+    let classes: Vec<serde_json::Value> = class_array
+        .iter()
+        .enumerate()
+        .filter(|(_, &n)| n > 0)
+        .map(|(i, &n)| {
+            json!({
+                "class": i,
+                "name": convert_class_val_to_class_string(i as u8),
+                "count": n,
+                "percent": percent(n),
+            })
+        })
+        .collect();
+
+    let vlrs: Vec<serde_json::Value> = input
+        .vlr_data
+        .iter()
+        .enumerate()
+        .map(|(i, vlr)| {
+            json!({
+                "index": i,
+                "user_id": vlr.user_id.trim_end_matches(['\0', ' ']),
+                "record_id": vlr.record_id,
+                "description": vlr.description.trim_end_matches(['\0', ' ']),
+                "record_length_after_header": vlr.record_length_after_header,
+            })
+        })
+        .collect();
+
+    let epsg_code = input.geokeys.find_epsg_code();
+    let wkt = input.get_wkt();
+    let crs = json!({
+        "method": format!("{:?}", input.header.global_encoding.coordinate_reference_system_method()),
+        "epsg": if epsg_code > 0 { Some(epsg_code) } else { None },
+        "wkt": if wkt.is_empty() || wkt == "Unknown EPSG Code" { None } else { Some(wkt) },
+    });
+
+    let gps_time = if has_gps_time && num_points > 0 {
+        json!({
+            "type": format!("{:?}", input.header.global_encoding.gps_time()),
+            "min": min_time,
+            "max": max_time,
+        })
+    } else {
+        serde_json::Value::Null
+    };
+
+    let h = &input.header;
+    let project_id = format!(
+        "{:08x}-{:04x}-{:04x}-{}",
+        h.project_id1,
+        h.project_id2,
+        h.project_id3,
+        h.project_id4
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<String>>()
+            .join("")
+    );
+    // the extended VLR and 64-bit point count fields were introduced in LAS 1.4
+    let points_by_return: Vec<u64> = if h.version_minor >= 4 {
+        h.number_of_points_by_return.to_vec()
+    } else {
+        h.number_of_points_by_return_old
+            .iter()
+            .map(|&n| n as u64)
+            .collect()
+    };
+    let mut header = json!({
+        "file_signature": h.file_signature.trim_end_matches(['\0', ' ']),
+        "file_source_id": h.file_source_id,
+        "global_encoding": {
+            "value": h.global_encoding.value,
+            "gps_time": format!("{:?}", h.global_encoding.gps_time()),
+            "waveform_data_internal": h.global_encoding.waveform_data_internal(),
+            "waveform_data_external": h.global_encoding.waveform_data_external(),
+            "return_data_synthetic": h.global_encoding.return_data_synthetic(),
+            "crs_method": format!("{:?}", h.global_encoding.coordinate_reference_system_method()),
+        },
+        "project_id": if h.project_id_used { Some(project_id) } else { None },
+        "version": format!("{}.{}", h.version_major, h.version_minor),
+        "system_id": h.system_id.trim_end_matches(['\0', ' ']),
+        "generating_software": h.generating_software.trim_end_matches(['\0', ' ']),
+        "file_creation_day": h.file_creation_day,
+        "file_creation_year": h.file_creation_year,
+        "header_size": h.header_size,
+        "offset_to_points": h.offset_to_points,
+        "number_of_vlrs": h.number_of_vlrs,
+        "point_format": h.point_format,
+        "point_record_length": h.point_record_length,
+        "number_of_points": h.number_of_points,
+        "number_of_points_by_return": points_by_return,
+        "scale_factor": [h.x_scale_factor, h.y_scale_factor, h.z_scale_factor],
+        "offset": [h.x_offset, h.y_offset, h.z_offset],
+        "min": [h.min_x, h.min_y, h.min_z],
+        "max": [h.max_x, h.max_y, h.max_z],
+        "waveform_data_start": h.waveform_data_start,
+    });
+    if h.version_minor >= 4 {
+        header["number_of_extended_vlrs"] = json!(h.number_of_extended_vlrs);
+        header["offset_to_extended_vlrs"] = json!(h.offset_to_ex_vlrs);
+    }
+
+    json!({
+        "file_name": file_name,
+        "short_file_name": input.get_short_filename(),
+        "header": header,
+        "vlrs": vlrs,
+        "crs": crs,
+        "points": {
+            "count": num_points,
+            "intensity": if num_points > 0 { json!({ "min": min_i, "max": max_i }) } else { serde_json::Value::Null },
+            "synthetic": num_synthetic,
+            "keypoint": num_keypoint,
+            "withheld": num_withheld,
+            "overlap": num_overlap,
+        },
+        "returns": {
+            "by_return_number": returns,
+            "by_position": {
+                "only": num_only,
+                "first": num_first,
+                "intermediate": num_intermediate,
+                "last": num_last,
+            },
+        },
+        "classes": classes,
+        "density": density,
+        "gps_time": gps_time,
+    })
+}
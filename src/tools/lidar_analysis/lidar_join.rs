@@ -158,11 +158,7 @@ impl WhiteboxTool for LidarJoin {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = std::path::MAIN_SEPARATOR;
-
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let mut output: LasFile = LasFile::new(&output_file, "w")?;
 
@@ -178,9 +174,7 @@ impl WhiteboxTool for LidarJoin {
         for value in vec {
             if !value.trim().is_empty() {
                 let mut input_file = value.trim().to_owned();
-                if !input_file.contains(sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
 
                 let input = match LasFile::new(&input_file, "r") {
                     Ok(lf) => lf,
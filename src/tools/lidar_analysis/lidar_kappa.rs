@@ -219,26 +219,16 @@ impl WhiteboxTool for LidarKappaIndex {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: i32;
         let mut old_progress: i32 = -1;
 
-        if !input_file1.contains(&sep) && !input_file1.contains("/") {
-            input_file1 = format!("{}{}", working_directory, input_file1);
-        }
-        if !input_file2.contains(&sep) && !input_file2.contains("/") {
-            input_file2 = format!("{}{}", working_directory, input_file2);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file1 = resolve_path(&input_file1, working_directory);
+        input_file2 = resolve_path(&input_file2, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
         if !output_file.ends_with(".html") {
             output_file = output_file + ".html";
         }
-        if !class_accuracy_file.contains(&sep) && !class_accuracy_file.contains("/") {
-            class_accuracy_file = format!("{}{}", working_directory, class_accuracy_file);
-        }
+        class_accuracy_file = resolve_path(&class_accuracy_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -0,0 +1,1214 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::na::{DMatrix, DVector};
+use crate::raster::*;
+use crate::structures::{BoundingBox, DistanceMetric, FixedRadiusSearch2D};
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Interpolates LAS files using ordinary kriging. An empirical semivariogram is calculated from
+/// a sample of the selected points in each tile and a variogram model, either `spherical`
+/// (default), `exponential`, or `gaussian` (`--model`), is fitted to it using weighted least
+/// squares, with the number of point pairs in each lag used as weights. Any of the model's
+/// `--range`, `--sill` (i.e. the total sill, including the nugget), and `--nugget` parameters may
+/// be specified by the user, in which case only the remaining parameters are fitted. For the
+/// exponential and gaussian models, the range is the practical range, at which the model reaches
+/// 95% of the sill. The fitted parameters are recorded in the output raster's metadata.
+///
+/// Each grid cell is then estimated from the nearest points (`--max_points`, default 16) within
+/// the search radius (`--radius`) of the cell centre, by solving the ordinary kriging system.
+/// Cells without any points within the search radius are assigned NoData. The kriging
+/// (prediction) variance of each cell may optionally be output (`--variance_output`). When the
+/// tool is run on all of the LAS files in the working directory, the variance rasters are named
+/// after the output rasters, with a `_variance` suffix, and the variogram is fitted to each tile
+/// separately.
+///
+/// Points may be excluded from the interpolation based on their return type (`--returns`),
+/// class (`--exclude_cls`), elevation (`--minz` and `--maxz`), and GPS time (`--min_time` and
+/// `--max_time`).
+///
+/// Kriging is considerably slower than the other LiDAR gridding methods, particularly when a
+/// large number of neighbouring points is used.
+///
+/// # See Also
+/// `LidarIdwInterpolation`, `LidarTINGridding`, `LidarNearestNeighbourGridding`
+pub struct LidarKrigingInterpolation {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarKrigingInterpolation {
+    pub fn new() -> LidarKrigingInterpolation {
+        // public constructor
+        let name = "LidarKrigingInterpolation".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Interpolates LAS files using ordinary kriging with a fitted semivariogram model. When the input/output parameters are not specified, the tool interpolates all LAS files contained within the working directory."
+            .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file (including extension).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file (including extension).".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Variance File (optional)".to_owned(),
+            flags: vec!["--variance_output".to_owned()],
+            description: "Optional output kriging variance raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter{
+            name: "Interpolation Parameter".to_owned(),
+            flags: vec!["--parameter".to_owned()],
+            description: "Interpolation parameter; options are 'elevation' (default), 'intensity', 'class', 'return_number', 'number_of_returns', 'scan angle', 'user data'.".to_owned(),
+            parameter_type: ParameterType::OptionList(
+                vec![
+                    "elevation".to_owned(),
+                    "intensity".to_owned(),
+                    "class".to_owned(),
+                    "return_number".to_owned(),
+                    "number_of_returns".to_owned(),
+                    "scan angle".to_owned(),
+                    "user data".to_owned()
+                ]
+            ),
+            default_value: Some("elevation".to_owned()),
+            optional: true
+        });
+
+        parameters.push(ToolParameter {
+            name: "Point Returns Included".to_owned(),
+            flags: vec!["--returns".to_owned()],
+            description:
+                "Point return types to include; options are 'all' (default), 'last', 'first'."
+                    .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "all".to_owned(),
+                "last".to_owned(),
+                "first".to_owned(),
+            ]),
+            default_value: Some("all".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Output raster's grid resolution.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Radius".to_owned(),
+            flags: vec!["--radius".to_owned()],
+            description: "Search Radius.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Number of Points".to_owned(),
+            flags: vec!["--max_points".to_owned()],
+            description: "Maximum number of neighbouring points used to estimate each grid cell."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("16".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Variogram Model".to_owned(),
+            flags: vec!["--model".to_owned()],
+            description: "Semivariogram model; options are 'spherical' (default), 'exponential', 'gaussian'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "spherical".to_owned(),
+                "exponential".to_owned(),
+                "gaussian".to_owned(),
+            ]),
+            default_value: Some("spherical".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Variogram Range (optional)".to_owned(),
+            flags: vec!["--range".to_owned()],
+            description: "Optional variogram range, in map units. Fitted if unspecified.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Variogram Sill (optional)".to_owned(),
+            flags: vec!["--sill".to_owned()],
+            description: "Optional variogram (total) sill. Fitted if unspecified.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Variogram Nugget (optional)".to_owned(),
+            flags: vec!["--nugget".to_owned()],
+            description: "Optional variogram nugget. Fitted if unspecified.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter{
+            name: "Exclusion Classes (0-18, based on LAS spec; e.g. 3,4,5,6,7)".to_owned(),
+            flags: vec!["--exclude_cls".to_owned()],
+            description: "Optional exclude classes from interpolation; Valid class values range from 0 to 18, based on LAS specifications. Example, --exclude_cls='3,4,5,6,7,18'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Elevation Value (optional)".to_owned(),
+            flags: vec!["--minz".to_owned()],
+            description: "Optional minimum elevation for inclusion in interpolation.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Elevation Value (optional)".to_owned(),
+            flags: vec!["--maxz".to_owned()],
+            description: "Optional maximum elevation for inclusion in interpolation.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum GPS Time (optional)".to_owned(),
+            flags: vec!["--min_time".to_owned()],
+            description: "Optional minimum GPS time for inclusion in interpolation.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum GPS Time (optional)".to_owned(),
+            flags: vec!["--max_time".to_owned()],
+            description: "Optional maximum GPS time for inclusion in interpolation.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.extend(InputFileFilter::parameters());
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --resolution=2.0 --radius=5.0\"
+.*{0} -r={1} --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --variance_output=variance.tif --resolution=1.0 --radius=5.0 --max_points=24 --model=exponential --nugget=0.0 --exclude_cls='3,4,5,6,7,18'", short_exe, name).replace("*", &sep);
+
+        LidarKrigingInterpolation {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarKrigingInterpolation {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--resolution".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--radius".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--max_points".to_string(), ParameterConstraint::Min(1.0)),
+            ("--exclude_cls".to_string(), ParameterConstraint::ListRange(0.0, 255.0)),
+        ]
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_dir}{input_stem}.tif".to_string())
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+        let mut output_file: String = "".to_string();
+        let mut variance_file: String = "".to_string();
+        let mut interp_parameter = "elevation".to_string();
+        let mut return_type = "all".to_string();
+        let mut grid_res: f64 = 1.0;
+        let mut search_radius = 2.5;
+        let mut max_points = 16usize;
+        let mut model_str = "spherical".to_string();
+        let mut range: Option<f64> = None;
+        let mut sill: Option<f64> = None;
+        let mut nugget: Option<f64> = None;
+        let mut include_class_vals = vec![true; 256];
+        let mut exclude_cls_str = String::new();
+        let mut max_z = f64::INFINITY;
+        let mut min_z = f64::NEG_INFINITY;
+        let mut min_time = f64::NEG_INFINITY;
+        let mut max_time = f64::INFINITY;
+        let mut file_filter = InputFileFilter::default();
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-variance_output" {
+                variance_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-parameter" {
+                interp_parameter = if keyval {
+                    vec[1].to_string().to_lowercase()
+                } else {
+                    args[i + 1].to_string().to_lowercase()
+                };
+            } else if flag_val == "-returns" {
+                return_type = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution" {
+                grid_res = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-radius" {
+                search_radius = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_points" {
+                max_points = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-model" {
+                model_str = if keyval {
+                    vec[1].to_string().to_lowercase()
+                } else {
+                    args[i + 1].to_string().to_lowercase()
+                };
+            } else if flag_val == "-range" {
+                range = Some(if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                });
+            } else if flag_val == "-sill" {
+                sill = Some(if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                });
+            } else if flag_val == "-nugget" {
+                nugget = Some(if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                });
+            } else if flag_val == "-exclude_cls" {
+                exclude_cls_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                let mut cmd = exclude_cls_str.split(",");
+                let mut vec = cmd.collect::<Vec<&str>>();
+                if vec.len() == 1 {
+                    cmd = exclude_cls_str.split(";");
+                    vec = cmd.collect::<Vec<&str>>();
+                }
+                for value in vec {
+                    if !value.trim().is_empty() {
+                        let c = value.trim().parse::<usize>().unwrap();
+                        include_class_vals[c] = false;
+                    }
+                }
+            } else if flag_val == "-minz" {
+                min_z = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-maxz" {
+                max_z = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_time" {
+                min_time = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_time" {
+                max_time = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-include" {
+                file_filter.add_include(if keyval { vec[1] } else { &args[i + 1] });
+            } else if flag_val == "-exclude" {
+                file_filter.add_exclude(if keyval { vec[1] } else { &args[i + 1] });
+            } else if flag_val == "-recursive" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    file_filter.recursive = true;
+                }
+            }
+        }
+
+        let (all_returns, late_returns, early_returns): (bool, bool, bool);
+        if return_type.contains("last") {
+            all_returns = false;
+            late_returns = true;
+            early_returns = false;
+        } else if return_type.contains("first") {
+            all_returns = false;
+            late_returns = false;
+            early_returns = true;
+        } else {
+            // all
+            all_returns = true;
+            late_returns = false;
+            early_returns = false;
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if interp_parameter == "rgb" {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "RGB colour data cannot be interpolated using kriging.",
+            ));
+        }
+
+        let model = if model_str.contains("exp") {
+            VariogramModel::Exponential
+        } else if model_str.contains("gau") {
+            VariogramModel::Gaussian
+        } else {
+            VariogramModel::Spherical
+        };
+
+        if let Some(r) = range {
+            if r <= 0f64 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The variogram range must be greater than zero.",
+                ));
+            }
+        }
+        if let Some(c0) = nugget {
+            if c0 < 0f64 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The variogram nugget must not be negative.",
+                ));
+            }
+        }
+        if let Some(s) = sill {
+            if s < nugget.unwrap_or(0f64) || s <= 0f64 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The variogram sill must be greater than zero and no less than the nugget.",
+                ));
+            }
+        }
+        if max_points == 0 {
+            max_points = 1;
+        }
+
+        let filter_by_time = min_time.is_finite() || max_time.is_finite();
+        if min_time > max_time {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The minimum GPS time (--min_time) must be less than the maximum GPS time (--max_time).",
+            ));
+        }
+
+        let start = Instant::now();
+
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        let mut variance_outputs = vec![];
+        if input_file.is_empty() {
+            if working_directory.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "This tool must be run by specifying either an individual input file or a working directory."));
+            }
+            // the output file may be a template used to name the output of each input file,
+            // e.g. '{input_stem}_dem.tif'
+            let output_template = if is_output_template(&output_file) {
+                output_file.clone()
+            } else {
+                self.get_default_output_template().unwrap()
+            };
+            if std::path::Path::new(&working_directory).is_dir() {
+                for s in file_filter.find_lidar_files(working_directory)? {
+                    let mut out_file =
+                        expand_output_template(&output_template, &s, &self.get_tool_name());
+                    out_file = resolve_path(&out_file, working_directory);
+                    inputs.push(s);
+                    outputs.push(out_file);
+                }
+                for output in &outputs {
+                    if variance_file.is_empty() {
+                        variance_outputs.push(String::new());
+                    } else {
+                        variance_outputs.push(output.replace(".tif", "_variance.tif"));
+                    }
+                }
+            } else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The input directory ({}) is incorrect.", working_directory),
+                ));
+            }
+        } else {
+            input_file = resolve_path(&input_file, working_directory);
+            inputs.push(input_file.clone());
+            if output_file.is_empty() {
+                output_file = expand_output_template(
+                    &self.get_default_output_template().unwrap(),
+                    &input_file,
+                    &self.get_tool_name(),
+                );
+            }
+            output_file = resolve_path(&output_file, working_directory);
+            outputs.push(output_file);
+            variance_file = resolve_path(&variance_file, working_directory);
+            variance_outputs.push(variance_file);
+        }
+
+        /*
+        If multiple files are being interpolated, we will need to know their bounding boxes,
+        in order to retrieve points from adjacent tiles. This is so that there are no edge
+        effects.
+        */
+        let mut bounding_boxes = vec![];
+        for in_file in &inputs {
+            let header = LasHeader::read_las_header(&in_file.replace("\"", ""))?;
+            bounding_boxes.push(BoundingBox {
+                min_x: header.min_x,
+                max_x: header.max_x,
+                min_y: header.min_y,
+                max_y: header.max_y,
+            });
+        }
+
+        if verbose {
+            println!("Performing interpolation...");
+        }
+
+        let num_tiles = inputs.len();
+        let tile_list = Arc::new(Mutex::new(0..num_tiles));
+        let inputs = Arc::new(inputs);
+        let outputs = Arc::new(outputs);
+        let variance_outputs = Arc::new(variance_outputs);
+        let bounding_boxes = Arc::new(bounding_boxes);
+        let num_procs2 = num_cpus::get() as isize;
+        let (tx2, rx2) = mpsc::channel();
+        for _ in 0..num_procs2 {
+            let inputs = inputs.clone();
+            let outputs = outputs.clone();
+            let variance_outputs = variance_outputs.clone();
+            let bounding_boxes = bounding_boxes.clone();
+            let tile_list = tile_list.clone();
+            // copy over the string parameters
+            let interp_parameter = interp_parameter.clone();
+            let return_type = return_type.clone();
+            let tool_name = self.get_tool_name();
+            let exclude_cls_str = exclude_cls_str.clone();
+            let include_class_vals = include_class_vals.clone();
+            let tx2 = tx2.clone();
+            thread::spawn(move || {
+                let mut tile = 0;
+                while tile < num_tiles {
+                    // Get the next tile up for interpolation
+                    tile = match tile_list.lock().unwrap().next() {
+                        Some(val) => val,
+                        None => break, // There are no more tiles to interpolate
+                    };
+                    let start_run = Instant::now();
+
+                    let input_file = inputs[tile].replace("\"", "").clone();
+                    let output_file = outputs[tile].replace("\"", "").clone();
+                    let variance_file = variance_outputs[tile].replace("\"", "").clone();
+
+                    // Expand the bounding box to include the areas of overlap
+                    let bb = BoundingBox {
+                        min_x: bounding_boxes[tile].min_x - search_radius,
+                        max_x: bounding_boxes[tile].max_x + search_radius,
+                        min_y: bounding_boxes[tile].min_y - search_radius,
+                        max_y: bounding_boxes[tile].max_y + search_radius,
+                    };
+                    let mut frs: FixedRadiusSearch2D<usize> =
+                        FixedRadiusSearch2D::new(search_radius, DistanceMetric::Euclidean);
+                    let mut points: Vec<(f64, f64, f64)> = vec![];
+                    // the indices of the points within the tile itself, used to fit the variogram
+                    let mut tile_points: Vec<usize> = vec![];
+
+                    if verbose && inputs.len() == 1 {
+                        println!("Reading input LAS file...");
+                    }
+
+                    let mut progress: i32;
+                    let mut old_progress: i32 = -1;
+
+                    for m in 0..inputs.len() {
+                        if bounding_boxes[m].overlaps(bb) {
+                            let las_file_name = inputs[m].replace("\"", "");
+                            let las = if m == tile {
+                                LasFile::new(&las_file_name, "r")
+                            } else {
+                                LasFile::new_in_extent(&las_file_name, bb)
+                            };
+                            let input = match las {
+                                Ok(lf) => lf,
+                                Err(err) => panic!("Error reading file {}: {}", las_file_name, err),
+                            };
+
+                            if filter_by_time && !input.has_gps_time() {
+                                panic!(
+                                    "The file {} does not contain GPS time data, which is required by --min_time and --max_time.",
+                                    las_file_name
+                                );
+                            }
+                            let n_points = input.header.number_of_points as usize;
+                            if n_points == 0 {
+                                continue;
+                            }
+                            let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
+
+                            for i in 0..n_points {
+                                let p: PointData = input[i];
+                                if !p.withheld() {
+                                    if all_returns
+                                        || (p.is_late_return() & late_returns)
+                                        || (p.is_early_return() & early_returns)
+                                    {
+                                        if include_class_vals[p.classification() as usize] {
+                                            if bb.is_point_in_box(p.x, p.y)
+                                                && p.z >= min_z
+                                                && p.z <= max_z
+                                                && input.is_gps_time_in_range(i, min_time, max_time)
+                                            {
+                                                let value = match &interp_parameter as &str {
+                                                    "elevation" | "z" => p.z,
+                                                    "intensity" => p.intensity as f64,
+                                                    "scan angle" | "scan_angle" => p.scan_angle as f64,
+                                                    "class" => p.classification() as f64,
+                                                    "return_number" => p.return_number() as f64,
+                                                    "number_of_returns" => p.number_of_returns() as f64,
+                                                    _ => p.user_data as f64,
+                                                };
+                                                if m == tile {
+                                                    tile_points.push(points.len());
+                                                }
+                                                frs.insert(p.x, p.y, points.len());
+                                                points.push((p.x, p.y, value));
+                                            }
+                                        }
+                                    }
+                                }
+                                if verbose && inputs.len() == 1 {
+                                    progress = (100.0_f64 * i as f64 / num_points) as i32;
+                                    if progress != old_progress {
+                                        println!("Reading points: {}%", progress);
+                                        old_progress = progress;
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    let variogram =
+                        Variogram::fit(model, &points, &tile_points, range, sill, nugget);
+                    if verbose && inputs.len() == 1 {
+                        println!(
+                            "Fitted variogram: range={:.4}, sill={:.4}, nugget={:.4}",
+                            variogram.range,
+                            variogram.nugget + variogram.partial_sill,
+                            variogram.nugget
+                        );
+                    }
+
+                    let west: f64 = bounding_boxes[tile].min_x;
+                    let north: f64 = bounding_boxes[tile].max_y;
+                    let rows: isize =
+                        (((north - bounding_boxes[tile].min_y) / grid_res).ceil()) as isize;
+                    let columns: isize =
+                        (((bounding_boxes[tile].max_x - west) / grid_res).ceil()) as isize;
+                    let south: f64 = north - rows as f64 * grid_res;
+                    let east = west + columns as f64 * grid_res;
+                    let nodata = -32768.0f64;
+
+                    let mut configs = RasterConfigs {
+                        ..Default::default()
+                    };
+                    configs.rows = rows as usize;
+                    configs.columns = columns as usize;
+                    configs.north = north;
+                    configs.south = south;
+                    configs.east = east;
+                    configs.west = west;
+                    configs.resolution_x = grid_res;
+                    configs.resolution_y = grid_res;
+                    configs.nodata = nodata;
+                    configs.data_type = DataType::F32;
+                    configs.photometric_interp = PhotometricInterpretation::Continuous;
+
+                    let mut output = Raster::initialize_using_config(&output_file, &configs);
+                    let mut variance = if !variance_file.is_empty() {
+                        Some(Raster::initialize_using_config(&variance_file, &configs))
+                    } else {
+                        None
+                    };
+
+                    if num_tiles > 1 {
+                        let (mut x, mut y): (f64, f64);
+                        for row in 0..rows {
+                            for col in 0..columns {
+                                x = west + (col as f64 + 0.5) * grid_res;
+                                y = north - (row as f64 + 0.5) * grid_res;
+                                if let Some((val, var)) =
+                                    variogram.krige(&frs, &points, x, y, max_points)
+                                {
+                                    output.set_value(row, col, val);
+                                    if let Some(ref mut v) = variance {
+                                        if var.is_finite() {
+                                            v.set_value(row, col, var);
+                                        }
+                                    }
+                                }
+                            }
+                            if verbose && inputs.len() == 1 {
+                                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as i32;
+                                if progress != old_progress {
+                                    println!("Progress: {}%", progress);
+                                    old_progress = progress;
+                                }
+                            }
+                        }
+                    } else {
+                        // there's only one tile, so use all cores to interpolate this one tile.
+                        let frs = Arc::new(frs);
+                        let points = Arc::new(points);
+                        let num_procs = num_cpus::get() as isize;
+                        let (tx, rx) = mpsc::channel();
+                        for tid in 0..num_procs {
+                            let frs = frs.clone();
+                            let points = points.clone();
+                            let tx1 = tx.clone();
+                            thread::spawn(move || {
+                                let (mut x, mut y): (f64, f64);
+                                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                                    let mut data = vec![nodata; columns as usize];
+                                    let mut var_data = vec![nodata; columns as usize];
+                                    for col in 0..columns {
+                                        x = west + (col as f64 + 0.5) * grid_res;
+                                        y = north - (row as f64 + 0.5) * grid_res;
+                                        if let Some((val, var)) =
+                                            variogram.krige(&frs, &points, x, y, max_points)
+                                        {
+                                            data[col as usize] = val;
+                                            if var.is_finite() {
+                                                var_data[col as usize] = var;
+                                            }
+                                        }
+                                    }
+                                    tx1.send((row, data, var_data)).unwrap();
+                                }
+                            });
+                        }
+
+                        for row in 0..rows {
+                            let data = rx.recv().unwrap();
+                            output.set_row_data(data.0, data.1);
+                            if let Some(ref mut v) = variance {
+                                v.set_row_data(data.0, data.2);
+                            }
+                            if verbose {
+                                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as i32;
+                                if progress != old_progress {
+                                    println!("Progress: {}%", progress);
+                                    old_progress = progress;
+                                }
+                            }
+                        }
+                    }
+
+                    let elapsed_time_run = get_formatted_elapsed_time(start_run);
+
+                    let mut metadata = vec![];
+                    metadata.push(format!("Created by whitebox_tools\' {} tool", tool_name));
+                    metadata.push(format!("Input file: {}", input_file));
+                    metadata.push(format!("Grid resolution: {}", grid_res));
+                    metadata.push(format!("Search radius: {}", search_radius));
+                    metadata.push(format!("Maximum number of points: {}", max_points));
+                    metadata.push(format!("Variogram model: {}", variogram.model.description()));
+                    metadata.push(format!("Variogram range: {}", variogram.range));
+                    metadata.push(format!(
+                        "Variogram sill: {}",
+                        variogram.nugget + variogram.partial_sill
+                    ));
+                    metadata.push(format!("Variogram nugget: {}", variogram.nugget));
+                    metadata.push(format!("Interpolation parameter: {}", interp_parameter));
+                    metadata.push(format!("Returns: {}", return_type));
+                    metadata.push(format!("Excluded classes: {}", exclude_cls_str));
+                    if filter_by_time {
+                        metadata.push(format!("GPS time range: {} to {}", min_time, max_time));
+                    }
+                    metadata.push(format!(
+                        "Elapsed Time (including I/O): {}",
+                        elapsed_time_run
+                    ));
+                    for entry in &metadata {
+                        output.add_metadata_entry(entry.clone());
+                    }
+
+                    if verbose && inputs.len() == 1 {
+                        println!("Saving data...")
+                    };
+
+                    let _ = output.write().unwrap();
+
+                    if let Some(mut v) = variance {
+                        v.configs.palette = "spectrum.plt".to_string();
+                        for entry in &metadata {
+                            v.add_metadata_entry(entry.clone());
+                        }
+                        v.add_metadata_entry("Kriging variance".to_string());
+                        let _ = v.write().unwrap();
+                    }
+
+                    tx2.send(tile).unwrap();
+                }
+            });
+        }
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+        for tile in 0..inputs.len() {
+            let tile_completed = rx2.recv().unwrap();
+            if verbose {
+                println!(
+                    "Finished interpolating {} ({} of {})",
+                    inputs[tile_completed]
+                        .replace("\"", "")
+                        .replace(working_directory, "")
+                        .replace(".las", ""),
+                    tile + 1,
+                    inputs.len()
+                );
+            }
+            if verbose {
+                progress = (100.0_f64 * tile as f64 / (inputs.len() - 1) as f64) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (including I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum VariogramModel {
+    Spherical,
+    Exponential,
+    Gaussian,
+}
+
+impl VariogramModel {
+    /// Returns the model's semivariance at lag distance `h`, for a unit sill and no nugget.
+    fn unit_value(&self, h: f64, range: f64) -> f64 {
+        let r = h / range;
+        match self {
+            VariogramModel::Spherical => {
+                if r >= 1f64 {
+                    1f64
+                } else {
+                    1.5 * r - 0.5 * r * r * r
+                }
+            }
+            VariogramModel::Exponential => 1f64 - (-3f64 * r).exp(),
+            VariogramModel::Gaussian => 1f64 - (-3f64 * r * r).exp(),
+        }
+    }
+
+    fn description(self) -> String {
+        match self {
+            VariogramModel::Spherical => "spherical".to_string(),
+            VariogramModel::Exponential => "exponential".to_string(),
+            VariogramModel::Gaussian => "gaussian".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Variogram {
+    model: VariogramModel,
+    nugget: f64,
+    partial_sill: f64,
+    range: f64,
+}
+
+impl Variogram {
+    fn semivariance(&self, h: f64) -> f64 {
+        if h <= 0f64 {
+            return 0f64;
+        }
+        self.nugget + self.partial_sill * self.model.unit_value(h, self.range)
+    }
+
+    /// Fits the variogram model to the empirical semivariogram of a sample of the points
+    /// referenced by `indices`. Parameters that are specified by the user are held fixed.
+    fn fit(
+        model: VariogramModel,
+        points: &[(f64, f64, f64)],
+        indices: &[usize],
+        range: Option<f64>,
+        sill: Option<f64>,
+        nugget: Option<f64>,
+    ) -> Variogram {
+        const MAX_SAMPLES: usize = 2000;
+        const NUM_LAGS: usize = 20;
+
+        let num_samples = indices.len().min(MAX_SAMPLES);
+        let step = indices.len() as f64 / num_samples.max(1) as f64;
+        let sample: Vec<(f64, f64, f64)> = (0..num_samples)
+            .map(|k| points[indices[(k as f64 * step) as usize]])
+            .collect();
+
+        // the maximum lag is half of the diagonal of the sample's extent
+        let (mut min_x, mut max_x) = (f64::INFINITY, f64::NEG_INFINITY);
+        let (mut min_y, mut max_y) = (f64::INFINITY, f64::NEG_INFINITY);
+        for p in &sample {
+            min_x = min_x.min(p.0);
+            max_x = max_x.max(p.0);
+            min_y = min_y.min(p.1);
+            max_y = max_y.max(p.1);
+        }
+        let max_lag = ((max_x - min_x).powi(2) + (max_y - min_y).powi(2)).sqrt() / 2f64;
+
+        let mut variogram = Variogram {
+            model,
+            nugget: nugget.unwrap_or(0f64),
+            partial_sill: sill.unwrap_or(1f64) - nugget.unwrap_or(0f64),
+            range: range.unwrap_or(if max_lag > 0f64 { max_lag } else { 1f64 }),
+        };
+        if num_samples < 2 || max_lag <= 0f64 {
+            return variogram;
+        }
+
+        // the empirical semivariogram
+        let lag_width = max_lag / NUM_LAGS as f64;
+        let mut counts = [0f64; NUM_LAGS];
+        let mut lags = [0f64; NUM_LAGS];
+        let mut gammas = [0f64; NUM_LAGS];
+        for i in 0..num_samples {
+            for j in i + 1..num_samples {
+                let h = ((sample[i].0 - sample[j].0).powi(2) + (sample[i].1 - sample[j].1).powi(2))
+                    .sqrt();
+                if h < max_lag {
+                    let bin = ((h / lag_width) as usize).min(NUM_LAGS - 1);
+                    counts[bin] += 1f64;
+                    lags[bin] += h;
+                    gammas[bin] += 0.5 * (sample[i].2 - sample[j].2).powi(2);
+                }
+            }
+        }
+        let mut empirical = vec![];
+        for k in 0..NUM_LAGS {
+            if counts[k] > 0f64 {
+                empirical.push((lags[k] / counts[k], gammas[k] / counts[k], counts[k]));
+            }
+        }
+        if empirical.is_empty() {
+            return variogram;
+        }
+
+        // For a given range, the model is linear in the nugget and partial sill, which are
+        // solved for by weighted least squares; the range is found by a grid search.
+        let candidate_ranges: Vec<f64> = match range {
+            Some(r) => vec![r],
+            None => (1..=100).map(|k| max_lag * k as f64 / 100f64).collect(),
+        };
+        let mut min_sse = f64::INFINITY;
+        for r in candidate_ranges {
+            let f: Vec<f64> = empirical
+                .iter()
+                .map(|e| model.unit_value(e.0, r))
+                .collect();
+            let (c0, c) = match (nugget, sill) {
+                (Some(c0), Some(s)) => (c0, s - c0),
+                (Some(c0), None) => {
+                    let (mut num, mut den) = (0f64, 0f64);
+                    for k in 0..empirical.len() {
+                        num += empirical[k].2 * f[k] * (empirical[k].1 - c0);
+                        den += empirical[k].2 * f[k] * f[k];
+                    }
+                    (c0, if den > 0f64 { (num / den).max(0f64) } else { 0f64 })
+                }
+                (None, Some(s)) => {
+                    let (mut num, mut den) = (0f64, 0f64);
+                    for k in 0..empirical.len() {
+                        num += empirical[k].2 * (1f64 - f[k]) * (empirical[k].1 - s * f[k]);
+                        den += empirical[k].2 * (1f64 - f[k]) * (1f64 - f[k]);
+                    }
+                    let c0 = if den > 0f64 {
+                        (num / den).max(0f64).min(s)
+                    } else {
+                        0f64
+                    };
+                    (c0, s - c0)
+                }
+                (None, None) => {
+                    let (mut sw, mut sf, mut sff, mut sg, mut sfg) = (0f64, 0f64, 0f64, 0f64, 0f64);
+                    for k in 0..empirical.len() {
+                        let w = empirical[k].2;
+                        sw += w;
+                        sf += w * f[k];
+                        sff += w * f[k] * f[k];
+                        sg += w * empirical[k].1;
+                        sfg += w * f[k] * empirical[k].1;
+                    }
+                    let det = sw * sff - sf * sf;
+                    let (mut c0, mut c) = if det.abs() > 0f64 {
+                        ((sg * sff - sf * sfg) / det, (sw * sfg - sf * sg) / det)
+                    } else {
+                        (0f64, if sff > 0f64 { sfg / sff } else { 0f64 })
+                    };
+                    if c0 < 0f64 {
+                        c0 = 0f64;
+                        c = if sff > 0f64 { sfg / sff } else { 0f64 };
+                    }
+                    if c < 0f64 {
+                        c = 0f64;
+                        c0 = sg / sw;
+                    }
+                    (c0, c)
+                }
+            };
+            let mut sse = 0f64;
+            for k in 0..empirical.len() {
+                sse += empirical[k].2 * (empirical[k].1 - c0 - c * f[k]).powi(2);
+            }
+            if sse < min_sse {
+                min_sse = sse;
+                variogram.nugget = c0;
+                variogram.partial_sill = c;
+                variogram.range = r;
+            }
+        }
+        variogram
+    }
+
+    /// Estimates the value, and kriging variance, at (x, y) by ordinary kriging of the nearest
+    /// points within the search radius. Returns `None` if there are no points within the search
+    /// radius. The variance is NaN if the kriging system could not be solved, in which case the
+    /// value is the mean of the neighbouring points.
+    fn krige(
+        &self,
+        frs: &FixedRadiusSearch2D<usize>,
+        points: &[(f64, f64, f64)],
+        x: f64,
+        y: f64,
+        max_points: usize,
+    ) -> Option<(f64, f64)> {
+        let mut ret = frs.search(x, y);
+        if ret.is_empty() {
+            return None;
+        }
+        ret.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        if ret[0].1 == 0f64 {
+            return Some((points[ret[0].0].2, 0f64));
+        }
+
+        // coincident points would make the kriging system singular
+        let mut neighbours: Vec<(f64, f64, f64)> = Vec::with_capacity(max_points);
+        for (i, _) in &ret {
+            let p = points[*i];
+            if !neighbours.iter().any(|q| q.0 == p.0 && q.1 == p.1) {
+                neighbours.push(p);
+                if neighbours.len() == max_points {
+                    break;
+                }
+            }
+        }
+
+        let n = neighbours.len();
+        let a = DMatrix::from_fn(n + 1, n + 1, |i, j| {
+            if i == n && j == n {
+                0f64
+            } else if i == n || j == n {
+                1f64
+            } else {
+                let h = ((neighbours[i].0 - neighbours[j].0).powi(2)
+                    + (neighbours[i].1 - neighbours[j].1).powi(2))
+                .sqrt();
+                self.semivariance(h)
+            }
+        });
+        let gamma0: Vec<f64> = neighbours
+            .iter()
+            .map(|p| self.semivariance(((p.0 - x).powi(2) + (p.1 - y).powi(2)).sqrt()))
+            .collect();
+        let b = DVector::from_fn(n + 1, |i, _| if i == n { 1f64 } else { gamma0[i] });
+
+        match a.lu().solve(&b) {
+            Some(solution) => {
+                let mut value = 0f64;
+                let mut variance = solution[n];
+                for i in 0..n {
+                    value += solution[i] * neighbours[i].2;
+                    variance += solution[i] * gamma0[i];
+                }
+                Some((value, variance.max(0f64)))
+            }
+            None => {
+                let mean = neighbours.iter().map(|p| p.2).sum::<f64>() / n as f64;
+                Some((mean, f64::NAN))
+            }
+        }
+    }
+}
@@ -0,0 +1,558 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::na::{DMatrix, DVector};
+use crate::tools::*;
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// The number of neighbouring ground points used to fit each local thin plate spline.
+const NUM_SPLINE_POINTS: usize = 12;
+
+/// The maximum number of iterations within each scale domain.
+const MAX_ITERATIONS: usize = 100;
+
+/// This tool classifies the ground points within a LiDAR point cloud using the Multiscale
+/// Curvature Classification (MCC) algorithm of Evans and Hudak (2007). MCC was developed for
+/// forested, low-relief terrain, where a dense, multi-layered canopy leaves few returns from the
+/// ground, and it offers an independent alternative to the slope-based `LidarGroundPointFilter`
+/// and the morphological `LidarSmrfFilter`, against which ground surfaces can be cross-validated.
+///
+/// MCC iteratively removes points that lie above an interpolated surface, i.e. that have a positive
+/// local curvature, within three scale domains. Within each iteration, a thin plate spline surface
+/// is interpolated from the remaining ground points onto a grid, the grid is smoothed using a 3 x 3
+/// mean filter, and each point that lies more than the curvature tolerance above the surface,
+/// bilinearly interpolated at the point's location, is classified as non-ground. The iterations
+/// continue until fewer than 1% of the remaining points are removed (0.1% in the last scale domain).
+/// The grid resolutions of the three scale domains are 0.5, 1.0, and 1.5 times the scale parameter
+/// (`--scale`), and the curvature tolerance (`--curvature`) is increased by 0.1 in each successive
+/// domain. The surface is fit by regularized thin plate splines using the 12 nearest ground
+/// points of each grid cell.
+///
+/// The scale parameter should be approximately the mean point spacing of the point cloud, which
+/// can be found using the `LidarInfo` tool; larger values are appropriate in areas with large
+/// trees or buildings. The curvature tolerance is in elevation units; lower values remove more
+/// low vegetation at the expense of removing some ground points in areas of rough terrain. The
+/// defaults (a scale of 1.5 and a tolerance of 0.3) are those recommended by Evans and Hudak
+/// (2007) for forested terrain.
+///
+/// Ground points are assigned class 2 and non-ground points are assigned class 1 (unclassified)
+/// in the output file. Points that are classified as noise (classes 7 and 18) or are withheld are
+/// excluded from the analysis and their classes are left unaltered. Since MCC only removes points
+/// that lie above the surface, low noise should be classified, e.g. using `LidarOutliers`, prior
+/// to running this tool.
+///
+/// # Reference
+/// Evans, J. S., & Hudak, A. T. (2007). A multiscale curvature algorithm for classifying discrete
+/// return LiDAR in forested environments. *IEEE Transactions on Geoscience and Remote Sensing*,
+/// 45(4), 1029-1038.
+///
+/// # See Also
+/// `LidarGroundPointFilter`, `LidarSmrfFilter`, `LidarOutliers`, `LidarInfo`
+pub struct LidarMccFilter {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarMccFilter {
+    pub fn new() -> LidarMccFilter {
+        // public constructor
+        let name = "LidarMccFilter".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Identifies ground points within LiDAR point clouds using Multiscale Curvature Classification (MCC)."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Scale Parameter".to_owned(),
+            flags: vec!["--scale".to_owned()],
+            description: "Scale parameter, i.e. the grid resolution of the middle scale domain, which should approximate the point spacing.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Curvature Tolerance".to_owned(),
+            flags: vec!["--curvature".to_owned()],
+            description: "Curvature tolerance, i.e. the height above the interpolated surface beyond which points are classified as non-ground.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.3".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=\"input.las\" -o=\"output.las\" --scale=1.5 --curvature=0.3", short_exe, name).replace("*", &sep);
+
+        LidarMccFilter {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarMccFilter {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--scale".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--curvature".to_string(), ParameterConstraint::Min(0.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+        let mut output_file: String = "".to_string();
+        let mut scale = 1.5f64;
+        let mut curvature = 0.3f64;
+        let ground_class_value = 2u8;
+        let otp_class_value = 1u8;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-scale" {
+                scale = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-curvature" {
+                curvature = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if scale <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The scale parameter must be greater than zero.",
+            ));
+        }
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        let start = Instant::now();
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        // Find the points that are included in the analysis; initially, all are ground points.
+        let mut points: Vec<[f64; 3]> = Vec::with_capacity(n_points);
+        let mut point_index = vec![usize::MAX; n_points];
+        for i in 0..n_points {
+            let p: PointData = input.get_point_info(i);
+            if !p.withheld() && !p.is_classified_noise() {
+                point_index[i] = points.len();
+                points.push([p.x, p.y, p.z]);
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Reading points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        if points.len() < 3 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file does not contain enough points that can be classified.",
+            ));
+        }
+        let points = Arc::new(points);
+        let mut is_ground = vec![true; points.len()];
+
+        // The three scale domains, with their grid resolutions, curvature tolerances, and the
+        // proportion of points below which removals are considered to have converged.
+        let scale_domains = [
+            (0.5 * scale, curvature, 0.01),
+            (scale, curvature + 0.1, 0.01),
+            (1.5 * scale, curvature + 0.2, 0.001),
+        ];
+        for (sd, &(resolution, tolerance, convergence)) in scale_domains.iter().enumerate() {
+            for iteration in 1..=MAX_ITERATIONS {
+                let ground: Vec<usize> = (0..points.len()).filter(|&k| is_ground[k]).collect();
+                if ground.len() < 3 {
+                    break;
+                }
+                let surface = SplineSurface::new(
+                    &points,
+                    &ground,
+                    resolution,
+                    input.header.min_x,
+                    input.header.max_x,
+                    input.header.min_y,
+                    input.header.max_y,
+                );
+                let mut num_removed = 0usize;
+                for &k in ground.iter() {
+                    let p = points[k];
+                    if p[2] - surface.interpolate(p[0], p[1]) > tolerance {
+                        is_ground[k] = false;
+                        num_removed += 1;
+                    }
+                }
+                if verbose {
+                    println!(
+                        "Scale domain {} (resolution {:.3}), iteration {}: {} points removed",
+                        sd + 1,
+                        resolution,
+                        iteration,
+                        num_removed
+                    );
+                }
+                if (num_removed as f64) < convergence * ground.len() as f64 {
+                    break;
+                }
+            }
+        }
+
+        // Classify the points and output the data
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        let mut num_ground = 0usize;
+        for i in 0..n_points {
+            let mut pr = input.get_record(i);
+            if point_index[i] != usize::MAX {
+                if is_ground[point_index[i]] {
+                    pr.get_point_data_mut()
+                        .set_classification(ground_class_value);
+                    num_ground += 1;
+                } else {
+                    pr.get_point_data_mut().set_classification(otp_class_value);
+                }
+            }
+            output.add_point_record(pr);
+            output.add_extra_bytes(input.get_extra_bytes(i));
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Classifying points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "Number of ground points: {} ({:.1}%)",
+                num_ground,
+                100f64 * num_ground as f64 / n_points.max(1) as f64
+            );
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A gridded surface interpolated from ground points using local thin plate splines and smoothed
+/// using a 3 x 3 mean filter.
+struct SplineSurface {
+    west: f64,
+    north: f64,
+    resolution: f64,
+    rows: isize,
+    columns: isize,
+    data: Vec<f64>,
+}
+
+impl SplineSurface {
+    fn new(
+        points: &Arc<Vec<[f64; 3]>>,
+        ground: &[usize],
+        resolution: f64,
+        west: f64,
+        east: f64,
+        south: f64,
+        north: f64,
+    ) -> SplineSurface {
+        let rows = (((north - south) / resolution).ceil() as isize).max(1);
+        let columns = (((east - west) / resolution).ceil() as isize).max(1);
+        let mut tree = KdTree::new_with_capacity(2, 64);
+        for &k in ground {
+            tree.add([points[k][0], points[k][1]], k).unwrap();
+        }
+        let tree = Arc::new(tree);
+
+        let num_procs = num_cpus::get() as isize;
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let points = points.clone();
+            let tree = tree.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                    let y = north - (row as f64 + 0.5) * resolution;
+                    let mut data = vec![0f64; columns as usize];
+                    for col in 0..columns {
+                        let x = west + (col as f64 + 0.5) * resolution;
+                        let neighbours: Vec<[f64; 3]> = tree
+                            .nearest(&[x, y], NUM_SPLINE_POINTS, &squared_euclidean)
+                            .unwrap()
+                            .iter()
+                            .map(|(_, &k)| points[k])
+                            .collect();
+                        data[col as usize] = thin_plate_spline(&neighbours, x, y);
+                    }
+                    tx.send((row, data)).unwrap();
+                }
+            });
+        }
+        let mut grid = vec![0f64; (rows * columns) as usize];
+        for _ in 0..rows {
+            let (row, data) = rx.recv().unwrap();
+            let start = (row * columns) as usize;
+            grid[start..start + columns as usize].copy_from_slice(&data);
+        }
+
+        // smooth the surface using a 3 x 3 mean filter
+        let mut smoothed = vec![0f64; grid.len()];
+        for row in 0..rows {
+            for col in 0..columns {
+                let mut sum = 0f64;
+                let mut n = 0f64;
+                for r in (row - 1).max(0)..=(row + 1).min(rows - 1) {
+                    for c in (col - 1).max(0)..=(col + 1).min(columns - 1) {
+                        sum += grid[(r * columns + c) as usize];
+                        n += 1f64;
+                    }
+                }
+                smoothed[(row * columns + col) as usize] = sum / n;
+            }
+        }
+
+        SplineSurface {
+            west,
+            north,
+            resolution,
+            rows,
+            columns,
+            data: smoothed,
+        }
+    }
+
+    /// Returns the surface elevation at a location by bilinear interpolation between the centres
+    /// of the grid cells.
+    fn interpolate(&self, x: f64, y: f64) -> f64 {
+        let half_res = self.resolution / 2f64;
+        let fc = ((x - self.west - half_res) / self.resolution)
+            .max(0f64)
+            .min((self.columns - 1) as f64);
+        let fr = ((self.north - half_res - y) / self.resolution)
+            .max(0f64)
+            .min((self.rows - 1) as f64);
+        let (c0, r0) = (fc.floor() as isize, fr.floor() as isize);
+        let (c1, r1) = ((c0 + 1).min(self.columns - 1), (r0 + 1).min(self.rows - 1));
+        let (tx, ty) = (fc - c0 as f64, fr - r0 as f64);
+        let z = |r: isize, c: isize| self.data[(r * self.columns + c) as usize];
+        (z(r0, c0) * (1f64 - tx) + z(r0, c1) * tx) * (1f64 - ty)
+            + (z(r1, c0) * (1f64 - tx) + z(r1, c1) * tx) * ty
+    }
+}
+
+/// Evaluates, at (`x`, `y`), a regularized thin plate spline fit to a set of points. Coordinates are
+/// scaled by the distance to the farthest point, for numerical stability. The mean elevation of the
+/// points is returned if the spline cannot be fit, e.g. when the points are collinear.
+fn thin_plate_spline(points: &[[f64; 3]], x: f64, y: f64) -> f64 {
+    const REGULARIZATION: f64 = 1e-4;
+    let n = points.len();
+    let mean = points.iter().map(|p| p[2]).sum::<f64>() / n.max(1) as f64;
+    if n < 3 {
+        return mean;
+    }
+    let scale = points
+        .iter()
+        .map(|p| ((p[0] - x).powi(2) + (p[1] - y).powi(2)).sqrt())
+        .fold(0f64, f64::max)
+        .max(f64::EPSILON);
+    let local: Vec<(f64, f64)> = points
+        .iter()
+        .map(|p| ((p[0] - x) / scale, (p[1] - y) / scale))
+        .collect();
+    let kernel = |r2: f64| if r2 > 0f64 { 0.5 * r2 * r2.ln() } else { 0f64 };
+    let a = DMatrix::from_fn(n + 3, n + 3, |i, j| {
+        if i < n && j < n {
+            if i == j {
+                REGULARIZATION
+            } else {
+                kernel((local[i].0 - local[j].0).powi(2) + (local[i].1 - local[j].1).powi(2))
+            }
+        } else if i < n {
+            match j - n {
+                0 => 1f64,
+                1 => local[i].0,
+                _ => local[i].1,
+            }
+        } else if j < n {
+            match i - n {
+                0 => 1f64,
+                1 => local[j].0,
+                _ => local[j].1,
+            }
+        } else {
+            0f64
+        }
+    });
+    let b = DVector::from_fn(n + 3, |i, _| if i < n { points[i][2] - mean } else { 0f64 });
+    match a.lu().solve(&b) {
+        Some(solution) if solution.iter().all(|v| v.is_finite()) => {
+            // the spline is evaluated at the origin of the local coordinates
+            let mut value = mean + solution[n];
+            for i in 0..n {
+                value += solution[i] * kernel(local[i].0 * local[i].0 + local[i].1 * local[i].1);
+            }
+            value
+        }
+        _ => mean,
+    }
+}
@@ -0,0 +1,397 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::raster::*;
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool normalizes the elevations of a LiDAR point cloud (`--input`) against a raster digital
+/// terrain model (`--dem`), such that each point's elevation is replaced by its height above the
+/// ground. Height normalization is the standard precursor to the calculation of canopy metrics
+/// (e.g. `LidarCanopyMetrics`) and to individual tree detection. The ground elevation at each
+/// point is estimated by bilinear interpolation between the centres of the four DEM grid cells
+/// that surround the point, ignoring any NoData cells. The DEM and the LiDAR file must share the
+/// same coordinate reference system and vertical units. A DEM may be created from the ground
+/// points of the point cloud, e.g. using `LidarTINGridding` with `--exclude_cls` set to exclude
+/// all but class 2 points, or obtained from an external source.
+///
+/// The heights may be written either to the point elevations (`--target=z`, the default) or to an
+/// extra bytes attribute named `HeightAboveGround` (`--target=extra_bytes`), which preserves the
+/// original elevations. Extra bytes attributes may be interpolated by the `LidarTINGridding` tool.
+/// Points that lie outside of the DEM, or within NoData areas, are excluded from the output when
+/// the heights are written to the point elevations, and are assigned the no-data value of the
+/// attribute (-9999) otherwise. Small negative heights are common for ground points, because of
+/// the smoothing of the DEM; the `--clip_negative` flag sets negative heights to zero.
+///
+/// # See Also
+/// `LidarCanopyMetrics`, `LidarTINGridding`, `LidarGroundPointFilter`, `HeightAboveGround`
+pub struct LidarNormalizeByDem {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarNormalizeByDem {
+    pub fn new() -> LidarNormalizeByDem {
+        // public constructor
+        let name = "LidarNormalizeByDem".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Normalizes LiDAR point elevations to heights above the ground using a raster DEM."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster DEM (ground surface) file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output LiDAR File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Height Target".to_owned(),
+            flags: vec!["--target".to_owned()],
+            description: "Where heights are written; options are 'z' and 'extra_bytes'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "z".to_owned(),
+                "extra_bytes".to_owned(),
+            ]),
+            default_value: Some("z".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Set negative heights to zero?".to_owned(),
+            flags: vec!["--clip_negative".to_owned()],
+            description: "Set negative heights to zero.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las --dem=dtm.tif -o=normalized.las --clip_negative
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las --dem=dtm.tif -o=output.las --target=extra_bytes", short_exe, name).replace("*", &sep);
+
+        LidarNormalizeByDem {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarNormalizeByDem {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut dem_file = String::new();
+        let mut output_file = String::new();
+        let mut target = "z".to_string();
+        let mut clip_negative = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-target" {
+                target = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-clip_negative" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    clip_negative = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        dem_file = resolve_path(&dem_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        let use_extra_bytes = match target.as_str() {
+            "z" | "elevation" => false,
+            "extra_bytes" | "extrabytes" | "extra bytes" => true,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The --target parameter must be either 'z' or 'extra_bytes'.",
+                ))
+            }
+        };
+
+        if verbose {
+            println!("Reading input files...");
+        }
+        let input = LasFile::new(&input_file, "r")?;
+        let dem = Raster::new(&dem_file, "r")?;
+
+        let start = Instant::now();
+
+        let nodata = dem.configs.nodata;
+        let west = dem.configs.west;
+        let north = dem.configs.north;
+        let res_x = dem.configs.resolution_x;
+        let res_y = dem.configs.resolution_y;
+        // bilinear interpolation between the cell centres, ignoring NoData cells
+        let ground_elevation = |x: f64, y: f64| -> Option<f64> {
+            if dem.get_value(dem.get_row_from_y(y), dem.get_column_from_x(x)) == nodata {
+                return None;
+            }
+            let c = (x - west) / res_x - 0.5;
+            let r = (north - y) / res_y - 0.5;
+            let (r0, c0) = (r.floor(), c.floor());
+            let (fr, fc) = (r - r0, c - c0);
+            let (r0, c0) = (r0 as isize, c0 as isize);
+            let mut sum = 0f64;
+            let mut sum_w = 0f64;
+            for (dr, dc, w) in [
+                (0, 0, (1f64 - fr) * (1f64 - fc)),
+                (0, 1, (1f64 - fr) * fc),
+                (1, 0, fr * (1f64 - fc)),
+                (1, 1, fr * fc),
+            ] {
+                let z = dem.get_value(r0 + dr, c0 + dc);
+                if z != nodata && w > 0f64 {
+                    sum += z * w;
+                    sum_w += w;
+                }
+            }
+            if sum_w > 0f64 {
+                Some(sum / sum_w)
+            } else {
+                None
+            }
+        };
+
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+
+        // the height attribute is appended to any existing extra bytes attributes
+        let height_no_data = -9999f64;
+        let mut height_descriptor =
+            ExtraBytesDescriptor::new("HeightAboveGround", 9u8, "height above the DEM");
+        height_descriptor.set_no_data(height_no_data);
+        let attribute_offset = input.get_extra_bytes_length();
+        if use_extra_bytes {
+            let mut descriptors = input.extra_bytes_descriptors.clone();
+            descriptors.retain(|d| d.name != "HeightAboveGround");
+            if descriptors.len() < input.extra_bytes_descriptors.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input file already contains a HeightAboveGround attribute.",
+                ));
+            }
+            descriptors.push(height_descriptor.clone());
+            output.set_extra_bytes_descriptors(descriptors);
+        }
+        let mut extra_bytes = vec![0u8; output.get_extra_bytes_length()];
+
+        let n_points = input.header.number_of_points as usize;
+        let (mut num_outside, mut num_negative) = (0usize, 0usize);
+        let (mut min_height, mut max_height) = (f64::INFINITY, f64::NEG_INFINITY);
+        for i in 0..n_points {
+            let mut pr = input.get_record(i);
+            let p = pr.get_point_data_mut();
+            let height = match ground_elevation(p.x, p.y) {
+                Some(ground_z) => {
+                    let mut h = p.z - ground_z;
+                    if h < 0f64 {
+                        num_negative += 1;
+                        if clip_negative {
+                            h = 0f64;
+                        }
+                    }
+                    min_height = min_height.min(h);
+                    max_height = max_height.max(h);
+                    Some(h)
+                }
+                None => {
+                    num_outside += 1;
+                    None
+                }
+            };
+            if use_extra_bytes {
+                let input_bytes = input.get_extra_bytes(i);
+                extra_bytes[0..input_bytes.len()].copy_from_slice(input_bytes);
+                height_descriptor.set_value(
+                    height.unwrap_or(height_no_data),
+                    &mut extra_bytes[attribute_offset..],
+                );
+                output.add_point_record(pr);
+                output.add_extra_bytes(&extra_bytes);
+            } else if let Some(h) = height {
+                p.z = h;
+                output.add_point_record(pr);
+                output.add_extra_bytes(input.get_extra_bytes(i));
+            }
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / n_points as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            if min_height <= max_height {
+                println!("Height range: {:.3} to {:.3}", min_height, max_height);
+            }
+            println!(
+                "Number of points below the ground surface: {}{}",
+                num_negative,
+                if clip_negative { " (set to zero)" } else { "" }
+            );
+        }
+        if num_outside > 0 {
+            if use_extra_bytes {
+                println!(
+                    "Warning: {} points were outside of the DEM or within NoData areas and were assigned a height of {}.",
+                    num_outside, height_no_data
+                );
+            } else {
+                println!(
+                    "Warning: {} points were outside of the DEM or within NoData areas and were excluded from the output.",
+                    num_outside
+                );
+            }
+        }
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 10/07/2017
-Last Modified: 10/05/2019
+Last Modified: 16/10/2026
 License: MIT
 
 NOTES:
@@ -18,7 +18,6 @@ use crate::tools::*;
 use num_cpus;
 use std::env;
 use std::f64;
-use std::fs;
 use std::io::{Error, ErrorKind};
 use std::path;
 use std::sync::mpsc;
@@ -129,6 +128,40 @@ impl LidarPointDensity {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Only update changed tiles?".to_owned(),
+            flags: vec!["--incremental".to_owned()],
+            description: "Only process tiles with missing outputs, or outputs that are older than the tile or its overlapping neighbours.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Resolution Units".to_owned(),
+            flags: vec!["--res_units".to_owned()],
+            description: "Units of the grid resolution and search radius; 'native' indicates the units of the point coordinates.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "native".to_owned(),
+                "meters".to_owned(),
+                "feet".to_owned(),
+                "degrees".to_owned(),
+            ]),
+            default_value: Some("native".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Convert mismatched units?".to_owned(),
+            flags: vec!["--convert_units".to_owned()],
+            description: "Convert the grid resolution and search radius into the units of the point coordinates when they do not match.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.extend(InputFileFilter::parameters());
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -188,6 +221,18 @@ impl WhiteboxTool for LidarPointDensity {
         self.toolbox.clone()
     }
 
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--resolution".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--radius".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--exclude_cls".to_string(), ParameterConstraint::ListRange(0.0, 255.0)),
+        ]
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_dir}{input_stem}.tif".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -204,6 +249,10 @@ impl WhiteboxTool for LidarPointDensity {
         let mut exclude_cls_str = String::new();
         let mut max_z = f64::INFINITY;
         let mut min_z = f64::NEG_INFINITY;
+        let mut incremental = false;
+        let mut res_units = CoordinateUnits::NotSpecified;
+        let mut convert_units = false;
+        let mut file_filter = InputFileFilter::default();
 
         // read the arguments
         if args.len() == 0 {
@@ -288,6 +337,28 @@ impl WhiteboxTool for LidarPointDensity {
                 } else {
                     max_z = args[i + 1].to_string().parse::<f64>().unwrap();
                 }
+            } else if flag_val == "-incremental" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    incremental = true;
+                }
+            } else if flag_val == "-res_units" {
+                res_units = if keyval {
+                    CoordinateUnits::parse(vec[1])?
+                } else {
+                    CoordinateUnits::parse(&args[i + 1])?
+                };
+            } else if flag_val == "-convert_units" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    convert_units = true;
+                }
+            } else if flag_val == "-include" {
+                file_filter.add_include(if keyval { vec[1] } else { &args[i + 1] });
+            } else if flag_val == "-exclude" {
+                file_filter.add_exclude(if keyval { vec[1] } else { &args[i + 1] });
+            } else if flag_val == "-recursive" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    file_filter.recursive = true;
+                }
             }
         }
 
@@ -345,29 +416,20 @@ impl WhiteboxTool for LidarPointDensity {
             //         }
             //     }
             // }
+            // the output file may be a template used to name the output of each input file,
+            // e.g. '{input_stem}_dem.tif'
+            let output_template = if is_output_template(&output_file) {
+                output_file.clone()
+            } else {
+                self.get_default_output_template().unwrap()
+            };
             if std::path::Path::new(&working_directory).is_dir() {
-                for entry in fs::read_dir(working_directory.clone())? {
-                    let s = entry?
-                    .path()
-                    .into_os_string()
-                    .to_str()
-                    .expect("Error reading path string")
-                    .to_string();
-                    if s.to_lowercase().ends_with(".las") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".las", ".tif")
-                                .replace(".LAS", ".tif"),
-                        )
-                    } else if s.to_lowercase().ends_with(".zip") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".zip", ".tif")
-                                .replace(".ZIP", ".tif"),
-                        )
-                    }
+                for s in file_filter.find_lidar_files(working_directory)? {
+                    let mut out_file =
+                        expand_output_template(&output_template, &s, &self.get_tool_name());
+                    out_file = resolve_path(&out_file, working_directory);
+                    inputs.push(s);
+                    outputs.push(out_file);
                 }
             } else {
                 return Err(Error::new(
@@ -376,19 +438,16 @@ impl WhiteboxTool for LidarPointDensity {
                 ));
             }
         } else {
-            if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-                input_file = format!("{}{}", working_directory, input_file);
-            }
+            input_file = resolve_path(&input_file, working_directory);
             inputs.push(input_file.clone());
             if output_file.is_empty() {
-                output_file = input_file
-                    .clone()
-                    .replace(".las", ".tif")
-                    .replace(".LAS", ".tif");
-            }
-            if !output_file.contains(path::MAIN_SEPARATOR) && !output_file.contains("/") {
-                output_file = format!("{}{}", working_directory, output_file);
+                output_file = expand_output_template(
+                    &self.get_default_output_template().unwrap(),
+                    &input_file,
+                    &self.get_tool_name(),
+                );
             }
+            output_file = resolve_path(&output_file, working_directory);
             outputs.push(output_file);
         }
 
@@ -408,12 +467,46 @@ impl WhiteboxTool for LidarPointDensity {
             });
         }
 
+        // check the grid resolution against the units of the point coordinates
+        if !inputs.is_empty() {
+            let mut first_tile = LasFile::new(&inputs[0].replace("\"", ""), "rv")?;
+            let data_units = CoordinateUnits::from_las(&mut first_tile);
+            let (multiplier, message) = check_distance_units(
+                "grid resolution",
+                grid_res,
+                res_units,
+                data_units,
+                bounding_boxes[0],
+                convert_units,
+            );
+            if let Some(message) = message {
+                println!("{}", message);
+            }
+            grid_res *= multiplier;
+            search_radius *= multiplier;
+        }
+
+        let tiles_to_update = if incremental {
+            let tile_outputs: Vec<Vec<String>> = outputs.iter().map(|f| vec![f.clone()]).collect();
+            find_tiles_to_update(&inputs, &tile_outputs, &bounding_boxes, search_radius)
+        } else {
+            (0..inputs.len()).collect()
+        };
+        let num_updates = tiles_to_update.len();
+        if verbose && incremental {
+            println!(
+                "{} of {} tiles require updating.",
+                num_updates,
+                inputs.len()
+            );
+        }
+
         if verbose {
             println!("Performing analysis...");
         }
 
         let num_tiles = inputs.len();
-        let tile_list = Arc::new(Mutex::new(0..num_tiles));
+        let tile_list = Arc::new(Mutex::new(tiles_to_update.into_iter()));
         let inputs = Arc::new(inputs);
         let outputs = Arc::new(outputs);
         let bounding_boxes = Arc::new(bounding_boxes);
@@ -464,17 +557,23 @@ impl WhiteboxTool for LidarPointDensity {
 
                     for m in 0..inputs.len() {
                         if bounding_boxes[m].overlaps(bb) {
-                            let input =
-                                match LasFile::new(&inputs[m].replace("\"", "").clone(), "r") {
-                                    Ok(lf) => lf,
-                                    Err(err) => panic!(
-                                        "Error reading file {}: {}",
-                                        inputs[m].replace("\"", ""),
-                                        err
-                                    ),
-                                };
+                            // Only the points within the buffered extent are needed from
+                            // neighbouring tiles, which can be read quickly if they are indexed.
+                            let las_file_name = inputs[m].replace("\"", "");
+                            let las = if m == tile {
+                                LasFile::new(&las_file_name, "r")
+                            } else {
+                                LasFile::new_in_extent(&las_file_name, bb)
+                            };
+                            let input = match las {
+                                Ok(lf) => lf,
+                                Err(err) => panic!("Error reading file {}: {}", las_file_name, err),
+                            };
 
                             let n_points = input.header.number_of_points as usize;
+                            if n_points == 0 {
+                                continue;
+                            }
                             let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
 
                             for i in 0..n_points {
@@ -617,7 +716,7 @@ impl WhiteboxTool for LidarPointDensity {
 
         let mut progress: i32;
         let mut old_progress: i32 = -1;
-        for tile in 0..inputs.len() {
+        for tile in 0..num_updates {
             let tile_completed = rx2.recv().unwrap();
             if verbose {
                 println!(
@@ -627,11 +726,11 @@ impl WhiteboxTool for LidarPointDensity {
                         .replace(working_directory, "")
                         .replace(".las", ""),
                     tile + 1,
-                    inputs.len()
+                    num_updates
                 );
             }
             if verbose {
-                progress = (100.0_f64 * tile as f64 / (inputs.len() - 1) as f64) as i32;
+                progress = (100.0_f64 * tile as f64 / (num_updates - 1) as f64) as i32;
                 if progress != old_progress {
                     println!("Progress: {}%", progress);
                     old_progress = progress;
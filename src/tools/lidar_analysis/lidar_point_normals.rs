@@ -0,0 +1,500 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::na::Matrix3;
+use crate::structures::{DistanceMetric, FixedRadiusSearch3D};
+use crate::tools::*;
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool estimates the surface normal vector of each point in a LiDAR point cloud using a
+/// principal component analysis (PCA) of the point's local neighbourhood. The neighbourhood
+/// contains either the *k* nearest neighbours of the point (`--num_neighbours`, default 12) or, if
+/// a search radius (`--radius`) is specified, all of the points within the radius. The normal
+/// vector is the eigenvector of the neighbourhood's covariance matrix that is associated with its
+/// smallest eigenvalue. Normals are oriented upwards, i.e. such that their z components are
+/// non-negative. The tool also calculates the *surface variation* (Pauly et al., 2002), a measure
+/// of the local curvature, as:
+///
+/// > *C* = λ<sub>3</sub> / (λ<sub>1</sub> + λ<sub>2</sub> + λ<sub>3</sub>)
+///
+/// where λ<sub>1</sub> ≥ λ<sub>2</sub> ≥ λ<sub>3</sub> are the eigenvalues. The surface variation
+/// is zero for points on planar surfaces and has a maximum of 1/3 for isotropically scattered
+/// points, e.g. within vegetation.
+///
+/// By default (`--output_type=extra_bytes`), the normal vector components and curvature are stored
+/// as the 32-bit floating-point extra bytes attributes *nx*, *ny*, *nz*, and *curvature* of each
+/// point in the output LAS file, in addition to any extra bytes attributes within the input file.
+/// These attributes can be used by other tools, e.g. `LidarTINGridding` and `LidarIdwInterpolation`
+/// with `--parameter=nz`. Alternatively (`--output_type=colour`), the normal vectors are stored in
+/// the RGB colour fields of the output points, as in the `NormalVectors` tool, with each vector
+/// component rescaled from the range -1 to 1 into the range of the colour channel. This is
+/// convenient for visualizing the normals, but the curvature is not output and the point format
+/// of the output file may be changed to one that includes colour data. Points with fewer than
+/// three neighbours are assigned the NoData value (-9999) in the extra bytes output and neutral
+/// grey in the colour output.
+///
+/// Point normals are a prerequisite for many point cloud analyses, including roof plane
+/// segmentation and surface smoothing.
+///
+/// # Reference
+/// Pauly, M., Gross, M., & Kobbelt, L. P. (2002). Efficient simplification of point-sampled surfaces.
+/// In *Proceedings of IEEE Visualization 2002* (pp. 163-170).
+///
+/// # See Also
+/// `NormalVectors`, `LidarRansacPlanes`, `LidarSegmentation`
+pub struct LidarPointNormals {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarPointNormals {
+    pub fn new() -> LidarPointNormals {
+        // public constructor
+        let name = "LidarPointNormals".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Estimates the normal vector and curvature of each point in a LiDAR point cloud using a local PCA.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Neighbours".to_owned(),
+            flags: vec!["--num_neighbours".to_owned()],
+            description: "Number of nearest neighbours (k) in the local neighbourhood.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("12".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Radius (optional)".to_owned(),
+            flags: vec!["--radius".to_owned()],
+            description: "Optional search radius; if specified, a fixed-radius neighbourhood is used rather than the k nearest neighbours.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Type".to_owned(),
+            flags: vec!["--output_type".to_owned()],
+            description: "Output type; options include 'extra_bytes' and 'colour'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "extra_bytes".to_owned(),
+                "colour".to_owned(),
+            ]),
+            default_value: Some("extra_bytes".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --num_neighbours=16
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --radius=1.5 --output_type=colour", short_exe, name).replace("*", &sep);
+
+        LidarPointNormals {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarPointNormals {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--num_neighbours".to_string(), ParameterConstraint::Min(3.0)),
+            ("--radius".to_string(), ParameterConstraint::GreaterThan(0.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut num_neighbours = 12usize;
+        let mut search_radius = 0f64;
+        let mut output_type = String::from("extra_bytes");
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-num_neighbours" || flag_val == "-num_neighbors" || flag_val == "-k" {
+                num_neighbours = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-radius" {
+                search_radius = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-output_type" {
+                output_type = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let colour_output = if output_type.contains("colo") || output_type.contains("rgb") {
+            true
+        } else if output_type.contains("extra") {
+            false
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unrecognized output type; options include 'extra_bytes' and 'colour'.",
+            ));
+        };
+        if search_radius <= 0f64 && num_neighbours < 3 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The number of neighbours must be at least three.",
+            ));
+        }
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        let start = Instant::now();
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        // build the neighbourhood search structure
+        let use_radius = search_radius > 0f64;
+        let mut frs: FixedRadiusSearch3D<usize> = FixedRadiusSearch3D::new(
+            if use_radius { search_radius } else { 1f64 },
+            DistanceMetric::SquaredEuclidean,
+        );
+        let mut tree = KdTree::new_with_capacity(3, 64);
+        for i in 0..n_points {
+            let p: PointData = input.get_point_info(i);
+            if use_radius {
+                frs.insert(p.x, p.y, p.z, i);
+            } else {
+                tree.add([p.x, p.y, p.z], i).unwrap();
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Binning points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let frs = Arc::new(frs);
+        let tree = Arc::new(tree);
+        let input = Arc::new(input);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let frs = frs.clone();
+            let tree = tree.clone();
+            let input = input.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let mut neighbours: Vec<[f64; 3]> = vec![];
+                for i in (0..n_points).filter(|point_num| point_num % num_procs == tid) {
+                    let p: PointData = input.get_point_info(i);
+                    neighbours.clear();
+                    if use_radius {
+                        for (j, _) in frs.search(p.x, p.y, p.z) {
+                            let p2 = input.get_point_info(j);
+                            neighbours.push([p2.x, p2.y, p2.z]);
+                        }
+                    } else {
+                        for (_, j) in tree
+                            .nearest(&[p.x, p.y, p.z], num_neighbours, &squared_euclidean)
+                            .unwrap()
+                        {
+                            let p2 = input.get_point_info(*j);
+                            neighbours.push([p2.x, p2.y, p2.z]);
+                        }
+                    }
+                    tx.send((i, point_normal(&neighbours))).unwrap();
+                }
+            });
+        }
+
+        let mut normals: Vec<Option<[f64; 4]>> = vec![None; n_points];
+        for i in 0..n_points {
+            let data = rx.recv().unwrap();
+            normals[data.0] = data.1;
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Calculating point normals: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // now output the data
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        if colour_output {
+            let out_pt_format = match input.header.point_format {
+                0 | 2 => 2,              // No GPS data supplied
+                1 | 3 | 4 | 5 => 3,      // GPS data is supplied
+                6..=10 => 3, // This is a 64-bit format and will require LAS 1.4 output support. For now, output point format 3.
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "Unsupport input point record format.",
+                    ))
+                }
+            };
+            output.header.point_format = out_pt_format;
+            for i in 0..n_points {
+                let n = normals[i].unwrap_or([0f64; 4]);
+                let rgb = ColourData {
+                    red: ((1.0 + n[0]) / 2.0 * 255.0) as u16 * 256u16,
+                    green: ((1.0 + n[1]) / 2.0 * 255.0) as u16 * 256u16,
+                    blue: ((1.0 + n[2]) / 2.0 * 255.0) as u16 * 256u16,
+                    nir: 0u16,
+                };
+                let p = input.get_point_info(i);
+                if out_pt_format == 2 {
+                    output.add_point_record(LidarPointRecord::PointRecord2 {
+                        point_data: p,
+                        colour_data: rgb,
+                    });
+                } else {
+                    output.add_point_record(LidarPointRecord::PointRecord3 {
+                        point_data: p,
+                        gps_data: input.get_gps_time(i)?,
+                        colour_data: rgb,
+                    });
+                }
+                output.add_extra_bytes(input.get_extra_bytes(i));
+                if verbose {
+                    progress = (100.0_f64 * i as f64 / num_points) as i32;
+                    if progress != old_progress {
+                        println!("Saving data: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        } else {
+            // The normals are appended to any existing extra bytes attributes.
+            let nodata = -9999f64;
+            let mut descriptors = input.extra_bytes_descriptors.clone();
+            let normals_offset = input.get_extra_bytes_length();
+            let mut new_descriptors = [
+                ExtraBytesDescriptor::new("nx", 9u8, "normal vector x component"),
+                ExtraBytesDescriptor::new("ny", 9u8, "normal vector y component"),
+                ExtraBytesDescriptor::new("nz", 9u8, "normal vector z component"),
+                ExtraBytesDescriptor::new("curvature", 9u8, "surface variation"),
+            ];
+            for d in new_descriptors.iter_mut() {
+                d.set_no_data(nodata);
+                descriptors.push(d.clone());
+            }
+            output.set_extra_bytes_descriptors(descriptors);
+            let mut extra_bytes = vec![0u8; output.get_extra_bytes_length()];
+            for i in 0..n_points {
+                let input_bytes = input.get_extra_bytes(i);
+                extra_bytes[0..input_bytes.len()].copy_from_slice(input_bytes);
+                let values = normals[i].unwrap_or([nodata; 4]);
+                for (a, d) in new_descriptors.iter().enumerate() {
+                    d.set_value(values[a], &mut extra_bytes[normals_offset + 4 * a..]);
+                }
+                output.add_point_record(input.get_record(i));
+                output.add_extra_bytes(&extra_bytes);
+                if verbose {
+                    progress = (100.0_f64 * i as f64 / num_points) as i32;
+                    if progress != old_progress {
+                        println!("Saving data: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Estimates the upward-oriented unit normal vector and the surface variation of a neighbourhood
+/// of points, returned as [nx, ny, nz, curvature], or None if there are fewer than three points.
+fn point_normal(points: &[[f64; 3]]) -> Option<[f64; 4]> {
+    let n = points.len();
+    if n < 3 {
+        return None;
+    }
+    let mut centroid = [0f64; 3];
+    for p in points {
+        for a in 0..3 {
+            centroid[a] += p[a] / n as f64;
+        }
+    }
+    let mut cov = Matrix3::<f64>::zeros();
+    for p in points {
+        let d = [p[0] - centroid[0], p[1] - centroid[1], p[2] - centroid[2]];
+        for r in 0..3 {
+            for c in 0..3 {
+                cov[(r, c)] += d[r] * d[c];
+            }
+        }
+    }
+    let eig = cov.symmetric_eigen();
+    let mut min_index = 0;
+    for a in 1..3 {
+        if eig.eigenvalues[a] < eig.eigenvalues[min_index] {
+            min_index = a;
+        }
+    }
+    let sum_eigenvalues: f64 = eig.eigenvalues.iter().map(|v| v.max(0f64)).sum();
+    let curvature = if sum_eigenvalues > 0f64 {
+        eig.eigenvalues[min_index].max(0f64) / sum_eigenvalues
+    } else {
+        // all of the points are coincident
+        return None;
+    };
+    let v = eig.eigenvectors.column(min_index);
+    let sign = if v[2] < 0f64 { -1f64 } else { 1f64 };
+    Some([sign * v[0], sign * v[1], sign * v[2], curvature])
+}
@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 18/02/2018
-Last Modified: 31/10/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
@@ -42,6 +42,10 @@ use std::thread;
 /// When the input/output parameters are not specified, the tool works on all LAS files contained within 
 /// the working directory.
 /// 
+/// The optional `--min_time` and `--max_time` parameters restrict the summarized points to those with
+/// GPS times within the specified range, which allows individual flightlines, or acquisition windows,
+/// to be compared. These parameters require input files with point formats that include GPS time.
+/// 
 /// **Notes**:
 /// 1. The num_pulses output is actually the number of pulses with at lease one return; specifically it is
 ///    the sum of the early returns (first and only) in a grid cell. In areas of low reflectance, such as
@@ -148,6 +152,24 @@ impl LidarPointStats {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Minimum GPS Time (optional)".to_owned(),
+            flags: vec!["--min_time".to_owned()],
+            description: "Optional minimum GPS time of points to include.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum GPS Time (optional)".to_owned(),
+            flags: vec!["--max_time".to_owned()],
+            description: "Optional maximum GPS time of points to include.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -224,6 +246,8 @@ impl WhiteboxTool for LidarPointStats {
         let mut z_range = false;
         let mut intensity_range = false;
         let mut predominant_class = false;
+        let mut min_time = f64::NEG_INFINITY;
+        let mut max_time = f64::INFINITY;
 
         // read the arguments
         if args.len() == 0 {
@@ -278,9 +302,29 @@ impl WhiteboxTool for LidarPointStats {
                 if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
                     avg_points_per_pulse = true;
                 }
+            } else if flag_val == "-min_time" {
+                min_time = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_time" {
+                max_time = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
             }
         }
 
+        let filter_by_time = min_time.is_finite() || max_time.is_finite();
+        if min_time > max_time {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The minimum GPS time (--min_time) must be less than the maximum GPS time (--max_time).",
+            ));
+        }
+
         let start = Instant::now();
 
         // check to see if all of the outputs are false and if so, set them all the true
@@ -357,6 +401,12 @@ impl WhiteboxTool for LidarPointStats {
                         Ok(lf) => lf,
                         Err(err) => panic!("Error reading file {}: {}", input_file, err),
                     };
+                    if filter_by_time && !input.has_gps_time() {
+                        panic!(
+                            "The file {} does not contain GPS time data, which is required by --min_time and --max_time.",
+                            input_file
+                        );
+                    }
 
                     let mut progress: i32;
                     let mut old_progress: i32 = -1;
@@ -407,6 +457,9 @@ impl WhiteboxTool for LidarPointStats {
                         let (mut row, mut col): (isize, isize);
                         for i in 0..n_points {
                             let p: PointData = input.get_point_info(i);
+                            if !input.is_gps_time_in_range(i, min_time, max_time) {
+                                continue;
+                            }
                             col = (((columns - 1) as f64 * (p.x - west - half_grid_res) / ew_range)
                                 .round()) as isize;
                             row = (((rows - 1) as f64 * (north - half_grid_res - p.y) / ns_range)
@@ -525,6 +578,9 @@ impl WhiteboxTool for LidarPointStats {
                         let (mut row, mut col): (isize, isize);
                         for i in 0..n_points {
                             let p: PointData = input.get_point_info(i);
+                            if !input.is_gps_time_in_range(i, min_time, max_time) {
+                                continue;
+                            }
                             col = (((columns - 1) as f64 * (p.x - west - half_grid_res) / ew_range)
                                 .round()) as isize;
                             row = (((rows - 1) as f64 * (north - half_grid_res - p.y) / ns_range)
@@ -640,6 +696,9 @@ impl WhiteboxTool for LidarPointStats {
                         let (mut row, mut col): (isize, isize);
                         for i in 0..n_points {
                             let p: PointData = input.get_point_info(i);
+                            if !input.is_gps_time_in_range(i, min_time, max_time) {
+                                continue;
+                            }
                             col = (((columns - 1) as f64 * (p.x - west - half_grid_res) / ew_range)
                                 .round()) as isize;
                             row = (((rows - 1) as f64 * (north - half_grid_res - p.y) / ns_range)
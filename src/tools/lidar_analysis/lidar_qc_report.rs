@@ -0,0 +1,1210 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::triangulate;
+use crate::lidar::*;
+use crate::structures::{BoundingBox, Point2D};
+use crate::tools::*;
+use crate::vector::*;
+use serde_json::json;
+use std::env;
+use std::f64;
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::path;
+
+/// This tool evaluates a LiDAR delivery, i.e. a set of LAS tiles, against the checks of the ASPRS
+/// positional accuracy standards and the USGS Lidar Base Specification, and summarizes the results
+/// in a single report. It consolidates several of the quality control (QC) steps that are otherwise
+/// performed separately, using tools such as `LidarInfo`, `LidarPointDensity`, and
+/// `LidarDistanceToSurface`. The requirements of each check are those of the specified USGS quality
+/// level (`--quality_level`; QL0, QL1, QL2, or QL3). The checks include:
+///
+/// - **Aggregate nominal pulse density (ANPD)**: the density of first returns within the data area of
+///   each tile, which must meet the minimum density of the quality level (8 pts/m<sup>2</sup> for QL0
+///   and QL1, 2 pts/m<sup>2</sup> for QL2, and 0.5 pts/m<sup>2</sup> for QL3).
+/// - **Spatial distribution**: the percentage of grid cells, of a size equal to twice the nominal
+///   pulse spacing (NPS) of the quality level, containing at least one first return, which must be
+///   at least 90%.
+/// - **Data voids**: areas without first returns that are enclosed by the data, including the gaps
+///   left by missing tiles, that are larger than (4 &times; NPS)<sup>2</sup>. Water bodies lacking
+///   returns are also reported as voids, and should be reviewed.
+/// - **Classification completeness**: the delivery should not contain points that were never
+///   classified (class 0) or that have reserved class values, and each tile should contain ground
+///   (class 2) points.
+/// - **LAS version and format**: tiles should be in LAS 1.4 format using point data record formats
+///   6 to 10.
+/// - **Non-vegetated vertical accuracy (NVA)**: if checkpoints are provided (`--checkpoints`), as
+///   either a PointZ Shapefile or a LAS file of surveyed points, the ground points within the search
+///   radius (`--search_radius`) of each checkpoint are triangulated and the TIN elevation at the
+///   checkpoint is compared with the surveyed elevation. The RMSE<sub>z</sub> must not exceed that
+///   of the quality level (5 cm for QL0, 10 cm for QL1 and QL2, and 20 cm for QL3). Checkpoints are
+///   assumed to be located in non-vegetated terrain.
+///
+/// The tiles to be evaluated may be specified as a list of files (`--inputs`); if unspecified,
+/// all of the LAS files in the working directory are evaluated. The requirements of the base
+/// specification are stated in metres and are converted into the units of the point coordinates,
+/// which are determined from the coordinate reference system of the first tile. Point clouds in
+/// geographic coordinates must be projected before they are evaluated. Vertical units are assumed
+/// to be the same as the horizontal units.
+///
+/// The report (`--output`) is written in HTML format and a JSON version, suitable for automated
+/// acceptance workflows, is written alongside it with the same file name and a `.json` extension.
+///
+/// # Reference
+/// U.S. Geological Survey (2022). Lidar Base Specification 2022 rev. A. Techniques and Methods,
+/// book 11, chap. B4.
+///
+/// American Society for Photogrammetry and Remote Sensing (2015). ASPRS Positional Accuracy
+/// Standards for Digital Geospatial Data. Photogrammetric Engineering & Remote Sensing, 81(3),
+/// A1-A26.
+///
+/// # See Also
+/// `LidarInfo`, `LidarPointDensity`, `LidarDistanceToSurface`, `LidarTileFootprint`
+pub struct LidarQcReport {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarQcReport {
+    pub fn new() -> LidarQcReport {
+        // public constructor
+        let name = "LidarQcReport".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Evaluates a LiDAR delivery against the ASPRS/USGS base specification checks and creates a QC report."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR Files (optional)".to_owned(),
+            flags: vec!["-i".to_owned(), "--inputs".to_owned()],
+            description: "Input LiDAR files; if unspecified, all LAS files in the working directory are evaluated.".to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Checkpoints File (optional)".to_owned(),
+            flags: vec!["--checkpoints".to_owned()],
+            description: "Optional input surveyed checkpoints, as a PointZ Shapefile or LAS file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Any),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Report File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output HTML report file; a JSON version is written alongside it.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Html),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "USGS Quality Level".to_owned(),
+            flags: vec!["--quality_level".to_owned()],
+            description: "USGS quality level of the delivery; options include 'QL0', 'QL1', 'QL2', and 'QL3'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "QL0".to_owned(),
+                "QL1".to_owned(),
+                "QL2".to_owned(),
+                "QL3".to_owned(),
+            ]),
+            default_value: Some("QL2".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Checkpoint Search Radius (optional)".to_owned(),
+            flags: vec!["--search_radius".to_owned()],
+            description: "Optional radius of the ground points triangulated around each checkpoint; ten times the nominal pulse spacing by default.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -o=qc_report.html --quality_level=QL1
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='tile1.las, tile2.las' --checkpoints=checkpoints.shp -o=qc_report.html", short_exe, name).replace("*", &sep);
+
+        LidarQcReport {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarQcReport {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_files = String::new();
+        let mut checkpoints_file = String::new();
+        let mut output_file = String::new();
+        let mut quality_level = String::from("QL2");
+        let mut search_radius = f64::NAN;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-inputs" {
+                input_files = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-checkpoints" {
+                checkpoints_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-quality_level" {
+                quality_level = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-search_radius" {
+                search_radius = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let start = Instant::now();
+
+        let requirements = QualityLevel::from_str(&quality_level)?;
+
+        output_file = resolve_path(&output_file, working_directory);
+        if !output_file.to_lowercase().ends_with(".html") {
+            output_file.push_str(".html");
+        }
+        let json_file = format!("{}.json", &output_file[..output_file.len() - 5]);
+        checkpoints_file = resolve_path(&checkpoints_file, working_directory);
+
+        let mut inputs = vec![];
+        if input_files.trim().is_empty() {
+            if working_directory.is_empty() || !path::Path::new(&working_directory).is_dir() {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "This tool must be run by specifying either a list of input files or a working directory."));
+            }
+            for entry in fs::read_dir(working_directory)? {
+                let s = entry?
+                    .path()
+                    .into_os_string()
+                    .to_str()
+                    .expect("Error reading path string")
+                    .to_string();
+                if s.to_lowercase().ends_with(".las") {
+                    inputs.push(s);
+                }
+            }
+            inputs.sort();
+        } else {
+            for value in input_files.split([';', ',']) {
+                if !value.trim().is_empty() {
+                    let mut input_file = value.trim().to_owned();
+                    input_file = resolve_path(&input_file, working_directory);
+                    inputs.push(input_file);
+                }
+            }
+        }
+        let num_tiles = inputs.len();
+        if num_tiles == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No input LAS files were found.",
+            ));
+        }
+
+        // the extent of the delivery, from the tile headers
+        let mut bounding_boxes = Vec::with_capacity(num_tiles);
+        for input_file in &inputs {
+            let header = LasHeader::read_las_header(input_file).map_err(|e| {
+                Error::new(
+                    e.kind(),
+                    format!("Error reading file {}: {}", input_file, e),
+                )
+            })?;
+            bounding_boxes.push(BoundingBox::new(
+                header.min_x,
+                header.max_x,
+                header.min_y,
+                header.max_y,
+            ));
+        }
+        let mut extent = bounding_boxes[0];
+        for bb in &bounding_boxes[1..] {
+            extent.expand_to(*bb);
+        }
+
+        // the requirements are stated in metres and are converted into the units of the data
+        let mut first_tile = LasFile::new(&inputs[0], "r")?;
+        let data_units = CoordinateUnits::from_las(&mut first_tile);
+        drop(first_tile);
+        if data_units == CoordinateUnits::Degrees {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The point coordinates are in degrees; the delivery must be projected before it can be evaluated.",
+            ));
+        }
+        if data_units == CoordinateUnits::NotSpecified && verbose {
+            println!("Warning: The units of the point coordinates could not be determined and are assumed to be metres.");
+        }
+        let units_per_metre = 1f64 / data_units.to_meters(0f64);
+        let nps = requirements.nominal_pulse_spacing() * units_per_metre;
+        if search_radius.is_nan() || search_radius <= 0f64 {
+            search_radius = 10f64 * nps;
+        }
+
+        // The coverage grid has cells of twice the nominal pulse spacing, as used by the spatial
+        // distribution check; very large deliveries are evaluated with coarser cells.
+        let max_cells = 250_000_000f64;
+        let mut cell_size = 2f64 * nps;
+        let num_cells = (extent.get_width() / cell_size).ceil() * (extent.get_height() / cell_size).ceil();
+        if num_cells > max_cells {
+            cell_size *= (num_cells / max_cells).sqrt();
+            if verbose {
+                println!(
+                    "Warning: The coverage grid cell size has been increased to {:.3} because of the size of the delivery.",
+                    cell_size
+                );
+            }
+        }
+        let grid = CoverageGrid::new(extent, cell_size);
+        let mut coverage = vec![EMPTY; grid.rows * grid.columns];
+
+        let mut checkpoints = match checkpoints_file.is_empty() {
+            true => vec![],
+            false => read_checkpoints(&checkpoints_file)?,
+        };
+
+        let mut tiles = Vec::with_capacity(num_tiles);
+        let mut class_counts = vec![0u64; 256];
+        let mut class_names = vec![String::new(); 256];
+        for tile in 0..num_tiles {
+            if verbose {
+                println!(
+                    "Evaluating {} ({} of {})...",
+                    file_name(&inputs[tile]),
+                    tile + 1,
+                    num_tiles
+                );
+            }
+            let input = LasFile::new(&inputs[tile], "r")?;
+            let bb = bounding_boxes[tile];
+            let tile_grid = CoverageGrid::new(bb, cell_size);
+            let mut tile_coverage = vec![false; tile_grid.rows * tile_grid.columns];
+            let search_bb = BoundingBox::new(
+                bb.min_x - search_radius,
+                bb.max_x + search_radius,
+                bb.min_y - search_radius,
+                bb.max_y + search_radius,
+            );
+            let nearby_checkpoints: Vec<usize> = (0..checkpoints.len())
+                .filter(|&c| search_bb.is_point_in_box(checkpoints[c].x, checkpoints[c].y))
+                .collect();
+            let radius_sqrd = search_radius * search_radius;
+
+            let mut summary = TileSummary {
+                file_name: file_name(&inputs[tile]),
+                version: format!("{}.{}", input.header.version_major, input.header.version_minor),
+                point_format: input.header.point_format,
+                num_points: input.header.number_of_points,
+                num_first_returns: 0,
+                num_ground: 0,
+                num_unclassified: 0,
+                data_area: 0f64,
+            };
+            let n_points = input.header.number_of_points as usize;
+            for i in 0..n_points {
+                let p: PointData = input[i];
+                if p.withheld() {
+                    continue;
+                }
+                let class = p.classification();
+                if class_counts[class as usize] == 0 {
+                    class_names[class as usize] = p.classification_string();
+                }
+                class_counts[class as usize] += 1;
+                if class == 0 {
+                    summary.num_unclassified += 1;
+                }
+                if class == 2 {
+                    summary.num_ground += 1;
+                    for &c in &nearby_checkpoints {
+                        let (dx, dy) = (p.x - checkpoints[c].x, p.y - checkpoints[c].y);
+                        if dx * dx + dy * dy <= radius_sqrd {
+                            checkpoints[c].ground_points.push([p.x, p.y, p.z]);
+                        }
+                    }
+                }
+                // noise points are excluded from the density and coverage checks
+                if p.is_early_return() && class != 7 && class != 18 {
+                    summary.num_first_returns += 1;
+                    if let Some(cell) = tile_grid.cell(p.x, p.y) {
+                        tile_coverage[cell] = true;
+                    }
+                    if let Some(cell) = grid.cell(p.x, p.y) {
+                        coverage[cell] = OCCUPIED;
+                    }
+                }
+            }
+            summary.data_area =
+                tile_coverage.iter().filter(|v| **v).count() as f64 * cell_size * cell_size;
+            tiles.push(summary);
+        }
+
+        if verbose {
+            println!("Evaluating coverage...");
+        }
+        let voids = find_voids(&grid, &mut coverage);
+        let num_interior_cells = coverage.iter().filter(|v| **v != EXTERIOR).count();
+        let num_occupied_cells = coverage.iter().filter(|v| **v == OCCUPIED).count();
+
+        let mut vertical_errors = vec![];
+        if !checkpoints.is_empty() {
+            if verbose {
+                println!("Assessing vertical accuracy...");
+            }
+            for checkpoint in checkpoints.iter_mut() {
+                checkpoint.lidar_z = tin_elevation(checkpoint.x, checkpoint.y, &checkpoint.ground_points);
+                if let Some(z) = checkpoint.lidar_z {
+                    vertical_errors.push(z - checkpoint.z);
+                }
+                checkpoint.ground_points = vec![];
+            }
+        }
+
+        // evaluate the checks
+        let area_units = match data_units {
+            CoordinateUnits::Feet => "ft<sup>2</sup>",
+            _ => "m<sup>2</sup>",
+        };
+        let min_density = requirements.min_density() / (units_per_metre * units_per_metre);
+        let mut checks = vec![];
+        let total_first_returns: u64 = tiles.iter().map(|t| t.num_first_returns).sum();
+        let total_data_area: f64 = tiles.iter().map(|t| t.data_area).sum();
+        let anpd = if total_data_area > 0f64 {
+            total_first_returns as f64 / total_data_area
+        } else {
+            0f64
+        };
+        let low_density_tiles: Vec<&TileSummary> =
+            tiles.iter().filter(|t| t.density() < min_density).collect();
+        checks.push(QcCheck::new(
+            "Aggregate nominal pulse density",
+            format!("&ge; {:.3} pts/{}", min_density, area_units),
+            format!("{:.3} pts/{} ({} tiles below)", anpd, area_units, low_density_tiles.len()),
+            Some(anpd >= min_density && low_density_tiles.is_empty()),
+        ));
+        let distribution = if num_interior_cells > 0 {
+            100f64 * num_occupied_cells as f64 / num_interior_cells as f64
+        } else {
+            0f64
+        };
+        checks.push(QcCheck::new(
+            "Spatial distribution",
+            format!("&ge; 90% of {:.3} &times; {:.3} cells occupied", cell_size, cell_size),
+            format!("{:.2}%", distribution),
+            Some(distribution >= 90f64),
+        ));
+        let min_void_area = 16f64 * nps * nps;
+        let large_voids: Vec<&Void> = voids
+            .iter()
+            .filter(|v| v.num_cells as f64 * cell_size * cell_size >= min_void_area)
+            .collect();
+        checks.push(QcCheck::new(
+            "Data voids",
+            format!("none &ge; {:.3} {}", min_void_area, area_units),
+            format!("{} voids", large_voids.len()),
+            Some(large_voids.is_empty()),
+        ));
+        let num_unclassified: u64 = tiles.iter().map(|t| t.num_unclassified).sum();
+        checks.push(QcCheck::new(
+            "Points never classified (class 0)",
+            "0".to_string(),
+            format!("{}", num_unclassified),
+            Some(num_unclassified == 0),
+        ));
+        let reserved_classes: Vec<usize> = (0..256)
+            .filter(|&c| class_counts[c] > 0 && (c == 8 || c == 12 || (c >= 23 && c <= 63)))
+            .collect();
+        checks.push(QcCheck::new(
+            "Reserved class values",
+            "none".to_string(),
+            if reserved_classes.is_empty() {
+                "none".to_string()
+            } else {
+                format!("{:?}", reserved_classes)
+            },
+            Some(reserved_classes.is_empty()),
+        ));
+        let tiles_without_ground = tiles.iter().filter(|t| t.num_ground == 0).count();
+        checks.push(QcCheck::new(
+            "Tiles containing ground points (class 2)",
+            format!("{}", num_tiles),
+            format!("{}", num_tiles - tiles_without_ground),
+            Some(tiles_without_ground == 0),
+        ));
+        let nonconforming_formats = tiles.iter().filter(|t| !t.is_conforming_format()).count();
+        checks.push(QcCheck::new(
+            "LAS 1.4, point formats 6-10",
+            format!("{} tiles", num_tiles),
+            format!("{} tiles", num_tiles - nonconforming_formats),
+            Some(nonconforming_formats == 0),
+        ));
+        let max_rmse = requirements.max_rmse_z() * units_per_metre;
+        let accuracy = VerticalAccuracy::new(&vertical_errors);
+        match &accuracy {
+            Some(a) => {
+                checks.push(QcCheck::new(
+                    "NVA RMSE<sub>z</sub>",
+                    format!("&le; {:.3}", max_rmse),
+                    format!("{:.3} ({} of {} checkpoints)", a.rmse, a.n, checkpoints.len()),
+                    Some(a.rmse <= max_rmse),
+                ));
+                checks.push(QcCheck::new(
+                    "NVA at the 95% confidence level",
+                    format!("&le; {:.3}", 1.96 * max_rmse),
+                    format!("{:.3}", a.nva),
+                    Some(a.nva <= 1.96 * max_rmse),
+                ));
+            }
+            None => {
+                checks.push(QcCheck::new(
+                    "NVA RMSE<sub>z</sub>",
+                    format!("&le; {:.3}", max_rmse),
+                    if checkpoints.is_empty() {
+                        "no checkpoints".to_string()
+                    } else {
+                        "no checkpoints within the TIN of ground points".to_string()
+                    },
+                    None,
+                ));
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        let num_failed = checks.iter().filter(|c| c.passed == Some(false)).count();
+        println!("LiDAR QC report ({}, {} tiles):", requirements.name(), num_tiles);
+        for check in &checks {
+            println!(
+                "  {}: {} [{}]",
+                strip_tags(&check.name),
+                strip_tags(&check.value),
+                check.status()
+            );
+        }
+        println!("{} of {} checks failed.", num_failed, checks.len());
+
+        let report = QcReport {
+            quality_level: requirements,
+            inputs: &inputs,
+            checkpoints_file: &checkpoints_file,
+            units: data_units,
+            checks: &checks,
+            tiles: &tiles,
+            min_density: min_density,
+            voids: &large_voids,
+            grid: &grid,
+            class_counts: &class_counts,
+            class_names: &class_names,
+            checkpoints: &checkpoints,
+            accuracy: &accuracy,
+        };
+        report.write_html(&output_file)?;
+        report.write_json(&json_file)?;
+
+        if verbose {
+            println!("Please see {} for the QC report.", output_file);
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The USGS Lidar Base Specification quality levels.
+#[derive(Clone, Copy, PartialEq)]
+enum QualityLevel {
+    QL0,
+    QL1,
+    QL2,
+    QL3,
+}
+
+impl QualityLevel {
+    fn from_str(s: &str) -> Result<QualityLevel, Error> {
+        match s.trim().to_lowercase().replace(" ", "").as_str() {
+            "ql0" | "0" => Ok(QualityLevel::QL0),
+            "ql1" | "1" => Ok(QualityLevel::QL1),
+            "ql2" | "2" => Ok(QualityLevel::QL2),
+            "ql3" | "3" => Ok(QualityLevel::QL3),
+            v => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unrecognized quality level '{}'. Use 'QL0', 'QL1', 'QL2', or 'QL3'.", v),
+            )),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            QualityLevel::QL0 => "QL0",
+            QualityLevel::QL1 => "QL1",
+            QualityLevel::QL2 => "QL2",
+            QualityLevel::QL3 => "QL3",
+        }
+    }
+
+    /// The minimum aggregate nominal pulse density, in points per square metre.
+    fn min_density(&self) -> f64 {
+        match self {
+            QualityLevel::QL0 | QualityLevel::QL1 => 8f64,
+            QualityLevel::QL2 => 2f64,
+            QualityLevel::QL3 => 0.5f64,
+        }
+    }
+
+    /// The nominal pulse spacing corresponding to the minimum density, in metres.
+    fn nominal_pulse_spacing(&self) -> f64 {
+        1f64 / self.min_density().sqrt()
+    }
+
+    /// The maximum non-vegetated RMSEz, in metres.
+    fn max_rmse_z(&self) -> f64 {
+        match self {
+            QualityLevel::QL0 => 0.05f64,
+            QualityLevel::QL1 | QualityLevel::QL2 => 0.1f64,
+            QualityLevel::QL3 => 0.2f64,
+        }
+    }
+}
+
+/// The result of an individual QC check. A check that could not be evaluated has no result.
+struct QcCheck {
+    name: String,
+    requirement: String,
+    value: String,
+    passed: Option<bool>,
+}
+
+impl QcCheck {
+    fn new(name: &str, requirement: String, value: String, passed: Option<bool>) -> QcCheck {
+        QcCheck {
+            name: name.to_string(),
+            requirement: requirement,
+            value: value,
+            passed: passed,
+        }
+    }
+
+    fn status(&self) -> &'static str {
+        match self.passed {
+            Some(true) => "PASS",
+            Some(false) => "FAIL",
+            None => "NOT ASSESSED",
+        }
+    }
+}
+
+/// The statistics of an individual tile.
+struct TileSummary {
+    file_name: String,
+    version: String,
+    point_format: u8,
+    num_points: u64,
+    num_first_returns: u64,
+    num_ground: u64,
+    num_unclassified: u64,
+    /// The area of the coverage grid cells containing first returns.
+    data_area: f64,
+}
+
+impl TileSummary {
+    fn density(&self) -> f64 {
+        if self.data_area > 0f64 {
+            self.num_first_returns as f64 / self.data_area
+        } else {
+            0f64
+        }
+    }
+
+    fn is_conforming_format(&self) -> bool {
+        self.version == "1.4" && self.point_format >= 6 && self.point_format <= 10
+    }
+}
+
+/// A surveyed checkpoint, along with the nearby ground points and the TIN elevation derived from them.
+struct Checkpoint {
+    x: f64,
+    y: f64,
+    z: f64,
+    ground_points: Vec<[f64; 3]>,
+    lidar_z: Option<f64>,
+}
+
+/// Reads the checkpoints from a PointZ/MultiPointZ Shapefile or a LAS file.
+fn read_checkpoints(file_name: &str) -> Result<Vec<Checkpoint>, Error> {
+    let mut checkpoints = vec![];
+    let mut add = |x: f64, y: f64, z: f64| {
+        checkpoints.push(Checkpoint {
+            x: x,
+            y: y,
+            z: z,
+            ground_points: vec![],
+            lidar_z: None,
+        })
+    };
+    if file_name.to_lowercase().ends_with(".las") {
+        let las = LasFile::new(file_name, "r")?;
+        for i in 0..las.header.number_of_points as usize {
+            let p: PointData = las[i];
+            if !p.withheld() {
+                add(p.x, p.y, p.z);
+            }
+        }
+    } else {
+        let shapefile = Shapefile::read(file_name)?;
+        if shapefile.header.shape_type != ShapeType::PointZ
+            && shapefile.header.shape_type != ShapeType::MultiPointZ
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The checkpoints must be of a PointZ or MultiPointZ shape type.",
+            ));
+        }
+        for record_num in 0..shapefile.num_records {
+            let record = shapefile.get_record(record_num);
+            for (j, p) in record.points.iter().enumerate() {
+                add(p.x, p.y, record.z_array[j]);
+            }
+        }
+    }
+    Ok(checkpoints)
+}
+
+/// Returns the elevation at (x, y) of the TIN of a set of points, or None if (x, y) is not within
+/// the TIN.
+fn tin_elevation(x: f64, y: f64, points: &[[f64; 3]]) -> Option<f64> {
+    if points.len() < 3 {
+        return None;
+    }
+    let points2d: Vec<Point2D> = points.iter().map(|p| Point2D::new(p[0], p[1])).collect();
+    let result = triangulate(&points2d)?;
+    for t in 0..result.triangles.len() / 3 {
+        let [a, b, c] = [
+            points[result.triangles[3 * t]],
+            points[result.triangles[3 * t + 1]],
+            points[result.triangles[3 * t + 2]],
+        ];
+        let det = (b[1] - c[1]) * (a[0] - c[0]) + (c[0] - b[0]) * (a[1] - c[1]);
+        if det == 0f64 {
+            continue;
+        }
+        let l1 = ((b[1] - c[1]) * (x - c[0]) + (c[0] - b[0]) * (y - c[1])) / det;
+        let l2 = ((c[1] - a[1]) * (x - c[0]) + (a[0] - c[0]) * (y - c[1])) / det;
+        let l3 = 1f64 - l1 - l2;
+        let eps = -1e-9;
+        if l1 >= eps && l2 >= eps && l3 >= eps {
+            return Some(l1 * a[2] + l2 * b[2] + l3 * c[2]);
+        }
+    }
+    None
+}
+
+/// The vertical accuracy statistics of the checkpoint errors (LiDAR minus surveyed elevation).
+struct VerticalAccuracy {
+    n: usize,
+    mean: f64,
+    std_dev: f64,
+    rmse: f64,
+    nva: f64,
+    percentile_95: f64,
+}
+
+impl VerticalAccuracy {
+    fn new(errors: &[f64]) -> Option<VerticalAccuracy> {
+        let n = errors.len();
+        if n == 0 {
+            return None;
+        }
+        let mean = errors.iter().sum::<f64>() / n as f64;
+        let rmse = (errors.iter().map(|d| d * d).sum::<f64>() / n as f64).sqrt();
+        let std_dev = if n > 1 {
+            (errors.iter().map(|d| (d - mean) * (d - mean)).sum::<f64>() / (n - 1) as f64).sqrt()
+        } else {
+            0f64
+        };
+        let mut abs_errors: Vec<f64> = errors.iter().map(|d| d.abs()).collect();
+        abs_errors.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let rank = ((0.95 * n as f64).ceil() as usize).clamp(1, n);
+        Some(VerticalAccuracy {
+            n: n,
+            mean: mean,
+            std_dev: std_dev,
+            rmse: rmse,
+            nva: 1.96 * rmse,
+            percentile_95: abs_errors[rank - 1],
+        })
+    }
+}
+
+const EMPTY: u8 = 0u8;
+const OCCUPIED: u8 = 1u8;
+const EXTERIOR: u8 = 2u8;
+const VOID: u8 = 3u8;
+
+/// A grid used to record the cells containing first returns.
+struct CoverageGrid {
+    west: f64,
+    north: f64,
+    cell_size: f64,
+    rows: usize,
+    columns: usize,
+}
+
+impl CoverageGrid {
+    fn new(extent: BoundingBox, cell_size: f64) -> CoverageGrid {
+        CoverageGrid {
+            west: extent.min_x,
+            north: extent.max_y,
+            cell_size: cell_size,
+            rows: ((extent.get_height() / cell_size).ceil() as usize).max(1),
+            columns: ((extent.get_width() / cell_size).ceil() as usize).max(1),
+        }
+    }
+
+    /// Returns the index of the cell containing (x, y), if it is within the grid.
+    fn cell(&self, x: f64, y: f64) -> Option<usize> {
+        let row = ((self.north - y) / self.cell_size).floor() as isize;
+        let col = ((x - self.west) / self.cell_size).floor() as isize;
+        // points on the southern and eastern edges belong to the last row and column
+        let row = if row == self.rows as isize { row - 1 } else { row };
+        let col = if col == self.columns as isize { col - 1 } else { col };
+        if row < 0 || col < 0 || row >= self.rows as isize || col >= self.columns as isize {
+            return None;
+        }
+        Some(row as usize * self.columns + col as usize)
+    }
+}
+
+/// A connected region of empty cells that is enclosed by occupied cells.
+struct Void {
+    num_cells: usize,
+    centre_x: f64,
+    centre_y: f64,
+}
+
+/// Finds the voids of the coverage grid. Empty cells connected to the edges of the grid are marked
+/// as exterior; the remaining empty cells are grouped into voids, which are returned in order of
+/// decreasing size.
+fn find_voids(grid: &CoverageGrid, coverage: &mut [u8]) -> Vec<Void> {
+    let (rows, columns) = (grid.rows, grid.columns);
+    let dx = [1isize, 0, -1, 0];
+    let dy = [0isize, 1, 0, -1];
+    let mut stack = vec![];
+    for row in 0..rows {
+        for col in 0..columns {
+            if (row == 0 || col == 0 || row == rows - 1 || col == columns - 1)
+                && coverage[row * columns + col] == EMPTY
+            {
+                coverage[row * columns + col] = EXTERIOR;
+                stack.push((row, col));
+            }
+        }
+    }
+    let fill = |stack: &mut Vec<(usize, usize)>, coverage: &mut [u8], value: u8| -> (usize, f64, f64) {
+        let (mut n, mut sum_row, mut sum_col) = (0usize, 0f64, 0f64);
+        while let Some((row, col)) = stack.pop() {
+            n += 1;
+            sum_row += row as f64;
+            sum_col += col as f64;
+            for k in 0..4 {
+                let (r, c) = (row as isize + dy[k], col as isize + dx[k]);
+                if r >= 0 && c >= 0 && r < rows as isize && c < columns as isize {
+                    let idx = r as usize * columns + c as usize;
+                    if coverage[idx] == EMPTY {
+                        coverage[idx] = value;
+                        stack.push((r as usize, c as usize));
+                    }
+                }
+            }
+        }
+        (n, sum_row, sum_col)
+    };
+    fill(&mut stack, coverage, EXTERIOR);
+
+    let mut voids = vec![];
+    for row in 0..rows {
+        for col in 0..columns {
+            if coverage[row * columns + col] == EMPTY {
+                coverage[row * columns + col] = VOID;
+                stack.push((row, col));
+                let (n, sum_row, sum_col) = fill(&mut stack, coverage, VOID);
+                voids.push(Void {
+                    num_cells: n,
+                    centre_x: grid.west + (sum_col / n as f64 + 0.5) * grid.cell_size,
+                    centre_y: grid.north - (sum_row / n as f64 + 0.5) * grid.cell_size,
+                });
+            }
+        }
+    }
+    voids.sort_by(|a, b| b.num_cells.cmp(&a.num_cells));
+    voids
+}
+
+fn file_name(s: &str) -> String {
+    path::Path::new(s)
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Removes the HTML markup of a report string, for console and JSON output.
+fn strip_tags(s: &str) -> String {
+    let mut ret = String::new();
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => ret.push(c),
+            _ => {}
+        }
+    }
+    ret.replace("&ge;", ">=")
+        .replace("&le;", "<=")
+        .replace("&times;", "x")
+}
+
+/// The contents of the QC report.
+struct QcReport<'a> {
+    quality_level: QualityLevel,
+    inputs: &'a [String],
+    checkpoints_file: &'a str,
+    units: CoordinateUnits,
+    checks: &'a [QcCheck],
+    tiles: &'a [TileSummary],
+    min_density: f64,
+    voids: &'a [&'a Void],
+    grid: &'a CoverageGrid,
+    class_counts: &'a [u64],
+    class_names: &'a [String],
+    checkpoints: &'a [Checkpoint],
+    accuracy: &'a Option<VerticalAccuracy>,
+}
+
+impl<'a> QcReport<'a> {
+    fn write_html(&self, report_file: &str) -> Result<(), Error> {
+        let f = File::create(report_file)?;
+        let mut writer = BufWriter::new(f);
+        writer.write_all(
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">
+<head>
+    <meta content=\"text/html; charset=iso-8859-1\" http-equiv=\"content-type\">
+    <title>LiDAR QC Report</title>
+    <style  type=\"text/css\">
+        h1 { font-size: 14pt; margin-left: 15px; margin-right: 15px; text-align: center; font-family: Helvetica, Verdana, Geneva, Arial, sans-serif; }
+        h2 { font-size: 12pt; margin-left: 15px; margin-right: 15px; font-family: Helvetica, Verdana, Geneva, Arial, sans-serif; }
+        p { font-size: 12pt; font-family: Helvetica, Verdana, Geneva, Arial, sans-serif; margin-left: 15px; margin-right: 15px; }
+        table { font-size: 12pt; font-family: Helvetica, Verdana, Geneva, Arial, sans-serif; border-collapse: collapse; margin-left: 15px; }
+        th { text-align: left; padding: 8px; border-bottom: 1px solid #333333; }
+        td { text-align: left; padding: 8px; }
+        tr:nth-child(1) { border-top: 2px solid #333333; }
+        tr:last-child { border-bottom: 2px solid #333333; }
+        tr:nth-child(even) { background-color: #dddddd; }
+        .numberCell { text-align: right; }
+        .pass { color: #006600; font-weight: bold; }
+        .fail { color: #cc0000; font-weight: bold; }
+    </style>
+</head>
+<body>
+<h1>LiDAR QC Report</h1>\n"
+                .as_bytes(),
+        )?;
+        writer.write_all(
+            format!(
+                "<p><strong>Quality level:</strong> USGS {}<br><strong>Number of tiles:</strong> {}<br><strong>Coordinate units:</strong> {}<br><strong>Checkpoints:</strong> {}</p>\n",
+                self.quality_level.name(),
+                self.inputs.len(),
+                self.units.description(),
+                if self.checkpoints_file.is_empty() { "none".to_string() } else { file_name(self.checkpoints_file) }
+            )
+            .as_bytes(),
+        )?;
+
+        writer.write_all("<h2>Summary of Checks</h2>\n<table>\n<tr><th>Check</th><th>Requirement</th><th>Result</th><th>Status</th></tr>\n".as_bytes())?;
+        for check in self.checks {
+            let class = match check.passed {
+                Some(true) => "pass",
+                Some(false) => "fail",
+                None => "",
+            };
+            writer.write_all(
+                format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td></tr>\n",
+                    check.name,
+                    check.requirement,
+                    check.value,
+                    class,
+                    check.status()
+                )
+                .as_bytes(),
+            )?;
+        }
+        writer.write_all("</table>\n".as_bytes())?;
+
+        writer.write_all("<h2>Tiles</h2>\n<table>\n<tr><th>File</th><th>Version</th><th>Format</th><th>Points</th><th>First Returns</th><th>Density</th><th>Ground Points</th><th>Class 0 Points</th></tr>\n".as_bytes())?;
+        for tile in self.tiles {
+            let density_class = if tile.density() < self.min_density { " fail" } else { "" };
+            writer.write_all(
+                format!(
+                    "<tr><td>{}</td><td>{}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{}</td><td class=\"numberCell{}\">{:.3}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{}</td></tr>\n",
+                    tile.file_name,
+                    tile.version,
+                    tile.point_format,
+                    tile.num_points,
+                    tile.num_first_returns,
+                    density_class,
+                    tile.density(),
+                    tile.num_ground,
+                    tile.num_unclassified
+                )
+                .as_bytes(),
+            )?;
+        }
+        writer.write_all("</table>\n".as_bytes())?;
+
+        writer.write_all("<h2>Classification</h2>\n<table>\n<tr><th>Class</th><th>Description</th><th>Points</th></tr>\n".as_bytes())?;
+        for c in 0..self.class_counts.len() {
+            if self.class_counts[c] > 0 {
+                writer.write_all(
+                    format!(
+                        "<tr><td class=\"numberCell\">{}</td><td>{}</td><td class=\"numberCell\">{}</td></tr>\n",
+                        c, self.class_names[c], self.class_counts[c]
+                    )
+                    .as_bytes(),
+                )?;
+            }
+        }
+        writer.write_all("</table>\n".as_bytes())?;
+
+        if !self.voids.is_empty() {
+            writer.write_all("<h2>Data Voids</h2>\n<table>\n<tr><th>Centre X</th><th>Centre Y</th><th>Area</th></tr>\n".as_bytes())?;
+            for v in self.voids.iter() {
+                writer.write_all(
+                    format!(
+                        "<tr><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{:.3}</td></tr>\n",
+                        v.centre_x,
+                        v.centre_y,
+                        v.num_cells as f64 * self.grid.cell_size * self.grid.cell_size
+                    )
+                    .as_bytes(),
+                )?;
+            }
+            writer.write_all("</table>\n".as_bytes())?;
+        }
+
+        if let Some(a) = self.accuracy {
+            writer.write_all("<h2>Vertical Accuracy</h2>\n<table>\n".as_bytes())?;
+            let rows = vec![
+                ("Number of checkpoints assessed", format!("{}", a.n)),
+                ("Mean error (bias)", format!("{:.4}", a.mean)),
+                ("Standard deviation", format!("{:.4}", a.std_dev)),
+                ("RMSE<sub>z</sub>", format!("{:.4}", a.rmse)),
+                ("NVA (95% confidence; 1.96 &times; RMSE<sub>z</sub>)", format!("{:.4}", a.nva)),
+                ("95th percentile absolute error", format!("{:.4}", a.percentile_95)),
+            ];
+            for (label, value) in rows {
+                writer.write_all(
+                    format!(
+                        "<tr><td>{}</td><td class=\"numberCell\">{}</td></tr>\n",
+                        label, value
+                    )
+                    .as_bytes(),
+                )?;
+            }
+            writer.write_all("</table>\n".as_bytes())?;
+            writer.write_all("<p></p>\n<table>\n<tr><th>X</th><th>Y</th><th>Surveyed Z</th><th>LiDAR Z</th><th>Error</th></tr>\n".as_bytes())?;
+            for cp in self.checkpoints {
+                let (lidar_z, error) = match cp.lidar_z {
+                    Some(z) => (format!("{:.4}", z), format!("{:.4}", z - cp.z)),
+                    None => ("n/a".to_string(), "n/a".to_string()),
+                };
+                writer.write_all(
+                    format!(
+                        "<tr><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{:.3}</td><td class=\"numberCell\">{:.4}</td><td class=\"numberCell\">{}</td><td class=\"numberCell\">{}</td></tr>\n",
+                        cp.x, cp.y, cp.z, lidar_z, error
+                    )
+                    .as_bytes(),
+                )?;
+            }
+            writer.write_all("</table>\n".as_bytes())?;
+        }
+
+        writer.write_all("<p>Notes:<br>1. Densities are of first returns, excluding noise (classes 7 and 18), within the area of the coverage grid cells containing first returns.<br>
+2. Voids are enclosed areas lacking first returns, including missing tiles and water bodies without returns.<br>
+3. Vertical errors are the elevations of the TIN of ground points minus the surveyed checkpoint elevations.</p>\n</body>\n".as_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write_json(&self, report_file: &str) -> Result<(), Error> {
+        let checks: Vec<serde_json::Value> = self
+            .checks
+            .iter()
+            .map(|c| {
+                json!({
+                    "name": strip_tags(&c.name),
+                    "requirement": strip_tags(&c.requirement),
+                    "result": strip_tags(&c.value),
+                    "passed": c.passed,
+                })
+            })
+            .collect();
+        let tiles: Vec<serde_json::Value> = self
+            .tiles
+            .iter()
+            .map(|t| {
+                json!({
+                    "file": t.file_name,
+                    "version": t.version,
+                    "point_format": t.point_format,
+                    "num_points": t.num_points,
+                    "num_first_returns": t.num_first_returns,
+                    "density": t.density(),
+                    "num_ground_points": t.num_ground,
+                    "num_class0_points": t.num_unclassified,
+                })
+            })
+            .collect();
+        let classes: Vec<serde_json::Value> = (0..self.class_counts.len())
+            .filter(|&c| self.class_counts[c] > 0)
+            .map(|c| json!({ "class": c, "description": self.class_names[c], "num_points": self.class_counts[c] }))
+            .collect();
+        let voids: Vec<serde_json::Value> = self
+            .voids
+            .iter()
+            .map(|v| {
+                json!({
+                    "centre_x": v.centre_x,
+                    "centre_y": v.centre_y,
+                    "area": v.num_cells as f64 * self.grid.cell_size * self.grid.cell_size,
+                })
+            })
+            .collect();
+        let accuracy = self.accuracy.as_ref().map(|a| {
+            json!({
+                "num_checkpoints": a.n,
+                "mean_error": a.mean,
+                "std_dev": a.std_dev,
+                "rmse_z": a.rmse,
+                "nva_95": a.nva,
+                "percentile_95": a.percentile_95,
+            })
+        });
+        let report = json!({
+            "quality_level": self.quality_level.name(),
+            "coordinate_units": self.units.description(),
+            "num_tiles": self.inputs.len(),
+            "passed": self.checks.iter().all(|c| c.passed != Some(false)),
+            "checks": checks,
+            "tiles": tiles,
+            "classes": classes,
+            "voids": voids,
+            "vertical_accuracy": accuracy,
+        });
+        let s = serde_json::to_string_pretty(&report)
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
+        fs::write(report_file, s)
+    }
+}
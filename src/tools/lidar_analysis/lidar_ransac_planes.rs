@@ -269,13 +269,8 @@ impl WhiteboxTool for LidarRansacPlanes {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input LAS file...");
@@ -436,6 +431,7 @@ impl WhiteboxTool for LidarRansacPlanes {
             for i in 0..n_points {
                 if is_a_planar_surface[i] {
                     output.add_point_record(input.get_record(i));
+                    output.add_extra_bytes(input.get_extra_bytes(i));
                 } else {
                     num_points_filtered += 1;
                 }
@@ -583,6 +579,7 @@ impl WhiteboxTool for LidarRansacPlanes {
                     }
                 }
                 output.add_point_record(pr2);
+                output.add_extra_bytes(input.get_extra_bytes(point_num));
                 if verbose {
                     progress = (100.0_f64 * point_num as f64 / num_points) as i32;
                     if progress != old_progress {
@@ -0,0 +1,399 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::spatial_ref_system::Projection;
+use crate::tools::*;
+use byteorder::{LittleEndian, WriteBytesExt};
+use num_cpus;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+/// This tool transforms the horizontal coordinates of the points within a LiDAR file from one
+/// coordinate reference system (CRS) into another, e.g. to merge a delivery that spans two UTM
+/// zones into a single zone, or to convert UTM coordinates into a national grid. Point
+/// elevations, and all other point attributes, are unaltered.
+///
+/// The output CRS (`--to`) may be specified using an EPSG code (e.g. `32617` or `EPSG:32617`),
+/// a PROJ string (e.g. `+proj=utm +zone=17 +datum=WGS84` or
+/// `+proj=tmerc +lat_0=49 +lon_0=-2 +k_0=0.9996012717 +x_0=400000 +y_0=-100000 +ellps=airy
+/// +towgs84=446.448,-125.157,542.06,0.15,0.247,0.842,-20.489`), or a WKT string. The input CRS
+/// is read from the GeoKeys or WKT variable length records (VLRs) of the input file, unless it is
+/// specified using the optional `--from` parameter. The Transverse Mercator (including UTM and
+/// Gauss-Kruger), Lambert Conformal Conic, and geographic (longitude/latitude) coordinate systems
+/// are supported.
+///
+/// When the datums of the input and output systems differ, points are transformed using each
+/// datum's seven-parameter (Helmert) transformation to WGS84. These parameters are taken from
+/// the `+towgs84` PROJ parameter or WKT `TOWGS84` element, and are known for common datums that
+/// are equivalent to WGS84 at the metre level (e.g. NAD83, ETRS89, and GDA94) and OSGB 1936. A
+/// warning is issued, and only the map projection is changed, when no transformation is known.
+/// Grid-based datum transformations (e.g. NADCON and NTv2) are not supported.
+///
+/// The header offsets and scale factors of the output file are updated to suit the transformed
+/// coordinates (0.001 for projected systems and 0.0000001 for geographic systems), and the
+/// GeoKeys and WKT VLRs of the input are replaced with ones describing the output CRS.
+///
+/// # See Also
+/// `LidarInfo`, `LidarTile`, `LidarJoin`
+pub struct LidarReproject {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarReproject {
+    pub fn new() -> LidarReproject {
+        // public constructor
+        let name = "LidarReproject".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Transforms the horizontal coordinates of a LiDAR point cloud into a different coordinate reference system.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Coordinate System".to_owned(),
+            flags: vec!["--to".to_owned()],
+            description: "Output coordinate reference system, as an EPSG code, PROJ string, or WKT string.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Coordinate System (optional)".to_owned(),
+            flags: vec!["--from".to_owned()],
+            description: "Optional input coordinate reference system, as an EPSG code, PROJ string, or WKT string. By default, it is read from the input file.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --to=32617
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --from=\"EPSG:26916\" --to=\"+proj=utm +zone=17 +datum=NAD83\"", short_exe, name).replace("*", &sep);
+
+        LidarReproject {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarReproject {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut to_crs = String::new();
+        let mut from_crs = String::new();
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let arg = args[i].replace("\'", "");
+            let cmd = arg.splitn(2, "="); // the CRS strings may themselves contain equals signs
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].replace("\"", "").to_lowercase().replace("--", "-");
+            let value = if keyval {
+                vec[1].to_string()
+            } else if i + 1 < args.len() {
+                args[i + 1].to_string()
+            } else {
+                String::new()
+            };
+            let value = value.trim_matches('"').to_string();
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = value;
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = value;
+            } else if flag_val == "-to" {
+                to_crs = value;
+            } else if flag_val == "-from" {
+                from_crs = value;
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let start = Instant::now();
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if to_crs.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The output coordinate reference system (--to) must be specified.",
+            ));
+        }
+        let target = Projection::parse(&to_crs)?;
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let mut input = LasFile::new(&input_file, "r")?;
+
+        let source = if !from_crs.trim().is_empty() {
+            Projection::parse(&from_crs)?
+        } else {
+            let epsg_code = input.geokeys.find_epsg_code();
+            if epsg_code != 0 && epsg_code != 32767 {
+                Projection::from_epsg(epsg_code)?
+            } else {
+                let wkt = input.get_wkt();
+                if wkt.is_empty() || wkt.starts_with("Unknown") {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "The coordinate reference system of the input file could not be determined. Please specify it using the --from parameter.",
+                    ));
+                }
+                Projection::from_wkt(&wkt)?
+            }
+        };
+
+        if source.datum_differs(&target) && !source.can_transform_datum(&target) {
+            println!(
+                "Warning: No transformation is known between the {} and {} datums. Only the map projection will be changed, which may introduce errors of up to several hundred metres.",
+                source.datum, target.datum
+            );
+        }
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
+        let mut coordinates = vec![(0f64, 0f64); n_points];
+        {
+            let input = Arc::new(input.clone());
+            let source = Arc::new(source.clone());
+            let target = Arc::new(target.clone());
+            let num_procs = num_cpus::get();
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let input = input.clone();
+                let source = source.clone();
+                let target = target.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for i in (0..n_points).filter(|p| p % num_procs == tid) {
+                        let p = input.get_point_info(i);
+                        tx.send((i, source.transform_to(&target, p.x, p.y))).unwrap();
+                    }
+                });
+            }
+            drop(tx);
+
+            let mut progress: usize;
+            let mut old_progress: usize = 1;
+            for n in 0..n_points {
+                let (i, xy) = rx.recv().expect("Error receiving data from thread.");
+                if !xy.0.is_finite() || !xy.1.is_finite() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "A point could not be transformed into the output coordinate system. Please check that the input coordinate system is correct.",
+                    ));
+                }
+                coordinates[i] = xy;
+                if verbose {
+                    progress = (100.0_f64 * n as f64 / num_points) as usize;
+                    if progress != old_progress {
+                        println!("Transforming points: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        output.header.system_id = "REPROJECTION".to_string();
+
+        // replace the coordinate system VLRs with ones describing the output CRS
+        output
+            .vlr_data
+            .retain(|vlr| !(vlr.record_id >= 34_735 && vlr.record_id <= 34_737) && vlr.record_id != 2112);
+        output.header.number_of_vlrs = output.vlr_data.len() as u32;
+        output.add_vlr(geokeys_vlr(&target));
+        let wkt = target.to_wkt();
+        let mut binary_data = wkt.as_bytes().to_vec();
+        binary_data.push(0u8);
+        output.add_vlr(Vlr {
+            reserved: 0u16,
+            user_id: "LASF_Projection".to_string(),
+            record_id: 2112,
+            record_length_after_header: binary_data.len() as u16,
+            description: "OGC Coordinate System WKT".to_string(),
+            binary_data,
+        });
+        output.wkt = wkt;
+
+        if target.is_geographic() {
+            output.header.x_scale_factor = 0.0000001;
+            output.header.y_scale_factor = 0.0000001;
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for i in 0..n_points {
+            let mut pr = input.get_record(i);
+            let p = pr.get_point_data_mut();
+            p.x = coordinates[i].0;
+            p.y = coordinates[i].1;
+            output.add_point_record(pr);
+            output.add_extra_bytes(input.get_extra_bytes(i));
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as usize;
+                if progress != old_progress {
+                    println!("Saving points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a GeoKeys directory VLR describing the projection.
+fn geokeys_vlr(projection: &Projection) -> Vlr {
+    let mut keys = vec![1u16, 1, 0, 0];
+    if projection.is_geographic() {
+        keys.extend(vec![1024, 0, 1, 2]);
+        keys.extend(vec![2048, 0, 1, projection.epsg_code.unwrap_or(32767)]);
+    } else {
+        keys.extend(vec![1024, 0, 1, 1]);
+        keys.extend(vec![3072, 0, 1, projection.epsg_code.unwrap_or(32767)]);
+        let unit_code = if (projection.linear_unit - 0.3048).abs() < 1e-9 {
+            9002
+        } else if (projection.linear_unit - 1200.0 / 3937.0).abs() < 1e-9 {
+            9003
+        } else {
+            9001
+        };
+        keys.extend(vec![3076, 0, 1, unit_code]);
+    }
+    keys[3] = (keys.len() / 4 - 1) as u16;
+    let mut binary_data = vec![];
+    for k in keys {
+        binary_data.write_u16::<LittleEndian>(k).unwrap();
+    }
+    Vlr {
+        reserved: 0u16,
+        user_id: "LASF_Projection".to_string(),
+        record_id: 34_735,
+        record_length_after_header: binary_data.len() as u16,
+        description: "GeoTiff Projection Keys".to_string(),
+        binary_data,
+    }
+}
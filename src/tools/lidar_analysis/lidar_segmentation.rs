@@ -251,13 +251,8 @@ impl WhiteboxTool for LidarSegmentation {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input LAS file...");
@@ -450,6 +445,7 @@ impl WhiteboxTool for LidarSegmentation {
                     colour_data: rgb,
                 };
                 output.add_point_record(lpr);
+                output.add_extra_bytes(input.get_extra_bytes(point_num));
             }
             if verbose {
                 progress = (100.0_f64 * point_num as f64 / num_points) as i32;
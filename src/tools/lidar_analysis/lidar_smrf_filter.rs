@@ -0,0 +1,540 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use super::lidar_utils::fill_gaps;
+use crate::lidar::*;
+use crate::tools::*;
+use std::collections::VecDeque;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool classifies the ground points within a LiDAR point cloud using the Simple Morphological
+/// Filter (SMRF) of Pingel et al. (2013). SMRF is a progressive morphological filter that performs
+/// well in areas of steep terrain and dense vegetation, where slope-based filters, such as
+/// `LidarGroundPointFilter`, tend to either remove ground points on steep slopes or retain
+/// vegetation points. The algorithm proceeds as follows:
+///
+/// 1. A minimum surface is created by gridding the lowest point elevation within each grid cell of
+///    size `--resolution`. Empty cells are filled by interpolation.
+/// 2. A series of morphological openings, using disk-shaped structuring elements of increasing
+///    radius up to the maximum window size (`--max_window`), is applied to the minimum surface. Cells
+///    that are lowered by an opening by more than the slope threshold (`--slope`, as rise over run)
+///    multiplied by the window radius are flagged as non-ground objects.
+/// 3. A provisional ground surface is created by removing the object cells from the minimum surface
+///    and filling the resulting gaps by interpolation.
+/// 4. Each point is classified as ground if its vertical distance to the provisional surface is no
+///    greater than the elevation threshold (`--elev_threshold`) plus the elevation scaling factor
+///    (`--elev_scaling`) multiplied by the local gradient of the provisional surface.
+///
+/// The maximum window size should be set to approximately the size of the largest non-ground
+/// object, e.g. the largest building footprint, in the scene. Ground points are assigned class 2
+/// and non-ground points are assigned class 1 (unclassified) in the output file. Points that are
+/// classified as noise (classes 7 and 18) or are withheld are excluded from the analysis and
+/// their classes are left unaltered. Low noise should therefore be classified, e.g. using
+/// `LidarOutliers`, prior to running this tool.
+///
+/// # Reference
+/// Pingel, T. J., Clarke, K. C., & McBride, W. A. (2013). An improved simple morphological filter for
+/// the terrain classification of airborne LIDAR data. *ISPRS Journal of Photogrammetry and Remote
+/// Sensing*, 77, 21-30.
+///
+/// # See Also
+/// `LidarGroundPointFilter`, `LidarMccFilter`, `LidarOutliers`
+pub struct LidarSmrfFilter {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarSmrfFilter {
+    pub fn new() -> LidarSmrfFilter {
+        // public constructor
+        let name = "LidarSmrfFilter".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Identifies ground points within LiDAR point clouds using the Simple Morphological Filter (SMRF)."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Grid Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Cell size of the minimum surface grid.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Slope Threshold".to_owned(),
+            flags: vec!["--slope".to_owned()],
+            description: "Slope threshold (rise over run) used to identify non-ground objects."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.15".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Window Size".to_owned(),
+            flags: vec!["--max_window".to_owned()],
+            description: "Maximum window size (diameter), in xy units, which should approximate the size of the largest non-ground object.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("18.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Elevation Threshold".to_owned(),
+            flags: vec!["--elev_threshold".to_owned()],
+            description: "Maximum vertical distance between a ground point and the provisional ground surface.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Elevation Scaling Factor".to_owned(),
+            flags: vec!["--elev_scaling".to_owned()],
+            description: "Factor by which the local gradient of the provisional surface increases the elevation threshold.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.25".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=\"input.las\" -o=\"output.las\" --resolution=1.0 --slope=0.15 --max_window=18.0 --elev_threshold=0.5 --elev_scaling=1.25", short_exe, name).replace("*", &sep);
+
+        LidarSmrfFilter {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarSmrfFilter {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--resolution".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--slope".to_string(), ParameterConstraint::Min(0.0)),
+            ("--max_window".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--elev_threshold".to_string(), ParameterConstraint::Min(0.0)),
+            ("--elev_scaling".to_string(), ParameterConstraint::Min(0.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file: String = "".to_string();
+        let mut output_file: String = "".to_string();
+        let mut grid_res = 1f64;
+        let mut slope_threshold = 0.15f64;
+        let mut max_window = 18f64;
+        let mut elev_threshold = 0.5f64;
+        let mut elev_scaling = 1.25f64;
+        let ground_class_value = 2u8;
+        let otp_class_value = 1u8;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution" || flag_val == "-cell_size" {
+                grid_res = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-slope" {
+                slope_threshold = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_window" {
+                max_window = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-elev_threshold" {
+                elev_threshold = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-elev_scaling" {
+                elev_scaling = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if grid_res <= 0f64 || max_window <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The grid resolution and maximum window size must be greater than zero.",
+            ));
+        }
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        let start = Instant::now();
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        // Create the minimum surface
+        let west = input.header.min_x;
+        let north = input.header.max_y;
+        let rows = (((north - input.header.min_y) / grid_res).ceil() as isize).max(1);
+        let columns = (((input.header.max_x - west) / grid_res).ceil() as isize).max(1);
+        let num_cells = (rows * columns) as usize;
+        let mut z_min = vec![f64::NAN; num_cells];
+        let mut is_included = vec![false; n_points];
+        let (mut row, mut col): (isize, isize);
+        let mut idx: usize;
+        for i in 0..n_points {
+            let p: PointData = input.get_point_info(i);
+            if !p.withheld() && !p.is_classified_noise() {
+                is_included[i] = true;
+                col = (((p.x - west) / grid_res).floor() as isize).max(0).min(columns - 1);
+                row = (((north - p.y) / grid_res).floor() as isize).max(0).min(rows - 1);
+                idx = (row * columns + col) as usize;
+                if z_min[idx].is_nan() || p.z < z_min[idx] {
+                    z_min[idx] = p.z;
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Creating minimum surface: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        if z_min.iter().all(|z| z.is_nan()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file does not contain any points that can be classified.",
+            ));
+        }
+        let z_min = fill_gaps(&z_min, rows, columns);
+
+        // Progressively open the minimum surface with increasing window radii, flagging cells
+        // that drop by more than the slope threshold allows as non-ground objects.
+        let max_radius = ((max_window / 2f64) / grid_res).ceil().max(1f64) as usize;
+        let mut is_object = vec![false; num_cells];
+        let mut last_surface = z_min.clone();
+        for radius in 1..=max_radius {
+            let eroded = morphological_filter(&z_min, rows, columns, radius, true);
+            let opened = morphological_filter(&eroded, rows, columns, radius, false);
+            let threshold = slope_threshold * radius as f64 * grid_res;
+            for k in 0..num_cells {
+                if last_surface[k] - opened[k] > threshold {
+                    is_object[k] = true;
+                }
+            }
+            last_surface = opened;
+            if verbose {
+                progress = (100.0_f64 * radius as f64 / max_radius as f64) as i32;
+                if progress != old_progress {
+                    println!("Morphological filtering: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        drop(last_surface);
+
+        // Create the provisional ground surface and its gradient.
+        let provisional: Vec<f64> = (0..num_cells)
+            .map(|k| if is_object[k] { f64::NAN } else { z_min[k] })
+            .collect();
+        let dem = fill_gaps(&provisional, rows, columns);
+        let mut gradient = vec![0f64; num_cells];
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = |r: isize, c: isize| -> f64 {
+                    dem[(r.max(0).min(rows - 1) * columns + c.max(0).min(columns - 1)) as usize]
+                };
+                let dx = (z(row, col + 1) - z(row, col - 1)) / (2f64 * grid_res);
+                let dy = (z(row - 1, col) - z(row + 1, col)) / (2f64 * grid_res);
+                gradient[(row * columns + col) as usize] = (dx * dx + dy * dy).sqrt();
+            }
+        }
+
+        // Classify the points and output the data
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        let mut num_ground = 0usize;
+        let half_res = grid_res / 2f64;
+        for i in 0..n_points {
+            let mut pr = input.get_record(i);
+            if is_included[i] {
+                let p = input.get_point_info(i);
+                // bilinear interpolation between the cell centres of the provisional surface
+                let fc = ((p.x - west - half_res) / grid_res)
+                    .max(0f64)
+                    .min((columns - 1) as f64);
+                let fr = ((north - half_res - p.y) / grid_res)
+                    .max(0f64)
+                    .min((rows - 1) as f64);
+                let (c0, r0) = (fc.floor() as isize, fr.floor() as isize);
+                let (c1, r1) = ((c0 + 1).min(columns - 1), (r0 + 1).min(rows - 1));
+                let (tx, ty) = (fc - c0 as f64, fr - r0 as f64);
+                let z00 = dem[(r0 * columns + c0) as usize];
+                let z01 = dem[(r0 * columns + c1) as usize];
+                let z10 = dem[(r1 * columns + c0) as usize];
+                let z11 = dem[(r1 * columns + c1) as usize];
+                let surface_z = (z00 * (1f64 - tx) + z01 * tx) * (1f64 - ty)
+                    + (z10 * (1f64 - tx) + z11 * tx) * ty;
+                let nearest = (fr.round() as isize * columns + fc.round() as isize) as usize;
+                let threshold = elev_threshold + elev_scaling * gradient[nearest];
+                if (p.z - surface_z).abs() <= threshold {
+                    pr.get_point_data_mut()
+                        .set_classification(ground_class_value);
+                    num_ground += 1;
+                } else {
+                    pr.get_point_data_mut().set_classification(otp_class_value);
+                }
+            }
+            output.add_point_record(pr);
+            output.add_extra_bytes(input.get_extra_bytes(i));
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Classifying points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "Number of ground points: {} ({:.1}%)",
+                num_ground,
+                100f64 * num_ground as f64 / n_points.max(1) as f64
+            );
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Performs a grey-scale erosion (`minimum == true`) or dilation of a grid using a disk-shaped
+/// structuring element of the specified radius, in cells. The disk is decomposed into horizontal
+/// runs, each of which is evaluated using a running extreme along the rows.
+fn morphological_filter(
+    grid: &[f64],
+    rows: isize,
+    columns: isize,
+    radius: usize,
+    minimum: bool,
+) -> Vec<f64> {
+    let r = radius as isize;
+    let better = |a: f64, b: f64| if minimum { a < b } else { a > b };
+    let mut output = grid.to_vec();
+    let mut half_widths: Vec<(isize, usize)> = (-r..=r)
+        .map(|dy| (dy, ((r * r - dy * dy) as f64).sqrt().floor() as usize))
+        .collect();
+    half_widths.sort_by_key(|a| a.1);
+    let mut running = vec![0f64; grid.len()];
+    let mut k = 0;
+    while k < half_widths.len() {
+        let w = half_widths[k].1;
+        for row in 0..rows {
+            let start = (row * columns) as usize;
+            running_extreme(
+                &grid[start..start + columns as usize],
+                w,
+                minimum,
+                &mut running[start..start + columns as usize],
+            );
+        }
+        while k < half_widths.len() && half_widths[k].1 == w {
+            let dy = half_widths[k].0;
+            for row in 0..rows {
+                let src_row = row + dy;
+                if src_row >= 0 && src_row < rows {
+                    for col in 0..columns {
+                        let v = running[(src_row * columns + col) as usize];
+                        let idx = (row * columns + col) as usize;
+                        if better(v, output[idx]) {
+                            output[idx] = v;
+                        }
+                    }
+                }
+            }
+            k += 1;
+        }
+    }
+    output
+}
+
+/// Calculates the minimum (or maximum) of the values within a half-width `w` of each position.
+fn running_extreme(values: &[f64], w: usize, minimum: bool, output: &mut [f64]) {
+    let better = |a: f64, b: f64| if minimum { a <= b } else { a >= b };
+    let n = values.len();
+    let mut deque: VecDeque<usize> = VecDeque::new();
+    let mut next = 0;
+    for i in 0..n {
+        let upper = (i + w).min(n - 1);
+        while next <= upper {
+            while let Some(&back) = deque.back() {
+                if better(values[next], values[back]) {
+                    deque.pop_back();
+                } else {
+                    break;
+                }
+            }
+            deque.push_back(next);
+            next += 1;
+        }
+        while let Some(&front) = deque.front() {
+            if front + w < i {
+                deque.pop_front();
+            } else {
+                break;
+            }
+        }
+        output[i] = values[*deque.front().unwrap()];
+    }
+}
@@ -0,0 +1,413 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::tools::*;
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool performs a statistical outlier removal (SOR) filtering of a LiDAR point cloud (Rusu et
+/// al., 2008). For each point, the mean three-dimensional distance to its *k* nearest neighbours
+/// (`--num_neighbours`) is calculated. Assuming that these mean distances are approximately normally
+/// distributed, points with a mean distance greater than:
+///
+/// > *threshold* = *μ* + *n* *σ*
+///
+/// where *μ* and *σ* are the mean and standard deviation of the mean distances of all points and *n*
+/// is a user-specified multiplier (`--std_dev_mult`), are considered to be outliers. Unlike
+/// `LidarRemoveOutliers`, which compares the elevation of each point with those of its neighbours
+/// within a vertical column, the SOR filter identifies points that are isolated in three
+/// dimensions. It is therefore effective at finding the sparse, mid-air blunders that are caused by
+/// birds, atmospheric particles, and multi-path returns, which may have elevations similar to those
+/// of nearby vegetation or buildings.
+///
+/// By default, outlier points are removed from the output file. Alternatively, if the `--classify`
+/// flag is specified, outlier points are assigned to the low noise (class 7) or high noise (class
+/// 18) classes, depending on whether they lie below or above the mean elevation of their
+/// neighbours, and all points are output. Points that are already classified as noise, or that are
+/// withheld, are excluded from the neighbourhood calculations; these points are removed from the
+/// output when filtering and are unchanged when classifying.
+///
+/// Smaller values of *n* and *k* result in more aggressive filtering. Values of *k* between 6 and 20
+/// and values of *n* between 1.0 and 3.0 are typical.
+///
+/// # Reference
+/// Rusu, R. B., Marton, Z. C., Blodow, N., Dolha, M., & Beetz, M. (2008). Towards 3D point cloud based
+/// object maps for household environments. *Robotics and Autonomous Systems*, 56(11), 927-941.
+///
+/// # See Also
+/// `LidarRemoveOutliers`, `FilterLidarClasses`, `LidarElevationSlice`
+pub struct LidarSorFilter {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarSorFilter {
+    pub fn new() -> LidarSorFilter {
+        // public constructor
+        let name = "LidarSorFilter".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Removes or classifies isolated outlier points in a LiDAR point cloud using a statistical outlier removal (SOR) filter.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Neighbours".to_owned(),
+            flags: vec!["--num_neighbours".to_owned()],
+            description: "Number of nearest neighbours (k) used to calculate the mean distance of each point.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("8".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Standard Deviation Multiplier".to_owned(),
+            flags: vec!["--std_dev_mult".to_owned()],
+            description: "Number of standard deviations (n) above the mean of the mean distances used as the outlier threshold.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Classify Points".to_owned(),
+            flags: vec!["--classify".to_owned()],
+            description: "Classify outlier points as noise (class 7 or 18) rather than removing them.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --num_neighbours=10 --std_dev_mult=2.5
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --classify", short_exe, name).replace("*", &sep);
+
+        LidarSorFilter {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarSorFilter {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--num_neighbours".to_string(), ParameterConstraint::Min(1.0)),
+            ("--std_dev_mult".to_string(), ParameterConstraint::Min(0.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut num_neighbours = 8usize;
+        let mut std_dev_mult = 2f64;
+        let mut classify = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-num_neighbours" || flag_val == "-num_neighbors" || flag_val == "-k" {
+                num_neighbours = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-std_dev_mult" {
+                std_dev_mult = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-classify" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    classify = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if num_neighbours < 1 {
+            num_neighbours = 1;
+        }
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        let start = Instant::now();
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        let mut tree = KdTree::new_with_capacity(3, 64);
+        let mut num_valid_points = 0usize;
+        for i in 0..n_points {
+            let p: PointData = input.get_point_info(i);
+            if !p.is_classified_noise() && !p.withheld() {
+                tree.add([p.x, p.y, p.z], i).unwrap();
+                num_valid_points += 1;
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Adding points to search tree: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        if num_valid_points <= num_neighbours {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file does not contain enough points for the specified number of neighbours.",
+            ));
+        }
+
+        // calculate the mean distance of each point to its k nearest neighbours
+        let tree = Arc::new(tree);
+        let input = Arc::new(input);
+        let num_procs = num_cpus::get();
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let tree = tree.clone();
+            let input = input.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for i in (0..n_points).filter(|point_num| point_num % num_procs == tid) {
+                    let p: PointData = input.get_point_info(i);
+                    if p.is_classified_noise() || p.withheld() {
+                        tx.send((i, None)).unwrap();
+                        continue;
+                    }
+                    // the point itself is the nearest point in the tree
+                    let ret = tree
+                        .nearest(&[p.x, p.y, p.z], num_neighbours + 1, &squared_euclidean)
+                        .unwrap();
+                    let mut sum_dist = 0f64;
+                    let mut sum_z = 0f64;
+                    let mut n = 0usize;
+                    for (dist, j) in ret {
+                        if *j != i && n < num_neighbours {
+                            sum_dist += dist.sqrt();
+                            sum_z += input.get_point_info(*j).z;
+                            n += 1;
+                        }
+                    }
+                    tx.send((i, Some((sum_dist / n as f64, p.z - sum_z / n as f64))))
+                        .unwrap();
+                }
+            });
+        }
+
+        let mut mean_dists: Vec<Option<(f64, f64)>> = vec![None; n_points];
+        for i in 0..n_points {
+            let data = rx.recv().unwrap();
+            mean_dists[data.0] = data.1;
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Calculating neighbour distances: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let mut sum = 0f64;
+        let mut sum_sqr = 0f64;
+        for (d, _) in mean_dists.iter().flatten() {
+            sum += d;
+            sum_sqr += d * d;
+        }
+        let n = num_valid_points as f64;
+        let mean = sum / n;
+        let std_dev = (sum_sqr / n - mean * mean).max(0f64).sqrt();
+        let threshold = mean + std_dev_mult * std_dev;
+        if verbose {
+            println!("Mean neighbour distance: {:.4}", mean);
+            println!("Std. dev. of neighbour distance: {:.4}", std_dev);
+            println!("Outlier threshold: {:.4}", threshold);
+        }
+
+        // now output the data
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        output.header.system_id = "EXTRACTION".to_string();
+        let mut num_outliers = 0;
+        for i in 0..n_points {
+            let is_outlier = match mean_dists[i] {
+                Some((d, _)) => d > threshold,
+                None => false,
+            };
+            if is_outlier {
+                num_outliers += 1;
+            }
+            if classify {
+                let mut pr = input.get_record(i);
+                if is_outlier {
+                    let (_, z_residual) = mean_dists[i].unwrap();
+                    let class_val = if z_residual > 0f64 { 18u8 } else { 7u8 };
+                    pr.get_point_data_mut().set_classification(class_val);
+                }
+                output.add_point_record(pr);
+                output.add_extra_bytes(input.get_extra_bytes(i));
+            } else if mean_dists[i].is_some() && !is_outlier {
+                output.add_point_record(input.get_record(i));
+                output.add_extra_bytes(input.get_extra_bytes(i));
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Saving data: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if verbose {
+            println!(
+                "Number of outlier points {}: {}",
+                if classify { "classified" } else { "removed" },
+                num_outliers
+            );
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::tools::*;
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool splits a LiDAR (LAS) file (`--input`) into several output files in a single pass
+/// through the points, such that return-specific or class-specific processing (e.g. gridding a
+/// first-return surface) does not require the repeated filtering of the full point cloud. The
+/// splitting criterion (`--criterion`) may be either:
+///
+/// - 'returns': points are split into four return categories, written to files with the suffixes
+///   `_only` (single returns, i.e. the only return of a pulse), `_first` (the first of several
+///   returns), `_intermediate`, and `_last` (the last of several returns). Notice that a single
+///   return is both the first and the last return of its pulse; use `LidarJoin` to combine the
+///   `_only` file with the `_first` or `_last` files if all first or last returns are required.
+/// - 'class': points are split by classification value, written to files with the suffix
+///   `_class` followed by the class value, e.g. `_class2` for ground points.
+///
+/// The output files are named by appending the suffix to the name of the input file, and are saved
+/// to the output directory (`--outdir`), which is the directory of the input file by default.
+/// Categories that do not contain any points do not produce an output file. Each output file
+/// retains the point format, header properties, variable length records (including the coordinate
+/// reference system), and extra bytes of the input file.
+///
+/// # See Also
+/// `FilterLidarClasses`, `LidarJoin`, `LidarTile`, `LidarInfo`
+pub struct LidarSplit {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarSplit {
+    pub fn new() -> LidarSplit {
+        // public constructor
+        let name = "LidarSplit".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Splits a LiDAR file into one file per return category or per class value.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Splitting Criterion".to_owned(),
+            flags: vec!["--criterion".to_owned()],
+            description: "Splitting criterion; options are 'returns' and 'class'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "returns".to_owned(),
+                "class".to_owned(),
+            ]),
+            default_value: Some("returns".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Directory (optional)".to_owned(),
+            flags: vec!["--outdir".to_owned()],
+            description: "Output directory; the directory of the input file is used by default.".to_owned(),
+            parameter_type: ParameterType::Directory,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las --criterion=returns
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las --criterion=class --outdir='*path*to*output*'", short_exe, name).replace("*", &sep);
+
+        LidarSplit {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarSplit {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut criterion = "returns".to_string();
+        let mut output_directory = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-criterion" {
+                criterion = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-outdir" {
+                output_directory = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+
+        let split_by_class = match criterion.as_str() {
+            "returns" | "return" => false,
+            "class" | "classes" | "classification" => true,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The --criterion parameter must be either 'returns' or 'class'.",
+                ))
+            }
+        };
+
+        let input_path = path::Path::new(&input_file);
+        if output_directory.is_empty() {
+            output_directory = input_path
+                .parent()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+        } else {
+            output_directory = resolve_path(&output_directory, working_directory);
+        }
+        if !output_directory.is_empty()
+            && !output_directory.ends_with(&sep)
+            && !output_directory.ends_with("/")
+        {
+            output_directory.push_str(&sep);
+        }
+        let file_stem = input_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = LasFile::new(&input_file, "r")?;
+
+        let start = Instant::now();
+
+        // outputs are keyed by category, in the order 'only', 'first', 'intermediate', 'last' for
+        // return categories, and by class value otherwise
+        let return_suffixes = ["only", "first", "intermediate", "last"];
+        let mut outputs: BTreeMap<u8, LasFile> = BTreeMap::new();
+        let n_points = input.header.number_of_points as usize;
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            let key = if split_by_class {
+                p.classification()
+            } else {
+                let (rn, nr) = (p.return_number(), p.number_of_returns());
+                if nr <= 1 {
+                    0u8
+                } else if rn <= 1 {
+                    1u8
+                } else if rn >= nr {
+                    3u8
+                } else {
+                    2u8
+                }
+            };
+            let output = outputs.entry(key).or_insert_with(|| {
+                let suffix = if split_by_class {
+                    format!("class{}", key)
+                } else {
+                    return_suffixes[key as usize].to_string()
+                };
+                let file_name = format!("{}{}_{}.las", output_directory, file_stem, suffix);
+                LasFile::initialize_using_file(&file_name, &input)
+            });
+            output.add_point_record(input.get_record(i));
+            output.add_extra_bytes(input.get_extra_bytes(i));
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / n_points as f64) as usize;
+                if progress != old_progress {
+                    println!("Splitting points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if outputs.is_empty() {
+            println!("Warning: The input file does not contain any points. No output files have been created.");
+        }
+
+        let num_outputs = outputs.len();
+        for (i, output) in outputs.values_mut().enumerate() {
+            if verbose {
+                println!(
+                    "Writing {} ({} points; {} of {})...",
+                    output.get_short_filename(),
+                    output.header.number_of_points,
+                    i + 1,
+                    num_outputs
+                );
+            }
+            let _ = match output.write() {
+                Ok(_) => {}
+                Err(e) => println!("error while writing: {:?}", e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
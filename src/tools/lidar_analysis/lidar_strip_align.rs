@@ -0,0 +1,759 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::na::{DMatrix, DVector, Matrix3, Vector3};
+use crate::tools::*;
+use std::collections::HashMap;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool can be used to remove systematic elevation offsets between the overlapping
+/// flightlines (strips) of a LiDAR point cloud, a process often referred to as strip adjustment.
+/// Unresolved errors in the boresight calibration and the GNSS/IMU trajectory of an airborne survey
+/// commonly result in the points of adjacent strips being offset by several centimetres, which
+/// introduces artifacts into derived products such as digital terrain models (DTMs).
+///
+/// The input point cloud (`--input`) is divided into a grid of cells of a specified size
+/// (`--resolution`). Within each cell, a plane is fitted to the points of each strip using least
+/// squares. Cells that do not represent smooth surfaces, i.e. where the root-mean-square (RMS)
+/// residual of a strip's plane fit exceeds the roughness threshold (`--max_roughness`) or where the
+/// slope of the plane exceeds the slope threshold (`--max_slope`, in degrees), are ignored, as are
+/// strips with fewer than a minimum number of points in a cell (`--min_points`). This excludes
+/// vegetation, building edges, and other areas where the differences between strips are not a
+/// reliable measure of their misalignment. The differences between the elevations of the planes of
+/// each pair of strips in a cell are then used as observations in a least-squares adjustment that
+/// estimates a correction for each strip. Observations with residuals greater than three times the
+/// RMS residual are removed and the adjustment is repeated. Three correction models (`--model`) are
+/// supported:
+///
+/// - **offset**: A vertical offset is estimated for each strip (the default).
+/// - **plane**: A planar (first-order polynomial) vertical correction is estimated for each strip,
+///   which also removes the tilts that result from roll and pitch calibration errors.
+/// - **translation**: A rigid three-dimensional translation (*dx*, *dy*, *dz*) is estimated for each
+///   strip. Horizontal offsets are estimated from the elevation differences on sloping surfaces and
+///   therefore require that the overlap areas contain surfaces with a variety of slope aspects. A
+///   larger `--max_slope` value is generally appropriate when using this model.
+///
+/// Because strip adjustment only improves the relative alignment of the strips, the corrections are
+/// constrained to have a mean of zero, i.e. the overall position of the point cloud is unchanged.
+/// Strips that do not overlap any other strip are not modified.
+///
+/// Strips are identified using the point source ID of each point. Alternatively, if the
+/// `--use_gps_time` flag is specified, the points are sorted by GPS time and a new strip is started
+/// wherever two consecutive times differ by more than the time threshold (`--time_threshold`). The
+/// estimated corrections and the RMS of the inter-strip differences before and after the adjustment
+/// are reported, and the corrected point cloud is saved to the output file (`--output`).
+///
+/// # See Also
+/// `FlightlineOverlap`, `ClassifyOverlapPoints`, `LidarNormalizeByDem`
+pub struct LidarStripAlign {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarStripAlign {
+    pub fn new() -> LidarStripAlign {
+        // public constructor
+        let name = "LidarStripAlign".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Estimates and applies per-flightline corrections that minimize the elevation differences between overlapping LiDAR strips.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Correction Model".to_owned(),
+            flags: vec!["--model".to_owned()],
+            description: "Per-strip correction model; options are 'offset', 'plane', and 'translation'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "offset".to_owned(),
+                "plane".to_owned(),
+                "translation".to_owned(),
+            ]),
+            default_value: Some("offset".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Cell Size".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Size of the grid cells within which strip surfaces are compared.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Surface Roughness".to_owned(),
+            flags: vec!["--max_roughness".to_owned()],
+            description: "Maximum RMS residual of a strip's plane fit within a cell for the cell to be used.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.05".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Slope (degrees)".to_owned(),
+            flags: vec!["--max_slope".to_owned()],
+            description: "Maximum slope, in degrees, of a strip's surface within a cell for the cell to be used.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("15.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Points Per Cell".to_owned(),
+            flags: vec!["--min_points".to_owned()],
+            description: "Minimum number of a strip's points within a cell for the strip's surface to be used.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Identify Strips Using GPS Time?".to_owned(),
+            flags: vec!["--use_gps_time".to_owned()],
+            description: "Identify strips using gaps in GPS time rather than point source IDs?".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "GPS Time Threshold".to_owned(),
+            flags: vec!["--time_threshold".to_owned()],
+            description: "Minimum gap in GPS time between the points of consecutive strips.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("15.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --model=translation --resolution=3.0 --max_roughness=0.08 --max_slope=40.0", short_exe, name).replace("*", &sep);
+
+        LidarStripAlign {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarStripAlign {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            (
+                "--resolution".to_string(),
+                ParameterConstraint::GreaterThan(0f64),
+            ),
+            (
+                "--max_roughness".to_string(),
+                ParameterConstraint::GreaterThan(0f64),
+            ),
+            (
+                "--max_slope".to_string(),
+                ParameterConstraint::Range(0f64, 89f64),
+            ),
+            ("--min_points".to_string(), ParameterConstraint::Min(3f64)),
+            (
+                "--time_threshold".to_string(),
+                ParameterConstraint::GreaterThan(0f64),
+            ),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut model = String::from("offset");
+        let mut grid_res = 2f64;
+        let mut max_roughness = 0.05f64;
+        let mut max_slope = 15f64;
+        let mut min_points = 5usize;
+        let mut use_gps_time = false;
+        let mut time_threshold = 15f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-model" {
+                model = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-resolution" {
+                grid_res = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_roughness" {
+                max_roughness = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_slope" {
+                max_slope = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_points" {
+                min_points = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-use_gps_time" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    use_gps_time = true;
+                }
+            } else if flag_val == "-time_threshold" {
+                time_threshold = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        // the number of parameters of each strip's correction
+        let is_translation = model.contains("trans") || model.contains("rigid");
+        let num_params = if model.contains("off") {
+            1
+        } else if is_translation || model.contains("plane") || model.contains("poly") {
+            3
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unrecognized correction model; options are 'offset', 'plane', and 'translation'.",
+            ));
+        };
+        min_points = min_points.max(3);
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        let start = Instant::now();
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        // identify the strip of each point
+        let mut strip = vec![0usize; n_points];
+        let mut strip_ids: Vec<String> = vec![];
+        if use_gps_time {
+            if !input.has_gps_time() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input file has a Point Format that does not include GPS time, which is required when --use_gps_time is specified.",
+                ));
+            }
+            let mut times = Vec::with_capacity(n_points);
+            for i in 0..n_points {
+                times.push((input.get_gps_time(i)?, i));
+            }
+            times.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            let mut strip_start = 0usize;
+            for j in 0..times.len() {
+                if j > 0 && times[j].0 - times[j - 1].0 > time_threshold {
+                    strip_ids.push(format!("{:.1}-{:.1}", times[strip_start].0, times[j - 1].0));
+                    strip_start = j;
+                }
+                strip[times[j].1] = strip_ids.len();
+            }
+            if !times.is_empty() {
+                strip_ids.push(format!(
+                    "{:.1}-{:.1}",
+                    times[strip_start].0,
+                    times[times.len() - 1].0
+                ));
+            }
+        } else {
+            let mut id_map: HashMap<u16, usize> = HashMap::new();
+            for i in 0..n_points {
+                let id = input[i].point_source_id;
+                let num_strips = id_map.len();
+                strip[i] = *id_map.entry(id).or_insert(num_strips);
+            }
+            strip_ids = vec![String::new(); id_map.len()];
+            for (id, s) in id_map {
+                strip_ids[s] = format!("{}", id);
+            }
+        }
+        let num_strips = strip_ids.len();
+        if verbose {
+            println!("Number of strips: {}", num_strips);
+        }
+        if num_strips < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input file must contain at least two strips (flightlines).",
+            ));
+        }
+
+        // accumulate the plane-fitting sums of each strip within each grid cell
+        let west = input.header.min_x;
+        let north = input.header.max_y;
+        let columns = (((input.header.max_x - west) / grid_res).ceil() as usize).max(1);
+        let mut cell_sums: HashMap<(usize, usize), PlaneSums> = HashMap::new();
+        for i in 0..n_points {
+            let p: PointData = input.get_point_info(i);
+            if p.is_classified_noise() || p.withheld() {
+                continue;
+            }
+            let row = ((north - p.y) / grid_res).floor().max(0f64) as usize;
+            let col = (((p.x - west) / grid_res).floor().max(0f64) as usize).min(columns - 1);
+            // coordinates relative to the cell centre
+            let xc = west + (col as f64 + 0.5) * grid_res;
+            let yc = north - (row as f64 + 0.5) * grid_res;
+            cell_sums
+                .entry((row * columns + col, strip[i]))
+                .or_insert(PlaneSums::new())
+                .add(p.x - xc, p.y - yc, p.z);
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Binning points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // fit the strip surfaces within each cell, retaining only smooth surfaces
+        let max_gradient = max_slope.to_radians().tan();
+        let mut cell_surfaces: HashMap<usize, Vec<(usize, f64, f64, f64)>> = HashMap::new();
+        for (&(cell, s), sums) in &cell_sums {
+            if sums.n < min_points {
+                continue;
+            }
+            if let Some((z, gx, gy, rms)) = sums.fit() {
+                if rms <= max_roughness && (gx * gx + gy * gy).sqrt() <= max_gradient {
+                    cell_surfaces
+                        .entry(cell)
+                        .or_insert(vec![])
+                        .push((s, z, gx, gy));
+                }
+            }
+        }
+        drop(cell_sums);
+
+        // create an observation for each pair of strips in each cell
+        let x_mid = (input.header.min_x + input.header.max_x) / 2f64;
+        let y_mid = (input.header.min_y + input.header.max_y) / 2f64;
+        // scale the planar terms so that they are of similar magnitude to the offsets
+        let scale = ((input.header.max_x - input.header.min_x)
+            .max(input.header.max_y - input.header.min_y)
+            / 2f64)
+            .max(grid_res);
+        let mut observations: Vec<Observation> = vec![];
+        for (cell, surfaces) in &cell_surfaces {
+            if surfaces.len() < 2 {
+                continue;
+            }
+            let xs = (west + ((cell % columns) as f64 + 0.5) * grid_res - x_mid) / scale;
+            let ys = (north - ((cell / columns) as f64 + 0.5) * grid_res - y_mid) / scale;
+            for a in 0..surfaces.len() {
+                for b in a + 1..surfaces.len() {
+                    let (sa, za, gxa, gya) = surfaces[a];
+                    let (sb, zb, gxb, gyb) = surfaces[b];
+                    let basis = if is_translation {
+                        // the height change at a fixed location caused by shifting a surface by
+                        // (dx, dy, dz) is dz - gx dx - gy dy
+                        let gx = (gxa + gxb) / 2f64;
+                        let gy = (gya + gyb) / 2f64;
+                        [1f64, -gx, -gy]
+                    } else {
+                        [1f64, xs, ys]
+                    };
+                    observations.push(Observation {
+                        strip_a: sa,
+                        strip_b: sb,
+                        basis: basis,
+                        value: zb - za,
+                        active: true,
+                    });
+                }
+            }
+        }
+        drop(cell_surfaces);
+
+        if observations.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No smooth overlapping surfaces were found between the strips. Try increasing --max_roughness, --max_slope, or --resolution.",
+            ));
+        }
+        let mut strip_num_obs = vec![0usize; num_strips];
+        for obs in &observations {
+            strip_num_obs[obs.strip_a] += 1;
+            strip_num_obs[obs.strip_b] += 1;
+        }
+        let rms_before = (observations.iter().map(|o| o.value * o.value).sum::<f64>()
+            / observations.len() as f64)
+            .sqrt();
+
+        // least-squares adjustment with outlier removal
+        let num_unknowns = num_strips * num_params;
+        let mut corrections = vec![0f64; num_unknowns];
+        let mut rms_after = rms_before;
+        for iteration in 0..4 {
+            if iteration > 0 {
+                let threshold = 3f64 * rms_after;
+                for obs in observations.iter_mut() {
+                    obs.active = obs.residual(&corrections, num_params).abs() <= threshold;
+                }
+            }
+            let mut n = DMatrix::<f64>::zeros(num_unknowns, num_unknowns);
+            let mut rhs = DVector::<f64>::zeros(num_unknowns);
+            let mut num_active = 0usize;
+            for obs in observations.iter().filter(|o| o.active) {
+                // the observation equation is f_a - f_b = z_b - z_a
+                let mut row: Vec<(usize, f64)> = Vec::with_capacity(2 * num_params);
+                for k in 0..num_params {
+                    row.push((obs.strip_a * num_params + k, obs.basis[k]));
+                    row.push((obs.strip_b * num_params + k, -obs.basis[k]));
+                }
+                for &(r, vr) in &row {
+                    rhs[r] += vr * obs.value;
+                    for &(c, vc) in &row {
+                        n[(r, c)] += vr * vc;
+                    }
+                }
+                num_active += 1;
+            }
+            // constrain each type of correction to a mean of zero over the connected strips
+            let weight = num_active as f64;
+            for k in 0..num_params {
+                for a in (0..num_strips).filter(|s| strip_num_obs[*s] > 0) {
+                    for b in (0..num_strips).filter(|s| strip_num_obs[*s] > 0) {
+                        n[(a * num_params + k, b * num_params + k)] += weight;
+                    }
+                }
+            }
+            // a small amount of regularization keeps poorly determined parameters near zero
+            let ridge = 1e-9 * (0..num_unknowns).map(|i| n[(i, i)]).fold(0f64, f64::max).max(1f64);
+            for i in 0..num_unknowns {
+                n[(i, i)] += ridge;
+            }
+            match n.lu().solve(&rhs) {
+                Some(solution) => {
+                    for i in 0..num_unknowns {
+                        corrections[i] = solution[i];
+                    }
+                }
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::Other,
+                        "The strip adjustment could not be solved.",
+                    ));
+                }
+            }
+            let (sum_sqr, count) = observations
+                .iter()
+                .filter(|o| o.active)
+                .map(|o| o.residual(&corrections, num_params).powi(2))
+                .fold((0f64, 0usize), |acc, r| (acc.0 + r, acc.1 + 1));
+            rms_after = (sum_sqr / count.max(1) as f64).sqrt();
+        }
+        let num_active = observations.iter().filter(|o| o.active).count();
+        let rms_before_active = (observations
+            .iter()
+            .filter(|o| o.active)
+            .map(|o| o.value * o.value)
+            .sum::<f64>()
+            / num_active.max(1) as f64)
+            .sqrt();
+
+        if verbose {
+            println!(
+                "Number of observations: {} ({} used)",
+                observations.len(),
+                num_active
+            );
+            println!(
+                "RMS strip difference before adjustment: {:.4} (all observations: {:.4})",
+                rms_before_active, rms_before
+            );
+            println!("RMS strip difference after adjustment: {:.4}", rms_after);
+            println!("Strip corrections:");
+            if is_translation {
+                println!(
+                    "{:>16} {:>10} {:>10} {:>10} {:>10}",
+                    "STRIP", "NUM_OBS", "DX", "DY", "DZ"
+                );
+            } else if num_params == 3 {
+                println!(
+                    "{:>16} {:>10} {:>10} {:>10} {:>10}",
+                    "STRIP", "NUM_OBS", "DZ", "DZ_DX", "DZ_DY"
+                );
+            } else {
+                println!("{:>16} {:>10} {:>10}", "STRIP", "NUM_OBS", "DZ");
+            }
+            for s in 0..num_strips {
+                let c = &corrections[s * num_params..(s + 1) * num_params];
+                if is_translation {
+                    println!(
+                        "{:>16} {:>10} {:>10.4} {:>10.4} {:>10.4}",
+                        strip_ids[s], strip_num_obs[s], c[1], c[2], c[0]
+                    );
+                } else if num_params == 3 {
+                    // report the tilt in elevation units per map unit
+                    println!(
+                        "{:>16} {:>10} {:>10.4} {:>10.6} {:>10.6}",
+                        strip_ids[s],
+                        strip_num_obs[s],
+                        c[0],
+                        c[1] / scale,
+                        c[2] / scale
+                    );
+                } else {
+                    println!("{:>16} {:>10} {:>10.4}", strip_ids[s], strip_num_obs[s], c[0]);
+                }
+            }
+        }
+
+        // now output the corrected points
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        output.header.system_id = "EXTRACTION".to_string();
+        for i in 0..n_points {
+            let mut pr = input.get_record(i);
+            let c = &corrections[strip[i] * num_params..(strip[i] + 1) * num_params];
+            let p = pr.get_point_data_mut();
+            if is_translation {
+                p.x += c[1];
+                p.y += c[2];
+                p.z += c[0];
+            } else if num_params == 3 {
+                p.z += c[0] + c[1] * (p.x - x_mid) / scale + c[2] * (p.y - y_mid) / scale;
+            } else {
+                p.z += c[0];
+            }
+            output.add_point_record(pr);
+            output.add_extra_bytes(input.get_extra_bytes(i));
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Saving data: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The sums needed to fit a least-squares plane, z = a + b x + c y, to a set of points.
+struct PlaneSums {
+    n: usize,
+    x: f64,
+    y: f64,
+    z: f64,
+    xx: f64,
+    xy: f64,
+    yy: f64,
+    xz: f64,
+    yz: f64,
+    zz: f64,
+}
+
+impl PlaneSums {
+    fn new() -> PlaneSums {
+        PlaneSums {
+            n: 0,
+            x: 0f64,
+            y: 0f64,
+            z: 0f64,
+            xx: 0f64,
+            xy: 0f64,
+            yy: 0f64,
+            xz: 0f64,
+            yz: 0f64,
+            zz: 0f64,
+        }
+    }
+
+    fn add(&mut self, x: f64, y: f64, z: f64) {
+        self.n += 1;
+        self.x += x;
+        self.y += y;
+        self.z += z;
+        self.xx += x * x;
+        self.xy += x * y;
+        self.yy += y * y;
+        self.xz += x * z;
+        self.yz += y * z;
+        self.zz += z * z;
+    }
+
+    /// Returns the plane's elevation at the origin, its x and y gradients, and the RMS residual.
+    fn fit(&self) -> Option<(f64, f64, f64, f64)> {
+        let n = self.n as f64;
+        let a = Matrix3::new(
+            n, self.x, self.y, self.x, self.xx, self.xy, self.y, self.xy, self.yy,
+        );
+        let b = Vector3::new(self.z, self.xz, self.yz);
+        let c = a.lu().solve(&b)?;
+        // the residual sum of squares is z'z - c'X'z
+        let ssr = (self.zz - c.dot(&b)).max(0f64);
+        Some((c[0], c[1], c[2], (ssr / n).sqrt()))
+    }
+}
+
+/// An observed elevation difference between the surfaces of two strips within a grid cell.
+struct Observation {
+    strip_a: usize,
+    strip_b: usize,
+    basis: [f64; 3],
+    value: f64,
+    active: bool,
+}
+
+impl Observation {
+    fn residual(&self, corrections: &[f64], num_params: usize) -> f64 {
+        let mut predicted = 0f64;
+        for k in 0..num_params {
+            predicted += self.basis[k]
+                * (corrections[self.strip_a * num_params + k]
+                    - corrections[self.strip_b * num_params + k]);
+        }
+        self.value - predicted
+    }
+}
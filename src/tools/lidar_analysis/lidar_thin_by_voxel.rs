@@ -0,0 +1,495 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::tools::*;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool thins a LiDAR point cloud using either voxel-grid downsampling or Poisson-disk
+/// (minimum spacing) thinning. Unlike `LidarThin`, which retains a single point within each cell of
+/// a two-dimensional grid, and random decimation, which removes points irrespective of their
+/// position, both methods operate in three dimensions. Points in the understory and on the ground
+/// beneath a vegetation canopy are therefore retained in proportion to the volume that they occupy,
+/// rather than being discarded in favour of canopy returns.
+///
+/// With the `voxel` method (the default), the point cloud is divided into cubic voxels with a side
+/// length equal to `--resolution` and a single point is retained within each occupied voxel. With the
+/// `poisson` method, points are accepted in order of priority, and a point is only accepted if it is
+/// further than `--resolution` from every previously accepted point. The output of the Poisson-disk
+/// method has a more even point spacing, since it is not affected by the placement of voxel
+/// boundaries, although it is slower to compute.
+///
+/// The `--selection` parameter determines which point is retained within a voxel, or the priority
+/// with which points are accepted using the Poisson-disk method. Options include `lowest`, `highest`,
+/// and `centroid`, which favours the point that is nearest the centroid of the points within its
+/// voxel. Using the `lowest` selection is recommended when the thinned point cloud will be used to
+/// model the ground surface.
+///
+/// If the `--save_filtered` flag is specified, the points that are removed by the thinning are saved
+/// to a second output file, with the suffix *_filtered_points.las*.
+///
+/// # See Also
+/// `LidarThin`, `LidarThinHighDensity`, `LidarSorFilter`
+pub struct LidarThinByVoxel {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarThinByVoxel {
+    pub fn new() -> LidarThinByVoxel {
+        // public constructor
+        let name = "LidarThinByVoxel".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Thins a LiDAR point cloud using voxel-grid downsampling or Poisson-disk (minimum spacing) thinning.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Voxel Size or Minimum Spacing".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "The voxel size, or the minimum point spacing for the Poisson-disk method, in xy-units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Thinning Method".to_owned(),
+            flags: vec!["--method".to_owned()],
+            description: "Thinning method; options are 'voxel' (default) and 'poisson'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "voxel".to_owned(),
+                "poisson".to_owned(),
+            ]),
+            default_value: Some("voxel".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Point Selection".to_owned(),
+            flags: vec!["--selection".to_owned()],
+            description: "Point selection criterion; options are 'lowest' (default), 'highest', and 'centroid'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "lowest".to_owned(),
+                "highest".to_owned(),
+                "centroid".to_owned(),
+            ]),
+            default_value: Some("lowest".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Save filtered points to seperate file?".to_owned(),
+            flags: vec!["--save_filtered".to_owned()],
+            description: "Save filtered points to seperate file?".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --resolution=0.5 --selection=lowest
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=input.las -o=output.las --resolution=0.5 --method=poisson --selection=centroid --save_filtered", short_exe, name).replace("*", &sep);
+
+        LidarThinByVoxel {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarThinByVoxel {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![(
+            "--resolution".to_string(),
+            ParameterConstraint::GreaterThan(0.0),
+        )]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut resolution = 1f64;
+        let mut method = "voxel".to_string();
+        let mut selection = "lowest".to_string();
+        let mut save_filtered = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-resolution" {
+                resolution = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-method" {
+                method = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                }
+                .to_lowercase();
+            } else if flag_val == "-selection" {
+                selection = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                }
+                .to_lowercase();
+            } else if flag_val == "-save_filtered" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    save_filtered = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if method.contains("poisson") || method.contains("disk") {
+            method = "poisson".to_string();
+        } else if method.contains("voxel") {
+            method = "voxel".to_string();
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Specified 'method' parameter ({}) is not recognized.", method),
+            ));
+        }
+        if selection.contains("low") {
+            selection = "lowest".to_string();
+        } else if selection.contains("high") {
+            selection = "highest".to_string();
+        } else if selection.contains("cent") {
+            selection = "centroid".to_string();
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Specified 'selection' parameter ({}) is not recognized.",
+                    selection
+                ),
+            ));
+        }
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        let start = Instant::now();
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points as f64 - 1f64).max(1f64); // used for progress calculation only
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+
+        let min_x = input.header.min_x;
+        let min_y = input.header.min_y;
+        let min_z = input.header.min_z;
+        let get_voxel = |p: &PointData| -> (isize, isize, isize) {
+            (
+                ((p.x - min_x) / resolution).floor() as isize,
+                ((p.y - min_y) / resolution).floor() as isize,
+                ((p.z - min_z) / resolution).floor() as isize,
+            )
+        };
+
+        // The centroid selection requires the mean position of the points within each voxel.
+        let mut centroids: HashMap<(isize, isize, isize), (f64, f64, f64, usize)> = HashMap::new();
+        if selection == "centroid" {
+            for i in 0..n_points {
+                let p: PointData = input.get_point_info(i);
+                let c = centroids.entry(get_voxel(&p)).or_insert((0f64, 0f64, 0f64, 0));
+                c.0 += p.x;
+                c.1 += p.y;
+                c.2 += p.z;
+                c.3 += 1;
+                if verbose {
+                    progress = (100.0_f64 * i as f64 / num_points) as i32;
+                    if progress != old_progress {
+                        println!("Calculating voxel centroids: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        // Lower priority values are preferred.
+        let priority = |p: &PointData| -> f64 {
+            match &selection as &str {
+                "lowest" => p.z,
+                "highest" => -p.z,
+                _ => {
+                    let c = centroids[&get_voxel(p)];
+                    let n = c.3 as f64;
+                    (p.x - c.0 / n) * (p.x - c.0 / n)
+                        + (p.y - c.1 / n) * (p.y - c.1 / n)
+                        + (p.z - c.2 / n) * (p.z - c.2 / n)
+                }
+            }
+        };
+
+        let mut filtered = vec![true; n_points];
+        if method == "voxel" {
+            let mut selected: HashMap<(isize, isize, isize), (usize, f64)> = HashMap::new();
+            for i in 0..n_points {
+                let p: PointData = input.get_point_info(i);
+                let pr = priority(&p);
+                let s = selected.entry(get_voxel(&p)).or_insert((i, pr));
+                if pr < s.1 {
+                    *s = (i, pr);
+                }
+                if verbose {
+                    progress = (100.0_f64 * i as f64 / num_points) as i32;
+                    if progress != old_progress {
+                        println!("Thinning points: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+            for (i, _) in selected.values() {
+                filtered[*i] = false;
+            }
+        } else {
+            let mut order: Vec<(f64, usize)> = (0..n_points)
+                .map(|i| (priority(&input.get_point_info(i)), i))
+                .collect();
+            order.sort_by(|a, b| {
+                a.0.partial_cmp(&b.0)
+                    .unwrap_or(Ordering::Equal)
+                    .then(a.1.cmp(&b.1))
+            });
+
+            // The accepted points are stored in a hash grid with a cell size equal to the minimum
+            // spacing, such that only the 27 cells surrounding a point need to be searched.
+            let sqrd_res = resolution * resolution;
+            let mut accepted: HashMap<(isize, isize, isize), Vec<(f64, f64, f64)>> = HashMap::new();
+            for (n, (_, i)) in order.iter().enumerate() {
+                let p: PointData = input.get_point_info(*i);
+                let (vx, vy, vz) = get_voxel(&p);
+                let mut is_clear = true;
+                'search: for dx in -1..=1 {
+                    for dy in -1..=1 {
+                        for dz in -1..=1 {
+                            if let Some(pts) = accepted.get(&(vx + dx, vy + dy, vz + dz)) {
+                                for q in pts {
+                                    if (p.x - q.0) * (p.x - q.0)
+                                        + (p.y - q.1) * (p.y - q.1)
+                                        + (p.z - q.2) * (p.z - q.2)
+                                        < sqrd_res
+                                    {
+                                        is_clear = false;
+                                        break 'search;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                if is_clear {
+                    accepted
+                        .entry((vx, vy, vz))
+                        .or_insert(vec![])
+                        .push((p.x, p.y, p.z));
+                    filtered[*i] = false;
+                }
+                if verbose {
+                    progress = (100.0_f64 * n as f64 / num_points) as i32;
+                    if progress != old_progress {
+                        println!("Thinning points: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        // now output the data
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        output.header.system_id = "EXTRACTION".to_string();
+        let mut filtered_output = if save_filtered {
+            let p = path::Path::new(&output_file);
+            let mut extension = String::from(".");
+            let ext = p.extension().unwrap().to_str().unwrap();
+            extension.push_str(ext);
+            let filtered_output_file = output_file.replace(&extension, "_filtered_points.las");
+            let mut lf = LasFile::initialize_using_file(&filtered_output_file, &input);
+            lf.header.system_id = "EXTRACTION".to_string();
+            Some(lf)
+        } else {
+            None
+        };
+
+        let mut num_retained = 0usize;
+        for i in 0..n_points {
+            if !filtered[i] {
+                output.add_point_record(input.get_record(i));
+                output.add_extra_bytes(input.get_extra_bytes(i));
+                num_retained += 1;
+            } else if let Some(ref mut lf) = filtered_output {
+                lf.add_point_record(input.get_record(i));
+                lf.add_extra_bytes(input.get_extra_bytes(i));
+            }
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Saving data: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if verbose {
+            println!(
+                "Number of points retained: {} of {} ({:.1}%)",
+                num_retained,
+                n_points,
+                100f64 * num_retained as f64 / n_points.max(1) as f64
+            );
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if let Some(mut lf) = filtered_output {
+            let _ = match lf.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Filtered points LAS file saved")
+                    }
+                }
+                Err(e) => println!("error while writing: {:?}", e),
+            };
+        }
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Complete!")
+                }
+            }
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
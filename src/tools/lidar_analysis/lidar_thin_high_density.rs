@@ -203,13 +203,8 @@ impl WhiteboxTool for LidarThinHighDensity {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input LAS file...");
@@ -337,6 +332,7 @@ impl WhiteboxTool for LidarThinHighDensity {
             for i in 0..n_points {
                 if !filtered[i] {
                     output.add_point_record(input.get_record(i));
+                    output.add_extra_bytes(input.get_extra_bytes(i));
                 }
                 if verbose {
                     progress = (100.0_f64 * i as f64 / num_points) as i32;
@@ -359,8 +355,10 @@ impl WhiteboxTool for LidarThinHighDensity {
             for i in 0..n_points {
                 if !filtered[i] {
                     output.add_point_record(input.get_record(i));
+                    output.add_extra_bytes(input.get_extra_bytes(i));
                 } else {
                     filtered_output.add_point_record(input.get_record(i));
+                    filtered_output.add_extra_bytes(input.get_extra_bytes(i));
                 }
                 if verbose {
                     progress = (100.0_f64 * i as f64 / num_points) as i32;
@@ -227,15 +227,11 @@ impl WhiteboxTool for LidarTile {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = std::path::MAIN_SEPARATOR;
-
         if min_points < 2 {
             min_points = 2;
         }
 
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
 
         if verbose {
             println!("Performing analysis...");
@@ -364,6 +360,7 @@ impl WhiteboxTool for LidarTile {
                 for i in first_point_num[tile_num]..last_point_num[tile_num] {
                     if tile_data[i] == tile_num {
                         output.add_point_record(input.get_record(i));
+                        output.add_extra_bytes(input.get_extra_bytes(i));
                     }
                 }
                 let _ = match output.write() {
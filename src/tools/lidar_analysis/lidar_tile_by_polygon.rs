@@ -0,0 +1,546 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms;
+use crate::lidar::*;
+use crate::structures::{BoundingBox, Point2D};
+use crate::tools::*;
+use crate::vector::{FieldData, ShapeType, Shapefile, ShapefileGeometry};
+use num_cpus;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool clips one or more LiDAR files (`--inputs`) to each of the polygon features contained
+/// within a vector file (`--polygons`), creating one output LAS file per polygon. This is the
+/// common operation of cutting a tiled LiDAR delivery to project boundaries, property parcels, or
+/// management units. All of the points that fall within a polygon are written to its output file,
+/// regardless of which input tile they come from, and polygon holes are respected. If the input
+/// files are not specified, all of the LAS files within the working directory are used.
+///
+/// Output files are named by the values of an attribute field (`--field`), e.g. a parcel
+/// identifier, with characters that are not valid in file names replaced by underscores. If no
+/// field is specified, or the value of a feature is null, the feature number (starting from 1) is
+/// used instead, and if several features share a value, the feature number is appended to the
+/// names of the later features. The output files are saved to the output directory (`--outdir`),
+/// which is the working directory by default. Polygons that do not contain any points do not
+/// produce an output file.
+///
+/// The optional buffer distance (`--buffer`), in the horizontal units of the point cloud, extends
+/// each polygon outwards by the specified distance, such that points outside of a polygon but
+/// within the buffer distance of its boundary are also included, e.g. to provide an overlap
+/// between neighbouring clips for later processing. A negative buffer distance shrinks the
+/// polygons instead. Where polygons (or their buffers) overlap, points are written to each of the
+/// overlapping outputs. All of the input files must share the same point format.
+///
+/// # See Also
+/// `ClipLidarToPolygon`, `SelectTilesByPolygon`, `LidarTile`, `LidarJoin`
+pub struct LidarTileByPolygon {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarTileByPolygon {
+    pub fn new() -> LidarTileByPolygon {
+        // public constructor
+        let name = "LidarTileByPolygon".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Clips one or more LiDAR files to each feature of a polygon vector, creating one output per polygon."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input LiDAR Files".to_owned(),
+            flags: vec!["-i".to_owned(), "--inputs".to_owned()],
+            description: "Input LiDAR files; if unspecified, all LAS files in the working directory are used.".to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Lidar),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Vector Polygon File".to_owned(),
+            flags: vec!["--polygons".to_owned()],
+            description: "Input vector polygons file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Name Field (optional)".to_owned(),
+            flags: vec!["--field".to_owned()],
+            description: "Attribute field used to name the output files; the feature number is used by default.".to_owned(),
+            parameter_type: ParameterType::VectorAttributeField(
+                AttributeType::Any,
+                "--polygons".to_string(),
+            ),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Buffer Distance".to_owned(),
+            flags: vec!["--buffer".to_owned()],
+            description: "Distance by which the polygons are extended (or, if negative, shrunk).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Directory (optional)".to_owned(),
+            flags: vec!["--outdir".to_owned()],
+            description: "Output directory; the working directory is used by default.".to_owned(),
+            parameter_type: ParameterType::Directory,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='tile1.las, tile2.las' --polygons=parcels.shp --field=PARCEL_ID --buffer=5.0 --outdir='*path*to*output*'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --polygons=project_boundary.shp", short_exe, name).replace("*", &sep);
+
+        LidarTileByPolygon {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarTileByPolygon {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_files = String::new();
+        let mut polygons_file = String::new();
+        let mut field_name = String::new();
+        let mut buffer = 0f64;
+        let mut output_directory = String::new();
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-inputs" || flag_val == "-input" {
+                input_files = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-polygon" || flag_val == "-polygons" {
+                polygons_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-field" {
+                field_name = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-buffer" {
+                buffer = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-outdir" {
+                output_directory = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+
+        let start = Instant::now();
+
+        polygons_file = resolve_path(&polygons_file, working_directory);
+        if output_directory.is_empty() {
+            output_directory = working_directory.to_string();
+        }
+        if !output_directory.is_empty() && !output_directory.ends_with(&sep) && !output_directory.ends_with("/") {
+            output_directory.push_str(&sep);
+        }
+
+        let mut inputs = vec![];
+        if input_files.trim().is_empty() {
+            if std::path::Path::new(&working_directory).is_dir() {
+                for entry in fs::read_dir(working_directory)? {
+                    let s = entry?
+                        .path()
+                        .into_os_string()
+                        .to_str()
+                        .expect("Error reading path string")
+                        .to_string();
+                    if s.to_lowercase().ends_with(".las") || s.to_lowercase().ends_with(".zip") {
+                        inputs.push(s);
+                    }
+                }
+                inputs.sort();
+            } else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The input directory ({}) is incorrect.", working_directory),
+                ));
+            }
+        } else {
+            let mut cmd = input_files.split(";");
+            let mut vec = cmd.collect::<Vec<&str>>();
+            if vec.len() == 1 {
+                cmd = input_files.split(",");
+                vec = cmd.collect::<Vec<&str>>();
+            }
+            for value in vec {
+                if !value.trim().is_empty() {
+                    let mut input_file = value.trim().to_owned();
+                    input_file = resolve_path(&input_file, working_directory);
+                    inputs.push(input_file);
+                }
+            }
+        }
+        if inputs.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No input LiDAR files were found.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading polygons...")
+        };
+        let polygons = Shapefile::read(&polygons_file)?;
+        let num_records = polygons.num_records;
+
+        // make sure the input vector file is of polygon type
+        if polygons.header.shape_type.base_shape_type() != ShapeType::Polygon {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input vector data must be of polygon base shape type.",
+            ));
+        }
+
+        if !field_name.is_empty() && polygons.attributes.get_field_num(&field_name).is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("The polygons file does not contain a field named {}.", field_name),
+            ));
+        }
+
+        // name the output of each polygon
+        let mut names: Vec<String> = Vec::with_capacity(num_records);
+        let mut used_names = HashMap::new();
+        for record_num in 0..num_records {
+            let value = if field_name.is_empty() {
+                String::new()
+            } else {
+                match polygons.attributes.get_value(record_num, &field_name) {
+                    FieldData::Int(v) => format!("{}", v),
+                    FieldData::Real(v) => format!("{}", v),
+                    FieldData::Text(v) => v.trim().to_string(),
+                    FieldData::Date(v) => format!("{}", v),
+                    FieldData::Bool(v) => format!("{}", v),
+                    FieldData::Null => String::new(),
+                }
+            };
+            let mut name: String = value
+                .chars()
+                .map(|c| {
+                    if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            if name.is_empty() {
+                name = format!("{}", record_num + 1);
+            }
+            if used_names.contains_key(&name) {
+                name = format!("{}_{}", name, record_num + 1);
+            }
+            used_names.insert(name.clone(), record_num);
+            names.push(name);
+        }
+
+        // place the buffered bounding boxes of each of the polygons into a vector
+        let expand = buffer.max(0f64);
+        let mut bb: Vec<BoundingBox> = Vec::with_capacity(num_records);
+        for record_num in 0..num_records {
+            let record = polygons.get_record(record_num);
+            bb.push(BoundingBox::new(
+                record.x_min - expand,
+                record.x_max + expand,
+                record.y_min - expand,
+                record.y_max + expand,
+            ));
+        }
+
+        let polygons = Arc::new(polygons);
+        let bb = Arc::new(bb);
+        let mut outputs: HashMap<usize, LasFile> = HashMap::new();
+        let mut point_format = -1i32;
+        let mut extra_bytes_length = 0usize;
+        let num_files = inputs.len();
+        for (file_num, input_file) in inputs.iter().enumerate() {
+            if verbose {
+                println!("Clipping file {} of {}...", file_num + 1, num_files);
+            }
+            let input = match LasFile::new(input_file, "r") {
+                Ok(lf) => lf,
+                Err(_) => {
+                    return Err(Error::new(
+                        ErrorKind::NotFound,
+                        format!("No such file or directory ({})", input_file),
+                    ))
+                }
+            };
+
+            let lidar_bb = BoundingBox::new(
+                input.header.min_x,
+                input.header.max_x,
+                input.header.min_y,
+                input.header.max_y,
+            );
+            let record_nums: Vec<usize> = (0..num_records)
+                .filter(|r| bb[*r].overlaps(lidar_bb))
+                .collect();
+            if record_nums.is_empty() {
+                continue;
+            }
+
+            if point_format == -1 {
+                point_format = input.header.point_format as i32;
+                extra_bytes_length = input.get_extra_bytes_length();
+            } else if input.header.point_format as i32 != point_format
+                || input.get_extra_bytes_length() != extra_bytes_length
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "All input files must be of the same LAS Point Format.",
+                ));
+            }
+
+            let n_points = input.header.number_of_points as usize;
+            let num_procs = num_cpus::get();
+            let input = Arc::new(input);
+            let record_nums = Arc::new(record_nums);
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let input = input.clone();
+                let polygons = polygons.clone();
+                let record_nums = record_nums.clone();
+                let bb = bb.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    let mut matches = vec![];
+                    for point_num in (0..n_points).filter(|point_num| point_num % num_procs == tid) {
+                        let p = input.get_point_info(point_num);
+                        let pt = Point2D { x: p.x, y: p.y };
+                        for record_num in record_nums.iter() {
+                            if bb[*record_num].is_point_in_box(p.x, p.y)
+                                && is_point_in_buffered_polygon(
+                                    &pt,
+                                    polygons.get_record(*record_num),
+                                    buffer,
+                                )
+                            {
+                                matches.push((point_num, *record_num));
+                            }
+                        }
+                    }
+                    tx.send(matches).unwrap();
+                });
+            }
+            drop(tx);
+
+            let mut matches = vec![];
+            for m in rx.iter() {
+                matches.extend(m);
+            }
+            // preserve the order of the points within each output
+            matches.sort();
+
+            for (point_num, record_num) in matches {
+                let output = outputs.entry(record_num).or_insert_with(|| {
+                    let file_name = format!("{}{}.las", output_directory, names[record_num]);
+                    let mut lf = LasFile::initialize_using_file(&file_name, &input);
+                    lf.header.system_id = "EXTRACTION".to_string();
+                    lf
+                });
+                output.add_point_record(input.get_record(point_num));
+                output.add_extra_bytes(input.get_extra_bytes(point_num));
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if outputs.is_empty() {
+            println!("Warning: None of the input points are contained within the polygons. No output files have been created.");
+        }
+
+        let mut record_nums: Vec<usize> = outputs.keys().cloned().collect();
+        record_nums.sort();
+        let num_outputs = record_nums.len();
+        for (i, record_num) in record_nums.iter().enumerate() {
+            let output = outputs.get_mut(record_num).unwrap();
+            if verbose {
+                println!(
+                    "Writing {} ({} of {})...",
+                    output.get_short_filename(),
+                    i + 1,
+                    num_outputs
+                );
+            }
+            let _ = match output.write() {
+                Ok(_) => {}
+                Err(e) => println!("error while writing: {:?}", e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns true if the point is within the polygon, respecting holes, extended (or, if negative,
+/// shrunk) by the buffer distance.
+fn is_point_in_buffered_polygon(pt: &Point2D, record: &ShapefileGeometry, buffer: f64) -> bool {
+    let mut in_poly = false;
+    let mut in_hole = false;
+    let mut min_dist = f64::INFINITY;
+    for part in 0..record.num_parts as usize {
+        let start_point_in_part = record.parts[part] as usize;
+        let end_point_in_part = if part < record.num_parts as usize - 1 {
+            record.parts[part + 1] as usize - 1
+        } else {
+            record.num_points as usize - 1
+        };
+        let points = &record.points[start_point_in_part..end_point_in_part + 1];
+        if algorithms::point_in_poly(pt, points) {
+            if record.is_hole(part as i32) {
+                in_hole = true;
+            } else {
+                in_poly = true;
+            }
+        }
+        if buffer != 0f64 {
+            for j in 1..points.len() {
+                min_dist = min_dist.min(distance_to_segment(pt, &points[j - 1], &points[j]));
+            }
+        }
+    }
+    let inside = in_poly && !in_hole;
+    if buffer > 0f64 {
+        inside || min_dist <= buffer
+    } else if buffer < 0f64 {
+        inside && min_dist >= -buffer
+    } else {
+        inside
+    }
+}
+
+fn distance_to_segment(p: &Point2D, a: &Point2D, b: &Point2D) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len_sqrd = dx * dx + dy * dy;
+    if len_sqrd == 0f64 {
+        return p.distance(a);
+    }
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / len_sqrd).clamp(0f64, 1f64);
+    p.distance(&Point2D::new(a.x + t * dx, a.y + t * dy))
+}
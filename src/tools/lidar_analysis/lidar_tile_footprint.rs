@@ -195,13 +195,9 @@ impl WhiteboxTool for LidarTileFootprint {
             }
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let start = Instant::now();
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
         let mut inputs = vec![];
         let mut contains_laz = false;
         if input_file.is_empty() {
@@ -245,9 +241,7 @@ impl WhiteboxTool for LidarTileFootprint {
                 ));
             }
         } else {
-            if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-                input_file = format!("{}{}", working_directory, input_file);
-            }
+            input_file = resolve_path(&input_file, working_directory);
             inputs.push(input_file.clone());
         }
 
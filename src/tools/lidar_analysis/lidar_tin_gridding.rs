@@ -2,24 +2,77 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 21/09/2018
-Last Modified: 31/08/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
-use self::na::Vector3;
-use crate::algorithms::{point_in_poly, triangulate};
+use crate::algorithms::{triangulate, NaturalNeighbourInterpolator, TinInterpolator};
+use super::lidar_utils::{check_interpolation_parameters, parameter_output_file};
 use crate::lidar::*;
-use crate::na;
 use crate::raster::*;
 use crate::structures::{BoundingBox, Point2D};
 use crate::tools::*;
 use num_cpus;
 use std::io::{Error, ErrorKind};
 use std::sync::mpsc;
-use std::sync::{Arc, Mutex};
-use std::{env, f64, fs, path, thread};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::{env, f64, path, thread};
 
 /// Creates a raster grid based on a Delaunay triangular irregular network (TIN) fitted to LiDAR points.
+///
+/// By default, values are linearly interpolated on the planar facets of the TIN. Alternatively,
+/// Sibson's natural neighbour interpolation may be used (`--method=natural_neighbour`), which is
+/// computed from the same triangulation. Natural neighbour interpolation produces a smoother
+/// surface, without the faceting that is often apparent in TINs of low-density ground returns,
+/// although it is somewhat slower. In either case, values are only interpolated within triangles
+/// that meet the `--max_triangle_edge_length` criterion.
+///
+/// Points may be excluded from the interpolation based on their class (`--exclude_cls`), elevation
+/// (`--minz` and `--maxz`), and GPS time (`--min_time` and `--max_time`). Filtering by GPS time allows
+/// individual flightlines, or acquisition windows, to be interpolated separately and requires input
+/// files with point formats that include GPS time.
+///
+/// Several point attributes may be gridded from a single read of the point cloud by specifying a
+/// comma-separated list of interpolation parameters, e.g. `--parameter=elevation,intensity,class`.
+/// The triangulation is shared by each of the parameters and one output raster is created per
+/// parameter, named by appending the parameter to the output file name (e.g. `outfile_intensity.tif`).
+/// Points that lack a value for any of the parameters, such as those with no-data extra bytes
+/// values, are excluded from all of the outputs.
+///
+/// When a directory of LAS tiles is interpolated (i.e. the `--input` parameter is unspecified), the
+/// tiles may be selected using comma-separated glob patterns, e.g. `--include='*_ground.las'` and
+/// `--exclude='*_old.las'`, and the `--recursive` flag adds the tiles within the subdirectories of
+/// the working directory. Each of the LAS files within a zip archive that contains several of them,
+/// including those in folders of the archive, is interpolated as a separate tile; an individual
+/// archived file may also be specified as the input, e.g. `--input='tiles.zip#2020/tile1.las'`.
+/// Each output is written beside its tile (or archive) by default. The
+/// `--incremental` flag may be used to update an existing set of output rasters after new tiles have
+/// been added to the directory, or existing tiles have been modified. In incremental mode, only
+/// those tiles whose outputs are missing, or are older than the tile or any of the neighbouring tiles
+/// that overlap its edges, are interpolated; the outputs of all other tiles are left unchanged.
+/// Tiles are interpolated in parallel; when a single file is interpolated, the rasterization of
+/// the TIN is instead divided into blocks of rows that are processed in parallel. Each input file
+/// is read only once per run; its points are cached and shared by the neighbouring tiles that use
+/// them to avoid edge effects, until each of those tiles has been interpolated.
+///
+/// The grid resolution and maximum triangle edge length are normally expressed in the units of the
+/// point coordinates. The tool determines these units from the coordinate reference system of the
+/// (first) input file and warns of likely mismatches, e.g. a resolution specified in metres
+/// (`--res_units=meters`) for a point cloud in feet, or a resolution of several units for a point
+/// cloud in geographic coordinates, which would otherwise produce a grid with cells that are
+/// thousands of times larger than intended. The `--convert_units` flag converts the resolution
+/// and edge length into the units of the point coordinates rather than only reporting the
+/// mismatch. Linear distances are converted into degrees using the length of a degree of
+/// longitude at the centre of the tile.
+///
+/// The `rgb` parameter creates a colour raster with 8-bit channels packed into each cell value.
+/// The red, green, and blue channels, and the near-infrared (NIR) channel of point formats 8 and
+/// 10, may also be interpolated individually (`red`, `green`, `blue`, and `nir`), preserving their
+/// 16-bit values. Alternatively, the `--multiband` flag creates a true multi-band GeoTIFF from
+/// the `rgb` parameter, with 16-bit red, green, and blue bands and, for point formats 8 and 10, a
+/// fourth NIR band, from which vegetation indices such as the NDVI, i.e. (NIR - red) / (NIR + red),
+/// can be calculated directly. Cells outside of the TIN are assigned zero in each band.
 pub struct LidarTINGridding {
     name: String,
     description: String,
@@ -57,7 +110,7 @@ impl LidarTINGridding {
         parameters.push(ToolParameter{
             name: "Interpolation Parameter".to_owned(), 
             flags: vec!["--parameter".to_owned()], 
-            description: "Interpolation parameter; options are 'elevation' (default), 'intensity', 'class', 'return_number', 'number_of_returns', 'scan angle', 'rgb', 'user data'.".to_owned(),
+            description: "Interpolation parameter; options are 'elevation' (default), 'intensity', 'class', 'return_number', 'number_of_returns', 'scan angle', 'rgb', 'red', 'green', 'blue', 'nir', 'user data', or the name of an extra bytes attribute (e.g. 'HeightAboveGround'). Multiple comma-separated parameters (e.g. 'elevation,intensity,class') may be interpolated in a single pass, creating one output file per parameter.".to_owned(),
             parameter_type: ParameterType::OptionList(
                 vec![
                     "elevation".to_owned(), 
@@ -67,6 +120,10 @@ impl LidarTINGridding {
                     "number_of_returns".to_owned(), 
                     "scan angle".to_owned(), 
                     "rgb".to_owned(),
+                    "red".to_owned(),
+                    "green".to_owned(),
+                    "blue".to_owned(),
+                    "nir".to_owned(),
                     "user data".to_owned()
                 ]
             ),
@@ -125,6 +182,24 @@ impl LidarTINGridding {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Minimum GPS Time (optional)".to_owned(),
+            flags: vec!["--min_time".to_owned()],
+            description: "Optional minimum GPS time for inclusion in interpolation.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum GPS Time (optional)".to_owned(),
+            flags: vec!["--max_time".to_owned()],
+            description: "Optional maximum GPS time for inclusion in interpolation.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
         parameters.push(ToolParameter {
             name: "Maximum Triangle Edge Length (optional)".to_owned(),
             flags: vec!["--max_triangle_edge_length".to_owned()],
@@ -134,6 +209,63 @@ impl LidarTINGridding {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Interpolation Method".to_owned(),
+            flags: vec!["--method".to_owned()],
+            description:
+                "Interpolation method; options are 'linear' (default) and 'natural_neighbour'."
+                    .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "linear".to_owned(),
+                "natural_neighbour".to_owned(),
+            ]),
+            default_value: Some("linear".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Only update changed tiles?".to_owned(),
+            flags: vec!["--incremental".to_owned()],
+            description: "Only process tiles with missing outputs, or outputs that are older than the tile or its overlapping neighbours.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Resolution Units".to_owned(),
+            flags: vec!["--res_units".to_owned()],
+            description: "Units of the grid resolution and maximum triangle edge length; 'native' indicates the units of the point coordinates.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "native".to_owned(),
+                "meters".to_owned(),
+                "feet".to_owned(),
+                "degrees".to_owned(),
+            ]),
+            default_value: Some("native".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Convert mismatched units?".to_owned(),
+            flags: vec!["--convert_units".to_owned()],
+            description: "Convert the grid resolution and maximum triangle edge length into the units of the point coordinates when they do not match.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Write multi-band colour output?".to_owned(),
+            flags: vec!["--multiband".to_owned()],
+            description: "Interpolate the 'rgb' parameter as a multi-band (red, green, blue, and, if available, NIR) 16-bit raster rather than packed 8-bit colours.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.extend(InputFileFilter::parameters());
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -145,7 +277,7 @@ impl LidarTINGridding {
         if e.contains(".exe") {
             short_exe += ".exe";
         }
-        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --returns=last --resolution=2.0 --exclude_cls='3,4,5,6,7,18' --max_triangle_edge_length=5.0", short_exe, name).replace("*", &sep);
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=outfile.tif --returns=last --resolution=2.0 --exclude_cls='3,4,5,6,7,18' --max_triangle_edge_length=5.0 --method=natural_neighbour", short_exe, name).replace("*", &sep);
 
         LidarTINGridding {
             name: name,
@@ -192,6 +324,17 @@ impl WhiteboxTool for LidarTINGridding {
         self.toolbox.clone()
     }
 
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--resolution".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--exclude_cls".to_string(), ParameterConstraint::ListRange(0.0, 255.0)),
+        ]
+    }
+
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_dir}{input_stem}.tif".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -201,14 +344,21 @@ impl WhiteboxTool for LidarTINGridding {
         let mut input_file: String = "".to_string();
         let mut output_file: String = "".to_string();
         let mut interp_parameter = "elevation".to_string();
-        let mut interp_parameter_is_rgb = false;
         let mut return_type = "all".to_string();
         let mut grid_res: f64 = 1.0;
         let mut include_class_vals = vec![true; 256];
         let mut exclude_cls_str = String::new();
         let mut max_z = f64::INFINITY;
         let mut min_z = f64::NEG_INFINITY;
+        let mut min_time = f64::NEG_INFINITY;
+        let mut max_time = f64::INFINITY;
         let mut max_triangle_edge_length = f64::INFINITY;
+        let mut natural_neighbour = false;
+        let mut incremental = false;
+        let mut res_units = CoordinateUnits::NotSpecified;
+        let mut convert_units = false;
+        let mut multiband = false;
+        let mut file_filter = InputFileFilter::default();
 
         // read the arguments
         if args.len() == 0 {
@@ -245,9 +395,6 @@ impl WhiteboxTool for LidarTINGridding {
                 } else {
                     args[i + 1].to_string().to_lowercase()
                 };
-                if interp_parameter == "rgb" {
-                    interp_parameter_is_rgb = true;
-                }
             } else if flag_val == "-returns" {
                 return_type = if keyval {
                     vec[1].to_string()
@@ -290,6 +437,18 @@ impl WhiteboxTool for LidarTINGridding {
                 } else {
                     args[i + 1].to_string().parse::<f64>().unwrap()
                 };
+            } else if flag_val == "-min_time" {
+                min_time = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_time" {
+                max_time = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
             } else if flag_val == "-max_triangle_edge_length" {
                 max_triangle_edge_length = if keyval {
                     vec[1].to_string().parse::<f64>().unwrap()
@@ -298,6 +457,39 @@ impl WhiteboxTool for LidarTINGridding {
                 };
 
                 max_triangle_edge_length *= max_triangle_edge_length; // actually squared distance
+            } else if flag_val == "-method" {
+                let method = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+                natural_neighbour = method.contains("natural") || method == "nn" || method == "sibson";
+            } else if flag_val == "-incremental" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    incremental = true;
+                }
+            } else if flag_val == "-res_units" {
+                res_units = if keyval {
+                    CoordinateUnits::parse(vec[1])?
+                } else {
+                    CoordinateUnits::parse(&args[i + 1])?
+                };
+            } else if flag_val == "-convert_units" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    convert_units = true;
+                }
+            } else if flag_val == "-multiband" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    multiband = true;
+                }
+            } else if flag_val == "-include" {
+                file_filter.add_include(if keyval { vec[1] } else { &args[i + 1] });
+            } else if flag_val == "-exclude" {
+                file_filter.add_exclude(if keyval { vec[1] } else { &args[i + 1] });
+            } else if flag_val == "-recursive" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    file_filter.recursive = true;
+                }
             }
         }
 
@@ -307,6 +499,28 @@ impl WhiteboxTool for LidarTINGridding {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
+        let filter_by_time = min_time.is_finite() || max_time.is_finite();
+        if min_time > max_time {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The minimum GPS time (--min_time) must be less than the maximum GPS time (--max_time).",
+            ));
+        }
+
+        // Several comma-separated parameters may be interpolated from a single read of the points.
+        let output_parameters: Vec<String> = interp_parameter
+            .split(",")
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+        if output_parameters.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No interpolation parameter (--parameter) was specified.",
+            ));
+        }
+        let num_outputs = output_parameters.len();
+
         let start = Instant::now();
 
         let (all_returns, late_returns, early_returns): (bool, bool, bool);
@@ -334,29 +548,20 @@ impl WhiteboxTool for LidarTINGridding {
                 return Err(Error::new(ErrorKind::InvalidInput,
                     "This tool must be run by specifying either an individual input file or a working directory."));
             }
+            // the output file may be a template used to name the output of each input file,
+            // e.g. '{input_stem}_dem.tif'
+            let output_template = if is_output_template(&output_file) {
+                output_file.clone()
+            } else {
+                self.get_default_output_template().unwrap()
+            };
             if std::path::Path::new(&working_directory).is_dir() {
-                for entry in fs::read_dir(working_directory.clone())? {
-                    let s = entry?
-                    .path()
-                    .into_os_string()
-                    .to_str()
-                    .expect("Error reading path string")
-                    .to_string();
-                    if s.to_lowercase().ends_with(".las") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".las", ".tif")
-                                .replace(".LAS", ".tif"),
-                        )
-                    } else if s.to_lowercase().ends_with(".zip") {
-                        inputs.push(s);
-                        outputs.push(
-                            inputs[inputs.len() - 1]
-                                .replace(".zip", ".tif")
-                                .replace(".ZIP", ".tif"),
-                        )
-                    }
+                for s in file_filter.find_lidar_files(working_directory)? {
+                    let mut out_file =
+                        expand_output_template(&output_template, &s, &self.get_tool_name());
+                    out_file = resolve_path(&out_file, working_directory);
+                    inputs.push(s);
+                    outputs.push(out_file);
                 }
             } else {
                 return Err(Error::new(
@@ -365,19 +570,16 @@ impl WhiteboxTool for LidarTINGridding {
                 ));
             }
         } else {
-            if !input_file.contains(path::MAIN_SEPARATOR) && !input_file.contains("/") {
-                input_file = format!("{}{}", working_directory, input_file);
-            }
+            input_file = resolve_path(&input_file, working_directory);
             inputs.push(input_file.clone());
             if output_file.is_empty() {
-                output_file = input_file
-                    .clone()
-                    .replace(".las", ".tif")
-                    .replace(".LAS", ".tif");
-            }
-            if !output_file.contains(path::MAIN_SEPARATOR) && !output_file.contains("/") {
-                output_file = format!("{}{}", working_directory, output_file);
+                output_file = expand_output_template(
+                    &self.get_default_output_template().unwrap(),
+                    &input_file,
+                    &self.get_tool_name(),
+                );
             }
+            output_file = resolve_path(&output_file, working_directory);
             outputs.push(output_file);
         }
 
@@ -397,12 +599,142 @@ impl WhiteboxTool for LidarTINGridding {
             });
         }
 
+        // check the grid resolution against the units of the point coordinates
+        let mut has_nir = false;
+        if !inputs.is_empty() {
+            let mut first_tile = LasFile::new(&inputs[0].replace("\"", ""), "rv")?;
+            let data_units = CoordinateUnits::from_las(&mut first_tile);
+            let (multiplier, message) = check_distance_units(
+                "grid resolution",
+                grid_res,
+                res_units,
+                data_units,
+                bounding_boxes[0],
+                convert_units,
+            );
+            if let Some(message) = message {
+                println!("{}", message);
+            }
+            grid_res *= multiplier;
+            max_triangle_edge_length *= multiplier * multiplier; // a squared distance
+            has_nir = first_tile.header.point_format == 8 || first_tile.header.point_format == 10;
+        }
+
+        // The multi-band colour output is interpolated from the individual colour channels. Each
+        // interpolated parameter is written to a band of one of the output rasters.
+        let mut colour_channels = vec!["red", "green", "blue"];
+        if has_nir {
+            colour_channels.push("nir");
+        }
+        let colour_bands = if multiband { colour_channels.len() } else { 0 };
+        let mut interp_parameters = vec![];
+        let mut targets: Vec<(usize, usize)> = vec![];
+        for (j, param) in output_parameters.iter().enumerate() {
+            if multiband && param == "rgb" {
+                for (band, channel) in colour_channels.iter().enumerate() {
+                    interp_parameters.push(channel.to_string());
+                    targets.push((j, band));
+                }
+            } else {
+                interp_parameters.push(param.clone());
+                targets.push((j, 0));
+            }
+        }
+        let num_params = interp_parameters.len();
+
+        // check that each input file contains the interpolation parameters
+        check_interpolation_parameters(&inputs, &interp_parameters)?;
+
+        let tiles_to_update = if incremental {
+            let tile_outputs: Vec<Vec<String>> = outputs
+                .iter()
+                .map(|f| {
+                    output_parameters
+                        .iter()
+                        .map(|param| parameter_output_file(f, param, num_outputs))
+                        .collect()
+                })
+                .collect();
+            find_tiles_to_update(&inputs, &tile_outputs, &bounding_boxes, search_radius)
+        } else {
+            (0..inputs.len()).collect()
+        };
+        let num_updates = tiles_to_update.len();
+        if verbose && incremental {
+            println!(
+                "{} of {} tiles require updating.",
+                num_updates,
+                inputs.len()
+            );
+        }
+
         if verbose {
             println!("Performing interpolation...");
         }
 
         let num_tiles = inputs.len();
-        let tile_list = Arc::new(Mutex::new(0..num_tiles));
+
+        // The tiles overlapping the buffered extent of each tile supply the points needed to
+        // avoid edge effects. Each file is read once and its points are cached until all of the
+        // tiles that it neighbours have been interpolated.
+        let tile_neighbours: Vec<Vec<usize>> = (0..num_tiles)
+            .map(|tile| {
+                let mut bb = bounding_boxes[tile];
+                bb.expand_by(search_radius);
+                (0..num_tiles)
+                    .filter(|&m| bounding_boxes[m].overlaps(bb))
+                    .collect()
+            })
+            .collect();
+        let mut remaining_uses = vec![0usize; num_tiles];
+        for &tile in &tiles_to_update {
+            for &m in &tile_neighbours[tile] {
+                remaining_uses[m] += 1;
+            }
+        }
+        let point_cache = Arc::new(TilePointCache::new(remaining_uses));
+        let tile_neighbours = Arc::new(tile_neighbours);
+        let selection = Arc::new(PointSelection {
+            interp_parameters: interp_parameters.clone(),
+            all_returns: all_returns,
+            late_returns: late_returns,
+            early_returns: early_returns,
+            include_class_vals: include_class_vals.clone(),
+            min_z: min_z,
+            max_z: max_z,
+            filter_by_time: filter_by_time,
+            min_time: min_time,
+            max_time: max_time,
+        });
+
+        // Interpolating the tiles in row-major order allows cached points to be released sooner.
+        let mut tiles_to_update = tiles_to_update;
+        if num_updates > 1 {
+            let mean_height = bounding_boxes.iter().map(|bb| bb.get_height()).sum::<f64>()
+                / num_tiles as f64;
+            let north = bounding_boxes
+                .iter()
+                .map(|bb| bb.max_y)
+                .fold(f64::NEG_INFINITY, f64::max);
+            let tile_row = |tile: usize| {
+                let centre_y = (bounding_boxes[tile].min_y + bounding_boxes[tile].max_y) / 2f64;
+                if mean_height > 0f64 {
+                    ((north - centre_y) / mean_height).floor() as i64
+                } else {
+                    0i64
+                }
+            };
+            tiles_to_update.sort_by(|&a, &b| {
+                tile_row(a).cmp(&tile_row(b)).then(
+                    bounding_boxes[a]
+                        .min_x
+                        .partial_cmp(&bounding_boxes[b].min_x)
+                        .unwrap_or(std::cmp::Ordering::Equal),
+                )
+            });
+        }
+
+        let tile_list = Arc::new(Mutex::new(tiles_to_update.into_iter()));
         let inputs = Arc::new(inputs);
         let outputs = Arc::new(outputs);
         let bounding_boxes = Arc::new(bounding_boxes);
@@ -413,13 +745,17 @@ impl WhiteboxTool for LidarTINGridding {
             let outputs = outputs.clone();
             let bounding_boxes = bounding_boxes.clone();
             let tile_list = tile_list.clone();
+            let tile_neighbours = tile_neighbours.clone();
+            let point_cache = point_cache.clone();
+            let selection = selection.clone();
             // copy over the string parameters
-            let interp_parameter = interp_parameter.clone();
+            let output_parameters = output_parameters.clone();
+            let targets = targets.clone();
+            let colour_channels = colour_channels.clone();
             // let palette = palette.clone();
             let return_type = return_type.clone();
             let tool_name = self.get_tool_name();
             let exclude_cls_str = exclude_cls_str.clone();
-            let include_class_vals = include_class_vals.clone();
             let tx2 = tx2.clone();
             thread::spawn(move || {
                 let mut tile = 0;
@@ -443,259 +779,31 @@ impl WhiteboxTool for LidarTINGridding {
                     };
 
                     let mut points = vec![];
-                    let mut z_values = vec![];
+                    // one set of values for each interpolation parameter
+                    let mut z_values: Vec<Vec<f64>> = vec![vec![]; num_params];
+                    let mut is_rgb = vec![false; num_params];
 
                     if verbose && inputs.len() == 1 {
                         println!("Reading input LAS file...");
                     }
 
-                    let mut progress: i32;
-                    let mut old_progress: i32 = -1;
-
-                    for m in 0..inputs.len() {
-                        if bounding_boxes[m].overlaps(bb) {
-                            let input =
-                                match LasFile::new(&inputs[m].replace("\"", "").clone(), "r") {
-                                    Ok(lf) => lf,
-                                    Err(err) => panic!(
-                                        "Error reading file {}: {}",
-                                        inputs[m].replace("\"", ""),
-                                        err
-                                    ),
-                                };
-
-                            let n_points = input.header.number_of_points as usize;
-                            let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
-
-                            match &interp_parameter as &str {
-                                "elevation" | "z" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.z);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "intensity" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.intensity as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "scan angle" | "scan_angle" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.scan_angle as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "class" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.classification() as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "return_number" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.return_number() as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "number_of_returns" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.number_of_returns() as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                "rgb" => {
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        // let val = input.get_rgb(i); // ((a << 24) | (b << 16) | (g << 8) | r) as f64;
-                                                        z_values.push(p.number_of_returns() as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
-                                }
-                                _ => {
-                                    // user data
-                                    for i in 0..n_points {
-                                        let p: PointData = input[i];
-                                        if !p.withheld() {
-                                            if all_returns
-                                                || (p.is_late_return() & late_returns)
-                                                || (p.is_early_return() & early_returns)
-                                            {
-                                                if include_class_vals[p.classification() as usize] {
-                                                    if bb.is_point_in_box(p.x, p.y)
-                                                        && p.z >= min_z
-                                                        && p.z <= max_z
-                                                    {
-                                                        points.push(Point2D { x: p.x, y: p.y });
-                                                        z_values.push(p.user_data as f64);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if verbose && inputs.len() == 1 {
-                                            progress = (100.0_f64 * i as f64 / num_points) as i32;
-                                            if progress != old_progress {
-                                                println!("Reading points: {}%", progress);
-                                                old_progress = progress;
-                                            }
-                                        }
-                                    }
+                    for &m in &tile_neighbours[tile] {
+                        let tile_points = point_cache.get(m, || {
+                            selection.read(&inputs[m].replace("\"", ""), verbose && inputs.len() == 1)
+                        });
+                        for k in 0..num_params {
+                            is_rgb[k] |= tile_points.is_rgb[k];
+                        }
+                        // only the points within the buffered extent are needed from neighbouring tiles
+                        for (i, p) in tile_points.points.iter().enumerate() {
+                            if bb.is_point_in_box(p.x, p.y) {
+                                points.push(p.clone());
+                                for k in 0..num_params {
+                                    z_values[k].push(tile_points.z_values[k][i]);
                                 }
                             }
                         }
+                        point_cache.release(m);
                     }
 
                     if points.len() == 0 {
@@ -703,6 +811,7 @@ impl WhiteboxTool for LidarTINGridding {
                             println!("No points found in {}", inputs[tile].clone());
                         }
                         tx2.send(tile).unwrap();
+                        continue;
                     }
 
                     let west: f64 = bounding_boxes[tile].min_x;
@@ -730,10 +839,26 @@ impl WhiteboxTool for LidarTINGridding {
                     configs.data_type = DataType::F32;
                     configs.photometric_interp = PhotometricInterpretation::Continuous;
 
-                    let mut output = Raster::initialize_using_config(&output_file, &configs);
-                    if interp_parameter == "rgb" {
-                        output.configs.photometric_interp = PhotometricInterpretation::RGB;
-                        output.configs.data_type = DataType::RGBA32;
+                    // When there are multiple parameters, each output file name is suffixed by its parameter.
+                    let mut output_rasters = Vec::with_capacity(num_outputs);
+                    for k in 0..num_params {
+                        let (j, band) = targets[k];
+                        if band > 0 {
+                            continue; // the band belongs to the preceding multi-band output
+                        }
+                        let file_name =
+                            parameter_output_file(&output_file, &output_parameters[j], num_outputs);
+                        let mut output = Raster::initialize_using_config(&file_name, &configs);
+                        if is_rgb[k] {
+                            output.configs.photometric_interp = PhotometricInterpretation::RGB;
+                            output.configs.data_type = DataType::RGBA32;
+                        } else if colour_bands > 0 && output_parameters[j] == "rgb" {
+                            output.configs.photometric_interp = PhotometricInterpretation::RGB;
+                            output.configs.data_type = DataType::U16;
+                            output.configs.nodata = 0f64;
+                            output.set_num_bands(colour_bands as u8);
+                        }
+                        output_rasters.push(output);
                     }
 
                     // do the triangulation
@@ -741,195 +866,79 @@ impl WhiteboxTool for LidarTINGridding {
                         println!("Performing triangulation...");
                     }
                     let result = triangulate(&points).expect("No triangulation exists.");
-                    let num_triangles = result.triangles.len() / 3;
-
-                    let (mut p1, mut p2, mut p3): (usize, usize, usize);
-                    let (mut top, mut bottom, mut left, mut right): (f64, f64, f64, f64);
-
-                    let (mut top_row, mut bottom_row, mut left_col, mut right_col): (
-                        isize,
-                        isize,
-                        isize,
-                        isize,
-                    );
-                    let mut tri_points: Vec<Point2D> = vec![Point2D::new(0f64, 0f64); 4];
-                    let mut k: f64;
-                    let mut norm: Vector3<f64>;
-                    let (mut a, mut b, mut c): (Vector3<f64>, Vector3<f64>, Vector3<f64>);
-                    let (mut x, mut y): (f64, f64);
-                    let mut zn: f64;
-                    let mut i: usize;
-                    if !interp_parameter_is_rgb {
-                        for triangle in 0..num_triangles {
-                            i = triangle * 3;
-                            p1 = result.triangles[i];
-                            p2 = result.triangles[i + 1];
-                            p3 = result.triangles[i + 2];
-
-                            if max_distance_squared(points[p1], points[p2], points[p3], z_values[p1], 
-                                z_values[p2], z_values[p3]) < max_triangle_edge_length {
-
-                                tri_points[0] = points[p1].clone();
-                                tri_points[1] = points[p2].clone();
-                                tri_points[2] = points[p3].clone();
-                                tri_points[3] = points[p1].clone();
-                                
-                                // get the equation of the plane
-                                a = Vector3::new(tri_points[0].x, tri_points[0].y, z_values[p1]);
-                                b = Vector3::new(tri_points[1].x, tri_points[1].y, z_values[p2]);
-                                c = Vector3::new(tri_points[2].x, tri_points[2].y, z_values[p3]);
-                                norm = (b - a).cross(&(c - a));
-                                k = -(tri_points[0].x * norm.x
-                                    + tri_points[0].y * norm.y
-                                    + norm.z * z_values[p1]);
-
-                                // find grid intersections with this triangle
-                                bottom = points[p1].y.min(points[p2].y.min(points[p3].y));
-                                top = points[p1].y.max(points[p2].y.max(points[p3].y));
-                                left = points[p1].x.min(points[p2].x.min(points[p3].x));
-                                right = points[p1].x.max(points[p2].x.max(points[p3].x));
-
-                                bottom_row = ((north - bottom) / grid_res).ceil() as isize; // output.get_row_from_y(bottom);
-                                top_row = ((north - top) / grid_res).floor() as isize; // output.get_row_from_y(top);
-                                left_col = ((left - west) / grid_res).floor() as isize; // output.get_column_from_x(left);
-                                right_col = ((right - west) / grid_res).ceil() as isize; // output.get_column_from_x(right);
-
-                                for row in top_row..=bottom_row {
-                                    for col in left_col..=right_col {
-                                        x = west + col as f64 * grid_res;
-                                        y = north - row as f64 * grid_res;
-                                        if point_in_poly(&Point2D::new(x, y), &tri_points) {
-                                            // calculate the z values
-                                            zn = -(norm.x * x + norm.y * y + k) / norm.z;
-                                            output.set_value(row, col, zn);
-                                        }
-                                    }
-                                }
-
-                                if verbose && num_tiles == 1 {
-                                    progress =
-                                        (100.0_f64 * triangle as f64 / (num_triangles - 1) as f64) as i32;
-                                    if progress != old_progress {
-                                        println!("Progress: {}%", progress);
-                                        old_progress = progress;
-                                    }
-                                }
-                            }
-                        }
+                    let nn_interpolator = if natural_neighbour {
+                        Some(NaturalNeighbourInterpolator::new(&points, &result))
                     } else {
-                        let (mut k_r, mut k_g, mut k_b): (f64, f64, f64);
-                        let (mut norm_r, mut norm_g, mut norm_b): (Vector3<f64>, Vector3<f64>, Vector3<f64>);
-                        let (mut red, mut green, mut blue): (f64, f64, f64);
-                        for triangle in 0..num_triangles {
-                            i = triangle * 3;
-                            p1 = result.triangles[i];
-                            p2 = result.triangles[i + 1];
-                            p3 = result.triangles[i + 2];
-
-                            if max_distance_squared(points[p1], points[p2], points[p3], z_values[p1], 
-                                z_values[p2], z_values[p3]) < max_triangle_edge_length {
-
-                                tri_points[0] = points[p1].clone();
-                                tri_points[1] = points[p2].clone();
-                                tri_points[2] = points[p3].clone();
-                                tri_points[3] = points[p1].clone();
-                                
-                                // get the equation of the plane
-                                red = (z_values[p1] as u32 & 0xFF) as f64;
-                                a = Vector3::new(tri_points[0].x, tri_points[0].y, red);
-                                red = (z_values[p2] as u32 & 0xFF) as f64;
-                                b = Vector3::new(tri_points[1].x, tri_points[1].y, red);
-                                red = (z_values[p3] as u32 & 0xFF) as f64;
-                                c = Vector3::new(tri_points[2].x, tri_points[2].y, red);
-                                norm_r = (b - a).cross(&(c - a));
-                                k_r = -(tri_points[2].x * norm_r.x
-                                    + tri_points[2].y * norm_r.y
-                                    + norm_r.z * red);
-
-                                green = ((z_values[p1] as u32 >> 8) & 0xFF) as f64;
-                                a = Vector3::new(tri_points[0].x, tri_points[0].y, green);
-                                green = ((z_values[p2] as u32 >> 8) & 0xFF) as f64;
-                                b = Vector3::new(tri_points[1].x, tri_points[1].y, green);
-                                green = ((z_values[p3] as u32 >> 8) & 0xFF) as f64;
-                                c = Vector3::new(tri_points[2].x, tri_points[2].y, green);
-                                norm_g = (b - a).cross(&(c - a));
-                                k_g = -(tri_points[2].x * norm_g.x
-                                    + tri_points[2].y * norm_g.y
-                                    + norm_g.z * green);
-
-                                blue = ((z_values[p1] as u32 >> 16) & 0xFF) as f64;
-                                a = Vector3::new(tri_points[0].x, tri_points[0].y, blue);
-                                blue = ((z_values[p2] as u32 >> 16) & 0xFF) as f64;
-                                b = Vector3::new(tri_points[1].x, tri_points[1].y, blue);
-                                blue = ((z_values[p3] as u32 >> 16) & 0xFF) as f64;
-                                c = Vector3::new(tri_points[2].x, tri_points[2].y, blue);
-                                norm_b = (b - a).cross(&(c - a));
-                                k_b = -(tri_points[2].x * norm_b.x
-                                    + tri_points[2].y * norm_b.y
-                                    + norm_b.z * blue);
-
-                                // find grid intersections with this triangle
-                                bottom = points[p1].y.min(points[p2].y.min(points[p3].y));
-                                top = points[p1].y.max(points[p2].y.max(points[p3].y));
-                                left = points[p1].x.min(points[p2].x.min(points[p3].x));
-                                right = points[p1].x.max(points[p2].x.max(points[p3].x));
-
-                                bottom_row = ((north - bottom) / grid_res).ceil() as isize; // output.get_row_from_y(bottom);
-                                top_row = ((north - top) / grid_res).floor() as isize; // output.get_row_from_y(top);
-                                left_col = ((left - west) / grid_res).floor() as isize; // output.get_column_from_x(left);
-                                right_col = ((right - west) / grid_res).ceil() as isize; // output.get_column_from_x(right);
-
-                                for row in top_row..=bottom_row {
-                                    for col in left_col..=right_col {
-                                        x = west + col as f64 * grid_res;
-                                        y = north - row as f64 * grid_res;
-                                        if point_in_poly(&Point2D::new(x, y), &tri_points) {
-                                            // calculate the colour values
-                                            red = -(norm_r.x * x + norm_r.y * y + k_r) / norm_r.z;
-                                            green = -(norm_g.x * x + norm_g.y * y + k_g) / norm_g.z;
-                                            blue = -(norm_b.x * x + norm_b.y * y + k_b) / norm_b.z;
-                                            zn = ((255u32 << 24) | ((blue.round() as u32) << 16) | ((green.round() as u32) << 8) | (red.round() as u32)) as f64;
-                                            output.set_value(row, col, zn);
-                                        }
-                                    }
-                                }
+                        None
+                    };
 
-                                if verbose && num_tiles == 1 {
-                                    progress =
-                                        (100.0_f64 * triangle as f64 / (num_triangles - 1) as f64) as i32;
-                                    if progress != old_progress {
-                                        println!("Progress: {}%", progress);
-                                        old_progress = progress;
-                                    }
-                                }
-                            }
-                        }
-                    }
+                    // The triangles are rasterized in blocks of rows. When a single tile is being
+                    // interpolated, there is no tile-level parallelism to exploit and so the blocks
+                    // are instead shared among the available threads.
+                    let surface = TinInterpolator {
+                        points: &points,
+                        z_values: &z_values,
+                        is_rgb: &is_rgb,
+                        triangles: &result.triangles,
+                        nn_interpolator: nn_interpolator.as_ref(),
+                        max_triangle_edge_length: max_triangle_edge_length,
+                        west: west,
+                        north: north,
+                        grid_res: grid_res,
+                        columns: columns,
+                        nodata: nodata,
+                    };
+                    let num_threads = if num_tiles == 1 { num_procs2 } else { 1 };
+                    surface.rasterize_rows(rows, num_threads, verbose, |start_row, end_row, data| {
+                        store_rows(&mut output_rasters, &targets, &data, start_row, end_row, nodata);
+                    });
 
                     let elapsed_time_run = get_formatted_elapsed_time(start_run);
-                    output.add_metadata_entry(format!(
-                        "Created by whitebox_tools\' {} tool",
-                        tool_name
-                    ));
-                    output.add_metadata_entry(format!("Input file: {}", input_file));
-                    output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
-                    output.add_metadata_entry(format!("Search radius: {}", search_radius));
-                    output.add_metadata_entry(format!(
-                        "Interpolation parameter: {}",
-                        interp_parameter
-                    ));
-                    output.add_metadata_entry(format!("Returns: {}", return_type));
-                    output.add_metadata_entry(format!("Excluded classes: {}", exclude_cls_str));
-                    output.add_metadata_entry(format!(
-                        "Elapsed Time (including I/O): {}",
-                        elapsed_time_run
-                    ));
+                    for (j, output) in output_rasters.iter_mut().enumerate() {
+                        output.add_metadata_entry(format!(
+                            "Created by whitebox_tools\' {} tool",
+                            tool_name
+                        ));
+                        output.add_metadata_entry(format!("Input file: {}", input_file));
+                        output.add_metadata_entry(format!("Grid resolution: {}", grid_res));
+                        output.add_metadata_entry(format!("Search radius: {}", search_radius));
+                        if output.configs.bands > 1 {
+                            output.add_metadata_entry(format!(
+                                "Interpolation parameter: {} ({} bands)",
+                                output_parameters[j],
+                                colour_channels.join(", ")
+                            ));
+                        } else {
+                            output.add_metadata_entry(format!(
+                                "Interpolation parameter: {}",
+                                output_parameters[j]
+                            ));
+                        }
+                        output.add_metadata_entry(format!("Returns: {}", return_type));
+                        output.add_metadata_entry(format!(
+                            "Interpolation method: {}",
+                            if natural_neighbour { "natural neighbour" } else { "linear" }
+                        ));
+                        output.add_metadata_entry(format!("Excluded classes: {}", exclude_cls_str));
+                        if filter_by_time {
+                            output.add_metadata_entry(format!(
+                                "GPS time range: {} to {}",
+                                min_time, max_time
+                            ));
+                        }
+                        output.add_metadata_entry(format!(
+                            "Elapsed Time (including I/O): {}",
+                            elapsed_time_run
+                        ));
+                    }
 
                     if verbose && inputs.len() == 1 {
                         println!("Saving data...")
                     };
 
-                    let _ = output.write().unwrap();
+                    for output in output_rasters.iter_mut() {
+                        let _ = output.write().unwrap();
+                    }
 
                     tx2.send(tile).unwrap();
                 }
@@ -938,7 +947,7 @@ impl WhiteboxTool for LidarTINGridding {
 
         let mut progress: i32;
         let mut old_progress: i32 = -1;
-        for tile in 0..inputs.len() {
+        for tile in 0..num_updates {
             let tile_completed = rx2.recv().unwrap();
             if verbose {
                 println!(
@@ -948,11 +957,11 @@ impl WhiteboxTool for LidarTINGridding {
                         .replace(working_directory, "")
                         .replace(".las", ""),
                     tile + 1,
-                    inputs.len()
+                    num_updates
                 );
             }
             if verbose {
-                progress = (100.0_f64 * tile as f64 / (inputs.len() - 1) as f64) as i32;
+                progress = (100.0_f64 * tile as f64 / (num_updates - 1) as f64) as i32;
                 if progress != old_progress {
                     println!("Progress: {}%", progress);
                     old_progress = progress;
@@ -973,30 +982,175 @@ impl WhiteboxTool for LidarTINGridding {
     }
 }
 
-/// Calculate squared Euclidean distance between the point and another.
-pub fn max_distance_squared(p1: Point2D, p2: Point2D, p3: Point2D, z1: f64, z2: f64, z3: f64) -> f64 {
-    let mut dx = p1.x - p2.x;
-    let mut dy = p1.y - p2.y;
-    let mut dz = z1 - z2;
-    let mut max_dist = dx * dx + dy * dy + dz * dz;
+/// The criteria used to select the points, and their interpolation parameters, from the input files.
+struct PointSelection {
+    interp_parameters: Vec<String>,
+    all_returns: bool,
+    late_returns: bool,
+    early_returns: bool,
+    include_class_vals: Vec<bool>,
+    min_z: f64,
+    max_z: f64,
+    filter_by_time: bool,
+    min_time: f64,
+    max_time: f64,
+}
+
+/// The selected points of an input file and their interpolation parameter values.
+struct TilePoints {
+    points: Vec<Point2D>,
+    /// One set of values for each interpolation parameter
+    z_values: Vec<Vec<f64>>,
+    is_rgb: Vec<bool>,
+}
+
+impl PointSelection {
+    /// Reads the selected points of a LAS file. Points lacking a value for any of the
+    /// interpolation parameters, such as those with no-data extra bytes values, are excluded.
+    fn read(&self, las_file_name: &str, show_progress: bool) -> TilePoints {
+        let num_params = self.interp_parameters.len();
+        let mut ret = TilePoints {
+            points: vec![],
+            z_values: vec![vec![]; num_params],
+            is_rgb: vec![false; num_params],
+        };
+        let input = match LasFile::new(las_file_name, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", las_file_name, err),
+        };
+
+        if self.filter_by_time && !input.has_gps_time() {
+            panic!(
+                "The file {} does not contain GPS time data, which is required by --min_time and --max_time.",
+                las_file_name
+            );
+        }
+        let n_points = input.header.number_of_points as usize;
+        if n_points == 0 {
+            return ret;
+        }
+        let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
+
+        // the parameters were checked before the tiles were interpolated
+        let attributes = match input.get_point_attributes(&self.interp_parameters) {
+            Ok(attributes) => attributes,
+            Err(err) => panic!("Error reading file {}: {}", las_file_name, err),
+        };
+        for k in 0..num_params {
+            ret.is_rgb[k] = attributes[k] == PointAttribute::Rgb;
+        }
+
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+        let mut values = vec![0f64; num_params];
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            if !p.withheld() {
+                if self.all_returns
+                    || (p.is_late_return() & self.late_returns)
+                    || (p.is_early_return() & self.early_returns)
+                {
+                    if self.include_class_vals[p.classification() as usize] {
+                        if p.z >= self.min_z
+                            && p.z <= self.max_z
+                            && input.is_gps_time_in_range(i, self.min_time, self.max_time)
+                        {
+                            for k in 0..num_params {
+                                values[k] = input.get_point_attribute_value(i, attributes[k]);
+                            }
+                            if values.iter().all(|v| !v.is_nan()) {
+                                ret.points.push(Point2D { x: p.x, y: p.y });
+                                for k in 0..num_params {
+                                    ret.z_values[k].push(values[k]);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if show_progress {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Reading points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        ret
+    }
+}
 
-    dx = p1.x - p3.x;
-    dy = p1.y - p3.y;
-    dz = z1 - z3;
-    let mut dist = dx * dx + dy * dy + dz * dz;
+/// A cache of the points read from the input files, shared by the interpolation threads, so that
+/// each file is read once per run rather than once for every tile that it neighbours. The entry
+/// of a file is dropped once all of the tiles that use it have been interpolated.
+struct TilePointCache {
+    entries: Mutex<HashMap<usize, Arc<OnceLock<Arc<TilePoints>>>>>,
+    remaining_uses: Mutex<Vec<usize>>,
+}
 
-    if dist > max_dist {
-        max_dist = dist
+impl TilePointCache {
+    /// Creates a cache, given the number of tiles that will use the points of each file.
+    fn new(remaining_uses: Vec<usize>) -> TilePointCache {
+        TilePointCache {
+            entries: Mutex::new(HashMap::new()),
+            remaining_uses: Mutex::new(remaining_uses),
+        }
     }
 
-    dx = p2.x - p3.x;
-    dy = p2.y - p3.y;
-    dz = z2 - z3;
-    dist = dx * dx + dy * dy + dz * dz;
+    /// Returns the points of a file, calling `read` if they are not already cached. Threads
+    /// requesting a file that is being read by another thread wait for the read to complete.
+    fn get<F: FnOnce() -> TilePoints>(&self, file: usize, read: F) -> Arc<TilePoints> {
+        let entry = self
+            .entries
+            .lock()
+            .unwrap()
+            .entry(file)
+            .or_insert_with(|| Arc::new(OnceLock::new()))
+            .clone();
+        entry.get_or_init(|| Arc::new(read())).clone()
+    }
 
-    if dist > max_dist {
-        max_dist = dist
+    /// Records that a tile has finished using the points of a file.
+    fn release(&self, file: usize) {
+        let mut remaining_uses = self.remaining_uses.lock().unwrap();
+        remaining_uses[file] = remaining_uses[file].saturating_sub(1);
+        if remaining_uses[file] == 0 {
+            self.entries.lock().unwrap().remove(&file);
+        }
     }
+}
 
-    max_dist
-}
\ No newline at end of file
+/// Copies the interpolated values of the grid rows from `start_row` up to, but not including,
+/// `end_row` into the output rasters. `targets` gives the output raster and band of each
+/// interpolation parameter. Values written to a multi-band colour raster are rounded to the
+/// 16-bit range, and cells outside of the TIN are assigned the raster's nodata value.
+fn store_rows(
+    output_rasters: &mut [Raster],
+    targets: &[(usize, usize)],
+    data: &[Vec<f64>],
+    start_row: isize,
+    end_row: isize,
+    nodata: f64,
+) {
+    for (k, &(j, band)) in targets.iter().enumerate() {
+        let output = &mut output_rasters[j];
+        let columns = output.configs.columns;
+        for row in start_row..end_row {
+            let offset = (row - start_row) as usize * columns;
+            let values = &data[k][offset..offset + columns];
+            if output.configs.bands > 1 {
+                let out_nodata = output.configs.nodata;
+                for (col, &value) in values.iter().enumerate() {
+                    let value = if value == nodata {
+                        out_nodata
+                    } else {
+                        value.round().max(0f64).min(65535f64)
+                    };
+                    output.set_band_value(band, row, col as isize, value);
+                }
+            } else {
+                output.set_row_data(row, values.to_vec());
+            }
+        }
+    }
+}
@@ -0,0 +1,769 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::algorithms::triangulate;
+use crate::lidar::*;
+use crate::structures::Point2D;
+use crate::tools::*;
+use std::collections::HashMap;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::path;
+
+/// This tool creates a Delaunay triangular irregular network (TIN) from a LiDAR point cloud and
+/// writes it as a 3D mesh, for use in 3D visualization software and in modelling packages (e.g.
+/// computational fluid dynamics or solar radiation models) that require a surface mesh. The
+/// triangulation is the same as that used by the `LidarTINGridding` tool, and points may similarly
+/// be excluded based on their return type (`--returns`), class (`--exclude_cls`), and elevation
+/// (`--minz` and `--maxz`). Triangles with edges longer than the `--max_triangle_edge_length` are
+/// excluded from the mesh, which prevents the long, thin triangles that otherwise span data gaps
+/// and the concave edges of the point cloud. Withheld points are always excluded.
+///
+/// The mesh format is determined by the extension of the output file. The supported formats are
+/// Wavefront OBJ (*.obj*), binary STL (*.stl*), and glTF 2.0, either as a JSON file with an
+/// accompanying binary buffer (*.gltf* and *.bin*) or a single binary file (*.glb*).
+///
+/// The density of the mesh may be reduced by specifying a decimation resolution
+/// (`--decimation_res`). In this case, the point cloud is thinned prior to triangulation, such
+/// that only the point nearest the centre of each grid cell of the specified size is retained.
+/// When the `--colours` flag is specified, the RGB colours of the points are stored as vertex
+/// colours in the OBJ and glTF formats; this requires an input file with a point format that
+/// includes colour data. The STL format does not support vertex colours.
+///
+/// The glTF and STL formats store vertex coordinates as single-precision values, which cannot
+/// represent projected coordinates (e.g. UTM) with sub-metre precision. Thus, glTF vertices are
+/// stored relative to the minimum corner of the point cloud, with this offset stored as the
+/// translation of the mesh node. Because glTF uses a y-up coordinate system, the point cloud's
+/// elevation is stored as the y coordinate and its northing as the negative z coordinate. The
+/// `--local_origin` flag may be used to also subtract this offset from the vertices of OBJ and STL
+/// outputs, which do not possess a means of storing the offset; the offset is reported by the tool.
+///
+/// # See Also
+/// `LidarTINGridding`, `LidarThin`
+pub struct LidarTINToMesh {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarTINToMesh {
+    pub fn new() -> LidarTINToMesh {
+        // public constructor
+        let name = "LidarTINToMesh".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description =
+            "Exports a Delaunay TIN fitted to LiDAR points as an OBJ, STL, or glTF 3D mesh."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output mesh file (*.obj, *.stl, *.gltf, or *.glb).".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Any),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Point Returns Included".to_owned(),
+            flags: vec!["--returns".to_owned()],
+            description:
+                "Point return types to include; options are 'all' (default), 'last', 'first'."
+                    .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "all".to_owned(),
+                "last".to_owned(),
+                "first".to_owned(),
+            ]),
+            default_value: Some("all".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Exclusion Classes (0-18, based on LAS spec; e.g. 3,4,5,6,7)".to_owned(),
+            flags: vec!["--exclude_cls".to_owned()],
+            description: "Optional exclude classes from the mesh; Valid class values range from 0 to 18, based on LAS specifications. Example, --exclude_cls='3,4,5,6,7,18'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Elevation Value (optional)".to_owned(),
+            flags: vec!["--minz".to_owned()],
+            description: "Optional minimum elevation for inclusion in the mesh.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Elevation Value (optional)".to_owned(),
+            flags: vec!["--maxz".to_owned()],
+            description: "Optional maximum elevation for inclusion in the mesh.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Triangle Edge Length (optional)".to_owned(),
+            flags: vec!["--max_triangle_edge_length".to_owned()],
+            description: "Optional maximum triangle edge length; triangles larger than this size will not be included in the mesh.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Decimation Resolution (optional)".to_owned(),
+            flags: vec!["--decimation_res".to_owned()],
+            description: "Optional grid resolution used to thin the points prior to triangulation."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Store RGB vertex colours?".to_owned(),
+            flags: vec!["--colours".to_owned()],
+            description: "Flag indicating whether to store the point RGB values as vertex colours."
+                .to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Use a local origin for OBJ and STL vertices?".to_owned(),
+            flags: vec!["--local_origin".to_owned()],
+            description: "Flag indicating whether OBJ and STL vertices are stored relative to the minimum corner of the point cloud.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=surface.obj --returns=last --exclude_cls='3,4,5,6,7,18' --max_triangle_edge_length=5.0
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=file.las -o=surface.glb --decimation_res=2.0 --colours", short_exe, name).replace("*", &sep);
+
+        LidarTINToMesh {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarTINToMesh {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut return_type = "all".to_string();
+        let mut include_class_vals = vec![true; 256];
+        let mut max_z = f64::INFINITY;
+        let mut min_z = f64::NEG_INFINITY;
+        let mut max_triangle_edge_length = f64::INFINITY;
+        let mut decimation_res = 0f64;
+        let mut use_colours = false;
+        let mut local_origin = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-returns" {
+                return_type = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-exclude_cls" {
+                let exclude_cls_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                for value in exclude_cls_str.split([',', ';']) {
+                    if !value.trim().is_empty() {
+                        let c = value.trim().parse::<usize>().map_err(|_| {
+                            Error::new(
+                                ErrorKind::InvalidInput,
+                                format!("Invalid class value '{}'.", value.trim()),
+                            )
+                        })?;
+                        if c < 256 {
+                            include_class_vals[c] = false;
+                        }
+                    }
+                }
+            } else if flag_val == "-minz" {
+                min_z = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-maxz" {
+                max_z = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_triangle_edge_length" {
+                max_triangle_edge_length = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-decimation_res" {
+                decimation_res = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-colours" || flag_val == "-colors" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    use_colours = true;
+                }
+            } else if flag_val == "-local_origin" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    local_origin = true;
+                }
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        let format = match MeshFormat::from_file_name(&output_file) {
+            Some(f) => f,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The output file must have an *.obj, *.stl, *.gltf, or *.glb extension.",
+                ))
+            }
+        };
+        if use_colours && format == MeshFormat::Stl {
+            println!("Warning: The STL format does not support vertex colours; colours will not be output.");
+            use_colours = false;
+        }
+
+        let (all_returns, late_returns, early_returns) = match return_type.as_str() {
+            "last" => (false, true, false),
+            "first" => (false, false, true),
+            _ => (true, false, false),
+        };
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let input = LasFile::new(&input_file, "r")?;
+
+        let start = Instant::now();
+
+        if use_colours && !input.has_rgb() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The --colours flag was specified but the input file does not contain RGB data.",
+            ));
+        }
+
+        // select the points to be triangulated
+        let n_points = input.header.number_of_points as usize;
+        let mut point_indices = vec![];
+        for i in 0..n_points {
+            let p: PointData = input[i];
+            if !p.withheld()
+                && (all_returns
+                    || (p.is_late_return() & late_returns)
+                    || (p.is_early_return() & early_returns))
+                && include_class_vals[p.classification() as usize]
+                && p.z >= min_z
+                && p.z <= max_z
+            {
+                point_indices.push(i);
+            }
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / n_points as f64) as usize;
+                if progress != old_progress {
+                    println!("Reading points: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if decimation_res > 0f64 {
+            // retain the point nearest the centre of each grid cell
+            let (west, south) = (input.header.min_x, input.header.min_y);
+            let mut cells: HashMap<(i64, i64), (usize, f64)> = HashMap::new();
+            for &i in &point_indices {
+                let p = input[i];
+                let col = ((p.x - west) / decimation_res).floor();
+                let row = ((p.y - south) / decimation_res).floor();
+                let dx = p.x - (west + (col + 0.5) * decimation_res);
+                let dy = p.y - (south + (row + 0.5) * decimation_res);
+                let dist = dx * dx + dy * dy;
+                let entry = cells.entry((col as i64, row as i64)).or_insert((i, dist));
+                if dist < entry.1 {
+                    *entry = (i, dist);
+                }
+            }
+            point_indices = cells.values().map(|v| v.0).collect();
+            point_indices.sort_unstable();
+            if verbose {
+                println!("Number of points after decimation: {}", point_indices.len());
+            }
+        }
+
+        if point_indices.len() < 3 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Fewer than three points were selected for triangulation.",
+            ));
+        }
+
+        if verbose {
+            println!("Performing triangulation...");
+        }
+        let points: Vec<Point2D> = point_indices
+            .iter()
+            .map(|&i| Point2D::new(input[i].x, input[i].y))
+            .collect();
+        let result = match triangulate(&points) {
+            Some(t) => t,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "No triangulation exists for the selected points; they may be collinear.",
+                ))
+            }
+        };
+
+        // remove long-edged triangles and the vertices that are not used by any remaining triangle
+        let max_edge_sqrd = max_triangle_edge_length * max_triangle_edge_length;
+        let edge_sqrd = |a: usize, b: usize| {
+            let (pa, pb) = (&points[a], &points[b]);
+            (pa.x - pb.x) * (pa.x - pb.x) + (pa.y - pb.y) * (pa.y - pb.y)
+        };
+        let mut vertex_ids = vec![u32::MAX; points.len()];
+        let mut mesh = Mesh::default();
+        for tri in result.triangles.chunks(3) {
+            if edge_sqrd(tri[0], tri[1]) > max_edge_sqrd
+                || edge_sqrd(tri[1], tri[2]) > max_edge_sqrd
+                || edge_sqrd(tri[2], tri[0]) > max_edge_sqrd
+            {
+                continue;
+            }
+            // the triangulation's triangles are clockwise; mesh faces are counter-clockwise when
+            // viewed from above, such that their normals point upwards
+            for &v in [tri[0], tri[2], tri[1]].iter() {
+                if vertex_ids[v] == u32::MAX {
+                    vertex_ids[v] = mesh.vertices.len() as u32;
+                    let p = input[point_indices[v]];
+                    mesh.vertices.push([p.x, p.y, p.z]);
+                    if use_colours {
+                        let c = input.get_rgb(point_indices[v])?;
+                        mesh.colours.push([c.red, c.green, c.blue]);
+                    }
+                }
+                mesh.indices.push(vertex_ids[v]);
+            }
+        }
+        if mesh.indices.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No triangles satisfy the maximum triangle edge length criterion.",
+            ));
+        }
+        mesh.origin = [
+            input.header.min_x,
+            input.header.min_y,
+            mesh.vertices.iter().fold(f64::INFINITY, |m, v| m.min(v[2])),
+        ];
+        // coordinates are written with the precision of the input file's scale factors
+        let min_scale = input
+            .header
+            .x_scale_factor
+            .min(input.header.y_scale_factor)
+            .min(input.header.z_scale_factor);
+        mesh.decimals = if min_scale > 0f64 && min_scale < 1f64 {
+            (-min_scale.log10()).ceil().min(9f64) as usize
+        } else {
+            0
+        };
+
+        if verbose {
+            println!(
+                "Number of vertices: {}\nNumber of triangles: {}",
+                mesh.vertices.len(),
+                mesh.indices.len() / 3
+            );
+            if format == MeshFormat::Gltf || format == MeshFormat::Glb || local_origin {
+                println!(
+                    "Vertex coordinates are relative to the origin ({}, {}, {})",
+                    mesh.origin[0], mesh.origin[1], mesh.origin[2]
+                );
+            }
+            println!("Saving data...");
+        }
+
+        match format {
+            MeshFormat::Obj => mesh.write_obj(&output_file, local_origin)?,
+            MeshFormat::Stl => mesh.write_stl(&output_file, local_origin)?,
+            MeshFormat::Gltf | MeshFormat::Glb => mesh.write_gltf(&output_file, format)?,
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        if verbose {
+            println!("{}", &format!("Elapsed Time: {}", elapsed_time));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MeshFormat {
+    Obj,
+    Stl,
+    Gltf,
+    Glb,
+}
+
+impl MeshFormat {
+    fn from_file_name(file_name: &str) -> Option<MeshFormat> {
+        let extension = path::Path::new(file_name)
+            .extension()?
+            .to_string_lossy()
+            .to_lowercase();
+        match extension.as_str() {
+            "obj" => Some(MeshFormat::Obj),
+            "stl" => Some(MeshFormat::Stl),
+            "gltf" => Some(MeshFormat::Gltf),
+            "glb" => Some(MeshFormat::Glb),
+            _ => None,
+        }
+    }
+}
+
+/// An indexed triangle mesh with optional 16-bit RGB vertex colours.
+#[derive(Default)]
+struct Mesh {
+    vertices: Vec<[f64; 3]>,
+    colours: Vec<[u16; 3]>,
+    indices: Vec<u32>,
+    origin: [f64; 3],
+    /// The number of decimal places of the coordinates written to text formats.
+    decimals: usize,
+}
+
+impl Mesh {
+    /// Returns the vertex colours scaled to the range 0-1. LAS files store 16-bit colours,
+    /// although some producers store 8-bit values, which are detected from their range.
+    fn normalized_colours(&self) -> Vec<[f32; 3]> {
+        let max_value = self
+            .colours
+            .iter()
+            .flat_map(|c| c.iter())
+            .fold(0u16, |m, &v| m.max(v));
+        let scale = if max_value <= 255 { 255f32 } else { 65535f32 };
+        self.colours
+            .iter()
+            .map(|c| {
+                [
+                    c[0] as f32 / scale,
+                    c[1] as f32 / scale,
+                    c[2] as f32 / scale,
+                ]
+            })
+            .collect()
+    }
+
+    fn write_obj(&self, file_name: &str, local_origin: bool) -> Result<(), Error> {
+        let origin = if local_origin { self.origin } else { [0f64; 3] };
+        let mut writer = BufWriter::new(File::create(file_name)?);
+        writeln!(writer, "# Created by WhiteboxTools")?;
+        if local_origin {
+            writeln!(
+                writer,
+                "# Origin: {:.*} {:.*} {:.*}",
+                self.decimals, origin[0], self.decimals, origin[1], self.decimals, origin[2]
+            )?;
+        }
+        let colours = self.normalized_colours();
+        for (i, v) in self.vertices.iter().enumerate() {
+            if colours.is_empty() {
+                writeln!(
+                    writer,
+                    "v {:.*} {:.*} {:.*}",
+                    self.decimals,
+                    v[0] - origin[0],
+                    self.decimals,
+                    v[1] - origin[1],
+                    self.decimals,
+                    v[2] - origin[2]
+                )?;
+            } else {
+                writeln!(
+                    writer,
+                    "v {:.*} {:.*} {:.*} {:.4} {:.4} {:.4}",
+                    self.decimals,
+                    v[0] - origin[0],
+                    self.decimals,
+                    v[1] - origin[1],
+                    self.decimals,
+                    v[2] - origin[2],
+                    colours[i][0],
+                    colours[i][1],
+                    colours[i][2]
+                )?;
+            }
+        }
+        for tri in self.indices.chunks(3) {
+            // OBJ vertex indices are one-based
+            writeln!(writer, "f {} {} {}", tri[0] + 1, tri[1] + 1, tri[2] + 1)?;
+        }
+        writer.flush()
+    }
+
+    fn write_stl(&self, file_name: &str, local_origin: bool) -> Result<(), Error> {
+        let origin = if local_origin { self.origin } else { [0f64; 3] };
+        let mut writer = BufWriter::new(File::create(file_name)?);
+        let mut header = [0u8; 80];
+        let text = b"Binary STL created by WhiteboxTools";
+        header[..text.len()].copy_from_slice(text);
+        writer.write_all(&header)?;
+        writer.write_all(&((self.indices.len() / 3) as u32).to_le_bytes())?;
+        for tri in self.indices.chunks(3) {
+            let v: Vec<[f64; 3]> = tri
+                .iter()
+                .map(|&i| {
+                    let p = self.vertices[i as usize];
+                    [p[0] - origin[0], p[1] - origin[1], p[2] - origin[2]]
+                })
+                .collect();
+            let a = [v[1][0] - v[0][0], v[1][1] - v[0][1], v[1][2] - v[0][2]];
+            let b = [v[2][0] - v[0][0], v[2][1] - v[0][1], v[2][2] - v[0][2]];
+            let mut normal = [
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ];
+            let length =
+                (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+            if length > 0f64 {
+                normal.iter_mut().for_each(|n| *n /= length);
+            }
+            for value in normal.iter().chain(v.iter().flat_map(|p| p.iter())) {
+                writer.write_all(&(*value as f32).to_le_bytes())?;
+            }
+            writer.write_all(&0u16.to_le_bytes())?; // attribute byte count
+        }
+        writer.flush()
+    }
+
+    fn write_gltf(&self, file_name: &str, format: MeshFormat) -> Result<(), Error> {
+        use serde_json::json;
+
+        // glTF is y-up; vertices are stored as (easting, elevation, -northing) about the origin
+        let positions: Vec<[f32; 3]> = self
+            .vertices
+            .iter()
+            .map(|v| {
+                [
+                    (v[0] - self.origin[0]) as f32,
+                    (v[2] - self.origin[2]) as f32,
+                    -(v[1] - self.origin[1]) as f32,
+                ]
+            })
+            .collect();
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for p in &positions {
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+        let colours = self.normalized_colours();
+
+        // the binary buffer holds the indices, positions, and colours, in that order
+        let mut buffer: Vec<u8> =
+            Vec::with_capacity(self.indices.len() * 4 + positions.len() * 12 + colours.len() * 12);
+        for i in &self.indices {
+            buffer.extend_from_slice(&i.to_le_bytes());
+        }
+        let positions_offset = buffer.len();
+        for value in positions.iter().flat_map(|p| p.iter()) {
+            buffer.extend_from_slice(&value.to_le_bytes());
+        }
+        let colours_offset = buffer.len();
+        for value in colours.iter().flat_map(|c| c.iter()) {
+            buffer.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let mut buffer_views = vec![
+            json!({ "buffer": 0, "byteOffset": 0, "byteLength": positions_offset, "target": 34963 }),
+            json!({ "buffer": 0, "byteOffset": positions_offset, "byteLength": colours_offset - positions_offset, "target": 34962 }),
+        ];
+        let mut accessors = vec![
+            json!({ "bufferView": 0, "componentType": 5125, "count": self.indices.len(), "type": "SCALAR" }),
+            json!({ "bufferView": 1, "componentType": 5126, "count": positions.len(), "type": "VEC3", "min": min, "max": max }),
+        ];
+        let mut attributes = json!({ "POSITION": 1 });
+        if !colours.is_empty() {
+            buffer_views.push(json!({ "buffer": 0, "byteOffset": colours_offset, "byteLength": buffer.len() - colours_offset, "target": 34962 }));
+            accessors.push(json!({ "bufferView": 2, "componentType": 5126, "count": colours.len(), "type": "VEC3" }));
+            attributes["COLOR_0"] = json!(2);
+        }
+
+        let bin_file = path::Path::new(file_name).with_extension("bin");
+        let mut buffer_json = json!({ "byteLength": buffer.len() });
+        if format == MeshFormat::Gltf {
+            buffer_json["uri"] = json!(bin_file
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_default());
+        }
+        let gltf = json!({
+            "asset": { "version": "2.0", "generator": "WhiteboxTools" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{
+                "mesh": 0,
+                "translation": [self.origin[0], self.origin[2], -self.origin[1]],
+            }],
+            "meshes": [{
+                "primitives": [{ "attributes": attributes, "indices": 0, "mode": 4 }],
+            }],
+            "accessors": accessors,
+            "bufferViews": buffer_views,
+            "buffers": [buffer_json],
+        });
+        let mut json_bytes = serde_json::to_vec(&gltf).map_err(|e| Error::other(e.to_string()))?;
+
+        if format == MeshFormat::Gltf {
+            File::create(file_name)?.write_all(&json_bytes)?;
+            File::create(&bin_file)?.write_all(&buffer)?;
+            return Ok(());
+        }
+
+        // GLB chunks are padded to four-byte boundaries, JSON with spaces and binary with zeros
+        while !json_bytes.len().is_multiple_of(4) {
+            json_bytes.push(b' ');
+        }
+        while !buffer.len().is_multiple_of(4) {
+            buffer.push(0);
+        }
+        let mut writer = BufWriter::new(File::create(file_name)?);
+        writer.write_all(b"glTF")?;
+        writer.write_all(&2u32.to_le_bytes())?;
+        writer.write_all(&((12 + 8 + json_bytes.len() + 8 + buffer.len()) as u32).to_le_bytes())?;
+        writer.write_all(&(json_bytes.len() as u32).to_le_bytes())?;
+        writer.write_all(b"JSON")?;
+        writer.write_all(&json_bytes)?;
+        writer.write_all(&(buffer.len() as u32).to_le_bytes())?;
+        writer.write_all(b"BIN\0")?;
+        writer.write_all(&buffer)?;
+        writer.flush()
+    }
+}
@@ -0,0 +1,81 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+//! Functions that are shared by several of the LiDAR tools.
+
+use crate::lidar::LasFile;
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use std::io::{Error, ErrorKind};
+
+/// Fills the NaN-valued cells of a grid by inverse-distance weighting of the nearest valid cells.
+pub fn fill_gaps(grid: &[f64], rows: isize, columns: isize) -> Vec<f64> {
+    let mut filled = grid.to_vec();
+    if grid.iter().all(|z| !z.is_nan()) {
+        return filled;
+    }
+    let mut tree = KdTree::new_with_capacity(2, 64);
+    for row in 0..rows {
+        for col in 0..columns {
+            let z = grid[(row * columns + col) as usize];
+            if !z.is_nan() {
+                tree.add([col as f64, row as f64], z).unwrap();
+            }
+        }
+    }
+    for row in 0..rows {
+        for col in 0..columns {
+            let idx = (row * columns + col) as usize;
+            if grid[idx].is_nan() {
+                let ret = tree
+                    .nearest(&[col as f64, row as f64], 8, &squared_euclidean)
+                    .unwrap();
+                let mut sum_weights = 0f64;
+                let mut sum = 0f64;
+                for (dist, z) in ret {
+                    let w = 1f64 / dist;
+                    sum_weights += w;
+                    sum += w * *z;
+                }
+                filled[idx] = sum / sum_weights;
+            }
+        }
+    }
+    filled
+}
+
+/// Checks that each of the input LAS files contains the interpolation parameters, i.e. the point
+/// attributes or extra bytes attributes that are to be interpolated, such that an invalid
+/// parameter is reported before any of the files are interpolated. Only the headers and VLRs of
+/// the files are read.
+pub fn check_interpolation_parameters(inputs: &[String], parameters: &[String]) -> Result<(), Error> {
+    for in_file in inputs {
+        let las_file_name = in_file.replace("\"", "");
+        let las = LasFile::new(&las_file_name, "rv")?;
+        if let Err(err) = las.get_point_attributes(parameters) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Error reading file {}: {}", las_file_name, err),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the name of the output file for an interpolation parameter. When there are multiple
+/// parameters, the output file name is suffixed by the parameter, e.g. `outfile_intensity.tif`.
+pub fn parameter_output_file(output_file: &str, parameter: &str, num_params: usize) -> String {
+    if num_params == 1 {
+        return output_file.to_string();
+    }
+    let suffix = parameter.replace(" ", "_");
+    match output_file.rfind('.') {
+        Some(pos) => format!("{}_{}{}", &output_file[..pos], suffix, &output_file[pos..]),
+        None => format!("{}_{}", output_file, suffix),
+    }
+}
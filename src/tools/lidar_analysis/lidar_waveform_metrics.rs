@@ -0,0 +1,402 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::*;
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool derives metrics from the full-waveform data associated with the points in a
+/// LAS file (point formats 4, 5, 9, and 10) and outputs them as extra bytes attributes of
+/// the points in a new LAS file. The waveform packets may be stored either internally,
+/// following the point records, or in an external .wdp file with the same name as the
+/// input LAS file. The following attributes are added to each point:
+///
+/// - **echo_width**: The full width at half maximum (in nanoseconds) of the waveform peak
+///   associated with the point's return.
+/// - **amplitude**: The height of this peak above the noise level of the waveform, in
+///   digitizer units (i.e. after applying the digitizer gain and offset).
+/// - **num_peaks**: The number of peaks detected within the point's waveform.
+///
+/// The noise level of each waveform is estimated as the median of its samples. A sample is
+/// considered a peak if it is a local maximum and its height above the noise level exceeds
+/// `--threshold` multiplied by the height of the waveform maximum above the noise level. The
+/// peak associated with a point is the one nearest to the point's return location within the
+/// waveform (the *return point waveform location*). Points without waveform data, or for which
+/// no peak can be found, are assigned the NoData value (-9999) for echo_width and amplitude.
+///
+/// The output file will contain any extra bytes attributes contained within the input file.
+/// Notice that the waveform packets themselves are not written to the output file, as output
+/// LAS files are restricted to point formats 0-3; the waveform metrics can be used with tools
+/// that accept extra bytes attributes, e.g. by specifying the attribute name as the
+/// `--parameter` of `LidarTINGridding`.
+///
+/// # See Also
+/// `LidarTINGridding`, `LidarInfo`
+pub struct LidarWaveformMetrics {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LidarWaveformMetrics {
+    pub fn new() -> LidarWaveformMetrics {
+        // public constructor
+        let name = "LidarWaveformMetrics".to_string();
+        let toolbox = "LiDAR Tools".to_string();
+        let description = "Derives echo width, amplitude, and number of peaks from full-waveform LiDAR data and stores them as extra bytes attributes.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input LiDAR file containing waveform packets.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output LiDAR file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Lidar),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Peak Threshold (0-1)".to_owned(),
+            flags: vec!["--threshold".to_owned()],
+            description: "Minimum peak height, as a proportion of the waveform maximum above the noise level.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.1".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=\"input.las\" -o=\"output.las\" --threshold=0.15", short_exe, name).replace("*", &sep);
+
+        LidarWaveformMetrics {
+            name,
+            description,
+            toolbox,
+            parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LidarWaveformMetrics {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut threshold = 0.1f64;
+
+        // read the arguments
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-threshold" {
+                threshold = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        threshold = threshold.clamp(0f64, 1f64);
+
+        if verbose {
+            println!("Reading input LAS file...");
+        }
+        let mut input = match LasFile::new(&input_file, "r") {
+            Ok(lf) => lf,
+            Err(err) => panic!("Error reading file {}: {}", input_file, err),
+        };
+
+        if !input.has_waveform_data() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input LAS file does not contain waveform packets (point formats 4, 5, 9, and 10).",
+            ));
+        }
+
+        if verbose {
+            println!("Reading waveform data...");
+        }
+        input.read_waveform_data()?;
+
+        let start = Instant::now();
+
+        if verbose {
+            println!("Performing analysis...");
+        }
+
+        // The waveform metrics are appended to any existing extra bytes attributes.
+        let nodata = -9999f64;
+        let mut descriptors = input.extra_bytes_descriptors.clone();
+        let metrics_offset = input.get_extra_bytes_length();
+        let mut width_descriptor =
+            ExtraBytesDescriptor::new("echo_width", 9u8, "echo width (ns)");
+        width_descriptor.set_no_data(nodata);
+        let mut amplitude_descriptor =
+            ExtraBytesDescriptor::new("amplitude", 9u8, "echo amplitude");
+        amplitude_descriptor.set_no_data(nodata);
+        let peaks_descriptor = ExtraBytesDescriptor::new("num_peaks", 1u8, "number of peaks");
+        descriptors.push(width_descriptor.clone());
+        descriptors.push(amplitude_descriptor.clone());
+        descriptors.push(peaks_descriptor.clone());
+
+        let mut output = LasFile::initialize_using_file(&output_file, &input);
+        output.set_extra_bytes_descriptors(descriptors);
+        let extra_bytes_length = output.get_extra_bytes_length();
+
+        let n_points = input.header.number_of_points as usize;
+        let num_points: f64 = (input.header.number_of_points - 1) as f64; // used for progress calculation only
+        let mut num_failed = 0usize;
+        let mut extra_bytes = vec![0u8; extra_bytes_length];
+        let mut progress: i32;
+        let mut old_progress: i32 = -1;
+        for i in 0..n_points {
+            let (mut echo_width, mut amplitude, mut num_peaks) = (nodata, nodata, 0usize);
+            if let Ok(samples) = input.get_waveform_samples(i) {
+                let wfp = input.get_waveform_packet(i).unwrap();
+                let wpd = input
+                    .get_waveform_packet_descriptor(wfp.packet_descriptor_index)
+                    .unwrap();
+                if let Some(metrics) = measure_waveform(
+                    &samples,
+                    wfp.ret_point_waveform_loc as f64,
+                    wpd.temporal_sample_spacing as f64,
+                    threshold,
+                ) {
+                    echo_width = metrics.0;
+                    amplitude = metrics.1;
+                    num_peaks = metrics.2;
+                } else {
+                    num_failed += 1;
+                }
+            } else {
+                num_failed += 1;
+            }
+
+            for b in extra_bytes.iter_mut() {
+                *b = 0u8;
+            }
+            let input_bytes = input.get_extra_bytes(i);
+            extra_bytes[0..input_bytes.len()].copy_from_slice(input_bytes);
+            width_descriptor.set_value(echo_width, &mut extra_bytes[metrics_offset..]);
+            amplitude_descriptor.set_value(amplitude, &mut extra_bytes[metrics_offset + 4..]);
+            peaks_descriptor.set_value(num_peaks as f64, &mut extra_bytes[metrics_offset + 8..]);
+
+            output.add_point_record(input.get_record(i));
+            output.add_extra_bytes(&extra_bytes);
+
+            if verbose {
+                progress = (100.0_f64 * i as f64 / num_points) as i32;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if num_failed > 0 {
+            println!(
+                "Warning: Waveform metrics could not be derived for {} points.",
+                num_failed
+            );
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Writing output LAS file...");
+        }
+        let _ = match output.write() {
+            Ok(_) => println!("Complete!"),
+            Err(e) => println!("error while writing: {:?}", e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the echo width (ns), amplitude, and number of peaks of a waveform, or None if
+/// the waveform does not contain any peaks. `return_location` and `sample_spacing` are
+/// in picoseconds.
+fn measure_waveform(
+    samples: &[f64],
+    return_location: f64,
+    sample_spacing: f64,
+    threshold: f64,
+) -> Option<(f64, f64, usize)> {
+    let n = samples.len();
+    if n < 3 {
+        return None;
+    }
+
+    // The noise level is estimated as the median sample value.
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let noise = if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2f64
+    };
+    let max_height = sorted[n - 1] - noise;
+    if max_height <= 0f64 {
+        return None;
+    }
+    let min_height = threshold * max_height;
+
+    let mut peaks = vec![];
+    for j in 0..n {
+        let left = if j > 0 { samples[j - 1] } else { f64::NEG_INFINITY };
+        let right = if j < n - 1 { samples[j + 1] } else { f64::NEG_INFINITY };
+        if samples[j] > left && samples[j] >= right && samples[j] - noise >= min_height {
+            peaks.push(j);
+        }
+    }
+    if peaks.is_empty() {
+        return None;
+    }
+
+    // Find the peak nearest the return location.
+    let return_sample = if sample_spacing > 0f64 {
+        return_location / sample_spacing
+    } else {
+        0f64
+    };
+    let mut peak = peaks[0];
+    for &j in &peaks {
+        if (j as f64 - return_sample).abs() < (peak as f64 - return_sample).abs() {
+            peak = j;
+        }
+    }
+    let amplitude = samples[peak] - noise;
+
+    // The full width at half maximum, using linear interpolation between samples.
+    let half = noise + amplitude / 2f64;
+    let mut left = 0f64;
+    let mut j = peak;
+    while j > 0 {
+        if samples[j - 1] < half {
+            left = (j - 1) as f64 + (half - samples[j - 1]) / (samples[j] - samples[j - 1]);
+            break;
+        }
+        j -= 1;
+    }
+    let mut right = (n - 1) as f64;
+    j = peak;
+    while j < n - 1 {
+        if samples[j + 1] < half {
+            right = j as f64 + (samples[j] - half) / (samples[j] - samples[j + 1]);
+            break;
+        }
+        j += 1;
+    }
+    let echo_width = (right - left) * sample_spacing / 1000f64;
+
+    Some((echo_width, amplitude, peaks.len()))
+}
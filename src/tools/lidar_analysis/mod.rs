@@ -1,8 +1,10 @@
 // private sub-module defined in other files
-// mod ascii_to_las;
+mod ascii_columns;
+mod ascii_to_las;
 mod block_maximum;
 mod block_minimum;
 mod classify_overlap_points;
+mod clip_lidar_to_corridor;
 mod clip_lidar_to_polygon;
 mod erase_polygon_from_lidar;
 mod filter_lidar_classes;
@@ -12,40 +14,66 @@ mod flightline_overlap;
 mod las_to_ascii;
 mod las_to_multipoint_shapefile;
 mod las_to_shapefile;
+mod lidar_canopy_layers;
+mod lidar_canopy_metrics;
+mod lidar_cell_stats;
+mod lidar_classify_from_raster;
+mod lidar_classify_powerlines;
 mod lidar_classify_subset;
 mod lidar_colourize;
 mod lidar_construct_vector_tin;
+mod lidar_distance_to_surface;
+mod lidar_eigenvalue_features;
 mod lidar_elevation_slice;
+mod lidar_extract_buildings;
+mod lidar_extract_water_bodies;
 mod lidar_ground_point_filter;
 mod lidar_hex_bin;
 mod lidar_hillshade;
 mod lidar_histogram;
 mod lidar_idw_interpolation;
+mod lidar_index;
 mod lidar_info;
 mod lidar_join;
 mod lidar_kappa;
+mod lidar_kriging_interpolation;
+mod lidar_mcc_filter;
 mod lidar_nn_gridding;
+mod lidar_normalize_by_dem;
 mod lidar_outliers;
 mod lidar_point_density;
+mod lidar_point_normals;
 mod lidar_point_stats;
+mod lidar_qc_report;
 mod lidar_ransac_planes;
+mod lidar_reproject;
 mod lidar_segmentation;
 mod lidar_segmentation_based_filter;
+mod lidar_smrf_filter;
+mod lidar_sor_filter;
+mod lidar_split;
+mod lidar_strip_align;
 mod lidar_thin;
+mod lidar_thin_by_voxel;
 mod lidar_thin_high_density;
 mod lidar_tile;
+mod lidar_tile_by_polygon;
 mod lidar_tile_footprint;
 mod lidar_tin_gridding;
+mod lidar_tin_to_mesh;
 mod lidar_tophat_transform;
+mod lidar_utils;
+mod lidar_waveform_metrics;
 mod normal_vectors;
 mod remove_duplicates;
 mod select_tiles_by_polygon;
 
 // exports identifiers from private sub-modules in the current module namespace
-// pub use self::ascii_to_las::AsciiToLas;
+pub use self::ascii_to_las::AsciiToLas;
 pub use self::block_maximum::LidarBlockMaximum;
 pub use self::block_minimum::LidarBlockMinimum;
 pub use self::classify_overlap_points::ClassifyOverlapPoints;
+pub use self::clip_lidar_to_corridor::ClipLidarToCorridor;
 pub use self::clip_lidar_to_polygon::ClipLidarToPolygon;
 pub use self::erase_polygon_from_lidar::ErasePolygonFromLidar;
 pub use self::filter_lidar_classes::FilterLidarClasses;
@@ -55,31 +83,55 @@ pub use self::flightline_overlap::FlightlineOverlap;
 pub use self::las_to_ascii::LasToAscii;
 pub use self::las_to_multipoint_shapefile::LasToMultipointShapefile;
 pub use self::las_to_shapefile::LasToShapefile;
+pub use self::lidar_canopy_layers::LidarCanopyLayers;
+pub use self::lidar_canopy_metrics::LidarCanopyMetrics;
+pub use self::lidar_cell_stats::LidarCellStats;
+pub use self::lidar_classify_from_raster::LidarClassifyFromRaster;
+pub use self::lidar_classify_powerlines::LidarClassifyPowerlines;
 pub use self::lidar_classify_subset::LidarClassifySubset;
 pub use self::lidar_colourize::LidarColourize;
 pub use self::lidar_construct_vector_tin::LidarConstructVectorTIN;
+pub use self::lidar_distance_to_surface::LidarDistanceToSurface;
+pub use self::lidar_eigenvalue_features::LidarEigenvalueFeatures;
 pub use self::lidar_elevation_slice::LidarElevationSlice;
+pub use self::lidar_extract_buildings::LidarExtractBuildings;
+pub use self::lidar_extract_water_bodies::LidarExtractWaterBodies;
 pub use self::lidar_ground_point_filter::LidarGroundPointFilter;
 pub use self::lidar_hex_bin::LidarHexBinning;
 pub use self::lidar_hillshade::LidarHillshade;
 pub use self::lidar_histogram::LidarHistogram;
 pub use self::lidar_idw_interpolation::LidarIdwInterpolation;
+pub use self::lidar_index::LidarIndex;
 pub use self::lidar_info::LidarInfo;
 pub use self::lidar_join::LidarJoin;
 pub use self::lidar_kappa::LidarKappaIndex;
+pub use self::lidar_kriging_interpolation::LidarKrigingInterpolation;
+pub use self::lidar_mcc_filter::LidarMccFilter;
 pub use self::lidar_nn_gridding::LidarNearestNeighbourGridding;
+pub use self::lidar_normalize_by_dem::LidarNormalizeByDem;
 pub use self::lidar_outliers::LidarRemoveOutliers;
 pub use self::lidar_point_density::LidarPointDensity;
+pub use self::lidar_point_normals::LidarPointNormals;
 pub use self::lidar_point_stats::LidarPointStats;
+pub use self::lidar_qc_report::LidarQcReport;
 pub use self::lidar_ransac_planes::LidarRansacPlanes;
+pub use self::lidar_reproject::LidarReproject;
 pub use self::lidar_segmentation::LidarSegmentation;
 pub use self::lidar_segmentation_based_filter::LidarSegmentationBasedFilter;
+pub use self::lidar_smrf_filter::LidarSmrfFilter;
+pub use self::lidar_sor_filter::LidarSorFilter;
+pub use self::lidar_split::LidarSplit;
+pub use self::lidar_strip_align::LidarStripAlign;
 pub use self::lidar_thin::LidarThin;
+pub use self::lidar_thin_by_voxel::LidarThinByVoxel;
 pub use self::lidar_thin_high_density::LidarThinHighDensity;
 pub use self::lidar_tile::LidarTile;
+pub use self::lidar_tile_by_polygon::LidarTileByPolygon;
 pub use self::lidar_tile_footprint::LidarTileFootprint;
 pub use self::lidar_tin_gridding::LidarTINGridding;
+pub use self::lidar_tin_to_mesh::LidarTINToMesh;
 pub use self::lidar_tophat_transform::LidarTophatTransform;
+pub use self::lidar_waveform_metrics::LidarWaveformMetrics;
 pub use self::normal_vectors::NormalVectors;
 pub use self::remove_duplicates::LidarRemoveDuplicates;
 pub use self::select_tiles_by_polygon::SelectTilesByPolygon;
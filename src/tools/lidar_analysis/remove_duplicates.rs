@@ -181,13 +181,8 @@ impl WhiteboxTool for LidarRemoveDuplicates {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep = path::MAIN_SEPARATOR;
-        if !input_file.contains(sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading input LAS file...");
@@ -279,6 +274,7 @@ impl WhiteboxTool for LidarRemoveDuplicates {
         for i in 0..n_points {
             if !is_duplicate[i] {
                 output.add_point_record(input.get_record(i));
+                output.add_extra_bytes(input.get_extra_bytes(i));
             }
             if verbose {
                 progress = (100.0_f64 * i as f64 / num_points) as i32;
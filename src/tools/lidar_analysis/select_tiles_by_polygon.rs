@@ -183,14 +183,11 @@ impl WhiteboxTool for SelectTilesByPolygon {
         }
 
         let start = Instant::now();
-        let sep: String = path::MAIN_SEPARATOR.to_string();
 
         if input_directory.is_empty() {
             input_directory = working_directory.clone().to_string();
         }
-        if !polygons_file.contains(&sep) && !polygons_file.contains("/") {
-            polygons_file = format!("{}{}", working_directory, polygons_file);
-        }
+        polygons_file = resolve_path(&polygons_file, working_directory);
 
         if verbose {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
@@ -180,14 +180,10 @@ impl WhiteboxTool for Add {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         // Are either of the inputs constants?
         let mut input1_constant = f64::NEG_INFINITY;
@@ -199,9 +195,7 @@ impl WhiteboxTool for Add {
             Err(_) => false,
         };
         if !input1_is_constant {
-            if !input1.contains(&sep) && !input1.contains("/") {
-                input1 = format!("{}{}", working_directory, input1);
-            }
+            input1 = resolve_path(&input1, working_directory);
         }
 
         let mut input2_constant = f64::NEG_INFINITY;
@@ -213,9 +207,7 @@ impl WhiteboxTool for Add {
             Err(_) => false,
         };
         if !input2_is_constant {
-            if !input2.contains(&sep) && !input2.contains("/") {
-                input2 = format!("{}{}", working_directory, input2);
-            }
+            input2 = resolve_path(&input2, working_directory);
         }
 
         if input1_is_constant && input2_is_constant {
@@ -180,20 +180,12 @@ impl WhiteboxTool for And {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
-        if !input1.contains(&sep) && !input1.contains("/") {
-            input1 = format!("{}{}", working_directory, input1);
-        }
-        if !input2.contains(&sep) && !input2.contains("/") {
-            input2 = format!("{}{}", working_directory, input2);
-        }
+        output_file = resolve_path(&output_file, working_directory);
+        input1 = resolve_path(&input1, working_directory);
+        input2 = resolve_path(&input2, working_directory);
 
         if verbose {
             println!("Reading data...")
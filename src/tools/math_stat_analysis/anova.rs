@@ -181,20 +181,12 @@ impl WhiteboxTool for Anova {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !feature_file.contains(&sep) && !feature_file.contains("/") {
-            feature_file = format!("{}{}", working_directory, feature_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        feature_file = resolve_path(&feature_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
         if !output_file.ends_with(".html") {
             output_file = output_file + ".html";
         }
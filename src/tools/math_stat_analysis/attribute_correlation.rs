@@ -186,16 +186,12 @@ impl WhiteboxTool for AttributeCorrelation {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
         let start = Instant::now();
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
         if output_file.len() == 0 {
             // output_file not specified and should be based on input file
             let p = path::Path::new(&input_file);
@@ -204,9 +200,7 @@ impl WhiteboxTool for AttributeCorrelation {
             extension.push_str(ext);
             output_file = input_file.replace(&extension, ".html");
         }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading vector data...")
@@ -0,0 +1,569 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use crate::vector::{AttributeField, FieldData, FieldDataType, Shapefile};
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool performs a nearest-neighbour join in attribute space. For each feature in the input
+/// vector (`--input`), the tool finds the *k* (`--num_neighbours`) features within a reference
+/// vector (`--reference`) that are most similar, based on the values of a list of numeric
+/// attributes (`--fields`), and writes the IDs of the matching features, and their distances, into
+/// the attribute table of the output vector (`--output`). Similarity is measured using the
+/// Euclidean distance between features in the multi-dimensional space defined by the attributes.
+/// If the reference vector is unspecified, the input features are matched against the other
+/// features within the input vector, i.e. a feature is never matched to itself.
+///
+/// Because attributes are commonly measured in very different units, each attribute is normalized
+/// before the distances are calculated (`--normalize`). By default, the attributes are converted to
+/// z-scores (`zscore`), using the means and standard deviations of the reference features. The
+/// attributes may alternatively be rescaled to the range 0-1 of the reference features (`range`),
+/// or left unnormalized (`none`), e.g. when they are already in comparable units.
+///
+/// The output vector contains the geometries and attributes of the input vector, along with the
+/// fields MATCH1, DIST1, MATCH2, DIST2, ..., MATCHk, DISTk, ordered from the most to the least
+/// similar match. Match IDs are the values of the reference vector's FID field, if it has one, or
+/// the reference features' record numbers (starting at 1) otherwise. Input features with missing
+/// (null) values for any of the attributes are not matched.
+///
+/// The tool can also be used to impute missing attributes, or to transfer attributes from a set of
+/// analog sites, by specifying the fields of the reference vector to be imputed (`--impute`). For
+/// each imputed field, a new field (named I_ followed by the field name) is added to the output
+/// table. Numeric fields are imputed using the inverse-distance weighted mean of the *k* matches,
+/// while other fields are assigned the value of the most similar match.
+///
+/// # See Also
+/// `JoinTables`, `AttributeCorrelation`, `KMeansClustering`
+pub struct AttributeNearestNeighbourJoin {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl AttributeNearestNeighbourJoin {
+    pub fn new() -> AttributeNearestNeighbourJoin {
+        // public constructor
+        let name = "AttributeNearestNeighbourJoin".to_string();
+        let toolbox = "Math and Stats Tools".to_string();
+        let description = "Matches each feature to the k most similar features in attribute space, optionally imputing attributes.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Vector File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input vector file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Reference Vector File (optional)".to_owned(),
+            flags: vec!["--reference".to_owned()],
+            description: "Optional reference vector file containing the candidate matches; the input file is used if unspecified.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Similarity Fields".to_owned(),
+            flags: vec!["--fields".to_owned()],
+            description: "Comma-separated list of the numeric fields used to measure similarity.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Neighbours".to_owned(),
+            flags: vec!["-k".to_owned(), "--num_neighbours".to_owned()],
+            description: "Number of matches (k) for each feature.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("1".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Normalization Method".to_owned(),
+            flags: vec!["--normalize".to_owned()],
+            description: "Attribute normalization method; options include 'zscore', 'range', and 'none'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "zscore".to_owned(),
+                "range".to_owned(),
+                "none".to_owned(),
+            ]),
+            default_value: Some("zscore".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Imputed Fields (optional)".to_owned(),
+            flags: vec!["--impute".to_owned()],
+            description: "Optional comma-separated list of reference fields to impute from the matches.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=plots.shp --reference=sites.shp --fields='ELEV,SLOPE,TWI' -o=matches.shp -k=3 --impute='BIOMASS'", short_exe, name).replace("*", &sep);
+
+        AttributeNearestNeighbourJoin {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for AttributeNearestNeighbourJoin {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![("--num_neighbours".to_string(), ParameterConstraint::Min(1.0))]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut reference_file = String::new();
+        let mut output_file = String::new();
+        let mut fields_str = String::new();
+        let mut impute_str = String::new();
+        let mut num_neighbours = 1usize;
+        let mut normalize = String::from("zscore");
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-reference" {
+                reference_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-fields" {
+                fields_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-impute" {
+                impute_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-k" || flag_val == "-num_neighbours" || flag_val == "-num_neighbors" {
+                num_neighbours = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-normalize" || flag_val == "-normalise" {
+                normalize = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        reference_file = resolve_path(&reference_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if num_neighbours < 1 {
+            num_neighbours = 1;
+        }
+        let normalize = if normalize.contains("z") || normalize.contains("std") {
+            Normalization::ZScore
+        } else if normalize.contains("range") || normalize.contains("minmax") {
+            Normalization::Range
+        } else if normalize.contains("none") {
+            Normalization::None
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unrecognized normalization method; options include 'zscore', 'range', and 'none'.",
+            ));
+        };
+
+        let fields = parse_field_list(&fields_str);
+        if fields.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one similarity field must be specified (--fields).",
+            ));
+        }
+        let impute_fields = parse_field_list(&impute_str);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let input = Shapefile::read(&input_file)?;
+        let self_join = reference_file.is_empty() || reference_file == input_file;
+        let reference = if self_join {
+            None
+        } else {
+            Some(Shapefile::read(&reference_file)?)
+        };
+        let reference_ref = match reference {
+            Some(ref r) => r,
+            None => &input,
+        };
+
+        let start = Instant::now();
+
+        let input_field_nums = get_numeric_field_nums(&input, &fields, &input_file)?;
+        let reference_field_nums = get_numeric_field_nums(reference_ref, &fields, &reference_file)?;
+        let mut impute_field_nums = vec![];
+        for name in &impute_fields {
+            match reference_ref.attributes.get_field_num(name) {
+                Some(n) => impute_field_nums.push(n),
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("The reference attribute table does not contain the field {}.", name),
+                    ))
+                }
+            }
+        }
+        let fid_field_num = reference_ref.attributes.get_field_num("FID");
+
+        // read the reference attribute vectors and calculate the normalization parameters
+        let num_dims = fields.len();
+        let mut reference_values: Vec<Option<Vec<f64>>> = Vec::with_capacity(reference_ref.num_records);
+        for record_num in 0..reference_ref.num_records {
+            reference_values.push(read_values(reference_ref, record_num, &reference_field_nums));
+        }
+        let mut offsets = vec![0f64; num_dims];
+        let mut scales = vec![1f64; num_dims];
+        if normalize != Normalization::None {
+            for d in 0..num_dims {
+                let values: Vec<f64> = reference_values
+                    .iter()
+                    .filter_map(|v| v.as_ref().map(|v| v[d]))
+                    .collect();
+                if values.is_empty() {
+                    continue;
+                }
+                if normalize == Normalization::ZScore {
+                    let n = values.len() as f64;
+                    let mean = values.iter().sum::<f64>() / n;
+                    let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
+                    offsets[d] = mean;
+                    scales[d] = variance.sqrt();
+                } else {
+                    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+                    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                    offsets[d] = min;
+                    scales[d] = max - min;
+                }
+                if scales[d] <= 0f64 {
+                    // a constant attribute does not contribute to the distances
+                    scales[d] = 1f64;
+                }
+            }
+        }
+        let normalize_values = |v: &[f64]| -> Vec<f64> {
+            (0..num_dims).map(|d| (v[d] - offsets[d]) / scales[d]).collect()
+        };
+
+        let mut tree = KdTree::new_with_capacity(num_dims, 64);
+        let mut num_candidates = 0usize;
+        for record_num in 0..reference_ref.num_records {
+            if let Some(ref v) = reference_values[record_num] {
+                tree.add(normalize_values(v), record_num).unwrap();
+                num_candidates += 1;
+            }
+        }
+        if num_candidates == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The reference attribute table does not contain any features with valid values for all of the similarity fields.",
+            ));
+        }
+
+        // create output file
+        let mut output =
+            Shapefile::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
+        for n in 1..=num_neighbours {
+            output.attributes.add_field(&AttributeField::new(
+                &format!("MATCH{}", n),
+                FieldDataType::Int,
+                8u8,
+                0u8,
+            ));
+            output.attributes.add_field(&AttributeField::new(
+                &format!("DIST{}", n),
+                FieldDataType::Real,
+                12u8,
+                5u8,
+            ));
+        }
+        for a in 0..impute_fields.len() {
+            let mut field = reference_ref.attributes.get_field_info(impute_field_nums[a]);
+            field.name = format!("I_{}", impute_fields[a]).chars().take(10).collect();
+            if reference_ref.attributes.is_field_numeric(impute_field_nums[a]) {
+                // weighted means are real-valued
+                field.field_type = FieldDataType::Real.to_char();
+                field.field_length = 12u8.max(field.field_length);
+                field.decimal_count = 5u8.max(field.decimal_count);
+            }
+            output.attributes.add_field(&field);
+        }
+
+        for record_num in 0..input.num_records {
+            let record = input.get_record(record_num);
+            output.add_record(record.clone());
+
+            let mut atts = input.attributes.get_record(record_num);
+            let mut matches: Vec<(usize, f64)> = vec![];
+            if let Some(v) = read_values(&input, record_num, &input_field_nums) {
+                let query = normalize_values(&v);
+                // in a self-join, the feature itself is found and must be excluded
+                let k = if self_join { num_neighbours + 1 } else { num_neighbours };
+                for (dist, j) in tree.nearest(&query, k, &squared_euclidean).unwrap() {
+                    if self_join && *j == record_num {
+                        continue;
+                    }
+                    matches.push((*j, dist.sqrt()));
+                }
+                matches.truncate(num_neighbours);
+            }
+            for n in 0..num_neighbours {
+                if n < matches.len() {
+                    let id = match fid_field_num {
+                        Some(f) => reference_ref.attributes.get_record(matches[n].0)[f].clone(),
+                        None => FieldData::Int(matches[n].0 as i32 + 1i32),
+                    };
+                    atts.push(id);
+                    atts.push(FieldData::Real(matches[n].1));
+                } else {
+                    atts.push(FieldData::Null);
+                    atts.push(FieldData::Null);
+                }
+            }
+            for field_num in &impute_field_nums {
+                atts.push(impute_value(reference_ref, &matches, *field_num));
+            }
+            output.attributes.add_record(atts, false);
+
+            if verbose {
+                progress =
+                    (100.0_f64 * (record_num + 1) as f64 / input.num_records as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("{}", &format!("Elapsed Time: {}", elapsed_time));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Normalization {
+    ZScore,
+    Range,
+    None,
+}
+
+fn parse_field_list(s: &str) -> Vec<String> {
+    s.split(&[',', ';'][..])
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .collect()
+}
+
+fn get_numeric_field_nums(
+    vector: &Shapefile,
+    fields: &[String],
+    file_name: &str,
+) -> Result<Vec<usize>, Error> {
+    let mut ret = vec![];
+    for name in fields {
+        match vector.attributes.get_field_num(name) {
+            Some(n) => {
+                if !vector.attributes.is_field_numeric(n) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("The field {} of {} is not numeric.", name, file_name),
+                    ));
+                }
+                ret.push(n);
+            }
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The attribute table of {} does not contain the field {}.", file_name, name),
+                ))
+            }
+        }
+    }
+    Ok(ret)
+}
+
+fn numeric_value(value: &FieldData) -> Option<f64> {
+    match value {
+        FieldData::Int(v) => Some(*v as f64),
+        FieldData::Real(v) if v.is_finite() => Some(*v),
+        _ => None,
+    }
+}
+
+/// Reads the values of the specified fields for a record, or None if any of them are missing.
+fn read_values(vector: &Shapefile, record_num: usize, field_nums: &[usize]) -> Option<Vec<f64>> {
+    let rec = vector.attributes.get_record(record_num);
+    field_nums.iter().map(|f| numeric_value(&rec[*f])).collect()
+}
+
+/// Imputes a field from a feature's matches, which are sorted from the most to the least similar.
+fn impute_value(reference: &Shapefile, matches: &[(usize, f64)], field_num: usize) -> FieldData {
+    if matches.is_empty() {
+        return FieldData::Null;
+    }
+    if !reference.attributes.is_field_numeric(field_num) {
+        return reference.attributes.get_record(matches[0].0)[field_num].clone();
+    }
+    let mut sum_weights = 0f64;
+    let mut sum = 0f64;
+    for (j, dist) in matches {
+        if let Some(v) = numeric_value(&reference.attributes.get_record(*j)[field_num]) {
+            if *dist == 0f64 {
+                // an exact match
+                return FieldData::Real(v);
+            }
+            sum_weights += 1f64 / dist;
+            sum += v / dist;
+        }
+    }
+    if sum_weights > 0f64 {
+        FieldData::Real(sum / sum_weights)
+    } else {
+        FieldData::Null
+    }
+}
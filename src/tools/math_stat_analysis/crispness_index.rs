@@ -194,14 +194,10 @@ impl WhiteboxTool for CrispnessIndex {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
         if output_file.len() == 0 {
             // output_file not specified and should be based on input file
             let p = path::Path::new(&input_file);
@@ -210,9 +206,7 @@ impl WhiteboxTool for CrispnessIndex {
             extension.push_str(ext);
             output_file = input_file.replace(&extension, ".html");
         }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
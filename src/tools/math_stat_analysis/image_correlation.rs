@@ -191,8 +191,6 @@ impl WhiteboxTool for ImageCorrelation {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -213,18 +211,14 @@ impl WhiteboxTool for ImageCorrelation {
             extension.push_str(ext);
             output_file = files_vec[0].replace(&extension, ".html");
         }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let mut file_names = vec![];
         for a in 0..files_vec.len() {
             let value = files_vec[a];
             if !value.trim().is_empty() {
                 let mut input_file = value.trim().to_owned();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
                 file_names.push(input_file);
             }
         }
@@ -264,18 +264,12 @@ impl WhiteboxTool for ImageRegression {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file1.contains(&sep) && !input_file1.contains("/") {
-            input_file1 = format!("{}{}", working_directory, input_file1);
-        }
+        input_file1 = resolve_path(&input_file1, working_directory);
 
-        if !input_file2.contains(&sep) && !input_file2.contains("/") {
-            input_file2 = format!("{}{}", working_directory, input_file2);
-        }
+        input_file2 = resolve_path(&input_file2, working_directory);
 
         if output_file.len() == 0 {
             // output_file not specified and should be based on input file
@@ -288,9 +282,7 @@ impl WhiteboxTool for ImageRegression {
         if !output_file.ends_with(".html") {
             output_file = output_file + ".html";
         }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let input1 = Arc::new(Raster::new(&input_file1, "r")?);
         let rows = input1.configs.rows as isize;
@@ -427,9 +419,7 @@ impl WhiteboxTool for ImageRegression {
         let slope_pvalue = 2f64 * (1f64 - t.cdf(slope.abs() / slope_se));
 
         if output_residuals {
-            if !residuals_file.contains(&sep) {
-                residuals_file = format!("{}{}", working_directory, residuals_file);
-            }
+            residuals_file = resolve_path(&residuals_file, working_directory);
             let (tx, rx) = mpsc::channel();
             for tid in 0..num_procs {
                 let input1 = input1.clone();
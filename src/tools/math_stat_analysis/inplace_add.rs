@@ -170,14 +170,10 @@ impl WhiteboxTool for InPlaceAdd {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input1.contains(&sep) && !input1.contains("/") {
-            input1 = format!("{}{}", working_directory, input1);
-        }
+        input1 = resolve_path(&input1, working_directory);
 
         let mut input2_constant = f64::NEG_INFINITY;
         let input2_is_constant = match input2.parse::<f64>() {
@@ -188,9 +184,7 @@ impl WhiteboxTool for InPlaceAdd {
             Err(_) => false,
         };
         if !input2_is_constant {
-            if !input2.contains(&sep) && !input2.contains("/") {
-                input2 = format!("{}{}", working_directory, input2);
-            }
+            input2 = resolve_path(&input2, working_directory);
         }
 
         if verbose {
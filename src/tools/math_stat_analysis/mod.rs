@@ -12,6 +12,7 @@ mod artanh;
 mod atan2;
 mod attribute_correlation;
 mod attribute_histogram;
+mod attribute_nearest_neighbour_join;
 mod attribute_scattergram;
 mod ceil;
 mod cos;
@@ -24,6 +25,7 @@ mod divide;
 mod equal_to;
 mod exp;
 mod exp2;
+mod time_series_statistic;
 mod zonal_statistics;
 mod floor;
 mod greater_than;
@@ -96,6 +98,7 @@ pub use self::artanh::Artanh;
 pub use self::atan2::Atan2;
 pub use self::attribute_correlation::AttributeCorrelation;
 pub use self::attribute_histogram::AttributeHistogram;
+pub use self::attribute_nearest_neighbour_join::AttributeNearestNeighbourJoin;
 pub use self::attribute_scattergram::AttributeScattergram;
 pub use self::ceil::Ceil;
 pub use self::cos::Cos;
@@ -108,6 +111,7 @@ pub use self::divide::Divide;
 pub use self::equal_to::EqualTo;
 pub use self::exp::Exp;
 pub use self::exp2::Exp2;
+pub use self::time_series_statistic::TimeSeriesStatistic;
 pub use self::zonal_statistics::ZonalStatistics;
 pub use self::floor::Floor;
 pub use self::greater_than::GreaterThan;
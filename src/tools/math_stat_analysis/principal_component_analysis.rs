@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 15/03/2018
-Last Modified: 18/10/2019
+Last Modified: 17/10/2026
 License: MIT
 */
 
@@ -34,7 +34,8 @@ use std::process::Command;
 /// multi-spectral data set can be represented by the first three or four PCA components. The higher-order components are often 
 /// associated with noise in the original data set.
 /// 
-/// The user must specify the names of the multiple input images (`--inputs`). Additionally, the user must specify whether to 
+/// The user must specify the names of the multiple input images (`--inputs`). Each band of a multi-band input image, e.g. a
+/// multi-spectral GeoTIFF composite, is treated as a separate input image. Additionally, the user must specify whether to 
 /// perform a standardized PCA (`--standardized`) and the number of output components (`--num_comp`) to generate (all components 
 /// will be output unless otherwise specified). A standardized PCA is performed using the correlation matrix rather than the 
 /// variance-covariance matrix. This is appropriate when the variances in the input images differ substantially, such as would be 
@@ -244,12 +245,6 @@ impl WhiteboxTool for PrincipalComponentAnalysis {
             cmd = input_files_str.split(",");
             input_files = cmd.collect::<Vec<&str>>();
         }
-        let num_files = input_files.len();
-        if num_files < 3 {
-            return Err(Error::new(ErrorKind::InvalidInput,
-                "There is something incorrect about the input files. At least three inputs are required to operate this tool."));
-        }
-
         let wd = if working_directory.is_empty() {
             // set thw working directory to that of the first input file.
             let p = path::Path::new(input_files[0].trim());
@@ -259,9 +254,7 @@ impl WhiteboxTool for PrincipalComponentAnalysis {
             working_directory.clone().to_owned()
         };
 
-        if !output_html_file.contains(&sep) && !output_html_file.contains("/") {
-            output_html_file = format!("{}{}", wd, output_html_file);
-        }
+        output_html_file = resolve_path(&output_html_file, &wd);
 
         if !output_html_file.ends_with(".html") {
             output_html_file.push_str(".html");
@@ -272,50 +265,65 @@ impl WhiteboxTool for PrincipalComponentAnalysis {
         let mut rows = -1isize;
         let mut columns = -1isize;
 
-        let mut nodata = vec![0f64; num_files];
-        let mut average = vec![0f64; num_files];
-        let mut num_cells = vec![0f64; num_files];
-        let mut input_raster: Vec<Raster> = Vec::with_capacity(num_files);
+        let mut input_raster: Vec<Raster> = Vec::with_capacity(input_files.len());
         let mut file_names = vec![];
-        if verbose {
-            println!("Calculating image means...");
-        }
-        for i in 0..num_files {
-            if !input_files[i].trim().is_empty() {
+        for file in input_files.iter() {
+            if !file.trim().is_empty() {
                 // quality control on the image file name.
-                let mut input_file = input_files[i].trim().to_owned();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
-
-                // read the image
-                // let input = Raster::new(&input_file, "r")?;
-                input_raster.push(Raster::new(&input_file, "r")?);
+                let mut input_file = file.trim().to_owned();
+                input_file = resolve_path(&input_file, working_directory);
 
-                // get the nodata value, the number of valid cells, and the average
-                nodata[i] = input_raster[i].configs.nodata;
-                num_cells[i] = input_raster[i].num_valid_cells() as f64;
-                average[i] = input_raster[i].calculate_mean();
-                file_names.push(input_raster[i].get_short_filename());
+                // read the image; each band of a multi-band image is treated as a separate image
+                let input = Raster::new(&input_file, "r")?;
 
                 // initialize the rows and column and check that each image has the same dimensions
                 if rows == -1 || columns == -1 {
-                    rows = input_raster[i].configs.rows as isize;
-                    columns = input_raster[i].configs.columns as isize;
+                    rows = input.configs.rows as isize;
+                    columns = input.configs.columns as isize;
                 } else {
-                    if input_raster[i].configs.rows as isize != rows
-                        || input_raster[i].configs.columns as isize != columns
+                    if input.configs.rows as isize != rows
+                        || input.configs.columns as isize != columns
                     {
                         return Err(Error::new(ErrorKind::InvalidInput,
                             "All input images must share the same dimensions (rows and columns) and spatial extent."));
                     }
                 }
+
+                let short_filename = input.get_short_filename();
+                let bands = input.into_bands();
+                if bands.len() > 1 {
+                    for band in 0..bands.len() {
+                        file_names.push(format!("{} (band {})", short_filename, band + 1));
+                    }
+                } else {
+                    file_names.push(short_filename);
+                }
+                input_raster.extend(bands);
             } else {
                 return Err(Error::new(ErrorKind::InvalidInput,
                     "There is something incorrect about the input files. At least one is an empty string."));
             }
         }
 
+        let num_files = input_raster.len();
+        if num_files < 3 {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                "There is something incorrect about the input files. At least three inputs, or bands of a multi-band input, are required to operate this tool."));
+        }
+
+        // get the nodata value, the number of valid cells, and the average
+        if verbose {
+            println!("Calculating image means...");
+        }
+        let mut nodata = vec![0f64; num_files];
+        let mut average = vec![0f64; num_files];
+        let mut num_cells = vec![0f64; num_files];
+        for i in 0..num_files {
+            nodata[i] = input_raster[i].configs.nodata;
+            num_cells[i] = input_raster[i].num_valid_cells() as f64;
+            average[i] = input_raster[i].calculate_mean();
+        }
+
         if rows == -1 || columns == -1 {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
@@ -170,17 +170,11 @@ impl WhiteboxTool for RootMeanSquareError {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !base_file.contains(&sep) && !base_file.contains("/") {
-            base_file = format!("{}{}", working_directory, base_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        base_file = resolve_path(&base_file, working_directory);
 
         if verbose {
             println!("Reading data...")
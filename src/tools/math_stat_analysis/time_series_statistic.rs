@@ -0,0 +1,472 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool calculates a per-cell statistic (`--stat`) over the time (or band) dimension of a stack of
+/// co-registered rasters, e.g. the images of a time series. The stack (`--inputs`) may be specified either as a
+/// list of raster files, in temporal order, or as a single cube definition file. A definition file is either a
+/// GDAL-style virtual raster (.vrt), in which each band is sourced from a single-band raster, or a text file
+/// (.lst) containing one raster file name per line, optionally followed by a comma and the time of the layer,
+/// either as a number or as an ISO date (YYYY-MM-DD), e.g.:
+///
+/// ```text
+/// # file, time
+/// ndvi_2019.tif, 2019-06-15
+/// ndvi_2020.tif, 2020-06-12
+/// ndvi_2021.tif, 2021-06-20
+/// ```
+///
+/// Dates are converted to decimal years. When times are not specified, the zero-based layer index is used. The
+/// available statistics are:
+///
+/// | Statistic      | Description                                                               |
+/// | :------------- | :------------------------------------------------------------------------ |
+/// | `mean`         | the mean of the valid values                                              |
+/// | `min`          | the minimum value                                                         |
+/// | `max`          | the maximum value                                                         |
+/// | `range`        | the difference between the maximum and minimum values                     |
+/// | `sum`          | the sum of the valid values                                               |
+/// | `stdev`        | the (population) standard deviation of the valid values                   |
+/// | `median`       | the median of the valid values                                            |
+/// | `count`        | the number of valid (non-NoData) values                                   |
+/// | `slope`        | the least-squares linear trend of the values, in units per unit time      |
+/// | `first`        | the first valid value                                                     |
+/// | `last`         | the last valid value                                                      |
+/// | `time_of_min`  | the time of the minimum value                                             |
+/// | `time_of_max`  | the time of the maximum value                                             |
+///
+/// NoData values within a series are ignored. Cells with no valid values in any layer are assigned NoData in the
+/// output (except for `count`, which is zero), as are cells with fewer than two valid values for the `slope` and
+/// `stdev` statistics. Each of the input layers must share the same number of rows and columns and spatial extent.
+///
+/// Unlike the overlay tools, which hold each of the input rasters in memory at the same time, this tool reads the
+/// stack once into a temporary cache file and then processes it in chunks of rows, so that long time series of
+/// large rasters can be analyzed with a modest memory requirement.
+///
+/// # See Also
+/// `AverageOverlay`, `MaxOverlay`, `MinOverlay`, `PrincipalComponentAnalysis`
+pub struct TimeSeriesStatistic {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl TimeSeriesStatistic {
+    pub fn new() -> TimeSeriesStatistic {
+        // public constructor
+        let name = "TimeSeriesStatistic".to_string();
+        let toolbox = "Math and Stats Tools".to_string();
+        let description =
+            "Calculates a per-cell statistic over the time dimension of a stack of rasters."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Files".to_owned(),
+            flags: vec!["-i".to_owned(), "--inputs".to_owned()],
+            description:
+                "Input raster files, in temporal order, or a cube definition file (.vrt or .lst)."
+                    .to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Any),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Statistic".to_owned(),
+            flags: vec!["--stat".to_owned()],
+            description: "Statistic to calculate.".to_owned(),
+            parameter_type: ParameterType::OptionList(
+                vec![
+                    "mean",
+                    "min",
+                    "max",
+                    "range",
+                    "sum",
+                    "stdev",
+                    "median",
+                    "count",
+                    "slope",
+                    "first",
+                    "last",
+                    "time_of_min",
+                    "time_of_max",
+                ]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            ),
+            default_value: Some("mean".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd='*path*to*data*' -i='ndvi_2019.tif;ndvi_2020.tif;ndvi_2021.tif' -o=ndvi_mean.tif --stat=mean
+>>.*{0} -r={1} -v --wd='*path*to*data*' -i=ndvi_series.lst -o=ndvi_trend.tif --stat=slope", short_exe, name).replace("*", &sep);
+
+        TimeSeriesStatistic {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for TimeSeriesStatistic {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_files = String::new();
+        let mut output_file = String::new();
+        let mut stat = String::from("mean");
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-inputs" || flag_val == "-input" {
+                input_files = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-stat" {
+                stat = if keyval {
+                    vec[1].to_string().to_lowercase()
+                } else {
+                    args[i + 1].to_string().to_lowercase()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let statistic = match Statistic::from_str(&stat) {
+            Some(s) => s,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unrecognized statistic '{}'.", stat),
+                ))
+            }
+        };
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        output_file = resolve_path(&output_file, working_directory);
+
+        let mut cmd = input_files.split(";");
+        let mut vec = cmd.collect::<Vec<&str>>();
+        if vec.len() == 1 {
+            cmd = input_files.split(",");
+            vec = cmd.collect::<Vec<&str>>();
+        }
+        let files: Vec<String> = vec
+            .iter()
+            .filter(|f| !f.trim().is_empty())
+            .map(|f| resolve_path(f, working_directory))
+            .collect();
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let cube = if files.len() == 1 && RasterCube::is_definition_file(&files[0]) {
+            RasterCube::from_definition_file(&files[0])?
+        } else {
+            if files.len() < 2 {
+                return Err(Error::new(ErrorKind::InvalidInput,
+                    "There is something incorrect with the input files. At least two inputs, or a cube definition file, are required to operate this tool."));
+            }
+            RasterCube::new(&files)?
+        };
+
+        let start = Instant::now();
+        let rows = cube.rows();
+        let columns = cube.columns();
+        let nodata = cube.nodata();
+        let times = Arc::new(cube.times());
+        if verbose {
+            println!(
+                "The cube contains {} layers of {} rows and {} columns.",
+                cube.num_layers(),
+                rows,
+                columns
+            );
+        }
+
+        let mut output = Raster::initialize_using_config(&output_file, &cube.configs);
+        output.configs.data_type = DataType::F32;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        output.configs.palette = "spectrum.plt".to_string();
+
+        let num_procs = num_cpus::get();
+        let mut rows_completed = 0usize;
+        for chunk in cube.chunks(cube.default_chunk_rows()) {
+            let chunk = Arc::new(chunk?);
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let chunk = chunk.clone();
+                let times = times.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for row in (chunk.start_row..chunk.end_row()).filter(|r| r % num_procs == tid) {
+                        let data: Vec<f64> = (0..columns)
+                            .map(|col| statistic.calculate(chunk.series(row, col), &times, nodata))
+                            .collect();
+                        tx.send((row, data)).unwrap();
+                    }
+                });
+            }
+
+            for _ in 0..chunk.num_rows {
+                let (row, data) = rx.recv().unwrap();
+                output.set_row_data(row as isize, data);
+
+                rows_completed += 1;
+                if verbose {
+                    progress = (100.0_f64 * rows_completed as f64 / rows.max(1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Number of layers: {}", cube.num_layers()));
+        output.add_metadata_entry(format!("Statistic: {}", stat));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Statistic {
+    Mean,
+    Min,
+    Max,
+    Range,
+    Sum,
+    Stdev,
+    Median,
+    Count,
+    Slope,
+    First,
+    Last,
+    TimeOfMin,
+    TimeOfMax,
+}
+
+impl Statistic {
+    fn from_str(s: &str) -> Option<Statistic> {
+        match s {
+            "mean" | "average" => Some(Statistic::Mean),
+            "min" | "minimum" => Some(Statistic::Min),
+            "max" | "maximum" => Some(Statistic::Max),
+            "range" => Some(Statistic::Range),
+            "sum" | "total" => Some(Statistic::Sum),
+            "stdev" | "std_dev" | "sd" => Some(Statistic::Stdev),
+            "median" => Some(Statistic::Median),
+            "count" => Some(Statistic::Count),
+            "slope" | "trend" => Some(Statistic::Slope),
+            "first" => Some(Statistic::First),
+            "last" => Some(Statistic::Last),
+            "time_of_min" => Some(Statistic::TimeOfMin),
+            "time_of_max" => Some(Statistic::TimeOfMax),
+            _ => None,
+        }
+    }
+
+    /// Calculates the statistic for the series of a grid cell, ignoring NoData values.
+    fn calculate(&self, series: &[f64], times: &[f64], nodata: f64) -> f64 {
+        let valid: Vec<(f64, f64)> = series
+            .iter()
+            .zip(times)
+            .filter(|(v, _)| **v != nodata && !v.is_nan())
+            .map(|(v, t)| (*v, *t))
+            .collect();
+        let n = valid.len();
+        if n == 0 {
+            return if *self == Statistic::Count {
+                0f64
+            } else {
+                nodata
+            };
+        }
+        let min = valid.iter().fold(
+            (f64::INFINITY, 0f64),
+            |acc, p| if p.0 < acc.0 { *p } else { acc },
+        );
+        let max = valid.iter().fold((f64::NEG_INFINITY, 0f64), |acc, p| {
+            if p.0 > acc.0 {
+                *p
+            } else {
+                acc
+            }
+        });
+        let sum = valid.iter().fold(0f64, |acc, p| acc + p.0);
+        let mean = sum / n as f64;
+        match self {
+            Statistic::Mean => mean,
+            Statistic::Min => min.0,
+            Statistic::Max => max.0,
+            Statistic::Range => max.0 - min.0,
+            Statistic::Sum => sum,
+            Statistic::Stdev => {
+                if n < 2 {
+                    return nodata;
+                }
+                let ss = valid
+                    .iter()
+                    .fold(0f64, |acc, p| acc + (p.0 - mean) * (p.0 - mean));
+                (ss / n as f64).sqrt()
+            }
+            Statistic::Median => {
+                let mut values: Vec<f64> = valid.iter().map(|p| p.0).collect();
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                if n % 2 == 1 {
+                    values[n / 2]
+                } else {
+                    (values[n / 2 - 1] + values[n / 2]) / 2f64
+                }
+            }
+            Statistic::Count => n as f64,
+            Statistic::Slope => {
+                if n < 2 {
+                    return nodata;
+                }
+                let mean_t = valid.iter().fold(0f64, |acc, p| acc + p.1) / n as f64;
+                let mut sxy = 0f64;
+                let mut sxx = 0f64;
+                for (v, t) in &valid {
+                    sxy += (t - mean_t) * (v - mean);
+                    sxx += (t - mean_t) * (t - mean_t);
+                }
+                if sxx > 0f64 {
+                    sxy / sxx
+                } else {
+                    nodata
+                }
+            }
+            Statistic::First => valid[0].0,
+            Statistic::Last => valid[n - 1].0,
+            Statistic::TimeOfMin => min.1,
+            Statistic::TimeOfMax => max.1,
+        }
+    }
+}
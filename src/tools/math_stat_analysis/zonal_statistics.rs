@@ -247,26 +247,16 @@ impl WhiteboxTool for ZonalStatistics {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !features_file.contains(&sep) && !features_file.contains("/") {
-            features_file = format!("{}{}", working_directory, features_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        features_file = resolve_path(&features_file, working_directory);
         if !output_file.is_empty() {
-            if !output_file.contains(&sep) && !output_file.contains("/") {
-                output_file = format!("{}{}", working_directory, output_file);
-            }
+            output_file = resolve_path(&output_file, working_directory);
         }
         if !output_html_file.is_empty() {
-            if !output_html_file.contains(&sep) {
-                output_html_file = format!("{}{}", working_directory, output_html_file);
-            }
+            output_html_file = resolve_path(&output_html_file, working_directory);
         }
         if output_file.is_empty() && output_html_file.is_empty() {
             return Err(Error::new(
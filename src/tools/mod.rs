@@ -6,8 +6,25 @@ pub mod lidar_analysis;
 pub mod math_stat_analysis;
 pub mod stream_network_analysis;
 pub mod terrain_analysis;
+mod incremental;
+mod input_files;
+mod output_names;
+mod parameter_constraints;
+mod telemetry;
+mod tool_parameter;
+mod units;
 
-use crate::utils::get_formatted_elapsed_time;
+pub use self::incremental::find_tiles_to_update;
+pub use self::input_files::{matches_glob, InputFileFilter};
+pub use self::output_names::{expand_output_template, is_output_template};
+pub use self::parameter_constraints::ParameterConstraint;
+pub use self::telemetry::{begin_stage, peak_memory_usage, RunTelemetry};
+use self::tool_parameter::*;
+pub use self::units::{
+    check_distance_units, AngleUnits, AzimuthConvention, CoordinateUnits, LinearUnits, Units,
+};
+
+use crate::utils::{get_formatted_elapsed_time, resolve_path};
 use serde_json;
 use std::io::{Error, ErrorKind};
 use std::time::Instant;
@@ -17,6 +34,8 @@ use std::time::Instant;
 pub struct ToolManager {
     pub working_dir: String,
     pub verbose: bool,
+    /// Whether a provenance sidecar is written alongside each output file of a tool run.
+    pub provenance: bool,
     tool_names: Vec<String>,
 }
 
@@ -31,9 +50,13 @@ impl ToolManager {
         tool_names.push("CleanVector".to_string());
         tool_names.push("ConvertNodataToZero".to_string());
         tool_names.push("ConvertRasterFormat".to_string());
+        tool_names.push("CreateSpatialIndex".to_string());
         tool_names.push("CsvPointsToVector".to_string());
+        tool_names.push("EditRasterValues".to_string());
         tool_names.push("ExportTableToCsv".to_string());
+        tool_names.push("ExportTiles".to_string());
         tool_names.push("JoinTables".to_string());
+        tool_names.push("JoinTableToVector".to_string());
         tool_names.push("LinesToPolygons".to_string());
         tool_names.push("MergeTableWithCsv".to_string());
         tool_names.push("MergeVectors".to_string());
@@ -48,11 +71,15 @@ impl ToolManager {
         tool_names.push("RemovePolygonHoles".to_string());
         tool_names.push("SetNodataValue".to_string());
         tool_names.push("SinglePartToMultiPart".to_string());
+        tool_names.push("TileRaster".to_string());
+        tool_names.push("UpdateRasterFromVector".to_string());
         tool_names.push("VectorLinesToRaster".to_string());
         tool_names.push("VectorPointsToRaster".to_string());
         tool_names.push("VectorPolygonsToRaster".to_string());
+        tool_names.push("WriteRasterStatistics".to_string());
 
         // gis_analysis
+        tool_names.push("AdaptiveKernelDensity".to_string());
         tool_names.push("AggregateRaster".to_string());
         tool_names.push("AverageOverlay".to_string());
         tool_names.push("BlockMaximumGridding".to_string());
@@ -74,6 +101,8 @@ impl ToolManager {
         tool_names.push("CreateHexagonalVectorGrid".to_string());
         tool_names.push("CreatePlane".to_string());
         tool_names.push("CreateRectangularVectorGrid".to_string());
+        tool_names.push("CreateVectorTessellation".to_string());
+        tool_names.push("CutFill".to_string());
         tool_names.push("Difference".to_string());
         tool_names.push("Dissolve".to_string());
         tool_names.push("EdgeProportion".to_string());
@@ -128,7 +157,7 @@ impl ToolManager {
         tool_names.push("RelatedCircumscribingCircle".to_string());
         tool_names.push("ShapeComplexityIndex".to_string());
         tool_names.push("ShapeComplexityIndexRaster".to_string());
-        // tool_names.push("SibsonInterpolation".to_string());
+        tool_names.push("SibsonInterpolation".to_string());
         tool_names.push("SmoothVectors".to_string());
         tool_names.push("SplitWithLines".to_string());
         tool_names.push("SumOverlay".to_string());
@@ -141,12 +170,14 @@ impl ToolManager {
         tool_names.push("WeightedSum".to_string());
 
         // hydro_analysis
+        tool_names.push("AgreeBurn".to_string());
         tool_names.push("AverageFlowpathSlope".to_string());
         tool_names.push("AverageUpslopeFlowpathLength".to_string());
         tool_names.push("Basins".to_string());
         tool_names.push("BreachDepressions".to_string());
         tool_names.push("BreachSingleCellPits".to_string());
         tool_names.push("BurnStreamsAtRoads".to_string());
+        tool_names.push("ConditioningAudit".to_string());
         tool_names.push("D8FlowAccumulation".to_string());
         tool_names.push("D8MassFlux".to_string());
         tool_names.push("D8Pointer".to_string());
@@ -156,6 +187,7 @@ impl ToolManager {
         tool_names.push("DInfPointer".to_string());
         tool_names.push("DownslopeDistanceToStream".to_string());
         tool_names.push("DownslopeFlowpathLength".to_string());
+        tool_names.push("DownslopeStatistic".to_string());
         tool_names.push("ElevationAboveStream".to_string());
         tool_names.push("ElevationAboveStreamEuclidean".to_string());
         tool_names.push("FD8FlowAccumulation".to_string());
@@ -165,10 +197,14 @@ impl ToolManager {
         tool_names.push("FillSingleCellPits".to_string());
         tool_names.push("FindNoFlowCells".to_string());
         tool_names.push("FindParallelFlow".to_string());
+        tool_names.push("FindPitsAndFlats".to_string());
         tool_names.push("FlattenLakes".to_string());
         tool_names.push("FloodOrder".to_string());
         tool_names.push("FlowAccumulationFullWorkflow".to_string());
         tool_names.push("FlowLengthDiff".to_string());
+        tool_names.push("GaugeCatchments".to_string());
+        tool_names.push("GeomorphicFloodplain".to_string());
+        tool_names.push("HillslopeLinkage".to_string());
         tool_names.push("Hillslopes".to_string());
         tool_names.push("ImpoundmentSizeIndex".to_string());
         tool_names.push("Isobasins".to_string());
@@ -177,7 +213,9 @@ impl ToolManager {
         tool_names.push("MaxUpslopeFlowpathLength".to_string());
         tool_names.push("NumInflowingNeighbours".to_string());
         tool_names.push("RaiseWalls".to_string());
+        tool_names.push("RelativeElevationModel".to_string());
         tool_names.push("Rho8Pointer".to_string());
+        tool_names.push("SeaLevelRiseInundation".to_string());
         tool_names.push("Sink".to_string());
         tool_names.push("SnapPourPoints".to_string());
         tool_names.push("StochasticDepressionAnalysis".to_string());
@@ -185,13 +223,16 @@ impl ToolManager {
         tool_names.push("Subbasins".to_string());
         tool_names.push("TraceDownslopeFlowpaths".to_string());
         tool_names.push("UnnestBasins".to_string());
+        tool_names.push("UpslopeStatistic".to_string());
         tool_names.push("Watershed".to_string());
+        tool_names.push("WatershedStratifiedSample".to_string());
 
         // image_analysis
         tool_names.push("AdaptiveFilter".to_string());
         tool_names.push("BalanceContrastEnhancement".to_string());
         tool_names.push("BilateralFilter".to_string());
         tool_names.push("ChangeVectorAnalysis".to_string());
+        tool_names.push("ClassProbabilitySmoothing".to_string());
         tool_names.push("Closing".to_string());
         tool_names.push("ConservativeSmoothingFilter".to_string());
         tool_names.push("CornerDetection".to_string());
@@ -207,16 +248,19 @@ impl ToolManager {
         tool_names.push("GammaCorrection".to_string());
         tool_names.push("GaussianContrastStretch".to_string());
         tool_names.push("GaussianFilter".to_string());
+        tool_names.push("GeneralizeClassifiedRaster".to_string());
         tool_names.push("HighPassFilter".to_string());
         tool_names.push("HighPassMedianFilter".to_string());
         tool_names.push("HistogramEqualization".to_string());
         tool_names.push("HistogramMatching".to_string());
         tool_names.push("HistogramMatchingTwoImages".to_string());
+        tool_names.push("HsvToRgb".to_string());
         tool_names.push("IhsToRgb".to_string());
         tool_names.push("ImageStackProfile".to_string());
         tool_names.push("IntegralImage".to_string());
         tool_names.push("KMeansClustering".to_string());
         tool_names.push("KNearestMeanFilter".to_string());
+        tool_names.push("LabToRgb".to_string());
         tool_names.push("LaplacianFilter".to_string());
         tool_names.push("LaplacianOfGaussianFilter".to_string());
         tool_names.push("LeeSigmaFilter".to_string());
@@ -238,10 +282,14 @@ impl ToolManager {
         tool_names.push("PercentageContrastStretch".to_string());
         tool_names.push("PercentileFilter".to_string());
         tool_names.push("PrewittFilter".to_string());
+        tool_names.push("RandomForestRegression".to_string());
         tool_names.push("RangeFilter".to_string());
+        tool_names.push("RegionGrowing".to_string());
         tool_names.push("RemoveSpurs".to_string());
         tool_names.push("Resample".to_string());
+        tool_names.push("RgbToHsv".to_string());
         tool_names.push("RgbToIhs".to_string());
+        tool_names.push("RgbToLab".to_string());
         tool_names.push("RobertsCrossFilter".to_string());
         tool_names.push("ScharrFilter".to_string());
         tool_names.push("SigmoidalContrastStretch".to_string());
@@ -258,6 +306,7 @@ impl ToolManager {
 
         // lidar_analysis
         // tool_names.push("AsciiToLas".to_string());
+        tool_names.push("ClipLidarToCorridor".to_string());
         tool_names.push("LidarBlockMaximum".to_string());
         tool_names.push("LidarBlockMinimum".to_string());
         tool_names.push("ClassifyOverlapPoints".to_string());
@@ -270,32 +319,56 @@ impl ToolManager {
         tool_names.push("LasToAscii".to_string());
         tool_names.push("LasToMultipointShapefile".to_string());
         tool_names.push("LasToShapefile".to_string());
+        tool_names.push("LidarCanopyLayers".to_string());
+        tool_names.push("LidarCanopyMetrics".to_string());
+        tool_names.push("LidarCellStats".to_string());
+        tool_names.push("LidarClassifyFromRaster".to_string());
+        tool_names.push("LidarClassifyPowerlines".to_string());
         tool_names.push("LidarClassifySubset".to_string());
         tool_names.push("LidarColourize".to_string());
         tool_names.push("LidarConstructVectorTIN".to_string());
+        tool_names.push("LidarDistanceToSurface".to_string());
+        tool_names.push("LidarEigenvalueFeatures".to_string());
         tool_names.push("LidarElevationSlice".to_string());
+        tool_names.push("LidarExtractBuildings".to_string());
+        tool_names.push("LidarExtractWaterBodies".to_string());
         tool_names.push("LidarGroundPointFilter".to_string());
         tool_names.push("LidarHexBinning".to_string());
         tool_names.push("LidarHillshade".to_string());
         tool_names.push("LidarHistogram".to_string());
         tool_names.push("LidarIdwInterpolation".to_string());
+        tool_names.push("LidarIndex".to_string());
         tool_names.push("LidarInfo".to_string());
         tool_names.push("LidarJoin".to_string());
         tool_names.push("LidarKappaIndex".to_string());
+        tool_names.push("LidarKrigingInterpolation".to_string());
+        tool_names.push("LidarMccFilter".to_string());
         tool_names.push("LidarNearestNeighbourGridding".to_string());
+        tool_names.push("LidarNormalizeByDem".to_string());
         tool_names.push("LidarPointDensity".to_string());
+        tool_names.push("LidarPointNormals".to_string());
         tool_names.push("LidarPointStats".to_string());
+        tool_names.push("LidarQcReport".to_string());
         tool_names.push("LidarRansacPlanes".to_string());
         tool_names.push("LidarRemoveDuplicates".to_string());
         tool_names.push("LidarRemoveOutliers".to_string());
+        tool_names.push("LidarReproject".to_string());
         tool_names.push("LidarSegmentation".to_string());
         tool_names.push("LidarSegmentationBasedFilter".to_string());
+        tool_names.push("LidarSmrfFilter".to_string());
+        tool_names.push("LidarSorFilter".to_string());
+        tool_names.push("LidarSplit".to_string());
+        tool_names.push("LidarStripAlign".to_string());
         tool_names.push("LidarThin".to_string());
+        tool_names.push("LidarThinByVoxel".to_string());
         tool_names.push("LidarThinHighDensity".to_string());
         tool_names.push("LidarTile".to_string());
+        tool_names.push("LidarTileByPolygon".to_string());
         tool_names.push("LidarTileFootprint".to_string());
         tool_names.push("LidarTINGridding".to_string());
+        tool_names.push("LidarTINToMesh".to_string());
         tool_names.push("LidarTophatTransform".to_string());
+        tool_names.push("LidarWaveformMetrics".to_string());
         tool_names.push("NormalVectors".to_string());
         tool_names.push("SelectTilesByPolygon".to_string());
 
@@ -313,6 +386,7 @@ impl ToolManager {
         tool_names.push("Artanh".to_string());
         tool_names.push("AttributeCorrelation".to_string());
         tool_names.push("AttributeHistogram".to_string());
+        tool_names.push("AttributeNearestNeighbourJoin".to_string());
         tool_names.push("AttributeScattergram".to_string());
         tool_names.push("Ceil".to_string());
         tool_names.push("Cos".to_string());
@@ -325,6 +399,7 @@ impl ToolManager {
         tool_names.push("EqualTo".to_string());
         tool_names.push("Exp".to_string());
         tool_names.push("Exp2".to_string());
+        tool_names.push("TimeSeriesStatistic".to_string());
         tool_names.push("ZonalStatistics".to_string());
         tool_names.push("Floor".to_string());
         tool_names.push("GreaterThan".to_string());
@@ -394,6 +469,7 @@ impl ToolManager {
         tool_names.push("LengthOfUpstreamChannels".to_string());
         tool_names.push("LongProfile".to_string());
         tool_names.push("LongProfileFromPoints".to_string());
+        tool_names.push("PropagateStreamLinkAttributes".to_string());
         tool_names.push("RasterizeStreams".to_string());
         tool_names.push("RasterStreamsToVector".to_string());
         tool_names.push("RemoveShortStreams".to_string());
@@ -408,6 +484,7 @@ impl ToolManager {
         tool_names.push("TributaryIdentifier".to_string());
 
         // terrain_analysis
+        tool_names.push("ArchaeologicalVisualization".to_string());
         tool_names.push("Aspect".to_string());
         tool_names.push("AverageNormalVectorAngularDeviation".to_string());
         tool_names.push("CircularVarianceOfAspect".to_string());
@@ -424,10 +501,12 @@ impl ToolManager {
         tool_names.push("FeaturePreservingSmoothing".to_string());
         tool_names.push("FetchAnalysis".to_string());
         tool_names.push("FillMissingData".to_string());
+        tool_names.push("FindElevationSeams".to_string());
         tool_names.push("FindRidges".to_string());
         // tool_names.push("Geomorphons".to_string());
         tool_names.push("Hillshade".to_string());
         tool_names.push("HorizonAngle".to_string());
+        tool_names.push("HydroEnforcedSmoothing".to_string());
         tool_names.push("HypsometricAnalysis".to_string());
         tool_names.push("MaxAnisotropyDev".to_string());
         tool_names.push("MaxAnisotropyDevSignature".to_string());
@@ -450,6 +529,9 @@ impl ToolManager {
         tool_names.push("ProfileCurvature".to_string());
         tool_names.push("Profile".to_string());
         tool_names.push("RelativeAspect".to_string());
+        tool_names.push("ShadowMask".to_string());
+        tool_names.push("SnowDepthDifferencing".to_string());
+        tool_names.push("SnowmeltIndex".to_string());
         tool_names.push("StreamPowerIndex".to_string());
         tool_names.push("RelativeTopographicPosition".to_string());
         tool_names.push("RemoveOffTerrainObjects".to_string());
@@ -461,6 +543,7 @@ impl ToolManager {
         tool_names.push("StandardDeviationOfSlope".to_string());
         tool_names.push("SurfaceAreaRatio".to_string());
         tool_names.push("TangentialCurvature".to_string());
+        tool_names.push("TerrainSimilarity".to_string());
         tool_names.push("TotalCurvature".to_string());
         tool_names.push("Viewshed".to_string());
         tool_names.push("VisibilityIndex".to_string());
@@ -471,6 +554,7 @@ impl ToolManager {
         let tm = ToolManager {
             working_dir: working_directory.to_string(),
             verbose: *verbose_mode,
+            provenance: false,
             tool_names: tool_names,
         };
         Ok(tm)
@@ -485,9 +569,13 @@ impl ToolManager {
             "cleanvector" => Some(Box::new(data_tools::CleanVector::new())),
             "convertnodatatozero" => Some(Box::new(data_tools::ConvertNodataToZero::new())),
             "convertrasterformat" => Some(Box::new(data_tools::ConvertRasterFormat::new())),
+            "createspatialindex" => Some(Box::new(data_tools::CreateSpatialIndex::new())),
             "csvpointstovector" => Some(Box::new(data_tools::CsvPointsToVector::new())),
+            "editrastervalues" => Some(Box::new(data_tools::EditRasterValues::new())),
             "exporttabletocsv" => Some(Box::new(data_tools::ExportTableToCsv::new())),
+            "exporttiles" => Some(Box::new(data_tools::ExportTiles::new())),
             "jointables" => Some(Box::new(data_tools::JoinTables::new())),
+            "jointabletovector" => Some(Box::new(data_tools::JoinTableToVector::new())),
             "linestopolygons" => Some(Box::new(data_tools::LinesToPolygons::new())),
             "mergetablewithcsv" => Some(Box::new(data_tools::MergeTableWithCsv::new())),
             "mergevectors" => Some(Box::new(data_tools::MergeVectors::new())),
@@ -504,11 +592,15 @@ impl ToolManager {
             "removepolygonholes" => Some(Box::new(data_tools::RemovePolygonHoles::new())),
             "setnodatavalue" => Some(Box::new(data_tools::SetNodataValue::new())),
             "singleparttomultipart" => Some(Box::new(data_tools::SinglePartToMultiPart::new())),
+            "tileraster" => Some(Box::new(data_tools::TileRaster::new())),
+            "updaterasterfromvector" => Some(Box::new(data_tools::UpdateRasterFromVector::new())),
             "vectorlinestoraster" => Some(Box::new(data_tools::VectorLinesToRaster::new())),
             "vectorpointstoraster" => Some(Box::new(data_tools::VectorPointsToRaster::new())),
             "vectorpolygonstoraster" => Some(Box::new(data_tools::VectorPolygonsToRaster::new())),
+            "writerasterstatistics" => Some(Box::new(data_tools::WriteRasterStatistics::new())),
 
             // gis_analysis
+            "adaptivekerneldensity" => Some(Box::new(gis_analysis::AdaptiveKernelDensity::new())),
             "aggregateraster" => Some(Box::new(gis_analysis::AggregateRaster::new())),
             "averageoverlay" => Some(Box::new(gis_analysis::AverageOverlay::new())),
             "blockmaximumgridding" => Some(Box::new(gis_analysis::BlockMaximumGridding::new())),
@@ -534,6 +626,10 @@ impl ToolManager {
             "createrectangularvectorgrid" => {
                 Some(Box::new(gis_analysis::CreateRectangularVectorGrid::new()))
             }
+            "createvectortessellation" => {
+                Some(Box::new(gis_analysis::CreateVectorTessellation::new()))
+            }
+            "cutfill" => Some(Box::new(gis_analysis::CutFill::new())),
             "difference" => Some(Box::new(gis_analysis::Difference::new())),
             "dissolve" => Some(Box::new(gis_analysis::Dissolve::new())),
             "edgeproportion" => Some(Box::new(gis_analysis::EdgeProportion::new())),
@@ -602,9 +698,7 @@ impl ToolManager {
             }
             "shapecomplexityindex" => Some(Box::new(gis_analysis::ShapeComplexityIndex::new())),
             "shapecomplexityindexraster" => Some(Box::new(gis_analysis::ShapeComplexityIndexRaster::new())),
-            // "sibsoninterpolation" => {
-            //     Some(Box::new(gis_analysis::SibsonInterpolation::new()))
-            // }
+            "sibsoninterpolation" => Some(Box::new(gis_analysis::SibsonInterpolation::new())),
             "smoothvectors" => Some(Box::new(gis_analysis::SmoothVectors::new())),
             "splitwithlines" => Some(Box::new(gis_analysis::SplitWithLines::new())),
             "sumoverlay" => Some(Box::new(gis_analysis::SumOverlay::new())),
@@ -617,6 +711,7 @@ impl ToolManager {
             "weightedsum" => Some(Box::new(gis_analysis::WeightedSum::new())),
 
             // hydro_analysis
+            "agreeburn" => Some(Box::new(hydro_analysis::AgreeBurn::new())),
             "averageflowpathslope" => Some(Box::new(hydro_analysis::AverageFlowpathSlope::new())),
             "averageupslopeflowpathlength" => {
                 Some(Box::new(hydro_analysis::AverageUpslopeFlowpathLength::new()))
@@ -625,6 +720,7 @@ impl ToolManager {
             "breachdepressions" => Some(Box::new(hydro_analysis::BreachDepressions::new())),
             "breachsinglecellpits" => Some(Box::new(hydro_analysis::BreachSingleCellPits::new())),
             "burnstreamsatroads" => Some(Box::new(hydro_analysis::BurnStreamsAtRoads::new())),
+            "conditioningaudit" => Some(Box::new(hydro_analysis::ConditioningAudit::new())),
             "d8flowaccumulation" => Some(Box::new(hydro_analysis::D8FlowAccumulation::new())),
             "d8massflux" => Some(Box::new(hydro_analysis::D8MassFlux::new())),
             "d8pointer" => Some(Box::new(hydro_analysis::D8Pointer::new())),
@@ -638,6 +734,7 @@ impl ToolManager {
             "downslopeflowpathlength" => {
                 Some(Box::new(hydro_analysis::DownslopeFlowpathLength::new()))
             }
+            "downslopestatistic" => Some(Box::new(hydro_analysis::DownslopeStatistic::new())),
             "elevationabovestream" => Some(Box::new(hydro_analysis::ElevationAboveStream::new())),
             "elevationabovestreameuclidean" => Some(Box::new(
                 hydro_analysis::ElevationAboveStreamEuclidean::new(),
@@ -649,12 +746,16 @@ impl ToolManager {
             "fillsinglecellpits" => Some(Box::new(hydro_analysis::FillSingleCellPits::new())),
             "findnoflowcells" => Some(Box::new(hydro_analysis::FindNoFlowCells::new())),
             "findparallelflow" => Some(Box::new(hydro_analysis::FindParallelFlow::new())),
+            "findpitsandflats" => Some(Box::new(hydro_analysis::FindPitsAndFlats::new())),
             "flattenlakes" => Some(Box::new(hydro_analysis::FlattenLakes::new())),
             "floodorder" => Some(Box::new(hydro_analysis::FloodOrder::new())),
             "flowaccumulationfullworkflow" => {
                 Some(Box::new(hydro_analysis::FlowAccumulationFullWorkflow::new()))
             }
             "flowlengthdiff" => Some(Box::new(hydro_analysis::FlowLengthDiff::new())),
+            "gaugecatchments" => Some(Box::new(hydro_analysis::GaugeCatchments::new())),
+            "geomorphicfloodplain" => Some(Box::new(hydro_analysis::GeomorphicFloodplain::new())),
+            "hillslopelinkage" => Some(Box::new(hydro_analysis::HillslopeLinkage::new())),
             "hillslopes" => Some(Box::new(hydro_analysis::Hillslopes::new())),
             "impoundmentsizeindex" => Some(Box::new(hydro_analysis::ImpoundmentSizeIndex::new())),
             "isobasins" => Some(Box::new(hydro_analysis::Isobasins::new())),
@@ -667,7 +768,11 @@ impl ToolManager {
                 Some(Box::new(hydro_analysis::NumInflowingNeighbours::new()))
             }
             "raisewalls" => Some(Box::new(hydro_analysis::RaiseWalls::new())),
+            "relativeelevationmodel" => {
+                Some(Box::new(hydro_analysis::RelativeElevationModel::new()))
+            }
             "rho8pointer" => Some(Box::new(hydro_analysis::Rho8Pointer::new())),
+            "sealevelriseinundation" => Some(Box::new(hydro_analysis::SeaLevelRiseInundation::new())),
             "sink" => Some(Box::new(hydro_analysis::Sink::new())),
             "snappourpoints" => Some(Box::new(hydro_analysis::SnapPourPoints::new())),
             "stochasticdepressionanalysis" => {
@@ -679,7 +784,11 @@ impl ToolManager {
                 Some(Box::new(hydro_analysis::TraceDownslopeFlowpaths::new()))
             }
             "unnestbasins" => Some(Box::new(hydro_analysis::UnnestBasins::new())),
+            "upslopestatistic" => Some(Box::new(hydro_analysis::UpslopeStatistic::new())),
             "watershed" => Some(Box::new(hydro_analysis::Watershed::new())),
+            "watershedstratifiedsample" => {
+                Some(Box::new(hydro_analysis::WatershedStratifiedSample::new()))
+            }
 
             // image_analysis
             "adaptivefilter" => Some(Box::new(image_analysis::AdaptiveFilter::new())),
@@ -688,6 +797,7 @@ impl ToolManager {
             }
             "bilateralfilter" => Some(Box::new(image_analysis::BilateralFilter::new())),
             "changevectoranalysis" => Some(Box::new(image_analysis::ChangeVectorAnalysis::new())),
+            "classprobabilitysmoothing" => Some(Box::new(image_analysis::ClassProbabilitySmoothing::new())),
             "closing" => Some(Box::new(image_analysis::Closing::new())),
             "cornerdetection" => Some(Box::new(image_analysis::CornerDetection::new())),
             "correctvignetting" => Some(Box::new(image_analysis::CorrectVignetting::new())),
@@ -713,6 +823,7 @@ impl ToolManager {
                 Some(Box::new(image_analysis::GaussianContrastStretch::new()))
             }
             "gaussianfilter" => Some(Box::new(image_analysis::GaussianFilter::new())),
+            "generalizeclassifiedraster" => Some(Box::new(image_analysis::GeneralizeClassifiedRaster::new())),
             "highpassfilter" => Some(Box::new(image_analysis::HighPassFilter::new())),
             "highpassmedianfilter" => Some(Box::new(image_analysis::HighPassMedianFilter::new())),
             "histogramequalization" => Some(Box::new(image_analysis::HistogramEqualization::new())),
@@ -720,11 +831,13 @@ impl ToolManager {
             "histogrammatchingtwoimages" => {
                 Some(Box::new(image_analysis::HistogramMatchingTwoImages::new()))
             }
+            "hsvtorgb" => Some(Box::new(image_analysis::HsvToRgb::new())),
             "ihstorgb" => Some(Box::new(image_analysis::IhsToRgb::new())),
             "imagestackprofile" => Some(Box::new(image_analysis::ImageStackProfile::new())),
             "integralimage" => Some(Box::new(image_analysis::IntegralImage::new())),
             "kmeansclustering" => Some(Box::new(image_analysis::KMeansClustering::new())),
             "knearestmeanfilter" => Some(Box::new(image_analysis::KNearestMeanFilter::new())),
+            "labtorgb" => Some(Box::new(image_analysis::LabToRgb::new())),
             "laplacianfilter" => Some(Box::new(image_analysis::LaplacianFilter::new())),
             "laplacianofgaussianfilter" => {
                 Some(Box::new(image_analysis::LaplacianOfGaussianFilter::new()))
@@ -756,10 +869,16 @@ impl ToolManager {
             }
             "percentilefilter" => Some(Box::new(image_analysis::PercentileFilter::new())),
             "prewittfilter" => Some(Box::new(image_analysis::PrewittFilter::new())),
+            "randomforestregression" => Some(Box::new(
+                image_analysis::RandomForestRegression::new(),
+            )),
             "rangefilter" => Some(Box::new(image_analysis::RangeFilter::new())),
+            "regiongrowing" => Some(Box::new(image_analysis::RegionGrowing::new())),
             "removespurs" => Some(Box::new(image_analysis::RemoveSpurs::new())),
             "resample" => Some(Box::new(image_analysis::Resample::new())),
+            "rgbtohsv" => Some(Box::new(image_analysis::RgbToHsv::new())),
             "rgbtoihs" => Some(Box::new(image_analysis::RgbToIhs::new())),
+            "rgbtolab" => Some(Box::new(image_analysis::RgbToLab::new())),
             "robertscrossfilter" => Some(Box::new(image_analysis::RobertsCrossFilter::new())),
             "scharrfilter" => Some(Box::new(image_analysis::ScharrFilter::new())),
             "sigmoidalcontraststretch" => {
@@ -785,7 +904,8 @@ impl ToolManager {
             }
 
             // lidar_analysis
-            // "asciitolas" => Some(Box::new(lidar_analysis::AsciiToLas::new())),
+            "asciitolas" => Some(Box::new(lidar_analysis::AsciiToLas::new())),
+            "cliplidartocorridor" => Some(Box::new(lidar_analysis::ClipLidarToCorridor::new())),
             "lidarblockmaximum" => Some(Box::new(lidar_analysis::LidarBlockMaximum::new())),
             "lidarblockminimum" => Some(Box::new(lidar_analysis::LidarBlockMinimum::new())),
             "classifyoverlappoints" => Some(Box::new(lidar_analysis::ClassifyOverlapPoints::new())),
@@ -802,12 +922,21 @@ impl ToolManager {
                 Some(Box::new(lidar_analysis::LasToMultipointShapefile::new()))
             }
             "lastoshapefile" => Some(Box::new(lidar_analysis::LasToShapefile::new())),
+            "lidarcanopylayers" => Some(Box::new(lidar_analysis::LidarCanopyLayers::new())),
+            "lidarcanopymetrics" => Some(Box::new(lidar_analysis::LidarCanopyMetrics::new())),
+            "lidarcellstats" => Some(Box::new(lidar_analysis::LidarCellStats::new())),
+            "lidarclassifyfromraster" => Some(Box::new(lidar_analysis::LidarClassifyFromRaster::new())),
+            "lidarclassifypowerlines" => Some(Box::new(lidar_analysis::LidarClassifyPowerlines::new())),
             "lidarclassifysubset" => Some(Box::new(lidar_analysis::LidarClassifySubset::new())),
             "lidarcolourize" => Some(Box::new(lidar_analysis::LidarColourize::new())),
             "lidarconstructvectortin" => {
                 Some(Box::new(lidar_analysis::LidarConstructVectorTIN::new()))
             }
+            "lidardistancetosurface" => Some(Box::new(lidar_analysis::LidarDistanceToSurface::new())),
+            "lidareigenvaluefeatures" => Some(Box::new(lidar_analysis::LidarEigenvalueFeatures::new())),
             "lidarelevationslice" => Some(Box::new(lidar_analysis::LidarElevationSlice::new())),
+            "lidarextractbuildings" => Some(Box::new(lidar_analysis::LidarExtractBuildings::new())),
+            "lidarextractwaterbodies" => Some(Box::new(lidar_analysis::LidarExtractWaterBodies::new())),
             "lidargroundpointfilter" => {
                 Some(Box::new(lidar_analysis::LidarGroundPointFilter::new()))
             }
@@ -815,27 +944,42 @@ impl ToolManager {
             "lidarhillshade" => Some(Box::new(lidar_analysis::LidarHillshade::new())),
             "lidarhistogram" => Some(Box::new(lidar_analysis::LidarHistogram::new())),
             "lidaridwinterpolation" => Some(Box::new(lidar_analysis::LidarIdwInterpolation::new())),
+            "lidarindex" => Some(Box::new(lidar_analysis::LidarIndex::new())),
             "lidarinfo" => Some(Box::new(lidar_analysis::LidarInfo::new())),
             "lidarjoin" => Some(Box::new(lidar_analysis::LidarJoin::new())),
             "lidarkappaindex" => Some(Box::new(lidar_analysis::LidarKappaIndex::new())),
+            "lidarkriginginterpolation" => Some(Box::new(lidar_analysis::LidarKrigingInterpolation::new())),
+            "lidarmccfilter" => Some(Box::new(lidar_analysis::LidarMccFilter::new())),
             "lidarnearestneighbourgridding" => Some(Box::new(
                 lidar_analysis::LidarNearestNeighbourGridding::new(),
             )),
+            "lidarnormalizebydem" => Some(Box::new(lidar_analysis::LidarNormalizeByDem::new())),
             "lidarpointdensity" => Some(Box::new(lidar_analysis::LidarPointDensity::new())),
+            "lidarpointnormals" => Some(Box::new(lidar_analysis::LidarPointNormals::new())),
             "lidarpointstats" => Some(Box::new(lidar_analysis::LidarPointStats::new())),
+            "lidarqcreport" => Some(Box::new(lidar_analysis::LidarQcReport::new())),
             "lidarransacplanes" => Some(Box::new(lidar_analysis::LidarRansacPlanes::new())),
             "lidarremoveduplicates" => Some(Box::new(lidar_analysis::LidarRemoveDuplicates::new())),
             "lidarremoveoutliers" => Some(Box::new(lidar_analysis::LidarRemoveOutliers::new())),
+            "lidarreproject" => Some(Box::new(lidar_analysis::LidarReproject::new())),
             "lidarsegmentation" => Some(Box::new(lidar_analysis::LidarSegmentation::new())),
             "lidarsegmentationbasedfilter" => {
                 Some(Box::new(lidar_analysis::LidarSegmentationBasedFilter::new()))
             }
+            "lidarsmrffilter" => Some(Box::new(lidar_analysis::LidarSmrfFilter::new())),
+            "lidarsorfilter" => Some(Box::new(lidar_analysis::LidarSorFilter::new())),
+            "lidarsplit" => Some(Box::new(lidar_analysis::LidarSplit::new())),
+            "lidarstripalign" => Some(Box::new(lidar_analysis::LidarStripAlign::new())),
             "lidarthin" => Some(Box::new(lidar_analysis::LidarThin::new())),
+            "lidarthinbyvoxel" => Some(Box::new(lidar_analysis::LidarThinByVoxel::new())),
             "lidarthinhighdensity" => Some(Box::new(lidar_analysis::LidarThinHighDensity::new())),
             "lidartile" => Some(Box::new(lidar_analysis::LidarTile::new())),
+            "lidartilebypolygon" => Some(Box::new(lidar_analysis::LidarTileByPolygon::new())),
             "lidartilefootprint" => Some(Box::new(lidar_analysis::LidarTileFootprint::new())),
             "lidartingridding" => Some(Box::new(lidar_analysis::LidarTINGridding::new())),
+            "lidartintomesh" => Some(Box::new(lidar_analysis::LidarTINToMesh::new())),
             "lidartophattransform" => Some(Box::new(lidar_analysis::LidarTophatTransform::new())),
+            "lidarwaveformmetrics" => Some(Box::new(lidar_analysis::LidarWaveformMetrics::new())),
             "normalvectors" => Some(Box::new(lidar_analysis::NormalVectors::new())),
             "selecttilesbypolygon" => Some(Box::new(lidar_analysis::SelectTilesByPolygon::new())),
 
@@ -855,6 +999,9 @@ impl ToolManager {
                 Some(Box::new(math_stat_analysis::AttributeCorrelation::new()))
             }
             "attributehistogram" => Some(Box::new(math_stat_analysis::AttributeHistogram::new())),
+            "attributenearestneighbourjoin" => Some(Box::new(
+                math_stat_analysis::AttributeNearestNeighbourJoin::new(),
+            )),
             "attributescattergram" => {
                 Some(Box::new(math_stat_analysis::AttributeScattergram::new()))
             }
@@ -871,6 +1018,7 @@ impl ToolManager {
             "equalto" => Some(Box::new(math_stat_analysis::EqualTo::new())),
             "exp" => Some(Box::new(math_stat_analysis::Exp::new())),
             "exp2" => Some(Box::new(math_stat_analysis::Exp2::new())),
+            "timeseriesstatistic" => Some(Box::new(math_stat_analysis::TimeSeriesStatistic::new())),
             "zonalstatistics" => {
                 Some(Box::new(math_stat_analysis::ZonalStatistics::new()))
             }
@@ -958,6 +1106,9 @@ impl ToolManager {
             "longprofilefrompoints" => Some(Box::new(
                 stream_network_analysis::LongProfileFromPoints::new(),
             )),
+            "propagatestreamlinkattributes" => Some(Box::new(
+                stream_network_analysis::PropagateStreamLinkAttributes::new(),
+            )),
             "rasterizestreams" => Some(Box::new(stream_network_analysis::RasterizeStreams::new())),
             "rasterstreamstovector" => Some(Box::new(
                 stream_network_analysis::RasterStreamsToVector::new(),
@@ -988,6 +1139,7 @@ impl ToolManager {
             }
 
             // terrain_analysis
+            "archaeologicalvisualization" => Some(Box::new(terrain_analysis::ArchaeologicalVisualization::new())),
             "aspect" => Some(Box::new(terrain_analysis::Aspect::new())),
             "averagenormalvectorangulardeviation" => Some(Box::new(terrain_analysis::AverageNormalVectorAngularDeviation::new())),
             "circularvarianceofaspect" => Some(Box::new(terrain_analysis::CircularVarianceOfAspect::new())),
@@ -996,6 +1148,13 @@ impl ToolManager {
             "directionalrelief" => Some(Box::new(terrain_analysis::DirectionalRelief::new())),
             "downslopeindex" => Some(Box::new(terrain_analysis::DownslopeIndex::new())),
             // "drainagepreservingsmoothing" => Some(Box::new(
+            "findelevationseams" => Some(Box::new(terrain_analysis::FindElevationSeams::new())),
+            "shadowmask" => Some(Box::new(terrain_analysis::ShadowMask::new())),
+            "snowdepthdifferencing" => {
+                Some(Box::new(terrain_analysis::SnowDepthDifferencing::new()))
+            }
+            "snowmeltindex" => Some(Box::new(terrain_analysis::SnowmeltIndex::new())),
+            "terrainsimilarity" => Some(Box::new(terrain_analysis::TerrainSimilarity::new())),
             //     terrain_analysis::DrainagePreservingSmoothing::new(),
             // )),
             "edgedensity" => Some(Box::new(terrain_analysis::EdgeDensity::new())),
@@ -1014,6 +1173,9 @@ impl ToolManager {
             // "geomorphons" => Some(Box::new(terrain_analysis::Geomorphons::new())),
             "hillshade" => Some(Box::new(terrain_analysis::Hillshade::new())),
             "horizonangle" => Some(Box::new(terrain_analysis::HorizonAngle::new())),
+            "hydroenforcedsmoothing" => {
+                Some(Box::new(terrain_analysis::HydroEnforcedSmoothing::new()))
+            }
             "hypsometricanalysis" => Some(Box::new(terrain_analysis::HypsometricAnalysis::new())),
             "maxanisotropydev" => Some(Box::new(terrain_analysis::MaxAnisotropyDev::new())),
             "maxanisotropydevsignature" => {
@@ -1088,7 +1250,42 @@ impl ToolManager {
         // }
 
         match self.get_tool(tool_name.as_ref()) {
-            Some(tool) => return tool.run(args, &self.working_dir, self.verbose),
+            Some(tool) => {
+                telemetry::start("Argument validation");
+                let args = output_names::expand_output_args(
+                    &tool.get_tool_parameters(),
+                    &args,
+                    &tool.get_tool_name(),
+                    tool.get_default_output_template(),
+                );
+                parameter_constraints::validate_args(
+                    &tool.get_tool_parameters(),
+                    &tool.get_parameter_constraints(),
+                    &args,
+                )?;
+                telemetry::begin_stage("Tool run");
+                tool.run(args.clone(), &self.working_dir, self.verbose)?;
+                let run_telemetry = telemetry::finish(&tool.get_tool_name());
+                if self.verbose {
+                    println!("{}", run_telemetry.report());
+                }
+                if self.provenance {
+                    for output_file in
+                        output_names::find_output_files(&tool.get_tool_parameters(), &args)
+                    {
+                        let output_file = resolve_path(&output_file, &self.working_dir);
+                        if std::path::Path::new(&output_file).exists() {
+                            telemetry::write_provenance_sidecar(
+                                &output_file,
+                                &args,
+                                &self.working_dir,
+                                &run_telemetry,
+                            )?;
+                        }
+                    }
+                }
+                return Ok(());
+            }
             None => {
                 return Err(Error::new(
                     ErrorKind::NotFound,
@@ -1122,7 +1319,13 @@ impl ToolManager {
 
     pub fn tool_parameters(&self, tool_name: String) -> Result<(), Error> {
         match self.get_tool(tool_name.as_ref()) {
-            Some(tool) => println!("{}", tool.get_tool_parameters()),
+            Some(tool) => println!(
+                "{}",
+                parameter_constraints::add_constraints_to_parameters(
+                    &tool.get_tool_parameters(),
+                    &tool.get_parameter_constraints()
+                )
+            ),
             None => {
                 return Err(Error::new(
                     ErrorKind::NotFound,
@@ -1223,6 +1426,18 @@ pub trait WhiteboxTool {
         working_directory: &'a str,
         verbose: bool,
     ) -> Result<(), Error>;
+
+    /// Returns the constraints on the tool's parameters, keyed on a flag of the constrained
+    /// parameter. These are checked against the tool's arguments before the tool is run.
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![]
+    }
+
+    /// Returns the template used to name the tool's output file when it is unspecified, e.g.
+    /// `{input_stem}_hs{input_ext}`. See `expand_output_template` for the supported placeholders.
+    fn get_default_output_template(&self) -> Option<String> {
+        None
+    }
 }
 
 fn get_help<'a>(wt: Box<dyn WhiteboxTool + 'a>) -> String {
@@ -1230,6 +1445,9 @@ fn get_help<'a>(wt: Box<dyn WhiteboxTool + 'a>) -> String {
     let description = wt.get_tool_description();
     let parameters = wt.get_tool_parameters();
     let toolbox = wt.get_toolbox();
+    let constraints = wt.get_parameter_constraints();
+    let default_output = wt.get_default_output_template();
+    let mut output_described = false;
     let o: serde_json::Value = serde_json::from_str(&parameters).unwrap();
     let a = o["parameters"].as_array().unwrap();
     let mut p = String::new();
@@ -1237,13 +1455,26 @@ fn get_help<'a>(wt: Box<dyn WhiteboxTool + 'a>) -> String {
     p.push_str("-----------------  -----------\n");
     for d in a {
         let mut s = String::new();
+        let mut flags = vec![];
         for f in d["flags"].as_array().unwrap() {
             s.push_str(&format!("{}, ", f.as_str().unwrap()));
+            flags.push(f.as_str().unwrap().to_string());
+        }
+        let mut description = d["description"].as_str().unwrap().to_string();
+        let c = parameter_constraints::describe_constraints(&flags, &constraints);
+        if !c.is_empty() {
+            description.push_str(&format!(" ({})", c));
+        }
+        if d["parameter_type"].get("NewFile").is_some() && !output_described {
+            if let Some(ref template) = default_output {
+                description.push_str(&format!(" (default: {})", template));
+            }
+            output_described = true;
         }
         p.push_str(&format!(
             "{:width$} {}\n",
             s.trim().trim_matches(','),
-            d["description"].as_str().unwrap(),
+            description,
             width = 18
         ));
     }
@@ -1280,72 +1511,3 @@ Example usage:
 fn get_name_and_description<'a>(wt: Box<dyn WhiteboxTool + 'a>) -> (String, String) {
     (wt.get_tool_name(), wt.get_tool_description())
 }
-
-#[derive(Serialize, Deserialize, Debug)]
-struct ToolParameter {
-    name: String,
-    flags: Vec<String>,
-    description: String,
-    parameter_type: ParameterType,
-    default_value: Option<String>,
-    optional: bool,
-}
-
-impl ToolParameter {
-    pub fn to_string(&self) -> String {
-        let v = match serde_json::to_string(&self) {
-            Ok(json_str) => json_str,
-            Err(err) => format!("{:?}", err),
-        };
-        v
-    }
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-enum ParameterType {
-    Boolean,
-    String,
-    StringList,
-    Integer,
-    Float,
-    VectorAttributeField(AttributeType, String),
-    StringOrNumber,
-    ExistingFile(ParameterFileType),
-    ExistingFileOrFloat(ParameterFileType),
-    NewFile(ParameterFileType),
-    FileList(ParameterFileType),
-    Directory,
-    OptionList(Vec<String>),
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-enum ParameterFileType {
-    Any,
-    Lidar,
-    Raster,
-    RasterAndVector(VectorGeometryType),
-    Vector(VectorGeometryType),
-    Text,
-    Html,
-    Csv,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-enum VectorGeometryType {
-    Any,
-    Point,
-    Line,
-    Polygon,
-    LineOrPolygon,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-enum AttributeType {
-    Any,
-    Integer,
-    Float,
-    Number,
-    Text,
-    Boolean,
-    Date,
-}
@@ -0,0 +1,180 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::parameter_constraints::{get_flags, is_flag, normalize_flag};
+use crate::lidar::split_zip_entry;
+use serde_json;
+use std::path::Path;
+
+/// Returns true if a file name is a template containing one or more of the placeholders that are
+/// replaced by `expand_output_template`.
+pub fn is_output_template(file_name: &str) -> bool {
+    ["{input_dir}", "{input_name}", "{input_stem}", "{input_ext}", "{tool}"]
+        .iter()
+        .any(|p| file_name.contains(p))
+}
+
+/// Creates an output file name from a template, based on the name of the input file and the tool.
+/// The supported placeholders are:
+///
+/// - `{input_dir}`: the directory of the input file, including the trailing separator, or an empty
+///   string if the input file name does not include a directory
+/// - `{input_name}`: the file name of the input file, e.g. `DEM.tif`
+/// - `{input_stem}`: the file name of the input file without its extension, e.g. `DEM`
+/// - `{input_ext}`: the extension of the input file, including the leading period, e.g. `.tif`
+/// - `{tool}`: the name of the tool, e.g. `Hillshade`
+///
+/// For example, the template `{input_stem}_hs.tif` expands to `DEM_hs.tif` for the input `DEM.tif`.
+/// For a LAS file within a zip archive, e.g. `tiles.zip#2020/tile1.las`, the directory is that of
+/// the archive and the name is that of the archived file, i.e. `tile1.las`.
+pub fn expand_output_template(template: &str, input_file: &str, tool_name: &str) -> String {
+    let input_file = input_file.trim().replace("\"", "").replace("\'", "");
+    let (archive_name, entry_name) = split_zip_entry(&input_file);
+    let input_dir = match archive_name.rfind(['/', '\\']) {
+        Some(i) => &archive_name[..i + 1],
+        None => "",
+    };
+    let input_file = entry_name.unwrap_or(archive_name);
+    let input_name = match input_file.rfind(['/', '\\']) {
+        Some(i) => &input_file[i + 1..],
+        None => input_file,
+    };
+    let input_path = Path::new(input_name);
+    let input_stem = match input_path.file_stem() {
+        Some(s) => s.to_str().unwrap_or("").to_string(),
+        None => input_name.to_string(),
+    };
+    let input_ext = match input_path.extension() {
+        Some(e) => format!(".{}", e.to_str().unwrap_or("")),
+        None => String::new(),
+    };
+    template
+        .replace("{input_dir}", input_dir)
+        .replace("{input_name}", input_name)
+        .replace("{input_stem}", &input_stem)
+        .replace("{input_ext}", &input_ext)
+        .replace("{tool}", tool_name)
+}
+
+/// Expands any output file name templates within a tool's arguments, using the value of the tool's
+/// first input file parameter. If the tool's output file is unspecified and the tool has a default
+/// output template, the output file argument is added using the default template. The `parameters`
+/// are the tool's parameters, as returned by `WhiteboxTool::get_tool_parameters`.
+pub fn expand_output_args(
+    parameters: &str,
+    args: &[String],
+    tool_name: &str,
+    default_template: Option<String>,
+) -> Vec<String> {
+    let json: serde_json::Value = match serde_json::from_str(parameters) {
+        Ok(v) => v,
+        Err(_) => return args.to_vec(),
+    };
+    let params = match json["parameters"].as_array() {
+        Some(p) => p.clone(),
+        None => return args.to_vec(),
+    };
+    let is_file_type = |p: &serde_json::Value, file_type: &str| p["parameter_type"].get(file_type).is_some();
+    let input_flags = match params.iter().find(|p| is_file_type(p, "ExistingFile")) {
+        Some(p) => get_flags(p),
+        None => return args.to_vec(),
+    };
+    let output_flags = match params.iter().find(|p| is_file_type(p, "NewFile")) {
+        Some(p) => get_flags(p),
+        None => return args.to_vec(),
+    };
+
+    // find the input file and the position of the output file value
+    let mut input_file = String::new();
+    let mut output_index = None;
+    for i in 0..args.len() {
+        if !is_flag(&args[i]) {
+            continue;
+        }
+        let mut parts = args[i].splitn(2, '=');
+        let flag = normalize_flag(parts.next().unwrap_or(""));
+        let (value_index, value) = match parts.next() {
+            Some(v) => (i, v.to_string()),
+            None => {
+                if i + 1 < args.len() && !is_flag(&args[i + 1]) {
+                    (i + 1, args[i + 1].clone())
+                } else {
+                    continue;
+                }
+            }
+        };
+        if input_flags.contains(&flag) && input_file.is_empty() {
+            input_file = value.replace("\"", "").replace("\'", "");
+        } else if output_flags.contains(&flag) && output_index.is_none() {
+            output_index = Some(value_index);
+        }
+    }
+    if input_file.is_empty() || input_file.contains(';') || input_file.contains(',') {
+        // there is no single input file on which to base the output file name
+        return args.to_vec();
+    }
+
+    let mut ret = args.to_vec();
+    match output_index {
+        Some(i) => {
+            if is_output_template(&ret[i]) {
+                ret[i] = expand_output_template(&ret[i], &input_file, tool_name);
+            }
+        }
+        None => {
+            if let Some(template) = default_template {
+                let output_file = expand_output_template(&template, &input_file, tool_name);
+                // flags are normalized to a single leading hyphen, e.g. '-output'
+                let flag = match output_flags.iter().find(|f| f.len() > 2) {
+                    Some(f) => format!("-{}", f),
+                    None => output_flags[0].clone(),
+                };
+                ret.push(format!("{}={}", flag, output_file));
+            }
+        }
+    }
+    ret
+}
+
+/// Returns the output file names specified within a tool's arguments, i.e. the values of all of the
+/// tool's new file parameters, in the order in which they appear in the arguments.
+pub fn find_output_files(parameters: &str, args: &[String]) -> Vec<String> {
+    let json: serde_json::Value = match serde_json::from_str(parameters) {
+        Ok(v) => v,
+        Err(_) => return vec![],
+    };
+    let output_flags: Vec<String> = match json["parameters"].as_array() {
+        Some(params) => params
+            .iter()
+            .filter(|p| p["parameter_type"].get("NewFile").is_some())
+            .flat_map(|p| get_flags(p))
+            .collect(),
+        None => return vec![],
+    };
+    let mut ret = vec![];
+    for i in 0..args.len() {
+        if !is_flag(&args[i]) {
+            continue;
+        }
+        let mut parts = args[i].splitn(2, '=');
+        let flag = normalize_flag(parts.next().unwrap_or(""));
+        if !output_flags.contains(&flag) {
+            continue;
+        }
+        let value = match parts.next() {
+            Some(v) => v.to_string(),
+            None if i + 1 < args.len() && !is_flag(&args[i + 1]) => args[i + 1].clone(),
+            None => continue,
+        };
+        let value = value.trim().replace("\"", "").replace("\'", "");
+        if !value.is_empty() {
+            ret.push(value);
+        }
+    }
+    ret
+}
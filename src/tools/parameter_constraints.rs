@@ -0,0 +1,366 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+pub use super::tool_parameter::ParameterConstraint;
+use serde_json;
+use std::fmt;
+use std::io::{Error, ErrorKind};
+
+impl fmt::Display for ParameterConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParameterConstraint::Min(v) => write!(f, "must be >= {}", v),
+            ParameterConstraint::Max(v) => write!(f, "must be <= {}", v),
+            ParameterConstraint::GreaterThan(v) => write!(f, "must be > {}", v),
+            ParameterConstraint::Range(a, b) => write!(f, "must be between {} and {}", a, b),
+            ParameterConstraint::ListRange(a, b) => {
+                write!(f, "values must be between {} and {}", a, b)
+            }
+            ParameterConstraint::ExclusiveWith(flag) => write!(f, "cannot be used with {}", flag),
+            ParameterConstraint::RequiredIf(flag) => write!(f, "required when {} is used", flag),
+        }
+    }
+}
+
+/// Checks the arguments of a tool against its parameter constraints. The `parameters` are the
+/// tool's parameters, as returned by `WhiteboxTool::get_tool_parameters`, which are used to
+/// associate each argument, given by any one of its flags, with its parameter.
+pub fn validate_args(
+    parameters: &str,
+    constraints: &[(String, ParameterConstraint)],
+    args: &[String],
+) -> Result<(), Error> {
+    if constraints.is_empty() {
+        return Ok(());
+    }
+    let flag_sets = parameter_flags(parameters)?;
+    let parsed_args = parse_args(args);
+
+    // Returns the flags of the parameter that has the specified flag.
+    let flags_of = |flag: &str| -> Vec<String> {
+        let flag = normalize_flag(flag);
+        match flag_sets.iter().find(|flags| flags.contains(&flag)) {
+            Some(flags) => flags.clone(),
+            None => vec![flag],
+        }
+    };
+    // Returns the value of a parameter, if it is specified, or None otherwise.
+    let value_of = |flag: &str| -> Option<String> {
+        let flags = flags_of(flag);
+        parsed_args
+            .iter()
+            .find(|a| flags.contains(&a.0))
+            .map(|a| a.1.clone().unwrap_or_default())
+    };
+    let invalid = |flag: &str, value: &str, constraint: &ParameterConstraint| -> Error {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Invalid value '{}' for parameter {} ({}).",
+                value, flag, constraint
+            ),
+        )
+    };
+
+    for (flag, constraint) in constraints {
+        let value = value_of(flag);
+        match constraint {
+            ParameterConstraint::ExclusiveWith(other) => {
+                if value.is_some() && value_of(other).is_some() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "The parameters {} and {} cannot be used together.",
+                            flag, other
+                        ),
+                    ));
+                }
+            }
+            ParameterConstraint::RequiredIf(other) => {
+                if value.is_none() && value_of(other).is_some() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("The parameter {} is required when {} is used.", flag, other),
+                    ));
+                }
+            }
+            ParameterConstraint::ListRange(min, max) => {
+                if let Some(value) = value {
+                    for token in value
+                        .split(&[',', ';'][..])
+                        .map(|t| t.trim())
+                        .filter(|t| !t.is_empty())
+                    {
+                        match token.parse::<f64>() {
+                            Ok(v) if v >= *min && v <= *max => {}
+                            _ => return Err(invalid(flag, token, constraint)),
+                        }
+                    }
+                }
+            }
+            _ => {
+                if let Some(value) = value {
+                    if value.trim().is_empty() {
+                        continue;
+                    }
+                    let v = match value.trim().parse::<f64>() {
+                        Ok(v) => v,
+                        Err(_) => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidInput,
+                                format!(
+                                    "The parameter {} requires a numeric value; found '{}'.",
+                                    flag, value
+                                ),
+                            ))
+                        }
+                    };
+                    let is_valid = match constraint {
+                        ParameterConstraint::Min(min) => v >= *min,
+                        ParameterConstraint::Max(max) => v <= *max,
+                        ParameterConstraint::GreaterThan(bound) => v > *bound,
+                        ParameterConstraint::Range(min, max) => v >= *min && v <= *max,
+                        _ => true,
+                    };
+                    if !is_valid {
+                        return Err(invalid(flag, &value, constraint));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Adds the constraints of each parameter to a tool's parameters JSON, as the `constraints`
+/// array of the parameter.
+pub fn add_constraints_to_parameters(
+    parameters: &str,
+    constraints: &[(String, ParameterConstraint)],
+) -> String {
+    if constraints.is_empty() {
+        return parameters.to_string();
+    }
+    let mut json: serde_json::Value = match serde_json::from_str(parameters) {
+        Ok(v) => v,
+        Err(_) => return parameters.to_string(),
+    };
+    if let Some(params) = json["parameters"].as_array_mut() {
+        for p in params.iter_mut() {
+            let flags = get_flags(p);
+            let param_constraints: Vec<&ParameterConstraint> = constraints
+                .iter()
+                .filter(|c| flags.contains(&normalize_flag(&c.0)))
+                .map(|c| &c.1)
+                .collect();
+            if !param_constraints.is_empty() {
+                p["constraints"] = serde_json::to_value(param_constraints).unwrap_or_default();
+            }
+        }
+    }
+    json.to_string()
+}
+
+/// Returns a description of the constraints on the parameter with the specified flags, for
+/// display in the tool's help, or an empty string if there are none.
+pub fn describe_constraints(
+    flags: &[String],
+    constraints: &[(String, ParameterConstraint)],
+) -> String {
+    let flags: Vec<String> = flags.iter().map(|f| normalize_flag(f)).collect();
+    constraints
+        .iter()
+        .filter(|c| flags.contains(&normalize_flag(&c.0)))
+        .map(|c| c.1.to_string())
+        .collect::<Vec<String>>()
+        .join("; ")
+}
+
+/// Normalizes a flag in the same way as the tools' argument parsing, e.g. `--Input` -> `-input`.
+pub(super) fn normalize_flag(flag: &str) -> String {
+    flag.trim().to_lowercase().replace("--", "-")
+}
+
+pub(super) fn is_flag(arg: &str) -> bool {
+    let mut chars = arg.chars();
+    chars.next() == Some('-')
+        && chars
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '-' || c == '_')
+}
+
+pub(super) fn get_flags(parameter: &serde_json::Value) -> Vec<String> {
+    match parameter["flags"].as_array() {
+        Some(flags) => flags
+            .iter()
+            .filter_map(|f| f.as_str())
+            .map(normalize_flag)
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Reads the flags of each parameter from a tool's parameters JSON.
+fn parameter_flags(parameters: &str) -> Result<Vec<Vec<String>>, Error> {
+    let json: serde_json::Value = serde_json::from_str(parameters)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{}", e)))?;
+    Ok(match json["parameters"].as_array() {
+        Some(params) => params.iter().map(get_flags).collect(),
+        None => vec![],
+    })
+}
+
+/// Splits the tool arguments into (normalized flag, value) pairs. Values may either follow
+/// an equals sign or be given as the next argument.
+fn parse_args(args: &[String]) -> Vec<(String, Option<String>)> {
+    let mut ret = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].replace("\"", "").replace("\'", "");
+        if is_flag(&arg) {
+            let mut parts = arg.splitn(2, '=');
+            let flag = normalize_flag(parts.next().unwrap_or(""));
+            let value = match parts.next() {
+                Some(v) => Some(v.to_string()),
+                None => {
+                    if i + 1 < args.len() && !is_flag(&args[i + 1]) {
+                        i += 1;
+                        Some(args[i].replace("\"", "").replace("\'", ""))
+                    } else {
+                        None
+                    }
+                }
+            };
+            ret.push((flag, value));
+        }
+        i += 1;
+    }
+    ret
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PARAMETERS: &str = r#"{"parameters":[
+        {"name":"Input File","flags":["-i","--input"]},
+        {"name":"Filter Size","flags":["--filter"]},
+        {"name":"Weight","flags":["-w","--weight"]},
+        {"name":"Radius","flags":["--radius"]},
+        {"name":"Resolution","flags":["--resolution"]},
+        {"name":"Base Raster","flags":["--base"]},
+        {"name":"Minimum Time","flags":["--min_time"]},
+        {"name":"Maximum Time","flags":["--max_time"]},
+        {"name":"Return Numbers","flags":["--returns"]}
+    ]}"#;
+
+    fn constraints() -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--filter".to_string(), ParameterConstraint::Min(3.0)),
+            ("--weight".to_string(), ParameterConstraint::Range(0.0, 10.0)),
+            ("--radius".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--resolution".to_string(), ParameterConstraint::Max(100.0)),
+            (
+                "--resolution".to_string(),
+                ParameterConstraint::ExclusiveWith("--base".to_string()),
+            ),
+            (
+                "--max_time".to_string(),
+                ParameterConstraint::RequiredIf("--min_time".to_string()),
+            ),
+            ("--returns".to_string(), ParameterConstraint::ListRange(1.0, 5.0)),
+        ]
+    }
+
+    fn validate(args: &[&str]) -> Result<(), Error> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        validate_args(PARAMETERS, &constraints(), &args)
+    }
+
+    #[test]
+    fn test_min_max_bounds() {
+        assert!(validate(&["--filter=3"]).is_ok());
+        assert!(validate(&["--filter=2"]).is_err());
+        assert!(validate(&["--resolution=100"]).is_ok());
+        assert!(validate(&["--resolution=100.5"]).is_err());
+        assert!(validate(&["--radius=0.1"]).is_ok());
+        assert!(validate(&["--radius=0"]).is_err());
+        assert!(validate(&["--weight=0"]).is_ok());
+        assert!(validate(&["--weight=10.1"]).is_err());
+        assert!(validate(&["--filter=big"]).is_err());
+        assert!(validate(&["--returns=1,3;5"]).is_ok());
+        assert!(validate(&["--returns=1,6"]).is_err());
+    }
+
+    #[test]
+    fn test_argument_forms() {
+        // values may follow an equals sign or be the next argument, and any flag of the
+        // parameter may be used
+        assert!(validate(&["--weight", "11"]).is_err());
+        assert!(validate(&["-w=11"]).is_err());
+        assert!(validate(&["-w", "2"]).is_ok());
+        assert!(validate(&["--Weight='11'"]).is_err());
+        // negative numbers are values rather than flags
+        assert!(validate(&["--weight", "-1"]).is_err());
+        // a flag without a value is not checked against numeric bounds
+        assert!(validate(&["--filter", "-v"]).is_ok());
+    }
+
+    #[test]
+    fn test_relationship_constraints() {
+        assert!(validate(&["--resolution=10"]).is_ok());
+        assert!(validate(&["--base=dem.tif"]).is_ok());
+        assert!(validate(&["--resolution=10", "--base=dem.tif"]).is_err());
+        assert!(validate(&["--resolution", "10", "--base", "dem.tif"]).is_err());
+        assert!(validate(&["--max_time=20"]).is_ok());
+        assert!(validate(&["--min_time=10", "--max_time=20"]).is_ok());
+        assert!(validate(&["--min_time=10"]).is_err());
+    }
+
+    #[test]
+    fn test_unknown_flags() {
+        assert!(validate(&["-i=points.las", "--foo=bar", "-v", "--compress_rasters"]).is_ok());
+        assert!(validate(&["--foo", "-1", "--filter=5"]).is_ok());
+        // constraints on flags that are not tool parameters are checked by flag alone
+        let constraints = vec![("--unknown".to_string(), ParameterConstraint::Min(1.0))];
+        let args = vec!["--unknown=0".to_string()];
+        assert!(validate_args(PARAMETERS, &constraints, &args).is_err());
+        let args = vec!["--other=0".to_string()];
+        assert!(validate_args(PARAMETERS, &constraints, &args).is_ok());
+    }
+
+    #[test]
+    fn test_add_constraints_to_parameters() {
+        let json = add_constraints_to_parameters(PARAMETERS, &constraints());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let params = value["parameters"].as_array().unwrap();
+        assert!(params[0].get("constraints").is_none());
+        assert_eq!(params[1]["constraints"], serde_json::json!([{"Min": 3.0}]));
+        assert_eq!(
+            params[4]["constraints"],
+            serde_json::json!([{"Max": 100.0}, {"ExclusiveWith": "--base"}])
+        );
+        assert_eq!(add_constraints_to_parameters(PARAMETERS, &[]), PARAMETERS);
+    }
+
+    #[test]
+    fn test_describe_constraints() {
+        let flags = vec!["-w".to_string(), "--weight".to_string()];
+        assert_eq!(
+            describe_constraints(&flags, &constraints()),
+            "must be between 0 and 10"
+        );
+        let flags = vec!["--resolution".to_string()];
+        assert_eq!(
+            describe_constraints(&flags, &constraints()),
+            "must be <= 100; cannot be used with --base"
+        );
+        let flags = vec!["--input".to_string()];
+        assert_eq!(describe_constraints(&flags, &constraints()), "");
+    }
+}
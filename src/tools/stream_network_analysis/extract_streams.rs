@@ -211,17 +211,11 @@ impl WhiteboxTool for ExtractStreams {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !flow_accum_file.contains(&sep) && !flow_accum_file.contains("/") {
-            flow_accum_file = format!("{}{}", working_directory, flow_accum_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        flow_accum_file = resolve_path(&flow_accum_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
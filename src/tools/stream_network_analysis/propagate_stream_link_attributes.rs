@@ -0,0 +1,574 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::structures::{Point2D, StreamNetworkGraph};
+use crate::tools::*;
+use crate::vector::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool propagates the attributes of the links in a vector stream network through the
+/// network, either downstream or upstream, and writes the results into a new field of the
+/// network's attribute table. When the attributes are propagated downstream (`--direction=downstream`),
+/// the output value of each link is a statistic of the values of the link and all of the links
+/// upstream of it, e.g. the total length of the upstream channel network or the maximum of a
+/// contaminant flag over the upstream network. When the attributes are propagated upstream
+/// (`--direction=upstream`), the output value of each link is a statistic of the values of the
+/// link and all of the links on its flow path to the outlet, e.g. the length of the flow path
+/// from the link to the outlet or whether it drains through a contaminated link.
+///
+/// The propagated attribute is specified with the `--field` parameter and must be numeric; Boolean
+/// fields are treated as 0 and 1 and null values are ignored. If no field is specified, the length
+/// of each link is used. The statistic (`--stat`) may be one of 'sum', 'max', 'min', or 'mean'; the
+/// mean of the upstream network is weighted by the number of links rather than by their lengths.
+/// The name of the output field (`--out_field`) defaults to the statistic prefixed by 'US' for
+/// downstream propagation, i.e. a statistic of the upstream network, or 'DS' for upstream
+/// propagation.
+///
+/// The input streams (`--input`) must be split into links at confluences, as are the streams
+/// that are output by the `RasterStreamsToVector` tool. Each link flows into the link whose
+/// upstream end is within the snap distance (`--snap`) of its downstream end. By default, the
+/// first vertex of each link is assumed to be its upstream end, i.e. the links are digitized in
+/// the direction of flow. Alternatively, if a DEM (`--dem`) is specified, the upstream end of each
+/// link is taken to be its higher end. Links that are digitized in inconsistent directions can
+/// create cycles in the network, in which case a warning is issued because the propagated values
+/// of the links on and downstream of the cycles are unreliable.
+///
+/// # See Also
+/// `RasterStreamsToVector`, `LengthOfUpstreamChannels`, `DistanceToOutlet`
+pub struct PropagateStreamLinkAttributes {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl PropagateStreamLinkAttributes {
+    pub fn new() -> PropagateStreamLinkAttributes {
+        // public constructor
+        let name = "PropagateStreamLinkAttributes".to_string();
+        let toolbox = "Stream Network Analysis".to_string();
+        let description =
+            "Propagates the attributes of vector stream links downstream or upstream through the stream network."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Vector Streams File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input vector streams file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Field Name".to_owned(),
+            flags: vec!["--field".to_owned()],
+            description: "Numeric field to propagate; the link length is used if unspecified."
+                .to_owned(),
+            parameter_type: ParameterType::VectorAttributeField(
+                AttributeType::Number,
+                "--input".to_string(),
+            ),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Direction".to_owned(),
+            flags: vec!["--direction".to_owned()],
+            description: "Direction of propagation; options are 'downstream' and 'upstream'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "downstream".to_owned(),
+                "upstream".to_owned(),
+            ]),
+            default_value: Some("downstream".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Statistic".to_owned(),
+            flags: vec!["--stat".to_owned()],
+            description: "Statistic of the propagated values; options are 'sum', 'max', 'min', and 'mean'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "sum".to_owned(),
+                "max".to_owned(),
+                "min".to_owned(),
+                "mean".to_owned(),
+            ]),
+            default_value: Some("sum".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Field Name".to_owned(),
+            flags: vec!["--out_field".to_owned()],
+            description: "Name of the output field.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Snap Distance".to_owned(),
+            flags: vec!["--snap".to_owned()],
+            description: "Distance within which link end points are connected (xy units)."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input DEM File (optional)".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Optional input DEM used to orient the links in the downslope direction."
+                .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector streams file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=streams.shp -o=output.shp
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=streams.shp --field=CONTAM --direction=downstream --stat=max --out_field=US_CONTAM --dem=DEM.tif --snap=0.5 -o=output.shp", short_exe, name).replace("*", &sep);
+
+        PropagateStreamLinkAttributes {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for PropagateStreamLinkAttributes {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut field_name = String::new();
+        let mut direction = String::from("downstream");
+        let mut stat = String::from("sum");
+        let mut out_field = String::new();
+        let mut snap_distance = 0f64;
+        let mut dem_file = String::new();
+        let mut output_file = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-field" {
+                field_name = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-direction" {
+                direction = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-stat" {
+                stat = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-out_field" {
+                out_field = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-snap" {
+                snap_distance = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        let downstream = if direction.contains("up") {
+            false
+        } else if direction.contains("down") {
+            true
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unrecognized direction; options are 'downstream' and 'upstream'.",
+            ));
+        };
+        if stat != "sum" && stat != "max" && stat != "min" && stat != "mean" {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unrecognized statistic; options are 'sum', 'max', 'min', and 'mean'.",
+            ));
+        }
+        if out_field.is_empty() {
+            let prefix = if downstream { "US" } else { "DS" };
+            out_field = format!("{}_{}", prefix, stat.to_uppercase());
+        }
+        if out_field.len() > 10 {
+            // field names in dBase files are limited to 10 characters
+            out_field.truncate(10);
+        }
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        if !dem_file.is_empty() {
+            dem_file = resolve_path(&dem_file, working_directory);
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let input = Shapefile::read(&input_file)?;
+
+        let start = Instant::now();
+
+        if input.header.shape_type.base_shape_type() != ShapeType::PolyLine {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input vector data must be of polyline base shape type.",
+            ));
+        }
+
+        if !field_name.is_empty() && input.attributes.get_field_num(&field_name).is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("The field '{}' is not in the attribute table.", field_name),
+            ));
+        }
+
+        if input.attributes.get_field_num(&out_field).is_some() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The output field '{}' is already in the attribute table; specify another name with --out_field.",
+                    out_field
+                ),
+            ));
+        }
+
+        let dem = if !dem_file.is_empty() {
+            if verbose {
+                println!("Reading DEM...")
+            };
+            Some(Raster::new(&dem_file, "r")?)
+        } else {
+            None
+        };
+
+        // find the end points and values of the links
+        let num_links = input.num_records;
+        let mut links = Vec::with_capacity(num_links);
+        let mut values = Vec::with_capacity(num_links);
+        let mut num_reversed = 0;
+        for record_num in 0..num_links {
+            let record = input.get_record(record_num);
+            if record.num_points == 0 {
+                // a null shape cannot be connected to the network
+                let p = Point2D::new(f64::NAN, f64::NAN);
+                links.push((p, p));
+                values.push(LinkStatistic::empty());
+                continue;
+            }
+            let mut upstream_end = record.points[0];
+            let mut downstream_end = record.points[record.num_points as usize - 1];
+            if let Some(ref dem) = dem {
+                let z1 = dem.get_value(
+                    dem.get_row_from_y(upstream_end.y),
+                    dem.get_column_from_x(upstream_end.x),
+                );
+                let z2 = dem.get_value(
+                    dem.get_row_from_y(downstream_end.y),
+                    dem.get_column_from_x(downstream_end.x),
+                );
+                let nodata = dem.configs.nodata;
+                if z1 != nodata && z2 != nodata && z2 > z1 {
+                    std::mem::swap(&mut upstream_end, &mut downstream_end);
+                    num_reversed += 1;
+                }
+            }
+            links.push((upstream_end, downstream_end));
+
+            let value = if field_name.is_empty() {
+                Some(link_length(record))
+            } else {
+                match input.attributes.get_value(record_num, &field_name) {
+                    FieldData::Int(v) => Some(v as f64),
+                    FieldData::Real(v) => Some(v),
+                    FieldData::Bool(v) => Some(if v { 1f64 } else { 0f64 }),
+                    _ => None,
+                }
+            };
+            values.push(match value {
+                Some(v) if v.is_finite() => LinkStatistic::new(v),
+                _ => LinkStatistic::empty(),
+            });
+
+            if verbose {
+                progress = (100.0_f64 * (record_num + 1) as f64 / num_links as f64) as usize;
+                if progress != old_progress {
+                    println!("Reading links: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if verbose {
+            println!("Building the stream network graph...")
+        };
+        let graph = StreamNetworkGraph::new(&links, snap_distance);
+        if graph.num_cyclic_links() > 0 {
+            println!(
+                "Warning: {} links are on, or downstream of, cycles in the stream network, which are commonly caused by links digitized in inconsistent directions. Their values are unreliable.",
+                graph.num_cyclic_links()
+            );
+        }
+
+        let accumulated = if downstream {
+            graph.accumulate_downstream(&values, |a, b| a.combine(b))
+        } else {
+            graph.accumulate_upstream(&values, |a, b| a.combine(b))
+        };
+
+        // create the output file
+        let mut output =
+            Shapefile::initialize_using_file(&output_file, &input, input.header.shape_type, true)?;
+        output.attributes.add_field(&AttributeField::new(
+            &out_field,
+            FieldDataType::Real,
+            14u8,
+            4u8,
+        ));
+
+        for (record_num, link_stat) in accumulated.iter().enumerate() {
+            let record = input.get_record(record_num);
+            output.add_record(record.clone());
+
+            let mut atts = input.attributes.get_record(record_num);
+            atts.push(match link_stat.get(&stat) {
+                Some(v) => FieldData::Real(v),
+                None => FieldData::Null,
+            });
+            output.attributes.add_record(atts, false);
+
+            if verbose {
+                progress = (100.0_f64 * (record_num + 1) as f64 / num_links as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if verbose {
+            println!("Number of links: {}", num_links);
+            println!("Number of outlets: {}", graph.outlets().len());
+            if dem.is_some() {
+                println!("Number of links reversed to flow downslope: {}", num_reversed);
+            }
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("{}", &format!("Elapsed Time: {}", elapsed_time));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the length of a polyline, summed over its parts.
+fn link_length(record: &ShapefileGeometry) -> f64 {
+    let mut length = 0f64;
+    for part in 0..record.num_parts as usize {
+        let start = record.parts[part] as usize;
+        let end = if part < record.num_parts as usize - 1 {
+            record.parts[part + 1] as usize
+        } else {
+            record.num_points as usize
+        };
+        for i in start + 1..end {
+            length += record.points[i].distance(&record.points[i - 1]);
+        }
+    }
+    length
+}
+
+/// The running statistics of the values of a set of links; links with null values are not counted.
+#[derive(Clone, Copy)]
+struct LinkStatistic {
+    sum: f64,
+    count: usize,
+    min: f64,
+    max: f64,
+}
+
+impl LinkStatistic {
+    fn new(value: f64) -> LinkStatistic {
+        LinkStatistic {
+            sum: value,
+            count: 1,
+            min: value,
+            max: value,
+        }
+    }
+
+    fn empty() -> LinkStatistic {
+        LinkStatistic {
+            sum: 0f64,
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    fn combine(&self, other: &LinkStatistic) -> LinkStatistic {
+        LinkStatistic {
+            sum: self.sum + other.sum,
+            count: self.count + other.count,
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    fn get(&self, stat: &str) -> Option<f64> {
+        if self.count == 0 {
+            return if stat == "sum" { Some(0f64) } else { None };
+        }
+        match stat {
+            "max" => Some(self.max),
+            "min" => Some(self.min),
+            "mean" => Some(self.sum / self.count as f64),
+            _ => Some(self.sum),
+        }
+    }
+}
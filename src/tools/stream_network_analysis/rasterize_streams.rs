@@ -210,20 +210,12 @@ impl WhiteboxTool for RasterizeStreams {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !streams_file.contains(&sep) && !streams_file.contains("/") {
-            streams_file = format!("{}{}", working_directory, streams_file);
-        }
-        if !base_file.contains(&sep) && !base_file.contains("/") {
-            base_file = format!("{}{}", working_directory, base_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        streams_file = resolve_path(&streams_file, working_directory);
+        base_file = resolve_path(&base_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading streams data...")
@@ -213,18 +213,10 @@ impl WhiteboxTool for VectorStreamNetworkAnalysis {
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !streams_file.contains(&sep) && !streams_file.contains("/") {
-            streams_file = format!("{}{}", working_directory, streams_file);
-        }
-        if !dem_file.contains(&sep) && !dem_file.contains("/") {
-            dem_file = format!("{}{}", working_directory, dem_file);
-        }
-        if !lakes_file.contains(&sep) && !lakes_file.contains("/") {
-            lakes_file = format!("{}{}", working_directory, lakes_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        streams_file = resolve_path(&streams_file, working_directory);
+        dem_file = resolve_path(&dem_file, working_directory);
+        lakes_file = resolve_path(&lakes_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         let start = Instant::now();
 
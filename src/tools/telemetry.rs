@@ -0,0 +1,149 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use serde_json::json;
+use std::fs;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// The stage timer of the tool run in progress.
+struct StageTimer {
+    start: Instant,
+    stage_start: Instant,
+    stage_name: String,
+    stages: Vec<(String, f64)>,
+}
+
+static CURRENT_RUN: Mutex<Option<StageTimer>> = Mutex::new(None);
+
+/// Begins recording the telemetry of a tool run, with the first stage named `stage_name`.
+pub(super) fn start(stage_name: &str) {
+    let now = Instant::now();
+    if let Ok(mut current) = CURRENT_RUN.lock() {
+        *current = Some(StageTimer {
+            start: now,
+            stage_start: now,
+            stage_name: stage_name.to_string(),
+            stages: vec![],
+        });
+    }
+}
+
+/// Ends the current stage of the tool run and begins a new stage named `stage_name`. Tools may call
+/// this function to break their processing into stages (e.g. reading, interpolating, writing),
+/// the wall times of which are reported when the run ends. It has no effect when called outside of
+/// a tool run.
+pub fn begin_stage(stage_name: &str) {
+    if let Ok(mut current) = CURRENT_RUN.lock() {
+        if let Some(timer) = current.as_mut() {
+            let now = Instant::now();
+            let name = std::mem::replace(&mut timer.stage_name, stage_name.to_string());
+            timer
+                .stages
+                .push((name, (now - timer.stage_start).as_secs_f64()));
+            timer.stage_start = now;
+        }
+    }
+}
+
+/// Ends the recording of a tool run, returning its telemetry.
+pub(super) fn finish(tool_name: &str) -> RunTelemetry {
+    let timer = CURRENT_RUN.lock().ok().and_then(|mut current| current.take());
+    let (elapsed, stages) = match timer {
+        Some(mut timer) => {
+            let now = Instant::now();
+            timer
+                .stages
+                .push((timer.stage_name, (now - timer.stage_start).as_secs_f64()));
+            ((now - timer.start).as_secs_f64(), timer.stages)
+        }
+        None => (0f64, vec![]),
+    };
+    RunTelemetry {
+        tool_name: tool_name.to_string(),
+        elapsed: elapsed,
+        stages: stages,
+        peak_memory: peak_memory_usage(),
+    }
+}
+
+/// Returns the peak resident set size (RSS) of the process, in bytes, i.e. the largest amount of
+/// physical memory that it has occupied. The peak RSS is read from `/proc/self/status` and is
+/// therefore only available on Linux; `None` is returned on other platforms.
+pub fn peak_memory_usage() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+/// The runtime and memory telemetry of a tool run.
+pub struct RunTelemetry {
+    pub tool_name: String,
+    /// The total wall time of the run, in seconds.
+    pub elapsed: f64,
+    /// The name and wall time, in seconds, of each stage of the run.
+    pub stages: Vec<(String, f64)>,
+    /// The peak resident set size of the process, in bytes, if it is available.
+    pub peak_memory: Option<u64>,
+}
+
+impl RunTelemetry {
+    /// Returns a human-readable summary of the telemetry, as printed in verbose mode.
+    pub fn report(&self) -> String {
+        let mut s = format!("Run telemetry for {}:\n", self.tool_name);
+        for (name, seconds) in &self.stages {
+            s.push_str(&format!("  {}: {:.3}s\n", name, seconds));
+        }
+        s.push_str(&format!("  Total wall time: {:.3}s\n", self.elapsed));
+        match self.peak_memory {
+            Some(bytes) => s.push_str(&format!(
+                "  Peak memory usage: {:.1} MB",
+                bytes as f64 / 1_048_576f64
+            )),
+            None => s.push_str("  Peak memory usage: unavailable on this platform"),
+        }
+        s
+    }
+
+    /// Returns the telemetry as a JSON value, for inclusion within a provenance sidecar.
+    pub fn to_json(&self) -> serde_json::Value {
+        let stages: Vec<serde_json::Value> = self
+            .stages
+            .iter()
+            .map(|(name, seconds)| json!({ "name": name, "wall_time_s": seconds }))
+            .collect();
+        json!({
+            "wall_time_s": self.elapsed,
+            "stages": stages,
+            "peak_memory_bytes": self.peak_memory,
+        })
+    }
+}
+
+/// Writes a provenance sidecar for an output file, named by appending `.provenance.json` to the
+/// output file name. The sidecar records the tool, its arguments, the working directory, the
+/// WhiteboxTools version, the completion time, and the run telemetry.
+pub(super) fn write_provenance_sidecar(
+    output_file: &str,
+    args: &[String],
+    working_directory: &str,
+    telemetry: &RunTelemetry,
+) -> Result<(), std::io::Error> {
+    let provenance = json!({
+        "tool": telemetry.tool_name,
+        "arguments": args,
+        "working_directory": working_directory,
+        "whitebox_tools_version": option_env!("CARGO_PKG_VERSION").unwrap_or("unknown"),
+        "completed": chrono::Local::now().to_rfc3339(),
+        "telemetry": telemetry.to_json(),
+    });
+    let s = serde_json::to_string_pretty(&provenance)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    fs::write(format!("{}.provenance.json", output_file), s)
+}
@@ -0,0 +1,697 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool creates a single-band visualization raster from an input digital elevation model (DEM)
+/// that is well suited to the detection of subtle earthworks and other micro-topographic features
+/// of archaeological interest in LiDAR-derived DEMs. The visualization is a weighted blend of three
+/// commonly used derivatives:
+///
+/// 1. A **local relief model** (LRM; Hesse, 2010), i.e. the DEM minus a smoothed trend surface. The
+///    trend surface is estimated using a mean filter of size `--filter` grid cells. Positive relief
+///    (e.g. banks, mounds) appears bright and negative relief (e.g. ditches, hollow ways) appears dark.
+/// 2. The **sky-view factor** (SVF; Zakšek et al., 2011), i.e. the proportion of the visible sky
+///    hemisphere, estimated by tracing `--num_dirs` rays out to a distance of `--max_dist` (in the
+///    same units as the X-Y coordinates of the DEM) and measuring the horizon angle along each.
+///    Enclosed locations appear dark.
+/// 3. **Slope gradient**, calculated using Horn's (1981) method, which is inverted such that steep
+///    slopes appear dark.
+///
+/// Each of the three components is contrast-stretched to the range 0-1 before blending. The LRM is
+/// linearly stretched between its mean plus and minus 2.5 standard deviations, the SVF is stretched
+/// between 0.65 and 1.0, and slope is stretched between 0 and 60 degrees. The blended output is the
+/// weighted average of the stretched components, with weights specified using `--lrm_weight`,
+/// `--svf_weight`, and `--slope_weight`. The individual (un-stretched) components may optionally be
+/// output using the `--out_lrm`, `--out_svf`, and `--out_slope` parameters.
+///
+/// The *Z conversion factor* (`--zfactor`) is only important when the vertical and horizontal units
+/// are not the same in the DEM. The output is best displayed using a grey-scale palette.
+///
+/// # References
+/// Hesse, R. (2010). LiDAR‐derived Local Relief Models–a new tool for archaeological prospection.
+/// Archaeological Prospection, 17(2), 67-72.
+///
+/// Kokalj, Ž., and Somrak, M. (2019). Why not a single image? Combining visualizations to facilitate
+/// fieldwork and on-screen mapping. Remote Sensing, 11(7), 747.
+///
+/// Zakšek, K., Oštir, K., and Kokalj, Ž. (2011). Sky-view factor as a relief visualization technique.
+/// Remote Sensing, 3(2), 398-415.
+///
+/// # See Also
+/// `DevFromMeanElev`, `HorizonAngle`, `Slope`, `Hillshade`
+pub struct ArchaeologicalVisualization {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ArchaeologicalVisualization {
+    pub fn new() -> ArchaeologicalVisualization {
+        // public constructor
+        let name = "ArchaeologicalVisualization".to_string();
+        let toolbox = "Geomorphometric Analysis".to_string();
+        let description = "Blends a local relief model, sky-view factor, and slope into a single visualization raster for detecting subtle earthworks.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["-i".to_owned(), "--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output blended visualization raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Local Relief Model File (optional)".to_owned(),
+            flags: vec!["--out_lrm".to_owned()],
+            description: "Optional output local relief model raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Sky-View Factor File (optional)".to_owned(),
+            flags: vec!["--out_svf".to_owned()],
+            description: "Optional output sky-view factor raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Slope File (optional)".to_owned(),
+            flags: vec!["--out_slope".to_owned()],
+            description: "Optional output slope (degrees) raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Trend Filter Size (cells)".to_owned(),
+            flags: vec!["--filter".to_owned()],
+            description: "Size of the mean filter used to estimate the trend surface of the local relief model, in grid cells.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("21".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Sky-View Factor Search Distance".to_owned(),
+            flags: vec!["--max_dist".to_owned()],
+            description: "Maximum search distance used in the sky-view factor calculation, in map units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Sky-View Factor Directions".to_owned(),
+            flags: vec!["--num_dirs".to_owned()],
+            description: "Number of search directions used in the sky-view factor calculation.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("16".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Local Relief Model Weight".to_owned(),
+            flags: vec!["--lrm_weight".to_owned()],
+            description: "Blending weight of the local relief model.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.4".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Sky-View Factor Weight".to_owned(),
+            flags: vec!["--svf_weight".to_owned()],
+            description: "Blending weight of the sky-view factor.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.3".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Slope Weight".to_owned(),
+            flags: vec!["--slope_weight".to_owned()],
+            description: "Blending weight of the (inverted) slope.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.3".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Z Conversion Factor".to_owned(),
+            flags: vec!["--zfactor".to_owned()],
+            description:
+                "Optional multiplier for when the vertical and horizontal units are not the same."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{} -r={} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=output.tif --filter=25 --max_dist=15.0 --num_dirs=16 --lrm_weight=0.5 --svf_weight=0.25 --slope_weight=0.25",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        ArchaeologicalVisualization {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ArchaeologicalVisualization {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut lrm_file = String::new();
+        let mut svf_file = String::new();
+        let mut slope_file = String::new();
+        let mut filter_size = 21isize;
+        let mut max_dist = 10f64;
+        let mut num_dirs = 16usize;
+        let mut lrm_weight = 0.4f64;
+        let mut svf_weight = 0.3f64;
+        let mut slope_weight = 0.3f64;
+        let mut z_factor = 1f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-dem" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_lrm" {
+                lrm_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_svf" {
+                svf_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_slope" {
+                slope_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-filter" {
+                filter_size = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as isize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as isize
+                };
+            } else if flag_val == "-max_dist" {
+                max_dist = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-num_dirs" {
+                num_dirs = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-lrm_weight" {
+                lrm_weight = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-svf_weight" {
+                svf_weight = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-slope_weight" {
+                slope_weight = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-zfactor" {
+                z_factor = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        lrm_file = resolve_path(&lrm_file, working_directory);
+        svf_file = resolve_path(&svf_file, working_directory);
+        slope_file = resolve_path(&slope_file, working_directory);
+
+        // the filter dimension must be an odd number
+        if filter_size < 3 {
+            filter_size = 3;
+        }
+        if filter_size % 2 == 0 {
+            filter_size += 1;
+        }
+        if num_dirs < 4 {
+            num_dirs = 4;
+        }
+        if lrm_weight < 0f64 || svf_weight < 0f64 || slope_weight < 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The blending weights must not be negative.",
+            ));
+        }
+        let weight_sum = lrm_weight + svf_weight + slope_weight;
+        if weight_sum <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one of the blending weights must be greater than zero.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let input = Arc::new(Raster::new(&input_file, "r")?);
+
+        let start = Instant::now();
+
+        let rows = input.configs.rows as isize;
+        let columns = input.configs.columns as isize;
+        let nodata = input.configs.nodata;
+        let res_x = input.configs.resolution_x;
+        let res_y = input.configs.resolution_y;
+
+        if input.is_in_geographic_coordinates() {
+            // calculate a new z-conversion factor
+            let mut mid_lat = (input.configs.north - input.configs.south) / 2.0;
+            if mid_lat <= 90.0 && mid_lat >= -90.0 {
+                mid_lat = mid_lat.to_radians();
+                z_factor = 1.0 / (113200.0 * mid_lat.cos());
+            }
+        }
+
+        // Calculate the integral images of elevation and valid-cell count, used by the trend filter.
+        let mut sums = vec![0f64; ((rows + 1) * (columns + 1)) as usize];
+        let mut counts = vec![0f64; ((rows + 1) * (columns + 1)) as usize];
+        let mut z: f64;
+        for row in 0..rows {
+            let mut row_sum = 0f64;
+            let mut row_count = 0f64;
+            for col in 0..columns {
+                z = input[(row, col)];
+                if z != nodata {
+                    row_sum += z * z_factor;
+                    row_count += 1f64;
+                }
+                let idx = ((row + 1) * (columns + 1) + col + 1) as usize;
+                let idx_above = (row * (columns + 1) + col + 1) as usize;
+                sums[idx] = sums[idx_above] + row_sum;
+                counts[idx] = counts[idx_above] + row_count;
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Calculating integral images: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        let sums = Arc::new(sums);
+        let counts = Arc::new(counts);
+
+        let num_procs = num_cpus::get() as isize;
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let input = input.clone();
+            let sums = sums.clone();
+            let counts = counts.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let midpoint = filter_size / 2;
+                let eight_grid_res_x = res_x * 8.0;
+                let eight_grid_res_y = res_y * 8.0;
+                let d_x = [1, 1, 1, 0, -1, -1, -1, 0];
+                let d_y = [-1, 0, 1, 1, 1, 0, -1, -1];
+                let mut n: [f64; 8] = [0.0; 8];
+                let mut z: f64;
+                let (mut fx, mut fy): (f64, f64);
+                let (mut x1, mut x2, mut y1, mut y2): (isize, isize, isize, isize);
+                let (mut sum, mut count): (f64, f64);
+
+                // ray directions for the sky-view factor
+                let mut ray_dx = vec![0f64; num_dirs];
+                let mut ray_dy = vec![0f64; num_dirs];
+                for d in 0..num_dirs {
+                    let azimuth = 2f64 * PI * d as f64 / num_dirs as f64;
+                    ray_dx[d] = azimuth.sin();
+                    ray_dy[d] = -azimuth.cos();
+                }
+                let step_size = res_x.min(res_y);
+                let num_steps = (max_dist / step_size).ceil().max(1f64) as usize;
+
+                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                    let mut lrm_data = vec![nodata; columns as usize];
+                    let mut slope_data = vec![nodata; columns as usize];
+                    let mut svf_data = vec![nodata; columns as usize];
+                    for col in 0..columns {
+                        z = input[(row, col)];
+                        if z != nodata {
+                            z *= z_factor;
+
+                            // local relief model
+                            y1 = (row - midpoint).max(0);
+                            y2 = (row + midpoint + 1).min(rows);
+                            x1 = (col - midpoint).max(0);
+                            x2 = (col + midpoint + 1).min(columns);
+                            sum = sums[(y2 * (columns + 1) + x2) as usize]
+                                - sums[(y1 * (columns + 1) + x2) as usize]
+                                - sums[(y2 * (columns + 1) + x1) as usize]
+                                + sums[(y1 * (columns + 1) + x1) as usize];
+                            count = counts[(y2 * (columns + 1) + x2) as usize]
+                                - counts[(y1 * (columns + 1) + x2) as usize]
+                                - counts[(y2 * (columns + 1) + x1) as usize]
+                                + counts[(y1 * (columns + 1) + x1) as usize];
+                            if count > 0f64 {
+                                lrm_data[col as usize] = z - sum / count;
+                            }
+
+                            // slope
+                            for c in 0..8 {
+                                n[c] = input[(row + d_y[c], col + d_x[c])];
+                                if n[c] != nodata {
+                                    n[c] = n[c] * z_factor;
+                                } else {
+                                    n[c] = z;
+                                }
+                            }
+                            fy = (n[6] - n[4] + 2.0 * (n[7] - n[3]) + n[0] - n[2])
+                                / eight_grid_res_y;
+                            fx = (n[2] - n[4] + 2.0 * (n[1] - n[5]) + n[0] - n[6])
+                                / eight_grid_res_x;
+                            slope_data[col as usize] =
+                                (fx * fx + fy * fy).sqrt().atan().to_degrees();
+
+                            // sky-view factor
+                            let mut sin_sum = 0f64;
+                            for d in 0..num_dirs {
+                                let mut max_tan = 0f64;
+                                for s in 1..=num_steps {
+                                    let dist = s as f64 * step_size;
+                                    let r = row + (ray_dy[d] * dist / res_y).round() as isize;
+                                    let c = col + (ray_dx[d] * dist / res_x).round() as isize;
+                                    if r < 0 || r >= rows || c < 0 || c >= columns {
+                                        break;
+                                    }
+                                    let zn = input[(r, c)];
+                                    if zn != nodata {
+                                        let tan = (zn * z_factor - z) / dist;
+                                        if tan > max_tan {
+                                            max_tan = tan;
+                                        }
+                                    }
+                                }
+                                sin_sum += max_tan.atan().sin();
+                            }
+                            svf_data[col as usize] = 1f64 - sin_sum / num_dirs as f64;
+                        }
+                    }
+                    tx.send((row, lrm_data, slope_data, svf_data)).unwrap();
+                }
+            });
+        }
+
+        let mut lrm = Raster::initialize_using_file(
+            if lrm_file.is_empty() {
+                &output_file
+            } else {
+                &lrm_file
+            },
+            &input,
+        );
+        lrm.configs.data_type = DataType::F32;
+        lrm.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        let mut slope = Raster::initialize_using_file(
+            if slope_file.is_empty() {
+                &output_file
+            } else {
+                &slope_file
+            },
+            &input,
+        );
+        slope.configs.data_type = DataType::F32;
+        slope.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        let mut svf = Raster::initialize_using_file(
+            if svf_file.is_empty() {
+                &output_file
+            } else {
+                &svf_file
+            },
+            &input,
+        );
+        svf.configs.data_type = DataType::F32;
+        svf.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        for row in 0..rows {
+            let data = rx.recv().unwrap();
+            lrm.set_row_data(data.0, data.1);
+            slope.set_row_data(data.0, data.2);
+            svf.set_row_data(data.0, data.3);
+
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Calculating components: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Contrast stretch each component and blend them.
+        let (lrm_mean, lrm_stdev) = lrm.calculate_mean_and_stdev();
+        let lrm_min = lrm_mean - 2.5 * lrm_stdev;
+        let lrm_range = if lrm_stdev > 0f64 { 5.0 * lrm_stdev } else { 1f64 };
+        let (svf_min, svf_max) = (0.65f64, 1f64);
+        let max_slope = 60f64;
+
+        let mut output = Raster::initialize_using_file(&output_file, &input);
+        output.configs.data_type = DataType::F32;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        let (mut lrm_val, mut svf_val, mut slope_val): (f64, f64, f64);
+        for row in 0..rows {
+            for col in 0..columns {
+                if input[(row, col)] != nodata {
+                    lrm_val = lrm[(row, col)];
+                    lrm_val = if lrm_val != nodata {
+                        ((lrm_val - lrm_min) / lrm_range).max(0f64).min(1f64)
+                    } else {
+                        0.5f64
+                    };
+                    svf_val = ((svf[(row, col)] - svf_min) / (svf_max - svf_min))
+                        .max(0f64)
+                        .min(1f64);
+                    slope_val = 1f64 - (slope[(row, col)] / max_slope).max(0f64).min(1f64);
+                    output.set_value(
+                        row,
+                        col,
+                        (lrm_weight * lrm_val + svf_weight * svf_val + slope_weight * slope_val)
+                            / weight_sum,
+                    );
+                }
+            }
+
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Blending components: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.configs.palette = "grey.plt".to_string();
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        output.add_metadata_entry(format!("Trend filter size: {}", filter_size));
+        output.add_metadata_entry(format!("SVF search distance: {}", max_dist));
+        output.add_metadata_entry(format!("SVF directions: {}", num_dirs));
+        output.add_metadata_entry(format!(
+            "Weights (LRM, SVF, slope): {}, {}, {}",
+            lrm_weight, svf_weight, slope_weight
+        ));
+        output.add_metadata_entry(format!("Z-factor: {}", z_factor));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        let component_outputs = vec![
+            (lrm, lrm_file, "grey.plt"),
+            (svf, svf_file, "grey.plt"),
+            (slope, slope_file, "spectrum_soft.plt"),
+        ];
+        for (mut component, file_name, palette) in component_outputs {
+            if !file_name.is_empty() {
+                component.configs.palette = palette.to_string();
+                component.add_metadata_entry(format!(
+                    "Created by whitebox_tools\' {} tool",
+                    self.get_tool_name()
+                ));
+                component.add_metadata_entry(format!("Input file: {}", input_file));
+                let _ = match component.write() {
+                    Ok(_) => {
+                        if verbose {
+                            println!("Output file written ({})", file_name)
+                        }
+                    }
+                    Err(e) => return Err(e),
+                };
+            }
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -205,8 +205,6 @@ impl WhiteboxTool for FillMissingData {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         // The filter dimensions must be odd numbers such that there is a middle pixel
         if (filter_size as f64 / 2f64).floor() == (filter_size as f64 / 2f64) {
             filter_size += 1;
@@ -217,12 +215,8 @@ impl WhiteboxTool for FillMissingData {
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -0,0 +1,499 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::structures::{Array2D, Point2D};
+use crate::tools::*;
+use crate::vector::ShapefileGeometry;
+use crate::vector::*;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool can be used to locate elevation discontinuities, or seams, within a digital elevation
+/// model (DEM) for quality assurance purposes. Seams are abrupt steps in the elevation surface that
+/// commonly occur along the boundaries between LiDAR acquisition projects, flightlines, or tiles
+/// that were processed separately, e.g. where there is a vertical offset between adjacent tiles.
+/// The detected seams are output as a vector of the POLYLINE ShapeType (`--output`).
+///
+/// The step across the edge between two neighbouring grid cells is measured as the elevation
+/// difference between the cells minus the average of the differences on either side of the edge,
+/// i.e. the local slope gradient is removed from the measured difference. Cell edges are identified
+/// as step edges when the magnitude of the step is at least as large as the step threshold
+/// (`--threshold`, in the DEM's vertical units) and is larger than the steps across the adjacent
+/// parallel edges. Connected step edges are then traced into polylines, which pass through the
+/// midpoints of the step edges, and only those polylines that persist for at least the minimum
+/// length (`--min_length`, in map units) are output. The minimum length is ten grid cells by
+/// default. Persistent features of this kind are rarely
+/// natural, although cliffs, building edges, and other sharp breaks of slope may also be detected.
+///
+/// The attribute table of the output contains the length of each seam (LENGTH), the mean and maximum
+/// step magnitudes along the seam (MEAN_STEP and MAX_STEP), and the standard deviation of the step
+/// magnitude (STDEV_STEP). Seams that are caused by vertical offsets between data sets will usually
+/// have a relatively consistent step magnitude along their length.
+///
+/// # See Also
+/// `FindRidges`, `EdgeDensity`
+pub struct FindElevationSeams {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl FindElevationSeams {
+    pub fn new() -> FindElevationSeams {
+        // public constructor
+        let name = "FindElevationSeams".to_string();
+        let toolbox = "Geomorphometric Analysis".to_string();
+        let description =
+            "Locates and vectorizes persistent elevation steps (seams) in a DEM for quality assurance."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["-i".to_owned(), "--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Vector File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output vector lines file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Step Threshold".to_owned(),
+            flags: vec!["--threshold".to_owned()],
+            description: "Minimum elevation step magnitude, in vertical units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.15".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Seam Length".to_owned(),
+            flags: vec!["--min_length".to_owned()],
+            description: "Minimum seam length, in map units. The default is ten grid cells."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=seams.shp --threshold=0.1 --min_length=50.0",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        FindElevationSeams {
+            name,
+            description,
+            toolbox,
+            parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for FindElevationSeams {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut threshold = 0.15f64;
+        let mut min_length = -1f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-dem" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-threshold" {
+                threshold = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-min_length" {
+                min_length = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let input = Raster::new(&input_file, "r")?;
+
+        let start = Instant::now();
+
+        let rows = input.configs.rows as isize;
+        let columns = input.configs.columns as isize;
+        let nodata = input.configs.nodata;
+        let res_x = input.configs.resolution_x;
+        let res_y = input.configs.resolution_y;
+        let west = input.configs.west;
+        let north = input.configs.north;
+        if min_length < 0f64 {
+            min_length = 10f64 * (res_x + res_y) / 2f64;
+        }
+
+        // Measure the detrended elevation step across the east edge (step_x) and south edge
+        // (step_y) of each grid cell.
+        let mut step_x: Array2D<f64> = Array2D::new(rows, columns, 0f64, 0f64)?;
+        let mut step_y: Array2D<f64> = Array2D::new(rows, columns, 0f64, 0f64)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = input.get_value(row, col);
+                if z == nodata {
+                    continue;
+                }
+                let z_east = input.get_value(row, col + 1);
+                if z_east != nodata && col < columns - 1 {
+                    step_x.set_value(
+                        row,
+                        col,
+                        detrended_step(input.get_value(row, col - 1), z, z_east, input.get_value(row, col + 2), nodata),
+                    );
+                }
+                let z_south = input.get_value(row + 1, col);
+                if z_south != nodata && row < rows - 1 {
+                    step_y.set_value(
+                        row,
+                        col,
+                        detrended_step(input.get_value(row - 1, col), z, z_south, input.get_value(row + 2, col), nodata),
+                    );
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Measuring elevation steps: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Identify the step edges, i.e. edges with steps greater than the threshold that are
+        // also larger than the steps across the adjacent parallel edges. The edges are
+        // stored as segments joining the grid cell corners, where corner (r, c) is the
+        // north-west corner of cell (r, c).
+        let corner_id = |r: isize, c: isize| -> usize { (r * (columns + 1) + c) as usize };
+        let mut segments: HashMap<usize, Vec<(usize, f64)>> = HashMap::new();
+        let mut add_segment = |a: usize, b: usize, step: f64| {
+            segments.entry(a).or_default().push((b, step));
+            segments.entry(b).or_default().push((a, step));
+        };
+        for row in 0..rows {
+            for col in 0..columns {
+                let s = step_x.get_value(row, col).abs();
+                if s >= threshold
+                    && s >= step_x.get_value(row, col - 1).abs()
+                    && s >= step_x.get_value(row, col + 1).abs()
+                {
+                    add_segment(corner_id(row, col + 1), corner_id(row + 1, col + 1), s);
+                }
+                let s = step_y.get_value(row, col).abs();
+                if s >= threshold
+                    && s >= step_y.get_value(row - 1, col).abs()
+                    && s >= step_y.get_value(row + 1, col).abs()
+                {
+                    add_segment(corner_id(row + 1, col), corner_id(row + 1, col + 1), s);
+                }
+            }
+        }
+
+        // Trace the connected step edges into polylines, starting from line ends and junctions
+        // and then tracing any remaining closed loops.
+        if verbose {
+            println!("Tracing seams...");
+        }
+        let mut traced: HashSet<(usize, usize)> = HashSet::new();
+        let mut seams: Vec<(Vec<usize>, Vec<f64>)> = vec![];
+        let mut start_nodes: Vec<usize> = segments
+            .iter()
+            .filter(|(_, v)| v.len() != 2)
+            .map(|(k, _)| *k)
+            .collect();
+        start_nodes.sort();
+        let mut loop_nodes: Vec<usize> = segments.keys().copied().collect();
+        loop_nodes.sort();
+        start_nodes.extend(loop_nodes);
+        for node in start_nodes {
+            for (next, step) in segments[&node].clone() {
+                if traced.contains(&(node.min(next), node.max(next))) {
+                    continue;
+                }
+                traced.insert((node.min(next), node.max(next)));
+                let mut nodes = vec![node, next];
+                let mut steps = vec![step];
+                let mut current = next;
+                while segments[&current].len() == 2 {
+                    let mut advanced = false;
+                    for (n, s) in &segments[&current] {
+                        let key = (current.min(*n), current.max(*n));
+                        if !traced.contains(&key) {
+                            traced.insert(key);
+                            nodes.push(*n);
+                            steps.push(*s);
+                            current = *n;
+                            advanced = true;
+                            break;
+                        }
+                    }
+                    if !advanced {
+                        break;
+                    }
+                }
+                seams.push((nodes, steps));
+            }
+        }
+
+        // create output file
+        let mut output = Shapefile::new(&output_file, ShapeType::PolyLine)?;
+        output.projection = input.configs.coordinate_ref_system_wkt.clone();
+        output
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 7u8, 0u8));
+        output.attributes.add_field(&AttributeField::new(
+            "LENGTH",
+            FieldDataType::Real,
+            12u8,
+            3u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "MEAN_STEP",
+            FieldDataType::Real,
+            12u8,
+            4u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "MAX_STEP",
+            FieldDataType::Real,
+            12u8,
+            4u8,
+        ));
+        output.attributes.add_field(&AttributeField::new(
+            "STDEV_STEP",
+            FieldDataType::Real,
+            12u8,
+            4u8,
+        ));
+
+        let num_seams = seams.len();
+        let mut fid = 1i32;
+        for (i, (nodes, steps)) in seams.iter().enumerate() {
+            // The vertices are placed at the ends of the seam and the midpoints of its cell
+            // edges, such that diagonal seams are not represented as staircases.
+            let corner = |node: usize| -> Point2D {
+                let r = (node / (columns + 1) as usize) as f64;
+                let c = (node % (columns + 1) as usize) as f64;
+                Point2D::new(west + c * res_x, north - r * res_y)
+            };
+            let mut vertices = vec![corner(nodes[0])];
+            for n in 1..nodes.len() {
+                vertices.push(Point2D::midpoint(&corner(nodes[n - 1]), &corner(nodes[n])));
+            }
+            vertices.push(corner(nodes[nodes.len() - 1]));
+
+            let mut points: Vec<Point2D> = vec![];
+            let mut length = 0f64;
+            for p in vertices {
+                // remove vertices along straight runs
+                if points.len() > 1 {
+                    let p1 = points[points.len() - 2];
+                    let p2 = points[points.len() - 1];
+                    if ((p2.x - p1.x) * (p.y - p2.y) - (p2.y - p1.y) * (p.x - p2.x)).abs()
+                        < 1e-9 * res_x * res_y
+                    {
+                        points.pop();
+                    }
+                }
+                points.push(p);
+            }
+            for n in 1..points.len() {
+                length += points[n].distance(&points[n - 1]);
+            }
+            if length < min_length {
+                continue;
+            }
+
+            let n = steps.len() as f64;
+            let mean = steps.iter().sum::<f64>() / n;
+            let max = steps.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let stdev = (steps.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>() / n).sqrt();
+
+            let mut sfg = ShapefileGeometry::new(ShapeType::PolyLine);
+            sfg.add_part(&points);
+            output.add_record(sfg);
+            output.attributes.add_record(
+                vec![
+                    FieldData::Int(fid),
+                    FieldData::Real(length),
+                    FieldData::Real(mean),
+                    FieldData::Real(max),
+                    FieldData::Real(stdev),
+                ],
+                false,
+            );
+            fid += 1;
+
+            if verbose {
+                progress = (100.0_f64 * i as f64 / (num_seams - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Creating polylines: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Number of seams found: {}", fid - 1);
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the elevation step between z1 and z2, after removing the local gradient estimated
+/// from the differences on either side (z0 to z1 and z2 to z3), where available.
+fn detrended_step(z0: f64, z1: f64, z2: f64, z3: f64, nodata: f64) -> f64 {
+    let mut gradient = 0f64;
+    let mut n = 0f64;
+    if z0 != nodata {
+        gradient += z1 - z0;
+        n += 1f64;
+    }
+    if z3 != nodata {
+        gradient += z3 - z2;
+        n += 1f64;
+    }
+    if n == 0f64 {
+        return 0f64;
+    }
+    (z2 - z1) - gradient / n
+}
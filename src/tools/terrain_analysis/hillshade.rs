@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 22/06/2017
-Last Modified: 31/03/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
@@ -32,6 +32,14 @@ use std::thread;
 /// 
 /// where `mid_lat` is the latitude of the centre of the raster, in radians.
 /// 
+/// The optional `--units` parameter is shared by the terrain analysis tools. It contains one or
+/// more keywords, separated by commas, that specify the vertical (`z_m` or `z_ft`) and horizontal
+/// (`xy_m` or `xy_ft`) units of the DEM, in which case the Z conversion factor is calculated
+/// automatically; the units are otherwise read from the DEM, where available.
+/// It may also include `degrees` (the default) or `radians`, which sets the units of the input
+/// `--azimuth` and `--altitude`, and `geographic` (the default; measured clockwise from north) or
+/// `arithmetic` (measured counter-clockwise from east), which sets the convention of the `--azimuth`.
+/// 
 /// The hillshade value (*HS*) of a DEM grid cell is calculate as:
 /// 
 /// > *HS* = tan(*s*) / [1 - tan(*s*)<sup>2</sup>]<sup>0.5</sup> x [sin(*Alt*) / tan(*s*) - cos(*Alt*) x sin(*Az* - *a*)]
@@ -109,6 +117,17 @@ impl Hillshade {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Units".to_owned(),
+            flags: vec!["--units".to_owned()],
+            description:
+                "Optional units keywords, e.g. 'percent', 'radians', 'arithmetic', 'z_ft', 'xy_m'."
+                    .to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -167,6 +186,10 @@ impl WhiteboxTool for Hillshade {
         self.toolbox.clone()
     }
 
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_stem}_hs{input_ext}".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -178,6 +201,7 @@ impl WhiteboxTool for Hillshade {
         let mut azimuth = 315.0f64;
         let mut altitude = 30.0f64;
         let mut z_factor = 1f64;
+        let mut units = String::new();
 
         if args.len() == 0 {
             return Err(Error::new(
@@ -222,6 +246,12 @@ impl WhiteboxTool for Hillshade {
                 } else {
                     altitude = args[i + 1].to_string().parse::<f64>().unwrap();
                 }
+            } else if vec[0].to_lowercase() == "-units" || vec[0].to_lowercase() == "--units" {
+                if keyval {
+                    units = vec[1].to_string();
+                } else {
+                    units = args[i + 1].to_string();
+                }
             } else if vec[0].to_lowercase() == "-zfactor" || vec[0].to_lowercase() == "--zfactor" {
                 if keyval {
                     z_factor = vec[1].to_string().parse::<f64>().unwrap();
@@ -237,17 +267,11 @@ impl WhiteboxTool for Hillshade {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -257,21 +281,15 @@ impl WhiteboxTool for Hillshade {
 
         let start = Instant::now();
 
-        azimuth = (azimuth - 90f64).to_radians();
-        altitude = altitude.to_radians();
+        let units = Units::parse(&units)?;
+        z_factor = units.get_z_factor(&input, z_factor);
+
+        azimuth = units.azimuth_to_geographic(azimuth) - 90f64.to_radians();
+        altitude = units.angle_to_radians(altitude);
         let sin_theta = altitude.sin();
         let cos_theta = altitude.cos();
         let eight_grid_res = input.configs.resolution_x * 8.0;
 
-        if input.is_in_geographic_coordinates() {
-            // calculate a new z-conversion factor
-            let mut mid_lat = (input.configs.north - input.configs.south) / 2.0;
-            if mid_lat <= 90.0 && mid_lat >= -90.0 {
-                mid_lat = mid_lat.to_radians();
-                z_factor = 1.0 / (113200.0 * mid_lat.cos());
-            }
-        }
-
         let mut configs = input.configs.clone();
         configs.data_type = DataType::I16;
         configs.nodata = -32768f64;
@@ -0,0 +1,448 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::structures::Array2D;
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+
+/// This tool generalizes a digital elevation model (`--dem`) into a cartographically smooth surface
+/// while preserving its hydrological structure, e.g. for the production of smooth contours or
+/// small-scale relief maps from high-resolution DEMs. Smoothing is performed by iterative relaxation
+/// (Gauss-Seidel iteration), which progressively minimizes the curvature (the discrete Laplacian) of
+/// the surface, subject to the following constraints:
+///
+/// 1. Stream cells, i.e. the non-zero, non-nodata cells of the streams raster (`--streams`), are
+///    held at their original elevations.
+/// 2. The cells neighbouring a stream must remain higher than the adjacent stream cells, such that
+///    the stream lines remain the local minima of the smoothed surface. Cells that are already
+///    lower than an adjacent stream cell in the original DEM may not be lowered.
+/// 3. Peaks, i.e. cells that are higher than each of their neighbours in the original DEM, may be
+///    lowered by no more than the peak tolerance (`--peak_tolerance`).
+/// 4. Optionally, no cell may be changed by more than a maximum elevation difference (`--max_diff`).
+///
+/// Because a relaxed surface has no local extrema away from its constraints, smoothing does not
+/// create new depressions. Larger numbers of iterations (`--num_iter`) produce smoother, more
+/// generalized surfaces; with an unlimited maximum elevation difference, the surface converges
+/// towards the smoothest surface that passes through the streams and peaks. The input DEM should
+/// have been hydrologically conditioned (see `BreachDepressions`) and the streams should be
+/// derived from it, e.g. using `ExtractStreams`, since any depressions in the input DEM that are
+/// deeper than the maximum elevation difference are retained.
+///
+/// # See Also
+/// `FeaturePreservingSmoothing`, `BreachDepressions`, `ExtractStreams`
+pub struct HydroEnforcedSmoothing {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl HydroEnforcedSmoothing {
+    pub fn new() -> HydroEnforcedSmoothing {
+        // public constructor
+        let name = "HydroEnforcedSmoothing".to_string();
+        let toolbox = "Geomorphometric Analysis".to_string();
+        let description = "Smooths a DEM by minimizing its curvature while keeping streams as local minima and preserving peaks.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["-i".to_owned(), "--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Iterations".to_owned(),
+            flags: vec!["--num_iter".to_owned()],
+            description: "Number of relaxation iterations.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("100".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Peak Tolerance".to_owned(),
+            flags: vec!["--peak_tolerance".to_owned()],
+            description: "Maximum amount by which peaks may be lowered, in elevation units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Elevation Change (optional)".to_owned(),
+            flags: vec!["--max_diff".to_owned()],
+            description: "Optional maximum allowable absolute elevation change in any cell.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif --streams=streams.tif -o=output.tif --num_iter=200 --peak_tolerance=1.0 --max_diff=5.0", short_exe, name).replace("*", &sep);
+
+        HydroEnforcedSmoothing {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for HydroEnforcedSmoothing {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut streams_file = String::new();
+        let mut output_file = String::new();
+        let mut num_iter = 100usize;
+        let mut peak_tolerance = 0.5f64;
+        let mut max_z_diff = f64::INFINITY;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-num_iter" {
+                num_iter = if keyval {
+                    vec[1].to_string().parse::<f32>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f32>().unwrap() as usize
+                };
+            } else if flag_val == "-peak_tolerance" {
+                peak_tolerance = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_diff" {
+                max_z_diff = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if num_iter < 1 {
+            num_iter = 1;
+        }
+        if peak_tolerance < 0f64 {
+            peak_tolerance = 0f64;
+        }
+        if max_z_diff < 0f64 {
+            max_z_diff = 0f64;
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(&dem_file, working_directory);
+        streams_file = resolve_path(&streams_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading DEM data...")
+        };
+        let dem = Raster::new(&dem_file, "r")?;
+        if verbose {
+            println!("Reading streams data...")
+        };
+        let streams = Raster::new(&streams_file, "r")?;
+
+        let start = Instant::now();
+
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+        let streams_nodata = streams.configs.nodata;
+
+        // make sure the input files have the same size
+        if dem.configs.rows != streams.configs.rows
+            || dem.configs.columns != streams.configs.columns
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must have the same number of rows and columns and spatial extent.",
+            ));
+        }
+
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+        // The weights of the 9-point discrete Laplacian, i.e. 4 for cardinal and 1 for diagonal neighbours.
+        let weights = [1f64, 4f64, 1f64, 4f64, 1f64, 4f64, 1f64, 4f64];
+        let small_num = {
+            let elev_digits = ((dem.configs.maximum as i64).to_string()).len();
+            let elev_multiplier = 10.0_f64.powi((6 - elev_digits) as i32);
+            1.0_f64 / elev_multiplier as f64
+        };
+
+        // Find the lower and upper bounds on the elevation of each cell. Stream cells are fixed,
+        // the neighbours of streams must remain above them, and peaks may only be lowered by the
+        // peak tolerance.
+        let mut is_stream: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                let s = streams.get_value(row, col);
+                if s > 0f64 && s != streams_nodata && dem.get_value(row, col) != nodata {
+                    is_stream.set_value(row, col, 1u8);
+                }
+            }
+        }
+
+        let mut lower_bound: Array2D<f64> = Array2D::new(rows, columns, nodata, nodata)?;
+        let mut upper_bound: Array2D<f64> = Array2D::new(rows, columns, nodata, nodata)?;
+        let mut num_streams = 0usize;
+        let mut num_peaks = 0usize;
+        let (mut z, mut zn): (f64, f64);
+        let mut is_peak: bool;
+        for row in 0..rows {
+            for col in 0..columns {
+                z = dem.get_value(row, col);
+                if z == nodata {
+                    continue;
+                }
+                if is_stream.get_value(row, col) == 1u8 {
+                    lower_bound.set_value(row, col, z);
+                    upper_bound.set_value(row, col, z);
+                    num_streams += 1;
+                    continue;
+                }
+                let mut lower = z - max_z_diff;
+                let upper = z + max_z_diff;
+                let mut stream_elev = f64::NEG_INFINITY;
+                is_peak = true;
+                let mut num_neighbours = 0;
+                for n in 0..8 {
+                    zn = dem.get_value(row + dy[n], col + dx[n]);
+                    if zn != nodata {
+                        num_neighbours += 1;
+                        if zn >= z {
+                            is_peak = false;
+                        }
+                        if is_stream.get_value(row + dy[n], col + dx[n]) == 1u8 {
+                            stream_elev = stream_elev.max(zn);
+                        }
+                    }
+                }
+                if stream_elev > f64::NEG_INFINITY {
+                    // a cell that was already lower than a neighbouring stream cell may not be lowered
+                    lower = lower.max((stream_elev + small_num).min(z));
+                }
+                if is_peak && num_neighbours > 0 {
+                    lower = lower.max(z - peak_tolerance);
+                    num_peaks += 1;
+                }
+                lower_bound.set_value(row, col, lower);
+                // the stream constraint takes precedence over the maximum elevation change
+                upper_bound.set_value(row, col, upper.max(lower));
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Finding constraints: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        drop(is_stream);
+
+        // Relax the surface, updating the elevations in place (Gauss-Seidel iteration).
+        let mut output: Array2D<f64> = dem.get_data_as_array2d();
+        let (mut sum, mut sum_w): (f64, f64);
+        let (mut row_n, mut col_n): (isize, isize);
+        let mut max_change: f64 = 0f64;
+        for loop_num in 0..num_iter {
+            max_change = 0f64;
+            for row in 0..rows {
+                for col in 0..columns {
+                    z = output.get_value(row, col);
+                    if z == nodata || lower_bound.get_value(row, col) == upper_bound.get_value(row, col) {
+                        continue;
+                    }
+                    sum = 0f64;
+                    sum_w = 0f64;
+                    for n in 0..8 {
+                        row_n = row + dy[n];
+                        col_n = col + dx[n];
+                        if row_n < 0 || row_n >= rows || col_n < 0 || col_n >= columns {
+                            continue;
+                        }
+                        zn = output.get_value(row_n, col_n);
+                        if zn != nodata {
+                            sum += weights[n] * zn;
+                            sum_w += weights[n];
+                        }
+                    }
+                    if sum_w > 0f64 {
+                        zn = (sum / sum_w)
+                            .max(lower_bound.get_value(row, col))
+                            .min(upper_bound.get_value(row, col));
+                        if (zn - z).abs() > max_change {
+                            max_change = (zn - z).abs();
+                        }
+                        output.set_value(row, col, zn);
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * loop_num as f64 / (num_iter - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Smoothing (max. change {:.4}): {}%", max_change, progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let mut output_raster = Raster::initialize_using_file(&output_file, &dem);
+        output_raster.configs.data_type = DataType::F32;
+        for row in 0..rows {
+            output_raster.set_row_data(row, output.get_row_data(row));
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output_raster.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output_raster.add_metadata_entry(format!("DEM file: {}", dem_file));
+        output_raster.add_metadata_entry(format!("Streams file: {}", streams_file));
+        output_raster.add_metadata_entry(format!("Iterations: {}", num_iter));
+        output_raster.add_metadata_entry(format!("Peak tolerance: {}", peak_tolerance));
+        output_raster.add_metadata_entry(format!("Max. z difference: {}", max_z_diff));
+        output_raster.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Stream cells: {}", num_streams);
+            println!("Peaks: {}", num_peaks);
+            println!("Max. elevation change in final iteration: {:.6}", max_change);
+            println!("Saving data...")
+        }
+        let _ = match output_raster.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
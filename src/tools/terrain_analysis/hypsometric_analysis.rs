@@ -182,8 +182,6 @@ impl WhiteboxTool for HypsometricAnalysis {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -201,9 +199,7 @@ impl WhiteboxTool for HypsometricAnalysis {
                                 "There is something incorrect about the input DEM files. At least one input DEM is required to operate this tool."));
         }
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let f = File::create(output_file.clone())?;
         let mut writer = BufWriter::new(f);
@@ -234,9 +230,7 @@ impl WhiteboxTool for HypsometricAnalysis {
         if watershed_files_str.is_empty() {
             for i in 0..num_files {
                 let mut input_file = input_files[i].to_string();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
                 let input = Raster::new(&input_file, "r")?;
 
                 if num_files == 1 {
@@ -317,9 +311,7 @@ impl WhiteboxTool for HypsometricAnalysis {
 
             for i in 0..num_files {
                 let mut input_file = input_files[i].to_string();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
                 let input = Raster::new(&input_file, "r")?;
                 if num_files == 1 {
                     writer.write_all(
@@ -335,9 +327,7 @@ impl WhiteboxTool for HypsometricAnalysis {
                 let nodata = input.configs.nodata;
 
                 let mut watershed_file = watershed_files[i].to_string();
-                if !watershed_file.contains(&sep) {
-                    watershed_file = format!("{}{}", working_directory, watershed_file);
-                }
+                watershed_file = resolve_path(&watershed_file, working_directory);
                 let watershed = Raster::new(&watershed_file, "r")?;
                 let ws_nodata = watershed.configs.nodata;
                 if watershed.configs.rows as isize != rows
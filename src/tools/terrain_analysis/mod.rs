@@ -1,4 +1,5 @@
 // private sub-module defined in other files
+mod archaeological_visualization;
 mod aspect;
 mod average_normal_vector_angular_deviation;
 mod circular_variance_of_aspect;
@@ -15,10 +16,12 @@ mod elev_relative_to_watershed_min_max;
 mod feature_preserving_smoothing;
 mod fetch_analysis;
 mod fill_missing_data;
+mod find_elevation_seams;
 mod find_ridges;
 // mod geomorphons;
 mod hillshade;
 mod horizon_angle;
+mod hydro_enforced_smoothing;
 mod hypsometric_analysis;
 mod max_anisotropy_dev;
 mod max_anisotropy_dev_signature;
@@ -46,18 +49,23 @@ mod relative_topographic_position;
 mod remove_off_terrain_objects;
 mod ruggedness_index;
 mod sediment_transport_index;
+mod shadow_mask;
 mod slope;
 mod slope_vs_elev_plot;
+mod snow_depth_differencing;
+mod snowmelt_index;
 mod spherical_std_dev_of_normals;
 mod standard_deviation_of_slope;
 mod surface_area_ratio;
 mod tan_curvature;
+mod terrain_similarity;
 mod total_curvature;
 mod viewshed;
 mod visibility_index;
 mod wetness_index;
 
 // exports identifiers from private sub-modules in the current module namespace
+pub use self::archaeological_visualization::ArchaeologicalVisualization;
 pub use self::aspect::Aspect;
 pub use self::average_normal_vector_angular_deviation::AverageNormalVectorAngularDeviation;
 pub use self::circular_variance_of_aspect::CircularVarianceOfAspect;
@@ -74,10 +82,12 @@ pub use self::elev_relative_to_watershed_min_max::ElevRelativeToWatershedMinMax;
 pub use self::feature_preserving_smoothing::FeaturePreservingSmoothing;
 pub use self::fetch_analysis::FetchAnalysis;
 pub use self::fill_missing_data::FillMissingData;
+pub use self::find_elevation_seams::FindElevationSeams;
 pub use self::find_ridges::FindRidges;
 // pub use self::geomorphons::Geomorphons;
 pub use self::hillshade::Hillshade;
 pub use self::horizon_angle::HorizonAngle;
+pub use self::hydro_enforced_smoothing::HydroEnforcedSmoothing;
 pub use self::hypsometric_analysis::HypsometricAnalysis;
 pub use self::max_anisotropy_dev::MaxAnisotropyDev;
 pub use self::max_anisotropy_dev_signature::MaxAnisotropyDevSignature;
@@ -104,13 +114,17 @@ pub use self::relative_topographic_position::RelativeTopographicPosition;
 pub use self::remove_off_terrain_objects::RemoveOffTerrainObjects;
 pub use self::ruggedness_index::RuggednessIndex;
 pub use self::sediment_transport_index::SedimentTransportIndex;
+pub use self::shadow_mask::ShadowMask;
 pub use self::slope::Slope;
 pub use self::slope_vs_elev_plot::SlopeVsElevationPlot;
+pub use self::snow_depth_differencing::SnowDepthDifferencing;
+pub use self::snowmelt_index::SnowmeltIndex;
 pub use self::spherical_std_dev_of_normals::SphericalStdDevOfNormals;
 pub use self::multiscale_std_dev_normals_signature::MultiscaleStdDevNormalsSignature;
 pub use self::standard_deviation_of_slope::StandardDeviationOfSlope;
 pub use self::surface_area_ratio::SurfaceAreaRatio;
 pub use self::tan_curvature::TangentialCurvature;
+pub use self::terrain_similarity::TerrainSimilarity;
 pub use self::total_curvature::TotalCurvature;
 pub use self::viewshed::Viewshed;
 pub use self::visibility_index::VisibilityIndex;
@@ -239,20 +239,12 @@ impl WhiteboxTool for MultiscaleRoughness {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_mag_file.contains(&sep) && !output_mag_file.contains("/") {
-            output_mag_file = format!("{}{}", working_directory, output_mag_file);
-        }
-        if !output_scale_file.contains(&sep) && !output_scale_file.contains("/") {
-            output_scale_file = format!("{}{}", working_directory, output_scale_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_mag_file = resolve_path(&output_mag_file, working_directory);
+        output_scale_file = resolve_path(&output_scale_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -226,23 +226,13 @@ impl WhiteboxTool for MultiscaleTopographicPositionImage {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input1_file.contains(&sep) && input1_file.contains("/") {
-            input1_file = format!("{}{}", working_directory, input1_file);
-        }
-        if !input2_file.contains(&sep) && input2_file.contains("/") {
-            input2_file = format!("{}{}", working_directory, input2_file);
-        }
-        if !input3_file.contains(&sep) && input3_file.contains("/") {
-            input3_file = format!("{}{}", working_directory, input3_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input1_file = resolve_path(&input1_file, working_directory);
+        input2_file = resolve_path(&input2_file, working_directory);
+        input3_file = resolve_path(&input3_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading broad-scale DEV data...")
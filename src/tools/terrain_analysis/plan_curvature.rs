@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 01/06/2017
-Last Modified: 30/01/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
@@ -32,6 +32,13 @@ use std::thread;
 /// 
 /// where `mid_lat` is the latitude of the centre of the raster, in radians.
 /// 
+/// The optional `--units` parameter is shared by the terrain analysis tools. It contains one or
+/// more keywords, separated by commas, that specify the vertical (`z_m` or `z_ft`) and horizontal
+/// (`xy_m` or `xy_ft`) units of the DEM, in which case the Z conversion factor is calculated
+/// automatically; the units are otherwise read from the DEM, where available.
+/// It may also include `degrees` (the default) or `radians`, which sets the angular units of the
+/// output curvature (which is multiplied by 100 in either case).
+/// 
 /// The algorithm uses the same formula for the calculation of plan curvature as Gallant and 
 /// Wilson (2000). Plan curvature is negative for diverging flow along ridges and positive for 
 /// convergent areas, e.g. along valley bottoms.
@@ -88,6 +95,17 @@ impl PlanCurvature {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Units".to_owned(),
+            flags: vec!["--units".to_owned()],
+            description:
+                "Optional units keywords, e.g. 'percent', 'radians', 'arithmetic', 'z_ft', 'xy_m'."
+                    .to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -150,6 +168,10 @@ impl WhiteboxTool for PlanCurvature {
         self.toolbox.clone()
     }
 
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_stem}_plan_curv{input_ext}".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -159,6 +181,7 @@ impl WhiteboxTool for PlanCurvature {
         let mut input_file = String::new();
         let mut output_file = String::new();
         let mut z_factor = 1f64;
+        let mut units = String::new();
 
         if args.len() == 0 {
             return Err(Error::new(
@@ -188,6 +211,12 @@ impl WhiteboxTool for PlanCurvature {
                 } else {
                     args[i + 1].to_string()
                 };
+            } else if flag_val == "-units" {
+                units = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
             } else if flag_val == "-zfactor" {
                 z_factor = if keyval {
                     vec[1].to_string().parse::<f64>().unwrap()
@@ -203,17 +232,11 @@ impl WhiteboxTool for PlanCurvature {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -228,14 +251,10 @@ impl WhiteboxTool for PlanCurvature {
         let cell_size_sqrd = cell_size * cell_size;
         let four_times_cell_size_sqrd = cell_size_sqrd * 4.0f64;
 
-        if input.is_in_geographic_coordinates() {
-            // calculate a new z-conversion factor
-            let mut mid_lat = (input.configs.north - input.configs.south) / 2.0;
-            if mid_lat <= 90.0 && mid_lat >= -90.0 {
-                mid_lat = mid_lat.to_radians();
-                z_factor = 1.0 / (113200.0 * mid_lat.cos());
-            }
-        }
+        let units = Units::parse(&units)?;
+        z_factor = units.get_z_factor(&input, z_factor);
+        // curvatures are reported in degrees (or radians) multiplied by 100
+        let curvature_multiplier = units.convert_angle(100f64);
 
         let mut output = Raster::initialize_using_file(&output_file, &input);
         let rows = input.configs.rows as isize;
@@ -292,8 +311,7 @@ impl WhiteboxTool for PlanCurvature {
                                 data[col as usize] = ((zxx * zy2 - 2.0f64 * zxy * zx * zy
                                     + zyy * zx2)
                                     / p.powf(1.5f64))
-                                .to_degrees()
-                                    * 100f64;
+                                 * curvature_multiplier;
                             }
                         }
                     }
@@ -187,20 +187,12 @@ impl WhiteboxTool for Profile {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !profile_file.contains(&sep) && !profile_file.contains("/") {
-            profile_file = format!("{}{}", working_directory, profile_file);
-        }
-        if !surface_file.contains(&sep) && !surface_file.contains("/") {
-            surface_file = format!("{}{}", working_directory, surface_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        profile_file = resolve_path(&profile_file, working_directory);
+        surface_file = resolve_path(&surface_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading profile data...")
@@ -238,20 +238,12 @@ impl WhiteboxTool for SedimentTransportIndex {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
-        if !sca_file.contains(&sep) && !sca_file.contains("/") {
-            sca_file = format!("{}{}", working_directory, sca_file);
-        }
-        if !slope_file.contains(&sep) && !slope_file.contains("/") {
-            slope_file = format!("{}{}", working_directory, slope_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
+        sca_file = resolve_path(&sca_file, working_directory);
+        slope_file = resolve_path(&slope_file, working_directory);
 
         if verbose {
             println!("Reading data...")
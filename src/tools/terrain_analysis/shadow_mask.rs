@@ -0,0 +1,786 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool maps the areas of a digital elevation model (DEM) that are in shadow at a specific date
+/// and time. The position of the sun is calculated from the date (`--date`, formatted as
+/// *YYYY-MM-DD*), the local time (`--time`, formatted as *HH:MM* or *HH:MM:SS*), the offset of the
+/// local time zone from Coordinated Universal Time (`--utc_offset`, in hours, e.g. -5 for Eastern
+/// Standard Time), and the latitude and longitude of the site, using the solar position equations of
+/// the NOAA Global Monitoring Laboratory, which are based on Meeus (1998). Atmospheric refraction is
+/// accounted for. For DEMs in geographic coordinates, the coordinates of the centre of the DEM are
+/// used by default; otherwise, the latitude and longitude of the site must be specified
+/// (`--latitude` and `--longitude`, in decimal degrees).
+///
+/// Each grid cell in the output raster (`--output`) is assigned one of the following values:
+///
+/// | Value | Description                                                                     |
+/// |-------|---------------------------------------------------------------------------------|
+/// | 0     | Illuminated                                                                     |
+/// | 1     | Self-shaded, i.e. the surface faces away from the sun or the sun is below the horizon |
+/// | 2     | In a cast shadow, i.e. the sun is obscured by the surrounding surface           |
+///
+/// Cast shadows are identified by sweeping the surface along the direction of the sun from each
+/// grid cell and comparing the elevation of the surface with the elevation of the ray that connects
+/// the cell with the sun, i.e. by determining whether the horizon angle in the direction of the sun
+/// exceeds the solar altitude. Elevations between grid cell centres are estimated using bilinear
+/// interpolation. Sweeping stops once the ray rises above the highest elevation in the DEM or when
+/// the optional maximum search distance (`--max_dist`, in map units) is reached.
+///
+/// Optionally, a digital surface model (DSM; `--dsm`) containing the elevations of buildings and
+/// vegetation canopies may also be specified, in which case the shadows cast by and onto these
+/// objects are mapped. The DSM must contain absolute elevations, rather than heights above the
+/// ground, and where it contains NoData values the elevations of the DEM are used. The resulting
+/// masks are useful for solar energy siting, e.g. for identifying the rooftops that are not shaded
+/// during peak generation hours, and for the correction of shadows in aerial and satellite imagery,
+/// in which case the date and time of image acquisition should be used.
+///
+/// If the vertical and horizontal units of the DEM differ, the z-factor (`--zfactor`) or units
+/// (`--units`) should be specified. For DEMs in geographic coordinates, elevations are converted
+/// into degrees automatically.
+///
+/// # Reference
+/// Meeus, J. (1998). *Astronomical Algorithms*, 2nd edition. Willmann-Bell, Richmond, VA.
+///
+/// # See Also
+/// `HorizonAngle`, `Hillshade`, `SnowmeltIndex`
+pub struct ShadowMask {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ShadowMask {
+    pub fn new() -> ShadowMask {
+        // public constructor
+        let name = "ShadowMask".to_string();
+        let toolbox = "Geomorphometric Analysis".to_string();
+        let description =
+            "Maps the self-shaded and cast-shadow areas of a DEM for a specific date, time, and location."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["-i".to_owned(), "--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input DSM File (optional)".to_owned(),
+            flags: vec!["--dsm".to_owned()],
+            description: "Optional input raster digital surface model (DSM) file of building and canopy elevations.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Date (YYYY-MM-DD)".to_owned(),
+            flags: vec!["--date".to_owned()],
+            description: "Date, formatted as YYYY-MM-DD.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Local Time (HH:MM)".to_owned(),
+            flags: vec!["--time".to_owned()],
+            description: "Local time, formatted as HH:MM or HH:MM:SS.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: Some("12:00".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "UTC Offset (hours)".to_owned(),
+            flags: vec!["--utc_offset".to_owned()],
+            description: "Offset of the local time zone from UTC, in hours.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Latitude".to_owned(),
+            flags: vec!["--latitude".to_owned()],
+            description: "Latitude of the site, in degrees; required for DEMs in projected coordinates.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Longitude".to_owned(),
+            flags: vec!["--longitude".to_owned()],
+            description: "Longitude of the site, in degrees; required for DEMs in projected coordinates.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Search Distance".to_owned(),
+            flags: vec!["--max_dist".to_owned()],
+            description: "Optional maximum search distance, in map units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Z Conversion Factor".to_owned(),
+            flags: vec!["--zfactor".to_owned()],
+            description:
+                "Optional multiplier for when the vertical and horizontal units are not the same."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Units".to_owned(),
+            flags: vec!["--units".to_owned()],
+            description: "Optional units keywords, e.g. 'z_ft', 'xy_m'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=shadows.tif --date=2026-06-21 --time=15:30 --utc_offset=-4 --latitude=43.5 --longitude=-80.2
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif --dsm=DSM.tif -o=shadows.tif --date=2026-12-21 --time=10:00 --latitude=43.5 --longitude=-80.2 --max_dist=500.0", short_exe, name).replace("*", &sep);
+
+        ShadowMask {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ShadowMask {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            (
+                "--utc_offset".to_string(),
+                ParameterConstraint::Range(-14f64, 14f64),
+            ),
+            (
+                "--latitude".to_string(),
+                ParameterConstraint::Range(-90f64, 90f64),
+            ),
+            (
+                "--longitude".to_string(),
+                ParameterConstraint::Range(-180f64, 360f64),
+            ),
+            (
+                "--max_dist".to_string(),
+                ParameterConstraint::GreaterThan(0f64),
+            ),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut dsm_file = String::new();
+        let mut output_file = String::new();
+        let mut date = String::new();
+        let mut time = String::from("12:00");
+        let mut utc_offset = 0f64;
+        let mut latitude = f64::NAN;
+        let mut longitude = f64::NAN;
+        let mut max_dist = f64::INFINITY;
+        let mut z_factor = 1f64;
+        let mut units = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-dem" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-dsm" {
+                dsm_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-date" {
+                date = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-time" {
+                time = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-utc_offset" {
+                utc_offset = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-latitude" || flag_val == "-lat" {
+                latitude = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-longitude" || flag_val == "-lon" {
+                longitude = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-max_dist" {
+                max_dist = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-zfactor" {
+                z_factor = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-units" {
+                units = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let (year, month, day) = parse_date(&date)?;
+        let local_hours = parse_time(&time)?;
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        dsm_file = resolve_path(&dsm_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let input = Raster::new(&input_file, "r")?;
+        let dsm = if !dsm_file.is_empty() {
+            let d = Raster::new(&dsm_file, "r")?;
+            if d.configs.rows != input.configs.rows || d.configs.columns != input.configs.columns {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+            Some(d)
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+
+        if input.is_in_geographic_coordinates() {
+            if latitude.is_nan() {
+                latitude = (input.configs.north + input.configs.south) / 2f64;
+            }
+            if longitude.is_nan() {
+                longitude = (input.configs.east + input.configs.west) / 2f64;
+            }
+        } else if latitude.is_nan() || longitude.is_nan() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The site latitude and longitude (--latitude and --longitude) must be specified for DEMs in projected coordinates.",
+            ));
+        }
+        if longitude > 180f64 {
+            longitude -= 360f64;
+        }
+
+        let units = Units::parse(&units)?;
+        let z_factor = units.get_z_factor(&input, z_factor);
+
+        let (azimuth, altitude) = solar_position(
+            year,
+            month,
+            day,
+            local_hours - utc_offset,
+            latitude,
+            longitude,
+        );
+        if verbose {
+            println!(
+                "Solar azimuth: {:.2} degrees; solar altitude: {:.2} degrees",
+                azimuth, altitude
+            );
+        }
+
+        let rows = input.configs.rows as isize;
+        let columns = input.configs.columns as isize;
+        let nodata = input.configs.nodata;
+
+        // the shadowing surface, in horizontal units
+        let mut surface: Vec<f64> = Vec::with_capacity((rows * columns) as usize);
+        let mut max_z = f64::NEG_INFINITY;
+        for row in 0..rows {
+            for col in 0..columns {
+                let mut z = input.get_value(row, col);
+                if let Some(ref d) = dsm {
+                    let zd = d.get_value(row, col);
+                    if zd != d.configs.nodata {
+                        z = zd;
+                    }
+                }
+                if z != nodata {
+                    z *= z_factor;
+                    max_z = max_z.max(z);
+                    surface.push(z);
+                } else {
+                    surface.push(f64::NAN);
+                }
+            }
+        }
+        drop(dsm);
+
+        let mut output = Raster::initialize_using_file(&output_file, &input);
+        let out_nodata = -32768f64;
+        output.configs.nodata = out_nodata;
+        output.configs.data_type = DataType::I16;
+        output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+        output.configs.palette = "grey.plt".to_string();
+
+        let mut num_shaded = [0usize; 3];
+        if altitude <= 0f64 {
+            if verbose {
+                println!("Warning: The sun is below the horizon at the specified date and time.");
+            }
+            for row in 0..rows {
+                let data = (0..columns)
+                    .map(|col| {
+                        if surface[(row * columns + col) as usize].is_nan() {
+                            out_nodata
+                        } else {
+                            num_shaded[1] += 1;
+                            1f64
+                        }
+                    })
+                    .collect();
+                output.set_row_data(row, data);
+            }
+        } else {
+            let surface = Arc::new(surface);
+            let res_x = input.configs.resolution_x;
+            let res_y = input.configs.resolution_y;
+            let (sin_az, cos_az) = azimuth.to_radians().sin_cos();
+            let (sin_alt, cos_alt) = altitude.to_radians().sin_cos();
+            let tan_alt = altitude.to_radians().tan();
+            // the unit vector pointing towards the sun
+            let (sun_east, sun_north, sun_up) = (cos_alt * sin_az, cos_alt * cos_az, sin_alt);
+            // the sweep advances by the smaller of the grid resolutions at each step
+            let step = res_x.min(res_y);
+            let d_col = step * sin_az / res_x;
+            let d_row = -step * cos_az / res_y;
+
+            let num_procs = num_cpus::get() as isize;
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let surface = surface.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    let get_z = |row: isize, col: isize| -> f64 {
+                        if row < 0 || col < 0 || row >= rows || col >= columns {
+                            f64::NAN
+                        } else {
+                            surface[(row * columns + col) as usize]
+                        }
+                    };
+                    // bilinear interpolation between the cell centres, ignoring NoData cells
+                    let interpolate = |r: f64, c: f64| -> f64 {
+                        let (r0, c0) = (r.floor(), c.floor());
+                        let (fr, fc) = (r - r0, c - c0);
+                        let (r0, c0) = (r0 as isize, c0 as isize);
+                        let mut sum = 0f64;
+                        let mut sum_w = 0f64;
+                        for (dr, dc, w) in [
+                            (0, 0, (1f64 - fr) * (1f64 - fc)),
+                            (0, 1, (1f64 - fr) * fc),
+                            (1, 0, fr * (1f64 - fc)),
+                            (1, 1, fr * fc),
+                        ] {
+                            let z = get_z(r0 + dr, c0 + dc);
+                            if !z.is_nan() && w > 0f64 {
+                                sum += z * w;
+                                sum_w += w;
+                            }
+                        }
+                        if sum_w > 0f64 {
+                            sum / sum_w
+                        } else {
+                            f64::NAN
+                        }
+                    };
+                    let d_x = [1, 1, 1, 0, -1, -1, -1, 0];
+                    let d_y = [-1, 0, 1, 1, 1, 0, -1, -1];
+                    let mut n: [f64; 8] = [0.0; 8];
+                    for row in (0..rows).filter(|r| r % num_procs == tid) {
+                        let mut data = vec![out_nodata; columns as usize];
+                        for col in 0..columns {
+                            let z = get_z(row, col);
+                            if z.is_nan() {
+                                continue;
+                            }
+
+                            // is the sun obscured by the surrounding surface?
+                            let mut in_shadow = false;
+                            let mut k = 1f64;
+                            loop {
+                                let dist = k * step;
+                                let ray_z = z + dist * tan_alt;
+                                if ray_z > max_z || dist > max_dist {
+                                    break;
+                                }
+                                let (r, c) = (row as f64 + k * d_row, col as f64 + k * d_col);
+                                if r < -0.5
+                                    || c < -0.5
+                                    || r > rows as f64 - 0.5
+                                    || c > columns as f64 - 0.5
+                                {
+                                    break;
+                                }
+                                let zs = interpolate(r, c);
+                                if !zs.is_nan() && zs > ray_z {
+                                    in_shadow = true;
+                                    break;
+                                }
+                                k += 1f64;
+                            }
+                            if in_shadow {
+                                data[col as usize] = 2f64;
+                                continue;
+                            }
+
+                            // does the surface face away from the sun?
+                            for c in 0..8 {
+                                n[c] = get_z(row + d_y[c], col + d_x[c]);
+                                if n[c].is_nan() {
+                                    n[c] = z;
+                                }
+                            }
+                            let fx = (n[2] - n[4] + 2f64 * (n[1] - n[5]) + n[0] - n[6])
+                                / (8f64 * res_x);
+                            let fy = (n[6] - n[4] + 2f64 * (n[7] - n[3]) + n[0] - n[2])
+                                / (8f64 * res_y);
+                            let cos_incidence = sun_up - fx * sun_east - fy * sun_north;
+                            data[col as usize] = if cos_incidence > 0f64 { 0f64 } else { 1f64 };
+                        }
+                        tx.send((row, data)).unwrap();
+                    }
+                });
+            }
+
+            for r in 0..rows {
+                let (row, data) = rx.recv().unwrap();
+                for v in &data {
+                    if *v != out_nodata {
+                        num_shaded[*v as usize] += 1;
+                    }
+                }
+                output.set_row_data(row, data);
+                if verbose {
+                    progress = (100.0_f64 * r as f64 / (rows - 1).max(1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        if verbose {
+            let total = (num_shaded[0] + num_shaded[1] + num_shaded[2]).max(1) as f64;
+            println!(
+                "Illuminated: {:.1}%; self-shaded: {:.1}%; cast shadow: {:.1}%",
+                100f64 * num_shaded[0] as f64 / total,
+                100f64 * num_shaded[1] as f64 / total,
+                100f64 * num_shaded[2] as f64 / total
+            );
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input DEM file: {}", input_file));
+        if !dsm_file.is_empty() {
+            output.add_metadata_entry(format!("Input DSM file: {}", dsm_file));
+        }
+        output.add_metadata_entry(format!(
+            "Date and time: {} {} (UTC offset {})",
+            date, time, utc_offset
+        ));
+        output.add_metadata_entry(format!("Latitude: {}; longitude: {}", latitude, longitude));
+        output.add_metadata_entry(format!(
+            "Solar azimuth: {:.2}; solar altitude: {:.2}",
+            azimuth, altitude
+        ));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a date formatted as YYYY-MM-DD.
+fn parse_date(date: &str) -> Result<(i32, u32, u32), Error> {
+    let parts: Vec<&str> = date.trim().split(&['-', '/'][..]).collect();
+    let err = || {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("The date ({}) must be formatted as YYYY-MM-DD.", date),
+        )
+    };
+    if parts.len() != 3 {
+        return Err(err());
+    }
+    let year = parts[0].parse::<i32>().map_err(|_| err())?;
+    let month = parts[1].parse::<u32>().map_err(|_| err())?;
+    let day = parts[2].parse::<u32>().map_err(|_| err())?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(err());
+    }
+    Ok((year, month, day))
+}
+
+/// Parses a time formatted as HH:MM or HH:MM:SS, returning the number of hours.
+fn parse_time(time: &str) -> Result<f64, Error> {
+    let err = || {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("The time ({}) must be formatted as HH:MM or HH:MM:SS.", time),
+        )
+    };
+    let mut hours = 0f64;
+    let mut divisor = 1f64;
+    let parts: Vec<&str> = time.trim().split(':').collect();
+    if parts.len() < 2 || parts.len() > 3 {
+        return Err(err());
+    }
+    for part in parts {
+        hours += part.parse::<f64>().map_err(|_| err())? / divisor;
+        divisor *= 60f64;
+    }
+    if !(0f64..=24f64).contains(&hours) {
+        return Err(err());
+    }
+    Ok(hours)
+}
+
+/// Calculates the solar azimuth (degrees clockwise from north) and altitude (degrees above the
+/// horizon, corrected for atmospheric refraction) for a date, a UTC time (in hours, which may
+/// fall outside of 0-24), and a location, using the NOAA solar position equations.
+fn solar_position(
+    year: i32,
+    month: u32,
+    day: u32,
+    utc_hours: f64,
+    latitude: f64,
+    longitude: f64,
+) -> (f64, f64) {
+    // Julian day
+    let (mut y, mut m) = (year as f64, month as f64);
+    if month <= 2 {
+        y -= 1f64;
+        m += 12f64;
+    }
+    let a = (y / 100f64).floor();
+    let b = 2f64 - a + (a / 4f64).floor();
+    let jd = (365.25 * (y + 4716f64)).floor() + (30.6001 * (m + 1f64)).floor() + day as f64 + b
+        - 1524.5
+        + utc_hours / 24f64;
+    let t = (jd - 2451545f64) / 36525f64; // Julian century
+
+    let mean_long = (280.46646 + t * (36000.76983 + t * 0.0003032)).rem_euclid(360f64);
+    let mean_anomaly = 357.52911 + t * (35999.05029 - 0.0001537 * t);
+    let eccentricity = 0.016708634 - t * (0.000042037 + 0.0000001267 * t);
+    let ma = mean_anomaly.to_radians();
+    let centre = ma.sin() * (1.914602 - t * (0.004817 + 0.000014 * t))
+        + (2f64 * ma).sin() * (0.019993 - 0.000101 * t)
+        + (3f64 * ma).sin() * 0.000289;
+    let omega = (125.04 - 1934.136 * t).to_radians();
+    let apparent_long = (mean_long + centre - 0.00569 - 0.00478 * omega.sin()).to_radians();
+    let mean_obliquity =
+        23f64 + (26f64 + (21.448 - t * (46.815 + t * (0.00059 - t * 0.001813))) / 60f64) / 60f64;
+    let obliquity = (mean_obliquity + 0.00256 * omega.cos()).to_radians();
+    let declination = (obliquity.sin() * apparent_long.sin()).asin();
+
+    // the equation of time, in minutes
+    let var_y = (obliquity / 2f64).tan().powi(2);
+    let l0 = mean_long.to_radians();
+    let eq_time = 4f64
+        * (var_y * (2f64 * l0).sin() - 2f64 * eccentricity * ma.sin()
+            + 4f64 * eccentricity * var_y * ma.sin() * (2f64 * l0).cos()
+            - 0.5 * var_y * var_y * (4f64 * l0).sin()
+            - 1.25 * eccentricity * eccentricity * (2f64 * ma).sin())
+        .to_degrees();
+
+    let true_solar_time = (utc_hours * 60f64 + eq_time + 4f64 * longitude).rem_euclid(1440f64);
+    let hour_angle = (true_solar_time / 4f64 - 180f64).to_radians();
+    let phi = latitude.to_radians();
+    let cos_zenith = (phi.sin() * declination.sin()
+        + phi.cos() * declination.cos() * hour_angle.cos())
+    .clamp(-1f64, 1f64);
+    let zenith = cos_zenith.acos();
+    let mut altitude = 90f64 - zenith.to_degrees();
+
+    let azimuth = (hour_angle
+        .sin()
+        .atan2(hour_angle.cos() * phi.sin() - declination.tan() * phi.cos())
+        .to_degrees()
+        + 180f64)
+        .rem_euclid(360f64);
+
+    // atmospheric refraction
+    if altitude > -0.575 {
+        let refraction = if altitude > 5f64 {
+            let tan_alt = altitude.to_radians().tan();
+            58.1 / tan_alt - 0.07 / tan_alt.powi(3) + 0.000086 / tan_alt.powi(5)
+        } else {
+            1735f64
+                + altitude
+                    * (-518.2 + altitude * (103.4 + altitude * (-12.79 + altitude * 0.711)))
+        };
+        altitude += refraction / 3600f64;
+    } else {
+        altitude += -20.774 / altitude.to_radians().tan() / 3600f64;
+    }
+
+    (azimuth, altitude)
+}
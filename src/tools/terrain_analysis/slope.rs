@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 22/06/2017
-Last Modified: 30/01/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
@@ -29,6 +29,13 @@ use std::thread;
 /// 
 /// where `mid_lat` is the latitude of the centre of the raster, in radians.
 /// 
+/// The optional `--units` parameter is shared by the terrain analysis tools. It contains one or
+/// more keywords, separated by commas, that specify the vertical (`z_m` or `z_ft`) and horizontal
+/// (`xy_m` or `xy_ft`) units of the DEM, in which case the Z conversion factor is calculated
+/// automatically; the units are otherwise read from the DEM, where available.
+/// It may also include `degrees` (the default), `percent`, or `radians`, which sets the units of the
+/// output slope gradient.
+/// 
 /// The tool uses Horn's (1981) 3rd-order finite difference method to estimate slope. Given 
 /// the following clock-type grid cell numbering scheme (Gallant and Wilson, 2000),
 /// 
@@ -99,6 +106,17 @@ impl Slope {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Units".to_owned(),
+            flags: vec!["--units".to_owned()],
+            description:
+                "Optional units keywords, e.g. 'percent', 'radians', 'arithmetic', 'z_ft', 'xy_m'."
+                    .to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -161,6 +179,10 @@ impl WhiteboxTool for Slope {
         self.toolbox.clone()
     }
 
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_stem}_slope{input_ext}".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -170,6 +192,7 @@ impl WhiteboxTool for Slope {
         let mut input_file = String::new();
         let mut output_file = String::new();
         let mut z_factor = 1f64;
+        let mut units = String::new();
 
         if args.len() == 0 {
             return Err(Error::new(
@@ -201,6 +224,12 @@ impl WhiteboxTool for Slope {
                 } else {
                     output_file = args[i + 1].to_string();
                 }
+            } else if vec[0].to_lowercase() == "-units" || vec[0].to_lowercase() == "--units" {
+                if keyval {
+                    units = vec[1].to_string();
+                } else {
+                    units = args[i + 1].to_string();
+                }
             } else if vec[0].to_lowercase() == "-zfactor" || vec[0].to_lowercase() == "--zfactor" {
                 if keyval {
                     z_factor = vec[1].to_string().parse::<f64>().unwrap();
@@ -216,17 +245,11 @@ impl WhiteboxTool for Slope {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -238,14 +261,8 @@ impl WhiteboxTool for Slope {
 
         let eight_grid_res = input.configs.resolution_x * 8.0;
 
-        if input.is_in_geographic_coordinates() {
-            // calculate a new z-conversion factor
-            let mut mid_lat = (input.configs.north - input.configs.south) / 2.0;
-            if mid_lat <= 90.0 && mid_lat >= -90.0 {
-                mid_lat = mid_lat.to_radians();
-                z_factor = 1.0 / (113200.0 * mid_lat.cos());
-            }
-        }
+        let units = Units::parse(&units)?;
+        z_factor = units.get_z_factor(&input, z_factor);
 
         let mut output = Raster::initialize_using_file(&output_file, &input);
         if output.configs.data_type != DataType::F32 && output.configs.data_type != DataType::F64 {
@@ -282,7 +299,7 @@ impl WhiteboxTool for Slope {
                             // calculate slope
                             fy = (n[6] - n[4] + 2.0 * (n[7] - n[3]) + n[0] - n[2]) / eight_grid_res;
                             fx = (n[2] - n[4] + 2.0 * (n[1] - n[5]) + n[0] - n[6]) / eight_grid_res;
-                            data[col as usize] = (fx * fx + fy * fy).sqrt().atan().to_degrees();
+                            data[col as usize] = units.convert_slope((fx * fx + fy * fy).sqrt().atan());
                         }
                     }
                     tx1.send((row, data)).unwrap();
@@ -194,8 +194,6 @@ impl WhiteboxTool for SlopeVsElevationPlot {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
@@ -213,9 +211,7 @@ impl WhiteboxTool for SlopeVsElevationPlot {
                                 "There is something incorrect about the input DEM files. At least one input DEM is required to operate this tool."));
         }
 
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        output_file = resolve_path(&output_file, working_directory);
 
         let f = File::create(output_file.clone())?;
         let mut writer = BufWriter::new(f);
@@ -246,9 +242,7 @@ impl WhiteboxTool for SlopeVsElevationPlot {
         if watershed_files_str.is_empty() {
             for i in 0..num_files {
                 let mut input_file = input_files[i].to_string();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
                 let input = Arc::new(Raster::new(&input_file, "r")?);
 
                 if num_files == 1 {
@@ -391,9 +385,7 @@ impl WhiteboxTool for SlopeVsElevationPlot {
 
             for i in 0..num_files {
                 let mut input_file = input_files[i].to_string();
-                if !input_file.contains(&sep) && !input_file.contains("/") {
-                    input_file = format!("{}{}", working_directory, input_file);
-                }
+                input_file = resolve_path(&input_file, working_directory);
                 let input = Arc::new(Raster::new(&input_file, "r")?);
                 if num_files == 1 {
                     writer.write_all(
@@ -475,9 +467,7 @@ impl WhiteboxTool for SlopeVsElevationPlot {
                 }
 
                 let mut watershed_file = watershed_files[i].to_string();
-                if !watershed_file.contains(&sep) {
-                    watershed_file = format!("{}{}", working_directory, watershed_file);
-                }
+                watershed_file = resolve_path(&watershed_file, working_directory);
                 let watershed = Raster::new(&watershed_file, "r")?;
                 let ws_nodata = watershed.configs.nodata;
                 if watershed.configs.rows as isize != rows
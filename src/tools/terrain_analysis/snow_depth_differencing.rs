@@ -0,0 +1,817 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::path;
+use std::path::Path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool maps snow depth by differencing a snow-on digital elevation model (DEM; `--snow_on`)
+/// and a snow-off DEM (`--snow_off`), e.g. those derived from repeat airborne LiDAR surveys, and
+/// summarizes the snow depth and volume within elevation bands. The two DEMs must share the same
+/// grid; the snow-off DEM should be a bare-earth model, while the snow-on DEM should be a model of
+/// the snow surface that is interpolated from ground (i.e. snow surface) returns.
+///
+/// The vertical error of a LiDAR-derived DEM increases with slope steepness, because any
+/// horizontal positioning error results in a vertical error proportional to the local slope
+/// gradient. The uncertainty of each survey is therefore modelled as:
+///
+/// > &sigma;<sub>z</sub> = [&sigma;<sub>v</sub><sup>2</sup> + (&sigma;<sub>h</sub> tan *S*)<sup>2</sup>]<sup>0.5</sup>
+///
+/// where &sigma;<sub>v</sub> is the vertical error of the survey on flat terrain (`--vert_error`,
+/// default 0.05), &sigma;<sub>h</sub> is its horizontal error (`--horiz_error`, default 0.15), and
+/// *S* is the slope of the snow-off DEM. The uncertainty of the difference between two surveys of
+/// equal accuracy is &sigma;<sub>d</sub> = 2<sup>0.5</sup> &sigma;<sub>z</sub>. Elevation
+/// differences that are smaller than the level of detection, i.e. *t* &sigma;<sub>d</sub> where
+/// *t* is the confidence multiplier (`--confidence`, default 1.96 for 95% confidence), are not
+/// significant and are assigned a snow depth of zero (snow-free), as are negative differences. The
+/// number of significant negative differences, which typically indicate vegetation, structures,
+/// or misregistration of the surveys, is reported. By default, snow depths are measured
+/// vertically; when the `--slope_normal` flag is specified, they are instead measured
+/// perpendicular to the ground surface, i.e. multiplied by cos *S*.
+///
+/// Elevation differences beneath vegetation are unreliable, because snow is intercepted by the
+/// canopy and the penetration of the laser pulses differs between the surveys. The optional
+/// `--landcover` raster, e.g. a rasterized point classification or land cover map on the same
+/// grid, can be used to mask vegetated cells. Cells containing any of the vegetation class values
+/// (`--veg_classes`, default '3,4,5', i.e. the low, medium, and high vegetation classes of the
+/// ASPRS LAS classification) are assigned NoData in the outputs and excluded from the summary.
+///
+/// The output raster (`--output`) contains the snow depth of each grid cell and the optional
+/// `--uncertainty` raster contains the uncertainty (&sigma;<sub>d</sub>) of the depth. A summary
+/// of each elevation band of the snow-off DEM, of width `--band_interval` (default 100.0), is
+/// written to the CSV file `--out_table`, which defaults to the output file name with a
+/// '_bands.csv' suffix. The table contains the following fields:
+///
+/// | Field        | Description                                                       |
+/// |--------------|-------------------------------------------------------------------|
+/// | BAND_MIN     | The lower elevation of the band                                   |
+/// | BAND_MAX     | The upper elevation of the band                                   |
+/// | AREA         | The unmasked area of the band                                     |
+/// | MASKED_AREA  | The area of the band that is masked as vegetation                 |
+/// | SNOW_AREA    | The area with a significant (non-zero) snow depth                 |
+/// | MEAN_DEPTH   | The mean snow depth of the unmasked area                          |
+/// | MAX_DEPTH    | The maximum snow depth                                            |
+/// | VOLUME       | The snow volume                                                   |
+/// | VOLUME_UNC   | The uncertainty of the snow volume                                |
+///
+/// The volume uncertainty is the sum of the depth uncertainty multiplied by the cell area of the
+/// snow-covered cells, which assumes that the errors are fully spatially correlated and is
+/// therefore a conservative estimate. Areas and volumes are measured in map units or, for DEMs in
+/// geographic coordinates, in square metres and elevation units multiplied by square metres
+/// respectively. The *Z conversion factor* (`--zfactor`) and the `--units` parameter are only
+/// important when the vertical and horizontal units of the DEMs are not the same, as in the
+/// `Slope` tool.
+///
+/// # Reference
+/// Hodgson, M. E., & Bresnahan, P. (2004). Accuracy of airborne lidar-derived elevation:
+/// Empirical assessment and error budget. *Photogrammetric Engineering & Remote Sensing*, 70(3),
+/// 331-339.
+///
+/// # See Also
+/// `CutFill`, `SnowmeltIndex`, `Slope`, `Subtract`
+pub struct SnowDepthDifferencing {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl SnowDepthDifferencing {
+    pub fn new() -> SnowDepthDifferencing {
+        // public constructor
+        let name = "SnowDepthDifferencing".to_string();
+        let toolbox = "Geomorphometric Analysis".to_string();
+        let description = "Maps snow depth from snow-off and snow-on DEMs with slope-dependent uncertainty and reports it by elevation band.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Snow-Off DEM File".to_owned(),
+            flags: vec!["--snow_off".to_owned()],
+            description: "Input raster bare-earth DEM file of the snow-off survey.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Snow-On DEM File".to_owned(),
+            flags: vec!["--snow_on".to_owned()],
+            description: "Input raster DEM file of the snow surface.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output snow depth raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Uncertainty File (optional)".to_owned(),
+            flags: vec!["--uncertainty".to_owned()],
+            description: "Optional output raster file of the snow depth uncertainty.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Elevation Band Table File (optional)".to_owned(),
+            flags: vec!["--out_table".to_owned()],
+            description: "Output CSV table of snow depth and volume by elevation band; defaults to the output base name with a '_bands.csv' suffix.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Csv),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Land Cover File (optional)".to_owned(),
+            flags: vec!["--landcover".to_owned()],
+            description: "Optional input land cover or point classification raster file used to mask vegetation.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Vegetation Class Values".to_owned(),
+            flags: vec!["--veg_classes".to_owned()],
+            description: "Comma-separated list of the land cover values that are masked as vegetation.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: Some("3,4,5".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Vertical Error".to_owned(),
+            flags: vec!["--vert_error".to_owned()],
+            description: "Vertical error (RMSE) of each survey on flat terrain, in elevation units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.05".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Horizontal Error".to_owned(),
+            flags: vec!["--horiz_error".to_owned()],
+            description: "Horizontal error (RMSE) of each survey, in the same units as the vertical error.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.15".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Confidence Multiplier".to_owned(),
+            flags: vec!["--confidence".to_owned()],
+            description: "Multiplier of the depth uncertainty that gives the level of detection, e.g. 1.96 for 95% confidence.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.96".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Elevation Band Interval".to_owned(),
+            flags: vec!["--band_interval".to_owned()],
+            description: "Width of the elevation bands of the summary table.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("100.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Measure Depths Perpendicular To The Slope?".to_owned(),
+            flags: vec!["--slope_normal".to_owned()],
+            description: "Measure snow depths perpendicular to the ground surface rather than vertically.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Z Conversion Factor".to_owned(),
+            flags: vec!["--zfactor".to_owned()],
+            description:
+                "Optional multiplier for when the vertical and horizontal units are not the same."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Units".to_owned(),
+            flags: vec!["--units".to_owned()],
+            description: "Optional units keywords, e.g. 'z_ft', 'xy_m'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --snow_off=bare_earth.tif --snow_on=snow_surface.tif -o=snow_depth.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --snow_off=bare_earth.tif --snow_on=snow_surface.tif -o=snow_depth.tif --uncertainty=depth_unc.tif --out_table=bands.csv --landcover=classes.tif --veg_classes='3,4,5' --vert_error=0.08 --horiz_error=0.2 --band_interval=50.0 --slope_normal", short_exe, name).replace("*", &sep);
+
+        SnowDepthDifferencing {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for SnowDepthDifferencing {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--vert_error".to_string(), ParameterConstraint::Min(0f64)),
+            ("--horiz_error".to_string(), ParameterConstraint::Min(0f64)),
+            ("--confidence".to_string(), ParameterConstraint::Min(0f64)),
+            (
+                "--band_interval".to_string(),
+                ParameterConstraint::GreaterThan(0f64),
+            ),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut snow_off_file = String::new();
+        let mut snow_on_file = String::new();
+        let mut output_file = String::new();
+        let mut uncertainty_file = String::new();
+        let mut table_file = String::new();
+        let mut landcover_file = String::new();
+        let mut veg_classes_str = "3,4,5".to_string();
+        let mut vert_error = 0.05f64;
+        let mut horiz_error = 0.15f64;
+        let mut confidence = 1.96f64;
+        let mut band_interval = 100f64;
+        let mut slope_normal = false;
+        let mut z_factor = 1f64;
+        let mut units = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-snow_off" {
+                snow_off_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-snow_on" {
+                snow_on_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-uncertainty" {
+                uncertainty_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_table" {
+                table_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-landcover" {
+                landcover_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-veg_classes" {
+                veg_classes_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-vert_error" {
+                vert_error = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-horiz_error" {
+                horiz_error = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-confidence" {
+                confidence = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-band_interval" {
+                band_interval = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-slope_normal" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    slope_normal = true;
+                }
+            } else if flag_val == "-zfactor" {
+                z_factor = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-units" {
+                units = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if band_interval.is_nan() || band_interval <= 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The elevation band interval must be greater than zero.",
+            ));
+        }
+        let vert_error = vert_error.max(0f64);
+        let horiz_error = horiz_error.max(0f64);
+        let confidence = confidence.max(0f64);
+
+        let mut veg_classes = vec![];
+        for value in veg_classes_str.split([',', ';']) {
+            if !value.trim().is_empty() {
+                match value.trim().parse::<f64>() {
+                    Ok(v) => veg_classes.push(v),
+                    Err(_) => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("Unrecognized vegetation class value '{}'.", value.trim()),
+                        ))
+                    }
+                }
+            }
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        snow_off_file = resolve_path(&snow_off_file, working_directory);
+        snow_on_file = resolve_path(&snow_on_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+        uncertainty_file = resolve_path(&uncertainty_file, working_directory);
+        landcover_file = resolve_path(&landcover_file, working_directory);
+        if table_file.is_empty() {
+            table_file = match Path::new(&output_file).extension() {
+                Some(ext) => output_file.replace(
+                    &format!(".{}", ext.to_str().unwrap()),
+                    "_bands.csv",
+                ),
+                None => format!("{}_bands.csv", output_file),
+            };
+        }
+        table_file = resolve_path(&table_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let snow_off = Arc::new(Raster::new(&snow_off_file, "r")?);
+        let snow_on = Arc::new(Raster::new(&snow_on_file, "r")?);
+        let landcover = if !landcover_file.is_empty() {
+            Some(Arc::new(Raster::new(&landcover_file, "r")?))
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+
+        let rows = snow_off.configs.rows as isize;
+        let columns = snow_off.configs.columns as isize;
+        if snow_on.configs.rows as isize != rows
+            || snow_on.configs.columns as isize != columns
+            || landcover.as_ref().is_some_and(|l| {
+                l.configs.rows as isize != rows || l.configs.columns as isize != columns
+            })
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must have the same number of rows and columns and spatial extent.",
+            ));
+        }
+
+        let units = Units::parse(&units)?;
+        let z_factor = units.get_z_factor(&snow_off, z_factor);
+
+        let mut configs = snow_off.configs.clone();
+        configs.data_type = DataType::F32;
+        configs.nodata = -32768f64;
+        configs.photometric_interp = PhotometricInterpretation::Continuous;
+        let out_nodata = configs.nodata;
+
+        // each row is returned as the snow depths, depth uncertainties, and a flag of whether each
+        // cell is masked as vegetation or has a significant negative difference
+        let veg_classes = Arc::new(veg_classes);
+        let num_procs = num_cpus::get() as isize;
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let snow_off = snow_off.clone();
+            let snow_on = snow_on.clone();
+            let landcover = landcover.clone();
+            let veg_classes = veg_classes.clone();
+            let tx1 = tx.clone();
+            thread::spawn(move || {
+                let nodata_off = snow_off.configs.nodata;
+                let nodata_on = snow_on.configs.nodata;
+                let eight_res_x = snow_off.configs.resolution_x * 8f64;
+                let eight_res_y = snow_off.configs.resolution_y * 8f64;
+                let d_x = [1, 1, 1, 0, -1, -1, -1, 0];
+                let d_y = [-1, 0, 1, 1, 1, 0, -1, -1];
+                let mut n: [f64; 8] = [0.0; 8];
+                let (mut fx, mut fy): (f64, f64);
+                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                    let mut depths = vec![out_nodata; columns as usize];
+                    let mut sigmas = vec![out_nodata; columns as usize];
+                    let mut flags = vec![CellFlag::None; columns as usize];
+                    for col in 0..columns {
+                        let z_off = snow_off.get_value(row, col);
+                        let z_on = snow_on.get_value(row, col);
+                        if z_off == nodata_off || z_on == nodata_on {
+                            continue;
+                        }
+                        if let Some(l) = &landcover {
+                            let class = l.get_value(row, col);
+                            if class != l.configs.nodata
+                                && veg_classes.iter().any(|v| (class - v).abs() < 1e-6)
+                            {
+                                flags[col as usize] = CellFlag::Vegetation;
+                                continue;
+                            }
+                        }
+                        // slope of the snow-off surface
+                        let z = z_off * z_factor;
+                        for c in 0..8 {
+                            n[c] = snow_off.get_value(row + d_y[c], col + d_x[c]);
+                            if n[c] != nodata_off {
+                                n[c] *= z_factor;
+                            } else {
+                                n[c] = z;
+                            }
+                        }
+                        fx = (n[2] - n[4] + 2f64 * (n[1] - n[5]) + n[0] - n[6]) / eight_res_x;
+                        fy = (n[6] - n[4] + 2f64 * (n[7] - n[3]) + n[0] - n[2]) / eight_res_y;
+                        let tan_slope = (fx * fx + fy * fy).sqrt();
+                        let cos_slope = 1f64 / (1f64 + tan_slope * tan_slope).sqrt();
+
+                        let sigma_survey = (vert_error * vert_error
+                            + (horiz_error * tan_slope) * (horiz_error * tan_slope))
+                            .sqrt();
+                        let mut sigma = f64::consts::SQRT_2 * sigma_survey;
+                        let diff = z_on - z_off;
+                        let mut depth = if diff > confidence * sigma { diff } else { 0f64 };
+                        if diff < -confidence * sigma {
+                            flags[col as usize] = CellFlag::Negative;
+                        }
+                        if slope_normal {
+                            depth *= cos_slope;
+                            sigma *= cos_slope;
+                        }
+                        depths[col as usize] = depth;
+                        sigmas[col as usize] = sigma;
+                    }
+                    tx1.send((row, depths, sigmas, flags)).unwrap();
+                }
+            });
+        }
+
+        let mut output = Raster::initialize_using_config(&output_file, &configs);
+        output.configs.palette = "blue_white_red.plt".to_string();
+        let mut uncertainty = if !uncertainty_file.is_empty() {
+            Some(Raster::initialize_using_config(&uncertainty_file, &configs))
+        } else {
+            None
+        };
+
+        let is_geographic = snow_off.is_in_geographic_coordinates();
+        let cell_area = |row: isize| -> f64 {
+            if is_geographic {
+                let lat = snow_off.get_y_from_row(row).to_radians();
+                snow_off.configs.resolution_x
+                    * snow_off.configs.resolution_y
+                    * 111319.49f64
+                    * 111319.49f64
+                    * lat.cos()
+            } else {
+                snow_off.configs.resolution_x * snow_off.configs.resolution_y
+            }
+        };
+
+        let base_elev = (snow_off.configs.minimum / band_interval).floor() * band_interval;
+        let num_bands = (((snow_off.configs.maximum - base_elev) / band_interval).floor() as usize + 1).max(1);
+        let mut bands = vec![SnowBand::new(); num_bands];
+        let mut total = SnowBand::new();
+        let mut num_negative = 0usize;
+        for r in 0..rows {
+            let (row, depths, sigmas, flags) = rx.recv().expect("Error receiving data from thread.");
+            let area = cell_area(row);
+            for col in 0..columns as usize {
+                let z_off = snow_off.get_value(row, col as isize);
+                if z_off == snow_off.configs.nodata {
+                    continue;
+                }
+                let band = (((z_off - base_elev) / band_interval).floor().max(0f64) as usize)
+                    .min(num_bands - 1);
+                if flags[col] == CellFlag::Vegetation {
+                    bands[band].masked_area += area;
+                    total.masked_area += area;
+                } else if depths[col] != out_nodata {
+                    bands[band].add_cell(depths[col], sigmas[col], area);
+                    total.add_cell(depths[col], sigmas[col], area);
+                    if flags[col] == CellFlag::Negative {
+                        num_negative += 1;
+                    }
+                }
+            }
+            output.set_row_data(row, depths);
+            if let Some(u) = uncertainty.as_mut() {
+                u.set_row_data(row, sigmas);
+            }
+
+            if verbose {
+                progress = (100.0_f64 * r as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Calculating snow depths: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        let f = File::create(&table_file)?;
+        let mut writer = BufWriter::new(f);
+        writer.write_all(
+            "BAND_MIN,BAND_MAX,AREA,MASKED_AREA,SNOW_AREA,MEAN_DEPTH,MAX_DEPTH,VOLUME,VOLUME_UNC\n"
+                .as_bytes(),
+        )?;
+        if verbose {
+            println!(
+                "{:>10} {:>10} {:>14} {:>14} {:>10} {:>14} {:>14}",
+                "BAND_MIN", "BAND_MAX", "AREA", "SNOW_AREA", "MEAN_DEPTH", "VOLUME", "VOLUME_UNC"
+            );
+        }
+        for (b, band) in bands.iter().enumerate() {
+            if band.area == 0f64 && band.masked_area == 0f64 {
+                continue;
+            }
+            let band_min = base_elev + b as f64 * band_interval;
+            let band_max = band_min + band_interval;
+            writer.write_all(
+                format!(
+                    "{},{},{},{},{},{},{},{},{}\n",
+                    band_min,
+                    band_max,
+                    band.area,
+                    band.masked_area,
+                    band.snow_area,
+                    band.mean_depth(),
+                    band.max_depth,
+                    band.volume,
+                    band.volume_uncertainty
+                )
+                .as_bytes(),
+            )?;
+            if verbose {
+                println!(
+                    "{:>10.2} {:>10.2} {:>14.3} {:>14.3} {:>10.3} {:>14.3} {:>14.3}",
+                    band_min,
+                    band_max,
+                    band.area,
+                    band.snow_area,
+                    band.mean_depth(),
+                    band.volume,
+                    band.volume_uncertainty
+                );
+            }
+        }
+        let _ = writer.flush();
+
+        if verbose {
+            println!(
+                "Snow-covered area: {:.3} of {:.3} ({:.3} masked as vegetation)",
+                total.snow_area, total.area, total.masked_area
+            );
+            println!("Mean snow depth: {:.3}", total.mean_depth());
+            println!(
+                "Snow volume: {:.3} +/- {:.3}",
+                total.volume, total.volume_uncertainty
+            );
+        }
+        if num_negative > 0 {
+            println!(
+                "Warning: {} cells have significant negative elevation differences, which may indicate vegetation, structures, or misregistered surveys.",
+                num_negative
+            );
+        }
+
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Snow-off DEM file: {}", snow_off_file));
+        output.add_metadata_entry(format!("Snow-on DEM file: {}", snow_on_file));
+        if !landcover_file.is_empty() {
+            output.add_metadata_entry(format!("Land cover file: {}", landcover_file));
+            output.add_metadata_entry(format!("Vegetation classes: {}", veg_classes_str));
+        }
+        output.add_metadata_entry(format!("Vertical error: {}", vert_error));
+        output.add_metadata_entry(format!("Horizontal error: {}", horiz_error));
+        output.add_metadata_entry(format!("Confidence multiplier: {}", confidence));
+        output.add_metadata_entry(format!(
+            "Depth measured: {}",
+            if slope_normal { "perpendicular to slope" } else { "vertically" }
+        ));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let Some(mut u) = uncertainty {
+            u.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            u.add_metadata_entry(format!("Snow depth file: {}", output_file));
+            u.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+            let _ = match u.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Uncertainty file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CellFlag {
+    None,
+    Vegetation,
+    Negative,
+}
+
+/// The snow-covered area, depth, and volume within an elevation band.
+#[derive(Clone)]
+struct SnowBand {
+    area: f64,
+    masked_area: f64,
+    snow_area: f64,
+    max_depth: f64,
+    volume: f64,
+    volume_uncertainty: f64,
+}
+
+impl SnowBand {
+    fn new() -> SnowBand {
+        SnowBand {
+            area: 0f64,
+            masked_area: 0f64,
+            snow_area: 0f64,
+            max_depth: 0f64,
+            volume: 0f64,
+            volume_uncertainty: 0f64,
+        }
+    }
+
+    fn add_cell(&mut self, depth: f64, sigma: f64, cell_area: f64) {
+        self.area += cell_area;
+        if depth > 0f64 {
+            self.snow_area += cell_area;
+            self.volume += depth * cell_area;
+            self.volume_uncertainty += sigma * cell_area;
+            if depth > self.max_depth {
+                self.max_depth = depth;
+            }
+        }
+    }
+
+    fn mean_depth(&self) -> f64 {
+        if self.area > 0f64 {
+            self.volume / self.area
+        } else {
+            0f64
+        }
+    }
+}
@@ -0,0 +1,691 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool calculates a snowmelt susceptibility index from the shortwave solar radiation that is
+/// received at the snow surface over a range of days. The index accounts for the exposure of the
+/// terrain to the sun, i.e. the local slope gradient and aspect, which are derived from the input
+/// digital elevation model (DEM; `--dem`), and for the shading of the snowpack by the forest canopy
+/// (`--canopy`). It is intended to be used to distribute melt rates, e.g. within a
+/// radiation-adjusted degree-day snowmelt model, across a catchment.
+///
+/// Clear-sky solar radiation is modelled at each time step (`--time_step`, in minutes) of each day
+/// within the specified date range (`--start_day` and `--end_day`, as days of the year, 1-366). The
+/// date range may span the end of the year, e.g. a start day of 335 and an end day of 59, in which
+/// case day 366 is only included if it is the start day. The solar position is calculated from the
+/// day of the year and the latitude. For DEMs in geographic coordinates, the latitude of each grid
+/// cell is used; otherwise, the latitude of the site must be specified (`--latitude`). The direct
+/// beam radiation is attenuated by the atmosphere using the bulk atmospheric transmissivity
+/// (`--transmissivity`, default 0.7) and the relative optical air mass of Kasten and Young (1989),
+/// while the diffuse radiation is modelled as 30% of the radiation that is attenuated by the
+/// atmosphere. The direct beam radiation received by a grid cell depends on the angle of incidence
+/// between the sun and the local surface, and the diffuse radiation is reduced by the proportion of
+/// the sky hemisphere that is obscured by the local slope. Shading by the surrounding terrain is
+/// not modelled.
+///
+/// The optional canopy cover raster contains the fractional canopy cover of each grid cell, either
+/// as proportions (0-1) or percentages (0-100). The radiation that reaches the snow beneath the
+/// canopy is calculated as:
+///
+/// > *R*<sub>snow</sub> = *R* x [1 - *C* x (1 - *T*<sub>c</sub>)]
+///
+/// where *R* is the radiation received by the terrain surface, *C* is the canopy cover, and
+/// *T*<sub>c</sub> is the canopy transmissivity (`--canopy_transmissivity`, default 0.1), i.e. the
+/// proportion of radiation that penetrates the canopy elements themselves.
+///
+/// By default, the index is expressed relative to the radiation received by an unobstructed,
+/// horizontal surface at the same latitude over the same period, such that a value of 1.0
+/// indicates that a site receives as much energy as an open flat site, values greater than 1.0
+/// occur on sun-facing slopes, and values less than 1.0 occur on shaded slopes and beneath the
+/// canopy. When the `--absolute` flag is specified, the index is instead the total radiation
+/// received at the snow surface in MJ m<sup>-2</sup>. The index is aggregated over the full date
+/// range unless the `--daily` flag is specified, in which case one raster is output for each day,
+/// with the day of the year appended to the output file name, e.g. *melt_index_075.tif*.
+///
+/// The *Z conversion factor* (`--zfactor`) and the `--units` parameter are only important when
+/// the vertical and horizontal units of the DEM are not the same, as in the `Slope` tool.
+///
+/// # Reference
+/// Kasten, F., & Young, A. T. (1989). Revised optical air mass tables and approximation formula.
+/// *Applied Optics*, 28(22), 4735-4738.
+///
+/// # See Also
+/// `Slope`, `Aspect`, `Hillshade`, `HorizonAngle`
+pub struct SnowmeltIndex {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl SnowmeltIndex {
+    pub fn new() -> SnowmeltIndex {
+        // public constructor
+        let name = "SnowmeltIndex".to_string();
+        let toolbox = "Geomorphometric Analysis".to_string();
+        let description = "Calculates a snowmelt susceptibility index from the solar radiation received by the terrain and transmitted through the canopy.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["-i".to_owned(), "--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Canopy Cover File (optional)".to_owned(),
+            flags: vec!["--canopy".to_owned()],
+            description: "Optional input raster of fractional canopy cover, as proportions or percentages.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Start Day of Year".to_owned(),
+            flags: vec!["--start_day".to_owned()],
+            description: "First day of the date range, as a day of the year (1-366).".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "End Day of Year".to_owned(),
+            flags: vec!["--end_day".to_owned()],
+            description: "Last day of the date range, as a day of the year (1-366).".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Latitude (optional)".to_owned(),
+            flags: vec!["--latitude".to_owned()],
+            description: "Latitude of the site, in degrees; required for DEMs in projected coordinates.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Time Step (minutes)".to_owned(),
+            flags: vec!["--time_step".to_owned()],
+            description: "Time step of the solar radiation model, in minutes.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("30.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Atmospheric Transmissivity".to_owned(),
+            flags: vec!["--transmissivity".to_owned()],
+            description: "Bulk atmospheric transmissivity for clear-sky conditions (0-1).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.7".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Canopy Transmissivity".to_owned(),
+            flags: vec!["--canopy_transmissivity".to_owned()],
+            description: "Proportion of radiation transmitted by the canopy elements (0-1).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.1".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output daily indices?".to_owned(),
+            flags: vec!["--daily".to_owned()],
+            description: "Output one index raster for each day rather than an aggregated index.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output absolute radiation?".to_owned(),
+            flags: vec!["--absolute".to_owned()],
+            description: "Output the received radiation (MJ m-2) rather than a relative index.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Z Conversion Factor".to_owned(),
+            flags: vec!["--zfactor".to_owned()],
+            description:
+                "Optional multiplier for when the vertical and horizontal units are not the same."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Units".to_owned(),
+            flags: vec!["--units".to_owned()],
+            description: "Optional units keywords, e.g. 'z_ft', 'xy_m'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif --canopy=canopy.tif -o=melt_index.tif --start_day=60 --end_day=151 --latitude=50.5
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=melt_index.tif --start_day=335 --end_day=59 --latitude=50.5 --daily --absolute", short_exe, name).replace("*", &sep);
+
+        SnowmeltIndex {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for SnowmeltIndex {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--start_day".to_string(), ParameterConstraint::Range(1.0, 366.0)),
+            ("--end_day".to_string(), ParameterConstraint::Range(1.0, 366.0)),
+            ("--latitude".to_string(), ParameterConstraint::Range(-90.0, 90.0)),
+            ("--time_step".to_string(), ParameterConstraint::GreaterThan(0.0)),
+            ("--transmissivity".to_string(), ParameterConstraint::Range(0.0, 1.0)),
+            ("--canopy_transmissivity".to_string(), ParameterConstraint::Range(0.0, 1.0)),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut canopy_file = String::new();
+        let mut output_file = String::new();
+        let mut start_day = 0usize;
+        let mut end_day = 0usize;
+        let mut latitude = f64::NAN;
+        let mut time_step = 30f64;
+        let mut transmissivity = 0.7f64;
+        let mut canopy_transmissivity = 0.1f64;
+        let mut daily = false;
+        let mut absolute = false;
+        let mut z_factor = 1f64;
+        let mut units = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-dem" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-canopy" {
+                canopy_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-start_day" {
+                start_day = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-end_day" {
+                end_day = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap() as usize
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap() as usize
+                };
+            } else if flag_val == "-latitude" || flag_val == "-lat" {
+                latitude = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-time_step" {
+                time_step = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-transmissivity" {
+                transmissivity = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-canopy_transmissivity" {
+                canopy_transmissivity = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-daily" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    daily = true;
+                }
+            } else if flag_val == "-absolute" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    absolute = true;
+                }
+            } else if flag_val == "-zfactor" {
+                z_factor = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-units" {
+                units = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        if !(1..=366).contains(&start_day) || !(1..=366).contains(&end_day) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The start and end days must be days of the year (1-366).",
+            ));
+        }
+        if time_step.is_nan() || time_step <= 0f64 || time_step > 1440f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The time step must be between 0 and 1440 minutes.",
+            ));
+        }
+        if transmissivity.is_nan() || transmissivity <= 0f64 || transmissivity > 1f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The atmospheric transmissivity must be between 0 and 1.",
+            ));
+        }
+        let canopy_transmissivity = canopy_transmissivity.clamp(0f64, 1f64);
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(&input_file, working_directory);
+        canopy_file = resolve_path(&canopy_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let input = Arc::new(Raster::new(&input_file, "r")?);
+        let canopy = if !canopy_file.is_empty() {
+            let c = Raster::new(&canopy_file, "r")?;
+            if c.configs.rows != input.configs.rows || c.configs.columns != input.configs.columns {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+            Some(Arc::new(c))
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+
+        let is_geographic = input.is_in_geographic_coordinates();
+        if !is_geographic && latitude.is_nan() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The site latitude (--latitude) must be specified for DEMs in projected coordinates.",
+            ));
+        }
+        if !latitude.is_nan() && latitude.abs() > 90f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The latitude must be between -90 and 90 degrees.",
+            ));
+        }
+
+        let units = Units::parse(&units)?;
+        let z_factor = units.get_z_factor(&input, z_factor);
+
+        // canopy cover may be expressed as either proportions or percentages
+        let canopy_scale = match &canopy {
+            Some(c) if c.configs.maximum > 1f64 => 0.01f64,
+            _ => 1f64,
+        };
+
+        let days: Vec<usize> = if end_day >= start_day {
+            (start_day..=end_day).collect()
+        } else {
+            (start_day..=start_day.max(365)).chain(1..=end_day).collect()
+        };
+        let periods: Vec<Vec<usize>> = if daily {
+            days.iter().map(|d| vec![*d]).collect()
+        } else {
+            vec![days.clone()]
+        };
+
+        let rows = input.configs.rows as isize;
+        let mut configs = input.configs.clone();
+        configs.data_type = DataType::F32;
+        configs.nodata = -32768f64;
+        configs.photometric_interp = PhotometricInterpretation::Continuous;
+        let out_nodata = configs.nodata;
+        let num_procs = num_cpus::get() as isize;
+        let num_periods = periods.len();
+        for (p, period) in periods.into_iter().enumerate() {
+            let period = Arc::new(period);
+            // the solar positions are shared by all rows for DEMs in projected coordinates
+            let shared_suns = Arc::new(if is_geographic {
+                vec![]
+            } else {
+                solar_positions(latitude, &period, time_step, transmissivity)
+            });
+            let (tx, rx) = mpsc::channel();
+            for tid in 0..num_procs {
+                let input = input.clone();
+                let canopy = canopy.clone();
+                let period = period.clone();
+                let shared_suns = shared_suns.clone();
+                let tx1 = tx.clone();
+                thread::spawn(move || {
+                    let nodata = input.configs.nodata;
+                    let columns = input.configs.columns as isize;
+                    let eight_res_x = input.configs.resolution_x * 8f64;
+                    let eight_res_y = input.configs.resolution_y * 8f64;
+                    let d_x = [1, 1, 1, 0, -1, -1, -1, 0];
+                    let d_y = [-1, 0, 1, 1, 1, 0, -1, -1];
+                    let mut n: [f64; 8] = [0.0; 8];
+                    let mut z: f64;
+                    let (mut fx, mut fy): (f64, f64);
+                    let mut row_suns: Vec<SunPosition>;
+                    for row in (0..rows).filter(|r| r % num_procs == tid) {
+                        let suns: &[SunPosition] = if is_geographic {
+                            row_suns = solar_positions(
+                                input.get_y_from_row(row),
+                                &period,
+                                time_step,
+                                transmissivity,
+                            );
+                            &row_suns
+                        } else {
+                            &shared_suns
+                        };
+                        let flat_total: f64 = suns
+                            .iter()
+                            .map(|s| s.beam * s.up + s.diffuse)
+                            .sum();
+                        let mut data = vec![out_nodata; columns as usize];
+                        for col in 0..columns {
+                            z = input.get_value(row, col);
+                            if z == nodata {
+                                continue;
+                            }
+                            let mut cover = 0f64;
+                            if let Some(c) = &canopy {
+                                let v = c.get_value(row, col);
+                                if v == c.configs.nodata {
+                                    continue;
+                                }
+                                cover = (v * canopy_scale).clamp(0f64, 1f64);
+                            }
+                            z *= z_factor;
+                            for c in 0..8 {
+                                n[c] = input.get_value(row + d_y[c], col + d_x[c]);
+                                if n[c] != nodata {
+                                    n[c] *= z_factor;
+                                } else {
+                                    n[c] = z;
+                                }
+                            }
+                            // the surface normal is (-fx, -fy, 1), where fx and fy are the
+                            // elevation gradients towards the east and north
+                            fx = (n[2] - n[4] + 2f64 * (n[1] - n[5]) + n[0] - n[6]) / eight_res_x;
+                            fy = (n[6] - n[4] + 2f64 * (n[7] - n[3]) + n[0] - n[2]) / eight_res_y;
+                            let norm = (1f64 + fx * fx + fy * fy).sqrt();
+                            let sky_view = (1f64 + 1f64 / norm) / 2f64;
+                            let mut total = 0f64;
+                            for s in suns {
+                                let cos_incidence = (s.up - fx * s.east - fy * s.north) / norm;
+                                if cos_incidence > 0f64 {
+                                    total += s.beam * cos_incidence;
+                                }
+                                total += s.diffuse * sky_view;
+                            }
+                            total *= 1f64 - cover * (1f64 - canopy_transmissivity);
+                            data[col as usize] = if absolute {
+                                total
+                            } else if flat_total > 0f64 {
+                                total / flat_total
+                            } else {
+                                // polar night
+                                0f64
+                            };
+                        }
+                        tx1.send((row, data)).unwrap();
+                    }
+                });
+            }
+
+            let file_name = if daily {
+                let suffix = format!("{:03}", period[0]);
+                match output_file.rfind('.') {
+                    Some(pos) => format!("{}_{}{}", &output_file[..pos], suffix, &output_file[pos..]),
+                    None => format!("{}_{}", output_file, suffix),
+                }
+            } else {
+                output_file.clone()
+            };
+            let mut output = Raster::initialize_using_config(&file_name, &configs);
+            for row in 0..rows {
+                let data = rx.recv().unwrap();
+                output.set_row_data(data.0, data.1);
+
+                if verbose {
+                    progress = (100.0_f64 * (p as f64 + row as f64 / (rows - 1).max(1) as f64)
+                        / num_periods as f64) as usize;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+
+            let elapsed_time = get_formatted_elapsed_time(start);
+            output.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            output.add_metadata_entry(format!("Input DEM file: {}", input_file));
+            if !canopy_file.is_empty() {
+                output.add_metadata_entry(format!("Input canopy cover file: {}", canopy_file));
+            }
+            if daily {
+                output.add_metadata_entry(format!("Day of year: {}", period[0]));
+            } else {
+                output.add_metadata_entry(format!("Start day: {}", start_day));
+                output.add_metadata_entry(format!("End day: {}", end_day));
+            }
+            output.add_metadata_entry(format!("Atmospheric transmissivity: {}", transmissivity));
+            output.add_metadata_entry(format!("Canopy transmissivity: {}", canopy_transmissivity));
+            output.add_metadata_entry(format!(
+                "Index units: {}",
+                if absolute { "MJ m-2" } else { "relative to an open, flat site" }
+            ));
+            output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+            if verbose && p == num_periods - 1 {
+                println!("Saving data...")
+            };
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose && p == num_periods - 1 {
+                        println!("Output file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if verbose {
+            let elapsed_time = get_formatted_elapsed_time(start);
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The direction of the sun, as a unit vector in east, north, and up components, and the
+/// radiation, in MJ m-2, that is received from it over one time step.
+struct SunPosition {
+    east: f64,
+    north: f64,
+    up: f64,
+    /// direct beam radiation on a surface normal to the sun
+    beam: f64,
+    /// diffuse radiation on a horizontal surface
+    diffuse: f64,
+}
+
+/// Calculates the clear-sky solar positions for each time step of each of the days, at a
+/// latitude (in degrees), for which the sun is above the horizon.
+fn solar_positions(latitude: f64, days: &[usize], time_step: f64, transmissivity: f64) -> Vec<SunPosition> {
+    let solar_constant = 1367f64; // W m-2
+    let num_steps = (1440f64 / time_step).ceil() as usize;
+    let step_radians = 2f64 * PI / num_steps as f64;
+    let step_seconds = 86400f64 / num_steps as f64;
+    let phi = latitude.to_radians();
+    let mut suns = vec![];
+    for day in days {
+        let day_angle = 2f64 * PI * *day as f64 / 365f64;
+        let extraterrestrial = solar_constant * (1f64 + 0.033 * day_angle.cos());
+        let declination = 23.45f64.to_radians() * (2f64 * PI * (284f64 + *day as f64) / 365f64).sin();
+        for k in 0..num_steps {
+            // hour angle at the middle of the time step
+            let omega = -PI + (k as f64 + 0.5) * step_radians;
+            let east = -declination.cos() * omega.sin();
+            let north = declination.sin() * phi.cos() - declination.cos() * phi.sin() * omega.cos();
+            let up = declination.sin() * phi.sin() + declination.cos() * phi.cos() * omega.cos();
+            if up <= 0f64 {
+                continue;
+            }
+            // relative optical air mass (Kasten and Young, 1989)
+            let zenith = up.acos().to_degrees();
+            let air_mass = 1f64 / (up + 0.50572 * (96.07995 - zenith).powf(-1.6364));
+            let attenuation = transmissivity.powf(air_mass);
+            let energy = extraterrestrial * step_seconds / 1_000_000f64;
+            suns.push(SunPosition {
+                east: east,
+                north: north,
+                up: up,
+                beam: energy * attenuation,
+                diffuse: energy * 0.3 * (1f64 - attenuation) * up,
+            });
+        }
+    }
+    suns
+}
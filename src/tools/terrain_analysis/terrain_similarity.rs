@@ -0,0 +1,510 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::raster::*;
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// This tool measures the similarity of each grid cell to a prototype terrain condition, based on
+/// multiple terrain attributes, using fuzzy membership functions. It is intended for digital soil
+/// mapping and landform classification workflows, in which the locations that resemble a known
+/// prototype site (e.g. a soil pit or a typical landform element) are mapped. The user must specify
+/// a set of co-registered terrain attribute rasters (`--inputs`), such as slope (`Slope`), profile
+/// curvature (`ProfileCurvature`), and topographic position index (`DevFromMeanElev`). All of the
+/// inputs must share the same number of rows and columns and spatial extent.
+///
+/// The prototype may be specified either as a location (`--x` and `--y`), in which case the
+/// prototype attribute values are read from the inputs at that location, or as an attribute
+/// signature (`--signature`), i.e. a comma-separated list of attribute values with one value per
+/// input raster. The membership of a cell in the prototype, for each attribute *i*, is calculated
+/// using a Gaussian membership function:
+///
+/// > *m<sub>i</sub>* = exp(-0.5 ((*z<sub>i</sub>* - *p<sub>i</sub>*) / *w<sub>i</sub>*)<sup>2</sup>)
+///
+/// where *z<sub>i</sub>* is the cell's attribute value, *p<sub>i</sub>* is the prototype value, and
+/// *w<sub>i</sub>* is the width of the membership function, i.e. the attribute difference at which
+/// membership falls to about 0.61. The widths can be specified as a comma-separated list
+/// (`--widths`); by default, each width is the standard deviation of the corresponding attribute.
+/// The overall similarity is calculated from the attribute memberships using either the fuzzy
+/// intersection, i.e. the minimum membership (`--method=min`, the default), which follows the
+/// limiting-factor principle, or the average membership (`--method=mean`). The output similarity
+/// values range from 0 (dissimilar) to 1 (identical to the prototype). Cells with NoData in any
+/// of the inputs are assigned NoData in the output.
+///
+/// # Reference
+/// Zhu, A. X., Band, L. E., Dutton, B., & Nimlos, T. J. (1996). Automated soil inference under fuzzy
+/// logic. *Ecological Modelling*, 90(2), 123-145.
+///
+/// # See Also
+/// `Slope`, `ProfileCurvature`, `DevFromMeanElev`, `PennockLandformClass`
+pub struct TerrainSimilarity {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl TerrainSimilarity {
+    pub fn new() -> TerrainSimilarity {
+        // public constructor
+        let name = "TerrainSimilarity".to_string();
+        let toolbox = "Geomorphometric Analysis".to_string();
+        let description = "Calculates the fuzzy similarity of each grid cell to a prototype location or attribute signature, based on multiple terrain attributes.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Terrain Attribute Files".to_owned(),
+            flags: vec!["-i".to_owned(), "--inputs".to_owned()],
+            description: "Input terrain attribute raster files (e.g. slope, curvature, TPI).".to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output similarity raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Prototype X Coordinate (optional)".to_owned(),
+            flags: vec!["--x".to_owned()],
+            description: "X coordinate of the prototype location.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Prototype Y Coordinate (optional)".to_owned(),
+            flags: vec!["--y".to_owned()],
+            description: "Y coordinate of the prototype location.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Prototype Attribute Signature (optional)".to_owned(),
+            flags: vec!["--signature".to_owned()],
+            description: "Comma-separated prototype attribute values, one per input, used in place of a prototype location.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Membership Function Widths (optional)".to_owned(),
+            flags: vec!["--widths".to_owned()],
+            description: "Comma-separated membership function widths, one per input; the default is each attribute's standard deviation.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Combination Method".to_owned(),
+            flags: vec!["--method".to_owned()],
+            description: "Method of combining the attribute memberships; options are 'min' (default) and 'mean'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec!["min".to_owned(), "mean".to_owned()]),
+            default_value: Some("min".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let p = format!("{}", env::current_dir().unwrap().display());
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='slope.tif;prof_curv.tif;tpi.tif' -o=similarity.tif --x=545230.5 --y=4821043.0
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='slope.tif;prof_curv.tif;tpi.tif' -o=similarity.tif --signature='4.5,-0.002,1.2' --widths='2.0,0.001,0.5' --method=mean", short_exe, name).replace("*", &sep);
+
+        TerrainSimilarity {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for TerrainSimilarity {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn get_parameter_constraints(&self) -> Vec<(String, ParameterConstraint)> {
+        vec![
+            ("--signature".to_string(), ParameterConstraint::ExclusiveWith("--x".to_string())),
+            ("--signature".to_string(), ParameterConstraint::ExclusiveWith("--y".to_string())),
+            ("--y".to_string(), ParameterConstraint::RequiredIf("--x".to_string())),
+            ("--x".to_string(), ParameterConstraint::RequiredIf("--y".to_string())),
+        ]
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_files = String::new();
+        let mut output_file = String::new();
+        let mut x = f64::NAN;
+        let mut y = f64::NAN;
+        let mut signature_str = String::new();
+        let mut widths_str = String::new();
+        let mut use_min = true;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no paramters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-inputs" || flag_val == "-input" {
+                input_files = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-x" {
+                x = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-y" {
+                y = if keyval {
+                    vec[1].to_string().parse::<f64>().unwrap()
+                } else {
+                    args[i + 1].to_string().parse::<f64>().unwrap()
+                };
+            } else if flag_val == "-signature" {
+                signature_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-widths" {
+                widths_str = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-method" {
+                let method = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+                use_min = !(method.contains("mean") || method.contains("av"));
+            }
+        }
+
+        if verbose {
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+            println!("* Welcome to {} *", self.get_tool_name());
+            println!("***************{}", "*".repeat(self.get_tool_name().len()));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        output_file = resolve_path(&output_file, working_directory);
+
+        let mut cmd = input_files.split(";");
+        let mut vec = cmd.collect::<Vec<&str>>();
+        if vec.len() == 1 {
+            cmd = input_files.split(",");
+            vec = cmd.collect::<Vec<&str>>();
+        }
+        let file_names: Vec<String> = vec
+            .iter()
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .map(|v| resolve_path(v, working_directory))
+            .collect();
+        let num_files = file_names.len();
+        if num_files == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one input terrain attribute raster is required to operate this tool.",
+            ));
+        }
+
+        let parse_list = |s: &str, name: &str| -> Result<Vec<f64>, Error> {
+            let mut values = vec![];
+            for v in s.split(",").map(|v| v.trim()).filter(|v| !v.is_empty()) {
+                match v.parse::<f64>() {
+                    Ok(val) => values.push(val),
+                    Err(_) => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("Error parsing the {} value '{}'.", name, v),
+                        ))
+                    }
+                }
+            }
+            if !values.is_empty() && values.len() != num_files {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The number of {} values must equal the number of input rasters ({}).", name, num_files),
+                ));
+            }
+            Ok(values)
+        };
+        let mut prototype = parse_list(&signature_str, "signature")?;
+        let mut widths = parse_list(&widths_str, "width")?;
+        if prototype.is_empty() && (x.is_nan() || y.is_nan()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either a prototype location (--x and --y) or an attribute signature (--signature) must be specified.",
+            ));
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let mut inputs = Vec::with_capacity(num_files);
+        for file_name in &file_names {
+            let input = Raster::new(file_name, "r")?;
+            if !inputs.is_empty() {
+                let first: &Raster = &inputs[0];
+                if input.configs.rows != first.configs.rows
+                    || input.configs.columns != first.configs.columns
+                {
+                    return Err(Error::new(ErrorKind::InvalidInput,
+                        "The input files must have the same number of rows and columns and spatial extent."));
+                }
+            }
+            inputs.push(input);
+        }
+
+        let start = Instant::now();
+
+        let rows = inputs[0].configs.rows as isize;
+        let columns = inputs[0].configs.columns as isize;
+
+        if prototype.is_empty() {
+            let row = inputs[0].get_row_from_y(y);
+            let col = inputs[0].get_column_from_x(x);
+            if row < 0 || col < 0 || row >= rows || col >= columns {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The prototype location is outside of the extent of the input rasters.",
+                ));
+            }
+            for input in &inputs {
+                let z = input.get_value(row, col);
+                if z == input.configs.nodata {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("The prototype location is NoData in the input {}.", input.get_short_filename()),
+                    ));
+                }
+                prototype.push(z);
+            }
+        }
+
+        if widths.is_empty() {
+            for input in &inputs {
+                let (_, stdev) = input.calculate_mean_and_stdev();
+                widths.push(stdev);
+            }
+        }
+        for i in 0..num_files {
+            if widths[i].is_nan() || widths[i] <= 0f64 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The membership function width of {} must be greater than zero.", inputs[i].get_short_filename()),
+                ));
+            }
+        }
+
+        if verbose {
+            for i in 0..num_files {
+                println!(
+                    "{}: prototype value = {}, width = {}",
+                    inputs[i].get_short_filename(),
+                    prototype[i],
+                    widths[i]
+                );
+            }
+        }
+
+        let out_nodata = -32768f64;
+        let mut output = Raster::initialize_using_file(&output_file, &inputs[0]);
+        output.configs.nodata = out_nodata;
+        output.configs.data_type = DataType::F32;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        output.configs.palette = "grey.plt".to_string();
+
+        let inputs = Arc::new(inputs);
+        let prototype = Arc::new(prototype);
+        let widths = Arc::new(widths);
+        let num_procs = num_cpus::get() as isize;
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let inputs = inputs.clone();
+            let prototype = prototype.clone();
+            let widths = widths.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let mut z: f64;
+                let mut membership: f64;
+                let mut similarity: f64;
+                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                    let mut data = vec![out_nodata; columns as usize];
+                    'col: for col in 0..columns {
+                        similarity = if use_min { 1f64 } else { 0f64 };
+                        for i in 0..num_files {
+                            z = inputs[i].get_value(row, col);
+                            if z == inputs[i].configs.nodata {
+                                continue 'col;
+                            }
+                            z = (z - prototype[i]) / widths[i];
+                            membership = (-0.5 * z * z).exp();
+                            if use_min {
+                                similarity = similarity.min(membership);
+                            } else {
+                                similarity += membership;
+                            }
+                        }
+                        if !use_min {
+                            similarity /= num_files as f64;
+                        }
+                        data[col as usize] = similarity;
+                    }
+                    tx.send((row, data)).unwrap();
+                }
+            });
+        }
+
+        for r in 0..rows {
+            let (row, data) = rx.recv().unwrap();
+            output.set_row_data(row, data);
+            if verbose {
+                progress = (100.0_f64 * r as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.configs.display_min = 0f64;
+        output.configs.display_max = 1f64;
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        for i in 0..num_files {
+            output.add_metadata_entry(format!(
+                "Input file: {} (prototype value: {}, width: {})",
+                file_names[i], prototype[i], widths[i]
+            ));
+        }
+        if !x.is_nan() && !y.is_nan() && signature_str.is_empty() {
+            output.add_metadata_entry(format!("Prototype location: ({}, {})", x, y));
+        }
+        output.add_metadata_entry(format!(
+            "Combination method: {}",
+            if use_min { "min" } else { "mean" }
+        ));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. John Lindsay
 Created: 22/06/2017
-Last Modified: 30/01/2019
+Last Modified: 16/10/2026
 License: MIT
 */
 
@@ -30,6 +30,13 @@ use std::thread;
 /// XY units measured in degrees, an appropriate Z Conversion Factor is calculated internally based on
 /// site latitude.
 /// 
+/// The optional `--units` parameter is shared by the terrain analysis tools. It contains one or
+/// more keywords, separated by commas, that specify the vertical (`z_m` or `z_ft`) and horizontal
+/// (`xy_m` or `xy_ft`) units of the DEM, in which case the Z conversion factor is calculated
+/// automatically; the units are otherwise read from the DEM, where available.
+/// It may also include `degrees` (the default) or `radians`, which sets the angular units of the
+/// output curvature (which is multiplied by 100 in either case).
+/// 
 /// # Reference
 /// Gallant, J. C., and J. P. Wilson, 2000, Primary topographic attributes, in Terrain Analysis: Principles 
 /// and Applications, edited by J. P. Wilson and J. C. Gallant pp. 51-86, John Wiley, Hoboken, N.J.
@@ -80,6 +87,17 @@ impl TotalCurvature {
             optional: true,
         });
 
+        parameters.push(ToolParameter {
+            name: "Units".to_owned(),
+            flags: vec!["--units".to_owned()],
+            description:
+                "Optional units keywords, e.g. 'percent', 'radians', 'arithmetic', 'z_ft', 'xy_m'."
+                    .to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let p = format!("{}", env::current_dir().unwrap().display());
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -142,6 +160,10 @@ impl WhiteboxTool for TotalCurvature {
         self.toolbox.clone()
     }
 
+    fn get_default_output_template(&self) -> Option<String> {
+        Some("{input_stem}_total_curv{input_ext}".to_string())
+    }
+
     fn run<'a>(
         &self,
         args: Vec<String>,
@@ -151,6 +173,7 @@ impl WhiteboxTool for TotalCurvature {
         let mut input_file = String::new();
         let mut output_file = String::new();
         let mut z_factor = 1f64;
+        let mut units = String::new();
 
         if args.len() == 0 {
             return Err(Error::new(
@@ -182,6 +205,12 @@ impl WhiteboxTool for TotalCurvature {
                 } else {
                     output_file = args[i + 1].to_string();
                 }
+            } else if vec[0].to_lowercase() == "-units" || vec[0].to_lowercase() == "--units" {
+                if keyval {
+                    units = vec[1].to_string();
+                } else {
+                    units = args[i + 1].to_string();
+                }
             } else if vec[0].to_lowercase() == "-zfactor" || vec[0].to_lowercase() == "--zfactor" {
                 if keyval {
                     z_factor = vec[1].to_string().parse::<f64>().unwrap();
@@ -197,17 +226,11 @@ impl WhiteboxTool for TotalCurvature {
             println!("***************{}", "*".repeat(self.get_tool_name().len()));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
@@ -221,14 +244,10 @@ impl WhiteboxTool for TotalCurvature {
         let cell_size_sqrd = cell_size * cell_size;
         let four_times_cell_size_sqrd = cell_size_sqrd * 4.0f64;
 
-        if input.is_in_geographic_coordinates() {
-            // calculate a new z-conversion factor
-            let mut mid_lat = (input.configs.north - input.configs.south) / 2.0;
-            if mid_lat <= 90.0 && mid_lat >= -90.0 {
-                mid_lat = mid_lat.to_radians();
-                z_factor = 1.0 / (113200.0 * mid_lat.cos());
-            }
-        }
+        let units = Units::parse(&units)?;
+        z_factor = units.get_z_factor(&input, z_factor);
+        // curvatures are reported in degrees (or radians) multiplied by 100
+        let curvature_multiplier = units.convert_angle(100f64);
 
         let mut output = Raster::initialize_using_file(&output_file, &input);
         if output.configs.data_type != DataType::F32 && output.configs.data_type != DataType::F64 {
@@ -268,7 +287,7 @@ impl WhiteboxTool for TotalCurvature {
                             zyy = (n[7] - 2.0f64 * z + n[3]) / cell_size_sqrd;
                             zxy = (-n[6] + n[0] + n[4] - n[2]) / four_times_cell_size_sqrd;
                             data[col as usize] =
-                                (zxx * zxx + 2.0f64 * zxy * zxy + zyy * zyy).to_degrees() * 100f64;
+                                (zxx * zxx + 2.0f64 * zxy * zxy + zyy * zyy) * curvature_multiplier;
                         }
                     }
                     tx.send((row, data)).unwrap();
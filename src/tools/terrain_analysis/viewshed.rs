@@ -217,20 +217,12 @@ impl WhiteboxTool for Viewshed {
 
         let num_procs = num_cpus::get() as isize;
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !input_file.contains(&sep) && !input_file.contains("/") {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !stations_file.contains(&sep) && !stations_file.contains("/") {
-            stations_file = format!("{}{}", working_directory, stations_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        input_file = resolve_path(&input_file, working_directory);
+        stations_file = resolve_path(&stations_file, working_directory);
+        output_file = resolve_path(&output_file, working_directory);
 
         if verbose {
             println!("Reading data...")
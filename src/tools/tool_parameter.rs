@@ -0,0 +1,107 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+//! The types that describe the parameters of tools, which are serialized to JSON for the
+//! `--toolparameters` output.
+
+// The serde derives wrap their impls in named constants, which rustc reports as non-local impls.
+#![allow(non_local_definitions)]
+
+use serde_json;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(super) struct ToolParameter {
+    pub(super) name: String,
+    pub(super) flags: Vec<String>,
+    pub(super) description: String,
+    pub(super) parameter_type: ParameterType,
+    pub(super) default_value: Option<String>,
+    pub(super) optional: bool,
+}
+
+impl ToolParameter {
+    pub fn to_string(&self) -> String {
+        let v = match serde_json::to_string(&self) {
+            Ok(json_str) => json_str,
+            Err(err) => format!("{:?}", err),
+        };
+        v
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(super) enum ParameterType {
+    Boolean,
+    String,
+    StringList,
+    Integer,
+    Float,
+    VectorAttributeField(AttributeType, String),
+    StringOrNumber,
+    ExistingFile(ParameterFileType),
+    ExistingFileOrFloat(ParameterFileType),
+    NewFile(ParameterFileType),
+    FileList(ParameterFileType),
+    Directory,
+    OptionList(Vec<String>),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(super) enum ParameterFileType {
+    Any,
+    Lidar,
+    Raster,
+    RasterAndVector(VectorGeometryType),
+    Vector(VectorGeometryType),
+    Text,
+    Html,
+    Csv,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(super) enum VectorGeometryType {
+    Any,
+    Point,
+    Line,
+    Polygon,
+    LineOrPolygon,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(super) enum AttributeType {
+    Any,
+    Integer,
+    Float,
+    Number,
+    Text,
+    Boolean,
+    Date,
+}
+
+/// A machine-checkable constraint on the value of a tool parameter, or on its relationship with
+/// the other parameters of the tool. Constraints are declared by tools, keyed on a flag of the
+/// constrained parameter, using `WhiteboxTool::get_parameter_constraints`. They are listed in the
+/// tool's help and parameters output and are checked by `ToolManager::run_tool` before the tool
+/// is run, such that invalid values are rejected with a clear message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ParameterConstraint {
+    /// The value must be greater than or equal to the minimum.
+    Min(f64),
+    /// The value must be less than or equal to the maximum.
+    Max(f64),
+    /// The value must be greater than the bound.
+    GreaterThan(f64),
+    /// The value must be within the inclusive range.
+    Range(f64, f64),
+    /// Each value within a comma- or semicolon-separated list must be within the inclusive range.
+    ListRange(f64, f64),
+    /// The parameter cannot be used together with the parameter with the specified flag.
+    ExclusiveWith(String),
+    /// The parameter is required when the parameter with the specified flag is used.
+    RequiredIf(String),
+}
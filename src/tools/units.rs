@@ -0,0 +1,442 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::lidar::LasFile;
+use crate::raster::Raster;
+use crate::structures::BoundingBox;
+use std::f64;
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+
+/// The units of slope gradients and other angular quantities.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AngleUnits {
+    Degrees,
+    Percent,
+    Radians,
+}
+
+/// The convention used to express azimuths (directions).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AzimuthConvention {
+    /// Measured clockwise from north, i.e. compass bearings.
+    Geographic,
+    /// Measured counter-clockwise from east, i.e. mathematical angles.
+    Arithmetic,
+}
+
+/// The units of horizontal or vertical distances.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LinearUnits {
+    Meters,
+    Feet,
+    NotSpecified,
+}
+
+impl LinearUnits {
+    /// Interprets a unit description, such as the `xy_units` and `z_units` of a raster.
+    pub fn from_description(s: &str) -> LinearUnits {
+        let s = s.trim().to_lowercase();
+        if s.contains("feet") || s.contains("foot") || s == "ft" || s == "us_ft" {
+            LinearUnits::Feet
+        } else if s.contains("met") || s == "m" {
+            LinearUnits::Meters
+        } else {
+            LinearUnits::NotSpecified
+        }
+    }
+
+    /// Returns the length of one unit in metres.
+    pub fn to_meters(&self) -> f64 {
+        match self {
+            LinearUnits::Feet => 0.3048,
+            _ => 1f64,
+        }
+    }
+}
+
+/// The unit conventions shared by the terrain analysis tools, as specified by the
+/// `--units` parameter. The parameter value is a list of keywords, separated by commas,
+/// semicolons, or spaces, e.g. `--units="percent, arithmetic, z_ft"`:
+///
+/// | Keyword | Meaning |
+/// |---------|---------|
+/// | `degrees`, `percent`, `radians` | Units of output slopes and angles (default degrees). Percent applies to slope gradients only; other angles are output in degrees. |
+/// | `geographic`, `arithmetic` | Azimuths measured clockwise from north (default) or counter-clockwise from east. |
+/// | `z_m`, `z_ft` | Units of the DEM elevations. |
+/// | `xy_m`, `xy_ft` | Units of the DEM horizontal coordinates. |
+///
+/// When the elevation or horizontal units are not specified, they are taken from the DEM's
+/// metadata, where available, and are otherwise assumed to be equal.
+#[derive(Clone, Copy, Debug)]
+pub struct Units {
+    pub angle: AngleUnits,
+    pub azimuth: AzimuthConvention,
+    pub z_units: LinearUnits,
+    pub xy_units: LinearUnits,
+}
+
+impl Default for Units {
+    fn default() -> Units {
+        Units {
+            angle: AngleUnits::Degrees,
+            azimuth: AzimuthConvention::Geographic,
+            z_units: LinearUnits::NotSpecified,
+            xy_units: LinearUnits::NotSpecified,
+        }
+    }
+}
+
+impl Units {
+    /// Parses the value of a `--units` parameter.
+    pub fn parse(s: &str) -> Result<Units, Error> {
+        let mut units = Units::default();
+        for token in s
+            .split(&[',', ';', ' '][..])
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+        {
+            match token.as_str() {
+                "degrees" | "degree" | "deg" => units.angle = AngleUnits::Degrees,
+                "percent" | "pct" | "%" => units.angle = AngleUnits::Percent,
+                "radians" | "radian" | "rad" => units.angle = AngleUnits::Radians,
+                "geographic" | "compass" => units.azimuth = AzimuthConvention::Geographic,
+                "arithmetic" | "mathematical" => units.azimuth = AzimuthConvention::Arithmetic,
+                "z_m" | "z_meters" | "z_metres" => units.z_units = LinearUnits::Meters,
+                "z_ft" | "z_feet" => units.z_units = LinearUnits::Feet,
+                "xy_m" | "xy_meters" | "xy_metres" => units.xy_units = LinearUnits::Meters,
+                "xy_ft" | "xy_feet" => units.xy_units = LinearUnits::Feet,
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unrecognized units keyword '{}'.", token),
+                    ))
+                }
+            }
+        }
+        Ok(units)
+    }
+
+    /// Returns the multiplier that converts the elevations of a DEM into its horizontal units.
+    /// The user-specified `z_factor` is applied in addition to any unit conversion. For DEMs in
+    /// geographic coordinates, elevations are converted into degrees using the latitude at the
+    /// centre of the DEM.
+    pub fn get_z_factor(&self, input: &Raster, z_factor: f64) -> f64 {
+        let mut z_units = self.z_units;
+        if z_units == LinearUnits::NotSpecified {
+            z_units = LinearUnits::from_description(&input.configs.z_units);
+        }
+        if input.is_in_geographic_coordinates() {
+            let mid_lat = ((input.configs.north + input.configs.south) / 2.0).to_radians();
+            let z_meters = if z_units == LinearUnits::Feet {
+                LinearUnits::Feet.to_meters()
+            } else {
+                1f64
+            };
+            return z_factor * z_meters / (113200.0 * mid_lat.cos());
+        }
+        let mut xy_units = self.xy_units;
+        if xy_units == LinearUnits::NotSpecified {
+            xy_units = LinearUnits::from_description(&input.configs.xy_units);
+        }
+        if z_units == LinearUnits::NotSpecified || xy_units == LinearUnits::NotSpecified {
+            // only one of the units is known; assume that they are the same
+            return z_factor;
+        }
+        z_factor * z_units.to_meters() / xy_units.to_meters()
+    }
+
+    /// Converts a slope gradient, in radians, into the output units.
+    pub fn convert_slope(&self, slope: f64) -> f64 {
+        match self.angle {
+            AngleUnits::Degrees => slope.to_degrees(),
+            AngleUnits::Percent => slope.tan() * 100f64,
+            AngleUnits::Radians => slope,
+        }
+    }
+
+    /// Converts an angle (other than a slope gradient), in radians, into the output units.
+    pub fn convert_angle(&self, angle: f64) -> f64 {
+        match self.angle {
+            AngleUnits::Radians => angle,
+            _ => angle.to_degrees(),
+        }
+    }
+
+    /// Converts an input angle, in the angular units, into radians.
+    pub fn angle_to_radians(&self, angle: f64) -> f64 {
+        match self.angle {
+            AngleUnits::Radians => angle,
+            _ => angle.to_radians(),
+        }
+    }
+
+    /// Converts a geographic azimuth (clockwise from north), in radians, into the output
+    /// azimuth convention and angular units. The result is within the range [0, 360) degrees.
+    pub fn convert_azimuth(&self, azimuth: f64) -> f64 {
+        let mut a = match self.azimuth {
+            AzimuthConvention::Geographic => azimuth,
+            AzimuthConvention::Arithmetic => PI / 2f64 - azimuth,
+        };
+        a = a.rem_euclid(2f64 * PI);
+        self.convert_angle(a)
+    }
+
+    /// Converts an input azimuth, expressed using the azimuth convention and angular units,
+    /// into a geographic azimuth (clockwise from north) in radians.
+    pub fn azimuth_to_geographic(&self, azimuth: f64) -> f64 {
+        let a = self.angle_to_radians(azimuth);
+        match self.azimuth {
+            AzimuthConvention::Geographic => a,
+            AzimuthConvention::Arithmetic => (PI / 2f64 - a).rem_euclid(2f64 * PI),
+        }
+    }
+
+    /// Returns a description of the angular units of slope outputs, for metadata.
+    pub fn slope_units_description(&self) -> String {
+        match self.angle {
+            AngleUnits::Degrees => "degrees".to_string(),
+            AngleUnits::Percent => "percent".to_string(),
+            AngleUnits::Radians => "radians".to_string(),
+        }
+    }
+
+    /// Returns a description of the angular units of other angular outputs, for metadata.
+    pub fn angle_units_description(&self) -> String {
+        match self.angle {
+            AngleUnits::Radians => "radians".to_string(),
+            _ => "degrees".to_string(),
+        }
+    }
+}
+
+/// The units of the horizontal coordinates of a data set, or of a horizontal distance parameter,
+/// such as the grid resolution of a LiDAR gridding tool.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CoordinateUnits {
+    Meters,
+    Feet,
+    Degrees,
+    NotSpecified,
+}
+
+impl CoordinateUnits {
+    /// Parses a units keyword, e.g. the value of a `--res_units` parameter. The keyword
+    /// `native`, or an empty value, indicates the units of the data's coordinates and is
+    /// returned as `NotSpecified`.
+    pub fn parse(s: &str) -> Result<CoordinateUnits, Error> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "native" => Ok(CoordinateUnits::NotSpecified),
+            "m" | "meters" | "metres" | "meter" | "metre" => Ok(CoordinateUnits::Meters),
+            "ft" | "feet" | "foot" | "us_ft" => Ok(CoordinateUnits::Feet),
+            "deg" | "degrees" | "degree" => Ok(CoordinateUnits::Degrees),
+            v => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unrecognized units '{}'. Use 'native', 'meters', 'feet', or 'degrees'.", v),
+            )),
+        }
+    }
+
+    /// Infers the units of the horizontal coordinates of a LAS file from its GeoKeys or
+    /// OGC WKT. When the file does not specify a coordinate reference system, coordinates
+    /// that lie within the range of longitudes and latitudes and that are stored with a
+    /// sub-metre scale factor (i.e. at least five decimal places) are assumed to be in degrees.
+    pub fn from_las(las: &mut LasFile) -> CoordinateUnits {
+        let extent = las.get_extent();
+        let in_lon_lat_range = extent.min_x >= -180f64
+            && extent.max_x <= 180f64
+            && extent.min_y >= -90f64
+            && extent.max_y <= 90f64;
+
+        let mut units = CoordinateUnits::NotSpecified;
+        let geokeys = &las.geokeys;
+        let model_type = geokeys.get_short_value(1024); // GTModelTypeGeoKey
+        let projected_cs = geokeys.get_short_value(3072).filter(|v| *v != 0 && *v != 32767);
+        if model_type == Some(2) || (model_type.is_none() && projected_cs.is_none() && geokeys.get_short_value(2048).is_some()) {
+            units = CoordinateUnits::Degrees;
+        } else if model_type == Some(1) || projected_cs.is_some() {
+            // ProjLinearUnitsGeoKey
+            units = match geokeys.get_short_value(3076) {
+                Some(9001) => CoordinateUnits::Meters,
+                Some(9002) | Some(9003) | Some(9005) | Some(9070) | Some(9300) => CoordinateUnits::Feet,
+                _ => CoordinateUnits::NotSpecified,
+            };
+        }
+        if units == CoordinateUnits::NotSpecified {
+            units = CoordinateUnits::from_wkt(&las.get_wkt());
+        }
+
+        if units == CoordinateUnits::Degrees && !in_lon_lat_range {
+            // the metadata are inconsistent with the coordinates themselves
+            return CoordinateUnits::NotSpecified;
+        }
+        if units == CoordinateUnits::NotSpecified
+            && in_lon_lat_range
+            && las.header.x_scale_factor > 0f64
+            && las.header.x_scale_factor <= 0.00001
+        {
+            return CoordinateUnits::Degrees;
+        }
+        units
+    }
+
+    /// Infers the units of the horizontal coordinates described by an OGC WKT string.
+    pub fn from_wkt(wkt: &str) -> CoordinateUnits {
+        let wkt = wkt.to_lowercase().replace(" ", "");
+        if wkt.is_empty() {
+            return CoordinateUnits::NotSpecified;
+        }
+        if !wkt.contains("projcs[") && !wkt.contains("projcrs[") {
+            if wkt.starts_with("geogcs[") || wkt.starts_with("geogcrs[") || wkt.starts_with("geodcrs[") {
+                return CoordinateUnits::Degrees;
+            }
+            return CoordinateUnits::NotSpecified;
+        }
+        // the linear unit of a projected system follows the units of its base geographic system
+        match wkt.rfind("unit[") {
+            Some(i) => {
+                let unit = wkt[i..].split(']').next().unwrap_or("");
+                if unit.contains("foot") || unit.contains("feet") || unit.contains("\"ft") {
+                    CoordinateUnits::Feet
+                } else if unit.contains("met") {
+                    CoordinateUnits::Meters
+                } else {
+                    CoordinateUnits::NotSpecified
+                }
+            }
+            None => CoordinateUnits::NotSpecified,
+        }
+    }
+
+    /// Returns the length of one unit in metres. The length of a degree is that of a degree
+    /// of longitude at the specified `latitude`.
+    pub fn to_meters(&self, latitude: f64) -> f64 {
+        match self {
+            CoordinateUnits::Feet => 0.3048,
+            CoordinateUnits::Degrees => 111_320f64 * latitude.to_radians().cos(),
+            _ => 1f64,
+        }
+    }
+
+    /// Returns a description of the units, e.g. for the `xy_units` of an output raster.
+    pub fn description(&self) -> String {
+        match self {
+            CoordinateUnits::Meters => "metres".to_string(),
+            CoordinateUnits::Feet => "feet".to_string(),
+            CoordinateUnits::Degrees => "degrees".to_string(),
+            CoordinateUnits::NotSpecified => "not specified".to_string(),
+        }
+    }
+}
+
+/// Checks a horizontal distance parameter of a LiDAR gridding tool, usually the grid
+/// resolution, against the units of the point coordinates (`data_units`), which may be
+/// determined using `CoordinateUnits::from_las`. The `value_units` are the units in which the
+/// user specified the value, or `NotSpecified` if the value is in the units of the data.
+///
+/// The function returns the multiplier that converts the value, and any other distance
+/// parameters specified in the same units, into the units of the data, along with a warning
+/// message describing any apparent unit mismatch. Unless `convert` is true, the multiplier is
+/// always 1, i.e. mismatches are reported but the value is used as specified. Values specified
+/// in degrees cannot be converted for projected data, nor can linear values be converted for
+/// data in degrees when the value's units are not stated and the value is not implausibly large.
+pub fn check_distance_units(
+    parameter: &str,
+    value: f64,
+    value_units: CoordinateUnits,
+    data_units: CoordinateUnits,
+    extent: BoundingBox,
+    convert: bool,
+) -> (f64, Option<String>) {
+    let mid_lat = (extent.min_y + extent.max_y) / 2f64;
+    if value_units != CoordinateUnits::NotSpecified
+        && data_units != CoordinateUnits::NotSpecified
+        && value_units != data_units
+    {
+        if value_units == CoordinateUnits::Degrees {
+            return (
+                1f64,
+                Some(format!(
+                    "Warning: The {} is specified in degrees but the point coordinates are in {}. The value cannot be converted and will be interpreted in {}.",
+                    parameter,
+                    data_units.description(),
+                    data_units.description()
+                )),
+            );
+        }
+        let multiplier = value_units.to_meters(mid_lat) / data_units.to_meters(mid_lat);
+        if convert {
+            return (
+                multiplier,
+                Some(format!(
+                    "Note: The {} ({} {}) has been converted into the units of the point coordinates ({} {}).",
+                    parameter,
+                    value,
+                    value_units.description(),
+                    value * multiplier,
+                    data_units.description()
+                )),
+            );
+        }
+        return (
+            1f64,
+            Some(format!(
+                "Warning: The {} is specified in {} but the point coordinates are in {}. The value will be interpreted as {} {}; use --convert_units to convert it to {} {}.",
+                parameter,
+                value_units.description(),
+                data_units.description(),
+                value,
+                data_units.description(),
+                value * multiplier,
+                data_units.description()
+            )),
+        );
+    }
+
+    if value_units == CoordinateUnits::NotSpecified && data_units == CoordinateUnits::Degrees && value >= 0.01 {
+        // a value of 0.01 degrees is roughly one kilometre; such values are almost certainly linear
+        let multiplier = 1f64 / CoordinateUnits::Degrees.to_meters(mid_lat);
+        if convert {
+            return (
+                multiplier,
+                Some(format!(
+                    "Note: The point coordinates are in degrees; the {} ({}) has been assumed to be in metres and converted to {} degrees.",
+                    parameter,
+                    value,
+                    value * multiplier
+                )),
+            );
+        }
+        return (
+            1f64,
+            Some(format!(
+                "Warning: The point coordinates are in degrees but the {} ({}) appears to be linear. If it is in metres, specify --res_units=meters or use --convert_units (approx. {:.8} degrees).",
+                parameter,
+                value,
+                value * multiplier
+            )),
+        );
+    }
+
+    if value_units == CoordinateUnits::NotSpecified
+        && (data_units == CoordinateUnits::Meters || data_units == CoordinateUnits::Feet)
+        && value < 0.001
+    {
+        return (
+            1f64,
+            Some(format!(
+                "Warning: The {} ({}) is very small for point coordinates in {}. Was it specified in degrees?",
+                parameter,
+                value,
+                data_units.description()
+            )),
+        );
+    }
+
+    (1f64, None)
+}
@@ -118,4 +118,9 @@ impl<W: Write> ByteOrderWriter<W> {
     pub fn get_inner(&mut self) -> &W {
         &self.writer
     }
+
+    /// Consumes the writer, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
 }
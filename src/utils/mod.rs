@@ -1,11 +1,15 @@
 // private sub-module defined in other files
 mod byte_order_reader;
 mod byte_order_writer;
+mod paths;
+mod sqlite_writer;
 
 // exports identifiers from private sub-modules in the current module namespace
 pub use self::byte_order_reader::ByteOrderReader;
 pub use self::byte_order_reader::Endianness;
 pub use self::byte_order_writer::ByteOrderWriter;
+pub use self::paths::{is_absolute_path, normalize_path, resolve_path};
+pub use self::sqlite_writer::{SqlValue, SqliteWriter};
 
 use std::time::Instant;
 
@@ -0,0 +1,181 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+use std::path;
+
+/// Returns true if a file name is an absolute path on any platform, i.e. it begins with a Unix
+/// root (`/data/dem.tif`), a Windows drive (`C:\data\dem.tif`, `C:/data/dem.tif`), or a Windows
+/// root or UNC share (`\data\dem.tif`, `\\server\share\dem.tif`).
+pub fn is_absolute_path(file_name: &str) -> bool {
+    let bytes = file_name.as_bytes();
+    if file_name.starts_with('/') || file_name.starts_with('\\') {
+        return true;
+    }
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// Resolves a file name given to a tool against the working directory. Absolute paths are
+/// returned unchanged, while relative paths, including bare file names (`dem.tif`) and paths that
+/// begin with `.` or `..` components (`./dem.tif`, `..\data\dem.tif`), are appended to the working
+/// directory. The `.` and `..` components of the result are then removed. Empty file names, and
+/// those given without a working directory, are returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// # use whitebox_tools::utils::resolve_path;
+/// assert_eq!(resolve_path("dem.tif", "/data/"), "/data/dem.tif");
+/// assert_eq!(resolve_path("../other/dem.tif", "/data/sub/"), "/data/other/dem.tif");
+/// assert_eq!(resolve_path("/tmp/dem.tif", "/data/"), "/tmp/dem.tif");
+/// ```
+pub fn resolve_path(file_name: &str, working_directory: &str) -> String {
+    let file_name = file_name.trim();
+    if file_name.is_empty() || working_directory.is_empty() || is_absolute_path(file_name) {
+        return file_name.to_string();
+    }
+    let joined = if working_directory.ends_with('/') || working_directory.ends_with('\\') {
+        format!("{}{}", working_directory, file_name)
+    } else {
+        format!("{}{}{}", working_directory, path::MAIN_SEPARATOR, file_name)
+    };
+    normalize_path(&joined)
+}
+
+/// Lexically removes the `.` and `..` components of a path, e.g. `/data/sub/../dem.tif` becomes
+/// `/data/dem.tif`, keeping the separator that precedes each remaining component. Leading `..`
+/// components of relative paths, and those that would rise above the root, are kept.
+pub fn normalize_path(file_name: &str) -> String {
+    let is_separator = |c: char| c == '/' || c == '\\';
+    // the prefix, i.e. a drive and/or root, is kept as is
+    let bytes = file_name.as_bytes();
+    let mut prefix_len = if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        2
+    } else {
+        0
+    };
+    let is_unc =
+        file_name[prefix_len..].starts_with("\\\\") || file_name[prefix_len..].starts_with("//");
+    prefix_len += file_name[prefix_len..]
+        .chars()
+        .take_while(|c| is_separator(*c))
+        .count();
+    let (prefix, rest) = file_name.split_at(prefix_len);
+    let is_rooted = prefix.chars().any(is_separator);
+
+    // split the rest into (separator, component) pairs
+    let mut components: Vec<(Option<char>, &str)> = vec![];
+    let mut separator = None;
+    let mut start = 0;
+    for (i, c) in rest.char_indices() {
+        if is_separator(c) {
+            if i > start {
+                components.push((separator, &rest[start..i]));
+            }
+            separator = Some(c);
+            start = i + 1;
+        }
+    }
+    let trailing_separator = start == rest.len() && !rest.is_empty();
+    if start < rest.len() {
+        components.push((separator, &rest[start..]));
+    }
+
+    let mut kept: Vec<(Option<char>, &str)> = vec![];
+    for &(sep, component) in components.iter() {
+        match component {
+            "." => {}
+            // a root, or the server and share names of a UNC path, cannot be risen above
+            ".." if (is_rooted && kept.is_empty()) || (is_unc && kept.len() <= 2) => {}
+            ".." if !kept.is_empty() && kept[kept.len() - 1].1 != ".." => {
+                kept.pop();
+            }
+            _ => {
+                // the first component takes no separator; later ones keep their own
+                let sep = if kept.is_empty() {
+                    None
+                } else {
+                    sep.or(Some(path::MAIN_SEPARATOR))
+                };
+                kept.push((sep, component));
+            }
+        }
+    }
+
+    let mut s = prefix.to_string();
+    for (sep, component) in kept {
+        if let Some(c) = sep {
+            s.push(c);
+        }
+        s.push_str(component);
+    }
+    if trailing_separator && !s.is_empty() && !s.ends_with(is_separator) {
+        s.push(path::MAIN_SEPARATOR);
+    }
+    if s.is_empty() {
+        s.push('.');
+    }
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_absolute_path, normalize_path, resolve_path};
+
+    #[test]
+    fn test_is_absolute_path() {
+        assert!(is_absolute_path("/data/dem.tif"));
+        assert!(is_absolute_path("C:\\data\\dem.tif"));
+        assert!(is_absolute_path("c:/data/dem.tif"));
+        assert!(is_absolute_path("\\\\server\\share\\dem.tif"));
+        assert!(!is_absolute_path("dem.tif"));
+        assert!(!is_absolute_path("./dem.tif"));
+        assert!(!is_absolute_path("..\\data\\dem.tif"));
+        assert!(!is_absolute_path("sub/dem.tif"));
+        assert!(!is_absolute_path("sst.nc:analysed_sst[2]"));
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        assert_eq!(resolve_path("dem.tif", "/data/"), "/data/dem.tif");
+        assert_eq!(
+            resolve_path("dem.tif", "/data"),
+            format!("/data{}dem.tif", std::path::MAIN_SEPARATOR)
+        );
+        assert_eq!(resolve_path("./dem.tif", "/data/"), "/data/dem.tif");
+        assert_eq!(resolve_path("sub/dem.tif", "/data/"), "/data/sub/dem.tif");
+        assert_eq!(
+            resolve_path("../other/dem.tif", "/data/sub/"),
+            "/data/other/dem.tif"
+        );
+        assert_eq!(
+            resolve_path("..\\..\\dem.tif", "C:\\data\\sub\\"),
+            "C:\\dem.tif"
+        );
+        assert_eq!(
+            resolve_path("..\\dem.tif", "\\\\server\\share\\"),
+            "\\\\server\\share\\dem.tif"
+        );
+        assert_eq!(resolve_path("/tmp/dem.tif", "/data/"), "/tmp/dem.tif");
+        assert_eq!(resolve_path("D:\\dem.tif", "C:\\data\\"), "D:\\dem.tif");
+        assert_eq!(
+            resolve_path("cube.zarr:ndvi[3]", "/data/"),
+            "/data/cube.zarr:ndvi[3]"
+        );
+        assert_eq!(resolve_path("", "/data/"), "");
+        assert_eq!(resolve_path("dem.tif", ""), "dem.tif");
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/data/./sub/../dem.tif"), "/data/dem.tif");
+        assert_eq!(normalize_path("/../dem.tif"), "/dem.tif");
+        assert_eq!(normalize_path("../../dem.tif"), "../../dem.tif");
+        assert_eq!(normalize_path("a/../../dem.tif"), "../dem.tif");
+        assert_eq!(normalize_path("/data/sub/"), "/data/sub/");
+    }
+}
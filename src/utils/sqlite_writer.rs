@@ -0,0 +1,542 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+//! A minimal writer of SQLite database files, sufficient for creating tables of records and
+//! indices on them, e.g. MBTiles tile sets, without linking to the SQLite library. Records are
+//! appended to the leaf pages of each table's b-tree as they are inserted, such that large
+//! values (e.g. images) need not be held in memory, and the interior pages of the b-trees, the
+//! indices, and the schema are written when the database is finished. The database cannot be
+//! read or modified by the writer.
+
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufWriter, Error, ErrorKind, Seek, SeekFrom, Write};
+
+const PAGE_SIZE: usize = 4096;
+/// The SQLite library version recorded in the header, i.e. 3.40.0.
+const SQLITE_VERSION_NUMBER: u32 = 3_040_000;
+const TABLE_LEAF: u8 = 0x0D;
+const TABLE_INTERIOR: u8 = 0x05;
+const INDEX_LEAF: u8 = 0x0A;
+const INDEX_INTERIOR: u8 = 0x02;
+
+/// A value of a column of a record.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl SqlValue {
+    /// Compares values in the order used by SQLite, i.e. NULL, numbers, text, then blobs.
+    fn compare(&self, other: &SqlValue) -> Ordering {
+        let class = |v: &SqlValue| match v {
+            SqlValue::Null => 0,
+            SqlValue::Integer(_) | SqlValue::Real(_) => 1,
+            SqlValue::Text(_) => 2,
+            SqlValue::Blob(_) => 3,
+        };
+        match (self, other) {
+            (SqlValue::Integer(a), SqlValue::Integer(b)) => a.cmp(b),
+            (SqlValue::Integer(a), SqlValue::Real(b)) => (*a as f64).partial_cmp(b).unwrap_or(Ordering::Equal),
+            (SqlValue::Real(a), SqlValue::Integer(b)) => a.partial_cmp(&(*b as f64)).unwrap_or(Ordering::Equal),
+            (SqlValue::Real(a), SqlValue::Real(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            (SqlValue::Text(a), SqlValue::Text(b)) => a.as_bytes().cmp(b.as_bytes()),
+            (SqlValue::Blob(a), SqlValue::Blob(b)) => a.cmp(b),
+            _ => class(self).cmp(&class(other)),
+        }
+    }
+}
+
+/// Appends a variable-length integer, in SQLite's big-endian encoding of up to nine bytes.
+fn put_varint(out: &mut Vec<u8>, v: u64) {
+    if v <= 0x7f {
+        out.push(v as u8);
+    } else if v > 0x00ff_ffff_ffff_ffff {
+        // the ninth byte holds eight bits
+        let mut buf = [0u8; 9];
+        buf[8] = v as u8;
+        let mut x = v >> 8;
+        for b in buf[0..8].iter_mut().rev() {
+            *b = (x & 0x7f) as u8 | 0x80;
+            x >>= 7;
+        }
+        out.extend_from_slice(&buf);
+    } else {
+        let mut groups = vec![];
+        let mut x = v;
+        while x > 0 {
+            groups.push((x & 0x7f) as u8);
+            x >>= 7;
+        }
+        for i in (0..groups.len()).rev() {
+            out.push(if i > 0 { groups[i] | 0x80 } else { groups[i] });
+        }
+    }
+}
+
+fn varint_len(v: u64) -> usize {
+    let mut out = Vec::with_capacity(9);
+    put_varint(&mut out, v);
+    out.len()
+}
+
+/// Encodes the values of a record in the SQLite record format.
+fn encode_record(values: &[SqlValue]) -> Vec<u8> {
+    let mut types = vec![];
+    let mut body = vec![];
+    for value in values {
+        match value {
+            SqlValue::Null => put_varint(&mut types, 0),
+            SqlValue::Integer(0) => put_varint(&mut types, 8),
+            SqlValue::Integer(1) => put_varint(&mut types, 9),
+            SqlValue::Integer(i) => {
+                let i = *i;
+                let (serial_type, size) = if i >= i8::MIN as i64 && i <= i8::MAX as i64 {
+                    (1, 1)
+                } else if i >= i16::MIN as i64 && i <= i16::MAX as i64 {
+                    (2, 2)
+                } else if (-0x80_0000..0x80_0000).contains(&i) {
+                    (3, 3)
+                } else if i >= i32::MIN as i64 && i <= i32::MAX as i64 {
+                    (4, 4)
+                } else if (-0x8000_0000_0000..0x8000_0000_0000).contains(&i) {
+                    (5, 6)
+                } else {
+                    (6, 8)
+                };
+                put_varint(&mut types, serial_type);
+                body.extend_from_slice(&i.to_be_bytes()[8 - size..]);
+            }
+            SqlValue::Real(v) => {
+                put_varint(&mut types, 7);
+                body.extend_from_slice(&v.to_be_bytes());
+            }
+            SqlValue::Text(s) => {
+                put_varint(&mut types, 2 * s.len() as u64 + 13);
+                body.extend_from_slice(s.as_bytes());
+            }
+            SqlValue::Blob(b) => {
+                put_varint(&mut types, 2 * b.len() as u64 + 12);
+                body.extend_from_slice(b);
+            }
+        }
+    }
+    // the header size includes its own varint
+    let mut header_size = types.len() + 1;
+    while types.len() + varint_len(header_size as u64) != header_size {
+        header_size = types.len() + varint_len(header_size as u64);
+    }
+    let mut record = Vec::with_capacity(header_size + body.len());
+    put_varint(&mut record, header_size as u64);
+    record.extend_from_slice(&types);
+    record.extend_from_slice(&body);
+    record
+}
+
+/// Assembles a b-tree page from its cells, which are stored in order from the end of the page.
+/// `offset` is the position of the page header, i.e. 100 on the first page of the database.
+fn build_page(page_type: u8, cells: &[Vec<u8>], right_child: Option<u32>, offset: usize) -> Vec<u8> {
+    let mut page = vec![0u8; PAGE_SIZE];
+    let header_size = if right_child.is_some() { 12 } else { 8 };
+    let mut content_start = PAGE_SIZE;
+    for (i, cell) in cells.iter().enumerate() {
+        content_start -= cell.len();
+        page[content_start..content_start + cell.len()].copy_from_slice(cell);
+        let p = offset + header_size + 2 * i;
+        page[p..p + 2].copy_from_slice(&(content_start as u16).to_be_bytes());
+    }
+    page[offset] = page_type;
+    page[offset + 3..offset + 5].copy_from_slice(&(cells.len() as u16).to_be_bytes());
+    page[offset + 5..offset + 7].copy_from_slice(&(content_start as u16).to_be_bytes());
+    if let Some(child) = right_child {
+        page[offset + 8..offset + 12].copy_from_slice(&child.to_be_bytes());
+    }
+    page
+}
+
+/// Returns the number of bytes occupied by a set of cells and their pointers on a page.
+fn cells_size(cells: &[Vec<u8>]) -> usize {
+    cells.iter().map(|c| c.len() + 2).sum()
+}
+
+struct Table {
+    name: String,
+    sql: String,
+    next_rowid: i64,
+    /// the cells of the leaf page that is being filled
+    cells: Vec<Vec<u8>>,
+    /// the page numbers and largest rowids of the leaf pages that have been written
+    leaves: Vec<(u32, i64)>,
+}
+
+struct Index {
+    name: String,
+    table: usize,
+    sql: String,
+    columns: Vec<usize>,
+    /// the indexed values of each record, followed by its rowid
+    keys: Vec<Vec<SqlValue>>,
+}
+
+/// Writes a SQLite database file.
+///
+/// ## Example
+/// ```no_run
+/// # use whitebox_tools::utils::{SqlValue, SqliteWriter};
+/// let mut db = SqliteWriter::create("/tmp/example.db").unwrap();
+/// let t = db.create_table("points", "CREATE TABLE points (name text, z real)");
+/// db.create_index("points_name", t, "CREATE INDEX points_name ON points (name)", &[0]);
+/// db.insert(t, &[SqlValue::Text("summit".to_string()), SqlValue::Real(1234.5)]).unwrap();
+/// db.finish().unwrap();
+/// ```
+pub struct SqliteWriter {
+    writer: BufWriter<File>,
+    num_pages: u32,
+    application_id: u32,
+    tables: Vec<Table>,
+    indices: Vec<Index>,
+}
+
+impl SqliteWriter {
+    /// Creates a database file, replacing any existing file.
+    pub fn create(file_name: &str) -> Result<SqliteWriter, Error> {
+        let mut writer = BufWriter::new(File::create(file_name)?);
+        // the first page, which holds the header and schema, is written when the database is finished
+        writer.write_all(&[0u8; PAGE_SIZE])?;
+        Ok(SqliteWriter {
+            writer,
+            num_pages: 1,
+            application_id: 0,
+            tables: vec![],
+            indices: vec![],
+        })
+    }
+
+    /// Sets the application ID of the header, which identifies the file format of the database,
+    /// e.g. 0x4d504258 for MBTiles.
+    pub fn set_application_id(&mut self, application_id: u32) {
+        self.application_id = application_id;
+    }
+
+    /// Adds a table, given its name and the SQL statement that creates it, and returns the table
+    /// number used to insert records.
+    pub fn create_table(&mut self, name: &str, sql: &str) -> usize {
+        self.tables.push(Table {
+            name: name.to_string(),
+            sql: sql.to_string(),
+            next_rowid: 1,
+            cells: vec![],
+            leaves: vec![],
+        });
+        self.tables.len() - 1
+    }
+
+    /// Adds an index on the columns of a table, given its name and the SQL statement that creates
+    /// it. The index must be created before any records are inserted into the table.
+    pub fn create_index(&mut self, name: &str, table: usize, sql: &str, columns: &[usize]) {
+        self.indices.push(Index {
+            name: name.to_string(),
+            table,
+            sql: sql.to_string(),
+            columns: columns.to_vec(),
+            keys: vec![],
+        });
+    }
+
+    /// Inserts a record into a table and returns its rowid.
+    pub fn insert(&mut self, table: usize, values: &[SqlValue]) -> Result<i64, Error> {
+        if table >= self.tables.len() {
+            return Err(Error::new(ErrorKind::InvalidInput, "Unknown table."));
+        }
+        let rowid = self.tables[table].next_rowid;
+        let record = encode_record(values);
+        let cell = self.table_leaf_cell(rowid, &record)?;
+        if cells_size(&self.tables[table].cells) + cell.len() + 2 + 8 > PAGE_SIZE {
+            self.flush_leaf(table)?;
+        }
+        self.tables[table].cells.push(cell);
+        self.tables[table].next_rowid += 1;
+
+        for index in self.indices.iter_mut().filter(|idx| idx.table == table) {
+            let mut key: Vec<SqlValue> = index
+                .columns
+                .iter()
+                .map(|c| values.get(*c).cloned().unwrap_or(SqlValue::Null))
+                .collect();
+            key.push(SqlValue::Integer(rowid));
+            index.keys.push(key);
+        }
+        Ok(rowid)
+    }
+
+    fn append_page(&mut self, page: &[u8]) -> Result<u32, Error> {
+        self.writer.write_all(page)?;
+        self.num_pages += 1;
+        Ok(self.num_pages)
+    }
+
+    /// Returns the leaf cell of a record, writing the part of the record that does not fit on the
+    /// leaf page to overflow pages.
+    fn table_leaf_cell(&mut self, rowid: i64, record: &[u8]) -> Result<Vec<u8>, Error> {
+        let usable = PAGE_SIZE;
+        let max_local = usable - 35;
+        let min_local = (usable - 12) * 32 / 255 - 23;
+        let p = record.len();
+        let local = if p <= max_local {
+            p
+        } else {
+            let k = min_local + (p - min_local) % (usable - 4);
+            if k <= max_local {
+                k
+            } else {
+                min_local
+            }
+        };
+        let mut cell = Vec::with_capacity(local + 22);
+        put_varint(&mut cell, p as u64);
+        put_varint(&mut cell, rowid as u64);
+        cell.extend_from_slice(&record[..local]);
+        if local < p {
+            // the overflow pages are written consecutively, each pointing to the next
+            let chunks: Vec<&[u8]> = record[local..].chunks(usable - 4).collect();
+            let first_page = self.num_pages + 1;
+            for (i, chunk) in chunks.iter().enumerate() {
+                let mut page = vec![0u8; PAGE_SIZE];
+                let next = if i + 1 < chunks.len() { self.num_pages + 2 } else { 0 };
+                page[0..4].copy_from_slice(&next.to_be_bytes());
+                page[4..4 + chunk.len()].copy_from_slice(chunk);
+                self.append_page(&page)?;
+            }
+            cell.extend_from_slice(&first_page.to_be_bytes());
+        }
+        Ok(cell)
+    }
+
+    fn flush_leaf(&mut self, table: usize) -> Result<(), Error> {
+        let cells = std::mem::take(&mut self.tables[table].cells);
+        let page = build_page(TABLE_LEAF, &cells, None, 0);
+        let page_num = self.append_page(&page)?;
+        // the record being inserted, if any, is not yet counted
+        let max_rowid = self.tables[table].next_rowid - 1;
+        self.tables[table].leaves.push((page_num, max_rowid));
+        Ok(())
+    }
+
+    /// Writes the interior pages of a table b-tree and returns its root page.
+    fn build_table_tree(&mut self, mut children: Vec<(u32, i64)>) -> Result<u32, Error> {
+        while children.len() > 1 {
+            // a cell is a child page number and the largest rowid of the child's subtree
+            let max_cell = 4 + 9 + 2;
+            let per_page = (PAGE_SIZE - 12) / max_cell + 1;
+            let num_pages = children.len().div_ceil(per_page);
+            let (base, extra) = (children.len() / num_pages, children.len() % num_pages);
+            let mut parents = Vec::with_capacity(num_pages);
+            let mut start = 0;
+            for k in 0..num_pages {
+                let end = start + base + if k < extra { 1 } else { 0 };
+                let cells: Vec<Vec<u8>> = children[start..end - 1]
+                    .iter()
+                    .map(|(page, key)| {
+                        let mut cell = page.to_be_bytes().to_vec();
+                        put_varint(&mut cell, *key as u64);
+                        cell
+                    })
+                    .collect();
+                let page = build_page(TABLE_INTERIOR, &cells, Some(children[end - 1].0), 0);
+                let page_num = self.append_page(&page)?;
+                parents.push((page_num, children[end - 1].1));
+                start = end;
+            }
+            children = parents;
+        }
+        Ok(children[0].0)
+    }
+
+    /// Writes an index b-tree of sorted keys and returns its root page.
+    fn build_index_tree(&mut self, keys: &[Vec<SqlValue>]) -> Result<u32, Error> {
+        let max_local = (PAGE_SIZE - 12) * 64 / 255 - 23;
+        let mut entries = Vec::with_capacity(keys.len());
+        for key in keys {
+            let record = encode_record(key);
+            if record.len() > max_local {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The indexed values are too large to be stored.",
+                ));
+            }
+            let mut cell = vec![];
+            put_varint(&mut cell, record.len() as u64);
+            cell.extend_from_slice(&record);
+            entries.push(cell);
+        }
+
+        // fill the leaves, promoting the entry that follows each full leaf to its parent
+        let mut children: Vec<u32> = vec![];
+        let mut separators: Vec<Vec<u8>> = vec![];
+        let mut cells: Vec<Vec<u8>> = vec![];
+        let mut i = 0;
+        while i < entries.len() {
+            if !cells.is_empty() && cells_size(&cells) + entries[i].len() + 2 + 8 > PAGE_SIZE {
+                let page = build_page(INDEX_LEAF, &cells, None, 0);
+                children.push(self.append_page(&page)?);
+                cells.clear();
+                if i + 1 < entries.len() {
+                    separators.push(entries[i].clone());
+                    i += 1;
+                    continue;
+                }
+            }
+            cells.push(entries[i].clone());
+            i += 1;
+        }
+        let page = build_page(INDEX_LEAF, &cells, None, 0);
+        children.push(self.append_page(&page)?);
+
+        // each interior page holds the entries between its children, and the entry that follows
+        // its last child is promoted to the next level
+        while children.len() > 1 {
+            let max_cell = separators.iter().map(|s| s.len() + 4 + 2).max().unwrap_or(6);
+            let per_page = ((PAGE_SIZE - 12) / max_cell + 1).max(2);
+            let num_pages = children.len().div_ceil(per_page);
+            let (base, extra) = (children.len() / num_pages, children.len() % num_pages);
+            let mut parents = Vec::with_capacity(num_pages);
+            let mut parent_separators = vec![];
+            let mut start = 0;
+            for k in 0..num_pages {
+                let end = start + base + if k < extra { 1 } else { 0 };
+                let cells: Vec<Vec<u8>> = (start..end - 1)
+                    .map(|j| {
+                        let mut cell = children[j].to_be_bytes().to_vec();
+                        cell.extend_from_slice(&separators[j]);
+                        cell
+                    })
+                    .collect();
+                let page = build_page(INDEX_INTERIOR, &cells, Some(children[end - 1]), 0);
+                parents.push(self.append_page(&page)?);
+                if end < children.len() {
+                    parent_separators.push(separators[end - 1].clone());
+                }
+                start = end;
+            }
+            children = parents;
+            separators = parent_separators;
+        }
+        Ok(children[0])
+    }
+
+    /// Writes the remaining pages, the schema, and the header of the database.
+    pub fn finish(mut self) -> Result<(), Error> {
+        let mut schema = vec![];
+        for t in 0..self.tables.len() {
+            if !self.tables[t].cells.is_empty() || self.tables[t].leaves.is_empty() {
+                self.flush_leaf(t)?;
+            }
+            let leaves = std::mem::take(&mut self.tables[t].leaves);
+            let root = self.build_table_tree(leaves)?;
+            schema.push(vec![
+                SqlValue::Text("table".to_string()),
+                SqlValue::Text(self.tables[t].name.clone()),
+                SqlValue::Text(self.tables[t].name.clone()),
+                SqlValue::Integer(root as i64),
+                SqlValue::Text(self.tables[t].sql.clone()),
+            ]);
+        }
+        for i in 0..self.indices.len() {
+            let mut keys = std::mem::take(&mut self.indices[i].keys);
+            keys.sort_by(|a, b| {
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(x, y)| x.compare(y))
+                    .find(|o| *o != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            });
+            let root = self.build_index_tree(&keys)?;
+            schema.push(vec![
+                SqlValue::Text("index".to_string()),
+                SqlValue::Text(self.indices[i].name.clone()),
+                SqlValue::Text(self.tables[self.indices[i].table].name.clone()),
+                SqlValue::Integer(root as i64),
+                SqlValue::Text(self.indices[i].sql.clone()),
+            ]);
+        }
+
+        // the schema table is rooted on the first page, following the header
+        let mut cells = vec![];
+        for (i, values) in schema.iter().enumerate() {
+            let record = encode_record(values);
+            let mut cell = vec![];
+            put_varint(&mut cell, record.len() as u64);
+            put_varint(&mut cell, i as u64 + 1);
+            cell.extend_from_slice(&record);
+            cells.push(cell);
+        }
+        if cells_size(&cells) + 100 + 8 > PAGE_SIZE {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The database schema is too large to be written.",
+            ));
+        }
+        let mut page = build_page(TABLE_LEAF, &cells, None, 100);
+        page[0..16].copy_from_slice(b"SQLite format 3\0");
+        page[16..18].copy_from_slice(&(PAGE_SIZE as u16).to_be_bytes());
+        page[18] = 1; // legacy file format write version
+        page[19] = 1; // and read version
+        page[20] = 0; // reserved bytes at the end of each page
+        page[21] = 64; // maximum embedded payload fraction
+        page[22] = 32; // minimum embedded payload fraction
+        page[23] = 32; // leaf payload fraction
+        page[24..28].copy_from_slice(&1u32.to_be_bytes()); // file change counter
+        page[28..32].copy_from_slice(&self.num_pages.to_be_bytes()); // database size in pages
+        page[40..44].copy_from_slice(&1u32.to_be_bytes()); // schema cookie
+        page[44..48].copy_from_slice(&4u32.to_be_bytes()); // schema format number
+        page[56..60].copy_from_slice(&1u32.to_be_bytes()); // UTF-8 text encoding
+        page[68..72].copy_from_slice(&self.application_id.to_be_bytes());
+        page[92..96].copy_from_slice(&1u32.to_be_bytes()); // version-valid-for number
+        page[96..100].copy_from_slice(&SQLITE_VERSION_NUMBER.to_be_bytes());
+
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(&page)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{encode_record, put_varint, SqlValue};
+
+    #[test]
+    fn test_varint() {
+        let encode = |v: u64| {
+            let mut out = vec![];
+            put_varint(&mut out, v);
+            out
+        };
+        assert_eq!(encode(0), vec![0x00]);
+        assert_eq!(encode(127), vec![0x7f]);
+        assert_eq!(encode(128), vec![0x81, 0x00]);
+        assert_eq!(encode(16384), vec![0x81, 0x80, 0x00]);
+        assert_eq!(encode(u64::MAX), vec![0xff; 9]);
+    }
+
+    #[test]
+    fn test_encode_record() {
+        let record = encode_record(&[
+            SqlValue::Null,
+            SqlValue::Integer(1),
+            SqlValue::Integer(300),
+            SqlValue::Text("ab".to_string()),
+        ]);
+        // header size, serial types (NULL, one, 16-bit integer, 2-byte text), then the body
+        assert_eq!(record, vec![5, 0, 9, 2, 17, 0x01, 0x2c, b'a', b'b']);
+    }
+}
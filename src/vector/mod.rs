@@ -6,6 +6,9 @@ Last Modified: 12/04/2018
 License: MIT
 */
 
+//! Reading and writing of vector data. The `Shapefile` type contains the geometries
+//! (`ShapefileGeometry`) and attribute table (`ShapefileAttributes`) of an ESRI Shapefile.
+
 /*
 Eventually this will be used to support multiple vector formats but
 for now it's just Shapefiles.
@@ -26,6 +29,9 @@ pub use crate::vector::shapefile::geometry::*;
 // pub use self::shapefile::geometry::{ShapeType, ShapeTypeDimension, ShapefileGeometry};
 pub use crate::structures::Point2D;
 pub use crate::vector::shapefile::geometry::ShapeType;
+pub use crate::vector::shapefile::spatial_index::{
+    is_spatial_index_output, set_spatial_index_output, SpatialIndex,
+};
 pub use crate::vector::shapefile::Shapefile;
 
 // /// A 2-D point, with x and y fields.
@@ -10,9 +10,11 @@ Notes: The logic behind working with the ESRI Shapefile format.
 
 pub mod attributes;
 pub mod geometry;
+pub mod spatial_index;
 
 use self::attributes::*;
 use self::geometry::*;
+use self::spatial_index::*;
 // use attributes::{
 //     AttributeField, AttributeHeader, DateData, FieldData, FieldDataType, Intersector,
 //     ShapefileAttributes,
@@ -89,7 +91,10 @@ m_max: {}",
 ///
 /// Examples:
 ///
-/// ```
+/// ```no_run
+/// # use whitebox_tools::vector::*;
+/// # fn main() -> std::io::Result<()> {
+/// # let (input_file, output_file) = ("input.shp", "output.shp");
 /// // Read a Shapefile from a file.
 /// let input = Shapefile::read(&input_file)?;
 ///
@@ -101,7 +106,8 @@ m_max: {}",
 /// let val = AttributeField::new("Value", FieldDataType::Real, 12u8, 4u8);
 /// output.attributes.add_field(&fid);
 /// output.attributes.add_field(&val);
-///
+/// # Ok(())
+/// # }
 /// ```
 #[derive(Default, Clone)]
 pub struct Shapefile {
@@ -231,6 +237,27 @@ impl Shapefile {
         }
     }
 
+    /// Returns a spatial index of the records, which is read from the shapefile's `.qix` file if
+    /// one is present and up to date, and otherwise built in memory.
+    pub fn spatial_index(&self) -> SpatialIndex {
+        let qix_file = spatial_index_file_name(&self.file_name);
+        let is_current = match (fs::metadata(&qix_file), fs::metadata(&self.file_name)) {
+            (Ok(qix), Ok(shp)) => match (qix.modified(), shp.modified()) {
+                (Ok(t1), Ok(t2)) => t1 >= t2,
+                _ => false,
+            },
+            _ => false,
+        };
+        if is_current && self.file_mode == "r" {
+            if let Ok(index) = SpatialIndex::read(&qix_file) {
+                if index.num_records() == self.records.len() {
+                    return index;
+                }
+            }
+        }
+        SpatialIndex::new(self)
+    }
+
     // pub fn get_attributes_table<'a>(&'a mut self) -> &'a mut ShapefileAttributes {
     //     &mut self.attributes
     // }
@@ -1236,6 +1263,24 @@ impl Shapefile {
 
         writer.write_u8(0x1A)?; // file terminator byte
 
+        //////////////////////////////////
+        // Write the spatial index file //
+        //////////////////////////////////
+
+        // Any existing index files are out of date; the ESRI .sbn/.sbx pair is not written.
+        let qix_file = spatial_index_file_name(&self.file_name);
+        for ext in ["sbn", "sbx"].iter() {
+            let f = Path::new(&self.file_name).with_extension(ext);
+            if f.exists() {
+                fs::remove_file(f)?;
+            }
+        }
+        if is_spatial_index_output() {
+            SpatialIndex::new(self).write(&qix_file)?;
+        } else if Path::new(&qix_file).exists() {
+            fs::remove_file(&qix_file)?;
+        }
+
         Ok(())
     }
 
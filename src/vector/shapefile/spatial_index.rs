@@ -0,0 +1,365 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. John Lindsay
+Created: 17/10/2026
+Last Modified: 17/10/2026
+License: MIT
+*/
+
+//! Quadtree spatial indices of shapefile records, stored in `.qix` sidecar files. The `.qix`
+//! format is that of shapelib's `shptree`, which is also read and written by GDAL/OGR, QGIS, and
+//! MapServer. Each node of the tree stores the records whose extents are contained within the
+//! node's bounds, but not within the bounds of any of its four children, such that the records
+//! that may overlap a query box are found without testing the extent of every record.
+
+use super::Shapefile;
+use crate::structures::BoundingBox;
+use byteorder::{BigEndian, ByteOrder, LittleEndian, WriteBytesExt};
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SPATIAL_INDEX_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// The maximum depth of the tree, which is that used by shapelib.
+const MAX_DEFAULT_TREE_DEPTH: usize = 12;
+
+/// Sets whether a `.qix` spatial index is written for every shapefile output, e.g. when the
+/// `--spatial_index` flag is specified.
+pub fn set_spatial_index_output(enabled: bool) {
+    SPATIAL_INDEX_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns true if a `.qix` spatial index is written for every shapefile output.
+pub fn is_spatial_index_output() -> bool {
+    SPATIAL_INDEX_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Returns the file name of the `.qix` spatial index of a shapefile, e.g. 'roads.qix'.
+pub fn spatial_index_file_name(file_name: &str) -> String {
+    Path::new(file_name)
+        .with_extension("qix")
+        .into_os_string()
+        .into_string()
+        .unwrap()
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    bounds: BoundingBox,
+    records: Vec<usize>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn new(bounds: BoundingBox) -> Node {
+        Node {
+            bounds,
+            records: vec![],
+            children: vec![],
+        }
+    }
+
+    fn insert(&mut self, record: usize, bb: &BoundingBox, max_depth: usize) {
+        if max_depth > 1 {
+            if self.children.is_empty() {
+                // split along the longer axis, and then split each half again
+                let (half1, half2) = split_bounds(&self.bounds);
+                let (q1, q2) = split_bounds(&half1);
+                let (q3, q4) = split_bounds(&half2);
+                self.children = vec![Node::new(q1), Node::new(q2), Node::new(q3), Node::new(q4)];
+            }
+            if let Some(child) = self.children.iter_mut().find(|c| c.bounds.contains(*bb)) {
+                child.insert(record, bb, max_depth - 1);
+                return;
+            }
+        }
+        self.records.push(record);
+    }
+
+    /// Removes the empty branches below the node, returning true if the node itself is empty.
+    fn trim(&mut self) -> bool {
+        self.children.retain_mut(|c| !c.trim());
+        self.records.is_empty() && self.children.is_empty()
+    }
+
+    /// The size of the node's descendants in a `.qix` file.
+    fn subtree_size(&self) -> usize {
+        self.children
+            .iter()
+            .map(|c| 40 + 4 * (c.records.len() + 1) + c.subtree_size())
+            .sum()
+    }
+
+    fn query(&self, bb: &BoundingBox, found: &mut Vec<usize>) {
+        if !bb.overlaps(self.bounds) {
+            return;
+        }
+        found.extend_from_slice(&self.records);
+        for child in &self.children {
+            child.query(bb, found);
+        }
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write_i32::<LittleEndian>(self.subtree_size() as i32)?;
+        writer.write_f64::<LittleEndian>(self.bounds.min_x)?;
+        writer.write_f64::<LittleEndian>(self.bounds.min_y)?;
+        writer.write_f64::<LittleEndian>(self.bounds.max_x)?;
+        writer.write_f64::<LittleEndian>(self.bounds.max_y)?;
+        writer.write_i32::<LittleEndian>(self.records.len() as i32)?;
+        for &r in &self.records {
+            writer.write_i32::<LittleEndian>(r as i32)?;
+        }
+        writer.write_i32::<LittleEndian>(self.children.len() as i32)?;
+        for child in &self.children {
+            child.write(writer)?;
+        }
+        Ok(())
+    }
+}
+
+fn split_bounds(bb: &BoundingBox) -> (BoundingBox, BoundingBox) {
+    let (mut b1, mut b2) = (*bb, *bb);
+    if bb.max_x - bb.min_x > bb.max_y - bb.min_y {
+        let mid = (bb.min_x + bb.max_x) / 2f64;
+        b1.max_x = mid;
+        b2.min_x = mid;
+    } else {
+        let mid = (bb.min_y + bb.max_y) / 2f64;
+        b1.max_y = mid;
+        b2.min_y = mid;
+    }
+    (b1, b2)
+}
+
+/// A quadtree spatial index of the records of a shapefile.
+///
+/// Examples:
+///
+/// ```no_run
+/// # use whitebox_tools::vector::*;
+/// # use whitebox_tools::structures::BoundingBox;
+/// # fn main() -> std::io::Result<()> {
+/// let input = Shapefile::read("parcels.shp")?;
+/// // reads parcels.qix, if it is present and up to date, or otherwise builds the index
+/// let index = input.spatial_index();
+/// let query = BoundingBox::new(500000.0, 501000.0, 4800000.0, 4801000.0);
+/// for record_num in index.query(&query) {
+///     let record = input.get_record(record_num);
+///     // ...
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpatialIndex {
+    root: Node,
+    num_records: usize,
+    max_depth: usize,
+}
+
+impl SpatialIndex {
+    /// Builds the spatial index of the records of a shapefile. As in shapelib, the depth of the
+    /// tree is chosen such that its leaf nodes hold roughly eight records each.
+    pub fn new(sf: &Shapefile) -> SpatialIndex {
+        let num_records = sf.records.len();
+        let mut max_depth = 0;
+        let mut max_node_count = 1;
+        while max_node_count * 4 < num_records {
+            max_depth += 1;
+            max_node_count *= 2;
+        }
+        let max_depth = max_depth.clamp(1, MAX_DEFAULT_TREE_DEPTH);
+
+        let extents: Vec<Option<BoundingBox>> = sf
+            .records
+            .iter()
+            .map(|r| {
+                if r.points.is_empty() {
+                    None
+                } else {
+                    Some(BoundingBox::from_points(&r.points))
+                }
+            })
+            .collect();
+        let mut bounds = BoundingBox::default();
+        for bb in extents.iter().flatten() {
+            bounds.expand_to(*bb);
+        }
+        if extents.iter().all(|e| e.is_none()) {
+            bounds = BoundingBox::new(0f64, 0f64, 0f64, 0f64);
+        }
+
+        let mut root = Node::new(bounds);
+        for (record, bb) in extents.iter().enumerate() {
+            if let Some(bb) = bb {
+                root.insert(record, bb, max_depth);
+            }
+        }
+        root.trim();
+        SpatialIndex {
+            root,
+            num_records,
+            max_depth,
+        }
+    }
+
+    /// Reads a `.qix` spatial index file.
+    pub fn read(file_name: &str) -> Result<SpatialIndex, Error> {
+        let buf = fs::read(file_name)?;
+        if buf.len() < 16 || &buf[0..3] != b"SQT" {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The file is not a .qix spatial index.",
+            ));
+        }
+        let big_endian = buf[3] == 2;
+        let read_i32 = |pos: usize| -> Result<i32, Error> {
+            if pos + 4 > buf.len() {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "The .qix spatial index is truncated.",
+                ));
+            }
+            Ok(if big_endian {
+                BigEndian::read_i32(&buf[pos..pos + 4])
+            } else {
+                LittleEndian::read_i32(&buf[pos..pos + 4])
+            })
+        };
+        let read_f64 = |pos: usize| -> Result<f64, Error> {
+            if pos + 8 > buf.len() {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "The .qix spatial index is truncated.",
+                ));
+            }
+            Ok(if big_endian {
+                BigEndian::read_f64(&buf[pos..pos + 8])
+            } else {
+                LittleEndian::read_f64(&buf[pos..pos + 8])
+            })
+        };
+        let num_records = read_i32(8)? as usize;
+        let max_depth = read_i32(12)? as usize;
+
+        // the nodes are stored depth-first
+        fn read_node(
+            pos: &mut usize,
+            read_i32: &dyn Fn(usize) -> Result<i32, Error>,
+            read_f64: &dyn Fn(usize) -> Result<f64, Error>,
+            depth: usize,
+        ) -> Result<Node, Error> {
+            if depth > 64 {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The .qix spatial index is malformed.",
+                ));
+            }
+            let p = *pos + 4; // skip the subtree size
+            let bounds = BoundingBox {
+                min_x: read_f64(p)?,
+                min_y: read_f64(p + 8)?,
+                max_x: read_f64(p + 16)?,
+                max_y: read_f64(p + 24)?,
+            };
+            let num_shapes = read_i32(p + 32)?.max(0) as usize;
+            let mut node = Node::new(bounds);
+            let mut p = p + 36;
+            for _ in 0..num_shapes {
+                node.records.push(read_i32(p)? as usize);
+                p += 4;
+            }
+            let num_children = read_i32(p)?.max(0) as usize;
+            *pos = p + 4;
+            for _ in 0..num_children {
+                node.children
+                    .push(read_node(pos, read_i32, read_f64, depth + 1)?);
+            }
+            Ok(node)
+        }
+        let mut pos = 16;
+        let root = read_node(&mut pos, &read_i32, &read_f64, 0)?;
+        Ok(SpatialIndex {
+            root,
+            num_records,
+            max_depth,
+        })
+    }
+
+    /// Writes the spatial index to a `.qix` file.
+    pub fn write(&self, file_name: &str) -> Result<(), Error> {
+        let f = File::create(file_name)?;
+        let mut writer = BufWriter::new(f);
+        writer.write_all(b"SQT")?;
+        writer.write_u8(1)?; // LSB byte order
+        writer.write_u8(1)?; // version
+        writer.write_all(&[0u8; 3])?;
+        writer.write_i32::<LittleEndian>(self.num_records as i32)?;
+        writer.write_i32::<LittleEndian>(self.max_depth as i32)?;
+        self.root.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the number of records of the indexed shapefile.
+    pub fn num_records(&self) -> usize {
+        self.num_records
+    }
+
+    /// Returns the maximum depth of the tree.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Returns the indices, in ascending order, of the records that may overlap a bounding box,
+    /// i.e. those stored in the nodes of the tree that overlap the box. The extents of the
+    /// returned records should still be tested against the box.
+    pub fn query(&self, bb: &BoundingBox) -> Vec<usize> {
+        let mut found = vec![];
+        self.root.query(bb, &mut found);
+        found.retain(|&r| r < self.num_records);
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SpatialIndex;
+    use crate::structures::BoundingBox;
+    use crate::vector::{ShapeType, Shapefile};
+
+    #[test]
+    fn test_spatial_index_query_and_qix_round_trip() {
+        let mut sf = Shapefile::new("points.shp", ShapeType::Point).unwrap();
+        for i in 0..100 {
+            sf.add_point_record((i % 10) as f64, (i / 10) as f64);
+        }
+        let index = SpatialIndex::new(&sf);
+        let query = BoundingBox::new(1.5, 3.5, 4.5, 5.5);
+        let found: Vec<usize> = index
+            .query(&query)
+            .into_iter()
+            .filter(|&r| {
+                let p = sf.get_record(r).points[0];
+                query.is_point_in_box(p.x, p.y)
+            })
+            .collect();
+        assert_eq!(found, vec![52, 53]);
+        assert!(index.query(&query).len() < 100);
+
+        let file_name = std::env::temp_dir().join("wbt_spatial_index_test.qix");
+        let file_name = file_name.to_str().unwrap();
+        index.write(file_name).unwrap();
+        let read = SpatialIndex::read(file_name).unwrap();
+        let _ = std::fs::remove_file(file_name);
+        assert_eq!(read.num_records(), 100);
+        assert_eq!(read.query(&query), index.query(&query));
+    }
+}